@@ -0,0 +1,104 @@
+//! Strategy scoring and auto placement planning on 10k+ VM projects
+//!
+//! Run with `cargo bench -p backend --bench placement`. Each case runs once
+//! on a single-thread pool and once on the default pool, so the report
+//! shows the speedup from rayon on the machine at hand.
+
+use backend::models::migration_wizard_models::{MigrationWizardCluster, MigrationWizardPlacement, MigrationWizardVM};
+use backend::services::migration_wizard_service::MigrationWizardService;
+use backend::services::os_lifecycle_service::OsLifecycleCatalog;
+use chrono::{NaiveDate, Utc};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rayon::ThreadPoolBuilder;
+use surrealdb::sql::Thing;
+
+const GUEST_OS: &[&str] = &[
+    "Microsoft Windows Server 2019 (64-bit)",
+    "Microsoft Windows Server 2012 R2 (64-bit)",
+    "Microsoft Windows Server 2008 R2 (64-bit)",
+    "Red Hat Enterprise Linux 8 (64-bit)",
+    "Ubuntu Linux (64-bit)",
+    "CentOS 7 (64-bit)",
+];
+
+fn vms(count: usize) -> Vec<MigrationWizardVM> {
+    (0..count)
+        .map(|i| MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", format!("vm{:06}", i).as_str()))),
+            project_id: Thing::from(("migration_wizard_project", "bench")),
+            name: format!("vm{:06}", i),
+            powerstate: Some("poweredOn".to_string()),
+            template: Some(false),
+            cpus: [1, 2, 2, 4, 4, 8, 16][i % 7],
+            memory_mb: [2048, 4096, 8192, 16384, 32768][i % 5],
+            provisioned_mb: Some([40_960, 102_400, 204_800, 512_000][i % 4]),
+            in_use_mb: None,
+            primary_ip_address: None,
+            dns_name: None,
+            cluster: None,
+            host: None,
+            datacenter: None,
+            os: Some(GUEST_OS[i % GUEST_OS.len()].to_string()),
+            version: None,
+            num_disks: 1 + (i % 10) as i32,
+            num_nics: 1 + (i % 5) as i32,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        })
+        .collect()
+}
+
+fn clusters(count: usize) -> Vec<MigrationWizardCluster> {
+    (0..count)
+        .map(|i| MigrationWizardCluster {
+            id: Some(Thing::from(("migration_wizard_cluster", format!("c{:03}", i).as_str()))),
+            project_id: Thing::from(("migration_wizard_project", "bench")),
+            name: format!("c{:03}", i),
+            description: None,
+            cpu_ghz: 2.6,
+            total_cores: 256 + 64 * (i % 4) as i32,
+            memory_gb: 4096,
+            storage_tb: 200.0,
+            network_bandwidth_gbps: 25.0,
+            cpu_oversubscription_ratio: 4.0,
+            memory_oversubscription_ratio: 1.0,
+            strategy: "hyperv".to_string(),
+            destination_cluster_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+        .collect()
+}
+
+fn bench_placement(c: &mut Criterion) {
+    let catalog = OsLifecycleCatalog::builtin();
+    let as_of = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let serial = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let parallel = ThreadPoolBuilder::new().build().unwrap();
+    let existing: Vec<MigrationWizardPlacement> = Vec::new();
+
+    let mut group = c.benchmark_group("migration_wizard");
+    group.sample_size(10);
+
+    for count in [10_000, 50_000] {
+        let vms = vms(count);
+        let clusters = clusters(count / 100);
+
+        for (threads, pool) in [("1_thread", &serial), ("all_threads", &parallel)] {
+            group.bench_with_input(BenchmarkId::new(format!("strategy_{}", threads), count), &vms, |b, vms| {
+                b.iter(|| pool.install(|| MigrationWizardService::recommend_strategies(black_box(vms), &catalog, as_of)))
+            });
+            group.bench_with_input(BenchmarkId::new(format!("placement_{}", threads), count), &vms, |b, vms| {
+                b.iter(|| {
+                    pool.install(|| MigrationWizardService::plan_auto_placement(black_box(vms), &clusters, &existing))
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_placement);
+criterion_main!(benches);
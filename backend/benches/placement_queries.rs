@@ -0,0 +1,129 @@
+//! Auto placement, manual placement and utilization against an in-memory
+//! database, with the SurrealDB round trips each one makes
+//!
+//! Run with `cargo bench -p backend --features test-utils --bench placement_queries`.
+
+use std::time::{Duration, Instant};
+
+use backend::database::{new_test, RoundTripCounter};
+use backend::models::migration_wizard_models::{MigrationWizardCluster, MigrationWizardVM};
+use backend::services::migration_wizard_service::{IngestOptions, MigrationWizardService};
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use surrealdb::sql::Thing;
+use tokio::runtime::Runtime;
+
+const PROJECT: &str = "bench";
+
+fn vm(i: usize) -> MigrationWizardVM {
+    MigrationWizardVM {
+        id: Some(Thing::from(("migration_wizard_vm", format!("vm{:06}", i).as_str()))),
+        project_id: Thing::from(("migration_wizard_project", PROJECT)),
+        name: format!("vm{:06}", i),
+        powerstate: Some("poweredOn".to_string()),
+        template: Some(false),
+        cpus: [1, 2, 4, 8][i % 4],
+        memory_mb: [2048, 4096, 8192, 16384][i % 4],
+        provisioned_mb: Some(102_400),
+        in_use_mb: None,
+        primary_ip_address: None,
+        dns_name: None,
+        cluster: None,
+        host: None,
+        datacenter: None,
+        os: Some("Microsoft Windows Server 2019 (64-bit)".to_string()),
+        version: None,
+        num_disks: 1,
+        num_nics: 1,
+        annotation: None,
+        folder: None,
+        created_at: Utc::now(),
+    }
+}
+
+fn cluster(i: usize) -> MigrationWizardCluster {
+    MigrationWizardCluster {
+        id: Some(Thing::from(("migration_wizard_cluster", format!("c{:02}", i).as_str()))),
+        project_id: Thing::from(("migration_wizard_project", PROJECT)),
+        name: format!("c{:02}", i),
+        description: None,
+        cpu_ghz: 2.6,
+        total_cores: 1024,
+        memory_gb: 16384,
+        storage_tb: 500.0,
+        network_bandwidth_gbps: 25.0,
+        cpu_oversubscription_ratio: 4.0,
+        memory_oversubscription_ratio: 1.0,
+        strategy: "hyperv".to_string(),
+        destination_cluster_id: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+async fn seeded(vm_count: usize) -> MigrationWizardService {
+    let db = new_test().await.expect("in-memory database");
+    let clusters: Vec<_> = (0..20).map(cluster).collect();
+    db.query("INSERT INTO migration_wizard_cluster $clusters").bind(("clusters", clusters)).await.unwrap();
+
+    let service = MigrationWizardService::new(db);
+    service.ingest_vms(PROJECT, (0..vm_count).map(vm).collect(), &IngestOptions::default()).await.unwrap();
+    service
+}
+
+/// Round trips of one auto placement, manual placement and utilization read
+async fn count_round_trips(vm_count: usize) -> (usize, usize, usize) {
+    let service = seeded(vm_count).await;
+    let counter = RoundTripCounter::default();
+    let _guard = counter.install();
+
+    service.auto_place_vms(PROJECT).await.unwrap();
+    let auto_place = counter.take();
+    service.create_manual_placement(PROJECT, "vm000000", "c03", None).await.unwrap();
+    let manual = counter.take();
+    service.get_cluster_utilization(PROJECT).await.unwrap();
+    (auto_place, manual, counter.take())
+}
+
+fn bench_placement_queries(c: &mut Criterion) {
+    // Spans are counted per thread, so count on a single-thread runtime
+    let counting = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("placement_queries");
+    group.sample_size(10);
+
+    for vm_count in [1_000, 10_000] {
+        let (auto_place, manual, utilization) = counting.block_on(count_round_trips(vm_count));
+        println!(
+            "{} VMs: auto placement {} round trips, manual placement {}, utilization {}",
+            vm_count, auto_place, manual, utilization
+        );
+
+        group.bench_function(BenchmarkId::new("auto_place", vm_count), |b| {
+            b.to_async(&rt).iter_custom(|iters| async move {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iters {
+                    let service = seeded(vm_count).await;
+                    let started = Instant::now();
+                    service.auto_place_vms(PROJECT).await.unwrap();
+                    elapsed += started.elapsed();
+                }
+                elapsed
+            })
+        });
+
+        let service = rt.block_on(seeded(vm_count));
+        rt.block_on(service.auto_place_vms(PROJECT)).unwrap();
+        group.bench_function(BenchmarkId::new("cluster_utilization", vm_count), |b| {
+            b.to_async(&rt).iter(|| service.get_cluster_utilization(PROJECT))
+        });
+        group.bench_function(BenchmarkId::new("manual_placement", vm_count), |b| {
+            b.to_async(&rt).iter(|| service.create_manual_placement(PROJECT, "vm000000", "c03", None))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_placement_queries);
+criterion_main!(benches);
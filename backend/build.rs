@@ -0,0 +1,11 @@
+// Generates the collector ingestion gRPC server from the shared proto.
+// protoc comes from protoc-bin-vendored so builds need no system install.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=../proto/collector.proto");
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["../proto/collector.proto"], &["../proto"])?;
+    Ok(())
+}
@@ -0,0 +1,514 @@
+// Archer - AI Provider API
+// LLM provider configuration, tenant model selection, chat (plain and SSE), usage
+// the tool-calling assistant with its action approval queue, and natural-language
+// queries over environment and CMDB data
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    middleware::feature_flags::{require_feature, FeatureGate},
+    models::ai::*,
+    models::settings::FeatureFlag,
+    services::llm::{
+        service::{LlmCaller, StreamEvent},
+        tools::AgentTool,
+        AgentService, LlmError, LlmService,
+    },
+    services::nl_query_service::{self, NlDataset, NlQueryRequest, NlQueryService},
+    utils::api_response::helpers::error_body,
+};
+use core_engine::error::codes;
+
+/// Create AI API router with authentication
+pub fn create_ai_router(db: Arc<Database>) -> Router {
+    let auth_state = AuthState::new();
+
+    // The assistant ships dark; provider setup and usage stay available so
+    // admins can configure it before turning it on
+    let assistant_routes = Router::new()
+        .route("/chat", post(chat))
+        .route("/chat/stream", post(chat_stream))
+        .route("/agent/tools", get(list_agent_tools))
+        .route("/agent/run", post(run_agent))
+        .route("/agent/actions", get(list_agent_actions))
+        .route("/agent/actions/:id", get(get_agent_action))
+        .route("/agent/actions/:id/decision", post(decide_agent_action))
+        .route("/agent/sessions/:session_id/thoughts", get(list_agent_thoughts))
+        .route("/query", post(natural_language_query))
+        .route_layer(middleware::from_fn_with_state(
+            FeatureGate::new(db.clone(), FeatureFlag::AiAssistant),
+            require_feature,
+        ));
+
+    Router::new()
+        .route("/providers", get(list_providers).post(create_provider))
+        .route("/providers/:id", put(update_provider).delete(delete_provider))
+        .route("/providers/:id/models", get(list_provider_models))
+        .route("/providers/:id/health", get(provider_health))
+        .route("/model-selection", get(get_model_selection).put(set_model_selection))
+        .route("/usage", get(get_usage))
+        .merge(assistant_routes)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(db)
+}
+
+fn forbidden(permission: &str) -> Response {
+    error_body(codes::FORBIDDEN, &format!("Permission '{}' required", permission)).into_response()
+}
+
+fn llm_error(e: LlmError) -> Response {
+    let code = match &e {
+        LlmError::NotFound(_) => codes::NOT_FOUND,
+        LlmError::InvalidRequest(_) => codes::BAD_REQUEST,
+        LlmError::Forbidden(_) => codes::FORBIDDEN,
+        LlmError::NotConfigured(_) => codes::NOT_CONFIGURED,
+        LlmError::BudgetExceeded { .. } => codes::RATE_LIMITED,
+        LlmError::Http(_) | LlmError::Provider { .. } | LlmError::InvalidResponse(_) => codes::UPSTREAM_UNAVAILABLE,
+        LlmError::Secret(_) | LlmError::Database(_) => codes::INTERNAL_ERROR,
+    };
+    error_body(code, &e.to_string()).into_response()
+}
+
+fn caller(user: &AuthenticatedUser) -> LlmCaller<'_> {
+    LlmCaller {
+        user_id: &user.user_id,
+        tenant_id: user.tenant_id.as_deref(),
+        can_override: user.has_permission("ai:manage"),
+    }
+}
+
+// =============================================================================
+// PROVIDERS
+// =============================================================================
+
+/// List configured providers (keys are never returned)
+async fn list_providers(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    match LlmService::new(db).list_providers().await {
+        Ok(providers) => {
+            let views: Vec<LlmProviderView> = providers.iter().map(LlmProviderView::from).collect();
+            (StatusCode::OK, Json(views)).into_response()
+        }
+        Err(e) => llm_error(e),
+    }
+}
+
+async fn create_provider(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateLlmProviderRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    match LlmService::new(db).create_provider(request, &user.user_id).await {
+        Ok(provider) => (StatusCode::CREATED, Json(LlmProviderView::from(&provider))).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+async fn update_provider(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateLlmProviderRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    match LlmService::new(db).update_provider(&id, request).await {
+        Ok(provider) => (StatusCode::OK, Json(LlmProviderView::from(&provider))).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+async fn delete_provider(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    match LlmService::new(db).delete_provider(&id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+async fn load_provider(db: Arc<Database>, id: &str) -> Result<Box<dyn crate::services::llm::LlmProvider>, Response> {
+    let config = LlmService::new(db)
+        .get_provider(id)
+        .await
+        .map_err(llm_error)?
+        .ok_or_else(|| llm_error(LlmError::NotFound(format!("Provider {} not found", id))))?;
+    LlmService::build_provider(&config).map_err(llm_error)
+}
+
+/// Models reported by the provider itself
+async fn list_provider_models(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    let provider = match load_provider(db, &id).await {
+        Ok(provider) => provider,
+        Err(response) => return response,
+    };
+    match provider.list_models().await {
+        Ok(models) => (StatusCode::OK, Json(models)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+async fn provider_health(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    let provider = match load_provider(db, &id).await {
+        Ok(provider) => provider,
+        Err(response) => return response,
+    };
+    let healthy = provider.health_check().await;
+    (StatusCode::OK, Json(serde_json::json!({ "provider": provider.name(), "healthy": healthy }))).into_response()
+}
+
+// =============================================================================
+// MODEL SELECTION
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct SelectionScope {
+    /// `global` sets the default for tenants without their own selection
+    scope: Option<String>,
+}
+
+async fn get_model_selection(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:use") {
+        return forbidden("ai:use");
+    }
+
+    match LlmService::new(db).get_selection(user.tenant_id.as_deref()).await {
+        Ok(selection) => (StatusCode::OK, Json(selection)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+async fn set_model_selection(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<SelectionScope>,
+    Json(request): Json<SetTenantModelRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    let tenant_id = match params.scope.as_deref() {
+        Some("global") => {
+            if !user.has_permission("system:admin") {
+                return forbidden("system:admin");
+            }
+            None
+        }
+        _ => match user.tenant_id.as_deref() {
+            Some(tenant) => Some(tenant),
+            None => {
+                return llm_error(LlmError::InvalidRequest(
+                    "User has no tenant; use scope=global to set the default".to_string(),
+                ))
+            }
+        },
+    };
+
+    match LlmService::new(db).set_selection(tenant_id, request, &user.user_id).await {
+        Ok(selection) => (StatusCode::OK, Json(selection)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+// =============================================================================
+// CHAT
+// =============================================================================
+
+/// Chat completion returned in one response
+async fn chat(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<ChatCompletionRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:use") {
+        return forbidden("ai:use");
+    }
+
+    match LlmService::new(db).chat(&caller(&user), request).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+/// Chat completion streamed as server-sent events:
+/// `delta` ({content}) per chunk, then `done` (full response with usage) or `error`
+async fn chat_stream(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Response {
+    if !user.has_permission("ai:use") {
+        return forbidden("ai:use");
+    }
+
+    let events = match Arc::new(LlmService::new(db)).stream_chat(caller(&user), request).await {
+        Ok(events) => events,
+        Err(e) => return llm_error(e),
+    };
+
+    let stream = ReceiverStream::new(events).map(|event| match event {
+        StreamEvent::Delta(content) => Event::default()
+            .event("delta")
+            .json_data(serde_json::json!({ "content": content })),
+        StreamEvent::Done(response) => Event::default().event("done").json_data(response),
+        StreamEvent::Error(error) => Event::default()
+            .event("error")
+            .json_data(serde_json::json!({ "error": error })),
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+// =============================================================================
+// USAGE
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct UsageParams {
+    days: Option<u32>,
+}
+
+/// Token usage for the caller's tenant
+async fn get_usage(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<UsageParams>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    match LlmService::new(db)
+        .usage_summary(user.tenant_id.as_deref(), params.days.unwrap_or(30))
+        .await
+    {
+        Ok(summary) => (StatusCode::OK, Json(summary)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+// =============================================================================
+// ASSISTANT TOOLS
+// =============================================================================
+
+/// Tools the user's permissions allow the assistant to call for them
+fn allowed_tools(user: &AuthenticatedUser) -> Vec<AgentTool> {
+    AgentTool::ALL
+        .into_iter()
+        .filter(|tool| user.has_permission(tool.required_permission()))
+        .collect()
+}
+
+async fn list_agent_tools(Extension(user): Extension<AuthenticatedUser>) -> impl IntoResponse {
+    if !user.has_permission("ai:use") {
+        return forbidden("ai:use");
+    }
+
+    let specs: Vec<AgentToolSpec> = allowed_tools(&user).iter().map(AgentTool::spec).collect();
+    (StatusCode::OK, Json(specs)).into_response()
+}
+
+/// Run the assistant; stops early with AWAITING_APPROVAL when it asks for a
+/// mutating tool
+async fn run_agent(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<AgentRunRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:use") {
+        return forbidden("ai:use");
+    }
+
+    let tools = allowed_tools(&user);
+    match AgentService::new(db).run(&caller(&user), &tools, request).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AgentActionParams {
+    status: Option<AgentActionStatus>,
+    session_id: Option<String>,
+    limit: Option<u32>,
+}
+
+async fn list_agent_actions(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<AgentActionParams>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    match AgentService::new(db)
+        .list_actions(
+            user.tenant_id.as_deref(),
+            params.status,
+            params.session_id.as_deref(),
+            params.limit.unwrap_or(100),
+        )
+        .await
+    {
+        Ok(actions) => (StatusCode::OK, Json(actions)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+/// Requesters may see their own actions; everything else needs ai:manage
+async fn get_agent_action(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:use") {
+        return forbidden("ai:use");
+    }
+
+    match AgentService::new(db).get_action(&id).await {
+        Ok(Some(action)) if action.tenant_id == user.tenant_id
+            && (action.requested_by == user.user_id || user.has_permission("ai:manage")) =>
+        {
+            (StatusCode::OK, Json(action)).into_response()
+        }
+        Ok(_) => llm_error(LlmError::NotFound(format!("Agent action {} not found", id))),
+        Err(e) => llm_error(e),
+    }
+}
+
+/// Approve (runs the tool) or reject a pending action
+async fn decide_agent_action(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Json(request): Json<AgentActionDecisionRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:manage") {
+        return forbidden("ai:manage");
+    }
+
+    let service = AgentService::new(db);
+    match service.get_action(&id).await {
+        Ok(Some(action)) if action.tenant_id == user.tenant_id => {}
+        Ok(_) => return llm_error(LlmError::NotFound(format!("Agent action {} not found", id))),
+        Err(e) => return llm_error(e),
+    }
+
+    match service.decide(&id, &user.user_id, request).await {
+        Ok(action) => (StatusCode::OK, Json(action)).into_response(),
+        Err(e) => llm_error(e),
+    }
+}
+
+async fn list_agent_thoughts(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(session_id): Path<String>,
+) -> impl IntoResponse {
+    if !user.has_permission("ai:use") {
+        return forbidden("ai:use");
+    }
+
+    match AgentService::new(db)
+        .list_thoughts(user.tenant_id.as_deref(), &session_id)
+        .await
+    {
+        Ok(thoughts) => {
+            // Other users' sessions are only visible to managers
+            let visible: Vec<AiThoughtLog> = thoughts
+                .into_iter()
+                .filter(|t| t.user.as_deref() == Some(user.user_id.as_str()) || user.has_permission("ai:manage"))
+                .collect();
+            (StatusCode::OK, Json(visible)).into_response()
+        }
+        Err(e) => llm_error(e),
+    }
+}
+
+// =============================================================================
+// NATURAL-LANGUAGE QUERY
+// =============================================================================
+
+/// Answer a question like "which clusters exceed 80% memory?" with a table,
+/// the structured reading of the question and the query that produced it.
+/// Parsing is rule-based, so no LLM provider is needed.
+async fn natural_language_query(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<NlQueryRequest>,
+) -> impl IntoResponse {
+    let query = match nl_query_service::parse_question(&request.question) {
+        Ok(query) => query,
+        Err(message) => return error_body(codes::BAD_REQUEST, &message).into_response(),
+    };
+    if query.dataset == NlDataset::ConfigurationItems && !user.has_permission("cmdb:read") {
+        return forbidden("cmdb:read");
+    }
+
+    match NlQueryService::new((*db).clone())
+        .execute(
+            &request.question,
+            query,
+            user.tenant_id.as_deref(),
+            request.project_id.as_deref(),
+        )
+        .await
+    {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => error_body(codes::INTERNAL_ERROR, &e.to_string()).into_response(),
+    }
+}
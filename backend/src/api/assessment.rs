@@ -0,0 +1,136 @@
+// Archer - Assessment API
+// Rule packs and assessment runs against the VM inventory and CMDB
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::assessment::*,
+    services::assessment_service::AssessmentService,
+    utils::api_response::helpers::error_body,
+};
+use core_engine::error::codes;
+
+/// Create Assessment API router with authentication
+pub fn create_assessment_router(db: Arc<Database>) -> Router {
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/packs", get(list_packs))
+        .route("/packs/import", post(import_pack))
+        .route("/packs/:pack_id", delete(delete_pack))
+        .route("/runs", post(run_assessment))
+        .route("/runs/:id", get(get_run))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(db)
+}
+
+fn forbidden(permission: &str) -> axum::response::Response {
+    error_body(codes::FORBIDDEN, &format!("Permission '{}' required", permission))
+        .into_response()
+}
+
+/// List built-in and tenant rule packs
+async fn list_packs(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if !user.has_permission("assessments:read") {
+        return forbidden("assessments:read");
+    }
+
+    let service = AssessmentService::new(db.as_ref().clone());
+
+    match service.list_packs(user.tenant_id.as_deref()).await {
+        Ok(packs) => (StatusCode::OK, Json(packs)).into_response(),
+        Err(e) => error_body(codes::INTERNAL_ERROR, &e.to_string())
+            .into_response(),
+    }
+}
+
+/// Import a custom YAML rule pack for the caller's tenant
+async fn import_pack(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<ImportRulePackRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("assessments:manage") {
+        return forbidden("assessments:manage");
+    }
+
+    let service = AssessmentService::new(db.as_ref().clone());
+
+    match service.import_pack(user.tenant_id.as_deref(), &request.yaml).await {
+        Ok(pack) => (StatusCode::CREATED, Json(pack)).into_response(),
+        Err(e) => error_body(codes::BAD_REQUEST, &format!("{:#}", e))
+            .into_response(),
+    }
+}
+
+/// Delete a custom rule pack
+async fn delete_pack(
+    State(db): State<Arc<Database>>,
+    Path(pack_id): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if !user.has_permission("assessments:manage") {
+        return forbidden("assessments:manage");
+    }
+
+    let service = AssessmentService::new(db.as_ref().clone());
+
+    match service.delete_pack(user.tenant_id.as_deref(), &pack_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => error_body(codes::INTERNAL_ERROR, &e.to_string())
+            .into_response(),
+    }
+}
+
+/// Run rule packs against a project inventory and/or the CMDB
+async fn run_assessment(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<RunAssessmentRequest>,
+) -> impl IntoResponse {
+    if !user.has_permission("assessments:run") {
+        return forbidden("assessments:run");
+    }
+
+    let service = AssessmentService::new(db.as_ref().clone());
+
+    match service.run_assessment(user.tenant_id.as_deref(), request).await {
+        Ok(run) => (StatusCode::CREATED, Json(run)).into_response(),
+        Err(e) => error_body(codes::BAD_REQUEST, &e.to_string())
+            .into_response(),
+    }
+}
+
+/// Get a stored assessment run
+async fn get_run(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if !user.has_permission("assessments:read") {
+        return forbidden("assessments:read");
+    }
+
+    let service = AssessmentService::new(db.as_ref().clone());
+
+    match service.get_run(&id).await {
+        Ok(run) if run.tenant_id.is_none() || run.tenant_id == user.tenant_id => {
+            (StatusCode::OK, Json(run)).into_response()
+        }
+        Ok(_) | Err(_) => error_body(codes::NOT_FOUND, "Assessment run not found")
+            .into_response(),
+    }
+}
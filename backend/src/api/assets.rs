@@ -8,7 +8,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use surrealdb::sql::Thing;
-use crate::database::Database;
+use crate::database::{Database, TracedQuery};
+use crate::utils::pagination::{count_total, Pagination, SortOrder, Sortable};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Asset {
@@ -38,37 +39,53 @@ pub fn create_assets_router(db: Arc<Database>) -> Router {
 async fn list_assets(
     State(db): State<Arc<Database>>,
     Query(filter): Query<AssetFilter>,
+    pagination: Pagination<AssetSort>,
 ) -> impl IntoResponse {
     // For now, we primarily query the 'nutanix_cluster' table as our main asset source
     // In a real CMDB, this would query a unified 'asset' table or union multiple tables
-    
-    let mut query = "SELECT * FROM nutanix_cluster".to_string();
-    
-    // Simple filtering (in a real app, use parameterized queries for WHERE clauses)
-    // This is a simplified implementation for the prototype
-    
-    match db.query(&query).await {
-        Ok(mut response) => {
-            // SurrealDB returns a list of results. We take the first one.
-            let assets: Vec<Asset> = match response.take(0) {
-                Ok(a) => a,
-                Err(_) => vec![],
-            };
-            
-            // Manual filtering since we didn't do it in SQL for this quick prototype
-            let filtered: Vec<Asset> = assets.into_iter().filter(|a| {
-                if let Some(t) = &filter.asset_type {
-                    if &a.asset_type != t { return false; }
-                }
-                true
-            }).collect();
+    let mut conditions = vec!["true"];
+    if filter.asset_type.is_some() {
+        conditions.push("asset_type = $asset_type");
+    }
+    if filter.status.is_some() {
+        conditions.push("status = $status");
+    }
+    let where_clause = conditions.join(" AND ");
 
-            (StatusCode::OK, Json(filtered)).into_response()
-        },
+    let sql = format!(
+        "SELECT count() FROM nutanix_cluster WHERE {where_clause} GROUP ALL; \
+         SELECT * FROM nutanix_cluster WHERE {where_clause} {} {};",
+        pagination.order_clause(),
+        pagination.limit_clause()
+    );
+    let result = async {
+        let mut response = db
+            .query(sql)
+            .bind(("asset_type", filter.asset_type))
+            .bind(("status", filter.status))
+            .traced("assets.list")
+            .await?;
+        let count: Vec<serde_json::Value> = response.take(0)?;
+        let assets: Vec<Asset> = response.take(1)?;
+        Ok::<_, surrealdb::Error>(pagination.page(assets, count_total(&count)))
+    };
+
+    match result.await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
     }
 }
 
+/// Sortable asset list fields
+#[derive(Debug, Clone, Copy)]
+struct AssetSort;
+
+impl Sortable for AssetSort {
+    const SORT_FIELDS: &'static [&'static str] = &["name", "asset_type", "status", "created_at", "updated_at"];
+    const DEFAULT_SORT: &'static str = "name";
+    const DEFAULT_ORDER: SortOrder = SortOrder::Asc;
+}
+
 async fn get_asset(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
@@ -78,7 +95,7 @@ async fn get_asset(
     
     let sql = "SELECT * FROM nutanix_cluster WHERE id = $id OR external_id = $id";
     
-    match db.query(sql).bind(("id", id)).await {
+    match db.query(sql).bind(("id", id)).traced("assets.get_asset").await {
         Ok(mut response) => {
             let asset: Option<Asset> = response.take(0).ok().and_then(|v: Vec<Asset>| v.into_iter().next());
             match asset {
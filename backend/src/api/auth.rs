@@ -82,6 +82,7 @@ fn auth_error_response(error: AuthError) -> impl IntoResponse {
         AuthError::WeakPassword => (StatusCode::BAD_REQUEST, "Password does not meet requirements"),
         AuthError::CurrentPasswordIncorrect => (StatusCode::BAD_REQUEST, "Current password is incorrect"),
         AuthError::PermissionDenied => (StatusCode::FORBIDDEN, "Permission denied"),
+        AuthError::SeatLimitReached(_) => (StatusCode::FORBIDDEN, "All licensed seats are in use"),
         AuthError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
         AuthError::InternalError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"),
     };
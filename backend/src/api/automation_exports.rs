@@ -0,0 +1,134 @@
+// Archer - Automation Export API
+// Generated implementation code from a project's placement plan: IaC
+// scaffolds for the target clusters, Ansible cutover skeletons per wave and
+// Hyper-V PowerShell cutover scripts, as JSON or a zip download
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState},
+    middleware::project_access::{require_project_role, ProjectAccess},
+    models::automation_export::{AutomationBundle, AutomationExportQuery},
+    models::project_models::HypervisorType,
+    services::automation_export::{to_zip, AutomationExportError, AutomationExportService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Automation Export API router
+pub fn create_automation_exports_router(db: Arc<Database>) -> Router {
+    let access = ProjectAccess::new(db.clone());
+    let service = Arc::new(AutomationExportService::new(db));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/projects/:id/plan", get(get_plan))
+        .route("/projects/:id/iac", get(export_iac))
+        .route("/projects/:id/ansible", get(export_ansible))
+        .route("/projects/:id/powershell", get(export_powershell))
+        .route_layer(middleware::from_fn_with_state(access, require_project_role))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// The resolved plan every export is built from
+async fn get_plan(
+    State(service): State<Arc<AutomationExportService>>,
+    Path(project_id): Path<String>,
+    Query(query): Query<AutomationExportQuery>,
+) -> impl IntoResponse {
+    match service
+        .load_plan(&project_id, query.hypervisor.unwrap_or(HypervisorType::HyperV))
+        .await
+    {
+        Ok(plan) => (StatusCode::OK, Json(plan)).into_response(),
+        Err(e) => export_error_response(e),
+    }
+}
+
+/// Terraform/Bicep scaffold of the target infrastructure
+async fn export_iac(
+    State(service): State<Arc<AutomationExportService>>,
+    Path(project_id): Path<String>,
+    Query(query): Query<AutomationExportQuery>,
+) -> impl IntoResponse {
+    match service.iac_scaffold(&project_id, &query).await {
+        Ok(bundle) => bundle_response(bundle, query.format.as_deref()),
+        Err(e) => export_error_response(e),
+    }
+}
+
+/// Ansible inventory grouped by wave and target cluster, with playbook skeletons
+async fn export_ansible(
+    State(service): State<Arc<AutomationExportService>>,
+    Path(project_id): Path<String>,
+    Query(query): Query<AutomationExportQuery>,
+) -> impl IntoResponse {
+    match service.ansible_export(&project_id, &query).await {
+        Ok(bundle) => bundle_response(bundle, query.format.as_deref()),
+        Err(e) => export_error_response(e),
+    }
+}
+
+/// PowerShell scripts that create, configure and validate Hyper-V VM shells per wave
+async fn export_powershell(
+    State(service): State<Arc<AutomationExportService>>,
+    Path(project_id): Path<String>,
+    Query(query): Query<AutomationExportQuery>,
+) -> impl IntoResponse {
+    match service.powershell_export(&project_id, &query).await {
+        Ok(bundle) => bundle_response(bundle, query.format.as_deref()),
+        Err(e) => export_error_response(e),
+    }
+}
+
+/// A bundle as JSON (default) or as a zip download
+pub(crate) fn bundle_response(bundle: AutomationBundle, format: Option<&str>) -> Response {
+    match format.unwrap_or("json") {
+        "json" => (StatusCode::OK, Json(bundle)).into_response(),
+        "zip" => match to_zip(&bundle) {
+            Ok(archive) => (
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.zip\"", bundle.name)),
+                ],
+                archive,
+            )
+                .into_response(),
+            Err(e) => export_error_response(e),
+        },
+        other => export_error_response(AutomationExportError::Validation(format!(
+            "Unsupported format '{}'; use 'json' or 'zip'",
+            other
+        ))),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert AutomationExportError to HTTP response
+pub(crate) fn export_error_response(error: AutomationExportError) -> Response {
+    let (code, message) = match &error {
+        AutomationExportError::ProjectNotFound => (codes::NOT_FOUND, "Project not found"),
+        AutomationExportError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        AutomationExportError::Plan(_) => (codes::MIGRATION_PLANNING_ERROR, "Placement plan unavailable"),
+        AutomationExportError::Bundle(_) => (codes::IO_ERROR, "Bundle failed"),
+        AutomationExportError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
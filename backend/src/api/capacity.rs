@@ -1,28 +1,40 @@
 //! Capacity Planning API
 //!
-//! Endpoints for capacity calculation and VM placement planning.
+//! Endpoints for capacity calculation, VM placement planning and the
+//! tenant-wide capacity overview.
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Response},
-    routing::post,
+    routing::{get, post},
     Json, Router,
 };
 use std::sync::Arc;
 
 use crate::{
     database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    services::capacity_overview_service::{CapacityOverviewQuery, CapacityOverviewService},
     services::capacity_planner_service::{
-        CapacityPlannerService, CapacityPlanRequest, CapacityPlanResponse, PlacementRequest,
-        PlacementResponse,
+        CapacityPlannerService, CapacityPlanRequest, CapacityPlanResponse, ConsolidationRequest,
+        PlacementRequest, PlacementResponse,
     },
 };
 
 pub fn create_capacity_router(db: Arc<Database>) -> Router {
+    // The overview is tenant-scoped, so it sits behind authentication
+    let auth_state = AuthState::new();
+    let overview = Router::new()
+        .route("/overview", get(capacity_overview))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth));
+
     Router::new()
         .route("/plan", post(plan_capacity))
         .route("/placement", post(plan_placement))
+        .route("/optimize", post(optimize_consolidation))
+        .merge(overview)
         .with_state(db)
 }
 
@@ -66,6 +78,49 @@ async fn plan_placement(
     }
 }
 
+/// Optimize cluster consolidation
+///
+/// POST /capacity/optimize
+///
+/// Searches vendor hardware builds and node counts for the cheapest target
+/// cluster designs meeting CPU/RAM/storage headroom, returning the top-N
+/// with their trade-off metrics.
+async fn optimize_consolidation(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<ConsolidationRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = CapacityPlannerService::new((*db).clone());
+
+    match service.optimize_consolidation(request).await {
+        Ok(response) => Ok((StatusCode::OK, Json(response))),
+        Err(e) => Err(ApiError::InternalError(e.to_string())),
+    }
+}
+
+// =============================================================================
+// CAPACITY OVERVIEW ENDPOINT
+// =============================================================================
+
+/// Capacity overview across all projects and clusters in the tenant
+///
+/// GET /capacity/overview?threshold_percent=80&top=10
+///
+/// Returns per-cluster utilization and headroom, projected exhaustion dates
+/// from daily utilization snapshots, tenant totals and the top bottlenecks,
+/// so the executive dashboard renders from a single call.
+async fn capacity_overview(
+    State(db): State<Arc<Database>>,
+    Query(query): Query<CapacityOverviewQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = CapacityOverviewService::new((*db).clone());
+
+    match service.overview(user.tenant_id.as_deref(), &query).await {
+        Ok(overview) => Ok((StatusCode::OK, Json(overview))),
+        Err(e) => Err(ApiError::InternalError(e.to_string())),
+    }
+}
+
 // =============================================================================
 // ERROR HANDLING
 // =============================================================================
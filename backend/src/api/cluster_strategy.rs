@@ -28,6 +28,7 @@ use crate::{
     database::AppState, models::migration_models::*,
     services::dependency_validator::DependencyValidator,
 };
+use crate::database::TracedQuery;
 
 /// API response wrapper
 #[derive(Debug, Serialize, Deserialize)]
@@ -257,6 +258,7 @@ pub async fn list_cluster_strategies(
     let mut result = state
         .query(query)
         .bind(("project_id", project_thing))
+        .traced("cluster_strategy.list_cluster_strategies")
         .await
         .map_err(|e| {
             eprintln!("Database error listing cluster strategies: {:?}", e);
@@ -419,6 +421,7 @@ pub async fn validate_dependencies(
     let mut result = state
         .query(query)
         .bind(("project_id", project_thing))
+        .traced("cluster_strategy.validate_dependencies")
         .await
         .map_err(|e| {
             eprintln!("Database error fetching strategies for validation: {:?}", e);
@@ -454,6 +457,7 @@ pub async fn get_hardware_timeline(
     let mut strategies_result = state
         .query(strategies_query)
         .bind(("project_id", project_thing.clone()))
+        .traced("cluster_strategy.get_hardware_timeline")
         .await
         .map_err(|e| {
             eprintln!("Database error fetching strategies: {:?}", e);
@@ -470,6 +474,7 @@ pub async fn get_hardware_timeline(
     let mut procurement_result = state
         .query(procurement_query)
         .bind(("project_id", project_thing.clone()))
+        .traced("cluster_strategy.get_hardware_timeline")
         .await
         .map_err(|e| {
             eprintln!("Database error fetching procurement orders: {:?}", e);
@@ -3,8 +3,12 @@
 
 use crate::database::Database;
 use crate::middleware::auth::AuthenticatedUser;
+use crate::middleware::validation::{FieldErrors, Validate, ValidatedJson, ValidationRejection};
 use crate::models::cmdb::*;
+use crate::models::saved_view::{FilterEntity, FilterQueryRequest};
+use crate::services::filter_query::{self, FilterError};
 use crate::services::cmdb_service::{CMDBService, CMDBStatistics};
+use crate::services::depreciation_service::DepreciationService;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
@@ -24,12 +28,15 @@ pub fn cmdb_routes() -> Router<Arc<Database>> {
         // CI endpoints
         .route("/cis", get(list_cis).post(create_ci))
         .route("/cis/search", post(search_cis))
+        .route("/cis/query", post(query_cis))
         .route("/cis/statistics", get(get_statistics))
         .route("/cis/:id", get(get_ci).put(update_ci).delete(delete_ci))
         .route("/cis/:id/history", get(get_ci_history))
         .route("/cis/:id/impact", get(analyze_impact))
         // Lookup by CI ID (e.g., SRV-00001)
         .route("/cis/by-ci-id/:ci_id", get(get_ci_by_ci_id))
+        // Hardware book value and refresh forecast
+        .route("/financials/depreciation", get(get_depreciation_report))
         // Relationship endpoints
         .route("/relationships", get(list_relationships).post(create_relationship))
         .route("/relationships/:id", delete(delete_relationship))
@@ -43,7 +50,7 @@ pub fn cmdb_routes() -> Router<Arc<Database>> {
 async fn create_ci(
     State(db): State<Arc<Database>>,
     Extension(user): Extension<AuthenticatedUser>,
-    Json(request): Json<CreateCIRequest>,
+    ValidatedJson(request): ValidatedJson<CreateCIRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     if !user.has_permission("cmdb:create") {
         return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
@@ -110,7 +117,7 @@ async fn list_cis(
 async fn search_cis(
     State(db): State<Arc<Database>>,
     Extension(user): Extension<AuthenticatedUser>,
-    Json(request): Json<CISearchRequest>,
+    ValidatedJson(request): ValidatedJson<CISearchRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     if !user.has_permission("cmdb:read") {
         return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
@@ -127,6 +134,38 @@ async fn search_cis(
     }
 }
 
+/// Query CIs with a filter spec (field / operator / value, AND/OR groups)
+async fn query_cis(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<FilterQueryRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("cmdb:read") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'cmdb:read' required".to_string(),
+        })));
+    }
+
+    match filter_query::run_filter_query::<ConfigurationItem>(
+        &db,
+        FilterEntity::ConfigurationItems,
+        &request,
+        user.tenant_id.as_deref(),
+    )
+    .await
+    {
+        Ok(response) => Ok(Json(response)),
+        Err(FilterError::DatabaseError(e)) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e.to_string() }),
+        )),
+    }
+}
+
 /// Get CI by database ID
 async fn get_ci(
     State(db): State<Arc<Database>>,
@@ -182,7 +221,7 @@ async fn update_ci(
     State(db): State<Arc<Database>>,
     Extension(user): Extension<AuthenticatedUser>,
     Path(id): Path<String>,
-    Json(request): Json<UpdateCIRequest>,
+    ValidatedJson(request): ValidatedJson<UpdateCIRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     if !user.has_permission("cmdb:update") {
         return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
@@ -326,6 +365,27 @@ async fn get_statistics(
     }
 }
 
+/// Book value, monthly depreciation and refresh forecast for hardware CIs
+async fn get_depreciation_report(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<DepreciationReportQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("cmdb:read") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'cmdb:read' required".to_string(),
+        })));
+    }
+
+    match DepreciationService::report(db, params, user.tenant_id.as_deref()).await {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
 // ============================================================================
 // RELATIONSHIP HANDLERS
 // ============================================================================
@@ -361,7 +421,7 @@ async fn list_relationships(
 async fn create_relationship(
     State(db): State<Arc<Database>>,
     Extension(user): Extension<AuthenticatedUser>,
-    Json(request): Json<CreateRelationshipRequest>,
+    ValidatedJson(request): ValidatedJson<CreateRelationshipRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     if !user.has_permission("cmdb:update") {
         return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
@@ -457,6 +517,120 @@ pub struct RelationshipListParams {
     pub ci_id: Option<String>,
 }
 
+// ============================================================================
+// PAYLOAD VALIDATION
+// ============================================================================
+
+const MAX_NAME_LENGTH: usize = 255;
+const MAX_DESCRIPTION_LENGTH: usize = 10_000;
+
+impl Validate for CreateCIRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("name", &self.name);
+        errors.length("name", &self.name, MAX_NAME_LENGTH);
+        errors.required("ci_type", &self.ci_type);
+        errors.length("ci_type", &self.ci_type, MAX_NAME_LENGTH);
+        errors.not_blank("ci_id", &self.ci_id);
+        errors.optional_length("description", &self.description, MAX_DESCRIPTION_LENGTH);
+        errors.ip_address("ip_address", &self.ip_address);
+        errors.tags("tags", &self.tags);
+        check_lifecycle_dates(&mut errors, self.install_date, self.warranty_expiry, self.end_of_life);
+        if let Some(financials) = &self.financials {
+            if self.ci_class != CIClass::Hardware {
+                errors.add("financials", "hardware_only", "financials can only be recorded on hardware CIs");
+            }
+            check_financials(&mut errors, financials);
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateCIRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.not_blank("name", &self.name);
+        errors.optional_length("name", &self.name, MAX_NAME_LENGTH);
+        errors.not_blank("ci_type", &self.ci_type);
+        errors.optional_length("ci_type", &self.ci_type, MAX_NAME_LENGTH);
+        errors.optional_length("description", &self.description, MAX_DESCRIPTION_LENGTH);
+        errors.optional_length("change_reason", &self.change_reason, MAX_DESCRIPTION_LENGTH);
+        errors.ip_address("ip_address", &self.ip_address);
+        if let Some(tags) = &self.tags {
+            errors.tags("tags", tags);
+        }
+        check_lifecycle_dates(&mut errors, self.install_date, self.warranty_expiry, self.end_of_life);
+        if let Some(financials) = &self.financials {
+            check_financials(&mut errors, financials);
+        }
+        errors.into_result()
+    }
+}
+
+/// Costs must be non-negative with salvage no higher than cost, and the
+/// asset must depreciate over at least one month
+fn check_financials(errors: &mut FieldErrors, financials: &AssetFinancials) {
+    if !financials.purchase_cost.is_finite() || financials.purchase_cost < 0.0 {
+        errors.add("financials.purchase_cost", "min", "purchase_cost must not be negative");
+    }
+    if !financials.salvage_value.is_finite() || financials.salvage_value < 0.0 {
+        errors.add("financials.salvage_value", "min", "salvage_value must not be negative");
+    } else if financials.salvage_value > financials.purchase_cost {
+        errors.add("financials.salvage_value", "max", "salvage_value must not exceed purchase_cost");
+    }
+    errors.range("financials.useful_life_months", financials.useful_life_months, 1, 600);
+    if financials.declining_factor.map_or(false, |factor| !factor.is_finite() || factor <= 0.0) {
+        errors.add("financials.declining_factor", "min", "declining_factor must be greater than 0");
+    }
+    if financials.currency.as_deref().map_or(false, |code| code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic())) {
+        errors.add("financials.currency", "format", "currency must be a 3-letter ISO 4217 code");
+    }
+}
+
+/// A CI cannot go out of warranty or reach end of life before it is installed
+fn check_lifecycle_dates(
+    errors: &mut FieldErrors,
+    install_date: Option<chrono::DateTime<chrono::Utc>>,
+    warranty_expiry: Option<chrono::DateTime<chrono::Utc>>,
+    end_of_life: Option<chrono::DateTime<chrono::Utc>>,
+) {
+    let Some(installed) = install_date else { return };
+    if warranty_expiry.map_or(false, |date| date < installed) {
+        errors.add("warranty_expiry", "after_install_date", "warranty_expiry must not be before install_date");
+    }
+    if end_of_life.map_or(false, |date| date < installed) {
+        errors.add("end_of_life", "after_install_date", "end_of_life must not be before install_date");
+    }
+}
+
+impl Validate for CreateRelationshipRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("source_id", &self.source_id);
+        errors.required("target_id", &self.target_id);
+        if !self.source_id.trim().is_empty() && self.source_id == self.target_id {
+            errors.add("target_id", "distinct", "A CI cannot be related to itself");
+        }
+        errors.optional_length("description", &self.description, MAX_DESCRIPTION_LENGTH);
+        errors.into_result()
+    }
+}
+
+impl Validate for CISearchRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.optional_length("query", &self.query, MAX_NAME_LENGTH);
+        // Pages are 1-based; larger page sizes are capped by the service
+        if self.page == Some(0) {
+            errors.add("page", "min", "page must be at least 1");
+        }
+        if self.page_size == Some(0) {
+            errors.add("page_size", "min", "page_size must be at least 1");
+        }
+        errors.into_result()
+    }
+}
+
 // ============================================================================
 // ERROR RESPONSE
 // ============================================================================
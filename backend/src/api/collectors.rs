@@ -0,0 +1,112 @@
+// Archer - Collector API
+// Admin registry of the remote collectors allowed to stream inventory and
+// metrics over gRPC, and their tokens
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_admin,
+    },
+    models::collector::{CreateCollectorRequest, UpdateCollectorRequest},
+    services::collector_ingest::{CollectorError, CollectorService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Collector API router
+pub fn create_collectors_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(CollectorService::new(db));
+
+    Router::new()
+        .route("/", get(list_collectors).post(create_collector))
+        .route("/:id", get(get_collector).put(update_collector).delete(delete_collector))
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List every collector
+async fn list_collectors(State(service): State<Arc<CollectorService>>) -> impl IntoResponse {
+    match service.list_collectors().await {
+        Ok(collectors) => (StatusCode::OK, Json(collectors)).into_response(),
+        Err(e) => collector_error_response(e),
+    }
+}
+
+/// Register a collector; the response holds its token
+async fn create_collector(
+    State(service): State<Arc<CollectorService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateCollectorRequest>,
+) -> impl IntoResponse {
+    match service.create_collector(request, &user.username).await {
+        Ok(collector) => (StatusCode::CREATED, Json(collector)).into_response(),
+        Err(e) => collector_error_response(e),
+    }
+}
+
+async fn get_collector(
+    State(service): State<Arc<CollectorService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.get_collector(&id).await {
+        Ok(collector) => (StatusCode::OK, Json(collector)).into_response(),
+        Err(e) => collector_error_response(e),
+    }
+}
+
+/// Change a collector's projects or certificate pin, disable it or rotate
+/// its token
+async fn update_collector(
+    State(service): State<Arc<CollectorService>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateCollectorRequest>,
+) -> impl IntoResponse {
+    match service.update_collector(&id, request).await {
+        Ok(collector) => (StatusCode::OK, Json(collector)).into_response(),
+        Err(e) => collector_error_response(e),
+    }
+}
+
+async fn delete_collector(
+    State(service): State<Arc<CollectorService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_collector(&id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "deleted": id }))).into_response(),
+        Err(e) => collector_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert CollectorError to HTTP response
+fn collector_error_response(error: CollectorError) -> Response {
+    let (code, message) = match &error {
+        CollectorError::NotFound => (codes::NOT_FOUND, "Collector not found"),
+        CollectorError::Validation(_) => (codes::VALIDATION_ERROR, "Invalid collector"),
+        CollectorError::Unauthenticated => (codes::UNAUTHORIZED, "Invalid collector token"),
+        CollectorError::Forbidden(_) => (codes::FORBIDDEN, "Collector may not write to this project"),
+        CollectorError::Ingest(_) => (codes::INTERNAL_ERROR, "Collector ingestion failed"),
+        CollectorError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,129 @@
+//! Component Classification API
+//!
+//! Classifies hardware basket lines, lists the low-confidence review queue
+//! and records reviewers' corrections, which the classifier learns from for
+//! the reviewer's tenant.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::component_classification::*,
+    services::component_classification_service::{ComponentClassificationError, ComponentClassificationService},
+    utils::api_response::helpers::error_body,
+};
+use core_engine::error::codes;
+
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    InternalError(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (code, message) = match self {
+            ApiError::NotFound(msg) => (codes::NOT_FOUND, msg),
+            ApiError::BadRequest(msg) => (codes::BAD_REQUEST, msg),
+            ApiError::InternalError(msg) => (codes::INTERNAL_ERROR, msg),
+        };
+
+        error_body(code, &message).into_response()
+    }
+}
+
+impl From<ComponentClassificationError> for ApiError {
+    fn from(error: ComponentClassificationError) -> Self {
+        match error {
+            ComponentClassificationError::NotFound | ComponentClassificationError::CorrectionNotFound => {
+                ApiError::NotFound(error.to_string())
+            }
+            ComponentClassificationError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            ComponentClassificationError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+/// Create Component Classification API router with authentication
+pub fn create_component_classification_router(db: Arc<Database>) -> Router {
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/classify", post(classify))
+        .route("/review-queue", get(list_review_queue))
+        .route("/review-queue/:id/resolve", post(resolve_review_item))
+        .route("/corrections", get(list_corrections))
+        .route("/corrections/:id", delete(delete_correction))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(db)
+}
+
+/// Classify basket lines, queueing low-confidence ones for review
+async fn classify(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<ClassifyComponentsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = ComponentClassificationService::new(db.as_ref().clone())
+        .classify(user.tenant_id.as_deref(), request)
+        .await?;
+    Ok(Json(response))
+}
+
+/// List review items, optionally filtered by status
+async fn list_review_queue(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(query): Query<ReviewQueueQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let items = ComponentClassificationService::new(db.as_ref().clone())
+        .list_queue(user.tenant_id.as_deref(), query.status)
+        .await?;
+    Ok(Json(items))
+}
+
+/// Resolve a review item with the correct category
+async fn resolve_review_item(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Json(request): Json<ResolveReviewItemRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = ComponentClassificationService::new(db.as_ref().clone())
+        .resolve(user.tenant_id.as_deref(), &id, &user.username, request)
+        .await?;
+    Ok(Json(response))
+}
+
+/// List the tenant's corrections
+async fn list_corrections(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<impl IntoResponse, ApiError> {
+    let corrections = ComponentClassificationService::new(db.as_ref().clone())
+        .list_corrections(user.tenant_id.as_deref())
+        .await?;
+    Ok(Json(corrections))
+}
+
+/// Delete a correction
+async fn delete_correction(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    ComponentClassificationService::new(db.as_ref().clone())
+        .delete_correction(user.tenant_id.as_deref(), &id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,140 @@
+// Archer ITSM - Contracts API
+// REST endpoints for vendor support, maintenance and warranty contracts and
+// the CIs they cover
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    middleware::rbac::{check_assets_create, check_assets_delete, check_assets_read, check_assets_update},
+    models::contract::{ContractListQuery, ContractListResponse, CreateContractRequest, UpdateContractRequest},
+    services::contract_service::{ContractError, ContractService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Contracts API router
+pub fn create_contracts_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(ContractService::new(db));
+    let auth_state = AuthState::new();
+
+    let read_routes = Router::new()
+        .route("/", get(list_contracts))
+        .route("/:id", get(get_contract))
+        .layer(middleware::from_fn(check_assets_read));
+
+    let create_routes = Router::new()
+        .route("/", post(create_contract))
+        .layer(middleware::from_fn(check_assets_create));
+
+    let update_routes = Router::new()
+        .route("/:id", put(update_contract))
+        .layer(middleware::from_fn(check_assets_update));
+
+    let delete_routes = Router::new()
+        .route("/:id", delete(delete_contract))
+        .layer(middleware::from_fn(check_assets_delete));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(create_routes)
+        .merge(update_routes)
+        .merge(delete_routes)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List contracts, optionally by vendor, type, covered CI or upcoming expiry
+async fn list_contracts(
+    State(service): State<Arc<ContractService>>,
+    Query(query): Query<ContractListQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.list_contracts(&user, &query).await {
+        Ok(contracts) => {
+            let response = ContractListResponse {
+                total: contracts.len(),
+                contracts,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => contract_error_response(e),
+    }
+}
+
+/// Get a single contract
+async fn get_contract(
+    State(service): State<Arc<ContractService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.get_contract(&user, &id).await {
+        Ok(contract) => (StatusCode::OK, Json(contract)).into_response(),
+        Err(e) => contract_error_response(e),
+    }
+}
+
+/// Create a contract
+async fn create_contract(
+    State(service): State<Arc<ContractService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateContractRequest>,
+) -> impl IntoResponse {
+    match service.create_contract(&user, request).await {
+        Ok(contract) => (StatusCode::CREATED, Json(contract)).into_response(),
+        Err(e) => contract_error_response(e),
+    }
+}
+
+/// Update a contract; a new end date re-arms the expiry alerts
+async fn update_contract(
+    State(service): State<Arc<ContractService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateContractRequest>,
+) -> impl IntoResponse {
+    match service.update_contract(&user, &id, request).await {
+        Ok(contract) => (StatusCode::OK, Json(contract)).into_response(),
+        Err(e) => contract_error_response(e),
+    }
+}
+
+/// Delete a contract
+async fn delete_contract(
+    State(service): State<Arc<ContractService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.delete_contract(&user, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => contract_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert ContractError to HTTP response
+fn contract_error_response(error: ContractError) -> Response {
+    let (code, message) = match &error {
+        ContractError::NotFound => (codes::NOT_FOUND, "Contract not found"),
+        ContractError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        ContractError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
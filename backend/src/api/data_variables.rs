@@ -0,0 +1,140 @@
+// Archer - Data Variables API
+// REST endpoints for the data variables reports and HLD generation can use,
+// including user-defined variables computed from an expression
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::data_variable::{SaveDataVariableRequest, ValidateExpressionRequest},
+    services::data_variable_service::{upload_thing, DataVariableError, DataVariableService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Data Variables API router
+pub fn create_data_variables_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(DataVariableService::new((*db).clone()));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/", get(list_variables).post(create_variable))
+        .route("/validate", post(validate_expression))
+        .route("/evaluate", get(evaluate_variables))
+        .route("/:name", get(get_variable).put(update_variable).delete(delete_variable))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+#[derive(Debug, Deserialize)]
+struct EvaluateQuery {
+    upload_id: String,
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List built-in and user-defined variables
+async fn list_variables(State(service): State<Arc<DataVariableService>>) -> impl IntoResponse {
+    match service.list().await {
+        Ok(variables) => (StatusCode::OK, Json(variables)).into_response(),
+        Err(e) => data_variable_error_response(e),
+    }
+}
+
+/// Get one variable by name
+async fn get_variable(
+    State(service): State<Arc<DataVariableService>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match service.get(&name).await {
+        Ok(variable) => (StatusCode::OK, Json(variable)).into_response(),
+        Err(e) => data_variable_error_response(e),
+    }
+}
+
+/// Create a user-defined variable
+async fn create_variable(
+    State(service): State<Arc<DataVariableService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<SaveDataVariableRequest>,
+) -> impl IntoResponse {
+    match service.create(&user, request).await {
+        Ok(variable) => (StatusCode::CREATED, Json(variable)).into_response(),
+        Err(e) => data_variable_error_response(e),
+    }
+}
+
+/// Replace a user-defined variable's definition
+async fn update_variable(
+    State(service): State<Arc<DataVariableService>>,
+    Path(name): Path<String>,
+    Json(request): Json<SaveDataVariableRequest>,
+) -> impl IntoResponse {
+    match service.update(&name, request).await {
+        Ok(variable) => (StatusCode::OK, Json(variable)).into_response(),
+        Err(e) => data_variable_error_response(e),
+    }
+}
+
+/// Delete a user-defined variable
+async fn delete_variable(
+    State(service): State<Arc<DataVariableService>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match service.delete(&name).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => data_variable_error_response(e),
+    }
+}
+
+/// Check an expression, and evaluate it when an upload is given
+async fn validate_expression(
+    State(service): State<Arc<DataVariableService>>,
+    Json(request): Json<ValidateExpressionRequest>,
+) -> impl IntoResponse {
+    match service.validate_expression(&request).await {
+        Ok(validation) => (StatusCode::OK, Json(validation)).into_response(),
+        Err(e) => data_variable_error_response(e),
+    }
+}
+
+/// Every user-defined variable evaluated on one upload
+async fn evaluate_variables(
+    State(service): State<Arc<DataVariableService>>,
+    Query(params): Query<EvaluateQuery>,
+) -> impl IntoResponse {
+    match service.evaluate_custom(&upload_thing(&params.upload_id)).await {
+        Ok(values) => (StatusCode::OK, Json(values)).into_response(),
+        Err(e) => data_variable_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert DataVariableError to HTTP response
+pub(crate) fn data_variable_error_response(error: DataVariableError) -> Response {
+    let (code, message) = match &error {
+        DataVariableError::NotFound => (codes::NOT_FOUND, "Data variable not found"),
+        DataVariableError::BuiltIn => (codes::FORBIDDEN, "Built-in data variables cannot be changed"),
+        DataVariableError::Conflict(_) => (codes::CONFLICT, "Variable name already in use"),
+        DataVariableError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        DataVariableError::Expression(_) => (codes::VALIDATION_ERROR, "Invalid expression"),
+        DataVariableError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
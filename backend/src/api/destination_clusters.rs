@@ -6,7 +6,7 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
 use chrono::Utc;
@@ -18,8 +18,16 @@ use surrealdb::sql::Thing;
 use crate::{
     database::Database,
     models::project_models::*,
+    models::build_checklist::{ChecklistItemKind, SignOffChecklistItemRequest, UpdateChecklistRequest},
+    models::settings::ApplyOvercommitPolicyRequest,
+    services::build_checklist_service::{BuildChecklistError, BuildChecklistService},
     services::capacity_planner_service::CapacityPlannerService,
+    services::firmware_baseline_service::{FirmwareBaselineError, FirmwareBaselineService},
+    services::naming_policy_service::{NamingPolicyError, NamingPolicyService},
+    services::network_profile_service::{NetworkProfileError, NetworkProfileService},
+    services::overcommit_policy_service::{OvercommitPolicyError, OvercommitPolicyService},
 };
+use crate::database::TracedQuery;
 
 pub fn create_destination_clusters_router(db: Arc<Database>) -> Router {
     Router::new()
@@ -29,7 +37,17 @@ pub fn create_destination_clusters_router(db: Arc<Database>) -> Router {
         .route("/:cluster_id", patch(update_cluster))
         .route("/:cluster_id", delete(delete_cluster))
         .route("/:cluster_id/validate", post(validate_cluster))
+        .route("/:cluster_id/network-validation", post(validate_cluster_network))
         .route("/:cluster_id/build-status", patch(update_build_status))
+        .route("/:cluster_id/overcommit-policy", put(apply_overcommit_policy))
+        .route("/:cluster_id/overcommit-policy/preview", post(preview_overcommit_policy))
+        .route("/:cluster_id/checklist", get(get_checklist))
+        .route("/:cluster_id/checklist", patch(update_checklist))
+        .route("/:cluster_id/checklist/run", post(run_checklist))
+        .route("/:cluster_id/checklist/items/:kind/sign-off", post(sign_off_checklist_item))
+        .route("/:cluster_id/firmware-compliance", get(get_firmware_compliance))
+        .route("/:cluster_id/firmware-compliance", post(remediate_firmware_compliance))
+        .route("/:cluster_id/firmware-tasks", get(list_firmware_tasks))
         .with_state(db)
 }
 
@@ -47,6 +65,8 @@ pub struct CreateClusterRequest {
     pub nodes: Vec<String>, // Hardware pool IDs
     pub ha_policy: HaPolicy,
     pub overcommit_ratios: OvercommitRatios,
+    /// Library policy to take the ratios from instead
+    pub overcommit_policy_id: Option<String>,
     pub management_network: NetworkConfig,
     pub workload_network: NetworkConfig,
     pub storage_network: Option<NetworkConfig>,
@@ -61,6 +81,7 @@ pub struct UpdateClusterRequest {
     pub description: Option<String>,
     pub nodes: Option<Vec<String>>,
     pub ha_policy: Option<HaPolicy>,
+    /// Custom ratios; detaches the cluster from its policy
     pub overcommit_ratios: Option<OvercommitRatios>,
     pub management_network: Option<NetworkConfig>,
     pub workload_network: Option<NetworkConfig>,
@@ -147,6 +168,16 @@ async fn create_cluster(
         }
     }
 
+    let (overcommit_ratios, overcommit_policy_id) = match &request.overcommit_policy_id {
+        Some(policy_id) => {
+            let policy = OvercommitPolicyService::new(db.as_ref().clone())
+                .get_policy(policy_id)
+                .await?;
+            (policy.ratios(), policy.id)
+        }
+        None => (request.overcommit_ratios, None),
+    };
+
     // Create cluster
     let cluster = DestinationCluster {
         id: None,
@@ -158,7 +189,8 @@ async fn create_cluster(
         storage_type: request.storage_type,
         nodes: node_things,
         node_count: request.nodes.len() as i32,
-        overcommit_ratios: request.overcommit_ratios,
+        overcommit_ratios,
+        overcommit_policy_id,
         ha_policy: request.ha_policy,
         capacity_totals: total_capacity.clone(),
         capacity_available: total_capacity.clone(),
@@ -239,6 +271,7 @@ async fn list_clusters(
 
     let clusters: Result<Vec<DestinationCluster>, _> = db
         .query(query_str)
+        .traced("destination_clusters.list_clusters")
         .await
         .map(|mut response| response.take(0))
         .and_then(|result| result);
@@ -316,6 +349,7 @@ async fn update_cluster(
 
     if let Some(overcommit_ratios) = request.overcommit_ratios {
         cluster.overcommit_ratios = overcommit_ratios;
+        cluster.overcommit_policy_id = None;
     }
 
     if let Some(management_network) = request.management_network {
@@ -505,6 +539,20 @@ async fn validate_cluster(
         _ => {}
     }
 
+    // Check against the template of the applied network profile
+    validation_results.extend(
+        NetworkProfileService::new(db.as_ref().clone())
+            .applied_profile_issues(&cluster_id, &cluster)
+            .await?,
+    );
+
+    // Name and tags against the tenant's naming policy
+    validation_results.extend(
+        NamingPolicyService::new(db.as_ref().clone())
+            .destination_cluster_issues(&cluster)
+            .await?,
+    );
+
     // Update cluster status
     cluster.validation_results = validation_results.clone();
     cluster.status = if validation_results
@@ -582,6 +630,139 @@ async fn update_build_status(
     }
 }
 
+// =============================================================================
+// NETWORK PROFILE VALIDATION
+// =============================================================================
+
+/// Check the cluster's NICs and VLANs against a network profile template and
+/// record the failures in its validation results
+async fn validate_cluster_network(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+    request: Option<Json<ValidateClusterNetworkRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let validation = NetworkProfileService::new(db.as_ref().clone())
+        .validate_cluster(&cluster_id, &request)
+        .await?;
+    Ok(Json(validation))
+}
+
+// =============================================================================
+// OVERCOMMIT POLICY
+// =============================================================================
+
+/// Apply a library overcommit policy to the cluster
+async fn apply_overcommit_policy(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+    Json(request): Json<ApplyOvercommitPolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cluster = OvercommitPolicyService::new(db.as_ref().clone())
+        .apply_to_cluster(&cluster_id, &request.policy_id)
+        .await?;
+    let validation_summary = compute_validation_summary(&cluster.validation_results);
+    Ok(Json(ClusterResponse {
+        cluster,
+        validation_summary,
+    }))
+}
+
+/// Preview the cluster's capacity under a library overcommit policy
+async fn preview_overcommit_policy(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+    Json(request): Json<ApplyOvercommitPolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let preview = OvercommitPolicyService::new(db.as_ref().clone())
+        .preview_apply(&cluster_id, &request.policy_id)
+        .await?;
+    Ok(Json(preview))
+}
+
+// =============================================================================
+// BUILD CHECKLIST
+// =============================================================================
+
+/// Get the cluster's build checklist
+async fn get_checklist(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let checklist = BuildChecklistService::new(db.as_ref().clone())
+        .get_checklist(&cluster_id)
+        .await?;
+    Ok(Json(checklist))
+}
+
+/// Update checklist settings such as the firmware baseline
+async fn update_checklist(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+    Json(request): Json<UpdateChecklistRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let checklist = BuildChecklistService::new(db.as_ref().clone())
+        .update_checklist(&cluster_id, request)
+        .await?;
+    Ok(Json(checklist))
+}
+
+/// Run the automated checks and update the build status
+async fn run_checklist(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let checklist = BuildChecklistService::new(db.as_ref().clone())
+        .run_checks(&cluster_id)
+        .await?;
+    Ok(Json(checklist))
+}
+
+/// Sign off a checklist item that needs a manual decision
+async fn sign_off_checklist_item(
+    State(db): State<Arc<Database>>,
+    Path((cluster_id, kind)): Path<(String, ChecklistItemKind)>,
+    Json(request): Json<SignOffChecklistItemRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let checklist = BuildChecklistService::new(db.as_ref().clone())
+        .sign_off(&cluster_id, kind, request)
+        .await?;
+    Ok(Json(checklist))
+}
+
+/// Compare the cluster's nodes with their firmware baselines
+async fn get_firmware_compliance(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let report = FirmwareBaselineService::new(db.as_ref().clone())
+        .cluster_compliance(&cluster_id, false)
+        .await?;
+    Ok(Json(report))
+}
+
+/// Compare with the baselines and open remediation tasks for the gaps
+async fn remediate_firmware_compliance(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let report = FirmwareBaselineService::new(db.as_ref().clone())
+        .cluster_compliance(&cluster_id, true)
+        .await?;
+    Ok(Json(report))
+}
+
+/// List the cluster's firmware remediation tasks
+async fn list_firmware_tasks(
+    State(db): State<Arc<Database>>,
+    Path(cluster_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let tasks = FirmwareBaselineService::new(db.as_ref().clone())
+        .list_tasks(&cluster_id)
+        .await?;
+    Ok(Json(tasks))
+}
+
 // =============================================================================
 // HELPER FUNCTIONS
 // =============================================================================
@@ -615,14 +796,79 @@ fn compute_validation_summary(validation_results: &[ValidationIssue]) -> Validat
 #[derive(Debug)]
 enum ApiError {
     NotFound(String),
+    BadRequest(String),
     Conflict(String),
     InternalError(String),
 }
 
+impl From<OvercommitPolicyError> for ApiError {
+    fn from(error: OvercommitPolicyError) -> Self {
+        match error {
+            OvercommitPolicyError::NotFound | OvercommitPolicyError::ClusterNotFound => {
+                ApiError::NotFound(error.to_string())
+            }
+            OvercommitPolicyError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            OvercommitPolicyError::InUse(_) => ApiError::Conflict(error.to_string()),
+            OvercommitPolicyError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+impl From<BuildChecklistError> for ApiError {
+    fn from(error: BuildChecklistError) -> Self {
+        match error {
+            BuildChecklistError::ClusterNotFound | BuildChecklistError::ItemNotFound => {
+                ApiError::NotFound(error.to_string())
+            }
+            BuildChecklistError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            BuildChecklistError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+impl From<FirmwareBaselineError> for ApiError {
+    fn from(error: FirmwareBaselineError) -> Self {
+        match error {
+            FirmwareBaselineError::NotFound
+            | FirmwareBaselineError::ClusterNotFound
+            | FirmwareBaselineError::NodeNotFound
+            | FirmwareBaselineError::TaskNotFound => ApiError::NotFound(error.to_string()),
+            FirmwareBaselineError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            FirmwareBaselineError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+impl From<NetworkProfileError> for ApiError {
+    fn from(error: NetworkProfileError) -> Self {
+        match error {
+            NetworkProfileError::NotFound | NetworkProfileError::ClusterNotFound => {
+                ApiError::NotFound(error.to_string())
+            }
+            NetworkProfileError::Validation(_) | NetworkProfileError::Standard => {
+                ApiError::BadRequest(error.to_string())
+            }
+            NetworkProfileError::InUse(_) => ApiError::Conflict(error.to_string()),
+            NetworkProfileError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+impl From<NamingPolicyError> for ApiError {
+    fn from(error: NamingPolicyError) -> Self {
+        match error {
+            NamingPolicyError::NotFound => ApiError::NotFound(error.to_string()),
+            NamingPolicyError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            NamingPolicyError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
             ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
             ApiError::InternalError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
         };
@@ -0,0 +1,45 @@
+//! Diagnostics API
+//!
+//! Admin-only download of a scrubbed diagnostics bundle for support tickets.
+
+use axum::{
+    extract::State,
+    http::header,
+    middleware,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState},
+        rbac::check_admin,
+    },
+    services::diagnostics_service::DiagnosticsService,
+};
+
+pub fn create_diagnostics_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/bundle", get(download_bundle))
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(db)
+}
+
+/// GET /api/v1/diagnostics/bundle - Diagnostics bundle as a JSON attachment
+async fn download_bundle(State(db): State<Arc<Database>>) -> impl IntoResponse {
+    let bundle = DiagnosticsService::new(db.as_ref().clone()).bundle().await;
+    let filename = format!("archer-diagnostics-{}.json", Utc::now().format("%Y%m%d-%H%M%S"));
+
+    (
+        [(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )],
+        Json(bundle),
+    )
+}
@@ -0,0 +1,182 @@
+// Archer - Document Review API
+// Review and sign-off of generated HLD versions: reviewers, section comments,
+// the workflow-driven decision and publishing of the approved version
+
+use axum::{
+    body::StreamBody,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::document_review::{AddReviewCommentRequest, ReviewListQuery, StartReviewRequest},
+    services::document_review_service::{DocumentReviewError, DocumentReviewService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+const DOCX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Create Document Review API router
+pub fn create_document_reviews_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(DocumentReviewService::new(db));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/", get(list_reviews).post(start_review))
+        .route("/:id", get(get_review))
+        .route("/:id/cancel", post(cancel_review))
+        .route("/:id/publish", post(publish_review))
+        .route("/:id/published/content", get(download_published))
+        .route("/:id/comments", get(list_comments).post(add_comment))
+        .route("/:id/comments/:comment_id/resolve", post(resolve_comment))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List reviews, optionally by project or status
+async fn list_reviews(
+    State(service): State<Arc<DocumentReviewService>>,
+    Query(query): Query<ReviewListQuery>,
+) -> impl IntoResponse {
+    match service.list_reviews(&query).await {
+        Ok(reviews) => (StatusCode::OK, Json(reviews)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+/// Send an HLD version out for review
+async fn start_review(
+    State(service): State<Arc<DocumentReviewService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<StartReviewRequest>,
+) -> impl IntoResponse {
+    match service.start_review(&user, request).await {
+        Ok(review) => (StatusCode::CREATED, Json(review)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+/// A review with its comments
+async fn get_review(
+    State(service): State<Arc<DocumentReviewService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.get_review(&id).await {
+        Ok(review) => (StatusCode::OK, Json(review)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+/// Withdraw a review that has not been published
+async fn cancel_review(
+    State(service): State<Arc<DocumentReviewService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.cancel_review(&id).await {
+        Ok(review) => (StatusCode::OK, Json(review)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+/// Lock an approved version and store it with its approval page
+async fn publish_review(
+    State(service): State<Arc<DocumentReviewService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.publish_review(&user, &id).await {
+        Ok(review) => (StatusCode::OK, Json(review)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+/// Download the published copy
+async fn download_published(
+    State(service): State<Arc<DocumentReviewService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.open_published(&id).await {
+        Ok((document, stream)) => {
+            let filename = format!(
+                "{}-v{}-approved.docx",
+                document.document_name.replace(' ', "-"),
+                document.version
+            );
+            (
+                [
+                    (header::CONTENT_TYPE, DOCX_CONTENT_TYPE.to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+                ],
+                StreamBody::new(stream),
+            )
+                .into_response()
+        }
+        Err(e) => review_error_response(e),
+    }
+}
+
+async fn list_comments(
+    State(service): State<Arc<DocumentReviewService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.list_comments(&id).await {
+        Ok(comments) => (StatusCode::OK, Json(comments)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+/// Comment on a section of the reviewed version
+async fn add_comment(
+    State(service): State<Arc<DocumentReviewService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Json(request): Json<AddReviewCommentRequest>,
+) -> impl IntoResponse {
+    match service.add_comment(&user, &id, request).await {
+        Ok(comment) => (StatusCode::CREATED, Json(comment)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+async fn resolve_comment(
+    State(service): State<Arc<DocumentReviewService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path((id, comment_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service.resolve_comment(&user, &id, &comment_id).await {
+        Ok(comment) => (StatusCode::OK, Json(comment)).into_response(),
+        Err(e) => review_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert DocumentReviewError to HTTP response
+fn review_error_response(error: DocumentReviewError) -> Response {
+    let (code, message) = match &error {
+        DocumentReviewError::NotFound => (codes::NOT_FOUND, "Review not found"),
+        DocumentReviewError::DocumentNotFound(_) => (codes::NOT_FOUND, "Document version not found"),
+        DocumentReviewError::CommentNotFound => (codes::NOT_FOUND, "Comment not found"),
+        DocumentReviewError::InvalidState(_) => (codes::CONFLICT, "Invalid state"),
+        DocumentReviewError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        DocumentReviewError::Workflow(_) => (codes::BAD_REQUEST, "Workflow error"),
+        DocumentReviewError::Storage(_) => (codes::IO_ERROR, "Storage error"),
+        DocumentReviewError::Generation(_) => (codes::DOCUMENT_GENERATION_FAILED, "Generation failed"),
+        DocumentReviewError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,312 @@
+//! Project Documents API
+//!
+//! Upload, download and delete project documents kept in the configured
+//! storage backend. Downloads stream from storage; `/:id/url` hands the
+//! frontend a time-limited link (an S3 presigned URL, or a signed link to
+//! `/blob` for the disk backend) so large files bypass the API.
+//!
+//! Reading a document needs a viewer role on its project, uploading and
+//! deleting an editor role.
+
+use axum::{
+    body::StreamBody,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    middleware::project_access::{require_project_role, ProjectAccess},
+    models::project_access::ProjectRole,
+    models::workflow::{DocumentType, ProjectDocument},
+    services::document_service::DocumentService,
+    services::document_storage::{DiskDocumentStore, DocumentStore, StorageError},
+    services::project_access_service::{ProjectAccessError, ProjectAccessService},
+    utils::api_response::helpers::error_body,
+};
+use core_engine::error::codes;
+
+/// Matches the request size limit applied to every route
+const MAX_UPLOAD_BYTES: usize = 50 * 1024 * 1024;
+
+#[derive(Debug)]
+enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    Forbidden(String),
+    InternalError(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (code, message) = match self {
+            ApiError::NotFound(msg) => (codes::NOT_FOUND, msg),
+            ApiError::BadRequest(msg) => (codes::BAD_REQUEST, msg),
+            ApiError::Forbidden(msg) => (codes::FORBIDDEN, msg),
+            ApiError::InternalError(msg) => (codes::INTERNAL_ERROR, msg),
+        };
+
+        error_body(code, &message).into_response()
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        ApiError::InternalError(error.to_string())
+    }
+}
+
+impl From<StorageError> for ApiError {
+    fn from(error: StorageError) -> Self {
+        match error {
+            StorageError::InvalidToken => ApiError::Forbidden(error.to_string()),
+            StorageError::InvalidKey(_) => ApiError::BadRequest(error.to_string()),
+            _ => ApiError::InternalError(error.to_string()),
+        }
+    }
+}
+
+impl From<ProjectAccessError> for ApiError {
+    fn from(error: ProjectAccessError) -> Self {
+        match error {
+            ProjectAccessError::Forbidden(msg) => ApiError::Forbidden(msg),
+            _ => ApiError::InternalError(error.to_string()),
+        }
+    }
+}
+
+pub fn create_documents_router(db: Arc<Database>) -> Router {
+    let auth_state = AuthState::new();
+
+    let project_documents = Router::new()
+        .route(
+            "/projects/:project_id",
+            get(list_documents)
+                .post(upload_document)
+                .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)),
+        )
+        .route_layer(middleware::from_fn_with_state(ProjectAccess::new(db.clone()), require_project_role));
+
+    // Keyed by document id; the handlers check the document's project
+    let authenticated = Router::new()
+        .merge(project_documents)
+        .route("/:id", get(get_document).delete(delete_document))
+        .route("/:id/content", get(download_document))
+        .route("/:id/url", get(document_url))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth));
+
+    Router::new()
+        // Authorized by the signed token in the link instead of a session
+        .route("/blob", get(download_signed))
+        .merge(authenticated)
+        .with_state(db)
+}
+
+fn document_id(document: &ProjectDocument) -> String {
+    document
+        .id
+        .as_ref()
+        .map(|id| id.id.to_raw())
+        .unwrap_or_default()
+}
+
+/// The document, if the caller's role on its project allows `role`
+async fn authorized_document(
+    db: &Arc<Database>,
+    user: &AuthenticatedUser,
+    id: &str,
+    role: ProjectRole,
+) -> Result<ProjectDocument, ApiError> {
+    let document = DocumentService::get_document(db, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Document not found".to_string()))?;
+    ProjectAccessService::new(db.clone())
+        .authorize(user, &document.project_id.id.to_raw(), role)
+        .await?;
+    Ok(document)
+}
+
+fn stream_response(
+    stream: crate::services::document_storage::ByteStream,
+    content_type: &str,
+    file_name: &str,
+    content_length: Option<u64>,
+) -> Response {
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name.replace('"', "")),
+            ),
+        ],
+        StreamBody::new(stream),
+    )
+        .into_response();
+    if let Some(length) = content_length {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, header::HeaderValue::from(length));
+    }
+    response
+}
+
+/// Documents of a project
+async fn list_documents(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let documents = DocumentService::list_project_documents(&db, &project_id).await?;
+    Ok(Json(documents))
+}
+
+/// Upload a document as multipart `file` (and optional `document_type`,
+/// e.g. `HLD`). The file is streamed to storage as it arrives.
+async fn upload_document(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut document_type = DocumentType::Custom;
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+    {
+        let name = field.name().unwrap_or("").to_string();
+        match name.as_str() {
+            "document_type" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                document_type = serde_json::from_value(serde_json::Value::String(text.clone()))
+                    .map_err(|_| ApiError::BadRequest(format!("Unknown document type '{}'", text)))?;
+            }
+            "file" => {
+                let file_name = field
+                    .file_name()
+                    .map(|name| name.rsplit(['/', '\\']).next().unwrap_or(name).to_string())
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| ApiError::BadRequest("file needs a file name".to_string()))?;
+                let content_type = field
+                    .content_type()
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+
+                // Pump the field into a pipe while storage reads the other end
+                let (mut writer, mut reader) = tokio::io::duplex(64 * 1024);
+                let pump = async move {
+                    while let Some(chunk) = field.chunk().await.map_err(|e| e.to_string())? {
+                        writer.write_all(&chunk).await.map_err(|e| e.to_string())?;
+                    }
+                    Ok::<_, String>(())
+                };
+                let store = DocumentService::store_document(
+                    &db,
+                    &project_id,
+                    document_type.clone(),
+                    &file_name,
+                    &content_type,
+                    &mut reader,
+                );
+                let (pumped, stored) = tokio::join!(pump, store);
+                let document = stored?;
+                if let Err(e) = pumped {
+                    // The client went away mid-upload; don't keep a truncated file
+                    DocumentService::delete_document(&db, &document_id(&document)).await?;
+                    return Err(ApiError::BadRequest(format!("Upload interrupted: {}", e)));
+                }
+                return Ok((StatusCode::CREATED, Json(document)));
+            }
+            _ => {}
+        }
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
+
+async fn get_document(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let document = authorized_document(&db, &user, &id, ProjectRole::Viewer).await?;
+    Ok(Json(document))
+}
+
+/// Stream the document's contents
+async fn download_document(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    authorized_document(&db, &user, &id, ProjectRole::Viewer).await?;
+    let (document, stream) = DocumentService::open_document(&db, &id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Document not found".to_string()))?;
+    let content_type = document
+        .content_type
+        .as_deref()
+        .unwrap_or("application/octet-stream");
+    Ok(stream_response(
+        stream,
+        content_type,
+        &document.document_name,
+        Some(document.file_size),
+    ))
+}
+
+/// A time-limited download link for the frontend
+async fn document_url(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    authorized_document(&db, &user, &id, ProjectRole::Viewer).await?;
+    let url = DocumentService::document_url(&db, &id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Document not found".to_string()))?;
+    Ok(Json(url))
+}
+
+async fn delete_document(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    authorized_document(&db, &user, &id, ProjectRole::Editor).await?;
+    DocumentService::delete_document(&db, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedDownloadQuery {
+    token: String,
+}
+
+/// Download through a link issued by `/:id/url` on the disk backend
+async fn download_signed(Query(query): Query<SignedDownloadQuery>) -> Result<Response, ApiError> {
+    let store = DiskDocumentStore::from_env();
+    let key = store.verify_download_token(&query.token)?;
+    let stream = store
+        .get_stream(&key)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Document not found".to_string()))?;
+    let file_name = key.rsplit('/').next().unwrap_or(&key).to_string();
+    Ok(stream_response(
+        stream,
+        "application/octet-stream",
+        &file_name,
+        None,
+    ))
+}
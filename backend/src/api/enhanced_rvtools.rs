@@ -16,6 +16,7 @@ use crate::{
     services::enhanced_rvtools_service::{EnhancedRvToolsService, RvToolsExcelUploadData},
 };
 use serde_json::json;
+use crate::database::TracedQuery;
 
 pub fn create_enhanced_rvtools_router(db: Arc<Database>) -> Router {
     Router::new()
@@ -142,6 +143,7 @@ async fn get_rvtools_uploads(
 
     let uploads: Vec<RvToolsUpload> = db
         .query(&query_str)
+        .traced("enhanced_rvtools.get_rvtools_uploads")
         .await
         .map_err(|e| ApiError::InternalError(e.to_string()))?
         .take(0)
@@ -162,6 +164,7 @@ async fn get_project_clusters(
 
     let uploads: Vec<RvToolsUpload> = db
         .query(&query_str)
+        .traced("enhanced_rvtools.get_project_clusters")
         .await
         .map_err(|e| ApiError::InternalError(e.to_string()))?
         .take(0)
@@ -286,6 +289,7 @@ async fn list_report_templates(
     // Use raw JSON response to avoid struct conversion issues
     let raw_templates: Result<Vec<serde_json::Value>, _> = db
         .query(query_str)
+        .traced("enhanced_rvtools.list_report_templates")
         .await
         .map(|mut response| response.take(0))
         .and_then(|result| result);
@@ -336,6 +340,7 @@ async fn get_storage_analysis(
             "upload_id",
             surrealdb::sql::Thing::from(("rvtools_upload", upload_id.as_str())),
         ))
+        .traced("enhanced_rvtools.get_storage_analysis")
         .await
         .map(|mut response| response.take(0))
         .and_then(|result| result);
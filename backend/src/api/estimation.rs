@@ -0,0 +1,251 @@
+// Effort & Cost Estimation API Endpoints
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::{delete, get, post, put},
+    Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::models::estimation::*;
+use crate::services::currency_service::{CurrencyError, CurrencyService};
+use crate::services::estimation_service::EstimationService;
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::{codes, ErrorCode};
+
+pub fn create_estimation_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/rate-cards", get(list_rate_cards))
+        .route("/rate-cards", post(create_rate_card))
+        .route("/rate-cards/default", get(get_default_rate_card))
+        .route("/rate-cards/:id", get(get_rate_card))
+        .route("/rate-cards/:id", delete(delete_rate_card))
+        .route("/projects/:id", get(get_project_estimate))
+        .route("/projects/:id", post(estimate_project))
+        .route("/projects/:id/currency", get(get_project_currency))
+        .route("/projects/:id/currency", put(set_project_currency))
+        .route("/projects/:id/bom", post(convert_project_bom))
+        .with_state(db)
+}
+
+fn error_response(code: ErrorCode, e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    error_body(code, &e.to_string())
+}
+
+fn currency_error_response(e: CurrencyError) -> (StatusCode, Json<serde_json::Value>) {
+    let code = match e {
+        CurrencyError::ProjectNotFound => codes::NOT_FOUND,
+        CurrencyError::Validation(_) | CurrencyError::Conversion(_) => codes::BAD_REQUEST,
+        CurrencyError::Feed(_) => codes::UPSTREAM_UNAVAILABLE,
+        CurrencyError::DatabaseError(_) => codes::INTERNAL_ERROR,
+    };
+    error_response(code, e.into())
+}
+
+/// List configured rate cards
+/// GET /api/v1/estimation/rate-cards
+async fn list_rate_cards(
+    State(db): State<Arc<Database>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Listing rate cards");
+
+    let service = EstimationService::new(db.as_ref().clone());
+
+    match service.list_rate_cards().await {
+        Ok(cards) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "rate_cards": cards,
+                "total": cards.len()
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to list rate cards: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Get the built-in rate card (template for custom cards)
+/// GET /api/v1/estimation/rate-cards/default
+async fn get_default_rate_card() -> impl IntoResponse {
+    Json(json!({
+        "success": true,
+        "result": EstimationService::default_rate_card()
+    }))
+}
+
+/// Create a rate card
+/// POST /api/v1/estimation/rate-cards
+async fn create_rate_card(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<CreateRateCardRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Creating rate card: {}", request.name);
+
+    let service = EstimationService::new(db.as_ref().clone());
+
+    match service.create_rate_card(request).await {
+        Ok(card) => Ok((StatusCode::CREATED, Json(json!({
+            "success": true,
+            "result": card
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to create rate card: {}", e);
+            Err(error_response(codes::BAD_REQUEST, e))
+        }
+    }
+}
+
+/// Get a rate card
+/// GET /api/v1/estimation/rate-cards/:id
+async fn get_rate_card(
+    State(db): State<Arc<Database>>,
+    Path(rate_card_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Getting rate card: {}", rate_card_id);
+
+    let service = EstimationService::new(db.as_ref().clone());
+
+    match service.get_rate_card(&rate_card_id).await {
+        Ok(card) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": card
+        })))),
+        Err(e) => Err(error_response(codes::NOT_FOUND, e)),
+    }
+}
+
+/// Delete a rate card
+/// DELETE /api/v1/estimation/rate-cards/:id
+async fn delete_rate_card(
+    State(db): State<Arc<Database>>,
+    Path(rate_card_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Deleting rate card: {}", rate_card_id);
+
+    let service = EstimationService::new(db.as_ref().clone());
+
+    match service.delete_rate_card(&rate_card_id).await {
+        Ok(()) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "message": "Rate card deleted successfully"
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to delete rate card: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Get the latest services estimate for a project
+/// GET /api/v1/estimation/projects/:id
+async fn get_project_estimate(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Getting services estimate for project: {}", project_id);
+
+    let service = EstimationService::new(db.as_ref().clone());
+
+    match service.get_project_estimate(&project_id).await {
+        Ok(Some(estimate)) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": estimate
+        })))),
+        Ok(None) => Err(error_body(codes::NOT_FOUND, "No services estimate has been generated for this project")),
+        Err(e) => {
+            tracing::error!("Failed to get services estimate: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Estimate services effort and cost for a project
+/// POST /api/v1/estimation/projects/:id
+async fn estimate_project(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<EstimateServicesRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Estimating services for project: {}", project_id);
+
+    let service = EstimationService::new(db.as_ref().clone());
+
+    match service.estimate_project(&project_id, request).await {
+        Ok(estimate) => Ok((StatusCode::CREATED, Json(json!({
+            "success": true,
+            "result": estimate
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to estimate services: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Get the currency a project's costs are shown in
+/// GET /api/v1/estimation/projects/:id/currency
+async fn get_project_currency(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = CurrencyService::new(db.as_ref().clone());
+
+    match service.project_currency(&project_id).await {
+        Ok(currency) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "currency": currency
+            }
+        })))),
+        Err(e) => Err(currency_error_response(e)),
+    }
+}
+
+/// Set the currency a project's costs are shown in
+/// PUT /api/v1/estimation/projects/:id/currency
+async fn set_project_currency(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<SetDisplayCurrencyRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Setting display currency for project {}: {}", project_id, request.currency);
+
+    let service = CurrencyService::new(db.as_ref().clone());
+
+    match service.set_project_currency(&project_id, &request.currency).await {
+        Ok(project) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": project
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to set display currency: {}", e);
+            Err(currency_error_response(e))
+        }
+    }
+}
+
+/// Total a bill of materials, possibly quoted in several currencies, in the
+/// project's display currency
+/// POST /api/v1/estimation/projects/:id/bom
+async fn convert_project_bom(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<ConvertBomRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = CurrencyService::new(db.as_ref().clone());
+
+    match service.convert_bom(&project_id, &request.lines).await {
+        Ok(bom) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": bom
+        })))),
+        Err(e) => Err(currency_error_response(e)),
+    }
+}
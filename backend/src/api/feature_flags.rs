@@ -0,0 +1,53 @@
+// Archer - Feature Flags API
+// Evaluates every feature flag for the caller so the frontend can hide features
+// that are off. Flags are changed through the settings API (`features.*` keys).
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::settings::EffectiveSettingsQuery,
+    services::feature_flag_service::FeatureFlagService,
+    services::settings_service::SettingsError,
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Feature Flags API router
+pub fn create_feature_flags_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/", get(evaluate_flags))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(Arc::new(FeatureFlagService::new(db)))
+}
+
+/// Every flag, whether it is on for the caller and which layer decided it
+async fn evaluate_flags(
+    State(service): State<Arc<FeatureFlagService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Query(query): Query<EffectiveSettingsQuery>,
+) -> impl IntoResponse {
+    match service.evaluate_all(&user, query.project_id.as_deref()).await {
+        Ok(flags) => (StatusCode::OK, Json(flags)).into_response(),
+        Err(e) => feature_flag_error_response(e),
+    }
+}
+
+fn feature_flag_error_response(error: SettingsError) -> Response {
+    let (code, message) = match &error {
+        SettingsError::Forbidden(_) => (codes::FORBIDDEN, "Access denied"),
+        SettingsError::UnknownKey(_) | SettingsError::NotFound => (codes::NOT_FOUND, "Not found"),
+        SettingsError::Validation(_) => (codes::VALIDATION_ERROR, "Validation failed"),
+        SettingsError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,140 @@
+//! Firmware Baselines API
+//!
+//! Supported BIOS/firmware/driver versions per hardware model and
+//! hypervisor, vendor support matrix import and remediation task updates.
+//! Cluster compliance lives under the destination cluster routes.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, patch, post},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    models::firmware_baseline::*,
+    services::firmware_baseline_service::{FirmwareBaselineError, FirmwareBaselineService},
+    utils::api_response::helpers::error_body,
+};
+use core_engine::error::codes;
+
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    InternalError(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (code, message) = match self {
+            ApiError::NotFound(msg) => (codes::NOT_FOUND, msg),
+            ApiError::BadRequest(msg) => (codes::BAD_REQUEST, msg),
+            ApiError::InternalError(msg) => (codes::INTERNAL_ERROR, msg),
+        };
+
+        error_body(code, &message).into_response()
+    }
+}
+
+impl From<FirmwareBaselineError> for ApiError {
+    fn from(error: FirmwareBaselineError) -> Self {
+        match error {
+            FirmwareBaselineError::NotFound
+            | FirmwareBaselineError::ClusterNotFound
+            | FirmwareBaselineError::NodeNotFound
+            | FirmwareBaselineError::TaskNotFound => ApiError::NotFound(error.to_string()),
+            FirmwareBaselineError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            FirmwareBaselineError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+/// Create Firmware Baselines API router
+pub fn create_firmware_baselines_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/", get(list_baselines).post(create_baseline))
+        .route("/import", post(import_matrix))
+        .route("/:id", get(get_baseline).put(update_baseline).delete(delete_baseline))
+        .route("/tasks/:task_id", patch(update_task))
+        .with_state(db)
+}
+
+/// List baselines
+async fn list_baselines(State(db): State<Arc<Database>>) -> Result<impl IntoResponse, ApiError> {
+    let baselines = FirmwareBaselineService::new(db.as_ref().clone())
+        .list_baselines()
+        .await?;
+    Ok(Json(baselines))
+}
+
+/// Get a baseline
+async fn get_baseline(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let baseline = FirmwareBaselineService::new(db.as_ref().clone())
+        .get_baseline(&id)
+        .await?;
+    Ok(Json(baseline))
+}
+
+/// Create a baseline for a hardware model and hypervisor
+async fn create_baseline(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<CreateFirmwareBaselineRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let baseline = FirmwareBaselineService::new(db.as_ref().clone())
+        .create_baseline(request)
+        .await?;
+    Ok((StatusCode::CREATED, Json(baseline)))
+}
+
+/// Replace a baseline's component versions or source
+async fn update_baseline(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateFirmwareBaselineRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let baseline = FirmwareBaselineService::new(db.as_ref().clone())
+        .update_baseline(&id, request)
+        .await?;
+    Ok(Json(baseline))
+}
+
+/// Delete a baseline
+async fn delete_baseline(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    FirmwareBaselineService::new(db.as_ref().clone())
+        .delete_baseline(&id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Import a vendor support matrix (CSV or JSON)
+async fn import_matrix(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<ImportFirmwareMatrixRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = FirmwareBaselineService::new(db.as_ref().clone())
+        .import_matrix(request)
+        .await?;
+    Ok(Json(result))
+}
+
+/// Mark a remediation task done or reopen it
+async fn update_task(
+    State(db): State<Arc<Database>>,
+    Path(task_id): Path<String>,
+    Json(request): Json<UpdateRemediationTaskRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let task = FirmwareBaselineService::new(db.as_ref().clone())
+        .update_task(&task_id, request)
+        .await?;
+    Ok(Json(task))
+}
@@ -0,0 +1,694 @@
+// Archer - GraphQL API
+// Read-only GraphQL facade over the migration wizard for nested views such as
+// project → clusters → placements → VMs in one request. Mutations stay on
+// the REST API.
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Error, ErrorExtensions, Guard, Object, Result, Schema, ID,
+};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::{extract::State, middleware, routing::post, Extension, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use tokio::sync::OnceCell;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        feature_flags::{require_feature, FeatureGate},
+    },
+    models::{
+        migration_wizard_models::{
+            MigrationWizardCluster, MigrationWizardPlacement, MigrationWizardProject, MigrationWizardVM,
+            ProjectFilter, ProjectStatus,
+        },
+        project_access::ProjectRole,
+        settings::FeatureFlag,
+    },
+    services::{
+        migration_wizard_service::MigrationWizardService,
+        project_access_service::{project_key, ProjectAccessError, ProjectAccessService},
+    },
+};
+use core_engine::error::{codes, ErrorCode};
+
+pub type ArcherSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Deepest selection accepted; project → clusters → placements → vm → … is 5
+const MAX_DEPTH: usize = 10;
+const MAX_COMPLEXITY: usize = 1000;
+const DEFAULT_PROJECT_PAGE: i32 = 50;
+const MAX_PROJECT_PAGE: i32 = 200;
+
+/// Permission needed for the addresses, host and notes of a VM
+const GUEST_DETAILS_PERMISSION: &str = "assets:read";
+
+/// Create GraphQL API router
+pub fn create_graphql_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/", post(execute))
+        .route_layer(middleware::from_fn_with_state(
+            FeatureGate::new(db.clone(), FeatureFlag::GraphqlApi),
+            require_feature,
+        ))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(build_schema(db))
+}
+
+pub fn build_schema(db: Arc<Database>) -> ArcherSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(db)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Run a query as the signed-in user
+async fn execute(
+    State(schema): State<ArcherSchema>,
+    Extension(user): Extension<AuthenticatedUser>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    schema.execute(request.into_inner().data(user)).await.into()
+}
+
+// ============================================================================
+// QUERY ROOT
+// ============================================================================
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Projects the caller is a member of or that nobody has shared yet,
+    /// newest first
+    async fn projects(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+        #[graphql(default_with = "DEFAULT_PROJECT_PAGE")] first: i32,
+        #[graphql(default = 0)] offset: i32,
+    ) -> Result<Vec<ProjectNode>> {
+        let (db, user) = caller(ctx)?;
+        let filter = ProjectFilter {
+            status: status.as_deref().map(parse_status).transpose()?,
+            limit: Some(first.clamp(1, MAX_PROJECT_PAGE) as usize),
+            offset: Some(offset.max(0) as usize),
+        };
+        let projects = MigrationWizardService::new(db.as_ref().clone())
+            .list_projects(Some(filter))
+            .await
+            .map_err(internal_error)?;
+
+        let ids: Vec<String> = projects.iter().map(|p| record_key(&p.id)).collect();
+        let visible = ProjectAccessService::new(db.clone())
+            .visible_projects(user, &ids)
+            .await
+            .map_err(access_error)?;
+        Ok(projects
+            .into_iter()
+            .filter(|p| visible.contains(&record_key(&p.id)))
+            .map(|p| ProjectNode::new(p, None))
+            .collect())
+    }
+
+    /// One project; needs a viewer role on it
+    async fn project(&self, ctx: &Context<'_>, id: ID) -> Result<ProjectNode> {
+        let (db, user) = caller(ctx)?;
+        let key = project_key(&id);
+        let role = ProjectAccessService::new(db.clone())
+            .authorize(user, &key, ProjectRole::Viewer)
+            .await
+            .map_err(access_error)?;
+        let project = MigrationWizardService::new(db.as_ref().clone())
+            .get_project(&key)
+            .await
+            .map_err(|_| api_error(codes::NOT_FOUND, "Project not found"))?;
+        Ok(ProjectNode::new(project, Some(role)))
+    }
+}
+
+// ============================================================================
+// PROJECT
+// ============================================================================
+
+pub struct ProjectNode {
+    project: MigrationWizardProject,
+    /// Known when the project was fetched by id; looked up on demand otherwise
+    role: Option<ProjectRole>,
+    plan: OnceCell<Arc<ProjectPlan>>,
+}
+
+impl ProjectNode {
+    fn new(project: MigrationWizardProject, role: Option<ProjectRole>) -> Self {
+        Self {
+            project,
+            role,
+            plan: OnceCell::new(),
+        }
+    }
+
+    fn key(&self) -> String {
+        record_key(&self.project.id)
+    }
+
+    /// VMs, clusters and placements, loaded once whichever nested fields ask
+    async fn plan(&self, ctx: &Context<'_>) -> Result<Arc<ProjectPlan>> {
+        let plan = self
+            .plan
+            .get_or_try_init(|| async {
+                let (db, _) = caller(ctx)?;
+                let (vms, clusters, placements) = MigrationWizardService::new(db.as_ref().clone())
+                    .get_project_plan(&self.key())
+                    .await
+                    .map_err(internal_error)?;
+                Ok::<_, Error>(Arc::new(ProjectPlan::new(vms, clusters, placements)))
+            })
+            .await?;
+        Ok(plan.clone())
+    }
+}
+
+#[Object(name = "Project")]
+impl ProjectNode {
+    async fn id(&self) -> ID {
+        ID(self.key())
+    }
+
+    async fn name(&self) -> &str {
+        &self.project.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.project.description.as_deref()
+    }
+
+    /// draft, in_progress, completed or archived
+    async fn status(&self) -> String {
+        snake_case(&self.project.status)
+    }
+
+    async fn tenant_id(&self) -> Option<&str> {
+        self.project.tenant_id.as_deref()
+    }
+
+    async fn display_currency(&self) -> Option<&str> {
+        self.project.display_currency.as_deref()
+    }
+
+    async fn total_vms(&self) -> i32 {
+        self.project.total_vms
+    }
+
+    async fn total_clusters(&self) -> i32 {
+        self.project.total_clusters
+    }
+
+    async fn wizard_step(&self) -> i32 {
+        self.project.wizard_step
+    }
+
+    async fn rvtools_filename(&self) -> Option<&str> {
+        self.project.rvtools_filename.as_deref()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.project.created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.project.updated_at
+    }
+
+    /// The caller's role on the project: viewer, editor or owner
+    async fn my_role(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let role = match self.role {
+            Some(role) => Some(role),
+            None => {
+                let (db, user) = caller(ctx)?;
+                ProjectAccessService::new(db.clone())
+                    .effective_role(user, &self.key())
+                    .await
+                    .map_err(access_error)?
+            }
+        };
+        Ok(role.map(|role| snake_case(&role)))
+    }
+
+    /// Destination clusters in the order they were added
+    async fn clusters(&self, ctx: &Context<'_>) -> Result<Vec<ClusterNode>> {
+        let plan = self.plan(ctx).await?;
+        Ok((0..plan.clusters.len()).map(|index| ClusterNode { plan: plan.clone(), index }).collect())
+    }
+
+    async fn placements(&self, ctx: &Context<'_>) -> Result<Vec<PlacementNode>> {
+        let plan = self.plan(ctx).await?;
+        Ok((0..plan.placements.len())
+            .map(|index| PlacementNode { plan: plan.clone(), index })
+            .collect())
+    }
+
+    /// VMs by name. `sourceCluster` and `powerstate` filter like the REST
+    /// list; `placed: false` leaves only the VMs without a placement.
+    async fn vms(
+        &self,
+        ctx: &Context<'_>,
+        source_cluster: Option<String>,
+        powerstate: Option<String>,
+        placed: Option<bool>,
+        first: Option<i32>,
+        #[graphql(default = 0)] offset: i32,
+    ) -> Result<Vec<VmNode>> {
+        let plan = self.plan(ctx).await?;
+        let matching = plan.vms.iter().enumerate().filter(|(_, vm)| {
+            source_cluster.as_ref().map_or(true, |c| vm.cluster.as_ref() == Some(c))
+                && powerstate.as_ref().map_or(true, |p| vm.powerstate.as_ref() == Some(p))
+                && placed.map_or(true, |placed| plan.placement_of(vm).is_some() == placed)
+        });
+        Ok(matching
+            .skip(offset.max(0) as usize)
+            .take(first.map_or(usize::MAX, |first| first.max(0) as usize))
+            .map(|(index, _)| VmNode { plan: plan.clone(), index })
+            .collect())
+    }
+}
+
+// ============================================================================
+// CLUSTER, PLACEMENT AND VM
+// ============================================================================
+
+/// Records of one project with lookups between them
+pub struct ProjectPlan {
+    vms: Vec<MigrationWizardVM>,
+    clusters: Vec<MigrationWizardCluster>,
+    placements: Vec<MigrationWizardPlacement>,
+    vm_index: HashMap<String, usize>,
+    cluster_index: HashMap<String, usize>,
+    /// Placement index by VM key
+    placement_index: HashMap<String, usize>,
+}
+
+impl ProjectPlan {
+    fn new(
+        vms: Vec<MigrationWizardVM>,
+        clusters: Vec<MigrationWizardCluster>,
+        placements: Vec<MigrationWizardPlacement>,
+    ) -> Self {
+        let vm_index = vms.iter().enumerate().map(|(i, vm)| (record_key(&vm.id), i)).collect();
+        let cluster_index = clusters.iter().enumerate().map(|(i, c)| (record_key(&c.id), i)).collect();
+        let placement_index = placements.iter().enumerate().map(|(i, p)| (p.vm_id.id.to_raw(), i)).collect();
+        Self {
+            vms,
+            clusters,
+            placements,
+            vm_index,
+            cluster_index,
+            placement_index,
+        }
+    }
+
+    fn placement_of(&self, vm: &MigrationWizardVM) -> Option<usize> {
+        self.placement_index.get(&record_key(&vm.id)).copied()
+    }
+
+    fn cluster_of(&self, placement: &MigrationWizardPlacement) -> Option<usize> {
+        self.cluster_index.get(&placement.cluster_id.id.to_raw()).copied()
+    }
+
+    fn placements_on(&self, cluster: &MigrationWizardCluster) -> impl Iterator<Item = usize> + '_ {
+        let key = record_key(&cluster.id);
+        self.placements
+            .iter()
+            .enumerate()
+            .filter(move |(_, p)| p.cluster_id.id.to_raw() == key)
+            .map(|(index, _)| index)
+    }
+}
+
+pub struct ClusterNode {
+    plan: Arc<ProjectPlan>,
+    index: usize,
+}
+
+impl ClusterNode {
+    fn cluster(&self) -> &MigrationWizardCluster {
+        &self.plan.clusters[self.index]
+    }
+
+    /// Placements onto this cluster
+    fn placed(&self) -> impl Iterator<Item = &MigrationWizardPlacement> {
+        self.plan.placements_on(self.cluster()).map(|index| &self.plan.placements[index])
+    }
+}
+
+#[Object(name = "Cluster")]
+impl ClusterNode {
+    async fn id(&self) -> ID {
+        ID(record_key(&self.cluster().id))
+    }
+
+    async fn name(&self) -> &str {
+        &self.cluster().name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.cluster().description.as_deref()
+    }
+
+    async fn strategy(&self) -> &str {
+        &self.cluster().strategy
+    }
+
+    async fn cpu_model(&self) -> Option<&str> {
+        self.cluster().cpu_model.as_deref()
+    }
+
+    async fn cpu_ghz(&self) -> f64 {
+        self.cluster().cpu_ghz
+    }
+
+    async fn total_cores(&self) -> i32 {
+        self.cluster().total_cores
+    }
+
+    async fn memory_gb(&self) -> i32 {
+        self.cluster().memory_gb
+    }
+
+    async fn storage_tb(&self) -> f64 {
+        self.cluster().storage_tb
+    }
+
+    async fn network_bandwidth_gbps(&self) -> f64 {
+        self.cluster().network_bandwidth_gbps
+    }
+
+    async fn cpu_oversubscription_ratio(&self) -> f64 {
+        self.cluster().cpu_oversubscription_ratio
+    }
+
+    async fn memory_oversubscription_ratio(&self) -> f64 {
+        self.cluster().memory_oversubscription_ratio
+    }
+
+    async fn destination_cluster_id(&self) -> Option<&str> {
+        self.cluster().destination_cluster_id.as_deref()
+    }
+
+    async fn tags(&self) -> async_graphql::Json<BTreeMap<String, String>> {
+        async_graphql::Json(self.cluster().tags.clone())
+    }
+
+    /// vCPUs placed on the cluster
+    async fn allocated_cpu(&self) -> i32 {
+        self.placed().map(|p| p.allocated_cpu).sum()
+    }
+
+    async fn allocated_memory_mb(&self) -> i32 {
+        self.placed().map(|p| p.allocated_memory_mb).sum()
+    }
+
+    async fn allocated_storage_gb(&self) -> f64 {
+        self.placed().map(|p| p.allocated_storage_gb).sum()
+    }
+
+    async fn placements(&self) -> Vec<PlacementNode> {
+        self.plan
+            .placements_on(self.cluster())
+            .map(|index| PlacementNode { plan: self.plan.clone(), index })
+            .collect()
+    }
+
+    /// VMs placed on the cluster
+    async fn vms(&self) -> Vec<VmNode> {
+        self.placed()
+            .filter_map(|p| self.plan.vm_index.get(&p.vm_id.id.to_raw()))
+            .map(|&index| VmNode { plan: self.plan.clone(), index })
+            .collect()
+    }
+}
+
+pub struct PlacementNode {
+    plan: Arc<ProjectPlan>,
+    index: usize,
+}
+
+impl PlacementNode {
+    fn placement(&self) -> &MigrationWizardPlacement {
+        &self.plan.placements[self.index]
+    }
+}
+
+#[Object(name = "Placement")]
+impl PlacementNode {
+    async fn id(&self) -> ID {
+        ID(record_key(&self.placement().id))
+    }
+
+    async fn strategy(&self) -> &str {
+        &self.placement().strategy
+    }
+
+    async fn confidence_score(&self) -> Option<f64> {
+        self.placement().confidence_score
+    }
+
+    async fn warnings(&self) -> Vec<String> {
+        self.placement().warnings.clone().unwrap_or_default()
+    }
+
+    async fn allocated_cpu(&self) -> i32 {
+        self.placement().allocated_cpu
+    }
+
+    async fn allocated_memory_mb(&self) -> i32 {
+        self.placement().allocated_memory_mb
+    }
+
+    async fn allocated_storage_gb(&self) -> f64 {
+        self.placement().allocated_storage_gb
+    }
+
+    async fn tags(&self) -> async_graphql::Json<BTreeMap<String, String>> {
+        async_graphql::Json(self.placement().tags.clone())
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.placement().created_at
+    }
+
+    async fn vm(&self) -> Option<VmNode> {
+        let index = *self.plan.vm_index.get(&self.placement().vm_id.id.to_raw())?;
+        Some(VmNode { plan: self.plan.clone(), index })
+    }
+
+    async fn cluster(&self) -> Option<ClusterNode> {
+        let index = self.plan.cluster_of(self.placement())?;
+        Some(ClusterNode { plan: self.plan.clone(), index })
+    }
+}
+
+pub struct VmNode {
+    plan: Arc<ProjectPlan>,
+    index: usize,
+}
+
+impl VmNode {
+    fn vm(&self) -> &MigrationWizardVM {
+        &self.plan.vms[self.index]
+    }
+}
+
+#[Object(name = "Vm")]
+impl VmNode {
+    async fn id(&self) -> ID {
+        ID(record_key(&self.vm().id))
+    }
+
+    async fn name(&self) -> &str {
+        &self.vm().name
+    }
+
+    async fn powerstate(&self) -> Option<&str> {
+        self.vm().powerstate.as_deref()
+    }
+
+    async fn template(&self) -> bool {
+        self.vm().template.unwrap_or(false)
+    }
+
+    async fn cpus(&self) -> i32 {
+        self.vm().cpus
+    }
+
+    async fn memory_mb(&self) -> i32 {
+        self.vm().memory_mb
+    }
+
+    async fn provisioned_mb(&self) -> Option<i32> {
+        self.vm().provisioned_mb
+    }
+
+    async fn in_use_mb(&self) -> Option<i32> {
+        self.vm().in_use_mb
+    }
+
+    async fn num_disks(&self) -> i32 {
+        self.vm().num_disks
+    }
+
+    async fn num_nics(&self) -> i32 {
+        self.vm().num_nics
+    }
+
+    async fn os(&self) -> Option<&str> {
+        self.vm().os.as_deref()
+    }
+
+    async fn os_version(&self) -> Option<&str> {
+        self.vm().version.as_deref()
+    }
+
+    /// Cluster the VM runs on today
+    async fn source_cluster(&self) -> Option<&str> {
+        self.vm().cluster.as_deref()
+    }
+
+    async fn datacenter(&self) -> Option<&str> {
+        self.vm().datacenter.as_deref()
+    }
+
+    #[graphql(guard = "PermissionGuard::new(GUEST_DETAILS_PERMISSION)")]
+    async fn host(&self) -> Option<&str> {
+        self.vm().host.as_deref()
+    }
+
+    #[graphql(guard = "PermissionGuard::new(GUEST_DETAILS_PERMISSION)")]
+    async fn primary_ip_address(&self) -> Option<&str> {
+        self.vm().primary_ip_address.as_deref()
+    }
+
+    #[graphql(guard = "PermissionGuard::new(GUEST_DETAILS_PERMISSION)")]
+    async fn dns_name(&self) -> Option<&str> {
+        self.vm().dns_name.as_deref()
+    }
+
+    #[graphql(guard = "PermissionGuard::new(GUEST_DETAILS_PERMISSION)")]
+    async fn folder(&self) -> Option<&str> {
+        self.vm().folder.as_deref()
+    }
+
+    #[graphql(guard = "PermissionGuard::new(GUEST_DETAILS_PERMISSION)")]
+    async fn annotation(&self) -> Option<&str> {
+        self.vm().annotation.as_deref()
+    }
+
+    async fn placement(&self) -> Option<PlacementNode> {
+        let index = self.plan.placement_of(self.vm())?;
+        Some(PlacementNode { plan: self.plan.clone(), index })
+    }
+
+    /// Destination cluster the VM is placed on
+    async fn target_cluster(&self) -> Option<ClusterNode> {
+        let placement = &self.plan.placements[self.plan.placement_of(self.vm())?];
+        let index = self.plan.cluster_of(placement)?;
+        Some(ClusterNode { plan: self.plan.clone(), index })
+    }
+}
+
+// ============================================================================
+// FIELD GUARDS
+// ============================================================================
+
+/// Hides a field from callers without a permission. The field resolves to
+/// null with a FORBIDDEN error; the rest of the response is unaffected.
+struct PermissionGuard {
+    permission: &'static str,
+}
+
+impl PermissionGuard {
+    fn new(permission: &'static str) -> Self {
+        Self { permission }
+    }
+}
+
+#[async_trait::async_trait]
+impl Guard for PermissionGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let (_, user) = caller(ctx)?;
+        if user.has_permission(self.permission) {
+            Ok(())
+        } else {
+            Err(api_error(codes::FORBIDDEN, format!("Permission '{}' required", self.permission)))
+        }
+    }
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+fn caller<'a>(ctx: &Context<'a>) -> Result<(&'a Arc<Database>, &'a AuthenticatedUser)> {
+    Ok((ctx.data::<Arc<Database>>()?, ctx.data::<AuthenticatedUser>()?))
+}
+
+/// Raw id of a record, as the REST API returns it
+fn record_key(id: &Option<Thing>) -> String {
+    id.as_ref().map(|thing| thing.id.to_raw()).unwrap_or_default()
+}
+
+/// The serde name of an enum value, e.g. `in_progress`
+fn snake_case<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Only known statuses reach the project query
+fn parse_status(status: &str) -> Result<String> {
+    serde_json::from_value::<ProjectStatus>(serde_json::Value::String(status.to_string()))
+        .map(|status| snake_case(&status))
+        .map_err(|_| api_error(codes::VALIDATION_ERROR, format!("Unknown project status '{}'", status)))
+}
+
+/// A GraphQL error carrying the REST API's error code in `extensions.code`
+fn api_error(code: ErrorCode, message: impl Into<String>) -> Error {
+    Error::new(message.into()).extend_with(|_, extensions| extensions.set("code", code.code))
+}
+
+fn access_error(error: ProjectAccessError) -> Error {
+    match error {
+        ProjectAccessError::Forbidden(message) => api_error(codes::FORBIDDEN, message),
+        other => internal_error(other),
+    }
+}
+
+fn internal_error(error: impl std::fmt::Display) -> Error {
+    tracing::error!("GraphQL query failed: {}", error);
+    api_error(codes::INTERNAL_ERROR, "Internal error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status_accepts_known_statuses_only() {
+        assert_eq!(parse_status("in_progress").unwrap(), "in_progress");
+        assert!(parse_status("draft' OR true").is_err());
+    }
+
+    #[test]
+    fn test_schema_exposes_nested_project_types() {
+        let sdl = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish().sdl();
+        for ty in ["type Project", "type Cluster", "type Placement", "type Vm"] {
+            assert!(sdl.contains(ty), "missing {}", ty);
+        }
+        assert!(!sdl.contains("type MutationRoot"));
+    }
+}
@@ -1,8 +1,9 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Response},
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
@@ -11,12 +12,16 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::{
-    database::Database,
+    database::{Database, TracedQuery},
+    middleware::response_cache::etag,
+    models::firmware_baseline::RecordNodeFirmwareRequest,
     models::project_models::*,
+    services::firmware_baseline_service::{FirmwareBaselineError, FirmwareBaselineService},
     services::hardware_pool_service::{
         AllocationRequest, AllocationResult, CreateHardwarePoolRequest, HardwarePoolService,
         HardwareRequirements, UpdateHardwareRequest,
     },
+    utils::pagination::{count_total, Pagination, SortOrder, Sortable},
 };
 
 pub fn create_hardware_pool_router(db: Arc<Database>) -> Router {
@@ -31,6 +36,7 @@ pub fn create_hardware_pool_router(db: Arc<Database>) -> Router {
             "/servers/:server_id/maintenance",
             post(schedule_maintenance),
         )
+        .route("/servers/:server_id/firmware", put(record_firmware))
         .route("/search", post(search_servers))
         .route("/allocate", post(allocate_servers))
         .route("/allocations", post(create_allocation))
@@ -38,7 +44,7 @@ pub fn create_hardware_pool_router(db: Arc<Database>) -> Router {
         .route("/allocations/:allocation_id", get(get_allocation))
         .route("/allocations/:allocation_id", patch(update_allocation_status))
         .route("/allocations/:allocation_id", delete(release_allocation))
-        .route("/analytics", get(get_analytics))
+        .route("/analytics", get(get_analytics).layer(middleware::from_fn(etag)))
         .route("/procurement/:procurement_id/track", get(track_procurement))
         .with_state(db)
 }
@@ -64,52 +70,61 @@ struct ListServersQuery {
     status: Option<String>,
     vendor: Option<String>,
     datacenter: Option<String>,
-    limit: Option<usize>,
-    offset: Option<usize>,
+}
+
+/// Sortable server list fields
+#[derive(Debug, Clone, Copy)]
+struct ServerSort;
+
+impl Sortable for ServerSort {
+    const SORT_FIELDS: &'static [&'static str] = &[
+        "asset_tag", "vendor", "model", "availability_status", "datacenter", "location", "cpu_cores_total",
+        "memory_gb", "available_from_date", "created_at", "updated_at",
+    ];
+    const DEFAULT_SORT: &'static str = "created_at";
+    const DEFAULT_ORDER: SortOrder = SortOrder::Desc;
 }
 
 async fn list_servers(
     State(db): State<Arc<Database>>,
     Query(query): Query<ListServersQuery>,
+    pagination: Pagination<ServerSort>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let mut conditions = Vec::new();
+    let mut conditions = vec!["true"];
 
-    if let Some(status) = query.status {
-        conditions.push(format!("availability_status = '{}'", status));
-    }
-    if let Some(vendor) = query.vendor {
-        conditions.push(format!("vendor = '{}'", vendor));
+    if query.status.is_some() {
+        conditions.push("availability_status = $status");
     }
-    if let Some(datacenter) = query.datacenter {
-        conditions.push(format!("datacenter = '{}'", datacenter));
+    if query.vendor.is_some() {
+        conditions.push("vendor = $vendor");
     }
-
-    let mut query_str = "SELECT * FROM hardware_pool".to_string();
-
-    if !conditions.is_empty() {
-        query_str.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+    if query.datacenter.is_some() {
+        conditions.push("datacenter = $datacenter");
     }
+    let where_clause = conditions.join(" AND ");
+
+    let query_str = format!(
+        "SELECT count() FROM hardware_pool WHERE {where_clause} GROUP ALL; \
+         SELECT * FROM hardware_pool WHERE {where_clause} {} {};",
+        pagination.order_clause(),
+        pagination.limit_clause()
+    );
+
+    let page = async {
+        let mut response = db
+            .query(query_str)
+            .bind(("status", query.status))
+            .bind(("vendor", query.vendor))
+            .bind(("datacenter", query.datacenter))
+            .traced("hardware_pool.list_servers")
+            .await?;
+        let count: Vec<serde_json::Value> = response.take(0)?;
+        let servers: Vec<HardwarePool> = response.take(1)?;
+        Ok::<_, surrealdb::Error>(pagination.page(servers, count_total(&count)))
+    };
 
-    query_str.push_str(" ORDER BY created_at DESC");
-
-    if let Some(limit) = query.limit {
-        query_str.push_str(&format!(" LIMIT {}", limit));
-        if let Some(offset) = query.offset {
-            query_str.push_str(&format!(" START {}", offset));
-        }
-    }
-
-    let servers: Result<Vec<HardwarePool>, _> = db
-        .query(query_str)
-        .await
-        .map(|mut response| response.take(0))
-        .and_then(|result| result);
-
-    match servers {
-        Ok(servers) => {
-            let total = servers.len();
-            Ok(Json(ServersListResponse { servers, total }))
-        }
+    match page.await {
+        Ok(page) => Ok(Json(page)),
         Err(e) => Err(ApiError::InternalError(e.to_string())),
     }
 }
@@ -152,6 +167,7 @@ async fn remove_server(
     let active_allocations: Result<Vec<HardwareAllocation>, _> = db
         .query("SELECT * FROM hardware_allocation WHERE server_id = $server_id AND (allocation_end IS NONE OR allocation_end > time::now())")
         .bind(("server_id", surrealdb::sql::Thing::from(("hardware_pool", server_id.as_str()))))
+        .traced("hardware_pool.remove_server")
         .await
         .map(|mut response| response.take(0))
         .and_then(|result| result);
@@ -217,6 +233,23 @@ async fn schedule_maintenance(
     }
 }
 
+/// Record BIOS/firmware/driver versions entered for or discovered on a server
+async fn record_firmware(
+    State(db): State<Arc<Database>>,
+    Path(server_id): Path<String>,
+    Json(request): Json<RecordNodeFirmwareRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = FirmwareBaselineService::new((*db).clone());
+
+    match service.record_node_firmware(&server_id, request).await {
+        Ok(server) => Ok(Json(server)),
+        Err(FirmwareBaselineError::NodeNotFound) => {
+            Err(ApiError::NotFound(format!("Server {} not found", server_id)))
+        }
+        Err(e) => Err(ApiError::InternalError(e.to_string())),
+    }
+}
+
 // =============================================================================
 // INTELLIGENT SERVER SEARCH AND ALLOCATION
 // =============================================================================
@@ -280,42 +313,42 @@ async fn allocate_servers(
 async fn list_allocations(
     State(db): State<Arc<Database>>,
     Query(query): Query<ListAllocationsQuery>,
+    pagination: Pagination<AllocationSort>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let mut conditions = Vec::new();
+    let mut conditions = vec!["true"];
 
-    if let Some(project_id) = query.project_id {
-        conditions.push(format!("project_id = project:{}", project_id));
+    if query.project_id.is_some() {
+        conditions.push("project_id = $project");
     }
-    if let Some(server_id) = query.server_id {
-        conditions.push(format!("server_id = hardware_pool:{}", server_id));
+    if query.server_id.is_some() {
+        conditions.push("server_id = $server");
     }
     if query.active_only.unwrap_or(false) {
-        conditions.push("(allocation_end IS NONE OR allocation_end > time::now())".to_string());
+        conditions.push("(allocation_end IS NONE OR allocation_end > time::now())");
     }
+    let where_clause = conditions.join(" AND ");
+
+    let query_str = format!(
+        "SELECT count() FROM hardware_allocation WHERE {where_clause} GROUP ALL; \
+         SELECT * FROM hardware_allocation WHERE {where_clause} {} {};",
+        pagination.order_clause(),
+        pagination.limit_clause()
+    );
+
+    let page = async {
+        let mut response = db
+            .query(query_str)
+            .bind(("project", query.project_id.map(|id| surrealdb::sql::Thing::from(("project", id.as_str())))))
+            .bind(("server", query.server_id.map(|id| surrealdb::sql::Thing::from(("hardware_pool", id.as_str())))))
+            .traced("hardware_pool.list_allocations")
+            .await?;
+        let count: Vec<serde_json::Value> = response.take(0)?;
+        let allocations: Vec<HardwareAllocation> = response.take(1)?;
+        Ok::<_, surrealdb::Error>(pagination.page(allocations, count_total(&count)))
+    };
 
-    let mut query_str = "SELECT * FROM hardware_allocation".to_string();
-
-    if !conditions.is_empty() {
-        query_str.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
-    }
-
-    query_str.push_str(" ORDER BY allocation_start DESC");
-
-    if let Some(limit) = query.limit {
-        query_str.push_str(&format!(" LIMIT {}", limit));
-    }
-
-    let allocations: Result<Vec<HardwareAllocation>, _> = db
-        .query(query_str)
-        .await
-        .map(|mut response| response.take(0))
-        .and_then(|result| result);
-
-    match allocations {
-        Ok(allocations) => {
-            let total = allocations.len();
-            Ok(Json(AllocationsListResponse { allocations, total }))
-        }
+    match page.await {
+        Ok(page) => Ok(Json(page)),
         Err(e) => Err(ApiError::InternalError(e.to_string())),
     }
 }
@@ -527,6 +560,7 @@ async fn track_procurement(
 async fn get_total_servers(db: &Database) -> Result<usize, Box<dyn std::error::Error>> {
     let count: Vec<serde_json::Value> = db
         .query("SELECT COUNT() as total FROM hardware_pool")
+        .traced("hardware_pool.get_total_servers")
         .await?
         .take(0)?;
 
@@ -538,12 +572,6 @@ async fn get_total_servers(db: &Database) -> Result<usize, Box<dyn std::error::E
 }
 
 // Response types
-#[derive(Debug, Serialize)]
-struct ServersListResponse {
-    servers: Vec<HardwarePool>,
-    total: usize,
-}
-
 #[derive(Debug, Serialize)]
 struct ServerSearchResponse {
     recommendations: Vec<crate::services::hardware_pool_service::ServerRecommendation>,
@@ -600,13 +628,17 @@ struct ListAllocationsQuery {
     project_id: Option<String>,
     server_id: Option<String>,
     active_only: Option<bool>,
-    limit: Option<usize>,
 }
 
-#[derive(Debug, Serialize)]
-struct AllocationsListResponse {
-    allocations: Vec<HardwareAllocation>,
-    total: usize,
+/// Sortable allocation list fields
+#[derive(Debug, Clone, Copy)]
+struct AllocationSort;
+
+impl Sortable for AllocationSort {
+    const SORT_FIELDS: &'static [&'static str] =
+        &["allocation_start", "allocation_end", "allocation_type", "purpose", "created_at"];
+    const DEFAULT_SORT: &'static str = "allocation_start";
+    const DEFAULT_ORDER: SortOrder = SortOrder::Desc;
 }
 
 #[derive(Debug, Serialize)]
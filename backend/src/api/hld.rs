@@ -7,19 +7,48 @@
 // ============================================================================
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    middleware,
     http::{StatusCode, header},
     response::{IntoResponse, Response},
-    Json,
-    body::Body,
+    Extension, Json,
+    body::{Body, StreamBody},
 };
+use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashMap;
 use surrealdb::sql::Thing;
 use chrono::Utc;
 
+use crate::middleware::auth::{require_auth, AuthState, AuthenticatedUser};
+use crate::middleware::project_access::{require_project_role, ProjectAccess};
 use crate::models::hld::*;
+use crate::models::project_models::{
+    DocumentFormat, DocumentGenerationStatus, DocumentType, DocumentVersionDiff, GeneratedDocument,
+};
+use crate::models::settings::AnonymizeOptions;
+use crate::services::anonymization_service::{AnonymizationService, MAP_ID_HEADER};
+use crate::services::document_redaction_service::{self, redact_record};
+use crate::services::document_version_service::{
+    diff_versions, snapshot_sections, DocumentVersionError, DocumentVersionService, VariantArtifact,
+};
+use crate::services::project_access_service::{ProjectAccessError, ProjectAccessService};
+use crate::services::data_variable_service::DataVariableService;
+use crate::services::hld_dry_run_service;
+use crate::services::network_conflict_service::NetworkConflictService;
+use crate::services::variable_definitions::VariableDefinitions;
 use crate::services::word_generator::WordGenerator;
 use crate::database::AppState;
+use crate::database::TracedQuery;
+
+const DOCX_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Version number of the generated document in export responses
+const DOCUMENT_VERSION_HEADER: &str = "X-Document-Version";
+
+/// Audience of the variant in document responses
+const DOCUMENT_AUDIENCE_HEADER: &str = "X-Document-Audience";
 
 // ============================================================================
 // ERROR HANDLING
@@ -31,6 +60,7 @@ pub enum HLDApiError {
     NotFound(String),
     ValidationError(String),
     Conflict(String),
+    Forbidden(String),
 }
 
 impl IntoResponse for HLDApiError {
@@ -40,12 +70,33 @@ impl IntoResponse for HLDApiError {
             Self::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             Self::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
             Self::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            Self::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
         };
 
         (status, Json(json!({ "error": message }))).into_response()
     }
 }
 
+impl From<DocumentVersionError> for HLDApiError {
+    fn from(error: DocumentVersionError) -> Self {
+        match error {
+            DocumentVersionError::NotFound(_) | DocumentVersionError::ArtifactMissing(_) => {
+                Self::NotFound(error.to_string())
+            }
+            _ => Self::DatabaseError(error.to_string()),
+        }
+    }
+}
+
+impl From<ProjectAccessError> for HLDApiError {
+    fn from(error: ProjectAccessError) -> Self {
+        match error {
+            ProjectAccessError::Forbidden(msg) => Self::Forbidden(msg),
+            _ => Self::DatabaseError(error.to_string()),
+        }
+    }
+}
+
 type ApiResult<T> = Result<T, HLDApiError>;
 
 // ============================================================================
@@ -90,6 +141,7 @@ pub async fn list_templates(
 
     let templates: Vec<HLDTemplate> = db
         .query("SELECT * FROM hld_templates WHERE is_active = true ORDER BY created_at DESC")
+        .traced("hld.list_templates")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -163,6 +215,7 @@ pub async fn delete_template(
     let _updated: Option<HLDTemplate> = db
         .query("UPDATE $template SET is_active = false")
         .bind(("template", Thing::from(("hld_templates", id.as_str()))))
+        .traced("hld.delete_template")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -180,6 +233,7 @@ pub async fn get_template_sections(
     let sections: Vec<HLDSection> = db
         .query("SELECT * FROM hld_sections WHERE template_id = $template ORDER BY order ASC")
         .bind(("template", Thing::from(("hld_templates", template_id.as_str()))))
+        .traced("hld.get_template_sections")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -188,6 +242,40 @@ pub async fn get_template_sections(
     Ok(Json(sections))
 }
 
+/// PUT /api/v1/hld/templates/:id/sections/:section_id/sensitivity - Tag a section
+///
+/// Exports leave tagged sections out of the variants for audiences that
+/// may not see them.
+pub async fn update_section_sensitivity(
+    State(db): State<AppState>,
+    Path((template_id, section_id)): Path<(String, String)>,
+    Json(payload): Json<UpdateSectionSensitivityRequest>,
+) -> ApiResult<Json<HLDSection>> {
+    let mut sensitivity = payload.sensitivity;
+    sensitivity.sort_by_key(|tag| *tag as u8);
+    sensitivity.dedup();
+
+    let updated: Vec<HLDSection> = db
+        .query(
+            "UPDATE hld_sections SET sensitivity = $sensitivity \
+             WHERE template_id = $template AND section_id = $section",
+        )
+        .bind(("sensitivity", sensitivity))
+        .bind(("template", Thing::from(("hld_templates", template_id.as_str()))))
+        .bind(("section", section_id.clone()))
+        .traced("hld.update_section_sensitivity")
+        .await
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
+        .take(0)
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+
+    updated
+        .into_iter()
+        .next()
+        .map(Json)
+        .ok_or_else(|| HLDApiError::NotFound(format!("Section {} not found in template {}", section_id, template_id)))
+}
+
 // ============================================================================
 // PROJECT ENDPOINTS (3 endpoints)
 // ============================================================================
@@ -203,6 +291,7 @@ pub async fn create_hld_project(
     let existing: Vec<HLDProject> = db
         .query("SELECT * FROM hld_projects WHERE project_id = $project")
         .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.create_hld_project")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -232,6 +321,7 @@ pub async fn create_hld_project(
             "template",
             Thing::from(("hld_templates", payload.template_id.as_str())),
         ))
+        .traced("hld.create_hld_project")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -281,6 +371,7 @@ pub async fn get_hld_project(
     let projects: Vec<HLDProject> = db
         .query("SELECT * FROM hld_projects WHERE project_id = $project")
         .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.get_hld_project")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -306,6 +397,7 @@ pub async fn update_enabled_sections(
     let mut projects: Vec<HLDProject> = db
         .query("SELECT * FROM hld_projects WHERE project_id = $project")
         .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.update_enabled_sections")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -352,6 +444,7 @@ pub async fn get_variables(
     let hld_projects: Vec<HLDProject> = db
         .query("SELECT * FROM hld_projects WHERE project_id = $project")
         .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.get_variables")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -369,6 +462,7 @@ pub async fn get_variables(
     let variables: Vec<HLDVariable> = db
         .query("SELECT * FROM hld_variables WHERE hld_project_id = $hld_project ORDER BY section, variable_name")
         .bind(("hld_project", hld_project_id))
+        .traced("hld.get_variables")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -388,6 +482,7 @@ pub async fn bulk_update_variables(
     let hld_projects: Vec<HLDProject> = db
         .query("SELECT * FROM hld_projects WHERE project_id = $project")
         .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.bulk_update_variables")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -409,6 +504,7 @@ pub async fn bulk_update_variables(
             .query("SELECT * FROM hld_variables WHERE hld_project_id = $hld_project AND variable_name = $name")
             .bind(("hld_project", hld_project_id.clone()))
             .bind(("name", var_update.name.as_str()))
+            .traced("hld.bulk_update_variables")
             .await
             .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
             .take(0)
@@ -450,6 +546,7 @@ pub async fn get_variable(
     let hld_projects: Vec<HLDProject> = db
         .query("SELECT * FROM hld_projects WHERE project_id = $project")
         .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.get_variable")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -468,6 +565,7 @@ pub async fn get_variable(
         .query("SELECT * FROM hld_variables WHERE hld_project_id = $hld_project AND variable_name = $name")
         .bind(("hld_project", hld_project_id))
         .bind(("name", variable_name.as_str()))
+        .traced("hld.get_variable")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -496,6 +594,7 @@ pub async fn update_variable(
     let hld_projects: Vec<HLDProject> = db
         .query("SELECT * FROM hld_projects WHERE project_id = $project")
         .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.update_variable")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -514,6 +613,7 @@ pub async fn update_variable(
         .query("SELECT * FROM hld_variables WHERE hld_project_id = $hld_project AND variable_name = $name")
         .bind(("hld_project", hld_project_id))
         .bind(("name", variable_name.as_str()))
+        .traced("hld.update_variable")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
@@ -551,49 +651,85 @@ pub async fn update_variable(
 // EXPORT ENDPOINTS (2 endpoints - placeholders for Week 3)
 // ============================================================================
 
-/// POST /api/v1/projects/:project_id/hld/export - Export HLD to Word document
-pub async fn export_hld(
-    State(db): State<AppState>,
-    Path(project_id): Path<String>,
-) -> Result<impl IntoResponse, HLDApiError> {
-    // 1. Get HLD project
-    let hld_project_query = format!(
-        "SELECT * FROM hld_projects WHERE project_id = type::thing('projects', '{}')",
-        project_id
-    );
-    let mut hld_result = db
-        .query(&hld_project_query)
+/// Add the user-defined data variables, evaluated on the project's latest
+/// RVTools upload, so sections can use them as `{{name}}` placeholders
+///
+/// A value the project set itself wins over the computed one.
+async fn add_data_variables(
+    db: &AppState,
+    hld_project: &HLDProject,
+    variables: &mut Vec<HLDVariable>,
+) -> ApiResult<()> {
+    let Some(hld_project_id) = hld_project.id.clone() else {
+        return Ok(());
+    };
+    let service = DataVariableService::new(db.as_ref().clone());
+    let upload = service
+        .latest_project_upload(&hld_project.project_id)
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
-    
-    let hld_projects: Vec<HLDProject> = hld_result
-        .take(0)
+    let Some(upload) = upload else {
+        return Ok(());
+    };
+    let evaluated = service
+        .evaluate_custom(&upload)
+        .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
-    
-    if hld_projects.is_empty() {
-        return Err(HLDApiError::NotFound(
-            "HLD project not found - create one first".to_string(),
-        ));
+
+    for data_variable in evaluated {
+        let already_set = variables.iter().any(|v| {
+            v.variable_name == data_variable.variable_name
+                && v.variable_value.as_ref().is_some_and(|value| !value.is_null())
+        });
+        let Some(value) = data_variable.value.filter(|_| !already_set) else {
+            continue;
+        };
+        let (value, variable_type) = if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+            (VariableValue::Integer(value as i64), VariableType::Integer)
+        } else {
+            (VariableValue::Float(value), VariableType::Float)
+        };
+        variables.retain(|v| v.variable_name != data_variable.variable_name);
+        variables.push(
+            HLDVariable::new(
+                hld_project_id.clone(),
+                data_variable.variable_name,
+                Some(value),
+                variable_type,
+                "custom".to_string(),
+            )
+            .with_source(VariableSource::RVTools),
+        );
     }
-    
-    let hld_project = &hld_projects[0];
-    
-    // 2. Get HLD variables
-    let vars_query = format!(
-        "SELECT * FROM hld_variables WHERE hld_project_id = $hld_project_id ORDER BY variable_name"
-    );
-    let mut vars_result = db
-        .query(&vars_query)
-        .bind(("hld_project_id", hld_project.id.clone()))
+    Ok(())
+}
+
+/// Copy the sensitivity tags of the project's template onto its sections
+async fn apply_template_sensitivity(
+    db: &AppState,
+    hld_project: &HLDProject,
+    sections: &mut [SectionDefinition],
+) -> ApiResult<()> {
+    let template_sections: Vec<HLDSection> = db
+        .query("SELECT * FROM hld_sections WHERE template_id = $template")
+        .bind(("template", hld_project.template_id.clone()))
+        .traced("hld.apply_template_sensitivity")
         .await
-        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
-    
-    let variables: Vec<HLDVariable> = vars_result
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
         .take(0)
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
-    
-    // 3. Get section definitions (enabled sections only)
-    // For now, use the enabled sections from the project
+
+    for section in sections.iter_mut() {
+        if let Some(tagged) = template_sections.iter().find(|t| t.section_id == section.section_id) {
+            section.sensitivity = tagged.sensitivity.clone();
+        }
+    }
+    Ok(())
+}
+
+/// Sections an export of the project generates, in order: the project's
+/// section order, or the default sections when it has none
+fn project_sections(hld_project: &HLDProject) -> Vec<SectionDefinition> {
     let section_order = hld_project.section_order.clone();
     
     // Create section definitions from enabled sections
@@ -609,6 +745,7 @@ pub async fn export_hld(
             enabled: true,
             order_index: index as i32,
             depends_on: Vec::new(),
+            sensitivity: Vec::new(),
             created_at: Utc::now(),
         });
     }
@@ -626,6 +763,7 @@ pub async fn export_hld(
                 enabled: true,
                 order_index: 0,
                 depends_on: Vec::new(),
+                sensitivity: Vec::new(),
                 created_at: Utc::now(),
             },
             SectionDefinition {
@@ -638,6 +776,7 @@ pub async fn export_hld(
                 enabled: true,
                 order_index: 1,
                 depends_on: Vec::new(),
+                sensitivity: Vec::new(),
                 created_at: Utc::now(),
             },
             SectionDefinition {
@@ -650,6 +789,7 @@ pub async fn export_hld(
                 enabled: true,
                 order_index: 2,
                 depends_on: Vec::new(),
+                sensitivity: Vec::new(),
                 created_at: Utc::now(),
             },
             SectionDefinition {
@@ -662,6 +802,7 @@ pub async fn export_hld(
                 enabled: true,
                 order_index: 3,
                 depends_on: Vec::new(),
+                sensitivity: Vec::new(),
                 created_at: Utc::now(),
             },
             SectionDefinition {
@@ -674,6 +815,7 @@ pub async fn export_hld(
                 enabled: true,
                 order_index: 4,
                 depends_on: Vec::new(),
+                sensitivity: Vec::new(),
                 created_at: Utc::now(),
             },
             SectionDefinition {
@@ -686,34 +828,432 @@ pub async fn export_hld(
                 enabled: true,
                 order_index: 5,
                 depends_on: Vec::new(),
+                sensitivity: Vec::new(),
                 created_at: Utc::now(),
             },
         ];
     }
+
+    sections
+}
+
+/// POST /api/v1/projects/:project_id/hld/export - Export HLD to Word document
+///
+/// With `?anonymize=true` customer identifiers in the variables are replaced
+/// by pseudonyms; the id of the local mapping file is returned in the
+/// `X-Anonymization-Map` header.
+///
+/// Every export is kept as a new version of the project's HLD; its number is
+/// returned in the `X-Document-Version` header.
+///
+/// One run renders the full document and a variant per redacted audience.
+/// The response is the caller's variant, named in `X-Document-Audience`.
+pub async fn export_hld(
+    State(db): State<AppState>,
+    Path(project_id): Path<String>,
+    Query(options): Query<AnonymizeOptions>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<impl IntoResponse, HLDApiError> {
+    // 1. Get HLD project
+    let hld_project_query = format!(
+        "SELECT * FROM hld_projects WHERE project_id = type::thing('projects', '{}')",
+        project_id
+    );
+    let mut hld_result = db
+        .query(&hld_project_query)
+        .traced("hld.export_hld")
+        .await
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+    
+    let hld_projects: Vec<HLDProject> = hld_result
+        .take(0)
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+    
+    if hld_projects.is_empty() {
+        return Err(HLDApiError::NotFound(
+            "HLD project not found - create one first".to_string(),
+        ));
+    }
+    
+    let hld_project = &hld_projects[0];
+    
+    // 2. Get HLD variables
+    let vars_query = format!(
+        "SELECT * FROM hld_variables WHERE hld_project_id = $hld_project_id ORDER BY variable_name"
+    );
+    let mut vars_result = db
+        .query(&vars_query)
+        .bind(("hld_project_id", hld_project.id.clone()))
+        .traced("hld.export_hld")
+        .await
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+    
+    let mut variables: Vec<HLDVariable> = vars_result
+        .take(0)
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+    add_data_variables(&db, hld_project, &mut variables).await?;
+
+    let map_id = if options.anonymize {
+        let service = AnonymizationService::new(db.as_ref().clone());
+        let mut anonymizer = service
+            .anonymizer(&options)
+            .await
+            .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+        for variable in variables.iter_mut() {
+            let Some(value) = variable.variable_value.take() else {
+                continue;
+            };
+            let mut json_value = serde_json::to_value(&value)
+                .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+            anonymizer.anonymize_field(&variable.variable_name, &mut json_value);
+            variable.variable_value = Some(serde_json::from_value(json_value).unwrap_or(value));
+        }
+        Some(
+            service
+                .save_map(&anonymizer)
+                .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?,
+        )
+    } else {
+        None
+    };
+    
+    // 3. Get section definitions, tagged by the template
+    let mut sections = project_sections(hld_project);
+    apply_template_sensitivity(&db, hld_project, &mut sections).await?;
     
-    // 4. Generate Word document
+    // 4. Generate the Word document, then a redacted variant per audience
     let mut generator = WordGenerator::new();
     let docx_bytes = generator
         .generate_hld(hld_project, &variables, &sections)
         .map_err(|e| HLDApiError::DatabaseError(format!("Word generation failed: {}", e)))?;
+    let mut variants = Vec::new();
+    for audience in DocumentAudience::ALL.into_iter().filter(|a| *a != DocumentAudience::Full) {
+        let redacted = document_redaction_service::redact(audience, &sections, &variables);
+        let bytes = WordGenerator::new()
+            .generate_hld(hld_project, &redacted.variables, &redacted.sections)
+            .map_err(|e| HLDApiError::DatabaseError(format!("Word generation failed: {}", e)))?;
+        variants.push(VariantArtifact {
+            audience,
+            bytes,
+            redacted_sections: redacted.redacted_sections,
+            redacted_variables: redacted.redacted_variables,
+        });
+    }
+    let audience = ProjectAccessService::new(db.clone())
+        .effective_audience(&user, &project_id)
+        .await?;
+    let response_bytes = variants
+        .iter()
+        .find(|variant| variant.audience == audience)
+        .map(|variant| variant.bytes.clone())
+        .unwrap_or_else(|| docx_bytes.clone());
+
+    // 5. Keep this export as the next version, with what it was built from
+    let variables_snapshot: HashMap<String, serde_json::Value> = variables
+        .iter()
+        .filter_map(|v| {
+            let value = serde_json::to_value(v.variable_value.as_ref()?).ok()?;
+            Some((v.variable_name.clone(), value))
+        })
+        .collect();
+    let section_ids: Vec<&str> = sections.iter().map(|s| s.section_id.as_str()).collect();
+    let generation_parameters = HashMap::from([
+        ("sections".to_string(), json!(section_ids)),
+        ("anonymize".to_string(), json!(options.anonymize)),
+        ("template_id".to_string(), json!(hld_project.template_id.to_string())),
+        ("template_version".to_string(), json!(hld_project.template_version)),
+    ]);
+    let draft = GeneratedDocument {
+        id: None,
+        project_id: hld_project.project_id.clone(),
+        activity_id: Thing::from(("activity", "hld_export")),
+        template_id: hld_project.template_id.clone(),
+        document_name: format!("Project {} HLD", project_id),
+        document_type: DocumentType::Hld,
+        file_path: String::new(),
+        file_size_bytes: 0,
+        file_format: DocumentFormat::Docx,
+        section_snapshot: snapshot_sections(&sections, &variables_snapshot),
+        variables_snapshot,
+        data_sources: Vec::new(),
+        generation_status: DocumentGenerationStatus::Completed,
+        error_message: None,
+        metadata: HashMap::new(),
+        generated_at: Utc::now(),
+        generated_by: user.username.clone(),
+        expires_at: None,
+        version: 0,
+        previous_version: None,
+        generation_parameters,
+        storage_backend: Default::default(),
+        locked: false,
+        approval_record: None,
+        published_file_path: None,
+        variants: Vec::new(),
+    };
+    let document = DocumentVersionService::new(db.clone())
+        .record_version_with_variants(draft, &docx_bytes, DOCX_CONTENT_TYPE, variants)
+        .await?;
+
+    // 6. Return as downloadable file
+    let filename = version_filename(&project_id, &document, audience);
     
-    // 5. Return as downloadable file
-    let filename = format!(
-        "Project-{}-HLD-{}.docx",
-        project_id.replace(' ', "-"),
-        Utc::now().format("%Y%m%d")
-    );
-    
-    let response = Response::builder()
+    let mut response = Response::builder()
         .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+        .header(header::CONTENT_TYPE, DOCX_CONTENT_TYPE)
         .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))
-        .body(Body::from(docx_bytes))
+        .header(DOCUMENT_VERSION_HEADER, document.version)
+        .header(DOCUMENT_AUDIENCE_HEADER, audience.as_str());
+    if let Some(map_id) = map_id {
+        response = response.header(MAP_ID_HEADER, map_id);
+    }
+    let response = response
+        .body(Body::from(response_bytes))
         .map_err(|e| HLDApiError::DatabaseError(format!("Failed to build response: {}", e)))?;
     
     Ok(response)
 }
 
+// ============================================================================
+// VERSION ENDPOINTS
+// ============================================================================
+
+fn hld_project_thing(project_id: &str) -> Thing {
+    Thing::from(("projects", project_id))
+}
+
+fn version_filename(project_id: &str, document: &GeneratedDocument, audience: DocumentAudience) -> String {
+    let suffix = match audience {
+        DocumentAudience::Full => String::new(),
+        audience => format!("-{}", audience.as_str()),
+    };
+    format!(
+        "Project-{}-HLD-v{}-{}{}.docx",
+        project_id.replace(' ', "-"),
+        document.version,
+        document.generated_at.format("%Y%m%d"),
+        suffix
+    )
+}
+
+async fn caller_audience(db: &AppState, user: &AuthenticatedUser, project_id: &str) -> ApiResult<DocumentAudience> {
+    Ok(ProjectAccessService::new(db.clone())
+        .effective_audience(user, project_id)
+        .await?)
+}
+
+/// The version as `audience` may see it
+fn visible_version(document: GeneratedDocument, audience: DocumentAudience) -> ApiResult<GeneratedDocument> {
+    let version = document.version;
+    redact_record(document, audience).ok_or_else(|| {
+        HLDApiError::Forbidden(format!(
+            "Version {} has no {} variant; export the HLD again",
+            version,
+            audience.as_str()
+        ))
+    })
+}
+
+/// GET /api/v1/hld/projects/:project_id/versions - Exported versions, newest first
+///
+/// Redacted audiences only see the versions that have their variant.
+pub async fn list_hld_versions(
+    State(db): State<AppState>,
+    Path(project_id): Path<String>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> ApiResult<Json<Vec<GeneratedDocument>>> {
+    let audience = caller_audience(&db, &user, &project_id).await?;
+    let versions = DocumentVersionService::new(db)
+        .list_versions(&hld_project_thing(&project_id), &DocumentType::Hld)
+        .await?;
+    Ok(Json(
+        versions
+            .into_iter()
+            .filter_map(|document| redact_record(document, audience))
+            .collect(),
+    ))
+}
+
+/// GET /api/v1/hld/projects/:project_id/versions/:version - One version's record
+pub async fn get_hld_version(
+    State(db): State<AppState>,
+    Path((project_id, version)): Path<(String, u32)>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> ApiResult<Json<GeneratedDocument>> {
+    let audience = caller_audience(&db, &user, &project_id).await?;
+    let document = DocumentVersionService::new(db)
+        .get_version(&hld_project_thing(&project_id), &DocumentType::Hld, version)
+        .await?;
+    Ok(Json(visible_version(document, audience)?))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VariantQuery {
+    /// A more redacted variant than the caller's own, e.g. to check what
+    /// the customer gets
+    #[serde(default)]
+    pub audience: Option<DocumentAudience>,
+}
+
+/// GET /api/v1/hld/projects/:project_id/versions/:version/content - Download a version
+pub async fn download_hld_version(
+    State(db): State<AppState>,
+    Path((project_id, version)): Path<(String, u32)>,
+    Query(query): Query<VariantQuery>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> ApiResult<Response> {
+    let own = caller_audience(&db, &user, &project_id).await?;
+    let audience = query.audience.map_or(own, |requested| requested.max(own));
+    let service = DocumentVersionService::new(db);
+    let document = service
+        .get_version(&hld_project_thing(&project_id), &DocumentType::Hld, version)
+        .await?;
+    let document = visible_version(document, audience)?;
+    let stream = service.open_artifact(&document).await?;
+    let filename = version_filename(&project_id, &document, audience);
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, DOCX_CONTENT_TYPE.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+            (header::HeaderName::from_static("x-document-audience"), audience.as_str().to_string()),
+        ],
+        StreamBody::new(stream),
+    )
+        .into_response();
+    response.headers_mut().insert(
+        header::CONTENT_LENGTH,
+        header::HeaderValue::from(document.file_size_bytes.max(0) as u64),
+    );
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VersionDiffQuery {
+    pub from: u32,
+    pub to: u32,
+}
+
+/// GET /api/v1/hld/projects/:project_id/diff?from=1&to=2 - What regeneration changed
+///
+/// Redacted audiences compare their own variants.
+pub async fn diff_hld_versions(
+    State(db): State<AppState>,
+    Path(project_id): Path<String>,
+    Query(query): Query<VersionDiffQuery>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> ApiResult<Json<DocumentVersionDiff>> {
+    let audience = caller_audience(&db, &user, &project_id).await?;
+    let service = DocumentVersionService::new(db);
+    let project = hld_project_thing(&project_id);
+    let from = service.get_version(&project, &DocumentType::Hld, query.from).await?;
+    let to = service.get_version(&project, &DocumentType::Hld, query.to).await?;
+    Ok(Json(diff_versions(
+        &visible_version(from, audience)?,
+        &visible_version(to, audience)?,
+    )))
+}
+
+/// POST /api/v1/projects/:project_id/hld/dry-run - Check an export without generating it
+///
+/// Reports which variables resolve, which are missing or invalid, and which
+/// sections would be empty. The body may name another `template_id` to check
+/// the project's variables against that template's sections.
+pub async fn dry_run_hld(
+    State(db): State<AppState>,
+    Path(project_id): Path<String>,
+    payload: Option<Json<HLDDryRunRequest>>,
+) -> ApiResult<Json<HLDDryRunReport>> {
+    let hld_project: HLDProject = db
+        .query("SELECT * FROM hld_projects WHERE project_id = $project")
+        .bind(("project", Thing::from(("projects", project_id.as_str()))))
+        .traced("hld.dry_run_hld")
+        .await
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
+        .take::<Vec<HLDProject>>(0)
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| HLDApiError::NotFound("HLD project not found - create one first".to_string()))?;
+
+    let mut variables: Vec<HLDVariable> = db
+        .query("SELECT * FROM hld_variables WHERE hld_project_id = $hld_project_id")
+        .bind(("hld_project_id", hld_project.id.clone()))
+        .traced("hld.dry_run_hld")
+        .await
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
+        .take(0)
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+    add_data_variables(&db, &hld_project, &mut variables).await?;
+
+    let own_template = hld_project.template_id.id.to_raw();
+    let template_id = payload
+        .and_then(|Json(request)| request.template_id)
+        .unwrap_or_else(|| own_template.clone());
+
+    let sections = if template_id == own_template {
+        project_sections(&hld_project)
+    } else {
+        let template: Option<HLDTemplate> = db
+            .select(("hld_templates", template_id.as_str()))
+            .await
+            .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+        if template.is_none() {
+            return Err(HLDApiError::NotFound(format!("Template {} not found", template_id)));
+        }
+
+        let template_sections: Vec<HLDSection> = db
+            .query("SELECT * FROM hld_sections WHERE template_id = $template ORDER BY order ASC")
+            .bind(("template", Thing::from(("hld_templates", template_id.as_str()))))
+            .traced("hld.dry_run_hld")
+            .await
+            .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?
+            .take(0)
+            .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+
+        // Required sections always generate; optional ones only when the
+        // project has enabled them
+        template_sections
+            .into_iter()
+            .map(|section| SectionDefinition {
+                id: None,
+                enabled: section.required || hld_project.enabled_sections.contains(&section.section_id),
+                section_name: section.section_id.clone(),
+                section_id: section.section_id,
+                display_name: section.name,
+                description: section.description,
+                required: section.required,
+                order_index: section.order,
+                depends_on: section.depends_on,
+                sensitivity: section.sensitivity,
+                created_at: Utc::now(),
+            })
+            .collect()
+    };
+
+    let mut report = hld_dry_run_service::dry_run(
+        &project_id,
+        &template_id,
+        &sections,
+        &variables,
+        &VariableDefinitions::get_all(),
+    );
+
+    // Other migrations in the tenant targeting the same VLANs or subnets
+    let conflicts = NetworkConflictService::new(db.as_ref().clone())
+        .project_conflicts(&project_id)
+        .await
+        .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
+    report.issues.extend(conflicts.into_iter().map(|conflict| DryRunIssue {
+        severity: "warning".to_string(),
+        variable: None,
+        section_id: Some("network_design".to_string()),
+        message: conflict.message,
+    }));
+
+    Ok(Json(report))
+}
+
 /// POST /api/v1/projects/:project_id/hld/autofill-preview - Preview RVTools auto-fill
 pub async fn autofill_preview(
     State(db): State<AppState>,
@@ -730,6 +1270,7 @@ pub async fn autofill_preview(
     );
     let mut hld_result = db
         .query(&hld_project_query)
+        .traced("hld.autofill_preview")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
     
@@ -752,6 +1293,7 @@ pub async fn autofill_preview(
     );
     let mut rvtools_result = db
         .query(&rvtools_query)
+        .traced("hld.autofill_preview")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
     
@@ -772,6 +1314,7 @@ pub async fn autofill_preview(
     let mut data_result = db
         .query(&rvtools_data_query)
         .bind(("upload_id", rvtools_upload.id.clone()))
+        .traced("hld.autofill_preview")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
     
@@ -797,6 +1340,7 @@ pub async fn autofill_preview(
     let mut vars_result = db
         .query(&current_vars_query)
         .bind(("hld_project_id", hld_project.id.clone()))
+        .traced("hld.autofill_preview")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
     
@@ -808,6 +1352,7 @@ pub async fn autofill_preview(
     let var_defs_query = "SELECT * FROM variable_definitions";
     let mut var_defs_result = db
         .query(var_defs_query)
+        .traced("hld.autofill_preview")
         .await
         .map_err(|e| HLDApiError::DatabaseError(e.to_string()))?;
     
@@ -904,6 +1449,17 @@ use axum::Router;
 
 /// Create HLD router (main export for router setup)
 pub fn create_hld_router(state: AppState) -> Router {
+    // Exports and their versions are served per the caller's project role
+    // and document audience
+    let documents = Router::new()
+        .route("/projects/:project_id/export", post(export_hld))
+        .route("/projects/:project_id/versions", get(list_hld_versions))
+        .route("/projects/:project_id/versions/:version", get(get_hld_version))
+        .route("/projects/:project_id/versions/:version/content", get(download_hld_version))
+        .route("/projects/:project_id/diff", get(diff_hld_versions))
+        .route_layer(middleware::from_fn_with_state(ProjectAccess::new(state.clone()), require_project_role))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth));
+
     Router::new()
         // Template routes
         .route("/templates", post(create_template))
@@ -912,6 +1468,7 @@ pub fn create_hld_router(state: AppState) -> Router {
         .route("/templates/:id", put(update_template))
         .route("/templates/:id", delete(delete_template))
         .route("/templates/:id/sections", get(get_template_sections))
+        .route("/templates/:id/sections/:section_id/sensitivity", put(update_section_sensitivity))
         // Project routes (these will be at /api/v1/hld/projects/:project_id/...)
         .route("/projects/:project_id", post(create_hld_project))
         .route("/projects/:project_id", get(get_hld_project))
@@ -921,9 +1478,10 @@ pub fn create_hld_router(state: AppState) -> Router {
         .route("/projects/:project_id/variables", put(bulk_update_variables))
         .route("/projects/:project_id/variables/:name", get(get_variable))
         .route("/projects/:project_id/variables/:name", put(update_variable))
-        // Export routes
-        .route("/projects/:project_id/export", post(export_hld))
+        // Export checks
         .route("/projects/:project_id/autofill-preview", post(autofill_preview))
+        .route("/projects/:project_id/dry-run", post(dry_run_hld))
+        .merge(documents)
         .with_state(state)
 }
 
@@ -940,6 +1498,10 @@ pub fn hld_routes() -> Router<AppState> {
             "/api/v1/hld/templates/:id/sections",
             get(get_template_sections),
         )
+        .route(
+            "/api/v1/hld/templates/:id/sections/:section_id/sensitivity",
+            put(update_section_sensitivity),
+        )
         // Project routes
         .route(
             "/api/v1/projects/:project_id/hld",
@@ -973,4 +1535,25 @@ pub fn hld_routes() -> Router<AppState> {
             "/api/v1/projects/:project_id/hld/autofill-preview",
             post(autofill_preview),
         )
+        .route(
+            "/api/v1/projects/:project_id/hld/dry-run",
+            post(dry_run_hld),
+        )
+        // Version history of exports
+        .route(
+            "/api/v1/projects/:project_id/hld/versions",
+            get(list_hld_versions),
+        )
+        .route(
+            "/api/v1/projects/:project_id/hld/versions/:version",
+            get(get_hld_version),
+        )
+        .route(
+            "/api/v1/projects/:project_id/hld/versions/:version/content",
+            get(download_hld_version),
+        )
+        .route(
+            "/api/v1/projects/:project_id/hld/diff",
+            get(diff_hld_versions),
+        )
 }
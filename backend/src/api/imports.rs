@@ -0,0 +1,250 @@
+// Archer - Spreadsheet Import API
+// Column-mapped CSV/XLSX imports into the CMDB and hardware pool
+
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::import::{CreateMappingProfileRequest, ImportOptions, ImportTarget},
+    services::import_service::{self, ImportError, ImportService},
+    utils::api_response::helpers::{error_body, error_body_with_details},
+};
+use core_engine::error::codes;
+
+/// Create Imports API router
+pub fn create_imports_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(ImportService::new(db));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/fields/:target", get(list_fields))
+        .route("/preview", post(preview_import))
+        .route("/run", post(run_import))
+        .route("/history", get(list_history))
+        .route("/history/:id", get(get_history))
+        .route("/mappings", get(list_mappings).post(create_mapping))
+        .route("/mappings/:id", delete(delete_mapping))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// QUERY PARAMETERS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct TargetQuery {
+    target: Option<ImportTarget>,
+}
+
+/// Uploaded file plus the named text fields sent with it
+struct ImportUpload {
+    filename: String,
+    bytes: Vec<u8>,
+    fields: std::collections::HashMap<String, String>,
+}
+
+async fn read_upload(mut multipart: Multipart) -> Result<ImportUpload, Response> {
+    let bad_request = |message: String| error_body(codes::BAD_REQUEST, &message).into_response();
+
+    let mut file = None;
+    let mut fields = std::collections::HashMap::new();
+    while let Some(field) = multipart.next_field().await.map_err(|e| bad_request(e.to_string()))? {
+        let name = field.name().unwrap_or("").to_string();
+        if name == "file" {
+            let filename = field.file_name().unwrap_or("upload.csv").to_string();
+            let bytes = field.bytes().await.map_err(|e| bad_request(e.to_string()))?;
+            file = Some((filename, bytes.to_vec()));
+        } else {
+            let value = field.text().await.map_err(|e| bad_request(e.to_string()))?;
+            fields.insert(name, value);
+        }
+    }
+
+    let (filename, bytes) = file.ok_or_else(|| bad_request("No file uploaded".to_string()))?;
+    Ok(ImportUpload {
+        filename,
+        bytes,
+        fields,
+    })
+}
+
+/// CI imports need CMDB write access; the hardware pool is open to any signed-in user
+fn check_permission(user: &AuthenticatedUser, target: ImportTarget) -> Result<(), Response> {
+    if target == ImportTarget::ConfigurationItems
+        && !(user.has_permission("cmdb:create") && user.has_permission("cmdb:update"))
+    {
+        return Err(error_body(codes::FORBIDDEN, "Permissions 'cmdb:create' and 'cmdb:update' required").into_response());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// Importable fields for a target, for the column-mapping UI
+async fn list_fields(Path(target): Path<ImportTarget>) -> impl IntoResponse {
+    Json(serde_json::json!({
+        "target": target,
+        "fields": import_service::field_info(target),
+    }))
+}
+
+/// Read headers and sample rows and suggest a column mapping
+/// Multipart fields: `file`, `target`, optional `sheet`
+async fn preview_import(
+    State(service): State<Arc<ImportService>>,
+    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+    multipart: Multipart,
+) -> impl IntoResponse {
+    let upload = match read_upload(multipart).await {
+        Ok(upload) => upload,
+        Err(response) => return response,
+    };
+    let target = match upload
+        .fields
+        .get("target")
+        .map(|t| serde_json::from_value::<ImportTarget>(serde_json::Value::String(t.clone())))
+    {
+        Some(Ok(target)) => target,
+        _ => {
+            return import_error_response(ImportError::Mapping(
+                "target must be 'configuration_items' or 'hardware_pool'".to_string(),
+            ))
+        }
+    };
+
+    match service.preview(
+        target,
+        &upload.filename,
+        &upload.bytes,
+        upload.fields.get("sheet").map(String::as_str),
+    ) {
+        Ok(preview) => (StatusCode::OK, Json(preview)).into_response(),
+        Err(e) => import_error_response(e),
+    }
+}
+
+/// Validate (dry run, the default) or apply an import
+/// Multipart fields: `file`, `options` (JSON ImportOptions)
+async fn run_import(
+    State(service): State<Arc<ImportService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    multipart: Multipart,
+) -> impl IntoResponse {
+    let upload = match read_upload(multipart).await {
+        Ok(upload) => upload,
+        Err(response) => return response,
+    };
+    let options: ImportOptions = match upload.fields.get("options").map(|o| serde_json::from_str(o)) {
+        Some(Ok(options)) => options,
+        Some(Err(e)) => return import_error_response(ImportError::Mapping(format!("invalid options: {}", e))),
+        None => return import_error_response(ImportError::Mapping("options field is required".to_string())),
+    };
+    if let Err(response) = check_permission(&user, options.target) {
+        return response;
+    }
+
+    tracing::info!(
+        "Import of {} into {:?} by {} (dry run: {})",
+        upload.filename,
+        options.target,
+        user.username,
+        options.dry_run
+    );
+
+    match service.run_import(&user, &upload.filename, &upload.bytes, options).await {
+        Ok(run) => (StatusCode::OK, Json(run)).into_response(),
+        Err(e) => import_error_response(e),
+    }
+}
+
+/// Past imports, newest first
+async fn list_history(
+    State(service): State<Arc<ImportService>>,
+    Query(params): Query<TargetQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.list_runs(&user, params.target).await {
+        Ok(runs) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "total": runs.len(), "runs": runs })),
+        )
+            .into_response(),
+        Err(e) => import_error_response(e),
+    }
+}
+
+/// A single import with its row-level errors and outcomes
+async fn get_history(
+    State(service): State<Arc<ImportService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.get_run(&user, &id).await {
+        Ok(run) => (StatusCode::OK, Json(run)).into_response(),
+        Err(e) => import_error_response(e),
+    }
+}
+
+/// Saved column-mapping profiles
+async fn list_mappings(
+    State(service): State<Arc<ImportService>>,
+    Query(params): Query<TargetQuery>,
+) -> impl IntoResponse {
+    match service.list_mappings(params.target).await {
+        Ok(profiles) => (StatusCode::OK, Json(profiles)).into_response(),
+        Err(e) => import_error_response(e),
+    }
+}
+
+/// Save a column-mapping profile
+async fn create_mapping(
+    State(service): State<Arc<ImportService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateMappingProfileRequest>,
+) -> impl IntoResponse {
+    match service.create_mapping(&user, request).await {
+        Ok(profile) => (StatusCode::CREATED, Json(profile)).into_response(),
+        Err(e) => import_error_response(e),
+    }
+}
+
+/// Delete a column-mapping profile
+async fn delete_mapping(
+    State(service): State<Arc<ImportService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_mapping(&id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => import_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert ImportError to HTTP response
+fn import_error_response(error: ImportError) -> Response {
+    let (code, message) = match &error {
+        ImportError::UnsupportedFormat(_) => (codes::UNSUPPORTED_FORMAT, "Unsupported file type"),
+        ImportError::Parse(_) => (codes::PARSE_FAILED, "Could not read spreadsheet"),
+        ImportError::Mapping(_) => (codes::BAD_REQUEST, "Invalid column mapping"),
+        ImportError::NotFound => (codes::NOT_FOUND, "Not found"),
+        ImportError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -1,25 +1,264 @@
 use axum::{
-    extract::{State, Json},
+    extract::{Path, Query, State, Json},
     http::StatusCode,
-    response::IntoResponse,
-    routing::post,
-    Router,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Router,
 };
 use std::sync::Arc;
 use serde_json::json;
 use crate::database::Database;
+use crate::middleware::auth::{require_auth, AuthState, AuthenticatedUser};
+use crate::models::integration::{
+    CreateIntegrationConnectionRequest, IntegrationConnectionView, SyncMode, TriggerSyncRequest,
+    UpdateIntegrationConnectionRequest,
+};
 use crate::services::integration_hub::{
-    IntegrationConfig, ProviderType, NutanixClient, IntegrationConnector
+    ActiveDirectoryClient, CatalystCenterClient, F5BigIpClient, SplunkClient, VeeamClient, IntegrationConfig, IntegrationError, IntegrationService, InventorySyncService, ProviderType, NutanixClient,
+    IntegrationConnector,
 };
+use crate::database::TracedQuery;
 
 pub fn create_integration_router(db: Arc<Database>) -> Router {
     let db_clone = db.clone();
+    let auth_state = AuthState::new();
+
+    // Stored connections and inventory sync
+    let connections = Router::new()
+        .route("/connections", get(list_connections).post(create_connection))
+        .route(
+            "/connections/:id",
+            get(get_connection).put(update_connection).delete(delete_connection),
+        )
+        .route("/connections/:id/test", post(test_connection))
+        .route("/connections/:id/status", get(sync_status))
+        .route("/connections/:id/sync", post(trigger_sync))
+        .route("/connections/:id/runs", get(list_runs))
+        .route("/runs/:id", get(get_run))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(db);
+
     Router::new()
         .route("/scan", post(move |body| async move {
             trigger_scan(db_clone, body).await
         }))
+        .merge(connections)
 }
 
+// ============================================================================
+// CONNECTIONS & SYNC
+// ============================================================================
+
+#[derive(serde::Deserialize)]
+struct RunsQuery {
+    limit: Option<usize>,
+}
+
+/// Viewing connections and sync history needs `integrations:read`;
+/// changing connections or starting syncs `integrations:manage`
+fn require_permission(user: &AuthenticatedUser, permission: &str) -> Result<(), Response> {
+    if user.has_permission(permission) {
+        return Ok(());
+    }
+    Err((
+        StatusCode::FORBIDDEN,
+        Json(json!({
+            "error": format!("Permission '{}' required", permission),
+            "code": 403
+        })),
+    )
+        .into_response())
+}
+
+async fn list_connections(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:read") {
+        return response;
+    }
+    match IntegrationService::new(db).list_connections(&user).await {
+        Ok(connections) => {
+            let views: Vec<IntegrationConnectionView> =
+                connections.iter().map(IntegrationConnectionView::from).collect();
+            (StatusCode::OK, Json(json!({ "total": views.len(), "connections": views }))).into_response()
+        }
+        Err(e) => integration_error_response(e),
+    }
+}
+
+async fn create_connection(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateIntegrationConnectionRequest>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:manage") {
+        return response;
+    }
+    match IntegrationService::new(db).create_connection(&user, request).await {
+        Ok(connection) => {
+            (StatusCode::CREATED, Json(IntegrationConnectionView::from(&connection))).into_response()
+        }
+        Err(e) => integration_error_response(e),
+    }
+}
+
+async fn get_connection(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:read") {
+        return response;
+    }
+    match IntegrationService::new(db).get_connection(&user, &id).await {
+        Ok(connection) => (StatusCode::OK, Json(IntegrationConnectionView::from(&connection))).into_response(),
+        Err(e) => integration_error_response(e),
+    }
+}
+
+async fn update_connection(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateIntegrationConnectionRequest>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:manage") {
+        return response;
+    }
+    match IntegrationService::new(db).update_connection(&user, &id, request).await {
+        Ok(connection) => (StatusCode::OK, Json(IntegrationConnectionView::from(&connection))).into_response(),
+        Err(e) => integration_error_response(e),
+    }
+}
+
+async fn delete_connection(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:manage") {
+        return response;
+    }
+    match IntegrationService::new(db).delete_connection(&user, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => integration_error_response(e),
+    }
+}
+
+/// Check credentials and reachability without syncing
+async fn test_connection(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:manage") {
+        return response;
+    }
+    match IntegrationService::new(db).test_connection(&user, &id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "success": true }))).into_response(),
+        Err(IntegrationError::Connector(message)) => {
+            (StatusCode::OK, Json(json!({ "success": false, "error": message }))).into_response()
+        }
+        Err(e) => integration_error_response(e),
+    }
+}
+
+async fn sync_status(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:read") {
+        return response;
+    }
+    match IntegrationService::new(db).sync_status(&user, &id).await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(e) => integration_error_response(e),
+    }
+}
+
+/// Start a sync in the background and return the RUNNING run (`?mode=FULL|DELTA`)
+async fn trigger_sync(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Query(params): Query<TriggerSyncRequest>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:manage") {
+        return response;
+    }
+    let connection = match IntegrationService::new(db.clone()).get_connection(&user, &id).await {
+        Ok(connection) => connection,
+        Err(e) => return integration_error_response(e),
+    };
+
+    let sync = InventorySyncService::new(db);
+    let mode = params.mode.unwrap_or(SyncMode::Delta);
+    match sync.start_run(&connection, mode, &user.user_id).await {
+        Ok(run) => {
+            let started = run.clone();
+            tokio::spawn(async move {
+                sync.execute_run(connection, run).await;
+            });
+            (StatusCode::ACCEPTED, Json(started)).into_response()
+        }
+        Err(e) => integration_error_response(e),
+    }
+}
+
+async fn list_runs(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Query(params): Query<RunsQuery>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:read") {
+        return response;
+    }
+    match IntegrationService::new(db).list_runs(&user, &id, params.limit.unwrap_or(20)).await {
+        Ok(runs) => (StatusCode::OK, Json(json!({ "total": runs.len(), "runs": runs }))).into_response(),
+        Err(e) => integration_error_response(e),
+    }
+}
+
+async fn get_run(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "integrations:read") {
+        return response;
+    }
+    match IntegrationService::new(db).get_run(&user, &id).await {
+        Ok(run) => (StatusCode::OK, Json(run)).into_response(),
+        Err(e) => integration_error_response(e),
+    }
+}
+
+fn integration_error_response(error: IntegrationError) -> Response {
+    let (status, message) = match &error {
+        IntegrationError::NotFound => (StatusCode::NOT_FOUND, "Not found"),
+        IntegrationError::Validation(_) => (StatusCode::BAD_REQUEST, "Validation error"),
+        IntegrationError::Conflict(_) => (StatusCode::CONFLICT, "Conflict"),
+        IntegrationError::Connector(_) => (StatusCode::BAD_GATEWAY, "Integration source error"),
+        IntegrationError::Secret(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Secret storage error"),
+        IntegrationError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let body = json!({
+        "error": message,
+        "code": status.as_u16(),
+        "details": error.to_string()
+    });
+
+    (status, Json(body)).into_response()
+}
+
+// ============================================================================
+// AD-HOC SCAN
+// ============================================================================
 
 #[derive(serde::Deserialize)]
 pub struct ScanRequest {
@@ -37,11 +276,16 @@ async fn trigger_scan(
         Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": format!("Invalid request: {}", e) }))).into_response(),
     };
 
-    // In a real app, we would look up the config from the DB using an ID.
-    // For now, we accept the config in the payload for testing.
+    // Ad-hoc scans take the config in the payload; stored connections sync
+    // through /connections/:id/sync instead.
 
     let connector: Box<dyn IntegrationConnector> = match payload.provider_type {
         ProviderType::NutanixPrism => Box::new(NutanixClient::new(payload.config)),
+        ProviderType::CiscoCatalystCenter => Box::new(CatalystCenterClient::new(payload.config)),
+        ProviderType::F5BigIp => Box::new(F5BigIpClient::new(payload.config)),
+        ProviderType::VeeamBackup => Box::new(VeeamClient::new(payload.config)),
+        ProviderType::ActiveDirectory => Box::new(ActiveDirectoryClient::new(payload.config)),
+        ProviderType::Splunk => Box::new(SplunkClient::new(payload.config)),
         _ => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": "Unsupported provider type" }))).into_response(),
     };
 
@@ -70,19 +314,20 @@ async fn trigger_scan(
                 // Upsert logic
                 // We use query to perform an UPSERT-like operation using type::thing to handle special chars in IDs
                 let sql = "UPDATE type::thing($table, $id) CONTENT $data";
-                
+
                 match db.query(sql)
                     .bind(("table", table))
                     .bind(("id", asset.external_id.as_str()))
                     .bind(("data", record))
+                    .traced("integration.trigger_scan")
                     .await {
                     Ok(_) => saved_count += 1,
                     Err(e) => println!("Failed to save asset {}: {}", asset.name, e),
                 }
             }
 
-            (StatusCode::OK, Json(json!({ 
-                "message": "Scan completed successfully", 
+            (StatusCode::OK, Json(json!({
+                "message": "Scan completed successfully",
                 "assets_found": assets.len(),
                 "assets_saved": saved_count,
                 "details": assets
@@ -93,6 +338,3 @@ async fn trigger_scan(
         }
     }
 }
-
-
-
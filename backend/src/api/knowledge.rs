@@ -6,9 +6,11 @@ use crate::middleware::auth::AuthenticatedUser;
 use crate::models::knowledge::*;
 use crate::services::knowledge_service::KnowledgeService;
 use crate::services::kb_suggestion_service::KBSuggestionService;
+use crate::services::kb_attachment_service::{AttachmentLimits, KbAttachmentService};
+use crate::utils::pagination::Pagination;
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
     routing::{delete, get, post, put},
     Extension, Json, Router,
@@ -30,6 +32,19 @@ pub fn knowledge_routes() -> Router<Arc<Database>> {
         .route("/articles/:id/rate", post(rate_article))
         .route("/articles/:id/versions", get(get_article_versions))
         .route("/articles/by-slug/:slug", get(get_article_by_slug))
+        // Attachment endpoints
+        .route(
+            "/articles/:id/attachments",
+            get(list_attachments)
+                .post(upload_attachment)
+                // Leave headroom over the file limit for the multipart envelope
+                .layer(DefaultBodyLimit::max(
+                    AttachmentLimits::from_env().max_bytes as usize + 64 * 1024,
+                )),
+        )
+        .route("/attachments/cleanup", post(cleanup_attachments))
+        .route("/attachments/:id", get(get_attachment).delete(delete_attachment))
+        .route("/attachments/:id/content", get(get_attachment_content))
         // Category endpoints
         .route("/categories", get(list_categories).post(create_category))
         .route("/categories/:id", get(get_category).put(update_category).delete(delete_category))
@@ -39,6 +54,9 @@ pub fn knowledge_routes() -> Router<Arc<Database>> {
         .route("/suggest", get(suggest_articles))
         .route("/related-to-ticket/:ticket_id", get(get_related_articles))
         .route("/top-resolution-articles", get(get_top_resolution_articles))
+        .route("/suggest/ticket/:ticket_id", get(suggest_for_ticket))
+        .route("/suggestions/metrics", get(get_suggestion_metrics))
+        .route("/suggestions/:id/feedback", post(record_suggestion_feedback))
 }
 
 // ============================================================================
@@ -81,6 +99,7 @@ async fn list_articles(
     State(db): State<Arc<Database>>,
     Extension(user): Extension<AuthenticatedUser>,
     Query(params): Query<ListArticlesParams>,
+    pagination: Pagination<ArticleSort>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     if !user.has_permission("kb:read") {
         return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
@@ -98,11 +117,9 @@ async fn list_articles(
         status: params.status,
         visibility: if include_internal { None } else { Some(vec![ArticleVisibility::Public]) },
         author_id: params.author_id,
-        page: params.page,
-        page_size: params.page_size,
     };
 
-    match KnowledgeService::search_articles(db, request).await {
+    match KnowledgeService::search_articles(db, request, &pagination).await {
         Ok(response) => Ok(Json(response)),
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -115,6 +132,7 @@ async fn list_articles(
 async fn search_articles(
     State(db): State<Arc<Database>>,
     Extension(user): Extension<AuthenticatedUser>,
+    pagination: Pagination<ArticleSort>,
     Json(mut request): Json<KBSearchRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     if !user.has_permission("kb:read") {
@@ -128,7 +146,7 @@ async fn search_articles(
         request.visibility = Some(vec![ArticleVisibility::Public]);
     }
 
-    match KnowledgeService::search_articles(db, request).await {
+    match KnowledgeService::search_articles(db, request, &pagination).await {
         Ok(response) => Ok(Json(response)),
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -514,8 +532,6 @@ pub struct ListArticlesParams {
     pub tags: Option<Vec<String>>,
     pub status: Option<Vec<ArticleStatus>>,
     pub author_id: Option<String>,
-    pub page: Option<u32>,
-    pub page_size: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -524,6 +540,226 @@ pub struct RateArticleRequest {
     pub feedback: Option<String>,
 }
 
+// ============================================================================
+// ATTACHMENT HANDLERS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct AttachmentContentParams {
+    /// Image rendition: thumb, medium or large (original when omitted)
+    size: Option<String>,
+}
+
+fn attachment_service(db: Arc<Database>) -> Result<KbAttachmentService, (StatusCode, Json<ErrorResponse>)> {
+    KbAttachmentService::new(db).map_err(|e| (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse { error: e }),
+    ))
+}
+
+/// Load the owning article and apply the same visibility rule as get_article
+async fn readable_article(
+    db: Arc<Database>,
+    user: &AuthenticatedUser,
+    article_id: &str,
+) -> Result<KBArticle, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("kb:read") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'kb:read' required".to_string(),
+        })));
+    }
+
+    let article = KnowledgeService::get_article(db, article_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: "Article not found".to_string() }),
+        ))?;
+
+    if article.visibility == ArticleVisibility::Internal
+        && !user.has_permission("kb:read_internal")
+    {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Access to internal articles denied".to_string(),
+        })));
+    }
+    Ok(article)
+}
+
+/// Load an attachment the user is allowed to read
+async fn readable_attachment(
+    db: Arc<Database>,
+    user: &AuthenticatedUser,
+    id: &str,
+) -> Result<(KbAttachmentService, KBAttachment), (StatusCode, Json<ErrorResponse>)> {
+    let service = attachment_service(db.clone())?;
+    let attachment = service
+        .get(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: "Attachment not found".to_string() }),
+        ))?;
+    readable_article(db, user, &attachment.article_id.id.to_raw()).await?;
+    Ok((service, attachment))
+}
+
+/// Upload an attachment (multipart field `file`)
+async fn upload_attachment(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("kb:update") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'kb:update' required".to_string(),
+        })));
+    }
+
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }));
+
+    let mut file = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| bad_request(e.to_string()))? {
+        if field.name() == Some("file") {
+            let filename = field.file_name().unwrap_or("attachment").to_string();
+            let bytes = field.bytes().await.map_err(|e| bad_request(e.to_string()))?;
+            file = Some((filename, bytes.to_vec()));
+        }
+    }
+    let (filename, bytes) = file.ok_or_else(|| bad_request("No file uploaded".to_string()))?;
+
+    let service = attachment_service(db)?;
+    match service.upload(&id, &filename, bytes, &user.user_id).await {
+        Ok(response) => Ok((StatusCode::CREATED, Json(response))),
+        Err(e) if e.contains("not found") => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) if e.contains("limit") || e.contains("maximum") => Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) if e.contains("not allowed") || e.contains("empty") => Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
+/// List an article's attachments
+async fn list_attachments(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    readable_article(db.clone(), &user, &id).await?;
+
+    match attachment_service(db)?.list_for_article(&id).await {
+        Ok(attachments) => Ok(Json(attachments)),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
+/// Get attachment metadata
+async fn get_attachment(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let (_, attachment) = readable_attachment(db, &user, &id).await?;
+    Ok(Json(attachment))
+}
+
+/// Download attachment bytes, or a resized rendition with `?size=`
+async fn get_attachment_content(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Query(params): Query<AttachmentContentParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let (service, attachment) = readable_attachment(db, &user, &id).await?;
+
+    let (bytes, content_type) = service
+        .read_content(&attachment, params.size.as_deref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: "Attachment content is missing from storage".to_string() }),
+        ))?;
+
+    // Images render inline in articles; everything else downloads
+    let disposition = if attachment.is_image { "inline" } else { "attachment" };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("{}; filename=\"{}\"", disposition, attachment.filename),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CACHE_CONTROL, "private, max-age=3600".to_string()),
+            (header::ETAG, format!("\"{}\"", attachment.checksum)),
+        ],
+        bytes,
+    ))
+}
+
+/// Delete an attachment
+async fn delete_attachment(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("kb:update") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'kb:update' required".to_string(),
+        })));
+    }
+
+    match attachment_service(db)?.delete(&id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) if e.contains("not found") => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
+/// Remove attachments whose article no longer exists
+async fn cleanup_attachments(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("kb:delete") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'kb:delete' required".to_string(),
+        })));
+    }
+
+    match attachment_service(db)?.cleanup_orphans().await {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
     error: String,
@@ -605,10 +841,95 @@ async fn get_top_resolution_articles(
     }
 }
 
+/// Rank KB articles against a ticket's title and description and log them
+async fn suggest_for_ticket(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(ticket_id): Path<String>,
+    Query(params): Query<TopArticlesParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("kb:read") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'kb:read' required".to_string(),
+        })));
+    }
+
+    let include_internal = user.has_permission("kb:read_internal");
+    let limit = params.limit.unwrap_or(5);
+
+    match KBSuggestionService::suggest_for_ticket(db, &ticket_id, include_internal, limit, &user.user_id).await {
+        Ok(response) => Ok(Json(response)),
+        Err(e) if e.contains("not found") => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
+/// Record whether a suggestion was viewed, accepted or dismissed
+async fn record_suggestion_feedback(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Json(request): Json<SuggestionFeedbackRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("kb:read") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'kb:read' required".to_string(),
+        })));
+    }
+
+    match KBSuggestionService::record_feedback(db, &id, request, &user.user_id).await {
+        Ok(log) => Ok(Json(log)),
+        Err(e) if e.contains("not found") => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) if e.contains("Outcome") => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
+/// Suggestion acceptance rates for tuning the ranking
+async fn get_suggestion_metrics(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<SuggestionMetricsParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !user.has_permission("kb:update") {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            error: "Permission 'kb:update' required".to_string(),
+        })));
+    }
+
+    match KBSuggestionService::suggestion_metrics(db, params.days.unwrap_or(30)).await {
+        Ok(metrics) => Ok(Json(metrics)),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+    }
+}
+
 // ============================================================================
 // QUERY PARAMS FOR KB-TICKET INTEGRATION
 // ============================================================================
 
+#[derive(Debug, Deserialize)]
+pub struct SuggestionMetricsParams {
+    pub days: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct KBSuggestionParams {
     pub title: Option<String>,
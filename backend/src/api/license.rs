@@ -0,0 +1,76 @@
+// Archer - License API
+// Admin view of the installed license and its consumption (seats, modules,
+// expiry), and installation of renewed license keys
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_admin,
+    },
+    models::license::InstallLicenseRequest,
+    services::license_service::{LicenseError, LicenseService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create License API router
+pub fn create_license_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(LicenseService::new(db));
+
+    Router::new()
+        .route("/", get(get_entitlement).put(install_license))
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// Current license status, seats in use and licensed modules
+async fn get_entitlement(State(service): State<Arc<LicenseService>>) -> impl IntoResponse {
+    match service.entitlement().await {
+        Ok(entitlement) => (StatusCode::OK, Json(entitlement)).into_response(),
+        Err(e) => license_error_response(e),
+    }
+}
+
+/// Verify and install a license key; takes effect immediately on this replica
+async fn install_license(
+    State(service): State<Arc<LicenseService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<InstallLicenseRequest>,
+) -> impl IntoResponse {
+    match service.install(&request.key, &user.username).await {
+        Ok(entitlement) => (StatusCode::OK, Json(entitlement)).into_response(),
+        Err(e) => license_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert LicenseError to HTTP response
+fn license_error_response(error: LicenseError) -> Response {
+    let (code, message) = match &error {
+        LicenseError::InvalidKey(_) => (codes::VALIDATION_ERROR, "Invalid license key"),
+        LicenseError::NotEnforced => (codes::NOT_CONFIGURED, "Licensing is not enforced"),
+        LicenseError::MissingPublicKey => (codes::NOT_CONFIGURED, "No vendor public key"),
+        LicenseError::SeatLimitReached(_) => (codes::SEAT_LIMIT_REACHED, "Seat limit reached"),
+        LicenseError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
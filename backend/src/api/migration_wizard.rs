@@ -2,22 +2,72 @@
 use axum::{
     extract::{Multipart, Path, Query, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Json},
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use chrono::Utc;
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
 use crate::database::Database;
+use crate::middleware::auth::{require_auth, AuthState, AuthenticatedUser};
+use crate::middleware::feature_flags::{require_feature, FeatureGate};
+use crate::middleware::project_access::{require_project_role, ProjectAccess};
+use crate::middleware::response_cache::{
+    cache_responses, invalidate, invalidate_on_write, project_scope, ResponseCache,
+};
+use crate::middleware::validation::{FieldErrors, Validate, ValidatedJson, ValidationRejection};
+use crate::models::collector::VmMetricQuery;
+use crate::models::guest_scan::{CreateGuestCredentialRequest, GuestProtocol, GuestScanRequest};
 use crate::models::migration_wizard_models::*;
+use crate::models::settings::{AnonymizeOptions, FeatureFlag};
+use crate::models::vm_hygiene::{HygieneThresholds, UpdateRemediationTaskRequest};
+use crate::services::anonymization_service::AnonymizationService;
+use crate::services::collector_ingest::CollectorService;
+use crate::services::cpu_compatibility_service::CpuCompatibilityService;
+use crate::services::guest_scan::{GuestScanError, GuestScanService};
+use crate::services::naming_policy_service::NamingPolicyService;
+use crate::models::project_access::ProjectRole;
 use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::project_access_service::{ProjectAccessError, ProjectAccessService};
+use crate::services::vm_hygiene_service::VmHygieneService;
 use crate::utils::api_response::{ApiResponse, helpers};
+use crate::utils::pagination::Pagination;
 
 pub fn create_migration_wizard_router(db: Arc<Database>) -> Router {
+    let cache = ResponseCache::per_project(db.clone());
+
+    // Heavy read models, served from the response cache until the project changes
+    let cached = Router::new()
+        .route("/projects/:id/strategy-analysis", get(analyze_project_strategy))
+        .route("/projects/:id/strategy-stats", get(get_project_strategy_stats))
+        .route("/projects/:id/cluster-utilization", get(get_cluster_utilization))
+        .route("/projects/:id/source-summary", get(get_source_summary))
+        .route("/projects/:id/cpu-compatibility", get(get_cpu_compatibility))
+        .route("/projects/:id/networks/discover", get(discover_networks))
+        .route("/projects/:id/network-topology", get(get_network_topology))
+        .route("/projects/:id/network-topology/mermaid", get(get_network_mermaid))
+        .route("/projects/:id/network-topology/visualization", get(get_network_visualization))
+        .route_layer(middleware::from_fn_with_state(cache.clone(), cache_responses));
+
+    // Agentless in-guest scans sign in to customer VMs, so they stay off
+    // until an administrator enables them
+    let guest_scans = Router::new()
+        .route("/projects/:id/guest-credentials", post(create_guest_credential))
+        .route("/projects/:id/guest-credentials", get(list_guest_credentials))
+        .route("/projects/:id/guest-credentials/:credential_id", delete(delete_guest_credential))
+        .route("/projects/:id/guest-scans", post(run_guest_scan))
+        .route("/projects/:id/guest-scans", get(list_guest_scans))
+        .route_layer(middleware::from_fn_with_state(
+            FeatureGate::new(db.clone(), FeatureFlag::GuestScan),
+            require_feature,
+        ));
+
     Router::new()
         .route("/projects", post(create_project))
         .route("/projects", get(list_projects))
@@ -26,33 +76,59 @@ pub fn create_migration_wizard_router(db: Arc<Database>) -> Router {
         .route("/projects/:id/vms", get(get_project_vms))
         .route("/projects/:id/wizard-state", post(save_wizard_state))
         .route("/projects/:id/wizard-state", get(load_wizard_state))
-        .route("/projects/:id/strategy-analysis", get(analyze_project_strategy))
-        .route("/projects/:id/strategy-stats", get(get_project_strategy_stats))
         .route("/projects/:id/clusters", post(create_cluster))
         .route("/projects/:id/clusters", get(get_project_clusters))
         .route("/projects/:id/auto-place", post(auto_place_vms))
         .route("/projects/:id/placements", post(create_manual_placement))
         .route("/projects/:id/placements", get(get_project_placements))
-        .route("/projects/:id/cluster-utilization", get(get_cluster_utilization))
-        .route("/projects/:id/networks/discover", get(discover_networks))
         .route("/projects/:id/network-mappings", post(create_network_mapping))
         .route("/projects/:id/network-mappings", get(get_project_network_mappings))
         .route("/projects/:id/network-mappings/validate", post(validate_network_mappings))
-        .route("/projects/:id/network-topology", get(get_network_topology))
-        .route("/projects/:id/network-topology/mermaid", get(get_network_mermaid))
-        .route("/projects/:id/network-topology/visualization", get(get_network_visualization))
         .route("/projects/:id/hld", post(generate_hld))
+        .route("/projects/:id/hygiene", get(get_vm_hygiene))
+        .route("/projects/:id/hygiene/tasks", post(raise_remediation_tasks))
+        .route("/projects/:id/remediation-tasks", get(list_remediation_tasks))
+        .route("/projects/:id/naming-compliance", get(get_naming_compliance))
+        .route("/projects/:id/vm-metrics", get(get_vm_metrics))
+        .merge(cached)
+        .merge(guest_scans)
+        // Writes under /projects/:id retire that project's cached responses.
+        // Routes below are keyed by their own ids and invalidate explicitly.
+        .route_layer(middleware::from_fn_with_state(cache, invalidate_on_write))
+        // Reads need a viewer role on the project, writes an editor role
+        .route_layer(middleware::from_fn_with_state(ProjectAccess::new(db.clone()), require_project_role))
         .route("/network-icons", get(get_all_icon_mappings))
         .route("/network-icons/:vendor/:node_type", get(get_icon_mapping))
         .route("/clusters/:id", get(get_cluster))
         .route("/clusters/:id", put(update_cluster))
         .route("/clusters/:id", delete(delete_cluster))
         .route("/placements/:id", delete(delete_placement))
+        .route("/remediation-tasks/:id", patch(update_remediation_task))
         .route("/network-mappings/:id", put(update_network_mapping))
         .route("/network-mappings/:id", delete(delete_network_mapping))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
         .with_state(db)
 }
 
+/// Check the caller's role on a project for routes keyed by another id
+async fn authorize_project(
+    db: &Arc<Database>,
+    user: &AuthenticatedUser,
+    project_id: &str,
+    role: ProjectRole,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    match ProjectAccessService::new(db.clone()).authorize(user, project_id, role).await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let status = match e {
+                ProjectAccessError::Forbidden(_) => StatusCode::FORBIDDEN,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            Err((status, Json(json!({ "success": false, "error": e.to_string() }))))
+        }
+    }
+}
+
 // =============================================================================
 // PROJECT MANAGEMENT
 // =============================================================================
@@ -61,7 +137,8 @@ pub fn create_migration_wizard_router(db: Arc<Database>) -> Router {
 /// POST /api/v1/migration-wizard/projects
 async fn create_project(
     State(db): State<Arc<Database>>,
-    Json(payload): Json<CreateProjectRequest>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    ValidatedJson(payload): ValidatedJson<CreateProjectRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Creating migration wizard project: {}", payload.name);
 
@@ -81,6 +158,11 @@ async fn create_project(
                 })
                 .unwrap_or_else(|| "unknown".to_string());
 
+            // The creator owns the project; without a member it stays open to everyone
+            if let Err(e) = ProjectAccessService::new(db.clone()).add_owner(&user, &project_id).await {
+                tracing::warn!("Failed to make {} owner of project {}: {}", user.user_id, project_id, e);
+            }
+
             let response = CreateProjectResponse {
                 id: project_id,
                 name: project.name,
@@ -110,6 +192,7 @@ async fn create_project(
 /// GET /api/v1/migration-wizard/projects?status=draft&limit=10
 async fn list_projects(
     State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
     Query(filter): Query<ProjectFilter>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Listing migration wizard projects");
@@ -117,7 +200,22 @@ async fn list_projects(
     let service = MigrationWizardService::new(db.as_ref().clone());
     
     match service.list_projects(Some(filter)).await {
-        Ok(projects) => {
+        Ok(mut projects) => {
+            // Only projects the caller is a member of, or that nobody has shared yet
+            let ids: Vec<String> = projects
+                .iter()
+                .filter_map(|p| p.id.as_ref().map(|id| id.id.to_raw()))
+                .collect();
+            match ProjectAccessService::new(db.clone()).visible_projects(&user, &ids).await {
+                Ok(visible) => projects.retain(|p| p.id.as_ref().map_or(false, |id| visible.contains(&id.id.to_raw()))),
+                Err(e) => {
+                    tracing::error!("Failed to check project access: {}", e);
+                    return Err((
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({ "success": false, "error": e.to_string() })),
+                    ));
+                }
+            }
             let total = projects.len();
             let response = ListProjectsResponse { projects, total };
 
@@ -286,13 +384,14 @@ async fn upload_rvtools(
 
     // Process RVTools file
     match service.process_rvtools_file(&project_id, &file_path, filename.clone()).await {
-        Ok(vm_count) => {
+        Ok(ingest) => {
             let response = UploadRVToolsResponse {
                 project_id: project_id.clone(),
                 filename,
-                total_vms: vm_count as i32,
+                total_vms: ingest.vms_inserted as i32,
                 upload_date: Utc::now(),
                 processing_status: "completed".to_string(),
+                ingest,
             };
 
             Ok((StatusCode::OK, Json(json!({
@@ -322,7 +421,7 @@ async fn upload_rvtools(
 async fn save_wizard_state(
     State(db): State<Arc<Database>>,
     Path(project_id): Path<String>,
-    Json(payload): Json<SaveWizardStateRequest>,
+    ValidatedJson(payload): ValidatedJson<SaveWizardStateRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Saving wizard state for project: {}", project_id);
 
@@ -377,19 +476,17 @@ async fn get_project_vms(
     State(db): State<Arc<Database>>,
     Path(project_id): Path<String>,
     Query(filter): Query<VMFilter>,
+    pagination: Pagination<VmSort>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Getting VMs for project: {}", project_id);
 
     let service = MigrationWizardService::new(db.as_ref().clone());
     
-    match service.get_project_vms(&project_id, Some(filter)).await {
-        Ok(vms) => {
-            let total = vms.len();
-            let response = ListVMsResponse { vms, total };
-
+    match service.list_project_vms(&project_id, filter, &pagination).await {
+        Ok(page) => {
             Ok((StatusCode::OK, Json(json!({
                 "success": true,
-                "result": response
+                "result": page
             }))))
         }
         Err(e) => {
@@ -491,6 +588,19 @@ async fn get_project_strategy_stats(
 // CLUSTER MANAGEMENT
 // =============================================================================
 
+/// String values of the payload's `tags` object
+fn cluster_tags(payload: &serde_json::Value) -> BTreeMap<String, String> {
+    payload
+        .get("tags")
+        .and_then(|v| v.as_object())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|(key, value)| value.as_str().map(|v| (key.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Create a new destination cluster
 /// POST /api/v1/migration-wizard/projects/:id/clusters
 async fn create_cluster(
@@ -523,6 +633,9 @@ async fn create_cluster(
         cpu_oversubscription_ratio: payload.get("cpu_oversubscription_ratio").and_then(|v| v.as_f64()).unwrap_or(1.0),
         memory_oversubscription_ratio: payload.get("memory_oversubscription_ratio").and_then(|v| v.as_f64()).unwrap_or(1.0),
         strategy: payload.get("strategy").and_then(|v| v.as_str()).unwrap_or("lift-shift").to_string(),
+        cpu_model: payload.get("cpu_model").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        destination_cluster_id: payload.get("destination_cluster_id").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        tags: cluster_tags(&payload),
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -623,6 +736,7 @@ async fn update_cluster(
 
     match service.update_cluster(&cluster_id, updates).await {
         Ok(cluster) => {
+            invalidate(&db, &project_scope(&cluster.project_id.id.to_raw())).await;
             Ok((StatusCode::OK, Json(json!({
                 "success": true,
                 "result": cluster
@@ -652,7 +766,8 @@ async fn delete_cluster(
     let service = MigrationWizardService::new(db.as_ref().clone());
 
     match service.delete_cluster(&cluster_id).await {
-        Ok(()) => {
+        Ok(cluster) => {
+            invalidate(&db, &project_scope(&cluster.project_id.id.to_raw())).await;
             Ok((StatusCode::OK, Json(json!({
                 "success": true,
                 "result": {
@@ -688,19 +803,15 @@ async fn auto_place_vms(
     let service = MigrationWizardService::new(db.as_ref().clone());
     
     match service.auto_place_vms(&project_id).await {
-        Ok((placements, warnings)) => {
-            // Get cluster utilization stats
-            let utilization = service.get_cluster_utilization(&project_id).await
-                .unwrap_or_default();
-            
+        Ok((placements, warnings, utilization)) => {
             let cluster_util: Vec<serde_json::Value> = utilization.iter().map(|(cluster, cpu, memory, storage, vm_count)| {
                 let cluster_id = cluster.id.as_ref()
-                    .and_then(|thing| thing.id.to_string().split(':').nth(1).map(|s| s.to_string()))
+                    .map(|thing| thing.id.to_raw())
                     .unwrap_or_default();
                 
                 let cpu_total = (cluster.total_cores as f64 * cluster.cpu_oversubscription_ratio) as i32;
-                let memory_total = (cluster.memory_gb as f64 * 1024.0 * cluster.memory_oversubscription_ratio) as i32;
-                let storage_total = cluster.storage_tb * 1024.0;
+                let memory_total = cluster.allocatable_memory().as_mib() as i32;
+                let storage_total = cluster.storage_capacity().as_gib();
                 
                 json!({
                     "cluster_id": cluster_id,
@@ -746,31 +857,13 @@ async fn auto_place_vms(
 async fn create_manual_placement(
     State(db): State<Arc<Database>>,
     Path(project_id): Path<String>,
-    Json(payload): Json<serde_json::Value>,
+    ValidatedJson(payload): ValidatedJson<ManualPlacementRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Creating manual placement for project: {}", project_id);
 
-    let vm_id = payload.get("vm_id")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| (
-            StatusCode::BAD_REQUEST,
-            Json(json!({ "success": false, "error": "Missing 'vm_id' field" }))
-        ))?;
-
-    let cluster_id = payload.get("cluster_id")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| (
-            StatusCode::BAD_REQUEST,
-            Json(json!({ "success": false, "error": "Missing 'cluster_id' field" }))
-        ))?;
-
-    let strategy = payload.get("strategy")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-
     let service = MigrationWizardService::new(db.as_ref().clone());
     
-    match service.create_manual_placement(&project_id, vm_id, cluster_id, strategy).await {
+    match service.create_manual_placement(&project_id, &payload.vm_id, &payload.cluster_id, payload.strategy, payload.tags).await {
         Ok((placement, warnings)) => {
             Ok((StatusCode::CREATED, Json(json!({
                 "success": true,
@@ -830,14 +923,21 @@ async fn get_project_placements(
 /// DELETE /api/v1/migration-wizard/placements/:id
 async fn delete_placement(
     State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
     Path(placement_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Deleting placement: {}", placement_id);
 
     let service = MigrationWizardService::new(db.as_ref().clone());
+    if let Ok(Some(placement)) = service.get_placement(&placement_id).await {
+        authorize_project(&db, &user, &placement.project_id.id.to_raw(), ProjectRole::Editor).await?;
+    }
     
     match service.delete_placement(&placement_id).await {
-        Ok(()) => {
+        Ok(deleted) => {
+            if let Some(placement) = deleted {
+                invalidate(&db, &project_scope(&placement.project_id.id.to_raw())).await;
+            }
             Ok((StatusCode::OK, Json(json!({
                 "success": true,
                 "result": {
@@ -876,8 +976,8 @@ async fn get_cluster_utilization(
                     .unwrap_or_default();
                 
                 let cpu_total = (cluster.total_cores as f64 * cluster.cpu_oversubscription_ratio) as i32;
-                let memory_total = (cluster.memory_gb as f64 * 1024.0 * cluster.memory_oversubscription_ratio) as i32;
-                let storage_total = cluster.storage_tb * 1024.0;
+                let memory_total = cluster.allocatable_memory().as_mib() as i32;
+                let storage_total = cluster.storage_capacity().as_gib();
                 
                 json!({
                     "cluster_id": cluster_id,
@@ -913,6 +1013,273 @@ async fn get_cluster_utilization(
     }
 }
 
+// =============================================================================
+// VM HYGIENE
+// =============================================================================
+
+fn hygiene_error(context: &str, e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    tracing::error!("{}: {}", context, e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    )
+}
+
+/// Target clusters, placed VMs and destination networks checked against
+/// the naming policy of the project's tenant
+/// GET /api/v1/migration-wizard/projects/:id/naming-compliance
+async fn get_naming_compliance(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let report = NamingPolicyService::new(db.as_ref().clone())
+        .check_project(&project_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to check naming compliance: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "success": false, "error": e.to_string() })),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": report }))))
+}
+
+/// Utilization samples streamed by collectors, newest first; filter with
+/// `?vm_name=`, `?since=` and `?limit=` (default 1000, at most 10000)
+/// GET /api/v1/migration-wizard/projects/:id/vm-metrics
+async fn get_vm_metrics(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Query(query): Query<VmMetricQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let samples = CollectorService::new(db)
+        .list_metrics(&project_id, &query)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to list VM metrics: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "success": false, "error": e.to_string() })),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": { "total": samples.len(), "samples": samples } }))))
+}
+
+fn guest_scan_error(context: &str, e: GuestScanError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match &e {
+        GuestScanError::CredentialNotFound => StatusCode::NOT_FOUND,
+        GuestScanError::Validation(_) => StatusCode::BAD_REQUEST,
+        GuestScanError::Connection(_) | GuestScanError::Unauthorized | GuestScanError::Script(_) => StatusCode::BAD_GATEWAY,
+        GuestScanError::Secret(_) | GuestScanError::Database(_) => {
+            tracing::error!("{}: {}", context, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    (status, Json(json!({ "success": false, "error": e.to_string() })))
+}
+
+/// Store a WinRM or SSH credential for in-guest scans; the secret is
+/// sealed and never returned
+/// POST /api/v1/migration-wizard/projects/:id/guest-credentials
+async fn create_guest_credential(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    ValidatedJson(request): ValidatedJson<CreateGuestCredentialRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let credential = GuestScanService::new(db.as_ref().clone())
+        .create_credential(&project_id, request, &user.user_id)
+        .await
+        .map_err(|e| guest_scan_error("Failed to create guest credential", e))?;
+
+    Ok((StatusCode::CREATED, Json(json!({ "success": true, "result": credential }))))
+}
+
+/// GET /api/v1/migration-wizard/projects/:id/guest-credentials
+async fn list_guest_credentials(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let credentials = GuestScanService::new(db.as_ref().clone())
+        .list_credentials(&project_id)
+        .await
+        .map_err(|e| guest_scan_error("Failed to list guest credentials", e))?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": credentials }))))
+}
+
+/// DELETE /api/v1/migration-wizard/projects/:id/guest-credentials/:credential_id
+async fn delete_guest_credential(
+    State(db): State<Arc<Database>>,
+    Path((project_id, credential_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    GuestScanService::new(db.as_ref().clone())
+        .delete_credential(&project_id, &credential_id)
+        .await
+        .map_err(|e| guest_scan_error("Failed to delete guest credential", e))?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true }))))
+}
+
+/// Scan the selected VMs and keep each VM's latest result; unreachable
+/// guests are reported as failed scans
+/// POST /api/v1/migration-wizard/projects/:id/guest-scans
+async fn run_guest_scan(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    ValidatedJson(request): ValidatedJson<GuestScanRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let summary = GuestScanService::new(db.as_ref().clone())
+        .scan(&project_id, request)
+        .await
+        .map_err(|e| guest_scan_error("Failed to scan guests", e))?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": summary }))))
+}
+
+/// GET /api/v1/migration-wizard/projects/:id/guest-scans
+async fn list_guest_scans(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let scans = GuestScanService::new(db.as_ref().clone())
+        .list_scans(&project_id)
+        .await
+        .map_err(|e| guest_scan_error("Failed to list guest scans", e))?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": scans }))))
+}
+
+/// Old or large snapshots and outdated or missing VMware Tools, with the
+/// storage snapshots hold
+/// GET /api/v1/migration-wizard/projects/:id/hygiene?snapshot_max_age_days=3&snapshot_max_size_gb=50
+async fn get_vm_hygiene(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Query(thresholds): Query<HygieneThresholds>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let report = VmHygieneService::new(db.as_ref().clone())
+        .assess(&project_id, thresholds, false)
+        .await
+        .map_err(|e| hygiene_error("Failed to assess VM hygiene", e))?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": report }))))
+}
+
+/// Assess and raise consolidate-snapshot and update-tools tasks for the
+/// affected VMs; tasks follow the VMs into their waves' runbooks
+/// POST /api/v1/migration-wizard/projects/:id/hygiene/tasks
+async fn raise_remediation_tasks(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Query(thresholds): Query<HygieneThresholds>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let report = VmHygieneService::new(db.as_ref().clone())
+        .assess(&project_id, thresholds, true)
+        .await
+        .map_err(|e| hygiene_error("Failed to raise remediation tasks", e))?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": report }))))
+}
+
+/// GET /api/v1/migration-wizard/projects/:id/remediation-tasks
+async fn list_remediation_tasks(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let tasks = VmHygieneService::new(db.as_ref().clone())
+        .list_tasks(&project_id)
+        .await
+        .map_err(|e| hygiene_error("Failed to list remediation tasks", e))?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": tasks }))))
+}
+
+/// Mark a remediation task done or reopen it
+/// PATCH /api/v1/migration-wizard/remediation-tasks/:id
+async fn update_remediation_task(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+    Json(request): Json<UpdateRemediationTaskRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = VmHygieneService::new(db.as_ref().clone());
+    let not_found = || (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Remediation task not found" })));
+
+    let task = service
+        .get_task(&task_id)
+        .await
+        .map_err(|e| hygiene_error("Failed to get remediation task", e))?
+        .ok_or_else(not_found)?;
+    authorize_project(&db, &user, &task.project_id.id.to_raw(), ProjectRole::Editor).await?;
+
+    let task = service
+        .update_task(&task_id, request)
+        .await
+        .map_err(|e| hygiene_error("Failed to update remediation task", e))?
+        .ok_or_else(not_found)?;
+
+    Ok((StatusCode::OK, Json(json!({ "success": true, "result": task }))))
+}
+
+/// Source clusters (hosts, HA/DRS settings, VM demand) and storage by type,
+/// from the vHost, vCluster, vDatastore and vRP sheets of the RVTools upload
+/// GET /api/v1/migration-wizard/projects/:id/source-summary
+async fn get_source_summary(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = MigrationWizardService::new(db.as_ref().clone());
+
+    match service.get_source_summary(&project_id).await {
+        Ok(summary) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": summary
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to get source summary: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "success": false,
+                    "error": e.to_string()
+                }))
+            ))
+        }
+    }
+}
+
+/// GET /api/v1/migration-wizard/projects/:id/cpu-compatibility
+async fn get_cpu_compatibility(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = CpuCompatibilityService::new(db.as_ref().clone());
+
+    match service.analyze(&project_id).await {
+        Ok(report) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": report
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to analyze CPU compatibility: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "success": false,
+                    "error": e.to_string()
+                }))
+            ))
+        }
+    }
+}
+
 // =============================================================================
 // NETWORK CONFIGURATION ENDPOINTS
 // =============================================================================
@@ -947,7 +1314,7 @@ async fn discover_networks(
 async fn create_network_mapping(
     State(db): State<Arc<Database>>,
     Path(project_id): Path<String>,
-    Json(payload): Json<CreateNetworkMappingRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateNetworkMappingRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Creating network mapping for project: {}", project_id);
 
@@ -978,6 +1345,7 @@ async fn create_network_mapping(
                 destination_dns: mapping.destination_dns,
                 is_valid: mapping.is_valid,
                 validation_errors: mapping.validation_errors,
+                tags: mapping.tags,
                 created_at: mapping.created_at,
             };
 
@@ -1035,6 +1403,7 @@ async fn get_project_network_mappings(
                     destination_dns: m.destination_dns.clone(),
                     is_valid: m.is_valid,
                     validation_errors: m.validation_errors.clone(),
+                    tags: m.tags.clone(),
                     created_at: m.created_at,
                 }
             }).collect();
@@ -1073,6 +1442,7 @@ async fn update_network_mapping(
     
     match service.update_network_mapping(&mapping_id, payload).await {
         Ok(mapping) => {
+            invalidate(&db, &project_scope(&mapping.project_id.id.to_raw())).await;
             Ok((StatusCode::OK, Json(json!({
                 "success": true,
                 "result": mapping
@@ -1102,7 +1472,10 @@ async fn delete_network_mapping(
     let service = MigrationWizardService::new(db.as_ref().clone());
     
     match service.delete_network_mapping(&mapping_id).await {
-        Ok(_) => {
+        Ok(deleted) => {
+            if let Some(mapping) = deleted {
+                invalidate(&db, &project_scope(&mapping.project_id.id.to_raw())).await;
+            }
             Ok((StatusCode::OK, Json(json!({
                 "success": true,
                 "message": "Network mapping deleted successfully"
@@ -1281,6 +1654,7 @@ async fn get_all_icon_mappings(
 async fn generate_hld(
     State(db): State<Arc<Database>>,
     Path(project_id): Path<String>,
+    Query(options): Query<AnonymizeOptions>,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!("Generating HLD document for project: {}", project_id);
@@ -1296,14 +1670,36 @@ async fn generate_hld(
         .unwrap_or(true);
     
     match service.generate_hld_document(&project_id, include_network, include_placements).await {
-        Ok(hld_markdown) => {
+        Ok(mut hld_markdown) => {
+            // Documents for vendors: mask VM names, hosts and addresses and
+            // keep the mapping locally
+            let mut anonymization_map = None;
+            if options.anonymize {
+                let (masked, map_id) = AnonymizationService::new(db.as_ref().clone())
+                    .anonymize_project_text(&options, &project_id, &hld_markdown)
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Failed to anonymize HLD document: {}", e);
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(json!({
+                                "success": false,
+                                "error": e.to_string()
+                            })),
+                        )
+                    })?;
+                hld_markdown = masked;
+                anonymization_map = Some(map_id);
+            }
+
             Ok((StatusCode::OK, Json(json!({
                 "success": true,
                 "result": {
                     "document_format": "markdown",
                     "content": hld_markdown,
                     "generated_at": chrono::Utc::now(),
-                    "project_id": project_id
+                    "project_id": project_id,
+                    "anonymization_map": anonymization_map
                 }
             }))))
         }
@@ -1392,3 +1788,104 @@ async fn get_icon_mapping(
         "result": response
     }))))
 }
+
+// =============================================================================
+// PAYLOAD VALIDATION
+// =============================================================================
+
+/// Steps in the frontend planning wizard
+const WIZARD_STEP_COUNT: i32 = 5;
+const MAX_NAME_LENGTH: usize = 255;
+/// VMs per guest scan request; larger selections go in several runs
+const MAX_GUEST_SCAN_VMS: usize = 200;
+
+impl Validate for CreateProjectRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("name", &self.name);
+        errors.length("name", &self.name, MAX_NAME_LENGTH);
+        errors.optional_length("description", &self.description, 10_000);
+        errors.into_result()
+    }
+}
+
+impl Validate for SaveWizardStateRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.range("current_step", self.current_step, 1, WIZARD_STEP_COUNT);
+        errors.range("total_clusters", self.total_clusters, 0, i32::MAX);
+        errors.range("network_mappings_count", self.network_mappings_count, 0, i32::MAX);
+        errors.not_blank("selected_rvtools_id", &self.selected_rvtools_id);
+        errors.optional_length("vm_name_pattern", &self.vm_name_pattern, MAX_NAME_LENGTH);
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateNetworkMappingRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("source_vlan_name", &self.source_vlan_name);
+        errors.length("source_vlan_name", &self.source_vlan_name, MAX_NAME_LENGTH);
+        errors.required("destination_vlan_name", &self.destination_vlan_name);
+        errors.length("destination_vlan_name", &self.destination_vlan_name, MAX_NAME_LENGTH);
+        for (field, vlan_id) in [
+            ("source_vlan_id", self.source_vlan_id),
+            ("destination_vlan_id", self.destination_vlan_id),
+        ] {
+            if let Some(vlan_id) = vlan_id {
+                errors.range(field, vlan_id, 0, 4094);
+            }
+        }
+        errors.tag_map("tags", &self.tags);
+        // Subnets, gateway and DNS are checked by the service, which stores
+        // the mapping with its validation errors rather than rejecting it
+        errors.into_result()
+    }
+}
+
+impl Validate for ManualPlacementRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("vm_id", &self.vm_id);
+        errors.required("cluster_id", &self.cluster_id);
+        errors.not_blank("strategy", &self.strategy);
+        errors.tag_map("tags", &self.tags);
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateGuestCredentialRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("name", &self.name);
+        errors.length("name", &self.name, MAX_NAME_LENGTH);
+        errors.required("username", &self.username);
+        errors.length("username", &self.username, MAX_NAME_LENGTH);
+        errors.not_blank("password", &self.password);
+        errors.not_blank("private_key", &self.private_key);
+        if self.password.is_some() == self.private_key.is_some() {
+            errors.add("password", "one_of", "set exactly one of password and private_key");
+        }
+        if self.private_key.is_some() && self.protocol != GuestProtocol::Ssh {
+            errors.add("private_key", "ssh_only", "private_key can only be used with ssh");
+        }
+        if self.port == Some(0) {
+            errors.add("port", "min", "port must be between 1 and 65535");
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for GuestScanRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("credential_id", &self.credential_id);
+        if self.vm_ids.is_empty() {
+            errors.add("vm_ids", "required", "vm_ids must list at least one VM");
+        }
+        if self.vm_ids.len() > MAX_GUEST_SCAN_VMS {
+            errors.add("vm_ids", "max", format!("vm_ids must list at most {} VMs", MAX_GUEST_SCAN_VMS));
+        }
+        errors.into_result()
+    }
+}
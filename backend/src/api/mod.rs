@@ -1,27 +1,64 @@
+pub mod ai; // LLM providers, chat & usage
+pub mod assessment; // Assessment rule packs API
+pub mod automation_exports; // IaC & cutover automation export API
 pub mod auth; // Authentication API (Phase 0)
 pub mod capacity;
 pub mod cluster_strategy;
 pub mod cmdb; // CMDB API (Phase 2)
+pub mod contracts; // Vendor contracts API
+pub mod component_classification; // Component classifier review queue API
 pub mod destination_clusters;
+pub mod diagnostics; // Support diagnostics bundle API
+pub mod documents; // Project document storage API
+pub mod document_reviews; // Document review & sign-off API
+pub mod estimation; // Effort & cost estimation API
+pub mod firmware_baselines; // Host firmware baselines API
+pub mod graphql; // Read-only GraphQL facade for nested project queries
 pub mod hardware_pool;
+pub mod imports; // Spreadsheet import API
 pub mod knowledge; // Knowledge Base API (Phase 1.5)
+pub mod license; // License & entitlement API
 pub mod migration_wizard; // Migration Planning Wizard API
+pub mod naming_policies; // Naming conventions & mandatory tags API
+pub mod procurement; // BOM purchase requests & PO tracking API
+pub mod reconciliation; // Post-migration reconciliation API
 pub mod project_lifecycle;
 pub mod project_workflow;
+pub mod project_access; // Per-project members, roles & invitations API
+pub mod feature_flags; // Feature flag evaluation for the frontend
 pub mod reporting; // Reporting & Dashboard API (Phase 6)
+pub mod report_schedules; // Scheduled reports API
+pub mod risks; // Project risk register API
 pub mod rvtools;
+pub mod saved_views; // Saved ticket/CI views API
+pub mod data_variables; // User-defined report/HLD data variables API
 pub mod service_catalog; // Service Catalog API (Phase 5)
+pub mod spare_parts; // Hardware pool spare parts API
+pub mod search; // Workspace search API
+pub mod sustainability; // Sustainability report API
 pub mod settings; // Global settings API
 pub mod teams; // Team Management API (Phase 1+)
 pub mod tickets; // Tickets API
 pub mod ticket_relationships; // Ticket Relationships API
+pub mod ticket_analytics; // Ticket & SLA analytics API
+pub mod ticket_templates; // Ticket templates & macros API
+pub mod ticket_schedules; // Recurring ticket schedules API
 pub mod tiering; // Hot/Cold Data Tiering API
+pub mod timeline; // Migration timeline & wave plan API
+pub mod uploads; // Resumable chunked uploads API
+pub mod webhooks; // Outbound webhook subscriptions & delivery logs API
+pub mod collectors; // Remote collector registry & tokens API
+pub mod usage_telemetry; // Opt-in usage telemetry API
+pub mod retention; // Data retention policies & purge API
+pub mod privacy; // User data export & erasure API
 pub mod assets; // CMDB Assets API
 pub mod monitoring; // Monitoring API
 pub mod integration; // Integration Hub API
 pub mod wizard; // Activity wizard API
 pub mod vm_placement; // VM placement API
 pub mod network_templates; // Network templates API
+pub mod network_profiles; // Network profile template authoring API
+pub mod os_lifecycle; // OS lifecycle & exposure API
 pub mod hld; // HLD generation API
 pub mod workflows; // Workflow Engine API (Phase 3)
                 // pub mod analytics; // TODO: Convert from actix_web to axum
@@ -30,18 +67,55 @@ pub mod enhanced_rvtools; // TODO: Fix compilation errors
 
 use crate::database::AppState;
 use crate::database::Database;
+use crate::database::DatabaseConfig;
+use crate::middleware::cors::CorsSettings;
+use crate::middleware::license::{require_license, require_module};
+use crate::middleware::rate_limiting::{create_shared_rate_limit_middleware, RateLimitConfig};
+use crate::models::license::LicensedModule;
 use crate::utils::api_response::{helpers, ApiResponse};
-use axum::{routing::get, Json, Router};
+use axum::{routing::{get, post}, Json, Router};
+use core_engine::compat::{self, CompatibilityReport, VersionInfo};
+use core_engine::error::{ErrorCode, ERROR_REGISTRY};
 use std::sync::Arc;
 
-pub fn api_router(state: AppState) -> Router {
+pub fn api_router(state: AppState, cors: &CorsSettings) -> Router {
+    // Routes handling credentials or secrets get a stricter CORS policy
+    // Login attempts are limited per client across all replicas sharing the
+    // database, since a load balancer spreads a client over them
+    let auth_routes = auth::create_auth_router(state.clone());
+    let auth_routes = if DatabaseConfig::default().is_shared() {
+        auth_routes.layer(axum::middleware::from_fn(create_shared_rate_limit_middleware(
+            RateLimitConfig::default(),
+            state.clone(),
+        )))
+    } else {
+        auth_routes
+    };
+
+    let sensitive_v1_routes = Router::new()
+        // Authentication routes (public + protected)
+        .nest("/auth", auth_routes)
+        .nest("/settings", settings::create_settings_router(state.clone()))
+        .nest("/diagnostics", diagnostics::create_diagnostics_router(state.clone()))
+        .nest("/license", license::create_license_router(state.clone()))
+        .nest("/usage-telemetry", usage_telemetry::create_usage_telemetry_router(state.clone()))
+        .layer(cors.sensitive_layer());
+
     // API v1 routes with proper versioning
     let v1_routes = Router::new()
-        // Authentication routes (public + protected)
-        .nest("/auth", auth::create_auth_router(state.clone()))
         .merge(project_workflow::routes().with_state(state.clone()))
         .merge(cluster_strategy::routes().with_state(state.clone()))
         .merge(wizard::wizard_routes().with_state(state.clone())) // Activity wizard routes
+        .nest("/hardware-pool/parts", spare_parts::create_spare_parts_router(state.clone()))
+        .nest("/procurement", procurement::create_procurement_router(state.clone()))
+        .nest("/document-reviews", document_reviews::create_document_reviews_router(state.clone()))
+        .nest("/project-access", project_access::create_project_access_router(state.clone()))
+        .nest("/features", feature_flags::create_feature_flags_router(state.clone()))
+        .nest("/retention", retention::create_retention_router(state.clone()))
+        .nest("/naming-policies", naming_policies::create_naming_policies_router(state.clone()))
+        .nest("/webhooks", webhooks::create_webhooks_router(state.clone()))
+        .nest("/collectors", collectors::create_collectors_router(state.clone()))
+        .nest("/privacy", privacy::create_privacy_router(state.clone()))
         .nest(
             "/hardware-pool",
             hardware_pool::create_hardware_pool_router(state.clone()),
@@ -52,6 +126,8 @@ pub fn api_router(state: AppState) -> Router {
         )
         .nest("/capacity", capacity::create_capacity_router(state.clone()))
         .nest("/rvtools", rvtools::create_rvtools_router(state.clone()))
+        .nest("/uploads", uploads::create_uploads_router(state.clone()))
+        .nest("/documents", documents::create_documents_router(state.clone()))
         .nest(
             "/enhanced-rvtools",
             enhanced_rvtools::create_enhanced_rvtools_router(state.clone()),
@@ -60,21 +136,79 @@ pub fn api_router(state: AppState) -> Router {
             "/project-lifecycle",
             project_lifecycle::create_project_lifecycle_router(state.clone()),
         )
-        .nest("/vm-placement", vm_placement::create_vm_placement_router(state.clone()))
+        .nest("/vm-placement", licensed(LicensedModule::Migration, vm_placement::create_vm_placement_router(state.clone())))
         .nest("/network-templates", network_templates::create_network_templates_router(state.clone()))
+        .nest(
+            "/network-profile-templates",
+            network_profiles::create_network_profiles_router(state.clone()),
+        )
         .nest("/hld", hld::create_hld_router(state.clone()))
-        .nest("/migration-wizard", migration_wizard::create_migration_wizard_router(state.clone()))
-        .nest("/tickets", tickets::create_tickets_router(state.clone()))
+        .nest(
+            "/migration-wizard",
+            licensed(LicensedModule::Migration, migration_wizard::create_migration_wizard_router(state.clone())),
+        )
+        .nest("/timeline", licensed(LicensedModule::Migration, timeline::create_timeline_router(state.clone())))
+        .nest("/graphql", licensed(LicensedModule::Migration, graphql::create_graphql_router(state.clone())))
+        .nest(
+            "/automation-exports",
+            licensed(LicensedModule::Automation, automation_exports::create_automation_exports_router(state.clone())),
+        )
+        .nest(
+            "/reconciliation",
+            licensed(LicensedModule::Automation, reconciliation::create_reconciliation_router(state.clone())),
+        )
+        .nest("/estimation", estimation::create_estimation_router(state.clone()))
+        .nest("/firmware-baselines", firmware_baselines::create_firmware_baselines_router(state.clone()))
+        .nest(
+            "/component-classification",
+            component_classification::create_component_classification_router(state.clone()),
+        )
+        .nest("/risks", risks::create_risks_router(state.clone()))
+        .nest("/os-lifecycle", os_lifecycle::create_os_lifecycle_router(state.clone()))
+        .nest("/sustainability", sustainability::create_sustainability_router(state.clone()))
+        .nest("/search", search::create_search_router(state.clone()))
+        .nest("/assessment", assessment::create_assessment_router(state.clone()))
+        .nest(
+            "/tickets/schedules",
+            licensed(LicensedModule::Itsm, ticket_schedules::create_ticket_schedules_router(state.clone())),
+        )
+        .nest("/tickets", licensed(LicensedModule::Itsm, tickets::create_tickets_router(state.clone())))
+        .nest(
+            "/analytics/tickets",
+            licensed(LicensedModule::Itsm, ticket_analytics::create_ticket_analytics_router(state.clone())),
+        )
+        .nest(
+            "/ticket-templates",
+            licensed(LicensedModule::Itsm, ticket_templates::create_ticket_templates_router(state.clone())),
+        )
+        .nest(
+            "/ticket-macros",
+            licensed(LicensedModule::Itsm, ticket_templates::create_ticket_macros_router(state.clone())),
+        )
         .nest("/teams", teams::create_teams_router(state.clone()))
+        .nest("/saved-views", saved_views::create_saved_views_router(state.clone()))
+        .nest("/data-variables", data_variables::create_data_variables_router(state.clone()))
         .nest("/knowledge", knowledge::knowledge_routes().with_state(state.clone()))
-        .nest("/cmdb", cmdb::cmdb_routes().with_state(state.clone()))
+        .nest("/cmdb", licensed(LicensedModule::Cmdb, cmdb::cmdb_routes().with_state(state.clone())))
+        .nest("/contracts", contracts::create_contracts_router(state.clone()))
         .nest("/assets", assets::create_assets_router(state.clone()))
+        .nest("/imports", imports::create_imports_router(state.clone()))
+        .nest("/report-schedules", report_schedules::create_report_schedules_router(state.clone()))
         .nest("/monitoring", monitoring::routes(state.clone()))
-        .nest("/integration", integration::create_integration_router(state.clone()))
-        .nest("/settings", settings::create_settings_router(state.clone()))
-        .nest("/workflows", workflows::create_workflows_router(state.clone()))
-        .nest("/catalog", service_catalog::create_service_catalog_router(state.clone()))
-        .nest("/tiering", tiering::create_tiering_router(state.clone()));
+        .nest(
+            "/integration",
+            licensed(LicensedModule::Integrations, integration::create_integration_router(state.clone())),
+        )
+        .nest("/workflows", licensed(LicensedModule::Itsm, workflows::create_workflows_router(state.clone())))
+        .nest(
+            "/catalog",
+            licensed(LicensedModule::Itsm, service_catalog::create_service_catalog_router(state.clone())),
+        )
+        .nest("/tiering", tiering::create_tiering_router(state.clone()))
+        .nest("/ai", licensed(LicensedModule::Ai, ai::create_ai_router(state.clone())))
+        .route("/error-codes", get(list_error_codes))
+        .route("/version", get(get_version))
+        .route("/version/handshake", post(version_handshake));
 
     Router::new()
         .route("/health", get(health_check))
@@ -96,6 +230,16 @@ pub fn api_router(state: AppState) -> Router {
             "/api/project-lifecycle",
             project_lifecycle::create_project_lifecycle_router(state.clone()),
         )
+        // Without a usable license the API is read-only; the sensitive routes
+        // below stay writable so an administrator can sign in and install a key
+        .layer(axum::middleware::from_fn(require_license))
+        .layer(cors.layer())
+        .nest("/api/v1", sensitive_v1_routes)
+}
+
+/// Reject requests to a module the license doesn't include
+fn licensed(module: LicensedModule, routes: Router) -> Router {
+    routes.layer(axum::middleware::from_fn_with_state(module, require_module))
 }
 
 async fn health_check() -> ApiResponse<serde_json::Value> {
@@ -106,3 +250,34 @@ async fn health_check() -> ApiResponse<serde_json::Value> {
         "api_version": "v1"
     }))
 }
+
+fn backend_version() -> VersionInfo {
+    VersionInfo::current("backend", env!("CARGO_PKG_VERSION"))
+}
+
+/// Backend release, schema version and the oldest client schema it serves
+async fn get_version() -> ApiResponse<VersionInfo> {
+    helpers::ok(backend_version())
+}
+
+/// Compare a client's version with this backend. Clients call this on
+/// startup and warn the user when the report is not compatible.
+async fn version_handshake(Json(client): Json<VersionInfo>) -> ApiResponse<CompatibilityReport> {
+    let report = compat::negotiate(client, backend_version());
+    if !report.is_compatible() {
+        tracing::warn!(
+            client = %report.client.component,
+            client_version = %report.client.version,
+            client_schema = report.client.schema_version,
+            status = ?report.status,
+            "Incompatible client connected"
+        );
+    }
+    helpers::ok(report)
+}
+
+/// Registry of stable error codes returned in `error.code` of API responses
+/// and Tauri command errors, with their category, HTTP status and retryability
+async fn list_error_codes() -> ApiResponse<&'static [ErrorCode]> {
+    helpers::ok(ERROR_REGISTRY)
+}
@@ -0,0 +1,87 @@
+// Archer - Naming Policy API
+// Admin management of the installation default and per-tenant naming
+// conventions and mandatory tags
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_admin,
+    },
+    models::naming_policy::UpsertNamingPolicyRequest,
+    services::naming_policy_service::{NamingPolicyError, NamingPolicyService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Naming Policy API router
+pub fn create_naming_policies_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(NamingPolicyService::new(db.as_ref().clone()));
+
+    Router::new()
+        .route("/", get(list_policies).put(upsert_policy))
+        .route("/:id", delete(delete_policy))
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List every naming policy
+async fn list_policies(State(service): State<Arc<NamingPolicyService>>) -> impl IntoResponse {
+    match service.list_policies().await {
+        Ok(policies) => (StatusCode::OK, Json(policies)).into_response(),
+        Err(e) => naming_policy_error_response(e),
+    }
+}
+
+/// Create or replace the policy of a tenant or the default
+async fn upsert_policy(
+    State(service): State<Arc<NamingPolicyService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<UpsertNamingPolicyRequest>,
+) -> impl IntoResponse {
+    match service.upsert_policy(request, &user.username).await {
+        Ok(policy) => (StatusCode::OK, Json(policy)).into_response(),
+        Err(e) => naming_policy_error_response(e),
+    }
+}
+
+/// Remove a policy; its tenant falls back to the default
+async fn delete_policy(
+    State(service): State<Arc<NamingPolicyService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_policy(&id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "deleted": id }))).into_response(),
+        Err(e) => naming_policy_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert NamingPolicyError to HTTP response
+fn naming_policy_error_response(error: NamingPolicyError) -> Response {
+    let (code, message) = match &error {
+        NamingPolicyError::NotFound => (codes::NOT_FOUND, "Naming policy not found"),
+        NamingPolicyError::Validation(_) => (codes::VALIDATION_ERROR, "Invalid naming policy"),
+        NamingPolicyError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
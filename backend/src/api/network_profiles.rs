@@ -0,0 +1,113 @@
+// Archer - Network Profile Templates API
+// Authoring of custom network profile templates; the standard ones are
+// read-only. Clusters are checked against a template through
+// POST /destination-clusters/:cluster_id/network-validation
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::project_models::{NetworkProfileTemplateFilter, SaveNetworkProfileTemplateRequest},
+    services::network_profile_service::{NetworkProfileError, NetworkProfileService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Network Profile Templates API router
+pub fn create_network_profiles_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(NetworkProfileService::new((*db).clone()));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/", get(list_templates).post(create_template))
+        .route("/:template_id", get(get_template).put(update_template).delete(delete_template))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List active templates, standard ones first
+async fn list_templates(
+    State(service): State<Arc<NetworkProfileService>>,
+    Query(filter): Query<NetworkProfileTemplateFilter>,
+) -> impl IntoResponse {
+    match service.list_templates(&filter).await {
+        Ok(templates) => (StatusCode::OK, Json(templates)).into_response(),
+        Err(e) => network_profile_error_response(e),
+    }
+}
+
+async fn get_template(
+    State(service): State<Arc<NetworkProfileService>>,
+    Path(template_id): Path<String>,
+) -> impl IntoResponse {
+    match service.get_template(&template_id).await {
+        Ok(template) => (StatusCode::OK, Json(template)).into_response(),
+        Err(e) => network_profile_error_response(e),
+    }
+}
+
+/// Create a custom template
+async fn create_template(
+    State(service): State<Arc<NetworkProfileService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<SaveNetworkProfileTemplateRequest>,
+) -> impl IntoResponse {
+    match service.create_template(&user.user_id, request).await {
+        Ok(template) => (StatusCode::CREATED, Json(template)).into_response(),
+        Err(e) => network_profile_error_response(e),
+    }
+}
+
+/// Replace a custom template's definition
+async fn update_template(
+    State(service): State<Arc<NetworkProfileService>>,
+    Path(template_id): Path<String>,
+    Json(request): Json<SaveNetworkProfileTemplateRequest>,
+) -> impl IntoResponse {
+    match service.update_template(&template_id, request).await {
+        Ok(template) => (StatusCode::OK, Json(template)).into_response(),
+        Err(e) => network_profile_error_response(e),
+    }
+}
+
+/// Delete a custom template no network profile uses
+async fn delete_template(
+    State(service): State<Arc<NetworkProfileService>>,
+    Path(template_id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_template(&template_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => network_profile_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert NetworkProfileError to HTTP response
+fn network_profile_error_response(error: NetworkProfileError) -> Response {
+    let (code, message) = match &error {
+        NetworkProfileError::NotFound => (codes::NOT_FOUND, "Network profile template not found"),
+        NetworkProfileError::ClusterNotFound => (codes::NOT_FOUND, "Destination cluster not found"),
+        NetworkProfileError::Standard => (codes::FORBIDDEN, "Standard templates cannot be changed"),
+        NetworkProfileError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        NetworkProfileError::InUse(_) => (codes::CONFLICT, "Template is in use"),
+        NetworkProfileError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
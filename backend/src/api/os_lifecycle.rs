@@ -0,0 +1,120 @@
+// OS Lifecycle API Endpoints
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::{get, post},
+    Router,
+};
+use chrono::Utc;
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::models::os_lifecycle::*;
+use crate::services::os_lifecycle_service::OsLifecycleService;
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::{codes, ErrorCode};
+
+pub fn create_os_lifecycle_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/", get(list_entries))
+        .route("/import", post(import_entries))
+        .route("/classify", get(classify_os))
+        .route("/projects/:id/exposure", get(get_exposure_report))
+        .with_state(db)
+}
+
+fn error_response(code: ErrorCode, e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    error_body(code, &e.to_string())
+}
+
+/// List the lifecycle dataset (built-in entries merged with imports)
+/// GET /api/v1/os-lifecycle
+async fn list_entries(
+    State(db): State<Arc<Database>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Listing OS lifecycle entries");
+
+    let service = OsLifecycleService::new(db.as_ref().clone());
+
+    match service.catalog().await {
+        Ok(catalog) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "entries": catalog.entries(),
+                "total": catalog.entries().len()
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to load OS lifecycle catalog: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Import lifecycle entries from JSON
+/// POST /api/v1/os-lifecycle/import
+async fn import_entries(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<OsLifecycleImportRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Importing {} OS lifecycle entries (replace: {})", request.entries.len(), request.replace);
+
+    let service = OsLifecycleService::new(db.as_ref().clone());
+
+    match service.import_entries(request).await {
+        Ok(imported) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": { "imported": imported }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to import OS lifecycle entries: {}", e);
+            Err(error_response(codes::BAD_REQUEST, e))
+        }
+    }
+}
+
+/// Normalise a guest OS string and report its support status
+/// GET /api/v1/os-lifecycle/classify?os=...
+async fn classify_os(
+    State(db): State<Arc<Database>>,
+    Query(query): Query<ClassifyOsQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Classifying OS string: {}", query.os);
+
+    let service = OsLifecycleService::new(db.as_ref().clone());
+
+    match service.catalog().await {
+        Ok(catalog) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": catalog.classify(Some(&query.os), Utc::now().date_naive())
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to load OS lifecycle catalog: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Count a project's VMs by OS support status
+/// GET /api/v1/os-lifecycle/projects/:id/exposure
+async fn get_exposure_report(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Building OS exposure report for project: {}", project_id);
+
+    let service = OsLifecycleService::new(db.as_ref().clone());
+
+    match service.exposure_report(&project_id).await {
+        Ok(report) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": report
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to build OS exposure report: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
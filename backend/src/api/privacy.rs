@@ -0,0 +1,127 @@
+// Archer - Personal Data API
+// Admin export of everything tied to a user account, and approval-gated
+// erasure of a departed user's personal identifiers
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_admin,
+    },
+    models::privacy::CreateErasureRequest,
+    services::privacy_service::{PrivacyError, PrivacyService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Personal Data API router
+pub fn create_privacy_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(PrivacyService::new(db));
+
+    Router::new()
+        .route("/users/:id/export", get(export_user))
+        .route("/erasure-requests", get(list_requests).post(create_request))
+        .route("/erasure-requests/:id", get(get_request))
+        .route("/erasure-requests/:id/execute", post(execute_request))
+        .route("/erasure-requests/:id/cancel", post(cancel_request))
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// Download everything tied to a user as JSON
+async fn export_user(
+    State(service): State<Arc<PrivacyService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.export_user(&id, &user).await {
+        Ok(export) => {
+            let filename = format!("user-data-{}.json", id.rsplit(':').next().unwrap_or(&id));
+            (
+                StatusCode::OK,
+                [(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))],
+                Json(export),
+            )
+                .into_response()
+        }
+        Err(e) => privacy_error_response(e),
+    }
+}
+
+/// List erasure requests, newest first
+async fn list_requests(State(service): State<Arc<PrivacyService>>) -> impl IntoResponse {
+    match service.list_requests().await {
+        Ok(requests) => (StatusCode::OK, Json(requests)).into_response(),
+        Err(e) => privacy_error_response(e),
+    }
+}
+
+/// Request erasure of a user and start its approval
+async fn create_request(
+    State(service): State<Arc<PrivacyService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateErasureRequest>,
+) -> impl IntoResponse {
+    match service.create_request(request, &user).await {
+        Ok(request) => (StatusCode::CREATED, Json(request)).into_response(),
+        Err(e) => privacy_error_response(e),
+    }
+}
+
+async fn get_request(State(service): State<Arc<PrivacyService>>, Path(id): Path<String>) -> impl IntoResponse {
+    match service.get_request(&id).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => privacy_error_response(e),
+    }
+}
+
+/// Erase the user of an approved request
+async fn execute_request(
+    State(service): State<Arc<PrivacyService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.execute_request(&id, &user).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => privacy_error_response(e),
+    }
+}
+
+async fn cancel_request(State(service): State<Arc<PrivacyService>>, Path(id): Path<String>) -> impl IntoResponse {
+    match service.cancel_request(&id).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => privacy_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert PrivacyError to HTTP response
+fn privacy_error_response(error: PrivacyError) -> Response {
+    let (code, message) = match &error {
+        PrivacyError::UserNotFound => (codes::NOT_FOUND, "User not found"),
+        PrivacyError::NotFound => (codes::NOT_FOUND, "Erasure request not found"),
+        PrivacyError::InvalidState(_) => (codes::CONFLICT, "Invalid state"),
+        PrivacyError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        PrivacyError::Workflow(_) => (codes::BAD_REQUEST, "Workflow error"),
+        PrivacyError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,195 @@
+// Archer ITSM - Procurement API
+// Purchase requests raised from a BOM, their approval, purchase orders and
+// receipt into the hardware pool
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    middleware::rbac::{check_assets_create, check_assets_delete, check_assets_read, check_assets_update},
+    models::procurement::{
+        CreatePurchaseRequest, PurchaseRequestListQuery, PurchaseRequestListResponse, ReceiveLineRequest,
+        RecordOrderRequest, SubmitPurchaseRequest, UpdateLineRequest,
+    },
+    services::procurement_service::{ProcurementError, ProcurementService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Procurement API router
+pub fn create_procurement_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(ProcurementService::new(db));
+    let auth_state = AuthState::new();
+
+    let read_routes = Router::new()
+        .route("/requests", get(list_requests))
+        .route("/requests/:id", get(get_request))
+        .layer(middleware::from_fn(check_assets_read));
+
+    let create_routes = Router::new()
+        .route("/requests", post(create_request))
+        .layer(middleware::from_fn(check_assets_create));
+
+    let update_routes = Router::new()
+        .route("/requests/:id/submit", post(submit_request))
+        .route("/requests/:id/cancel", post(cancel_request))
+        .route("/requests/:id/order", post(record_order))
+        .route("/requests/:id/lines/:line_no", put(update_line))
+        .route("/requests/:id/lines/:line_no/receive", post(receive_line))
+        .layer(middleware::from_fn(check_assets_update));
+
+    let delete_routes = Router::new()
+        .route("/requests/:id", delete(delete_request))
+        .layer(middleware::from_fn(check_assets_delete));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(create_routes)
+        .merge(update_routes)
+        .merge(delete_routes)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List purchase requests, optionally by status, project or vendor
+async fn list_requests(
+    State(service): State<Arc<ProcurementService>>,
+    Query(query): Query<PurchaseRequestListQuery>,
+) -> impl IntoResponse {
+    match service.list_requests(&query).await {
+        Ok(requests) => {
+            let response = PurchaseRequestListResponse {
+                total: requests.len(),
+                requests,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Get a single purchase request
+async fn get_request(
+    State(service): State<Arc<ProcurementService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.get_request(&id).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Convert BOM lines into a draft purchase request
+async fn create_request(
+    State(service): State<Arc<ProcurementService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreatePurchaseRequest>,
+) -> impl IntoResponse {
+    match service.create_request(&user, request).await {
+        Ok(request) => (StatusCode::CREATED, Json(request)).into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Delete a draft purchase request
+async fn delete_request(
+    State(service): State<Arc<ProcurementService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_request(&id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Start the approval workflow
+async fn submit_request(
+    State(service): State<Arc<ProcurementService>>,
+    Path(id): Path<String>,
+    body: Option<Json<SubmitPurchaseRequest>>,
+) -> impl IntoResponse {
+    let submit = body.map(|Json(b)| b).unwrap_or_default();
+    match service.submit_request(&id, submit).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Cancel a purchase request that has not been received
+async fn cancel_request(
+    State(service): State<Arc<ProcurementService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.cancel_request(&id).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Record the purchase order for an approved request
+async fn record_order(
+    State(service): State<Arc<ProcurementService>>,
+    Path(id): Path<String>,
+    Json(order): Json<RecordOrderRequest>,
+) -> impl IntoResponse {
+    match service.record_order(&id, order).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Mark a line shipped or cancelled, or move its expected delivery
+async fn update_line(
+    State(service): State<Arc<ProcurementService>>,
+    Path((id, line_no)): Path<(String, u32)>,
+    Json(update): Json<UpdateLineRequest>,
+) -> impl IntoResponse {
+    match service.update_line(&id, line_no, update).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+/// Receive units of a line into hardware pool stock
+async fn receive_line(
+    State(service): State<Arc<ProcurementService>>,
+    Path((id, line_no)): Path<(String, u32)>,
+    Json(receipt): Json<ReceiveLineRequest>,
+) -> impl IntoResponse {
+    match service.receive_line(&id, line_no, receipt).await {
+        Ok(request) => (StatusCode::OK, Json(request)).into_response(),
+        Err(e) => procurement_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert ProcurementError to HTTP response
+fn procurement_error_response(error: ProcurementError) -> Response {
+    let (code, message) = match &error {
+        ProcurementError::NotFound => (codes::NOT_FOUND, "Purchase request not found"),
+        ProcurementError::LineNotFound(_) => (codes::NOT_FOUND, "Purchase request line not found"),
+        ProcurementError::InvalidState(_) => (codes::CONFLICT, "Invalid state"),
+        ProcurementError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        ProcurementError::Workflow(_) => (codes::BAD_REQUEST, "Workflow error"),
+        ProcurementError::Receipt(_) => (codes::VALIDATION_ERROR, "Receipt failed"),
+        ProcurementError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
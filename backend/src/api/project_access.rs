@@ -0,0 +1,178 @@
+// Archer - Project Access API
+// Members of a project with their roles, invitations of users, teams and
+// email addresses, and the caller's own invitations
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::project_access::{InviteRequest, UpdateMemberRequest},
+    services::project_access_service::{ProjectAccessError, ProjectAccessService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Project Access API router
+pub fn create_project_access_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(ProjectAccessService::new(db));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/projects/:project_id/me", get(get_my_access))
+        .route("/projects/:project_id/members", get(list_members))
+        .route("/projects/:project_id/members/:member_id", put(update_member).delete(remove_member))
+        .route("/projects/:project_id/invitations", get(list_invitations).post(invite))
+        .route("/projects/:project_id/invitations/:invitation_id", delete(revoke_invitation))
+        .route("/invitations", get(my_invitations))
+        .route("/invitations/:id/accept", post(accept_invitation))
+        .route("/invitations/:id/decline", post(decline_invitation))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// The caller's role on a project
+async fn get_my_access(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> impl IntoResponse {
+    match service.summary(&user, &project_id).await {
+        Ok(summary) => (StatusCode::OK, Json(summary)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+async fn list_members(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> impl IntoResponse {
+    match service.list_members(&user, &project_id).await {
+        Ok(members) => (StatusCode::OK, Json(members)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+/// Change a member's role (owners only)
+async fn update_member(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path((project_id, member_id)): Path<(String, String)>,
+    Json(request): Json<UpdateMemberRequest>,
+) -> impl IntoResponse {
+    match service.update_member(&user, &project_id, &member_id, request).await {
+        Ok(member) => (StatusCode::OK, Json(member)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+/// Remove a member (owners), or leave the project
+async fn remove_member(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path((project_id, member_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service.remove_member(&user, &project_id, &member_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+async fn list_invitations(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> impl IntoResponse {
+    match service.list_invitations(&user, &project_id).await {
+        Ok(invitations) => (StatusCode::OK, Json(invitations)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+/// Invite a user, team or email address (owners only)
+async fn invite(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Json(request): Json<InviteRequest>,
+) -> impl IntoResponse {
+    match service.invite(&user, &project_id, request).await {
+        Ok(invitation) => (StatusCode::CREATED, Json(invitation)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+async fn revoke_invitation(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path((project_id, invitation_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service.revoke_invitation(&user, &project_id, &invitation_id).await {
+        Ok(invitation) => (StatusCode::OK, Json(invitation)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+/// Pending invitations the caller can accept
+async fn my_invitations(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.my_invitations(&user).await {
+        Ok(invitations) => (StatusCode::OK, Json(invitations)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+async fn accept_invitation(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.accept_invitation(&user, &id).await {
+        Ok(member) => (StatusCode::OK, Json(member)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+async fn decline_invitation(
+    State(service): State<Arc<ProjectAccessService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.decline_invitation(&user, &id).await {
+        Ok(invitation) => (StatusCode::OK, Json(invitation)).into_response(),
+        Err(e) => project_access_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert ProjectAccessError to HTTP response
+pub(crate) fn project_access_error_response(error: ProjectAccessError) -> Response {
+    let (code, message) = match &error {
+        ProjectAccessError::Forbidden(_) => (codes::FORBIDDEN, "Not allowed on this project"),
+        ProjectAccessError::MemberNotFound => (codes::NOT_FOUND, "Member not found"),
+        ProjectAccessError::InvitationNotFound => (codes::NOT_FOUND, "Invitation not found"),
+        ProjectAccessError::LastOwner => (codes::PRECONDITION_FAILED, "A project needs at least one owner"),
+        ProjectAccessError::InvalidState(_) => (codes::PRECONDITION_FAILED, "Invalid invitation state"),
+        ProjectAccessError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        ProjectAccessError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -15,6 +15,7 @@ use crate::database::AppState;
 use crate::models::project_models::*;
 use crate::services::enhanced_rvtools_service::{EnhancedRvToolsService, RvToolsExcelUploadData};
 use crate::services::project_management_service::ProjectManagementService;
+use crate::database::TracedQuery;
 // use crate::migration_models::*; // TODO: Fix migration_models imports
 
 // Note: S2dComplianceCheck types are defined in RVTools service
@@ -469,6 +470,7 @@ pub async fn list_lifecycle_analyses(
     let analyses: Vec<ProjectLifecycleAnalysis> = state
         .as_ref()
         .query(query)
+        .traced("project_lifecycle.list_lifecycle_analyses")
         .await
         .map_err(|e| {
             (
@@ -0,0 +1,114 @@
+// Archer - Post-Migration Reconciliation API
+// Compares a project's placement plan with the VMs found on the target after
+// cutover: synced Nutanix VMs, an inventory in the request, or an uploaded
+// vInfo workbook
+
+use axum::{
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState},
+    middleware::project_access::{require_project_role, ProjectAccess},
+    models::reconciliation::ReconciliationRequest,
+    services::reconciliation_service::{ReconciliationError, ReconciliationService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Reconciliation API router
+pub fn create_reconciliation_router(db: Arc<Database>) -> Router {
+    let access = ProjectAccess::new(db.clone());
+    let service = Arc::new(ReconciliationService::new(db));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/projects/:id", post(reconcile_project))
+        .route("/projects/:id/upload", post(reconcile_upload))
+        .route_layer(middleware::from_fn_with_state(access, require_project_role))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// Reconcile against synced Nutanix VMs or the VMs sent in the request
+async fn reconcile_project(
+    State(service): State<Arc<ReconciliationService>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<ReconciliationRequest>,
+) -> impl IntoResponse {
+    match service.reconcile(&project_id, request).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => reconciliation_error_response(e),
+    }
+}
+
+/// Reconcile against a multipart `file` (`.xlsx` with a vInfo sheet, or
+/// `.csv`) and an optional `wave_id`
+async fn reconcile_upload(
+    State(service): State<Arc<ReconciliationService>>,
+    Path(project_id): Path<String>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut wave_id: Option<String> = None;
+    let mut file: Option<(String, Vec<u8>)> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return reconciliation_error_response(ReconciliationError::Validation(e.to_string())),
+        };
+        let name = field.name().unwrap_or("").to_string();
+        match name.as_str() {
+            "wave_id" => match field.text().await {
+                Ok(text) => wave_id = Some(text.trim().to_string()).filter(|w| !w.is_empty()),
+                Err(e) => return reconciliation_error_response(ReconciliationError::Validation(e.to_string())),
+            },
+            "file" => {
+                let file_name = field.file_name().unwrap_or("inventory.xlsx").to_string();
+                match field.bytes().await {
+                    Ok(bytes) => file = Some((file_name, bytes.to_vec())),
+                    Err(e) => return reconciliation_error_response(ReconciliationError::Validation(e.to_string())),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some((file_name, bytes)) = file else {
+        return reconciliation_error_response(ReconciliationError::Validation("No file uploaded".to_string()));
+    };
+    match service
+        .reconcile_workbook(&project_id, &file_name, &bytes, wave_id.as_deref())
+        .await
+    {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => reconciliation_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert ReconciliationError to HTTP response
+fn reconciliation_error_response(error: ReconciliationError) -> Response {
+    let (code, message) = match &error {
+        ReconciliationError::ProjectNotFound => (codes::NOT_FOUND, "Project not found"),
+        ReconciliationError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        ReconciliationError::Plan(_) => (codes::MIGRATION_PLANNING_ERROR, "Placement plan unavailable"),
+        ReconciliationError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,233 @@
+// Archer - Report Schedules API
+// Cron-scheduled report generation, artifact history and downloads
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::report_schedule::{CreateReportScheduleRequest, UpdateReportScheduleRequest},
+    services::report_scheduler_service::{ReportScheduleError, ReportSchedulerService},
+    utils::api_response::helpers::{error_body, error_body_with_details},
+};
+use core_engine::error::codes;
+
+/// Create Report Schedules API router
+pub fn create_report_schedules_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(ReportSchedulerService::new(db));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/", get(list_schedules).post(create_schedule))
+        .route("/artifacts", get(list_artifacts))
+        .route("/artifacts/:id", get(get_artifact))
+        .route("/artifacts/:id/download", get(download_artifact))
+        .route("/:id", get(get_schedule).put(update_schedule).delete(delete_schedule))
+        .route("/:id/run", post(run_schedule))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// QUERY PARAMETERS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ArtifactQuery {
+    schedule_id: Option<String>,
+}
+
+/// Reading schedules and artifacts needs `reports:read`; changing or running them `reports:create`
+fn require_permission(user: &AuthenticatedUser, permission: &str) -> Result<(), Response> {
+    if user.has_permission(permission) {
+        return Ok(());
+    }
+    Err(error_body(codes::FORBIDDEN, &format!("Permission '{}' required", permission)).into_response())
+}
+
+// ============================================================================
+// SCHEDULE ENDPOINTS
+// ============================================================================
+
+/// List report schedules
+async fn list_schedules(
+    State(service): State<Arc<ReportSchedulerService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:read") {
+        return response;
+    }
+    match service.list_schedules(&user).await {
+        Ok(schedules) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "total": schedules.len(), "schedules": schedules })),
+        )
+            .into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+/// Get a single schedule
+async fn get_schedule(
+    State(service): State<Arc<ReportSchedulerService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:read") {
+        return response;
+    }
+    match service.get_schedule(&user, &id).await {
+        Ok(schedule) => (StatusCode::OK, Json(schedule)).into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+/// Create a schedule
+async fn create_schedule(
+    State(service): State<Arc<ReportSchedulerService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateReportScheduleRequest>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:create") {
+        return response;
+    }
+    match service.create_schedule(&user, request).await {
+        Ok(schedule) => (StatusCode::CREATED, Json(schedule)).into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+/// Update a schedule; the next run is recomputed from the cron expression
+async fn update_schedule(
+    State(service): State<Arc<ReportSchedulerService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateReportScheduleRequest>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:create") {
+        return response;
+    }
+    match service.update_schedule(&user, &id, request).await {
+        Ok(schedule) => (StatusCode::OK, Json(schedule)).into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+/// Delete a schedule
+async fn delete_schedule(
+    State(service): State<Arc<ReportSchedulerService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:create") {
+        return response;
+    }
+    match service.delete_schedule(&user, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+/// Generate and distribute a schedule's report now
+async fn run_schedule(
+    State(service): State<Arc<ReportSchedulerService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:create") {
+        return response;
+    }
+    match service.run_now(&user, &id).await {
+        Ok(artifact) => (StatusCode::OK, Json(artifact)).into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+// ============================================================================
+// ARTIFACT ENDPOINTS
+// ============================================================================
+
+/// Generated artifacts, newest first
+async fn list_artifacts(
+    State(service): State<Arc<ReportSchedulerService>>,
+    Query(params): Query<ArtifactQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:read") {
+        return response;
+    }
+    match service.list_artifacts(&user, params.schedule_id.as_deref()).await {
+        Ok(artifacts) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "total": artifacts.len(), "artifacts": artifacts })),
+        )
+            .into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+/// Artifact metadata including per-recipient delivery results
+async fn get_artifact(
+    State(service): State<Arc<ReportSchedulerService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:read") {
+        return response;
+    }
+    match service.get_artifact(&user, &id).await {
+        Ok(artifact) => (StatusCode::OK, Json(artifact)).into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+/// Download the artifact file
+async fn download_artifact(
+    State(service): State<Arc<ReportSchedulerService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    if let Err(response) = require_permission(&user, "reports:read") {
+        return response;
+    }
+    match service.read_artifact(&user, &id).await {
+        Ok((artifact, bytes)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, artifact.format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", artifact.filename),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => report_schedule_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert ReportScheduleError to HTTP response
+fn report_schedule_error_response(error: ReportScheduleError) -> Response {
+    let (code, message) = match &error {
+        ReportScheduleError::NotFound => (codes::NOT_FOUND, "Not found"),
+        ReportScheduleError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        ReportScheduleError::Generation(_) => (codes::INTERNAL_ERROR, "Report generation failed"),
+        ReportScheduleError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
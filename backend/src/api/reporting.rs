@@ -25,6 +25,7 @@ use crate::{
     },
     services::reporting_service,
 };
+use crate::database::TracedQuery;
 
 /// Create Reporting API router with authentication
 pub fn create_reporting_router(db: Arc<Database>) -> Router {
@@ -71,6 +72,7 @@ async fn list_reports(
     
     match db.query(query)
         .bind(("user", user.username.clone()))
+        .traced("reporting.list_reports")
         .await
     {
         Ok(mut result) => {
@@ -200,7 +202,7 @@ async fn update_report(
 
     let query = format!("UPDATE {} SET {}", id, updates.join(", "));
     
-    match db.query(&query).await {
+    match db.query(&query).traced("reporting.update_report").await {
         Ok(mut result) => {
             match result.take::<Option<ReportDefinition>>(0) {
                 Ok(Some(report)) => {
@@ -433,6 +435,7 @@ async fn list_dashboards(
     
     match db.query(query)
         .bind(("user", user.username.clone()))
+        .traced("reporting.list_dashboards")
         .await
     {
         Ok(mut result) => {
@@ -669,6 +672,7 @@ async fn create_widget(
                 if let Some(widget_id) = &widget.id {
                     let _ = db.query(&query)
                         .bind(("widget_id", widget_id.clone()))
+                        .traced("reporting.create_widget")
                         .await;
                 }
                 
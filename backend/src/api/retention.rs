@@ -0,0 +1,113 @@
+// Archer - Data Retention API
+// Admin management of per-tenant retention policies, dry-run previews and
+// on-demand purges
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_admin,
+    },
+    models::retention::{PurgeQuery, UpsertRetentionPolicyRequest},
+    services::retention_service::{RetentionError, RetentionService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Data Retention API router
+pub fn create_retention_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(RetentionService::new(db));
+
+    Router::new()
+        .route("/policies", get(list_policies).put(upsert_policy))
+        .route("/policies/:id", delete(delete_policy))
+        .route("/preview", get(preview_purge))
+        .route("/purge", post(run_purge))
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List every retention policy
+async fn list_policies(State(service): State<Arc<RetentionService>>) -> impl IntoResponse {
+    match service.list_policies().await {
+        Ok(policies) => (StatusCode::OK, Json(policies)).into_response(),
+        Err(e) => retention_error_response(e),
+    }
+}
+
+/// Create or replace the policy of a data class for a tenant or the default
+async fn upsert_policy(
+    State(service): State<Arc<RetentionService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<UpsertRetentionPolicyRequest>,
+) -> impl IntoResponse {
+    match service.upsert_policy(request, &user.username).await {
+        Ok(policy) => (StatusCode::OK, Json(policy)).into_response(),
+        Err(e) => retention_error_response(e),
+    }
+}
+
+/// Remove a policy; its tenant falls back to the default
+async fn delete_policy(
+    State(service): State<Arc<RetentionService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_policy(&id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "deleted": id }))).into_response(),
+        Err(e) => retention_error_response(e),
+    }
+}
+
+/// Count what the next purge would delete
+async fn preview_purge(
+    State(service): State<Arc<RetentionService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.run(true, &user.username).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => retention_error_response(e),
+    }
+}
+
+/// Purge now instead of waiting for the nightly run
+async fn run_purge(
+    State(service): State<Arc<RetentionService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(query): Query<PurgeQuery>,
+) -> impl IntoResponse {
+    match service.run(query.dry_run, &user.username).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => retention_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert RetentionError to HTTP response
+fn retention_error_response(error: RetentionError) -> Response {
+    let (code, message) = match &error {
+        RetentionError::NotFound => (codes::NOT_FOUND, "Retention policy not found"),
+        RetentionError::Validation(_) => (codes::VALIDATION_ERROR, "Invalid retention policy"),
+        RetentionError::Storage(_) => (codes::INTERNAL_ERROR, "Document storage error"),
+        RetentionError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,146 @@
+// Risk Register API Endpoints
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::{get, post, put},
+    Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::models::risk::*;
+use crate::services::risk_register_service::RiskRegisterService;
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::{codes, ErrorCode};
+
+pub fn create_risks_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/projects/:id", get(list_risks).post(create_risk))
+        .route("/projects/:id/seed", post(seed_risks))
+        .route("/:id", put(update_risk).delete(delete_risk))
+        .with_state(db)
+}
+
+fn error_response(code: ErrorCode, e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    error_body(code, &e.to_string())
+}
+
+/// List the risk register for a project
+/// GET /api/v1/risks/projects/:id
+async fn list_risks(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Listing risks for project: {}", project_id);
+
+    let service = RiskRegisterService::new(db.as_ref().clone());
+
+    match service.list_risks(&project_id).await {
+        Ok(risks) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "risks": risks,
+                "total": risks.len()
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to list risks: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Raise a risk manually
+/// POST /api/v1/risks/projects/:id
+async fn create_risk(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<CreateRiskRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Creating risk '{}' for project: {}", request.title, project_id);
+
+    let service = RiskRegisterService::new(db.as_ref().clone());
+
+    match service.create_risk(&project_id, request).await {
+        Ok(risk) => Ok((StatusCode::CREATED, Json(json!({
+            "success": true,
+            "result": risk
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to create risk: {}", e);
+            Err(error_response(codes::BAD_REQUEST, e))
+        }
+    }
+}
+
+/// Seed or refresh the register from analysis findings
+/// POST /api/v1/risks/projects/:id/seed
+async fn seed_risks(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<SeedRisksRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Seeding risk register for project: {}", project_id);
+
+    let service = RiskRegisterService::new(db.as_ref().clone());
+
+    match service.seed_from_analysis(&project_id, request).await {
+        Ok(result) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": result
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to seed risk register: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Update a risk (owner, status, mitigation, ratings...)
+/// PUT /api/v1/risks/:id
+async fn update_risk(
+    State(db): State<Arc<Database>>,
+    Path(risk_id): Path<String>,
+    Json(request): Json<UpdateRiskRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Updating risk: {}", risk_id);
+
+    let service = RiskRegisterService::new(db.as_ref().clone());
+
+    match service.update_risk(&risk_id, request).await {
+        Ok(risk) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": risk
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to update risk: {}", e);
+            Err(error_response(codes::NOT_FOUND, e))
+        }
+    }
+}
+
+/// Delete a risk
+/// DELETE /api/v1/risks/:id
+async fn delete_risk(
+    State(db): State<Arc<Database>>,
+    Path(risk_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Deleting risk: {}", risk_id);
+
+    let service = RiskRegisterService::new(db.as_ref().clone());
+
+    match service.delete_risk(&risk_id).await {
+        Ok(()) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "message": "Risk deleted successfully"
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to delete risk: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
@@ -1,23 +1,31 @@
 use axum::{
     extract::{Multipart, Path, Query, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
+use core_engine::models::ImportReport;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::{
     database::Database,
+    middleware::response_cache::etag,
     models::project_models::*,
+    models::settings::AnonymizeOptions,
+    services::anonymization_service::AnonymizationService,
     services::enhanced_rvtools_service::{
         EnhancedRvToolsProcessingResult, EnhancedRvToolsService, RvToolsExcelUploadData,
     },
     services::rvtools_service::{RvToolsService, RvToolsSyncOptions, RvToolsUploadData},
+    models::usage_telemetry::UsageFeature,
+    services::usage_telemetry_service,
 };
+use crate::database::TracedQuery;
 
 pub fn create_rvtools_router(db: Arc<Database>) -> Router {
     Router::new()
@@ -25,8 +33,9 @@ pub fn create_rvtools_router(db: Arc<Database>) -> Router {
         .route("/uploads", get(list_uploads))
         .route("/uploads/:upload_id", get(get_upload))
         .route("/uploads/:upload_id/data", get(get_upload_data))
+        .route("/uploads/:upload_id/report", get(get_import_report))
         .route("/uploads/:upload_id/sync", post(sync_to_hardware_pool))
-        .route("/analytics", get(get_analytics))
+        .route("/analytics", get(get_analytics).layer(middleware::from_fn(etag)))
         .route("/analytics/project/:project_id", get(get_project_analytics))
         .with_state(db)
 }
@@ -80,6 +89,29 @@ async fn upload_rvtools(
     let file_bytes =
         file_bytes.ok_or_else(|| ApiError::BadRequest("No file content".to_string()))?;
 
+    Ok(import_rvtools_file(&db, filename, file_bytes, project_id).await)
+}
+
+/// Import a received RVTools export; shared by the multipart upload and
+/// completed resumable uploads
+pub(crate) async fn import_rvtools_file(
+    db: &Arc<Database>,
+    filename: String,
+    file_bytes: Vec<u8>,
+    project_id: Option<surrealdb::sql::Thing>,
+) -> Response {
+    import_by_extension(db, filename, file_bytes, project_id)
+        .await
+        .into_response()
+}
+
+async fn import_by_extension(
+    db: &Arc<Database>,
+    filename: String,
+    file_bytes: Vec<u8>,
+    project_id: Option<surrealdb::sql::Thing>,
+) -> Result<impl IntoResponse, ApiError> {
+    usage_telemetry_service::record(UsageFeature::RvtoolsParse);
     let extension = filename
         .rsplit('.')
         .next()
@@ -108,6 +140,7 @@ async fn upload_rvtools(
                         servers_processed: result.servers_processed,
                         servers_added_to_pool: result.servers_added_to_pool,
                         processing_errors: result.processing_errors,
+                        report: result.report,
                         summary: result.summary,
                         upload_timestamp: Utc::now(),
                     }),
@@ -136,6 +169,7 @@ async fn upload_rvtools(
                             servers_processed: result.total_rows_processed as usize,
                             servers_added_to_pool: 0,
                             processing_errors,
+                            report: result.report,
                             summary,
                             upload_timestamp: Utc::now(),
                         }),
@@ -177,6 +211,7 @@ async fn list_uploads(
 
     let uploads: Result<Vec<RvToolsUpload>, _> = db
         .query(query_str)
+        .traced("rvtools.list_uploads")
         .await
         .map(|mut response| response.take(0))
         .and_then(|result| result);
@@ -288,10 +323,30 @@ async fn get_upload(
     }
 }
 
+async fn get_import_report(
+    State(db): State<Arc<Database>>,
+    Path(upload_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let upload: Option<RvToolsUpload> = db
+        .select(("rvtools_upload", upload_id.as_str()))
+        .await
+        .map_err(|e| ApiError::InternalError(e.to_string()))?;
+    let upload = upload.ok_or_else(|| ApiError::NotFound("Upload not found".to_string()))?;
+
+    Ok(Json(RvToolsImportReportResponse {
+        upload_id,
+        upload_status: upload.upload_status,
+        // Uploads processed before row-level reporting have no report
+        report: upload.import_report.unwrap_or_default(),
+        processed_at: upload.processed_at,
+    }))
+}
+
 async fn get_upload_data(
     State(db): State<Arc<Database>>,
     Path(upload_id): Path<String>,
     Query(query): Query<UploadDataQuery>,
+    Query(options): Query<AnonymizeOptions>,
 ) -> Result<impl IntoResponse, ApiError> {
     let upload_thing = surrealdb::sql::Thing::from(("rvtools_upload", upload_id.as_str()));
 
@@ -313,21 +368,36 @@ async fn get_upload_data(
     let data: Result<Vec<RvToolsData>, _> = db
         .query(query_str)
         .bind(("upload_id", upload_thing))
+        .traced("rvtools.get_upload_data")
         .await
         .map(|mut response| response.take(0))
         .and_then(|result| result);
 
-    match data {
-        Ok(data) => {
-            let total = data.len();
-            Ok(Json(RvToolsDataResponse {
-                upload_id,
-                data,
-                total,
-            }))
-        }
-        Err(e) => Err(ApiError::InternalError(e.to_string())),
+    let mut data = data.map_err(|e| ApiError::InternalError(e.to_string()))?;
+    let mut anonymization_map = None;
+    if options.anonymize {
+        let service = AnonymizationService::new(db.as_ref().clone());
+        let mut anonymizer = service
+            .anonymizer(&options)
+            .await
+            .map_err(|e| ApiError::InternalError(e.to_string()))?;
+        let mut rows = serde_json::to_value(&data).map_err(|e| ApiError::InternalError(e.to_string()))?;
+        anonymizer.anonymize_json(&mut rows);
+        data = serde_json::from_value(rows).map_err(|e| ApiError::InternalError(e.to_string()))?;
+        anonymization_map = Some(
+            service
+                .save_map(&anonymizer)
+                .map_err(|e| ApiError::InternalError(e.to_string()))?,
+        );
     }
+
+    let total = data.len();
+    Ok(Json(RvToolsDataResponse {
+        upload_id,
+        data,
+        total,
+        anonymization_map,
+    }))
 }
 
 // =============================================================================
@@ -401,10 +471,19 @@ struct RvToolsUploadResponse {
     servers_processed: usize,
     servers_added_to_pool: usize,
     processing_errors: Vec<crate::services::rvtools_service::RvToolsProcessingError>,
+    report: ImportReport,
     summary: crate::services::rvtools_service::RvToolsProcessingSummary,
     upload_timestamp: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize)]
+struct RvToolsImportReportResponse {
+    upload_id: String,
+    upload_status: RvToolsStatus,
+    report: ImportReport,
+    processed_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ListUploadsQuery {
     project_id: Option<String>,
@@ -430,6 +509,9 @@ struct RvToolsDataResponse {
     upload_id: String,
     data: Vec<RvToolsData>,
     total: usize,
+    /// Id of the local mapping file when the rows were anonymized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anonymization_map: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,192 @@
+// Archer ITSM - Saved Views API
+// REST endpoints for per-user / per-team saved filter views on tickets and CIs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::saved_view::{
+        CreateSavedViewRequest, FilterEntity, SavedViewListResponse, UpdateSavedViewRequest,
+    },
+    services::filter_query::{self, FilterError},
+    services::saved_view_service::{SavedViewError, SavedViewService},
+    utils::api_response::helpers::{error_body, error_body_with_details},
+};
+use core_engine::error::codes;
+
+/// Create Saved Views API router
+pub fn create_saved_views_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(SavedViewService::new((*db).clone()));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/", get(list_views).post(create_view))
+        .route("/fields/:entity", get(list_fields))
+        .route("/:id", get(get_view).put(update_view).delete(delete_view))
+        .route("/:id/results", get(apply_view))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// QUERY PARAMETERS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ViewListQuery {
+    entity: Option<FilterEntity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageQuery {
+    page: Option<u32>,
+    page_size: Option<u32>,
+}
+
+/// Read permission needed to see an entity's records
+fn read_permission(entity: FilterEntity) -> &'static str {
+    match entity {
+        FilterEntity::Tickets => "tickets:read",
+        FilterEntity::ConfigurationItems => "cmdb:read",
+    }
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List views visible to the user (own + team-shared)
+async fn list_views(
+    State(service): State<Arc<SavedViewService>>,
+    Query(params): Query<ViewListQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.list_views(&user, params.entity).await {
+        Ok(views) => {
+            let response = SavedViewListResponse {
+                total: views.len(),
+                views,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => saved_view_error_response(e),
+    }
+}
+
+/// Fields a filter may reference for an entity
+async fn list_fields(Path(entity): Path<FilterEntity>) -> impl IntoResponse {
+    Json(serde_json::json!({
+        "entity": entity,
+        "fields": filter_query::filterable_fields(entity),
+    }))
+}
+
+/// Get a single view
+async fn get_view(
+    State(service): State<Arc<SavedViewService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.get_view(&user, &id).await {
+        Ok(view) => (StatusCode::OK, Json(view)).into_response(),
+        Err(e) => saved_view_error_response(e),
+    }
+}
+
+/// Create a view
+async fn create_view(
+    State(service): State<Arc<SavedViewService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateSavedViewRequest>,
+) -> impl IntoResponse {
+    match service.create_view(&user, request).await {
+        Ok(view) => (StatusCode::CREATED, Json(view)).into_response(),
+        Err(e) => saved_view_error_response(e),
+    }
+}
+
+/// Update a view (owner only)
+async fn update_view(
+    State(service): State<Arc<SavedViewService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateSavedViewRequest>,
+) -> impl IntoResponse {
+    match service.update_view(&user, &id, request).await {
+        Ok(view) => (StatusCode::OK, Json(view)).into_response(),
+        Err(e) => saved_view_error_response(e),
+    }
+}
+
+/// Delete a view (owner only)
+async fn delete_view(
+    State(service): State<Arc<SavedViewService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.delete_view(&user, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => saved_view_error_response(e),
+    }
+}
+
+/// Apply a view and return one page of matching tickets or CIs
+async fn apply_view(
+    State(service): State<Arc<SavedViewService>>,
+    Path(id): Path<String>,
+    Query(params): Query<PageQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let view = match service.get_view(&user, &id).await {
+        Ok(view) => view,
+        Err(e) => return saved_view_error_response(e),
+    };
+    let permission = read_permission(view.entity);
+    if !user.has_permission(permission) {
+        return error_body(codes::FORBIDDEN, &format!("Permission '{}' required", permission))
+            .into_response();
+    }
+
+    match service.apply_view(&user, &id, params.page, params.page_size).await {
+        Ok((view, results)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "view": view,
+                "results": results,
+            })),
+        )
+            .into_response(),
+        Err(e) => saved_view_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert SavedViewError to HTTP response
+pub(crate) fn saved_view_error_response(error: SavedViewError) -> Response {
+    let (code, message) = match &error {
+        SavedViewError::NotFound => (codes::NOT_FOUND, "Saved view not found"),
+        SavedViewError::NotOwner => (codes::FORBIDDEN, "Only the owner can modify this view"),
+        SavedViewError::NotTeamMember => (codes::FORBIDDEN, "Not a member of the team"),
+        SavedViewError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        SavedViewError::Filter(FilterError::DatabaseError(_)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+        }
+        SavedViewError::Filter(_) => (codes::BAD_REQUEST, "Invalid filter"),
+        SavedViewError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,98 @@
+// Workspace Search API Endpoints
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Json},
+    routing::get,
+    Extension, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::middleware::auth::{require_auth, AuthState, AuthenticatedUser};
+use crate::models::search::*;
+use crate::services::search_service::{SearchService, DEFAULT_LIMIT};
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::{codes, ErrorCode};
+
+pub fn create_search_router(db: Arc<Database>) -> Router {
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/", get(search))
+        .with_state(db)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+}
+
+fn error_response(code: ErrorCode, e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    error_body(code, &e.to_string())
+}
+
+/// Entity types the user may see; projects and documents are not permission-gated
+fn permitted(user: &AuthenticatedUser, entity_type: SearchEntityType) -> bool {
+    match entity_type {
+        SearchEntityType::Ticket => user.has_permission("tickets:read"),
+        SearchEntityType::KbArticle => user.has_permission("kb:read"),
+        SearchEntityType::ConfigurationItem => user.has_permission("cmdb:read"),
+        SearchEntityType::Project | SearchEntityType::Document => true,
+    }
+}
+
+/// Ranked full-text search across the workspace
+/// GET /api/v1/search?q=vpn&types=ticket,kb&limit=20
+async fn search(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Workspace search by {}: {}", user.username, query.q);
+
+    let requested = match query.types.as_deref().filter(|t| !t.trim().is_empty()) {
+        Some(types) => types
+            .split(',')
+            .map(|t| {
+                SearchEntityType::parse(t).ok_or_else(|| {
+                    error_response(
+                        codes::BAD_REQUEST,
+                        anyhow::anyhow!("Unknown search type '{}'", t.trim()),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => SearchEntityType::ALL.to_vec(),
+    };
+    let mut types: Vec<SearchEntityType> = Vec::new();
+    for entity_type in requested {
+        if permitted(&user, entity_type) && !types.contains(&entity_type) {
+            types.push(entity_type);
+        }
+    }
+
+    let service = SearchService::new(db.as_ref().clone());
+
+    match service
+        .search(
+            &query.q,
+            &types,
+            user.tenant_id.as_deref(),
+            query.limit.unwrap_or(DEFAULT_LIMIT),
+        )
+        .await
+    {
+        Ok(response) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": response
+        })))),
+        Err(e) => {
+            tracing::error!("Workspace search failed: {}", e);
+            let code = if query.q.trim().is_empty() {
+                codes::BAD_REQUEST
+            } else {
+                codes::INTERNAL_ERROR
+            };
+            Err(error_response(code, e))
+        }
+    }
+}
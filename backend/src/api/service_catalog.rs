@@ -25,6 +25,7 @@ use crate::{
         rbac::{check_admin, check_catalog_admin, check_service_catalog_read},
     },
 };
+use crate::database::TracedQuery;
 
 // ============================================================================
 // QUERY PARAMETERS
@@ -116,7 +117,7 @@ async fn list_categories(
         "SELECT * FROM catalog_category ORDER BY sort_order".to_string()
     };
 
-    match db.query(query).await {
+    match db.query(query).traced("service_catalog.list_categories").await {
         Ok(mut response) => {
             let categories: Vec<CatalogCategory> = response.take(0).unwrap_or_default();
             (StatusCode::OK, Json(serde_json::json!({
@@ -245,7 +246,7 @@ async fn list_catalog_items(
         "SELECT * FROM catalog_item".to_string()
     };
 
-    match db.query(query).await {
+    match db.query(query).traced("service_catalog.list_catalog_items").await {
         Ok(mut response) => {
             let mut items: Vec<CatalogItem> = response.take(0).unwrap_or_default();
             
@@ -463,6 +464,7 @@ async fn list_my_requests(
     match db.query(query)
         .bind(("requester_id", &user.username))
         .bind(("status", status_filter.unwrap_or_default()))
+        .traced("service_catalog.list_my_requests")
         .await
     {
         Ok(mut response) => {
@@ -515,7 +517,7 @@ async fn list_pending_requests(
 
     let query = "SELECT * FROM service_request WHERE status = 'PENDING_APPROVAL' ORDER BY created_at ASC";
 
-    match db.query(query).await {
+    match db.query(query).traced("service_catalog.list_pending_requests").await {
         Ok(mut response) => {
             let requests: Vec<ServiceRequest> = response.take(0).unwrap_or_default();
             (StatusCode::OK, Json(serde_json::json!({
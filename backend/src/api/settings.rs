@@ -2,12 +2,16 @@
 //!
 //! Manages application-wide configuration and default values.
 //! Settings are stored as a singleton record in the database.
+//!
+//! Layered settings (system ← tenant ← project ← user) live under `/keys`,
+//! `/effective`, `/overrides` and `/changes`; see [`SettingsService`].
 
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Response},
-    routing::{get, patch},
+    routing::{get, patch, post, put},
     Json, Router,
 };
 use chrono::Utc;
@@ -16,13 +20,25 @@ use std::sync::Arc;
 
 use crate::{
     database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::integration::UpdateSimulationSettingsRequest,
     models::settings::*,
+    models::workflow::StorageBackend,
+    services::anonymization_service::{AnonymizationError, AnonymizationService},
+    services::currency_service::{CurrencyError, CurrencyService},
+    services::document_storage,
+    services::integration_hub::{IntegrationError, SimulationService},
+    services::overcommit_policy_service::{OvercommitPolicyError, OvercommitPolicyService},
+    services::settings_service::{SettingsError, SettingsService},
 };
 
 /// API Error type (placeholder - should use shared error type)
 #[derive(Debug)]
 pub enum ApiError {
     NotFound(String),
+    BadRequest(String),
+    Forbidden(String),
+    Conflict(String),
     InternalError(String),
 }
 
@@ -30,6 +46,9 @@ impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
             ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
             ApiError::InternalError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
         };
 
@@ -37,11 +56,105 @@ impl IntoResponse for ApiError {
     }
 }
 
+impl From<OvercommitPolicyError> for ApiError {
+    fn from(error: OvercommitPolicyError) -> Self {
+        match error {
+            OvercommitPolicyError::NotFound | OvercommitPolicyError::ClusterNotFound => {
+                ApiError::NotFound(error.to_string())
+            }
+            OvercommitPolicyError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            OvercommitPolicyError::InUse(_) => ApiError::Conflict(error.to_string()),
+            OvercommitPolicyError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+impl From<CurrencyError> for ApiError {
+    fn from(error: CurrencyError) -> Self {
+        match error {
+            CurrencyError::ProjectNotFound => ApiError::NotFound(error.to_string()),
+            CurrencyError::Validation(_) | CurrencyError::Conversion(_) | CurrencyError::Feed(_) => {
+                ApiError::BadRequest(error.to_string())
+            }
+            CurrencyError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+impl From<AnonymizationError> for ApiError {
+    fn from(error: AnonymizationError) -> Self {
+        match error {
+            AnonymizationError::MapNotFound(_) => ApiError::NotFound(error.to_string()),
+            AnonymizationError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            AnonymizationError::Io(msg) | AnonymizationError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
+impl From<IntegrationError> for ApiError {
+    fn from(error: IntegrationError) -> Self {
+        match error {
+            IntegrationError::NotFound => ApiError::NotFound(error.to_string()),
+            IntegrationError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            IntegrationError::Conflict(_) => ApiError::Conflict(error.to_string()),
+            IntegrationError::Connector(msg) | IntegrationError::Secret(msg) | IntegrationError::DatabaseError(msg) => {
+                ApiError::InternalError(msg)
+            }
+        }
+    }
+}
+
+impl From<SettingsError> for ApiError {
+    fn from(error: SettingsError) -> Self {
+        match error {
+            SettingsError::UnknownKey(_) | SettingsError::NotFound => ApiError::NotFound(error.to_string()),
+            SettingsError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            SettingsError::Forbidden(_) => ApiError::Forbidden(error.to_string()),
+            SettingsError::DatabaseError(msg) => ApiError::InternalError(msg),
+        }
+    }
+}
+
 /// Create Settings API router
 pub fn create_settings_router(db: Arc<Database>) -> Router {
     Router::new()
         .route("/", get(get_settings))
         .route("/", patch(update_settings))
+        .route("/overcommit-policies", get(list_overcommit_policies).post(create_overcommit_policy))
+        .route(
+            "/overcommit-policies/:id",
+            get(get_overcommit_policy)
+                .patch(update_overcommit_policy)
+                .delete(delete_overcommit_policy),
+        )
+        .route("/overcommit-policies/:id/preview", post(preview_overcommit_policy))
+        .route("/exchange-rates", get(get_exchange_rates).put(update_exchange_rates))
+        .route("/exchange-rates/refresh", post(refresh_exchange_rates))
+        .route("/anonymization", get(get_anonymization_settings).put(update_anonymization_settings))
+        .route("/anonymization/maps", get(list_anonymization_maps))
+        .route("/anonymization/maps/:id/deanonymize", post(deanonymize_text))
+        .route(
+            "/integration-simulation",
+            get(get_integration_simulation).put(update_integration_simulation),
+        )
+        .route(
+            "/document-storage",
+            get(get_document_storage_settings).put(update_document_storage_settings),
+        )
+        .with_state(db.clone())
+        .merge(create_settings_hierarchy_router(db))
+}
+
+/// Layered settings; resolved per caller, so every route needs auth
+fn create_settings_hierarchy_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/keys", get(list_setting_keys))
+        .route("/effective", get(get_effective_settings))
+        .route("/effective/:key", get(get_effective_setting))
+        .route("/overrides", get(list_setting_overrides))
+        .route("/overrides/:key", put(set_setting_override).delete(clear_setting_override))
+        .route("/changes", get(list_setting_changes))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
         .with_state(db)
 }
 
@@ -128,3 +241,282 @@ async fn update_settings(
         Err(e) => Err(ApiError::InternalError(e.to_string())),
     }
 }
+
+// =============================================================================
+// OVERCOMMIT POLICY LIBRARY
+// =============================================================================
+
+/// List overcommit policies
+async fn list_overcommit_policies(
+    State(db): State<Arc<Database>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = OvercommitPolicyService::new(db.as_ref().clone());
+    Ok(Json(service.list_policies().await?))
+}
+
+/// Get an overcommit policy
+async fn get_overcommit_policy(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = OvercommitPolicyService::new(db.as_ref().clone());
+    Ok(Json(service.get_policy(&id).await?))
+}
+
+/// Create an overcommit policy
+async fn create_overcommit_policy(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<CreateOvercommitPolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = OvercommitPolicyService::new(db.as_ref().clone());
+    let policy = service.create_policy(request).await?;
+    Ok((StatusCode::CREATED, Json(policy)))
+}
+
+/// Update an overcommit policy; every cluster using it takes the new ratios
+async fn update_overcommit_policy(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateOvercommitPolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = OvercommitPolicyService::new(db.as_ref().clone());
+    Ok(Json(service.update_policy(&id, request).await?))
+}
+
+/// Delete an overcommit policy no cluster uses
+async fn delete_overcommit_policy(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = OvercommitPolicyService::new(db.as_ref().clone());
+    service.delete_policy(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Preview how a policy change would alter capacity on the clusters using it
+async fn preview_overcommit_policy(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateOvercommitPolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = OvercommitPolicyService::new(db.as_ref().clone());
+    Ok(Json(service.preview_update(&id, &request).await?))
+}
+
+// =============================================================================
+// EXCHANGE RATES
+// =============================================================================
+
+/// Get the exchange rate table used for currency conversion
+async fn get_exchange_rates(State(db): State<Arc<Database>>) -> Result<impl IntoResponse, ApiError> {
+    let rates = CurrencyService::new(db.as_ref().clone()).get_rates().await?;
+    Ok(Json(rates))
+}
+
+/// Replace the exchange rates with manually entered ones
+async fn update_exchange_rates(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<UpdateExchangeRatesRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let rates = CurrencyService::new(db.as_ref().clone())
+        .set_manual_rates(request)
+        .await?;
+    Ok(Json(rates))
+}
+
+/// Load today's ECB reference rates
+async fn refresh_exchange_rates(State(db): State<Arc<Database>>) -> Result<impl IntoResponse, ApiError> {
+    let rates = CurrencyService::new(db.as_ref().clone())
+        .refresh_from_ecb()
+        .await?;
+    Ok(Json(rates))
+}
+
+// =============================================================================
+// ANONYMIZATION
+// =============================================================================
+
+fn anonymization_response(settings: AnonymizationSettings) -> AnonymizationSettingsResponse {
+    AnonymizationSettingsResponse {
+        preserve: settings.preserve,
+        map_dir: AnonymizationService::map_dir().display().to_string(),
+        updated_at: settings.updated_at,
+    }
+}
+
+/// Get the preserve-list applied to anonymized exports
+async fn get_anonymization_settings(State(db): State<Arc<Database>>) -> Result<impl IntoResponse, ApiError> {
+    let settings = AnonymizationService::new(db.as_ref().clone()).get_settings().await?;
+    Ok(Json(anonymization_response(settings)))
+}
+
+/// Replace the preserve-list applied to anonymized exports
+async fn update_anonymization_settings(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<UpdateAnonymizationSettingsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let settings = AnonymizationService::new(db.as_ref().clone())
+        .update_settings(request)
+        .await?;
+    Ok(Json(anonymization_response(settings)))
+}
+
+/// List the mapping files kept from anonymized exports
+async fn list_anonymization_maps(State(db): State<Arc<Database>>) -> Result<impl IntoResponse, ApiError> {
+    let maps = AnonymizationService::new(db.as_ref().clone()).list_maps()?;
+    Ok(Json(maps))
+}
+
+/// Translate text that refers to pseudonyms (e.g. vendor feedback) back to real names
+async fn deanonymize_text(
+    State(db): State<Arc<Database>>,
+    Path(map_id): Path<String>,
+    Json(request): Json<DeanonymizeRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let text = AnonymizationService::new(db.as_ref().clone()).deanonymize(&map_id, &request.text)?;
+    Ok(Json(DeanonymizeResponse { map_id, text }))
+}
+
+// =============================================================================
+// INTEGRATION SIMULATION
+// =============================================================================
+
+/// Which integration connections serve recorded fixtures instead of live data
+async fn get_integration_simulation(State(db): State<Arc<Database>>) -> Result<impl IntoResponse, ApiError> {
+    let settings = SimulationService::new(db).settings_response().await?;
+    Ok(Json(settings))
+}
+
+/// Turn simulation on or off for all connections, providers or single connections
+async fn update_integration_simulation(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<UpdateSimulationSettingsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let settings = SimulationService::new(db).update_settings(request).await?;
+    Ok(Json(settings))
+}
+
+// =============================================================================
+// DOCUMENT STORAGE
+// =============================================================================
+
+/// Where project documents are stored. The backend chosen here overrides
+/// `DOCUMENT_STORAGE_BACKEND`; bucket and directory still come from
+/// `DOCUMENT_STORAGE_*` / `DOCUMENT_S3_*`.
+async fn get_document_storage_settings(State(db): State<Arc<Database>>) -> Result<impl IntoResponse, ApiError> {
+    Ok(Json(document_storage_response(&db).await?))
+}
+
+/// Choose the backend new documents are written to
+async fn update_document_storage_settings(
+    State(db): State<Arc<Database>>,
+    Json(request): Json<UpdateDocumentStorageSettingsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    document_storage::save_backend_setting(&db, request.backend)
+        .await
+        .map_err(|e| match e {
+            document_storage::StorageError::Config(_) => ApiError::BadRequest(e.to_string()),
+            _ => ApiError::InternalError(e.to_string()),
+        })?;
+    Ok(Json(document_storage_response(&db).await?))
+}
+
+async fn document_storage_response(db: &Database) -> Result<DocumentStorageSettingsResponse, ApiError> {
+    let internal = |e: document_storage::StorageError| ApiError::InternalError(e.to_string());
+    let (backend, backend_source) = match document_storage::backend_setting(db).await.map_err(internal)? {
+        Some(settings) => (settings.backend, "settings"),
+        None => (document_storage::env_backend().map_err(internal)?, "environment"),
+    };
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    let (directory, bucket, endpoint) = match backend {
+        StorageBackend::Disk => (Some(document_storage::documents_dir().display().to_string()), None, None),
+        StorageBackend::S3 => (None, var("DOCUMENT_S3_BUCKET"), var("DOCUMENT_S3_ENDPOINT")),
+    };
+    Ok(DocumentStorageSettingsResponse {
+        backend,
+        backend_source: backend_source.to_string(),
+        directory,
+        bucket,
+        endpoint,
+        url_ttl_secs: document_storage::url_ttl().as_secs(),
+    })
+}
+
+// =============================================================================
+// SETTINGS HIERARCHY
+// =============================================================================
+
+/// Every layered key with its type, default and deepest scope
+async fn list_setting_keys() -> impl IntoResponse {
+    Json(SettingsService::definitions())
+}
+
+/// Effective value of every key for the caller, optionally within a project
+async fn get_effective_settings(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Query(query): Query<EffectiveSettingsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = SettingsService::new(db);
+    let context = service.context_for(&user, query.project_id.as_deref()).await?;
+    Ok(Json(service.resolve_all(&context).await?))
+}
+
+/// Effective value of one key, with the layer it came from and every layer consulted
+async fn get_effective_setting(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(key): Path<String>,
+    Query(query): Query<EffectiveSettingsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let key = SettingsService::parse_key(&key)?;
+    let service = SettingsService::new(db);
+    let context = service.context_for(&user, query.project_id.as_deref()).await?;
+    Ok(Json(service.resolve(key, &context).await?))
+}
+
+/// Overrides stored at one layer
+async fn list_setting_overrides(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Query(query): Query<SettingScopeQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let overrides = SettingsService::new(db)
+        .list_overrides(&user, query.scope, query.scope_id)
+        .await?;
+    Ok(Json(overrides))
+}
+
+/// Set a key at one layer
+async fn set_setting_override(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(key): Path<String>,
+    Json(request): Json<SetSettingOverrideRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let key = SettingsService::parse_key(&key)?;
+    Ok(Json(SettingsService::new(db).set_override(&user, key, request).await?))
+}
+
+/// Clear a key at one layer so the layer below applies again
+async fn clear_setting_override(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Path(key): Path<String>,
+    Query(query): Query<SettingScopeQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let key = SettingsService::parse_key(&key)?;
+    SettingsService::new(db)
+        .clear_override(&user, key, query.scope, query.scope_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Audit trail of override changes, newest first
+async fn list_setting_changes(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Query(query): Query<SettingChangeQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    Ok(Json(SettingsService::new(db).changes(&user, query).await?))
+}
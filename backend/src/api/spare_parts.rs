@@ -0,0 +1,179 @@
+// Archer ITSM - Hardware Pool Spare Parts API
+// Spare component stock, ticket-linked consumption and restocking
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    middleware::rbac::{
+        check_assets_create, check_assets_delete, check_assets_read, check_assets_update, check_tickets_update,
+    },
+    models::spare_part::{
+        ConsumeSparePartRequest, ConsumptionListQuery, CreateSparePartRequest, RestockSparePartRequest,
+        SparePartListQuery, UpdateSparePartRequest,
+    },
+    services::hardware_pool_service::parts_inventory::{PartsInventoryError, PartsInventoryService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Spare Parts API router
+pub fn create_spare_parts_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(PartsInventoryService::new(db));
+    let auth_state = AuthState::new();
+
+    let read_routes = Router::new()
+        .route("/", get(list_parts))
+        .route("/consumption", get(list_consumption))
+        .route("/:id", get(get_part))
+        .layer(middleware::from_fn(check_assets_read));
+
+    let create_routes = Router::new()
+        .route("/", post(create_part))
+        .layer(middleware::from_fn(check_assets_create));
+
+    let update_routes = Router::new()
+        .route("/:id", put(update_part))
+        .route("/:id/restock", post(restock_part))
+        .layer(middleware::from_fn(check_assets_update));
+
+    // Technicians take parts while working a ticket
+    let consume_routes = Router::new()
+        .route("/:id/consume", post(consume_part))
+        .layer(middleware::from_fn(check_tickets_update));
+
+    let delete_routes = Router::new()
+        .route("/:id", delete(delete_part))
+        .layer(middleware::from_fn(check_assets_delete));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(create_routes)
+        .merge(update_routes)
+        .merge(consume_routes)
+        .merge(delete_routes)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List stocked parts, optionally by category, location, fitting model or low stock
+async fn list_parts(
+    State(service): State<Arc<PartsInventoryService>>,
+    Query(query): Query<SparePartListQuery>,
+) -> impl IntoResponse {
+    match service.list_parts(&query).await {
+        Ok(parts) => (StatusCode::OK, Json(parts)).into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+/// Get a single part
+async fn get_part(
+    State(service): State<Arc<PartsInventoryService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.get_part(&id).await {
+        Ok(part) => (StatusCode::OK, Json(part)).into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+/// Start stocking a part at a location
+async fn create_part(
+    State(service): State<Arc<PartsInventoryService>>,
+    Json(request): Json<CreateSparePartRequest>,
+) -> impl IntoResponse {
+    match service.create_part(request).await {
+        Ok(part) => (StatusCode::CREATED, Json(part)).into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+/// Update part details and thresholds
+async fn update_part(
+    State(service): State<Arc<PartsInventoryService>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateSparePartRequest>,
+) -> impl IntoResponse {
+    match service.update_part(&id, request).await {
+        Ok(part) => (StatusCode::OK, Json(part)).into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+/// Stop stocking a part
+async fn delete_part(
+    State(service): State<Arc<PartsInventoryService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_part(&id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+/// Take parts out of stock against a ticket
+async fn consume_part(
+    State(service): State<Arc<PartsInventoryService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<ConsumeSparePartRequest>,
+) -> impl IntoResponse {
+    match service.consume(&user, &id, request).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+/// Add received stock
+async fn restock_part(
+    State(service): State<Arc<PartsInventoryService>>,
+    Path(id): Path<String>,
+    Json(request): Json<RestockSparePartRequest>,
+) -> impl IntoResponse {
+    match service.restock(&id, request).await {
+        Ok(part) => (StatusCode::OK, Json(part)).into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+/// Consumption ledger, by part or ticket
+async fn list_consumption(
+    State(service): State<Arc<PartsInventoryService>>,
+    Query(query): Query<ConsumptionListQuery>,
+) -> impl IntoResponse {
+    match service.list_consumption(&query).await {
+        Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+        Err(e) => parts_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert PartsInventoryError to HTTP response
+fn parts_error_response(error: PartsInventoryError) -> Response {
+    let (code, message) = match &error {
+        PartsInventoryError::NotFound => (codes::NOT_FOUND, "Spare part not found"),
+        PartsInventoryError::TicketNotFound => (codes::BAD_REQUEST, "Ticket not found"),
+        PartsInventoryError::InsufficientStock { .. } => (codes::CONFLICT, "Insufficient stock"),
+        PartsInventoryError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        PartsInventoryError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -0,0 +1,128 @@
+// Sustainability API Endpoints
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::models::sustainability::*;
+use crate::services::sustainability_service::SustainabilityService;
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::{codes, ErrorCode};
+
+pub fn create_sustainability_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/regions", get(list_regions))
+        .route("/projects/:id/settings", get(get_settings).put(update_settings))
+        .route("/projects/:id/report", get(get_report))
+        .with_state(db)
+}
+
+fn error_response(code: ErrorCode, e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    error_body(code, &e.to_string())
+}
+
+/// List built-in grid carbon intensities
+/// GET /api/v1/sustainability/regions
+async fn list_regions() -> impl IntoResponse {
+    let regions: Vec<_> = SustainabilityService::regions()
+        .into_iter()
+        .map(|(region, intensity)| json!({ "region": region, "carbon_intensity_g_per_kwh": intensity }))
+        .collect();
+
+    Json(json!({
+        "success": true,
+        "result": regions
+    }))
+}
+
+/// Get emissions model settings for a project
+/// GET /api/v1/sustainability/projects/:id/settings
+async fn get_settings(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Getting sustainability settings for project: {}", project_id);
+
+    let service = SustainabilityService::new(db.as_ref().clone());
+
+    match service.get_settings(&project_id).await {
+        Ok(settings) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": settings
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to get sustainability settings: {}", e);
+            Err(error_response(codes::INTERNAL_ERROR, e))
+        }
+    }
+}
+
+/// Update emissions model settings for a project
+/// PUT /api/v1/sustainability/projects/:id/settings
+async fn update_settings(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<UpdateSustainabilitySettingsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Updating sustainability settings for project: {}", project_id);
+
+    let service = SustainabilityService::new(db.as_ref().clone());
+
+    match service.update_settings(&project_id, request).await {
+        Ok(settings) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": settings
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to update sustainability settings: {}", e);
+            Err(error_response(codes::BAD_REQUEST, e))
+        }
+    }
+}
+
+/// Current vs proposed energy and emissions report
+/// GET /api/v1/sustainability/projects/:id/report?format=json|markdown
+async fn get_report(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Query(query): Query<SustainabilityReportQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Generating sustainability report for project: {}", project_id);
+
+    let service = SustainabilityService::new(db.as_ref().clone());
+
+    let report = service.generate_report(&project_id).await.map_err(|e| {
+        tracing::error!("Failed to generate sustainability report: {}", e);
+        error_response(codes::INTERNAL_ERROR, e)
+    })?;
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": report
+        })))
+            .into_response()),
+        "markdown" | "md" => Ok((
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/markdown; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"sustainability-{}.md\"", project_id),
+                ),
+            ],
+            SustainabilityService::render_markdown(&report),
+        )
+            .into_response()),
+        other => Err(error_response(
+            codes::BAD_REQUEST,
+            anyhow::anyhow!("Unsupported report format '{}'; use 'json' or 'markdown'", other),
+        )),
+    }
+}
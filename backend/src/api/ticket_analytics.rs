@@ -0,0 +1,144 @@
+// Archer ITSM - Ticket Analytics API
+// Dashboard aggregates over a date range: GET /api/v1/analytics/tickets/*
+// Query: from, to (RFC 3339), granularity (day|week|month), refresh
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_tickets_read,
+        response_cache::etag,
+    },
+    models::reporting::{TicketAnalytics, TicketAnalyticsQuery},
+    services::ticket_analytics_service::{AnalyticsError, TicketAnalyticsService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Ticket Analytics API router
+pub fn create_ticket_analytics_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(TicketAnalyticsService::new(db));
+    let auth_state = AuthState::new();
+
+    Router::new()
+        .route("/summary", get(get_summary))
+        .route("/volume", get(get_volume))
+        .route("/mttr", get(get_mttr))
+        .route("/first-response", get(get_first_response))
+        .route("/sla-breaches", get(get_sla_breaches))
+        .route("/backlog", get(get_backlog))
+        // Sections come from the service's aggregate cache, so repeat polls revalidate cheaply
+        .layer(middleware::from_fn(etag))
+        .layer(middleware::from_fn(check_tickets_read))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+/// Run the (cached) aggregation and return one section alongside the range it covers
+async fn section(
+    service: &TicketAnalyticsService,
+    user: &AuthenticatedUser,
+    query: &TicketAnalyticsQuery,
+    pick: impl FnOnce(&TicketAnalytics) -> Value,
+) -> Response {
+    match service.analytics(user.tenant_id.as_deref(), query).await {
+        Ok(analytics) => (
+            StatusCode::OK,
+            Json(json!({
+                "from": analytics.from,
+                "to": analytics.to,
+                "granularity": analytics.granularity,
+                "generated_at": analytics.generated_at,
+                "data": pick(&analytics),
+            })),
+        )
+            .into_response(),
+        Err(e) => analytics_error_response(e),
+    }
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// All ticket analytics in one payload
+async fn get_summary(
+    State(service): State<Arc<TicketAnalyticsService>>,
+    Query(query): Query<TicketAnalyticsQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.analytics(user.tenant_id.as_deref(), &query).await {
+        Ok(analytics) => (StatusCode::OK, Json(analytics.as_ref().clone())).into_response(),
+        Err(e) => analytics_error_response(e),
+    }
+}
+
+/// Created vs. resolved tickets per day/week/month
+async fn get_volume(
+    State(service): State<Arc<TicketAnalyticsService>>,
+    Query(query): Query<TicketAnalyticsQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    section(&service, &user, &query, |a| json!(a.volume)).await
+}
+
+/// Mean/median/p90 time to resolve, overall and by priority
+async fn get_mttr(
+    State(service): State<Arc<TicketAnalyticsService>>,
+    Query(query): Query<TicketAnalyticsQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    section(&service, &user, &query, |a| json!(a.mttr)).await
+}
+
+/// First-response SLA compliance
+async fn get_first_response(
+    State(service): State<Arc<TicketAnalyticsService>>,
+    Query(query): Query<TicketAnalyticsQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    section(&service, &user, &query, |a| json!(a.first_response)).await
+}
+
+/// Resolution SLA breach rates by priority and assignment group
+async fn get_sla_breaches(
+    State(service): State<Arc<TicketAnalyticsService>>,
+    Query(query): Query<TicketAnalyticsQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    section(&service, &user, &query, |a| json!(a.sla_breaches)).await
+}
+
+/// Open tickets at the end of the range, bucketed by age
+async fn get_backlog(
+    State(service): State<Arc<TicketAnalyticsService>>,
+    Query(query): Query<TicketAnalyticsQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    section(&service, &user, &query, |a| json!(a.backlog)).await
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert AnalyticsError to HTTP response
+fn analytics_error_response(error: AnalyticsError) -> Response {
+    let (code, message) = match &error {
+        AnalyticsError::Validation(_) => (codes::VALIDATION_ERROR, "Invalid date range"),
+        AnalyticsError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
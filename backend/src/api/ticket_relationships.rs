@@ -22,6 +22,7 @@ use crate::{
         rbac::{check_tickets_read, check_tickets_update},
     },
 };
+use crate::database::TracedQuery;
 
 /// Create Ticket Relationships API router with RBAC protection
 pub fn create_ticket_relationships_router(db: Arc<Database>) -> Router {
@@ -81,7 +82,7 @@ async fn list_relationships(
         ticket_thing
     );
 
-    match db.query(&query).await {
+    match db.query(&query).traced("ticket_relationships.list_relationships").await {
         Ok(mut response) => {
             let relationships: Vec<TicketRelationship> = response.take(0).unwrap_or_default();
             
@@ -170,7 +171,7 @@ async fn create_relationship(
         source_ticket_thing, target_ticket_thing, payload.relationship_type
     );
     
-    match db.query(&check_query).await {
+    match db.query(&check_query).traced("ticket_relationships.create_relationship").await {
         Ok(mut response) => {
             let existing: Vec<TicketRelationship> = response.take(0).unwrap_or_default();
             if !existing.is_empty() {
@@ -202,12 +203,12 @@ async fn create_relationship(
                 let _ = db.query(&format!(
                     "UPDATE {} SET parent_ticket_id = {}",
                     source_ticket_thing, target_ticket_thing
-                )).await;
+                )).traced("ticket_relationships.create_relationship").await;
             } else if matches!(payload.relationship_type, TicketRelationType::ParentOf) {
                 let _ = db.query(&format!(
                     "UPDATE {} SET parent_ticket_id = {}",
                     target_ticket_thing, source_ticket_thing
-                )).await;
+                )).traced("ticket_relationships.create_relationship").await;
             }
 
             // Create inverse relationship if symmetric or has inverse
@@ -277,12 +278,12 @@ async fn delete_relationship(
                         let _ = db.query(&format!(
                             "UPDATE {} SET parent_ticket_id = NONE",
                             rel.source_ticket_id
-                        )).await;
+                        )).traced("ticket_relationships.delete_relationship").await;
                     } else if matches!(rel.relationship_type, TicketRelationType::ParentOf) {
                         let _ = db.query(&format!(
                             "UPDATE {} SET parent_ticket_id = NONE",
                             rel.target_ticket_id
-                        )).await;
+                        )).traced("ticket_relationships.delete_relationship").await;
                     }
 
                     // Delete inverse relationship if it exists
@@ -291,7 +292,7 @@ async fn delete_relationship(
                             "DELETE FROM ticket_relationships WHERE source_ticket_id = {} AND target_ticket_id = {} AND relationship_type = '{:?}'",
                             rel.target_ticket_id, rel.source_ticket_id, inverse_type
                         );
-                        let _ = db.query(&inverse_query).await;
+                        let _ = db.query(&inverse_query).traced("ticket_relationships.delete_relationship").await;
                     }
 
                     (StatusCode::NO_CONTENT, ()).into_response()
@@ -320,7 +321,7 @@ async fn list_children(
         ticket_thing
     );
 
-    match db.query(&query).await {
+    match db.query(&query).traced("ticket_relationships.list_children").await {
         Ok(mut response) => {
             let relationships: Vec<TicketRelationship> = response.take(0).unwrap_or_default();
             
@@ -445,7 +446,7 @@ async fn mark_as_duplicate(
         user.user_id,
         user.username
     );
-    let _ = db.query(&comment_query).await;
+    let _ = db.query(&comment_query).traced("ticket_relationships.mark_as_duplicate").await;
 
     // Transfer watchers if requested
     if payload.transfer_watchers {
@@ -521,7 +522,7 @@ fn is_descendant<'a>(
             ancestor
         );
 
-        match db.query(&query).await {
+        match db.query(&query).traced("ticket_relationships.is_descendant").await {
             Ok(mut response) => {
                 let children: Vec<TicketRelationship> = response.take(0).unwrap_or_default();
                 for child in children {
@@ -553,7 +554,7 @@ fn is_ancestor<'a>(
             descendant
         );
 
-        match db.query(&query).await {
+        match db.query(&query).traced("ticket_relationships.is_ancestor").await {
             Ok(mut response) => {
                 let parents: Vec<TicketRelationship> = response.take(0).unwrap_or_default();
                 for parent in parents {
@@ -596,7 +597,7 @@ fn build_hierarchy_tree<'a>(
                 tid
             );
 
-            if let Ok(mut response) = db.query(&query).await {
+            if let Ok(mut response) = db.query(&query).traced("ticket_relationships.build_hierarchy_tree").await {
                 let child_rels: Vec<TicketRelationship> = response.take(0).unwrap_or_default();
                 for rel in child_rels {
                     if let Ok(Some(child_ticket)) = db.select::<Option<Ticket>>(rel.target_ticket_id.clone()).await {
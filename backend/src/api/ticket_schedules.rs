@@ -0,0 +1,200 @@
+// Archer ITSM - Recurring Ticket Schedules API
+// REST endpoints to manage schedules that create maintenance tickets from a
+// template, preview upcoming runs, and run or skip an occurrence by hand
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    middleware::rbac::{check_tickets_manage, check_tickets_read},
+    models::ticket_schedule::{
+        CreateTicketScheduleRequest, TicketScheduleListResponse, UpdateTicketScheduleRequest,
+    },
+    services::ticket_schedule_service::{TicketScheduleError, TicketScheduleService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Ticket Schedules API router
+pub fn create_ticket_schedules_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(TicketScheduleService::new(db));
+    let auth_state = AuthState::new();
+
+    let read_routes = Router::new()
+        .route("/", get(list_schedules))
+        .route("/:id", get(get_schedule))
+        .route("/:id/runs", get(list_runs))
+        .route("/:id/preview", get(preview_schedule))
+        .layer(middleware::from_fn(check_tickets_read));
+
+    let manage_routes = Router::new()
+        .route("/", post(create_schedule))
+        .route("/:id", put(update_schedule).delete(delete_schedule))
+        .route("/:id/run", post(run_schedule))
+        .route("/:id/skip", post(skip_next))
+        .layer(middleware::from_fn(check_tickets_manage));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(manage_routes)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// QUERY PARAMETERS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct PreviewQuery {
+    #[serde(default = "default_preview_count")]
+    count: usize,
+}
+
+fn default_preview_count() -> usize {
+    5
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List ticket schedules
+async fn list_schedules(
+    State(service): State<Arc<TicketScheduleService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.list_schedules(&user).await {
+        Ok(schedules) => {
+            let response = TicketScheduleListResponse {
+                total: schedules.len(),
+                schedules,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Get a single schedule
+async fn get_schedule(
+    State(service): State<Arc<TicketScheduleService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.get_schedule(&user, &id).await {
+        Ok(schedule) => (StatusCode::OK, Json(schedule)).into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Create a schedule
+async fn create_schedule(
+    State(service): State<Arc<TicketScheduleService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateTicketScheduleRequest>,
+) -> impl IntoResponse {
+    match service.create_schedule(&user, request).await {
+        Ok(schedule) => (StatusCode::CREATED, Json(schedule)).into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Update a schedule; the next run is recalculated
+async fn update_schedule(
+    State(service): State<Arc<TicketScheduleService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateTicketScheduleRequest>,
+) -> impl IntoResponse {
+    match service.update_schedule(&user, &id, request).await {
+        Ok(schedule) => (StatusCode::OK, Json(schedule)).into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Delete a schedule
+async fn delete_schedule(
+    State(service): State<Arc<TicketScheduleService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.delete_schedule(&user, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Run history, newest first
+async fn list_runs(
+    State(service): State<Arc<TicketScheduleService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.list_runs(&user, &id).await {
+        Ok(runs) => (StatusCode::OK, Json(runs)).into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Upcoming occurrences with weekend/holiday handling applied
+async fn preview_schedule(
+    State(service): State<Arc<TicketScheduleService>>,
+    Path(id): Path<String>,
+    Query(params): Query<PreviewQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.preview(&user, &id, params.count).await {
+        Ok(occurrences) => (StatusCode::OK, Json(occurrences)).into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Create the schedule's tickets now
+async fn run_schedule(
+    State(service): State<Arc<TicketScheduleService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.run_now(&user, &id).await {
+        Ok(run) => (StatusCode::OK, Json(run)).into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+/// Skip the next occurrence
+async fn skip_next(
+    State(service): State<Arc<TicketScheduleService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.skip_next(&user, &id).await {
+        Ok(schedule) => (StatusCode::OK, Json(schedule)).into_response(),
+        Err(e) => ticket_schedule_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert TicketScheduleError to HTTP response
+fn ticket_schedule_error_response(error: TicketScheduleError) -> Response {
+    let (code, message) = match &error {
+        TicketScheduleError::NotFound => (codes::NOT_FOUND, "Schedule not found"),
+        TicketScheduleError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        TicketScheduleError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
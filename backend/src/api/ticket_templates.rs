@@ -0,0 +1,290 @@
+// Archer ITSM - Ticket Templates & Macros API
+// REST endpoints to manage team templates and macros and to apply them on
+// ticket creation or to existing tickets
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    middleware::rbac::{check_tickets_create, check_tickets_read, check_tickets_update},
+    models::ticket_template::{
+        CreateTicketFromTemplateRequest, CreateTicketMacroRequest, CreateTicketTemplateRequest,
+        TicketMacroListResponse, TicketTemplateListResponse, UpdateTicketMacroRequest,
+        UpdateTicketTemplateRequest,
+    },
+    services::ticket_template_service::{TicketTemplateError, TicketTemplateService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Ticket Templates API router
+pub fn create_ticket_templates_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(TicketTemplateService::new(db));
+    let auth_state = AuthState::new();
+
+    let read_routes = Router::new()
+        .route("/", get(list_templates))
+        .route("/:id", get(get_template))
+        .layer(middleware::from_fn(check_tickets_read));
+
+    let create_routes = Router::new()
+        .route("/:id/tickets", post(create_ticket_from_template))
+        .layer(middleware::from_fn(check_tickets_create));
+
+    // Managing templates also needs team membership or tickets:manage,
+    // checked by the service
+    let update_routes = Router::new()
+        .route("/", post(create_template))
+        .route("/:id", put(update_template).delete(delete_template))
+        .route("/:id/apply/:ticket_id", post(apply_template))
+        .layer(middleware::from_fn(check_tickets_update));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(create_routes)
+        .merge(update_routes)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+/// Create Ticket Macros API router
+pub fn create_ticket_macros_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(TicketTemplateService::new(db));
+    let auth_state = AuthState::new();
+
+    let read_routes = Router::new()
+        .route("/", get(list_macros))
+        .route("/:id", get(get_macro))
+        .layer(middleware::from_fn(check_tickets_read));
+
+    let update_routes = Router::new()
+        .route("/", post(create_macro))
+        .route("/:id", put(update_macro).delete(delete_macro))
+        .route("/:id/apply/:ticket_id", post(apply_macro))
+        .layer(middleware::from_fn(check_tickets_update));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(update_routes)
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// QUERY PARAMETERS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    #[serde(default)]
+    include_inactive: bool,
+}
+
+// ============================================================================
+// TEMPLATE ENDPOINTS
+// ============================================================================
+
+/// List templates visible to the user (global + their teams)
+async fn list_templates(
+    State(service): State<Arc<TicketTemplateService>>,
+    Query(params): Query<ListQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.list_templates(&user, params.include_inactive).await {
+        Ok(templates) => {
+            let response = TicketTemplateListResponse {
+                total: templates.len(),
+                templates,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Get a single template
+async fn get_template(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.get_template(&user, &id).await {
+        Ok(template) => (StatusCode::OK, Json(template)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Create a template
+async fn create_template(
+    State(service): State<Arc<TicketTemplateService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateTicketTemplateRequest>,
+) -> impl IntoResponse {
+    match service.create_template(&user, request).await {
+        Ok(template) => (StatusCode::CREATED, Json(template)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Update a template
+async fn update_template(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateTicketTemplateRequest>,
+) -> impl IntoResponse {
+    match service.update_template(&user, &id, request).await {
+        Ok(template) => (StatusCode::OK, Json(template)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Delete a template
+async fn delete_template(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.delete_template(&user, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Create a ticket from a template
+async fn create_ticket_from_template(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateTicketFromTemplateRequest>,
+) -> impl IntoResponse {
+    match service.create_ticket_from_template(&user, &id, request).await {
+        Ok(ticket) => (StatusCode::CREATED, Json(ticket)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Apply a template to an existing ticket
+async fn apply_template(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path((id, ticket_id)): Path<(String, String)>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.apply_template(&user, &id, &ticket_id).await {
+        Ok(ticket) => (StatusCode::OK, Json(ticket)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+// ============================================================================
+// MACRO ENDPOINTS
+// ============================================================================
+
+/// List macros visible to the user (global + their teams)
+async fn list_macros(
+    State(service): State<Arc<TicketTemplateService>>,
+    Query(params): Query<ListQuery>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.list_macros(&user, params.include_inactive).await {
+        Ok(macros) => {
+            let response = TicketMacroListResponse {
+                total: macros.len(),
+                macros,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Get a single macro
+async fn get_macro(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.get_macro(&user, &id).await {
+        Ok(ticket_macro) => (StatusCode::OK, Json(ticket_macro)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Create a macro
+async fn create_macro(
+    State(service): State<Arc<TicketTemplateService>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<CreateTicketMacroRequest>,
+) -> impl IntoResponse {
+    match service.create_macro(&user, request).await {
+        Ok(ticket_macro) => (StatusCode::CREATED, Json(ticket_macro)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Update a macro
+async fn update_macro(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateTicketMacroRequest>,
+) -> impl IntoResponse {
+    match service.update_macro(&user, &id, request).await {
+        Ok(ticket_macro) => (StatusCode::OK, Json(ticket_macro)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Delete a macro
+async fn delete_macro(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.delete_macro(&user, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Apply a macro (field changes + comment) to a ticket
+async fn apply_macro(
+    State(service): State<Arc<TicketTemplateService>>,
+    Path((id, ticket_id)): Path<(String, String)>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match service.apply_macro(&user, &id, &ticket_id).await {
+        Ok(ticket) => (StatusCode::OK, Json(ticket)).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert TicketTemplateError to HTTP response
+pub(crate) fn ticket_template_error_response(error: TicketTemplateError) -> Response {
+    let (code, message) = match &error {
+        TicketTemplateError::TemplateNotFound => (codes::NOT_FOUND, "Template not found"),
+        TicketTemplateError::MacroNotFound => (codes::NOT_FOUND, "Macro not found"),
+        TicketTemplateError::TicketNotFound => (codes::NOT_FOUND, "Ticket not found"),
+        TicketTemplateError::TaskNotFound => (codes::NOT_FOUND, "Task not found"),
+        TicketTemplateError::Forbidden(_) => (codes::FORBIDDEN, "Forbidden"),
+        TicketTemplateError::Validation(_) => (codes::VALIDATION_ERROR, "Validation error"),
+        TicketTemplateError::TicketRejected(_) => (codes::VALIDATION_ERROR, "Ticket update rejected"),
+        TicketTemplateError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -13,16 +13,28 @@ use surrealdb::sql::Thing;
 use uuid::Uuid;
 
 use crate::{
+    api::ticket_templates::ticket_template_error_response,
     database::Database,
+    database::TracedQuery,
     models::ticket::{
         Ticket, CreateTicketRequest, UpdateTicketRequest, TicketStatus,
         TicketComment, CreateCommentRequest, CommentType, TicketAttachment,
+        HistoryChangeType, UpdateWatchPreferencesRequest,
     },
     models::knowledge::{LinkArticleToTicketRequest, KBLinkType},
+    models::ticket_template::UpdateTicketTaskRequest,
+    models::saved_view::{FilterEntity, FilterQueryRequest},
+    models::webhook::WebhookEventType,
+    services::filter_query::{self, FilterError},
     services::kb_suggestion_service::KBSuggestionService,
+    services::ticket_service::emit_ticket_event,
+    services::ticket_template_service::TicketTemplateService,
+    services::ticket_watch_service::{notify_watchers, TicketWatchError, TicketWatchService},
+    utils::pagination::{count_total, Pagination, SortOrder, Sortable},
     middleware::{
         auth::{require_auth, AuthState, AuthenticatedUser},
         rbac::{check_tickets_create, check_tickets_read, check_tickets_update, check_tickets_delete},
+        validation::{FieldErrors, Validate, ValidatedJson, ValidationRejection},
     },
 };
 use std::path::PathBuf;
@@ -36,10 +48,16 @@ pub fn create_tickets_router(db: Arc<Database>) -> Router {
     // Routes that require read permission
     let read_routes = Router::new()
         .route("/", get(list_tickets))
+        .route("/query", post(query_tickets))
         .route("/:id", get(get_ticket))
         .route("/:id/comments", get(list_comments))
         .route("/:id/attachments", get(list_attachments))
         .route("/:id/attachments/:attachment_id", get(download_attachment))
+        // Watching only needs read access
+        .route("/watching", get(list_watched_tickets))
+        .route("/watching/preferences", get(get_watch_preferences).put(update_watch_preferences))
+        .route("/:id/watch", post(watch_ticket).delete(unwatch_ticket))
+        .route("/:id/tasks", get(list_tasks))
         .layer(middleware::from_fn(check_tickets_read))
         .with_state(db.clone());
 
@@ -54,6 +72,7 @@ pub fn create_tickets_router(db: Arc<Database>) -> Router {
         .route("/:id", patch(update_ticket))
         .route("/:id/comments", post(add_comment))
         .route("/:id/attachments", post(upload_attachment))
+        .route("/:id/tasks/:task_id", patch(update_task))
         .layer(middleware::from_fn(check_tickets_update))
         .with_state(db.clone());
 
@@ -81,23 +100,66 @@ pub fn create_tickets_router(db: Arc<Database>) -> Router {
 async fn list_tickets(
     State(db): State<Arc<Database>>,
     axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    pagination: Pagination<TicketSort>,
 ) -> impl IntoResponse {
     // Log the query for audit purposes
     log_audit(&db, &user, "tickets", "list", None, true).await;
 
-    match db.select("ticket").await {
-        Ok(tickets) => {
-            let tickets: Vec<Ticket> = tickets;
-            (StatusCode::OK, Json(serde_json::json!({
-                "data": tickets,
-                "count": tickets.len(),
-                "user": user.username
-            }))).into_response()
-        },
+    let sql = format!(
+        "SELECT count() FROM ticket GROUP ALL; SELECT * FROM ticket {} {};",
+        pagination.order_clause(),
+        pagination.limit_clause()
+    );
+    let result = async {
+        let mut response = db.query(sql).traced("tickets.list").await?;
+        let count: Vec<serde_json::Value> = response.take(0)?;
+        let tickets: Vec<Ticket> = response.take(1)?;
+        Ok::<_, surrealdb::Error>(pagination.page(tickets, count_total(&count)))
+    };
+
+    match result.await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
     }
 }
 
+/// Sortable ticket list fields
+#[derive(Debug, Clone, Copy)]
+struct TicketSort;
+
+impl Sortable for TicketSort {
+    const SORT_FIELDS: &'static [&'static str] = &[
+        "title", "type", "priority", "status", "assignee", "created_at", "updated_at",
+        "sla_breach_at", "resolved_at",
+    ];
+    const DEFAULT_SORT: &'static str = "created_at";
+    const DEFAULT_ORDER: SortOrder = SortOrder::Desc;
+}
+
+/// Query tickets with a filter spec (field / operator / value, AND/OR groups)
+async fn query_tickets(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(request): Json<FilterQueryRequest>,
+) -> impl IntoResponse {
+    log_audit(&db, &user, "tickets", "query", None, true).await;
+
+    match filter_query::run_filter_query::<Ticket>(
+        &db,
+        FilterEntity::Tickets,
+        &request,
+        user.tenant_id.as_deref(),
+    )
+    .await
+    {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(FilterError::DatabaseError(e)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    }
+}
+
 async fn get_ticket(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
@@ -126,7 +188,7 @@ async fn get_ticket(
 async fn create_ticket(
     State(db): State<Arc<Database>>,
     axum::Extension(user): axum::Extension<AuthenticatedUser>,
-    Json(payload): Json<CreateTicketRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateTicketRequest>,
 ) -> impl IntoResponse {
     // Use authenticated user as creator (override payload if provided)
     let created_by = if payload.created_by.is_empty() {
@@ -189,6 +251,7 @@ async fn create_ticket(
                 Some(t) => {
                     let ticket_id = t.id.as_ref().map(|id| id.to_string());
                     log_audit(&db, &user, "tickets", "create", ticket_id.as_deref(), true).await;
+                    emit_ticket_event(&db, WebhookEventType::TicketCreated, &t, serde_json::json!({}));
                     (StatusCode::CREATED, Json(t)).into_response()
                 },
                 None => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": "Failed to create ticket" }))).into_response(),
@@ -202,7 +265,7 @@ async fn update_ticket(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
     axum::Extension(user): axum::Extension<AuthenticatedUser>,
-    Json(payload): Json<UpdateTicketRequest>,
+    ValidatedJson(payload): ValidatedJson<UpdateTicketRequest>,
 ) -> impl IntoResponse {
     let id_thing = match thing(&id) {
         Ok(t) => t,
@@ -216,6 +279,8 @@ async fn update_ticket(
     };
 
     if let Some(mut ticket) = existing {
+        let old_status = ticket.status.clone();
+        let before = ticket.clone();
         if let Some(title) = payload.title { ticket.title = title; }
         if let Some(desc) = payload.description { ticket.description = Some(desc); }
         if let Some(status) = payload.status { ticket.status = status; }
@@ -227,6 +292,29 @@ async fn update_ticket(
             Ok(updated) => {
                 let updated: Option<Ticket> = updated;
                 log_audit(&db, &user, "tickets", "update", Some(&id), true).await;
+                if let Some(ticket) = updated.as_ref() {
+                    let changed_fields = changed_fields(&before, ticket);
+                    if !changed_fields.is_empty() {
+                        emit_ticket_event(
+                            &db,
+                            WebhookEventType::TicketUpdated,
+                            ticket,
+                            serde_json::json!({ "changed_fields": changed_fields }),
+                        );
+                    }
+                    if ticket.status != old_status {
+                        emit_ticket_event(
+                            &db,
+                            WebhookEventType::TicketStatusChanged,
+                            ticket,
+                            serde_json::json!({ "previous_status": old_status }),
+                        );
+                    }
+                }
+                if let Some(ticket) = updated.clone().filter(|t| t.status != old_status) {
+                    let summary = format!("Status changed from {:?} to {:?}", old_status, ticket.status);
+                    notify_watchers(db.clone(), ticket, HistoryChangeType::StatusChange, summary, &user);
+                }
                 (StatusCode::OK, Json(updated)).into_response()
             },
             Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
@@ -236,6 +324,24 @@ async fn update_ticket(
     }
 }
 
+/// Fields other than the status that an update changed
+fn changed_fields(before: &Ticket, after: &Ticket) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    if before.title != after.title {
+        changed.push("title");
+    }
+    if before.description != after.description {
+        changed.push("description");
+    }
+    if before.priority != after.priority {
+        changed.push("priority");
+    }
+    if before.assignee != after.assignee {
+        changed.push("assignee");
+    }
+    changed
+}
+
 async fn delete_ticket(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
@@ -276,7 +382,7 @@ async fn list_comments(
         ticket_thing
     );
 
-    match db.query(&query).await {
+    match db.query(&query).traced("tickets.list_comments").await {
         Ok(mut response) => {
             let comments: Vec<TicketComment> = response.take(0).unwrap_or_default();
             log_audit(&db, &user, "ticket_comments", "list", Some(&id), true).await;
@@ -295,7 +401,7 @@ async fn add_comment(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
     axum::Extension(user): axum::Extension<AuthenticatedUser>,
-    Json(payload): Json<CreateCommentRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateCommentRequest>,
 ) -> impl IntoResponse {
     let ticket_thing = match thing(&id) {
         Ok(t) => t,
@@ -308,9 +414,9 @@ async fn add_comment(
         Err(_) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Ticket not found" }))).into_response(),
     };
 
-    if ticket_exists.is_none() {
+    let Some(ticket) = ticket_exists else {
         return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Ticket not found" }))).into_response();
-    }
+    };
 
     let now = Utc::now();
     let comment = TicketComment {
@@ -330,12 +436,14 @@ async fn add_comment(
         Ok(created) => {
             let created: Vec<TicketComment> = created;
             log_audit(&db, &user, "ticket_comments", "create", Some(&id), true).await;
+            let summary = format!("New comment: {}", excerpt(&comment.content, 200));
+            notify_watchers(db.clone(), ticket, HistoryChangeType::Comment, summary, &user);
             
             // Update the ticket's updated_at timestamp
             let _ = db.query(&format!(
                 "UPDATE {} SET updated_at = time::now()",
                 id
-            )).await;
+            )).traced("tickets.add_comment").await;
 
             (StatusCode::CREATED, Json(created.into_iter().next())).into_response()
         },
@@ -383,6 +491,200 @@ async fn delete_comment(
     }
 }
 
+// ============================================================================
+// CHECKLIST TASK HANDLERS
+// ============================================================================
+
+/// List a ticket's checklist tasks
+async fn list_tasks(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+) -> Response {
+    match TicketTemplateService::new(db).list_tasks(&id).await {
+        Ok(tasks) => (StatusCode::OK, Json(serde_json::json!({
+            "data": tasks,
+            "count": tasks.len(),
+            "ticket_id": id
+        }))).into_response(),
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+/// Tick or untick a checklist task
+async fn update_task(
+    State(db): State<Arc<Database>>,
+    Path((id, task_id)): Path<(String, String)>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(payload): Json<UpdateTicketTaskRequest>,
+) -> Response {
+    match TicketTemplateService::new(db.clone()).set_task_completed(&user, &id, &task_id, payload.completed).await {
+        Ok(task) => {
+            log_audit(&db, &user, "ticket_tasks", "update", Some(&task_id), true).await;
+            (StatusCode::OK, Json(task)).into_response()
+        }
+        Err(e) => ticket_template_error_response(e),
+    }
+}
+
+// ============================================================================
+// WATCH HANDLERS
+// ============================================================================
+
+/// Start watching a ticket as the current user
+async fn watch_ticket(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> Response {
+    let Ok(ticket_thing) = thing(&id) else {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": "Invalid ticket ID format" }))).into_response();
+    };
+    match TicketWatchService::new(db.clone()).watch(&ticket_thing, &user.user_id).await {
+        Ok(ticket) => {
+            log_audit(&db, &user, "tickets", "watch", Some(&id), true).await;
+            (StatusCode::OK, Json(ticket)).into_response()
+        }
+        Err(e) => watch_error(e),
+    }
+}
+
+/// Stop watching a ticket as the current user
+async fn unwatch_ticket(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> Response {
+    let Ok(ticket_thing) = thing(&id) else {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": "Invalid ticket ID format" }))).into_response();
+    };
+    match TicketWatchService::new(db.clone()).unwatch(&ticket_thing, &user.user_id).await {
+        Ok(ticket) => {
+            log_audit(&db, &user, "tickets", "unwatch", Some(&id), true).await;
+            (StatusCode::OK, Json(ticket)).into_response()
+        }
+        Err(e) => watch_error(e),
+    }
+}
+
+/// Tickets the current user watches
+async fn list_watched_tickets(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> Response {
+    match TicketWatchService::new(db).watched_tickets(&user.user_id).await {
+        Ok(tickets) => (StatusCode::OK, Json(serde_json::json!({
+            "data": tickets,
+            "count": tickets.len()
+        }))).into_response(),
+        Err(e) => watch_error(e),
+    }
+}
+
+async fn get_watch_preferences(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> Response {
+    match TicketWatchService::new(db).preferences(&user.user_id).await {
+        Ok(preferences) => (StatusCode::OK, Json(preferences)).into_response(),
+        Err(e) => watch_error(e),
+    }
+}
+
+async fn update_watch_preferences(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(payload): Json<UpdateWatchPreferencesRequest>,
+) -> Response {
+    match TicketWatchService::new(db).update_preferences(&user.user_id, payload).await {
+        Ok(preferences) => (StatusCode::OK, Json(preferences)).into_response(),
+        Err(e) => watch_error(e),
+    }
+}
+
+fn watch_error(e: TicketWatchError) -> Response {
+    let status = match e {
+        TicketWatchError::NotFound => StatusCode::NOT_FOUND,
+        TicketWatchError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(serde_json::json!({ "error": e.to_string() }))).into_response()
+}
+
+// ============================================================================
+// PAYLOAD VALIDATION
+// ============================================================================
+
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_TEXT_LENGTH: usize = 20_000;
+const MAX_NAME_LENGTH: usize = 255;
+
+impl Validate for CreateTicketRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("title", &self.title);
+        errors.length("title", &self.title, MAX_TITLE_LENGTH);
+        errors.optional_length("description", &self.description, MAX_TEXT_LENGTH);
+        for (field, value) in [
+            ("related_asset", &self.related_asset),
+            ("related_project", &self.related_project),
+            ("assignee", &self.assignee),
+            ("category", &self.category),
+            ("subcategory", &self.subcategory),
+            ("assigned_group", &self.assigned_group),
+            ("assignment_team_id", &self.assignment_team_id),
+        ] {
+            errors.not_blank(field, value);
+            errors.optional_length(field, value, MAX_NAME_LENGTH);
+        }
+        errors.length("created_by", &self.created_by, MAX_NAME_LENGTH);
+        errors.tags("tags", &self.tags);
+        for (i, watcher) in self.watchers.iter().enumerate() {
+            errors.required(&format!("watchers[{}]", i), watcher);
+        }
+        if let Some(custom_fields) = &self.custom_fields {
+            if !custom_fields.is_object() {
+                errors.add("custom_fields", "type", "custom_fields must be an object");
+            }
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateTicketRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.not_blank("title", &self.title);
+        errors.optional_length("title", &self.title, MAX_TITLE_LENGTH);
+        errors.optional_length("description", &self.description, MAX_TEXT_LENGTH);
+        for (field, value) in [
+            ("assignee", &self.assignee),
+            ("category", &self.category),
+            ("subcategory", &self.subcategory),
+            ("assigned_group", &self.assigned_group),
+            ("assignment_team_id", &self.assignment_team_id),
+        ] {
+            errors.optional_length(field, value, MAX_NAME_LENGTH);
+        }
+        if let Some(tags) = &self.tags {
+            errors.tags("tags", tags);
+        }
+        if let Some(custom_fields) = &self.custom_fields {
+            if !custom_fields.is_object() {
+                errors.add("custom_fields", "type", "custom_fields must be an object");
+            }
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateCommentRequest {
+    fn validate(&self) -> Result<(), ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        errors.required("content", &self.content);
+        errors.length("content", &self.content, MAX_TEXT_LENGTH);
+        errors.into_result()
+    }
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
@@ -406,6 +708,14 @@ fn comment_thing(id: &str) -> Result<Thing, surrealdb::Error> {
 }
 
 /// Log an audit entry for ticket operations
+/// First `max` characters of `text`, with an ellipsis when cut
+fn excerpt(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
 async fn log_audit(
     db: &Database,
     user: &AuthenticatedUser,
@@ -436,7 +746,7 @@ async fn log_audit(
     );
 
     // Fire and forget - don't fail the request if audit logging fails
-    let _ = db.query(&query).await;
+    let _ = db.query(&query).traced("tickets.log_audit").await;
 }
 
 // ============================================================================
@@ -611,7 +921,7 @@ async fn list_attachments(
         ticket_thing
     );
 
-    match db.query(&query).await {
+    match db.query(&query).traced("tickets.list_attachments").await {
         Ok(mut response) => {
             let attachments: Vec<TicketAttachment> = response.take(0).unwrap_or_default();
             log_audit(&db, &user, "ticket_attachments", "list", Some(&ticket_id), true).await;
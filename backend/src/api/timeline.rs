@@ -0,0 +1,434 @@
+// Migration Timeline & Wave Plan API Endpoints
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, patch, post},
+    Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::models::settings::AnonymizeOptions;
+use crate::models::timeline::*;
+use crate::services::anonymization_service::{AnonymizationService, MAP_ID_HEADER};
+use crate::services::cutover_readiness_service::CutoverReadinessService;
+use crate::services::rollback_plan_service::RollbackPlanService;
+use crate::services::timeline_service::TimelineService;
+use crate::utils::api_response::helpers::{error_body, error_body_with_details};
+use core_engine::error::codes;
+
+pub fn create_timeline_router(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/projects/:id/waves", get(list_waves))
+        .route("/projects/:id/waves", post(create_wave))
+        .route("/projects/:id/waves/generate", post(generate_waves))
+        .route("/projects/:id/readiness", get(get_project_readiness))
+        .route("/waves/:id", delete(delete_wave))
+        .route("/waves/:id/status", patch(update_wave_status))
+        .route("/waves/:id/runbook", get(get_wave_runbook))
+        .route("/waves/:id/backup-impact", get(get_wave_backup_impact))
+        .route("/waves/:id/log-sources", get(get_wave_log_sources))
+        .route("/waves/:id/readiness", get(get_wave_readiness))
+        .route("/waves/:id/rollback-plan", get(get_wave_rollback_plan))
+        .route("/waves/:id/rollback-plan/attach", post(attach_wave_rollback_plan))
+        .route("/projects/:id", get(get_timeline))
+        .route("/projects/:id/generate", post(generate_timeline))
+        .route("/projects/:id/export", get(export_timeline))
+        .with_state(db)
+}
+
+fn internal_error(e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    error_body(codes::INTERNAL_ERROR, &e.to_string())
+}
+
+/// List waves for a project
+/// GET /api/v1/timeline/projects/:id/waves
+async fn list_waves(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Listing migration waves for project: {}", project_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.list_waves(&project_id).await {
+        Ok(waves) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "waves": waves,
+                "total": waves.len()
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to list waves: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Create a wave manually
+/// POST /api/v1/timeline/projects/:id/waves
+async fn create_wave(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<CreateWaveRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Creating migration wave '{}' for project: {}", request.name, project_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.create_wave(&project_id, request).await {
+        Ok(wave) => Ok((StatusCode::CREATED, Json(json!({
+            "success": true,
+            "result": wave
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to create wave: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Generate waves from the project's VM placements
+/// POST /api/v1/timeline/projects/:id/waves/generate
+async fn generate_waves(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<GenerateWavesRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Generating migration waves for project: {}", project_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.generate_waves(&project_id, request).await {
+        Ok(waves) => Ok((StatusCode::CREATED, Json(json!({
+            "success": true,
+            "result": {
+                "waves": waves,
+                "total": waves.len()
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to generate waves: {}", e);
+            Err(error_body(codes::BAD_REQUEST, &e.to_string()))
+        }
+    }
+}
+
+/// Move a wave to a new status; starting it is refused while a target
+/// cluster's build checklist is open
+/// PATCH /api/v1/timeline/waves/:id/status
+async fn update_wave_status(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+    Json(request): Json<UpdateWaveStatusRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Updating status of migration wave: {}", wave_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.update_wave_status(&wave_id, request.status).await {
+        Ok(WaveTransition::Updated(wave)) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": wave
+        })))),
+        Ok(WaveTransition::Blocked(blockers)) => Err(error_body_with_details(
+            codes::CONFLICT,
+            "Destination cluster builds are not signed off",
+            json!({ "blockers": blockers }),
+        )),
+        Err(e) => {
+            tracing::error!("Failed to update wave status: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Delete a wave
+/// DELETE /api/v1/timeline/waves/:id
+async fn delete_wave(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Deleting migration wave: {}", wave_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.delete_wave(&wave_id).await {
+        Ok(()) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "message": "Wave deleted successfully"
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to delete wave: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Get the cutover runbook for a wave
+/// GET /api/v1/timeline/waves/:id/runbook?format=json|markdown
+async fn get_wave_runbook(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+    Query(query): Query<RunbookQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Building runbook for wave: {}", wave_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    let runbook = match service.wave_runbook(&wave_id).await {
+        Ok(runbook) => runbook,
+        Err(e) => {
+            tracing::error!("Failed to build wave runbook: {}", e);
+            return Err(internal_error(e));
+        }
+    };
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": runbook
+        }))).into_response()),
+        "markdown" | "md" => Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8".to_string())],
+            TimelineService::runbook_markdown(&runbook),
+        )
+            .into_response()),
+        other => Err(error_body(codes::BAD_REQUEST, &format!("Unsupported runbook format '{}'; use 'json' or 'markdown'", other))),
+    }
+}
+
+/// List the wave's VMs whose backup jobs must be reconfigured after the move
+/// GET /api/v1/timeline/waves/:id/backup-impact
+async fn get_wave_backup_impact(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.wave_backup_impact(&wave_id).await {
+        Ok(impact) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": impact
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to assess backup impact: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// List the Splunk log sources the wave pauses and the forwarders to re-point
+/// GET /api/v1/timeline/waves/:id/log-sources
+async fn get_wave_log_sources(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.wave_log_source_impact(&wave_id).await {
+        Ok(impact) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": impact
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to assess log source impact: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Red/amber/green cutover readiness scorecard of a wave
+/// GET /api/v1/timeline/waves/:id/readiness
+async fn get_wave_readiness(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = CutoverReadinessService::new(db.as_ref().clone());
+
+    match service.wave_readiness(&wave_id).await {
+        Ok(readiness) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": readiness
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to assess wave readiness: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Reverse checklist, decision criteria and decision deadline for a wave
+/// GET /api/v1/timeline/waves/:id/rollback-plan
+async fn get_wave_rollback_plan(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = RollbackPlanService::new(db.as_ref().clone());
+
+    match service.wave_rollback_plan(&wave_id).await {
+        Ok(plan) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": plan
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to build wave rollback plan: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Post the wave's rollback plan to its open change tickets
+/// POST /api/v1/timeline/waves/:id/rollback-plan/attach
+async fn attach_wave_rollback_plan(
+    State(db): State<Arc<Database>>,
+    Path(wave_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Attaching rollback plan of wave {} to its change tickets", wave_id);
+
+    let service = RollbackPlanService::new(db.as_ref().clone());
+
+    match service.attach_to_change_tickets(&wave_id).await {
+        Ok(attachment) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": attachment
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to attach wave rollback plan: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Readiness scorecards of every wave of a project
+/// GET /api/v1/timeline/projects/:id/readiness
+async fn get_project_readiness(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let service = CutoverReadinessService::new(db.as_ref().clone());
+
+    match service.project_readiness(&project_id).await {
+        Ok(waves) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": {
+                "waves": waves,
+                "total": waves.len()
+            }
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to assess project readiness: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Get the generated timeline for a project
+/// GET /api/v1/timeline/projects/:id
+async fn get_timeline(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Getting timeline for project: {}", project_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.get_timeline(&project_id).await {
+        Ok(Some(timeline)) => Ok((StatusCode::OK, Json(json!({
+            "success": true,
+            "result": timeline
+        })))),
+        Ok(None) => Err(error_body(codes::NOT_FOUND, "No timeline has been generated for this project")),
+        Err(e) => {
+            tracing::error!("Failed to get timeline: {}", e);
+            Err(internal_error(e))
+        }
+    }
+}
+
+/// Generate (or regenerate) the project timeline from its wave plan
+/// POST /api/v1/timeline/projects/:id/generate
+async fn generate_timeline(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Json(request): Json<GenerateTimelineRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!("Generating timeline for project: {}", project_id);
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    match service.generate_timeline(&project_id, request).await {
+        Ok(timeline) => Ok((StatusCode::CREATED, Json(json!({
+            "success": true,
+            "result": timeline
+        })))),
+        Err(e) => {
+            tracing::error!("Failed to generate timeline: {}", e);
+            Err(error_body(codes::BAD_REQUEST, &e.to_string()))
+        }
+    }
+}
+
+/// Export the project timeline as MS Project XML or CSV
+/// GET /api/v1/timeline/projects/:id/export?format=xml|csv[&anonymize=true]
+async fn export_timeline(
+    State(db): State<Arc<Database>>,
+    Path(project_id): Path<String>,
+    Query(query): Query<TimelineExportQuery>,
+    Query(options): Query<AnonymizeOptions>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let format_name = query.format.unwrap_or_else(|| "xml".to_string());
+    tracing::info!("Exporting timeline for project {} as {}", project_id, format_name);
+
+    let format = TimelineExportFormat::parse(&format_name).ok_or_else(|| {
+        error_body(codes::BAD_REQUEST, &format!("Unsupported export format '{}'; use 'xml' or 'csv'", format_name))
+    })?;
+
+    let service = TimelineService::new(db.as_ref().clone());
+
+    let timeline = match service.get_timeline(&project_id).await {
+        Ok(Some(timeline)) => timeline,
+        Ok(None) => {
+            return Err(error_body(codes::NOT_FOUND, "No timeline has been generated for this project"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to load timeline for export: {}", e);
+            return Err(internal_error(e));
+        }
+    };
+
+    let mut body = TimelineService::export(&timeline, format);
+    let mut map_id = None;
+    if options.anonymize {
+        let (masked, id) = AnonymizationService::new(db.as_ref().clone())
+            .anonymize_project_text(&options, &project_id, &body)
+            .await
+            .map_err(|e| internal_error(e.into()))?;
+        body = masked;
+        map_id = Some(id);
+    }
+    let filename = format!("migration-timeline-{}.{}", project_id, format.extension());
+
+    let mut response = (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response();
+    if let Some(value) = map_id.and_then(|id| header::HeaderValue::from_str(&id).ok()) {
+        response.headers_mut().insert(MAP_ID_HEADER, value);
+    }
+    Ok(response)
+}
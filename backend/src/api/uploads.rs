@@ -0,0 +1,266 @@
+//! Resumable Upload API
+//!
+//! Large RVTools exports and hardware basket pricing files are sent in
+//! chunks at explicit byte offsets (in the manner of tus). A client whose
+//! connection drops asks for the session's `Upload-Offset` and continues
+//! from there; completing the session verifies the checksum and hands the
+//! assembled file to the importer for its target.
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use core_engine::hardware_parser::basket_parser_new::HardwareBasketParser;
+use std::io::Write;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+
+use crate::{
+    api::rvtools::import_rvtools_file,
+    database::Database,
+    middleware::auth::{require_auth, AuthState, AuthenticatedUser},
+    models::upload_session::*,
+    models::usage_telemetry::UsageFeature,
+    services::upload_session_service::{
+        AssembledUpload, UploadLimits, UploadSessionError, UploadSessionService,
+    },
+    services::usage_telemetry_service,
+    utils::api_response::helpers::error_body,
+};
+use core_engine::error::codes;
+
+pub const UPLOAD_OFFSET: HeaderName = HeaderName::from_static("upload-offset");
+pub const UPLOAD_CHECKSUM: HeaderName = HeaderName::from_static("upload-checksum");
+
+#[derive(Debug)]
+enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    Conflict(String, Option<u64>),
+    UnprocessableEntity(String),
+    InternalError(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (code, message, offset) = match self {
+            ApiError::NotFound(msg) => (codes::NOT_FOUND, msg, None),
+            ApiError::BadRequest(msg) => (codes::BAD_REQUEST, msg, None),
+            ApiError::Conflict(msg, offset) => (codes::CONFLICT, msg, offset),
+            ApiError::UnprocessableEntity(msg) => (codes::VALIDATION_ERROR, msg, None),
+            ApiError::InternalError(msg) => (codes::INTERNAL_ERROR, msg, None),
+        };
+
+        let mut response = error_body(code, &message).into_response();
+        // Tell a client that lost track where to resume from
+        if let Some(offset) = offset {
+            response.headers_mut().insert(UPLOAD_OFFSET, HeaderValue::from(offset));
+        }
+        response
+    }
+}
+
+impl From<UploadSessionError> for ApiError {
+    fn from(error: UploadSessionError) -> Self {
+        match error {
+            UploadSessionError::NotFound => ApiError::NotFound(error.to_string()),
+            UploadSessionError::Validation(_) => ApiError::BadRequest(error.to_string()),
+            UploadSessionError::OffsetMismatch { expected, .. } => ApiError::Conflict(error.to_string(), Some(expected)),
+            UploadSessionError::Incomplete { received, .. } => ApiError::Conflict(error.to_string(), Some(received)),
+            UploadSessionError::AlreadyCompleted => ApiError::Conflict(error.to_string(), None),
+            UploadSessionError::ChecksumMismatch { .. } => ApiError::UnprocessableEntity(error.to_string()),
+            UploadSessionError::Storage(msg) | UploadSessionError::DatabaseError(msg) => {
+                ApiError::InternalError(msg)
+            }
+        }
+    }
+}
+
+pub fn create_uploads_router(db: Arc<Database>) -> Router {
+    let auth_state = AuthState::new();
+    // Leave headroom over the chunk limit, the session rejects oversized chunks itself
+    let chunk_limit = UploadLimits::from_env().max_chunk_bytes as usize + 64 * 1024;
+
+    Router::new()
+        .route("/", post(create_session))
+        .route(
+            "/:id",
+            get(get_session)
+                .patch(append_chunk)
+                .delete(abort_session)
+                .layer(DefaultBodyLimit::max(chunk_limit)),
+        )
+        .route("/:id/complete", post(complete_session))
+        .layer(middleware::from_fn_with_state(auth_state, require_auth))
+        .with_state(db)
+}
+
+fn offset_headers(offset: u64) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(UPLOAD_OFFSET, HeaderValue::from(offset));
+    headers
+}
+
+/// Start an upload; the client then sends chunks from offset 0
+async fn create_session(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateUploadSessionRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = UploadSessionService::new(db);
+    let session = service.create(&user, request).await?;
+    Ok((
+        StatusCode::CREATED,
+        offset_headers(0),
+        Json(service.response(&session)),
+    ))
+}
+
+/// Where the upload stands; a resuming client continues at `Upload-Offset`
+async fn get_session(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = UploadSessionService::new(db);
+    let session = service.get(&user, &id).await?;
+    Ok((
+        offset_headers(session.received_bytes),
+        Json(service.response(&session)),
+    ))
+}
+
+/// Append the request body at `Upload-Offset`. `Upload-Checksum: sha256
+/// <hex>` is verified before anything is written.
+async fn append_chunk(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    let offset = headers
+        .get(UPLOAD_OFFSET)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .ok_or_else(|| ApiError::BadRequest("Upload-Offset header is required".to_string()))?;
+
+    let checksum = match headers.get(UPLOAD_CHECKSUM) {
+        None => None,
+        Some(value) => {
+            let value = value.to_str().unwrap_or_default().trim();
+            match value.split_once(' ') {
+                Some((algorithm, sum)) if algorithm.eq_ignore_ascii_case("sha256") => Some(sum.trim().to_string()),
+                _ => {
+                    return Err(ApiError::BadRequest(
+                        "Upload-Checksum must be \"sha256 <hex>\"".to_string(),
+                    ))
+                }
+            }
+        }
+    };
+
+    let service = UploadSessionService::new(db);
+    let session = service
+        .append(&user, &id, offset, &body, checksum.as_deref())
+        .await?;
+    Ok((
+        StatusCode::NO_CONTENT,
+        offset_headers(session.received_bytes),
+    ))
+}
+
+async fn abort_session(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    UploadSessionService::new(db).abort(&user, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Verify the assembled file and import it. The response is what the
+/// target's single-request upload returns.
+async fn complete_session(
+    State(db): State<Arc<Database>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let upload = UploadSessionService::new(db.clone())
+        .complete(&user, &id)
+        .await?;
+
+    match upload.session.target {
+        UploadTarget::Rvtools => {
+            let project_id = upload
+                .session
+                .metadata
+                .get("project_id")
+                .filter(|id| !id.is_empty())
+                .map(|id| Thing::from(("project", id.as_str())));
+            Ok(import_rvtools_file(&db, upload.session.file_name, upload.bytes, project_id).await)
+        }
+        UploadTarget::HardwareBasket => parse_hardware_basket(upload).await,
+    }
+}
+
+/// Parse a basket workbook the way the desktop app's `parse_hardware_file`
+/// does, returning the models, configurations and prices found
+async fn parse_hardware_basket(upload: AssembledUpload) -> Result<Response, ApiError> {
+    usage_telemetry_service::record(UsageFeature::HardwareBasketParse);
+    let metadata = &upload.session.metadata;
+    let basket_id = Thing::from((
+        "hardware_basket",
+        metadata
+            .get("basket_id")
+            .cloned()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+            .as_str(),
+    ));
+    let vendor_id = Thing::from((
+        "hardware_vendor",
+        metadata
+            .get("vendor_id")
+            .cloned()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+            .as_str(),
+    ));
+    let file_name = upload.session.file_name;
+    let bytes = upload.bytes;
+
+    let parsed = tokio::task::spawn_blocking(move || {
+        // calamine reads from a path
+        let mut file = tempfile::Builder::new()
+            .suffix(".xlsx")
+            .tempfile()
+            .map_err(|e| e.to_string())?;
+        file.write_all(&bytes).map_err(|e| e.to_string())?;
+        let path = file.path().to_string_lossy().to_string();
+        HardwareBasketParser
+            .parse_file(&path, &basket_id, &vendor_id)
+            .map(|result| (basket_id, result))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| ApiError::InternalError(e.to_string()))?;
+
+    let (basket_id, (models, configurations, prices)) =
+        parsed.map_err(|e| ApiError::UnprocessableEntity(format!("Failed to parse {}: {}", file_name, e)))?;
+
+    Ok(Json(serde_json::json!({
+        "file_name": file_name,
+        "basket_id": basket_id.to_string(),
+        "models_count": models.len(),
+        "configurations_count": configurations.len(),
+        "prices_count": prices.len(),
+        "models": models,
+        "configurations": configurations,
+        "prices": prices,
+    }))
+    .into_response())
+}
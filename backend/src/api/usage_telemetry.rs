@@ -0,0 +1,100 @@
+// Archer - Usage Telemetry API
+// Admin opt-in for anonymized feature usage, the local viewer of everything
+// recorded, and manual submission or purge
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_admin,
+    },
+    models::usage_telemetry::UpdateTelemetryRequest,
+    services::usage_telemetry_service::{UsageTelemetryError, UsageTelemetryService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Usage Telemetry API router
+pub fn create_usage_telemetry_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(UsageTelemetryService::new(db));
+
+    Router::new()
+        .route("/", get(get_overview).put(update_telemetry))
+        .route("/submit", post(submit_now))
+        .route("/data", delete(purge_data))
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// Opt-in state, daily counts and the batches the next submission sends
+async fn get_overview(State(service): State<Arc<UsageTelemetryService>>) -> impl IntoResponse {
+    // Include counts recorded since the last scheduled flush
+    if let Err(e) = service.flush().await {
+        return telemetry_error_response(e);
+    }
+    match service.overview().await {
+        Ok(overview) => (StatusCode::OK, Json(overview)).into_response(),
+        Err(e) => telemetry_error_response(e),
+    }
+}
+
+/// Opt in or out
+async fn update_telemetry(
+    State(service): State<Arc<UsageTelemetryService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateTelemetryRequest>,
+) -> impl IntoResponse {
+    match service.set_enabled(request.enabled, &user.username).await {
+        Ok(overview) => (StatusCode::OK, Json(overview)).into_response(),
+        Err(e) => telemetry_error_response(e),
+    }
+}
+
+/// Submit finished days now instead of waiting for the nightly run
+async fn submit_now(State(service): State<Arc<UsageTelemetryService>>) -> impl IntoResponse {
+    match service.submit().await {
+        Ok(sent) => (StatusCode::OK, Json(json!({ "batches_sent": sent }))).into_response(),
+        Err(e) => telemetry_error_response(e),
+    }
+}
+
+/// Delete every local aggregate
+async fn purge_data(State(service): State<Arc<UsageTelemetryService>>) -> impl IntoResponse {
+    if let Err(e) = service.purge().await {
+        return telemetry_error_response(e);
+    }
+    match service.overview().await {
+        Ok(overview) => (StatusCode::OK, Json(overview)).into_response(),
+        Err(e) => telemetry_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert UsageTelemetryError to HTTP response
+fn telemetry_error_response(error: UsageTelemetryError) -> Response {
+    let (code, message) = match &error {
+        UsageTelemetryError::HardDisabled => (codes::PRECONDITION_FAILED, "Telemetry is switched off"),
+        UsageTelemetryError::Submission(_) => (codes::UPSTREAM_UNAVAILABLE, "Telemetry submission failed"),
+        UsageTelemetryError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
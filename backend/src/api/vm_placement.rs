@@ -5,6 +5,7 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
@@ -14,6 +15,10 @@ use std::sync::Arc;
 
 use crate::{
     database::Database,
+    middleware::auth::{require_auth, AuthState},
+    middleware::feature_flags::{require_feature, FeatureGate},
+    middleware::project_access::{require_project_role, ProjectAccess},
+    models::settings::FeatureFlag,
     services::vm_placement_service::{
         ClusterCapacityStatus, PlacementResult, PlacementStrategy, VMPlacementService,
         VMResourceRequirements,
@@ -21,10 +26,21 @@ use crate::{
 };
 
 pub fn create_vm_placement_router(db: Arc<Database>) -> Router {
+    // Optimizing changes a project's placements, so it needs an editor role.
+    // Re-optimization is auto-placement v2 and ships behind its flag.
+    let project_routes = Router::new()
+        .route("/optimize/:project_id", post(optimize_placements))
+        .route_layer(middleware::from_fn_with_state(ProjectAccess::new(db.clone()), require_project_role))
+        .route_layer(middleware::from_fn_with_state(
+            FeatureGate::new(db.clone(), FeatureFlag::AutoPlacementV2),
+            require_feature,
+        ));
+
     Router::new()
         .route("/calculate", post(calculate_placements))
         .route("/validate", post(validate_placement))
-        .route("/optimize/:project_id", post(optimize_placements))
+        .merge(project_routes)
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
         .with_state(db)
 }
 
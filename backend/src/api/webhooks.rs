@@ -0,0 +1,144 @@
+// Archer - Webhook API
+// Admin management of outbound webhook subscriptions, their delivery logs
+// and redelivery of dead letters
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::{
+    database::Database,
+    middleware::{
+        auth::{require_auth, AuthState, AuthenticatedUser},
+        rbac::check_admin,
+    },
+    models::webhook::{
+        CreateWebhookSubscriptionRequest, UpdateWebhookSubscriptionRequest, WebhookDeliveryQuery,
+    },
+    services::webhook_service::{WebhookError, WebhookService},
+    utils::api_response::helpers::error_body_with_details,
+};
+use core_engine::error::codes;
+
+/// Create Webhook API router
+pub fn create_webhooks_router(db: Arc<Database>) -> Router {
+    let service = Arc::new(WebhookService::new(db));
+
+    Router::new()
+        .route("/subscriptions", get(list_subscriptions).post(create_subscription))
+        .route(
+            "/subscriptions/:id",
+            get(get_subscription).put(update_subscription).delete(delete_subscription),
+        )
+        .route("/subscriptions/:id/deliveries", get(list_deliveries))
+        .route(
+            "/subscriptions/:id/deliveries/:delivery_id/redeliver",
+            post(redeliver),
+        )
+        .layer(middleware::from_fn(check_admin))
+        .layer(middleware::from_fn_with_state(AuthState::new(), require_auth))
+        .with_state(service)
+}
+
+// ============================================================================
+// ENDPOINTS
+// ============================================================================
+
+/// List every subscription
+async fn list_subscriptions(State(service): State<Arc<WebhookService>>) -> impl IntoResponse {
+    match service.list_subscriptions().await {
+        Ok(subscriptions) => (StatusCode::OK, Json(subscriptions)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+/// Subscribe a URL to event types; the response holds the signing secret
+async fn create_subscription(
+    State(service): State<Arc<WebhookService>>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateWebhookSubscriptionRequest>,
+) -> impl IntoResponse {
+    match service.create_subscription(request, &user.username).await {
+        Ok(subscription) => (StatusCode::CREATED, Json(subscription)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+async fn get_subscription(
+    State(service): State<Arc<WebhookService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.get_subscription(&id).await {
+        Ok(subscription) => (StatusCode::OK, Json(subscription)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+/// Change a subscription or rotate its secret
+async fn update_subscription(
+    State(service): State<Arc<WebhookService>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateWebhookSubscriptionRequest>,
+) -> impl IntoResponse {
+    match service.update_subscription(&id, request).await {
+        Ok(subscription) => (StatusCode::OK, Json(subscription)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+/// Remove a subscription and its delivery log
+async fn delete_subscription(
+    State(service): State<Arc<WebhookService>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_subscription(&id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "deleted": id }))).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+/// Delivery log, newest first; `?status=dead_lettered` lists the dead letters
+async fn list_deliveries(
+    State(service): State<Arc<WebhookService>>,
+    Path(id): Path<String>,
+    Query(query): Query<WebhookDeliveryQuery>,
+) -> impl IntoResponse {
+    match service.list_deliveries(&id, &query).await {
+        Ok(deliveries) => (StatusCode::OK, Json(deliveries)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+/// Queue a delivered or dead-lettered event again
+async fn redeliver(
+    State(service): State<Arc<WebhookService>>,
+    Path((id, delivery_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service.redeliver(&id, &delivery_id).await {
+        Ok(delivery) => (StatusCode::ACCEPTED, Json(delivery)).into_response(),
+        Err(e) => webhook_error_response(e),
+    }
+}
+
+// ============================================================================
+// ERROR HANDLING
+// ============================================================================
+
+/// Convert WebhookError to HTTP response
+fn webhook_error_response(error: WebhookError) -> Response {
+    let (code, message) = match &error {
+        WebhookError::NotFound => (codes::NOT_FOUND, "Webhook subscription not found"),
+        WebhookError::DeliveryNotFound => (codes::NOT_FOUND, "Webhook delivery not found"),
+        WebhookError::Validation(_) => (codes::VALIDATION_ERROR, "Invalid webhook subscription"),
+        WebhookError::Secret(_) => (codes::INTERNAL_ERROR, "Webhook secret could not be sealed"),
+        WebhookError::DatabaseError(_) => (codes::INTERNAL_ERROR, "Database error"),
+    };
+    error_body_with_details(code, message, error.to_string().into()).into_response()
+}
@@ -3,7 +3,7 @@
 
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware,
     response::IntoResponse,
     routing::{get, post, put, delete},
@@ -18,7 +18,7 @@ use crate::{
     middleware::{
         auth::{require_auth, AuthState, AuthenticatedUser},
     },
-    services::workflow_engine_service::WorkflowEngineService,
+    services::{approval_service::ApprovalService, workflow_engine_service::WorkflowEngineService},
 };
 
 /// Create Workflows API router with authentication
@@ -33,6 +33,13 @@ pub fn create_workflows_router(db: Arc<Database>) -> Router {
         .route("/:id", put(update_workflow))
         .route("/:id", delete(delete_workflow))
         .route("/:id/trigger", post(trigger_workflow))
+        // Versioning and import/export
+        .route("/import", post(import_workflow))
+        .route("/:id/export", get(export_workflow))
+        .route("/:id/versions", get(list_workflow_versions))
+        .route("/:id/versions", post(create_workflow_version))
+        .route("/:id/publish", post(publish_workflow))
+        .route("/:id/archive", post(archive_workflow))
         // Workflow instance endpoints
         .route("/instances", get(list_workflow_instances))
         .route("/instances/:id", get(get_workflow_instance))
@@ -41,6 +48,10 @@ pub fn create_workflows_router(db: Arc<Database>) -> Router {
         .route("/approvals/pending", get(get_pending_approvals))
         .route("/approvals/:id/approve", post(approve_approval))
         .route("/approvals/:id/reject", post(reject_approval))
+        // Approval delegation (vacation) rules
+        .route("/approvals/delegations", get(list_delegations))
+        .route("/approvals/delegations", post(create_delegation))
+        .route("/approvals/delegations/:id", delete(revoke_delegation))
         .layer(middleware::from_fn_with_state(auth_state, require_auth))
         .with_state(db)
 }
@@ -52,11 +63,11 @@ pub fn create_workflows_router(db: Arc<Database>) -> Router {
 /// List all workflow definitions
 async fn list_workflows(
     State(db): State<Arc<Database>>,
-    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
 ) -> impl IntoResponse {
     let service = WorkflowEngineService::new(db);
 
-    match service.list_workflows().await {
+    match service.list_workflows(user.tenant_id.as_deref()).await {
         Ok(workflows) => {
             let response = WorkflowListResponse {
                 workflows: workflows.clone(),
@@ -114,7 +125,7 @@ async fn create_workflow(
 ) -> impl IntoResponse {
     let service = WorkflowEngineService::new(db);
 
-    match service.create_workflow(req, user.username).await {
+    match service.create_workflow(req, user.username, user.tenant_id).await {
         Ok(workflow) => (StatusCode::CREATED, Json(workflow)).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -232,6 +243,189 @@ async fn trigger_workflow(
     }
 }
 
+// ============================================================================
+// VERSIONING AND IMPORT/EXPORT HANDLERS
+// ============================================================================
+
+/// List all versions of a workflow
+async fn list_workflow_versions(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let id_thing = match id.parse::<Thing>() {
+        Ok(t) => t,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid ID format" })),
+            )
+                .into_response()
+        }
+    };
+
+    let service = WorkflowEngineService::new(db);
+
+    match service.list_versions(id_thing).await {
+        Ok((workflow_key, versions)) => {
+            let response = WorkflowVersionListResponse {
+                workflow_key,
+                total: versions.len() as u64,
+                versions,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Create a new draft version from an existing version
+async fn create_workflow_version(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let id_thing = match id.parse::<Thing>() {
+        Ok(t) => t,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid ID format" })),
+            )
+                .into_response()
+        }
+    };
+
+    let service = WorkflowEngineService::new(db);
+
+    match service.create_version(id_thing, user.username).await {
+        Ok(workflow) => (StatusCode::CREATED, Json(workflow)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Publish a workflow version, archiving the previously published one
+async fn publish_workflow(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+    req: Option<Json<PublishWorkflowRequest>>,
+) -> impl IntoResponse {
+    let id_thing = match id.parse::<Thing>() {
+        Ok(t) => t,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid ID format" })),
+            )
+                .into_response()
+        }
+    };
+
+    let service = WorkflowEngineService::new(db);
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+
+    match service.publish_workflow(id_thing, req).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Archive a workflow version
+async fn archive_workflow(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let id_thing = match id.parse::<Thing>() {
+        Ok(t) => t,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid ID format" })),
+            )
+                .into_response()
+        }
+    };
+
+    let service = WorkflowEngineService::new(db);
+
+    match service.archive_workflow(id_thing).await {
+        Ok(workflow) => (StatusCode::OK, Json(workflow)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Export a workflow version as JSON
+async fn export_workflow(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let id_thing = match id.parse::<Thing>() {
+        Ok(t) => t,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid ID format" })),
+            )
+                .into_response()
+        }
+    };
+
+    let service = WorkflowEngineService::new(db);
+
+    match service.export_workflow(id_thing).await {
+        Ok(export) => {
+            let filename = format!(
+                "attachment; filename=\"workflow-{}-v{}.json\"",
+                export.workflow.workflow_key.as_deref().unwrap_or("export"),
+                export.workflow.version
+            );
+            (StatusCode::OK, [(header::CONTENT_DISPOSITION, filename)], Json(export)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Import an exported workflow into the caller's tenant as a draft
+async fn import_workflow(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(export): Json<WorkflowExport>,
+) -> impl IntoResponse {
+    let service = WorkflowEngineService::new(db);
+
+    match service.import_workflow(export, user.username, user.tenant_id).await {
+        Ok(workflow) => (StatusCode::CREATED, Json(workflow)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
 // ============================================================================
 // WORKFLOW INSTANCE HANDLERS
 // ============================================================================
@@ -353,7 +547,7 @@ async fn get_pending_approvals(
 async fn approve_approval(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
-    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
     Json(req): Json<ApprovalResponseRequest>,
 ) -> impl IntoResponse {
     let id_thing = match id.parse::<Thing>() {
@@ -369,7 +563,7 @@ async fn approve_approval(
 
     let service = WorkflowEngineService::new(db);
 
-    match service.approve_approval(id_thing, req.comments).await {
+    match service.approve_approval(id_thing, &user.user_id, req.comments).await {
         Ok(approval) => (StatusCode::OK, Json(approval)).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -383,7 +577,7 @@ async fn approve_approval(
 async fn reject_approval(
     State(db): State<Arc<Database>>,
     Path(id): Path<String>,
-    axum::Extension(_user): axum::Extension<AuthenticatedUser>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
     Json(req): Json<ApprovalResponseRequest>,
 ) -> impl IntoResponse {
     let id_thing = match id.parse::<Thing>() {
@@ -399,7 +593,7 @@ async fn reject_approval(
 
     let service = WorkflowEngineService::new(db);
 
-    match service.reject_approval(id_thing, req.comments).await {
+    match service.reject_approval(id_thing, &user.user_id, req.comments).await {
         Ok(approval) => (StatusCode::OK, Json(approval)).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -408,3 +602,84 @@ async fn reject_approval(
             .into_response(),
     }
 }
+
+// ============================================================================
+// DELEGATION HANDLERS
+// ============================================================================
+
+/// List delegation rules where the caller is delegator or delegate
+async fn list_delegations(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let service = ApprovalService::new(db);
+
+    match service.list_delegations(&user.user_id).await {
+        Ok(delegations) => {
+            let response = DelegationListResponse {
+                total: delegations.len() as u64,
+                delegations,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Delegate the caller's approvals to another user between two dates
+async fn create_delegation(
+    State(db): State<Arc<Database>>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+    Json(req): Json<CreateDelegationRequest>,
+) -> impl IntoResponse {
+    let service = ApprovalService::new(db);
+
+    match service.create_delegation(&user.user_id, req).await {
+        Ok((delegation, reassigned)) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "delegation": delegation,
+                "reassigned_approvals": reassigned
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
+
+/// Revoke one of the caller's delegation rules
+async fn revoke_delegation(
+    State(db): State<Arc<Database>>,
+    Path(id): Path<String>,
+    axum::Extension(user): axum::Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let id_thing = match id.parse::<Thing>() {
+        Ok(t) => t,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid ID format" })),
+            )
+                .into_response()
+        }
+    };
+
+    let service = ApprovalService::new(db);
+
+    match service.revoke_delegation(&user.user_id, id_thing).await {
+        Ok(delegation) => (StatusCode::OK, Json(delegation)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response(),
+    }
+}
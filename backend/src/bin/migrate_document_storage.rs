@@ -0,0 +1,87 @@
+//! Move existing project documents to another storage backend
+//!
+//! Run with the same environment as the backend (database and
+//! `DOCUMENT_S3_*` settings). With `--switch`, new documents are written to
+//! the target once every document was copied:
+//!
+//!     migrate_document_storage --to s3 [--dry-run] [--delete-source] [--switch]
+
+use backend::database::init_database;
+use backend::models::workflow::StorageBackend;
+use backend::services::document_storage::{migrate_documents, save_backend_setting, store_for};
+use std::env;
+use std::process;
+
+fn usage(program: &str) -> ! {
+    eprintln!("Usage: {} --to <disk|s3> [--dry-run] [--delete-source] [--switch]", program);
+    process::exit(1);
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut target = None;
+    let mut dry_run = false;
+    let mut delete_source = false;
+    let mut switch = false;
+
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--to" => {
+                target = match rest.next().map(String::as_str) {
+                    Some("disk") => Some(StorageBackend::Disk),
+                    Some("s3") => Some(StorageBackend::S3),
+                    _ => usage(&args[0]),
+                }
+            }
+            "--dry-run" => dry_run = true,
+            "--delete-source" => delete_source = true,
+            "--switch" => switch = true,
+            _ => usage(&args[0]),
+        }
+    }
+    let Some(target) = target else { usage(&args[0]) };
+
+    let store = match store_for(target) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Cannot open target storage: {}", e);
+            process::exit(1);
+        }
+    };
+    let db = match init_database().await {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Cannot connect to the database: {}", e);
+            process::exit(1);
+        }
+    };
+
+    match migrate_documents(&db, store.as_ref(), dry_run, delete_source).await {
+        Ok(report) => {
+            let verb = if dry_run { "Would migrate" } else { "Migrated" };
+            println!("{} {} document(s), {} already on {:?}", verb, report.migrated, report.skipped, target);
+            for id in &report.missing {
+                println!("  missing from source: {}", id);
+            }
+            for failure in &report.failed {
+                println!("  failed: {}", failure);
+            }
+            if !report.failed.is_empty() {
+                process::exit(2);
+            }
+            if switch && !dry_run {
+                if let Err(e) = save_backend_setting(&db, target).await {
+                    eprintln!("Migrated, but could not switch the storage backend: {}", e);
+                    process::exit(1);
+                }
+                println!("New documents are now written to {:?}", target);
+            }
+        }
+        Err(e) => {
+            eprintln!("Migration failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
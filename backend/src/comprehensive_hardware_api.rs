@@ -13,6 +13,7 @@ use core_engine::hardware_parser::basket_parser::{
     HardwareBasketParser, 
     ParsedHardwareBasket,
 };
+use crate::database::TracedQuery;
 
 /// Process Excel file with comprehensive parsing and database storage
 pub async fn process_comprehensive_excel_file(
@@ -240,6 +241,7 @@ async fn store_hardware_lot(
         .bind(("excel_sheet_name", &lot.excel_sheet_name))
         .bind(("excel_row_number", lot.excel_row_number))
         .bind(("import_id", import_id))
+        .traced("comprehensive_hardware_api.store_hardware_lot")
         .await?;
     
     Ok(lot_id)
@@ -295,6 +297,7 @@ async fn store_hardware_component(
         .bind(("excel_sheet_name", &component.excel_sheet_name))
         .bind(("excel_row_number", component.excel_row_number))
         .bind(("import_id", import_id))
+        .traced("comprehensive_hardware_api.store_hardware_component")
         .await?;
     
     Ok(component_id)
@@ -346,6 +349,7 @@ async fn store_hardware_option(
         .bind(("excel_sheet_name", &option.excel_sheet_name))
         .bind(("excel_row_number", option.excel_row_number))
         .bind(("import_id", import_id))
+        .traced("comprehensive_hardware_api.store_hardware_option")
         .await?;
     
     Ok(option_id)
@@ -387,6 +391,7 @@ async fn store_import_history(
         .bind(("items_processed", parsed_basket.total_items_processed as i64))
         .bind(("items_failed", parsed_basket.processing_errors.len() as i64))
         .bind(("error_log", error_log_json))
+        .traced("comprehensive_hardware_api.store_import_history")
         .await?;
     
     Ok(history_id)
@@ -404,6 +409,7 @@ pub async fn get_comprehensive_hardware_data(
     let lots: Vec<serde_json::Value> = db
         .query(lots_sql)
         .bind(("vendor", &vendor))
+        .traced("comprehensive_hardware_api.get_comprehensive_hardware_data")
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .take(0)
@@ -414,6 +420,7 @@ pub async fn get_comprehensive_hardware_data(
     let components: Vec<serde_json::Value> = db
         .query(components_sql)
         .bind(("vendor", &vendor))
+        .traced("comprehensive_hardware_api.get_comprehensive_hardware_data")
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .take(0)
@@ -424,6 +431,7 @@ pub async fn get_comprehensive_hardware_data(
     let options: Vec<serde_json::Value> = db
         .query(options_sql)
         .bind(("vendor", &vendor))
+        .traced("comprehensive_hardware_api.get_comprehensive_hardware_data")
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .take(0)
@@ -449,6 +457,7 @@ pub async fn get_import_history(
     let sql = "SELECT * FROM import_history ORDER BY imported_at DESC LIMIT 50";
     let history: Vec<serde_json::Value> = db
         .query(sql)
+        .traced("comprehensive_hardware_api.get_import_history")
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .take(0)
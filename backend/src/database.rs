@@ -1,26 +1,51 @@
 use crate::utils::api_response::{helpers, ApiResponse};
 use std::sync::Arc;
-use surrealdb::engine::local::{Db, Mem};
+use surrealdb::engine::any::connect;
+use surrealdb::opt::auth::Root;
 use surrealdb::Surreal;
 use tracing::{error, info, warn};
 
+pub mod locks;
 pub mod migrations;
 
+/// Engine behind [`Database`]: embedded (`mem://`) for a single process, or a
+/// shared server (`ws://`, `wss://`) when several replicas run side by side
+pub use surrealdb::engine::any::Any as Db;
+
 pub type Database = Surreal<Db>;
 pub type AppState = Arc<Database>;
 
 /// Database initialization configuration
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
+    /// `SURREALDB_URL`; replicas behind a load balancer must all point at
+    /// the same server
+    pub endpoint: String,
+    /// Root credentials for a remote server (`SURREALDB_USER`/`SURREALDB_PASS`)
+    pub credentials: Option<(String, String)>,
     pub namespace: String,
     pub database: String,
     pub enable_migrations: bool,
     pub connection_pool_size: usize,
 }
 
+impl DatabaseConfig {
+    /// Whether other processes can share this database; `mem://` is private
+    /// to the process
+    pub fn is_shared(&self) -> bool {
+        !self.endpoint.starts_with("mem://")
+    }
+}
+
 impl Default for DatabaseConfig {
     fn default() -> Self {
+        let credentials = match (std::env::var("SURREALDB_USER"), std::env::var("SURREALDB_PASS")) {
+            (Ok(user), Ok(pass)) => Some((user, pass)),
+            _ => None,
+        };
         Self {
+            endpoint: std::env::var("SURREALDB_URL").unwrap_or_else(|_| "mem://".to_string()),
+            credentials,
             namespace: "archer".to_string(),
             database: "main_db".to_string(),
             enable_migrations: true,
@@ -29,6 +54,12 @@ impl Default for DatabaseConfig {
     }
 }
 
+/// Lease held while running migrations; renewed while they run
+const MIGRATION_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How long a starting replica waits for another replica's migrations
+const MIGRATION_LOCK_WAIT: std::time::Duration = std::time::Duration::from_secs(600);
+
 /// Database initialization with comprehensive error handling
 pub async fn init_database() -> Result<AppState, DatabaseError> {
     let config = DatabaseConfig::default();
@@ -38,15 +69,21 @@ pub async fn init_database() -> Result<AppState, DatabaseError> {
 /// Initialize database with custom configuration
 pub async fn init_database_with_config(config: DatabaseConfig) -> Result<AppState, DatabaseError> {
     info!(
-        "🗄️  Initializing SurrealDB with namespace: {} database: {}",
-        config.namespace, config.database
+        "🗄️  Initializing SurrealDB at {} with namespace: {} database: {}",
+        config.endpoint, config.namespace, config.database
     );
 
     // Initialize SurrealDB connection
-    let db = Surreal::new::<Mem>(())
+    let db = connect(config.endpoint.as_str())
         .await
         .map_err(|e| DatabaseError::ConnectionFailed(e.to_string()))?;
 
+    if let Some((username, password)) = &config.credentials {
+        db.signin(Root { username, password })
+            .await
+            .map_err(|e| DatabaseError::ConnectionFailed(e.to_string()))?;
+    }
+
     // Set namespace and database
     db.use_ns(&config.namespace)
         .use_db(&config.database)
@@ -55,12 +92,22 @@ pub async fn init_database_with_config(config: DatabaseConfig) -> Result<AppStat
 
     info!("✅ Database connection established");
 
-    // Run migrations if enabled
+    // Run migrations if enabled; replicas starting together take turns so
+    // schema definitions and seed checks never interleave
     if config.enable_migrations {
-        if let Err(e) = run_all_migrations(&db).await {
-            warn!("Migration warnings occurred: {}", e);
-        } else {
-            info!("📊 All database migrations completed successfully");
+        match locks::acquire_waiting(&db, locks::MIGRATIONS_LOCK, MIGRATION_LOCK_TTL, MIGRATION_LOCK_WAIT).await {
+            Ok(lease) => {
+                if let Err(e) = run_all_migrations(&db).await {
+                    warn!("Migration warnings occurred: {}", e);
+                } else {
+                    info!("📊 All database migrations completed successfully");
+                }
+                lease.release().await;
+            }
+            Err(e) => {
+                error!("Could not obtain the migration lock: {}", e);
+                return Err(DatabaseError::MigrationFailed(e.to_string()));
+            }
         }
     }
 
@@ -77,7 +124,7 @@ pub async fn init_database_with_config(config: DatabaseConfig) -> Result<AppStat
 /// Create a test database instance (for testing only)
 #[cfg(any(test, feature = "test-utils"))]
 pub async fn new_test() -> Result<Database, DatabaseError> {
-    let db = Surreal::new::<Mem>(())
+    let db = connect("mem://")
         .await
         .map_err(|e| DatabaseError::ConnectionFailed(e.to_string()))?;
 
@@ -121,6 +168,27 @@ async fn run_all_migrations(db: &Database) -> Result<(), DatabaseError> {
         info!("✅ CMDB migrations completed");
     }
 
+    // Vendor contracts covering CIs
+    if let Err(e) = migrations::ContractMigrations::run_all(db).await {
+        warn!("Contract migrations failed: {}", e);
+    } else {
+        info!("✅ Contract migrations completed");
+    }
+
+    // Hardware pool spare parts inventory
+    if let Err(e) = migrations::SparePartMigrations::run_all(db).await {
+        warn!("Spare parts migrations failed: {}", e);
+    } else {
+        info!("✅ Spare parts migrations completed");
+    }
+
+    // BOM purchase requests
+    if let Err(e) = migrations::ProcurementMigrations::run_all(db).await {
+        warn!("Procurement migrations failed: {}", e);
+    } else {
+        info!("✅ Procurement migrations completed");
+    }
+
     // Service Catalog migrations (Phase 5)
     if let Err(e) = migrations::ServiceCatalogMigrations::run_all(db).await {
         warn!("Service Catalog migrations failed: {}", e);
@@ -163,6 +231,90 @@ async fn run_all_migrations(db: &Database) -> Result<(), DatabaseError> {
         info!("✅ Workflow Engine migrations completed");
     }
 
+    // Saved ticket/CI views
+    if let Err(e) = migrations::SavedViewMigrations::run_all(db).await {
+        warn!("Saved view migrations failed: {}", e);
+    } else {
+        info!("✅ Saved view migrations completed");
+    }
+
+    // Ticket templates, macros & checklist tasks
+    if let Err(e) = migrations::TicketTemplateMigrations::run_all(db).await {
+        warn!("Ticket template migrations failed: {}", e);
+    } else {
+        info!("✅ Ticket template migrations completed");
+    }
+
+    // Recurring ticket schedules
+    if let Err(e) = migrations::TicketScheduleMigrations::run_all(db).await {
+        warn!("Ticket schedule migrations failed: {}", e);
+    } else {
+        info!("✅ Ticket schedule migrations completed");
+    }
+
+    // Scheduled reports
+    if let Err(e) = migrations::ReportScheduleMigrations::run_all(db).await {
+        warn!("Report schedule migrations failed: {}", e);
+    } else {
+        info!("✅ Report schedule migrations completed");
+    }
+
+    // Capacity overview history
+    if let Err(e) = migrations::CapacitySnapshotMigrations::run_all(db).await {
+        warn!("Capacity snapshot migrations failed: {}", e);
+    } else {
+        info!("✅ Capacity snapshot migrations completed");
+    }
+
+    // LLM providers, tenant model selection & usage
+    if let Err(e) = migrations::AiMigrations::run_all(db).await {
+        warn!("AI provider migrations failed: {}", e);
+    } else {
+        info!("✅ AI provider migrations completed");
+    }
+
+    // Integration hub connections, sync runs & discovered asset ledger
+    if let Err(e) = migrations::IntegrationMigrations::run_all(db).await {
+        warn!("Integration hub migrations failed: {}", e);
+    } else {
+        info!("✅ Integration hub migrations completed");
+    }
+
+    // Version history of generated documents
+    if let Err(e) = migrations::DocumentVersionMigrations::run_all(db).await {
+        warn!("Generated document version migrations failed: {}", e);
+    } else {
+        info!("✅ Generated document version migrations completed");
+    }
+
+    // Review and sign-off of generated documents
+    if let Err(e) = migrations::DocumentReviewMigrations::run_all(db).await {
+        warn!("Document review migrations failed: {}", e);
+    } else {
+        info!("✅ Document review migrations completed");
+    }
+
+    // Per-project membership and invitations
+    if let Err(e) = migrations::ProjectAccessMigrations::run_all(db).await {
+        warn!("Project access migrations failed: {}", e);
+    } else {
+        info!("✅ Project access migrations completed");
+    }
+
+    // Layered setting overrides and their change history
+    if let Err(e) = migrations::SettingsMigrations::run_all(db).await {
+        warn!("Settings migrations failed: {}", e);
+    } else {
+        info!("✅ Settings migrations completed");
+    }
+
+    // Workspace search indexes (after all searchable tables exist)
+    if let Err(e) = migrations::SearchMigrations::run_all(db).await {
+        warn!("Search index migrations failed: {}", e);
+    } else {
+        info!("✅ Search index migrations completed");
+    }
+
     // Seed demo data (only if database is empty)
     let seed_enabled = std::env::var("SEED_DEMO_DATA")
         .map(|v| v == "true" || v == "1")
@@ -203,6 +355,7 @@ async fn run_hardware_basket_migrations(db: &Database) -> Result<(), DatabaseErr
     "#;
 
     db.query(basket_schema)
+        .traced("database.run_hardware_basket_migrations")
         .await
         .map_err(|e| DatabaseError::MigrationFailed(format!("Hardware basket schema: {}", e)))?;
 
@@ -224,6 +377,7 @@ async fn run_hardware_basket_migrations(db: &Database) -> Result<(), DatabaseErr
     "#;
 
     db.query(model_schema)
+        .traced("database.run_hardware_basket_migrations")
         .await
         .map_err(|e| DatabaseError::MigrationFailed(format!("Hardware model schema: {}", e)))?;
 
@@ -243,9 +397,34 @@ async fn run_hardware_basket_migrations(db: &Database) -> Result<(), DatabaseErr
     "#;
 
     db.query(pricing_schema)
+        .traced("database.run_hardware_basket_migrations")
         .await
         .map_err(|e| DatabaseError::MigrationFailed(format!("Hardware pricing schema: {}", e)))?;
 
+    // Classifier review queue and per-tenant corrections
+    let classification_schema = r#"
+        DEFINE TABLE component_review_item SCHEMALESS;
+        DEFINE FIELD part_number ON TABLE component_review_item TYPE string;
+        DEFINE FIELD description ON TABLE component_review_item TYPE string;
+        DEFINE FIELD status ON TABLE component_review_item TYPE string;
+        DEFINE FIELD created_at ON TABLE component_review_item TYPE datetime;
+
+        DEFINE INDEX component_review_status_idx ON TABLE component_review_item COLUMNS tenant_id, status;
+
+        DEFINE TABLE classifier_correction SCHEMALESS;
+        DEFINE FIELD part_number ON TABLE classifier_correction TYPE string;
+        DEFINE FIELD primary_category ON TABLE classifier_correction TYPE string;
+        DEFINE FIELD corrected_by ON TABLE classifier_correction TYPE string;
+        DEFINE FIELD created_at ON TABLE classifier_correction TYPE datetime;
+
+        DEFINE INDEX classifier_correction_tenant_idx ON TABLE classifier_correction COLUMNS tenant_id;
+    "#;
+
+    db.query(classification_schema)
+        .traced("database.run_hardware_basket_migrations")
+        .await
+        .map_err(|e| DatabaseError::MigrationFailed(format!("Component classification schema: {}", e)))?;
+
     Ok(())
 }
 
@@ -269,6 +448,7 @@ async fn run_project_management_migrations(db: &Database) -> Result<(), Database
     "#;
 
     db.query(project_schema)
+        .traced("database.run_project_management_migrations")
         .await
         .map_err(|e| DatabaseError::MigrationFailed(format!("Project schema: {}", e)))?;
 
@@ -282,6 +462,7 @@ async fn test_database_connection(db: &Database) -> Result<(), DatabaseError> {
     // Test basic query
     let _result = db
         .query("SELECT * FROM hardware_basket LIMIT 1")
+        .traced("database.test_database_connection")
         .await
         .map_err(|e| DatabaseError::ConnectionTestFailed(e.to_string()))?;
 
@@ -368,6 +549,8 @@ mod tests {
     #[tokio::test]
     async fn test_database_init() {
         let config = DatabaseConfig {
+            endpoint: "mem://".to_string(),
+            credentials: None,
             namespace: "test_ns".to_string(),
             database: "test_db".to_string(),
             enable_migrations: false,
@@ -388,3 +571,60 @@ mod tests {
         assert_eq!(thing.tb, recreated.tb);
     }
 }
+
+/// Client span for one SurrealDB round trip. Only the operation name is
+/// recorded; statements and bound values stay out of exported traces.
+pub fn query_span(operation: &'static str) -> tracing::Span {
+    tracing::info_span!(
+        "surrealdb.query",
+        otel.name = operation,
+        otel.kind = "client",
+        db.system = "surrealdb",
+        db.operation = operation,
+    )
+}
+
+/// `.traced("cmdb.search_cis")` on a query or any other awaitable wraps it in
+/// a [`query_span`]
+pub trait TracedQuery: std::future::IntoFuture + Sized {
+    fn traced(self, operation: &'static str) -> tracing::instrument::Instrumented<Self::IntoFuture> {
+        tracing::Instrument::instrument(self.into_future(), query_span(operation))
+    }
+}
+
+impl<F: std::future::IntoFuture> TracedQuery for F {}
+
+/// Counts [`query_span`]s, one per traced SurrealDB round trip, on the
+/// current thread while installed. Used by tests and benchmarks that pin
+/// down how many queries a code path makes.
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Debug, Clone, Default)]
+pub struct RoundTripCounter(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+#[cfg(any(test, feature = "test-utils"))]
+impl RoundTripCounter {
+    /// Count round trips on this thread until the guard is dropped
+    pub fn install(&self) -> tracing::subscriber::DefaultGuard {
+        use tracing_subscriber::layer::SubscriberExt;
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(self.clone()))
+    }
+
+    /// Round trips counted since the last call
+    pub fn take(&self) -> usize {
+        self.0.swap(0, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for RoundTripCounter {
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        _id: &tracing::span::Id,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if attrs.metadata().name() == "surrealdb.query" {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Job leases shared between backend replicas
+//!
+//! Schedulers, migrations and the approval escalation poller must run on one
+//! replica at a time. A lease is a `job_lock` record keyed by job name that
+//! names its holder and expires at a database-side timestamp, so a replica
+//! that dies mid-job frees the job once the TTL lapses. Acquisition is a
+//! single conditional `UPDATE`, which SurrealDB applies atomically per record.
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use super::Database;
+use crate::database::TracedQuery;
+
+pub const MIGRATIONS_LOCK: &str = "migrations";
+pub const TIERING_ARCHIVAL_LOCK: &str = "tiering_archival";
+pub const REPORT_SCHEDULER_LOCK: &str = "report_scheduler";
+pub const WORKFLOW_TIMERS_LOCK: &str = "workflow_timers";
+pub const INTEGRATION_SYNC_LOCK: &str = "integration_sync";
+pub const WATCH_DIGEST_LOCK: &str = "watch_digest";
+pub const TICKET_SCHEDULER_LOCK: &str = "ticket_scheduler";
+pub const CONTRACT_ALERTS_LOCK: &str = "contract_alerts";
+pub const UPLOAD_CLEANUP_LOCK: &str = "upload_cleanup";
+pub const WEBHOOK_DISPATCH_LOCK: &str = "webhook_dispatch";
+pub const CAPACITY_ALERTS_LOCK: &str = "capacity_alerts";
+
+/// Poll interval while waiting for a lease held by another replica
+const WAIT_POLL: Duration = Duration::from_secs(1);
+
+/// Identifies this process as a lease holder: `ARCHER_INSTANCE_ID` when set
+/// (e.g. the pod name), otherwise the host name plus a random suffix
+static INSTANCE_ID: Lazy<String> = Lazy::new(|| {
+    std::env::var("ARCHER_INSTANCE_ID").unwrap_or_else(|_| {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "archer".to_string());
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        format!("{}-{}", host, &suffix[..8])
+    })
+});
+
+pub fn instance_id() -> &'static str {
+    &INSTANCE_ID
+}
+
+#[derive(Debug, Deserialize)]
+struct LockRow {
+    holder: String,
+}
+
+/// TTL as a SurrealQL duration literal, cast in the query with `<duration>`
+fn ttl_literal(ttl: Duration) -> String {
+    format!("{}s", ttl.as_secs().max(1))
+}
+
+/// Take or renew the lease on `name`. Returns `false` while another replica
+/// holds an unexpired lease.
+pub async fn try_acquire(db: &Database, name: &str, ttl: Duration) -> Result<bool> {
+    let mut response = db
+        .query(
+            "UPDATE type::thing('job_lock', $name) SET \
+                name = $name, \
+                holder = $holder, \
+                acquired_at = IF holder = $holder THEN acquired_at ELSE time::now() END, \
+                expires_at = time::now() + <duration> $ttl \
+             WHERE holder = NONE OR holder = $holder OR expires_at < time::now()",
+        )
+        .bind(("name", name.to_string()))
+        .bind(("holder", instance_id().to_string()))
+        .bind(("ttl", ttl_literal(ttl)))
+        .traced("locks.try_acquire")
+        .await
+        .map_err(|e| anyhow!("Failed to acquire lock {}: {}", name, e))?;
+    let rows: Vec<LockRow> = response
+        .take(0)
+        .map_err(|e| anyhow!("Failed to acquire lock {}: {}", name, e))?;
+    Ok(rows.iter().any(|row| row.holder == instance_id()))
+}
+
+/// Give up the lease on `name` if this replica holds it
+pub async fn release(db: &Database, name: &str) -> Result<()> {
+    db.query("DELETE type::thing('job_lock', $name) WHERE holder = $holder")
+        .bind(("name", name.to_string()))
+        .bind(("holder", instance_id().to_string()))
+        .traced("locks.release")
+        .await
+        .map_err(|e| anyhow!("Failed to release lock {}: {}", name, e))?;
+    Ok(())
+}
+
+/// A held lease, renewed in the background at a third of its TTL until
+/// released or dropped
+pub struct Lease {
+    db: Database,
+    name: String,
+    heartbeat: tokio::task::JoinHandle<()>,
+}
+
+impl Lease {
+    fn start(db: &Database, name: &str, ttl: Duration) -> Self {
+        let heartbeat = {
+            let db = db.clone();
+            let name = name.to_string();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(ttl / 3);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    match try_acquire(&db, &name, ttl).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            warn!("Lost lock {} to another replica", name);
+                            break;
+                        }
+                        Err(e) => warn!("Failed to renew lock {}: {}", name, e),
+                    }
+                }
+            })
+        };
+        Self {
+            db: db.clone(),
+            name: name.to_string(),
+            heartbeat,
+        }
+    }
+
+    pub async fn release(self) {
+        self.heartbeat.abort();
+        if let Err(e) = release(&self.db, &self.name).await {
+            // The lease still expires on its own after the TTL
+            warn!("{}", e);
+        }
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.heartbeat.abort();
+    }
+}
+
+/// Wait up to `timeout` for the lease on `name`
+pub async fn acquire_waiting(db: &Database, name: &str, ttl: Duration, timeout: Duration) -> Result<Lease> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if try_acquire(db, name, ttl).await? {
+            return Ok(Lease::start(db, name, ttl));
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(anyhow!("Timed out waiting for lock {}", name));
+        }
+        debug!("Lock {} is held by another replica, waiting", name);
+        tokio::time::sleep(WAIT_POLL).await;
+    }
+}
+
+/// Run `job` only if this replica obtains the lease on `name`; returns `None`
+/// when another replica is already running it. The lease is renewed while the
+/// job runs and released afterwards.
+pub async fn run_exclusive<F, T>(db: &Database, name: &str, ttl: Duration, job: F) -> Result<Option<T>>
+where
+    F: Future<Output = T>,
+{
+    if !try_acquire(db, name, ttl).await? {
+        debug!("Skipping {}: running on another replica", name);
+        return Ok(None);
+    }
+    let lease = Lease::start(db, name, ttl);
+    let output = job.await;
+    lease.release().await;
+    Ok(Some(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::new_test;
+
+    #[tokio::test]
+    async fn test_lease_excludes_other_holders_until_released() {
+        let db = new_test().await.unwrap();
+        let ttl = Duration::from_secs(30);
+
+        assert!(try_acquire(&db, "job", ttl).await.unwrap());
+        // Renewal by the holder succeeds
+        assert!(try_acquire(&db, "job", ttl).await.unwrap());
+
+        db.query("UPDATE job_lock:job SET holder = 'other-replica'").traced("locks.test_lease_excludes_other_holders_until_released").await.unwrap();
+        assert!(!try_acquire(&db, "job", ttl).await.unwrap());
+
+        db.query("UPDATE job_lock:job SET expires_at = time::now() - 1s").traced("locks.test_lease_excludes_other_holders_until_released").await.unwrap();
+        assert!(try_acquire(&db, "job", ttl).await.unwrap());
+
+        release(&db, "job").await.unwrap();
+        let ran = run_exclusive(&db, "job", ttl, async { 42 }).await.unwrap();
+        assert_eq!(ran, Some(42));
+    }
+}
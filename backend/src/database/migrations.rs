@@ -1,4 +1,5 @@
 use crate::database::Database;
+use crate::models::settings::OvercommitPolicy;
 use anyhow::Result;
 use chrono::Utc;
 use serde_json::json;
@@ -117,6 +118,28 @@ impl TicketMigrations {
         )
         .await?;
 
+        // Watch activity and per-user watch notification settings
+        db.query(
+            r#"
+            DEFINE TABLE ticket_watch_events SCHEMAFULL;
+            DEFINE FIELD ticket_id ON ticket_watch_events TYPE record(ticket);
+            DEFINE FIELD ticket_title ON ticket_watch_events TYPE string;
+            DEFINE FIELD event_type ON ticket_watch_events TYPE string;
+            DEFINE FIELD summary ON ticket_watch_events TYPE string;
+            DEFINE FIELD actor_id ON ticket_watch_events TYPE string;
+            DEFINE FIELD actor_name ON ticket_watch_events TYPE string;
+            DEFINE FIELD watchers ON ticket_watch_events TYPE array DEFAULT [];
+            DEFINE FIELD created_at ON ticket_watch_events TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE ticket_watch_preferences SCHEMAFULL;
+            DEFINE FIELD user_id ON ticket_watch_preferences TYPE string;
+            DEFINE FIELD immediate ON ticket_watch_preferences TYPE bool DEFAULT true;
+            DEFINE FIELD daily_digest ON ticket_watch_preferences TYPE bool DEFAULT false;
+            DEFINE FIELD last_digest_at ON ticket_watch_preferences TYPE option<datetime>;
+            "#,
+        )
+        .await?;
+
         // SLA Policies table
         db.query(
             r#"
@@ -207,6 +230,14 @@ impl TicketMigrations {
         db.query("DEFINE INDEX idx_history_created ON ticket_history FIELDS created_at;")
             .await?;
 
+        // Watch indexes
+        db.query("DEFINE INDEX idx_watch_events_created ON ticket_watch_events FIELDS created_at;")
+            .await?;
+        db.query("DEFINE INDEX idx_ticket_watchers ON ticket FIELDS watchers;")
+            .await?;
+        db.query("DEFINE INDEX idx_watch_prefs_user ON ticket_watch_preferences FIELDS user_id UNIQUE;")
+            .await?;
+
         // SLA indexes
         db.query("DEFINE INDEX idx_sla_name ON sla_policies FIELDS name;")
             .await?;
@@ -550,6 +581,10 @@ impl EnhancedRvToolsMigrations {
             DEFINE FIELD formatting_rules ON data_variable_schema TYPE object;
             DEFINE FIELD validation_rules ON data_variable_schema TYPE array;
             DEFINE FIELD category ON data_variable_schema TYPE string;
+            DEFINE FIELD expression ON data_variable_schema TYPE option<string>;
+            DEFINE FIELD created_by ON data_variable_schema TYPE option<string>;
+            DEFINE FIELD created_at ON data_variable_schema TYPE option<datetime>;
+            DEFINE FIELD updated_at ON data_variable_schema TYPE option<datetime>;
         "#,
         )
         .await?;
@@ -750,6 +785,7 @@ impl EnhancedRvToolsMigrations {
             DEFINE FIELD vcenter_version ON rvtools_upload TYPE string;
             DEFINE FIELD environment_name ON rvtools_upload TYPE string;
             DEFINE FIELD metadata ON rvtools_upload TYPE object;
+            DEFINE FIELD import_report ON rvtools_upload TYPE object;
         "#,
         )
         .await?;
@@ -785,13 +821,133 @@ pub struct MigrationPlanningMigrations;
 impl MigrationPlanningMigrations {
     /// Run all migration planning migrations
     pub async fn run_all(db: &Database) -> Result<()> {
+        Self::create_overcommit_policy_tables(db).await?;
         Self::create_destination_cluster_tables(db).await?;
+        Self::create_build_checklist_tables(db).await?;
+        Self::create_firmware_baseline_tables(db).await?;
+        Self::create_vm_remediation_tables(db).await?;
+        Self::create_guest_scan_tables(db).await?;
         Self::create_placement_tables(db).await?;
         Self::create_capacity_tables(db).await?;
         Self::create_network_profile_tables(db).await?;
         Self::create_document_template_tables(db).await?;
         Self::create_indexes(db).await?;
         Self::seed_network_templates(db).await?;
+        Self::seed_overcommit_policies(db).await?;
+        Ok(())
+    }
+
+    /// Create destination cluster build checklist tables
+    async fn create_build_checklist_tables(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE cluster_build_checklist SCHEMALESS;
+            DEFINE FIELD cluster_id ON cluster_build_checklist TYPE record(destination_cluster);
+            DEFINE FIELD items ON cluster_build_checklist TYPE array;
+            DEFINE FIELD complete ON cluster_build_checklist TYPE bool;
+            DEFINE FIELD created_at ON cluster_build_checklist TYPE datetime;
+            DEFINE FIELD updated_at ON cluster_build_checklist TYPE datetime;
+        "#,
+        )
+        .await?;
+
+        println!("✅ Cluster build checklist tables created");
+        Ok(())
+    }
+
+    /// Create host firmware baseline and remediation task tables
+    async fn create_firmware_baseline_tables(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE firmware_baseline SCHEMALESS;
+            DEFINE FIELD vendor ON firmware_baseline TYPE string;
+            DEFINE FIELD model ON firmware_baseline TYPE string;
+            DEFINE FIELD hypervisor ON firmware_baseline TYPE string;
+            DEFINE FIELD components ON firmware_baseline TYPE array;
+            DEFINE FIELD created_at ON firmware_baseline TYPE datetime;
+            DEFINE FIELD updated_at ON firmware_baseline TYPE datetime;
+            DEFINE INDEX firmware_baseline_model_idx ON firmware_baseline COLUMNS vendor, model, hypervisor;
+
+            DEFINE TABLE firmware_remediation_task SCHEMALESS;
+            DEFINE FIELD cluster_id ON firmware_remediation_task TYPE record(destination_cluster);
+            DEFINE FIELD node_id ON firmware_remediation_task TYPE record(hardware_pool);
+            DEFINE FIELD component ON firmware_remediation_task TYPE string;
+            DEFINE FIELD status ON firmware_remediation_task TYPE string;
+            DEFINE FIELD created_at ON firmware_remediation_task TYPE datetime;
+            DEFINE INDEX firmware_task_cluster_idx ON firmware_remediation_task COLUMNS cluster_id;
+        "#,
+        )
+        .await?;
+
+        println!("✅ Firmware baseline tables created");
+        Ok(())
+    }
+
+    /// Create the pre-migration VM remediation task table
+    async fn create_vm_remediation_tables(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE vm_remediation_task SCHEMALESS;
+            DEFINE FIELD project_id ON vm_remediation_task TYPE record(migration_wizard_project);
+            DEFINE FIELD vm_id ON vm_remediation_task TYPE record(migration_wizard_vm);
+            DEFINE FIELD wave_id ON vm_remediation_task TYPE option<record(migration_wave)>;
+            DEFINE FIELD action ON vm_remediation_task TYPE string;
+            DEFINE FIELD status ON vm_remediation_task TYPE string;
+            DEFINE FIELD created_at ON vm_remediation_task TYPE datetime;
+            DEFINE INDEX vm_remediation_project_idx ON vm_remediation_task COLUMNS project_id;
+            DEFINE INDEX vm_remediation_vm_idx ON vm_remediation_task COLUMNS vm_id;
+        "#,
+        )
+        .await?;
+
+        println!("✅ VM remediation tables created");
+        Ok(())
+    }
+
+    /// Create guest scan credential and result tables
+    async fn create_guest_scan_tables(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE guest_credential SCHEMALESS;
+            DEFINE FIELD project_id ON guest_credential TYPE record(migration_wizard_project);
+            DEFINE FIELD name ON guest_credential TYPE string;
+            DEFINE FIELD protocol ON guest_credential TYPE string;
+            DEFINE FIELD secret_encrypted ON guest_credential TYPE string;
+            DEFINE FIELD created_at ON guest_credential TYPE datetime;
+            DEFINE INDEX guest_credential_project_idx ON guest_credential COLUMNS project_id;
+
+            DEFINE TABLE guest_scan SCHEMALESS;
+            DEFINE FIELD project_id ON guest_scan TYPE record(migration_wizard_project);
+            DEFINE FIELD vm_id ON guest_scan TYPE record(migration_wizard_vm);
+            DEFINE FIELD status ON guest_scan TYPE string;
+            DEFINE FIELD scanned_at ON guest_scan TYPE datetime;
+            DEFINE INDEX guest_scan_project_idx ON guest_scan COLUMNS project_id;
+        "#,
+        )
+        .await?;
+
+        println!("✅ Guest scan tables created");
+        Ok(())
+    }
+
+    /// Create the overcommit policy library
+    async fn create_overcommit_policy_tables(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE overcommit_policy SCHEMAFULL;
+            DEFINE FIELD name ON overcommit_policy TYPE string;
+            DEFINE FIELD description ON overcommit_policy TYPE option<string>;
+            DEFINE FIELD cpu_ratio ON overcommit_policy TYPE number;
+            DEFINE FIELD memory_ratio ON overcommit_policy TYPE number;
+            DEFINE FIELD updated_by ON overcommit_policy TYPE string;
+            DEFINE FIELD created_at ON overcommit_policy TYPE datetime;
+            DEFINE FIELD updated_at ON overcommit_policy TYPE datetime;
+            DEFINE INDEX idx_overcommit_policy_name ON overcommit_policy FIELDS name UNIQUE;
+        "#,
+        )
+        .await?;
+
+        println!("✅ Overcommit policy tables created");
         Ok(())
     }
 
@@ -809,6 +965,7 @@ impl MigrationPlanningMigrations {
             DEFINE FIELD nodes ON destination_cluster TYPE array<record(hardware_pool)>;
             DEFINE FIELD node_count ON destination_cluster TYPE int;
             DEFINE FIELD overcommit_ratios ON destination_cluster TYPE object;
+            DEFINE FIELD overcommit_policy_id ON destination_cluster TYPE option<record(overcommit_policy)>;
             DEFINE FIELD ha_policy ON destination_cluster TYPE string;
             DEFINE FIELD capacity_totals ON destination_cluster TYPE object;
             DEFINE FIELD capacity_available ON destination_cluster TYPE object;
@@ -918,6 +1075,7 @@ impl MigrationPlanningMigrations {
             DEFINE FIELD is_standard ON network_profile_template TYPE bool;
             DEFINE FIELD is_active ON network_profile_template TYPE bool;
             DEFINE FIELD metadata ON network_profile_template TYPE object;
+            DEFINE FIELD created_by ON network_profile_template TYPE option<string>;
             DEFINE FIELD created_at ON network_profile_template TYPE datetime;
             DEFINE FIELD updated_at ON network_profile_template TYPE datetime;
 
@@ -1125,6 +1283,39 @@ impl MigrationPlanningMigrations {
         println!("✅ Standard network templates seeded");
         Ok(())
     }
+
+    /// Seed the standard overcommit presets. Fixed ids make this a no-op
+    /// once they exist, so edits made in settings survive restarts.
+    async fn seed_overcommit_policies(db: &Database) -> Result<()> {
+        let presets = [
+            ("production", "Prod 4:1 CPU / 1:1 RAM", "Production workloads; memory is never overcommitted", 4.0, 1.0),
+            ("vdi", "VDI 8:1 CPU / 1.5:1 RAM", "Virtual desktops with bursty, mostly idle CPU", 8.0, 1.5),
+            ("dev_test", "Dev/Test 6:1 CPU / 1.5:1 RAM", "Non-production workloads", 6.0, 1.5),
+        ];
+
+        for (key, name, description, cpu_ratio, memory_ratio) in presets {
+            let existing: Option<OvercommitPolicy> = db.select(("overcommit_policy", key)).await?;
+            if existing.is_some() {
+                continue;
+            }
+            let _: Option<OvercommitPolicy> = db
+                .create(("overcommit_policy", key))
+                .content(OvercommitPolicy {
+                    id: None,
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                    cpu_ratio,
+                    memory_ratio,
+                    updated_by: "system".to_string(),
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                })
+                .await?;
+        }
+
+        println!("✅ Overcommit policy presets seeded");
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -1249,6 +1440,134 @@ impl AuthMigrations {
         )
         .await?;
 
+        // Installed license key (single app_license:current record)
+        db.query(
+            r#"
+            DEFINE TABLE app_license SCHEMAFULL;
+            DEFINE FIELD key ON app_license TYPE string;
+            DEFINE FIELD installed_by ON app_license TYPE string;
+            DEFINE FIELD installed_at ON app_license TYPE datetime;
+            "#,
+        )
+        .await?;
+
+        // Opt-in usage telemetry: config record and daily feature counts
+        db.query(
+            r#"
+            DEFINE TABLE usage_telemetry SCHEMAFULL;
+            DEFINE FIELD enabled ON usage_telemetry TYPE bool DEFAULT false;
+            DEFINE FIELD installation_id ON usage_telemetry TYPE string;
+            DEFINE FIELD updated_by ON usage_telemetry TYPE string;
+            DEFINE FIELD updated_at ON usage_telemetry TYPE datetime;
+            DEFINE FIELD last_submitted_at ON usage_telemetry TYPE option<datetime>;
+
+            DEFINE TABLE usage_daily SCHEMAFULL;
+            DEFINE FIELD day ON usage_daily TYPE string;
+            DEFINE FIELD feature ON usage_daily TYPE string;
+            DEFINE FIELD count ON usage_daily TYPE int DEFAULT 0;
+            DEFINE FIELD submitted_at ON usage_daily TYPE option<datetime>;
+            DEFINE INDEX idx_usage_daily_day ON usage_daily FIELDS day;
+            "#,
+        )
+        .await?;
+
+        // Retention policies: installation default and per-tenant overrides per data class
+        db.query(
+            r#"
+            DEFINE TABLE retention_policy SCHEMAFULL;
+            DEFINE FIELD tenant_id ON retention_policy TYPE option<string>;
+            DEFINE FIELD data_class ON retention_policy TYPE string
+                ASSERT $value INSIDE ['rvtools_rows', 'audit_logs', 'generated_documents'];
+            DEFINE FIELD retain_days ON retention_policy TYPE int ASSERT $value > 0;
+            DEFINE FIELD enabled ON retention_policy TYPE bool DEFAULT true;
+            DEFINE FIELD updated_by ON retention_policy TYPE string;
+            DEFINE FIELD updated_at ON retention_policy TYPE datetime;
+            "#,
+        )
+        .await?;
+
+        // Naming policies: installation default and per-tenant conventions
+        db.query(
+            r#"
+            DEFINE TABLE naming_policy SCHEMALESS;
+            DEFINE FIELD tenant_id ON naming_policy TYPE option<string>;
+            DEFINE FIELD rules ON naming_policy TYPE array;
+            DEFINE FIELD mandatory_tags ON naming_policy TYPE array;
+            DEFINE FIELD updated_by ON naming_policy TYPE string;
+            DEFINE FIELD updated_at ON naming_policy TYPE datetime;
+            "#,
+        )
+        .await?;
+
+        // Outbound webhook subscriptions and their delivery log
+        db.query(
+            r#"
+            DEFINE TABLE webhook_subscription SCHEMALESS;
+            DEFINE FIELD name ON webhook_subscription TYPE string;
+            DEFINE FIELD tenant_id ON webhook_subscription TYPE option<string>;
+            DEFINE FIELD url ON webhook_subscription TYPE string;
+            DEFINE FIELD event_types ON webhook_subscription TYPE array;
+            DEFINE FIELD secret_encrypted ON webhook_subscription TYPE string;
+            DEFINE FIELD enabled ON webhook_subscription TYPE bool DEFAULT true;
+            DEFINE FIELD created_at ON webhook_subscription TYPE datetime;
+            DEFINE FIELD updated_at ON webhook_subscription TYPE datetime;
+
+            DEFINE TABLE webhook_delivery SCHEMALESS;
+            DEFINE FIELD subscription_id ON webhook_delivery TYPE record(webhook_subscription);
+            DEFINE FIELD event_id ON webhook_delivery TYPE string;
+            DEFINE FIELD event_type ON webhook_delivery TYPE string;
+            DEFINE FIELD body ON webhook_delivery TYPE string;
+            DEFINE FIELD status ON webhook_delivery TYPE string
+                ASSERT $value INSIDE ['pending', 'delivered', 'dead_lettered'];
+            DEFINE FIELD next_attempt_at ON webhook_delivery TYPE datetime;
+            DEFINE FIELD created_at ON webhook_delivery TYPE datetime;
+            DEFINE INDEX idx_webhook_delivery_due ON webhook_delivery FIELDS status, next_attempt_at;
+            DEFINE INDEX idx_webhook_delivery_subscription ON webhook_delivery FIELDS subscription_id, created_at;
+            "#,
+        )
+        .await?;
+
+        // Remote collectors and the VM metric samples they stream over gRPC
+        db.query(
+            r#"
+            DEFINE TABLE collector SCHEMALESS;
+            DEFINE FIELD name ON collector TYPE string;
+            DEFINE FIELD project_ids ON collector TYPE array;
+            DEFINE FIELD token_hash ON collector TYPE string;
+            DEFINE FIELD certificate_fingerprint ON collector TYPE option<string>;
+            DEFINE FIELD enabled ON collector TYPE bool DEFAULT true;
+            DEFINE FIELD created_at ON collector TYPE datetime;
+            DEFINE FIELD updated_at ON collector TYPE datetime;
+            DEFINE INDEX idx_collector_token ON collector FIELDS token_hash UNIQUE;
+
+            DEFINE TABLE vm_metric_sample SCHEMALESS;
+            DEFINE FIELD project_id ON vm_metric_sample TYPE record(migration_wizard_project);
+            DEFINE FIELD collector_id ON vm_metric_sample TYPE record(collector);
+            DEFINE FIELD vm_name ON vm_metric_sample TYPE string;
+            DEFINE FIELD captured_at ON vm_metric_sample TYPE datetime;
+            DEFINE FIELD cpu_percent ON vm_metric_sample TYPE number;
+            DEFINE FIELD memory_percent ON vm_metric_sample TYPE number;
+            DEFINE INDEX idx_vm_metric_sample_vm ON vm_metric_sample FIELDS project_id, vm_name, captured_at;
+            "#,
+        )
+        .await?;
+
+        // Approval-gated erasure of a departed user's personal identifiers
+        db.query(
+            r#"
+            DEFINE TABLE user_erasure_request SCHEMALESS;
+            DEFINE FIELD user_id ON user_erasure_request TYPE record(users);
+            DEFINE FIELD username ON user_erasure_request TYPE string;
+            DEFINE FIELD status ON user_erasure_request TYPE string;
+            DEFINE FIELD workflow_instance_id ON user_erasure_request TYPE option<record(workflow_instance)>;
+            DEFINE FIELD requested_by ON user_erasure_request TYPE string;
+            DEFINE FIELD created_at ON user_erasure_request TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON user_erasure_request TYPE datetime DEFAULT time::now();
+            DEFINE INDEX idx_user_erasure_request_user ON user_erasure_request FIELDS user_id;
+            "#,
+        )
+        .await?;
+
         println!("✅ Authentication tables created successfully");
         Ok(())
     }
@@ -1350,6 +1669,16 @@ impl AuthMigrations {
             // Settings permissions
             ("settings:read", "View Settings", "settings", "read"),
             ("settings:manage", "Manage Settings", "settings", "manage"),
+            // Assessment permissions
+            ("assessments:read", "View Assessments", "assessments", "read"),
+            ("assessments:run", "Run Assessments", "assessments", "execute"),
+            ("assessments:manage", "Manage Rule Packs", "assessments", "manage"),
+            // AI permissions
+            ("ai:use", "Use AI Assistant", "ai", "execute"),
+            ("ai:manage", "Manage AI Providers", "ai", "manage"),
+            // Integration hub permissions
+            ("integrations:read", "View Integrations", "integrations", "read"),
+            ("integrations:manage", "Manage Integrations", "integrations", "manage"),
             // System permissions
             ("system:admin", "System Administration", "system", "manage"),
             ("audit:read", "View Audit Logs", "audit", "read"),
@@ -1400,6 +1729,9 @@ impl AuthMigrations {
                     "reports:create",
                     "reports:export",
                     "settings:manage",
+                    "assessments:manage",
+                    "ai:manage",
+                    "integrations:manage",
                     "audit:read",
                 ],
             ),
@@ -1415,6 +1747,10 @@ impl AuthMigrations {
                     "monitoring:read",
                     "reports:read",
                     "reports:create",
+                    "assessments:read",
+                    "assessments:run",
+                    "ai:use",
+                    "integrations:read",
                 ],
             ),
             (
@@ -1428,6 +1764,7 @@ impl AuthMigrations {
                     "assets:read",
                     "knowledge:read",
                     "knowledge:create",
+                    "ai:use",
                 ],
             ),
             (
@@ -1440,6 +1777,7 @@ impl AuthMigrations {
                     "knowledge:read",
                     "monitoring:read",
                     "reports:read",
+                    "assessments:read",
                 ],
             ),
         ];
@@ -1649,6 +1987,52 @@ impl KnowledgeBaseMigrations {
         )
         .await?;
 
+        // KB Attachments table (files live in the attachment store)
+        db.query(
+            r#"
+            DEFINE TABLE kb_attachments SCHEMAFULL;
+            DEFINE FIELD article_id ON kb_attachments TYPE record(kb_articles);
+            DEFINE FIELD filename ON kb_attachments TYPE string;
+            DEFINE FIELD content_type ON kb_attachments TYPE string;
+            DEFINE FIELD size_bytes ON kb_attachments TYPE int;
+            DEFINE FIELD storage_key ON kb_attachments TYPE string;
+            DEFINE FIELD checksum ON kb_attachments TYPE string;
+            DEFINE FIELD is_image ON kb_attachments TYPE bool DEFAULT false;
+            DEFINE FIELD width ON kb_attachments TYPE option<int>;
+            DEFINE FIELD height ON kb_attachments TYPE option<int>;
+            DEFINE FIELD variants ON kb_attachments TYPE array DEFAULT [];
+            DEFINE FIELD variants.* ON kb_attachments TYPE object;
+            DEFINE FIELD variants.*.label ON kb_attachments TYPE string;
+            DEFINE FIELD variants.*.width ON kb_attachments TYPE int;
+            DEFINE FIELD variants.*.height ON kb_attachments TYPE int;
+            DEFINE FIELD variants.*.content_type ON kb_attachments TYPE string;
+            DEFINE FIELD variants.*.storage_key ON kb_attachments TYPE string;
+            DEFINE FIELD variants.*.size_bytes ON kb_attachments TYPE int;
+            DEFINE FIELD uploaded_by ON kb_attachments TYPE string;
+            DEFINE FIELD uploaded_at ON kb_attachments TYPE datetime DEFAULT time::now();
+            DEFINE FIELD tenant_id ON kb_attachments TYPE option<record(tenants)>;
+            "#,
+        )
+        .await?;
+
+        // KB Suggestion log (what was suggested for which ticket, and the outcome)
+        db.query(
+            r#"
+            DEFINE TABLE kb_suggestion_log SCHEMAFULL;
+            DEFINE FIELD ticket_id ON kb_suggestion_log TYPE record(ticket);
+            DEFINE FIELD article_id ON kb_suggestion_log TYPE record(kb_articles);
+            DEFINE FIELD rank ON kb_suggestion_log TYPE int;
+            DEFINE FIELD score ON kb_suggestion_log TYPE float;
+            DEFINE FIELD similarity ON kb_suggestion_log TYPE float;
+            DEFINE FIELD method ON kb_suggestion_log TYPE string;
+            DEFINE FIELD shown_to ON kb_suggestion_log TYPE string;
+            DEFINE FIELD shown_at ON kb_suggestion_log TYPE datetime DEFAULT time::now();
+            DEFINE FIELD outcome ON kb_suggestion_log TYPE string DEFAULT 'SHOWN';
+            DEFINE FIELD responded_at ON kb_suggestion_log TYPE option<datetime>;
+            "#,
+        )
+        .await?;
+
         println!("✅ Knowledge Base tables created successfully");
         Ok(())
     }
@@ -1687,6 +2071,18 @@ impl KnowledgeBaseMigrations {
         db.query("DEFINE INDEX idx_kb_rating_user ON kb_article_ratings FIELDS user_id;")
             .await?;
 
+        // Attachment indexes
+        db.query("DEFINE INDEX idx_kb_attach_article ON kb_attachments FIELDS article_id;")
+            .await?;
+
+        // Suggestion log indexes
+        db.query("DEFINE INDEX idx_kb_sugg_ticket ON kb_suggestion_log FIELDS ticket_id;")
+            .await?;
+        db.query("DEFINE INDEX idx_kb_sugg_article ON kb_suggestion_log FIELDS article_id;")
+            .await?;
+        db.query("DEFINE INDEX idx_kb_sugg_shown ON kb_suggestion_log FIELDS shown_at;")
+            .await?;
+
         println!("✅ Knowledge Base indexes created successfully");
         Ok(())
     }
@@ -1769,6 +2165,14 @@ impl CMDBMigrations {
             DEFINE FIELD warranty_expiry ON configuration_items TYPE option<datetime>;
             DEFINE FIELD end_of_life ON configuration_items TYPE option<datetime>;
             DEFINE FIELD decommission_date ON configuration_items TYPE option<datetime>;
+            DEFINE FIELD financials ON configuration_items TYPE option<object>;
+            DEFINE FIELD financials.purchase_cost ON configuration_items TYPE number;
+            DEFINE FIELD financials.purchase_date ON configuration_items TYPE datetime;
+            DEFINE FIELD financials.depreciation_method ON configuration_items TYPE string DEFAULT 'STRAIGHT_LINE';
+            DEFINE FIELD financials.useful_life_months ON configuration_items TYPE int;
+            DEFINE FIELD financials.salvage_value ON configuration_items TYPE number DEFAULT 0;
+            DEFINE FIELD financials.declining_factor ON configuration_items TYPE option<number>;
+            DEFINE FIELD financials.currency ON configuration_items TYPE option<string>;
             DEFINE FIELD tags ON configuration_items TYPE array DEFAULT [];
             DEFINE FIELD tenant_id ON configuration_items TYPE option<record(tenants)>;
             DEFINE FIELD created_at ON configuration_items TYPE datetime DEFAULT time::now();
@@ -1867,6 +2271,133 @@ impl CMDBMigrations {
     }
 }
 
+// ============================================================================
+// CONTRACT MIGRATIONS
+// ============================================================================
+
+/// Database migrations for vendor contracts
+pub struct ContractMigrations;
+
+impl ContractMigrations {
+    /// Run all contract migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE contracts SCHEMALESS;
+            DEFINE FIELD name ON contracts TYPE string;
+            DEFINE FIELD vendor ON contracts TYPE string;
+            DEFINE FIELD contract_type ON contracts TYPE string;
+            DEFINE FIELD start_date ON contracts TYPE datetime;
+            DEFINE FIELD end_date ON contracts TYPE datetime;
+            DEFINE FIELD covered_cis ON contracts TYPE array DEFAULT [];
+            DEFINE FIELD notify ON contracts TYPE array DEFAULT [];
+            DEFINE FIELD alert_days ON contracts TYPE array DEFAULT [90, 30, 7];
+            DEFINE FIELD alerts_sent ON contracts TYPE array DEFAULT [];
+            DEFINE FIELD auto_renew ON contracts TYPE bool DEFAULT false;
+            DEFINE FIELD created_by ON contracts TYPE string;
+            DEFINE FIELD tenant_id ON contracts TYPE option<record(tenants)>;
+            DEFINE FIELD created_at ON contracts TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON contracts TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_contracts_end_date ON contracts FIELDS end_date;")
+            .await?;
+        db.query("DEFINE INDEX idx_contracts_vendor ON contracts FIELDS vendor;")
+            .await?;
+        db.query("DEFINE INDEX idx_contracts_covered_cis ON contracts FIELDS covered_cis;")
+            .await?;
+
+        println!("✅ Contract tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// SPARE PARTS MIGRATIONS
+// ============================================================================
+
+/// Hardware pool spare parts inventory migrations
+pub struct SparePartMigrations;
+
+impl SparePartMigrations {
+    /// Run all spare parts migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE spare_part SCHEMALESS;
+            DEFINE FIELD part_number ON spare_part TYPE string;
+            DEFINE FIELD category ON spare_part TYPE string;
+            DEFINE FIELD location ON spare_part TYPE string;
+            DEFINE FIELD quantity ON spare_part TYPE int ASSERT $value >= 0;
+            DEFINE FIELD min_quantity ON spare_part TYPE int DEFAULT 0;
+            DEFINE FIELD compatible_models ON spare_part TYPE array DEFAULT [];
+            DEFINE FIELD alert_emails ON spare_part TYPE array DEFAULT [];
+            DEFINE FIELD low_stock_alerted ON spare_part TYPE bool DEFAULT false;
+            DEFINE FIELD created_at ON spare_part TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON spare_part TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE spare_part_consumption SCHEMALESS;
+            DEFINE FIELD part_id ON spare_part_consumption TYPE record(spare_part);
+            DEFINE FIELD ticket_id ON spare_part_consumption TYPE record(ticket);
+            DEFINE FIELD server_id ON spare_part_consumption TYPE option<record(hardware_pool)>;
+            DEFINE FIELD quantity ON spare_part_consumption TYPE int;
+            DEFINE FIELD consumed_by ON spare_part_consumption TYPE string;
+            DEFINE FIELD consumed_at ON spare_part_consumption TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_spare_part_location ON spare_part FIELDS part_number, location UNIQUE;")
+            .await?;
+        db.query("DEFINE INDEX idx_spare_part_consumption_part ON spare_part_consumption FIELDS part_id;")
+            .await?;
+        db.query("DEFINE INDEX idx_spare_part_consumption_ticket ON spare_part_consumption FIELDS ticket_id;")
+            .await?;
+
+        println!("✅ Spare parts tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// PROCUREMENT MIGRATIONS
+// ============================================================================
+
+/// Purchase request migrations
+pub struct ProcurementMigrations;
+
+impl ProcurementMigrations {
+    /// Run all procurement migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE purchase_request SCHEMALESS;
+            DEFINE FIELD title ON purchase_request TYPE string;
+            DEFINE FIELD vendor ON purchase_request TYPE string;
+            DEFINE FIELD project_id ON purchase_request TYPE option<record(migration_wizard_project)>;
+            DEFINE FIELD lines ON purchase_request TYPE array;
+            DEFINE FIELD status ON purchase_request TYPE string;
+            DEFINE FIELD workflow_instance_id ON purchase_request TYPE option<record(workflow_instance)>;
+            DEFINE FIELD po_number ON purchase_request TYPE option<string>;
+            DEFINE FIELD requested_by ON purchase_request TYPE string;
+            DEFINE FIELD created_at ON purchase_request TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON purchase_request TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_purchase_request_status ON purchase_request FIELDS status;")
+            .await?;
+        db.query("DEFINE INDEX idx_purchase_request_po ON purchase_request FIELDS po_number;")
+            .await?;
+
+        println!("✅ Procurement tables created successfully");
+        Ok(())
+    }
+}
+
 // ============================================================================
 // SERVICE CATALOG MIGRATIONS (Phase 5)
 // ============================================================================
@@ -2180,6 +2711,11 @@ impl WorkflowMigrations {
             DEFINE FIELD created_by ON workflow_definition TYPE string;
             DEFINE FIELD created_at ON workflow_definition TYPE datetime DEFAULT time::now();
             DEFINE FIELD updated_at ON workflow_definition TYPE datetime DEFAULT time::now();
+            DEFINE FIELD workflow_key ON workflow_definition TYPE option<string>;
+            DEFINE FIELD version ON workflow_definition TYPE int DEFAULT 1;
+            DEFINE FIELD status ON workflow_definition TYPE string DEFAULT 'PUBLISHED';
+            DEFINE FIELD published_at ON workflow_definition TYPE option<datetime>;
+            DEFINE FIELD tenant_id ON workflow_definition TYPE option<string>;
             "#,
         )
         .await?;
@@ -2189,6 +2725,7 @@ impl WorkflowMigrations {
             r#"
             DEFINE TABLE workflow_instance SCHEMAFULL;
             DEFINE FIELD workflow_id ON workflow_instance TYPE record(workflow_definition);
+            DEFINE FIELD workflow_version ON workflow_instance TYPE option<int>;
             DEFINE FIELD trigger_record_type ON workflow_instance TYPE string;
             DEFINE FIELD trigger_record_id ON workflow_instance TYPE record;
             DEFINE FIELD status ON workflow_instance TYPE string DEFAULT 'RUNNING';
@@ -2197,6 +2734,7 @@ impl WorkflowMigrations {
             DEFINE FIELD started_at ON workflow_instance TYPE datetime DEFAULT time::now();
             DEFINE FIELD completed_at ON workflow_instance TYPE option<datetime>;
             DEFINE FIELD context ON workflow_instance TYPE object DEFAULT {};
+            DEFINE FIELD resume_at ON workflow_instance TYPE option<datetime>;
             "#,
         )
         .await?;
@@ -2213,6 +2751,32 @@ impl WorkflowMigrations {
             DEFINE FIELD requested_at ON approval TYPE datetime DEFAULT time::now();
             DEFINE FIELD responded_at ON approval TYPE option<datetime>;
             DEFINE FIELD comments ON approval TYPE option<string>;
+            DEFINE FIELD request_id ON approval TYPE option<string>;
+            DEFINE FIELD quorum ON approval TYPE option<int>;
+            DEFINE FIELD group_id ON approval TYPE option<record>;
+            DEFINE FIELD delegated_from ON approval TYPE option<record>;
+            DEFINE FIELD escalated_from ON approval TYPE option<record>;
+            DEFINE FIELD responded_by ON approval TYPE option<record>;
+            DEFINE FIELD remind_at ON approval TYPE option<datetime>;
+            DEFINE FIELD reminder_interval_minutes ON approval TYPE option<int>;
+            DEFINE FIELD reminders_sent ON approval TYPE int DEFAULT 0;
+            DEFINE FIELD escalate_at ON approval TYPE option<datetime>;
+            DEFINE FIELD escalate_to ON approval TYPE option<record>;
+            "#,
+        )
+        .await?;
+
+        // Approval delegation (vacation) rules
+        db.query(
+            r#"
+            DEFINE TABLE approval_delegation SCHEMAFULL;
+            DEFINE FIELD delegator_id ON approval_delegation TYPE record(users);
+            DEFINE FIELD delegate_id ON approval_delegation TYPE record(users);
+            DEFINE FIELD starts_at ON approval_delegation TYPE datetime;
+            DEFINE FIELD ends_at ON approval_delegation TYPE datetime;
+            DEFINE FIELD reason ON approval_delegation TYPE option<string>;
+            DEFINE FIELD is_active ON approval_delegation TYPE bool DEFAULT true;
+            DEFINE FIELD created_at ON approval_delegation TYPE datetime DEFAULT time::now();
             "#,
         )
         .await?;
@@ -2230,6 +2794,8 @@ impl WorkflowMigrations {
             .await?;
         db.query("DEFINE INDEX idx_workflow_trigger ON workflow_definition FIELDS trigger_type;")
             .await?;
+        db.query("DEFINE INDEX idx_workflow_key_version ON workflow_definition FIELDS workflow_key, version;")
+            .await?;
 
         // Workflow instance indexes
         db.query("DEFINE INDEX idx_instance_workflow ON workflow_instance FIELDS workflow_id;")
@@ -2240,6 +2806,8 @@ impl WorkflowMigrations {
             .await?;
         db.query("DEFINE INDEX idx_instance_started ON workflow_instance FIELDS started_at;")
             .await?;
+        db.query("DEFINE INDEX idx_instance_timer ON workflow_instance FIELDS status, resume_at;")
+            .await?;
 
         // Approval indexes
         db.query("DEFINE INDEX idx_approval_instance ON approval FIELDS workflow_instance_id;")
@@ -2250,6 +2818,10 @@ impl WorkflowMigrations {
             .await?;
         db.query("DEFINE INDEX idx_approval_requested ON approval FIELDS requested_at;")
             .await?;
+        db.query("DEFINE INDEX idx_approval_request ON approval FIELDS request_id;")
+            .await?;
+        db.query("DEFINE INDEX idx_delegation_delegator ON approval_delegation FIELDS delegator_id, is_active;")
+            .await?;
 
         println!("✅ Workflow indexes created successfully");
         Ok(())
@@ -2342,6 +2914,55 @@ impl TeamMigrations {
     }
 }
 
+// ============================================================================
+// WORKSPACE SEARCH MIGRATIONS
+// ============================================================================
+
+/// Full-text indexes backing the workspace search API
+pub struct SearchMigrations;
+
+impl SearchMigrations {
+    /// Run all search migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        Self::create_analyzer(db).await?;
+        Self::create_search_indexes(db).await?;
+        Ok(())
+    }
+
+    /// Shared analyzer: split on whitespace and character class, lowercase, fold accents, stem
+    async fn create_analyzer(db: &Database) -> Result<()> {
+        db.query(
+            "DEFINE ANALYZER archer_search TOKENIZERS blank,class FILTERS lowercase,ascii,snowball(english);",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// One BM25 index per searchable field; the service addresses them by match reference
+    async fn create_search_indexes(db: &Database) -> Result<()> {
+        println!("🔍 Creating full-text search indexes...");
+
+        db.query(
+            r#"
+            DEFINE INDEX ft_ticket_title ON ticket FIELDS title SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_ticket_description ON ticket FIELDS description SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_kb_title ON kb_articles FIELDS title SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_kb_content ON kb_articles FIELDS content SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_ci_name ON configuration_items FIELDS name SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_ci_description ON configuration_items FIELDS description SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_project_name ON migration_wizard_project FIELDS name SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_project_description ON migration_wizard_project FIELDS description SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_document_name ON generated_document FIELDS document_name SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            DEFINE INDEX ft_document_type ON generated_document FIELDS document_type SEARCH ANALYZER archer_search BM25 HIGHLIGHTS;
+            "#,
+        )
+        .await?;
+
+        println!("✅ Full-text search indexes created successfully");
+        Ok(())
+    }
+}
+
 // ============================================================================
 // SEED DATA: Demo/Mock Tickets
 // ============================================================================
@@ -2737,4 +3358,540 @@ impl SeedData {
         println!("✅ Seeded {} demo tickets", demo_tickets.len());
         Ok(())
     }
-}
\ No newline at end of file
+}
+// ============================================================================
+// SAVED VIEW MIGRATIONS
+// ============================================================================
+
+/// Database migrations for saved ticket/CI filter views
+pub struct SavedViewMigrations;
+
+impl SavedViewMigrations {
+    /// Run all saved view migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE saved_views SCHEMALESS;
+            DEFINE FIELD name ON saved_views TYPE string;
+            DEFINE FIELD entity ON saved_views TYPE string;
+            DEFINE FIELD filter ON saved_views TYPE object;
+            DEFINE FIELD visibility ON saved_views TYPE string DEFAULT 'PRIVATE';
+            DEFINE FIELD owner_id ON saved_views TYPE string;
+            DEFINE FIELD team_id ON saved_views TYPE option<record(teams)>;
+            DEFINE FIELD is_default ON saved_views TYPE bool DEFAULT false;
+            DEFINE FIELD tenant_id ON saved_views TYPE option<record(tenants)>;
+            DEFINE FIELD created_at ON saved_views TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON saved_views TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_saved_views_owner ON saved_views FIELDS owner_id, entity;")
+            .await?;
+        db.query("DEFINE INDEX idx_saved_views_team ON saved_views FIELDS team_id;")
+            .await?;
+
+        println!("✅ Saved view tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// TICKET TEMPLATE MIGRATIONS
+// ============================================================================
+
+/// Database migrations for ticket templates, macros and checklist tasks
+pub struct TicketTemplateMigrations;
+
+impl TicketTemplateMigrations {
+    /// Run all ticket template migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE ticket_templates SCHEMALESS;
+            DEFINE FIELD name ON ticket_templates TYPE string;
+            DEFINE FIELD team_id ON ticket_templates TYPE option<record(teams)>;
+            DEFINE FIELD type ON ticket_templates TYPE string;
+            DEFINE FIELD priority ON ticket_templates TYPE string;
+            DEFINE FIELD checklist ON ticket_templates TYPE array DEFAULT [];
+            DEFINE FIELD is_active ON ticket_templates TYPE bool DEFAULT true;
+            DEFINE FIELD created_by ON ticket_templates TYPE string;
+            DEFINE FIELD tenant_id ON ticket_templates TYPE option<record(tenants)>;
+            DEFINE FIELD created_at ON ticket_templates TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON ticket_templates TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE ticket_macros SCHEMALESS;
+            DEFINE FIELD name ON ticket_macros TYPE string;
+            DEFINE FIELD team_id ON ticket_macros TYPE option<record(teams)>;
+            DEFINE FIELD actions ON ticket_macros TYPE object;
+            DEFINE FIELD is_active ON ticket_macros TYPE bool DEFAULT true;
+            DEFINE FIELD created_by ON ticket_macros TYPE string;
+            DEFINE FIELD tenant_id ON ticket_macros TYPE option<record(tenants)>;
+            DEFINE FIELD created_at ON ticket_macros TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON ticket_macros TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE ticket_tasks SCHEMALESS;
+            DEFINE FIELD ticket_id ON ticket_tasks TYPE record(ticket);
+            DEFINE FIELD title ON ticket_tasks TYPE string;
+            DEFINE FIELD position ON ticket_tasks TYPE int;
+            DEFINE FIELD completed ON ticket_tasks TYPE bool DEFAULT false;
+            DEFINE FIELD template_id ON ticket_tasks TYPE option<record(ticket_templates)>;
+            DEFINE FIELD created_at ON ticket_tasks TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_ticket_templates_team ON ticket_templates FIELDS team_id;")
+            .await?;
+        db.query("DEFINE INDEX idx_ticket_macros_team ON ticket_macros FIELDS team_id;")
+            .await?;
+        db.query("DEFINE INDEX idx_ticket_tasks_ticket ON ticket_tasks FIELDS ticket_id, position;")
+            .await?;
+
+        println!("✅ Ticket template tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// TICKET SCHEDULE MIGRATIONS
+// ============================================================================
+
+/// Database migrations for recurring ticket schedules and their run history
+pub struct TicketScheduleMigrations;
+
+impl TicketScheduleMigrations {
+    /// Run all ticket schedule migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE ticket_schedules SCHEMALESS;
+            DEFINE FIELD name ON ticket_schedules TYPE string;
+            DEFINE FIELD template_id ON ticket_schedules TYPE record(ticket_templates);
+            DEFINE FIELD title ON ticket_schedules TYPE string;
+            DEFINE FIELD cron ON ticket_schedules TYPE string;
+            DEFINE FIELD ci_ids ON ticket_schedules TYPE array DEFAULT [];
+            DEFINE FIELD ticket_per_ci ON ticket_schedules TYPE bool DEFAULT false;
+            DEFINE FIELD enabled ON ticket_schedules TYPE bool DEFAULT true;
+            DEFINE FIELD next_run_at ON ticket_schedules TYPE option<datetime>;
+            DEFINE FIELD next_occurrence_at ON ticket_schedules TYPE option<datetime>;
+            DEFINE FIELD last_run_at ON ticket_schedules TYPE option<datetime>;
+            DEFINE FIELD occurrence_count ON ticket_schedules TYPE int DEFAULT 0;
+            DEFINE FIELD created_by ON ticket_schedules TYPE string;
+            DEFINE FIELD tenant_id ON ticket_schedules TYPE option<record(tenants)>;
+            DEFINE FIELD created_at ON ticket_schedules TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON ticket_schedules TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE ticket_schedule_runs SCHEMALESS;
+            DEFINE FIELD schedule_id ON ticket_schedule_runs TYPE record(ticket_schedules);
+            DEFINE FIELD occurrence_at ON ticket_schedule_runs TYPE datetime;
+            DEFINE FIELD status ON ticket_schedule_runs TYPE string;
+            DEFINE FIELD ticket_ids ON ticket_schedule_runs TYPE array DEFAULT [];
+            DEFINE FIELD tenant_id ON ticket_schedule_runs TYPE option<record(tenants)>;
+            DEFINE FIELD created_at ON ticket_schedule_runs TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_ticket_schedules_due ON ticket_schedules FIELDS enabled, next_run_at;")
+            .await?;
+        db.query("DEFINE INDEX idx_ticket_schedule_runs_schedule ON ticket_schedule_runs FIELDS schedule_id, occurrence_at;")
+            .await?;
+
+        println!("✅ Ticket schedule tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// REPORT SCHEDULE MIGRATIONS
+// ============================================================================
+
+/// Database migrations for scheduled reports and their generated artifacts
+pub struct ReportScheduleMigrations;
+
+impl ReportScheduleMigrations {
+    /// Run all report schedule migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE report_schedules SCHEMALESS;
+            DEFINE FIELD name ON report_schedules TYPE string;
+            DEFINE FIELD template ON report_schedules TYPE string;
+            DEFINE FIELD cron ON report_schedules TYPE string;
+            DEFINE FIELD enabled ON report_schedules TYPE bool DEFAULT true;
+            DEFINE FIELD next_run_at ON report_schedules TYPE option<datetime>;
+            DEFINE FIELD last_run_at ON report_schedules TYPE option<datetime>;
+            DEFINE FIELD tenant_id ON report_schedules TYPE option<record(tenants)>;
+            DEFINE FIELD created_at ON report_schedules TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON report_schedules TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE report_artifacts SCHEMALESS;
+            DEFINE FIELD schedule_id ON report_artifacts TYPE option<record(report_schedules)>;
+            DEFINE FIELD template ON report_artifacts TYPE string;
+            DEFINE FIELD filename ON report_artifacts TYPE string;
+            DEFINE FIELD status ON report_artifacts TYPE string;
+            DEFINE FIELD tenant_id ON report_artifacts TYPE option<record(tenants)>;
+            DEFINE FIELD generated_at ON report_artifacts TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_report_schedules_due ON report_schedules FIELDS enabled, next_run_at;")
+            .await?;
+        db.query("DEFINE INDEX idx_report_artifacts_schedule ON report_artifacts FIELDS schedule_id, generated_at;")
+            .await?;
+
+        println!("✅ Report schedule tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// CAPACITY SNAPSHOT MIGRATIONS
+// ============================================================================
+
+/// Daily per-cluster utilization history used for capacity exhaustion forecasts
+pub struct CapacitySnapshotMigrations;
+
+impl CapacitySnapshotMigrations {
+    /// Run all capacity snapshot migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE capacity_snapshots SCHEMALESS;
+            DEFINE FIELD cluster_id ON capacity_snapshots TYPE record(migration_wizard_cluster);
+            DEFINE FIELD project_id ON capacity_snapshots TYPE record(migration_wizard_project);
+            DEFINE FIELD cpu_percent ON capacity_snapshots TYPE number;
+            DEFINE FIELD memory_percent ON capacity_snapshots TYPE number;
+            DEFINE FIELD storage_percent ON capacity_snapshots TYPE number;
+            DEFINE FIELD captured_at ON capacity_snapshots TYPE datetime;
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_capacity_snapshots_cluster ON capacity_snapshots FIELDS cluster_id, captured_at;")
+            .await?;
+
+        println!("✅ Capacity snapshot tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// AI PROVIDER MIGRATIONS
+// ============================================================================
+
+/// Tables for LLM provider configuration, tenant model selection and usage
+pub struct AiMigrations;
+
+impl AiMigrations {
+    /// Run all AI provider migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE llm_providers SCHEMAFULL;
+            DEFINE FIELD name ON llm_providers TYPE string;
+            DEFINE FIELD kind ON llm_providers TYPE string;
+            DEFINE FIELD base_url ON llm_providers TYPE string;
+            DEFINE FIELD api_key_encrypted ON llm_providers TYPE option<string>;
+            DEFINE FIELD default_model ON llm_providers TYPE string;
+            DEFINE FIELD models ON llm_providers TYPE array DEFAULT [];
+            DEFINE FIELD models.* ON llm_providers TYPE string;
+            DEFINE FIELD enabled ON llm_providers TYPE bool DEFAULT true;
+            DEFINE FIELD timeout_seconds ON llm_providers TYPE int DEFAULT 120;
+            DEFINE FIELD created_by ON llm_providers TYPE string;
+            DEFINE FIELD created_at ON llm_providers TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON llm_providers TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE llm_tenant_settings SCHEMAFULL;
+            DEFINE FIELD provider_id ON llm_tenant_settings TYPE record(llm_providers);
+            DEFINE FIELD model ON llm_tenant_settings TYPE string;
+            DEFINE FIELD temperature ON llm_tenant_settings TYPE option<float>;
+            DEFINE FIELD max_tokens ON llm_tenant_settings TYPE option<int>;
+            DEFINE FIELD monthly_token_budget ON llm_tenant_settings TYPE option<int>;
+            DEFINE FIELD updated_by ON llm_tenant_settings TYPE string;
+            DEFINE FIELD updated_at ON llm_tenant_settings TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE llm_usage SCHEMAFULL;
+            DEFINE FIELD tenant_id ON llm_usage TYPE option<string>;
+            DEFINE FIELD user_id ON llm_usage TYPE string;
+            DEFINE FIELD provider_id ON llm_usage TYPE record(llm_providers);
+            DEFINE FIELD model ON llm_usage TYPE string;
+            DEFINE FIELD feature ON llm_usage TYPE option<string>;
+            DEFINE FIELD prompt_tokens ON llm_usage TYPE int;
+            DEFINE FIELD completion_tokens ON llm_usage TYPE int;
+            DEFINE FIELD total_tokens ON llm_usage TYPE int;
+            DEFINE FIELD estimated ON llm_usage TYPE bool DEFAULT false;
+            DEFINE FIELD streamed ON llm_usage TYPE bool DEFAULT false;
+            DEFINE FIELD latency_ms ON llm_usage TYPE int;
+            DEFINE FIELD success ON llm_usage TYPE bool;
+            DEFINE FIELD error ON llm_usage TYPE option<string>;
+            DEFINE FIELD created_at ON llm_usage TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_llm_providers_name ON llm_providers FIELDS name UNIQUE;")
+            .await?;
+        db.query("DEFINE INDEX idx_llm_usage_tenant_time ON llm_usage FIELDS tenant_id, created_at;")
+            .await?;
+
+        // Assistant audit trail; parameters/results are free-form per tool
+        db.query(
+            r#"
+            DEFINE TABLE agent_action SCHEMALESS;
+            DEFINE FIELD agent ON agent_action TYPE string;
+            DEFINE FIELD action_type ON agent_action TYPE string;
+            DEFINE FIELD target ON agent_action TYPE string;
+            DEFINE FIELD risk_score ON agent_action TYPE int;
+            DEFINE FIELD status ON agent_action TYPE string;
+            DEFINE FIELD requires_approval ON agent_action TYPE bool;
+            DEFINE FIELD session_id ON agent_action TYPE string;
+            DEFINE FIELD requested_by ON agent_action TYPE string;
+            DEFINE FIELD created_at ON agent_action TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE ai_thought_log SCHEMALESS;
+            DEFINE FIELD session_id ON ai_thought_log TYPE string;
+            DEFINE FIELD agent ON ai_thought_log TYPE string;
+            DEFINE FIELD model ON ai_thought_log TYPE string;
+            DEFINE FIELD created_at ON ai_thought_log TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE hld_section_drafts SCHEMALESS;
+            DEFINE FIELD hld_project_id ON hld_section_drafts TYPE record(hld_projects);
+            DEFINE FIELD section_id ON hld_section_drafts TYPE string;
+            DEFINE FIELD content ON hld_section_drafts TYPE string;
+            DEFINE FIELD created_at ON hld_section_drafts TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_action_status ON agent_action FIELDS status;").await?;
+        db.query("DEFINE INDEX idx_action_session ON agent_action FIELDS session_id;").await?;
+        db.query("DEFINE INDEX idx_action_created ON agent_action FIELDS created_at;").await?;
+        db.query("DEFINE INDEX idx_thought_session ON ai_thought_log FIELDS session_id;").await?;
+        db.query("DEFINE INDEX idx_thought_created ON ai_thought_log FIELDS created_at;").await?;
+        db.query("DEFINE INDEX idx_hld_drafts_project ON hld_section_drafts FIELDS hld_project_id, section_id;")
+            .await?;
+
+        println!("✅ AI provider and assistant tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// INTEGRATION HUB MIGRATIONS
+// ============================================================================
+
+pub struct IntegrationMigrations;
+
+impl IntegrationMigrations {
+    /// Run all integration hub migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE integration_connections SCHEMALESS;
+            DEFINE FIELD name ON integration_connections TYPE string;
+            DEFINE FIELD provider_type ON integration_connections TYPE string;
+            DEFINE FIELD base_url ON integration_connections TYPE string;
+            DEFINE FIELD enabled ON integration_connections TYPE bool DEFAULT true;
+            DEFINE FIELD created_at ON integration_connections TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE integration_sync_runs SCHEMALESS;
+            DEFINE FIELD connection_id ON integration_sync_runs TYPE record(integration_connections);
+            DEFINE FIELD mode ON integration_sync_runs TYPE string;
+            DEFINE FIELD status ON integration_sync_runs TYPE string;
+            DEFINE FIELD started_at ON integration_sync_runs TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE integration_assets SCHEMALESS;
+            DEFINE FIELD connection_id ON integration_assets TYPE record(integration_connections);
+            DEFINE FIELD external_id ON integration_assets TYPE string;
+            DEFINE FIELD asset_type ON integration_assets TYPE string;
+            DEFINE FIELD fingerprint ON integration_assets TYPE string;
+            DEFINE FIELD removed ON integration_assets TYPE bool DEFAULT false;
+
+            -- Relationships maintained by a sync remember which asset declared them
+            DEFINE FIELD integration_connection ON ci_relationships TYPE option<record(integration_connections)>;
+            DEFINE FIELD integration_asset ON ci_relationships TYPE option<string>;
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_integration_runs_connection ON integration_sync_runs FIELDS connection_id, started_at;")
+            .await?;
+        db.query("DEFINE INDEX idx_integration_runs_status ON integration_sync_runs FIELDS status;").await?;
+        db.query("DEFINE INDEX idx_integration_assets_key ON integration_assets FIELDS connection_id, external_id UNIQUE;")
+            .await?;
+        db.query("DEFINE INDEX idx_ci_rel_integration ON ci_relationships FIELDS integration_connection, integration_asset;")
+            .await?;
+
+        println!("✅ Integration hub tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// GENERATED DOCUMENT VERSION MIGRATIONS
+// ============================================================================
+
+/// Version history for generated documents
+pub struct DocumentVersionMigrations;
+
+impl DocumentVersionMigrations {
+    /// Run all generated document version migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            -- Snapshots and parameters are free-form objects
+            DEFINE TABLE generated_document SCHEMALESS;
+            -- HLD exports belong to projects and hld_templates
+            DEFINE FIELD project_id ON generated_document TYPE record;
+            DEFINE FIELD template_id ON generated_document TYPE record;
+            DEFINE FIELD version ON generated_document TYPE int DEFAULT 1;
+            DEFINE FIELD previous_version ON generated_document TYPE option<record(generated_document)>;
+            DEFINE FIELD generation_parameters ON generated_document TYPE object DEFAULT {};
+            DEFINE FIELD section_snapshot ON generated_document TYPE array DEFAULT [];
+            DEFINE FIELD storage_backend ON generated_document TYPE string DEFAULT 'disk';
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX generated_doc_version_idx ON generated_document FIELDS project_id, document_type, version;")
+            .await?;
+
+        println!("✅ Generated document version fields created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// DOCUMENT REVIEW MIGRATIONS
+// ============================================================================
+
+/// Review and sign-off of generated documents
+pub struct DocumentReviewMigrations;
+
+impl DocumentReviewMigrations {
+    /// Run all document review migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            DEFINE TABLE document_review SCHEMALESS;
+            DEFINE FIELD document_id ON document_review TYPE record(generated_document);
+            DEFINE FIELD project_id ON document_review TYPE record;
+            DEFINE FIELD document_version ON document_review TYPE int;
+            DEFINE FIELD status ON document_review TYPE string;
+            DEFINE FIELD reviewers ON document_review TYPE array;
+            DEFINE FIELD workflow_instance_id ON document_review TYPE option<record(workflow_instance)>;
+            DEFINE FIELD requested_by ON document_review TYPE string;
+            DEFINE FIELD created_at ON document_review TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON document_review TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE document_review_comment SCHEMALESS;
+            DEFINE FIELD review_id ON document_review_comment TYPE record(document_review);
+            DEFINE FIELD section_id ON document_review_comment TYPE string;
+            DEFINE FIELD body ON document_review_comment TYPE string;
+            DEFINE FIELD author ON document_review_comment TYPE string;
+            DEFINE FIELD reply_to ON document_review_comment TYPE option<record(document_review_comment)>;
+            DEFINE FIELD created_at ON document_review_comment TYPE datetime DEFAULT time::now();
+
+            -- A published version is locked and points at its signed-off copy
+            DEFINE FIELD locked ON generated_document TYPE bool DEFAULT false;
+            DEFINE FIELD published_file_path ON generated_document TYPE option<string>;
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_document_review_document ON document_review FIELDS document_id;")
+            .await?;
+        db.query("DEFINE INDEX idx_document_review_comment_review ON document_review_comment FIELDS review_id, section_id;")
+            .await?;
+
+        println!("✅ Document review tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// PROJECT ACCESS MIGRATIONS
+// ============================================================================
+
+/// Per-project membership and invitations
+pub struct ProjectAccessMigrations;
+
+impl ProjectAccessMigrations {
+    /// Run all project access migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            -- project_id is the raw id shared by wizard projects, documents and placements
+            DEFINE TABLE project_membership SCHEMALESS;
+            DEFINE FIELD project_id ON project_membership TYPE string;
+            DEFINE FIELD principal_type ON project_membership TYPE string ASSERT $value INSIDE ['user', 'team'];
+            DEFINE FIELD principal_id ON project_membership TYPE string;
+            DEFINE FIELD role ON project_membership TYPE string ASSERT $value INSIDE ['owner', 'editor', 'viewer'];
+            DEFINE FIELD added_by ON project_membership TYPE string;
+            DEFINE FIELD created_at ON project_membership TYPE datetime DEFAULT time::now();
+            DEFINE FIELD updated_at ON project_membership TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE project_invitation SCHEMALESS;
+            DEFINE FIELD project_id ON project_invitation TYPE string;
+            DEFINE FIELD invitee ON project_invitation TYPE object;
+            DEFINE FIELD role ON project_invitation TYPE string ASSERT $value INSIDE ['owner', 'editor', 'viewer'];
+            DEFINE FIELD status ON project_invitation TYPE string;
+            DEFINE FIELD invited_by ON project_invitation TYPE string;
+            DEFINE FIELD created_at ON project_invitation TYPE datetime DEFAULT time::now();
+            DEFINE FIELD expires_at ON project_invitation TYPE datetime;
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_project_membership_principal ON project_membership FIELDS project_id, principal_type, principal_id UNIQUE;")
+            .await?;
+        db.query("DEFINE INDEX idx_project_invitation_project ON project_invitation FIELDS project_id, status;")
+            .await?;
+
+        println!("✅ Project access tables created successfully");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// SETTINGS MIGRATIONS
+// ============================================================================
+
+/// Layered setting overrides and their change history
+pub struct SettingsMigrations;
+
+impl SettingsMigrations {
+    /// Run all settings migrations
+    pub async fn run_all(db: &Database) -> Result<()> {
+        db.query(
+            r#"
+            -- value holds whatever type the key declares, so the table stays schemaless
+            DEFINE TABLE setting_override SCHEMALESS;
+            DEFINE FIELD scope ON setting_override TYPE string ASSERT $value INSIDE ['system', 'tenant', 'project', 'user'];
+            DEFINE FIELD scope_id ON setting_override TYPE string;
+            DEFINE FIELD key ON setting_override TYPE string;
+            DEFINE FIELD updated_by ON setting_override TYPE string;
+            DEFINE FIELD updated_at ON setting_override TYPE datetime DEFAULT time::now();
+
+            DEFINE TABLE setting_change SCHEMALESS;
+            DEFINE FIELD scope ON setting_change TYPE string;
+            DEFINE FIELD scope_id ON setting_change TYPE string;
+            DEFINE FIELD key ON setting_change TYPE string;
+            DEFINE FIELD changed_by ON setting_change TYPE string;
+            DEFINE FIELD changed_at ON setting_change TYPE datetime DEFAULT time::now();
+            "#,
+        )
+        .await?;
+
+        db.query("DEFINE INDEX idx_setting_override_layer ON setting_override FIELDS scope, scope_id, key UNIQUE;")
+            .await?;
+        db.query("DEFINE INDEX idx_setting_change_time ON setting_change FIELDS changed_at;")
+            .await?;
+
+        println!("✅ Settings tables created successfully");
+        Ok(())
+    }
+}
@@ -17,6 +17,7 @@ use surrealdb::sql::Thing;
 use crate::database::Database;
 use std::io::Write;
 use std::sync::Arc;
+use crate::database::TracedQuery;
 
 pub type AppState = Arc<Database>;
 
@@ -276,6 +277,7 @@ async fn get_hardware_basket_models(
     let basket_thing = Thing::from(("hardware_basket", basket_id.as_str()));
     let mut result = db.query("SELECT * FROM hardware_model WHERE basket_id = $basket_id")
         .bind(("basket_id", basket_thing))
+        .traced("hardware_basket_api.get_hardware_basket_models")
         .await
         .map_err(|e| {
             eprintln!("Database error fetching models: {:?}", e);
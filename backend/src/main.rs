@@ -1,7 +1,7 @@
-use axum::{http::Method, middleware::from_fn};
+use axum::middleware::from_fn;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener as StdTcpListener};
 use std::sync::Arc;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tower_http::trace::TraceLayer;
 
 mod api;
 mod database;
@@ -12,7 +12,19 @@ mod utils;
 // mod hardware_basket_api; // Disabled - using new api/hardware_baskets.rs
 // mod parser; // Disabled - using new parser in core-engine
 
+use services::collector_ingest::{self, CollectorGrpcConfig};
+use services::contract_service::ContractAlertScheduler;
+use services::upload_session_service::UploadCleanupScheduler;
+use services::integration_hub::IntegrationSyncScheduler;
+use services::license_service::{LicenseScheduler, LicenseService};
+use services::report_scheduler_service::ReportScheduler;
+use services::usage_telemetry_service::{self, UsageTelemetryScheduler, UsageTelemetryService};
+use services::retention_service::RetentionScheduler;
+use services::ticket_schedule_service::TicketScheduler;
+use services::ticket_watch_service::WatchDigestScheduler;
 use services::tiering_service::TieringScheduler;
+use services::workflow_engine_service::WorkflowTimerScheduler;
+use services::webhook_service::WebhookScheduler;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -20,13 +32,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if std::env::var("RUST_LOG").is_err() {
         std::env::set_var("RUST_LOG", "info,tower_http=info,backend=info");
     }
-    tracing_subscriber::fmt::init();
+    utils::log_scrubbing::init_tracing();
 
     // Initialize document storage
-    if let Err(e) = services::document_service::DocumentService::init_storage() {
-        tracing::warn!("Failed to initialize document storage: {}", e);
-    } else {
-        tracing::info!("📁 Document storage initialized");
+    match services::document_service::DocumentService::init_storage() {
+        Ok(backend) => tracing::info!("📁 Document storage initialized ({:?})", backend),
+        Err(e) => tracing::warn!("Failed to initialize document storage: {}", e),
     }
 
     // Initialize database with enhanced error handling
@@ -41,6 +52,68 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    // Verify the license before serving; without a usable one the API is read-only
+    match LicenseService::new(Arc::clone(&db_state)).load().await {
+        Ok(license) => {
+            let status = license.status(chrono::Utc::now());
+            match (&license.claims, &license.error) {
+                (Some(claims), _) => tracing::info!(
+                    "🔑 License {} for {} ({:?}, {} seats)",
+                    claims.license_id,
+                    claims.customer,
+                    status,
+                    claims.seats
+                ),
+                (None, Some(error)) => tracing::warn!("🔑 License key rejected, API is read-only: {}", error),
+                (None, None) if license.enforced => tracing::warn!("🔑 No license key installed, API is read-only"),
+                (None, None) => tracing::info!("🔑 Licensing not enforced (no vendor public key configured)"),
+            }
+        }
+        Err(e) => tracing::warn!("Failed to load license: {}", e),
+    }
+
+    // Hourly license re-check, so keys installed on another replica take effect
+    let license_check_enabled = std::env::var("LICENSE_CHECK_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if license_check_enabled {
+        match LicenseScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("🔑 License check scheduled (hourly)"),
+            Err(e) => tracing::warn!("Failed to start license check scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("🔑 License check disabled (LICENSE_CHECK_ENABLED=false)");
+    }
+
+    // Opt-in usage telemetry; the hard off switch keeps it from ever starting
+    if usage_telemetry_service::hard_disabled() {
+        tracing::info!("📈 Usage telemetry switched off (ARCHER_TELEMETRY_DISABLED/DO_NOT_TRACK)");
+    } else {
+        match UsageTelemetryService::new(Arc::clone(&db_state)).load().await {
+            Ok(true) => tracing::info!("📈 Usage telemetry enabled (opted in)"),
+            Ok(false) => tracing::info!("📈 Usage telemetry not opted in"),
+            Err(e) => tracing::warn!("Failed to load usage telemetry settings: {}", e),
+        }
+        if let Err(e) = UsageTelemetryScheduler::new(Arc::clone(&db_state)).start().await {
+            tracing::warn!("Failed to start usage telemetry scheduler: {}", e);
+        }
+    }
+
+    // Nightly retention purge; without any policy it has nothing to delete
+    let retention_purge_enabled = std::env::var("RETENTION_PURGE_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if retention_purge_enabled {
+        match RetentionScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("🗑️ Retention purge scheduled (nightly)"),
+            Err(e) => tracing::warn!("Failed to start retention purge scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("🗑️ Retention purge disabled (RETENTION_PURGE_ENABLED=false)");
+    }
+
     // Initialize tiering scheduler for automatic ticket archival
     let tiering_scheduler = TieringScheduler::new(Arc::clone(&db_state));
     let scheduler_enabled = std::env::var("TIERING_SCHEDULER_ENABLED")
@@ -56,26 +129,145 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("⏰ Tiering scheduler disabled (set TIERING_SCHEDULER_ENABLED=true to enable)");
     }
 
+    // Scheduled report generation; polls once a minute for due schedules
+    let report_scheduler_enabled = std::env::var("REPORT_SCHEDULER_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if report_scheduler_enabled {
+        match ReportScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("📨 Report scheduler started"),
+            Err(e) => tracing::warn!("Failed to start report scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("📨 Report scheduler disabled (REPORT_SCHEDULER_ENABLED=false)");
+    }
+
+    // Workflow delay steps resume from a poller
+    let workflow_timers_enabled = std::env::var("WORKFLOW_TIMERS_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if workflow_timers_enabled {
+        match WorkflowTimerScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("⏱️ Workflow timer scheduler started"),
+            Err(e) => tracing::warn!("Failed to start workflow timer scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("⏱️ Workflow timer scheduler disabled (WORKFLOW_TIMERS_ENABLED=false)");
+    }
+
+    // Scheduled inventory syncs for integration connections
+    let integration_sync_enabled = std::env::var("INTEGRATION_SYNC_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if integration_sync_enabled {
+        match IntegrationSyncScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("🔄 Integration sync scheduler started"),
+            Err(e) => tracing::warn!("Failed to start integration sync scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("🔄 Integration sync scheduler disabled (INTEGRATION_SYNC_ENABLED=false)");
+    }
+
+    // Daily digest of activity on watched tickets
+    let watch_digest_enabled = std::env::var("WATCH_DIGEST_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if watch_digest_enabled {
+        match WatchDigestScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("👀 Watch digest scheduler started"),
+            Err(e) => tracing::warn!("Failed to start watch digest scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("👀 Watch digest scheduler disabled (WATCH_DIGEST_ENABLED=false)");
+    }
+
+    // Recurring maintenance tickets
+    let ticket_scheduler_enabled = std::env::var("TICKET_SCHEDULER_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if ticket_scheduler_enabled {
+        match TicketScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("🗓️ Ticket scheduler started"),
+            Err(e) => tracing::warn!("Failed to start ticket scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("🗓️ Ticket scheduler disabled (TICKET_SCHEDULER_ENABLED=false)");
+    }
+
+    // Contract expiry alerts
+    let contract_alerts_enabled = std::env::var("CONTRACT_ALERTS_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if contract_alerts_enabled {
+        match ContractAlertScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("📄 Contract expiry alerts scheduled"),
+            Err(e) => tracing::warn!("Failed to start contract alert scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("📄 Contract expiry alerts disabled (CONTRACT_ALERTS_ENABLED=false)");
+    }
+
+    // Abandoned resumable upload cleanup
+    let upload_cleanup_enabled = std::env::var("UPLOAD_CLEANUP_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if upload_cleanup_enabled {
+        match UploadCleanupScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("🧹 Upload cleanup scheduled"),
+            Err(e) => tracing::warn!("Failed to start upload cleanup scheduler: {}", e),
+        }
+    } else {
+        tracing::info!("🧹 Upload cleanup disabled (UPLOAD_CLEANUP_ENABLED=false)");
+    }
+
+    // Outbound webhook deliveries and the daily capacity alert sweep
+    let webhooks_enabled = std::env::var("WEBHOOKS_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    if webhooks_enabled {
+        match WebhookScheduler::new(Arc::clone(&db_state)).start().await {
+            Ok(_) => tracing::info!("🪝 Webhook dispatcher started"),
+            Err(e) => tracing::warn!("Failed to start webhook dispatcher: {}", e),
+        }
+    } else {
+        tracing::info!("🪝 Webhook dispatcher disabled (WEBHOOKS_ENABLED=false)");
+    }
+
+    // Collector ingestion over mutual-TLS gRPC, on its own port
+    match CollectorGrpcConfig::from_env() {
+        Some(Ok(config)) => {
+            let addr = config.addr;
+            let db = Arc::clone(&db_state);
+            tokio::spawn(async move {
+                if let Err(e) = collector_ingest::serve(db, config).await {
+                    tracing::error!("Collector gRPC server stopped: {}", e);
+                }
+            });
+            tracing::info!("📡 Collector gRPC ingestion listening on {}", addr);
+        }
+        Some(Err(e)) => tracing::warn!("Collector gRPC ingestion not started: {}", e),
+        None => tracing::info!("📡 Collector gRPC ingestion disabled (set COLLECTOR_GRPC_ADDR to enable)"),
+    }
+
     // build our application with the API router and middleware
-    let app = api::api_router(db_state)
+    let cors = middleware::cors::CorsSettings::from_env();
+    let app = api::api_router(db_state, &cors)
         .layer(from_fn(middleware::security_headers))
         .layer(from_fn(middleware::error_handler))
         .layer(from_fn(middleware::request_logger))
         .layer(from_fn(middleware::validate_json_content_type))
         .layer(from_fn(middleware::validate_request_size))
-        .layer(
-            // CORS configuration: Allow credentials from specific frontend origin
-            CorsLayer::new()
-                // Avoid parse().unwrap() on a constant by using HeaderValue::from_static
-                .allow_origin(axum::http::HeaderValue::from_static("http://localhost:1420"))
-                .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::PATCH])
-                .allow_headers([
-                    axum::http::header::CONTENT_TYPE,
-                    axum::http::header::AUTHORIZATION,
-                ])
-                .allow_credentials(true)
-        )
-        .layer(TraceLayer::new_for_http());
+        .layer(TraceLayer::new_for_http())
+        // Outermost so the request span covers every other layer
+        .layer(from_fn(utils::telemetry::trace_requests));
 
     // Determine bind host/port from env, prioritize Rust backend on 3001
     let host: IpAddr = std::env::var("RUST_BACKEND_HOST")
@@ -128,5 +320,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .await?;
 
+    utils::telemetry::shutdown();
     Ok(())
 }
@@ -0,0 +1,306 @@
+//! Cross-origin policy
+//!
+//! Browsers may only call the API from origins on an explicit allowlist. By
+//! default that is the Vite dev server and the packaged Tauri app; deployments
+//! that serve the frontend elsewhere set `CORS_ALLOWED_ORIGINS`.
+//!
+//! Routes that handle credentials or secrets (auth, settings, diagnostics) use
+//! [`CorsSettings::sensitive_layer`] instead: exact origins only (never `*`),
+//! a narrower request header set and a shorter preflight cache.
+
+use std::time::Duration;
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::warn;
+
+use crate::api::uploads::{UPLOAD_CHECKSUM, UPLOAD_OFFSET};
+use crate::utils::telemetry::TRACE_ID_HEADER;
+
+/// Vite dev server, and the Tauri app's origin on macOS/Linux and Windows
+pub const DEFAULT_ALLOWED_ORIGINS: &[&str] = &[
+    "http://localhost:1420",
+    "tauri://localhost",
+    "https://tauri.localhost",
+];
+
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(3600);
+/// Preflight cache ceiling for sensitive routes, so tightening the policy
+/// takes effect quickly
+const SENSITIVE_MAX_AGE: Duration = Duration::from_secs(600);
+
+const ALLOWED_METHODS: [Method; 5] = [Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::PATCH];
+
+#[derive(Debug, Clone)]
+pub struct CorsSettings {
+    /// Exact origins allowed on ordinary routes
+    pub allowed_origins: Vec<HeaderValue>,
+    /// `*` was configured; only honoured when credentials are disabled
+    pub allow_any_origin: bool,
+    /// Exact origins allowed on auth-sensitive routes
+    pub sensitive_origins: Vec<HeaderValue>,
+    pub allow_credentials: bool,
+    /// Request headers allowed on ordinary routes
+    pub allowed_headers: Vec<HeaderName>,
+    pub max_age: Duration,
+}
+
+impl Default for CorsSettings {
+    fn default() -> Self {
+        let origins: Vec<HeaderValue> = DEFAULT_ALLOWED_ORIGINS
+            .iter()
+            .copied()
+            .map(HeaderValue::from_static)
+            .collect();
+        Self {
+            allowed_origins: origins.clone(),
+            allow_any_origin: false,
+            sensitive_origins: origins,
+            allow_credentials: true,
+            allowed_headers: vec![
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                header::IF_NONE_MATCH,
+                UPLOAD_OFFSET,
+                UPLOAD_CHECKSUM,
+            ],
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+impl CorsSettings {
+    /// Read the policy from the environment:
+    ///
+    /// - `CORS_ALLOWED_ORIGINS`: comma-separated origins, or `*`
+    /// - `CORS_SENSITIVE_ORIGINS`: origins for auth, settings and diagnostics;
+    ///   defaults to `CORS_ALLOWED_ORIGINS` without `*`
+    /// - `CORS_ALLOW_CREDENTIALS`: `true` (default) or `false`
+    /// - `CORS_ALLOWED_HEADERS`: extra request headers for ordinary routes
+    /// - `CORS_MAX_AGE_SECS`: preflight cache lifetime, default 3600
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Self {
+        let mut settings = Self::default();
+        let var = |key: &str| get(key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        if let Some(value) = var("CORS_ALLOW_CREDENTIALS") {
+            settings.allow_credentials = value == "true" || value == "1";
+        }
+
+        if let Some(value) = var("CORS_ALLOWED_ORIGINS") {
+            let (origins, any) = parse_origins("CORS_ALLOWED_ORIGINS", &value);
+            settings.allowed_origins = origins;
+            settings.allow_any_origin = any;
+            if any && settings.allow_credentials {
+                warn!("CORS_ALLOWED_ORIGINS=* is ignored while CORS_ALLOW_CREDENTIALS is enabled");
+                settings.allow_any_origin = false;
+            }
+            settings.sensitive_origins = settings.allowed_origins.clone();
+        }
+
+        if let Some(value) = var("CORS_SENSITIVE_ORIGINS") {
+            let (origins, any) = parse_origins("CORS_SENSITIVE_ORIGINS", &value);
+            if any {
+                warn!("CORS_SENSITIVE_ORIGINS does not accept *; listed origins only");
+            }
+            settings.sensitive_origins = origins;
+        }
+
+        if let Some(value) = var("CORS_ALLOWED_HEADERS") {
+            for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                match HeaderName::try_from(name) {
+                    Ok(name) if !settings.allowed_headers.contains(&name) => settings.allowed_headers.push(name),
+                    Ok(_) => {}
+                    Err(_) => warn!("Ignoring invalid header name in CORS_ALLOWED_HEADERS: {}", name),
+                }
+            }
+        }
+
+        if let Some(value) = var("CORS_MAX_AGE_SECS") {
+            match value.parse() {
+                Ok(secs) => settings.max_age = Duration::from_secs(secs),
+                Err(_) => warn!("Ignoring invalid CORS_MAX_AGE_SECS: {}", value),
+            }
+        }
+
+        settings
+    }
+
+    /// Policy for ordinary API routes
+    pub fn layer(&self) -> CorsLayer {
+        let origin = if self.allow_any_origin {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(self.allowed_origins.clone())
+        };
+        CorsLayer::new()
+            .allow_origin(origin)
+            .allow_methods(ALLOWED_METHODS)
+            .allow_headers(self.allowed_headers.clone())
+            .allow_credentials(self.allow_credentials)
+            // Resumable uploads read the confirmed offset back after each chunk
+            .expose_headers([TRACE_ID_HEADER, header::ETAG, UPLOAD_OFFSET])
+            .max_age(self.max_age)
+    }
+
+    /// Policy for auth, settings and diagnostics routes
+    pub fn sensitive_layer(&self) -> CorsLayer {
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(self.sensitive_origins.clone()))
+            .allow_methods(ALLOWED_METHODS)
+            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+            .allow_credentials(self.allow_credentials)
+            .expose_headers([TRACE_ID_HEADER])
+            .max_age(self.max_age.min(SENSITIVE_MAX_AGE))
+    }
+}
+
+/// Split a comma-separated origin list; the flag reports a `*` entry
+fn parse_origins(key: &str, value: &str) -> (Vec<HeaderValue>, bool) {
+    let mut origins = Vec::new();
+    let mut any = false;
+    for origin in value.split(',').map(|o| o.trim().trim_end_matches('/')).filter(|o| !o.is_empty()) {
+        if origin == "*" {
+            any = true;
+            continue;
+        }
+        match HeaderValue::from_str(origin) {
+            Ok(origin) => origins.push(origin),
+            Err(_) => warn!("Ignoring invalid origin in {}: {}", key, origin),
+        }
+    }
+    (origins, any)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::post, Router};
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    fn settings(vars: &[(&str, &str)]) -> CorsSettings {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        CorsSettings::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    fn app(settings: &CorsSettings) -> Router {
+        let sensitive = Router::new()
+            .route("/auth/login", post(|| async { "token" }))
+            .layer(settings.sensitive_layer());
+        Router::new()
+            .route("/tickets", post(|| async { "ticket" }))
+            .layer(settings.layer())
+            .merge(sensitive)
+    }
+
+    async fn preflight(app: Router, uri: &str, origin: &str, headers: &str) -> axum::response::Response {
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri(uri)
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, headers)
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    fn allowed_origin(response: &axum::response::Response) -> Option<&str> {
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .and_then(|v| v.to_str().ok())
+    }
+
+    #[tokio::test]
+    async fn test_preflight_default_allowlist() {
+        let settings = CorsSettings::default();
+
+        let response = preflight(app(&settings), "/tickets", "tauri://localhost", "content-type,if-none-match").await;
+        assert_eq!(allowed_origin(&response), Some("tauri://localhost"));
+        assert_eq!(
+            response.headers().get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS).unwrap(),
+            "true"
+        );
+
+        let response = preflight(app(&settings), "/tickets", "https://evil.example", "content-type").await;
+        assert_eq!(allowed_origin(&response), None);
+    }
+
+    #[tokio::test]
+    async fn test_resumable_upload_headers_pass_preflight() {
+        let settings = CorsSettings::default();
+
+        let response = preflight(
+            app(&settings),
+            "/tickets",
+            "http://localhost:1420",
+            "content-type,upload-offset,upload-checksum",
+        )
+        .await;
+        assert_eq!(allowed_origin(&response), Some("http://localhost:1420"));
+        let allowed_headers = response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+            .and_then(|v| v.to_str().ok())
+            .unwrap();
+        assert!(allowed_headers.contains("upload-offset"));
+        assert!(allowed_headers.contains("upload-checksum"));
+
+        // The chunk response's offset must be readable from script
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/tickets")
+            .header(header::ORIGIN, "http://localhost:1420")
+            .body(Body::empty())
+            .unwrap();
+        let response = app(&settings).oneshot(request).await.unwrap();
+        let exposed = response
+            .headers()
+            .get(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+            .and_then(|v| v.to_str().ok())
+            .unwrap();
+        assert!(exposed.contains("upload-offset"));
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_routes_use_stricter_policy() {
+        let settings = settings(&[
+            ("CORS_ALLOWED_ORIGINS", "https://archer.example.com, https://reports.example.com/"),
+            ("CORS_SENSITIVE_ORIGINS", "https://archer.example.com"),
+            ("CORS_ALLOWED_HEADERS", "x-requested-with"),
+        ]);
+
+        let response = preflight(app(&settings), "/tickets", "https://reports.example.com", "x-requested-with").await;
+        assert_eq!(allowed_origin(&response), Some("https://reports.example.com"));
+
+        let response = preflight(app(&settings), "/auth/login", "https://reports.example.com", "content-type").await;
+        assert_eq!(allowed_origin(&response), None);
+
+        let response = preflight(app(&settings), "/auth/login", "https://archer.example.com", "content-type").await;
+        assert_eq!(allowed_origin(&response), Some("https://archer.example.com"));
+        let allowed_headers = response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+            .and_then(|v| v.to_str().ok())
+            .unwrap();
+        assert!(!allowed_headers.contains("x-requested-with"));
+        assert_eq!(response.headers().get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "600");
+    }
+
+    #[test]
+    fn test_wildcard_requires_credentials_off() {
+        let with_credentials = settings(&[("CORS_ALLOWED_ORIGINS", "*")]);
+        assert!(!with_credentials.allow_any_origin);
+        assert!(with_credentials.allowed_origins.is_empty());
+
+        let without = settings(&[("CORS_ALLOWED_ORIGINS", "*"), ("CORS_ALLOW_CREDENTIALS", "false")]);
+        assert!(without.allow_any_origin);
+        // `*` never carries over to the sensitive routes
+        assert!(without.sensitive_origins.is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::{Request, StatusCode, HeaderValue},
+    http::{Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -8,9 +8,6 @@ use tracing::{error, info, warn};
 
 use crate::utils::api_response::{helpers, ApiResponse};
 
-// Security: Consistent CORS origin
-const ALLOWED_ORIGIN: &str = "http://localhost:1420";
-
 /// Global error handling middleware
 pub async fn error_handler<B>(request: Request<B>, next: Next<B>) -> Response {
     let start = Instant::now();
@@ -57,34 +54,6 @@ pub async fn error_handler<B>(request: Request<B>, next: Next<B>) -> Response {
     response
 }
 
-/// CORS handling middleware
-pub async fn cors_handler<B>(request: Request<B>, next: Next<B>) -> Response {
-    let response = next.run(request).await;
-
-    // Add CORS headers if not already present
-    let (mut parts, body) = response.into_parts();
-
-    // Security: Use consistent, restricted CORS origin instead of wildcard
-    parts.headers.insert(
-        "Access-Control-Allow-Origin",
-        HeaderValue::from_static(ALLOWED_ORIGIN),
-    );
-    parts.headers.insert(
-        "Access-Control-Allow-Methods",
-        HeaderValue::from_static("GET, POST, PUT, DELETE, OPTIONS"),
-    );
-    parts.headers.insert(
-        "Access-Control-Allow-Headers",
-        HeaderValue::from_static("Content-Type, Authorization, X-Requested-With"),
-    );
-    parts.headers.insert(
-        "Access-Control-Max-Age",
-        HeaderValue::from_static("3600"),
-    );
-
-    Response::from_parts(parts, body)
-}
-
 /// Request logging middleware
 pub async fn request_logger<B>(request: Request<B>, next: Next<B>) -> Response {
     let start = Instant::now();
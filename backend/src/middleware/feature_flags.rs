@@ -0,0 +1,55 @@
+//! Feature flag guards
+//!
+//! [`require_feature`] rejects requests while a flag is off for the caller,
+//! so features can ship dark and be rolled out tenant by tenant. Apply it
+//! inside [`require_auth`](super::auth::require_auth), which supplies the
+//! caller the flag is evaluated for.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::auth::AuthenticatedUser;
+use crate::database::Database;
+use crate::models::settings::FeatureFlag;
+use crate::services::feature_flag_service::FeatureFlagService;
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::codes;
+
+/// State for [`require_feature`]: the flag that guards the routes
+#[derive(Clone)]
+pub struct FeatureGate {
+    service: Arc<FeatureFlagService>,
+    flag: FeatureFlag,
+}
+
+impl FeatureGate {
+    pub fn new(db: Arc<Database>, flag: FeatureFlag) -> Self {
+        Self {
+            service: Arc::new(FeatureFlagService::new(db)),
+            flag,
+        }
+    }
+}
+
+pub async fn require_feature(State(gate): State<FeatureGate>, request: Request<Body>, next: Next<Body>) -> Response {
+    let Some(user) = request.extensions().get::<AuthenticatedUser>().cloned() else {
+        return error_body(codes::UNAUTHORIZED, "Authentication required").into_response();
+    };
+
+    match gate.service.is_enabled(&user, gate.flag).await {
+        Ok(true) => next.run(request).await,
+        Ok(false) => error_body(
+            codes::FEATURE_DISABLED,
+            &format!("The {} feature is not enabled", gate.flag.key().as_str()),
+        )
+        .into_response(),
+        Err(e) => error_body(codes::INTERNAL_ERROR, &e.to_string()).into_response(),
+    }
+}
@@ -0,0 +1,56 @@
+//! License guards
+//!
+//! [`require_license`] makes the API read-only while the installation has no
+//! usable license, and flags responses during the grace period.
+//! [`require_module`] rejects requests to modules the license doesn't
+//! include. Both read the license cached by
+//! [`license_service`](crate::services::license_service).
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderValue, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+
+use crate::models::license::{LicenseStatus, LicensedModule};
+use crate::services::license_service;
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::codes;
+
+const LICENSE_WARNING_HEADER: &str = "x-license-warning";
+
+pub async fn require_license(request: Request<Body>, next: Next<Body>) -> Response {
+    let status = license_service::current().status(Utc::now());
+    let read_only = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+
+    if !status.allows_writes() && !read_only {
+        let message = match status {
+            LicenseStatus::Expired => "The license has expired; the API is read-only",
+            LicenseStatus::Invalid => "The installed license key is invalid; the API is read-only",
+            _ => "No license key is installed; the API is read-only",
+        };
+        return error_body(codes::LICENSE_REQUIRED, message).into_response();
+    }
+
+    let mut response = next.run(request).await;
+    if status == LicenseStatus::Grace {
+        response
+            .headers_mut()
+            .insert(LICENSE_WARNING_HEADER, HeaderValue::from_static("grace-period"));
+    }
+    response
+}
+
+pub async fn require_module(State(module): State<LicensedModule>, request: Request<Body>, next: Next<Body>) -> Response {
+    if !license_service::current().module_enabled(module) {
+        return error_body(
+            codes::MODULE_NOT_LICENSED,
+            &format!("The {} module is not included in your license", module.key()),
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
@@ -1,9 +1,14 @@
 // Authentication & Authorization (Phase 0)
 pub mod auth;
 pub mod rbac;
+pub mod project_access;
+pub mod feature_flags;
+pub mod license;
 
+pub mod cors;
 pub mod error_handling;
 pub mod rate_limiting;
+pub mod response_cache;
 pub mod validation;
 pub mod security_headers;
 
@@ -0,0 +1,88 @@
+//! Per-project role checks for project-scoped routes
+//!
+//! [`require_project_role`] takes the project from the `:project_id` or `:id`
+//! path parameter and checks the caller's role on it: reads need viewer,
+//! writes need editor. Apply it with `route_layer` so path parameters are
+//! available, inside [`require_auth`](super::auth::require_auth). Routes keyed
+//! by another resource's id check the project in their handler with
+//! [`ProjectAccessService::authorize`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::auth::AuthenticatedUser;
+use crate::database::Database;
+use crate::models::project_access::ProjectRole;
+use crate::services::project_access_service::{ProjectAccessError, ProjectAccessService};
+use crate::utils::api_response::helpers::error_body;
+use core_engine::error::codes;
+
+/// State for [`require_project_role`]
+#[derive(Clone)]
+pub struct ProjectAccess {
+    service: Arc<ProjectAccessService>,
+}
+
+impl ProjectAccess {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            service: Arc::new(ProjectAccessService::new(db)),
+        }
+    }
+}
+
+/// Role a request needs: reads need viewer, everything else editor
+pub fn required_role(method: &Method) -> ProjectRole {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        ProjectRole::Viewer
+    } else {
+        ProjectRole::Editor
+    }
+}
+
+pub async fn require_project_role(
+    State(access): State<ProjectAccess>,
+    params: Option<Path<HashMap<String, String>>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let project_id = params
+        .as_ref()
+        .and_then(|Path(params)| params.get("project_id").or_else(|| params.get("id")).cloned());
+    let Some(project_id) = project_id else {
+        return next.run(request).await;
+    };
+    let Some(user) = request.extensions().get::<AuthenticatedUser>().cloned() else {
+        return error_body(codes::UNAUTHORIZED, "Authentication required").into_response();
+    };
+
+    match access
+        .service
+        .authorize(&user, &project_id, required_role(request.method()))
+        .await
+    {
+        Ok(_) => next.run(request).await,
+        Err(ProjectAccessError::Forbidden(message)) => error_body(codes::FORBIDDEN, &message).into_response(),
+        Err(e) => error_body(codes::INTERNAL_ERROR, &e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_need_viewer_and_writes_editor() {
+        assert_eq!(required_role(&Method::GET), ProjectRole::Viewer);
+        assert_eq!(required_role(&Method::POST), ProjectRole::Editor);
+        assert_eq!(required_role(&Method::DELETE), ProjectRole::Editor);
+    }
+}
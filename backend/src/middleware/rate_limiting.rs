@@ -3,12 +3,15 @@ use axum::{
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use crate::database::AppState;
 use crate::utils::api_response::{helpers, ApiResponse};
+use crate::database::TracedQuery;
 
 /// Rate limiter configuration
 #[derive(Clone, Debug)]
@@ -36,11 +39,20 @@ struct RateLimitEntry {
     last_request: Instant,
 }
 
-/// In-memory rate limiter (for production, consider Redis)
+/// Request counters for one client in the shared store
+#[derive(Debug, Deserialize)]
+struct SharedCounter {
+    requests: u32,
+}
+
+/// Rate limiter keeping counters in memory, or in SurrealDB when created with
+/// [`RateLimiter::shared`] so all replicas behind a load balancer enforce one
+/// limit per client
 #[derive(Clone)]
 pub struct RateLimiter {
     config: RateLimitConfig,
     clients: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
+    shared: Option<AppState>,
 }
 
 impl RateLimiter {
@@ -48,6 +60,63 @@ impl RateLimiter {
         Self {
             config,
             clients: Arc::new(Mutex::new(HashMap::new())),
+            shared: None,
+        }
+    }
+
+    pub fn shared(config: RateLimitConfig, db: AppState) -> Self {
+        Self {
+            shared: Some(db),
+            ..Self::new(config)
+        }
+    }
+
+    /// Check against the shared store when configured, otherwise in memory
+    pub async fn check(&self, client_id: &str) -> Result<(), RateLimitError> {
+        match &self.shared {
+            Some(db) => self.check_shared(db, client_id).await,
+            None => self.check_rate_limit(client_id),
+        }
+    }
+
+    /// Fixed-window counters per client, one per window and one per second
+    /// for the burst limit. Rows carry an expiry and are pruned occasionally.
+    /// Fails open if the database is unreachable.
+    async fn check_shared(&self, db: &AppState, client_id: &str) -> Result<(), RateLimitError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let window_secs = self.config.window_duration.as_secs().max(1);
+
+        let result = db
+            .query(
+                "UPDATE type::thing('rate_limit', [$client, 'window', $window]) \
+                    SET requests += 1, expires_at = time::now() + <duration> $ttl; \
+                 UPDATE type::thing('rate_limit', [$client, 'burst', $second]) \
+                    SET requests += 1, expires_at = time::now() + 2s;",
+            )
+            .bind(("client", client_id.to_string()))
+            .bind(("window", now / window_secs))
+            .bind(("second", now))
+            .bind(("ttl", format!("{}s", window_secs)))
+            .traced("rate_limiting.check_shared")
+            .await
+            .and_then(|mut response| {
+                let window: Option<SharedCounter> = response.take(0)?;
+                let burst: Option<SharedCounter> = response.take(1)?;
+                Ok((window.map_or(0, |c| c.requests), burst.map_or(0, |c| c.requests)))
+            });
+
+        if rand::random::<u8>() == 0 {
+            let _ = db.query("DELETE rate_limit WHERE expires_at < time::now()").traced("rate_limiting.check_shared").await;
+        }
+
+        match result {
+            Ok((_, burst)) if burst > self.config.burst_limit => Err(RateLimitError::BurstLimitExceeded),
+            Ok((window, _)) if window > self.config.requests_per_minute => Err(RateLimitError::RateLimitExceeded),
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::warn!("Shared rate limit check failed, allowing request: {}", e);
+                Ok(())
+            }
         }
     }
 
@@ -112,8 +181,29 @@ pub fn create_rate_limit_middleware(
     Next<axum::body::Body>,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>
        + Clone {
-    let rate_limiter = RateLimiter::new(config);
+    rate_limit_middleware(RateLimiter::new(config))
+}
+
+/// Rate limiting middleware with counters in SurrealDB, for multi-replica
+/// deployments
+pub fn create_shared_rate_limit_middleware(
+    config: RateLimitConfig,
+    db: AppState,
+) -> impl Fn(
+    Request<axum::body::Body>,
+    Next<axum::body::Body>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>
+       + Clone {
+    rate_limit_middleware(RateLimiter::shared(config, db))
+}
 
+fn rate_limit_middleware(
+    rate_limiter: RateLimiter,
+) -> impl Fn(
+    Request<axum::body::Body>,
+    Next<axum::body::Body>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>
+       + Clone {
     move |request: Request<axum::body::Body>, next: Next<axum::body::Body>| {
         let rate_limiter = rate_limiter.clone();
 
@@ -121,7 +211,7 @@ pub fn create_rate_limit_middleware(
             // Extract client identifier (IP address or user ID if authenticated)
             let client_id = extract_client_id(&request);
 
-            match rate_limiter.check_rate_limit(&client_id) {
+            match rate_limiter.check(&client_id).await {
                 Ok(()) => {
                     // Add rate limit headers to response
                     let response = next.run(request).await;
@@ -258,6 +348,24 @@ mod tests {
         assert!(limiter.check_rate_limit("test_client").is_err());
     }
 
+    #[tokio::test]
+    async fn test_shared_limiter_counts_across_instances() {
+        let db = Arc::new(crate::database::new_test().await.unwrap());
+        let config = RateLimitConfig {
+            requests_per_minute: 2,
+            burst_limit: 10,
+            window_duration: Duration::from_secs(60),
+        };
+        // Two limiters over one database stand in for two replicas
+        let first = RateLimiter::shared(config.clone(), Arc::clone(&db));
+        let second = RateLimiter::shared(config, db);
+
+        assert!(first.check("test_client").await.is_ok());
+        assert!(second.check("test_client").await.is_ok());
+        assert!(first.check("test_client").await.is_err());
+        assert!(second.check("other_client").await.is_ok());
+    }
+
     #[test]
     fn test_different_clients_have_separate_limits() {
         let config = RateLimitConfig::default();
@@ -0,0 +1,328 @@
+//! Conditional requests and response caching for heavy read endpoints
+//!
+//! Topology, strategy and analytics payloads are large, expensive to build and
+//! re-fetched on every screen load. Responses carry a strong `ETag` derived
+//! from the body, and a request whose `If-None-Match` matches it gets
+//! `304 Not Modified` with no body.
+//!
+//! Routes behind [`cache_responses`] also keep the rendered body, keyed by URI
+//! and by the revision of the scope the data belongs to (a migration project).
+//! Revisions live in the `cache_revision` table and are bumped by
+//! [`invalidate`], so a write handled by one replica retires cached bodies on
+//! every replica. Tenant-scoped endpoints only use [`etag`], since their body
+//! depends on the caller and not just the URI.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use axum::{
+    body::{Body, Bytes, Full},
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::database::{AppState, Database, TracedQuery};
+
+/// Cached bodies kept per replica before the oldest are evicted
+const MAX_ENTRIES: usize = 256;
+/// Larger bodies are still tagged but never cached
+const MAX_CACHED_BODY: usize = 8 * 1024 * 1024;
+
+struct CachedResponse {
+    scope: String,
+    revision: u64,
+    etag: HeaderValue,
+    content_type: Option<HeaderValue>,
+    body: Bytes,
+    stored_at: Instant,
+}
+
+static RESPONSES: Lazy<RwLock<HashMap<String, CachedResponse>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Cache scope for everything derived from one migration project
+pub fn project_scope(project_id: &str) -> String {
+    format!("project:{}", project_id)
+}
+
+/// Current revision of `scope`; 0 until it is first invalidated
+pub async fn revision(db: &Database, scope: &str) -> Result<u64> {
+    #[derive(Deserialize)]
+    struct RevisionRow {
+        revision: u64,
+    }
+
+    let rows: Vec<RevisionRow> = db
+        .query("SELECT revision FROM type::thing('cache_revision', $scope)")
+        .bind(("scope", scope.to_string()))
+        .traced("response_cache.revision")
+        .await
+        .and_then(|mut response| response.take(0))
+        .map_err(|e| anyhow!("Failed to read cache revision for {}: {}", scope, e))?;
+    Ok(rows.first().map(|row| row.revision).unwrap_or(0))
+}
+
+/// Retire every cached response in `scope`, on all replicas. Call after any
+/// write to the data those responses are built from.
+pub async fn invalidate(db: &Database, scope: &str) {
+    evict_scope(scope);
+    let result = db
+        .query(
+            "UPDATE type::thing('cache_revision', $scope) SET \
+                scope = $scope, \
+                revision += 1, \
+                updated_at = time::now()",
+        )
+        .bind(("scope", scope.to_string()))
+        .traced("response_cache.invalidate")
+        .await;
+    if let Err(e) = result {
+        // Other replicas keep serving their copy until the next write
+        warn!("Failed to invalidate response cache for {}: {}", scope, e);
+    }
+}
+
+fn evict_scope(scope: &str) {
+    let mut responses = RESPONSES.write().unwrap_or_else(|e| e.into_inner());
+    responses.retain(|_, cached| cached.scope != scope);
+}
+
+/// State for the project-scoped cache middleware. The project is taken from
+/// the `:id` or `:project_id` path parameter.
+#[derive(Clone)]
+pub struct ResponseCache {
+    db: AppState,
+}
+
+impl ResponseCache {
+    pub fn per_project(db: AppState) -> Self {
+        Self { db }
+    }
+
+    fn scope(params: &Option<Path<HashMap<String, String>>>) -> Option<String> {
+        let Path(params) = params.as_ref()?;
+        params.get("project_id").or_else(|| params.get("id")).map(|id| project_scope(id))
+    }
+}
+
+/// Serve GETs from the per-project cache, rendering and storing the body on a
+/// miss. Apply with `route_layer` so path parameters are available.
+pub async fn cache_responses(
+    State(cache): State<ResponseCache>,
+    params: Option<Path<HashMap<String, String>>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let scope = match ResponseCache::scope(&params) {
+        Some(scope) if request.method() == Method::GET => scope,
+        _ => return next.run(request).await,
+    };
+    let revision = match revision(&cache.db, &scope).await {
+        Ok(revision) => revision,
+        Err(e) => {
+            warn!("{}", e);
+            return etag(request, next).await;
+        }
+    };
+
+    let key = request.uri().to_string();
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    {
+        let responses = RESPONSES.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = responses.get(&key).filter(|c| c.scope == scope && c.revision == revision) {
+            return cached_response(
+                if_none_match.as_ref(),
+                cached.etag.clone(),
+                cached.content_type.clone(),
+                cached.body.clone(),
+            );
+        }
+    }
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => return internal_error(e),
+    };
+    let tag = body_etag(&body);
+    let content_type = parts.headers.get(header::CONTENT_TYPE).cloned();
+
+    if body.len() <= MAX_CACHED_BODY {
+        store(
+            key,
+            CachedResponse {
+                scope,
+                revision,
+                etag: tag.clone(),
+                content_type: content_type.clone(),
+                body: body.clone(),
+                stored_at: Instant::now(),
+            },
+        );
+    }
+    cached_response(if_none_match.as_ref(), tag, content_type, body)
+}
+
+fn store(key: String, entry: CachedResponse) {
+    let mut responses = RESPONSES.write().unwrap_or_else(|e| e.into_inner());
+    if responses.len() >= MAX_ENTRIES && !responses.contains_key(&key) {
+        let oldest = responses
+            .iter()
+            .min_by_key(|(_, cached)| cached.stored_at)
+            .map(|(key, _)| key.clone());
+        if let Some(oldest) = oldest {
+            responses.remove(&oldest);
+        }
+    }
+    responses.insert(key, entry);
+}
+
+/// Bump the project's revision after a successful non-GET request. Apply with
+/// `route_layer` to routes whose `:id` is a project id.
+pub async fn invalidate_on_write(
+    State(cache): State<ResponseCache>,
+    params: Option<Path<HashMap<String, String>>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let is_write = !matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let response = next.run(request).await;
+    if is_write && response.status().is_success() {
+        if let Some(scope) = ResponseCache::scope(&params) {
+            invalidate(&cache.db, &scope).await;
+        }
+    }
+    response
+}
+
+/// Tag successful GET responses and answer matching `If-None-Match` with 304.
+/// The body is still built on every request; only the transfer is saved.
+pub async fn etag<B>(request: Request<B>, next: Next<B>) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => return internal_error(e),
+    };
+    let tag = body_etag(&body);
+    cached_response(
+        if_none_match.as_ref(),
+        tag,
+        parts.headers.get(header::CONTENT_TYPE).cloned(),
+        body,
+    )
+}
+
+/// Strong validator: a truncated SHA-256 of the body
+fn body_etag(body: &[u8]) -> HeaderValue {
+    let digest = Sha256::digest(body);
+    let tag = format!("\"{}\"", URL_SAFE_NO_PAD.encode(&digest[..16]));
+    HeaderValue::from_str(&tag).expect("base64url is a valid header value")
+}
+
+fn matches_etag(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(candidates) = if_none_match.to_str() else {
+        return false;
+    };
+    let etag = etag.to_str().unwrap_or_default();
+    candidates
+        .split(',')
+        .map(|candidate| candidate.trim())
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+fn cached_response(
+    if_none_match: Option<&HeaderValue>,
+    etag: HeaderValue,
+    content_type: Option<HeaderValue>,
+    body: Bytes,
+) -> Response {
+    let mut headers = HeaderMap::new();
+    // Clients may keep the body but must revalidate before using it
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+
+    if if_none_match.map_or(false, |value| matches_etag(value, &etag)) {
+        headers.insert(header::ETAG, etag);
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    headers.insert(header::ETAG, etag);
+    if let Some(content_type) = content_type {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    (StatusCode::OK, headers, Full::new(body)).into_response()
+}
+
+fn internal_error(e: impl std::fmt::Display) -> Response {
+    warn!("Failed to buffer response body: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware::from_fn_with_state, routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    static RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+    async fn topology() -> &'static str {
+        RENDERS.fetch_add(1, Ordering::SeqCst);
+        "{\"nodes\":[]}"
+    }
+
+    fn get_request(uri: &str, if_none_match: Option<&HeaderValue>) -> Request<Body> {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(value) = if_none_match {
+            builder = builder.header(header::IF_NONE_MATCH, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cached_until_project_invalidated() {
+        let db = Arc::new(crate::database::new_test().await.unwrap());
+        let app = Router::new()
+            .route("/projects/:id/network-topology", get(topology))
+            .route_layer(from_fn_with_state(ResponseCache::per_project(db.clone()), cache_responses));
+        let uri = "/projects/p1/network-topology";
+
+        let first = app.clone().oneshot(get_request(uri, None)).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let tag = first.headers().get(header::ETAG).cloned().unwrap();
+
+        let revalidated = app.clone().oneshot(get_request(uri, Some(&tag))).await.unwrap();
+        assert_eq!(revalidated.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(RENDERS.load(Ordering::SeqCst), 1);
+
+        invalidate(&db, &project_scope("p1")).await;
+        assert_eq!(revision(&db, &project_scope("p1")).await.unwrap(), 1);
+
+        let rebuilt = app.oneshot(get_request(uri, Some(&tag))).await.unwrap();
+        // Same payload, so the client's copy is still valid, but it was rebuilt
+        assert_eq!(rebuilt.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(RENDERS.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,12 +1,15 @@
 use axum::{
-    body::Body,
+    body::{Body, Bytes, HttpBody},
     extract::FromRequest,
     http::{Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
+    BoxError,
 };
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::utils::api_response::{helpers, ApiResponse};
 
@@ -69,6 +72,193 @@ pub async fn validate_request_size<B>(request: Request<B>, next: Next<B>) -> Res
     next.run(request).await
 }
 
+// ============================================================================
+// PAYLOAD VALIDATION
+// ============================================================================
+
+/// One rejected field: the JSON path, the rule it broke and a message
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub constraint: String,
+    pub message: String,
+}
+
+/// Field errors collected while validating a request payload
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn add(&mut self, field: &str, constraint: &str, message: impl Into<String>) {
+        self.0.push(FieldError {
+            field: field.to_string(),
+            constraint: constraint.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn required(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, "required", format!("{} must not be empty", field));
+        }
+    }
+
+    pub fn length(&mut self, field: &str, value: &str, max: usize) {
+        if value.chars().count() > max {
+            self.add(field, "max_length", format!("{} must be at most {} characters", field, max));
+        }
+    }
+
+    /// Optional fields are only checked when present
+    pub fn optional_length(&mut self, field: &str, value: &Option<String>, max: usize) {
+        if let Some(value) = value {
+            self.length(field, value, max);
+        }
+    }
+
+    /// Optional fields that are present must not be blank
+    pub fn not_blank(&mut self, field: &str, value: &Option<String>) {
+        if let Some(value) = value {
+            self.required(field, value);
+        }
+    }
+
+    pub fn range<N: PartialOrd + std::fmt::Display>(&mut self, field: &str, value: N, min: N, max: N) {
+        if value < min || value > max {
+            self.add(field, "range", format!("{} must be between {} and {}", field, min, max));
+        }
+    }
+
+    /// Record ids in `table:id` form
+    pub fn record_id(&mut self, field: &str, value: &str) {
+        if surrealdb::sql::thing(value).is_err() {
+            self.add(field, "record_id", format!("{} must be a record id like table:id", field));
+        }
+    }
+
+    pub fn ip_address(&mut self, field: &str, value: &Option<String>) {
+        if let Some(value) = value {
+            if value.trim().parse::<std::net::IpAddr>().is_err() {
+                self.add(field, "ip_address", format!("{} must be an IPv4 or IPv6 address", field));
+            }
+        }
+    }
+
+    pub fn tags(&mut self, field: &str, tags: &[String]) {
+        const MAX_TAGS: usize = 50;
+        if tags.len() > MAX_TAGS {
+            self.add(field, "max_items", format!("{} must have at most {} entries", field, MAX_TAGS));
+        }
+        for (i, tag) in tags.iter().enumerate() {
+            let path = format!("{}[{}]", field, i);
+            self.required(&path, tag);
+            self.length(&path, tag, 64);
+        }
+    }
+
+    /// Key-value tags: keys as in [`Self::tags`], values up to 255 characters
+    pub fn tag_map(&mut self, field: &str, tags: &BTreeMap<String, String>) {
+        let keys: Vec<String> = tags.keys().cloned().collect();
+        self.tags(field, &keys);
+        for (key, value) in tags {
+            self.length(&format!("{}.{}", field, key), value, 255);
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationRejection> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationRejection(self.0))
+        }
+    }
+}
+
+/// Per-endpoint payload rules, checked by `ValidatedJson` after deserialization
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationRejection>;
+}
+
+/// Rejected payload; responds 400 with the standard envelope and
+/// `error.details.fields` listing every field error
+#[derive(Debug)]
+pub struct ValidationRejection(pub Vec<FieldError>);
+
+impl ValidationRejection {
+    fn single(field: &str, constraint: &str, message: impl Into<String>) -> Self {
+        let mut errors = FieldErrors::default();
+        errors.add(field, constraint, message);
+        ValidationRejection(errors.0)
+    }
+
+    /// Map a deserialization failure to the field it happened at
+    fn from_serde(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let path = error.path().to_string();
+        let inner = error.into_inner();
+        let message = inner.to_string();
+
+        if inner.is_syntax() || inner.is_eof() {
+            return Self::single("body", "json", format!("Malformed JSON: {}", message));
+        }
+
+        // serde reports a missing field at its parent's path
+        if let Some(name) = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next())
+        {
+            let field = if path == "." { name.to_string() } else { format!("{}.{}", path, name) };
+            return Self::single(&field, "required", format!("{} is required", field));
+        }
+
+        let constraint = if message.starts_with("unknown variant") {
+            "enum"
+        } else if message.starts_with("invalid type") {
+            "type"
+        } else {
+            "format"
+        };
+        let field = if path == "." { "body".to_string() } else { path };
+        Self::single(&field, constraint, message)
+    }
+}
+
+impl IntoResponse for ValidationRejection {
+    fn into_response(self) -> Response {
+        helpers::validation_error("Request validation failed", serde_json::json!({ "fields": self.0 }))
+            .into_response()
+    }
+}
+
+/// JSON body extractor that reports serde failures and `Validate` rule
+/// violations as field-level 400s instead of letting handlers see bad data
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S, B> FromRequest<S, B> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let deserializer = &mut serde_json::Deserializer::from_slice(&bytes);
+        let value: T = serde_path_to_error::deserialize(deserializer)
+            .map_err(|e| ValidationRejection::from_serde(e).into_response())?;
+        value.validate().map_err(IntoResponse::into_response)?;
+
+        Ok(ValidatedJson(value))
+    }
+}
+
 /// Common input validation functions
 pub mod validators {
     use once_cell::sync::Lazy;
@@ -114,6 +304,7 @@ pub mod validators {
 #[cfg(test)]
 mod tests {
     use super::validators::*;
+    use super::*;
 
     #[test]
     fn test_email_validation() {
@@ -146,4 +337,62 @@ mod tests {
         assert!(!validate_string_length("", 1, 10));
         assert!(!validate_string_length("too long string", 1, 10));
     }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Payload {
+        #[allow(dead_code)]
+        name: String,
+        #[allow(dead_code)]
+        nested: Nested,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Nested {
+        #[allow(dead_code)]
+        count: u32,
+    }
+
+    fn rejection(body: &str) -> FieldError {
+        let deserializer = &mut serde_json::Deserializer::from_str(body);
+        let error = serde_path_to_error::deserialize::<_, Payload>(deserializer).unwrap_err();
+        ValidationRejection::from_serde(error).0.remove(0)
+    }
+
+    #[test]
+    fn test_serde_failures_map_to_fields() {
+        let error = rejection(r#"{"name": "a", "nested": {"count": "x"}}"#);
+        assert_eq!((error.field.as_str(), error.constraint.as_str()), ("nested.count", "type"));
+
+        let error = rejection(r#"{"name": "a", "nested": {}}"#);
+        assert_eq!((error.field.as_str(), error.constraint.as_str()), ("nested.count", "required"));
+
+        let error = rejection(r#"{"name": "a""#);
+        assert_eq!((error.field.as_str(), error.constraint.as_str()), ("body", "json"));
+    }
+
+    #[test]
+    fn test_field_errors_collect_rules() {
+        let mut errors = FieldErrors::default();
+        errors.required("title", "  ");
+        errors.length("title", &"x".repeat(300), 200);
+        errors.range("vlan_id", 5000, 1, 4094);
+        errors.record_id("related_asset", "not a record");
+        errors.tags("tags", &["ok".to_string(), "".to_string()]);
+        errors.ip_address("ip_address", &Some("10.0.0.300".to_string()));
+        errors.ip_address("ip_address", &Some("10.0.0.30".to_string()));
+        let ValidationRejection(fields) = errors.into_result().unwrap_err();
+        let constraints: Vec<_> = fields.iter().map(|f| (f.field.as_str(), f.constraint.as_str())).collect();
+        assert_eq!(
+            constraints,
+            vec![
+                ("title", "required"),
+                ("title", "max_length"),
+                ("vlan_id", "range"),
+                ("related_asset", "record_id"),
+                ("tags[1]", "required"),
+                ("ip_address", "ip_address"),
+            ]
+        );
+        assert!(FieldErrors::default().into_result().is_ok());
+    }
 }
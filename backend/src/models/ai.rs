@@ -0,0 +1,358 @@
+//! AI provider models
+//!
+//! LLM provider configuration, per-tenant model selection and token usage
+//! accounting. API keys are only ever stored encrypted and are never
+//! returned by the API.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// ============================================================================
+// PROVIDERS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LlmProviderKind {
+    /// Any server speaking the OpenAI chat completions API (OpenAI, Azure
+    /// OpenAI proxies, vLLM, LM Studio, LiteLLM, ...)
+    OpenaiCompatible,
+    /// Local Ollama daemon
+    Ollama,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmProviderConfig {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub kind: LlmProviderKind,
+    pub base_url: String,
+    /// Encrypted with the server secrets key (see services::llm::secrets)
+    pub api_key_encrypted: Option<String>,
+    pub default_model: String,
+    /// Models offered for selection; empty means "ask the provider"
+    #[serde(default)]
+    pub models: Vec<String>,
+    pub enabled: bool,
+    pub timeout_seconds: u64,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Provider as returned by the API (no key material)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmProviderView {
+    pub id: String,
+    pub name: String,
+    pub kind: LlmProviderKind,
+    pub base_url: String,
+    pub has_api_key: bool,
+    pub default_model: String,
+    pub models: Vec<String>,
+    pub enabled: bool,
+    pub timeout_seconds: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&LlmProviderConfig> for LlmProviderView {
+    fn from(config: &LlmProviderConfig) -> Self {
+        Self {
+            id: config
+                .id
+                .as_ref()
+                .map(|t| t.id.to_raw())
+                .unwrap_or_default(),
+            name: config.name.clone(),
+            kind: config.kind,
+            base_url: config.base_url.clone(),
+            has_api_key: config.api_key_encrypted.is_some(),
+            default_model: config.default_model.clone(),
+            models: config.models.clone(),
+            enabled: config.enabled,
+            timeout_seconds: config.timeout_seconds,
+            created_at: config.created_at,
+            updated_at: config.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateLlmProviderRequest {
+    pub name: String,
+    pub kind: LlmProviderKind,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub default_model: String,
+    #[serde(default)]
+    pub models: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateLlmProviderRequest {
+    pub name: Option<String>,
+    pub base_url: Option<String>,
+    /// New key; an empty string removes the stored key
+    pub api_key: Option<String>,
+    pub default_model: Option<String>,
+    pub models: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+    pub timeout_seconds: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// ============================================================================
+// TENANT MODEL SELECTION
+// ============================================================================
+
+/// Which provider/model a tenant uses. Stored as `llm_tenant_settings:<tenant>`,
+/// with `llm_tenant_settings:default` applying to tenants without their own row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantModelSelection {
+    pub id: Option<Thing>,
+    pub provider_id: Thing,
+    pub model: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Tokens per calendar month; requests are refused once exhausted
+    pub monthly_token_budget: Option<u64>,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetTenantModelRequest {
+    pub provider_id: String,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub monthly_token_budget: Option<u64>,
+}
+
+// ============================================================================
+// CHAT
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatCompletionRequest {
+    pub messages: Vec<ChatMessage>,
+    /// Override the tenant's provider/model (requires ai:manage)
+    pub provider_id: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Free-form tag for usage reports, e.g. "ticket_summary"
+    pub feature: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    /// True when the provider didn't report usage and it was approximated
+    #[serde(default)]
+    pub estimated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionResponse {
+    pub content: String,
+    pub provider: String,
+    pub model: String,
+    pub usage: TokenUsage,
+    pub finish_reason: Option<String>,
+}
+
+// ============================================================================
+// USAGE ACCOUNTING
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmUsageRecord {
+    pub id: Option<Thing>,
+    pub tenant_id: Option<String>,
+    pub user_id: String,
+    pub provider_id: Thing,
+    pub model: String,
+    pub feature: Option<String>,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub estimated: bool,
+    pub streamed: bool,
+    pub latency_ms: u64,
+    pub success: bool,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmUsageSummary {
+    pub tenant_id: Option<String>,
+    pub since: DateTime<Utc>,
+    pub requests: u64,
+    pub failed_requests: u64,
+    pub total_tokens: u64,
+    pub monthly_token_budget: Option<u64>,
+    pub month_to_date_tokens: u64,
+    pub by_model: Vec<ModelUsage>,
+}
+
+// ============================================================================
+// AGENT TOOLS
+// ============================================================================
+
+/// Tool advertised to the assistant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentToolSpec {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the tool's arguments
+    pub parameters: serde_json::Value,
+    /// Mutating tools only run after a human approves the action
+    pub mutating: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentActionStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Executed,
+    Failed,
+}
+
+/// One tool invocation requested by the assistant (table `agent_action`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentAction {
+    pub id: Option<Thing>,
+    pub agent: String,
+    /// Tool name
+    pub action_type: String,
+    /// Resource the tool reads or changes, e.g. "cmdb" or "hld_projects:abc"
+    pub target: String,
+    pub parameters: serde_json::Value,
+    /// 1-100
+    pub risk_score: u32,
+    #[serde(default)]
+    pub risk_factors: Vec<String>,
+    pub status: AgentActionStatus,
+    pub requires_approval: bool,
+    pub session_id: String,
+    /// Thought log entry of the model turn that asked for the tool
+    pub thought_log: Option<Thing>,
+    pub requested_by: String,
+    pub tenant_id: Option<String>,
+    pub approver: Option<String>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub decision_comment: Option<String>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One model turn of an assistant session (table `ai_thought_log`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiThoughtLog {
+    pub id: Option<Thing>,
+    pub session_id: String,
+    pub agent: String,
+    pub user: Option<String>,
+    pub tenant_id: Option<String>,
+    pub input: String,
+    pub chain_of_thought: String,
+    pub output: String,
+    pub model: String,
+    pub tokens_input: u64,
+    pub tokens_output: u64,
+    pub latency_ms: u64,
+    #[serde(default)]
+    pub context_chunks: Vec<String>,
+    pub feedback: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentRunRequest {
+    pub message: String,
+    /// Continue an earlier session; a new one is started when omitted
+    pub session_id: Option<String>,
+    /// Prior turns as returned in `AgentRunResponse::transcript`
+    #[serde(default)]
+    pub history: Vec<ChatMessage>,
+    pub provider_id: Option<String>,
+    pub model: Option<String>,
+    /// Model turns allowed before giving up (default 6, max 12)
+    pub max_steps: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AgentRunStatus {
+    Completed,
+    AwaitingApproval,
+    StepLimitReached,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentStep {
+    pub tool: String,
+    pub arguments: serde_json::Value,
+    pub action_id: String,
+    pub status: AgentActionStatus,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRunResponse {
+    pub session_id: String,
+    pub status: AgentRunStatus,
+    pub answer: Option<String>,
+    pub steps: Vec<AgentStep>,
+    /// Set when a mutating tool is waiting for approval
+    pub pending_action: Option<AgentAction>,
+    /// Conversation so far, to send back as `history` on the next turn
+    pub transcript: Vec<ChatMessage>,
+    pub usage: TokenUsage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentActionDecisionRequest {
+    pub approve: bool,
+    pub comment: Option<String>,
+}
@@ -0,0 +1,204 @@
+// Assessment Framework Models
+// Rule packs evaluated against the parsed VM inventory and the CMDB
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// =============================================================================
+// RULE PACK MODELS
+// =============================================================================
+
+/// A named, versioned set of assessment rules.
+///
+/// Packs are authored in YAML; built-in packs ship with the backend and
+/// custom packs are imported per tenant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePack {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// Stable slug, e.g. "hyperv-readiness"
+    pub pack_id: String,
+    pub name: String,
+    #[serde(default = "default_pack_version")]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Owning tenant; `None` for built-in packs visible to everyone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    pub builtin: bool,
+    pub rules: Vec<AssessmentRule>,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+fn default_pack_version() -> String {
+    "1.0".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssessmentRule {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub severity: FindingSeverity,
+    #[serde(default)]
+    pub applies_to: SubjectType,
+    /// Condition describing a *violation*; subjects matching it produce a finding
+    pub condition: RuleCondition,
+    #[serde(default)]
+    pub remediation: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl FindingSeverity {
+    /// Weight used when scoring a pack
+    pub fn weight(&self) -> u32 {
+        match self {
+            FindingSeverity::Info => 0,
+            FindingSeverity::Low => 1,
+            FindingSeverity::Medium => 3,
+            FindingSeverity::High => 5,
+            FindingSeverity::Critical => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectType {
+    Vm,
+    Ci,
+    #[default]
+    Any,
+}
+
+/// Boolean condition tree evaluated against a subject's fields.
+///
+/// ```yaml
+/// condition:
+///   all:
+///     - { field: os, op: contains, value: "2008" }
+///     - not: { field: powerstate, op: equals, value: poweredOff }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleCondition {
+    All { all: Vec<RuleCondition> },
+    Any { any: Vec<RuleCondition> },
+    Not { not: Box<RuleCondition> },
+    Field(FieldCondition),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldCondition {
+    /// Dotted path into the subject, e.g. "attributes.tpm_version"
+    pub field: String,
+    pub op: ConditionOperator,
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionOperator {
+    Equals,
+    NotEquals,
+    Contains,
+    NotContains,
+    Matches,
+    In,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Exists,
+    Missing,
+    /// Date field is earlier than `value` (RFC 3339 date or "now")
+    Before,
+}
+
+// =============================================================================
+// ASSESSMENT RESULT MODELS
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssessmentRun {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<Thing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub pack_scores: Vec<PackScore>,
+    pub findings: Vec<AssessmentFinding>,
+    /// Weighted score across all packs (0-100, higher is better)
+    pub overall_score: f64,
+    pub subjects_evaluated: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackScore {
+    pub pack_id: String,
+    pub name: String,
+    pub version: String,
+    pub rules_evaluated: usize,
+    pub checks: usize,
+    pub failures: usize,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssessmentFinding {
+    pub pack_id: String,
+    pub rule_id: String,
+    pub title: String,
+    pub severity: FindingSeverity,
+    pub subject_type: SubjectType,
+    pub subject_name: String,
+    pub remediation: String,
+}
+
+/// Something rules are evaluated against: a VM or a configuration item
+#[derive(Debug, Clone)]
+pub struct AssessmentSubject {
+    pub subject_type: SubjectType,
+    pub name: String,
+    pub data: serde_json::Value,
+}
+
+// =============================================================================
+// REQUEST MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct RunAssessmentRequest {
+    /// Migration wizard project whose VM inventory is assessed
+    pub project_id: Option<String>,
+    /// Also evaluate CMDB configuration items
+    #[serde(default)]
+    pub include_cmdb: bool,
+    /// Packs to run; all packs visible to the tenant when omitted
+    pub pack_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportRulePackRequest {
+    /// Rule pack definition in YAML
+    pub yaml: String,
+}
@@ -291,6 +291,8 @@ pub enum AuditEventType {
     SystemConfig,
     DataExport,
     DataImport,
+    DataPurge,
+    DataErasure,
 }
 
 // ============================================================================
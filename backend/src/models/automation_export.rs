@@ -0,0 +1,141 @@
+// Archer - Automation Export Models
+// The placement plan resolved for implementation automation: target
+// clusters, destination networks and VM shells with their wave, new IP and
+// VLAN, plus the generated file bundles built from it
+
+use serde::{Deserialize, Serialize};
+
+use super::project_models::HypervisorType;
+
+/// Placement plan of a migration wizard project, resolved against its
+/// network mappings and wave plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationPlan {
+    pub project_id: String,
+    pub project_name: String,
+    pub clusters: Vec<PlanCluster>,
+    pub networks: Vec<PlanNetwork>,
+    pub waves: Vec<PlanWave>,
+    /// Placed VMs only; unplaced VMs are reported in `warnings`
+    pub vms: Vec<PlanVm>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanCluster {
+    /// `migration_wizard_cluster` record key
+    pub key: String,
+    pub name: String,
+    /// From the linked destination cluster, else the export's default
+    pub hypervisor: HypervisorType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_cluster_id: Option<String>,
+    pub total_cores: i32,
+    pub memory_gb: i32,
+    pub storage_tb: f64,
+}
+
+/// A destination network from the network mappings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanNetwork {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vlan_id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subnet: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+    #[serde(default)]
+    pub dns_servers: Vec<String>,
+    pub source_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_subnet: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanWave {
+    /// `migration_wave` record key
+    pub key: String,
+    pub name: String,
+    pub sequence: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_window: Option<String>,
+}
+
+/// A VM shell on its target cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanVm {
+    /// `migration_wizard_vm` record key
+    pub key: String,
+    pub name: String,
+    /// Key of the target cluster
+    pub cluster: String,
+    /// Key of the wave; `None` until the VM is in a wave
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wave: Option<String>,
+    pub cpus: i32,
+    pub memory_mb: i32,
+    pub storage_gb: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_ip: Option<String>,
+    /// Destination network name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vlan_id: Option<i32>,
+    /// Address in the destination subnet, keeping the host part of the source address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_ip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix_length: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+    #[serde(default)]
+    pub dns_servers: Vec<String>,
+}
+
+/// IaC dialect for the exported scaffold
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IacTarget {
+    /// Bicep for Azure Local clusters, Terraform for the rest
+    #[default]
+    Auto,
+    Terraform,
+    Bicep,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AutomationExportQuery {
+    /// json (default) or zip
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub target: IacTarget,
+    /// Hypervisor of clusters not linked to a destination cluster; defaults to Hyper-V
+    #[serde(default)]
+    pub hypervisor: Option<HypervisorType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path inside the bundle, e.g. `terraform/main.tf`
+    pub path: String,
+    pub content: String,
+}
+
+/// Generated files, returned as JSON or downloaded as a zip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationBundle {
+    pub name: String,
+    pub files: Vec<BundleFile>,
+    pub warnings: Vec<String>,
+}
+
+impl AutomationBundle {
+    pub fn file(&self, path: &str) -> Option<&BundleFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+}
@@ -0,0 +1,113 @@
+// Destination Cluster Build Checklist Models
+// Readiness items a cluster must clear before waves may cut over onto it
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Checklist for one destination cluster, stored under the cluster's key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildChecklist {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub cluster_id: Thing,
+    pub items: Vec<BuildChecklistItem>,
+    /// Every item passed or was signed off
+    pub complete: bool,
+    /// Firmware version every node must run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub firmware_baseline: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildChecklistItem {
+    pub kind: ChecklistItemKind,
+    pub title: String,
+    /// How the last evaluation was decided
+    pub verification: ChecklistVerification,
+    pub status: ChecklistItemStatus,
+    /// What the automated check found, or why it needs a person
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_off_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_off_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl BuildChecklistItem {
+    pub fn is_done(&self) -> bool {
+        matches!(self.status, ChecklistItemStatus::Passed | ChecklistItemStatus::SignedOff)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistItemKind {
+    NetworkProfileApplied,
+    FirmwareBaseline,
+    StorageResiliency,
+    ClusterWitness,
+}
+
+impl ChecklistItemKind {
+    pub const ALL: [ChecklistItemKind; 4] = [
+        ChecklistItemKind::NetworkProfileApplied,
+        ChecklistItemKind::FirmwareBaseline,
+        ChecklistItemKind::StorageResiliency,
+        ChecklistItemKind::ClusterWitness,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ChecklistItemKind::NetworkProfileApplied => "Network profile applied",
+            ChecklistItemKind::FirmwareBaseline => "Node firmware matches baseline",
+            ChecklistItemKind::StorageResiliency => "Storage resiliency configured",
+            ChecklistItemKind::ClusterWitness => "Cluster witness set",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistVerification {
+    /// Decided from Archer or integration data
+    Automated,
+    /// No data to decide from; needs a sign-off
+    Manual,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistItemStatus {
+    Pending,
+    Passed,
+    Failed,
+    SignedOff,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignOffChecklistItemRequest {
+    pub signed_off_by: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateChecklistRequest {
+    pub firmware_baseline: Option<String>,
+}
+
+/// A wave target cluster whose build checklist is not complete
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterBuildBlocker {
+    pub cluster_id: String,
+    pub cluster_name: String,
+    pub destination_cluster_id: String,
+    pub open_items: Vec<String>,
+}
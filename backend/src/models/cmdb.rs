@@ -4,7 +4,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use super::contract::ContractCoverage;
 
 // ============================================================================
 // CONFIGURATION ITEM (CI) - Base Model
@@ -51,6 +53,9 @@ pub struct ConfigurationItem {
     pub warranty_expiry: Option<DateTime<Utc>>,
     pub end_of_life: Option<DateTime<Utc>>,
     pub decommission_date: Option<DateTime<Utc>>,
+    /// Purchase and depreciation data (hardware CIs only)
+    #[serde(default)]
+    pub financials: Option<AssetFinancials>,
     /// Tags for categorization
     pub tags: Vec<String>,
     /// Audit
@@ -153,6 +158,43 @@ impl Default for DiscoverySource {
     }
 }
 
+// ============================================================================
+// ASSET FINANCIALS (hardware CIs)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetFinancials {
+    pub purchase_cost: f64,
+    pub purchase_date: DateTime<Utc>,
+    #[serde(default)]
+    pub depreciation_method: DepreciationMethod,
+    /// Depreciation period; the asset is due for refresh when it ends
+    pub useful_life_months: u32,
+    /// Residual value at the end of the useful life
+    #[serde(default)]
+    pub salvage_value: f64,
+    /// Multiple of the straight-line rate for declining balance (2.0 = double declining)
+    #[serde(default)]
+    pub declining_factor: Option<f64>,
+    /// ISO 4217 code; amounts without one are reported as USD
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DepreciationMethod {
+    #[serde(rename = "STRAIGHT_LINE")]
+    StraightLine,
+    #[serde(rename = "DECLINING_BALANCE")]
+    DecliningBalance,
+}
+
+impl Default for DepreciationMethod {
+    fn default() -> Self {
+        DepreciationMethod::StraightLine
+    }
+}
+
 // ============================================================================
 // CI RELATIONSHIPS (Graph Edges)
 // ============================================================================
@@ -424,6 +466,8 @@ pub struct CreateCIRequest {
     pub install_date: Option<DateTime<Utc>>,
     pub warranty_expiry: Option<DateTime<Utc>>,
     pub end_of_life: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub financials: Option<AssetFinancials>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -448,6 +492,8 @@ pub struct UpdateCIRequest {
     pub install_date: Option<DateTime<Utc>>,
     pub warranty_expiry: Option<DateTime<Utc>>,
     pub end_of_life: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub financials: Option<AssetFinancials>,
     pub change_reason: Option<String>,
 }
 
@@ -495,6 +541,9 @@ pub struct CIDetailResponse {
     pub relationships: Vec<CIRelationshipExpanded>,
     pub history: Vec<CIHistory>,
     pub linked_tickets: Vec<Thing>,
+    /// Support entitlement, current contracts first
+    #[serde(default)]
+    pub contracts: Vec<ContractCoverage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -524,3 +573,71 @@ pub struct ImpactedCI {
     pub path: Vec<String>,
     pub relationship_type: RelationshipType,
 }
+
+// ============================================================================
+// DEPRECIATION REPORT
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepreciationReportQuery {
+    /// Valuation date (defaults to now)
+    pub as_of: Option<DateTime<Utc>>,
+    /// Months ahead to forecast refreshes (default 12)
+    pub horizon_months: Option<u32>,
+    pub location: Option<String>,
+    pub environment: Option<String>,
+}
+
+/// Book value of one hardware CI at the report date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetBookValue {
+    pub id: Option<Thing>,
+    pub ci_id: String,
+    pub name: String,
+    pub status: CIStatus,
+    pub location: Option<String>,
+    pub currency: String,
+    pub purchase_cost: f64,
+    pub purchase_date: DateTime<Utc>,
+    pub depreciation_method: DepreciationMethod,
+    pub salvage_value: f64,
+    pub book_value: f64,
+    pub accumulated_depreciation: f64,
+    /// Depreciation charged in the month containing the report date
+    pub monthly_depreciation: f64,
+    pub depreciation_end: DateTime<Utc>,
+    pub months_remaining: u32,
+    pub fully_depreciated: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DepreciationTotals {
+    pub asset_count: u32,
+    pub purchase_cost: f64,
+    pub book_value: f64,
+    pub accumulated_depreciation: f64,
+    pub monthly_depreciation: f64,
+}
+
+/// Hardware whose depreciation ends in a given month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshForecastMonth {
+    /// "YYYY-MM"
+    pub month: String,
+    pub asset_count: u32,
+    /// Purchase cost of the assets, per currency, as a replacement budget estimate
+    pub replacement_cost: BTreeMap<String, f64>,
+    pub ci_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepreciationReport {
+    pub as_of: DateTime<Utc>,
+    pub horizon_months: u32,
+    pub assets: Vec<AssetBookValue>,
+    /// Totals per currency
+    pub totals: BTreeMap<String, DepreciationTotals>,
+    /// In-service hardware already fully depreciated
+    pub refresh_overdue: Vec<String>,
+    pub refresh_forecast: Vec<RefreshForecastMonth>,
+}
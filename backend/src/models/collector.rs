@@ -0,0 +1,183 @@
+// Archer - Collector Models
+// Remote collector appliances that stream inventory snapshots and metric
+// batches over gRPC, and the VM metric samples they send
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Stored in `collector`. Only a hash of the token is kept; the client
+/// certificate is pinned by its SHA-256 fingerprint when one is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collector {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub name: String,
+    /// Raw ids of the migration wizard projects the collector may write to
+    pub project_ids: Vec<String>,
+    pub token_hash: String,
+    /// Lowercase hex SHA-256 of the client certificate (DER)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate_fingerprint: Option<String>,
+    pub enabled: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_snapshot_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub settings: CollectorAgentSettings,
+    /// Reported by the collector when it pulls its settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+impl Collector {
+    pub fn may_write(&self, project_id: &str) -> bool {
+        self.project_ids.iter().any(|id| id == project_id)
+    }
+}
+
+/// What a collector agent does; pulled by the agent over gRPC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectorAgentSettings {
+    /// Project snapshots are written to; must be one of `project_ids`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(default = "default_snapshot_interval")]
+    pub snapshot_interval_minutes: u32,
+    #[serde(default = "default_enabled")]
+    pub vcenter_enabled: bool,
+    #[serde(default = "default_enabled")]
+    pub nutanix_enabled: bool,
+    #[serde(default = "default_enabled")]
+    pub active_directory_enabled: bool,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl Default for CollectorAgentSettings {
+    fn default() -> Self {
+        Self {
+            project_id: None,
+            snapshot_interval_minutes: default_snapshot_interval(),
+            vcenter_enabled: true,
+            nutanix_enabled: true,
+            active_directory_enabled: true,
+            paused: false,
+        }
+    }
+}
+
+fn default_snapshot_interval() -> u32 {
+    360
+}
+
+/// Collector as returned by the API (no token hash)
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectorView {
+    pub id: String,
+    pub name: String,
+    pub project_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_fingerprint: Option<String>,
+    pub enabled: bool,
+    /// Only set in the response that created or rotated it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_snapshot_at: Option<DateTime<Utc>>,
+    pub settings: CollectorAgentSettings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+impl From<&Collector> for CollectorView {
+    fn from(c: &Collector) -> Self {
+        Self {
+            id: c.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+            name: c.name.clone(),
+            project_ids: c.project_ids.clone(),
+            certificate_fingerprint: c.certificate_fingerprint.clone(),
+            enabled: c.enabled,
+            token: None,
+            created_by: c.created_by.clone(),
+            created_at: c.created_at,
+            updated_at: c.updated_at,
+            last_seen_at: c.last_seen_at,
+            last_snapshot_at: c.last_snapshot_at,
+            settings: c.settings.clone(),
+            agent_version: c.agent_version.clone(),
+            hostname: c.hostname.clone(),
+        }
+    }
+}
+
+/// The token is generated and returned once
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateCollectorRequest {
+    pub name: String,
+    #[serde(default)]
+    pub project_ids: Vec<String>,
+    #[serde(default)]
+    pub certificate_fingerprint: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub settings: Option<CollectorAgentSettings>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Fields left out are kept; `rotate_token` issues a new token
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateCollectorRequest {
+    pub name: Option<String>,
+    pub project_ids: Option<Vec<String>>,
+    /// An empty string removes the pin
+    pub certificate_fingerprint: Option<String>,
+    pub enabled: Option<bool>,
+    /// Replaces the agent settings as a whole
+    pub settings: Option<CollectorAgentSettings>,
+    #[serde(default)]
+    pub rotate_token: bool,
+}
+
+/// One utilization sample of a VM, stored in `vm_metric_sample`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmMetricSample {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub collector_id: Thing,
+    pub vm_name: String,
+    pub captured_at: DateTime<Utc>,
+    pub cpu_percent: f64,
+    pub memory_percent: f64,
+    #[serde(default)]
+    pub disk_read_kbps: f64,
+    #[serde(default)]
+    pub disk_write_kbps: f64,
+    #[serde(default)]
+    pub network_kbps: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VmMetricQuery {
+    pub vm_name: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
@@ -0,0 +1,129 @@
+// Component Classification Review Models
+// Low-confidence classifier results waiting for a person to confirm them,
+// and the per-tenant corrections the classifier learns from
+
+use chrono::{DateTime, Utc};
+use core_engine::hardware_parser::component_classifier::{
+    Classification, ClassificationCorrection, ComponentSubcategory, PrimaryCategory,
+};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// A classified item whose confidence was below the review threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentReviewItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    pub part_number: String,
+    pub description: String,
+    /// Where the item came from, e.g. a basket file name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub suggestion: Classification,
+    pub status: ReviewStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_primary_category: Option<PrimaryCategory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_subcategory: Option<ComponentSubcategory>,
+    /// Username, or "classifier" when a later correction made it confident
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending,
+    Resolved,
+}
+
+/// A correction made by someone in a tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassifierCorrection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    pub part_number: String,
+    pub description: String,
+    pub primary_category: PrimaryCategory,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_subcategory: Option<ComponentSubcategory>,
+    pub corrected_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ClassifierCorrection {
+    pub fn to_correction(&self) -> ClassificationCorrection {
+        ClassificationCorrection {
+            part_number: self.part_number.clone(),
+            description: self.description.clone(),
+            primary_category: self.primary_category,
+            component_subcategory: self.component_subcategory,
+        }
+    }
+}
+
+// =============================================================================
+// REQUEST/RESPONSE MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ClassifyComponentsRequest {
+    /// Basket vendor, used to pick part-number shapes and vendor terms
+    pub vendor: Option<String>,
+    pub source: Option<String>,
+    pub items: Vec<ComponentLine>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComponentLine {
+    #[serde(default)]
+    pub part_number: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClassifiedLine {
+    pub part_number: String,
+    pub description: String,
+    pub classification: Classification,
+    /// Set when the item was queued for review
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_item_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClassifyComponentsResponse {
+    pub items: Vec<ClassifiedLine>,
+    pub queued_for_review: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewQueueQuery {
+    pub status: Option<ReviewStatus>,
+}
+
+/// The category a reviewer settled on
+#[derive(Debug, Deserialize)]
+pub struct ResolveReviewItemRequest {
+    pub primary_category: PrimaryCategory,
+    pub component_subcategory: Option<ComponentSubcategory>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolveReviewItemResponse {
+    pub item: ComponentReviewItem,
+    pub correction: ClassifierCorrection,
+    /// Other pending items the correction made confident enough to close
+    pub auto_resolved: usize,
+}
@@ -0,0 +1,220 @@
+// Archer ITSM - Contracts & Vendor Support Agreements
+// Support, maintenance and warranty contracts covering CIs, with expiry
+// alerting and the coverage agents see on asset detail
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Days before expiry at which owners are alerted, unless a contract sets its own
+pub const DEFAULT_ALERT_DAYS: [u32; 3] = [90, 30, 7];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContractType {
+    Support,
+    Maintenance,
+    Warranty,
+    License,
+    Subscription,
+    Lease,
+}
+
+/// Derived from the coverage dates, never stored
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContractStatus {
+    /// Coverage has not started yet
+    Pending,
+    Active,
+    /// Ends within the first alert threshold
+    Expiring,
+    Expired,
+}
+
+/// How to reach the vendor under this contract
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SupportContact {
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub portal_url: Option<String>,
+}
+
+/// Stored in `contracts`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contract {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub vendor: String,
+    /// Vendor's reference, quoted when opening a support case
+    #[serde(default)]
+    pub contract_number: Option<String>,
+    pub contract_type: ContractType,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// Total contract value
+    #[serde(default)]
+    pub cost: Option<f64>,
+    /// ISO 4217 code
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Entitlement, e.g. "24x7, 4h onsite"
+    #[serde(default)]
+    pub coverage_level: Option<String>,
+    #[serde(default)]
+    pub response_time_hours: Option<u32>,
+    #[serde(default)]
+    pub support_contact: SupportContact,
+    #[serde(default)]
+    pub covered_cis: Vec<Thing>,
+    /// Who is alerted before expiry: user record ids or email addresses
+    #[serde(default)]
+    pub notify: Vec<String>,
+    /// Days before `end_date` to alert at
+    #[serde(default = "default_alert_days")]
+    pub alert_days: Vec<u32>,
+    /// Thresholds already alerted for the current `end_date`
+    #[serde(default)]
+    pub alerts_sent: Vec<u32>,
+    #[serde(default)]
+    pub auto_renew: bool,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub tenant_id: Option<Thing>,
+}
+
+pub fn default_alert_days() -> Vec<u32> {
+    DEFAULT_ALERT_DAYS.to_vec()
+}
+
+impl Contract {
+    /// Whole days until `end_date`, negative once expired
+    pub fn days_remaining(&self, now: DateTime<Utc>) -> i64 {
+        (self.end_date - now).num_days()
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> ContractStatus {
+        let warn_days = self.alert_days.iter().max().copied().unwrap_or(0) as i64;
+        if now < self.start_date {
+            ContractStatus::Pending
+        } else if now >= self.end_date {
+            ContractStatus::Expired
+        } else if self.days_remaining(now) < warn_days {
+            ContractStatus::Expiring
+        } else {
+            ContractStatus::Active
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateContractRequest {
+    pub name: String,
+    pub vendor: String,
+    pub contract_number: Option<String>,
+    pub contract_type: ContractType,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub cost: Option<f64>,
+    pub currency: Option<String>,
+    pub coverage_level: Option<String>,
+    pub response_time_hours: Option<u32>,
+    #[serde(default)]
+    pub support_contact: SupportContact,
+    #[serde(default)]
+    pub covered_cis: Vec<String>,
+    #[serde(default)]
+    pub notify: Vec<String>,
+    pub alert_days: Option<Vec<u32>>,
+    #[serde(default)]
+    pub auto_renew: bool,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateContractRequest {
+    pub name: Option<String>,
+    pub vendor: Option<String>,
+    pub contract_number: Option<String>,
+    pub contract_type: Option<ContractType>,
+    pub start_date: Option<DateTime<Utc>>,
+    /// Moving the end date (a renewal) re-arms the expiry alerts
+    pub end_date: Option<DateTime<Utc>>,
+    pub cost: Option<f64>,
+    pub currency: Option<String>,
+    pub coverage_level: Option<String>,
+    pub response_time_hours: Option<u32>,
+    pub support_contact: Option<SupportContact>,
+    pub covered_cis: Option<Vec<String>>,
+    pub notify: Option<Vec<String>>,
+    pub alert_days: Option<Vec<u32>>,
+    pub auto_renew: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContractListQuery {
+    pub vendor: Option<String>,
+    pub contract_type: Option<ContractType>,
+    /// Only contracts covering this CI
+    pub ci_id: Option<String>,
+    /// Only contracts ending within this many days (expired ones excluded)
+    pub expiring_within_days: Option<u32>,
+}
+
+/// A contract with its derived status
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractResponse {
+    #[serde(flatten)]
+    pub contract: Contract,
+    pub status: ContractStatus,
+    pub days_remaining: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractListResponse {
+    pub contracts: Vec<ContractResponse>,
+    pub total: usize,
+}
+
+/// Support entitlement shown on a CI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractCoverage {
+    pub contract_id: Option<Thing>,
+    pub name: String,
+    pub vendor: String,
+    pub contract_number: Option<String>,
+    pub contract_type: ContractType,
+    pub coverage_level: Option<String>,
+    pub response_time_hours: Option<u32>,
+    pub support_contact: SupportContact,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub status: ContractStatus,
+    pub days_remaining: i64,
+}
+
+impl ContractCoverage {
+    pub fn from_contract(contract: &Contract, now: DateTime<Utc>) -> Self {
+        Self {
+            contract_id: contract.id.clone(),
+            name: contract.name.clone(),
+            vendor: contract.vendor.clone(),
+            contract_number: contract.contract_number.clone(),
+            contract_type: contract.contract_type,
+            coverage_level: contract.coverage_level.clone(),
+            response_time_hours: contract.response_time_hours,
+            support_contact: contract.support_contact.clone(),
+            start_date: contract.start_date,
+            end_date: contract.end_date,
+            status: contract.status(now),
+            days_remaining: contract.days_remaining(now),
+        }
+    }
+}
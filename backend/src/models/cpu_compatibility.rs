@@ -0,0 +1,122 @@
+// Archer - CPU Compatibility Models
+// Source hosts grouped by CPU generation and EVC baseline, target cluster
+// CPU profiles, and whether each VM and wave can move live or cold
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuVendor {
+    Intel,
+    Amd,
+}
+
+impl CpuVendor {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Intel => "Intel",
+            Self::Amd => "AMD",
+        }
+    }
+}
+
+/// A CPU generation and the vSphere EVC mode that masks a cluster down to
+/// it. `rank` orders the generations of one vendor, oldest first.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CpuBaseline {
+    pub vendor: CpuVendor,
+    pub generation: String,
+    pub evc_mode: String,
+    pub rank: u8,
+}
+
+impl CpuBaseline {
+    pub fn label(&self) -> String {
+        format!("{} {}", self.vendor.label(), self.generation)
+    }
+}
+
+/// Source hosts sharing a CPU baseline; hosts with an active EVC mode are
+/// grouped by that mode, the rest by their CPU's own generation
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceCpuGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<CpuBaseline>,
+    /// Whether the baseline comes from the cluster's EVC mode
+    pub evc_enabled: bool,
+    pub hosts: Vec<String>,
+    pub clusters: Vec<String>,
+    pub cpu_models: Vec<String>,
+    pub vm_count: i32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetCpuSource {
+    /// The `cpu_model` of the wizard cluster's hardware profile
+    ClusterProfile,
+    /// The `cpu_model` recorded in the destination cluster nodes' metadata
+    NodeHardware,
+    /// The CPUs the vendor catalog lists for the nodes' server model
+    VendorSpec,
+    Unknown,
+}
+
+/// CPU baseline of a target cluster: the oldest generation among its CPUs
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetCpuProfile {
+    pub cluster_id: String,
+    pub cluster_name: String,
+    /// Hypervisor of the linked destination cluster, e.g. "hyper-v"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hypervisor: Option<String>,
+    pub cpu_models: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<CpuBaseline>,
+    pub source: TargetCpuSource,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationMode {
+    /// vMotion while the VM keeps running
+    Live,
+    /// Power off, move or convert, power on
+    Cold,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VmMigrationMode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_id: Option<String>,
+    pub vm_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_baseline: Option<CpuBaseline>,
+    pub target_cluster_id: String,
+    pub target_cluster_name: String,
+    pub mode: MigrationMode,
+    pub rationale: String,
+}
+
+/// A wave moves live only when every VM in it can
+#[derive(Debug, Clone, Serialize)]
+pub struct WaveMigrationMode {
+    pub wave_id: String,
+    pub wave_name: String,
+    pub mode: MigrationMode,
+    pub live_vms: usize,
+    pub cold_vms: Vec<String>,
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CpuCompatibilityReport {
+    pub project_id: String,
+    pub source_groups: Vec<SourceCpuGroup>,
+    pub targets: Vec<TargetCpuProfile>,
+    /// VMs with a placement or a wave target
+    pub vms: Vec<VmMigrationMode>,
+    pub waves: Vec<WaveMigrationMode>,
+    pub generated_at: DateTime<Utc>,
+}
@@ -0,0 +1,105 @@
+// Archer - Data Variable Models
+// Rows of data_variable_schema: built-in variables with a fixed aggregation
+// method, and user-defined variables computed from an expression
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use surrealdb::sql::Thing;
+
+use crate::services::data_expression::DataSourceSummary;
+
+/// `aggregation_method` of a user-defined variable
+pub const EXPRESSION_METHOD: &str = "expression";
+
+/// A data variable reports and HLD generation can refer to by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataVariableSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub variable_name: String,
+    pub display_name: String,
+    pub data_type: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub source_sheets: Vec<String>,
+    #[serde(default)]
+    pub source_columns: Vec<String>,
+    pub aggregation_method: String,
+    /// Set when `aggregation_method` is `expression`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    #[serde(default)]
+    pub formatting_rules: JsonValue,
+    #[serde(default)]
+    pub validation_rules: Vec<JsonValue>,
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl DataVariableSchema {
+    pub fn is_custom(&self) -> bool {
+        self.aggregation_method == EXPRESSION_METHOD
+    }
+}
+
+/// Create or replace a user-defined variable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveDataVariableRequest {
+    pub variable_name: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+    pub expression: String,
+    #[serde(default = "default_category")]
+    pub category: String,
+    #[serde(default)]
+    pub formatting_rules: Option<JsonValue>,
+    /// Check the expression against this upload's data before saving
+    #[serde(default)]
+    pub upload_id: Option<String>,
+}
+
+fn default_category() -> String {
+    "custom".to_string()
+}
+
+/// Check an expression without saving it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateExpressionRequest {
+    pub expression: String,
+    /// Upload whose sheets and VMs to check against and evaluate on;
+    /// without one only the syntax is checked
+    #[serde(default)]
+    pub upload_id: Option<String>,
+}
+
+/// Outcome of checking an expression
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpressionValidation {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub source_sheets: Vec<String>,
+    pub source_columns: Vec<String>,
+    /// The value on the given upload's data, when the expression is valid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    /// Sheets and fields an expression can read, when an upload was given
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub available: Vec<DataSourceSummary>,
+}
+
+/// A user-defined variable evaluated on one upload
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluatedDataVariable {
+    pub variable_name: String,
+    pub value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
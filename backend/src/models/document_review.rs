@@ -0,0 +1,150 @@
+// Archer - Document Review Models
+// Customer review and sign-off of a generated document version: assigned
+// reviewers, comments anchored to sections, the workflow-driven decision and
+// the approval record embedded when the version is published
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewStatus {
+    InReview,
+    ChangesRequested,
+    Approved,
+    /// Approved and locked; the published artifact carries the approval record
+    Published,
+    Cancelled,
+}
+
+impl ReviewStatus {
+    /// Reviews that still hold the document version
+    pub fn is_open(self) -> bool {
+        matches!(self, ReviewStatus::InReview | ReviewStatus::Approved)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewerDecision {
+    Pending,
+    Approved,
+    ChangesRequested,
+    /// Another reviewer's decision closed the review first
+    NotRequired,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewerAssignment {
+    /// `users:...`
+    pub user_id: String,
+    pub decision: ReviewerDecision,
+    #[serde(default)]
+    pub decided_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub comments: Option<String>,
+    /// Set when a delegation rule routed the approval to someone else
+    #[serde(default)]
+    pub delegated_to: Option<String>,
+}
+
+/// Sign-off written into the published document's metadata page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentApprovalRecord {
+    pub document_name: String,
+    pub document_version: u32,
+    /// SHA-256 of the reviewed artifact
+    pub reviewed_sha256: String,
+    pub approvals: Vec<ReviewerAssignment>,
+    pub approved_at: DateTime<Utc>,
+    #[serde(default)]
+    pub workflow_instance_id: Option<String>,
+    pub published_by: String,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Stored in `document_review`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentReview {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// The `generated_document` version under review
+    pub document_id: Thing,
+    pub project_id: Thing,
+    pub document_version: u32,
+    pub status: ReviewStatus,
+    pub reviewers: Vec<ReviewerAssignment>,
+    #[serde(default)]
+    pub workflow_instance_id: Option<Thing>,
+    pub requested_by: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub approval_record: Option<DocumentApprovalRecord>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Stored in `document_review_comment`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub review_id: Thing,
+    /// Section anchor, e.g. `compute_design`
+    pub section_id: String,
+    /// Text in the section the comment refers to
+    #[serde(default)]
+    pub quote: Option<String>,
+    pub body: String,
+    pub author: String,
+    #[serde(default)]
+    pub reply_to: Option<Thing>,
+    #[serde(default)]
+    pub resolved_by: Option<String>,
+    #[serde(default)]
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartReviewRequest {
+    /// Project whose HLD version is reviewed
+    pub project_id: String,
+    pub document_version: u32,
+    /// `users:...` ids; every reviewer has to approve
+    pub reviewers: Vec<String>,
+    /// Review workflow; defaults to `DOCUMENT_REVIEW_WORKFLOW`
+    #[serde(default)]
+    pub workflow_id: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddReviewCommentRequest {
+    pub section_id: String,
+    pub body: String,
+    #[serde(default)]
+    pub quote: Option<String>,
+    #[serde(default)]
+    pub reply_to: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReviewListQuery {
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<ReviewStatus>,
+}
+
+/// A review with its comments, as returned by the detail endpoint
+#[derive(Debug, Serialize)]
+pub struct DocumentReviewDetail {
+    #[serde(flatten)]
+    pub review: DocumentReview,
+    pub comments: Vec<ReviewComment>,
+    pub open_comments: usize,
+}
@@ -0,0 +1,131 @@
+// Effort & Cost Estimation Models
+// Rate cards and professional-services estimates for migration projects
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use core_engine::currency::{BomLine, ConvertedMoney};
+
+// =============================================================================
+// RATE CARD MODELS
+// =============================================================================
+
+/// Hourly rates per delivery role used to price migration effort
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateCard {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub name: String,
+    /// ISO 4217 currency code, e.g. "USD"
+    pub currency: String,
+    pub rates: Vec<RoleRate>,
+    /// Contingency added on top of the priced effort (0-100)
+    pub contingency_percent: f64,
+    #[serde(default)]
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleRate {
+    pub role: String,
+    pub hourly_rate: f64,
+}
+
+impl RateCard {
+    pub fn rate_for(&self, role: &str) -> f64 {
+        self.rates
+            .iter()
+            .find(|r| r.role == role)
+            .map(|r| r.hourly_rate)
+            .unwrap_or(0.0)
+    }
+}
+
+// =============================================================================
+// ESTIMATE MODELS
+// =============================================================================
+
+/// Professional-services estimate derived from VM complexity and strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServicesEstimate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub rate_card_name: String,
+    pub currency: String,
+    pub vm_count: usize,
+    /// Mean VM complexity score (0 = trivial, 100 = very complex)
+    pub average_complexity: f64,
+    pub lines: Vec<EffortLine>,
+    pub roles: Vec<RoleEffortSummary>,
+    pub total_hours: f64,
+    pub subtotal: f64,
+    pub contingency_percent: f64,
+    pub contingency: f64,
+    /// One-time implementation services cost, fed into TCO and proposals
+    pub total_cost: f64,
+    /// Total in the project's display currency when it differs from the
+    /// rate card currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_total: Option<ConvertedMoney>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Effort for one task and role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffortLine {
+    pub task: String,
+    pub role: String,
+    pub hours: f64,
+    pub hourly_rate: f64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleEffortSummary {
+    pub role: String,
+    pub hours: f64,
+    pub cost: f64,
+}
+
+/// Per-VM input to the effort model
+#[derive(Debug, Clone)]
+pub struct VmEffortInput {
+    pub strategy: String,
+    pub complexity_score: f64,
+}
+
+// =============================================================================
+// REQUEST MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRateCardRequest {
+    pub name: String,
+    pub currency: Option<String>,
+    pub rates: Vec<RoleRate>,
+    pub contingency_percent: Option<f64>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EstimateServicesRequest {
+    /// Rate card to price with; the default card is used when omitted
+    pub rate_card_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetDisplayCurrencyRequest {
+    /// ISO 4217 code, e.g. "EUR"
+    pub currency: String,
+}
+
+/// Bill of materials to total in the project's display currency
+#[derive(Debug, Deserialize)]
+pub struct ConvertBomRequest {
+    pub lines: Vec<BomLine>,
+}
@@ -0,0 +1,210 @@
+// Host Firmware Baseline Models
+// Supported BIOS/firmware/driver versions per hardware model and hypervisor,
+// node compliance against them and the remediation tasks that follow
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use surrealdb::sql::Thing;
+
+/// Versions a hardware model must run under a hypervisor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirmwareBaseline {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub vendor: String,
+    pub model: String,
+    /// Hypervisor as destination clusters name it (e.g. "vmware", "hyper-v")
+    pub hypervisor: String,
+    pub components: Vec<BaselineComponent>,
+    /// Support matrix the versions came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FirmwareBaseline {
+    pub fn applies_to(&self, vendor: &str, model: &str, hypervisor: &str) -> bool {
+        self.vendor.eq_ignore_ascii_case(vendor)
+            && self.model.eq_ignore_ascii_case(model)
+            && self.hypervisor.eq_ignore_ascii_case(hypervisor)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BaselineComponent {
+    pub component: FirmwareComponent,
+    /// Minimum supported version; newer versions comply
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FirmwareComponent {
+    Bios,
+    Bmc,
+    NicFirmware,
+    NicDriver,
+    StorageControllerFirmware,
+    StorageControllerDriver,
+}
+
+impl FirmwareComponent {
+    pub fn key(&self) -> &'static str {
+        match self {
+            FirmwareComponent::Bios => "bios",
+            FirmwareComponent::Bmc => "bmc",
+            FirmwareComponent::NicFirmware => "nic_firmware",
+            FirmwareComponent::NicDriver => "nic_driver",
+            FirmwareComponent::StorageControllerFirmware => "storage_controller_firmware",
+            FirmwareComponent::StorageControllerDriver => "storage_controller_driver",
+        }
+    }
+
+    /// Accepts the snake_case key and the labels vendor matrices use
+    /// ("BIOS", "NIC Firmware", "Storage Controller Driver", "iDRAC", ...)
+    pub fn parse(label: &str) -> Option<Self> {
+        let normalized = label.trim().to_lowercase().replace(['-', ' '], "_");
+        Some(match normalized.as_str() {
+            "bios" | "uefi" | "system_bios" => FirmwareComponent::Bios,
+            "bmc" | "idrac" | "ilo" | "xcc" | "ipmi" => FirmwareComponent::Bmc,
+            "nic_firmware" | "nic" | "network_firmware" => FirmwareComponent::NicFirmware,
+            "nic_driver" | "network_driver" => FirmwareComponent::NicDriver,
+            "storage_controller_firmware" | "storage_controller" | "raid_controller" | "hba_firmware" => {
+                FirmwareComponent::StorageControllerFirmware
+            }
+            "storage_controller_driver" | "raid_driver" | "hba_driver" => FirmwareComponent::StorageControllerDriver,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceStatus {
+    Compliant,
+    /// Newer than the baseline; supported unless the vendor says otherwise
+    Newer,
+    Outdated,
+    /// The node reports no version for the component
+    Missing,
+}
+
+impl ComplianceStatus {
+    pub fn needs_remediation(&self) -> bool {
+        matches!(self, ComplianceStatus::Outdated | ComplianceStatus::Missing)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FirmwareFinding {
+    pub component: FirmwareComponent,
+    pub expected: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed: Option<String>,
+    pub status: ComplianceStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeCompliance {
+    pub node_id: String,
+    pub asset_tag: String,
+    pub vendor: String,
+    pub model: String,
+    /// None when no baseline covers the node's model and hypervisor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_id: Option<String>,
+    pub findings: Vec<FirmwareFinding>,
+}
+
+/// Firmware compliance of every node in a destination cluster
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterFirmwareReport {
+    pub cluster_id: String,
+    pub cluster_name: String,
+    pub hypervisor: String,
+    pub nodes: Vec<NodeCompliance>,
+    /// Every node has a baseline and nothing needs remediation
+    pub compliant: bool,
+    pub tasks_created: usize,
+}
+
+/// One component on one node to bring up to baseline before the build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirmwareRemediationTask {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub cluster_id: Thing,
+    pub node_id: Thing,
+    pub asset_tag: String,
+    pub component: FirmwareComponent,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_version: Option<String>,
+    pub target_version: String,
+    pub status: RemediationStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationStatus {
+    Open,
+    Done,
+}
+
+// =============================================================================
+// REQUEST/RESPONSE MODELS
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateFirmwareBaselineRequest {
+    pub vendor: String,
+    pub model: String,
+    pub hypervisor: String,
+    pub components: Vec<BaselineComponent>,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateFirmwareBaselineRequest {
+    pub components: Option<Vec<BaselineComponent>>,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MatrixFormat {
+    /// Columns: vendor, model, hypervisor, component, version
+    Csv,
+    /// Array of baselines in the create request shape
+    Json,
+}
+
+/// Import a vendor support matrix; rows merge into existing baselines
+#[derive(Debug, Deserialize)]
+pub struct ImportFirmwareMatrixRequest {
+    pub format: MatrixFormat,
+    pub content: String,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FirmwareMatrixImportResult {
+    pub created: usize,
+    pub updated: usize,
+    pub errors: Vec<String>,
+}
+
+/// Versions entered or discovered for a node, keyed by component
+#[derive(Debug, Deserialize)]
+pub struct RecordNodeFirmwareRequest {
+    pub versions: BTreeMap<FirmwareComponent, String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRemediationTaskRequest {
+    pub status: RemediationStatus,
+}
@@ -0,0 +1,190 @@
+// Archer - Guest Scan Models
+// Credentials and results of agentless in-guest scans of migration wizard
+// VMs: WinRM for Windows, SSH for Linux
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GuestProtocol {
+    /// WS-Management over HTTPS with Basic auth
+    Winrm,
+    Ssh,
+}
+
+impl GuestProtocol {
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Self::Winrm => 5986,
+            Self::Ssh => 22,
+        }
+    }
+}
+
+/// Sign-in for guest scans of one project; stored in `guest_credential`
+/// with the password or private key sealed by the secrets key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestCredential {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub name: String,
+    pub protocol: GuestProtocol,
+    pub username: String,
+    pub secret_encrypted: String,
+    /// The secret is an SSH private key rather than a password
+    #[serde(default)]
+    pub private_key: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Guests commonly serve WinRM with a self-signed certificate
+    #[serde(default)]
+    pub verify_tls: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GuestCredential {
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or_else(|| self.protocol.default_port())
+    }
+}
+
+/// Credential as returned by the API (no secret)
+#[derive(Debug, Clone, Serialize)]
+pub struct GuestCredentialView {
+    pub id: String,
+    pub name: String,
+    pub protocol: GuestProtocol,
+    pub username: String,
+    pub private_key: bool,
+    pub port: u16,
+    pub verify_tls: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&GuestCredential> for GuestCredentialView {
+    fn from(c: &GuestCredential) -> Self {
+        Self {
+            id: c.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+            name: c.name.clone(),
+            protocol: c.protocol,
+            username: c.username.clone(),
+            private_key: c.private_key,
+            port: c.port(),
+            verify_tls: c.verify_tls,
+            created_by: c.created_by.clone(),
+            created_at: c.created_at,
+        }
+    }
+}
+
+/// Exactly one of `password` and `private_key`; private keys are SSH only
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateGuestCredentialRequest {
+    pub name: String,
+    pub protocol: GuestProtocol,
+    pub username: String,
+    pub password: Option<String>,
+    pub private_key: Option<String>,
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub verify_tls: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuestScanRequest {
+    pub credential_id: String,
+    /// Keys of the project VMs to scan
+    pub vm_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstalledApplication {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+}
+
+/// A manually configured address; DHCP leases are not collected
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GuestIpConfig {
+    pub interface: String,
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix_length: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+    #[serde(default)]
+    pub dns_servers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GuestMount {
+    /// Mount point or drive letter, e.g. "/data" or "D:"
+    pub mount_point: String,
+    /// Device or remote share
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filesystem: Option<String>,
+    pub size_gb: f64,
+    pub used_gb: f64,
+    /// NFS, SMB or a mapped network drive
+    #[serde(default)]
+    pub network: bool,
+}
+
+/// What a scan found inside one guest
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GuestConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    #[serde(default)]
+    pub applications: Vec<InstalledApplication>,
+    #[serde(default)]
+    pub static_ips: Vec<GuestIpConfig>,
+    #[serde(default)]
+    pub mounts: Vec<GuestMount>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GuestScanStatus {
+    Succeeded,
+    Failed,
+}
+
+/// Latest scan of one VM; stored in `guest_scan` keyed by the VM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestScan {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub vm_id: Thing,
+    pub vm_name: String,
+    pub protocol: GuestProtocol,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub status: GuestScanStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub config: GuestConfig,
+    pub scanned_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GuestScanSummary {
+    pub requested: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub scans: Vec<GuestScan>,
+}
@@ -100,6 +100,50 @@ impl VariableConfidence {
     }
 }
 
+/// What a template section reveals that not every reader may see
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionSensitivity {
+    Pricing,
+    Credentials,
+    Licensing,
+}
+
+/// Reader a document variant is produced for. Each audience hides
+/// everything the one before it hides, so they are ordered from least to
+/// most redacted.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentAudience {
+    /// Internal readers: nothing is hidden
+    #[default]
+    Full,
+    /// The customer: credentials are hidden
+    Customer,
+    /// Hardware and software vendors: credentials, pricing and licensing are hidden
+    Vendor,
+}
+
+impl DocumentAudience {
+    pub const ALL: [DocumentAudience; 3] = [Self::Full, Self::Customer, Self::Vendor];
+
+    pub fn hides(self, sensitivity: SectionSensitivity) -> bool {
+        match self {
+            Self::Full => false,
+            Self::Customer => sensitivity == SectionSensitivity::Credentials,
+            Self::Vendor => true,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Customer => "customer",
+            Self::Vendor => "vendor",
+        }
+    }
+}
+
 // ============================================================================
 // VALIDATION RULES
 // ============================================================================
@@ -314,6 +358,10 @@ pub struct HLDSection {
     #[serde(default)]
     pub repeatable: bool,
 
+    /// Audiences that may not see the section get a variant without it
+    #[serde(default)]
+    pub sensitivity: Vec<SectionSensitivity>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<JsonValue>,
 
@@ -449,6 +497,14 @@ pub struct CreateHLDSectionRequest {
     pub depends_on: Vec<String>,
     #[serde(default)]
     pub repeatable: bool,
+    #[serde(default)]
+    pub sensitivity: Vec<SectionSensitivity>,
+}
+
+/// Request to tag a template section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSectionSensitivityRequest {
+    pub sensitivity: Vec<SectionSensitivity>,
 }
 
 /// Request to create HLD project
@@ -528,6 +584,86 @@ pub struct ValidationResult {
     pub warnings: Vec<ValidationError>,
 }
 
+/// Request for a generation dry run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HLDDryRunRequest {
+    /// Template to check against; the project's own template when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<String>,
+}
+
+/// Whether a variable would reach the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableResolution {
+    Resolved,
+    Missing,
+    Invalid,
+}
+
+/// How much of a section would be filled from project data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionReadiness {
+    /// Every variable the section reads has a value
+    Complete,
+    /// Some variables would fall back to placeholder text
+    Partial,
+    /// No project data would reach the section
+    Empty,
+}
+
+/// One variable in a dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunVariable {
+    pub name: String,
+    pub status: VariableResolution,
+    pub required: bool,
+    /// Sections that read the variable
+    #[serde(default)]
+    pub sections: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<String>,
+}
+
+/// One enabled section in a dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunSection {
+    pub section_id: String,
+    pub display_name: String,
+    pub required: bool,
+    pub status: SectionReadiness,
+    pub resolved: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+/// Something to fix before generating, pointing at a variable or section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunIssue {
+    pub severity: String, // "error" or "warning"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    pub message: String,
+}
+
+/// What generating the HLD would produce, without generating it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HLDDryRunReport {
+    pub project_id: String,
+    pub template_id: String,
+    /// No errors: generation would produce a complete document
+    pub ready: bool,
+    pub resolved_count: usize,
+    pub missing_count: usize,
+    pub invalid_count: usize,
+    pub empty_sections: usize,
+    pub variables: Vec<DryRunVariable>,
+    pub sections: Vec<DryRunSection>,
+    pub issues: Vec<DryRunIssue>,
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -628,5 +764,8 @@ pub struct SectionDefinition {
     #[serde(default)]
     pub depends_on: Vec<String>,
 
+    #[serde(default)]
+    pub sensitivity: Vec<SectionSensitivity>,
+
     pub created_at: DateTime<Utc>,
 }
@@ -0,0 +1,147 @@
+// Archer - Spreadsheet Import Models
+// Column-mapped CSV/XLSX imports into configuration_items and hardware_pool,
+// with dry-run validation, upsert by serial/asset tag, and import history
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+// ============================================================================
+// TARGETS & MAPPING
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportTarget {
+    ConfigurationItems,
+    HardwarePool,
+}
+
+/// A field the importer can populate
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportFieldInfo {
+    pub name: &'static str,
+    pub required: bool,
+    /// "text", "integer", "number", "date", "list" or an enum's allowed values
+    pub kind: String,
+    /// Used as the upsert key when present
+    pub is_key: bool,
+}
+
+/// Reusable column mapping, e.g. one per customer spreadsheet layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportMappingProfile {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub target: ImportTarget,
+    /// Target field → source column header
+    pub mapping: HashMap<String, String>,
+    /// Target field → value used when the column is absent or blank
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateMappingProfileRequest {
+    pub name: String,
+    pub target: ImportTarget,
+    pub mapping: HashMap<String, String>,
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+}
+
+/// Options sent alongside the uploaded file as the `options` multipart field
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportOptions {
+    pub target: ImportTarget,
+    /// Target field → source column header; merged over the saved profile
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+    /// Saved mapping profile to start from
+    pub mapping_id: Option<String>,
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+    /// Validate and report what would change without writing
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    /// Worksheet to read for XLSX files; first sheet when omitted
+    pub sheet: Option<String>,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+// ============================================================================
+// RESULTS & HISTORY
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportRowError {
+    /// 1-based spreadsheet row, counting the header as row 1
+    pub row: usize,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportRowAction {
+    Create,
+    Update,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRowOutcome {
+    pub row: usize,
+    pub key: String,
+    pub action: ImportRowAction,
+    /// Record written (or that would be updated in a dry run)
+    pub record_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportStatus {
+    /// Dry run finished; nothing was written
+    Validated,
+    Completed,
+    /// Some rows were written, others failed
+    CompletedWithErrors,
+    Failed,
+}
+
+/// One import attempt, persisted to `import_runs` for history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRun {
+    pub id: Option<Thing>,
+    pub target: ImportTarget,
+    pub filename: String,
+    pub dry_run: bool,
+    pub status: ImportStatus,
+    pub mapping: HashMap<String, String>,
+    pub total_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowError>,
+    pub outcomes: Vec<ImportRowOutcome>,
+    pub started_by: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub tenant_id: Option<Thing>,
+}
+
+/// Headers and sample rows shown while the user builds a mapping
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub sample_rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+    pub sheets: Vec<String>,
+    /// Target field → header, matched by normalised name
+    pub suggested_mapping: HashMap<String, String>,
+}
@@ -0,0 +1,273 @@
+//! Integration hub models
+//!
+//! Stored connections to external inventory sources (Nutanix Prism Central,
+//! Cisco Catalyst Center, ...), the per-connection ledger of discovered
+//! objects used for delta syncs, and the history of sync runs.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use crate::services::integration_hub::ProviderType;
+
+// ============================================================================
+// CONNECTIONS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationConnection {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub provider_type: ProviderType,
+    pub base_url: String,
+    pub username: Option<String>,
+    /// Encrypted with the server secrets key (see services::llm::secrets)
+    pub password_encrypted: Option<String>,
+    pub verify_tls: bool,
+    /// Minutes between scheduled delta syncs; no schedule when None
+    pub sync_interval_minutes: Option<u32>,
+    /// Hours between scheduled full syncs (default 24)
+    pub full_sync_interval_hours: Option<u32>,
+    pub enabled: bool,
+    pub tenant_id: Option<Thing>,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_full_sync_at: Option<DateTime<Utc>>,
+    pub last_sync_status: Option<SyncRunStatus>,
+    pub last_error: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Connection as returned by the API (no credentials)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationConnectionView {
+    pub id: String,
+    pub name: String,
+    pub provider_type: ProviderType,
+    pub base_url: String,
+    pub username: Option<String>,
+    pub has_password: bool,
+    pub verify_tls: bool,
+    pub sync_interval_minutes: Option<u32>,
+    pub full_sync_interval_hours: Option<u32>,
+    pub enabled: bool,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_full_sync_at: Option<DateTime<Utc>>,
+    pub last_sync_status: Option<SyncRunStatus>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&IntegrationConnection> for IntegrationConnectionView {
+    fn from(c: &IntegrationConnection) -> Self {
+        Self {
+            id: c.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+            name: c.name.clone(),
+            provider_type: c.provider_type.clone(),
+            base_url: c.base_url.clone(),
+            username: c.username.clone(),
+            has_password: c.password_encrypted.is_some(),
+            verify_tls: c.verify_tls,
+            sync_interval_minutes: c.sync_interval_minutes,
+            full_sync_interval_hours: c.full_sync_interval_hours,
+            enabled: c.enabled,
+            last_sync_at: c.last_sync_at,
+            last_full_sync_at: c.last_full_sync_at,
+            last_sync_status: c.last_sync_status,
+            last_error: c.last_error.clone(),
+            created_at: c.created_at,
+            updated_at: c.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateIntegrationConnectionRequest {
+    pub name: String,
+    pub provider_type: ProviderType,
+    pub base_url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub verify_tls: bool,
+    pub sync_interval_minutes: Option<u32>,
+    pub full_sync_interval_hours: Option<u32>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateIntegrationConnectionRequest {
+    pub name: Option<String>,
+    pub base_url: Option<String>,
+    pub username: Option<String>,
+    /// Empty string clears the stored password
+    pub password: Option<String>,
+    pub verify_tls: Option<bool>,
+    /// 0 removes the schedule
+    pub sync_interval_minutes: Option<u32>,
+    pub full_sync_interval_hours: Option<u32>,
+    pub enabled: Option<bool>,
+}
+
+// ============================================================================
+// SYNC RUNS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SyncMode {
+    /// Rewrite every discovered object
+    Full,
+    /// Only write objects whose fingerprint changed since the last sync
+    Delta,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SyncRunStatus {
+    Running,
+    Succeeded,
+    /// Some object kinds failed to load; the rest were synced
+    Partial,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SyncCounts {
+    pub discovered: u32,
+    pub created: u32,
+    pub updated: u32,
+    pub unchanged: u32,
+    pub removed: u32,
+    pub relationships: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationSyncRun {
+    pub id: Option<Thing>,
+    pub connection_id: Thing,
+    pub provider_type: ProviderType,
+    pub mode: SyncMode,
+    pub status: SyncRunStatus,
+    /// Manual runs carry the user id; scheduled runs "scheduler"
+    pub triggered_by: String,
+    pub counts: SyncCounts,
+    /// Counts per object kind, e.g. "VM" → counts
+    #[serde(default)]
+    pub by_kind: std::collections::BTreeMap<String, SyncCounts>,
+    #[serde(default)]
+    pub errors: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// One discovered object as last seen by a connection
+/// (table `integration_assets`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationAssetRecord {
+    pub id: Option<Thing>,
+    pub connection_id: Thing,
+    pub external_id: String,
+    pub asset_type: String,
+    pub name: String,
+    /// Hash of the source payload; unchanged objects are skipped on delta syncs
+    pub fingerprint: String,
+    pub ci: Option<Thing>,
+    pub environment_record: Option<Thing>,
+    pub removed: bool,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TriggerSyncRequest {
+    pub mode: Option<SyncMode>,
+}
+
+/// Connection status for the integration dashboard
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationSyncStatus {
+    pub connection: IntegrationConnectionView,
+    pub running: bool,
+    pub last_run: Option<IntegrationSyncRun>,
+    pub next_scheduled_at: Option<DateTime<Utc>>,
+    pub asset_counts: std::collections::BTreeMap<String, u64>,
+}
+
+// ============================================================================
+// SIMULATION
+// ============================================================================
+
+/// Which connections serve recorded fixtures instead of calling the real
+/// system. Stored as a singleton; `INTEGRATION_SIMULATION=true` forces it on
+/// for every connection (E2E runs, offline demos).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationSettings {
+    /// Simulate every connection
+    #[serde(default)]
+    pub enabled: bool,
+    /// Simulate every connection of these providers
+    #[serde(default)]
+    pub providers: Vec<ProviderType>,
+    /// Simulate individual connections, by id
+    #[serde(default)]
+    pub connection_ids: Vec<String>,
+    /// Response delay range; each call waits a random time in between
+    pub min_latency_ms: u64,
+    pub max_latency_ms: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            providers: Vec::new(),
+            connection_ids: Vec::new(),
+            min_latency_ms: 150,
+            max_latency_ms: 900,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+impl SimulationSettings {
+    pub fn applies_to(&self, connection: &IntegrationConnection) -> bool {
+        self.enabled
+            || self.providers.contains(&connection.provider_type)
+            || connection
+                .id
+                .as_ref()
+                .map(|id| {
+                    let raw = id.id.to_raw();
+                    self.connection_ids.iter().any(|c| *c == raw || *c == id.to_string())
+                })
+                .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateSimulationSettingsRequest {
+    pub enabled: Option<bool>,
+    pub providers: Option<Vec<ProviderType>>,
+    pub connection_ids: Option<Vec<String>>,
+    pub min_latency_ms: Option<u64>,
+    pub max_latency_ms: Option<u64>,
+}
+
+/// Settings as returned by the API
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationSettingsResponse {
+    #[serde(flatten)]
+    pub settings: SimulationSettings,
+    /// `INTEGRATION_SIMULATION` is set; every connection is simulated
+    /// regardless of the stored settings
+    pub forced_by_env: bool,
+}
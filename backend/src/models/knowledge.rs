@@ -5,6 +5,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 
+use crate::utils::pagination::{SortOrder, Sortable};
+
 // ============================================================================
 // ARTICLE MODEL
 // ============================================================================
@@ -34,6 +36,9 @@ pub struct KBArticle {
     pub helpfulness_score: f32,
     /// Related articles (manual curation)
     pub related_articles: Vec<Thing>,
+    /// Uploaded attachments (kb_attachments records)
+    #[serde(default)]
+    pub attachments: Vec<Thing>,
     /// SEO metadata
     pub seo_title: Option<String>,
     pub seo_description: Option<String>,
@@ -134,6 +139,43 @@ pub struct KBArticleRating {
     pub created_at: DateTime<Utc>,
 }
 
+// ============================================================================
+// ATTACHMENT MODEL
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KBAttachment {
+    pub id: Option<Thing>,
+    pub article_id: Thing,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    /// Key of the original file in the attachment store
+    pub storage_key: String,
+    /// MD5 of the original bytes, used for ETags
+    pub checksum: String,
+    pub is_image: bool,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Downscaled renditions for inline markdown images
+    #[serde(default)]
+    pub variants: Vec<KBImageVariant>,
+    pub uploaded_by: String,
+    pub uploaded_at: DateTime<Utc>,
+    pub tenant_id: Option<Thing>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KBImageVariant {
+    /// thumb, medium or large
+    pub label: String,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: String,
+    pub storage_key: String,
+    pub size_bytes: u64,
+}
+
 // ============================================================================
 // REQUEST/RESPONSE MODELS
 // ============================================================================
@@ -194,16 +236,18 @@ pub struct KBSearchRequest {
     pub status: Option<Vec<ArticleStatus>>,
     pub visibility: Option<Vec<ArticleVisibility>>,
     pub author_id: Option<String>,
-    pub page: Option<u32>,
-    pub page_size: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct KBArticleListResponse {
-    pub items: Vec<KBArticle>,
-    pub total: u64,
-    pub page: u32,
-    pub page_size: u32,
+/// Sortable article list fields; paging comes from the query string
+#[derive(Debug, Clone, Copy)]
+pub struct ArticleSort;
+
+impl Sortable for ArticleSort {
+    const SORT_FIELDS: &'static [&'static str] = &[
+        "title", "status", "view_count", "helpfulness_score", "created_at", "updated_at", "published_at",
+    ];
+    const DEFAULT_SORT: &'static str = "updated_at";
+    const DEFAULT_ORDER: SortOrder = SortOrder::Desc;
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -223,6 +267,20 @@ pub struct CategoryStat {
     pub article_count: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KBAttachmentUploadResponse {
+    pub attachment: KBAttachment,
+    /// Ready-to-paste markdown referencing the attachment
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KBAttachmentCleanupReport {
+    pub attachments_removed: u32,
+    pub blobs_removed: u32,
+    pub errors: Vec<String>,
+}
+
 // ============================================================================
 // KB-TICKET INTEGRATION MODELS (Phase 1.5)
 // ============================================================================
@@ -269,6 +327,75 @@ pub struct KBSuggestionRequest {
     pub limit: Option<u32>,
 }
 
+/// A ranked suggestion shown for a specific ticket. `suggestion_id` refers
+/// to the kb_suggestion_log row used to record the agent's response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketArticleSuggestion {
+    pub suggestion_id: String,
+    pub rank: u32,
+    #[serde(flatten)]
+    pub suggestion: ArticleSuggestion,
+    /// Terms that contributed most to the similarity score
+    pub matched_terms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketSuggestionResponse {
+    pub ticket_id: String,
+    pub method: String,
+    pub suggestions: Vec<TicketArticleSuggestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SuggestionOutcome {
+    Shown,
+    Viewed,
+    Accepted,
+    Dismissed,
+}
+
+/// Log of every article suggested for a ticket and what the agent did with it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KBSuggestionLog {
+    pub id: Option<Thing>,
+    pub ticket_id: Thing,
+    pub article_id: Thing,
+    pub rank: u32,
+    pub score: f32,
+    pub similarity: f32,
+    pub method: String,
+    pub shown_to: String,
+    pub shown_at: DateTime<Utc>,
+    pub outcome: SuggestionOutcome,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionFeedbackRequest {
+    pub outcome: SuggestionOutcome,
+    pub was_helpful: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionRankStat {
+    pub rank: u32,
+    pub shown: u64,
+    pub accepted: u64,
+    pub acceptance_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionMetrics {
+    pub since: DateTime<Utc>,
+    pub shown: u64,
+    pub viewed: u64,
+    pub accepted: u64,
+    pub dismissed: u64,
+    pub acceptance_rate: f64,
+    pub by_rank: Vec<SuggestionRankStat>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LinkArticleToTicketRequest {
     pub article_id: String,
@@ -0,0 +1,151 @@
+// Archer - License Models
+// Signed license keys for resold installations: seat count, licensed modules,
+// expiry and the entitlement report shown to administrators
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Days a license keeps working after it expires, unless the key sets its own
+pub const DEFAULT_GRACE_DAYS: u32 = 14;
+
+/// Payload of a license key. Keys are JWTs signed with the vendor's Ed25519
+/// key (`EdDSA`), so any holder of the public key can verify them offline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LicenseClaims {
+    #[serde(rename = "jti")]
+    pub license_id: String,
+    #[serde(rename = "sub")]
+    pub customer: String,
+    /// Users that may hold an account that isn't deactivated
+    pub seats: u32,
+    /// Keys of the licensed modules
+    #[serde(default)]
+    pub modules: Vec<String>,
+    /// Issued at, seconds since the epoch
+    pub iat: i64,
+    /// Expires at, seconds since the epoch
+    pub exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grace_days: Option<u32>,
+}
+
+/// Modules a license can enable. Everything else is always included.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LicensedModule {
+    /// Migration wizard, wave timeline and VM placement
+    Migration,
+    /// IaC/cutover automation exports and post-migration reconciliation
+    Automation,
+    /// Tickets, workflows and the service catalog
+    Itsm,
+    Cmdb,
+    /// Inventory integrations (Nutanix, Veeam, Splunk, ...)
+    Integrations,
+    /// AI assistant
+    Ai,
+}
+
+impl LicensedModule {
+    pub const ALL: [LicensedModule; 6] = [
+        Self::Migration,
+        Self::Automation,
+        Self::Itsm,
+        Self::Cmdb,
+        Self::Integrations,
+        Self::Ai,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Migration => "migration",
+            Self::Automation => "automation",
+            Self::Itsm => "itsm",
+            Self::Cmdb => "cmdb",
+            Self::Integrations => "integrations",
+            Self::Ai => "ai",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseStatus {
+    /// Valid and not expired
+    Active,
+    /// Expired, but still within the grace period
+    Grace,
+    /// Expired and past the grace period; the API is read-only
+    Expired,
+    /// The installed key doesn't verify; the API is read-only
+    Invalid,
+    /// No key is installed; the API is read-only
+    Unlicensed,
+    /// No vendor public key is configured, so nothing is enforced
+    Unenforced,
+}
+
+impl LicenseStatus {
+    /// Whether the API accepts changes
+    pub fn allows_writes(&self) -> bool {
+        matches!(self, Self::Active | Self::Grace | Self::Unenforced)
+    }
+}
+
+/// The installed license key (single `app_license:current` record)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledLicense {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub key: String,
+    pub installed_by: String,
+    pub installed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstallLicenseRequest {
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatUsage {
+    /// `None` when no license limits seats
+    pub licensed: Option<u32>,
+    /// Accounts that aren't deactivated
+    pub used: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available: Option<u32>,
+    pub over_limit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleEntitlement {
+    pub module: LicensedModule,
+    pub enabled: bool,
+}
+
+/// Current license and how much of it is consumed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseEntitlement {
+    pub status: LicenseStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grace_ends_at: Option<DateTime<Utc>>,
+    /// Whole days until expiry, or until the grace period ends while in grace
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_remaining: Option<i64>,
+    pub seats: SeatUsage,
+    pub modules: Vec<ModuleEntitlement>,
+    /// What an administrator should do, if anything
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
@@ -2,9 +2,15 @@
 // Complete type definitions for migration project management
 
 use chrono::{DateTime, Utc};
+use core_engine::units::Bytes;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use surrealdb::sql::Thing;
 
+use crate::models::cpu_compatibility::MigrationMode;
+use crate::models::naming_policy::NamingViolation;
+use crate::utils::pagination::{SortOrder, Sortable};
+
 // =============================================================================
 // PROJECT MODELS
 // =============================================================================
@@ -32,6 +38,14 @@ pub struct MigrationWizardProject {
     pub total_vms: i32,
     pub total_clusters: i32,
     pub wizard_step: i32,
+
+    /// Owning tenant; projects without one are visible to every tenant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+
+    /// Currency costs are aggregated into for this project (ISO 4217)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_currency: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -166,6 +180,23 @@ pub struct MigrationWizardVM {
     pub created_at: DateTime<Utc>,
 }
 
+impl MigrationWizardVM {
+    /// RVTools "MB" columns are MiB
+    pub fn memory(&self) -> Bytes {
+        Bytes::from_mib(self.memory_mb.max(0) as f64)
+    }
+
+    pub fn provisioned(&self) -> Option<Bytes> {
+        self.provisioned_mb.map(|mib| Bytes::from_mib(mib.max(0) as f64))
+    }
+
+    /// Storage a placement reserves; VMs without a provisioned size reserve
+    /// their memory size
+    pub fn placement_storage(&self) -> Bytes {
+        self.provisioned().unwrap_or_else(|| self.memory())
+    }
+}
+
 // =============================================================================
 // CLUSTER MODELS
 // =============================================================================
@@ -194,11 +225,40 @@ pub struct MigrationWizardCluster {
     
     // Strategy
     pub strategy: String,
+
+    /// CPU of the cluster's hardware profile, e.g. "Intel Xeon Gold 6430";
+    /// decides whether VMs can vMotion onto it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_model: Option<String>,
+
+    /// Key of the destination cluster being built for this target; waves
+    /// onto it wait for that cluster's build checklist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_cluster_id: Option<String>,
+
+    /// Tags the cluster is built with; checked against the naming policy
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
     
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl MigrationWizardCluster {
+    pub fn memory_capacity(&self) -> Bytes {
+        Bytes::from_gib(self.memory_gb.max(0) as f64)
+    }
+
+    /// Memory that can be allocated to VMs after oversubscription
+    pub fn allocatable_memory(&self) -> Bytes {
+        Bytes::from_gib(self.memory_gb.max(0) as f64 * self.memory_oversubscription_ratio)
+    }
+
+    pub fn storage_capacity(&self) -> Bytes {
+        Bytes::from_tib(self.storage_tb)
+    }
+}
+
 // =============================================================================
 // PLACEMENT MODELS
 // =============================================================================
@@ -222,10 +282,237 @@ pub struct MigrationWizardPlacement {
     pub allocated_cpu: i32,
     pub allocated_memory_mb: i32,
     pub allocated_storage_gb: f64,
+
+    /// Tags the VM gets on its target cluster
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
     
     pub created_at: DateTime<Utc>,
 }
 
+impl MigrationWizardPlacement {
+    pub fn allocated_memory(&self) -> Bytes {
+        Bytes::from_mib(self.allocated_memory_mb.max(0) as f64)
+    }
+}
+
+// =============================================================================
+// SOURCE INVENTORY MODELS
+// =============================================================================
+
+/// ESXi host from the RVTools vHost sheet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceHost {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datacenter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_mhz: Option<i32>,
+    pub sockets: i32,
+    pub cores: i32,
+    /// RVTools "MB" columns are MiB
+    pub memory_mb: i64,
+    pub vm_count: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub esx_version: Option<String>,
+    /// EVC mode the host runs under, e.g. "intel-broadwell"; None when
+    /// the cluster has EVC disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_evc: Option<String>,
+    /// Newest EVC mode the host's CPU supports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_evc: Option<String>,
+}
+
+impl SourceHost {
+    pub fn memory(&self) -> Bytes {
+        Bytes::from_mib(self.memory_mb.max(0) as f64)
+    }
+}
+
+/// HA and DRS settings of a cluster from the RVTools vCluster sheet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceClusterConfig {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datacenter: Option<String>,
+    pub num_hosts: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ha_enabled: Option<bool>,
+    /// Host failures the cluster tolerates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover_level: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admission_control_enabled: Option<bool>,
+    /// Admission control policy, as RVTools reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover_policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isolation_response: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drs_enabled: Option<bool>,
+    /// DRS automation level (manual, partiallyAutomated, fullyAutomated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drs_behavior: Option<String>,
+}
+
+/// Datastore from the RVTools vDatastore sheet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceDatastore {
+    pub name: String,
+    /// VMFS, NFS, vsan, vvol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datastore_type: Option<String>,
+    pub capacity_mb: i64,
+    pub provisioned_mb: i64,
+    pub in_use_mb: i64,
+    pub free_mb: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<String>,
+    pub num_hosts: i32,
+}
+
+/// Resource pool from the RVTools vRP sheet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceResourcePool {
+    pub name: String,
+    /// Inventory path, e.g. `/DC1/host/Cluster01/Resources/Prod`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<String>,
+    pub vm_count: i32,
+    pub cpu_reservation_mhz: i64,
+    /// None when unlimited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_limit_mhz: Option<i64>,
+    pub memory_reservation_mb: i64,
+    /// None when unlimited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<i64>,
+}
+
+/// VM snapshot from the RVTools vSnapshot sheet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceSnapshot {
+    pub vm_name: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Delta disks and memory state together
+    pub size_mb: i64,
+}
+
+impl SourceSnapshot {
+    pub fn size(&self) -> Bytes {
+        Bytes::from_mib(self.size_mb.max(0) as f64)
+    }
+}
+
+/// VMware Tools state of a VM from the RVTools vTools sheet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceVmTools {
+    pub vm_name: String,
+    /// toolsOk, toolsOld, toolsNotInstalled, toolsNotRunning or guestToolsUnmanaged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Version the host ships
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgradeable: Option<bool>,
+}
+
+/// Hosts, clusters, datastores, resource pools, snapshots and VMware Tools
+/// states of an RVTools export, stored once per project in
+/// `migration_wizard_source_inventory`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceInventory {
+    #[serde(default)]
+    pub hosts: Vec<SourceHost>,
+    #[serde(default)]
+    pub clusters: Vec<SourceClusterConfig>,
+    #[serde(default)]
+    pub datastores: Vec<SourceDatastore>,
+    #[serde(default)]
+    pub resource_pools: Vec<SourceResourcePool>,
+    #[serde(default)]
+    pub snapshots: Vec<SourceSnapshot>,
+    #[serde(default)]
+    pub tools: Vec<SourceVmTools>,
+}
+
+/// One source cluster: its hosts, HA/DRS settings, VM demand and storage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceClusterSummary {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datacenter: Option<String>,
+    pub hosts: i32,
+    pub sockets: i32,
+    pub cores: i32,
+    pub memory_gb: f64,
+    pub cpu_models: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ha_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover_level: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admission_control_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover_policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drs_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drs_behavior: Option<String>,
+    pub vm_count: i32,
+    pub vcpus: i32,
+    pub vm_memory_gb: f64,
+    /// vCPUs per physical core; 0 without host data
+    pub vcpu_per_core: f64,
+    pub datastores: i32,
+    pub storage_capacity_gb: f64,
+    pub storage_free_gb: f64,
+    pub resource_pools: i32,
+}
+
+impl SourceClusterSummary {
+    /// Cores and memory of an average host; what one tolerated host failure
+    /// keeps in reserve
+    pub fn average_host(&self) -> Option<(f64, f64)> {
+        (self.hosts > 0).then(|| (self.cores as f64 / self.hosts as f64, self.memory_gb / self.hosts as f64))
+    }
+}
+
+/// Datastore capacity per type (VMFS, NFS, vSAN, ...)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DatastoreTypeSummary {
+    pub datastore_type: String,
+    pub datastores: i32,
+    pub capacity_gb: f64,
+    pub provisioned_gb: f64,
+    pub free_gb: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceEnvironmentSummary {
+    pub hosts: usize,
+    pub datastores: usize,
+    pub resource_pools: usize,
+    pub clusters: Vec<SourceClusterSummary>,
+    pub storage: Vec<DatastoreTypeSummary>,
+}
+
 // =============================================================================
 // NETWORK MAPPING MODELS
 // =============================================================================
@@ -260,6 +547,10 @@ pub struct MigrationWizardNetworkMapping {
     pub is_valid: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub validation_errors: Option<Vec<String>>,
+
+    /// Tags of the destination network
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
     
     pub created_at: DateTime<Utc>,
 }
@@ -911,6 +1202,34 @@ pub struct UploadRVToolsResponse {
     pub total_vms: i32,
     pub upload_date: DateTime<Utc>,
     pub processing_status: String,
+    pub ingest: RvToolsIngestMetrics,
+}
+
+/// Throughput of one RVTools import into the database
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RvToolsIngestMetrics {
+    pub vms_parsed: usize,
+    pub vms_inserted: usize,
+    pub chunk_size: usize,
+    pub batches: usize,
+    pub parse_ms: u64,
+    pub insert_ms: u64,
+    pub total_ms: u64,
+    pub vms_per_second: f64,
+    /// Rows read from the vHost, vCluster, vDatastore, vRP, vSnapshot and
+    /// vTools sheets
+    #[serde(default)]
+    pub hosts_parsed: usize,
+    #[serde(default)]
+    pub clusters_parsed: usize,
+    #[serde(default)]
+    pub datastores_parsed: usize,
+    #[serde(default)]
+    pub resource_pools_parsed: usize,
+    #[serde(default)]
+    pub snapshots_parsed: usize,
+    #[serde(default)]
+    pub tools_parsed: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -926,12 +1245,6 @@ pub struct ProjectDetailsResponse {
     pub clusters: Vec<MigrationWizardCluster>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ListVMsResponse {
-    pub vms: Vec<MigrationWizardVM>,
-    pub total: usize,
-}
-
 // =============================================================================
 // FILTER MODELS
 // =============================================================================
@@ -943,12 +1256,22 @@ pub struct ProjectFilter {
     pub offset: Option<usize>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct VMFilter {
     pub cluster: Option<String>,
     pub powerstate: Option<String>,
-    pub limit: Option<usize>,
-    pub offset: Option<usize>,
+}
+
+/// Sortable VM list fields; paging comes from the query string
+#[derive(Debug, Clone, Copy)]
+pub struct VmSort;
+
+impl Sortable for VmSort {
+    const SORT_FIELDS: &'static [&'static str] = &[
+        "name", "powerstate", "cpus", "memory_mb", "provisioned_mb", "in_use_mb", "cluster", "host", "datacenter",
+    ];
+    const DEFAULT_SORT: &'static str = "name";
+    const DEFAULT_ORDER: SortOrder = SortOrder::Asc;
 }
 
 // =============================================================================
@@ -962,6 +1285,12 @@ pub struct StrategyRecommendation {
     pub confidence_score: f64, // 0-100
     pub warnings: Vec<String>,
     pub recommendations: Vec<String>,
+    /// Live or cold, from the CPU compatibility of the VM's source host and
+    /// target cluster; None until the VM has a target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migration_mode: Option<MigrationMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migration_mode_rationale: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -994,6 +1323,8 @@ pub struct ManualPlacementRequest {
     pub vm_id: String,
     pub cluster_id: String,
     pub strategy: Option<String>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -1041,6 +1372,8 @@ pub struct CreateNetworkMappingRequest {
     pub destination_subnet: Option<String>,
     pub destination_gateway: Option<String>,
     pub destination_dns: Option<Vec<String>>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -1057,6 +1390,8 @@ pub struct NetworkMappingResponse {
     pub destination_dns: Option<Vec<String>>,
     pub is_valid: bool,
     pub validation_errors: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -1074,6 +1409,22 @@ pub struct NetworkValidationResult {
     pub invalid_mappings: usize,
     pub errors: Vec<NetworkValidationError>,
     pub warnings: Vec<String>,
+    /// Mappings targeting a VLAN or subnet another project in the tenant targets
+    pub cross_project_conflicts: Vec<CrossProjectNetworkConflict>,
+    /// Destination network names and tags breaking the tenant's naming policy
+    pub naming_violations: Vec<NamingViolation>,
+}
+
+/// A mapping whose destination VLAN or subnet clashes with a mapping of
+/// another project in the same tenant
+#[derive(Debug, Serialize, Clone)]
+pub struct CrossProjectNetworkConflict {
+    pub mapping_id: String,
+    pub conflict_type: NetworkErrorType,
+    pub other_project_id: String,
+    pub other_project_name: String,
+    pub other_mapping_id: String,
+    pub message: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
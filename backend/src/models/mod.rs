@@ -1,6 +1,10 @@
 // Models are now defined in core-engine crate for consistency
+pub mod ai;  // LLM providers, model selection & usage
+pub mod assessment;  // Assessment rule packs & runs
 pub mod auth;  // Authentication & RBAC models (Phase 0)
 pub mod cmdb;  // CMDB/Asset models (Phase 2)
+pub mod contract;  // Vendor contracts & CI coverage
+pub mod estimation;  // Effort & cost estimation models
 pub mod hld;
 pub mod knowledge;  // Knowledge Base models (Phase 1.5)
 pub mod migration_models;
@@ -10,8 +14,40 @@ pub mod project_models;
 pub mod service_catalog;  // Service Catalog models (Phase 5)
 pub mod settings;
 pub mod settings_models;
+pub mod timeline;  // Migration wave plans & timelines
 pub mod team;  // Team Management models (Phase 1+)
 pub mod workflow;
 pub mod ticket;
+pub mod ticket_template;  // Ticket templates, macros & checklist tasks
+pub mod ticket_schedule;  // Recurring ticket schedules
 pub mod workflow_engine;  // Workflow Engine models (Phase 3)
 pub mod reporting;  // Reporting models (Phase 6)
+pub mod risk;  // Project risk register
+pub mod os_lifecycle;  // OS end-of-support dataset & exposure
+pub mod sustainability;  // Energy & CO2e reporting
+pub mod search;  // Workspace full-text search
+pub mod saved_view;  // Saved views & filter specs
+pub mod import;  // Spreadsheet imports (CIs, hardware pool)
+pub mod report_schedule;  // Scheduled reports & artifacts
+pub mod integration;  // Integration hub connections & sync runs
+pub mod build_checklist;  // Destination cluster build validation
+pub mod firmware_baseline;  // Host firmware/driver baselines & remediation
+pub mod vm_hygiene;  // Snapshot & VMware Tools findings, VM remediation tasks
+pub mod cpu_compatibility;  // CPU generation/EVC groups & live vs cold migration modes
+pub mod guest_scan;  // In-guest scan credentials, results & collected configuration
+pub mod naming_policy;  // Per-tenant naming rules, mandatory tags & violations
+pub mod webhook;  // Outbound webhook subscriptions, events & delivery logs
+pub mod collector;  // Remote collectors & the VM metric samples they stream
+pub mod spare_part;  // Hardware pool spare parts & consumption
+pub mod procurement;  // BOM purchase requests & purchase orders
+pub mod component_classification;  // Classifier review queue & tenant corrections
+pub mod upload_session;  // Resumable chunked uploads
+pub mod document_review;  // Generated document review & sign-off
+pub mod automation_export;  // Placement plans resolved for IaC & cutover automation
+pub mod reconciliation;  // Post-migration reconciliation of planned vs discovered VMs
+pub mod license;  // Signed license keys, seats & module entitlements
+pub mod usage_telemetry;  // Opt-in anonymized feature usage counts
+pub mod retention;  // Data retention policies & purge reports
+pub mod privacy;  // User data export & erasure requests
+pub mod project_access;  // Per-project membership, roles & invitations
+pub mod data_variable;  // User-defined data variables & expressions
@@ -0,0 +1,132 @@
+// Archer - Naming Policy Models
+// Naming rules and mandatory tags for target clusters, VMs and networks
+// (installation default and per-tenant policies) and their violations
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Kinds of objects a naming policy governs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingTarget {
+    /// Migration wizard target clusters and destination clusters
+    Cluster,
+    /// Placed VMs, by their name and placement tags
+    Vm,
+    /// Destination networks of network mappings
+    Network,
+}
+
+impl NamingTarget {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Cluster => "Cluster",
+            Self::Vm => "VM",
+            Self::Network => "Network",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingPatternKind {
+    /// A regular expression that must match the whole name
+    #[default]
+    Regex,
+    /// Literal text with `{token}` placeholders, e.g. `{site}-{env:prd|tst}-CL{##}`
+    Tokens,
+}
+
+/// A name is compliant when it matches at least one rule of its target
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NamingRule {
+    pub target: NamingTarget,
+    #[serde(default)]
+    pub kind: NamingPatternKind,
+    pub pattern: String,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl NamingRule {
+    /// Description, or the pattern itself, for violation messages
+    pub fn label(&self) -> &str {
+        self.description.as_deref().unwrap_or(&self.pattern)
+    }
+}
+
+/// A tag every object of the target must carry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagRequirement {
+    pub target: NamingTarget,
+    pub key: String,
+    /// Values the tag may take; empty allows any non-blank value
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+}
+
+/// Naming rules and mandatory tags. Without a `tenant_id` the policy is the
+/// installation default, applied to every tenant without its own policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamingPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<NamingRule>,
+    #[serde(default)]
+    pub mandatory_tags: Vec<TagRequirement>,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertNamingPolicyRequest {
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<NamingRule>,
+    #[serde(default)]
+    pub mandatory_tags: Vec<TagRequirement>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingViolationKind {
+    /// The name matches none of the target's rules
+    Name,
+    MissingTag,
+    /// The tag is set to a value outside its allowed values
+    TagValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NamingViolation {
+    pub target: NamingTarget,
+    /// Record key of the cluster, placement or network mapping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_id: Option<String>,
+    pub object_name: String,
+    pub kind: NamingViolationKind,
+    pub message: String,
+}
+
+/// Naming compliance of one migration wizard project
+#[derive(Debug, Clone, Serialize)]
+pub struct NamingComplianceReport {
+    pub project_id: String,
+    /// Tenant whose policy applied, None for the installation default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_tenant_id: Option<String>,
+    /// False when no policy applies and nothing was checked
+    pub policy_found: bool,
+    pub clusters_checked: usize,
+    pub vms_checked: usize,
+    pub networks_checked: usize,
+    pub violations: Vec<NamingViolation>,
+    pub evaluated_at: DateTime<Utc>,
+}
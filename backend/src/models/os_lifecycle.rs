@@ -0,0 +1,156 @@
+// OS Lifecycle Models
+// Guest OS end-of-support dataset and exposure reporting
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// =============================================================================
+// LIFECYCLE DATASET
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum OsFamily {
+    WindowsServer,
+    WindowsClient,
+    Rhel,
+    Centos,
+    Ubuntu,
+    Sles,
+    Debian,
+}
+
+impl OsFamily {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OsFamily::WindowsServer => "Windows Server",
+            OsFamily::WindowsClient => "Windows",
+            OsFamily::Rhel => "Red Hat Enterprise Linux",
+            OsFamily::Centos => "CentOS",
+            OsFamily::Ubuntu => "Ubuntu",
+            OsFamily::Sles => "SUSE Linux Enterprise Server",
+            OsFamily::Debian => "Debian",
+        }
+    }
+}
+
+/// Support dates for one OS release.
+///
+/// `mainstream_end` is the end of standard/general support; `extended_end`
+/// the end of paid extended support (ESU, ELS, ESM, LTSS). Either may be
+/// `None` when the vendor has not announced a date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsLifecycleEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub family: OsFamily,
+    /// Release as normalised, e.g. "2008 R2", "7", "20.04"
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mainstream_end: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extended_end: Option<NaiveDate>,
+}
+
+impl OsLifecycleEntry {
+    pub fn display_name(&self) -> String {
+        format!("{} {}", self.family.label(), self.version)
+    }
+
+    pub fn status_on(&self, date: NaiveDate) -> SupportStatus {
+        match (self.mainstream_end, self.extended_end) {
+            (_, Some(extended)) if date > extended => SupportStatus::EndOfSupport,
+            (Some(mainstream), None) if date > mainstream => SupportStatus::EndOfSupport,
+            (Some(mainstream), Some(_)) if date > mainstream => SupportStatus::ExtendedSupport,
+            _ => SupportStatus::Supported,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportStatus {
+    Supported,
+    /// Past mainstream support, still covered by paid extended support
+    ExtendedSupport,
+    EndOfSupport,
+    /// OS string missing or not recognised, or release not in the dataset
+    Unknown,
+}
+
+impl SupportStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SupportStatus::Supported => "Supported",
+            SupportStatus::ExtendedSupport => "Extended support only",
+            SupportStatus::EndOfSupport => "End of support",
+            SupportStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Result of normalising a free-form guest OS string
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NormalizedOs {
+    pub family: OsFamily,
+    /// `None` when the family is recognised but the release is not stated
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsClassification {
+    pub raw: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalized: Option<NormalizedOs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<OsLifecycleEntry>,
+    pub status: SupportStatus,
+}
+
+// =============================================================================
+// EXPOSURE REPORT
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsExposureReport {
+    pub project_id: String,
+    pub as_of: NaiveDate,
+    pub total_vms: usize,
+    pub supported: usize,
+    pub extended_support: usize,
+    pub end_of_support: usize,
+    pub unknown: usize,
+    pub by_os: Vec<OsExposureRow>,
+    /// Raw OS strings that could not be matched to the dataset
+    pub unrecognized: Vec<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsExposureRow {
+    pub os: String,
+    pub status: SupportStatus,
+    pub vm_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mainstream_end: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended_end: Option<NaiveDate>,
+}
+
+// =============================================================================
+// REQUEST MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct OsLifecycleImportRequest {
+    pub entries: Vec<OsLifecycleEntry>,
+    /// Remove previously imported entries before applying this import
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClassifyOsQuery {
+    pub os: String,
+}
@@ -0,0 +1,82 @@
+// Archer - Personal Data Models
+// Machine-readable export of everything tied to a user account, and erasure
+// requests that anonymize a departed user once approved
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Everything Archer holds about one user
+#[derive(Debug, Clone, Serialize)]
+pub struct UserDataExport {
+    pub exported_at: DateTime<Utc>,
+    /// The account, without its password hash
+    pub account: serde_json::Value,
+    /// Tickets the user raised, including archived ones
+    pub tickets_authored: Vec<serde_json::Value>,
+    /// Tickets assigned to the user
+    pub tickets_assigned: Vec<serde_json::Value>,
+    /// Ticket comments and notes, including archived ones
+    pub comments: Vec<serde_json::Value>,
+    pub audit_entries: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErasureStatus {
+    PendingApproval,
+    Approved,
+    Rejected,
+    Completed,
+    Cancelled,
+}
+
+/// Stored in `user_erasure_request`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErasureRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub user_id: Thing,
+    /// Replaced by the pseudonym once the erasure has run
+    pub username: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub status: ErasureStatus,
+    /// Approval run in the workflow engine
+    #[serde(default)]
+    pub workflow_instance_id: Option<Thing>,
+    pub requested_by: String,
+    #[serde(default)]
+    pub executed_by: Option<String>,
+    #[serde(default)]
+    pub summary: Option<ErasureSummary>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Request erasure of a departed user's personal identifiers
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateErasureRequest {
+    pub user_id: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Approval workflow; defaults to `USER_ERASURE_WORKFLOW`
+    #[serde(default)]
+    pub workflow_id: Option<String>,
+}
+
+/// Records rewritten by an erasure
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErasureSummary {
+    /// Name the user's records now carry
+    pub pseudonym: String,
+    pub comments: u64,
+    pub ticket_history: u64,
+    /// Tickets assigned by username, now assigned by user id
+    pub tickets_reassigned: u64,
+    pub audit_entries: u64,
+    pub kb_articles: u64,
+    pub sessions_revoked: u64,
+}
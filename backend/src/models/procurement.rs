@@ -0,0 +1,161 @@
+// Archer ITSM - Procurement Models
+// Purchase requests raised from an approved BOM, their workflow approval,
+// purchase order tracking per line and receipt into the hardware pool
+
+use chrono::{DateTime, Utc};
+use core_engine::currency::{BomLine, Money};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PurchaseRequestStatus {
+    Draft,
+    PendingApproval,
+    Approved,
+    Rejected,
+    Ordered,
+    PartiallyReceived,
+    Received,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LineStatus {
+    Pending,
+    Ordered,
+    Shipped,
+    PartiallyReceived,
+    Received,
+    Cancelled,
+}
+
+/// Where received units of a line go
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReceiptTarget {
+    /// Restock an existing spare part
+    SparePart { spare_part_id: String },
+    /// Add one hardware pool server per unit received
+    Server {
+        model: String,
+        /// Asset tags are `<prefix>-001`, `<prefix>-002`, ...
+        asset_tag_prefix: String,
+        #[serde(default)]
+        location: Option<String>,
+        #[serde(default)]
+        datacenter: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurchaseRequestLine {
+    pub line_no: u32,
+    pub part_number: String,
+    #[serde(default)]
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: Money,
+    #[serde(default)]
+    pub received_quantity: u32,
+    pub status: LineStatus,
+    #[serde(default)]
+    pub expected_delivery: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub receive_into: Option<ReceiptTarget>,
+}
+
+impl PurchaseRequestLine {
+    pub fn outstanding(&self) -> u32 {
+        self.quantity.saturating_sub(self.received_quantity)
+    }
+}
+
+/// Stored in `purchase_request`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurchaseRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub title: String,
+    #[serde(default)]
+    pub project_id: Option<Thing>,
+    pub vendor: String,
+    pub lines: Vec<PurchaseRequestLine>,
+    /// Line totals in the currencies they were quoted in
+    pub totals_by_currency: BTreeMap<String, f64>,
+    pub status: PurchaseRequestStatus,
+    /// Approval run in the workflow engine
+    #[serde(default)]
+    pub workflow_instance_id: Option<Thing>,
+    #[serde(default)]
+    pub po_number: Option<String>,
+    #[serde(default)]
+    pub order_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub requested_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Convert an approved BOM into a purchase request
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePurchaseRequest {
+    pub title: String,
+    pub project_id: Option<String>,
+    pub vendor: String,
+    pub lines: Vec<CreatePurchaseLine>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePurchaseLine {
+    #[serde(flatten)]
+    pub bom_line: BomLine,
+    pub receive_into: Option<ReceiptTarget>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PurchaseRequestListQuery {
+    pub status: Option<PurchaseRequestStatus>,
+    pub project_id: Option<String>,
+    pub vendor: Option<String>,
+}
+
+/// Send a draft for approval
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SubmitPurchaseRequest {
+    /// Approval workflow; defaults to `PROCUREMENT_APPROVAL_WORKFLOW`
+    pub workflow_id: Option<String>,
+}
+
+/// Record the purchase order placed for an approved request
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecordOrderRequest {
+    pub po_number: String,
+    pub order_date: Option<DateTime<Utc>>,
+    pub expected_delivery: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateLineRequest {
+    /// Only `SHIPPED` or `CANCELLED`; receipt goes through the receive endpoint
+    pub status: Option<LineStatus>,
+    pub expected_delivery: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReceiveLineRequest {
+    pub quantity: u32,
+    /// Serial numbers for server lines, in receipt order
+    #[serde(default)]
+    pub serial_numbers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PurchaseRequestListResponse {
+    pub requests: Vec<PurchaseRequest>,
+    pub total: usize,
+}
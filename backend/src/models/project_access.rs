@@ -0,0 +1,115 @@
+// Archer - Project Access Models
+// Per-project membership of users and teams with owner/editor/viewer roles,
+// and invitations that add them once accepted
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+pub use core_engine::models::project::ProjectRole;
+pub use crate::models::hld::DocumentAudience;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrincipalType {
+    User,
+    Team,
+}
+
+/// Stored in `project_membership`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMembership {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// Raw project id, e.g. the `abc` of `migration_wizard_project:abc`
+    pub project_id: String,
+    pub principal_type: PrincipalType,
+    /// `users:...` or `teams:...`
+    pub principal_id: String,
+    pub role: ProjectRole,
+    /// Variant of generated documents the member gets
+    #[serde(default)]
+    pub audience: DocumentAudience,
+    pub added_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Who an invitation is for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Invitee {
+    /// A user account, `users:...`
+    User { id: String },
+    /// A team, `teams:...`; accepted by one of its leads
+    Team { id: String },
+    /// Someone who may not have an account yet; accepted by the user with this email
+    Email { address: String },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Revoked,
+}
+
+/// Stored in `project_invitation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInvitation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: String,
+    pub invitee: Invitee,
+    pub role: ProjectRole,
+    #[serde(default)]
+    pub audience: DocumentAudience,
+    pub status: InvitationStatus,
+    pub invited_by: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    #[serde(default)]
+    pub responded_by: Option<String>,
+    #[serde(default)]
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectInvitation {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// The caller's access to a project
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectAccessSummary {
+    pub project_id: String,
+    /// `None` when the caller has no access
+    pub role: Option<ProjectRole>,
+    /// The project has no members yet and is open to every signed-in user
+    pub open: bool,
+    /// Variant of generated documents the caller gets
+    pub audience: DocumentAudience,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InviteRequest {
+    pub invitee: Invitee,
+    pub role: ProjectRole,
+    #[serde(default)]
+    pub audience: DocumentAudience,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberRequest {
+    pub role: ProjectRole,
+    /// Unchanged when omitted
+    #[serde(default)]
+    pub audience: Option<DocumentAudience>,
+}
@@ -1,8 +1,13 @@
 use chrono::{DateTime, Utc};
+use core_engine::models::ImportReport;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
 
+use crate::models::document_review::DocumentApprovalRecord;
+use crate::models::hld::DocumentAudience;
+use crate::models::workflow::StorageBackend;
+
 // =============================================================================
 // PROJECT MANAGEMENT MODELS
 // =============================================================================
@@ -863,6 +868,9 @@ pub struct RvToolsUpload {
     pub uploaded_at: DateTime<Utc>,
     pub processed_at: Option<DateTime<Utc>>,
     pub uploaded_by: String,
+    /// Rows that could not be imported, set once processing finishes
+    #[serde(default)]
+    pub import_report: Option<ImportReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -873,6 +881,9 @@ pub enum RvToolsStatus {
     Processing,
     #[serde(rename = "processed")]
     Processed,
+    /// Processed, but some rows were skipped; see the import report
+    #[serde(rename = "completed_with_warnings")]
+    CompletedWithWarnings,
     #[serde(rename = "failed")]
     Failed,
 }
@@ -1057,6 +1068,9 @@ pub struct DestinationCluster {
     
     // Capacity Configuration
     pub overcommit_ratios: OvercommitRatios,
+    /// Library policy the ratios come from; None for custom ratios
+    #[serde(default)]
+    pub overcommit_policy_id: Option<Thing>,
     pub ha_policy: HaPolicy,
     pub capacity_totals: ClusterCapacity,
     pub capacity_available: ClusterCapacity,
@@ -1080,7 +1094,7 @@ pub struct DestinationCluster {
     pub created_by: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum HypervisorType {
     #[serde(rename = "hyper-v")]
     HyperV,
@@ -1153,7 +1167,7 @@ pub enum ClusterStatus {
     Decommissioned,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BuildStatus {
     #[serde(rename = "not_started")]
     NotStarted,
@@ -1440,7 +1454,13 @@ pub struct NetworkProfileTemplate {
     pub is_standard: bool,
     pub is_active: bool,
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Author of a custom template; None for the standard ones
+    #[serde(default)]
+    pub created_by: Option<String>,
+    // The seeded standard templates carry no timestamps
+    #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
     pub updated_at: DateTime<Utc>,
 }
 
@@ -1482,6 +1502,18 @@ pub enum NetworkPurpose {
     Backup,
 }
 
+impl NetworkPurpose {
+    pub fn key(&self) -> &'static str {
+        match self {
+            NetworkPurpose::Management => "management",
+            NetworkPurpose::Workload => "workload",
+            NetworkPurpose::Storage => "storage",
+            NetworkPurpose::Migration => "migration",
+            NetworkPurpose::Backup => "backup",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkValidationRule {
     pub rule_type: NetworkRuleType,
@@ -1504,6 +1536,18 @@ pub enum NetworkRuleType {
     TeamingConfig,
 }
 
+impl NetworkRuleType {
+    pub fn key(&self) -> &'static str {
+        match self {
+            NetworkRuleType::MinBandwidth => "min_bandwidth",
+            NetworkRuleType::RdmaSupport => "rdma_support",
+            NetworkRuleType::VlanSeparation => "vlan_separation",
+            NetworkRuleType::NicCount => "nic_count",
+            NetworkRuleType::TeamingConfig => "teaming_config",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkProfileInstance {
     pub id: Option<Thing>,
@@ -1535,6 +1579,68 @@ pub struct NetworkValidationResult {
     pub recommendation: Option<String>,
 }
 
+/// Create or replace a custom network profile template
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveNetworkProfileTemplateRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub hypervisor: HypervisorType,
+    pub storage_type: DestinationStorageType,
+    pub required_nics: i32,
+    pub recommended_nics: Option<i32>,
+    #[serde(default)]
+    pub requires_rdma: bool,
+    #[serde(default)]
+    pub requires_teaming: bool,
+    pub min_bandwidth_gbps: f64,
+    pub network_topology: NetworkTopology,
+    #[serde(default)]
+    pub vlan_requirements: Vec<VlanRequirement>,
+    #[serde(default)]
+    pub validation_rules: Vec<NetworkValidationRule>,
+    pub example_configuration: Option<String>,
+    pub documentation_url: Option<String>,
+    pub is_active: Option<bool>,
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NetworkProfileTemplateFilter {
+    pub hypervisor: Option<HypervisorType>,
+    #[serde(default)]
+    pub include_inactive: bool,
+}
+
+/// Check a cluster against a template; without one, against the template
+/// of the network profile applied to the cluster
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ValidateClusterNetworkRequest {
+    pub template_id: Option<String>,
+}
+
+/// What the hardware pool records about one node's network adapters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeNetworkFacts {
+    pub asset_tag: String,
+    pub nic_count: Option<i32>,
+    pub nic_speed_gbps: Option<f64>,
+    pub rdma_capable: Option<bool>,
+}
+
+/// Outcome of checking a destination cluster against a network profile template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterNetworkValidation {
+    pub cluster_id: String,
+    pub template_id: String,
+    pub template_name: String,
+    /// No error or critical rule failed
+    pub passed: bool,
+    pub results: Vec<NetworkValidationResult>,
+    pub nodes: Vec<NodeNetworkFacts>,
+    pub checked_at: DateTime<Utc>,
+}
+
 // =============================================================================
 // DOCUMENT TEMPLATE MODELS (for HLD Generation)
 // =============================================================================
@@ -1617,6 +1723,98 @@ pub struct GeneratedDocument {
     pub generated_at: DateTime<Utc>,
     pub generated_by: String,
     pub expires_at: Option<DateTime<Utc>>,
+
+    // Versioning: regenerating adds a record, earlier artifacts are kept
+    #[serde(default = "first_document_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub previous_version: Option<Thing>,
+    /// Inputs that shaped the output: sections, template, anonymization
+    #[serde(default)]
+    pub generation_parameters: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub section_snapshot: Vec<GeneratedSectionSnapshot>,
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+
+    // Sign-off: a published version is locked and has a second artifact
+    // carrying the approval page
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub approval_record: Option<DocumentApprovalRecord>,
+    #[serde(default)]
+    pub published_file_path: Option<String>,
+
+    // Redacted copies from the same run; the record's own artifact is the
+    // full document
+    #[serde(default)]
+    pub variants: Vec<DocumentVariant>,
+}
+
+fn first_document_version() -> u32 {
+    1
+}
+
+/// A copy of a generated document without the sections its audience may not see
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentVariant {
+    pub audience: DocumentAudience,
+    pub file_path: String,
+    pub file_size_bytes: i64,
+    /// Sections left out
+    pub redacted_sections: Vec<String>,
+    /// Variables left out, with their values
+    pub redacted_variables: Vec<String>,
+}
+
+/// What one section of a generated document was built from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedSectionSnapshot {
+    pub section_id: String,
+    pub display_name: String,
+    pub order_index: i32,
+    /// Variables the section reads
+    pub variables: Vec<String>,
+    /// SHA-256 over the title and variable values, to spot changed sections
+    pub content_hash: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionChangeKind {
+    Added,
+    Removed,
+    Modified,
+    /// Same content at a different position
+    Moved,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionVersionChange {
+    pub section_id: String,
+    pub display_name: String,
+    pub change: VersionChangeKind,
+    /// Variables of the section whose values differ
+    pub changed_variables: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueVersionChange {
+    pub name: String,
+    pub change: VersionChangeKind,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+}
+
+/// What regeneration altered between two versions of a document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentVersionDiff {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub sections: Vec<SectionVersionChange>,
+    pub variables: Vec<ValueVersionChange>,
+    pub parameters: Vec<ValueVersionChange>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,114 @@
+// Archer - Post-Migration Reconciliation Models
+// The target state discovered after cutover and the report comparing it
+// with the project's placement plan
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where the discovered target state comes from
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetInventorySource {
+    /// VMs synced from a Nutanix Prism Central connection
+    #[default]
+    Nutanix,
+    /// VMs sent with the request, e.g. a Hyper-V inventory export
+    Inventory,
+    /// An uploaded workbook or CSV in the RVTools vInfo layout
+    Workbook,
+}
+
+/// A VM found on the target platform
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TargetVm {
+    pub name: String,
+    #[serde(default)]
+    pub cluster: Option<String>,
+    #[serde(default)]
+    pub cpus: Option<i32>,
+    #[serde(default)]
+    pub memory_mb: Option<i32>,
+    #[serde(default)]
+    pub ip_addresses: Vec<String>,
+    /// Names of the networks the VM's adapters connect to
+    #[serde(default)]
+    pub networks: Vec<String>,
+    #[serde(default)]
+    pub power_state: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReconciliationRequest {
+    #[serde(default)]
+    pub source: TargetInventorySource,
+    /// Nutanix connection to read; all connections when omitted
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    /// Only reconcile the VMs of this wave
+    #[serde(default)]
+    pub wave_id: Option<String>,
+    /// The target VMs when `source` is `inventory`
+    #[serde(default)]
+    pub vms: Vec<TargetVm>,
+}
+
+/// Planned placements compared with the discovered target state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub project_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wave_id: Option<String>,
+    pub source: TargetInventorySource,
+    /// Placed VMs in scope of the report
+    pub planned_vms: usize,
+    /// Planned VMs found on the target
+    pub found_vms: usize,
+    pub missing_vms: Vec<MissingVm>,
+    pub wrong_cluster: Vec<ClusterMismatch>,
+    pub spec_drift: Vec<SpecDrift>,
+    pub network_mismatches: Vec<NetworkMismatch>,
+    /// Found VMs whose network can't be checked: the target reports neither
+    /// an IPv4 address nor a network name for them
+    pub unverified_networks: Vec<String>,
+    /// No VM is missing, on the wrong cluster, drifted or on the wrong network
+    pub passed: bool,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingVm {
+    pub vm_id: String,
+    pub vm_name: String,
+    pub planned_cluster: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterMismatch {
+    pub vm_id: String,
+    pub vm_name: String,
+    pub planned_cluster: String,
+    pub actual_cluster: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecDrift {
+    pub vm_id: String,
+    pub vm_name: String,
+    /// cpus or memory_mb
+    pub field: String,
+    pub planned: i32,
+    pub actual: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkMismatch {
+    pub vm_id: String,
+    pub vm_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_network: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_subnet: Option<String>,
+    pub actual_ip_addresses: Vec<String>,
+    pub actual_networks: Vec<String>,
+    pub message: String,
+}
@@ -0,0 +1,198 @@
+// Archer - Scheduled Report Models
+// Recurring report generation on cron schedules, stored artifacts, and
+// per-schedule distribution over email and Microsoft Teams
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// ============================================================================
+// TEMPLATES & DISTRIBUTION
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportTemplate {
+    /// Hardware pool servers, cores, memory and storage by datacenter and status
+    CapacitySummary,
+    /// Response/resolution SLA attainment by ticket priority
+    SlaCompliance,
+    /// Software entitlements vs. consumption from CMDB software CIs
+    LicensePosition,
+}
+
+impl ReportTemplate {
+    pub fn title(&self) -> &'static str {
+        match self {
+            ReportTemplate::CapacitySummary => "Capacity Summary",
+            ReportTemplate::SlaCompliance => "SLA Compliance",
+            ReportTemplate::LicensePosition => "License Position",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ArtifactFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArtifactFormat::Csv => "csv",
+            ArtifactFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ArtifactFormat::Csv => "text/csv",
+            ArtifactFormat::Json => "application/json",
+        }
+    }
+}
+
+/// Who receives each generated artifact
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportDistribution {
+    /// Email addresses that get the artifact as an attachment
+    #[serde(default)]
+    pub email_recipients: Vec<String>,
+    /// Teams incoming-webhook URL that gets a card linking to the artifact
+    pub teams_webhook_url: Option<String>,
+}
+
+// ============================================================================
+// SCHEDULES
+// ============================================================================
+
+/// Stored in `report_schedules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportScheduleDefinition {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub template: ReportTemplate,
+    /// Cron expression in UTC; 5-field (minute precision) or 6-field with seconds
+    pub cron: String,
+    #[serde(default)]
+    pub format: ArtifactFormat,
+    /// Days of history covered by period-based templates (SLA compliance)
+    #[serde(default = "default_period_days")]
+    pub period_days: u32,
+    #[serde(default)]
+    pub distribution: ReportDistribution,
+    pub enabled: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_status: Option<ArtifactStatus>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub tenant_id: Option<Thing>,
+}
+
+fn default_period_days() -> u32 {
+    30
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateReportScheduleRequest {
+    pub name: String,
+    pub template: ReportTemplate,
+    pub cron: String,
+    #[serde(default)]
+    pub format: ArtifactFormat,
+    #[serde(default = "default_period_days")]
+    pub period_days: u32,
+    #[serde(default)]
+    pub distribution: ReportDistribution,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateReportScheduleRequest {
+    pub name: Option<String>,
+    pub cron: Option<String>,
+    pub format: Option<ArtifactFormat>,
+    pub period_days: Option<u32>,
+    pub distribution: Option<ReportDistribution>,
+    pub enabled: Option<bool>,
+}
+
+// ============================================================================
+// ARTIFACTS & DELIVERY
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ArtifactStatus {
+    /// Generated and every configured channel accepted it
+    Delivered,
+    /// Generated but at least one channel failed
+    PartiallyDelivered,
+    /// Generated with no distribution configured
+    Generated,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryChannel {
+    Email,
+    Teams,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeliveryStatus {
+    Sent,
+    /// Channel not configured on this server (e.g. no SMTP host)
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryResult {
+    pub channel: DeliveryChannel,
+    /// Recipient address or webhook host
+    pub target: String,
+    pub status: DeliveryStatus,
+    pub message: Option<String>,
+}
+
+/// One generated report file, stored in `report_artifacts`; content lives on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportArtifact {
+    pub id: Option<Thing>,
+    pub schedule_id: Option<Thing>,
+    pub schedule_name: String,
+    pub template: ReportTemplate,
+    pub format: ArtifactFormat,
+    pub filename: String,
+    pub file_path: String,
+    pub size_bytes: u64,
+    pub row_count: usize,
+    pub status: ArtifactStatus,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub deliveries: Vec<DeliveryResult>,
+    pub generated_at: DateTime<Utc>,
+    pub tenant_id: Option<Thing>,
+}
+
+/// Tabular report body shared by every template
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Headline figures included in the notification text
+    pub summary: serde_json::Value,
+}
@@ -360,3 +360,111 @@ pub struct KbArticleStats {
     pub helpful_votes: i64,
     pub not_helpful_votes: i64,
 }
+
+// ============================================================================
+// TICKET ANALYTICS MODELS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsGranularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+/// Query parameters shared by the ticket analytics endpoints
+#[derive(Debug, Clone, Deserialize)]
+pub struct TicketAnalyticsQuery {
+    /// Range start; defaults to 30 days before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Range end; defaults to now
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub granularity: AnalyticsGranularity,
+    /// Bypass the cache and recompute
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeTrendPoint {
+    /// Start of the bucket (UTC midnight; Monday for weeks, 1st for months)
+    pub period_start: DateTime<Utc>,
+    pub created: i64,
+    pub resolved: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionTimeStats {
+    pub label: String,
+    pub resolved: i64,
+    pub mean_hours: Option<f64>,
+    pub median_hours: Option<f64>,
+    pub p90_hours: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MttrStats {
+    pub overall: ResolutionTimeStats,
+    pub by_priority: Vec<ResolutionTimeStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FirstResponseStats {
+    /// Tickets with a first-response SLA outcome recorded
+    pub measured: i64,
+    pub met: i64,
+    pub compliance_pct: Option<f64>,
+    pub mean_minutes: Option<f64>,
+    pub by_priority: Vec<ComplianceBreakdown>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceBreakdown {
+    pub label: String,
+    pub measured: i64,
+    /// Met (first response) or breached (resolution SLA), depending on the section
+    pub count: i64,
+    pub rate_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlaBreachStats {
+    pub measured: i64,
+    pub breached: i64,
+    pub breach_rate_pct: Option<f64>,
+    pub by_priority: Vec<ComplianceBreakdown>,
+    pub by_group: Vec<ComplianceBreakdown>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BacklogBucket {
+    pub label: String,
+    pub min_days: i64,
+    pub max_days: Option<i64>,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BacklogAgeing {
+    /// Tickets open at the end of the range
+    pub open: i64,
+    pub buckets: Vec<BacklogBucket>,
+    pub by_priority: Vec<ChartDataPoint>,
+    pub oldest_days: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketAnalytics {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub granularity: AnalyticsGranularity,
+    pub volume: Vec<VolumeTrendPoint>,
+    pub mttr: MttrStats,
+    pub first_response: FirstResponseStats,
+    pub sla_breaches: SlaBreachStats,
+    pub backlog: BacklogAgeing,
+    pub generated_at: DateTime<Utc>,
+}
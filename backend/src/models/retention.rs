@@ -0,0 +1,93 @@
+// Archer - Data Retention Models
+// Retention policies per data class (installation default and per-tenant
+// overrides) and the reports of purge runs and dry-run previews
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Kinds of customer data that can be purged after a retention period
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionDataClass {
+    /// Raw rows of imported RVTools exports
+    RvtoolsRows,
+    /// Audit log entries, except the records of purges themselves
+    AuditLogs,
+    /// Generated project documents and HLD exports, with their files
+    GeneratedDocuments,
+}
+
+impl RetentionDataClass {
+    pub const ALL: [RetentionDataClass; 3] = [Self::RvtoolsRows, Self::AuditLogs, Self::GeneratedDocuments];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::RvtoolsRows => "rvtools_rows",
+            Self::AuditLogs => "audit_logs",
+            Self::GeneratedDocuments => "generated_documents",
+        }
+    }
+}
+
+/// How long one data class is kept. Without a `tenant_id` the policy is the
+/// installation default, applied to every tenant without its own policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub data_class: RetentionDataClass,
+    pub retain_days: u32,
+    /// Disabled policies keep the data forever, also overriding the default
+    pub enabled: bool,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertRetentionPolicyRequest {
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    pub data_class: RetentionDataClass,
+    pub retain_days: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurgeQuery {
+    /// Count what would be purged without deleting anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Records one policy purges (or would purge) from one table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeItem {
+    pub data_class: RetentionDataClass,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub table: String,
+    pub retain_days: u32,
+    /// Records created before this are purged
+    pub cutoff: DateTime<Utc>,
+    pub records: u64,
+    /// Stored document files deleted along with their records
+    #[serde(default)]
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub items: Vec<PurgeItem>,
+    pub total_records: u64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
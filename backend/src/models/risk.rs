@@ -0,0 +1,155 @@
+// Risk Register Models
+// Project risk register, auto-seeded from analysis findings and editable via API
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use super::workflow::HardwareCompatibilityResult;
+
+// =============================================================================
+// RISK REGISTER MODELS
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub title: String,
+    pub description: String,
+    pub category: RiskCategory,
+    pub impact: RiskRating,
+    pub likelihood: RiskRating,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub mitigation: String,
+    pub status: RiskStatus,
+    pub source: RiskSource,
+    /// Stable key of the analysis finding that raised this risk, used to
+    /// refresh auto-seeded entries without duplicating them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finding_key: Option<String>,
+    /// VMs, clusters or hosts the risk applies to
+    #[serde(default)]
+    pub affected_items: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RiskEntry {
+    /// Impact x likelihood, 1 (low/low) to 9 (high/high)
+    pub fn severity_score(&self) -> u8 {
+        self.impact.score() * self.likelihood.score()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskRating {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskRating {
+    pub fn score(&self) -> u8 {
+        match self {
+            RiskRating::Low => 1,
+            RiskRating::Medium => 2,
+            RiskRating::High => 3,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskRating::Low => "Low",
+            RiskRating::Medium => "Medium",
+            RiskRating::High => "High",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskCategory {
+    Compatibility,
+    Capacity,
+    Hardware,
+    Network,
+    Data,
+    Operational,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskStatus {
+    Open,
+    Mitigating,
+    Accepted,
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskSource {
+    Manual,
+    Analysis,
+}
+
+/// A risk raised by analysis, before it is merged into the register
+#[derive(Debug, Clone)]
+pub struct RiskFinding {
+    pub key: String,
+    pub title: String,
+    pub description: String,
+    pub category: RiskCategory,
+    pub impact: RiskRating,
+    pub likelihood: RiskRating,
+    pub mitigation: String,
+    pub affected_items: Vec<String>,
+}
+
+// =============================================================================
+// REQUEST MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRiskRequest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub category: RiskCategory,
+    pub impact: RiskRating,
+    pub likelihood: RiskRating,
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub mitigation: String,
+    #[serde(default)]
+    pub affected_items: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateRiskRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<RiskCategory>,
+    pub impact: Option<RiskRating>,
+    pub likelihood: Option<RiskRating>,
+    pub owner: Option<String>,
+    pub mitigation: Option<String>,
+    pub status: Option<RiskStatus>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SeedRisksRequest {
+    /// Optional result of a hardware compatibility check for the target hosts
+    pub hardware_compatibility: Option<HardwareCompatibilityResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeedRisksResponse {
+    pub created: usize,
+    pub refreshed: usize,
+    pub risks: Vec<RiskEntry>,
+}
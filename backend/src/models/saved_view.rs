@@ -0,0 +1,190 @@
+// Archer ITSM - Saved Views & Filter Specs
+// Structured filters (field / operator / value with AND/OR groups) for tickets
+// and configuration items, and per-user or per-team saved views that apply them
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// ============================================================================
+// FILTER SPEC
+// ============================================================================
+
+/// Entity a filter or view applies to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterEntity {
+    Tickets,
+    ConfigurationItems,
+}
+
+impl FilterEntity {
+    pub fn table(&self) -> &'static str {
+        match self {
+            FilterEntity::Tickets => "ticket",
+            FilterEntity::ConfigurationItems => "configuration_items",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogicalOperator {
+    #[default]
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    NotContains,
+    StartsWith,
+    In,
+    NotIn,
+    IsEmpty,
+    IsNotEmpty,
+}
+
+/// A single `field operator value` comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterCondition {
+    pub field: String,
+    pub operator: FilterOperator,
+    /// Omitted for `is_empty` / `is_not_empty`; an array for `in` / `not_in`
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+/// Either a nested group or a leaf condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FilterNode {
+    Group(FilterGroup),
+    Condition(FilterCondition),
+}
+
+/// Conditions joined by AND or OR; groups nest to express mixed logic
+///
+/// ```json
+/// { "op": "AND", "conditions": [
+///     { "field": "status", "operator": "in", "value": ["NEW", "IN_PROGRESS"] },
+///     { "op": "OR", "conditions": [
+///         { "field": "priority", "operator": "eq", "value": "P1" },
+///         { "field": "tags", "operator": "contains", "value": "vip" } ] } ] }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FilterGroup {
+    #[serde(default)]
+    pub op: LogicalOperator,
+    pub conditions: Vec<FilterNode>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Body for the `/query` endpoints
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FilterQueryRequest {
+    #[serde(default)]
+    pub filter: Option<FilterGroup>,
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterQueryResponse<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+// ============================================================================
+// SAVED VIEWS
+// ============================================================================
+
+/// Who can see a saved view
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ViewVisibility {
+    #[default]
+    Private,
+    Team,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedView {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub description: Option<String>,
+    pub entity: FilterEntity,
+    pub filter: FilterGroup,
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    /// Column keys the list UI should show, in order
+    #[serde(default)]
+    pub columns: Vec<String>,
+    pub visibility: ViewVisibility,
+    pub owner_id: String,
+    /// Team the view is shared with when visibility is TEAM
+    pub team_id: Option<Thing>,
+    #[serde(default)]
+    pub is_default: bool,
+    pub tenant_id: Option<Thing>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateSavedViewRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub entity: FilterEntity,
+    pub filter: FilterGroup,
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    #[serde(default)]
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub visibility: ViewVisibility,
+    pub team_id: Option<String>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateSavedViewRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub filter: Option<FilterGroup>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<SortOrder>,
+    pub columns: Option<Vec<String>>,
+    pub visibility: Option<ViewVisibility>,
+    pub team_id: Option<String>,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedViewListResponse {
+    pub views: Vec<SavedView>,
+    pub total: usize,
+}
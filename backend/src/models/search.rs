@@ -0,0 +1,86 @@
+// Workspace Search Models
+// Full-text search across tickets, KB articles, CIs, projects and generated documents
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// =============================================================================
+// ENTITY TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEntityType {
+    Ticket,
+    KbArticle,
+    ConfigurationItem,
+    Project,
+    Document,
+}
+
+impl SearchEntityType {
+    pub const ALL: [SearchEntityType; 5] = [
+        SearchEntityType::Ticket,
+        SearchEntityType::KbArticle,
+        SearchEntityType::ConfigurationItem,
+        SearchEntityType::Project,
+        SearchEntityType::Document,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchEntityType::Ticket => "ticket",
+            SearchEntityType::KbArticle => "kb_article",
+            SearchEntityType::ConfigurationItem => "configuration_item",
+            SearchEntityType::Project => "project",
+            SearchEntityType::Document => "document",
+        }
+    }
+
+    /// Accepts the canonical names plus the short aliases used by the command palette
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "ticket" | "tickets" => Some(SearchEntityType::Ticket),
+            "kb_article" | "kb" | "article" | "articles" => Some(SearchEntityType::KbArticle),
+            "configuration_item" | "ci" | "cis" | "cmdb" => Some(SearchEntityType::ConfigurationItem),
+            "project" | "projects" => Some(SearchEntityType::Project),
+            "document" | "documents" | "doc" | "docs" => Some(SearchEntityType::Document),
+            _ => None,
+        }
+    }
+}
+
+// =============================================================================
+// REQUEST / RESPONSE
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    /// Comma-separated entity types; all types when omitted
+    pub types: Option<String>,
+    /// Maximum hits returned overall (default 20, max 100)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub entity_type: SearchEntityType,
+    pub id: String,
+    pub title: String,
+    /// Excerpt with matched terms wrapped in `<mark>` tags
+    pub snippet: Option<String>,
+    /// BM25 relevance, summed over the matched fields
+    pub score: f64,
+    /// Frontend route for the command palette to navigate to
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub total: usize,
+    pub hits: Vec<SearchHit>,
+    /// Hits per entity type before the overall limit was applied
+    pub counts: HashMap<SearchEntityType, usize>,
+}
@@ -5,6 +5,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use surrealdb::sql::Thing;
 
 use super::project_models::OvercommitRatios;
@@ -115,3 +116,704 @@ pub struct UpdateSettingsRequest {
     pub features: Option<FeatureFlags>,
     pub updated_by: String,
 }
+
+// =============================================================================
+// OVERCOMMIT POLICY LIBRARY
+// =============================================================================
+
+/// Named overcommit ratios (e.g. "Prod 4:1 CPU / 1:1 RAM")
+///
+/// Destination clusters reference a policy instead of carrying their own
+/// numbers, so editing the policy re-rates every cluster that uses it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OvercommitPolicy {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub description: Option<String>,
+    pub cpu_ratio: f32,
+    pub memory_ratio: f32,
+    pub updated_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OvercommitPolicy {
+    pub fn ratios(&self) -> OvercommitRatios {
+        OvercommitRatios {
+            cpu_ratio: self.cpu_ratio,
+            memory_ratio: self.memory_ratio,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOvercommitPolicyRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub cpu_ratio: f32,
+    pub memory_ratio: f32,
+    pub updated_by: String,
+}
+
+/// Also the body of an impact preview, describing the proposed change
+#[derive(Debug, Deserialize)]
+pub struct UpdateOvercommitPolicyRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub cpu_ratio: Option<f32>,
+    pub memory_ratio: Option<f32>,
+    #[serde(default = "default_updated_by")]
+    pub updated_by: String,
+}
+
+fn default_updated_by() -> String {
+    "system".to_string()
+}
+
+/// Result of changing a policy, with how many clusters were re-rated
+#[derive(Debug, Serialize)]
+pub struct OvercommitPolicyUpdate {
+    pub policy: OvercommitPolicy,
+    pub clusters_updated: usize,
+}
+
+/// What a ratio change would do to the capacity of affected clusters
+#[derive(Debug, Serialize)]
+pub struct OvercommitImpactPreview {
+    pub proposed_ratios: OvercommitRatios,
+    pub clusters: Vec<ClusterOvercommitImpact>,
+    /// Clusters whose reserved capacity would exceed the new ceiling
+    pub clusters_over_capacity: usize,
+}
+
+/// Effective (overcommitted) capacity of one cluster before and after
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterOvercommitImpact {
+    pub cluster_id: String,
+    pub cluster_name: String,
+    pub current_ratios: OvercommitRatios,
+    pub current_vcpu_capacity: i64,
+    pub proposed_vcpu_capacity: i64,
+    pub current_memory_gb_capacity: i64,
+    pub proposed_memory_gb_capacity: i64,
+    pub reserved_vcpus: i64,
+    pub reserved_memory_gb: i64,
+    pub over_capacity: bool,
+}
+
+/// Reference a policy from a destination cluster
+#[derive(Debug, Deserialize)]
+pub struct ApplyOvercommitPolicyRequest {
+    pub policy_id: String,
+}
+
+// =============================================================================
+// EXCHANGE RATES
+// =============================================================================
+
+/// Manually maintained exchange rates; replaces the whole table
+#[derive(Debug, Deserialize)]
+pub struct UpdateExchangeRatesRequest {
+    /// Currency the rates are quoted against, e.g. "EUR"
+    pub base: String,
+    /// Units of each currency per one unit of `base`
+    pub rates: BTreeMap<String, f64>,
+}
+
+// =============================================================================
+// ANONYMIZATION
+// =============================================================================
+
+/// Anonymization settings for documents shared outside the organization.
+/// The secret is generated on first use and is never returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnonymizationSettings {
+    /// Key the pseudonyms are derived from; changing it changes every pseudonym
+    pub secret: String,
+    /// Values never anonymized, e.g. "*.vmware.com" or "10.0.0.*"
+    #[serde(default)]
+    pub preserve: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnonymizationSettingsResponse {
+    pub preserve: Vec<String>,
+    /// Local directory the mapping files are written to
+    pub map_dir: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAnonymizationSettingsRequest {
+    pub preserve: Vec<String>,
+}
+
+// =============================================================================
+// DOCUMENT STORAGE
+// =============================================================================
+
+/// Backend chosen in settings; overrides `DOCUMENT_STORAGE_BACKEND`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentStorageSettings {
+    pub backend: super::workflow::StorageBackend,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDocumentStorageSettingsRequest {
+    pub backend: super::workflow::StorageBackend,
+}
+
+/// Active document storage configuration
+#[derive(Debug, Serialize)]
+pub struct DocumentStorageSettingsResponse {
+    pub backend: super::workflow::StorageBackend,
+    /// `settings` when the backend was chosen in settings, else `environment`
+    pub backend_source: String,
+    /// Local directory, for the disk backend
+    pub directory: Option<String>,
+    /// Bucket and endpoint, for the S3 backend
+    pub bucket: Option<String>,
+    pub endpoint: Option<String>,
+    /// Lifetime of download URLs handed to the frontend
+    pub url_ttl_secs: u64,
+}
+
+/// Query options accepted by exports that can be anonymized
+#[derive(Debug, Default, Deserialize)]
+pub struct AnonymizeOptions {
+    #[serde(default)]
+    pub anonymize: bool,
+    /// Comma-separated preserve patterns for this export only
+    pub preserve: Option<String>,
+}
+
+impl AnonymizeOptions {
+    pub fn preserve_list(&self) -> Vec<String> {
+        self.preserve
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect()
+    }
+}
+
+/// A mapping file kept from an earlier anonymized export
+#[derive(Debug, Serialize)]
+pub struct AnonymizationMapSummary {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub entries: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeanonymizeRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeanonymizeResponse {
+    pub map_id: String,
+    pub text: String,
+}
+
+// =============================================================================
+// SETTINGS HIERARCHY
+// =============================================================================
+
+/// Layer a setting value is stored at. Later layers win:
+/// system ← tenant ← project ← user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingScope {
+    System,
+    Tenant,
+    Project,
+    User,
+}
+
+impl SettingScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingScope::System => "system",
+            SettingScope::Tenant => "tenant",
+            SettingScope::Project => "project",
+            SettingScope::User => "user",
+        }
+    }
+}
+
+/// Every setting that can be layered. Keys serialize as dotted names, e.g.
+/// `capacity.cpu_overcommit_ratio`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SettingKey {
+    #[serde(rename = "capacity.cpu_overcommit_ratio")]
+    CpuOvercommitRatio,
+    #[serde(rename = "capacity.memory_overcommit_ratio")]
+    MemoryOvercommitRatio,
+    #[serde(rename = "capacity.target_utilization_percent")]
+    TargetUtilizationPercent,
+    #[serde(rename = "timeline.migration_hours_per_host")]
+    MigrationHoursPerHost,
+    #[serde(rename = "timeline.decommission_hours_per_host")]
+    DecommissionHoursPerHost,
+    #[serde(rename = "timeline.expansion_hours_per_host")]
+    ExpansionHoursPerHost,
+    #[serde(rename = "timeline.maintenance_hours_per_host")]
+    MaintenanceHoursPerHost,
+    #[serde(rename = "organization.name")]
+    OrganizationName,
+    #[serde(rename = "documents.anonymize_exports")]
+    AnonymizeExports,
+    #[serde(rename = "display.currency")]
+    DisplayCurrency,
+    #[serde(rename = "display.theme")]
+    Theme,
+    #[serde(rename = "notifications.email_enabled")]
+    EmailNotifications,
+    #[serde(rename = "features.ai_assistant")]
+    FeatureAiAssistant,
+    #[serde(rename = "features.auto_placement_v2")]
+    FeatureAutoPlacementV2,
+    #[serde(rename = "features.graphql_api")]
+    FeatureGraphqlApi,
+    #[serde(rename = "features.guest_scan")]
+    FeatureGuestScan,
+    #[serde(rename = "features.hardware_pool")]
+    FeatureHardwarePool,
+    #[serde(rename = "features.rvtools_import")]
+    FeatureRvtoolsImport,
+    #[serde(rename = "features.capacity_analytics")]
+    FeatureCapacityAnalytics,
+    #[serde(rename = "features.timeline_gantt")]
+    FeatureTimelineGantt,
+}
+
+/// Type and bounds of a setting's value
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SettingType {
+    Bool,
+    Number { min: f64, max: f64 },
+    String { max_len: usize },
+    Choice { options: &'static [&'static str] },
+}
+
+impl SettingType {
+    /// Check a value against the type; the message says what was expected
+    pub fn validate(&self, value: &serde_json::Value) -> Result<(), String> {
+        match self {
+            SettingType::Bool if value.is_boolean() => Ok(()),
+            SettingType::Bool => Err("expected true or false".to_string()),
+            SettingType::Number { min, max } => match value.as_f64() {
+                Some(n) if n >= *min && n <= *max => Ok(()),
+                _ => Err(format!("expected a number between {} and {}", min, max)),
+            },
+            SettingType::String { max_len } => match value.as_str() {
+                Some(s) if !s.trim().is_empty() && s.len() <= *max_len => Ok(()),
+                _ => Err(format!("expected a non-empty string of at most {} characters", max_len)),
+            },
+            SettingType::Choice { options } => match value.as_str() {
+                Some(s) if options.contains(&s) => Ok(()),
+                _ => Err(format!("expected one of: {}", options.join(", "))),
+            },
+        }
+    }
+}
+
+/// What a key holds, its built-in default and how far down it can be overridden
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingDefinition {
+    pub key: SettingKey,
+    pub description: &'static str,
+    pub value_type: SettingType,
+    pub default: serde_json::Value,
+    /// Most specific layer the key may be set at; capacity ratios stop at
+    /// project so one user cannot change another's sizing
+    pub max_scope: SettingScope,
+}
+
+impl SettingKey {
+    pub const ALL: [SettingKey; 20] = [
+        SettingKey::CpuOvercommitRatio,
+        SettingKey::MemoryOvercommitRatio,
+        SettingKey::TargetUtilizationPercent,
+        SettingKey::MigrationHoursPerHost,
+        SettingKey::DecommissionHoursPerHost,
+        SettingKey::ExpansionHoursPerHost,
+        SettingKey::MaintenanceHoursPerHost,
+        SettingKey::OrganizationName,
+        SettingKey::AnonymizeExports,
+        SettingKey::DisplayCurrency,
+        SettingKey::Theme,
+        SettingKey::EmailNotifications,
+        SettingKey::FeatureAiAssistant,
+        SettingKey::FeatureAutoPlacementV2,
+        SettingKey::FeatureGraphqlApi,
+        SettingKey::FeatureGuestScan,
+        SettingKey::FeatureHardwarePool,
+        SettingKey::FeatureRvtoolsImport,
+        SettingKey::FeatureCapacityAnalytics,
+        SettingKey::FeatureTimelineGantt,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingKey::CpuOvercommitRatio => "capacity.cpu_overcommit_ratio",
+            SettingKey::MemoryOvercommitRatio => "capacity.memory_overcommit_ratio",
+            SettingKey::TargetUtilizationPercent => "capacity.target_utilization_percent",
+            SettingKey::MigrationHoursPerHost => "timeline.migration_hours_per_host",
+            SettingKey::DecommissionHoursPerHost => "timeline.decommission_hours_per_host",
+            SettingKey::ExpansionHoursPerHost => "timeline.expansion_hours_per_host",
+            SettingKey::MaintenanceHoursPerHost => "timeline.maintenance_hours_per_host",
+            SettingKey::OrganizationName => "organization.name",
+            SettingKey::AnonymizeExports => "documents.anonymize_exports",
+            SettingKey::DisplayCurrency => "display.currency",
+            SettingKey::Theme => "display.theme",
+            SettingKey::EmailNotifications => "notifications.email_enabled",
+            SettingKey::FeatureAiAssistant => "features.ai_assistant",
+            SettingKey::FeatureAutoPlacementV2 => "features.auto_placement_v2",
+            SettingKey::FeatureGraphqlApi => "features.graphql_api",
+            SettingKey::FeatureGuestScan => "features.guest_scan",
+            SettingKey::FeatureHardwarePool => "features.hardware_pool",
+            SettingKey::FeatureRvtoolsImport => "features.rvtools_import",
+            SettingKey::FeatureCapacityAnalytics => "features.capacity_analytics",
+            SettingKey::FeatureTimelineGantt => "features.timeline_gantt",
+        }
+    }
+
+    pub fn parse(key: &str) -> Option<SettingKey> {
+        SettingKey::ALL.into_iter().find(|k| k.as_str() == key)
+    }
+
+    pub fn definition(&self) -> SettingDefinition {
+        use serde_json::json;
+        let hours = SettingType::Number { min: 0.0, max: 200.0 };
+        let factors = TimelineFactors::default();
+        let (description, value_type, default, max_scope) = match self {
+            SettingKey::CpuOvercommitRatio => (
+                "vCPU to physical core ratio used for capacity planning",
+                SettingType::Number { min: 1.0, max: 32.0 },
+                json!(4.0),
+                SettingScope::Project,
+            ),
+            SettingKey::MemoryOvercommitRatio => (
+                "Memory overcommit ratio used for capacity planning",
+                SettingType::Number { min: 1.0, max: 32.0 },
+                json!(1.5),
+                SettingScope::Project,
+            ),
+            SettingKey::TargetUtilizationPercent => (
+                "Utilization a destination cluster is sized to, in percent",
+                SettingType::Number { min: 10.0, max: 100.0 },
+                json!(80.0),
+                SettingScope::Project,
+            ),
+            SettingKey::MigrationHoursPerHost => (
+                "Hours per host for migration activities",
+                hours,
+                json!(factors.migration_hours_per_host),
+                SettingScope::Project,
+            ),
+            SettingKey::DecommissionHoursPerHost => (
+                "Hours per host for decommission activities",
+                hours,
+                json!(factors.decommission_hours_per_host),
+                SettingScope::Project,
+            ),
+            SettingKey::ExpansionHoursPerHost => (
+                "Hours per host for expansion activities",
+                hours,
+                json!(factors.expansion_hours_per_host),
+                SettingScope::Project,
+            ),
+            SettingKey::MaintenanceHoursPerHost => (
+                "Hours per host for maintenance activities",
+                hours,
+                json!(factors.maintenance_hours_per_host),
+                SettingScope::Project,
+            ),
+            SettingKey::OrganizationName => (
+                "Organization name printed on generated documents",
+                SettingType::String { max_len: 200 },
+                json!("Archer"),
+                SettingScope::Tenant,
+            ),
+            SettingKey::AnonymizeExports => (
+                "Anonymize document exports unless the request says otherwise",
+                SettingType::Bool,
+                json!(false),
+                SettingScope::Project,
+            ),
+            SettingKey::DisplayCurrency => (
+                "Currency costs are shown in",
+                SettingType::String { max_len: 3 },
+                json!(crate::services::currency_service::DEFAULT_CURRENCY),
+                SettingScope::User,
+            ),
+            SettingKey::Theme => (
+                "Colour theme of the web and desktop apps",
+                SettingType::Choice {
+                    options: &["system", "light", "dark"],
+                },
+                json!("system"),
+                SettingScope::User,
+            ),
+            SettingKey::EmailNotifications => (
+                "Send notifications by email as well as in the app",
+                SettingType::Bool,
+                json!(true),
+                SettingScope::User,
+            ),
+            SettingKey::FeatureAiAssistant
+            | SettingKey::FeatureAutoPlacementV2
+            | SettingKey::FeatureGraphqlApi
+            | SettingKey::FeatureGuestScan
+            | SettingKey::FeatureHardwarePool
+            | SettingKey::FeatureRvtoolsImport
+            | SettingKey::FeatureCapacityAnalytics
+            | SettingKey::FeatureTimelineGantt => {
+                let flag = FeatureFlag::from_key(*self).expect("feature key");
+                (
+                    flag.description(),
+                    SettingType::Bool,
+                    json!(flag.default_enabled()),
+                    SettingScope::User,
+                )
+            }
+        };
+        SettingDefinition {
+            key: *self,
+            description,
+            value_type,
+            default,
+            max_scope,
+        }
+    }
+
+    /// Value the legacy global settings record holds for this key, so edits
+    /// made through `PATCH /settings` keep applying at the system layer
+    pub fn legacy_value(&self, global: &GlobalSettings) -> Option<serde_json::Value> {
+        use serde_json::json;
+        let timeline = &global.timeline_factors;
+        match self {
+            SettingKey::CpuOvercommitRatio => Some(json!(global.default_overcommit_ratios.cpu_ratio)),
+            SettingKey::MemoryOvercommitRatio => Some(json!(global.default_overcommit_ratios.memory_ratio)),
+            SettingKey::MigrationHoursPerHost => Some(json!(timeline.migration_hours_per_host)),
+            SettingKey::DecommissionHoursPerHost => Some(json!(timeline.decommission_hours_per_host)),
+            SettingKey::ExpansionHoursPerHost => Some(json!(timeline.expansion_hours_per_host)),
+            SettingKey::MaintenanceHoursPerHost => Some(json!(timeline.maintenance_hours_per_host)),
+            SettingKey::OrganizationName => global.organization_name.as_ref().map(|name| json!(name)),
+            SettingKey::FeatureHardwarePool => Some(json!(global.features.enable_hardware_pool)),
+            SettingKey::FeatureRvtoolsImport => Some(json!(global.features.enable_rvtools_import)),
+            SettingKey::FeatureCapacityAnalytics => Some(json!(global.features.enable_capacity_analytics)),
+            SettingKey::FeatureTimelineGantt => Some(json!(global.features.enable_timeline_gantt)),
+            _ => None,
+        }
+    }
+}
+
+/// A value set for one key at one layer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub scope: SettingScope,
+    /// Tenant, project or user id; empty for the system layer
+    pub scope_id: String,
+    pub key: SettingKey,
+    pub value: serde_json::Value,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Audit record of one change to an override
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingChange {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub scope: SettingScope,
+    pub scope_id: String,
+    pub key: SettingKey,
+    /// None when the override did not exist before
+    pub old_value: Option<serde_json::Value>,
+    /// None when the override was cleared
+    pub new_value: Option<serde_json::Value>,
+    pub changed_by: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Where an effective value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingSource {
+    /// Built-in default; nothing is stored at any layer
+    Default,
+    System,
+    Tenant,
+    Project,
+    User,
+}
+
+impl From<SettingScope> for SettingSource {
+    fn from(scope: SettingScope) -> Self {
+        match scope {
+            SettingScope::System => SettingSource::System,
+            SettingScope::Tenant => SettingSource::Tenant,
+            SettingScope::Project => SettingSource::Project,
+            SettingScope::User => SettingSource::User,
+        }
+    }
+}
+
+/// Value stored at one layer while resolving a key
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingLayer {
+    pub scope: SettingScope,
+    pub scope_id: Option<String>,
+    pub value: Option<serde_json::Value>,
+}
+
+/// Effective value of a key for a caller, with where it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedSetting {
+    pub key: SettingKey,
+    pub value: serde_json::Value,
+    pub source: SettingSource,
+    /// Tenant, project or user id of the winning layer
+    pub source_id: Option<String>,
+    /// Every layer consulted, least specific first
+    pub layers: Vec<SettingLayer>,
+}
+
+/// Whose settings to resolve; unset layers are skipped
+#[derive(Debug, Clone, Default)]
+pub struct SettingContext {
+    pub tenant_id: Option<String>,
+    pub project_id: Option<String>,
+    pub user_id: Option<String>,
+}
+
+impl SettingContext {
+    pub fn id_for(&self, scope: SettingScope) -> Option<&str> {
+        match scope {
+            SettingScope::System => Some(""),
+            SettingScope::Tenant => self.tenant_id.as_deref(),
+            SettingScope::Project => self.project_id.as_deref(),
+            SettingScope::User => self.user_id.as_deref(),
+        }
+    }
+}
+
+/// Query for effective settings; the tenant and user come from the caller
+#[derive(Debug, Default, Deserialize)]
+pub struct EffectiveSettingsQuery {
+    pub project_id: Option<String>,
+}
+
+/// Query selecting one layer
+#[derive(Debug, Deserialize)]
+pub struct SettingScopeQuery {
+    pub scope: SettingScope,
+    /// Tenant, project or user id; the caller's own tenant or user when omitted
+    pub scope_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetSettingOverrideRequest {
+    pub scope: SettingScope,
+    pub scope_id: Option<String>,
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SettingChangeQuery {
+    pub key: Option<String>,
+    pub scope: Option<SettingScope>,
+    pub scope_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+// =============================================================================
+// FEATURE FLAGS
+// =============================================================================
+
+/// Features that can be switched on or off per tenant, project or user.
+/// Each flag is a boolean `features.*` setting, so it is overridden and
+/// audited through the settings API like any other key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureFlag {
+    AiAssistant,
+    AutoPlacementV2,
+    GraphqlApi,
+    GuestScan,
+    HardwarePool,
+    RvtoolsImport,
+    CapacityAnalytics,
+    TimelineGantt,
+}
+
+impl FeatureFlag {
+    pub const ALL: [FeatureFlag; 8] = [
+        FeatureFlag::AiAssistant,
+        FeatureFlag::AutoPlacementV2,
+        FeatureFlag::GraphqlApi,
+        FeatureFlag::GuestScan,
+        FeatureFlag::HardwarePool,
+        FeatureFlag::RvtoolsImport,
+        FeatureFlag::CapacityAnalytics,
+        FeatureFlag::TimelineGantt,
+    ];
+
+    pub fn key(&self) -> SettingKey {
+        match self {
+            FeatureFlag::AiAssistant => SettingKey::FeatureAiAssistant,
+            FeatureFlag::AutoPlacementV2 => SettingKey::FeatureAutoPlacementV2,
+            FeatureFlag::GraphqlApi => SettingKey::FeatureGraphqlApi,
+            FeatureFlag::GuestScan => SettingKey::FeatureGuestScan,
+            FeatureFlag::HardwarePool => SettingKey::FeatureHardwarePool,
+            FeatureFlag::RvtoolsImport => SettingKey::FeatureRvtoolsImport,
+            FeatureFlag::CapacityAnalytics => SettingKey::FeatureCapacityAnalytics,
+            FeatureFlag::TimelineGantt => SettingKey::FeatureTimelineGantt,
+        }
+    }
+
+    pub fn from_key(key: SettingKey) -> Option<FeatureFlag> {
+        FeatureFlag::ALL.into_iter().find(|flag| flag.key() == key)
+    }
+
+    /// Risky features ship dark and are switched on tenant by tenant
+    pub fn default_enabled(&self) -> bool {
+        !matches!(
+            self,
+            FeatureFlag::AiAssistant | FeatureFlag::AutoPlacementV2 | FeatureFlag::GraphqlApi | FeatureFlag::GuestScan
+        )
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            FeatureFlag::AiAssistant => "AI chat, the tool-calling assistant and natural-language queries",
+            FeatureFlag::AutoPlacementV2 => "Strategy-based placement re-optimization of a project",
+            FeatureFlag::GraphqlApi => "Read-only GraphQL endpoint for nested project queries",
+            FeatureFlag::GuestScan => "Agentless in-guest scans of migration wizard VMs over WinRM and SSH",
+            FeatureFlag::HardwarePool => "Hardware pool management",
+            FeatureFlag::RvtoolsImport => "RVTools import",
+            FeatureFlag::CapacityAnalytics => "Capacity analytics",
+            FeatureFlag::TimelineGantt => "Timeline Gantt view",
+        }
+    }
+}
+
+/// Evaluated flag for the caller
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureFlagState {
+    pub flag: FeatureFlag,
+    pub enabled: bool,
+    pub source: SettingSource,
+    /// Tenant, project or user id of the layer that decided it
+    pub source_id: Option<String>,
+}
@@ -0,0 +1,148 @@
+// Hardware Pool Spare Parts Models
+// Stocked spare components (drives, DIMMs, PSUs, SFPs) per location, their
+// minimum-stock thresholds and the ticket-linked consumption ledger
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SparePartCategory {
+    Drive,
+    Memory,
+    PowerSupply,
+    Transceiver,
+    Fan,
+    Other,
+}
+
+/// One stocked part at one location, stored in `spare_part`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparePart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// Vendor part number (FRU / SKU)
+    pub part_number: String,
+    pub description: String,
+    pub category: SparePartCategory,
+    #[serde(default)]
+    pub vendor: Option<String>,
+    /// Server models the part fits, as hardware pool records name them
+    #[serde(default)]
+    pub compatible_models: Vec<String>,
+    pub location: String,
+    #[serde(default)]
+    pub datacenter: Option<String>,
+    pub quantity: u32,
+    /// Alert once stock falls below this
+    pub min_quantity: u32,
+    #[serde(default)]
+    pub unit_cost: Option<f64>,
+    /// Who is emailed when stock runs low
+    #[serde(default)]
+    pub alert_emails: Vec<String>,
+    /// Set when the low-stock alert went out; cleared by restocking
+    #[serde(default)]
+    pub low_stock_alerted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SparePart {
+    pub fn is_below_minimum(&self) -> bool {
+        self.quantity < self.min_quantity
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateSparePartRequest {
+    pub part_number: String,
+    pub description: String,
+    pub category: SparePartCategory,
+    pub vendor: Option<String>,
+    #[serde(default)]
+    pub compatible_models: Vec<String>,
+    pub location: String,
+    pub datacenter: Option<String>,
+    #[serde(default)]
+    pub quantity: u32,
+    #[serde(default)]
+    pub min_quantity: u32,
+    pub unit_cost: Option<f64>,
+    #[serde(default)]
+    pub alert_emails: Vec<String>,
+}
+
+/// Quantity is changed through consumption and restocking, not here
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateSparePartRequest {
+    pub description: Option<String>,
+    pub category: Option<SparePartCategory>,
+    pub vendor: Option<String>,
+    pub compatible_models: Option<Vec<String>>,
+    pub location: Option<String>,
+    pub datacenter: Option<String>,
+    pub min_quantity: Option<u32>,
+    pub unit_cost: Option<f64>,
+    pub alert_emails: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SparePartListQuery {
+    pub category: Option<SparePartCategory>,
+    pub location: Option<String>,
+    /// Only parts that fit this server model
+    pub model: Option<String>,
+    /// Only parts below their minimum stock
+    #[serde(default)]
+    pub low_stock: bool,
+}
+
+/// Take parts out of stock for a ticket
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConsumeSparePartRequest {
+    pub ticket_id: String,
+    pub quantity: u32,
+    /// Hardware pool server the part went into
+    pub server_id: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestockSparePartRequest {
+    pub quantity: u32,
+}
+
+/// Stored in `spare_part_consumption`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparePartConsumption {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub part_id: Thing,
+    pub part_number: String,
+    pub ticket_id: Thing,
+    #[serde(default)]
+    pub server_id: Option<Thing>,
+    pub quantity: u32,
+    /// Stock left after this consumption
+    pub remaining: u32,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub consumed_by: String,
+    pub consumed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConsumptionListQuery {
+    pub part_id: Option<String>,
+    pub ticket_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsumeSparePartResponse {
+    pub part: SparePart,
+    pub consumption: SparePartConsumption,
+    /// True when this consumption took stock below the minimum
+    pub low_stock_alert: bool,
+}
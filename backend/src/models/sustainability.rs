@@ -0,0 +1,121 @@
+// Sustainability Models
+// Energy and CO2e comparison of the current vs proposed estate
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// =============================================================================
+// SETTINGS
+// =============================================================================
+
+/// Per-project inputs to the emissions model.
+///
+/// Annual CO2e = hosts × typical watts × 8760 h × PUE × grid carbon intensity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SustainabilitySettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<Thing>,
+    /// Grid region key, e.g. "de", "uk", "us-west"
+    pub region: String,
+    /// Overrides the built-in intensity for `region` (g CO2e per kWh)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub carbon_intensity_g_per_kwh: Option<f64>,
+    /// Power usage effectiveness of the data centre
+    pub pue: f64,
+    /// Source host count; derived from the inventory's host column when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_host_count: Option<u32>,
+    /// Typical draw of an existing host
+    pub current_host_watts: f64,
+    /// Vendor model for the proposed nodes; its typical watts and cores come from vendor specs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposed_vendor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposed_model_id: Option<String>,
+    /// Proposed node count; derived from destination cluster cores when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposed_node_count: Option<u32>,
+    /// Fallback draw of a proposed node when no vendor model is set
+    pub proposed_node_watts: f64,
+    /// Fallback cores per proposed node when no vendor model is set
+    pub proposed_cores_per_node: u32,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for SustainabilitySettings {
+    fn default() -> Self {
+        Self {
+            id: None,
+            project_id: None,
+            region: "eu-average".to_string(),
+            carbon_intensity_g_per_kwh: None,
+            pue: 1.6,
+            current_host_count: None,
+            current_host_watts: 450.0,
+            proposed_vendor: None,
+            proposed_model_id: None,
+            proposed_node_count: None,
+            proposed_node_watts: 400.0,
+            proposed_cores_per_node: 64,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+// =============================================================================
+// REPORT
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstateEnergy {
+    pub hosts: u32,
+    pub watts_per_host: f64,
+    /// IT load plus facility overhead (× PUE)
+    pub facility_power_kw: f64,
+    pub annual_kwh: f64,
+    pub annual_co2e_tonnes: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SustainabilityReport {
+    pub project_id: String,
+    pub region: String,
+    pub carbon_intensity_g_per_kwh: f64,
+    pub pue: f64,
+    pub current: EstateEnergy,
+    pub proposed: EstateEnergy,
+    pub annual_kwh_saved: f64,
+    pub annual_co2e_saved_tonnes: f64,
+    /// Positive when the proposed estate emits less
+    pub reduction_percent: f64,
+    pub assumptions: Vec<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// REQUEST MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSustainabilitySettingsRequest {
+    pub region: Option<String>,
+    pub carbon_intensity_g_per_kwh: Option<f64>,
+    pub pue: Option<f64>,
+    pub current_host_count: Option<u32>,
+    pub current_host_watts: Option<f64>,
+    pub proposed_vendor: Option<String>,
+    pub proposed_model_id: Option<String>,
+    pub proposed_node_count: Option<u32>,
+    pub proposed_node_watts: Option<f64>,
+    pub proposed_cores_per_node: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SustainabilityReportQuery {
+    /// "json" (default) or "markdown"
+    pub format: Option<String>,
+}
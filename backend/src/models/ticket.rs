@@ -262,6 +262,8 @@ pub enum TicketSource {
     Monitoring, // Auto-generated from monitoring alert
     #[serde(rename = "API")]
     Api,
+    #[serde(rename = "SCHEDULED")]
+    Scheduled, // Created by a recurring ticket schedule
 }
 
 // ============================================================================
@@ -663,3 +665,52 @@ pub struct MarkDuplicateRequest {
     pub notes: Option<String>,
     pub transfer_watchers: bool,
 }
+
+// ============================================================================
+// TICKET WATCHING
+// ============================================================================
+
+/// Activity on a ticket that its watchers are told about. `watchers` is the
+/// list at the time of the event, so the digest doesn't depend on who watches
+/// the ticket later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketWatchEvent {
+    pub id: Option<Thing>,
+    pub ticket_id: Thing,
+    pub ticket_title: String,
+    /// `Comment` or `StatusChange`
+    pub event_type: HistoryChangeType,
+    pub summary: String,
+    pub actor_id: String,
+    pub actor_name: String,
+    pub watchers: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How a user hears about tickets they watch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchNotificationPreferences {
+    pub user_id: String,
+    /// Email on every comment and status change
+    pub immediate: bool,
+    /// One email a day summarising activity on watched tickets
+    pub daily_digest: bool,
+    pub last_digest_at: Option<DateTime<Utc>>,
+}
+
+impl WatchNotificationPreferences {
+    pub fn defaults_for(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            immediate: true,
+            daily_digest: false,
+            last_digest_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateWatchPreferencesRequest {
+    pub immediate: Option<bool>,
+    pub daily_digest: Option<bool>,
+}
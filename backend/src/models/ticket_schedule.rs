@@ -0,0 +1,187 @@
+// Archer ITSM - Recurring Ticket Schedules
+// Cron-driven ticket creation for routine maintenance (patching, DR tests),
+// built from a ticket template, with assignee rotation, weekend/holiday
+// handling and optional one-ticket-per-CI fan-out
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+// ============================================================================
+// CALENDAR & ASSIGNMENT
+// ============================================================================
+
+/// What to do with an occurrence that falls on a weekend or holiday
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HolidayPolicy {
+    /// Drop the occurrence; it is recorded as a skipped run
+    #[default]
+    Skip,
+    /// Run at the same time on the next business day
+    NextBusinessDay,
+}
+
+/// Days on which a schedule does not create tickets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleCalendar {
+    #[serde(default)]
+    pub skip_weekends: bool,
+    /// Public holidays and change freezes (UTC dates)
+    #[serde(default)]
+    pub holidays: Vec<NaiveDate>,
+    #[serde(default)]
+    pub policy: HolidayPolicy,
+}
+
+impl ScheduleCalendar {
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        !(self.skip_weekends && weekend) && !self.holidays.contains(&date)
+    }
+}
+
+/// Who the generated tickets are assigned to
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleAssignment {
+    /// Fixed assignee, used when `rotation` is empty
+    #[serde(default)]
+    pub assignee: Option<String>,
+    /// Assignees taken in turn, one per occurrence
+    #[serde(default)]
+    pub rotation: Vec<String>,
+    /// Overrides the template's assignment group
+    #[serde(default)]
+    pub assigned_group: Option<String>,
+}
+
+impl ScheduleAssignment {
+    /// Assignee for the `occurrence`-th run (0-based)
+    pub fn assignee_for(&self, occurrence: u32) -> Option<String> {
+        if self.rotation.is_empty() {
+            return self.assignee.clone();
+        }
+        self.rotation.get(occurrence as usize % self.rotation.len()).cloned()
+    }
+}
+
+// ============================================================================
+// SCHEDULES
+// ============================================================================
+
+/// Stored in `ticket_schedules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketSchedule {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub description: Option<String>,
+    /// Ticket template the tickets are created from
+    pub template_id: Thing,
+    /// Ticket title; `{date}`, `{month}` and `{ci}` are filled in per ticket
+    pub title: String,
+    /// Cron expression in UTC; 5-field (minute precision) or 6-field with seconds
+    pub cron: String,
+    /// Configuration items the work covers
+    #[serde(default)]
+    pub ci_ids: Vec<Thing>,
+    /// Create one ticket per CI instead of a single ticket for the first
+    #[serde(default)]
+    pub ticket_per_ci: bool,
+    #[serde(default)]
+    pub assignment: ScheduleAssignment,
+    #[serde(default)]
+    pub calendar: ScheduleCalendar,
+    pub enabled: bool,
+    /// When the scheduler will next create tickets
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// The cron occurrence `next_run_at` stands for; differs when it was
+    /// moved to the next business day
+    pub next_occurrence_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Occurrences that created tickets, drives assignee rotation
+    #[serde(default)]
+    pub occurrence_count: u32,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub tenant_id: Option<Thing>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTicketScheduleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub template_id: String,
+    pub title: String,
+    pub cron: String,
+    #[serde(default)]
+    pub ci_ids: Vec<String>,
+    #[serde(default)]
+    pub ticket_per_ci: bool,
+    #[serde(default)]
+    pub assignment: ScheduleAssignment,
+    #[serde(default)]
+    pub calendar: ScheduleCalendar,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateTicketScheduleRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub template_id: Option<String>,
+    pub title: Option<String>,
+    pub cron: Option<String>,
+    pub ci_ids: Option<Vec<String>>,
+    pub ticket_per_ci: Option<bool>,
+    pub assignment: Option<ScheduleAssignment>,
+    pub calendar: Option<ScheduleCalendar>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketScheduleListResponse {
+    pub schedules: Vec<TicketSchedule>,
+    pub total: usize,
+}
+
+/// An upcoming occurrence after weekend/holiday handling
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PlannedOccurrence {
+    /// The cron occurrence
+    pub occurrence_at: DateTime<Utc>,
+    /// When tickets will be created; `None` if the occurrence is skipped
+    pub run_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// RUN HISTORY
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScheduleRunStatus {
+    Created,
+    /// Weekend, holiday or skipped on request
+    Skipped,
+    Failed,
+}
+
+/// Stored in `ticket_schedule_runs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketScheduleRun {
+    pub id: Option<Thing>,
+    pub schedule_id: Thing,
+    pub occurrence_at: DateTime<Utc>,
+    pub status: ScheduleRunStatus,
+    #[serde(default)]
+    pub ticket_ids: Vec<Thing>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub tenant_id: Option<Thing>,
+}
@@ -0,0 +1,267 @@
+// Archer ITSM - Ticket Templates & Macros
+// Team-managed templates that pre-fill new tickets (fields, description
+// skeleton, checklist tasks) and macros that apply a set of field changes
+// plus a comment to an existing ticket in one action
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use super::ticket::{
+    TicketImpact, TicketPriority, TicketResponse, TicketSource, TicketStatus, TicketType,
+    TicketUrgency,
+};
+
+// ============================================================================
+// TICKET TEMPLATES
+// ============================================================================
+
+/// A checklist task a template adds to every ticket it is applied to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketTemplate {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub description: Option<String>,
+    /// Owning team; templates without a team are available to everyone
+    pub team_id: Option<Thing>,
+    #[serde(rename = "type")]
+    pub ticket_type: TicketType,
+    pub priority: TicketPriority,
+    #[serde(default)]
+    pub impact: Option<TicketImpact>,
+    #[serde(default)]
+    pub urgency: Option<TicketUrgency>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub subcategory: Option<String>,
+    #[serde(default)]
+    pub assigned_group: Option<String>,
+    /// Prepended to the title given when a ticket is created from the template
+    #[serde(default)]
+    pub title_prefix: Option<String>,
+    /// Description skeleton (headings, prompts) the agent fills in
+    #[serde(default)]
+    pub description_template: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    pub created_by: String,
+    pub tenant_id: Option<Thing>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTicketTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub team_id: Option<String>,
+    #[serde(rename = "type")]
+    pub ticket_type: TicketType,
+    pub priority: TicketPriority,
+    #[serde(default)]
+    pub impact: Option<TicketImpact>,
+    #[serde(default)]
+    pub urgency: Option<TicketUrgency>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub subcategory: Option<String>,
+    #[serde(default)]
+    pub assigned_group: Option<String>,
+    #[serde(default)]
+    pub title_prefix: Option<String>,
+    #[serde(default)]
+    pub description_template: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateTicketTemplateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub ticket_type: Option<TicketType>,
+    pub priority: Option<TicketPriority>,
+    pub impact: Option<TicketImpact>,
+    pub urgency: Option<TicketUrgency>,
+    pub category: Option<String>,
+    pub subcategory: Option<String>,
+    pub assigned_group: Option<String>,
+    pub title_prefix: Option<String>,
+    pub description_template: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub checklist: Option<Vec<ChecklistItem>>,
+    pub is_active: Option<bool>,
+}
+
+/// Body for creating a ticket from a template; anything given here wins
+/// over the template's value
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTicketFromTemplateRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Option<TicketPriority>,
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub assigned_group: Option<String>,
+    pub related_asset: Option<String>,
+    pub related_project: Option<String>,
+    #[serde(default)]
+    pub source: Option<TicketSource>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub watchers: Vec<String>,
+    #[serde(default)]
+    pub custom_fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketTemplateListResponse {
+    pub templates: Vec<TicketTemplate>,
+    pub total: usize,
+}
+
+// ============================================================================
+// CHECKLIST TASKS
+// ============================================================================
+
+/// A checklist task on a ticket, usually added by a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketTask {
+    pub id: Option<Thing>,
+    pub ticket_id: Thing,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub position: u32,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default)]
+    pub completed_by: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Template the task came from, if any
+    #[serde(default)]
+    pub template_id: Option<Thing>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A ticket after a template or macro was applied, with its checklist
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketWithTasksResponse {
+    #[serde(flatten)]
+    pub ticket: TicketResponse,
+    pub tasks: Vec<TicketTask>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateTicketTaskRequest {
+    pub completed: bool,
+}
+
+// ============================================================================
+// MACROS
+// ============================================================================
+
+/// Comment a macro posts when applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroComment {
+    pub content: String,
+    #[serde(default)]
+    pub is_internal: bool,
+}
+
+/// Field changes a macro makes; unset fields are left alone
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MacroActions {
+    #[serde(default)]
+    pub status: Option<TicketStatus>,
+    #[serde(default)]
+    pub priority: Option<TicketPriority>,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub assigned_group: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub subcategory: Option<String>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    #[serde(default)]
+    pub comment: Option<MacroComment>,
+}
+
+impl MacroActions {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.priority.is_none()
+            && self.assignee.is_none()
+            && self.assigned_group.is_none()
+            && self.category.is_none()
+            && self.subcategory.is_none()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.comment.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketMacro {
+    pub id: Option<Thing>,
+    pub name: String,
+    pub description: Option<String>,
+    /// Owning team; macros without a team are available to everyone
+    pub team_id: Option<Thing>,
+    pub actions: MacroActions,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    pub created_by: String,
+    pub tenant_id: Option<Thing>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTicketMacroRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub team_id: Option<String>,
+    pub actions: MacroActions,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateTicketMacroRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub actions: Option<MacroActions>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketMacroListResponse {
+    pub macros: Vec<TicketMacro>,
+    pub total: usize,
+}
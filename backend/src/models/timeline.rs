@@ -0,0 +1,404 @@
+// Migration Timeline Models
+// Durable wave plans and Gantt-style project schedules for migration projects
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use super::build_checklist::ClusterBuildBlocker;
+use super::workflow::{DependencyType, InfrastructureType};
+
+// =============================================================================
+// WAVE PLAN MODELS
+// =============================================================================
+
+/// A migration wave groups VMs that are cut over together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationWave {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub name: String,
+    /// 1-based execution order of the wave
+    pub sequence: u32,
+    pub vm_ids: Vec<Thing>,
+    pub target_cluster_ids: Vec<Thing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_start: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_end: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_window: Option<String>,
+    pub status: WaveStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WaveStatus {
+    Planned,
+    Scheduled,
+    InProgress,
+    Completed,
+    RolledBack,
+}
+
+// =============================================================================
+// TIMELINE MODELS
+// =============================================================================
+
+/// Durable Gantt-style schedule for a migration project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTimeline {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub name: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub total_duration_days: u32,
+    pub phases: Vec<TimelinePhase>,
+    pub tasks: Vec<TimelineTask>,
+    pub resources: Vec<TimelineResource>,
+    /// Task IDs on the critical path, in schedule order
+    pub critical_path: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelinePhase {
+    pub id: String,
+    pub name: String,
+    pub order: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineTask {
+    pub id: String,
+    pub name: String,
+    pub phase_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wave_id: Option<String>,
+    pub duration_days: u32,
+    pub dependencies: Vec<TaskLink>,
+    pub resource_ids: Vec<String>,
+    pub is_milestone: bool,
+
+    // Computed by the critical path pass (day offsets from timeline start)
+    pub early_start: i64,
+    pub early_finish: i64,
+    pub late_start: i64,
+    pub late_finish: i64,
+    pub total_float: i64,
+    pub is_critical: bool,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+}
+
+/// Dependency link from a predecessor task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskLink {
+    pub predecessor_id: String,
+    pub dependency_type: DependencyType,
+    /// Lag in days; negative values represent lead time
+    #[serde(default)]
+    pub lag_days: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineResource {
+    pub id: String,
+    pub name: String,
+    pub role: String,
+    /// Percentage of the resource's time assigned to each task (0-100)
+    pub allocation_percent: u8,
+}
+
+// =============================================================================
+// WAVE RUNBOOK MODELS
+// =============================================================================
+
+/// Operational runbook for cutting over one wave
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveRunbook {
+    pub wave_id: String,
+    pub wave_name: String,
+    pub sequence: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_start: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_window: Option<String>,
+    pub target_clusters: Vec<String>,
+    pub vms: Vec<RunbookVm>,
+    pub steps: Vec<RunbookStep>,
+    pub backup_impact: BackupImpact,
+    pub log_source_impact: LogSourceImpact,
+    /// Readiness scorecard shown on the runbook's cover page
+    pub readiness: WaveReadiness,
+    pub rollback_plan: RollbackPlan,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunbookVm {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_cluster: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunbookStep {
+    /// pre_migration, migration or post_migration
+    pub phase: String,
+    pub action: String,
+}
+
+/// Backup jobs affected by moving a wave's VMs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupImpact {
+    /// VMs protected by at least one backup job
+    pub affected_vms: Vec<VmBackupImpact>,
+    /// Jobs that must be reviewed after cutover
+    pub jobs_to_reconfigure: Vec<String>,
+    /// VMs no imported backup job protects
+    pub unprotected_vms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmBackupImpact {
+    pub vm_id: String,
+    pub vm_name: String,
+    pub jobs: Vec<BackupJobAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupJobAction {
+    pub job_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    pub action: String,
+}
+
+/// Splunk log sources affected by moving a wave's VMs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogSourceImpact {
+    /// Forwarders running on wave VMs; their events pause during cutover
+    pub forwarders: Vec<AffectedForwarder>,
+    /// Indexers and search heads that move with the wave
+    pub moving_servers: Vec<String>,
+    /// Forwarders (anywhere) that send to an indexer moving with the wave
+    pub forwarders_to_repoint: Vec<ForwarderRepoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AffectedForwarder {
+    pub vm_name: String,
+    pub forwarder: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forwarder_type: Option<String>,
+    pub indexers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwarderRepoint {
+    pub indexer: String,
+    pub forwarders: Vec<String>,
+}
+
+// =============================================================================
+// WAVE READINESS MODELS
+// =============================================================================
+
+/// Red/amber/green rating of a readiness signal or a whole wave
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessRating {
+    Green,
+    Amber,
+    Red,
+}
+
+impl ReadinessRating {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Green => "GREEN",
+            Self::Amber => "AMBER",
+            Self::Red => "RED",
+        }
+    }
+}
+
+/// Cutover readiness of one wave, aggregated from its signals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveReadiness {
+    pub wave_id: String,
+    pub wave_name: String,
+    pub sequence: u32,
+    /// Worst rating of any signal
+    pub rating: ReadinessRating,
+    /// 0-100; a green signal counts fully, an amber one half
+    pub score: u8,
+    pub signals: Vec<ReadinessSignal>,
+    /// Items that must be cleared before the wave may cut over
+    pub blocking_items: Vec<BlockingItem>,
+    pub assessed_at: DateTime<Utc>,
+}
+
+/// One input to the readiness scorecard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessSignal {
+    /// placements, network_mappings, ip_plan, backups, change_tickets or change_window
+    pub key: String,
+    pub name: String,
+    /// Red with a blocking item, amber with only warnings, else green
+    pub rating: ReadinessRating,
+    pub summary: String,
+    pub blocking: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockingItem {
+    /// Key of the signal that raised the item
+    pub signal: String,
+    pub message: String,
+}
+
+// =============================================================================
+// WAVE ROLLBACK MODELS
+// =============================================================================
+
+/// Reverse steps to back a wave out of its cutover
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackPlan {
+    pub wave_id: String,
+    pub wave_name: String,
+    pub sequence: u32,
+    /// Any one of these calls for a rollback
+    pub decision_criteria: Vec<String>,
+    /// Estimated minutes to carry out the rollback
+    pub rollback_minutes: i64,
+    /// Latest moment to decide so the rollback still ends within the window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decide_by: Option<DateTime<Utc>>,
+    /// Minutes from the planned start to `decide_by`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_decide_minutes: Option<i64>,
+    pub steps: Vec<RollbackStep>,
+    pub warnings: Vec<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// One item of the rollback checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackStep {
+    /// 1-based position in the checklist
+    pub order: u32,
+    /// decision, target, source, network, backups, log_sources or verification
+    pub phase: String,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_name: Option<String>,
+}
+
+/// Change tickets a rollback plan was posted to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackAttachment {
+    pub wave_id: String,
+    pub ticket_ids: Vec<String>,
+}
+
+// =============================================================================
+// REQUEST/RESPONSE MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWaveRequest {
+    pub name: String,
+    pub sequence: Option<u32>,
+    pub vm_ids: Vec<String>,
+    #[serde(default)]
+    pub target_cluster_ids: Vec<String>,
+    pub planned_start: Option<DateTime<Utc>>,
+    pub change_window: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWaveStatusRequest {
+    pub status: WaveStatus,
+}
+
+/// Outcome of moving a wave to a new status
+#[derive(Debug)]
+pub enum WaveTransition {
+    Updated(MigrationWave),
+    /// Target clusters whose build checklist is still open
+    Blocked(Vec<ClusterBuildBlocker>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenerateWavesRequest {
+    /// Maximum VMs per wave (defaults to 25)
+    pub max_vms_per_wave: Option<usize>,
+    /// Replace existing waves instead of failing when some exist
+    #[serde(default)]
+    pub replace_existing: bool,
+    /// Chunk VMs without keeping members of the same load-balancer pool together
+    #[serde(default)]
+    pub ignore_load_balancing: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenerateTimelineRequest {
+    pub start_date: Option<DateTime<Utc>>,
+    pub infrastructure_type: Option<InfrastructureType>,
+    #[serde(default)]
+    pub has_compatibility_issues: bool,
+    /// Optional resource pool; role-based defaults are used when omitted
+    pub resources: Option<Vec<TimelineResource>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunbookQuery {
+    /// json (default) or markdown
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimelineExportQuery {
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelineExportFormat {
+    MsProjectXml,
+    Csv,
+}
+
+impl TimelineExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "xml" | "msproject" | "ms_project" | "mspdi" => Some(Self::MsProjectXml),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::MsProjectXml => "application/xml",
+            Self::Csv => "text/csv",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::MsProjectXml => "xml",
+            Self::Csv => "csv",
+        }
+    }
+}
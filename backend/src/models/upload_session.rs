@@ -0,0 +1,72 @@
+// Resumable Upload Models
+// Chunked upload sessions for large RVTools exports and hardware basket
+// pricing files, resumed from the last acknowledged byte offset
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+/// What the assembled file is handed to once the upload completes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadTarget {
+    Rvtools,
+    HardwareBasket,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadSessionStatus {
+    InProgress,
+    Completed,
+}
+
+/// One resumable upload, stored in `upload_session`. The bytes received so
+/// far live in a part file under `UPLOAD_SESSION_DIR`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSession {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub target: UploadTarget,
+    pub file_name: String,
+    pub total_bytes: u64,
+    /// Bytes written to the part file; the offset the next chunk must start at
+    pub received_bytes: u64,
+    /// Hex SHA-256 of the whole file, checked on completion when given
+    #[serde(default)]
+    pub sha256: Option<String>,
+    pub status: UploadSessionStatus,
+    /// Form fields the target needs, e.g. `project_id` or `vendor`
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Abandoned sessions and their part files are removed after this
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateUploadSessionRequest {
+    pub target: UploadTarget,
+    pub file_name: String,
+    pub total_bytes: u64,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadSessionResponse {
+    pub id: String,
+    pub target: UploadTarget,
+    pub file_name: String,
+    pub total_bytes: u64,
+    pub received_bytes: u64,
+    pub status: UploadSessionStatus,
+    /// Largest chunk the server accepts in one request
+    pub max_chunk_bytes: u64,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,115 @@
+// Archer - Usage Telemetry Models
+// Opt-in, anonymized feature usage: invocation counts aggregated per day and
+// the batches submitted to the product team
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use surrealdb::sql::Thing;
+
+/// Features whose invocations are counted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageFeature {
+    /// RVTools export parsed (upload, resumable upload or migration wizard)
+    RvtoolsParse,
+    /// Hardware basket workbook parsed
+    HardwareBasketParse,
+    /// Spreadsheet parsed for a bulk import
+    SpreadsheetImport,
+    /// Destination capacity computed for a project
+    CapacitySizing,
+    /// VMs placed onto destination clusters
+    VmPlacement,
+    /// High-level design generated
+    HldGeneration,
+    /// Project document generated
+    DocumentGeneration,
+}
+
+impl UsageFeature {
+    pub const ALL: [UsageFeature; 7] = [
+        Self::RvtoolsParse,
+        Self::HardwareBasketParse,
+        Self::SpreadsheetImport,
+        Self::CapacitySizing,
+        Self::VmPlacement,
+        Self::HldGeneration,
+        Self::DocumentGeneration,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::RvtoolsParse => "rvtools_parse",
+            Self::HardwareBasketParse => "hardware_basket_parse",
+            Self::SpreadsheetImport => "spreadsheet_import",
+            Self::CapacitySizing => "capacity_sizing",
+            Self::VmPlacement => "vm_placement",
+            Self::HldGeneration => "hld_generation",
+            Self::DocumentGeneration => "document_generation",
+        }
+    }
+}
+
+/// Invocations of one feature on one day (`usage_daily:⟨day_feature⟩`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyUsage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub day: NaiveDate,
+    pub feature: UsageFeature,
+    pub count: u64,
+    /// Set once the day has been submitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+/// Opt-in state (single `usage_telemetry:config` record)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub enabled: bool,
+    /// Random id, unrelated to the license or any account, that lets the
+    /// receiver tell batches of different installations apart
+    pub installation_id: String,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_submitted_at: Option<DateTime<Utc>>,
+}
+
+/// What is sent for one day. Only feature counts leave the installation:
+/// no user, tenant, project or file names.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TelemetryBatch {
+    pub installation_id: String,
+    pub app_version: String,
+    pub day: NaiveDate,
+    pub counts: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTelemetryRequest {
+    pub enabled: bool,
+}
+
+/// Opt-in state and everything recorded locally
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryOverview {
+    pub enabled: bool,
+    /// `ARCHER_TELEMETRY_DISABLED` or `DO_NOT_TRACK` is set; nothing is
+    /// recorded or sent and telemetry can't be enabled
+    pub hard_disabled: bool,
+    /// Where batches are sent; `None` keeps the aggregates local
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_submitted_at: Option<DateTime<Utc>>,
+    /// Daily counts, newest first
+    pub days: Vec<DailyUsage>,
+    /// The batches the next submission will send
+    pub pending: Vec<TelemetryBatch>,
+}
@@ -0,0 +1,130 @@
+// Archer - VM Hygiene Models
+// Snapshot and VMware Tools findings from the RVTools vSnapshot and vTools
+// sheets, and the pre-migration remediation tasks raised from them
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+pub use super::firmware_baseline::{RemediationStatus, UpdateRemediationTaskRequest};
+
+/// When a snapshot counts as old or large
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HygieneThresholds {
+    #[serde(default = "default_snapshot_max_age_days")]
+    pub snapshot_max_age_days: i64,
+    #[serde(default = "default_snapshot_max_size_gb")]
+    pub snapshot_max_size_gb: f64,
+}
+
+fn default_snapshot_max_age_days() -> i64 {
+    3
+}
+
+fn default_snapshot_max_size_gb() -> f64 {
+    50.0
+}
+
+impl Default for HygieneThresholds {
+    fn default() -> Self {
+        Self {
+            snapshot_max_age_days: default_snapshot_max_age_days(),
+            snapshot_max_size_gb: default_snapshot_max_size_gb(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HygieneIssue {
+    OldSnapshot,
+    LargeSnapshot,
+    ToolsOutdated,
+    ToolsMissing,
+}
+
+/// One snapshot or VMware Tools problem on one VM
+#[derive(Debug, Clone, Serialize)]
+pub struct HygieneFinding {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_id: Option<String>,
+    pub vm_name: String,
+    pub issue: HygieneIssue,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_age_days: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_size_gb: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools_version: Option<String>,
+    /// Wave the VM migrates in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wave_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wave_name: Option<String>,
+}
+
+/// Storage held by snapshots on one source cluster
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterSnapshotStorage {
+    pub cluster: String,
+    pub snapshots: usize,
+    pub size_gb: f64,
+}
+
+/// Snapshot and VMware Tools hygiene of a project's source VMs
+#[derive(Debug, Clone, Serialize)]
+pub struct VmHygieneReport {
+    pub project_id: String,
+    pub thresholds: HygieneThresholds,
+    pub snapshots: usize,
+    pub vms_with_snapshots: usize,
+    /// Storage all snapshots hold; freed by consolidating them
+    pub snapshot_storage_gb: f64,
+    pub snapshot_storage_by_cluster: Vec<ClusterSnapshotStorage>,
+    pub tools_outdated: usize,
+    pub tools_missing: usize,
+    pub findings: Vec<HygieneFinding>,
+    pub tasks_created: usize,
+    pub assessed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationAction {
+    ConsolidateSnapshots,
+    UpdateTools,
+    InstallTools,
+}
+
+impl RemediationAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ConsolidateSnapshots => "Consolidate snapshots",
+            Self::UpdateTools => "Update VMware Tools",
+            Self::InstallTools => "Install VMware Tools",
+        }
+    }
+}
+
+/// One fix to make on one VM before it migrates; stored in
+/// `vm_remediation_task`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmRemediationTask {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub project_id: Thing,
+    pub vm_id: Thing,
+    pub vm_name: String,
+    /// Wave the VM was in when the task was raised or last re-assessed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wave_id: Option<Thing>,
+    pub action: RemediationAction,
+    pub detail: String,
+    pub status: RemediationStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
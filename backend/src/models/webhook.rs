@@ -0,0 +1,236 @@
+// Archer - Webhook Models
+// Outbound webhook subscriptions, the events they receive and the log of
+// every delivery attempt
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Events external systems can subscribe to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum WebhookEventType {
+    #[serde(rename = "ticket.created")]
+    TicketCreated,
+    /// Any field change other than the status
+    #[serde(rename = "ticket.updated")]
+    TicketUpdated,
+    #[serde(rename = "ticket.status_changed")]
+    TicketStatusChanged,
+    #[serde(rename = "wave.status_changed")]
+    WaveStatusChanged,
+    /// A cluster resource over its threshold or projected to run out
+    #[serde(rename = "capacity.alert")]
+    CapacityAlert,
+    #[serde(rename = "document.generated")]
+    DocumentGenerated,
+}
+
+impl WebhookEventType {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::TicketCreated => "ticket.created",
+            Self::TicketUpdated => "ticket.updated",
+            Self::TicketStatusChanged => "ticket.status_changed",
+            Self::WaveStatusChanged => "wave.status_changed",
+            Self::CapacityAlert => "capacity.alert",
+            Self::DocumentGenerated => "document.generated",
+        }
+    }
+}
+
+/// How often and how far apart a failed delivery is retried
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WebhookRetryPolicy {
+    /// Attempts including the first one; the delivery is dead-lettered after the last
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubles with every further attempt
+    #[serde(default = "default_backoff_seconds")]
+    pub backoff_seconds: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_backoff_seconds() -> u64 {
+    30
+}
+
+impl Default for WebhookRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_seconds: default_backoff_seconds(),
+        }
+    }
+}
+
+/// Stored in `webhook_subscription` with the signing secret sealed by the
+/// secrets key. Without a `tenant_id` it receives the events of every tenant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSubscription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub secret_encrypted: String,
+    #[serde(default)]
+    pub retry_policy: WebhookRetryPolicy,
+    pub enabled: bool,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    /// Shared events (no tenant) only reach installation-wide subscriptions
+    pub fn receives(&self, event_type: WebhookEventType, tenant_id: Option<&str>) -> bool {
+        self.enabled
+            && self.event_types.contains(&event_type)
+            && match self.tenant_id.as_deref() {
+                None => true,
+                Some(own) => tenant_id == Some(own),
+            }
+    }
+}
+
+/// Subscription as returned by the API (no secret)
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookSubscriptionView {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub retry_policy: WebhookRetryPolicy,
+    pub enabled: bool,
+    /// Only set in the response that created or rotated it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&WebhookSubscription> for WebhookSubscriptionView {
+    fn from(s: &WebhookSubscription) -> Self {
+        Self {
+            id: s.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+            name: s.name.clone(),
+            tenant_id: s.tenant_id.clone(),
+            url: s.url.clone(),
+            event_types: s.event_types.clone(),
+            retry_policy: s.retry_policy,
+            enabled: s.enabled,
+            signing_secret: None,
+            created_by: s.created_by.clone(),
+            created_at: s.created_at,
+            updated_at: s.updated_at,
+        }
+    }
+}
+
+/// Without `secret` one is generated and returned once
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateWebhookSubscriptionRequest {
+    pub name: String,
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub retry_policy: Option<WebhookRetryPolicy>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Fields left out are kept; `rotate_secret` generates a new secret
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateWebhookSubscriptionRequest {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub event_types: Option<Vec<WebhookEventType>>,
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub rotate_secret: bool,
+    pub retry_policy: Option<WebhookRetryPolicy>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Delivered,
+    /// Out of attempts, or rejected with a status that is not retried
+    DeadLettered,
+}
+
+/// One try at handing an event to the subscriber
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryAttempt {
+    pub attempted_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<u16>,
+    /// Transport error or truncated response body of a failed attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// An event queued for one subscription, stored in `webhook_delivery`.
+/// `body` is the exact JSON that is signed and sent on every attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub subscription_id: Thing,
+    pub event_id: String,
+    pub event_type: WebhookEventType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub body: String,
+    pub status: WebhookDeliveryStatus,
+    /// Attempts since the delivery was queued or last redelivered
+    #[serde(default)]
+    pub attempt_count: u32,
+    /// Every attempt, including those before a redelivery
+    #[serde(default)]
+    pub attempts: Vec<WebhookDeliveryAttempt>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+}
+
+/// Envelope posted to subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookDeliveryQuery {
+    pub status: Option<WebhookDeliveryStatus>,
+    pub limit: Option<usize>,
+}
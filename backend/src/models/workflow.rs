@@ -367,7 +367,13 @@ pub struct ProjectDocument {
     pub workflow_id: Option<Thing>,
     pub document_type: DocumentType,
     pub document_name: String,
+    /// Storage key within `storage_backend`; records written before storage
+    /// backends existed hold a path under the documents directory
     pub file_path: String,
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+    #[serde(default)]
+    pub content_type: Option<String>,
     pub file_size: u64,
     pub version: String,
     pub status: DocumentStatus,
@@ -381,6 +387,15 @@ pub struct ProjectDocument {
     pub created_at: DateTime<Utc>,
 }
 
+/// Where a document's bytes are kept
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    #[default]
+    Disk,
+    S3,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DocumentType {
     #[serde(rename = "HLD")]
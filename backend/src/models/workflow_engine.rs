@@ -22,6 +22,33 @@ pub struct WorkflowDefinition {
     pub created_by: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    // Versioning: all versions of a workflow share a workflow_key
+    #[serde(default)]
+    pub workflow_key: Option<String>,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub status: WorkflowVersionStatus,
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+/// Lifecycle of a workflow version. Only the published version of a workflow
+/// can be triggered; drafts are editable, published and archived versions are not.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkflowVersionStatus {
+    Draft,
+    // Definitions created before versioning behave as published
+    #[default]
+    Published,
+    Archived,
 }
 
 /// Event triggers for workflows
@@ -59,9 +86,10 @@ pub enum WorkflowStepType {
     FieldUpdate,   // Update record fields
     Assignment,    // Assign to user/group
     CreateRecord,  // Create child ticket, etc.
-    HttpCall,      // External API call
-    Condition,     // Branch based on condition
-    Delay,         // Wait for time period
+    HttpCall,      // Outbound webhook with templated payload and retry
+    Condition,     // Branch based on record fields
+    Delay,         // Wait for time period or until a timestamp
+    Script,        // Custom logic in Rhai
 }
 
 // ============================================================================
@@ -73,6 +101,8 @@ pub enum WorkflowStepType {
 pub struct WorkflowInstance {
     pub id: Option<Thing>,
     pub workflow_id: Thing,
+    #[serde(default)]
+    pub workflow_version: Option<u32>,
     pub trigger_record_type: String, // "ticket", "alert", etc.
     pub trigger_record_id: Thing,
     pub status: WorkflowInstanceStatus,
@@ -81,6 +111,8 @@ pub struct WorkflowInstance {
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub context: serde_json::Value, // Runtime data
+    #[serde(default)]
+    pub resume_at: Option<DateTime<Utc>>, // Set while a delay step is waiting
 }
 
 /// Status of a workflow instance
@@ -89,6 +121,7 @@ pub struct WorkflowInstance {
 pub enum WorkflowInstanceStatus {
     Running,
     WaitingApproval,
+    WaitingTimer,
     Completed,
     Failed,
     Cancelled,
@@ -104,6 +137,27 @@ pub struct StepExecution {
     pub completed_at: Option<DateTime<Utc>>,
     pub result: Option<serde_json::Value>,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub logs: Vec<StepLogEntry>,
+}
+
+/// Log line written while a step executes (webhook attempts, script output, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepLogEntry {
+    pub at: DateTime<Utc>,
+    pub level: StepLogLevel,
+    pub message: String,
+}
+
+/// Severity of a step log line
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StepLogLevel {
+    Info,
+    Warn,
+    Error,
 }
 
 /// Status of individual step execution
@@ -133,6 +187,35 @@ pub struct Approval {
     pub requested_at: DateTime<Utc>,
     pub responded_at: Option<DateTime<Utc>>,
     pub comments: Option<String>,
+    // Approvals created by one step execution share a request_id; the step
+    // completes once `quorum` of them are approved (default 1)
+    #[serde(default)]
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub quorum: Option<u32>,
+    #[serde(default)]
+    pub group_id: Option<Thing>,
+    /// Original approver when a delegation rule routed the approval elsewhere
+    #[serde(default)]
+    pub delegated_from: Option<Thing>,
+    /// Approver before the approval was escalated
+    #[serde(default)]
+    pub escalated_from: Option<Thing>,
+    /// User who answered; differs from `approver_id` when the delegator
+    /// answered themselves or a role holder claimed a role approval
+    #[serde(default)]
+    pub responded_by: Option<Thing>,
+    // Reminder and escalation timers
+    #[serde(default)]
+    pub remind_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub reminder_interval_minutes: Option<u32>,
+    #[serde(default)]
+    pub reminders_sent: u32,
+    #[serde(default)]
+    pub escalate_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub escalate_to: Option<Thing>,
 }
 
 /// Type of approver
@@ -152,6 +235,26 @@ pub enum ApprovalStatus {
     Approved,
     Rejected,
     Delegated,
+    Cancelled, // Closed because the request was decided by other approvers
+}
+
+/// Rule routing a user's approvals to a delegate for a period (e.g. vacation)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalDelegation {
+    pub id: Option<Thing>,
+    pub delegator_id: Thing,
+    pub delegate_id: Thing,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub reason: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApprovalDelegation {
+    pub fn covers(&self, at: DateTime<Utc>) -> bool {
+        self.is_active && self.starts_at <= at && at < self.ends_at
+    }
 }
 
 // ============================================================================
@@ -167,6 +270,9 @@ pub struct CreateWorkflowRequest {
     pub trigger_conditions: serde_json::Value,
     pub steps: Vec<WorkflowStep>,
     pub is_active: bool,
+    /// Start as a draft instead of publishing immediately
+    #[serde(default)]
+    pub draft: bool,
 }
 
 /// Request to update a workflow definition
@@ -179,6 +285,72 @@ pub struct UpdateWorkflowRequest {
     pub is_active: Option<bool>,
 }
 
+/// What happens to running instances of older versions on publish
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InFlightPolicy {
+    /// Running instances complete on the version they started on
+    #[default]
+    FinishOnOld,
+    /// Running instances continue on the new version where their current step exists
+    Migrate,
+}
+
+/// Request to publish a workflow version
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PublishWorkflowRequest {
+    #[serde(default)]
+    pub in_flight: InFlightPolicy,
+}
+
+/// Outcome of publishing a workflow version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishWorkflowResponse {
+    pub workflow: WorkflowDefinition,
+    pub archived_versions: Vec<u32>,
+    pub in_flight: InFlightPolicy,
+    pub migrated_instances: Vec<String>,
+    /// Instances left on their old version, with the reason
+    pub unmigrated_instances: Vec<UnmigratedInstance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmigratedInstance {
+    pub instance_id: String,
+    pub from_version: Option<u32>,
+    pub reason: String,
+}
+
+/// Portable workflow document for moving definitions between tenants/environments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowExport {
+    pub format: String,
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub workflow: ExportedWorkflow,
+}
+
+/// Definition content carried by an export; ids, tenant and audit fields are
+/// assigned by the importing side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedWorkflow {
+    pub workflow_key: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub version: u32,
+    pub trigger_type: WorkflowTrigger,
+    pub trigger_conditions: serde_json::Value,
+    pub steps: Vec<WorkflowStep>,
+}
+
+/// Response for the versions of one workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowVersionListResponse {
+    pub workflow_key: String,
+    pub versions: Vec<WorkflowDefinition>,
+    pub total: u64,
+}
+
 /// Request to manually trigger a workflow
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TriggerWorkflowRequest {
@@ -194,6 +366,25 @@ pub struct ApprovalResponseRequest {
     pub comments: Option<String>,
 }
 
+/// Request to delegate the caller's approvals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDelegationRequest {
+    pub delegate_id: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub reason: Option<String>,
+    /// Also move approvals already pending with the caller (when the rule is in effect now)
+    #[serde(default)]
+    pub reassign_pending: bool,
+}
+
+/// Response for delegation rules involving the caller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegationListResponse {
+    pub delegations: Vec<ApprovalDelegation>,
+    pub total: u64,
+}
+
 /// Approval decision
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
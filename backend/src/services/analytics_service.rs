@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
+use crate::database::TracedQuery;
 
 pub struct AnalyticsService {
     db: Database,
@@ -229,6 +230,7 @@ impl AnalyticsService {
         .bind(("start", time_range.start))
         .bind(("end", time_range.end))
         .bind(("granularity", self.granularity_to_string(&time_range.granularity)))
+        .traced("analytics_service.get_hardware_utilization_analytics")
         .await?;
 
         let utilization_data: Vec<HardwareUtilizationData> = db_query.take(0)?;
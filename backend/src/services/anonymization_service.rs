@@ -0,0 +1,237 @@
+//! Anonymization Service
+//!
+//! Masks customer identifiers in HLDs, analysis exports and workbooks before
+//! they are shared with vendors. Pseudonyms are derived from an installation
+//! secret stored in the database; each anonymized export writes its mapping
+//! to a local directory (`ANONYMIZATION_MAP_DIR`) so vendor feedback can be
+//! translated back. Mapping files are never served as downloads.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use core_engine::anonymization::{AnonymizationConfig, AnonymizationMap, Anonymizer, PseudonymKind};
+use core_engine::CoreEngineError;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::models::migration_wizard_models::MigrationWizardVM;
+use crate::models::settings::{
+    AnonymizationMapSummary, AnonymizationSettings, AnonymizeOptions, UpdateAnonymizationSettingsRequest,
+};
+use crate::database::TracedQuery;
+
+/// Response header carrying the id of the mapping file of an anonymized export
+pub const MAP_ID_HEADER: &str = "x-anonymization-map";
+
+const SETTINGS_RECORD: (&str, &str) = ("anonymization_settings", "default");
+const DEFAULT_MAP_DIR: &str = "./data/anonymization-maps";
+
+#[derive(Debug, Error)]
+pub enum AnonymizationError {
+    #[error("Anonymization map not found: {0}")]
+    MapNotFound(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Mapping file error: {0}")]
+    Io(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for AnonymizationError {
+    fn from(e: surrealdb::Error) -> Self {
+        AnonymizationError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<CoreEngineError> for AnonymizationError {
+    fn from(e: CoreEngineError) -> Self {
+        match e {
+            CoreEngineError::NotFound(msg) => AnonymizationError::MapNotFound(msg),
+            CoreEngineError::ValidationError(msg) => AnonymizationError::Validation(msg),
+            other => AnonymizationError::Io(other.to_string()),
+        }
+    }
+}
+
+pub struct AnonymizationService {
+    db: Database,
+}
+
+impl AnonymizationService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub fn map_dir() -> PathBuf {
+        std::env::var("ANONYMIZATION_MAP_DIR")
+            .unwrap_or_else(|_| DEFAULT_MAP_DIR.to_string())
+            .into()
+    }
+
+    // =========================================================================
+    // SETTINGS
+    // =========================================================================
+
+    /// Stored settings; the secret is created the first time it is needed
+    pub async fn get_settings(&self) -> Result<AnonymizationSettings, AnonymizationError> {
+        let stored: Option<AnonymizationSettings> = self.db.select(SETTINGS_RECORD).await?;
+        match stored {
+            Some(settings) => Ok(settings),
+            None => {
+                self.save_settings(AnonymizationSettings {
+                    secret: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+                    preserve: Vec::new(),
+                    updated_at: Utc::now(),
+                })
+                .await
+            }
+        }
+    }
+
+    pub async fn update_settings(
+        &self,
+        request: UpdateAnonymizationSettingsRequest,
+    ) -> Result<AnonymizationSettings, AnonymizationError> {
+        let mut preserve = Vec::new();
+        for pattern in request.preserve {
+            let pattern = pattern.trim().to_string();
+            if pattern.is_empty() {
+                continue;
+            }
+            if pattern.chars().all(|c| c == '*') {
+                return Err(AnonymizationError::Validation(
+                    "A preserve pattern of only '*' would disable anonymization".to_string(),
+                ));
+            }
+            if !preserve.contains(&pattern) {
+                preserve.push(pattern);
+            }
+        }
+
+        let mut settings = self.get_settings().await?;
+        settings.preserve = preserve;
+        settings.updated_at = Utc::now();
+        self.save_settings(settings).await
+    }
+
+    async fn save_settings(&self, settings: AnonymizationSettings) -> Result<AnonymizationSettings, AnonymizationError> {
+        let saved: Option<AnonymizationSettings> = self.db.update(SETTINGS_RECORD).content(settings).await?;
+        saved.ok_or_else(|| AnonymizationError::DatabaseError("Failed to store anonymization settings".to_string()))
+    }
+
+    // =========================================================================
+    // EXPORTS
+    // =========================================================================
+
+    /// Anonymizer for one export: the stored preserve-list plus any patterns
+    /// passed with the export request
+    pub async fn anonymizer(&self, options: &AnonymizeOptions) -> Result<Anonymizer, AnonymizationError> {
+        let settings = self.get_settings().await?;
+        let config = AnonymizationConfig::new(settings.secret)
+            .with_preserve(settings.preserve)
+            .with_preserve(options.preserve_list());
+        Ok(Anonymizer::new(config))
+    }
+
+    /// Register a migration project's VM names, hosts and addresses so that
+    /// free-text documents about the project are masked where no field names them
+    pub async fn register_project_vms(
+        &self,
+        anonymizer: &mut Anonymizer,
+        project_id: &str,
+    ) -> Result<(), AnonymizationError> {
+        let vms: Vec<MigrationWizardVM> = self
+            .db
+            .query("SELECT * FROM migration_wizard_vm WHERE project_id = type::thing('migration_wizard_project', $project_id)")
+            .bind(("project_id", project_id.to_string()))
+            .traced("anonymization_service.register_project_vms")
+            .await?
+            .take(0)?;
+
+        for vm in &vms {
+            anonymizer.register(PseudonymKind::VmName, &vm.name);
+            for host in [&vm.host, &vm.dns_name].into_iter().flatten() {
+                anonymizer.register(PseudonymKind::Hostname, host);
+            }
+            if let Some(ip) = &vm.primary_ip_address {
+                anonymizer.register(PseudonymKind::IpAddress, ip);
+            }
+        }
+        Ok(())
+    }
+
+    /// Anonymize a generated text document about a migration project and
+    /// save its mapping; returns the masked text and the mapping id
+    pub async fn anonymize_project_text(
+        &self,
+        options: &AnonymizeOptions,
+        project_id: &str,
+        text: &str,
+    ) -> Result<(String, String), AnonymizationError> {
+        let mut anonymizer = self.anonymizer(options).await?;
+        self.register_project_vms(&mut anonymizer, project_id).await?;
+        let masked = anonymizer.anonymize_text(text);
+        let map_id = self.save_map(&anonymizer)?;
+        Ok((masked, map_id))
+    }
+
+    /// Write the mapping of a finished export and return its id
+    pub fn save_map(&self, anonymizer: &Anonymizer) -> Result<String, AnonymizationError> {
+        let map = anonymizer.mapping();
+        let path = map.save(&Self::map_dir())?;
+        tracing::info!(
+            "Anonymization map {} written to {} ({} entries)",
+            map.id,
+            path.display(),
+            map.entries.len()
+        );
+        Ok(map.id)
+    }
+
+    // =========================================================================
+    // MAPPING FILES
+    // =========================================================================
+
+    /// Mapping files in the local directory, newest first
+    pub fn list_maps(&self) -> Result<Vec<AnonymizationMapSummary>, AnonymizationError> {
+        let dir = Self::map_dir();
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AnonymizationError::Io(format!("Failed to read {}: {}", dir.display(), e))),
+        };
+
+        let mut maps = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let id = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(id) => id,
+                None => continue,
+            };
+            match AnonymizationMap::load(&dir, id) {
+                Ok(map) => maps.push(AnonymizationMapSummary {
+                    id: map.id,
+                    created_at: map.created_at,
+                    entries: map.entries.len(),
+                }),
+                Err(e) => tracing::warn!("Skipping unreadable anonymization map {}: {}", path.display(), e),
+            }
+        }
+        maps.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(maps)
+    }
+
+    /// Translate text written against an anonymized export back to real names
+    pub fn deanonymize(&self, map_id: &str, text: &str) -> Result<String, AnonymizationError> {
+        let map = AnonymizationMap::load(&Self::map_dir(), map_id)?;
+        Ok(map.deanonymize_text(text))
+    }
+}
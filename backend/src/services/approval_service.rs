@@ -0,0 +1,811 @@
+// Archer ITSM - Approval Routing Service
+// Approval requests for workflow steps: delegation (vacation) rules, group
+// approvals with quorum, and reminder/escalation timers on pending approvals
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use tracing::{info, warn};
+
+use crate::{
+    database::Database,
+    models::workflow_engine::*,
+    services::notification_service::NotificationService,
+};
+use crate::database::TracedQuery;
+
+/// Delegations are followed at most this many hops (A → B → C ...)
+const MAX_DELEGATION_HOPS: usize = 5;
+
+/// State of an approval request after a response
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuorumDecision {
+    Approved,
+    Rejected,
+    Pending,
+}
+
+/// Approval routing service
+pub struct ApprovalService {
+    db: Arc<Database>,
+}
+
+impl ApprovalService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ========================================================================
+    // APPROVAL REQUESTS
+    // ========================================================================
+
+    /// Create the approvals for an approval step.
+    ///
+    /// Step config: `approver_id`, `approver_type` (USER | GROUP | ROLE),
+    /// `quorum` (GROUP only, default 1), `reminder_after_minutes` (repeats),
+    /// `escalate_after_minutes` and `escalate_to` (a user).
+    /// A GROUP approver is a team: every Lead/Member gets an approval and the
+    /// step completes once `quorum` of them approve.
+    /// Instead of `approver_id`, `approvers_from_context` can name a context
+    /// key holding a list of user ids, e.g. the reviewers a document review
+    /// was started with. Every listed user gets an approval and `quorum`
+    /// defaults to all of them.
+    pub async fn create_request(&self, instance_id: Thing, step: &WorkflowStep, context: &Value) -> Result<Value, String> {
+        let config = &step.config;
+        let context_users = context_approvers(config, context)?;
+        let approver_type = match config.get("approver_type").and_then(|v| v.as_str()).unwrap_or("USER") {
+            _ if context_users.is_some() => ApproverType::User,
+            "ROLE" => ApproverType::Role,
+            "GROUP" => ApproverType::Group,
+            _ => ApproverType::User,
+        };
+
+        let (approvers, group_id, quorum) = match context_users {
+            Some(users) => {
+                let quorum = config.get("quorum").and_then(|v| v.as_u64()).map_or(users.len() as u32, |q| q as u32);
+                if quorum == 0 || quorum as usize > users.len() {
+                    return Err(format!(
+                        "Quorum {} cannot be met by {} approver(s) from the context",
+                        quorum,
+                        users.len()
+                    ));
+                }
+                (users, None, quorum)
+            }
+            None => self.configured_approvers(config, &approver_type).await?,
+        };
+
+        let escalate_to = config
+            .get("escalate_to")
+            .and_then(|v| v.as_str())
+            .map(|id| id.parse::<Thing>().map_err(|_| format!("Invalid escalate_to ID format: {}", id)))
+            .transpose()?;
+        let reminder_interval = config
+            .get("reminder_after_minutes")
+            .and_then(|v| v.as_u64())
+            .filter(|m| *m > 0)
+            .map(|m| m as u32);
+        let escalate_after = config
+            .get("escalate_after_minutes")
+            .and_then(|v| v.as_i64())
+            .filter(|m| *m > 0);
+
+        let now = Utc::now();
+        let delegations = self.active_delegations(now).await?;
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let mut created_approvals = Vec::new();
+        let mut holders = approvers.clone();
+
+        for approver in approvers {
+            // Role approvals are claimed by any holder, so only users delegate
+            let (assignee, delegated_from) = if approver_type == ApproverType::Role {
+                (approver, None)
+            } else {
+                let delegate = resolve_request_delegate(&approver, &delegations, now, &holders);
+                holders.push(delegate.clone());
+                let from = (delegate != approver).then_some(approver);
+                (delegate, from)
+            };
+
+            let approval = Approval {
+                id: None,
+                workflow_instance_id: instance_id.clone(),
+                step_id: step.step_id.clone(),
+                approver_id: assignee,
+                approver_type: if approver_type == ApproverType::Role { ApproverType::Role } else { ApproverType::User },
+                status: ApprovalStatus::Pending,
+                requested_at: now,
+                responded_at: None,
+                comments: None,
+                request_id: Some(request_id.clone()),
+                quorum: Some(quorum),
+                group_id: group_id.clone(),
+                delegated_from,
+                escalated_from: None,
+                responded_by: None,
+                remind_at: reminder_interval.map(|m| now + Duration::minutes(m as i64)),
+                reminder_interval_minutes: reminder_interval,
+                reminders_sent: 0,
+                escalate_at: escalate_to.as_ref().and(escalate_after).map(|m| now + Duration::minutes(m)),
+                escalate_to: escalate_to.clone(),
+            };
+
+            let created: Vec<Approval> = self.db
+                .create("approval")
+                .content(&approval)
+                .await
+                .map_err(|e| format!("Failed to create approval: {}", e))?;
+            let created = created.into_iter().next()
+                .ok_or_else(|| "Failed to create approval".to_string())?;
+            created_approvals.push(created);
+        }
+
+        Ok(json!({
+            "status": "approval_requested",
+            "request_id": request_id,
+            "quorum": quorum,
+            "approvers": created_approvals.iter().map(|a| a.approver_id.to_string()).collect::<Vec<_>>(),
+            "delegated": created_approvals
+                .iter()
+                .filter_map(|a| a.delegated_from.as_ref().map(|from| json!({
+                    "from": from.to_string(),
+                    "to": a.approver_id.to_string()
+                })))
+                .collect::<Vec<_>>(),
+        }))
+    }
+
+    /// Approvers named by the step's `approver_id`, with the group and quorum
+    async fn configured_approvers(
+        &self,
+        config: &Value,
+        approver_type: &ApproverType,
+    ) -> Result<(Vec<Thing>, Option<Thing>, u32), String> {
+        let approver_id = config
+            .get("approver_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Approver ID not specified in step config".to_string())?;
+        let approver_thing: Thing = approver_id
+            .parse()
+            .map_err(|_| format!("Invalid approver ID format: {}", approver_id))?;
+
+        match approver_type {
+            ApproverType::Group => {
+                let members = self.group_members(&approver_thing).await?;
+                let quorum = config.get("quorum").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+                if quorum == 0 || quorum as usize > members.len() {
+                    return Err(format!(
+                        "Quorum {} cannot be met by {} member(s) of {}",
+                        quorum,
+                        members.len(),
+                        approver_thing
+                    ));
+                }
+                Ok((members, Some(approver_thing), quorum))
+            }
+            _ => Ok((vec![approver_thing], None, 1)),
+        }
+    }
+
+    /// Record an approve/reject response and evaluate the request's quorum.
+    /// Once the request is decided, the remaining pending approvals are cancelled.
+    pub async fn respond(
+        &self,
+        approval_id: Thing,
+        responder_id: &str,
+        decision: ApprovalDecision,
+        comments: Option<String>,
+    ) -> Result<(Approval, QuorumDecision), String> {
+        let mut approval: Approval = self.db
+            .select(approval_id.clone())
+            .await
+            .map_err(|e| format!("Failed to fetch approval: {}", e))?
+            .ok_or_else(|| "Approval not found".to_string())?;
+
+        if approval.status != ApprovalStatus::Pending {
+            return Err("Approval is not in pending state".to_string());
+        }
+        let responder = responder_id.parse::<Thing>().ok();
+        if approval.approver_type == ApproverType::User {
+            let responder = responder
+                .as_ref()
+                .ok_or_else(|| format!("Invalid user ID format: {}", responder_id))?;
+            // The original approver can still act on an approval they delegated
+            if approval.approver_id != *responder && approval.delegated_from.as_ref() != Some(responder) {
+                return Err("Only the assigned approver can respond to this approval".to_string());
+            }
+        }
+        if let (Some(request_id), Some(responder)) = (&approval.request_id, &responder) {
+            let already_answered = self
+                .request_approvals(request_id)
+                .await?
+                .iter()
+                .any(|a| a.id != approval.id && a.status != ApprovalStatus::Pending && responder_of(a) == responder);
+            if already_answered {
+                return Err("You have already responded to this approval request".to_string());
+            }
+        }
+
+        let now = Utc::now();
+        approval.status = match decision {
+            ApprovalDecision::Approve => ApprovalStatus::Approved,
+            ApprovalDecision::Reject => ApprovalStatus::Rejected,
+        };
+        approval.responded_at = Some(now);
+        approval.comments = comments;
+        approval.responded_by = responder;
+        approval.remind_at = None;
+        approval.escalate_at = None;
+
+        let updated: Approval = self.db
+            .update(approval_id)
+            .content(&approval)
+            .await
+            .map_err(|e| format!("Failed to update approval: {}", e))?
+            .ok_or_else(|| "Failed to update approval".to_string())?;
+
+        let siblings = match &updated.request_id {
+            Some(request_id) => self.request_approvals(request_id).await?,
+            None => vec![updated.clone()],
+        };
+
+        let decision = quorum_decision(&siblings, updated.quorum.unwrap_or(1));
+        if decision != QuorumDecision::Pending {
+            if let Some(request_id) = &updated.request_id {
+                self.db
+                    .query(
+                        "UPDATE approval SET status = 'CANCELLED', responded_at = $now, \
+                         remind_at = NONE, escalate_at = NONE \
+                         WHERE request_id = $request_id AND status = 'PENDING'",
+                    )
+                    .bind(("request_id", request_id.clone()))
+                    .bind(("now", now))
+                    .traced("approval_service.respond")
+                    .await
+                    .map_err(|e| format!("Failed to close approval request: {}", e))?;
+            }
+        }
+
+        Ok((updated, decision))
+    }
+
+    // ========================================================================
+    // DELEGATION RULES
+    // ========================================================================
+
+    /// Delegation rules where the user is delegator or delegate
+    pub async fn list_delegations(&self, user_id: &str) -> Result<Vec<ApprovalDelegation>, String> {
+        let user: Thing = user_id.parse()
+            .map_err(|_| format!("Invalid user ID format: {}", user_id))?;
+
+        let mut result = self.db
+            .query(
+                "SELECT * FROM approval_delegation WHERE delegator_id = $user OR delegate_id = $user \
+                 ORDER BY starts_at DESC",
+            )
+            .bind(("user", user))
+            .traced("approval_service.list_delegations")
+            .await
+            .map_err(|e| format!("Failed to fetch delegations: {}", e))?;
+
+        result.take(0).map_err(|e| format!("Failed to parse delegations: {}", e))
+    }
+
+    /// Delegate the user's approvals between two dates. Returns the rule and
+    /// how many pending approvals were moved to the delegate.
+    pub async fn create_delegation(
+        &self,
+        user_id: &str,
+        req: CreateDelegationRequest,
+    ) -> Result<(ApprovalDelegation, usize), String> {
+        let delegator: Thing = user_id.parse()
+            .map_err(|_| format!("Invalid user ID format: {}", user_id))?;
+        let delegate: Thing = req.delegate_id.parse()
+            .map_err(|_| format!("Invalid delegate ID format: {}", req.delegate_id))?;
+
+        if delegate == delegator {
+            return Err("Cannot delegate approvals to yourself".to_string());
+        }
+        if req.ends_at <= req.starts_at {
+            return Err("Delegation must end after it starts".to_string());
+        }
+
+        let now = Utc::now();
+        let delegation = ApprovalDelegation {
+            id: None,
+            delegator_id: delegator.clone(),
+            delegate_id: delegate,
+            starts_at: req.starts_at,
+            ends_at: req.ends_at,
+            reason: req.reason,
+            is_active: true,
+            created_at: now,
+        };
+
+        let created: Vec<ApprovalDelegation> = self.db
+            .create("approval_delegation")
+            .content(&delegation)
+            .await
+            .map_err(|e| format!("Failed to create delegation: {}", e))?;
+        let created = created.into_iter().next()
+            .ok_or_else(|| "Failed to create delegation".to_string())?;
+
+        let mut reassigned = 0;
+        if req.reassign_pending && created.covers(now) {
+            let delegations = self.active_delegations(now).await?;
+            let assignee = resolve_delegate(&delegator, &delegations, now);
+
+            let mut result = self.db
+                .query(
+                    "UPDATE approval SET approver_id = $assignee, delegated_from = $delegator \
+                     WHERE approver_id = $delegator AND approver_type = 'USER' AND status = 'PENDING'",
+                )
+                .bind(("assignee", assignee))
+                .bind(("delegator", delegator))
+                .traced("approval_service.create_delegation")
+                .await
+                .map_err(|e| format!("Failed to reassign pending approvals: {}", e))?;
+            let moved: Vec<Approval> = result.take(0)
+                .map_err(|e| format!("Failed to parse reassigned approvals: {}", e))?;
+            reassigned = moved.len();
+        }
+
+        Ok((created, reassigned))
+    }
+
+    /// Deactivate a delegation rule. Approvals already routed to the delegate stay with them.
+    pub async fn revoke_delegation(&self, user_id: &str, id: Thing) -> Result<ApprovalDelegation, String> {
+        let mut delegation: ApprovalDelegation = self.db
+            .select(id.clone())
+            .await
+            .map_err(|e| format!("Failed to fetch delegation: {}", e))?
+            .ok_or_else(|| "Delegation not found".to_string())?;
+
+        let user: Thing = user_id.parse()
+            .map_err(|_| format!("Invalid user ID format: {}", user_id))?;
+        if delegation.delegator_id != user {
+            return Err("Only the delegator can revoke a delegation".to_string());
+        }
+        delegation.is_active = false;
+
+        self.db
+            .update(id)
+            .content(&delegation)
+            .await
+            .map_err(|e| format!("Failed to revoke delegation: {}", e))?
+            .ok_or_else(|| "Failed to revoke delegation".to_string())
+    }
+
+    // ========================================================================
+    // REMINDERS AND ESCALATION
+    // ========================================================================
+
+    /// Send due reminders and escalate overdue approvals. Returns
+    /// (reminders sent, approvals escalated).
+    pub async fn process_timers(&self) -> Result<(usize, usize), String> {
+        let now = Utc::now();
+        let mut result = self.db
+            .query(
+                "SELECT * FROM approval WHERE status = 'PENDING' \
+                 AND (remind_at <= $now OR escalate_at <= $now)",
+            )
+            .bind(("now", now))
+            .traced("approval_service.process_timers")
+            .await
+            .map_err(|e| format!("Failed to fetch approval timers: {}", e))?;
+        let due: Vec<Approval> = result.take(0)
+            .map_err(|e| format!("Failed to parse approval timers: {}", e))?;
+        if due.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let notifier = NotificationService::from_env();
+        let delegations = self.active_delegations(now).await?;
+        let (mut reminded, mut escalated) = (0, 0);
+
+        for mut approval in due {
+            let Some(approval_id) = approval.id.clone() else { continue };
+            let interval = approval.reminder_interval_minutes.map(|m| Duration::minutes(m as i64));
+
+            let escalation_due = approval.escalate_at.is_some_and(|at| at <= now);
+            let escalation = match (escalation_due, approval.escalate_to.clone()) {
+                (true, Some(target)) => {
+                    let holders: Vec<Thing> = match &approval.request_id {
+                        Some(request_id) => self
+                            .request_approvals(request_id)
+                            .await?
+                            .iter()
+                            .filter(|a| a.id != approval.id)
+                            .map(|a| responder_of(a).clone())
+                            .collect(),
+                        None => Vec::new(),
+                    };
+                    escalation_assignee(&target, &delegations, now, &holders).map(|assignee| (target, assignee))
+                }
+                _ => None,
+            };
+            if let Some((target, assignee)) = escalation {
+                approval.escalated_from = Some(approval.approver_id.clone());
+                approval.delegated_from = (assignee != target).then_some(target);
+                approval.approver_id = assignee;
+                approval.approver_type = ApproverType::User;
+                approval.escalate_at = None;
+                approval.remind_at = interval.map(|i| now + i);
+                approval.reminders_sent = 0;
+
+                self.notify_approver(
+                    &notifier,
+                    &approval,
+                    "Escalated approval waiting for you",
+                    "An approval was escalated to you because it was not answered in time.",
+                )
+                .await;
+                escalated += 1;
+            } else if approval.remind_at.is_some_and(|at| at <= now) {
+                approval.reminders_sent += 1;
+                approval.remind_at = interval.map(|i| now + i);
+                if escalation_due {
+                    // Nobody (else) to escalate to
+                    approval.escalate_at = None;
+                }
+
+                self.notify_approver(
+                    &notifier,
+                    &approval,
+                    "Reminder: approval pending",
+                    &format!(
+                        "An approval requested {} is still waiting for your decision.",
+                        approval.requested_at.format("%Y-%m-%d %H:%M UTC")
+                    ),
+                )
+                .await;
+                reminded += 1;
+            } else {
+                approval.escalate_at = None;
+            }
+
+            let _: Option<Approval> = self.db
+                .update(approval_id)
+                .content(&approval)
+                .await
+                .map_err(|e| format!("Failed to update approval: {}", e))?;
+        }
+
+        Ok((reminded, escalated))
+    }
+
+    /// Email the approver when they are a user with an address; reminders are best effort
+    async fn notify_approver(&self, notifier: &NotificationService, approval: &Approval, subject: &str, body: &str) {
+        if approval.approver_type != ApproverType::User {
+            return;
+        }
+
+        let email = match self.db
+            .query("SELECT VALUE email FROM $user")
+            .bind(("user", approval.approver_id.clone()))
+            .traced("approval_service.notify_approver")
+            .await
+            .and_then(|mut r| r.take::<Vec<String>>(0))
+        {
+            Ok(emails) => emails.into_iter().next(),
+            Err(e) => {
+                warn!("Failed to look up approver {}: {}", approval.approver_id, e);
+                None
+            }
+        };
+        let Some(email) = email else { return };
+
+        let body = format!(
+            "{}\n\nWorkflow instance: {}\nStep: {}",
+            body, approval.workflow_instance_id, approval.step_id
+        );
+        for result in notifier.send_email(&[email], subject, &body, None).await {
+            info!("Approval notification to {}: {:?}", result.target, result.status);
+        }
+    }
+
+    /// Every approval created by one step execution
+    async fn request_approvals(&self, request_id: &str) -> Result<Vec<Approval>, String> {
+        let mut result = self.db
+            .query("SELECT * FROM approval WHERE request_id = $request_id")
+            .bind(("request_id", request_id.to_string()))
+            .traced("approval_service.request_approvals")
+            .await
+            .map_err(|e| format!("Failed to fetch approval request: {}", e))?;
+
+        result.take(0).map_err(|e| format!("Failed to parse approval request: {}", e))
+    }
+
+    async fn active_delegations(&self, at: DateTime<Utc>) -> Result<Vec<ApprovalDelegation>, String> {
+        let mut result = self.db
+            .query(
+                "SELECT * FROM approval_delegation WHERE is_active = true \
+                 AND starts_at <= $at AND ends_at > $at",
+            )
+            .bind(("at", at))
+            .traced("approval_service.active_delegations")
+            .await
+            .map_err(|e| format!("Failed to fetch delegations: {}", e))?;
+
+        result.take(0).map_err(|e| format!("Failed to parse delegations: {}", e))
+    }
+
+    /// Team members who can approve (leads and members, not observers)
+    async fn group_members(&self, team_id: &Thing) -> Result<Vec<Thing>, String> {
+        let mut result = self.db
+            .query("SELECT VALUE user_id FROM team_memberships WHERE team_id = $team AND role != 'OBSERVER'")
+            .bind(("team", team_id.clone()))
+            .traced("approval_service.group_members")
+            .await
+            .map_err(|e| format!("Failed to fetch group members: {}", e))?;
+        let mut members: Vec<Thing> = result.take(0)
+            .map_err(|e| format!("Failed to parse group members: {}", e))?;
+
+        members.sort_by_key(|m| m.to_string());
+        members.dedup();
+        if members.is_empty() {
+            return Err(format!("Approval group {} has no members", team_id));
+        }
+        Ok(members)
+    }
+}
+
+/// Follow active delegation rules from `approver`, stopping at cycles and
+/// after MAX_DELEGATION_HOPS. The most recently created rule wins when a
+/// user has overlapping delegations.
+pub fn resolve_delegate(approver: &Thing, delegations: &[ApprovalDelegation], at: DateTime<Utc>) -> Thing {
+    let mut current = approver.clone();
+    let mut visited = vec![current.clone()];
+
+    for _ in 0..MAX_DELEGATION_HOPS {
+        let next = delegations
+            .iter()
+            .filter(|d| d.delegator_id == current && d.covers(at))
+            .max_by_key(|d| d.created_at);
+        match next {
+            Some(rule) if !visited.contains(&rule.delegate_id) => {
+                current = rule.delegate_id.clone();
+                visited.push(current.clone());
+            }
+            _ => break,
+        }
+    }
+    current
+}
+
+/// `resolve_delegate`, except that a delegate who already holds an approval
+/// in the same request is skipped and the original approver keeps it, so one
+/// person never holds two votes toward a quorum
+pub fn resolve_request_delegate(
+    approver: &Thing,
+    delegations: &[ApprovalDelegation],
+    at: DateTime<Utc>,
+    holders: &[Thing],
+) -> Thing {
+    let delegate = resolve_delegate(approver, delegations, at);
+    if delegate != *approver && holders.contains(&delegate) {
+        approver.clone()
+    } else {
+        delegate
+    }
+}
+
+/// Who an overdue approval escalates to: the escalation target's delegate,
+/// else the target itself, skipping anyone who already holds or answered
+/// another approval in the request. `None` leaves the approval where it is.
+pub fn escalation_assignee(
+    target: &Thing,
+    delegations: &[ApprovalDelegation],
+    at: DateTime<Utc>,
+    holders: &[Thing],
+) -> Option<Thing> {
+    [resolve_delegate(target, delegations, at), target.clone()]
+        .into_iter()
+        .find(|candidate| !holders.contains(candidate))
+}
+
+/// The user an approval speaks for: whoever answered it, else its assignee
+fn responder_of(approval: &Approval) -> &Thing {
+    approval.responded_by.as_ref().unwrap_or(&approval.approver_id)
+}
+
+/// Approved once `quorum` distinct people have approved; rejected once
+/// enough rejections make the quorum unreachable
+pub fn quorum_decision(approvals: &[Approval], quorum: u32) -> QuorumDecision {
+    let mut approvers: Vec<&Thing> = approvals
+        .iter()
+        .filter(|a| a.status == ApprovalStatus::Approved)
+        .map(responder_of)
+        .collect();
+    approvers.sort_by_key(|t| t.to_string());
+    approvers.dedup();
+    // People who could still approve: everyone with an approval that is
+    // not rejected, counted once however many approvals they hold
+    let mut eligible: Vec<&Thing> = approvals
+        .iter()
+        .filter(|a| a.status != ApprovalStatus::Rejected)
+        .map(responder_of)
+        .collect();
+    eligible.sort_by_key(|t| t.to_string());
+    eligible.dedup();
+    let quorum = quorum.max(1) as usize;
+
+    if approvers.len() >= quorum {
+        QuorumDecision::Approved
+    } else if eligible.len() < quorum {
+        QuorumDecision::Rejected
+    } else {
+        QuorumDecision::Pending
+    }
+}
+
+/// Users listed under the step's `approvers_from_context` key, if it has one
+fn context_approvers(config: &Value, context: &Value) -> Result<Option<Vec<Thing>>, String> {
+    let Some(key) = config.get("approvers_from_context").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    let ids = context
+        .get(key)
+        .and_then(|v| v.as_array())
+        .filter(|ids| !ids.is_empty())
+        .ok_or_else(|| format!("Context has no approvers under '{}'", key))?;
+
+    let mut users = Vec::new();
+    for id in ids {
+        let id = id.as_str().ok_or_else(|| format!("Approvers under '{}' must be user IDs", key))?;
+        let user: Thing = id.parse().map_err(|_| format!("Invalid approver ID format: {}", id))?;
+        if !users.contains(&user) {
+            users.push(user);
+        }
+    }
+    Ok(Some(users))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn user(id: &str) -> Thing {
+        Thing::from(("users", id))
+    }
+
+    fn rule(from: &str, to: &str, start_day: u32, end_day: u32, created_minute: u32) -> ApprovalDelegation {
+        ApprovalDelegation {
+            id: None,
+            delegator_id: user(from),
+            delegate_id: user(to),
+            starts_at: Utc.with_ymd_and_hms(2024, 7, start_day, 0, 0, 0).unwrap(),
+            ends_at: Utc.with_ymd_and_hms(2024, 7, end_day, 0, 0, 0).unwrap(),
+            reason: Some("Vacation".to_string()),
+            is_active: true,
+            created_at: Utc.with_ymd_and_hms(2024, 6, 1, 0, created_minute, 0).unwrap(),
+        }
+    }
+
+    fn approval(status: ApprovalStatus) -> Approval {
+        Approval {
+            id: None,
+            workflow_instance_id: Thing::from(("workflow_instance", "i1")),
+            step_id: "cab".to_string(),
+            approver_id: user("a"),
+            approver_type: ApproverType::User,
+            status,
+            requested_at: Utc::now(),
+            responded_at: None,
+            comments: None,
+            request_id: Some("r1".to_string()),
+            quorum: Some(2),
+            group_id: None,
+            delegated_from: None,
+            escalated_from: None,
+            responded_by: None,
+            remind_at: None,
+            reminder_interval_minutes: None,
+            reminders_sent: 0,
+            escalate_at: None,
+            escalate_to: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_delegate_follows_active_rules() {
+        let at = Utc.with_ymd_and_hms(2024, 7, 10, 12, 0, 0).unwrap();
+        let rules = vec![
+            rule("alice", "bob", 1, 15, 0),
+            rule("bob", "carol", 5, 20, 0),
+            // Expired and inactive rules are ignored
+            rule("carol", "dave", 1, 5, 0),
+            ApprovalDelegation { is_active: false, ..rule("carol", "erin", 1, 31, 0) },
+        ];
+        assert_eq!(resolve_delegate(&user("alice"), &rules, at), user("carol"));
+        assert_eq!(resolve_delegate(&user("zed"), &rules, at), user("zed"));
+
+        // Outside the window nothing is delegated
+        let later = Utc.with_ymd_and_hms(2024, 7, 25, 0, 0, 0).unwrap();
+        assert_eq!(resolve_delegate(&user("alice"), &rules, later), user("alice"));
+    }
+
+    #[test]
+    fn test_resolve_delegate_stops_at_cycles_and_prefers_newest_rule() {
+        let at = Utc.with_ymd_and_hms(2024, 7, 10, 0, 0, 0).unwrap();
+        let cycle = vec![rule("alice", "bob", 1, 31, 0), rule("bob", "alice", 1, 31, 0)];
+        assert_eq!(resolve_delegate(&user("alice"), &cycle, at), user("bob"));
+
+        let overlapping = vec![rule("alice", "bob", 1, 31, 0), rule("alice", "carol", 1, 31, 5)];
+        assert_eq!(resolve_delegate(&user("alice"), &overlapping, at), user("carol"));
+    }
+
+    #[test]
+    fn test_quorum_decision() {
+        use ApprovalStatus::*;
+        let request = |statuses: &[ApprovalStatus]| {
+            statuses
+                .iter()
+                .enumerate()
+                .map(|(i, status)| Approval { approver_id: user(&format!("u{}", i)), ..approval(status.clone()) })
+                .collect::<Vec<_>>()
+        };
+
+        // 2 of 5
+        assert_eq!(quorum_decision(&request(&[Approved, Pending, Pending, Pending, Pending]), 2), QuorumDecision::Pending);
+        assert_eq!(quorum_decision(&request(&[Approved, Rejected, Approved, Pending, Pending]), 2), QuorumDecision::Approved);
+        assert_eq!(quorum_decision(&request(&[Rejected, Rejected, Rejected, Pending, Pending]), 2), QuorumDecision::Pending);
+        assert_eq!(quorum_decision(&request(&[Rejected, Rejected, Rejected, Rejected, Pending]), 2), QuorumDecision::Rejected);
+
+        // Single approver
+        assert_eq!(quorum_decision(&request(&[Approved]), 1), QuorumDecision::Approved);
+        assert_eq!(quorum_decision(&request(&[Rejected]), 1), QuorumDecision::Rejected);
+    }
+
+    #[test]
+    fn test_quorum_counts_people_when_delegation_and_escalation_collapse() {
+        use ApprovalStatus::*;
+        let at = Utc.with_ymd_and_hms(2024, 7, 10, 0, 0, 0).unwrap();
+        let cab = ["alice", "bob", "carol", "dave", "erin"].map(user);
+        // Alice is away and delegates to Bob, who also sits on the CAB and is
+        // the escalation target for overdue approvals
+        let rules = vec![rule("alice", "bob", 1, 31, 0)];
+
+        assert_eq!(resolve_request_delegate(&cab[0], &rules, at, &cab), user("alice"));
+        assert_eq!(resolve_request_delegate(&user("frank"), &rules, at, &cab), user("frank"));
+        assert_eq!(escalation_assignee(&user("bob"), &rules, at, &cab[..2]), None);
+        assert_eq!(escalation_assignee(&user("bob"), &rules, at, &cab[2..]), Some(user("bob")));
+        let to_zoe = vec![rule("bob", "zoe", 1, 31, 0)];
+        assert_eq!(escalation_assignee(&user("bob"), &to_zoe, at, &cab), Some(user("zoe")));
+
+        // Rows already collapsed onto Bob (delegated, escalated and his own)
+        // are one vote toward "2 of 5"
+        let held_by_bob = |status: ApprovalStatus| Approval {
+            approver_id: user("bob"),
+            responded_by: (status != Pending).then(|| user("bob")),
+            ..approval(status)
+        };
+        let request = vec![
+            Approval { delegated_from: Some(user("alice")), ..held_by_bob(Approved) },
+            held_by_bob(Approved),
+            Approval { escalated_from: Some(user("carol")), ..held_by_bob(Approved) },
+            Approval { approver_id: user("dave"), ..approval(Pending) },
+            Approval { approver_id: user("erin"), ..approval(Pending) },
+        ];
+        assert_eq!(quorum_decision(&request, 2), QuorumDecision::Pending);
+
+        // Bob also counts once toward the people who could still approve
+        let mut rejected = request.clone();
+        rejected[3].status = Rejected;
+        rejected[4].status = Rejected;
+        assert_eq!(quorum_decision(&rejected, 2), QuorumDecision::Rejected);
+    }
+
+    #[test]
+    fn test_context_approvers() {
+        let config = json!({ "approvers_from_context": "reviewers" });
+        let context = json!({ "reviewers": ["users:alice", "users:bob", "users:alice"] });
+        assert_eq!(context_approvers(&config, &context).unwrap(), Some(vec![user("alice"), user("bob")]));
+
+        assert_eq!(context_approvers(&json!({ "approver_id": "users:alice" }), &context).unwrap(), None);
+        assert!(context_approvers(&config, &json!({ "reviewers": [] })).is_err());
+        assert!(context_approvers(&config, &json!({})).is_err());
+    }
+}
@@ -0,0 +1,622 @@
+//! Assessment Service
+//!
+//! Runs rule packs against the parsed environment and the CMDB:
+//! - Built-in packs (Hyper-V readiness, hygiene baseline, vendor EOL exposure)
+//! - Custom YAML rule packs imported per tenant
+//! - Severity-weighted scoring per pack and overall
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashSet;
+use surrealdb::sql::{Id, Thing};
+
+use crate::database::Database;
+use crate::models::assessment::*;
+use crate::models::cmdb::ConfigurationItem;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::database::TracedQuery;
+
+const HYPERV_READINESS_PACK: &str = r#"
+pack_id: hyperv-readiness
+name: Hyper-V Readiness
+version: "1.0"
+description: Checks that VMs can move to Hyper-V without rework
+rules:
+  - id: HV-001
+    title: Guest OS without Hyper-V integration support
+    severity: critical
+    applies_to: vm
+    condition:
+      any:
+        - { field: os, op: matches, value: "(?i)windows (xp|2000|server 2003|2003)" }
+        - { field: os, op: matches, value: "(?i)(rhel|red hat).*\\b[45]\\b" }
+    remediation: Upgrade the guest OS or rebuild on a supported release before migration
+  - id: HV-002
+    title: Guest OS not identified
+    severity: medium
+    applies_to: vm
+    condition: { field: os, op: missing }
+    remediation: Confirm the guest OS manually so integration services can be planned
+  - id: HV-003
+    title: VM exceeds 64 virtual processors
+    severity: high
+    applies_to: vm
+    condition: { field: cpus, op: gt, value: 64 }
+    remediation: Right-size the VM or confirm the target hosts support the vCPU count
+  - id: HV-004
+    title: VM memory above 1 TB
+    severity: high
+    applies_to: vm
+    condition: { field: memory_mb, op: gt, value: 1048576 }
+    remediation: Validate host NUMA layout and memory capacity for large VMs
+  - id: HV-005
+    title: More than 8 network adapters
+    severity: medium
+    applies_to: vm
+    condition: { field: num_nics, op: gt, value: 8 }
+    remediation: Consolidate NICs; Hyper-V Gen 2 VMs support at most 8 synthetic adapters
+"#;
+
+const HYGIENE_BASELINE_PACK: &str = r#"
+pack_id: hygiene-baseline
+name: Hygiene Baseline
+version: "1.0"
+description: CIS-style hygiene checks for inventory and CMDB quality
+rules:
+  - id: HY-001
+    title: Powered-off VM still in inventory
+    severity: low
+    applies_to: vm
+    condition: { field: powerstate, op: equals, value: poweredOff }
+    remediation: Confirm whether the VM is still required or retire it instead of migrating
+  - id: HY-002
+    title: VM has no DNS name
+    severity: low
+    applies_to: vm
+    condition: { field: dns_name, op: missing }
+    remediation: Register the VM in DNS or record why it is intentionally unnamed
+  - id: HY-003
+    title: Template left in production inventory
+    severity: info
+    applies_to: vm
+    condition: { field: template, op: equals, value: true }
+    remediation: Move templates to a content library rather than migrating them as VMs
+  - id: HY-004
+    title: Configuration item has no owner
+    severity: medium
+    applies_to: ci
+    condition: { field: owner_id, op: missing }
+    remediation: Assign an accountable owner in the CMDB
+  - id: HY-005
+    title: Configuration item has no support group
+    severity: low
+    applies_to: ci
+    condition: { field: support_group, op: missing }
+    remediation: Assign a support group so incidents can be routed
+"#;
+
+const VENDOR_EOL_PACK: &str = r#"
+pack_id: vendor-eol-exposure
+name: Vendor EOL Exposure
+version: "1.0"
+description: Flags assets and guest operating systems past vendor end of life
+rules:
+  - id: EOL-001
+    title: Configuration item past vendor end of life
+    severity: high
+    applies_to: ci
+    condition: { field: end_of_life, op: before, value: now }
+    remediation: Plan hardware/software refresh or document an exception
+  - id: EOL-002
+    title: Configuration item out of warranty
+    severity: medium
+    applies_to: ci
+    condition: { field: warranty_expiry, op: before, value: now }
+    remediation: Renew support or schedule replacement
+  - id: EOL-003
+    title: Guest OS past end of support
+    severity: high
+    applies_to: vm
+    condition:
+      any:
+        - { field: os, op: matches, value: "(?i)windows (server )?(2003|2008)" }
+        - { field: os, op: matches, value: "(?i)(centos|red hat enterprise linux) ?[456]\\b" }
+        - { field: os, op: matches, value: "(?i)ubuntu.*1[0246]\\.04" }
+    remediation: Upgrade to a supported OS release or purchase extended support
+"#;
+
+pub struct AssessmentService {
+    db: Database,
+}
+
+impl AssessmentService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    // =========================================================================
+    // RULE PACKS
+    // =========================================================================
+
+    /// Built-in packs, parsed from their embedded YAML definitions
+    pub fn builtin_packs() -> Vec<RulePack> {
+        [HYPERV_READINESS_PACK, HYGIENE_BASELINE_PACK, VENDOR_EOL_PACK]
+            .iter()
+            .map(|yaml| {
+                let mut pack = Self::parse_pack(yaml).expect("built-in rule pack must be valid");
+                pack.builtin = true;
+                pack
+            })
+            .collect()
+    }
+
+    /// Parse and validate a YAML rule pack
+    pub fn parse_pack(yaml: &str) -> Result<RulePack> {
+        let pack: RulePack = serde_yaml::from_str(yaml).context("Invalid rule pack YAML")?;
+
+        if pack.pack_id.trim().is_empty() {
+            bail!("Rule pack must have a pack_id");
+        }
+        if pack.rules.is_empty() {
+            bail!("Rule pack '{}' has no rules", pack.pack_id);
+        }
+
+        let mut seen = HashSet::new();
+        for rule in &pack.rules {
+            if !seen.insert(rule.id.as_str()) {
+                bail!("Duplicate rule id '{}' in pack '{}'", rule.id, pack.pack_id);
+            }
+            validate_condition(&rule.condition)
+                .with_context(|| format!("Rule '{}' has an invalid condition", rule.id))?;
+        }
+
+        Ok(pack)
+    }
+
+    /// Built-in packs plus custom packs owned by the tenant
+    pub async fn list_packs(&self, tenant_id: Option<&str>) -> Result<Vec<RulePack>> {
+        let mut packs = Self::builtin_packs();
+
+        let custom: Vec<RulePack> = self
+            .db
+            .query("SELECT * FROM assessment_rule_pack WHERE tenant_id = $tenant_id ORDER BY pack_id ASC")
+            .bind(("tenant_id", tenant_id.map(|t| t.to_string())))
+            .traced("assessment_service.list_packs")
+            .await
+            .context("Failed to query rule packs")?
+            .take(0)
+            .context("Failed to parse rule packs")?;
+
+        packs.extend(custom);
+        Ok(packs)
+    }
+
+    /// Import (or replace) a custom rule pack for a tenant
+    pub async fn import_pack(&self, tenant_id: Option<&str>, yaml: &str) -> Result<RulePack> {
+        let mut pack = Self::parse_pack(yaml)?;
+
+        if Self::builtin_packs().iter().any(|p| p.pack_id == pack.pack_id) {
+            bail!("'{}' is a built-in pack and cannot be replaced", pack.pack_id);
+        }
+
+        self.db
+            .query("DELETE assessment_rule_pack WHERE pack_id = $pack_id AND tenant_id = $tenant_id")
+            .bind(("pack_id", pack.pack_id.clone()))
+            .bind(("tenant_id", tenant_id.map(|t| t.to_string())))
+            .traced("assessment_service.import_pack")
+            .await
+            .context("Failed to replace existing rule pack")?;
+
+        let now = Utc::now();
+        pack.id = None;
+        pack.tenant_id = tenant_id.map(|t| t.to_string());
+        pack.builtin = false;
+        pack.created_at = now;
+        pack.updated_at = now;
+
+        let created: Vec<RulePack> = self
+            .db
+            .create("assessment_rule_pack")
+            .content(pack)
+            .await
+            .context("Failed to store rule pack")?;
+
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No rule pack returned after import"))
+    }
+
+    pub async fn delete_pack(&self, tenant_id: Option<&str>, pack_id: &str) -> Result<()> {
+        self.db
+            .query("DELETE assessment_rule_pack WHERE pack_id = $pack_id AND tenant_id = $tenant_id")
+            .bind(("pack_id", pack_id.to_string()))
+            .bind(("tenant_id", tenant_id.map(|t| t.to_string())))
+            .traced("assessment_service.delete_pack")
+            .await
+            .context("Failed to delete rule pack")?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // ASSESSMENT RUNS
+    // =========================================================================
+
+    /// Gather subjects, evaluate the selected packs and store the run
+    pub async fn run_assessment(
+        &self,
+        tenant_id: Option<&str>,
+        request: RunAssessmentRequest,
+    ) -> Result<AssessmentRun> {
+        let mut packs = self.list_packs(tenant_id).await?;
+        if let Some(selected) = &request.pack_ids {
+            packs.retain(|p| selected.contains(&p.pack_id));
+            if packs.is_empty() {
+                bail!("None of the requested rule packs exist");
+            }
+        }
+
+        let mut subjects = Vec::new();
+        if let Some(project_id) = &request.project_id {
+            let wizard = MigrationWizardService::new(self.db.clone());
+            for vm in wizard.get_project_vms(project_id, None).await? {
+                subjects.push(AssessmentSubject {
+                    subject_type: SubjectType::Vm,
+                    name: vm.name.clone(),
+                    data: serde_json::to_value(&vm)?,
+                });
+            }
+        }
+        if request.include_cmdb {
+            let cis: Vec<ConfigurationItem> = self
+                .db
+                .query("SELECT * FROM configuration_items WHERE status != 'DISPOSED'")
+                .traced("assessment_service.run_assessment")
+                .await
+                .context("Failed to query configuration items")?
+                .take(0)
+                .context("Failed to parse configuration items")?;
+
+            for ci in cis {
+                let visible = match (tenant_id, &ci.tenant_id) {
+                    (Some(tenant), Some(owner)) => owner.to_string() == tenant || record_key(owner) == tenant,
+                    _ => true,
+                };
+                if visible {
+                    subjects.push(AssessmentSubject {
+                        subject_type: SubjectType::Ci,
+                        name: format!("{} ({})", ci.name, ci.ci_id),
+                        data: serde_json::to_value(&ci)?,
+                    });
+                }
+            }
+        }
+        if subjects.is_empty() {
+            bail!("Nothing to assess; provide a project_id and/or include_cmdb");
+        }
+
+        let mut run = Self::evaluate(&packs, &subjects, Utc::now());
+        run.project_id = request
+            .project_id
+            .as_deref()
+            .map(|id| Thing::from(("migration_wizard_project", id)));
+        run.tenant_id = tenant_id.map(|t| t.to_string());
+
+        let created: Vec<AssessmentRun> = self
+            .db
+            .create("assessment_run")
+            .content(run)
+            .await
+            .context("Failed to store assessment run")?;
+
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No assessment run returned after creation"))
+    }
+
+    pub async fn get_run(&self, run_id: &str) -> Result<AssessmentRun> {
+        let run: Option<AssessmentRun> = self
+            .db
+            .select(("assessment_run", run_id))
+            .await
+            .context("Failed to get assessment run")?;
+        run.ok_or_else(|| anyhow!("Assessment run not found"))
+    }
+
+    /// Evaluate packs against subjects.
+    ///
+    /// Each applicable (rule, subject) pair is one check weighted by rule
+    /// severity; a pack's score is the share of weight that passed.
+    pub fn evaluate(packs: &[RulePack], subjects: &[AssessmentSubject], now: DateTime<Utc>) -> AssessmentRun {
+        let mut findings = Vec::new();
+        let mut pack_scores = Vec::new();
+        let mut total_weight = 0u64;
+        let mut failed_weight = 0u64;
+
+        for pack in packs {
+            let mut checks = 0;
+            let mut failures = 0;
+            let mut pack_weight = 0u64;
+            let mut pack_failed = 0u64;
+
+            for rule in &pack.rules {
+                let weight = rule.severity.weight() as u64;
+                for subject in subjects {
+                    if rule.applies_to != SubjectType::Any && rule.applies_to != subject.subject_type {
+                        continue;
+                    }
+                    checks += 1;
+                    pack_weight += weight;
+
+                    if evaluate_condition(&rule.condition, &subject.data, now) {
+                        failures += 1;
+                        pack_failed += weight;
+                        findings.push(AssessmentFinding {
+                            pack_id: pack.pack_id.clone(),
+                            rule_id: rule.id.clone(),
+                            title: rule.title.clone(),
+                            severity: rule.severity,
+                            subject_type: subject.subject_type,
+                            subject_name: subject.name.clone(),
+                            remediation: rule.remediation.clone(),
+                        });
+                    }
+                }
+            }
+
+            total_weight += pack_weight;
+            failed_weight += pack_failed;
+            pack_scores.push(PackScore {
+                pack_id: pack.pack_id.clone(),
+                name: pack.name.clone(),
+                version: pack.version.clone(),
+                rules_evaluated: pack.rules.len(),
+                checks,
+                failures,
+                score: score(pack_weight, pack_failed),
+            });
+        }
+
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.rule_id.cmp(&b.rule_id)));
+
+        AssessmentRun {
+            id: None,
+            project_id: None,
+            tenant_id: None,
+            pack_scores,
+            findings,
+            overall_score: score(total_weight, failed_weight),
+            subjects_evaluated: subjects.len(),
+            created_at: now,
+        }
+    }
+}
+
+// =============================================================================
+// CONDITION EVALUATION
+// =============================================================================
+
+fn record_key(thing: &Thing) -> String {
+    match &thing.id {
+        Id::String(s) => s.clone(),
+        Id::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn score(total_weight: u64, failed_weight: u64) -> f64 {
+    if total_weight == 0 {
+        100.0
+    } else {
+        ((1.0 - failed_weight as f64 / total_weight as f64) * 1000.0).round() / 10.0
+    }
+}
+
+fn validate_condition(condition: &RuleCondition) -> Result<()> {
+    match condition {
+        RuleCondition::All { all } => all.iter().try_for_each(validate_condition),
+        RuleCondition::Any { any } => any.iter().try_for_each(validate_condition),
+        RuleCondition::Not { not } => validate_condition(not),
+        RuleCondition::Field(field) => {
+            if field.op == ConditionOperator::Matches {
+                let pattern = field
+                    .value
+                    .as_str()
+                    .ok_or_else(|| anyhow!("'matches' requires a string pattern"))?;
+                Regex::new(pattern).context("Invalid regular expression")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn lookup<'a>(data: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(data, |value, key| value.get(key))
+        .filter(|v| !v.is_null())
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn as_date(value: &Value, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let text = value.as_str()?;
+    if text.eq_ignore_ascii_case("now") {
+        return Some(now);
+    }
+    DateTime::parse_from_rfc3339(text)
+        .map(|d| d.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|d| Utc.from_utc_datetime(&d))
+        })
+}
+
+fn evaluate_condition(condition: &RuleCondition, data: &Value, now: DateTime<Utc>) -> bool {
+    match condition {
+        RuleCondition::All { all } => all.iter().all(|c| evaluate_condition(c, data, now)),
+        RuleCondition::Any { any } => any.iter().any(|c| evaluate_condition(c, data, now)),
+        RuleCondition::Not { not } => !evaluate_condition(not, data, now),
+        RuleCondition::Field(field) => evaluate_field(field, data, now),
+    }
+}
+
+fn evaluate_field(condition: &FieldCondition, data: &Value, now: DateTime<Utc>) -> bool {
+    let actual = lookup(data, &condition.field);
+    let expected = &condition.value;
+
+    match condition.op {
+        ConditionOperator::Exists => actual.is_some(),
+        ConditionOperator::Missing => match actual {
+            None => true,
+            Some(v) => v.as_str() == Some(""),
+        },
+        op => {
+            let Some(actual) = actual else {
+                return false;
+            };
+            match op {
+                ConditionOperator::Equals => values_equal(actual, expected),
+                ConditionOperator::NotEquals => !values_equal(actual, expected),
+                ConditionOperator::Contains => {
+                    as_text(actual).to_lowercase().contains(&as_text(expected).to_lowercase())
+                }
+                ConditionOperator::NotContains => {
+                    !as_text(actual).to_lowercase().contains(&as_text(expected).to_lowercase())
+                }
+                ConditionOperator::Matches => expected
+                    .as_str()
+                    .and_then(|p| Regex::new(p).ok())
+                    .is_some_and(|re| re.is_match(&as_text(actual))),
+                ConditionOperator::In => expected
+                    .as_array()
+                    .is_some_and(|items| items.iter().any(|item| values_equal(actual, item))),
+                ConditionOperator::Gt | ConditionOperator::Gte | ConditionOperator::Lt | ConditionOperator::Lte => {
+                    match (actual.as_f64(), expected.as_f64()) {
+                        (Some(a), Some(e)) => match op {
+                            ConditionOperator::Gt => a > e,
+                            ConditionOperator::Gte => a >= e,
+                            ConditionOperator::Lt => a < e,
+                            _ => a <= e,
+                        },
+                        _ => false,
+                    }
+                }
+                ConditionOperator::Before => match (as_date(actual, now), as_date(expected, now)) {
+                    (Some(a), Some(e)) => a < e,
+                    _ => false,
+                },
+                ConditionOperator::Exists | ConditionOperator::Missing => unreachable!(),
+            }
+        }
+    }
+}
+
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::String(a), Value::String(e)) => a.eq_ignore_ascii_case(e),
+        (Value::Number(a), Value::Number(e)) => a.as_f64() == e.as_f64(),
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vm(name: &str, data: Value) -> AssessmentSubject {
+        AssessmentSubject {
+            subject_type: SubjectType::Vm,
+            name: name.to_string(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_builtin_packs_parse() {
+        let packs = AssessmentService::builtin_packs();
+        assert_eq!(packs.len(), 3);
+        assert!(packs.iter().all(|p| p.builtin && !p.rules.is_empty()));
+    }
+
+    #[test]
+    fn test_custom_pack_yaml() {
+        let yaml = r#"
+pack_id: tenant-naming
+name: Naming Standard
+rules:
+  - id: N-1
+    title: VM name not prefixed
+    severity: low
+    applies_to: vm
+    condition:
+      not: { field: name, op: matches, value: "^(PRD|DEV)-" }
+"#;
+        let pack = AssessmentService::parse_pack(yaml).unwrap();
+        let subjects = vec![
+            vm("PRD-web01", json!({ "name": "PRD-web01" })),
+            vm("web02", json!({ "name": "web02" })),
+        ];
+
+        let run = AssessmentService::evaluate(&[pack], &subjects, Utc::now());
+        assert_eq!(run.findings.len(), 1);
+        assert_eq!(run.findings[0].subject_name, "web02");
+        assert_eq!(run.pack_scores[0].score, 50.0);
+    }
+
+    #[test]
+    fn test_invalid_regex_rejected() {
+        let yaml = r#"
+pack_id: broken
+name: Broken
+rules:
+  - id: B-1
+    title: Bad pattern
+    severity: low
+    condition: { field: name, op: matches, value: "([" }
+"#;
+        assert!(AssessmentService::parse_pack(yaml).is_err());
+    }
+
+    #[test]
+    fn test_hyperv_readiness_findings() {
+        let packs: Vec<RulePack> = AssessmentService::builtin_packs()
+            .into_iter()
+            .filter(|p| p.pack_id == "hyperv-readiness")
+            .collect();
+        let subjects = vec![
+            vm("old", json!({ "os": "Microsoft Windows Server 2003 Standard (32-bit)", "cpus": 2, "memory_mb": 4096, "num_nics": 1 })),
+            vm("big", json!({ "os": "Microsoft Windows Server 2022", "cpus": 96, "memory_mb": 4096, "num_nics": 1 })),
+            vm("ok", json!({ "os": "Microsoft Windows Server 2022", "cpus": 4, "memory_mb": 8192, "num_nics": 2 })),
+        ];
+
+        let run = AssessmentService::evaluate(&packs, &subjects, Utc::now());
+        let rules: Vec<&str> = run.findings.iter().map(|f| f.rule_id.as_str()).collect();
+        assert_eq!(rules, vec!["HV-001", "HV-003"]);
+        assert!(run.overall_score < 100.0);
+    }
+
+    #[test]
+    fn test_before_operator() {
+        let now = Utc::now();
+        let condition = FieldCondition {
+            field: "end_of_life".to_string(),
+            op: ConditionOperator::Before,
+            value: json!("now"),
+        };
+        assert!(evaluate_field(&condition, &json!({ "end_of_life": "2020-01-01T00:00:00Z" }), now));
+        assert!(!evaluate_field(&condition, &json!({ "end_of_life": "2999-01-01" }), now));
+        assert!(!evaluate_field(&condition, &json!({}), now));
+    }
+}
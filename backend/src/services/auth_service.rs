@@ -19,6 +19,8 @@ use std::sync::Arc;
 use surrealdb::sql::Thing;
 use thiserror::Error;
 use uuid::Uuid;
+use crate::database::TracedQuery;
+use crate::services::license_service::{LicenseError, LicenseService};
 
 // ============================================================================
 // ERROR TYPES
@@ -65,6 +67,9 @@ pub enum AuthError {
     #[error("Permission denied")]
     PermissionDenied,
 
+    #[error("Seat limit reached: {0}")]
+    SeatLimitReached(String),
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -430,6 +435,7 @@ impl AuthService {
         let mut result = self
             .db
             .query(&query)
+            .traced("auth_service.find_user_by_email")
             .await
             .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
 
@@ -521,7 +527,7 @@ impl AuthService {
             )
         };
 
-        let _ = self.db.query(&query).await;
+        let _ = self.db.query(&query).traced("auth_service.increment_failed_login_attempts").await;
     }
 
     /// Reset login tracking after successful login
@@ -537,7 +543,7 @@ impl AuthService {
             user_id
         );
 
-        let _ = self.db.query(&query).await;
+        let _ = self.db.query(&query).traced("auth_service.reset_login_tracking").await;
     }
 
     // ========================================================================
@@ -579,6 +585,7 @@ impl AuthService {
 
         self.db
             .query(&query)
+            .traced("auth_service.store_refresh_token")
             .await
             .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
 
@@ -597,6 +604,7 @@ impl AuthService {
         let mut result = self
             .db
             .query(&query)
+            .traced("auth_service.find_refresh_token")
             .await
             .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
 
@@ -621,6 +629,7 @@ impl AuthService {
 
         self.db
             .query(&query)
+            .traced("auth_service.revoke_refresh_token")
             .await
             .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
 
@@ -691,6 +700,8 @@ impl AuthService {
             return Err(AuthError::EmailExists);
         }
 
+        self.ensure_seat_available().await?;
+
         // Hash password
         let password_hash = self.hash_password(&password)?;
 
@@ -723,6 +734,17 @@ impl AuthService {
             .ok_or(AuthError::InternalError("User creation returned no result".to_string()))
     }
 
+    /// Refuse new accounts once every licensed seat is in use
+    async fn ensure_seat_available(&self) -> Result<(), AuthError> {
+        LicenseService::new(Arc::clone(&self.db))
+            .ensure_seat_available()
+            .await
+            .map_err(|e| match e {
+                LicenseError::SeatLimitReached(_) => AuthError::SeatLimitReached(e.to_string()),
+                other => AuthError::DatabaseError(other.to_string()),
+            })
+    }
+
     /// Get user profile by ID
     pub async fn get_user_profile(&self, user_id: &str) -> Result<UserProfile, AuthError> {
         let user = self.find_user_by_id(user_id).await?;
@@ -774,7 +796,7 @@ impl AuthService {
             r#"SELECT * FROM users WHERE username = '{}' LIMIT 1"#,
             username.replace('\'', "''")
         );
-        let mut result = self.db.query(&username_check).await
+        let mut result = self.db.query(&username_check).traced("auth_service.register_user").await
             .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
         let existing: Vec<User> = result.take(0)
             .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
@@ -783,6 +805,8 @@ impl AuthService {
             return Err(AuthError::UsernameExists);
         }
 
+        self.ensure_seat_available().await?;
+
         // Hash password
         let password_hash = self.hash_password(&password)?;
 
@@ -0,0 +1,286 @@
+//! Ansible inventory and playbook skeletons for wave cutovers
+//!
+//! The inventory has a group per wave and per target cluster, with the VM's
+//! cutover settings as host variables. Each wave gets a playbook that cuts
+//! its group over; `site.yml` runs the waves in sequence.
+
+use serde_json::{json, Map, Value};
+
+use super::{slug, terraform::hypervisor_name};
+use crate::models::automation_export::{AutomationPlan, BundleFile, PlanVm, PlanWave};
+
+const DIR: &str = "ansible";
+/// Group of placed VMs that are in no wave yet
+const UNSCHEDULED: &str = "unscheduled";
+
+pub fn render(plan: &AutomationPlan) -> Vec<BundleFile> {
+    let mut files = vec![
+        file("inventory.yml", inventory(plan)),
+        file("group_vars/all.yml", all_vars(plan)),
+    ];
+
+    let mut waves: Vec<&PlanWave> = plan
+        .waves
+        .iter()
+        .filter(|w| plan.vms.iter().any(|vm| vm.wave.as_deref() == Some(w.key.as_str())))
+        .collect();
+    waves.sort_by_key(|w| w.sequence);
+
+    for wave in &waves {
+        let group = wave_group(wave);
+        files.push(file(&format!("group_vars/{}.yml", group), wave_vars(wave)));
+        files.push(file(&format!("playbooks/{}.yml", group), playbook(&group, &wave.name)));
+    }
+    files.push(file("site.yml", site(&waves)));
+    files.push(file("README.md", readme(plan, &waves)));
+    files
+}
+
+fn file(name: &str, content: String) -> BundleFile {
+    BundleFile {
+        path: format!("{}/{}", DIR, name),
+        content,
+    }
+}
+
+/// Ansible group names allow letters, digits and underscores
+pub fn group_name(value: &str) -> String {
+    slug(value).replace('-', "_")
+}
+
+pub fn wave_group(wave: &PlanWave) -> String {
+    format!("wave_{:02}", wave.sequence)
+}
+
+fn is_windows(vm: &PlanVm) -> bool {
+    vm.os.as_deref().map_or(false, |os| os.to_ascii_lowercase().contains("windows"))
+}
+
+fn host_vars(plan: &AutomationPlan, vm: &PlanVm) -> Value {
+    let cluster = plan.clusters.iter().find(|c| c.key == vm.cluster);
+    let mut vars = Map::new();
+    // Cutover runs against the VM where it answers today
+    if let Some(ip) = &vm.source_ip {
+        vars.insert("ansible_host".to_string(), json!(ip));
+    }
+    if is_windows(vm) {
+        vars.insert("ansible_connection".to_string(), json!("winrm"));
+    }
+    vars.insert("vm_name".to_string(), json!(vm.name));
+    vars.insert("target_cluster".to_string(), json!(cluster.map(|c| c.name.as_str())));
+    // The failover cluster picks the node, so the cluster is the target host
+    vars.insert("target_host".to_string(), json!(cluster.map(|c| c.name.as_str())));
+    vars.insert(
+        "target_hypervisor".to_string(),
+        json!(cluster.map(|c| hypervisor_name(c.hypervisor))),
+    );
+    vars.insert("target_network".to_string(), json!(vm.network));
+    vars.insert("vlan_id".to_string(), json!(vm.vlan_id));
+    vars.insert("source_ip".to_string(), json!(vm.source_ip));
+    vars.insert("new_ip".to_string(), json!(vm.new_ip));
+    vars.insert("prefix_length".to_string(), json!(vm.prefix_length));
+    vars.insert("gateway".to_string(), json!(vm.gateway));
+    vars.insert("dns_servers".to_string(), json!(vm.dns_servers));
+    vars.insert("cpus".to_string(), json!(vm.cpus));
+    vars.insert("memory_mb".to_string(), json!(vm.memory_mb));
+    Value::Object(vars)
+}
+
+fn inventory(plan: &AutomationPlan) -> String {
+    let mut hosts = Map::new();
+    let mut waves: Map<String, Value> = Map::new();
+    let mut clusters: Map<String, Value> = Map::new();
+
+    let add = |groups: &mut Map<String, Value>, group: String, host: &str| {
+        let entry = groups.entry(group).or_insert_with(|| json!({ "hosts": {} }));
+        entry["hosts"][host] = Value::Null;
+    };
+
+    for vm in &plan.vms {
+        hosts.insert(vm.name.clone(), host_vars(plan, vm));
+
+        let wave = vm
+            .wave
+            .as_deref()
+            .and_then(|key| plan.waves.iter().find(|w| w.key == key))
+            .map(wave_group)
+            .unwrap_or_else(|| UNSCHEDULED.to_string());
+        add(&mut waves, wave, &vm.name);
+
+        if let Some(cluster) = plan.clusters.iter().find(|c| c.key == vm.cluster) {
+            add(&mut clusters, format!("cluster_{}", group_name(&cluster.name)), &vm.name);
+        }
+    }
+
+    let inventory = json!({
+        "all": {
+            "hosts": hosts,
+            "children": {
+                "waves": { "children": waves },
+                "target_clusters": { "children": clusters },
+            }
+        }
+    });
+    let yaml = serde_yaml::to_string(&inventory).unwrap_or_default();
+    format!("# Generated by Archer from the placement plan of {}\n{}", plan.project_name, yaml)
+}
+
+fn all_vars(plan: &AutomationPlan) -> String {
+    format!(
+        "# Settings shared by every wave\nproject_name: {}\n# Seconds to wait for a VM to answer on its new address\ncutover_timeout: 600\n",
+        yaml_string(&plan.project_name)
+    )
+}
+
+fn wave_vars(wave: &PlanWave) -> String {
+    format!(
+        "wave_name: {}\nwave_sequence: {}\nchange_window: {}\n",
+        yaml_string(&wave.name),
+        wave.sequence,
+        wave.change_window.as_deref().map(yaml_string).unwrap_or_else(|| "null".to_string())
+    )
+}
+
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn playbook(group: &str, wave_name: &str) -> String {
+    format!(
+        r#"# Cutover of {wave_name}
+# Skeleton generated by Archer; fill in the TODO tasks for your migration tool.
+---
+- name: Pre-cutover checks ({wave_name})
+  hosts: {group}
+  gather_facts: true
+  tasks:
+    - name: Record the source configuration
+      ansible.builtin.debug:
+        msg: "{{{{ vm_name }}}} moves to {{{{ target_host }}}} on {{{{ target_network | default('unmapped', true) }}}} (VLAN {{{{ vlan_id | default('none', true) }}}}) as {{{{ new_ip | default(source_ip, true) }}}}"
+
+    - name: Fail when the VM has no address on the target network
+      ansible.builtin.assert:
+        that: new_ip is not none
+        fail_msg: "{{{{ vm_name }}}} has no new IP; complete its network mapping first"
+
+    # TODO: stop application services, take a backup or snapshot
+
+- name: Cut over ({wave_name})
+  hosts: {group}
+  gather_facts: false
+  serial: 5
+  tasks:
+    # TODO: replicate and start the VM on target_host with your migration tool
+
+    - name: Set the new address (Linux)
+      community.general.nmcli:
+        conn_name: "{{{{ ansible_default_ipv4.interface }}}}"
+        type: ethernet
+        ip4: "{{{{ new_ip }}}}/{{{{ prefix_length }}}}"
+        gw4: "{{{{ gateway }}}}"
+        dns4: "{{{{ dns_servers }}}}"
+        state: present
+      when: ansible_connection | default('ssh') != 'winrm' and new_ip != source_ip
+
+    - name: Set the new address (Windows)
+      ansible.windows.win_shell: |
+        $adapter = Get-NetAdapter | Where-Object Status -eq 'Up' | Select-Object -First 1
+        Remove-NetIPAddress -InterfaceIndex $adapter.ifIndex -AddressFamily IPv4 -Confirm:$false
+        New-NetIPAddress -InterfaceIndex $adapter.ifIndex -IPAddress '{{{{ new_ip }}}}' -PrefixLength {{{{ prefix_length }}}} -DefaultGateway '{{{{ gateway }}}}'
+        Set-DnsClientServerAddress -InterfaceIndex $adapter.ifIndex -ServerAddresses {{{{ dns_servers | map('quote') | join(',') }}}}
+      async: 30
+      poll: 0
+      when: ansible_connection | default('ssh') == 'winrm' and new_ip != source_ip
+
+- name: Post-cutover validation ({wave_name})
+  hosts: {group}
+  gather_facts: false
+  tasks:
+    - name: Wait for the VM on its new address
+      ansible.builtin.wait_for:
+        host: "{{{{ new_ip }}}}"
+        port: "{{{{ 5985 if ansible_connection | default('ssh') == 'winrm' else 22 }}}}"
+        timeout: "{{{{ cutover_timeout }}}}"
+      delegate_to: localhost
+
+    # TODO: application smoke tests, re-enable monitoring and backups
+"#
+    )
+}
+
+fn site(waves: &[&PlanWave]) -> String {
+    let mut out = String::from("# Runs every wave in sequence; run a single wave with its playbook\n---\n");
+    for wave in waves {
+        out.push_str(&format!("- import_playbook: playbooks/{}.yml\n", wave_group(wave)));
+    }
+    if waves.is_empty() {
+        out.push_str("[]\n");
+    }
+    out
+}
+
+fn readme(plan: &AutomationPlan, waves: &[&PlanWave]) -> String {
+    let mut out = format!(
+        "# {} - Ansible cutover skeleton\n\nGenerated by Archer from the placement plan and network mappings.\n\n\
+         | Group | Wave | VMs |\n|-------|------|-----|\n",
+        plan.project_name
+    );
+    for wave in waves {
+        let count = plan.vms.iter().filter(|vm| vm.wave.as_deref() == Some(wave.key.as_str())).count();
+        out.push_str(&format!("| `{}` | {} | {} |\n", wave_group(wave), wave.name, count));
+    }
+    let unscheduled = plan.vms.iter().filter(|vm| vm.wave.is_none()).count();
+    if unscheduled > 0 {
+        out.push_str(&format!("| `{}` | not in a wave | {} |\n", UNSCHEDULED, unscheduled));
+    }
+    out.push_str(
+        "\nEach host has `target_host`, `target_network`, `vlan_id`, `new_ip`, `prefix_length`, `gateway` and `dns_servers`.\n\
+         Hosts are also grouped per target cluster under `target_clusters`.\n\n\
+         ```\nansible-playbook -i inventory.yml playbooks/wave_01.yml\n```\n",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::automation_export::tests::sample_plan;
+
+    #[test]
+    fn test_inventory_groups_by_wave_and_cluster() {
+        let files = render(&sample_plan());
+        let inventory = &files.iter().find(|f| f.path == "ansible/inventory.yml").unwrap().content;
+        let inventory: serde_yaml::Value = serde_yaml::from_str(inventory).unwrap();
+        let all = &inventory["all"];
+
+        let wave = &all["children"]["waves"]["children"]["wave_01"]["hosts"];
+        assert!(wave.get("WEB01").is_some() && wave.get("WEB02").is_some());
+        assert!(all["children"]["waves"]["children"]["unscheduled"]["hosts"].get("DB01").is_some());
+        assert!(all["children"]["target_clusters"]["children"]["cluster_cluster_hv"]["hosts"]
+            .get("WEB01")
+            .is_some());
+
+        let web01 = &all["hosts"]["WEB01"];
+        assert_eq!(web01["new_ip"].as_str(), Some("172.16.9.34"));
+        assert_eq!(web01["vlan_id"].as_i64(), Some(210));
+        assert_eq!(web01["target_host"].as_str(), Some("Cluster hv"));
+        assert_eq!(web01["ansible_host"].as_str(), Some("10.1.2.34"));
+        assert_eq!(web01["ansible_connection"].as_str(), Some("winrm"));
+    }
+
+    #[test]
+    fn test_playbook_per_wave() {
+        let files = render(&sample_plan());
+        let playbook = &files.iter().find(|f| f.path == "ansible/playbooks/wave_01.yml").unwrap().content;
+        let plays: serde_yaml::Value = serde_yaml::from_str(playbook).unwrap();
+        assert_eq!(plays.as_sequence().unwrap().len(), 3);
+        assert_eq!(plays[0]["hosts"].as_str(), Some("wave_01"));
+
+        let site = &files.iter().find(|f| f.path == "ansible/site.yml").unwrap().content;
+        assert!(site.contains("import_playbook: playbooks/wave_01.yml"));
+
+        let vars = &files.iter().find(|f| f.path == "ansible/group_vars/wave_01.yml").unwrap().content;
+        let vars: serde_yaml::Value = serde_yaml::from_str(vars).unwrap();
+        assert_eq!(vars["change_window"].as_str(), Some("Sat 22:00-02:00"));
+    }
+}
@@ -0,0 +1,311 @@
+//! Bicep scaffold for Azure Local target clusters
+//!
+//! `main.bicep` creates a logical network per destination network and
+//! cluster, and an Arc machine, NIC and VM instance per VM shell. The plan goes
+//! into `main.parameters.json`, an ARM deployment parameters file, so the same
+//! values work with `az deployment group create` for Bicep or compiled ARM.
+
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+use super::slug;
+use crate::models::automation_export::{AutomationPlan, BundleFile, PlanCluster};
+
+const DIR: &str = "bicep";
+/// Windows computer names are limited to 15 characters
+const COMPUTER_NAME_LEN: usize = 15;
+
+pub fn render(plan: &AutomationPlan, clusters: &[&PlanCluster]) -> Vec<BundleFile> {
+    if clusters.is_empty() {
+        return Vec::new();
+    }
+    vec![
+        file("main.bicep", MAIN_BICEP.to_string()),
+        file("main.parameters.json", parameters(plan, clusters)),
+        file("README.md", readme(plan, clusters)),
+    ]
+}
+
+fn file(name: &str, content: String) -> BundleFile {
+    BundleFile {
+        path: format!("{}/{}", DIR, name),
+        content,
+    }
+}
+
+/// Logical network name for a destination network on one cluster
+pub fn logical_network_name(network: &str, cluster: &PlanCluster) -> String {
+    format!("{}-{}", slug(network), slug(&cluster.name))
+}
+
+fn parameters(plan: &AutomationPlan, clusters: &[&PlanCluster]) -> String {
+    let mut custom_locations = Map::new();
+    for cluster in clusters {
+        custom_locations.insert(
+            cluster.key.clone(),
+            Value::String(format!("<custom location resource id of {}>", cluster.name)),
+        );
+    }
+
+    let mut logical_networks = Vec::new();
+    let mut created = BTreeSet::new();
+    let mut vms = Vec::new();
+    for vm in &plan.vms {
+        let Some(cluster) = clusters.iter().find(|c| c.key == vm.cluster) else { continue };
+        let network = vm
+            .network
+            .as_deref()
+            .and_then(|name| plan.networks.iter().find(|n| n.name == name));
+
+        let logical_network = match network {
+            Some(network) => {
+                let name = logical_network_name(&network.name, cluster);
+                if created.insert(name.clone()) {
+                    logical_networks.push(json!({
+                        "name": name,
+                        "cluster": cluster.key,
+                        "vlanId": network.vlan_id,
+                        "addressPrefix": network.subnet,
+                        "gateway": network.gateway.clone().unwrap_or_default(),
+                        "dnsServers": network.dns_servers,
+                    }));
+                }
+                name
+            }
+            None => String::new(),
+        };
+
+        let computer_name: String = slug(&vm.name).chars().take(COMPUTER_NAME_LEN).collect();
+        vms.push(json!({
+            "name": slug(&vm.name),
+            "computerName": computer_name.trim_end_matches('-'),
+            "cluster": cluster.key,
+            "wave": vm.wave.clone().unwrap_or_default(),
+            "cpus": vm.cpus,
+            "memoryMB": vm.memory_mb,
+            "logicalNetwork": logical_network,
+            "ipAddress": vm.new_ip.clone().unwrap_or_default(),
+        }));
+    }
+
+    let parameters = json!({
+        "$schema": "https://schema.management.azure.com/schemas/2019-04-01/deploymentParameters.json#",
+        "contentVersion": "1.0.0.0",
+        "parameters": {
+            "customLocations": { "value": Value::Object(custom_locations) },
+            "imageId": { "value": "<gallery image resource id>" },
+            "logicalNetworks": { "value": logical_networks },
+            "vms": { "value": vms },
+        }
+    });
+    let mut out = serde_json::to_string_pretty(&parameters).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+fn readme(plan: &AutomationPlan, clusters: &[&PlanCluster]) -> String {
+    let mut out = format!(
+        "# {} - Azure Local Bicep scaffold\n\nGenerated by Archer from the placement plan. It creates the logical networks \
+         and VM shells on the target Azure Local clusters; disks arrive with the migration tool.\n\n\
+         | Cluster | VMs |\n|---------|-----|\n",
+        plan.project_name
+    );
+    for cluster in clusters {
+        let count = plan.vms.iter().filter(|vm| vm.cluster == cluster.key).count();
+        out.push_str(&format!("| {} | {} |\n", cluster.name, count));
+    }
+    out.push_str(
+        "\n1. In `main.parameters.json`, replace the `<...>` placeholders with the custom location of each cluster and a gallery image.\n\
+         2. `az deployment group create -g <resource group> -f main.bicep -p main.parameters.json -p adminPassword=<password>`\n",
+    );
+    out
+}
+
+const MAIN_BICEP: &str = r#"// Azure Local target infrastructure scaffold generated by Archer from the
+// placement plan. The plan itself is in main.parameters.json.
+
+@description('Azure region the Azure Local instances are registered in')
+param location string = resourceGroup().location
+
+@description('Custom location resource id of each target cluster, by cluster key')
+param customLocations object
+
+@description('Virtual switch the logical networks attach to')
+param vmSwitchName string = 'ConvergedSwitch(compute_management)'
+
+@description('Gallery image the VM shells are created from')
+param imageId string
+
+param adminUsername string = 'archeradmin'
+
+@secure()
+param adminPassword string
+
+@description('Destination networks per cluster, from the network mappings')
+param logicalNetworks array
+
+@description('VM shells, from the placement plan')
+param vms array
+
+resource networks 'Microsoft.AzureStackHCI/logicalNetworks@2024-01-01' = [for net in logicalNetworks: {
+  name: net.name
+  location: location
+  extendedLocation: {
+    type: 'CustomLocation'
+    name: customLocations[net.cluster]
+  }
+  properties: {
+    vmSwitchName: vmSwitchName
+    dhcpOptions: {
+      dnsServers: net.dnsServers
+    }
+    subnets: [
+      {
+        name: net.name
+        properties: {
+          addressPrefix: net.addressPrefix
+          ipAllocationMethod: 'Static'
+          vlan: net.vlanId
+          routeTable: empty(net.gateway) ? null : {
+            properties: {
+              routes: [
+                {
+                  name: 'default'
+                  properties: {
+                    addressPrefix: '0.0.0.0/0'
+                    nextHopIpAddress: net.gateway
+                  }
+                }
+              ]
+            }
+          }
+        }
+      }
+    ]
+  }
+}]
+
+resource machines 'Microsoft.HybridCompute/machines@2023-10-03-preview' = [for vm in vms: {
+  name: vm.name
+  location: location
+  kind: 'HCI'
+  identity: {
+    type: 'SystemAssigned'
+  }
+  tags: {
+    migrationWave: vm.wave
+  }
+}]
+
+resource nics 'Microsoft.AzureStackHCI/networkInterfaces@2024-01-01' = [for vm in vms: if (!empty(vm.logicalNetwork)) {
+  name: '${vm.name}-nic'
+  location: location
+  extendedLocation: {
+    type: 'CustomLocation'
+    name: customLocations[vm.cluster]
+  }
+  properties: {
+    ipConfigurations: [
+      {
+        name: 'ipconfig1'
+        properties: {
+          subnet: {
+            id: resourceId('Microsoft.AzureStackHCI/logicalNetworks', vm.logicalNetwork)
+          }
+          privateIPAddress: empty(vm.ipAddress) ? null : vm.ipAddress
+        }
+      }
+    ]
+  }
+  dependsOn: [
+    networks
+  ]
+}]
+
+resource instances 'Microsoft.AzureStackHCI/virtualMachineInstances@2024-01-01' = [for (vm, i) in vms: {
+  name: 'default'
+  scope: machines[i]
+  extendedLocation: {
+    type: 'CustomLocation'
+    name: customLocations[vm.cluster]
+  }
+  properties: {
+    hardwareProfile: {
+      vmSize: 'Custom'
+      processors: vm.cpus
+      memoryMB: vm.memoryMB
+    }
+    osProfile: {
+      computerName: vm.computerName
+      adminUsername: adminUsername
+      adminPassword: adminPassword
+    }
+    storageProfile: {
+      imageReference: {
+        id: imageId
+      }
+    }
+    networkProfile: {
+      networkInterfaces: empty(vm.logicalNetwork) ? [] : [
+        {
+          id: nics[i].id
+        }
+      ]
+    }
+  }
+}]
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::automation_export::tests::sample_plan;
+
+    #[test]
+    fn test_parameters_cover_azure_local_vms() {
+        let plan = sample_plan();
+        let clusters: Vec<&PlanCluster> = plan.clusters.iter().filter(|c| c.key == "azl").collect();
+        let files = render(&plan, &clusters);
+        assert_eq!(files.len(), 3);
+
+        let parameters: Value = serde_json::from_str(&files[1].content).unwrap();
+        let vms = parameters["parameters"]["vms"]["value"].as_array().unwrap();
+        assert_eq!(vms.len(), 1);
+        assert_eq!(vms[0]["name"], "db01");
+        assert_eq!(vms[0]["cluster"], "azl");
+        // db01 has no address, so no network
+        assert_eq!(vms[0]["logicalNetwork"], "");
+        assert!(parameters["parameters"]["customLocations"]["value"]["azl"].is_string());
+    }
+
+    #[test]
+    fn test_logical_network_per_cluster() {
+        let mut plan = sample_plan();
+        for vm in &mut plan.vms {
+            vm.cluster = "azl".to_string();
+        }
+        let clusters: Vec<&PlanCluster> = plan.clusters.iter().filter(|c| c.key == "azl").collect();
+        let files = render(&plan, &clusters);
+        let parameters: Value = serde_json::from_str(&files[1].content).unwrap();
+
+        let networks = parameters["parameters"]["logicalNetworks"]["value"].as_array().unwrap();
+        assert_eq!(networks.len(), 1);
+        assert_eq!(networks[0]["name"], "app-vlan-210-cluster-azl");
+        assert_eq!(networks[0]["vlanId"], 210);
+        assert_eq!(networks[0]["addressPrefix"], "172.16.9.0/24");
+
+        let vms = parameters["parameters"]["vms"]["value"].as_array().unwrap();
+        let web01 = vms.iter().find(|v| v["name"] == "web01").unwrap();
+        assert_eq!(web01["logicalNetwork"], "app-vlan-210-cluster-azl");
+        assert_eq!(web01["ipAddress"], "172.16.9.34");
+    }
+
+    #[test]
+    fn test_computer_name_is_truncated() {
+        let mut plan = sample_plan();
+        plan.vms[2].name = "very-long-database-server-name".to_string();
+        let clusters: Vec<&PlanCluster> = plan.clusters.iter().filter(|c| c.key == "azl").collect();
+        let parameters: Value = serde_json::from_str(&render(&plan, &clusters)[1].content).unwrap();
+        assert_eq!(parameters["parameters"]["vms"]["value"][0]["computerName"], "very-long-datab");
+    }
+}
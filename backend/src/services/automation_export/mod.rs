@@ -0,0 +1,677 @@
+//! Automation exports
+//!
+//! Turns a migration wizard project's placement plan into files the
+//! implementation team starts from instead of the HLD prose:
+//! - IaC scaffolds: Bicep for Azure Local clusters, Terraform for other targets
+//! - Ansible inventories grouped by wave and target cluster, with a playbook
+//!   skeleton per wave
+//! - PowerShell cutover scripts for Hyper-V clusters, with a data file per wave
+//!
+//! Every export starts from the same [`AutomationPlan`]: placed VMs with their
+//! target cluster and wave, matched to a network mapping by their primary IP
+//! and given an address at the same host position in the destination subnet.
+
+pub mod ansible;
+pub mod bicep;
+pub mod powershell;
+pub mod terraform;
+
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::models::automation_export::*;
+use crate::models::migration_wizard_models::{
+    MigrationWizardCluster, MigrationWizardNetworkMapping, MigrationWizardPlacement, MigrationWizardProject,
+    MigrationWizardVM,
+};
+use crate::models::project_models::{DestinationCluster, HypervisorType};
+use crate::models::timeline::MigrationWave;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::timeline_service::TimelineService;
+use crate::utils::cidr::Ipv4Cidr;
+
+#[derive(Debug, Error)]
+pub enum AutomationExportError {
+    #[error("Project not found")]
+    ProjectNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Failed to load the placement plan: {0}")]
+    Plan(String),
+
+    #[error("Failed to build the bundle: {0}")]
+    Bundle(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for AutomationExportError {
+    fn from(e: surrealdb::Error) -> Self {
+        AutomationExportError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<anyhow::Error> for AutomationExportError {
+    fn from(e: anyhow::Error) -> Self {
+        AutomationExportError::Plan(e.to_string())
+    }
+}
+
+/// Records a plan is built from
+#[derive(Debug, Clone)]
+pub struct PlanSources {
+    pub project: MigrationWizardProject,
+    pub vms: Vec<MigrationWizardVM>,
+    pub clusters: Vec<MigrationWizardCluster>,
+    pub placements: Vec<MigrationWizardPlacement>,
+    pub mappings: Vec<MigrationWizardNetworkMapping>,
+    pub waves: Vec<MigrationWave>,
+    /// Hypervisor of each linked destination cluster, by destination cluster key
+    pub destination_hypervisors: HashMap<String, HypervisorType>,
+}
+
+pub struct AutomationExportService {
+    db: Arc<Database>,
+}
+
+impl AutomationExportService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Resolve a project's placement plan
+    pub async fn load_plan(
+        &self,
+        project_id: &str,
+        default_hypervisor: HypervisorType,
+    ) -> Result<AutomationPlan, AutomationExportError> {
+        let wizard = MigrationWizardService::new(self.db.as_ref().clone());
+        let project = wizard
+            .get_project(project_id)
+            .await
+            .map_err(|_| AutomationExportError::ProjectNotFound)?;
+        let vms = wizard.get_project_vms(project_id, None).await?;
+        let clusters = wizard.get_project_clusters(project_id).await?;
+        let placements = wizard.get_project_placements(project_id).await?;
+        let mappings = wizard.get_project_network_mappings(project_id).await?;
+        let waves = TimelineService::new(self.db.as_ref().clone()).list_waves(project_id).await?;
+
+        let mut destination_hypervisors = HashMap::new();
+        for destination in clusters.iter().filter_map(|c| c.destination_cluster_id.as_deref()) {
+            let cluster: Option<DestinationCluster> = self.db.select(("destination_cluster", destination)).await?;
+            if let Some(cluster) = cluster {
+                destination_hypervisors.insert(destination.to_string(), cluster.hypervisor);
+            }
+        }
+
+        Ok(build_plan(
+            PlanSources {
+                project,
+                vms,
+                clusters,
+                placements,
+                mappings,
+                waves,
+                destination_hypervisors,
+            },
+            default_hypervisor,
+        ))
+    }
+
+    /// Terraform and/or Bicep scaffold for a project's target infrastructure
+    pub async fn iac_scaffold(
+        &self,
+        project_id: &str,
+        query: &AutomationExportQuery,
+    ) -> Result<AutomationBundle, AutomationExportError> {
+        let plan = self
+            .load_plan(project_id, query.hypervisor.unwrap_or(HypervisorType::HyperV))
+            .await?;
+        iac_bundle(&plan, query.target)
+    }
+
+    /// Ansible inventory and per-wave playbook skeletons for a project
+    pub async fn ansible_export(
+        &self,
+        project_id: &str,
+        query: &AutomationExportQuery,
+    ) -> Result<AutomationBundle, AutomationExportError> {
+        let plan = self
+            .load_plan(project_id, query.hypervisor.unwrap_or(HypervisorType::HyperV))
+            .await?;
+        ansible_bundle(&plan)
+    }
+
+    /// Hyper-V cutover scripts per wave for a project
+    pub async fn powershell_export(
+        &self,
+        project_id: &str,
+        query: &AutomationExportQuery,
+    ) -> Result<AutomationBundle, AutomationExportError> {
+        let plan = self
+            .load_plan(project_id, query.hypervisor.unwrap_or(HypervisorType::HyperV))
+            .await?;
+        powershell_bundle(&plan)
+    }
+}
+
+// ============================================================================
+// PLAN
+// ============================================================================
+
+pub fn build_plan(sources: PlanSources, default_hypervisor: HypervisorType) -> AutomationPlan {
+    let PlanSources { project, vms, clusters, placements, mappings, waves, destination_hypervisors } = sources;
+    let mut warnings = Vec::new();
+
+    let clusters: Vec<PlanCluster> = clusters
+        .into_iter()
+        .filter_map(|cluster| {
+            let key = cluster.id.as_ref()?.id.to_raw();
+            let hypervisor = cluster
+                .destination_cluster_id
+                .as_deref()
+                .and_then(|d| destination_hypervisors.get(d).copied())
+                .unwrap_or(default_hypervisor);
+            Some(PlanCluster {
+                key,
+                name: cluster.name,
+                hypervisor,
+                destination_cluster_id: cluster.destination_cluster_id,
+                total_cores: cluster.total_cores,
+                memory_gb: cluster.memory_gb,
+                storage_tb: cluster.storage_tb,
+            })
+        })
+        .collect();
+
+    let networks: Vec<PlanNetwork> = mappings
+        .iter()
+        .map(|m| PlanNetwork {
+            name: m.destination_vlan_name.clone(),
+            vlan_id: m.destination_vlan_id,
+            subnet: m.destination_subnet.clone(),
+            gateway: m.destination_gateway.clone(),
+            dns_servers: m.destination_dns.clone().unwrap_or_default(),
+            source_name: m.source_vlan_name.clone(),
+            source_subnet: m.source_subnet.clone(),
+        })
+        .collect();
+
+    let mut wave_of: HashMap<String, String> = HashMap::new();
+    let waves: Vec<PlanWave> = waves
+        .into_iter()
+        .filter_map(|wave| {
+            let key = wave.id.as_ref()?.id.to_raw();
+            for vm in &wave.vm_ids {
+                wave_of.entry(vm.id.to_raw()).or_insert_with(|| key.clone());
+            }
+            Some(PlanWave {
+                key,
+                name: wave.name,
+                sequence: wave.sequence,
+                change_window: wave.change_window,
+            })
+        })
+        .collect();
+
+    let placement_of: HashMap<String, &MigrationWizardPlacement> =
+        placements.iter().map(|p| (p.vm_id.id.to_raw(), p)).collect();
+
+    let mut plan_vms = Vec::new();
+    let mut unplaced = 0;
+    for vm in &vms {
+        let Some(key) = vm.id.as_ref().map(|id| id.id.to_raw()) else { continue };
+        let Some(placement) = placement_of.get(&key) else {
+            unplaced += 1;
+            continue;
+        };
+        let cluster = placement.cluster_id.id.to_raw();
+        if !clusters.iter().any(|c| c.key == cluster) {
+            warnings.push(format!("{} is placed on a cluster that no longer exists", vm.name));
+            continue;
+        }
+
+        let mut plan_vm = PlanVm {
+            key: key.clone(),
+            name: vm.name.clone(),
+            cluster,
+            wave: wave_of.get(&key).cloned(),
+            cpus: placement.allocated_cpu,
+            memory_mb: placement.allocated_memory_mb,
+            storage_gb: placement.allocated_storage_gb,
+            os: vm.os.clone(),
+            source_ip: vm.primary_ip_address.clone(),
+            network: None,
+            vlan_id: None,
+            new_ip: None,
+            prefix_length: None,
+            gateway: None,
+            dns_servers: Vec::new(),
+        };
+        match vm.primary_ip_address.as_deref().and_then(|ip| ip.parse::<Ipv4Addr>().ok()) {
+            Some(ip) => match match_mapping(&mappings, ip) {
+                Some(mapping) => {
+                    apply_mapping(&mut plan_vm, mapping, ip);
+                    if plan_vm.new_ip.is_none() && mapping.destination_subnet.is_some() {
+                        warnings.push(format!(
+                            "{}: {} has no place in {}; assign an address by hand",
+                            vm.name,
+                            ip,
+                            mapping.destination_subnet.as_deref().unwrap_or_default()
+                        ));
+                    }
+                }
+                None => warnings.push(format!("{}: no network mapping covers {}", vm.name, ip)),
+            },
+            None => warnings.push(format!("{} has no IPv4 address; its network is left unset", vm.name)),
+        }
+        plan_vms.push(plan_vm);
+    }
+    if unplaced > 0 {
+        warnings.push(format!("{} VMs are not placed on a target cluster and are left out", unplaced));
+    }
+
+    AutomationPlan {
+        project_id: project.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+        project_name: project.name,
+        clusters,
+        networks,
+        waves,
+        vms: plan_vms,
+        warnings,
+    }
+}
+
+/// The mapping whose source subnet holds `ip`; the longest prefix wins
+fn match_mapping(mappings: &[MigrationWizardNetworkMapping], ip: Ipv4Addr) -> Option<&MigrationWizardNetworkMapping> {
+    mappings
+        .iter()
+        .filter_map(|m| {
+            let source: Ipv4Cidr = m.source_subnet.as_deref()?.parse().ok()?;
+            source.contains(ip).then_some((source.prefix(), m))
+        })
+        .max_by_key(|(prefix, _)| *prefix)
+        .map(|(_, m)| m)
+}
+
+fn apply_mapping(vm: &mut PlanVm, mapping: &MigrationWizardNetworkMapping, ip: Ipv4Addr) {
+    vm.network = Some(mapping.destination_vlan_name.clone());
+    vm.vlan_id = mapping.destination_vlan_id;
+    vm.gateway = mapping.destination_gateway.clone();
+    vm.dns_servers = mapping.destination_dns.clone().unwrap_or_default();
+
+    let source = mapping.source_subnet.as_deref().and_then(|s| s.parse::<Ipv4Cidr>().ok());
+    match mapping.destination_subnet.as_deref().and_then(|s| s.parse::<Ipv4Cidr>().ok()) {
+        Some(destination) => {
+            vm.prefix_length = Some(destination.prefix());
+            vm.new_ip = source
+                .and_then(|source| destination.translate(&source, ip))
+                .map(|ip| ip.to_string());
+        }
+        // No destination subnet: the VM keeps its address
+        None => {
+            vm.prefix_length = source.map(|s| s.prefix());
+            vm.new_ip = Some(ip.to_string());
+        }
+    }
+}
+
+// ============================================================================
+// BUNDLES
+// ============================================================================
+
+pub fn iac_bundle(plan: &AutomationPlan, target: IacTarget) -> Result<AutomationBundle, AutomationExportError> {
+    require_placed_vms(plan)?;
+
+    let mut warnings = plan.warnings.clone();
+    let azure_local = |c: &PlanCluster| c.hypervisor == HypervisorType::AzureLocal;
+    let (bicep_clusters, terraform_clusters): (Vec<&PlanCluster>, Vec<&PlanCluster>) =
+        plan.clusters.iter().partition(|c| azure_local(c));
+
+    let mut files = Vec::new();
+    match target {
+        IacTarget::Auto => {
+            files.extend(terraform::render(plan, &terraform_clusters));
+            files.extend(bicep::render(plan, &bicep_clusters));
+        }
+        IacTarget::Terraform => {
+            for cluster in &bicep_clusters {
+                warnings.push(format!("{} is an Azure Local cluster; export it as Bicep", cluster.name));
+            }
+            files.extend(terraform::render(plan, &terraform_clusters));
+        }
+        IacTarget::Bicep => {
+            for cluster in &terraform_clusters {
+                warnings.push(format!("{} is not an Azure Local cluster; export it as Terraform", cluster.name));
+            }
+            files.extend(bicep::render(plan, &bicep_clusters));
+        }
+    }
+    if files.is_empty() {
+        return Err(AutomationExportError::Validation(
+            "No target cluster matches the requested IaC target".to_string(),
+        ));
+    }
+
+    Ok(AutomationBundle {
+        name: format!("{}-iac", slug(&plan.project_name)),
+        files,
+        warnings,
+    })
+}
+
+pub fn ansible_bundle(plan: &AutomationPlan) -> Result<AutomationBundle, AutomationExportError> {
+    require_placed_vms(plan)?;
+    Ok(AutomationBundle {
+        name: format!("{}-ansible", slug(&plan.project_name)),
+        files: ansible::render(plan),
+        warnings: plan.warnings.clone(),
+    })
+}
+
+pub fn powershell_bundle(plan: &AutomationPlan) -> Result<AutomationBundle, AutomationExportError> {
+    require_placed_vms(plan)?;
+
+    let files = powershell::render(plan);
+    if files.is_empty() {
+        return Err(AutomationExportError::Validation(
+            "No VMs are placed on Hyper-V clusters".to_string(),
+        ));
+    }
+    let mut warnings = plan.warnings.clone();
+    for cluster in plan.clusters.iter().filter(|c| c.hypervisor != HypervisorType::HyperV) {
+        warnings.push(format!("{} is not a Hyper-V cluster; its VMs are left out of the scripts", cluster.name));
+    }
+
+    Ok(AutomationBundle {
+        name: format!("{}-hyperv", slug(&plan.project_name)),
+        files,
+        warnings,
+    })
+}
+
+fn require_placed_vms(plan: &AutomationPlan) -> Result<(), AutomationExportError> {
+    if plan.vms.is_empty() {
+        return Err(AutomationExportError::Validation(
+            "The project has no placed VMs; run placement first".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Zip archive of a bundle, with its files under a folder named after it
+pub fn to_zip(bundle: &AutomationBundle) -> Result<Vec<u8>, AutomationExportError> {
+    let bundle_error = |e: &dyn std::fmt::Display| AutomationExportError::Bundle(e.to_string());
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for file in &bundle.files {
+        writer
+            .start_file(format!("{}/{}", bundle.name, file.path), options)
+            .map_err(|e| bundle_error(&e))?;
+        writer.write_all(file.content.as_bytes()).map_err(|e| bundle_error(&e))?;
+    }
+    let archive = writer.finish().map_err(|e| bundle_error(&e))?;
+    Ok(archive.into_inner())
+}
+
+/// Lowercase name safe for file names and resource names
+pub fn slug(value: &str) -> String {
+    let mut slug = String::new();
+    for c in value.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "project".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::Utc;
+    use surrealdb::sql::Thing;
+
+    use crate::models::migration_wizard_models::ProjectStatus;
+    use crate::models::timeline::WaveStatus;
+
+    fn vm(key: &str, ip: Option<&str>) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_uppercase(),
+            powerstate: Some("poweredOn".to_string()),
+            template: Some(false),
+            cpus: 4,
+            memory_mb: 8192,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: ip.map(str::to_string),
+            dns_name: None,
+            cluster: Some("Prod".to_string()),
+            host: None,
+            datacenter: None,
+            os: Some("Microsoft Windows Server 2019 (64-bit)".to_string()),
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn cluster(key: &str, destination: Option<&str>) -> MigrationWizardCluster {
+        MigrationWizardCluster {
+            id: Some(Thing::from(("migration_wizard_cluster", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: format!("Cluster {}", key),
+            description: None,
+            cpu_ghz: 2.4,
+            total_cores: 128,
+            memory_gb: 1024,
+            storage_tb: 40.0,
+            network_bandwidth_gbps: 25.0,
+            cpu_oversubscription_ratio: 4.0,
+            memory_oversubscription_ratio: 1.0,
+            strategy: "rehost".to_string(),
+            cpu_model: None,
+            destination_cluster_id: destination.map(str::to_string),
+            tags: Default::default(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn placement(vm: &str, cluster: &str) -> MigrationWizardPlacement {
+        MigrationWizardPlacement {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            vm_id: Thing::from(("migration_wizard_vm", vm)),
+            cluster_id: Thing::from(("migration_wizard_cluster", cluster)),
+            strategy: "auto".to_string(),
+            confidence_score: None,
+            warnings: None,
+            allocated_cpu: 4,
+            allocated_memory_mb: 8192,
+            allocated_storage_gb: 120.0,
+            tags: Default::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn mapping(source: &str, name: &str, destination: Option<&str>) -> MigrationWizardNetworkMapping {
+        MigrationWizardNetworkMapping {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            source_vlan_name: format!("src-{}", source),
+            source_vlan_id: Some(10),
+            source_subnet: Some(source.to_string()),
+            destination_vlan_name: name.to_string(),
+            destination_vlan_id: Some(210),
+            destination_subnet: destination.map(str::to_string),
+            destination_gateway: Some("172.16.9.1".to_string()),
+            destination_dns: Some(vec!["172.16.0.10".to_string()]),
+            is_valid: true,
+            validation_errors: None,
+            tags: Default::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Two Hyper-V VMs in one wave, one Azure Local VM, one unplaced VM
+    pub(crate) fn sample_plan() -> AutomationPlan {
+        let now = Utc::now();
+        let sources = PlanSources {
+            project: MigrationWizardProject {
+                id: Some(Thing::from(("migration_wizard_project", "p1"))),
+                name: "Contoso DC Exit".to_string(),
+                description: None,
+                status: ProjectStatus::InProgress,
+                created_at: now,
+                updated_at: now,
+                rvtools_filename: None,
+                rvtools_upload_date: None,
+                rvtools_file_path: None,
+                total_vms: 4,
+                total_clusters: 2,
+                wizard_step: 4,
+                tenant_id: None,
+                display_currency: None,
+            },
+            vms: vec![
+                vm("web01", Some("10.1.2.34")),
+                vm("web02", Some("10.1.2.35")),
+                vm("db01", None),
+                vm("spare", Some("10.1.2.99")),
+            ],
+            clusters: vec![cluster("hv", None), cluster("azl", Some("dc1"))],
+            placements: vec![placement("web01", "hv"), placement("web02", "hv"), placement("db01", "azl")],
+            mappings: vec![
+                mapping("10.1.0.0/16", "Legacy-VLAN-10", None),
+                mapping("10.1.2.0/24", "App-VLAN-210", Some("172.16.9.0/24")),
+            ],
+            waves: vec![MigrationWave {
+                id: Some(Thing::from(("migration_wave", "w1"))),
+                project_id: Thing::from(("migration_wizard_project", "p1")),
+                name: "Wave 1 - Web".to_string(),
+                sequence: 1,
+                vm_ids: vec![
+                    Thing::from(("migration_wizard_vm", "web01")),
+                    Thing::from(("migration_wizard_vm", "web02")),
+                ],
+                target_cluster_ids: vec![Thing::from(("migration_wizard_cluster", "hv"))],
+                planned_start: None,
+                planned_end: None,
+                change_window: Some("Sat 22:00-02:00".to_string()),
+                status: WaveStatus::Planned,
+                created_at: now,
+                updated_at: now,
+            }],
+            destination_hypervisors: HashMap::from([("dc1".to_string(), HypervisorType::AzureLocal)]),
+        };
+        build_plan(sources, HypervisorType::HyperV)
+    }
+
+    #[test]
+    fn test_plan_resolves_wave_network_and_new_ip() {
+        let plan = sample_plan();
+        assert_eq!(plan.vms.len(), 3);
+        assert_eq!(plan.clusters[1].hypervisor, HypervisorType::AzureLocal);
+
+        let web01 = &plan.vms[0];
+        assert_eq!(web01.wave.as_deref(), Some("w1"));
+        // The /24 mapping is more specific than the /16
+        assert_eq!(web01.network.as_deref(), Some("App-VLAN-210"));
+        assert_eq!(web01.vlan_id, Some(210));
+        assert_eq!(web01.new_ip.as_deref(), Some("172.16.9.34"));
+        assert_eq!(web01.prefix_length, Some(24));
+
+        let db01 = plan.vms.iter().find(|v| v.key == "db01").unwrap();
+        assert!(db01.wave.is_none());
+        assert!(db01.network.is_none());
+        assert!(plan.warnings.iter().any(|w| w.contains("DB01 has no IPv4 address")));
+        assert!(plan.warnings.iter().any(|w| w.contains("1 VMs are not placed")));
+    }
+
+    #[test]
+    fn test_mapping_without_destination_subnet_keeps_address() {
+        let mut vm = PlanVm {
+            key: "a".to_string(),
+            name: "A".to_string(),
+            cluster: "hv".to_string(),
+            wave: None,
+            cpus: 1,
+            memory_mb: 1024,
+            storage_gb: 10.0,
+            os: None,
+            source_ip: Some("10.1.7.5".to_string()),
+            network: None,
+            vlan_id: None,
+            new_ip: None,
+            prefix_length: None,
+            gateway: None,
+            dns_servers: Vec::new(),
+        };
+        apply_mapping(&mut vm, &mapping("10.1.0.0/16", "Legacy-VLAN-10", None), "10.1.7.5".parse().unwrap());
+        assert_eq!(vm.new_ip.as_deref(), Some("10.1.7.5"));
+        assert_eq!(vm.prefix_length, Some(16));
+    }
+
+    #[test]
+    fn test_iac_bundle_splits_targets() {
+        let plan = sample_plan();
+        let bundle = iac_bundle(&plan, IacTarget::Auto).unwrap();
+        assert_eq!(bundle.name, "contoso-dc-exit-iac");
+        assert!(bundle.file("terraform/main.tf").is_some());
+        assert!(bundle.file("bicep/main.bicep").is_some());
+
+        let terraform_only = iac_bundle(&plan, IacTarget::Terraform).unwrap();
+        assert!(terraform_only.file("bicep/main.bicep").is_none());
+        assert!(terraform_only.warnings.iter().any(|w| w.contains("export it as Bicep")));
+    }
+
+    #[test]
+    fn test_powershell_bundle_skips_other_hypervisors() {
+        let plan = sample_plan();
+        let bundle = powershell_bundle(&plan).unwrap();
+        assert_eq!(bundle.name, "contoso-dc-exit-hyperv");
+        assert!(bundle.file("hyperv/waves/wave_01.psd1").is_some());
+        assert!(bundle.warnings.iter().any(|w| w.contains("Cluster azl is not a Hyper-V cluster")));
+
+        let mut azure_local_only = plan;
+        azure_local_only.vms.retain(|vm| vm.cluster == "azl");
+        assert!(matches!(
+            powershell_bundle(&azure_local_only),
+            Err(AutomationExportError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_zip_contains_every_file() {
+        let bundle = iac_bundle(&sample_plan(), IacTarget::Auto).unwrap();
+        let archive = to_zip(&bundle).unwrap();
+        let zip = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        assert_eq!(zip.len(), bundle.files.len());
+        assert!(zip.file_names().any(|n| n == "contoso-dc-exit-iac/terraform/main.tf"));
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("  Contoso / DC Exit! "), "contoso-dc-exit");
+        assert_eq!(slug("***"), "project");
+    }
+}
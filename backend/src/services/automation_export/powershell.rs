@@ -0,0 +1,328 @@
+//! PowerShell cutover scripts for Hyper-V target clusters
+//!
+//! Each wave gets a PowerShell data file (`waves/wave_NN.psd1`) with its VMs'
+//! sizes and network settings. Two scripts take a wave file as a parameter:
+//! `New-WaveVMs.ps1` creates and configures the VM shells, and
+//! `Test-WaveVMs.ps1` checks them after migration.
+
+use super::ansible::wave_group;
+use crate::models::automation_export::{AutomationPlan, BundleFile, PlanVm, PlanWave};
+use crate::models::project_models::HypervisorType;
+
+const DIR: &str = "hyperv";
+/// Data file of placed VMs that are in no wave yet
+const UNSCHEDULED: &str = "unscheduled";
+
+/// VMs placed on Hyper-V clusters
+pub fn hyperv_vms(plan: &AutomationPlan) -> Vec<&PlanVm> {
+    plan.vms
+        .iter()
+        .filter(|vm| {
+            plan.clusters
+                .iter()
+                .any(|c| c.key == vm.cluster && c.hypervisor == HypervisorType::HyperV)
+        })
+        .collect()
+}
+
+pub fn render(plan: &AutomationPlan) -> Vec<BundleFile> {
+    let vms = hyperv_vms(plan);
+    if vms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut waves: Vec<&PlanWave> = plan.waves.iter().collect();
+    waves.sort_by_key(|w| w.sequence);
+
+    let mut files = vec![
+        file("New-WaveVMs.ps1", NEW_WAVE_VMS.to_string()),
+        file("Test-WaveVMs.ps1", TEST_WAVE_VMS.to_string()),
+    ];
+    let mut index = Vec::new();
+    for wave in waves {
+        let members: Vec<&PlanVm> = vms
+            .iter()
+            .copied()
+            .filter(|vm| vm.wave.as_deref() == Some(wave.key.as_str()))
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+        let name = wave_group(wave);
+        files.push(file(
+            &format!("waves/{}.psd1", name),
+            wave_data(plan, &wave.name, Some(wave.sequence), wave.change_window.as_deref(), &members),
+        ));
+        index.push((name, wave.name.clone(), members.len()));
+    }
+    let unscheduled: Vec<&PlanVm> = vms.iter().copied().filter(|vm| vm.wave.is_none()).collect();
+    if !unscheduled.is_empty() {
+        files.push(file(
+            &format!("waves/{}.psd1", UNSCHEDULED),
+            wave_data(plan, "Not in a wave", None, None, &unscheduled),
+        ));
+        index.push((UNSCHEDULED.to_string(), "not in a wave".to_string(), unscheduled.len()));
+    }
+    files.push(file("README.md", readme(plan, &index)));
+    files
+}
+
+fn file(name: &str, content: String) -> BundleFile {
+    BundleFile {
+        path: format!("{}/{}", DIR, name),
+        content,
+    }
+}
+
+/// Single-quoted PowerShell string
+fn ps_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn ps_optional(value: Option<&str>) -> String {
+    value.map(ps_string).unwrap_or_else(|| "$null".to_string())
+}
+
+fn ps_number<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "$null".to_string())
+}
+
+fn wave_data(
+    plan: &AutomationPlan,
+    name: &str,
+    sequence: Option<u32>,
+    change_window: Option<&str>,
+    vms: &[&PlanVm],
+) -> String {
+    let mut out = format!(
+        "# Generated by Archer from the placement plan of {}\n@{{\n    Wave         = {}\n    Sequence     = {}\n    ChangeWindow = {}\n    VMs          = @(\n",
+        plan.project_name,
+        ps_string(name),
+        ps_number(sequence),
+        ps_optional(change_window)
+    );
+    for vm in vms {
+        let cluster = plan.clusters.iter().find(|c| c.key == vm.cluster).map(|c| c.name.as_str());
+        let dns = vm.dns_servers.iter().map(|d| ps_string(d)).collect::<Vec<_>>().join(", ");
+        out.push_str(&format!(
+            "        @{{\n            Name           = {}\n            Cluster        = {}\n            ProcessorCount = {}\n            MemoryMB       = {}\n            DiskGB         = {}\n            Network        = {}\n            VlanId         = {}\n            SourceIp       = {}\n            NewIp          = {}\n            PrefixLength   = {}\n            Gateway        = {}\n            DnsServers     = @({})\n        }}\n",
+            ps_string(&vm.name),
+            ps_optional(cluster),
+            vm.cpus,
+            vm.memory_mb,
+            vm.storage_gb.ceil() as i64,
+            ps_optional(vm.network.as_deref()),
+            ps_number(vm.vlan_id),
+            ps_optional(vm.source_ip.as_deref()),
+            ps_optional(vm.new_ip.as_deref()),
+            ps_number(vm.prefix_length),
+            ps_optional(vm.gateway.as_deref()),
+            dns
+        ));
+    }
+    out.push_str("    )\n}\n");
+    out
+}
+
+fn readme(plan: &AutomationPlan, index: &[(String, String, usize)]) -> String {
+    let mut out = format!(
+        "# {} - Hyper-V cutover scripts\n\nGenerated by Archer from the placement plan and network mappings. \
+         VM sizes are the placed CPU and memory; networks and VLANs come from the network mappings.\n\n\
+         | Wave file | Wave | VMs |\n|-----------|------|-----|\n",
+        plan.project_name
+    );
+    for (file, wave, count) in index {
+        out.push_str(&format!("| `waves/{}.psd1` | {} | {} |\n", file, wave, count));
+    }
+    out.push_str(
+        "\n`Cluster` in the wave files is the Archer cluster name; change it if the failover cluster is named differently.\n\n\
+         ```powershell\n\
+         # Preview, then create the shells of wave 1\n\
+         .\\New-WaveVMs.ps1 -WaveFile .\\waves\\wave_01.psd1 -SwitchName ConvergedSwitch -Path C:\\ClusterStorage\\Volume1\\VMs -WhatIf\n\
+         .\\New-WaveVMs.ps1 -WaveFile .\\waves\\wave_01.psd1 -SwitchName ConvergedSwitch -Path C:\\ClusterStorage\\Volume1\\VMs\n\n\
+         # After the disks are migrated and the VMs started\n\
+         .\\Test-WaveVMs.ps1 -WaveFile .\\waves\\wave_01.psd1\n\
+         ```\n",
+    );
+    out
+}
+
+const NEW_WAVE_VMS: &str = r#"<#
+.SYNOPSIS
+    Creates and configures the Hyper-V VM shells of one migration wave.
+
+.DESCRIPTION
+    Generated by Archer. For each VM in the wave file this script:
+      - creates the VM without disks (New-VM) unless it already exists
+      - sets its processor count and static memory (Set-VM)
+      - sets the access VLAN of its network adapter (Set-VMNetworkAdapterVlan)
+      - makes it highly available on its failover cluster
+    Disks are attached by the migration tool. Run with -WhatIf first.
+
+.EXAMPLE
+    .\New-WaveVMs.ps1 -WaveFile .\waves\wave_01.psd1 -SwitchName ConvergedSwitch -Path C:\ClusterStorage\Volume1\VMs
+#>
+[CmdletBinding(SupportsShouldProcess)]
+param(
+    [Parameter(Mandatory)]
+    [string] $WaveFile,
+
+    # Virtual switch the network adapters connect to
+    [Parameter(Mandatory)]
+    [string] $SwitchName,
+
+    # Folder the VM configuration files are stored in
+    [Parameter(Mandatory)]
+    [string] $Path,
+
+    # Hyper-V host the shells are created on
+    [string] $ComputerName = $env:COMPUTERNAME,
+
+    [ValidateSet(1, 2)]
+    [int] $Generation = 2,
+
+    # Leave the VMs out of the failover cluster
+    [switch] $SkipClusterRole
+)
+
+$ErrorActionPreference = 'Stop'
+$wave = Import-PowerShellDataFile -Path $WaveFile
+Write-Host "Wave $($wave.Sequence): $($wave.Wave) ($($wave.VMs.Count) VMs)"
+
+foreach ($vm in $wave.VMs) {
+    $memory = [int64]$vm.MemoryMB * 1MB
+
+    if (-not (Get-VM -Name $vm.Name -ComputerName $ComputerName -ErrorAction SilentlyContinue)) {
+        if ($PSCmdlet.ShouldProcess($vm.Name, "New-VM on $ComputerName")) {
+            New-VM -Name $vm.Name -ComputerName $ComputerName -Generation $Generation `
+                -MemoryStartupBytes $memory -Path $Path -NoVHD -SwitchName $SwitchName | Out-Null
+        }
+    }
+
+    if ($PSCmdlet.ShouldProcess($vm.Name, "Set-VM $($vm.ProcessorCount) vCPU, $($vm.MemoryMB) MB")) {
+        Set-VM -Name $vm.Name -ComputerName $ComputerName -ProcessorCount $vm.ProcessorCount `
+            -StaticMemory -MemoryStartupBytes $memory
+    }
+
+    if ($null -ne $vm.VlanId) {
+        if ($PSCmdlet.ShouldProcess($vm.Name, "Access VLAN $($vm.VlanId)")) {
+            Set-VMNetworkAdapterVlan -VMName $vm.Name -ComputerName $ComputerName -Access -VlanId $vm.VlanId
+        }
+    } else {
+        Write-Warning "$($vm.Name): no VLAN in the network mapping; adapter left untagged"
+    }
+
+    if (-not $SkipClusterRole -and $vm.Cluster) {
+        if (-not (Get-ClusterGroup -Cluster $vm.Cluster -Name $vm.Name -ErrorAction SilentlyContinue)) {
+            if ($PSCmdlet.ShouldProcess($vm.Name, "Add to cluster $($vm.Cluster)")) {
+                Add-ClusterVirtualMachineRole -Cluster $vm.Cluster -VMName $vm.Name | Out-Null
+            }
+        }
+    }
+}
+"#;
+
+const TEST_WAVE_VMS: &str = r#"<#
+.SYNOPSIS
+    Checks the VMs of one migration wave after cutover.
+
+.DESCRIPTION
+    Generated by Archer. For each VM in the wave file it checks that the VM
+    exists and is running, that its processor count, memory and VLAN match
+    the plan, that the guest reports a heartbeat, and that it answers on its
+    new address. Exits with 1 when a check fails.
+
+.EXAMPLE
+    .\Test-WaveVMs.ps1 -WaveFile .\waves\wave_01.psd1
+#>
+[CmdletBinding()]
+param(
+    [Parameter(Mandatory)]
+    [string] $WaveFile,
+
+    [string] $ComputerName = $env:COMPUTERNAME
+)
+
+$wave = Import-PowerShellDataFile -Path $WaveFile
+$results = foreach ($vm in $wave.VMs) {
+    $checks = [ordered]@{}
+    $hyperv = Get-VM -Name $vm.Name -ComputerName $ComputerName -ErrorAction SilentlyContinue
+
+    $checks.Exists = [bool]$hyperv
+    if ($hyperv) {
+        $checks.Running = $hyperv.State -eq 'Running'
+        $checks.Processors = $hyperv.ProcessorCount -eq $vm.ProcessorCount
+        $checks.Memory = $hyperv.MemoryStartup -eq ([int64]$vm.MemoryMB * 1MB)
+        if ($null -ne $vm.VlanId) {
+            $vlan = Get-VMNetworkAdapterVlan -VMName $vm.Name -ComputerName $ComputerName | Select-Object -First 1
+            $checks.Vlan = $vlan.AccessVlanId -eq $vm.VlanId
+        }
+        $checks.Heartbeat = $hyperv.Heartbeat -in @('OkApplicationsHealthy', 'OkApplicationsUnknown')
+    }
+    if ($vm.NewIp) {
+        $checks.Reachable = Test-Connection -ComputerName $vm.NewIp -Count 2 -Quiet
+    }
+
+    $failed = @($checks.Keys | Where-Object { -not $checks[$_] })
+    [pscustomobject]@{
+        Name   = $vm.Name
+        NewIp  = $vm.NewIp
+        Passed = $failed.Count -eq 0
+        Failed = $failed -join ', '
+    }
+}
+
+$results | Format-Table -AutoSize
+if ($results | Where-Object { -not $_.Passed }) {
+    Write-Error "Wave $($wave.Sequence): some VMs failed validation"
+    exit 1
+}
+Write-Host "Wave $($wave.Sequence): all $(@($results).Count) VMs passed"
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::automation_export::tests::sample_plan;
+
+    #[test]
+    fn test_wave_file_per_wave_with_hyperv_vms_only() {
+        let files = render(&sample_plan());
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert!(paths.contains(&"hyperv/New-WaveVMs.ps1"));
+        assert!(paths.contains(&"hyperv/Test-WaveVMs.ps1"));
+        assert!(paths.contains(&"hyperv/waves/wave_01.psd1"));
+        // db01, the only VM outside a wave, is on Azure Local
+        assert!(!paths.contains(&"hyperv/waves/unscheduled.psd1"));
+
+        let wave = &files.iter().find(|f| f.path == "hyperv/waves/wave_01.psd1").unwrap().content;
+        assert!(wave.contains("Name           = 'WEB01'"));
+        assert!(wave.contains("VlanId         = 210"));
+        assert!(wave.contains("NewIp          = '172.16.9.34'"));
+        assert!(wave.contains("ChangeWindow = 'Sat 22:00-02:00'"));
+        assert!(!wave.contains("DB01"));
+    }
+
+    #[test]
+    fn test_missing_values_are_null() {
+        let mut plan = sample_plan();
+        plan.vms[0].vlan_id = None;
+        plan.vms[0].new_ip = None;
+        let files = render(&plan);
+        let wave = &files.iter().find(|f| f.path == "hyperv/waves/wave_01.psd1").unwrap().content;
+        assert!(wave.contains("VlanId         = $null"));
+        assert!(wave.contains("NewIp          = $null"));
+    }
+
+    #[test]
+    fn test_strings_are_escaped() {
+        assert_eq!(ps_string("O'Brien's VM"), "'O''Brien''s VM'");
+    }
+
+    #[test]
+    fn test_no_hyperv_clusters_no_files() {
+        let mut plan = sample_plan();
+        plan.clusters.iter_mut().for_each(|c| c.hypervisor = HypervisorType::VMware);
+        assert!(render(&plan).is_empty());
+    }
+}
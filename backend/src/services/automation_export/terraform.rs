@@ -0,0 +1,493 @@
+//! Terraform scaffold for Hyper-V, vSphere and KVM target clusters
+//!
+//! The HCL is the same for every project; the plan goes into
+//! `terraform.tfvars.json` and the resources iterate over it, so the team can
+//! edit the data without touching the code.
+
+use serde_json::{json, Map, Value};
+
+use crate::models::automation_export::{AutomationPlan, BundleFile, PlanCluster};
+use crate::models::project_models::HypervisorType;
+
+const DIR: &str = "terraform";
+
+pub fn render(plan: &AutomationPlan, clusters: &[&PlanCluster]) -> Vec<BundleFile> {
+    if clusters.is_empty() {
+        return Vec::new();
+    }
+    let has = |hypervisor: HypervisorType| clusters.iter().any(|c| c.hypervisor == hypervisor);
+    // Unsupported hypervisors never reach here; Azure Local goes to Bicep
+    let providers: Vec<Provider> = [Provider::HyperV, Provider::Vsphere, Provider::Libvirt]
+        .into_iter()
+        .filter(|p| has(p.hypervisor()))
+        .collect();
+
+    let mut main = String::from(MAIN_HEADER);
+    for provider in &providers {
+        main.push_str(provider.resources());
+    }
+
+    vec![
+        file("versions.tf", versions_tf(&providers)),
+        file("variables.tf", variables_tf(&providers)),
+        file("main.tf", main),
+        file("terraform.tfvars.json", tfvars(plan, clusters)),
+        file("README.md", readme(plan, clusters)),
+    ]
+}
+
+fn file(name: &str, content: String) -> BundleFile {
+    BundleFile {
+        path: format!("{}/{}", DIR, name),
+        content,
+    }
+}
+
+pub fn hypervisor_name(hypervisor: HypervisorType) -> &'static str {
+    match hypervisor {
+        HypervisorType::HyperV => "hyper-v",
+        HypervisorType::VMware => "vmware",
+        HypervisorType::AzureLocal => "azure-local",
+        HypervisorType::Kvm => "kvm",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Provider {
+    HyperV,
+    Vsphere,
+    Libvirt,
+}
+
+impl Provider {
+    fn hypervisor(self) -> HypervisorType {
+        match self {
+            Provider::HyperV => HypervisorType::HyperV,
+            Provider::Vsphere => HypervisorType::VMware,
+            Provider::Libvirt => HypervisorType::Kvm,
+        }
+    }
+
+    fn requirement(self) -> &'static str {
+        match self {
+            Provider::HyperV => "    hyperv = {\n      source  = \"taliesins/hyperv\"\n      version = \"~> 1.2\"\n    }\n",
+            Provider::Vsphere => "    vsphere = {\n      source  = \"hashicorp/vsphere\"\n      version = \"~> 2.6\"\n    }\n",
+            Provider::Libvirt => "    libvirt = {\n      source  = \"dmacvicar/libvirt\"\n      version = \"~> 0.7\"\n    }\n",
+        }
+    }
+
+    fn variables(self) -> &'static str {
+        match self {
+            Provider::HyperV => HYPERV_VARIABLES,
+            Provider::Vsphere => VSPHERE_VARIABLES,
+            Provider::Libvirt => LIBVIRT_VARIABLES,
+        }
+    }
+
+    fn resources(self) -> &'static str {
+        match self {
+            Provider::HyperV => HYPERV_RESOURCES,
+            Provider::Vsphere => VSPHERE_RESOURCES,
+            Provider::Libvirt => LIBVIRT_RESOURCES,
+        }
+    }
+}
+
+fn versions_tf(providers: &[Provider]) -> String {
+    let mut out = String::from("terraform {\n  required_version = \">= 1.3\"\n\n  required_providers {\n");
+    for provider in providers {
+        out.push_str(provider.requirement());
+    }
+    out.push_str("  }\n}\n");
+    out
+}
+
+fn variables_tf(providers: &[Provider]) -> String {
+    let mut out = String::from(PLAN_VARIABLES);
+    for provider in providers {
+        out.push_str(provider.variables());
+    }
+    out
+}
+
+fn tfvars(plan: &AutomationPlan, clusters: &[&PlanCluster]) -> String {
+    let mut cluster_map = Map::new();
+    for cluster in clusters {
+        cluster_map.insert(
+            cluster.key.clone(),
+            json!({ "name": cluster.name, "hypervisor": hypervisor_name(cluster.hypervisor) }),
+        );
+    }
+
+    let vms: Vec<_> = plan
+        .vms
+        .iter()
+        .filter(|vm| cluster_map.contains_key(&vm.cluster))
+        .collect();
+
+    let mut networks = Map::new();
+    for vm in &vms {
+        let Some(name) = &vm.network else { continue };
+        if networks.contains_key(name) {
+            continue;
+        }
+        if let Some(network) = plan.networks.iter().find(|n| &n.name == name) {
+            networks.insert(
+                name.clone(),
+                json!({
+                    "vlan_id": network.vlan_id,
+                    "subnet": network.subnet,
+                    "gateway": network.gateway,
+                    "dns_servers": network.dns_servers,
+                }),
+            );
+        }
+    }
+
+    let mut vm_map = Map::new();
+    for vm in vms {
+        vm_map.insert(
+            vm.key.clone(),
+            json!({
+                "name": vm.name,
+                "cluster": vm.cluster,
+                "wave": vm.wave,
+                "cpus": vm.cpus,
+                "memory_mb": vm.memory_mb,
+                "storage_gb": vm.storage_gb.ceil() as i64,
+                "network": vm.network,
+                "vlan_id": vm.vlan_id,
+                "ip_address": vm.new_ip,
+                "prefix_length": vm.prefix_length,
+            }),
+        );
+    }
+
+    let vars = json!({
+        "clusters": Value::Object(cluster_map),
+        "networks": Value::Object(networks),
+        "vms": Value::Object(vm_map),
+    });
+    let mut out = serde_json::to_string_pretty(&vars).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+fn readme(plan: &AutomationPlan, clusters: &[&PlanCluster]) -> String {
+    let mut out = format!(
+        "# {} - Terraform scaffold\n\nGenerated by Archer from the placement plan. It creates the VM shells on the target clusters; \
+         disks arrive with the migration tool.\n\n| Cluster | Hypervisor | VMs |\n|---------|------------|-----|\n",
+        plan.project_name
+    );
+    for cluster in clusters {
+        let count = plan.vms.iter().filter(|vm| vm.cluster == cluster.key).count();
+        out.push_str(&format!("| {} | {} | {} |\n", cluster.name, hypervisor_name(cluster.hypervisor), count));
+    }
+    out.push_str(
+        "\n1. Fill in the connection variables in `variables.tf` (or a `*.auto.tfvars` file).\n\
+         2. Review `terraform.tfvars.json`; it holds the clusters, networks and VMs.\n\
+         3. `terraform init && terraform plan`\n",
+    );
+    out
+}
+
+const PLAN_VARIABLES: &str = r#"variable "clusters" {
+  description = "Target clusters from the placement plan, by cluster key"
+  type = map(object({
+    name       = string
+    hypervisor = string
+  }))
+}
+
+variable "networks" {
+  description = "Destination networks from the network mappings, by name"
+  type = map(object({
+    vlan_id     = optional(number)
+    subnet      = optional(string)
+    gateway     = optional(string)
+    dns_servers = optional(list(string), [])
+  }))
+  default = {}
+}
+
+variable "vms" {
+  description = "VM shells from the placement plan, by VM key"
+  type = map(object({
+    name          = string
+    cluster       = string
+    wave          = optional(string)
+    cpus          = number
+    memory_mb     = number
+    storage_gb    = number
+    network       = optional(string)
+    vlan_id       = optional(number)
+    ip_address    = optional(string)
+    prefix_length = optional(number)
+  }))
+}
+"#;
+
+const HYPERV_VARIABLES: &str = r#"
+variable "hyperv_host" {
+  description = "Hyper-V host or cluster name reached over WinRM"
+  type        = string
+}
+
+variable "hyperv_user" {
+  type = string
+}
+
+variable "hyperv_password" {
+  type      = string
+  sensitive = true
+}
+
+variable "hyperv_switch_name" {
+  description = "Virtual switch the VM network adapters connect to"
+  type        = string
+}
+
+variable "hyperv_vhd_path" {
+  description = "Folder the VM disks are created in"
+  type        = string
+  default     = "C:\\ClusterStorage\\Volume1\\VMs"
+}
+"#;
+
+const VSPHERE_VARIABLES: &str = r#"
+variable "vsphere_server" {
+  type = string
+}
+
+variable "vsphere_user" {
+  type = string
+}
+
+variable "vsphere_password" {
+  type      = string
+  sensitive = true
+}
+
+variable "vsphere_datacenter" {
+  type = string
+}
+
+variable "vsphere_datastore" {
+  type = string
+}
+
+variable "vsphere_guest_id" {
+  description = "Guest OS identifier of the shells; change per VM as needed"
+  type        = string
+  default     = "otherGuest64"
+}
+"#;
+
+const LIBVIRT_VARIABLES: &str = r#"
+variable "libvirt_uri" {
+  type    = string
+  default = "qemu:///system"
+}
+
+variable "libvirt_pool" {
+  description = "Storage pool the VM disks are created in"
+  type        = string
+  default     = "default"
+}
+"#;
+
+const MAIN_HEADER: &str = r#"# Target infrastructure scaffold generated by Archer from the placement plan.
+# The plan itself is in terraform.tfvars.json.
+
+locals {
+  vms_by_hypervisor = {
+    for hypervisor in distinct([for c in values(var.clusters) : c.hypervisor]) :
+    hypervisor => { for key, vm in var.vms : key => vm if var.clusters[vm.cluster].hypervisor == hypervisor }
+  }
+}
+"#;
+
+const HYPERV_RESOURCES: &str = r#"
+# ---------------------------------------------------------------------------
+# Hyper-V
+# ---------------------------------------------------------------------------
+
+provider "hyperv" {
+  host     = var.hyperv_host
+  user     = var.hyperv_user
+  password = var.hyperv_password
+  https    = true
+}
+
+resource "hyperv_vhd" "disk" {
+  for_each = lookup(local.vms_by_hypervisor, "hyper-v", {})
+
+  path = "${var.hyperv_vhd_path}\\${each.value.name}\\${each.value.name}.vhdx"
+  size = each.value.storage_gb * 1073741824
+}
+
+resource "hyperv_machine_instance" "vm" {
+  for_each = lookup(local.vms_by_hypervisor, "hyper-v", {})
+
+  name                 = each.value.name
+  generation           = 2
+  processor_count      = each.value.cpus
+  static_memory        = true
+  memory_startup_bytes = each.value.memory_mb * 1048576
+  state                = "Off"
+
+  network_adaptors {
+    name        = "Network Adapter"
+    switch_name = var.hyperv_switch_name
+    vlan_access = each.value.vlan_id != null
+    vlan_id     = coalesce(each.value.vlan_id, 0)
+  }
+
+  hard_disk_drives {
+    controller_type     = "Scsi"
+    controller_number   = 0
+    controller_location = 0
+    path                = hyperv_vhd.disk[each.key].path
+  }
+}
+"#;
+
+const VSPHERE_RESOURCES: &str = r#"
+# ---------------------------------------------------------------------------
+# vSphere
+# ---------------------------------------------------------------------------
+
+provider "vsphere" {
+  vsphere_server = var.vsphere_server
+  user           = var.vsphere_user
+  password       = var.vsphere_password
+}
+
+data "vsphere_datacenter" "dc" {
+  name = var.vsphere_datacenter
+}
+
+data "vsphere_datastore" "ds" {
+  name          = var.vsphere_datastore
+  datacenter_id = data.vsphere_datacenter.dc.id
+}
+
+data "vsphere_compute_cluster" "cluster" {
+  for_each = { for key, c in var.clusters : key => c if c.hypervisor == "vmware" }
+
+  name          = each.value.name
+  datacenter_id = data.vsphere_datacenter.dc.id
+}
+
+data "vsphere_network" "net" {
+  for_each = toset(compact([for vm in values(lookup(local.vms_by_hypervisor, "vmware", {})) : vm.network]))
+
+  name          = each.value
+  datacenter_id = data.vsphere_datacenter.dc.id
+}
+
+resource "vsphere_virtual_machine" "vm" {
+  for_each = lookup(local.vms_by_hypervisor, "vmware", {})
+
+  name             = each.value.name
+  resource_pool_id = data.vsphere_compute_cluster.cluster[each.value.cluster].resource_pool_id
+  datastore_id     = data.vsphere_datastore.ds.id
+  num_cpus         = each.value.cpus
+  memory           = each.value.memory_mb
+  guest_id         = var.vsphere_guest_id
+
+  dynamic "network_interface" {
+    for_each = each.value.network == null ? [] : [each.value.network]
+    content {
+      network_id = data.vsphere_network.net[network_interface.value].id
+    }
+  }
+
+  disk {
+    label = "disk0"
+    size  = each.value.storage_gb
+  }
+
+  wait_for_guest_net_timeout = 0
+}
+"#;
+
+const LIBVIRT_RESOURCES: &str = r#"
+# ---------------------------------------------------------------------------
+# KVM (libvirt)
+# ---------------------------------------------------------------------------
+
+provider "libvirt" {
+  uri = var.libvirt_uri
+}
+
+resource "libvirt_volume" "disk" {
+  for_each = lookup(local.vms_by_hypervisor, "kvm", {})
+
+  name = "${each.value.name}.qcow2"
+  pool = var.libvirt_pool
+  size = each.value.storage_gb * 1073741824
+}
+
+resource "libvirt_domain" "vm" {
+  for_each = lookup(local.vms_by_hypervisor, "kvm", {})
+
+  name    = each.value.name
+  vcpu    = each.value.cpus
+  memory  = each.value.memory_mb
+  running = false
+
+  disk {
+    volume_id = libvirt_volume.disk[each.key].id
+  }
+
+  dynamic "network_interface" {
+    for_each = each.value.network == null ? [] : [each.value.network]
+    content {
+      network_name = network_interface.value
+      addresses    = each.value.ip_address == null ? [] : [each.value.ip_address]
+    }
+  }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::automation_export::tests::sample_plan;
+
+    #[test]
+    fn test_renders_only_present_providers() {
+        let plan = sample_plan();
+        let clusters: Vec<&PlanCluster> = plan.clusters.iter().filter(|c| c.hypervisor != HypervisorType::AzureLocal).collect();
+        let files = render(&plan, &clusters);
+        let get = |name: &str| &files.iter().find(|f| f.path == format!("terraform/{}", name)).unwrap().content;
+
+        assert!(get("versions.tf").contains("taliesins/hyperv"));
+        assert!(!get("versions.tf").contains("hashicorp/vsphere"));
+        assert!(get("main.tf").contains("resource \"hyperv_machine_instance\" \"vm\""));
+        assert!(!get("main.tf").contains("vsphere_virtual_machine"));
+        assert!(get("variables.tf").contains("variable \"hyperv_switch_name\""));
+    }
+
+    #[test]
+    fn test_tfvars_holds_the_plan() {
+        let plan = sample_plan();
+        let clusters: Vec<&PlanCluster> = plan.clusters.iter().filter(|c| c.key == "hv").collect();
+        let files = render(&plan, &clusters);
+        let tfvars = &files.iter().find(|f| f.path == "terraform/terraform.tfvars.json").unwrap().content;
+        let vars: Value = serde_json::from_str(tfvars).unwrap();
+
+        assert_eq!(vars["clusters"]["hv"]["hypervisor"], "hyper-v");
+        assert_eq!(vars["vms"]["web01"]["ip_address"], "172.16.9.34");
+        assert_eq!(vars["vms"]["web01"]["wave"], "w1");
+        assert_eq!(vars["networks"]["App-VLAN-210"]["vlan_id"], 210);
+        // db01 is on the Azure Local cluster
+        assert!(vars["vms"].get("db01").is_none());
+    }
+
+    #[test]
+    fn test_no_clusters_no_files() {
+        assert!(render(&sample_plan(), &[]).is_empty());
+    }
+}
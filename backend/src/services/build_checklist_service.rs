@@ -0,0 +1,437 @@
+// Archer ITSM - Build Checklist Service
+// Evaluates destination cluster readiness items, records manual sign-offs,
+// drives the cluster build status and gates wave execution on completion
+
+use chrono::Utc;
+use serde::Deserialize;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::models::build_checklist::*;
+use crate::models::firmware_baseline::ClusterFirmwareReport;
+use crate::models::migration_wizard_models::MigrationWizardCluster;
+use crate::models::project_models::{BuildStatus, ClusterStatus, DestinationCluster, HardwarePool};
+use crate::services::firmware_baseline_service::{FirmwareBaselineError, FirmwareBaselineService};
+use crate::database::TracedQuery;
+
+const TABLE: &str = "cluster_build_checklist";
+
+#[derive(Debug, Error)]
+pub enum BuildChecklistError {
+    #[error("Destination cluster not found")]
+    ClusterNotFound,
+
+    #[error("Checklist item not found")]
+    ItemNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for BuildChecklistError {
+    fn from(e: surrealdb::Error) -> Self {
+        BuildChecklistError::DatabaseError(e.to_string())
+    }
+}
+
+/// Outcome of one check: how it was decided, the status and why
+type Evaluation = (ChecklistVerification, ChecklistItemStatus, Option<String>);
+
+pub struct BuildChecklistService {
+    db: Database,
+}
+
+impl BuildChecklistService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// The cluster's checklist, created with every item pending on first use
+    pub async fn get_checklist(&self, cluster_id: &str) -> Result<BuildChecklist, BuildChecklistError> {
+        self.get_cluster(cluster_id).await?;
+        let existing: Option<BuildChecklist> = self.db.select((TABLE, cluster_id)).await?;
+        if let Some(checklist) = existing {
+            return Ok(checklist);
+        }
+
+        let now = Utc::now();
+        let checklist = BuildChecklist {
+            id: None,
+            cluster_id: Thing::from(("destination_cluster", cluster_id)),
+            items: ChecklistItemKind::ALL
+                .iter()
+                .map(|kind| BuildChecklistItem {
+                    kind: *kind,
+                    title: kind.title().to_string(),
+                    verification: ChecklistVerification::Manual,
+                    status: ChecklistItemStatus::Pending,
+                    detail: None,
+                    signed_off_by: None,
+                    signed_off_at: None,
+                    notes: None,
+                })
+                .collect(),
+            complete: false,
+            firmware_baseline: None,
+            last_checked_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.save(cluster_id, checklist).await
+    }
+
+    /// Set the firmware baseline nodes are compared against
+    pub async fn update_checklist(
+        &self,
+        cluster_id: &str,
+        request: UpdateChecklistRequest,
+    ) -> Result<BuildChecklist, BuildChecklistError> {
+        let mut checklist = self.get_checklist(cluster_id).await?;
+        if let Some(baseline) = request.firmware_baseline {
+            let baseline = baseline.trim().to_string();
+            checklist.firmware_baseline = (!baseline.is_empty()).then_some(baseline);
+        }
+        checklist.updated_at = Utc::now();
+        self.save(cluster_id, checklist).await
+    }
+
+    /// Run every automated check and refresh the cluster's build status
+    pub async fn run_checks(&self, cluster_id: &str) -> Result<BuildChecklist, BuildChecklistError> {
+        let cluster = self.get_cluster(cluster_id).await?;
+        let mut checklist = self.get_checklist(cluster_id).await?;
+
+        let firmware = self.node_firmware(&cluster).await?;
+        let firmware_report = FirmwareBaselineService::new(self.db.clone())
+            .cluster_compliance(cluster_id, false)
+            .await
+            .map_err(|e| match e {
+                FirmwareBaselineError::ClusterNotFound => BuildChecklistError::ClusterNotFound,
+                other => BuildChecklistError::DatabaseError(other.to_string()),
+            })?;
+        let redundancy = self.storage_redundancy(&cluster.name).await?;
+        for item in checklist.items.iter_mut() {
+            let evaluation = match item.kind {
+                ChecklistItemKind::NetworkProfileApplied => evaluate_network(&cluster),
+                ChecklistItemKind::FirmwareBaseline => evaluate_firmware_report(&firmware_report)
+                    .unwrap_or_else(|| evaluate_firmware(checklist.firmware_baseline.as_deref(), &firmware)),
+                ChecklistItemKind::StorageResiliency => evaluate_storage(redundancy),
+                ChecklistItemKind::ClusterWitness => (
+                    ChecklistVerification::Manual,
+                    ChecklistItemStatus::Pending,
+                    Some("No integration reports witness configuration; confirm it and sign off".to_string()),
+                ),
+            };
+            apply_evaluation(item, evaluation);
+        }
+
+        let now = Utc::now();
+        checklist.complete = checklist.items.iter().all(BuildChecklistItem::is_done);
+        checklist.last_checked_at = Some(now);
+        checklist.updated_at = now;
+        let checklist = self.save(cluster_id, checklist).await?;
+        self.sync_build_status(cluster, checklist.complete).await?;
+        Ok(checklist)
+    }
+
+    /// Sign off an item no automated check could decide
+    pub async fn sign_off(
+        &self,
+        cluster_id: &str,
+        kind: ChecklistItemKind,
+        request: SignOffChecklistItemRequest,
+    ) -> Result<BuildChecklist, BuildChecklistError> {
+        if request.signed_off_by.trim().is_empty() {
+            return Err(BuildChecklistError::Validation("signed_off_by is required".to_string()));
+        }
+        let cluster = self.get_cluster(cluster_id).await?;
+        let mut checklist = self.get_checklist(cluster_id).await?;
+
+        let now = Utc::now();
+        let item = checklist
+            .items
+            .iter_mut()
+            .find(|i| i.kind == kind)
+            .ok_or(BuildChecklistError::ItemNotFound)?;
+        if item.verification == ChecklistVerification::Automated {
+            return Err(BuildChecklistError::Validation(format!(
+                "'{}' is checked automatically; fix the finding and re-run the checks",
+                item.title
+            )));
+        }
+        item.status = ChecklistItemStatus::SignedOff;
+        item.signed_off_by = Some(request.signed_off_by);
+        item.signed_off_at = Some(now);
+        item.notes = request.notes;
+
+        checklist.complete = checklist.items.iter().all(BuildChecklistItem::is_done);
+        checklist.updated_at = now;
+        let checklist = self.save(cluster_id, checklist).await?;
+        self.sync_build_status(cluster, checklist.complete).await?;
+        Ok(checklist)
+    }
+
+    /// Wave target clusters whose destination build is not signed off.
+    /// Targets are migration wizard clusters; only those linked to a
+    /// destination cluster are gated.
+    pub async fn wave_blockers(&self, target_cluster_ids: &[Thing]) -> Result<Vec<ClusterBuildBlocker>, BuildChecklistError> {
+        let mut blockers = Vec::new();
+        for target in target_cluster_ids {
+            let cluster: Option<MigrationWizardCluster> = self.db.select(target.clone()).await?;
+            let Some(cluster) = cluster else { continue };
+            let Some(destination) = cluster.destination_cluster_id.as_deref() else {
+                continue;
+            };
+
+            let checklist: Option<BuildChecklist> = self.db.select((TABLE, destination)).await?;
+            let open_items: Vec<String> = match &checklist {
+                Some(checklist) if checklist.complete => continue,
+                Some(checklist) => checklist
+                    .items
+                    .iter()
+                    .filter(|i| !i.is_done())
+                    .map(|i| i.title.clone())
+                    .collect(),
+                None => ChecklistItemKind::ALL.iter().map(|k| k.title().to_string()).collect(),
+            };
+            blockers.push(ClusterBuildBlocker {
+                cluster_id: target.id.to_raw(),
+                cluster_name: cluster.name,
+                destination_cluster_id: destination.to_string(),
+                open_items,
+            });
+        }
+        Ok(blockers)
+    }
+
+    async fn get_cluster(&self, cluster_id: &str) -> Result<DestinationCluster, BuildChecklistError> {
+        let cluster: Option<DestinationCluster> = self.db.select(("destination_cluster", cluster_id)).await?;
+        cluster.ok_or(BuildChecklistError::ClusterNotFound)
+    }
+
+    async fn save(&self, cluster_id: &str, checklist: BuildChecklist) -> Result<BuildChecklist, BuildChecklistError> {
+        let saved: Option<BuildChecklist> = self.db.update((TABLE, cluster_id)).content(checklist).await?;
+        saved.ok_or_else(|| BuildChecklistError::DatabaseError("Failed to save checklist".to_string()))
+    }
+
+    /// Firmware version of each node, keyed by asset tag. Hardware imports
+    /// record it in the node's metadata.
+    async fn node_firmware(&self, cluster: &DestinationCluster) -> Result<Vec<(String, Option<String>)>, BuildChecklistError> {
+        let mut firmware = Vec::new();
+        for node in &cluster.nodes {
+            let pool: Option<HardwarePool> = self.db.select(node.clone()).await?;
+            if let Some(pool) = pool {
+                let version = pool.metadata.get("firmware_version").and_then(|v| v.as_str()).map(str::to_string);
+                firmware.push((pool.asset_tag, version));
+            }
+        }
+        Ok(firmware)
+    }
+
+    /// Redundancy factor of the Nutanix cluster of the same name, when a
+    /// Prism Central integration has imported one
+    async fn storage_redundancy(&self, name: &str) -> Result<Option<i64>, BuildChecklistError> {
+        #[derive(Deserialize)]
+        struct Row {
+            redundancy_factor: Option<i64>,
+        }
+        let rows: Vec<Row> = self
+            .db
+            .query(
+                "SELECT redundancy_factor FROM nutanix_cluster \
+                 WHERE status = 'active' AND string::lowercase(name) = $name LIMIT 1",
+            )
+            .bind(("name", name.to_lowercase()))
+            .traced("build_checklist_service.storage_redundancy")
+            .await?
+            .take(0)?;
+        Ok(rows.into_iter().next().and_then(|r| r.redundancy_factor))
+    }
+
+    /// A complete checklist finishes the build; reopening one sends a
+    /// finished or configuring cluster back to validation
+    async fn sync_build_status(&self, mut cluster: DestinationCluster, complete: bool) -> Result<(), BuildChecklistError> {
+        let (build_status, status) = if complete {
+            (BuildStatus::Completed, ClusterStatus::Ready)
+        } else if matches!(cluster.build_status, BuildStatus::ClusterConfiguration | BuildStatus::Completed) {
+            (BuildStatus::Validation, ClusterStatus::Building)
+        } else {
+            return Ok(());
+        };
+        if cluster.build_status == build_status {
+            return Ok(());
+        }
+        cluster.build_status = build_status;
+        if !matches!(cluster.status, ClusterStatus::Active | ClusterStatus::Decommissioned) {
+            cluster.status = status;
+        }
+        cluster.updated_at = Utc::now();
+
+        let id = cluster.id.clone().ok_or(BuildChecklistError::ClusterNotFound)?;
+        let _: Option<DestinationCluster> = self.db.update(id).content(cluster).await?;
+        Ok(())
+    }
+}
+
+/// Keep a manual sign-off while no automated check can decide the item
+fn apply_evaluation(item: &mut BuildChecklistItem, (verification, status, detail): Evaluation) {
+    let keep_sign_off =
+        verification == ChecklistVerification::Manual && item.status == ChecklistItemStatus::SignedOff;
+    item.verification = verification;
+    item.detail = detail;
+    if keep_sign_off {
+        return;
+    }
+    item.status = status;
+    item.signed_off_by = None;
+    item.signed_off_at = None;
+}
+
+fn evaluate_network(cluster: &DestinationCluster) -> Evaluation {
+    let mut missing = Vec::new();
+    if cluster.network_profile_id.is_none() {
+        missing.push("no network profile is applied");
+    }
+    if cluster.management_network.vlan_id.is_none() {
+        missing.push("the management VLAN is not set");
+    }
+    if missing.is_empty() {
+        (ChecklistVerification::Automated, ChecklistItemStatus::Passed, None)
+    } else {
+        (ChecklistVerification::Automated, ChecklistItemStatus::Failed, Some(missing.join("; ")))
+    }
+}
+
+/// Decide the firmware item from per-model baselines; None when no node
+/// has a baseline, leaving the cluster-wide version check to decide
+fn evaluate_firmware_report(report: &ClusterFirmwareReport) -> Option<Evaluation> {
+    if report.nodes.iter().all(|n| n.baseline_id.is_none()) {
+        return None;
+    }
+    if report.compliant {
+        return Some((ChecklistVerification::Automated, ChecklistItemStatus::Passed, None));
+    }
+    let issues: Vec<String> = report
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            if node.baseline_id.is_none() {
+                return Some(format!("{} (no baseline for {} {})", node.asset_tag, node.vendor, node.model));
+            }
+            let components: Vec<&str> = node
+                .findings
+                .iter()
+                .filter(|f| f.status.needs_remediation())
+                .map(|f| f.component.key())
+                .collect();
+            (!components.is_empty()).then(|| format!("{} ({})", node.asset_tag, components.join(", ")))
+        })
+        .collect();
+    Some((
+        ChecklistVerification::Automated,
+        ChecklistItemStatus::Failed,
+        Some(format!("Firmware remediation needed: {}", issues.join("; "))),
+    ))
+}
+
+fn evaluate_firmware(baseline: Option<&str>, nodes: &[(String, Option<String>)]) -> Evaluation {
+    let Some(baseline) = baseline else {
+        return (
+            ChecklistVerification::Manual,
+            ChecklistItemStatus::Pending,
+            Some("No firmware baseline is set; set one or sign off".to_string()),
+        );
+    };
+    let unknown: Vec<&str> = nodes.iter().filter(|(_, v)| v.is_none()).map(|(tag, _)| tag.as_str()).collect();
+    if nodes.is_empty() || !unknown.is_empty() {
+        return (
+            ChecklistVerification::Manual,
+            ChecklistItemStatus::Pending,
+            Some(format!("Firmware version unknown for: {}", unknown.join(", "))),
+        );
+    }
+    let mismatched: Vec<String> = nodes
+        .iter()
+        .filter_map(|(tag, version)| {
+            let version = version.as_deref()?;
+            (version != baseline).then(|| format!("{} ({})", tag, version))
+        })
+        .collect();
+    if mismatched.is_empty() {
+        (ChecklistVerification::Automated, ChecklistItemStatus::Passed, None)
+    } else {
+        (
+            ChecklistVerification::Automated,
+            ChecklistItemStatus::Failed,
+            Some(format!("Not on baseline {}: {}", baseline, mismatched.join(", "))),
+        )
+    }
+}
+
+fn evaluate_storage(redundancy_factor: Option<i64>) -> Evaluation {
+    match redundancy_factor {
+        Some(rf) if rf >= 2 => (
+            ChecklistVerification::Automated,
+            ChecklistItemStatus::Passed,
+            Some(format!("Redundancy factor {}", rf)),
+        ),
+        Some(rf) => (
+            ChecklistVerification::Automated,
+            ChecklistItemStatus::Failed,
+            Some(format!("Redundancy factor {} tolerates no node failure", rf)),
+        ),
+        None => (
+            ChecklistVerification::Manual,
+            ChecklistItemStatus::Pending,
+            Some("No integration reports storage resiliency for this cluster; confirm it and sign off".to_string()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(tag: &str, version: Option<&str>) -> (String, Option<String>) {
+        (tag.to_string(), version.map(str::to_string))
+    }
+
+    #[test]
+    fn test_firmware_needs_baseline_and_known_versions() {
+        let nodes = [node("N1", Some("2.1")), node("N2", Some("2.0"))];
+        assert_eq!(evaluate_firmware(None, &nodes).0, ChecklistVerification::Manual);
+        assert_eq!(
+            evaluate_firmware(Some("2.1"), &[node("N1", Some("2.1")), node("N2", None)]).1,
+            ChecklistItemStatus::Pending
+        );
+
+        let (verification, status, detail) = evaluate_firmware(Some("2.1"), &nodes);
+        assert_eq!(verification, ChecklistVerification::Automated);
+        assert_eq!(status, ChecklistItemStatus::Failed);
+        assert_eq!(detail.unwrap(), "Not on baseline 2.1: N2 (2.0)");
+    }
+
+    #[test]
+    fn test_sign_off_survives_until_a_check_can_decide() {
+        let mut item = BuildChecklistItem {
+            kind: ChecklistItemKind::StorageResiliency,
+            title: ChecklistItemKind::StorageResiliency.title().to_string(),
+            verification: ChecklistVerification::Manual,
+            status: ChecklistItemStatus::SignedOff,
+            detail: None,
+            signed_off_by: Some("ops".to_string()),
+            signed_off_at: Some(Utc::now()),
+            notes: None,
+        };
+
+        apply_evaluation(&mut item, evaluate_storage(None));
+        assert_eq!(item.status, ChecklistItemStatus::SignedOff);
+
+        apply_evaluation(&mut item, evaluate_storage(Some(1)));
+        assert_eq!(item.status, ChecklistItemStatus::Failed);
+        assert!(item.signed_off_by.is_none());
+    }
+}
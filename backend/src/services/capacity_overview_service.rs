@@ -0,0 +1,586 @@
+//! Capacity Overview Service
+//!
+//! Tenant-wide capacity roll-up for the executive dashboard: per-cluster
+//! utilization and headroom across every migration project, projected
+//! exhaustion dates and the top bottlenecks.
+//!
+//! Exhaustion dates come from a least-squares trend over daily utilization
+//! snapshots in `capacity_snapshots`. A snapshot per cluster is recorded the
+//! first time the overview is computed each day, so the forecast sharpens as
+//! history accumulates; clusters with under two days of history have none.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::models::migration_wizard_models::{MigrationWizardCluster, MigrationWizardProject};
+use crate::database::TracedQuery;
+
+/// Days of snapshot history used for the trend line
+const FORECAST_WINDOW_DAYS: i64 = 90;
+/// Exhaustion further out than this is reported as "not projected"
+const MAX_FORECAST_DAYS: f64 = 3650.0;
+
+pub struct CapacityOverviewService {
+    db: Database,
+}
+
+// =============================================================================
+// REQUEST/RESPONSE TYPES
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CapacityOverviewQuery {
+    /// Utilization considered "full" for headroom and bottlenecks (default 80)
+    pub threshold_percent: Option<f64>,
+    /// Number of bottlenecks to return (default 10)
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum CapacityResource {
+    Cpu,
+    Memory,
+    Storage,
+}
+
+impl CapacityResource {
+    const ALL: [CapacityResource; 3] = [CapacityResource::Cpu, CapacityResource::Memory, CapacityResource::Storage];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CapacityResource::Cpu => "CPU",
+            CapacityResource::Memory => "memory",
+            CapacityResource::Storage => "storage",
+        }
+    }
+}
+
+/// Usage of one resource; CPU in vCPUs, memory and storage in GB
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsage {
+    pub used: f64,
+    pub capacity: f64,
+    pub utilization_percent: f64,
+    /// Capacity left before the threshold is reached (negative when over it)
+    pub headroom: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExhaustionForecast {
+    pub resource: CapacityResource,
+    pub exhaustion_date: DateTime<Utc>,
+    pub days_remaining: f64,
+    pub growth_percent_per_day: f64,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterCapacityOverview {
+    pub project_id: String,
+    pub project_name: String,
+    pub cluster_id: String,
+    pub cluster_name: String,
+    pub vm_count: usize,
+    pub cpu: ResourceUsage,
+    pub memory: ResourceUsage,
+    pub storage: ResourceUsage,
+    /// Resource with the highest utilization
+    pub constraining_resource: CapacityResource,
+    /// Earliest projected exhaustion across resources
+    pub projected_exhaustion: Option<ExhaustionForecast>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum BottleneckSeverity {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapacityBottleneckSummary {
+    pub project_name: String,
+    pub cluster_id: String,
+    pub cluster_name: String,
+    pub resource: CapacityResource,
+    pub severity: BottleneckSeverity,
+    pub utilization_percent: f64,
+    pub headroom: f64,
+    pub exhaustion_date: Option<DateTime<Utc>>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapacityTotals {
+    pub projects: usize,
+    pub clusters: usize,
+    pub vms_placed: usize,
+    pub cpu: ResourceUsage,
+    pub memory: ResourceUsage,
+    pub storage: ResourceUsage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapacityOverview {
+    pub threshold_percent: f64,
+    pub totals: CapacityTotals,
+    pub clusters: Vec<ClusterCapacityOverview>,
+    pub bottlenecks: Vec<CapacityBottleneckSummary>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// One day's utilization for a cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CapacitySnapshot {
+    cluster_id: Thing,
+    project_id: Thing,
+    cpu_percent: f64,
+    memory_percent: f64,
+    storage_percent: f64,
+    captured_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlacementAllocation {
+    cluster_id: Thing,
+    allocated_cpu: i32,
+    allocated_memory_mb: i32,
+    allocated_storage_gb: f64,
+}
+
+// =============================================================================
+// SERVICE IMPLEMENTATION
+// =============================================================================
+
+impl CapacityOverviewService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Build the overview for the caller's tenant. Projects without a tenant
+    /// are shared and included for everyone.
+    pub async fn overview(&self, tenant_id: Option<&str>, query: &CapacityOverviewQuery) -> Result<CapacityOverview> {
+        let threshold = query.threshold_percent.unwrap_or(80.0).clamp(1.0, 100.0);
+        let top = query.top.unwrap_or(10).min(100);
+        let now = Utc::now();
+
+        let projects: Vec<MigrationWizardProject> = self
+            .db
+            .query("SELECT * FROM migration_wizard_project WHERE status != 'archived'")
+            .traced("capacity_overview_service.overview")
+            .await
+            .context("Failed to query projects")?
+            .take(0)
+            .context("Failed to parse projects")?;
+        let projects: Vec<MigrationWizardProject> = projects
+            .into_iter()
+            .filter(|p| match (tenant_id, &p.tenant_id) {
+                (Some(tenant), Some(owner)) => owner == tenant,
+                _ => true,
+            })
+            .collect();
+        let project_ids: Vec<Thing> = projects.iter().filter_map(|p| p.id.clone()).collect();
+        let project_names: HashMap<&Thing, &str> = projects
+            .iter()
+            .filter_map(|p| p.id.as_ref().map(|id| (id, p.name.as_str())))
+            .collect();
+
+        let clusters: Vec<MigrationWizardCluster> = self
+            .db
+            .query("SELECT * FROM migration_wizard_cluster WHERE project_id INSIDE $projects ORDER BY name ASC")
+            .bind(("projects", project_ids.clone()))
+            .traced("capacity_overview_service.overview")
+            .await
+            .context("Failed to query clusters")?
+            .take(0)
+            .context("Failed to parse clusters")?;
+
+        let placements: Vec<PlacementAllocation> = self
+            .db
+            .query(
+                "SELECT cluster_id, allocated_cpu, allocated_memory_mb, allocated_storage_gb \
+                 FROM migration_wizard_placement WHERE project_id INSIDE $projects",
+            )
+            .bind(("projects", project_ids))
+            .traced("capacity_overview_service.overview")
+            .await
+            .context("Failed to query placements")?
+            .take(0)
+            .context("Failed to parse placements")?;
+
+        // cluster → (vms, vCPU, memory GB, storage GB)
+        let mut allocated: HashMap<Thing, (usize, f64, f64, f64)> = HashMap::new();
+        for placement in placements {
+            let entry = allocated.entry(placement.cluster_id).or_default();
+            entry.0 += 1;
+            entry.1 += f64::from(placement.allocated_cpu);
+            entry.2 += placement.allocated_memory().as_gib();
+            entry.3 += placement.allocated_storage_gb;
+        }
+
+        let cluster_ids: Vec<Thing> = clusters.iter().filter_map(|c| c.id.clone()).collect();
+        let history = self.snapshot_history(&cluster_ids, now).await?;
+
+        let mut overviews = Vec::with_capacity(clusters.len());
+        let mut todays_snapshots = Vec::new();
+        for cluster in &clusters {
+            let Some(cluster_id) = cluster.id.clone() else { continue };
+            let (vm_count, cpu_used, memory_used, storage_used) =
+                allocated.get(&cluster_id).copied().unwrap_or_default();
+
+            let cpu = usage(cpu_used, f64::from(cluster.total_cores) * cluster.cpu_oversubscription_ratio, threshold);
+            let memory = usage(memory_used, cluster.allocatable_memory().as_gib(), threshold);
+            let storage = usage(storage_used, cluster.storage_capacity().as_gib(), threshold);
+
+            let snapshot = CapacitySnapshot {
+                cluster_id: cluster_id.clone(),
+                project_id: cluster.project_id.clone(),
+                cpu_percent: cpu.utilization_percent,
+                memory_percent: memory.utilization_percent,
+                storage_percent: storage.utilization_percent,
+                captured_at: now,
+            };
+            let mut samples = history.get(&cluster_id).cloned().unwrap_or_default();
+            if !samples.iter().any(|s| s.captured_at.date_naive() == now.date_naive()) {
+                todays_snapshots.push(snapshot.clone());
+            }
+            samples.retain(|s| s.captured_at.date_naive() != now.date_naive());
+            samples.push(snapshot);
+
+            let projected_exhaustion = CapacityResource::ALL
+                .iter()
+                .filter_map(|&resource| {
+                    let series: Vec<(DateTime<Utc>, f64)> = samples
+                        .iter()
+                        .map(|s| (s.captured_at, snapshot_percent(s, resource)))
+                        .collect();
+                    project_exhaustion(resource, &series, now)
+                })
+                .min_by(|a, b| a.days_remaining.total_cmp(&b.days_remaining));
+
+            let constraining_resource = [
+                (CapacityResource::Cpu, &cpu),
+                (CapacityResource::Memory, &memory),
+                (CapacityResource::Storage, &storage),
+            ]
+            .into_iter()
+            .max_by(|a, b| a.1.utilization_percent.total_cmp(&b.1.utilization_percent))
+            .map(|(resource, _)| resource)
+            .unwrap_or(CapacityResource::Cpu);
+
+            overviews.push(ClusterCapacityOverview {
+                project_id: cluster.project_id.to_string(),
+                project_name: project_names
+                    .get(&cluster.project_id)
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+                cluster_id: cluster_id.to_string(),
+                cluster_name: cluster.name.clone(),
+                vm_count,
+                cpu,
+                memory,
+                storage,
+                constraining_resource,
+                projected_exhaustion,
+            });
+        }
+
+        self.record_snapshots(todays_snapshots).await;
+
+        let totals = totals(projects.len(), &overviews, threshold);
+        let bottlenecks = bottlenecks(&overviews, threshold, top, now);
+
+        Ok(CapacityOverview {
+            threshold_percent: threshold,
+            totals,
+            clusters: overviews,
+            bottlenecks,
+            generated_at: now,
+        })
+    }
+
+    async fn snapshot_history(
+        &self,
+        clusters: &[Thing],
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<Thing, Vec<CapacitySnapshot>>> {
+        let snapshots: Vec<CapacitySnapshot> = self
+            .db
+            .query(
+                "SELECT * FROM capacity_snapshots WHERE cluster_id INSIDE $clusters AND captured_at >= $since \
+                 ORDER BY captured_at ASC",
+            )
+            .bind(("clusters", clusters.to_vec()))
+            .bind(("since", now - Duration::days(FORECAST_WINDOW_DAYS)))
+            .traced("capacity_overview_service.snapshot_history")
+            .await
+            .context("Failed to query capacity snapshots")?
+            .take(0)
+            .context("Failed to parse capacity snapshots")?;
+
+        let mut history: HashMap<Thing, Vec<CapacitySnapshot>> = HashMap::new();
+        for snapshot in snapshots {
+            history.entry(snapshot.cluster_id.clone()).or_default().push(snapshot);
+        }
+        Ok(history)
+    }
+
+    /// Best effort: a failed write only costs one day of forecast history
+    async fn record_snapshots(&self, snapshots: Vec<CapacitySnapshot>) {
+        for snapshot in snapshots {
+            let key = format!("{}_{}", snapshot.cluster_id.id, snapshot.captured_at.format("%Y%m%d"));
+            let result: Result<Option<CapacitySnapshot>, _> =
+                self.db.update(("capacity_snapshots", key.as_str())).content(&snapshot).await;
+            if let Err(e) = result {
+                tracing::warn!("Failed to record capacity snapshot for {}: {}", snapshot.cluster_id, e);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// CALCULATIONS
+// =============================================================================
+
+fn usage(used: f64, capacity: f64, threshold: f64) -> ResourceUsage {
+    let utilization_percent = if capacity > 0.0 { used / capacity * 100.0 } else { 0.0 };
+    ResourceUsage {
+        used: round1(used),
+        capacity: round1(capacity),
+        utilization_percent: round1(utilization_percent),
+        headroom: round1(capacity * threshold / 100.0 - used),
+    }
+}
+
+fn snapshot_percent(snapshot: &CapacitySnapshot, resource: CapacityResource) -> f64 {
+    match resource {
+        CapacityResource::Cpu => snapshot.cpu_percent,
+        CapacityResource::Memory => snapshot.memory_percent,
+        CapacityResource::Storage => snapshot.storage_percent,
+    }
+}
+
+/// Fit a least-squares line through (day, utilization %) and project when it
+/// reaches 100%. Needs at least two samples spanning a day or more and a
+/// positive trend.
+fn project_exhaustion(
+    resource: CapacityResource,
+    series: &[(DateTime<Utc>, f64)],
+    now: DateTime<Utc>,
+) -> Option<ExhaustionForecast> {
+    let (first, _) = series.first()?;
+    let (last, current) = *series.last()?;
+    if series.len() < 2 || last - *first < Duration::days(1) {
+        return None;
+    }
+
+    let points: Vec<(f64, f64)> = series
+        .iter()
+        .map(|(at, pct)| ((*at - *first).num_seconds() as f64 / 86_400.0, *pct))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance == 0.0 {
+        return None;
+    }
+    let slope = covariance / variance;
+
+    let days_remaining = if current >= 100.0 {
+        0.0
+    } else if slope > 0.0 {
+        (100.0 - current) / slope
+    } else {
+        return None;
+    };
+    if days_remaining > MAX_FORECAST_DAYS {
+        return None;
+    }
+
+    Some(ExhaustionForecast {
+        resource,
+        exhaustion_date: now + Duration::seconds((days_remaining * 86_400.0) as i64),
+        days_remaining: round1(days_remaining),
+        growth_percent_per_day: (slope * 1000.0).round() / 1000.0,
+        samples: series.len(),
+    })
+}
+
+fn totals(projects: usize, clusters: &[ClusterCapacityOverview], threshold: f64) -> CapacityTotals {
+    let sum = |pick: fn(&ClusterCapacityOverview) -> &ResourceUsage| {
+        let (used, capacity) = clusters
+            .iter()
+            .map(pick)
+            .fold((0.0, 0.0), |(u, c), r| (u + r.used, c + r.capacity));
+        usage(used, capacity, threshold)
+    };
+
+    CapacityTotals {
+        projects,
+        clusters: clusters.len(),
+        vms_placed: clusters.iter().map(|c| c.vm_count).sum(),
+        cpu: sum(|c| &c.cpu),
+        memory: sum(|c| &c.memory),
+        storage: sum(|c| &c.storage),
+    }
+}
+
+/// Cluster resources over the threshold or projected to run out within 90
+/// days, most severe and soonest first
+fn bottlenecks(
+    clusters: &[ClusterCapacityOverview],
+    threshold: f64,
+    top: usize,
+    now: DateTime<Utc>,
+) -> Vec<CapacityBottleneckSummary> {
+    let mut found = Vec::new();
+    for cluster in clusters {
+        for (resource, usage) in [
+            (CapacityResource::Cpu, &cluster.cpu),
+            (CapacityResource::Memory, &cluster.memory),
+            (CapacityResource::Storage, &cluster.storage),
+        ] {
+            let exhaustion = cluster
+                .projected_exhaustion
+                .as_ref()
+                .filter(|f| f.resource == resource)
+                .map(|f| f.exhaustion_date);
+            let days_left = exhaustion.map(|d| (d - now).num_days());
+
+            let severity = if usage.utilization_percent >= 95.0 || days_left.is_some_and(|d| d <= 30) {
+                BottleneckSeverity::Critical
+            } else if usage.utilization_percent >= threshold || days_left.is_some_and(|d| d <= 90) {
+                BottleneckSeverity::Warning
+            } else {
+                continue;
+            };
+
+            let mut message = format!(
+                "{} {} at {:.1}% of capacity",
+                cluster.cluster_name,
+                resource.label(),
+                usage.utilization_percent
+            );
+            if let Some(days) = days_left {
+                message.push_str(&format!(", projected full in {} days", days));
+            }
+
+            found.push(CapacityBottleneckSummary {
+                project_name: cluster.project_name.clone(),
+                cluster_id: cluster.cluster_id.clone(),
+                cluster_name: cluster.cluster_name.clone(),
+                resource,
+                severity,
+                utilization_percent: usage.utilization_percent,
+                headroom: usage.headroom,
+                exhaustion_date: exhaustion,
+                message,
+            });
+        }
+    }
+
+    found.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| match (a.exhaustion_date, b.exhaustion_date) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+            .then_with(|| b.utilization_percent.total_cmp(&a.utilization_percent))
+    });
+    found.truncate(top);
+    found
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_usage_headroom_against_threshold() {
+        let u = usage(60.0, 100.0, 80.0);
+        assert_eq!(u.utilization_percent, 60.0);
+        assert_eq!(u.headroom, 20.0);
+        assert_eq!(usage(90.0, 100.0, 80.0).headroom, -10.0);
+        assert_eq!(usage(5.0, 0.0, 80.0).utilization_percent, 0.0);
+    }
+
+    #[test]
+    fn test_project_exhaustion_from_linear_growth() {
+        // +2% per day, 70% today → full in 15 days
+        let series: Vec<_> = (0..6).map(|i| (day(1 + i), 60.0 + 2.0 * i as f64)).collect();
+        let forecast = project_exhaustion(CapacityResource::Memory, &series, day(6)).unwrap();
+        assert_eq!(forecast.days_remaining, 15.0);
+        assert_eq!(forecast.exhaustion_date, day(21));
+        assert_eq!(forecast.samples, 6);
+
+        // Flat, shrinking or too little history: no projection
+        let flat = [(day(1), 50.0), (day(5), 50.0)];
+        assert!(project_exhaustion(CapacityResource::Cpu, &flat, day(5)).is_none());
+        let shrinking = [(day(1), 50.0), (day(5), 40.0)];
+        assert!(project_exhaustion(CapacityResource::Cpu, &shrinking, day(5)).is_none());
+        assert!(project_exhaustion(CapacityResource::Cpu, &[(day(1), 99.0)], day(1)).is_none());
+    }
+
+    #[test]
+    fn test_bottlenecks_rank_critical_and_soonest_first() {
+        let now = day(1);
+        let cluster = |name: &str, cpu: f64, memory: f64, exhaustion_days: Option<i64>| ClusterCapacityOverview {
+            project_id: "migration_wizard_project:p1".to_string(),
+            project_name: "DC Exit".to_string(),
+            cluster_id: format!("migration_wizard_cluster:{}", name),
+            cluster_name: name.to_string(),
+            vm_count: 10,
+            cpu: usage(cpu, 100.0, 80.0),
+            memory: usage(memory, 100.0, 80.0),
+            storage: usage(10.0, 100.0, 80.0),
+            constraining_resource: CapacityResource::Cpu,
+            projected_exhaustion: exhaustion_days.map(|d| ExhaustionForecast {
+                resource: CapacityResource::Memory,
+                exhaustion_date: now + Duration::days(d),
+                days_remaining: d as f64,
+                growth_percent_per_day: 1.0,
+                samples: 5,
+            }),
+        };
+        let clusters = vec![
+            cluster("a", 85.0, 40.0, None),
+            cluster("b", 50.0, 70.0, Some(20)),
+            cluster("c", 97.0, 20.0, None),
+            cluster("d", 30.0, 30.0, None),
+        ];
+
+        let found = bottlenecks(&clusters, 80.0, 10, now);
+        let order: Vec<(&str, CapacityResource, BottleneckSeverity)> = found
+            .iter()
+            .map(|b| (b.cluster_name.as_str(), b.resource, b.severity))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("b", CapacityResource::Memory, BottleneckSeverity::Critical),
+                ("c", CapacityResource::Cpu, BottleneckSeverity::Critical),
+                ("a", CapacityResource::Cpu, BottleneckSeverity::Warning),
+            ]
+        );
+        assert_eq!(bottlenecks(&clusters, 80.0, 1, now).len(), 1);
+    }
+}
@@ -10,7 +10,19 @@ use std::collections::HashMap;
 use surrealdb::sql::Thing;
 
 use crate::database::Database;
+use crate::models::migration_wizard_models::MigrationWizardVM;
 use crate::models::project_models::*;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::source_inventory;
+use core_engine::consolidation::{
+    ConsolidationConstraints, ConsolidationOptimizer, ConsolidationResult, NodeProfile,
+    SourceClusterDemand,
+};
+use core_engine::units::Bytes;
+use core_engine::vendor_data::VendorDataManager;
+use crate::database::TracedQuery;
+use crate::models::usage_telemetry::UsageFeature;
+use crate::services::usage_telemetry_service;
 
 pub struct CapacityPlannerService {
     db: Database,
@@ -88,6 +100,24 @@ pub struct PlacementSummary {
     pub clusters_used: i32,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConsolidationRequest {
+    /// Migration wizard project whose VMs are grouped by source cluster
+    pub project_id: Option<String>,
+    /// Explicit source demand; takes precedence over `project_id`
+    pub source_clusters: Option<Vec<SourceClusterDemand>>,
+    /// Vendor models to consider; every catalog model when omitted
+    pub candidate_models: Option<Vec<CandidateModel>>,
+    #[serde(default)]
+    pub constraints: ConsolidationConstraints,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CandidateModel {
+    pub vendor: String,
+    pub model_id: String,
+}
+
 // =============================================================================
 // SERVICE IMPLEMENTATION
 // =============================================================================
@@ -106,6 +136,8 @@ impl CapacityPlannerService {
         &self,
         request: CapacityPlanRequest,
     ) -> Result<CapacityPlanResponse> {
+        usage_telemetry_service::record(UsageFeature::CapacitySizing);
+
         // 1. Fetch source workload data
         let source_summary = self
             .fetch_source_summary(&request.source_upload_id, &request.source_filters)
@@ -181,6 +213,7 @@ impl CapacityPlannerService {
             .db
             .query(&query)
             .bind(("upload_id", upload_id))
+            .traced("capacity_planner_service.fetch_source_summary")
             .await
             .context("Failed to query RVTools data")?;
 
@@ -189,8 +222,8 @@ impl CapacityPlannerService {
         // Aggregate VM resources
         let mut total_cpu_cores = 0;
         let mut total_cpu_ghz = 0.0;
-        let mut total_memory_gb = 0;
-        let mut total_storage_gb: i64 = 0;
+        let mut total_memory = Bytes::ZERO;
+        let mut total_storage = Bytes::ZERO;
         let mut vm_count = 0;
 
         for record in data {
@@ -201,14 +234,15 @@ impl CapacityPlannerService {
                         vm_count += 1;
                     }
                 }
+                // Summed before converting; per-VM whole GB dropped up to 1 GB per VM
                 "Memory" | "Memory MB" => {
-                    if let Ok(mem_mb) = record.raw_value.parse::<i32>() {
-                        total_memory_gb += mem_mb / 1024;
+                    if let Ok(mib) = record.raw_value.parse::<f64>() {
+                        total_memory += Bytes::from_mib(mib);
                     }
                 }
                 "Provisioned MB" | "Provisioned MiB" => {
-                    if let Ok(storage_mb) = record.raw_value.parse::<i64>() {
-                        total_storage_gb += storage_mb / 1024;
+                    if let Ok(mib) = record.raw_value.parse::<f64>() {
+                        total_storage += Bytes::from_mib(mib);
                     }
                 }
                 _ => {}
@@ -219,8 +253,8 @@ impl CapacityPlannerService {
             total_vms: vm_count,
             total_cpu_cores,
             total_cpu_ghz,
-            total_memory_gb,
-            total_storage_gb,
+            total_memory_gb: total_memory.gib_floor() as i32,
+            total_storage_gb: total_storage.gib_floor() as i64,
             avg_cpu_utilization: None,
             avg_memory_utilization: None,
         })
@@ -266,6 +300,13 @@ impl CapacityPlannerService {
         // Calculate HA reserved capacity
         let ha_reserved = self.calculate_ha_reserve(&cluster.capacity_totals, ha_policy, cluster.node_count);
 
+        // Clusters on a library policy are rated by it, not the plan-wide ratios
+        let overcommit = if cluster.overcommit_policy_id.is_some() {
+            &cluster.overcommit_ratios
+        } else {
+            overcommit
+        };
+
         // Calculate available capacity with overcommit
         let mut available_capacity = cluster.capacity_totals.clone();
         available_capacity.cpu_cores = ((available_capacity.cpu_cores - ha_reserved.cpu_cores) as f32 * overcommit.cpu_ratio) as i32;
@@ -504,6 +545,86 @@ impl CapacityPlannerService {
         recommendations
     }
 
+    // =========================================================================
+    // CONSOLIDATION OPTIMIZATION
+    // =========================================================================
+
+    /// Search vendor hardware builds for the cheapest cluster designs
+    /// that absorb the source clusters within the given constraints
+    pub async fn optimize_consolidation(
+        &self,
+        request: ConsolidationRequest,
+    ) -> Result<ConsolidationResult> {
+        let sources = match (request.source_clusters, request.project_id) {
+            (Some(sources), _) => sources,
+            (None, Some(project_id)) => {
+                let wizard = MigrationWizardService::new(self.db.clone());
+                let vms = wizard.get_project_vms(&project_id, None).await?;
+                let inventory = wizard.get_source_inventory(&project_id).await?;
+                Self::source_demand(&vms, &source_inventory::datastore_usage_by_cluster(&inventory))
+            }
+            (None, None) => anyhow::bail!("Either project_id or source_clusters is required"),
+        };
+
+        let vendor_data = VendorDataManager::new();
+        let candidates = match request.candidate_models {
+            Some(candidates) => candidates,
+            None => vendor_data
+                .get_all_server_models()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load vendor models: {}", e))?
+                .into_iter()
+                .map(|m| CandidateModel { vendor: m.vendor, model_id: m.model_id })
+                .collect(),
+        };
+
+        let mut profiles: Vec<NodeProfile> = Vec::new();
+        for candidate in &candidates {
+            match vendor_data.get_model_specifications(&candidate.vendor, &candidate.model_id).await {
+                Ok(specs) => profiles.extend(NodeProfile::from_specifications(&specs)),
+                Err(e) => tracing::warn!(
+                    "Skipping {} {}: no specifications ({})",
+                    candidate.vendor,
+                    candidate.model_id,
+                    e
+                ),
+            }
+        }
+
+        ConsolidationOptimizer::optimize(&sources, &profiles, &request.constraints)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+
+    /// Aggregate non-template VMs per source cluster. Clusters whose VMs
+    /// report no provisioned storage fall back to their datastores' usage.
+    fn source_demand(vms: &[MigrationWizardVM], datastore_usage_gb: &HashMap<String, f64>) -> Vec<SourceClusterDemand> {
+        let mut clusters: HashMap<String, SourceClusterDemand> = HashMap::new();
+        for vm in vms.iter().filter(|vm| vm.template != Some(true)) {
+            let name = vm.cluster.clone().unwrap_or_else(|| "Unassigned".to_string());
+            let demand = clusters.entry(name.clone()).or_insert_with(|| SourceClusterDemand {
+                name,
+                vm_count: 0,
+                vcpus: 0,
+                memory_gb: 0.0,
+                storage_gb: 0.0,
+            });
+            demand.vm_count += 1;
+            demand.vcpus += vm.cpus.max(0) as u32;
+            demand.memory_gb += vm.memory().as_gib();
+            demand.storage_gb += vm.provisioned().unwrap_or_default().as_gib();
+        }
+
+        for demand in clusters.values_mut() {
+            if demand.storage_gb == 0.0 {
+                demand.storage_gb = datastore_usage_gb.get(&demand.name).copied().unwrap_or_default();
+            }
+        }
+
+        let mut sources: Vec<_> = clusters.into_values().collect();
+        sources.sort_by(|a, b| a.name.cmp(&b.name));
+        sources
+    }
+
     // =========================================================================
     // VM PLACEMENT PLANNING
     // =========================================================================
@@ -9,11 +9,13 @@
 //! - Growth headroom
 
 use serde::{Deserialize, Serialize};
-use surrealdb::{engine::local::Db, Surreal};
+use crate::database::Db;
+use surrealdb::Surreal;
 
 use crate::models::workflow::{
     CapacityValidationResult, ResourceStatus, ResourceValidation, ValidationStatus,
 };
+use crate::database::TracedQuery;
 
 /// Request for capacity validation
 #[derive(Debug, Serialize, Deserialize)]
@@ -332,7 +334,7 @@ impl CapacityValidationService {
 
         // Query for cluster data
         let query = format!("SELECT * FROM cluster WHERE id = 'cluster:{}'", cluster_id);
-        let mut response = db.query(&query).await?;
+        let mut response = db.query(&query).traced("capacity_validation_service.fetch_workload_summary").await?;
         let _clusters: Vec<serde_json::Value> = response.take(0)?;
 
         // For now, return mock data
@@ -2,12 +2,14 @@
 // Configuration Management Database operations with graph traversal
 
 use crate::models::cmdb::*;
+use crate::database::TracedQuery;
+use crate::services::contract_service::coverage_for_ci;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
-use surrealdb::engine::local::Db;
+use crate::database::Db;
 use surrealdb::sql::Thing;
 use surrealdb::Surreal;
 
@@ -36,6 +38,7 @@ impl CMDBService {
         let existing: Option<ConfigurationItem> = db
             .query("SELECT * FROM configuration_items WHERE ci_id = $ci_id LIMIT 1")
             .bind(("ci_id", &ci_id))
+            .traced("cmdb.create_ci")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -76,6 +79,7 @@ impl CMDBService {
             warranty_expiry: request.warranty_expiry,
             end_of_life: request.end_of_life,
             decommission_date: None,
+            financials: request.financials,
             tags: request.tags,
             created_at: now,
             updated_at: now,
@@ -131,6 +135,7 @@ impl CMDBService {
         let count_result: Vec<JsonValue> = db
             .query("SELECT count() FROM configuration_items WHERE ci_id CONTAINS $prefix GROUP ALL")
             .bind(("prefix", prefix))
+            .traced("cmdb.generate_ci_id")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -166,6 +171,7 @@ impl CMDBService {
         let ci: Option<ConfigurationItem> = db
             .query("SELECT * FROM configuration_items WHERE ci_id = $ci_id LIMIT 1")
             .bind(("ci_id", ci_id))
+            .traced("cmdb.get_ci_by_ci_id")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -193,6 +199,7 @@ impl CMDBService {
         let history: Vec<CIHistory> = db
             .query("SELECT * FROM ci_history WHERE ci_id = $ci_id ORDER BY created_at DESC LIMIT 50")
             .bind(("ci_id", &ci_thing))
+            .traced("cmdb.get_ci_detail")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -202,16 +209,23 @@ impl CMDBService {
         let linked_tickets: Vec<Thing> = db
             .query("SELECT id FROM tickets WHERE affected_ci = $ci_id")
             .bind(("ci_id", &ci_thing))
+            .traced("cmdb.get_ci_detail")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
             .map_err(|e| e.to_string())?;
 
+        // Get contract coverage
+        let contracts = coverage_for_ci(&db, &ci_thing)
+            .await
+            .map_err(|e| e.to_string())?;
+
         Ok(Some(CIDetailResponse {
             ci,
             relationships,
             history,
             linked_tickets,
+            contracts,
         }))
     }
 
@@ -226,6 +240,10 @@ impl CMDBService {
         let existing = Self::get_ci(db.clone(), id).await?
             .ok_or_else(|| "CI not found".to_string())?;
 
+        if request.financials.is_some() && existing.ci_class != CIClass::Hardware {
+            return Err("Financials can only be recorded on hardware CIs".to_string());
+        }
+
         let now = Utc::now();
         let mut changes: Vec<(String, String, String)> = Vec::new();
 
@@ -251,6 +269,7 @@ impl CMDBService {
         track_change!(owner_id, "owner_id");
         track_change!(support_group, "support_group");
         track_change!(ip_address, "ip_address");
+        track_change!(financials, "financials");
 
         // Build update query
         let updated: Option<ConfigurationItem> = db
@@ -276,6 +295,7 @@ impl CMDBService {
                     install_date = $install_date,
                     warranty_expiry = $warranty_expiry,
                     end_of_life = $end_of_life,
+                    financials = $financials,
                     updated_at = $updated_at,
                     updated_by = $updated_by
                 WHERE id = $id
@@ -302,8 +322,10 @@ impl CMDBService {
             .bind(("install_date", request.install_date.or(existing.install_date)))
             .bind(("warranty_expiry", request.warranty_expiry.or(existing.warranty_expiry)))
             .bind(("end_of_life", request.end_of_life.or(existing.end_of_life)))
+            .bind(("financials", request.financials.or(existing.financials)))
             .bind(("updated_at", now))
             .bind(("updated_by", user_id))
+            .traced("cmdb.update_ci")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -345,6 +367,7 @@ impl CMDBService {
             .bind(("status", CIStatus::Disposed))
             .bind(("date", Utc::now()))
             .bind(("user", user_id))
+            .traced("cmdb.delete_ci")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -354,6 +377,7 @@ impl CMDBService {
         let _: Vec<CIRelationship> = db
             .query("UPDATE ci_relationships SET is_active = false WHERE source_id = $id OR target_id = $id")
             .bind(("id", &ci_thing))
+            .traced("cmdb.delete_ci")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -459,6 +483,7 @@ impl CMDBService {
         }
 
         let count_result: Vec<JsonValue> = count_stmt
+            .traced("cmdb.search_cis")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -482,6 +507,7 @@ impl CMDBService {
         }
 
         let items: Vec<ConfigurationItem> = items_stmt
+            .traced("cmdb.search_cis")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -521,6 +547,7 @@ impl CMDBService {
             .bind(("source", &source))
             .bind(("target", &target))
             .bind(("type", &request.relationship_type))
+            .traced("cmdb.create_relationship")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -592,6 +619,7 @@ impl CMDBService {
             .query("UPDATE ci_relationships SET is_active = false, updated_at = $now WHERE id = $id")
             .bind(("id", &rel_thing))
             .bind(("now", Utc::now()))
+            .traced("cmdb.delete_relationship")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -621,6 +649,7 @@ impl CMDBService {
         let relationships: Vec<CIRelationship> = db
             .query("SELECT * FROM ci_relationships WHERE (source_id = $ci OR target_id = $ci) AND is_active = true")
             .bind(("ci", ci_id))
+            .traced("cmdb.get_ci_relationships")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -705,6 +734,7 @@ impl CMDBService {
         let impacted: Vec<JsonValue> = db
             .query(&impacted_query)
             .bind(("ci_id", &ci_thing))
+            .traced("cmdb.analyze_impact")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -788,6 +818,7 @@ impl CMDBService {
             .query("SELECT * FROM ci_history WHERE ci_id = $ci_id ORDER BY created_at DESC LIMIT $limit")
             .bind(("ci_id", &ci_thing))
             .bind(("limit", limit))
+            .traced("cmdb.get_ci_history")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -807,6 +838,7 @@ impl CMDBService {
         // Total CIs by class
         let class_counts: Vec<JsonValue> = db
             .query("SELECT ci_class, count() as count FROM configuration_items WHERE status != 'DISPOSED' GROUP BY ci_class")
+            .traced("cmdb.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -825,6 +857,7 @@ impl CMDBService {
         // Total CIs by status
         let status_counts: Vec<JsonValue> = db
             .query("SELECT status, count() as count FROM configuration_items GROUP BY status")
+            .traced("cmdb.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -843,6 +876,7 @@ impl CMDBService {
         // Total relationships
         let rel_count: Vec<JsonValue> = db
             .query("SELECT count() FROM ci_relationships WHERE is_active = true GROUP ALL")
+            .traced("cmdb.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -0,0 +1,272 @@
+use chrono::{DateTime, Utc};
+use surrealdb::sql::Thing;
+
+use super::grpc::proto;
+use super::service::CollectorError;
+use crate::models::collector::VmMetricSample;
+use crate::models::migration_wizard_models::*;
+
+/// Most VMs one snapshot may hold
+pub const MAX_SNAPSHOT_VMS: usize = 100_000;
+/// Most hosts, clusters, datastores, pools, snapshots and tools rows together
+pub const MAX_SNAPSHOT_INVENTORY_ROWS: usize = 500_000;
+
+/// The chunks of one `UploadInventory` stream, gathered until the last one
+#[derive(Debug, Default)]
+pub struct InventorySnapshot {
+    pub project_id: String,
+    pub snapshot_id: String,
+    pub vms: Vec<MigrationWizardVM>,
+    pub inventory: SourceInventory,
+    pub complete: bool,
+}
+
+impl InventorySnapshot {
+    /// Add a chunk. Every chunk must name the same project and snapshot,
+    /// and none may follow the last.
+    pub fn push(&mut self, chunk: proto::InventoryChunk) -> Result<(), CollectorError> {
+        if self.complete {
+            return Err(CollectorError::Validation("Chunk received after the last chunk".to_string()));
+        }
+        if self.project_id.is_empty() && self.snapshot_id.is_empty() {
+            if chunk.project_id.is_empty() {
+                return Err(CollectorError::Validation("project_id is required".to_string()));
+            }
+            self.project_id = chunk.project_id;
+            self.snapshot_id = chunk.snapshot_id;
+        } else if chunk.project_id != self.project_id || chunk.snapshot_id != self.snapshot_id {
+            return Err(CollectorError::Validation(
+                "project_id and snapshot_id must be the same in every chunk".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        self.vms.extend(chunk.vms.into_iter().map(|vm| vm_from_proto(vm, now)));
+        let inventory = &mut self.inventory;
+        inventory.hosts.extend(chunk.hosts.into_iter().map(host_from_proto));
+        inventory.clusters.extend(chunk.clusters.into_iter().map(cluster_from_proto));
+        inventory.datastores.extend(chunk.datastores.into_iter().map(datastore_from_proto));
+        inventory.resource_pools.extend(chunk.resource_pools.into_iter().map(resource_pool_from_proto));
+        inventory.snapshots.extend(chunk.snapshots.into_iter().map(snapshot_from_proto));
+        inventory.tools.extend(chunk.tools.into_iter().map(tools_from_proto));
+        self.complete = chunk.last;
+
+        if self.vms.len() > MAX_SNAPSHOT_VMS {
+            return Err(CollectorError::Validation(format!(
+                "Snapshot exceeds {} VMs",
+                MAX_SNAPSHOT_VMS
+            )));
+        }
+        if self.inventory_rows() > MAX_SNAPSHOT_INVENTORY_ROWS {
+            return Err(CollectorError::Validation(format!(
+                "Snapshot exceeds {} inventory rows",
+                MAX_SNAPSHOT_INVENTORY_ROWS
+            )));
+        }
+        Ok(())
+    }
+
+    fn inventory_rows(&self) -> usize {
+        let inventory = &self.inventory;
+        inventory.hosts.len()
+            + inventory.clusters.len()
+            + inventory.datastores.len()
+            + inventory.resource_pools.len()
+            + inventory.snapshots.len()
+            + inventory.tools.len()
+    }
+}
+
+/// The project id is set when the VMs are written
+fn vm_from_proto(vm: proto::Vm, now: DateTime<Utc>) -> MigrationWizardVM {
+    MigrationWizardVM {
+        id: None,
+        project_id: Thing::from(("migration_wizard_project", "")),
+        name: vm.name,
+        powerstate: vm.powerstate,
+        template: Some(vm.template),
+        cpus: vm.cpus,
+        memory_mb: vm.memory_mb,
+        provisioned_mb: vm.provisioned_mb,
+        in_use_mb: vm.in_use_mb,
+        primary_ip_address: vm.primary_ip_address,
+        dns_name: vm.dns_name,
+        cluster: vm.cluster,
+        host: vm.host,
+        datacenter: vm.datacenter,
+        os: vm.os,
+        version: vm.os_version,
+        num_disks: vm.num_disks,
+        num_nics: vm.num_nics,
+        annotation: vm.annotation,
+        folder: vm.folder,
+        created_at: now,
+    }
+}
+
+fn host_from_proto(host: proto::Host) -> SourceHost {
+    SourceHost {
+        name: host.name,
+        datacenter: host.datacenter,
+        cluster: host.cluster,
+        cpu_model: host.cpu_model,
+        cpu_mhz: host.cpu_mhz,
+        sockets: host.sockets,
+        cores: host.cores,
+        memory_mb: host.memory_mb,
+        vm_count: host.vm_count,
+        esx_version: host.esx_version,
+        current_evc: host.current_evc,
+        max_evc: host.max_evc,
+    }
+}
+
+fn cluster_from_proto(cluster: proto::ClusterConfig) -> SourceClusterConfig {
+    SourceClusterConfig {
+        name: cluster.name,
+        datacenter: cluster.datacenter,
+        num_hosts: cluster.num_hosts,
+        ha_enabled: cluster.ha_enabled,
+        failover_level: cluster.failover_level,
+        admission_control_enabled: cluster.admission_control_enabled,
+        failover_policy: cluster.failover_policy,
+        isolation_response: cluster.isolation_response,
+        drs_enabled: cluster.drs_enabled,
+        drs_behavior: cluster.drs_behavior,
+    }
+}
+
+fn datastore_from_proto(datastore: proto::Datastore) -> SourceDatastore {
+    SourceDatastore {
+        name: datastore.name,
+        datastore_type: datastore.datastore_type,
+        capacity_mb: datastore.capacity_mb,
+        provisioned_mb: datastore.provisioned_mb,
+        in_use_mb: datastore.in_use_mb,
+        free_mb: datastore.free_mb,
+        cluster: datastore.cluster,
+        num_hosts: datastore.num_hosts,
+    }
+}
+
+fn resource_pool_from_proto(pool: proto::ResourcePool) -> SourceResourcePool {
+    SourceResourcePool {
+        name: pool.name,
+        path: pool.path,
+        cluster: pool.cluster,
+        vm_count: pool.vm_count,
+        cpu_reservation_mhz: pool.cpu_reservation_mhz,
+        cpu_limit_mhz: pool.cpu_limit_mhz,
+        memory_reservation_mb: pool.memory_reservation_mb,
+        memory_limit_mb: pool.memory_limit_mb,
+    }
+}
+
+fn snapshot_from_proto(snapshot: proto::VmSnapshot) -> SourceSnapshot {
+    SourceSnapshot {
+        vm_name: snapshot.vm_name,
+        name: snapshot.name,
+        description: snapshot.description,
+        cluster: snapshot.cluster,
+        created_at: snapshot.created_at.and_then(|secs| DateTime::from_timestamp(secs, 0)),
+        size_mb: snapshot.size_mb,
+    }
+}
+
+fn tools_from_proto(tools: proto::VmTools) -> SourceVmTools {
+    SourceVmTools {
+        vm_name: tools.vm_name,
+        status: tools.status,
+        version: tools.version,
+        required_version: tools.required_version,
+        upgradeable: tools.upgradeable,
+    }
+}
+
+/// Samples with a timestamp chrono cannot represent are dropped; they
+/// count as rejected
+pub fn metric_samples(
+    batch: proto::MetricBatch,
+    project_id: &str,
+    collector_id: &Thing,
+) -> (Vec<VmMetricSample>, usize) {
+    let project = Thing::from(("migration_wizard_project", project_id));
+    let total = batch.samples.len();
+    let samples: Vec<VmMetricSample> = batch
+        .samples
+        .into_iter()
+        .filter_map(|sample| {
+            Some(VmMetricSample {
+                id: None,
+                project_id: project.clone(),
+                collector_id: collector_id.clone(),
+                vm_name: sample.vm_name,
+                captured_at: DateTime::from_timestamp(sample.captured_at, 0)?,
+                cpu_percent: sample.cpu_percent,
+                memory_percent: sample.memory_percent,
+                disk_read_kbps: sample.disk_read_kbps,
+                disk_write_kbps: sample.disk_write_kbps,
+                network_kbps: sample.network_kbps,
+            })
+        })
+        .collect();
+    let dropped = total - samples.len();
+    (samples, dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(project_id: &str, vms: &[&str], last: bool) -> proto::InventoryChunk {
+        proto::InventoryChunk {
+            project_id: project_id.to_string(),
+            snapshot_id: "s1".to_string(),
+            vms: vms
+                .iter()
+                .map(|name| proto::Vm { name: name.to_string(), cpus: 2, memory_mb: 4096, ..Default::default() })
+                .collect(),
+            hosts: vec![proto::Host { name: "esx-01".to_string(), cores: 32, ..Default::default() }],
+            last,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_gathers_chunks_until_last() {
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(chunk("p1", &["web-01", "web-02"], false)).unwrap();
+        snapshot.push(chunk("p1", &["db-01"], true)).unwrap();
+
+        assert!(snapshot.complete);
+        assert_eq!(snapshot.project_id, "p1");
+        assert_eq!(snapshot.vms.len(), 3);
+        assert_eq!(snapshot.vms[2].memory_mb, 4096);
+        assert_eq!(snapshot.inventory.hosts.len(), 2);
+        assert!(snapshot.push(chunk("p1", &[], true)).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_project_change() {
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(chunk("p1", &["web-01"], false)).unwrap();
+        assert!(snapshot.push(chunk("p2", &["web-02"], true)).is_err());
+        assert!(InventorySnapshot::default().push(chunk("", &[], true)).is_err());
+    }
+
+    #[test]
+    fn test_metric_samples_drop_unrepresentable_times() {
+        let batch = proto::MetricBatch {
+            project_id: "p1".to_string(),
+            sequence: 7,
+            samples: vec![
+                proto::MetricSample { vm_name: "web-01".to_string(), captured_at: 1_700_000_000, ..Default::default() },
+                proto::MetricSample { vm_name: "web-02".to_string(), captured_at: i64::MAX, ..Default::default() },
+            ],
+        };
+        let (samples, dropped) = metric_samples(batch, "p1", &Thing::from(("collector", "c1")));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(dropped, 1);
+        assert_eq!(samples[0].project_id, Thing::from(("migration_wizard_project", "p1")));
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, Semaphore};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::codec::CompressionEncoding;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status, Streaming};
+
+use super::convert::{metric_samples, InventorySnapshot};
+use super::service::{CollectorError, CollectorService};
+use crate::database::Database;
+use crate::models::collector::Collector;
+use crate::services::upload_session_service::sha256_hex;
+
+pub mod proto {
+    tonic::include_proto!("archer.collector.v1");
+}
+
+use proto::collector_ingest_server::{CollectorIngest, CollectorIngestServer};
+use proto::{CollectorSettings, InventoryChunk, InventorySummary, MetricAck, MetricBatch, SettingsRequest};
+
+/// Largest message accepted; collectors split snapshots into smaller chunks
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+/// Acknowledgements buffered per metric stream. The next batch is not read
+/// while the buffer is full, so a slow client or database backs up into
+/// HTTP/2 flow control instead of memory.
+const METRIC_QUEUE: usize = 4;
+const DEFAULT_SNAPSHOT_SLOTS: usize = 2;
+const DEFAULT_STREAMS_PER_CONNECTION: usize = 8;
+
+impl From<CollectorError> for Status {
+    fn from(e: CollectorError) -> Self {
+        match e {
+            CollectorError::NotFound => Status::not_found(e.to_string()),
+            CollectorError::Validation(_) => Status::invalid_argument(e.to_string()),
+            CollectorError::Unauthenticated => Status::unauthenticated(e.to_string()),
+            CollectorError::Forbidden(_) => Status::permission_denied(e.to_string()),
+            CollectorError::Ingest(_) | CollectorError::DatabaseError(_) => {
+                tracing::error!("Collector ingestion failed: {}", e);
+                Status::internal(e.to_string())
+            }
+        }
+    }
+}
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// Set by `COLLECTOR_GRPC_*`; the server only starts when an address is set
+#[derive(Debug, Clone)]
+pub struct CollectorGrpcConfig {
+    pub addr: SocketAddr,
+    pub cert_path: String,
+    pub key_path: String,
+    /// CA that signs collector client certificates
+    pub client_ca_path: String,
+    /// Snapshots written at once; further uploads wait before being read
+    pub snapshot_slots: usize,
+    pub streams_per_connection: usize,
+}
+
+impl CollectorGrpcConfig {
+    pub fn from_env() -> Option<anyhow::Result<Self>> {
+        let addr = std::env::var("COLLECTOR_GRPC_ADDR").ok().filter(|a| !a.is_empty())?;
+        let required = |name: &str| {
+            std::env::var(name).map_err(|_| anyhow::anyhow!("{} is required when COLLECTOR_GRPC_ADDR is set", name))
+        };
+        let count = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+
+        Some((|| {
+            Ok(Self {
+                addr: addr.parse().map_err(|e| anyhow::anyhow!("Invalid COLLECTOR_GRPC_ADDR '{}': {}", addr, e))?,
+                cert_path: required("COLLECTOR_GRPC_CERT")?,
+                key_path: required("COLLECTOR_GRPC_KEY")?,
+                client_ca_path: required("COLLECTOR_GRPC_CLIENT_CA")?,
+                snapshot_slots: count("COLLECTOR_GRPC_SNAPSHOT_SLOTS", DEFAULT_SNAPSHOT_SLOTS),
+                streams_per_connection: count("COLLECTOR_GRPC_STREAMS_PER_CONNECTION", DEFAULT_STREAMS_PER_CONNECTION),
+            })
+        })())
+    }
+}
+
+/// Serve `CollectorIngest` over mutual TLS until the process exits
+pub async fn serve(db: Arc<Database>, config: CollectorGrpcConfig) -> anyhow::Result<()> {
+    let identity = Identity::from_pem(
+        tokio::fs::read(&config.cert_path).await?,
+        tokio::fs::read(&config.key_path).await?,
+    );
+    let client_ca = Certificate::from_pem(tokio::fs::read(&config.client_ca_path).await?);
+    let tls = ServerTlsConfig::new().identity(identity).client_ca_root(client_ca);
+
+    // Collector agents gzip their snapshots
+    let service = CollectorIngestServer::new(CollectorGrpc::new(db, config.snapshot_slots))
+        .max_decoding_message_size(MAX_MESSAGE_BYTES)
+        .accept_compressed(CompressionEncoding::Gzip)
+        .send_compressed(CompressionEncoding::Gzip);
+    Server::builder()
+        .tls_config(tls)?
+        .concurrency_limit_per_connection(config.streams_per_connection)
+        .add_service(service)
+        .serve(config.addr)
+        .await?;
+    Ok(())
+}
+
+// ============================================================================
+// SERVICE
+// ============================================================================
+
+pub struct CollectorGrpc {
+    collectors: Arc<CollectorService>,
+    snapshot_slots: Arc<Semaphore>,
+}
+
+impl CollectorGrpc {
+    pub fn new(db: Arc<Database>, snapshot_slots: usize) -> Self {
+        Self {
+            collectors: Arc::new(CollectorService::new(db)),
+            snapshot_slots: Arc::new(Semaphore::new(snapshot_slots.max(1))),
+        }
+    }
+
+    /// The collector behind the bearer token and client certificate
+    async fn authenticate<T>(&self, request: &Request<T>) -> Result<Collector, Status> {
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("Missing bearer token"))?;
+        let fingerprint = request
+            .peer_certs()
+            .and_then(|certs| certs.first().map(|cert| sha256_hex(cert.get_ref())));
+        Ok(self.collectors.authenticate(token.trim(), fingerprint.as_deref()).await?)
+    }
+}
+
+#[tonic::async_trait]
+impl CollectorIngest for CollectorGrpc {
+    async fn upload_inventory(
+        &self,
+        request: Request<Streaming<InventoryChunk>>,
+    ) -> Result<Response<InventorySummary>, Status> {
+        let collector = self.authenticate(&request).await?;
+        // Wait for a slot before reading, so queued uploads hold no chunks
+        let _slot = self
+            .snapshot_slots
+            .acquire()
+            .await
+            .map_err(|_| Status::unavailable("Collector ingestion is shutting down"))?;
+        let started = Instant::now();
+
+        let mut stream = request.into_inner();
+        let mut snapshot = InventorySnapshot::default();
+        while let Some(chunk) = stream.message().await? {
+            // Fail fast instead of buffering a snapshot that cannot be written
+            if snapshot.project_id.is_empty() {
+                self.collectors.authorize(&collector, &chunk.project_id)?;
+            }
+            snapshot.push(chunk)?;
+        }
+        if !snapshot.complete {
+            return Err(Status::aborted("Stream ended before the last chunk; nothing was written"));
+        }
+
+        let metrics = self
+            .collectors
+            .ingest_snapshot(&collector, &snapshot.project_id, &snapshot.snapshot_id, snapshot.vms, &snapshot.inventory)
+            .await?;
+        tracing::info!(
+            collector = %collector.name,
+            project = %snapshot.project_id,
+            vms = metrics.vms_inserted,
+            hosts = metrics.hosts_parsed,
+            "Collector snapshot written"
+        );
+
+        Ok(Response::new(InventorySummary {
+            snapshot_id: snapshot.snapshot_id,
+            vms_inserted: metrics.vms_inserted as u32,
+            hosts: metrics.hosts_parsed as u32,
+            clusters: metrics.clusters_parsed as u32,
+            datastores: metrics.datastores_parsed as u32,
+            total_ms: started.elapsed().as_millis() as u64,
+        }))
+    }
+
+    type StreamMetricsStream = ReceiverStream<Result<MetricAck, Status>>;
+
+    async fn stream_metrics(
+        &self,
+        request: Request<Streaming<MetricBatch>>,
+    ) -> Result<Response<Self::StreamMetricsStream>, Status> {
+        let collector = self.authenticate(&request).await?;
+        let collector_id = collector
+            .id
+            .clone()
+            .ok_or_else(|| Status::internal("Collector has no id"))?;
+        let collectors = Arc::clone(&self.collectors);
+        let mut stream = request.into_inner();
+        let (tx, rx) = mpsc::channel(METRIC_QUEUE);
+
+        tokio::spawn(async move {
+            // VM names per project, loaded on the first batch for each
+            let mut known_vms = HashMap::new();
+            loop {
+                let batch = match stream.message().await {
+                    Ok(Some(batch)) => batch,
+                    Ok(None) => break,
+                    Err(status) => {
+                        tracing::debug!("Metric stream of collector {} ended: {}", collector.name, status);
+                        break;
+                    }
+                };
+
+                let project_id = batch.project_id.clone();
+                let sequence = batch.sequence;
+                let ack = async {
+                    collectors.authorize(&collector, &project_id)?;
+                    if !known_vms.contains_key(&project_id) {
+                        known_vms.insert(project_id.clone(), collectors.vm_names(&project_id).await?);
+                    }
+                    let (samples, dropped) = metric_samples(batch, &project_id, &collector_id);
+                    let offered = samples.len();
+                    let accepted = collectors.write_metrics(samples, &known_vms[&project_id]).await?;
+                    Ok::<_, CollectorError>(MetricAck {
+                        sequence,
+                        accepted: accepted as u32,
+                        rejected: (offered - accepted + dropped) as u32,
+                    })
+                }
+                .await;
+
+                let failed = ack.is_err();
+                // A full queue waits here; a closed one means the client left
+                if tx.send(ack.map_err(Status::from)).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn get_settings(&self, request: Request<SettingsRequest>) -> Result<Response<CollectorSettings>, Status> {
+        let collector = self.authenticate(&request).await?;
+        let agent = request.into_inner();
+        self.collectors
+            .record_agent(&collector, &agent.agent_version, &agent.hostname)
+            .await?;
+
+        let settings = collector.settings;
+        Ok(Response::new(CollectorSettings {
+            project_id: settings.project_id.unwrap_or_default(),
+            snapshot_interval_minutes: settings.snapshot_interval_minutes,
+            vcenter_enabled: settings.vcenter_enabled,
+            nutanix_enabled: settings.nutanix_enabled,
+            active_directory_enabled: settings.active_directory_enabled,
+            paused: settings.paused,
+        }))
+    }
+}
@@ -0,0 +1,15 @@
+//! Collector ingestion over gRPC
+//!
+//! Remote collector appliances stream inventory snapshots and VM metric
+//! batches to a tonic server secured with mutual TLS. Each collector is
+//! registered by an admin, authenticates with its own token (and optionally
+//! a pinned client certificate) and may only write to the projects it is
+//! assigned. Snapshots go through the same pipeline as RVTools uploads.
+
+pub mod convert;
+pub mod grpc;
+pub mod service;
+
+pub use convert::InventorySnapshot;
+pub use grpc::{serve, CollectorGrpc, CollectorGrpcConfig};
+pub use service::{CollectorError, CollectorService};
@@ -0,0 +1,515 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use std::collections::HashSet;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::collector::*;
+use crate::models::migration_wizard_models::{MigrationWizardVM, RvToolsIngestMetrics, SourceInventory};
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::project_access_service::project_key;
+use crate::services::upload_session_service::sha256_hex;
+
+const COLLECTOR_TABLE: &str = "collector";
+const METRIC_TABLE: &str = "vm_metric_sample";
+/// Samples older than this are rejected; collectors replay at most this much
+/// after an outage
+const MAX_SAMPLE_AGE_DAYS: i64 = 30;
+/// Tolerated clock skew for samples from the future
+const MAX_SAMPLE_SKEW_MINUTES: i64 = 5;
+const DEFAULT_METRIC_LIMIT: usize = 1000;
+const MIN_SNAPSHOT_INTERVAL_MINUTES: u32 = 15;
+const MAX_SNAPSHOT_INTERVAL_MINUTES: u32 = 7 * 24 * 60;
+const MAX_METRIC_LIMIT: usize = 10_000;
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum CollectorError {
+    #[error("Collector not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Invalid collector token or certificate")]
+    Unauthenticated,
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("Ingestion failed: {0}")]
+    Ingest(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for CollectorError {
+    fn from(e: surrealdb::Error) -> Self {
+        CollectorError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// SERVICE
+// ============================================================================
+
+pub struct CollectorService {
+    db: Arc<Database>,
+}
+
+impl CollectorService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ========================================================================
+    // REGISTRY
+    // ========================================================================
+
+    pub async fn list_collectors(&self) -> Result<Vec<CollectorView>, CollectorError> {
+        let collectors: Vec<Collector> = self
+            .db
+            .query("SELECT * FROM collector ORDER BY name ASC")
+            .traced("collector.list_collectors")
+            .await?
+            .take(0)?;
+        Ok(collectors.iter().map(CollectorView::from).collect())
+    }
+
+    pub async fn get_collector(&self, id: &str) -> Result<CollectorView, CollectorError> {
+        Ok(CollectorView::from(&self.load(id).await?))
+    }
+
+    /// Register a collector; the response holds its token
+    pub async fn create_collector(
+        &self,
+        request: CreateCollectorRequest,
+        created_by: &str,
+    ) -> Result<CollectorView, CollectorError> {
+        let name = validate_name(&request.name)?;
+        let project_ids = normalize_projects(request.project_ids)?;
+        let certificate_fingerprint = request.certificate_fingerprint.map(|f| normalize_fingerprint(&f)).transpose()?;
+        // A collector for a single project writes to it unless told otherwise
+        let mut settings = request.settings.unwrap_or_default();
+        if settings.project_id.is_none() && project_ids.len() == 1 {
+            settings.project_id = project_ids.first().cloned();
+        }
+        let settings = normalize_settings(settings, &project_ids)?;
+        let token = generate_token();
+        let now = Utc::now();
+
+        let collector = Collector {
+            id: None,
+            name,
+            project_ids,
+            token_hash: sha256_hex(token.as_bytes()),
+            certificate_fingerprint,
+            enabled: request.enabled,
+            created_by: created_by.to_string(),
+            created_at: now,
+            updated_at: now,
+            last_seen_at: None,
+            last_snapshot_at: None,
+            settings,
+            agent_version: None,
+            hostname: None,
+        };
+        let created: Vec<Collector> = self.db.create(COLLECTOR_TABLE).content(collector).await?;
+        let created = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| CollectorError::DatabaseError("Collector was not created".to_string()))?;
+
+        let mut view = CollectorView::from(&created);
+        view.token = Some(token);
+        Ok(view)
+    }
+
+    pub async fn update_collector(
+        &self,
+        id: &str,
+        request: UpdateCollectorRequest,
+    ) -> Result<CollectorView, CollectorError> {
+        let mut collector = self.load(id).await?;
+        if let Some(name) = request.name {
+            collector.name = validate_name(&name)?;
+        }
+        if let Some(project_ids) = request.project_ids {
+            collector.project_ids = normalize_projects(project_ids)?;
+        }
+        if let Some(fingerprint) = request.certificate_fingerprint {
+            collector.certificate_fingerprint = match fingerprint.trim() {
+                "" => None,
+                fingerprint => Some(normalize_fingerprint(fingerprint)?),
+            };
+        }
+        if let Some(enabled) = request.enabled {
+            collector.enabled = enabled;
+        }
+        let settings = request.settings.unwrap_or_else(|| collector.settings.clone());
+        collector.settings = normalize_settings(settings, &collector.project_ids)?;
+        let token = request.rotate_token.then(generate_token);
+        if let Some(token) = &token {
+            collector.token_hash = sha256_hex(token.as_bytes());
+        }
+        collector.updated_at = Utc::now();
+
+        let key = record_key(id, COLLECTOR_TABLE);
+        let updated: Option<Collector> = self.db.update((COLLECTOR_TABLE, key)).content(collector).await?;
+        let mut view = CollectorView::from(&updated.ok_or(CollectorError::NotFound)?);
+        view.token = token;
+        Ok(view)
+    }
+
+    /// Remove a collector; the metrics it sent stay with their projects
+    pub async fn delete_collector(&self, id: &str) -> Result<(), CollectorError> {
+        let key = record_key(id, COLLECTOR_TABLE);
+        let deleted: Option<Collector> = self.db.delete((COLLECTOR_TABLE, key)).await?;
+        deleted.map(|_| ()).ok_or(CollectorError::NotFound)
+    }
+
+    async fn load(&self, id: &str) -> Result<Collector, CollectorError> {
+        let key = record_key(id, COLLECTOR_TABLE);
+        let collector: Option<Collector> = self.db.select((COLLECTOR_TABLE, key)).await?;
+        collector.ok_or(CollectorError::NotFound)
+    }
+
+    // ========================================================================
+    // AUTHENTICATION
+    // ========================================================================
+
+    /// The enabled collector holding `token`. A collector with a pinned
+    /// certificate must also present that certificate.
+    pub async fn authenticate(
+        &self,
+        token: &str,
+        certificate_fingerprint: Option<&str>,
+    ) -> Result<Collector, CollectorError> {
+        let collectors: Vec<Collector> = self
+            .db
+            .query("SELECT * FROM collector WHERE token_hash = $hash LIMIT 1")
+            .bind(("hash", sha256_hex(token.as_bytes())))
+            .traced("collector.authenticate")
+            .await?
+            .take(0)?;
+        let collector = collectors.into_iter().next().ok_or(CollectorError::Unauthenticated)?;
+        if !collector.enabled {
+            return Err(CollectorError::Unauthenticated);
+        }
+        if let Some(pinned) = &collector.certificate_fingerprint {
+            if certificate_fingerprint != Some(pinned.as_str()) {
+                return Err(CollectorError::Unauthenticated);
+            }
+        }
+
+        self.db
+            .query("UPDATE $collector SET last_seen_at = time::now()")
+            .bind(("collector", collector.id.clone()))
+            .traced("collector.authenticate")
+            .await?;
+        Ok(collector)
+    }
+
+    /// Record the agent build and host a collector reported
+    pub async fn record_agent(
+        &self,
+        collector: &Collector,
+        agent_version: &str,
+        hostname: &str,
+    ) -> Result<(), CollectorError> {
+        let value = |v: &str| Some(v.chars().take(255).collect::<String>()).filter(|v| !v.is_empty());
+        self.db
+            .query("UPDATE $collector SET agent_version = $version, hostname = $hostname")
+            .bind(("collector", collector.id.clone()))
+            .bind(("version", value(agent_version)))
+            .bind(("hostname", value(hostname)))
+            .traced("collector.record_agent")
+            .await?;
+        Ok(())
+    }
+
+    /// Fails unless the collector is assigned to the project
+    pub fn authorize(&self, collector: &Collector, project_id: &str) -> Result<(), CollectorError> {
+        if collector.may_write(project_id) {
+            Ok(())
+        } else {
+            Err(CollectorError::Forbidden(format!(
+                "Collector '{}' may not write to project '{}'",
+                collector.name, project_id
+            )))
+        }
+    }
+
+    // ========================================================================
+    // INGESTION
+    // ========================================================================
+
+    /// Replace a project's VMs and source inventory with a collector
+    /// snapshot, through the same pipeline as an RVTools upload
+    pub async fn ingest_snapshot(
+        &self,
+        collector: &Collector,
+        project_id: &str,
+        snapshot_id: &str,
+        vms: Vec<MigrationWizardVM>,
+        inventory: &SourceInventory,
+    ) -> Result<RvToolsIngestMetrics, CollectorError> {
+        self.authorize(collector, project_id)?;
+        let wizard = MigrationWizardService::new(self.db.as_ref().clone());
+        wizard
+            .get_project(project_id)
+            .await
+            .map_err(|_| CollectorError::Validation(format!("Project '{}' not found", project_id)))?;
+
+        let source_name = format!("{} (collector snapshot {})", collector.name, snapshot_id);
+        wizard
+            .delete_project_vms(project_id)
+            .await
+            .map_err(|e| CollectorError::Ingest(e.to_string()))?;
+        let metrics = wizard
+            .ingest_inventory(project_id, vms, inventory, source_name, None)
+            .await
+            .map_err(|e| CollectorError::Ingest(e.to_string()))?;
+
+        self.db
+            .query("UPDATE $collector SET last_snapshot_at = time::now()")
+            .bind(("collector", collector.id.clone()))
+            .traced("collector.ingest_snapshot")
+            .await?;
+        Ok(metrics)
+    }
+
+    /// Names of the project's VMs, which metric samples must refer to
+    pub async fn vm_names(&self, project_id: &str) -> Result<HashSet<String>, CollectorError> {
+        let names: Vec<String> = self
+            .db
+            .query("SELECT VALUE name FROM migration_wizard_vm WHERE project_id = $project")
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("collector.vm_names")
+            .await?
+            .take(0)?;
+        Ok(names.into_iter().collect())
+    }
+
+    /// Store the samples that pass [`accept_sample`]; returns how many did
+    pub async fn write_metrics(
+        &self,
+        samples: Vec<VmMetricSample>,
+        known_vms: &HashSet<String>,
+    ) -> Result<usize, CollectorError> {
+        let now = Utc::now();
+        let accepted: Vec<VmMetricSample> =
+            samples.into_iter().filter(|s| accept_sample(s, known_vms, now)).collect();
+        if accepted.is_empty() {
+            return Ok(0);
+        }
+
+        let count = accepted.len();
+        self.db
+            .query("INSERT INTO vm_metric_sample $samples")
+            .bind(("samples", accepted))
+            .traced("collector.write_metrics")
+            .await?
+            .check()?;
+        Ok(count)
+    }
+
+    /// A project's samples, newest first
+    pub async fn list_metrics(
+        &self,
+        project_id: &str,
+        query: &VmMetricQuery,
+    ) -> Result<Vec<VmMetricSample>, CollectorError> {
+        let mut conditions = vec!["project_id = $project"];
+        if query.vm_name.is_some() {
+            conditions.push("vm_name = $vm_name");
+        }
+        if query.since.is_some() {
+            conditions.push("captured_at >= $since");
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_METRIC_LIMIT).clamp(1, MAX_METRIC_LIMIT);
+        let samples: Vec<VmMetricSample> = self
+            .db
+            .query(format!(
+                "SELECT * FROM {} WHERE {} ORDER BY captured_at DESC LIMIT {}",
+                METRIC_TABLE,
+                conditions.join(" AND "),
+                limit
+            ))
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .bind(("vm_name", query.vm_name.clone()))
+            .bind(("since", query.since))
+            .traced("collector.list_metrics")
+            .await?
+            .take(0)?;
+        Ok(samples)
+    }
+}
+
+// ============================================================================
+// PURE HELPERS
+// ============================================================================
+
+/// Samples must name a VM of the project, lie within the last 30 days (5
+/// minutes of skew allowed) and hold percentages between 0 and 100
+pub fn accept_sample(sample: &VmMetricSample, known_vms: &HashSet<String>, now: DateTime<Utc>) -> bool {
+    let percent = |value: f64| value.is_finite() && (0.0..=100.0).contains(&value);
+    let rate = |value: f64| value.is_finite() && value >= 0.0;
+
+    known_vms.contains(&sample.vm_name)
+        && sample.captured_at >= now - Duration::days(MAX_SAMPLE_AGE_DAYS)
+        && sample.captured_at <= now + Duration::minutes(MAX_SAMPLE_SKEW_MINUTES)
+        && percent(sample.cpu_percent)
+        && percent(sample.memory_percent)
+        && rate(sample.disk_read_kbps)
+        && rate(sample.disk_write_kbps)
+        && rate(sample.network_kbps)
+}
+
+/// Lowercase hex without separators, as `openssl x509 -fingerprint -sha256`
+/// prints it once the colons are dropped
+pub fn normalize_fingerprint(fingerprint: &str) -> Result<String, CollectorError> {
+    let hex: String = fingerprint
+        .trim()
+        .chars()
+        .filter(|c| *c != ':')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hex)
+    } else {
+        Err(CollectorError::Validation(
+            "certificate_fingerprint must be a SHA-256 fingerprint (64 hex digits)".to_string(),
+        ))
+    }
+}
+
+/// Snapshots at most every 15 minutes and at least weekly, into an assigned
+/// project
+fn normalize_settings(
+    mut settings: CollectorAgentSettings,
+    project_ids: &[String],
+) -> Result<CollectorAgentSettings, CollectorError> {
+    settings.project_id = settings.project_id.map(|id| project_key(id.trim())).filter(|id| !id.is_empty());
+    if !(MIN_SNAPSHOT_INTERVAL_MINUTES..=MAX_SNAPSHOT_INTERVAL_MINUTES).contains(&settings.snapshot_interval_minutes) {
+        return Err(CollectorError::Validation(format!(
+            "snapshot_interval_minutes must be between {} and {}",
+            MIN_SNAPSHOT_INTERVAL_MINUTES, MAX_SNAPSHOT_INTERVAL_MINUTES
+        )));
+    }
+    if let Some(project_id) = &settings.project_id {
+        if !project_ids.contains(project_id) {
+            return Err(CollectorError::Validation(format!(
+                "settings.project_id '{}' is not one of the collector's projects",
+                project_id
+            )));
+        }
+    }
+    Ok(settings)
+}
+
+fn validate_name(name: &str) -> Result<String, CollectorError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(CollectorError::Validation("name must be 1-100 characters".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+/// Raw project ids, without duplicates
+fn normalize_projects(project_ids: Vec<String>) -> Result<Vec<String>, CollectorError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(project_ids.len());
+    for id in project_ids {
+        let key = project_key(id.trim());
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(CollectorError::Validation(format!("'{}' is not a project id", id)));
+        }
+        if !normalized.contains(&key) {
+            normalized.push(key);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Accepts `collector:key` as well as the bare key
+fn record_key(id: &str, table: &str) -> String {
+    let key = id.strip_prefix(table).and_then(|k| k.strip_prefix(':')).unwrap_or(id);
+    key.trim_start_matches('⟨').trim_end_matches('⟩').to_string()
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(vm_name: &str, captured_at: DateTime<Utc>, cpu_percent: f64) -> VmMetricSample {
+        VmMetricSample {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            collector_id: Thing::from(("collector", "c1")),
+            vm_name: vm_name.to_string(),
+            captured_at,
+            cpu_percent,
+            memory_percent: 40.0,
+            disk_read_kbps: 0.0,
+            disk_write_kbps: 0.0,
+            network_kbps: 12.5,
+        }
+    }
+
+    #[test]
+    fn test_accept_sample_rejects_unknown_vms_stale_times_and_bad_values() {
+        let now = Utc::now();
+        let known: HashSet<String> = ["web-01".to_string()].into_iter().collect();
+
+        assert!(accept_sample(&sample("web-01", now, 55.0), &known, now));
+        assert!(!accept_sample(&sample("db-01", now, 55.0), &known, now));
+        assert!(!accept_sample(&sample("web-01", now - Duration::days(31), 55.0), &known, now));
+        assert!(!accept_sample(&sample("web-01", now + Duration::hours(1), 55.0), &known, now));
+        assert!(!accept_sample(&sample("web-01", now, 120.0), &known, now));
+        assert!(!accept_sample(&sample("web-01", now, f64::NAN), &known, now));
+    }
+
+    #[test]
+    fn test_normalize_fingerprint_accepts_openssl_output() {
+        let openssl = "AB:".repeat(31) + "AB";
+        assert_eq!(normalize_fingerprint(&openssl).unwrap(), "ab".repeat(32));
+        assert!(normalize_fingerprint("abcd").is_err());
+    }
+
+    #[test]
+    fn test_normalize_settings_bounds_interval_and_project() {
+        let projects = vec!["p1".to_string()];
+        let settings = CollectorAgentSettings {
+            project_id: Some("migration_wizard_project:p1".to_string()),
+            ..Default::default()
+        };
+        let normalized = normalize_settings(settings.clone(), &projects).unwrap();
+        assert_eq!(normalized.project_id.as_deref(), Some("p1"));
+
+        let too_often = CollectorAgentSettings { snapshot_interval_minutes: 5, ..settings.clone() };
+        assert!(normalize_settings(too_often, &projects).is_err());
+
+        let unassigned = CollectorAgentSettings { project_id: Some("p2".to_string()), ..settings };
+        assert!(normalize_settings(unassigned, &projects).is_err());
+    }
+
+    #[test]
+    fn test_normalize_projects_strips_table_and_duplicates() {
+        let ids = vec!["migration_wizard_project:k3n9".to_string(), "k3n9".to_string(), "x1".to_string()];
+        assert_eq!(normalize_projects(ids).unwrap(), vec!["k3n9", "x1"]);
+        assert!(normalize_projects(vec!["a' OR 1".to_string()]).is_err());
+    }
+}
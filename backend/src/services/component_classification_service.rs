@@ -0,0 +1,270 @@
+// Archer ITSM - Component Classification Service
+// Classifies hardware basket lines with the scoring classifier, queues the
+// ones it is unsure about for review and stores reviewers' corrections per
+// tenant so the classifier learns from them
+
+use chrono::Utc;
+use core_engine::hardware_parser::component_classifier::{
+    Classification, ClassificationCorrection, ComponentClassifier,
+};
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::models::component_classification::*;
+use crate::database::TracedQuery;
+
+const REVIEW_TABLE: &str = "component_review_item";
+const CORRECTION_TABLE: &str = "classifier_correction";
+
+/// `resolved_by` for items closed because a later correction made them confident
+pub const CLASSIFIER_RESOLVER: &str = "classifier";
+
+#[derive(Debug, Error)]
+pub enum ComponentClassificationError {
+    #[error("Review item not found")]
+    NotFound,
+
+    #[error("Correction not found")]
+    CorrectionNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for ComponentClassificationError {
+    fn from(e: surrealdb::Error) -> Self {
+        ComponentClassificationError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct ComponentClassificationService {
+    db: Database,
+}
+
+impl ComponentClassificationService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Classifier with the tenant's corrections applied
+    pub async fn classifier(&self, tenant_id: Option<&str>) -> Result<ComponentClassifier, ComponentClassificationError> {
+        let corrections: Vec<ClassificationCorrection> = self
+            .list_corrections(tenant_id)
+            .await?
+            .iter()
+            .map(ClassifierCorrection::to_correction)
+            .collect();
+        Ok(ComponentClassifier::with_corrections(&corrections))
+    }
+
+    /// Classify basket lines; low-confidence lines are queued for review
+    /// unless the same line is already waiting
+    pub async fn classify(
+        &self,
+        tenant_id: Option<&str>,
+        request: ClassifyComponentsRequest,
+    ) -> Result<ClassifyComponentsResponse, ComponentClassificationError> {
+        if request.items.is_empty() {
+            return Err(ComponentClassificationError::Validation("No items to classify".to_string()));
+        }
+        let classifier = self.classifier(tenant_id).await?;
+        let pending = self.list_queue(tenant_id, Some(ReviewStatus::Pending)).await?;
+
+        let mut items = Vec::with_capacity(request.items.len());
+        let mut queued_for_review = 0;
+        for line in request.items {
+            let classification = classifier.classify(&line.description, &line.part_number, request.vendor.as_deref());
+            let mut review_item_id = None;
+            if classification.needs_review() {
+                let waiting = pending
+                    .iter()
+                    .find(|item| item.part_number == line.part_number && item.description == line.description);
+                review_item_id = match waiting {
+                    Some(item) => item.id.as_ref().map(|id| id.id.to_raw()),
+                    None => {
+                        queued_for_review += 1;
+                        self.queue(tenant_id, &request, &line, classification.clone()).await?
+                    }
+                };
+            }
+            items.push(ClassifiedLine {
+                part_number: line.part_number,
+                description: line.description,
+                classification,
+                review_item_id,
+            });
+        }
+
+        Ok(ClassifyComponentsResponse { items, queued_for_review })
+    }
+
+    async fn queue(
+        &self,
+        tenant_id: Option<&str>,
+        request: &ClassifyComponentsRequest,
+        line: &ComponentLine,
+        suggestion: Classification,
+    ) -> Result<Option<String>, ComponentClassificationError> {
+        let item = ComponentReviewItem {
+            id: None,
+            tenant_id: tenant_id.map(str::to_string),
+            vendor: request.vendor.clone(),
+            part_number: line.part_number.clone(),
+            description: line.description.clone(),
+            source: request.source.clone(),
+            suggestion,
+            status: ReviewStatus::Pending,
+            resolved_primary_category: None,
+            resolved_subcategory: None,
+            resolved_by: None,
+            created_at: Utc::now(),
+            resolved_at: None,
+        };
+        let created: Vec<ComponentReviewItem> = self.db.create(REVIEW_TABLE).content(item).await?;
+        Ok(created.into_iter().next().and_then(|item| item.id).map(|id| id.id.to_raw()))
+    }
+
+    // =========================================================================
+    // REVIEW QUEUE
+    // =========================================================================
+
+    /// Review items of a tenant, newest first
+    pub async fn list_queue(
+        &self,
+        tenant_id: Option<&str>,
+        status: Option<ReviewStatus>,
+    ) -> Result<Vec<ComponentReviewItem>, ComponentClassificationError> {
+        let mut items: Vec<ComponentReviewItem> = self
+            .db
+            .query("SELECT * FROM component_review_item ORDER BY created_at DESC")
+            .traced("component_classification_service.list_queue")
+            .await?
+            .take(0)?;
+        items.retain(|item| item.tenant_id.as_deref() == tenant_id && status.map_or(true, |s| item.status == s));
+        Ok(items)
+    }
+
+    /// Settle a review item. The answer is stored as a tenant correction and
+    /// the rest of the tenant's queue is re-scored with it.
+    pub async fn resolve(
+        &self,
+        tenant_id: Option<&str>,
+        item_id: &str,
+        resolved_by: &str,
+        request: ResolveReviewItemRequest,
+    ) -> Result<ResolveReviewItemResponse, ComponentClassificationError> {
+        let mut item = self.get_item(tenant_id, item_id).await?;
+        if item.status == ReviewStatus::Resolved {
+            return Err(ComponentClassificationError::Validation("Review item is already resolved".to_string()));
+        }
+
+        let correction = ClassifierCorrection {
+            id: None,
+            tenant_id: tenant_id.map(str::to_string),
+            vendor: item.vendor.clone(),
+            part_number: item.part_number.clone(),
+            description: item.description.clone(),
+            primary_category: request.primary_category,
+            component_subcategory: request.component_subcategory,
+            corrected_by: resolved_by.to_string(),
+            created_at: Utc::now(),
+        };
+        correction
+            .to_correction()
+            .validate()
+            .map_err(|e| ComponentClassificationError::Validation(e.to_string()))?;
+        let created: Vec<ClassifierCorrection> = self.db.create(CORRECTION_TABLE).content(correction).await?;
+        let correction = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ComponentClassificationError::DatabaseError("Failed to store correction".to_string()))?;
+
+        item.status = ReviewStatus::Resolved;
+        item.resolved_primary_category = Some(request.primary_category);
+        item.resolved_subcategory = request.component_subcategory;
+        item.resolved_by = Some(resolved_by.to_string());
+        item.resolved_at = Some(Utc::now());
+        let item = self.save_item(item_id, item).await?;
+
+        let auto_resolved = self.rescore_pending(tenant_id).await?;
+        Ok(ResolveReviewItemResponse { item, correction, auto_resolved })
+    }
+
+    /// Close pending items the tenant's corrections now classify confidently
+    async fn rescore_pending(&self, tenant_id: Option<&str>) -> Result<usize, ComponentClassificationError> {
+        let classifier = self.classifier(tenant_id).await?;
+        let mut resolved = 0;
+        for mut item in self.list_queue(tenant_id, Some(ReviewStatus::Pending)).await? {
+            let classification = classifier.classify(&item.description, &item.part_number, item.vendor.as_deref());
+            if classification.needs_review() {
+                continue;
+            }
+            let Some(id) = item.id.as_ref().map(|id| id.id.to_raw()) else {
+                continue;
+            };
+            item.status = ReviewStatus::Resolved;
+            item.resolved_primary_category = Some(classification.primary_category);
+            item.resolved_subcategory = classification.component_subcategory;
+            item.resolved_by = Some(CLASSIFIER_RESOLVER.to_string());
+            item.resolved_at = Some(Utc::now());
+            item.suggestion = classification;
+            self.save_item(&id, item).await?;
+            resolved += 1;
+        }
+        Ok(resolved)
+    }
+
+    async fn get_item(
+        &self,
+        tenant_id: Option<&str>,
+        item_id: &str,
+    ) -> Result<ComponentReviewItem, ComponentClassificationError> {
+        let item: Option<ComponentReviewItem> = self.db.select((REVIEW_TABLE, item_id)).await?;
+        item.filter(|item| item.tenant_id.as_deref() == tenant_id)
+            .ok_or(ComponentClassificationError::NotFound)
+    }
+
+    async fn save_item(
+        &self,
+        item_id: &str,
+        item: ComponentReviewItem,
+    ) -> Result<ComponentReviewItem, ComponentClassificationError> {
+        let updated: Option<ComponentReviewItem> = self.db.update((REVIEW_TABLE, item_id)).content(item).await?;
+        updated.ok_or(ComponentClassificationError::NotFound)
+    }
+
+    // =========================================================================
+    // CORRECTIONS
+    // =========================================================================
+
+    pub async fn list_corrections(
+        &self,
+        tenant_id: Option<&str>,
+    ) -> Result<Vec<ClassifierCorrection>, ComponentClassificationError> {
+        let mut corrections: Vec<ClassifierCorrection> = self
+            .db
+            .query("SELECT * FROM classifier_correction ORDER BY created_at")
+            .traced("component_classification_service.list_corrections")
+            .await?
+            .take(0)?;
+        corrections.retain(|c| c.tenant_id.as_deref() == tenant_id);
+        Ok(corrections)
+    }
+
+    /// Forget a correction; the classifier stops applying it immediately
+    pub async fn delete_correction(
+        &self,
+        tenant_id: Option<&str>,
+        correction_id: &str,
+    ) -> Result<(), ComponentClassificationError> {
+        let correction: Option<ClassifierCorrection> = self.db.select((CORRECTION_TABLE, correction_id)).await?;
+        if correction.filter(|c| c.tenant_id.as_deref() == tenant_id).is_none() {
+            return Err(ComponentClassificationError::CorrectionNotFound);
+        }
+        let _: Option<ClassifierCorrection> = self.db.delete((CORRECTION_TABLE, correction_id)).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,521 @@
+// Archer ITSM - Contract Service
+// CRUD for vendor contracts, CI coverage lookup and expiry alerts sent
+// through the notification service
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{error, info};
+
+use crate::database::{locks, Database};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::contract::*;
+use crate::services::notification_service::NotificationService;
+use crate::database::TracedQuery;
+
+/// Daily at 06:00 UTC unless `CONTRACT_ALERT_CRON` says otherwise
+const DEFAULT_ALERT_CRON: &str = "0 0 6 * * *";
+const ALERT_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum ContractError {
+    #[error("Contract not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for ContractError {
+    fn from(e: surrealdb::Error) -> Self {
+        ContractError::DatabaseError(e.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UserEmail {
+    id: Thing,
+    email: String,
+}
+
+pub struct ContractService {
+    db: Arc<Database>,
+    notifier: NotificationService,
+}
+
+impl ContractService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            notifier: NotificationService::from_env(),
+        }
+    }
+
+    // ========================================================================
+    // CONTRACTS
+    // ========================================================================
+
+    pub async fn list_contracts(
+        &self,
+        user: &AuthenticatedUser,
+        query: &ContractListQuery,
+    ) -> Result<Vec<ContractResponse>, ContractError> {
+        let mut contracts: Vec<Contract> = self
+            .db
+            .query("SELECT * FROM contracts ORDER BY end_date ASC")
+            .traced("contract_service.list_contracts")
+            .await?
+            .take(0)?;
+
+        let tenant = user_tenant(user);
+        let ci = query.ci_id.as_deref().map(|id| parse_thing("configuration_items", id));
+        let now = Utc::now();
+        contracts.retain(|c| {
+            c.tenant_id == tenant
+                && query.vendor.as_ref().map_or(true, |v| c.vendor.eq_ignore_ascii_case(v))
+                && query.contract_type.map_or(true, |t| c.contract_type == t)
+                && ci.as_ref().map_or(true, |ci| c.covered_cis.contains(ci))
+                && query.expiring_within_days.map_or(true, |days| {
+                    let remaining = c.days_remaining(now);
+                    c.end_date > now && remaining <= days as i64
+                })
+        });
+        Ok(contracts.into_iter().map(|c| with_status(c, now)).collect())
+    }
+
+    pub async fn get_contract(&self, user: &AuthenticatedUser, id: &str) -> Result<ContractResponse, ContractError> {
+        Ok(with_status(self.load(user, id).await?, Utc::now()))
+    }
+
+    pub async fn create_contract(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateContractRequest,
+    ) -> Result<ContractResponse, ContractError> {
+        let now = Utc::now();
+        let contract = Contract {
+            id: None,
+            name: request.name.trim().to_string(),
+            vendor: request.vendor.trim().to_string(),
+            contract_number: request.contract_number,
+            contract_type: request.contract_type,
+            start_date: request.start_date,
+            end_date: request.end_date,
+            cost: request.cost,
+            currency: request.currency.map(|c| c.to_uppercase()),
+            coverage_level: request.coverage_level,
+            response_time_hours: request.response_time_hours,
+            support_contact: request.support_contact,
+            covered_cis: request
+                .covered_cis
+                .iter()
+                .map(|id| parse_thing("configuration_items", id))
+                .collect(),
+            notify: request.notify,
+            alert_days: request.alert_days.unwrap_or_else(default_alert_days),
+            alerts_sent: Vec::new(),
+            auto_renew: request.auto_renew,
+            notes: request.notes,
+            created_by: user.user_id.clone(),
+            created_at: now,
+            updated_at: now,
+            tenant_id: user_tenant(user),
+        };
+        self.validate(&contract).await?;
+
+        let created: Vec<Contract> = self.db.create("contracts").content(&contract).await?;
+        let created = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ContractError::DatabaseError("Failed to create contract".to_string()))?;
+        Ok(with_status(created, now))
+    }
+
+    pub async fn update_contract(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: UpdateContractRequest,
+    ) -> Result<ContractResponse, ContractError> {
+        let mut contract = self.load(user, id).await?;
+
+        if let Some(name) = request.name {
+            contract.name = name.trim().to_string();
+        }
+        if let Some(vendor) = request.vendor {
+            contract.vendor = vendor.trim().to_string();
+        }
+        if let Some(contract_number) = request.contract_number {
+            contract.contract_number = Some(contract_number);
+        }
+        if let Some(contract_type) = request.contract_type {
+            contract.contract_type = contract_type;
+        }
+        if let Some(start_date) = request.start_date {
+            contract.start_date = start_date;
+        }
+        if let Some(end_date) = request.end_date {
+            if end_date != contract.end_date {
+                contract.alerts_sent.clear();
+            }
+            contract.end_date = end_date;
+        }
+        if let Some(cost) = request.cost {
+            contract.cost = Some(cost);
+        }
+        if let Some(currency) = request.currency {
+            contract.currency = Some(currency.to_uppercase());
+        }
+        if let Some(coverage_level) = request.coverage_level {
+            contract.coverage_level = Some(coverage_level);
+        }
+        if let Some(hours) = request.response_time_hours {
+            contract.response_time_hours = Some(hours);
+        }
+        if let Some(support_contact) = request.support_contact {
+            contract.support_contact = support_contact;
+        }
+        if let Some(covered_cis) = request.covered_cis {
+            contract.covered_cis = covered_cis
+                .iter()
+                .map(|id| parse_thing("configuration_items", id))
+                .collect();
+        }
+        if let Some(notify) = request.notify {
+            contract.notify = notify;
+        }
+        if let Some(alert_days) = request.alert_days {
+            contract.alerts_sent.retain(|d| alert_days.contains(d));
+            contract.alert_days = alert_days;
+        }
+        if let Some(auto_renew) = request.auto_renew {
+            contract.auto_renew = auto_renew;
+        }
+        if let Some(notes) = request.notes {
+            contract.notes = Some(notes);
+        }
+        self.validate(&contract).await?;
+
+        let now = Utc::now();
+        contract.updated_at = now;
+        let thing = contract.id.clone().ok_or(ContractError::NotFound)?;
+        let updated: Option<Contract> = self.db.update(thing).content(&contract).await?;
+        updated.map(|c| with_status(c, now)).ok_or(ContractError::NotFound)
+    }
+
+    pub async fn delete_contract(&self, user: &AuthenticatedUser, id: &str) -> Result<(), ContractError> {
+        let contract = self.load(user, id).await?;
+        let thing = contract.id.ok_or(ContractError::NotFound)?;
+        let _: Option<Contract> = self.db.delete(thing).await?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // EXPIRY ALERTS
+    // ========================================================================
+
+    /// Alert each contract's recipients once per threshold in `alert_days`.
+    /// Returns the number of contracts alerted.
+    pub async fn send_expiry_alerts(&self) -> Result<usize, ContractError> {
+        let now = Utc::now();
+        let contracts: Vec<Contract> = self
+            .db
+            .query("SELECT * FROM contracts WHERE end_date > $now")
+            .bind(("now", now))
+            .traced("contract_service.send_expiry_alerts")
+            .await?
+            .take(0)?;
+
+        let mut alerted = 0;
+        for contract in contracts {
+            let due = due_thresholds(&contract, now);
+            if due.is_empty() {
+                continue;
+            }
+
+            let recipients = self.recipient_emails(&contract.notify).await?;
+            if !recipients.is_empty() {
+                let days = contract.days_remaining(now);
+                let subject = format!("Contract expiring in {} day(s): {} ({})", days, contract.name, contract.vendor);
+                let body = render_alert(&contract, days);
+                for result in self.notifier.send_email(&recipients, &subject, &body, None).await {
+                    info!("Contract expiry alert to {}: {:?}", result.target, result.status);
+                }
+                alerted += 1;
+            }
+
+            // Mark every passed threshold so a late first run sends one alert, not several
+            if let Some(id) = &contract.id {
+                self.db
+                    .query("UPDATE $id SET alerts_sent = array::union(alerts_sent, $due)")
+                    .bind(("id", id.clone()))
+                    .bind(("due", due))
+                    .traced("contract_service.send_expiry_alerts")
+                    .await?;
+            }
+        }
+        Ok(alerted)
+    }
+
+    /// Emails for `notify` entries; user ids are looked up, anything with an
+    /// `@` is used as is
+    async fn recipient_emails(&self, notify: &[String]) -> Result<Vec<String>, ContractError> {
+        let (addresses, user_ids): (Vec<&String>, Vec<&String>) = notify.iter().partition(|n| n.contains('@'));
+        let mut emails: Vec<String> = addresses.into_iter().cloned().collect();
+
+        let records: Vec<Thing> = user_ids
+            .iter()
+            .filter_map(|id| surrealdb::sql::thing(id).ok())
+            .collect();
+        if !records.is_empty() {
+            let users: Vec<UserEmail> = self
+                .db
+                .query("SELECT id, email FROM $users")
+                .bind(("users", records))
+                .traced("contract_service.recipient_emails")
+                .await?
+                .take(0)?;
+            let by_id: HashMap<String, String> = users.into_iter().map(|u| (u.id.to_string(), u.email)).collect();
+            emails.extend(user_ids.iter().filter_map(|id| by_id.get(*id).cloned()));
+        }
+        emails.sort();
+        emails.dedup();
+        Ok(emails)
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn load(&self, user: &AuthenticatedUser, id: &str) -> Result<Contract, ContractError> {
+        let contract: Option<Contract> = self.db.select(parse_thing("contracts", id)).await?;
+        let tenant = user_tenant(user);
+        contract.filter(|c| c.tenant_id == tenant).ok_or(ContractError::NotFound)
+    }
+
+    async fn validate(&self, contract: &Contract) -> Result<(), ContractError> {
+        if contract.name.is_empty() {
+            return Err(ContractError::Validation("Contract name is required".to_string()));
+        }
+        if contract.vendor.is_empty() {
+            return Err(ContractError::Validation("Vendor is required".to_string()));
+        }
+        if contract.end_date <= contract.start_date {
+            return Err(ContractError::Validation("end_date must be after start_date".to_string()));
+        }
+        if contract.cost.map_or(false, |c| !c.is_finite() || c < 0.0) {
+            return Err(ContractError::Validation("cost must not be negative".to_string()));
+        }
+        if contract.alert_days.iter().any(|d| *d > 730) {
+            return Err(ContractError::Validation("alert_days must be at most 730".to_string()));
+        }
+
+        if !contract.covered_cis.is_empty() {
+            let found: Vec<Thing> = self
+                .db
+                .query("SELECT VALUE id FROM configuration_items WHERE id INSIDE $ids")
+                .bind(("ids", contract.covered_cis.clone()))
+                .traced("contract_service.validate")
+                .await?
+                .take(0)?;
+            if let Some(missing) = contract.covered_cis.iter().find(|id| !found.contains(id)) {
+                return Err(ContractError::Validation(format!("CI {} not found", missing)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Contracts covering `ci`, current ones first, for CI detail views
+pub async fn coverage_for_ci(db: &Database, ci: &Thing) -> Result<Vec<ContractCoverage>, surrealdb::Error> {
+    let contracts: Vec<Contract> = db
+        .query("SELECT * FROM contracts WHERE covered_cis CONTAINS $ci ORDER BY end_date DESC")
+        .bind(("ci", ci.clone()))
+        .traced("contract_service.coverage_for_ci")
+        .await?
+        .take(0)?;
+
+    let now = Utc::now();
+    let mut coverage: Vec<ContractCoverage> = contracts
+        .iter()
+        .map(|c| ContractCoverage::from_contract(c, now))
+        .collect();
+    coverage.sort_by_key(|c| c.status == ContractStatus::Expired);
+    Ok(coverage)
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+fn with_status(contract: Contract, now: DateTime<Utc>) -> ContractResponse {
+    ContractResponse {
+        status: contract.status(now),
+        days_remaining: contract.days_remaining(now),
+        contract,
+    }
+}
+
+/// Alert thresholds reached but not yet alerted for the current end date
+fn due_thresholds(contract: &Contract, now: DateTime<Utc>) -> Vec<u32> {
+    let remaining = contract.days_remaining(now);
+    contract
+        .alert_days
+        .iter()
+        .copied()
+        .filter(|d| remaining <= *d as i64 && !contract.alerts_sent.contains(d))
+        .collect()
+}
+
+fn render_alert(contract: &Contract, days: i64) -> String {
+    let kind = match contract.contract_type {
+        ContractType::Support => "Support",
+        ContractType::Maintenance => "Maintenance",
+        ContractType::Warranty => "Warranty",
+        ContractType::License => "License",
+        ContractType::Subscription => "Subscription",
+        ContractType::Lease => "Lease",
+    };
+    let mut body = format!(
+        "{} contract \"{}\" with {} ends on {} ({} day(s) from now).\n",
+        kind,
+        contract.name,
+        contract.vendor,
+        contract.end_date.format("%Y-%m-%d"),
+        days
+    );
+    if let Some(number) = &contract.contract_number {
+        body.push_str(&format!("Contract number: {}\n", number));
+    }
+    body.push_str(&format!("Covered CIs: {}\n", contract.covered_cis.len()));
+    if contract.auto_renew {
+        body.push_str("The contract is set to renew automatically.\n");
+    } else {
+        body.push_str("The contract does not renew automatically; arrange a renewal or replacement.\n");
+    }
+    body
+}
+
+fn user_tenant(user: &AuthenticatedUser) -> Option<Thing> {
+    user.tenant_id.as_deref().map(|t| Thing::from(("tenants", t)))
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+// ============================================================================
+// EXPIRY ALERT SCHEDULER
+// ============================================================================
+
+pub struct ContractAlertScheduler {
+    db: Arc<Database>,
+}
+
+impl ContractAlertScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+
+        let cron = std::env::var("CONTRACT_ALERT_CRON").unwrap_or_else(|_| DEFAULT_ALERT_CRON.to_string());
+        let db = Arc::clone(&self.db);
+        let service = Arc::new(ContractService::new(Arc::clone(&self.db)));
+        let job = Job::new_async(cron.as_str(), move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                let run = locks::run_exclusive(&db, locks::CONTRACT_ALERTS_LOCK, ALERT_LOCK_TTL, service.send_expiry_alerts());
+                match run.await {
+                    Ok(None) | Ok(Some(Ok(0))) => {}
+                    Ok(Some(Ok(count))) => info!("📄 Sent expiry alerts for {} contract(s)", count),
+                    Ok(Some(Err(e))) => error!("❌ Contract expiry alert run failed: {}", e),
+                    Err(e) => error!("❌ Contract expiry alert lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create contract alert job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add contract alert job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+
+        // Keep the scheduler alive for the process lifetime
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn contract(end_date: DateTime<Utc>) -> Contract {
+        Contract {
+            id: None,
+            name: "ProSupport Plus".to_string(),
+            vendor: "Dell".to_string(),
+            contract_number: Some("DL-4471".to_string()),
+            contract_type: ContractType::Support,
+            start_date: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+            end_date,
+            cost: Some(18_000.0),
+            currency: Some("EUR".to_string()),
+            coverage_level: Some("24x7, 4h onsite".to_string()),
+            response_time_hours: Some(4),
+            support_contact: SupportContact::default(),
+            covered_cis: Vec::new(),
+            notify: Vec::new(),
+            alert_days: default_alert_days(),
+            alerts_sent: Vec::new(),
+            auto_renew: false,
+            notes: None,
+            created_by: "users:admin".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            tenant_id: None,
+        }
+    }
+
+    #[test]
+    fn test_status_and_due_thresholds() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 6, 0, 0).unwrap();
+
+        let mut c = contract(now + Duration::days(200));
+        assert_eq!(c.status(now), ContractStatus::Active);
+        assert!(due_thresholds(&c, now).is_empty());
+
+        // First run after both the 90 and 30 day marks passed
+        c.end_date = now + Duration::days(20);
+        assert_eq!(c.status(now), ContractStatus::Expiring);
+        assert_eq!(due_thresholds(&c, now), vec![90, 30]);
+
+        c.alerts_sent = vec![90, 30];
+        assert!(due_thresholds(&c, now).is_empty());
+        assert_eq!(due_thresholds(&c, now + Duration::days(14)), vec![7]);
+
+        assert_eq!(c.status(now + Duration::days(21)), ContractStatus::Expired);
+        assert_eq!(c.status(Utc.with_ymd_and_hms(2022, 6, 1, 0, 0, 0).unwrap()), ContractStatus::Pending);
+    }
+}
@@ -0,0 +1,684 @@
+//! CPU Compatibility Service
+//!
+//! Groups a project's source hosts by CPU generation and EVC baseline and
+//! works out the baseline of each target cluster from its hardware profile,
+//! its destination nodes or the vendor catalog. From the two it decides per
+//! VM and per wave whether vMotion can move the workload live or it has to
+//! be migrated cold, and records that on the strategy recommendations.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use core_engine::vendor_data::VendorDataManager;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::database::Database;
+use crate::models::cpu_compatibility::*;
+use crate::models::migration_wizard_models::{
+    MigrationWizardCluster, MigrationWizardPlacement, MigrationWizardVM, SourceHost, SourceInventory,
+    StrategyRecommendation,
+};
+use crate::models::project_models::{DestinationCluster, HardwarePool, HypervisorType};
+use crate::models::timeline::MigrationWave;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::timeline_service::TimelineService;
+
+/// Known generations, oldest first per vendor: (vendor, generation, EVC
+/// mode, rank). Generations without an EVC mode of their own share the
+/// previous one but rank above it, so a VM started on them still counts as
+/// newer than a host of that previous generation.
+const GENERATIONS: &[(CpuVendor, &str, &str, u8)] = &[
+    (CpuVendor::Intel, "Nehalem", "intel-nehalem", 1),
+    (CpuVendor::Intel, "Westmere", "intel-westmere", 2),
+    (CpuVendor::Intel, "Sandy Bridge", "intel-sandybridge", 3),
+    (CpuVendor::Intel, "Ivy Bridge", "intel-ivybridge", 4),
+    (CpuVendor::Intel, "Haswell", "intel-haswell", 5),
+    (CpuVendor::Intel, "Broadwell", "intel-broadwell", 6),
+    (CpuVendor::Intel, "Skylake", "intel-skylake", 7),
+    (CpuVendor::Intel, "Cascade Lake", "intel-cascadelake", 8),
+    (CpuVendor::Intel, "Ice Lake", "intel-icelake", 9),
+    (CpuVendor::Intel, "Sapphire Rapids", "intel-sapphirerapids", 10),
+    (CpuVendor::Intel, "Emerald Rapids", "intel-sapphirerapids", 11),
+    (CpuVendor::Amd, "Zen", "amd-zen", 1),
+    (CpuVendor::Amd, "Zen 2", "amd-zen2", 2),
+    (CpuVendor::Amd, "Zen 3", "amd-zen3", 3),
+    (CpuVendor::Amd, "Zen 4", "amd-zen4", 4),
+    (CpuVendor::Amd, "Zen 5", "amd-zen4", 5),
+];
+
+pub struct CpuCompatibilityService {
+    db: Database,
+}
+
+impl CpuCompatibilityService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn analyze(&self, project_id: &str) -> Result<CpuCompatibilityReport> {
+        let vms = MigrationWizardService::new(self.db.clone()).get_project_vms(project_id, None).await?;
+        self.analyze_vms(project_id, &vms).await
+    }
+
+    /// Analyze the given project VMs, for callers that already loaded them
+    pub async fn analyze_vms(&self, project_id: &str, vms: &[MigrationWizardVM]) -> Result<CpuCompatibilityReport> {
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let inventory = wizard.get_source_inventory(project_id).await?;
+        let placements = wizard.get_project_placements(project_id).await?;
+        let waves = TimelineService::new(self.db.clone()).list_waves(project_id).await?;
+
+        let mut targets = Vec::new();
+        for cluster in wizard.get_project_clusters(project_id).await? {
+            targets.push(self.target_profile(&cluster).await?);
+        }
+
+        Ok(analyze(project_id, &inventory, vms, &placements, &waves, targets, Utc::now()))
+    }
+
+    /// CPUs of a target cluster: its hardware profile's `cpu_model`, else
+    /// the `cpu_model` recorded on its destination nodes, else every CPU
+    /// the vendor catalog offers for the nodes' server models
+    async fn target_profile(&self, cluster: &MigrationWizardCluster) -> Result<TargetCpuProfile> {
+        let destination: Option<DestinationCluster> = match &cluster.destination_cluster_id {
+            Some(id) => self.db.select(("destination_cluster", id.as_str())).await?,
+            None => None,
+        };
+        let hypervisor = destination.as_ref().map(|d| d.hypervisor);
+
+        if let Some(cpu_model) = &cluster.cpu_model {
+            return Ok(target_profile(cluster, hypervisor, TargetCpuSource::ClusterProfile, vec![cpu_model.clone()]));
+        }
+
+        let mut nodes = Vec::new();
+        for node_thing in destination.iter().flat_map(|d| &d.nodes) {
+            let node: Option<HardwarePool> = self.db.select(node_thing.clone()).await?;
+            nodes.extend(node);
+        }
+        let recorded: BTreeSet<String> = nodes
+            .iter()
+            .filter_map(|node| node.metadata.get("cpu_model").and_then(Value::as_str).map(str::to_string))
+            .collect();
+        if !recorded.is_empty() {
+            return Ok(target_profile(cluster, hypervisor, TargetCpuSource::NodeHardware, recorded.into_iter().collect()));
+        }
+
+        let vendor_data = VendorDataManager::new();
+        let server_models: BTreeSet<(&str, &str)> =
+            nodes.iter().map(|node| (node.vendor.as_str(), node.model.as_str())).collect();
+        let mut offered = BTreeSet::new();
+        for (vendor, model) in server_models {
+            match vendor_data.get_model_specifications(vendor, model).await {
+                Ok(specs) => offered.extend(specs.supported_cpus.into_iter().map(|cpu| cpu.model_name)),
+                Err(e) => tracing::warn!("No vendor specs for {} {}: {}", vendor, model, e),
+            }
+        }
+        let source = if offered.is_empty() { TargetCpuSource::Unknown } else { TargetCpuSource::VendorSpec };
+        Ok(target_profile(cluster, hypervisor, source, offered.into_iter().collect()))
+    }
+}
+
+fn baseline(vendor: CpuVendor, generation: &str) -> Option<CpuBaseline> {
+    GENERATIONS
+        .iter()
+        .find(|(v, g, _, _)| *v == vendor && *g == generation)
+        .map(|&(vendor, generation, evc_mode, rank)| CpuBaseline {
+            vendor,
+            generation: generation.to_string(),
+            evc_mode: evc_mode.to_string(),
+            rank,
+        })
+}
+
+/// Generation of a CPU from its model string as vSphere reports it, e.g.
+/// "Intel(R) Xeon(R) Gold 6248R CPU @ 3.00GHz" or "AMD EPYC 7543 32-Core
+/// Processor". None for CPUs it does not know.
+pub fn classify_cpu(model: &str) -> Option<CpuBaseline> {
+    let upper = model.to_ascii_uppercase();
+    let words: Vec<&str> = upper
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let next = |i: usize| words.get(i + 1).copied().unwrap_or_default();
+
+    for (i, word) in words.iter().enumerate() {
+        match *word {
+            "PLATINUM" | "GOLD" | "SILVER" | "BRONZE" => {
+                let number = next(i);
+                if number.len() < 4 {
+                    continue;
+                }
+                let generation = match number.as_bytes()[1] {
+                    // Cooper Lake (8380H, 8376HL) carries Cascade Lake's EVC mode
+                    b'3' if number[4..].starts_with('H') => "Cascade Lake",
+                    b'1' => "Skylake",
+                    b'2' => "Cascade Lake",
+                    b'3' => "Ice Lake",
+                    b'4' => "Sapphire Rapids",
+                    b'5' => "Emerald Rapids",
+                    _ => continue,
+                };
+                return baseline(CpuVendor::Intel, generation);
+            }
+            "EPYC" => {
+                let generation = match next(i).as_bytes().get(3) {
+                    Some(b'1') => "Zen",
+                    Some(b'2') => "Zen 2",
+                    Some(b'3') => "Zen 3",
+                    Some(b'4') => "Zen 4",
+                    Some(b'5') => "Zen 5",
+                    _ => continue,
+                };
+                return baseline(CpuVendor::Amd, generation);
+            }
+            _ if word.starts_with("E5-") || word.starts_with("E7-") => {
+                // The version follows as its own word ("E5-2680 v4") or
+                // is glued on ("E5-2680V4")
+                let version = match word[3..].find('V') {
+                    Some(at) => &word[3 + at..],
+                    None if next(i).starts_with('V') => next(i),
+                    None => "",
+                };
+                let generation = match (version, word.starts_with("E5-")) {
+                    ("V2", _) => "Ivy Bridge",
+                    ("V3", _) => "Haswell",
+                    ("V4", _) => "Broadwell",
+                    ("", true) => "Sandy Bridge",
+                    ("", false) => "Westmere",
+                    _ => continue,
+                };
+                return baseline(CpuVendor::Intel, generation);
+            }
+            // Xeon 5500 and 5600 series, e.g. X5670 or E5520
+            _ if word.len() == 5
+                && matches!(word.as_bytes()[0], b'E' | b'L' | b'W' | b'X')
+                && word[1..].bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                match &word[1..3] {
+                    "55" => return baseline(CpuVendor::Intel, "Nehalem"),
+                    "56" => return baseline(CpuVendor::Intel, "Westmere"),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Baseline of an EVC mode, given as its key ("intel-broadwell") or as the
+/// vSphere Client shows it (Intel® "Broadwell" Generation)
+pub fn evc_baseline(mode: &str) -> Option<CpuBaseline> {
+    let normalized: String = mode.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_ascii_lowercase();
+    let (vendor, rest) = if let Some(rest) = normalized.strip_prefix("intel") {
+        (CpuVendor::Intel, rest)
+    } else if let Some(rest) = normalized.strip_prefix("amd") {
+        (CpuVendor::Amd, rest)
+    } else {
+        return None;
+    };
+    let rest = rest.strip_suffix("generation").unwrap_or(rest);
+
+    GENERATIONS
+        .iter()
+        .find(|(v, _, evc_mode, _)| *v == vendor && evc_mode.split_once('-').map(|(_, key)| key) == Some(rest))
+        .and_then(|(vendor, generation, _, _)| baseline(*vendor, generation))
+}
+
+/// A host's baseline and whether it comes from an active EVC mode
+fn host_baseline(host: &SourceHost) -> (Option<CpuBaseline>, bool) {
+    match host.current_evc.as_deref().and_then(evc_baseline) {
+        Some(evc) => (Some(evc), true),
+        None => (host.cpu_model.as_deref().and_then(classify_cpu), false),
+    }
+}
+
+/// Source hosts grouped by baseline, oldest first; unknown CPUs come last
+pub fn group_source_hosts(hosts: &[SourceHost]) -> Vec<SourceCpuGroup> {
+    let mut groups: BTreeMap<(Option<(CpuVendor, u8)>, bool), SourceCpuGroup> = BTreeMap::new();
+    for host in hosts {
+        let (baseline, evc_enabled) = host_baseline(host);
+        let key = (baseline.as_ref().map(|b| (b.vendor, b.rank)), evc_enabled);
+        let group = groups.entry(key).or_insert_with(|| SourceCpuGroup {
+            baseline,
+            evc_enabled,
+            hosts: Vec::new(),
+            clusters: Vec::new(),
+            cpu_models: Vec::new(),
+            vm_count: 0,
+        });
+        group.hosts.push(host.name.clone());
+        group.vm_count += host.vm_count;
+        for (list, value) in [(&mut group.clusters, &host.cluster), (&mut group.cpu_models, &host.cpu_model)] {
+            if let Some(value) = value {
+                if !list.contains(value) {
+                    list.push(value.clone());
+                }
+            }
+        }
+    }
+
+    let (known, unknown): (Vec<_>, Vec<_>) = groups.into_values().partition(|g| g.baseline.is_some());
+    known.into_iter().chain(unknown).collect()
+}
+
+/// A target's baseline is the oldest generation among its CPUs: EVC has to
+/// mask the cluster down to it for VMs to move freely between its hosts
+pub fn target_profile(
+    cluster: &MigrationWizardCluster,
+    hypervisor: Option<HypervisorType>,
+    source: TargetCpuSource,
+    cpu_models: Vec<String>,
+) -> TargetCpuProfile {
+    TargetCpuProfile {
+        cluster_id: cluster.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+        cluster_name: cluster.name.clone(),
+        hypervisor: hypervisor
+            .and_then(|h| serde_json::to_value(h).ok())
+            .and_then(|v| v.as_str().map(str::to_string)),
+        baseline: cpu_models.iter().filter_map(|model| classify_cpu(model)).min_by_key(|b| b.rank),
+        cpu_models,
+        source,
+    }
+}
+
+/// Live or cold for a VM with the given source baseline onto a target
+pub fn decide(source: Option<&CpuBaseline>, target: &TargetCpuProfile) -> (MigrationMode, String) {
+    if let Some(hypervisor) = target.hypervisor.as_deref().filter(|h| *h != "vmware") {
+        return (
+            MigrationMode::Cold,
+            format!("{} runs {}; the VM is converted while powered off", target.cluster_name, hypervisor),
+        );
+    }
+    let Some(source) = source else {
+        return (
+            MigrationMode::Cold,
+            "The CPU generation of the source host could not be determined; plan a cold migration".to_string(),
+        );
+    };
+    let Some(target_baseline) = &target.baseline else {
+        return (
+            MigrationMode::Cold,
+            format!("The CPU generation of {} could not be determined; plan a cold migration", target.cluster_name),
+        );
+    };
+
+    if source.vendor != target_baseline.vendor {
+        (
+            MigrationMode::Cold,
+            format!(
+                "{} runs {} CPUs but the source runs {}; vMotion cannot cross CPU vendors",
+                target.cluster_name,
+                target_baseline.vendor.label(),
+                source.label()
+            ),
+        )
+    } else if source.rank > target_baseline.rank {
+        (
+            MigrationMode::Cold,
+            format!(
+                "Source baseline {} is newer than {} on {}; a running VM cannot vMotion to an older CPU generation",
+                source.label(),
+                target_baseline.label(),
+                target.cluster_name
+            ),
+        )
+    } else {
+        (
+            MigrationMode::Live,
+            format!(
+                "{} on {} supports the source baseline {} (EVC {}), so the VM can vMotion live",
+                target_baseline.label(),
+                target.cluster_name,
+                source.label(),
+                source.evc_mode
+            ),
+        )
+    }
+}
+
+/// Group the source hosts and decide the mode of every VM with a target:
+/// its placement's cluster, else its wave's target clusters, where the VM
+/// moves cold if any of them requires it
+pub fn analyze(
+    project_id: &str,
+    inventory: &SourceInventory,
+    vms: &[MigrationWizardVM],
+    placements: &[MigrationWizardPlacement],
+    waves: &[MigrationWave],
+    targets: Vec<TargetCpuProfile>,
+    now: DateTime<Utc>,
+) -> CpuCompatibilityReport {
+    let hosts: HashMap<&str, &SourceHost> = inventory.hosts.iter().map(|h| (h.name.as_str(), h)).collect();
+    let target_by_id: HashMap<&str, &TargetCpuProfile> = targets.iter().map(|t| (t.cluster_id.as_str(), t)).collect();
+    let placed_on: HashMap<String, String> = placements
+        .iter()
+        .map(|p| (p.vm_id.id.to_raw(), p.cluster_id.id.to_raw()))
+        .collect();
+    let wave_of: HashMap<String, &MigrationWave> = waves
+        .iter()
+        .flat_map(|wave| wave.vm_ids.iter().map(move |id| (id.id.to_raw(), wave)))
+        .collect();
+
+    // Without a known host a VM may run on any host of its cluster, so
+    // the cluster's newest baseline is assumed
+    let source_of = |vm: &MigrationWizardVM| -> Option<CpuBaseline> {
+        if let Some(host) = vm.host.as_deref().and_then(|name| hosts.get(name)) {
+            return host_baseline(host).0;
+        }
+        let cluster = vm.cluster.as_deref()?;
+        inventory
+            .hosts
+            .iter()
+            .filter(|h| h.cluster.as_deref() == Some(cluster))
+            .filter_map(|h| host_baseline(h).0)
+            .max_by_key(|b| b.rank)
+    };
+
+    let mut decisions = Vec::new();
+    for vm in vms.iter().filter(|vm| vm.template != Some(true)) {
+        let vm_id = vm.id.as_ref().map(|id| id.id.to_raw());
+        let candidates: Vec<&TargetCpuProfile> = match vm_id.as_ref().and_then(|id| placed_on.get(id)) {
+            Some(cluster_id) => target_by_id.get(cluster_id.as_str()).copied().into_iter().collect(),
+            None => vm_id
+                .as_ref()
+                .and_then(|id| wave_of.get(id))
+                .map(|wave| {
+                    wave.target_cluster_ids
+                        .iter()
+                        .filter_map(|id| target_by_id.get(id.id.to_raw().as_str()).copied())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
+        let source = source_of(vm);
+        let outcomes: Vec<(&TargetCpuProfile, (MigrationMode, String))> =
+            candidates.into_iter().map(|target| (target, decide(source.as_ref(), target))).collect();
+        let Some((target, (mode, rationale))) = outcomes
+            .iter()
+            .find(|(_, (mode, _))| *mode == MigrationMode::Cold)
+            .or(outcomes.first())
+            .cloned()
+        else {
+            continue;
+        };
+        decisions.push(VmMigrationMode {
+            vm_id,
+            vm_name: vm.name.clone(),
+            source_baseline: source,
+            target_cluster_id: target.cluster_id.clone(),
+            target_cluster_name: target.cluster_name.clone(),
+            mode,
+            rationale,
+        });
+    }
+    decisions.sort_by(|a, b| a.vm_name.cmp(&b.vm_name));
+
+    CpuCompatibilityReport {
+        project_id: project_id.to_string(),
+        source_groups: group_source_hosts(&inventory.hosts),
+        waves: wave_modes(waves, &decisions),
+        vms: decisions,
+        targets,
+        generated_at: now,
+    }
+}
+
+/// A wave moves live when all its decided VMs do; waves without any are
+/// left out
+fn wave_modes(waves: &[MigrationWave], decisions: &[VmMigrationMode]) -> Vec<WaveMigrationMode> {
+    let by_id: HashMap<&str, &VmMigrationMode> =
+        decisions.iter().filter_map(|d| Some((d.vm_id.as_deref()?, d))).collect();
+
+    waves
+        .iter()
+        .filter_map(|wave| {
+            let mut members: Vec<&VmMigrationMode> =
+                wave.vm_ids.iter().filter_map(|id| by_id.get(id.id.to_raw().as_str()).copied()).collect();
+            if members.is_empty() {
+                return None;
+            }
+            members.sort_by(|a, b| a.vm_name.cmp(&b.vm_name));
+            let cold: Vec<&VmMigrationMode> = members.iter().copied().filter(|d| d.mode == MigrationMode::Cold).collect();
+            let (mode, rationale) = match cold.first() {
+                None => (MigrationMode::Live, format!("All {} VMs can vMotion live", members.len())),
+                Some(first) => (
+                    MigrationMode::Cold,
+                    format!(
+                        "{} of {} VMs need a cold migration; {}: {}",
+                        cold.len(),
+                        members.len(),
+                        first.vm_name,
+                        first.rationale
+                    ),
+                ),
+            };
+            Some(WaveMigrationMode {
+                wave_id: wave.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+                wave_name: wave.name.clone(),
+                mode,
+                live_vms: members.len() - cold.len(),
+                cold_vms: cold.iter().map(|d| d.vm_name.clone()).collect(),
+                rationale,
+            })
+        })
+        .collect()
+}
+
+/// Record each VM's migration mode and its rationale on its recommendation
+pub fn annotate(recommendations: &mut [StrategyRecommendation], report: &CpuCompatibilityReport) {
+    let by_name: HashMap<&str, &VmMigrationMode> = report.vms.iter().map(|d| (d.vm_name.as_str(), d)).collect();
+    for recommendation in recommendations {
+        if let Some(decision) = by_name.get(recommendation.vm_name.as_str()) {
+            recommendation.migration_mode = Some(decision.mode);
+            recommendation.migration_mode_rationale = Some(decision.rationale.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::timeline::WaveStatus;
+    use surrealdb::sql::Thing;
+
+    fn host(name: &str, cluster: &str, cpu_model: &str, current_evc: Option<&str>) -> SourceHost {
+        SourceHost {
+            name: name.to_string(),
+            cluster: Some(cluster.to_string()),
+            cpu_model: Some(cpu_model.to_string()),
+            current_evc: current_evc.map(str::to_string),
+            vm_count: 10,
+            ..Default::default()
+        }
+    }
+
+    fn vm(key: &str, host: &str) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_string(),
+            powerstate: Some("poweredOn".to_string()),
+            template: None,
+            cpus: 2,
+            memory_mb: 4096,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: None,
+            dns_name: None,
+            cluster: None,
+            host: Some(host.to_string()),
+            datacenter: None,
+            os: None,
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn target(key: &str, hypervisor: Option<&str>, cpu_model: &str) -> TargetCpuProfile {
+        TargetCpuProfile {
+            cluster_id: key.to_string(),
+            cluster_name: key.to_string(),
+            hypervisor: hypervisor.map(str::to_string),
+            cpu_models: vec![cpu_model.to_string()],
+            baseline: classify_cpu(cpu_model),
+            source: TargetCpuSource::ClusterProfile,
+        }
+    }
+
+    fn wave(key: &str, vm_ids: &[&str], targets: &[&str]) -> MigrationWave {
+        MigrationWave {
+            id: Some(Thing::from(("migration_wave", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_string(),
+            sequence: 1,
+            vm_ids: vm_ids.iter().map(|id| Thing::from(("migration_wizard_vm", *id))).collect(),
+            target_cluster_ids: targets.iter().map(|id| Thing::from(("migration_wizard_cluster", *id))).collect(),
+            planned_start: None,
+            planned_end: None,
+            change_window: None,
+            status: WaveStatus::Planned,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_classifies_cpu_models_and_evc_modes() {
+        let generation = |model: &str| classify_cpu(model).map(|b| b.label());
+        assert_eq!(generation("Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz").as_deref(), Some("Intel Broadwell"));
+        assert_eq!(generation("Intel(R) Xeon(R) CPU E5-2650 0 @ 2.00GHz").as_deref(), Some("Intel Sandy Bridge"));
+        assert_eq!(generation("Intel(R) Xeon(R) CPU E7-8890V3 @ 2.50GHz").as_deref(), Some("Intel Haswell"));
+        assert_eq!(generation("Intel(R) Xeon(R) CPU X5670 @ 2.93GHz").as_deref(), Some("Intel Westmere"));
+        assert_eq!(generation("Intel(R) Xeon(R) Gold 6248R CPU @ 3.00GHz").as_deref(), Some("Intel Cascade Lake"));
+        assert_eq!(generation("Intel(R) Xeon(R) Platinum 8380H CPU @ 2.90GHz").as_deref(), Some("Intel Cascade Lake"));
+        assert_eq!(generation("Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz").as_deref(), Some("Intel Ice Lake"));
+        assert_eq!(generation("Intel Xeon Gold 6430").as_deref(), Some("Intel Sapphire Rapids"));
+        assert_eq!(generation("AMD EPYC 7543 32-Core Processor").as_deref(), Some("AMD Zen 3"));
+        assert_eq!(generation("AMD EPYC 9654P 96-Core Processor").as_deref(), Some("AMD Zen 4"));
+        assert_eq!(generation("Apple M2"), None);
+
+        assert_eq!(evc_baseline("intel-broadwell").map(|b| b.rank), Some(6));
+        assert_eq!(evc_baseline("Intel® \"Cascade Lake\" Generation").map(|b| b.label()).as_deref(), Some("Intel Cascade Lake"));
+        assert_eq!(evc_baseline("AMD \"Zen 2\" Generation").map(|b| b.evc_mode).as_deref(), Some("amd-zen2"));
+        assert_eq!(evc_baseline("amd-zen4").map(|b| b.generation).as_deref(), Some("Zen 4"));
+        assert_eq!(evc_baseline("Disabled"), None);
+    }
+
+    #[test]
+    fn test_groups_hosts_by_evc_mode_before_cpu_generation() {
+        let hosts = vec![
+            host("esx01", "Prod", "Intel(R) Xeon(R) Gold 6248R CPU @ 3.00GHz", Some("intel-broadwell")),
+            host("esx02", "Prod", "Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz", Some("intel-broadwell")),
+            host("esx03", "Dev", "Intel(R) Xeon(R) Gold 6248R CPU @ 3.00GHz", None),
+            host("esx04", "Lab", "Mystery CPU", None),
+        ];
+
+        let groups = group_source_hosts(&hosts);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].baseline.as_ref().map(|b| b.evc_mode.as_str()), Some("intel-broadwell"));
+        assert!(groups[0].evc_enabled);
+        assert_eq!(groups[0].hosts, vec!["esx01", "esx02"]);
+        assert_eq!((groups[0].cpu_models.len(), groups[0].vm_count), (2, 20));
+        assert_eq!(groups[1].baseline.as_ref().map(|b| b.generation.as_str()), Some("Cascade Lake"));
+        assert!(!groups[1].evc_enabled);
+        assert!(groups[2].baseline.is_none(), "unknown CPUs come last");
+    }
+
+    #[test]
+    fn test_decides_live_only_onto_same_vendor_same_or_newer_vmware_target() {
+        let broadwell = evc_baseline("intel-broadwell");
+        let icelake = classify_cpu("Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz");
+
+        let (mode, rationale) = decide(broadwell.as_ref(), &target("new", Some("vmware"), "Intel Xeon Gold 6430"));
+        assert_eq!(mode, MigrationMode::Live);
+        assert!(rationale.contains("EVC intel-broadwell"));
+
+        let (mode, rationale) = decide(icelake.as_ref(), &target("old", None, "Intel Xeon Gold 6248"));
+        assert_eq!(mode, MigrationMode::Cold);
+        assert!(rationale.contains("older CPU generation"));
+
+        let (mode, rationale) = decide(broadwell.as_ref(), &target("epyc", None, "AMD EPYC 9354 32-Core Processor"));
+        assert_eq!(mode, MigrationMode::Cold);
+        assert!(rationale.contains("cannot cross CPU vendors"));
+
+        let (mode, rationale) = decide(broadwell.as_ref(), &target("hv", Some("hyper-v"), "Intel Xeon Gold 6430"));
+        assert_eq!(mode, MigrationMode::Cold);
+        assert!(rationale.contains("hyper-v"));
+
+        assert_eq!(decide(None, &target("new", None, "Intel Xeon Gold 6430")).0, MigrationMode::Cold);
+        assert_eq!(decide(broadwell.as_ref(), &target("new", None, "Unknown")).0, MigrationMode::Cold);
+    }
+
+    #[test]
+    fn test_wave_is_cold_when_any_vm_is_and_placements_win_over_wave_targets() {
+        let inventory = SourceInventory {
+            hosts: vec![
+                host("esx01", "Prod", "Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz", None),
+                host("esx02", "Prod", "Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz", None),
+            ],
+            ..Default::default()
+        };
+        let vms = vec![vm("app01", "esx01"), vm("app02", "esx02"), vm("app03", "esx02"), vm("idle", "esx01")];
+        let targets = vec![
+            target("cascade", Some("vmware"), "Intel Xeon Gold 6248"),
+            target("sapphire", Some("vmware"), "Intel Xeon Gold 6430"),
+        ];
+        let placements = vec![MigrationWizardPlacement {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            vm_id: Thing::from(("migration_wizard_vm", "app03")),
+            cluster_id: Thing::from(("migration_wizard_cluster", "sapphire")),
+            strategy: "manual".to_string(),
+            confidence_score: None,
+            warnings: None,
+            allocated_cpu: 2,
+            allocated_memory_mb: 4096,
+            allocated_storage_gb: 0.0,
+            tags: Default::default(),
+            created_at: Utc::now(),
+        }];
+        let waves = vec![
+            wave("w1", &["app01", "app02"], &["cascade"]),
+            wave("w2", &["app03"], &["cascade"]),
+            wave("w3", &[], &["cascade"]),
+        ];
+
+        let report = analyze("p1", &inventory, &vms, &placements, &waves, targets, Utc::now());
+        let mut recommendations: Vec<StrategyRecommendation> = vms
+            .iter()
+            .map(|vm| StrategyRecommendation {
+                vm_name: vm.name.clone(),
+                strategy: "lift_shift".to_string(),
+                confidence_score: 100.0,
+                warnings: vec![],
+                recommendations: vec![],
+                migration_mode: None,
+                migration_mode_rationale: None,
+            })
+            .collect();
+        annotate(&mut recommendations, &report);
+
+        let modes: Vec<(&str, MigrationMode)> = report.vms.iter().map(|d| (d.vm_name.as_str(), d.mode)).collect();
+        assert_eq!(
+            modes,
+            vec![("app01", MigrationMode::Live), ("app02", MigrationMode::Cold), ("app03", MigrationMode::Live)],
+            "the VM without a target is left out"
+        );
+        assert_eq!(report.vms[2].target_cluster_name, "sapphire");
+
+        assert_eq!(report.waves.len(), 2, "the empty wave is left out");
+        assert_eq!(report.waves[0].mode, MigrationMode::Cold);
+        assert_eq!((report.waves[0].live_vms, report.waves[0].cold_vms.clone()), (1, vec!["app02".to_string()]));
+        assert!(report.waves[0].rationale.starts_with("1 of 2 VMs need a cold migration; app02: Source baseline Intel Ice Lake"));
+        assert_eq!(report.waves[1].mode, MigrationMode::Live);
+
+        assert_eq!(recommendations[1].migration_mode, Some(MigrationMode::Cold));
+        assert!(recommendations[3].migration_mode.is_none());
+    }
+}
@@ -0,0 +1,150 @@
+//! Currency Service
+//!
+//! Stores the exchange rate table and each project's display currency.
+//! Prices keep the currency they were quoted in; conversion happens when
+//! estimates and BOMs are totalled for a project. Until rates are entered or
+//! loaded from the ECB feed only USD amounts can be aggregated.
+
+use chrono::Utc;
+use core_engine::currency::{normalize_code, BomLine, ConvertedBom, ConvertedMoney, ExchangeRates, Money, RateSource};
+use core_engine::CoreEngineError;
+use serde_json::json;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::models::migration_wizard_models::MigrationWizardProject;
+use crate::models::settings::UpdateExchangeRatesRequest;
+
+/// Display currency for projects that have not chosen one
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+const RATES_RECORD: (&str, &str) = ("exchange_rates", "current");
+
+#[derive(Debug, Error)]
+pub enum CurrencyError {
+    #[error("Project not found")]
+    ProjectNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Conversion error: {0}")]
+    Conversion(String),
+
+    #[error("Exchange rate feed unavailable: {0}")]
+    Feed(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for CurrencyError {
+    fn from(e: surrealdb::Error) -> Self {
+        CurrencyError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<CoreEngineError> for CurrencyError {
+    fn from(e: CoreEngineError) -> Self {
+        match e {
+            CoreEngineError::ValidationError(msg) => CurrencyError::Validation(msg),
+            CoreEngineError::NetworkError(msg) | CoreEngineError::ParsingError(msg) => CurrencyError::Feed(msg),
+            other => CurrencyError::Conversion(other.to_string()),
+        }
+    }
+}
+
+pub struct CurrencyService {
+    db: Database,
+}
+
+impl CurrencyService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    // =========================================================================
+    // EXCHANGE RATES
+    // =========================================================================
+
+    /// Current rate table, or a USD-only manual table if none is stored
+    pub async fn get_rates(&self) -> Result<ExchangeRates, CurrencyError> {
+        let stored: Option<ExchangeRates> = self.db.select(RATES_RECORD).await?;
+        match stored {
+            Some(rates) => Ok(rates),
+            None => Ok(ExchangeRates::new(DEFAULT_CURRENCY, RateSource::Manual)?),
+        }
+    }
+
+    /// Replace the rate table with manually entered rates
+    pub async fn set_manual_rates(&self, request: UpdateExchangeRatesRequest) -> Result<ExchangeRates, CurrencyError> {
+        let mut rates = ExchangeRates::new(&request.base, RateSource::Manual)?;
+        for (currency, rate) in &request.rates {
+            rates.set_rate(currency, *rate)?;
+        }
+        self.save_rates(rates).await
+    }
+
+    /// Replace the rate table with today's ECB reference rates
+    pub async fn refresh_from_ecb(&self) -> Result<ExchangeRates, CurrencyError> {
+        let rates = ExchangeRates::fetch_ecb().await?;
+        self.save_rates(rates).await
+    }
+
+    async fn save_rates(&self, rates: ExchangeRates) -> Result<ExchangeRates, CurrencyError> {
+        let saved: Option<ExchangeRates> = self.db.update(RATES_RECORD).content(rates).await?;
+        saved.ok_or_else(|| CurrencyError::DatabaseError("Failed to store exchange rates".to_string()))
+    }
+
+    // =========================================================================
+    // PROJECT DISPLAY CURRENCY
+    // =========================================================================
+
+    pub async fn project_currency(&self, project_id: &str) -> Result<String, CurrencyError> {
+        let project = self.get_project(project_id).await?;
+        Ok(project.display_currency.unwrap_or_else(|| DEFAULT_CURRENCY.to_string()))
+    }
+
+    /// Set the currency a project's costs are shown in; the rate table must
+    /// be able to convert into it
+    pub async fn set_project_currency(
+        &self,
+        project_id: &str,
+        currency: &str,
+    ) -> Result<MigrationWizardProject, CurrencyError> {
+        let currency = normalize_code(currency)?;
+        self.get_project(project_id).await?;
+        let rates = self.get_rates().await?;
+        if !rates.rates.contains_key(&currency) {
+            return Err(CurrencyError::Validation(format!(
+                "No exchange rate for {}; add one or refresh the rates first",
+                currency
+            )));
+        }
+
+        let updated: Option<MigrationWizardProject> = self
+            .db
+            .update(("migration_wizard_project", project_id))
+            .merge(json!({ "display_currency": currency, "updated_at": Utc::now() }))
+            .await?;
+        updated.ok_or(CurrencyError::ProjectNotFound)
+    }
+
+    /// Convert an amount into a project's display currency
+    pub async fn to_project_currency(&self, project_id: &str, money: &Money) -> Result<ConvertedMoney, CurrencyError> {
+        let currency = self.project_currency(project_id).await?;
+        Ok(self.get_rates().await?.convert_money(money, &currency)?)
+    }
+
+    /// Total a mixed-currency BOM in a project's display currency
+    pub async fn convert_bom(&self, project_id: &str, lines: &[BomLine]) -> Result<ConvertedBom, CurrencyError> {
+        let currency = self.project_currency(project_id).await?;
+        Ok(self.get_rates().await?.convert_bom(lines, &currency)?)
+    }
+
+    async fn get_project(&self, project_id: &str) -> Result<MigrationWizardProject, CurrencyError> {
+        let project: Option<MigrationWizardProject> =
+            self.db.select(("migration_wizard_project", project_id)).await?;
+        project.ok_or(CurrencyError::ProjectNotFound)
+    }
+}
@@ -0,0 +1,742 @@
+//! Cutover Readiness Service
+//!
+//! Scores each migration wave red/amber/green before it cuts over. The
+//! scorecard aggregates six signals:
+//! - placements: every VM of the wave is placed on one of its target clusters
+//! - network mappings: the project's mappings validate
+//! - IP plan: every placed VM has an address in its destination subnet
+//! - backups: every VM's backup job last ran successfully
+//! - change tickets: the wave's change tickets are approved
+//! - change window: the wave has a change window and a planned start
+//!
+//! A signal with a blocking item is red; one with only warnings is amber.
+//! The wave takes the worst rating of its signals.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::automation_export::{AutomationPlan, PlanVm};
+use crate::models::migration_wizard_models::{MigrationWizardVM, NetworkValidationResult};
+use crate::models::project_models::HypervisorType;
+use crate::models::ticket::TicketStatus;
+use crate::models::timeline::*;
+use crate::models::workflow_engine::Approval;
+use crate::services::approval_service::{quorum_decision, QuorumDecision};
+use crate::services::automation_export::{build_plan, PlanSources};
+use crate::services::integration_hub::dependencies::{backup_coverage, BackupJobCoverage};
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::timeline_service::TimelineService;
+
+/// Names listed in a blocking item before the rest are counted
+const MAX_LISTED: usize = 5;
+
+/// A change ticket linked to a wave, with the approvals of its workflows
+#[derive(Debug, Clone)]
+pub struct WaveChangeTicket {
+    pub key: String,
+    pub title: String,
+    pub status: TicketStatus,
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TicketRow {
+    id: Thing,
+    title: String,
+    status: TicketStatus,
+    #[serde(default)]
+    custom_fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstanceRow {
+    id: Thing,
+    trigger_record_id: Thing,
+}
+
+pub struct CutoverReadinessService {
+    db: Database,
+}
+
+impl CutoverReadinessService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Readiness scorecard of one wave
+    pub async fn wave_readiness(&self, wave_id: &str) -> Result<WaveReadiness> {
+        let wave = TimelineService::new(self.db.clone()).get_wave(wave_id).await?;
+        let project_id = wave.project_id.id.to_raw();
+        self.assess(&project_id, vec![wave])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("Migration wave not found"))
+    }
+
+    /// Readiness scorecards of every wave of a project, in execution order
+    pub async fn project_readiness(&self, project_id: &str) -> Result<Vec<WaveReadiness>> {
+        let waves = TimelineService::new(self.db.clone()).list_waves(project_id).await?;
+        self.assess(project_id, waves).await
+    }
+
+    /// Load the project-wide inputs once and score each wave against them
+    async fn assess(&self, project_id: &str, waves: Vec<MigrationWave>) -> Result<Vec<WaveReadiness>> {
+        if waves.is_empty() {
+            return Ok(Vec::new());
+        }
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let project = wizard.get_project(project_id).await?;
+        let vms = wizard.get_project_vms(project_id, None).await?;
+        let network = wizard.validate_network_mappings(project_id).await?;
+        let plan = build_plan(
+            PlanSources {
+                project,
+                vms: vms.clone(),
+                clusters: wizard.get_project_clusters(project_id).await?,
+                placements: wizard.get_project_placements(project_id).await?,
+                mappings: wizard.get_project_network_mappings(project_id).await?,
+                waves: waves.clone(),
+                destination_hypervisors: HashMap::new(),
+            },
+            // The hypervisor only matters to the generated automation files
+            HypervisorType::HyperV,
+        );
+        let jobs = backup_coverage(&self.db)
+            .await
+            .context("Failed to load backup jobs")?;
+        let mut tickets = self.change_tickets(&waves).await?;
+
+        let now = Utc::now();
+        Ok(waves
+            .iter()
+            .map(|wave| {
+                let key = wave.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default();
+                let members: HashSet<&Thing> = wave.vm_ids.iter().collect();
+                let wave_vms: Vec<MigrationWizardVM> = vms
+                    .iter()
+                    .filter(|vm| vm.id.as_ref().is_some_and(|id| members.contains(id)))
+                    .cloned()
+                    .collect();
+                let signals = vec![
+                    Self::placement_signal(wave, &wave_vms, &plan),
+                    Self::network_signal(&network),
+                    Self::ip_plan_signal(&wave_vms, &plan),
+                    Self::backup_signal(&wave_vms, &jobs),
+                    Self::ticket_signal(&tickets.remove(&key).unwrap_or_default()),
+                    Self::change_window_signal(wave, now),
+                ];
+                Self::scorecard(wave, signals, now)
+            })
+            .collect())
+    }
+
+    /// Change tickets linked to the waves through their `wave_id` custom
+    /// field, keyed by wave record key
+    async fn change_tickets(&self, waves: &[MigrationWave]) -> Result<HashMap<String, Vec<WaveChangeTicket>>> {
+        let keys: Vec<String> = waves
+            .iter()
+            .filter_map(|w| w.id.as_ref())
+            .flat_map(|id| [id.id.to_raw(), id.to_string()])
+            .collect();
+        let rows: Vec<TicketRow> = self
+            .db
+            .query("SELECT id, title, status, custom_fields FROM ticket WHERE type = 'CHANGE' AND custom_fields.wave_id INSIDE $waves")
+            .bind(("waves", keys))
+            .traced("cutover_readiness_service.change_tickets")
+            .await
+            .context("Failed to query change tickets")?
+            .take(0)
+            .context("Failed to parse change tickets")?;
+        if rows.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ticket_ids: Vec<Thing> = rows.iter().map(|t| t.id.clone()).collect();
+        let instances: Vec<InstanceRow> = self
+            .db
+            .query("SELECT id, trigger_record_id FROM workflow_instance WHERE trigger_record_id INSIDE $tickets")
+            .bind(("tickets", ticket_ids))
+            .traced("cutover_readiness_service.change_tickets")
+            .await
+            .context("Failed to query ticket workflows")?
+            .take(0)
+            .context("Failed to parse ticket workflows")?;
+        let ticket_of: HashMap<Thing, Thing> = instances
+            .into_iter()
+            .map(|i| (i.id, i.trigger_record_id))
+            .collect();
+        let approvals: Vec<Approval> = self
+            .db
+            .query("SELECT * FROM approval WHERE workflow_instance_id INSIDE $instances ORDER BY requested_at")
+            .bind(("instances", ticket_of.keys().cloned().collect::<Vec<_>>()))
+            .traced("cutover_readiness_service.change_tickets")
+            .await
+            .context("Failed to query ticket approvals")?
+            .take(0)
+            .context("Failed to parse ticket approvals")?;
+
+        let mut approvals_of: HashMap<Thing, Vec<Approval>> = HashMap::new();
+        for approval in approvals {
+            if let Some(ticket) = ticket_of.get(&approval.workflow_instance_id) {
+                approvals_of.entry(ticket.clone()).or_default().push(approval);
+            }
+        }
+
+        let mut tickets: HashMap<String, Vec<WaveChangeTicket>> = HashMap::new();
+        for row in rows {
+            let Some(wave) = row
+                .custom_fields
+                .as_ref()
+                .and_then(|f| f["wave_id"].as_str())
+                .map(|w| w.strip_prefix("migration_wave:").unwrap_or(w).to_string())
+            else {
+                continue;
+            };
+            tickets.entry(wave).or_default().push(WaveChangeTicket {
+                key: row.id.id.to_raw(),
+                title: row.title,
+                status: row.status,
+                approvals: approvals_of.remove(&row.id).unwrap_or_default(),
+            });
+        }
+        Ok(tickets)
+    }
+
+    // =========================================================================
+    // SIGNALS
+    // =========================================================================
+
+    /// Every VM of the wave is placed, on one of the wave's target clusters
+    pub fn placement_signal(wave: &MigrationWave, vms: &[MigrationWizardVM], plan: &AutomationPlan) -> ReadinessSignal {
+        let mut blocking = Vec::new();
+        let mut warnings = Vec::new();
+        if vms.is_empty() {
+            blocking.push("The wave has no VMs".to_string());
+        }
+
+        let targets: HashSet<String> = wave.target_cluster_ids.iter().map(|id| id.id.to_raw()).collect();
+        let mut unplaced = Vec::new();
+        let mut off_target = Vec::new();
+        for vm in vms {
+            match planned_vm(plan, vm) {
+                None => unplaced.push(vm.name.as_str()),
+                Some(placed) if !targets.is_empty() && !targets.contains(&placed.cluster) => {
+                    off_target.push(vm.name.as_str())
+                }
+                Some(_) => {}
+            }
+        }
+        if !unplaced.is_empty() {
+            blocking.push(format!("{} VM(s) have no valid placement: {}", unplaced.len(), listing(&unplaced)));
+        }
+        if !off_target.is_empty() {
+            warnings.push(format!(
+                "{} VM(s) are placed outside the wave's target clusters: {}",
+                off_target.len(),
+                listing(&off_target)
+            ));
+        }
+
+        let placed = vms.len() - unplaced.len();
+        signal(
+            "placements",
+            "Placements",
+            format!("{} of {} VM(s) placed", placed, vms.len()),
+            blocking,
+            warnings,
+        )
+    }
+
+    /// The project's network mappings validate; conflicts with other
+    /// projects are warnings
+    pub fn network_signal(validation: &NetworkValidationResult) -> ReadinessSignal {
+        let blocking = if validation.total_mappings == 0 {
+            vec!["The project has no network mappings".to_string()]
+        } else {
+            validation
+                .errors
+                .iter()
+                .map(|e| e.message.clone())
+                .chain(validation.naming_violations.iter().map(|v| v.message.clone()))
+                .collect()
+        };
+        signal(
+            "network_mappings",
+            "Network mappings",
+            format!(
+                "{} of {} mapping(s) valid",
+                validation.valid_mappings, validation.total_mappings
+            ),
+            blocking,
+            validation.warnings.clone(),
+        )
+    }
+
+    /// Every placed VM of the wave has an address in its destination subnet
+    pub fn ip_plan_signal(vms: &[MigrationWizardVM], plan: &AutomationPlan) -> ReadinessSignal {
+        let placed: Vec<_> = vms.iter().filter_map(|vm| planned_vm(plan, vm)).collect();
+        let missing: Vec<&str> = placed
+            .iter()
+            .filter(|vm| vm.new_ip.is_none())
+            .map(|vm| vm.name.as_str())
+            .collect();
+
+        let mut blocking = Vec::new();
+        if placed.is_empty() && !vms.is_empty() {
+            blocking.push("No VM of the wave is placed, so no address can be planned".to_string());
+        }
+        if !missing.is_empty() {
+            blocking.push(format!(
+                "{} VM(s) have no destination address: {}",
+                missing.len(),
+                listing(&missing)
+            ));
+        }
+        signal(
+            "ip_plan",
+            "IP plan",
+            format!("{} of {} placed VM(s) addressed", placed.len() - missing.len(), placed.len()),
+            blocking,
+            Vec::new(),
+        )
+    }
+
+    /// Every VM is protected by an enabled backup job whose last run
+    /// succeeded. Unprotected VMs get a manual backup in the runbook, so
+    /// they only warn.
+    pub fn backup_signal(vms: &[MigrationWizardVM], jobs: &[BackupJobCoverage]) -> ReadinessSignal {
+        let mut verified = 0;
+        let mut failed = Vec::new();
+        let mut warned = Vec::new();
+        let mut unprotected = Vec::new();
+        for vm in vms {
+            let results: Vec<String> = jobs
+                .iter()
+                .filter(|job| job.enabled != Some(false) && job.protects(&vm.name).is_some())
+                .map(|job| job.last_result.as_deref().unwrap_or_default().to_lowercase())
+                .collect();
+            if results.is_empty() {
+                unprotected.push(vm.name.as_str());
+            } else if results.iter().any(|r| r == "success") {
+                verified += 1;
+            } else if results.iter().any(|r| r == "warning") {
+                warned.push(vm.name.as_str());
+            } else {
+                failed.push(vm.name.as_str());
+            }
+        }
+
+        let mut blocking = Vec::new();
+        let mut warnings = Vec::new();
+        if !failed.is_empty() {
+            blocking.push(format!(
+                "{} VM(s) have no successful backup run: {}",
+                failed.len(),
+                listing(&failed)
+            ));
+        }
+        if !warned.is_empty() {
+            warnings.push(format!(
+                "{} VM(s) last backed up with warnings: {}",
+                warned.len(),
+                listing(&warned)
+            ));
+        }
+        if !unprotected.is_empty() {
+            warnings.push(format!(
+                "{} VM(s) are not protected by an imported backup job; take a manual backup: {}",
+                unprotected.len(),
+                listing(&unprotected)
+            ));
+        }
+        signal(
+            "backups",
+            "Backups",
+            format!("{} of {} VM(s) have a verified backup", verified, vms.len()),
+            blocking,
+            warnings,
+        )
+    }
+
+    /// The wave has at least one change ticket and every approval request
+    /// of its change tickets reached quorum. Cancelled tickets are ignored.
+    pub fn ticket_signal(tickets: &[WaveChangeTicket]) -> ReadinessSignal {
+        let tickets: Vec<&WaveChangeTicket> = tickets
+            .iter()
+            .filter(|t| t.status != TicketStatus::Cancelled)
+            .collect();
+
+        let mut blocking = Vec::new();
+        let mut approved = 0;
+        if tickets.is_empty() {
+            blocking.push("No change ticket is linked to the wave".to_string());
+        }
+        for ticket in &tickets {
+            // Approvals created by one step share a request_id
+            let mut requests: BTreeMap<String, Vec<Approval>> = BTreeMap::new();
+            for approval in &ticket.approvals {
+                let request = approval
+                    .request_id
+                    .clone()
+                    .or_else(|| approval.id.as_ref().map(|id| id.to_string()))
+                    .unwrap_or_default();
+                requests.entry(request).or_default().push(approval.clone());
+            }
+            let decisions: Vec<QuorumDecision> = requests
+                .values()
+                .map(|approvals| quorum_decision(approvals, approvals[0].quorum.unwrap_or(1)))
+                .collect();
+
+            let name = format!("Change ticket '{}' ({})", ticket.title, ticket.key);
+            if decisions.is_empty() {
+                blocking.push(format!("{} has not been sent for approval", name));
+            } else if decisions.contains(&QuorumDecision::Rejected) {
+                blocking.push(format!("{} was rejected", name));
+            } else if decisions.contains(&QuorumDecision::Pending) {
+                blocking.push(format!("{} is awaiting approval", name));
+            } else {
+                approved += 1;
+            }
+        }
+        signal(
+            "change_tickets",
+            "Change tickets",
+            format!("{} of {} change ticket(s) approved", approved, tickets.len()),
+            blocking,
+            Vec::new(),
+        )
+    }
+
+    /// The wave has a change window and a planned start that hasn't passed
+    pub fn change_window_signal(wave: &MigrationWave, now: DateTime<Utc>) -> ReadinessSignal {
+        let mut blocking = Vec::new();
+        let mut warnings = Vec::new();
+        if wave.change_window.is_none() {
+            blocking.push("No change window is scheduled".to_string());
+        }
+        match wave.planned_start {
+            None => warnings.push("No planned start date".to_string()),
+            Some(start)
+                if start < now && matches!(wave.status, WaveStatus::Planned | WaveStatus::Scheduled) =>
+            {
+                warnings.push(format!(
+                    "The planned start {} has passed; reschedule the wave",
+                    start.format("%Y-%m-%d %H:%M UTC")
+                ))
+            }
+            Some(_) => {}
+        }
+
+        let summary = match (&wave.change_window, wave.planned_start) {
+            (Some(window), Some(start)) => format!("{} from {}", window, start.format("%Y-%m-%d %H:%M UTC")),
+            (Some(window), None) => window.clone(),
+            (None, _) => "Not scheduled".to_string(),
+        };
+        signal("change_window", "Change window", summary, blocking, warnings)
+    }
+
+    /// Aggregate the signals of a wave into its scorecard
+    pub fn scorecard(wave: &MigrationWave, signals: Vec<ReadinessSignal>, now: DateTime<Utc>) -> WaveReadiness {
+        let rating = signals
+            .iter()
+            .map(|s| s.rating)
+            .max()
+            .unwrap_or(ReadinessRating::Green);
+        let points: usize = signals
+            .iter()
+            .map(|s| match s.rating {
+                ReadinessRating::Green => 2,
+                ReadinessRating::Amber => 1,
+                ReadinessRating::Red => 0,
+            })
+            .sum();
+        let score = if signals.is_empty() {
+            100
+        } else {
+            (points * 100 / (signals.len() * 2)) as u8
+        };
+        let blocking_items = signals
+            .iter()
+            .flat_map(|s| {
+                s.blocking.iter().map(|message| BlockingItem {
+                    signal: s.key.clone(),
+                    message: message.clone(),
+                })
+            })
+            .collect();
+
+        WaveReadiness {
+            wave_id: wave.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+            wave_name: wave.name.clone(),
+            sequence: wave.sequence,
+            rating,
+            score,
+            signals,
+            blocking_items,
+            assessed_at: now,
+        }
+    }
+}
+
+fn signal(key: &str, name: &str, summary: String, blocking: Vec<String>, warnings: Vec<String>) -> ReadinessSignal {
+    let rating = if !blocking.is_empty() {
+        ReadinessRating::Red
+    } else if !warnings.is_empty() {
+        ReadinessRating::Amber
+    } else {
+        ReadinessRating::Green
+    };
+    ReadinessSignal {
+        key: key.to_string(),
+        name: name.to_string(),
+        rating,
+        summary,
+        blocking,
+        warnings,
+    }
+}
+
+/// The wave VM's entry in the placement plan; only placed VMs have one
+fn planned_vm<'a>(plan: &'a AutomationPlan, vm: &MigrationWizardVM) -> Option<&'a PlanVm> {
+    let key = vm.id.as_ref()?.id.to_raw();
+    plan.vms.iter().find(|p| p.key == key)
+}
+
+/// The first few names, then how many more there are
+fn listing(names: &[&str]) -> String {
+    let mut listed = names.iter().take(MAX_LISTED).copied().collect::<Vec<_>>().join(", ");
+    if names.len() > MAX_LISTED {
+        listed.push_str(&format!(" and {} more", names.len() - MAX_LISTED));
+    }
+    listed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::migration_wizard_models::{NetworkErrorType, NetworkValidationError};
+    use crate::models::workflow_engine::{ApprovalStatus, ApproverType};
+    use crate::services::integration_hub::dependencies::ProtectedVm;
+    use chrono::Duration;
+
+    fn wave(targets: &[&str]) -> MigrationWave {
+        let now = Utc::now();
+        MigrationWave {
+            id: Some(Thing::from(("migration_wave", "w1"))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: "Wave 1".to_string(),
+            sequence: 1,
+            vm_ids: Vec::new(),
+            target_cluster_ids: targets.iter().map(|t| Thing::from(("migration_wizard_cluster", *t))).collect(),
+            planned_start: Some(now + Duration::days(7)),
+            planned_end: None,
+            change_window: Some("Sat 22:00-02:00".to_string()),
+            status: WaveStatus::Scheduled,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn vm(key: &str) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_string(),
+            powerstate: None,
+            template: None,
+            cpus: 2,
+            memory_mb: 4096,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: None,
+            dns_name: None,
+            cluster: None,
+            host: None,
+            datacenter: None,
+            os: None,
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn plan(vms: &[(&str, &str, Option<&str>)]) -> AutomationPlan {
+        AutomationPlan {
+            project_id: "p1".to_string(),
+            project_name: "Project".to_string(),
+            clusters: Vec::new(),
+            networks: Vec::new(),
+            waves: Vec::new(),
+            vms: vms
+                .iter()
+                .map(|(key, cluster, ip)| PlanVm {
+                    key: key.to_string(),
+                    name: key.to_string(),
+                    cluster: cluster.to_string(),
+                    wave: None,
+                    cpus: 2,
+                    memory_mb: 4096,
+                    storage_gb: 40.0,
+                    os: None,
+                    source_ip: None,
+                    network: None,
+                    vlan_id: None,
+                    new_ip: ip.map(str::to_string),
+                    prefix_length: None,
+                    gateway: None,
+                    dns_servers: Vec::new(),
+                })
+                .collect(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn approval(request: &str, status: ApprovalStatus) -> Approval {
+        Approval {
+            id: None,
+            workflow_instance_id: Thing::from(("workflow_instance", "i1")),
+            step_id: "cab".to_string(),
+            approver_id: Thing::from(("users", "u1")),
+            approver_type: ApproverType::User,
+            status,
+            requested_at: Utc::now(),
+            responded_at: None,
+            comments: None,
+            request_id: Some(request.to_string()),
+            quorum: None,
+            group_id: None,
+            delegated_from: None,
+            escalated_from: None,
+            responded_by: None,
+            remind_at: None,
+            reminder_interval_minutes: None,
+            reminders_sent: 0,
+            escalate_at: None,
+            escalate_to: None,
+        }
+    }
+
+    fn ticket(key: &str, status: TicketStatus, approvals: Vec<Approval>) -> WaveChangeTicket {
+        WaveChangeTicket {
+            key: key.to_string(),
+            title: format!("Cutover {}", key),
+            status,
+            approvals,
+        }
+    }
+
+    #[test]
+    fn test_placements_and_ip_plan_block_unplaced_and_unaddressed_vms() {
+        let vms = [vm("a"), vm("b"), vm("c")];
+        let plan = plan(&[("a", "c1", Some("10.0.0.5")), ("b", "c2", None)]);
+
+        let placements = CutoverReadinessService::placement_signal(&wave(&["c1"]), &vms, &plan);
+        assert_eq!(placements.rating, ReadinessRating::Red);
+        assert_eq!(placements.summary, "2 of 3 VM(s) placed");
+        assert_eq!(placements.blocking, vec!["1 VM(s) have no valid placement: c"]);
+        assert_eq!(placements.warnings, vec!["1 VM(s) are placed outside the wave's target clusters: b"]);
+
+        let ip_plan = CutoverReadinessService::ip_plan_signal(&vms, &plan);
+        assert_eq!(ip_plan.rating, ReadinessRating::Red);
+        assert_eq!(ip_plan.blocking, vec!["1 VM(s) have no destination address: b"]);
+    }
+
+    #[test]
+    fn test_backups_need_a_successful_run() {
+        let job = |name: &str, result: &str, vms: &[&str]| BackupJobCoverage {
+            name: name.to_string(),
+            last_result: Some(result.to_string()),
+            protected_vms: vms
+                .iter()
+                .map(|v| ProtectedVm { name: v.to_string(), included_directly: true, ..Default::default() })
+                .collect(),
+            ..Default::default()
+        };
+        let jobs = [job("Daily", "Success", &["a"]), job("Weekly", "Failed", &["b"]), job("Files", "Warning", &["c"])];
+
+        let backups = CutoverReadinessService::backup_signal(&[vm("A"), vm("b"), vm("c"), vm("d")], &jobs);
+        assert_eq!(backups.rating, ReadinessRating::Red);
+        assert_eq!(backups.summary, "1 of 4 VM(s) have a verified backup");
+        assert_eq!(backups.blocking, vec!["1 VM(s) have no successful backup run: b"]);
+        assert_eq!(backups.warnings.len(), 2);
+        assert!(backups.warnings[1].ends_with("take a manual backup: d"));
+    }
+
+    #[test]
+    fn test_change_tickets_need_quorum_on_every_request() {
+        let rating = |tickets: &[WaveChangeTicket]| CutoverReadinessService::ticket_signal(tickets);
+
+        let none = rating(&[]);
+        assert_eq!(none.blocking, vec!["No change ticket is linked to the wave"]);
+
+        let approved = ticket("t1", TicketStatus::Assigned, vec![approval("r1", ApprovalStatus::Approved)]);
+        let pending = ticket(
+            "t2",
+            TicketStatus::New,
+            vec![approval("r2", ApprovalStatus::Approved), approval("r3", ApprovalStatus::Pending)],
+        );
+        let unsent = ticket("t3", TicketStatus::New, Vec::new());
+        let cancelled = ticket("t4", TicketStatus::Cancelled, Vec::new());
+
+        let signal = rating(&[approved.clone(), pending, unsent, cancelled.clone()]);
+        assert_eq!(signal.summary, "1 of 3 change ticket(s) approved");
+        assert_eq!(
+            signal.blocking,
+            vec![
+                "Change ticket 'Cutover t2' (t2) is awaiting approval",
+                "Change ticket 'Cutover t3' (t3) has not been sent for approval",
+            ]
+        );
+
+        assert_eq!(rating(&[approved, cancelled]).rating, ReadinessRating::Green);
+    }
+
+    #[test]
+    fn test_scorecard_takes_the_worst_signal() {
+        let mut wave = wave(&[]);
+        let now = Utc::now();
+        wave.planned_start = Some(now - Duration::days(1));
+
+        let window = CutoverReadinessService::change_window_signal(&wave, now);
+        assert_eq!(window.rating, ReadinessRating::Amber);
+
+        let network = CutoverReadinessService::network_signal(&NetworkValidationResult {
+            is_valid: false,
+            total_mappings: 2,
+            valid_mappings: 1,
+            invalid_mappings: 1,
+            errors: vec![NetworkValidationError {
+                mapping_id: "m1".to_string(),
+                error_type: NetworkErrorType::SubnetOverlap,
+                message: "Subnet overlap detected: 10.0.0.0/16 and 10.0.1.0/24".to_string(),
+                affected_field: "destination_subnet".to_string(),
+            }],
+            warnings: Vec::new(),
+            cross_project_conflicts: Vec::new(),
+            naming_violations: Vec::new(),
+        });
+
+        let card = CutoverReadinessService::scorecard(&wave, vec![window.clone(), network], now);
+        assert_eq!(card.rating, ReadinessRating::Red);
+        assert_eq!(card.score, 25);
+        assert_eq!(card.blocking_items.len(), 1);
+        assert_eq!(card.blocking_items[0].signal, "network_mappings");
+
+        let card = CutoverReadinessService::scorecard(&wave, vec![window], now);
+        assert_eq!(card.rating, ReadinessRating::Amber);
+        assert!(card.blocking_items.is_empty());
+
+        wave.change_window = None;
+        let unscheduled = CutoverReadinessService::change_window_signal(&wave, now);
+        assert_eq!(unscheduled.blocking, vec!["No change window is scheduled"]);
+        assert_eq!(unscheduled.summary, "Not scheduled");
+    }
+}
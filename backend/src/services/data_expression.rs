@@ -0,0 +1,979 @@
+// Archer - Data Variable Expressions
+// A small expression language for user-defined data variables: aggregates over
+// RVTools sheets and parsed VM fields, combined with arithmetic. Expressions
+// are parsed into an AST and evaluated in memory; nothing reaches the database.
+//
+//   sum(vInfo.Memory where Powerstate = "poweredOn") / 1024
+//   count(vms where operating_system contains "Windows")
+//   avg(vHost.`# Cores`) * count(vHost)
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use crate::models::project_models::{RvToolsData, RvToolsExcelData};
+
+/// Longest expression accepted
+pub const MAX_EXPRESSION_LEN: usize = 1000;
+/// Deepest allowed nesting of parentheses and operators
+pub const MAX_DEPTH: usize = 16;
+/// Most aggregates allowed in one expression
+pub const MAX_AGGREGATES: usize = 20;
+
+/// Source name of the parsed VM model
+pub const VM_SOURCE: &str = "vms";
+/// Fields of the parsed VM model an expression may read
+pub const VM_FIELDS: &[&str] = &[
+    "vm_name",
+    "host_name",
+    "cpu_cores",
+    "memory_gb",
+    "disk_gb",
+    "operating_system",
+    "power_state",
+    "cluster",
+    "datacenter",
+    "network_adapters",
+];
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExpressionError {
+    #[error("Syntax error at position {position}: {message}")]
+    Syntax { position: usize, message: String },
+
+    #[error("Expression is too complex: {0}")]
+    TooComplex(String),
+
+    #[error("Unknown data source '{0}'")]
+    UnknownSource(String),
+
+    #[error("Unknown field '{field}' on '{source_name}'")]
+    UnknownField { source_name: String, field: String },
+
+    #[error("{0}")]
+    Evaluation(String),
+}
+
+// ============================================================================
+// AST
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFn {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    CountDistinct,
+}
+
+impl AggregateFn {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sum" => Some(Self::Sum),
+            "avg" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "count" => Some(Self::Count),
+            "count_distinct" => Some(Self::CountDistinct),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Compare { field: String, op: CompareOp, value: JsonValue },
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregate {
+    pub function: AggregateFn,
+    pub source: String,
+    pub field: Option<String>,
+    pub filter: Option<Condition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Aggregate(Aggregate),
+}
+
+impl Expr {
+    /// Every aggregate in the expression, left to right
+    pub fn aggregates(&self) -> Vec<&Aggregate> {
+        let mut found = Vec::new();
+        self.collect_aggregates(&mut found);
+        found
+    }
+
+    fn collect_aggregates<'a>(&'a self, found: &mut Vec<&'a Aggregate>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Negate(inner) => inner.collect_aggregates(found),
+            Expr::Binary(left, _, right) => {
+                left.collect_aggregates(found);
+                right.collect_aggregates(found);
+            }
+            Expr::Aggregate(aggregate) => found.push(aggregate),
+        }
+    }
+
+    /// Sources and source fields the expression reads, for
+    /// `source_sheets` and `source_columns` on the schema record
+    pub fn references(&self) -> (Vec<String>, Vec<String>) {
+        let mut sources = BTreeSet::new();
+        let mut fields = BTreeSet::new();
+        for aggregate in self.aggregates() {
+            sources.insert(aggregate.source.clone());
+            fields.extend(aggregate.field.clone());
+            if let Some(filter) = &aggregate.filter {
+                filter.fields(&mut fields);
+            }
+        }
+        (sources.into_iter().collect(), fields.into_iter().collect())
+    }
+}
+
+impl Condition {
+    fn fields(&self, out: &mut BTreeSet<String>) {
+        match self {
+            Condition::Compare { field, .. } => {
+                out.insert(field.clone());
+            }
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.fields(out);
+                right.fields(out);
+            }
+            Condition::Not(inner) => inner.fields(out),
+        }
+    }
+}
+
+// ============================================================================
+// TOKENIZER
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Str(String),
+    Op(&'static str),
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ExpressionError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let syntax = |position: usize, message: &str| ExpressionError::Syntax {
+        position,
+        message: message.to_string(),
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text.parse().map_err(|_| syntax(start, "invalid number"))?;
+            tokens.push((start, Token::Number(number)));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((start, Token::Ident(chars[start..i].iter().collect())));
+        } else if c == '`' {
+            // Quoted identifier, for RVTools columns with spaces or symbols
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&n| n == '`')
+                .ok_or_else(|| syntax(start, "unterminated `quoted` name"))?;
+            let name: String = chars[i + 1..i + 1 + end].iter().collect();
+            if name.trim().is_empty() {
+                return Err(syntax(start, "empty `quoted` name"));
+            }
+            tokens.push((start, Token::Ident(name)));
+            i += end + 2;
+        } else if c == '"' || c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&n| n == c)
+                .ok_or_else(|| syntax(start, "unterminated string"))?;
+            tokens.push((start, Token::Str(chars[i + 1..i + 1 + end].iter().collect())));
+            i += end + 2;
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let op = match two.as_str() {
+                "!=" => Some("!="),
+                "<=" => Some("<="),
+                ">=" => Some(">="),
+                _ => None,
+            };
+            if let Some(op) = op {
+                tokens.push((start, Token::Op(op)));
+                i += 2;
+                continue;
+            }
+            let op = match c {
+                '+' => "+",
+                '-' => "-",
+                '*' => "*",
+                '/' => "/",
+                '(' => "(",
+                ')' => ")",
+                '.' => ".",
+                '=' => "=",
+                '<' => "<",
+                '>' => ">",
+                _ => return Err(syntax(start, &format!("unexpected character '{}'", c))),
+            };
+            tokens.push((start, Token::Op(op)));
+            i += 1;
+        }
+    }
+
+    Ok(tokens)
+}
+
+// ============================================================================
+// PARSER
+// ============================================================================
+
+/// Parse an expression, enforcing the length, depth and aggregate limits
+pub fn parse(input: &str) -> Result<Expr, ExpressionError> {
+    if input.trim().is_empty() {
+        return Err(ExpressionError::Syntax {
+            position: 0,
+            message: "expression is empty".to_string(),
+        });
+    }
+    if input.len() > MAX_EXPRESSION_LEN {
+        return Err(ExpressionError::TooComplex(format!(
+            "longer than {} characters",
+            MAX_EXPRESSION_LEN
+        )));
+    }
+
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        end: input.chars().count(),
+        depth: 0,
+    };
+    let expr = parser.expr()?;
+    if let Some((position, _)) = parser.tokens.get(parser.pos) {
+        return Err(ExpressionError::Syntax {
+            position: *position,
+            message: "unexpected input after the end of the expression".to_string(),
+        });
+    }
+    if expr.aggregates().len() > MAX_AGGREGATES {
+        return Err(ExpressionError::TooComplex(format!(
+            "more than {} aggregates",
+            MAX_AGGREGATES
+        )));
+    }
+    Ok(expr)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(position, _)| *position)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ExpressionError> {
+        Err(ExpressionError::Syntax {
+            position: self.position(),
+            message: message.into(),
+        })
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(word)) if word.eq_ignore_ascii_case(keyword)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<(), ExpressionError> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            self.error(format!("expected '{}'", op))
+        }
+    }
+
+    fn ident(&mut self, what: &str) -> Result<String, ExpressionError> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => self.error(format!("expected {}", what)),
+        }
+    }
+
+    fn descend(&mut self) -> Result<(), ExpressionError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(ExpressionError::TooComplex(format!(
+                "nested deeper than {} levels",
+                MAX_DEPTH
+            )));
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self) -> Result<Expr, ExpressionError> {
+        self.descend()?;
+        let mut left = self.term()?;
+        loop {
+            let op = if self.eat_op("+") {
+                BinaryOp::Add
+            } else if self.eat_op("-") {
+                BinaryOp::Sub
+            } else {
+                break;
+            };
+            let right = self.term()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+        self.depth -= 1;
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.factor()?;
+        loop {
+            let op = if self.eat_op("*") {
+                BinaryOp::Mul
+            } else if self.eat_op("/") {
+                BinaryOp::Div
+            } else {
+                break;
+            };
+            let right = self.factor()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ExpressionError> {
+        if self.eat_op("-") {
+            self.descend()?;
+            let inner = self.factor()?;
+            self.depth -= 1;
+            return Ok(Expr::Negate(Box::new(inner)));
+        }
+        if self.eat_op("(") {
+            let inner = self.expr()?;
+            self.expect_op(")")?;
+            return Ok(inner);
+        }
+        match self.peek().cloned() {
+            Some(Token::Number(n)) => {
+                self.pos += 1;
+                Ok(Expr::Number(n))
+            }
+            Some(Token::Ident(name)) => {
+                let Some(function) = AggregateFn::parse(&name) else {
+                    return self.error(format!(
+                        "unknown function '{}'; use sum, avg, min, max, count or count_distinct",
+                        name
+                    ));
+                };
+                self.pos += 1;
+                self.aggregate(function)
+            }
+            _ => self.error("expected a number, an aggregate or '('"),
+        }
+    }
+
+    /// `fn(source[.field] [where condition])`
+    fn aggregate(&mut self, function: AggregateFn) -> Result<Expr, ExpressionError> {
+        self.expect_op("(")?;
+        let source = self.ident("a sheet name or 'vms'")?;
+        let field = if self.eat_op(".") {
+            Some(self.ident("a field name")?)
+        } else {
+            None
+        };
+        if field.is_none() && function != AggregateFn::Count {
+            return self.error("only count() may omit the field; write source.field");
+        }
+        let filter = if self.eat_keyword("where") {
+            Some(self.or_condition()?)
+        } else {
+            None
+        };
+        self.expect_op(")")?;
+        Ok(Expr::Aggregate(Aggregate {
+            function,
+            source,
+            field,
+            filter,
+        }))
+    }
+
+    fn or_condition(&mut self) -> Result<Condition, ExpressionError> {
+        self.descend()?;
+        let mut left = self.and_condition()?;
+        while self.eat_keyword("or") {
+            let right = self.and_condition()?;
+            left = Condition::Or(Box::new(left), Box::new(right));
+        }
+        self.depth -= 1;
+        Ok(left)
+    }
+
+    fn and_condition(&mut self) -> Result<Condition, ExpressionError> {
+        let mut left = self.unary_condition()?;
+        while self.eat_keyword("and") {
+            let right = self.unary_condition()?;
+            left = Condition::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary_condition(&mut self) -> Result<Condition, ExpressionError> {
+        if self.eat_keyword("not") {
+            self.descend()?;
+            let inner = self.unary_condition()?;
+            self.depth -= 1;
+            return Ok(Condition::Not(Box::new(inner)));
+        }
+        if self.eat_op("(") {
+            let inner = self.or_condition()?;
+            self.expect_op(")")?;
+            return Ok(inner);
+        }
+
+        let field = self.ident("a field name")?;
+        let op = if self.eat_op("=") {
+            CompareOp::Eq
+        } else if self.eat_op("!=") {
+            CompareOp::Ne
+        } else if self.eat_op("<=") {
+            CompareOp::Le
+        } else if self.eat_op(">=") {
+            CompareOp::Ge
+        } else if self.eat_op("<") {
+            CompareOp::Lt
+        } else if self.eat_op(">") {
+            CompareOp::Gt
+        } else if self.eat_keyword("contains") {
+            CompareOp::Contains
+        } else {
+            return self.error("expected a comparison (=, !=, <, <=, >, >=, contains)");
+        };
+
+        let negative = self.eat_op("-");
+        let value = match self.peek().cloned() {
+            Some(Token::Number(n)) => JsonValue::from(if negative { -n } else { n }),
+            Some(Token::Str(s)) if !negative => JsonValue::String(s),
+            Some(Token::Ident(word)) if !negative && word.eq_ignore_ascii_case("true") => JsonValue::Bool(true),
+            Some(Token::Ident(word)) if !negative && word.eq_ignore_ascii_case("false") => JsonValue::Bool(false),
+            _ => return self.error("expected a number, a quoted string, true or false"),
+        };
+        self.pos += 1;
+        if op == CompareOp::Contains && !value.is_string() {
+            return self.error("contains needs a quoted string");
+        }
+
+        Ok(Condition::Compare { field, op, value })
+    }
+}
+
+// ============================================================================
+// DATA
+// ============================================================================
+
+/// One sheet, or the parsed VM model, as rows of named values
+#[derive(Debug, Clone, Default)]
+pub struct DataSource {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<HashMap<String, JsonValue>>,
+}
+
+impl DataSource {
+    fn column(&self, field: &str) -> Option<&str> {
+        self.columns
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(field))
+            .map(String::as_str)
+    }
+}
+
+/// Columns and row count of a data source, for the editor
+#[derive(Debug, Clone, Serialize)]
+pub struct DataSourceSummary {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: usize,
+}
+
+/// The data expressions are checked and evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct DataSet {
+    sources: Vec<DataSource>,
+}
+
+impl DataSet {
+    /// Build sheets from the cell-level RVTools import
+    pub fn from_excel_cells(cells: &[RvToolsExcelData]) -> Self {
+        let mut sheets: Vec<DataSource> = Vec::new();
+        let mut rows: HashMap<(String, i32), usize> = HashMap::new();
+
+        for cell in cells {
+            let sheet_index = match sheets.iter().position(|s| s.name == cell.sheet_name) {
+                Some(index) => index,
+                None => {
+                    sheets.push(DataSource {
+                        name: cell.sheet_name.clone(),
+                        ..Default::default()
+                    });
+                    sheets.len() - 1
+                }
+            };
+            let sheet = &mut sheets[sheet_index];
+            if !sheet.columns.contains(&cell.column_name) {
+                sheet.columns.push(cell.column_name.clone());
+            }
+            let row_index = *rows
+                .entry((cell.sheet_name.clone(), cell.row_number))
+                .or_insert_with(|| {
+                    sheet.rows.push(HashMap::new());
+                    sheet.rows.len() - 1
+                });
+            let value = if cell.parsed_value.is_null() {
+                JsonValue::String(cell.raw_value.clone())
+            } else {
+                cell.parsed_value.clone()
+            };
+            sheet.rows[row_index].insert(cell.column_name.clone(), value);
+        }
+
+        Self { sources: sheets }
+    }
+
+    /// Add the parsed VM model as the `vms` source
+    pub fn with_vms(mut self, vms: &[RvToolsData]) -> Self {
+        let rows = vms
+            .iter()
+            .map(|vm| {
+                HashMap::from([
+                    ("vm_name".to_string(), JsonValue::from(vm.vm_name.clone())),
+                    ("host_name".to_string(), JsonValue::from(vm.host_name.clone())),
+                    ("cpu_cores".to_string(), JsonValue::from(vm.cpu_cores)),
+                    ("memory_gb".to_string(), JsonValue::from(vm.memory_gb)),
+                    ("disk_gb".to_string(), JsonValue::from(vm.disk_gb)),
+                    ("operating_system".to_string(), JsonValue::from(vm.operating_system.clone())),
+                    ("power_state".to_string(), JsonValue::from(vm.power_state.clone())),
+                    ("cluster".to_string(), JsonValue::from(vm.cluster.clone())),
+                    ("datacenter".to_string(), JsonValue::from(vm.datacenter.clone())),
+                    ("network_adapters".to_string(), JsonValue::from(vm.network_adapters)),
+                ])
+            })
+            .collect();
+        self.sources.retain(|s| !s.name.eq_ignore_ascii_case(VM_SOURCE));
+        self.sources.push(DataSource {
+            name: VM_SOURCE.to_string(),
+            columns: VM_FIELDS.iter().map(|f| f.to_string()).collect(),
+            rows,
+        });
+        self
+    }
+
+    pub fn push(&mut self, source: DataSource) {
+        self.sources.push(source);
+    }
+
+    pub fn source(&self, name: &str) -> Option<&DataSource> {
+        self.sources.iter().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn summary(&self) -> Vec<DataSourceSummary> {
+        self.sources
+            .iter()
+            .map(|s| DataSourceSummary {
+                name: s.name.clone(),
+                columns: s.columns.clone(),
+                rows: s.rows.len(),
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// VALIDATION & EVALUATION
+// ============================================================================
+
+/// Check that every source and field the expression reads exists in `data`
+///
+/// All problems are returned, not just the first, so the editor can mark
+/// them together.
+pub fn validate(expr: &Expr, data: &DataSet) -> Vec<ExpressionError> {
+    let mut errors = Vec::new();
+    for aggregate in expr.aggregates() {
+        let Some(source) = data.source(&aggregate.source) else {
+            let error = ExpressionError::UnknownSource(aggregate.source.clone());
+            if !errors.contains(&error) {
+                errors.push(error);
+            }
+            continue;
+        };
+        let mut fields = BTreeSet::new();
+        fields.extend(aggregate.field.clone());
+        if let Some(filter) = &aggregate.filter {
+            filter.fields(&mut fields);
+        }
+        for field in fields {
+            if source.column(&field).is_none() {
+                let error = ExpressionError::UnknownField {
+                    source_name: source.name.clone(),
+                    field,
+                };
+                if !errors.contains(&error) {
+                    errors.push(error);
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Evaluate against `data`; `None` when an aggregate has no rows to work on,
+/// such as the average of an empty selection
+pub fn evaluate(expr: &Expr, data: &DataSet) -> Result<Option<f64>, ExpressionError> {
+    if let Some(error) = validate(expr, data).into_iter().next() {
+        return Err(error);
+    }
+    eval(expr, data)
+}
+
+fn eval(expr: &Expr, data: &DataSet) -> Result<Option<f64>, ExpressionError> {
+    Ok(match expr {
+        Expr::Number(n) => Some(*n),
+        Expr::Negate(inner) => eval(inner, data)?.map(|v| -v),
+        Expr::Binary(left, op, right) => {
+            let (Some(left), Some(right)) = (eval(left, data)?, eval(right, data)?) else {
+                return Ok(None);
+            };
+            match op {
+                BinaryOp::Add => Some(left + right),
+                BinaryOp::Sub => Some(left - right),
+                BinaryOp::Mul => Some(left * right),
+                BinaryOp::Div if right == 0.0 => {
+                    return Err(ExpressionError::Evaluation("Division by zero".to_string()))
+                }
+                BinaryOp::Div => Some(left / right),
+            }
+        }
+        Expr::Aggregate(aggregate) => eval_aggregate(aggregate, data)?,
+    })
+}
+
+fn eval_aggregate(aggregate: &Aggregate, data: &DataSet) -> Result<Option<f64>, ExpressionError> {
+    let source = data
+        .source(&aggregate.source)
+        .ok_or_else(|| ExpressionError::UnknownSource(aggregate.source.clone()))?;
+    let field = aggregate.field.as_deref().and_then(|f| source.column(f));
+
+    let rows = source
+        .rows
+        .iter()
+        .filter(|row| aggregate.filter.as_ref().map_or(true, |c| matches(c, source, row)));
+
+    if aggregate.function == AggregateFn::Count && field.is_none() {
+        return Ok(Some(rows.count() as f64));
+    }
+    let field = field.unwrap_or_default();
+    let present = rows.filter_map(|row| row.get(field)).filter(|v| !is_blank(v));
+
+    match aggregate.function {
+        AggregateFn::Count => Ok(Some(present.count() as f64)),
+        AggregateFn::CountDistinct => {
+            let distinct: BTreeSet<String> = present.map(text).collect();
+            Ok(Some(distinct.len() as f64))
+        }
+        function => {
+            let mut numbers = Vec::new();
+            for value in present {
+                let number = as_number(value).ok_or_else(|| {
+                    ExpressionError::Evaluation(format!(
+                        "'{}' on '{}' has the non-numeric value '{}'",
+                        field,
+                        source.name,
+                        text(value)
+                    ))
+                })?;
+                numbers.push(number);
+            }
+            Ok(match function {
+                AggregateFn::Sum => Some(numbers.iter().sum()),
+                _ if numbers.is_empty() => None,
+                AggregateFn::Avg => Some(numbers.iter().sum::<f64>() / numbers.len() as f64),
+                AggregateFn::Min => numbers.into_iter().reduce(f64::min),
+                AggregateFn::Max => numbers.into_iter().reduce(f64::max),
+                AggregateFn::Count | AggregateFn::CountDistinct => unreachable!(),
+            })
+        }
+    }
+}
+
+fn matches(condition: &Condition, source: &DataSource, row: &HashMap<String, JsonValue>) -> bool {
+    match condition {
+        Condition::And(left, right) => matches(left, source, row) && matches(right, source, row),
+        Condition::Or(left, right) => matches(left, source, row) || matches(right, source, row),
+        Condition::Not(inner) => !matches(inner, source, row),
+        Condition::Compare { field, op, value } => {
+            let cell = source
+                .column(field)
+                .and_then(|column| row.get(column))
+                .unwrap_or(&JsonValue::Null);
+            compare(cell, *op, value)
+        }
+    }
+}
+
+fn compare(cell: &JsonValue, op: CompareOp, expected: &JsonValue) -> bool {
+    if let Some(expected) = expected.as_f64() {
+        let Some(actual) = as_number(cell) else {
+            return op == CompareOp::Ne;
+        };
+        return match op {
+            CompareOp::Eq => actual == expected,
+            CompareOp::Ne => actual != expected,
+            CompareOp::Lt => actual < expected,
+            CompareOp::Le => actual <= expected,
+            CompareOp::Gt => actual > expected,
+            CompareOp::Ge => actual >= expected,
+            CompareOp::Contains => false,
+        };
+    }
+
+    // Text and booleans compare case-insensitively, as RVTools writes
+    // "True"/"poweredOn" with varying case
+    let actual = text(cell).to_lowercase();
+    let expected = text(expected).to_lowercase();
+    match op {
+        CompareOp::Eq => actual == expected,
+        CompareOp::Ne => actual != expected,
+        CompareOp::Lt => actual < expected,
+        CompareOp::Le => actual <= expected,
+        CompareOp::Gt => actual > expected,
+        CompareOp::Ge => actual >= expected,
+        CompareOp::Contains => actual.contains(&expected),
+    }
+}
+
+fn is_blank(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => true,
+        JsonValue::String(s) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn as_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        JsonValue::String(s) => s.trim().replace(',', "").parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn data() -> DataSet {
+        let row = |name: &str, memory: JsonValue, state: &str, os: &str| {
+            HashMap::from([
+                ("VM".to_string(), json!(name)),
+                ("Memory".to_string(), memory),
+                ("Powerstate".to_string(), json!(state)),
+                ("OS according to the VMware Tools".to_string(), json!(os)),
+            ])
+        };
+        let mut data = DataSet::default();
+        data.push(DataSource {
+            name: "vInfo".to_string(),
+            columns: vec![
+                "VM".to_string(),
+                "Memory".to_string(),
+                "Powerstate".to_string(),
+                "OS according to the VMware Tools".to_string(),
+            ],
+            rows: vec![
+                row("web01", json!(4096), "poweredOn", "Microsoft Windows Server 2019"),
+                row("web02", json!("8,192"), "poweredOn", "Microsoft Windows Server 2022"),
+                row("db01", json!(16384), "poweredOff", "Red Hat Enterprise Linux 8"),
+                row("tmp01", json!(""), "poweredOff", "Ubuntu Linux"),
+            ],
+        });
+        data
+    }
+
+    fn value(expression: &str) -> Option<f64> {
+        evaluate(&parse(expression).unwrap(), &data()).unwrap()
+    }
+
+    #[test]
+    fn test_aggregates_with_filters_and_arithmetic() {
+        assert_eq!(value("count(vInfo)"), Some(4.0));
+        assert_eq!(value("count(vInfo.Memory)"), Some(3.0));
+        assert_eq!(value("sum(vinfo.memory) / 1024"), Some(28.0));
+        assert_eq!(value(r#"sum(vInfo.Memory where Powerstate = "POWEREDON")"#), Some(12288.0));
+        assert_eq!(
+            value("count(vInfo where `OS according to the VMware Tools` contains 'windows' and not VM = 'web02')"),
+            Some(1.0)
+        );
+        assert_eq!(value("max(vInfo.Memory where Memory < 10000 or VM = 'db01')"), Some(16384.0));
+        assert_eq!(value("count_distinct(vInfo.Powerstate) * -(2 + 1)"), Some(-6.0));
+    }
+
+    #[test]
+    fn test_empty_selection_has_no_value() {
+        assert_eq!(value("avg(vInfo.Memory where VM = 'none')"), None);
+        assert_eq!(value("avg(vInfo.Memory where VM = 'none') + 1"), None);
+        assert_eq!(value("sum(vInfo.Memory where VM = 'none')"), Some(0.0));
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        let error = parse("sum(vInfo.Memory").unwrap_err();
+        assert_eq!(
+            error,
+            ExpressionError::Syntax {
+                position: 16,
+                message: "expected ')'".to_string()
+            }
+        );
+        assert!(matches!(parse("sum(vInfo)"), Err(ExpressionError::Syntax { .. })));
+        assert!(matches!(parse("drop(vInfo.VM)"), Err(ExpressionError::Syntax { position: 0, .. })));
+        assert!(matches!(parse("count(vInfo) count(vInfo)"), Err(ExpressionError::Syntax { .. })));
+        assert!(matches!(parse("count(vInfo where VM contains 3)"), Err(ExpressionError::Syntax { .. })));
+    }
+
+    #[test]
+    fn test_limits_are_enforced() {
+        let deep = format!("{}1{}", "(".repeat(MAX_DEPTH + 1), ")".repeat(MAX_DEPTH + 1));
+        assert!(matches!(parse(&deep), Err(ExpressionError::TooComplex(_))));
+        let many = vec!["count(vInfo)"; MAX_AGGREGATES + 1].join(" + ");
+        assert!(matches!(parse(&many), Err(ExpressionError::TooComplex(_))));
+        let long = format!("1{}", " + 1".repeat(MAX_EXPRESSION_LEN));
+        assert!(matches!(parse(&long), Err(ExpressionError::TooComplex(_))));
+    }
+
+    #[test]
+    fn test_validation_reports_every_unknown_reference() {
+        let expr = parse("sum(vInfo.Memroy where State = 'on') + count(vDisk) + count(vDisk)").unwrap();
+        let errors = validate(&expr, &data());
+        assert_eq!(
+            errors,
+            vec![
+                ExpressionError::UnknownField {
+                    source_name: "vInfo".to_string(),
+                    field: "Memroy".to_string()
+                },
+                ExpressionError::UnknownField {
+                    source_name: "vInfo".to_string(),
+                    field: "State".to_string()
+                },
+                ExpressionError::UnknownSource("vDisk".to_string()),
+            ]
+        );
+        assert!(evaluate(&expr, &data()).is_err());
+    }
+
+    #[test]
+    fn test_non_numeric_values_and_division_by_zero_fail() {
+        let expr = parse("sum(vInfo.VM)").unwrap();
+        assert!(matches!(evaluate(&expr, &data()), Err(ExpressionError::Evaluation(_))));
+        let expr = parse("count(vInfo) / (count(vInfo) - 4)").unwrap();
+        assert!(matches!(evaluate(&expr, &data()), Err(ExpressionError::Evaluation(_))));
+    }
+
+    #[test]
+    fn test_references_and_vm_model() {
+        let expr = parse("sum(vms.memory_gb where power_state = 'poweredOn') + count(vInfo where VM != 'x')").unwrap();
+        assert_eq!(
+            expr.references(),
+            (
+                vec!["vInfo".to_string(), "vms".to_string()],
+                vec!["VM".to_string(), "memory_gb".to_string(), "power_state".to_string()]
+            )
+        );
+        assert!(validate(&expr, &DataSet::default().with_vms(&[])).contains(&ExpressionError::UnknownSource("vInfo".to_string())));
+        assert_eq!(evaluate(&parse("sum(vms.memory_gb)").unwrap(), &DataSet::default().with_vms(&[])).unwrap(), Some(0.0));
+    }
+}
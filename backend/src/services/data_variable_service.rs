@@ -0,0 +1,413 @@
+// Archer - Data Variable Service
+// Stores user-defined data variables in data_variable_schema next to the
+// built-in ones, and evaluates them on an RVTools upload for reports and HLD
+// generation
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::{Database, TracedQuery};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::data_variable::*;
+use crate::models::project_models::{RvToolsData, RvToolsExcelData};
+use crate::services::data_expression::{self, DataSet, ExpressionError};
+use crate::services::variable_definitions::VariableDefinitions;
+
+/// Longest variable name accepted
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Deserialize)]
+struct UploadRef {
+    id: Thing,
+}
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum DataVariableError {
+    #[error("Data variable not found")]
+    NotFound,
+
+    #[error("Built-in data variables cannot be changed")]
+    BuiltIn,
+
+    #[error("A variable named '{0}' already exists")]
+    Conflict(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Invalid expression: {0}")]
+    Expression(#[from] ExpressionError),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for DataVariableError {
+    fn from(e: surrealdb::Error) -> Self {
+        DataVariableError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// DATA VARIABLE SERVICE
+// ============================================================================
+
+pub struct DataVariableService {
+    db: Database,
+}
+
+impl DataVariableService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Built-in and user-defined variables
+    pub async fn list(&self) -> Result<Vec<DataVariableSchema>, DataVariableError> {
+        let variables: Vec<DataVariableSchema> = self
+            .db
+            .query("SELECT * FROM data_variable_schema ORDER BY category, variable_name")
+            .traced("data_variables.list")
+            .await?
+            .take(0)?;
+        Ok(variables)
+    }
+
+    pub async fn get(&self, name: &str) -> Result<DataVariableSchema, DataVariableError> {
+        let variables: Vec<DataVariableSchema> = self
+            .db
+            .query("SELECT * FROM data_variable_schema WHERE variable_name = $name LIMIT 1")
+            .bind(("name", name.to_string()))
+            .traced("data_variables.get")
+            .await?
+            .take(0)?;
+        variables.into_iter().next().ok_or(DataVariableError::NotFound)
+    }
+
+    /// User-defined variables only
+    pub async fn list_custom(&self) -> Result<Vec<DataVariableSchema>, DataVariableError> {
+        let variables: Vec<DataVariableSchema> = self
+            .db
+            .query("SELECT * FROM data_variable_schema WHERE aggregation_method = $method ORDER BY variable_name")
+            .bind(("method", EXPRESSION_METHOD))
+            .traced("data_variables.list_custom")
+            .await?
+            .take(0)?;
+        Ok(variables)
+    }
+
+    pub async fn create(
+        &self,
+        user: &AuthenticatedUser,
+        request: SaveDataVariableRequest,
+    ) -> Result<DataVariableSchema, DataVariableError> {
+        validate_name(&request.variable_name)?;
+        match self.get(&request.variable_name).await {
+            Ok(_) => return Err(DataVariableError::Conflict(request.variable_name)),
+            Err(DataVariableError::NotFound) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut variable = self.checked_record(request).await?;
+        variable.created_by = Some(user.user_id.clone());
+        variable.created_at = Some(Utc::now());
+        variable.updated_at = variable.created_at;
+
+        let created: Vec<DataVariableSchema> = self.db.create("data_variable_schema").content(&variable).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| DataVariableError::DatabaseError("Failed to create data variable".to_string()))
+    }
+
+    /// Replace a user-defined variable's definition; the name stays the same
+    pub async fn update(
+        &self,
+        name: &str,
+        request: SaveDataVariableRequest,
+    ) -> Result<DataVariableSchema, DataVariableError> {
+        let existing = self.get(name).await?;
+        if !existing.is_custom() {
+            return Err(DataVariableError::BuiltIn);
+        }
+        if request.variable_name != name {
+            return Err(DataVariableError::Validation(
+                "variable_name cannot be changed; create a new variable instead".to_string(),
+            ));
+        }
+
+        let mut variable = self.checked_record(request).await?;
+        variable.created_by = existing.created_by;
+        variable.created_at = existing.created_at;
+        variable.updated_at = Some(Utc::now());
+
+        let thing = existing.id.ok_or(DataVariableError::NotFound)?;
+        let updated: Option<DataVariableSchema> = self.db.update(thing).content(&variable).await?;
+        updated.ok_or(DataVariableError::NotFound)
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<(), DataVariableError> {
+        let existing = self.get(name).await?;
+        if !existing.is_custom() {
+            return Err(DataVariableError::BuiltIn);
+        }
+        let thing = existing.id.ok_or(DataVariableError::NotFound)?;
+        let _: Option<DataVariableSchema> = self.db.delete(thing).await?;
+        Ok(())
+    }
+
+    /// Parse an expression and, given an upload, check it against that
+    /// upload's data and evaluate it
+    pub async fn validate_expression(
+        &self,
+        request: &ValidateExpressionRequest,
+    ) -> Result<ExpressionValidation, DataVariableError> {
+        let expr = match data_expression::parse(&request.expression) {
+            Ok(expr) => expr,
+            Err(error) => {
+                return Ok(ExpressionValidation {
+                    valid: false,
+                    errors: vec![error.to_string()],
+                    source_sheets: Vec::new(),
+                    source_columns: Vec::new(),
+                    value: None,
+                    available: Vec::new(),
+                })
+            }
+        };
+        let (source_sheets, source_columns) = expr.references();
+
+        let Some(upload_id) = &request.upload_id else {
+            return Ok(ExpressionValidation {
+                valid: true,
+                errors: Vec::new(),
+                source_sheets,
+                source_columns,
+                value: None,
+                available: Vec::new(),
+            });
+        };
+
+        let data = self.load_data(&upload_thing(upload_id)).await?;
+        // Report every unknown reference at once, then any evaluation error
+        let mut errors: Vec<String> = data_expression::validate(&expr, &data)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let value = if errors.is_empty() {
+            data_expression::evaluate(&expr, &data).unwrap_or_else(|error| {
+                errors.push(error.to_string());
+                None
+            })
+        } else {
+            None
+        };
+
+        Ok(ExpressionValidation {
+            valid: errors.is_empty(),
+            errors,
+            source_sheets,
+            source_columns,
+            value,
+            available: data.summary(),
+        })
+    }
+
+    /// Sheets from the cell-level import plus the parsed VMs of one upload
+    pub async fn load_data(&self, upload_id: &Thing) -> Result<DataSet, DataVariableError> {
+        let mut response = self
+            .db
+            .query(
+                "SELECT * FROM rvtools_excel_data WHERE upload_id = $upload ORDER BY sheet_name, row_number, column_index;
+                 SELECT * FROM rvtools_data WHERE upload_id = $upload;",
+            )
+            .bind(("upload", upload_id.clone()))
+            .traced("data_variables.load_data")
+            .await?;
+        let cells: Vec<RvToolsExcelData> = response.take(0)?;
+        let vms: Vec<RvToolsData> = response.take(1)?;
+        Ok(DataSet::from_excel_cells(&cells).with_vms(&vms))
+    }
+
+    /// Most recent RVTools upload of a project, the one HLD auto-fill reads
+    pub async fn latest_project_upload(&self, project_id: &Thing) -> Result<Option<Thing>, DataVariableError> {
+        let uploads: Vec<UploadRef> = self
+            .db
+            .query("SELECT id, uploaded_at FROM rvtools_uploads WHERE project_id = $project ORDER BY uploaded_at DESC LIMIT 1")
+            .bind(("project", project_id.clone()))
+            .traced("data_variables.latest_project_upload")
+            .await?
+            .take(0)?;
+        Ok(uploads.into_iter().next().map(|upload| upload.id))
+    }
+
+    /// Every user-defined variable evaluated on one upload
+    ///
+    /// A variable that fails on this upload's data carries the error instead
+    /// of a value, so one bad definition does not hide the others.
+    pub async fn evaluate_custom(&self, upload_id: &Thing) -> Result<Vec<EvaluatedDataVariable>, DataVariableError> {
+        let variables = self.list_custom().await?;
+        if variables.is_empty() {
+            return Ok(Vec::new());
+        }
+        let data = self.load_data(upload_id).await?;
+        Ok(variables
+            .iter()
+            .map(|variable| evaluate_variable(variable, &data))
+            .collect())
+    }
+
+    /// Parse the request's expression, check it against the upload's data
+    /// when one was given, and build the record to store
+    async fn checked_record(&self, request: SaveDataVariableRequest) -> Result<DataVariableSchema, DataVariableError> {
+        if request.display_name.trim().is_empty() {
+            return Err(DataVariableError::Validation("display_name is required".to_string()));
+        }
+        let expr = data_expression::parse(&request.expression)?;
+        if let Some(upload_id) = &request.upload_id {
+            let data = self.load_data(&upload_thing(upload_id)).await?;
+            let errors = data_expression::validate(&expr, &data);
+            if !errors.is_empty() {
+                let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                return Err(DataVariableError::Validation(messages.join("; ")));
+            }
+        }
+        let (source_sheets, source_columns) = expr.references();
+
+        Ok(DataVariableSchema {
+            id: None,
+            variable_name: request.variable_name,
+            display_name: request.display_name.trim().to_string(),
+            data_type: "float".to_string(),
+            description: request.description,
+            source_sheets,
+            source_columns,
+            aggregation_method: EXPRESSION_METHOD.to_string(),
+            expression: Some(request.expression),
+            formatting_rules: request.formatting_rules.unwrap_or_else(|| json!({"format": "number"})),
+            validation_rules: Vec::new(),
+            category: request.category,
+            created_by: None,
+            created_at: None,
+            updated_at: None,
+        })
+    }
+}
+
+/// Accept `rvtools_uploads:abc` or a bare id
+pub fn upload_thing(upload_id: &str) -> Thing {
+    upload_id
+        .parse::<Thing>()
+        .unwrap_or_else(|_| Thing::from(("rvtools_uploads", upload_id)))
+}
+
+/// Names are referenced as `{{name}}` in HLD sections and by name in report
+/// templates, so they must be plain identifiers that no HLD variable uses
+fn validate_name(name: &str) -> Result<(), DataVariableError> {
+    let well_formed = name.len() <= MAX_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !well_formed {
+        return Err(DataVariableError::Validation(format!(
+            "variable_name must be lowercase letters, digits and underscores, start with a letter and be at most {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    if VariableDefinitions::get(name).is_some() {
+        return Err(DataVariableError::Conflict(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Evaluate one user-defined variable; built-in rows evaluate to nothing
+pub fn evaluate_variable(variable: &DataVariableSchema, data: &DataSet) -> EvaluatedDataVariable {
+    let result = variable
+        .expression
+        .as_deref()
+        .ok_or_else(|| ExpressionError::Evaluation("Variable has no expression".to_string()))
+        .and_then(data_expression::parse)
+        .and_then(|expr| data_expression::evaluate(&expr, data));
+    match result {
+        Ok(value) => EvaluatedDataVariable {
+            variable_name: variable.variable_name.clone(),
+            value,
+            error: None,
+        },
+        Err(error) => EvaluatedDataVariable {
+            variable_name: variable.variable_name.clone(),
+            value: None,
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::data_expression::DataSource;
+    use std::collections::HashMap;
+
+    fn variable(name: &str, expression: &str) -> DataVariableSchema {
+        DataVariableSchema {
+            id: None,
+            variable_name: name.to_string(),
+            display_name: name.to_string(),
+            data_type: "float".to_string(),
+            description: String::new(),
+            source_sheets: Vec::new(),
+            source_columns: Vec::new(),
+            aggregation_method: EXPRESSION_METHOD.to_string(),
+            expression: Some(expression.to_string()),
+            formatting_rules: json!({}),
+            validation_rules: Vec::new(),
+            category: "custom".to_string(),
+            created_by: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("windows_vm_count").is_ok());
+        assert!(matches!(validate_name("WindowsCount"), Err(DataVariableError::Validation(_))));
+        assert!(matches!(validate_name("1st"), Err(DataVariableError::Validation(_))));
+        assert!(matches!(validate_name("total-vms"), Err(DataVariableError::Validation(_))));
+        assert!(matches!(validate_name(&"a".repeat(MAX_NAME_LEN + 1)), Err(DataVariableError::Validation(_))));
+        // HLD variable names are taken
+        assert!(matches!(validate_name("node_count"), Err(DataVariableError::Conflict(_))));
+    }
+
+    #[test]
+    fn test_upload_thing_accepts_full_and_bare_ids() {
+        assert_eq!(upload_thing("rvtools_uploads:abc"), Thing::from(("rvtools_uploads", "abc")));
+        assert_eq!(upload_thing("abc"), Thing::from(("rvtools_uploads", "abc")));
+    }
+
+    #[test]
+    fn test_evaluate_variable_keeps_errors_per_variable() {
+        let mut data = DataSet::default();
+        data.push(DataSource {
+            name: "vHost".to_string(),
+            columns: vec!["# Cores".to_string()],
+            rows: vec![
+                HashMap::from([("# Cores".to_string(), json!(32))]),
+                HashMap::from([("# Cores".to_string(), json!(48))]),
+            ],
+        });
+
+        let ok = evaluate_variable(&variable("total_cores", "sum(vHost.`# Cores`)"), &data);
+        assert_eq!(ok.value, Some(80.0));
+        assert!(ok.error.is_none());
+
+        let bad = evaluate_variable(&variable("disk_count", "count(vDisk)"), &data);
+        assert_eq!(bad.value, None);
+        assert_eq!(bad.error.as_deref(), Some("Unknown data source 'vDisk'"));
+    }
+}
@@ -0,0 +1,268 @@
+// Archer ITSM - Asset Depreciation Service
+// Book value, monthly depreciation and refresh forecasting for hardware CIs
+// with purchase data
+
+use crate::database::{Db, TracedQuery};
+use crate::models::cmdb::*;
+use chrono::{DateTime, Datelike, Months, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+/// Currency assumed for financials recorded without one
+const DEFAULT_CURRENCY: &str = "USD";
+/// Default declining balance factor (double declining)
+const DEFAULT_DECLINING_FACTOR: f64 = 2.0;
+const DEFAULT_HORIZON_MONTHS: u32 = 12;
+const MAX_HORIZON_MONTHS: u32 = 120;
+
+/// Valuation of one asset at a point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Valuation {
+    pub book_value: f64,
+    pub accumulated_depreciation: f64,
+    pub monthly_depreciation: f64,
+    pub depreciation_end: DateTime<Utc>,
+    pub months_remaining: u32,
+}
+
+pub struct DepreciationService;
+
+impl DepreciationService {
+    /// Value `financials` as of `as_of`. Depreciation is charged in whole
+    /// months from the purchase date and never goes below the salvage value.
+    pub fn valuate(financials: &AssetFinancials, as_of: DateTime<Utc>) -> Valuation {
+        let life = financials.useful_life_months.max(1);
+        let elapsed = months_between(financials.purchase_date, as_of).min(life);
+        let book_value = value_after(financials, elapsed);
+
+        Valuation {
+            book_value: round_cents(book_value),
+            accumulated_depreciation: round_cents(financials.purchase_cost - book_value),
+            monthly_depreciation: round_cents(book_value - value_after(financials, elapsed + 1)),
+            depreciation_end: financials
+                .purchase_date
+                .checked_add_months(Months::new(life))
+                .unwrap_or(financials.purchase_date),
+            months_remaining: life - elapsed,
+        }
+    }
+
+    /// Book values, per-currency totals and refresh forecast for hardware
+    /// CIs with purchase data. Disposed CIs are left out.
+    pub async fn report(
+        db: Arc<Surreal<Db>>,
+        query: DepreciationReportQuery,
+        tenant_id: Option<&str>,
+    ) -> Result<DepreciationReport, String> {
+        let as_of = query.as_of.unwrap_or_else(Utc::now);
+        let horizon_months = query
+            .horizon_months
+            .unwrap_or(DEFAULT_HORIZON_MONTHS)
+            .min(MAX_HORIZON_MONTHS);
+
+        let mut conditions = vec![
+            "ci_class = 'HARDWARE'",
+            "financials != NONE",
+            "status != 'DISPOSED'",
+        ];
+        if tenant_id.is_some() {
+            conditions.push("tenant_id = $tenant");
+        }
+        if query.location.is_some() {
+            conditions.push("location = $location");
+        }
+        if query.environment.is_some() {
+            conditions.push("environment = $environment");
+        }
+        let sql = format!(
+            "SELECT * FROM configuration_items WHERE {} ORDER BY ci_id ASC",
+            conditions.join(" AND ")
+        );
+
+        let cis: Vec<ConfigurationItem> = db
+            .query(sql)
+            .bind(("tenant", tenant_id.map(|t| Thing::from(("tenants", t)))))
+            .bind(("location", query.location))
+            .bind(("environment", query.environment))
+            .traced("cmdb.depreciation_report")
+            .await
+            .map_err(|e| e.to_string())?
+            .take(0)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self::build_report(cis, as_of, horizon_months))
+    }
+
+    fn build_report(cis: Vec<ConfigurationItem>, as_of: DateTime<Utc>, horizon_months: u32) -> DepreciationReport {
+        let horizon_end = as_of.checked_add_months(Months::new(horizon_months)).unwrap_or(as_of);
+        let mut assets = Vec::new();
+        let mut totals: BTreeMap<String, DepreciationTotals> = BTreeMap::new();
+        let mut refresh_overdue = Vec::new();
+        let mut forecast: BTreeMap<String, RefreshForecastMonth> = BTreeMap::new();
+
+        for ci in cis {
+            let Some(financials) = ci.financials else { continue };
+            let valuation = Self::valuate(&financials, as_of);
+            let currency = financials
+                .currency
+                .as_deref()
+                .map(str::to_uppercase)
+                .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
+            let fully_depreciated = valuation.months_remaining == 0;
+
+            let total = totals.entry(currency.clone()).or_default();
+            total.asset_count += 1;
+            total.purchase_cost += financials.purchase_cost;
+            total.book_value += valuation.book_value;
+            total.accumulated_depreciation += valuation.accumulated_depreciation;
+            total.monthly_depreciation += valuation.monthly_depreciation;
+
+            // Retired hardware is already out of service and needs no refresh
+            if ci.status != CIStatus::Retired {
+                if fully_depreciated {
+                    refresh_overdue.push(ci.ci_id.clone());
+                } else if valuation.depreciation_end <= horizon_end {
+                    let end = valuation.depreciation_end;
+                    let month = format!("{:04}-{:02}", end.year(), end.month());
+                    let bucket = forecast.entry(month.clone()).or_insert_with(|| RefreshForecastMonth {
+                        month,
+                        asset_count: 0,
+                        replacement_cost: BTreeMap::new(),
+                        ci_ids: Vec::new(),
+                    });
+                    bucket.asset_count += 1;
+                    *bucket.replacement_cost.entry(currency.clone()).or_default() += financials.purchase_cost;
+                    bucket.ci_ids.push(ci.ci_id.clone());
+                }
+            }
+
+            assets.push(AssetBookValue {
+                id: ci.id,
+                ci_id: ci.ci_id,
+                name: ci.name,
+                status: ci.status,
+                location: ci.location,
+                currency,
+                purchase_cost: financials.purchase_cost,
+                purchase_date: financials.purchase_date,
+                depreciation_method: financials.depreciation_method,
+                salvage_value: financials.salvage_value,
+                book_value: valuation.book_value,
+                accumulated_depreciation: valuation.accumulated_depreciation,
+                monthly_depreciation: valuation.monthly_depreciation,
+                depreciation_end: valuation.depreciation_end,
+                months_remaining: valuation.months_remaining,
+                fully_depreciated,
+            });
+        }
+
+        for total in totals.values_mut() {
+            total.purchase_cost = round_cents(total.purchase_cost);
+            total.book_value = round_cents(total.book_value);
+            total.accumulated_depreciation = round_cents(total.accumulated_depreciation);
+            total.monthly_depreciation = round_cents(total.monthly_depreciation);
+        }
+
+        DepreciationReport {
+            as_of,
+            horizon_months,
+            assets,
+            totals,
+            refresh_overdue,
+            refresh_forecast: forecast.into_values().collect(),
+        }
+    }
+}
+
+/// Book value after `months` full months of depreciation (unrounded)
+fn value_after(financials: &AssetFinancials, months: u32) -> f64 {
+    let life = financials.useful_life_months.max(1);
+    let cost = financials.purchase_cost;
+    let salvage = financials.salvage_value.min(cost);
+    if months >= life {
+        return salvage;
+    }
+
+    match financials.depreciation_method {
+        DepreciationMethod::StraightLine => cost - (cost - salvage) * months as f64 / life as f64,
+        DepreciationMethod::DecliningBalance => {
+            let factor = financials.declining_factor.unwrap_or(DEFAULT_DECLINING_FACTOR);
+            let rate = (factor / life as f64).clamp(0.0, 1.0);
+            (cost * (1.0 - rate).powi(months as i32)).max(salvage)
+        }
+    }
+}
+
+/// Whole months from `start` to `end`, 0 if `end` is earlier
+fn months_between(start: DateTime<Utc>, end: DateTime<Utc>) -> u32 {
+    if end <= start {
+        return 0;
+    }
+    let mut months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    if start.checked_add_months(Months::new(months.max(0) as u32)).map_or(false, |d| d > end) {
+        months -= 1;
+    }
+    months.max(0) as u32
+}
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn server(method: DepreciationMethod) -> AssetFinancials {
+        AssetFinancials {
+            purchase_cost: 12_000.0,
+            purchase_date: Utc.with_ymd_and_hms(2023, 1, 15, 0, 0, 0).unwrap(),
+            depreciation_method: method,
+            useful_life_months: 36,
+            salvage_value: 1_200.0,
+            declining_factor: None,
+            currency: None,
+        }
+    }
+
+    #[test]
+    fn test_straight_line_valuation() {
+        let financials = server(DepreciationMethod::StraightLine);
+
+        // 12 full months on 2024-01-20; 10,800 over 36 months is 300/month
+        let valuation = DepreciationService::valuate(&financials, Utc.with_ymd_and_hms(2024, 1, 20, 0, 0, 0).unwrap());
+        assert_eq!(valuation.book_value, 8_400.0);
+        assert_eq!(valuation.accumulated_depreciation, 3_600.0);
+        assert_eq!(valuation.monthly_depreciation, 300.0);
+        assert_eq!(valuation.months_remaining, 24);
+        assert_eq!(valuation.depreciation_end, Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap());
+
+        // The month is not complete until the purchase day comes round
+        let valuation = DepreciationService::valuate(&financials, Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap());
+        assert_eq!(valuation.months_remaining, 25);
+
+        let valuation = DepreciationService::valuate(&financials, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(valuation.book_value, 1_200.0);
+        assert_eq!(valuation.monthly_depreciation, 0.0);
+        assert_eq!(valuation.months_remaining, 0);
+    }
+
+    #[test]
+    fn test_declining_balance_ends_at_salvage() {
+        let financials = server(DepreciationMethod::DecliningBalance);
+
+        // Double declining over 36 months: 1/18 of the book value each month
+        let valuation = DepreciationService::valuate(&financials, Utc.with_ymd_and_hms(2023, 2, 15, 0, 0, 0).unwrap());
+        assert_eq!(valuation.book_value, 11_333.33);
+        assert_eq!(valuation.monthly_depreciation, 629.63);
+
+        // The last month writes the remaining balance down to salvage
+        let valuation = DepreciationService::valuate(&financials, Utc.with_ymd_and_hms(2025, 12, 15, 0, 0, 0).unwrap());
+        assert_eq!(valuation.book_value, 1_623.14);
+        assert_eq!(valuation.monthly_depreciation, 423.14);
+        assert_eq!(valuation.months_remaining, 1);
+    }
+}
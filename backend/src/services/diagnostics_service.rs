@@ -0,0 +1,145 @@
+//! Diagnostics Service
+//!
+//! Builds the diagnostics bundle administrators attach to support tickets:
+//! version and platform, effective configuration, database reachability and
+//! recent log lines. Everything in the bundle is scrubbed; configuration
+//! values with secret names are replaced outright.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use core_engine::redaction::{is_sensitive_field, scrub, REDACTED};
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::utils::log_scrubbing::recent_logs;
+use crate::database::TracedQuery;
+
+/// Environment variables the backend reads; anything else in the process
+/// environment stays out of the bundle
+const CONFIG_KEYS: &[&str] = &[
+    "RUST_LOG",
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+    "OTEL_SERVICE_NAME",
+    "SURREALDB_URL",
+    "SURREALDB_USER",
+    "SURREALDB_PASS",
+    "ARCHER_INSTANCE_ID",
+    "BACKEND_HOST",
+    "BACKEND_PORT",
+    "RUST_BACKEND_HOST",
+    "RUST_BACKEND_PORT",
+    "BASE_URL",
+    "CORS_ALLOWED_ORIGINS",
+    "CORS_SENSITIVE_ORIGINS",
+    "CORS_ALLOW_CREDENTIALS",
+    "CORS_ALLOWED_HEADERS",
+    "CORS_MAX_AGE_SECS",
+    "EXPORT_DIR",
+    "HLD_OUTPUT_DIR",
+    "REPORT_ARTIFACT_DIR",
+    "KB_ATTACHMENT_DIR",
+    "KB_ATTACHMENT_BACKEND",
+    "DOCUMENT_STORAGE_BACKEND",
+    "DOCUMENT_STORAGE_DIR",
+    "DOCUMENT_S3_BUCKET",
+    "DOCUMENT_S3_REGION",
+    "DOCUMENT_S3_ENDPOINT",
+    "DOCUMENT_URL_TTL_SECS",
+    "UPLOAD_SESSION_DIR",
+    "UPLOAD_MAX_BYTES",
+    "UPLOAD_MAX_CHUNK_BYTES",
+    "UPLOAD_SESSION_TTL_HOURS",
+    "RVTOOLS_INGEST_CHUNK_SIZE",
+    "ANONYMIZATION_MAP_DIR",
+    "SMTP_HOST",
+    "SMTP_PORT",
+    "SMTP_USERNAME",
+    "SMTP_PASSWORD",
+    "SMTP_FROM",
+    "JWT_SECRET",
+    "ARCHER_SECRETS_KEY",
+    "SEED_DEMO_DATA",
+    "REPORT_SCHEDULER_ENABLED",
+    "TIERING_SCHEDULER_ENABLED",
+    "WORKFLOW_TIMERS_ENABLED",
+    "INTEGRATION_SYNC_ENABLED",
+    "WATCH_DIGEST_ENABLED",
+    "WATCH_DIGEST_CRON",
+    "TICKET_SCHEDULER_ENABLED",
+    "CONTRACT_ALERTS_ENABLED",
+    "CONTRACT_ALERT_CRON",
+    "UPLOAD_CLEANUP_ENABLED",
+    "PROCUREMENT_APPROVAL_WORKFLOW",
+    "INTEGRATION_SIMULATION",
+    "TICKET_ANALYTICS_CACHE_SECS",
+];
+
+/// Log lines included in a bundle
+const BUNDLE_LOG_LINES: usize = 1000;
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsBundle {
+    pub generated_at: DateTime<Utc>,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    /// Configured values; `None` when the variable is not set
+    pub configuration: BTreeMap<String, Option<String>>,
+    pub database: DatabaseDiagnostics,
+    pub recent_logs: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseDiagnostics {
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub struct DiagnosticsService {
+    db: Database,
+}
+
+impl DiagnosticsService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn bundle(&self) -> DiagnosticsBundle {
+        let configuration = CONFIG_KEYS
+            .iter()
+            .map(|key| {
+                let value = std::env::var(key).ok().map(|value| {
+                    if is_sensitive_field(key) {
+                        REDACTED.to_string()
+                    } else {
+                        scrub(&value)
+                    }
+                });
+                (key.to_string(), value)
+            })
+            .collect();
+
+        let database = match self.db.query("INFO FOR DB").traced("diagnostics_service.bundle").await {
+            Ok(_) => DatabaseDiagnostics {
+                reachable: true,
+                error: None,
+            },
+            Err(e) => DatabaseDiagnostics {
+                reachable: false,
+                error: Some(scrub(&e.to_string())),
+            },
+        };
+
+        DiagnosticsBundle {
+            generated_at: Utc::now(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            configuration,
+            database,
+            recent_logs: recent_logs(BUNDLE_LOG_LINES),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+// Archer - Document Redaction
+// Template sections carry sensitivity tags (pricing, credentials,
+// licensing). A generation run renders one variant per audience from the
+// same sections and variables, leaving out what the audience may not see.
+// Version records shown to a redacted audience are cut down the same way, so
+// the snapshots of hidden values do not leak through the API.
+
+use serde_json::json;
+use std::collections::BTreeSet;
+
+use crate::models::hld::{DocumentAudience, HLDVariable, SectionDefinition};
+use crate::models::project_models::GeneratedDocument;
+use crate::services::hld_dry_run_service::section_inputs;
+use crate::services::word_generator::WordGenerator;
+
+/// What one audience's variant is generated from
+#[derive(Debug, Clone)]
+pub struct RedactedInputs {
+    pub audience: DocumentAudience,
+    pub sections: Vec<SectionDefinition>,
+    pub variables: Vec<HLDVariable>,
+    pub redacted_sections: Vec<String>,
+    pub redacted_variables: Vec<String>,
+}
+
+/// Leave out the enabled sections tagged with something the audience may
+/// not see, and the variables only those sections show
+///
+/// A variable is hidden when it belongs to a hidden section, or when a
+/// hidden section reads it and neither the title page nor any visible
+/// section does.
+pub fn redact(
+    audience: DocumentAudience,
+    sections: &[SectionDefinition],
+    variables: &[HLDVariable],
+) -> RedactedInputs {
+    let (hidden, visible): (Vec<&SectionDefinition>, Vec<&SectionDefinition>) = sections
+        .iter()
+        .filter(|section| section.enabled)
+        .partition(|section| section.sensitivity.iter().any(|tag| audience.hides(*tag)));
+    let hidden_ids: BTreeSet<&str> = hidden.iter().map(|s| s.section_id.as_str()).collect();
+
+    let mut shown: BTreeSet<String> = WordGenerator::TITLE_PAGE_VARIABLES
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    shown.extend(visible.iter().flat_map(|section| section_inputs(section)));
+    let mut redacted_variables: BTreeSet<String> = hidden
+        .iter()
+        .flat_map(|section| section_inputs(section))
+        .filter(|name| !shown.contains(name))
+        .collect();
+    redacted_variables.extend(
+        variables
+            .iter()
+            .filter(|v| hidden_ids.contains(v.section.as_str()))
+            .map(|v| v.variable_name.clone()),
+    );
+
+    RedactedInputs {
+        audience,
+        sections: sections
+            .iter()
+            .filter(|s| !hidden_ids.contains(s.section_id.as_str()))
+            .cloned()
+            .collect(),
+        variables: variables
+            .iter()
+            .filter(|v| !redacted_variables.contains(&v.variable_name))
+            .cloned()
+            .collect(),
+        redacted_sections: hidden_ids.iter().map(|id| id.to_string()).collect(),
+        redacted_variables: redacted_variables.into_iter().collect(),
+    }
+}
+
+/// The version record as the audience may see it: its variant's artifact
+/// and snapshots without the redacted sections and values. `None` when the
+/// version has no variant for the audience, e.g. versions generated before
+/// variants existed.
+pub fn redact_record(mut document: GeneratedDocument, audience: DocumentAudience) -> Option<GeneratedDocument> {
+    if audience == DocumentAudience::Full {
+        return Some(document);
+    }
+    let variant = document
+        .variants
+        .iter()
+        .find(|variant| variant.audience == audience)
+        .cloned()?;
+
+    document.file_path = variant.file_path;
+    document.file_size_bytes = variant.file_size_bytes;
+    document
+        .section_snapshot
+        .retain(|section| !variant.redacted_sections.contains(&section.section_id));
+    for section in document.section_snapshot.iter_mut() {
+        section.variables.retain(|name| !variant.redacted_variables.contains(name));
+    }
+    document
+        .variables_snapshot
+        .retain(|name, _| !variant.redacted_variables.contains(name));
+    if let Some(sections) = document.generation_parameters.get_mut("sections").and_then(|s| s.as_array_mut()) {
+        sections.retain(|id| !id.as_str().is_some_and(|id| variant.redacted_sections.iter().any(|r| r == id)));
+    }
+    document
+        .generation_parameters
+        .insert("audience".to_string(), json!(audience));
+    // The signed-off copy is the full document
+    document.published_file_path = None;
+    document.variants.clear();
+    Some(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::hld::{SectionSensitivity, VariableType, VariableValue};
+    use crate::models::project_models::{DocumentFormat, DocumentGenerationStatus, DocumentType, DocumentVariant};
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use surrealdb::sql::Thing;
+
+    fn section(section_id: &str, description: &str, sensitivity: &[SectionSensitivity]) -> SectionDefinition {
+        SectionDefinition {
+            id: None,
+            section_id: section_id.to_string(),
+            section_name: section_id.to_string(),
+            display_name: section_id.to_string(),
+            description: description.to_string(),
+            required: false,
+            enabled: true,
+            order_index: 0,
+            depends_on: Vec::new(),
+            sensitivity: sensitivity.to_vec(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn variable(name: &str, section: &str) -> HLDVariable {
+        HLDVariable::new(
+            Thing::from(("hld_projects", "p1")),
+            name.to_string(),
+            Some(VariableValue::String("x".to_string())),
+            VariableType::String,
+            section.to_string(),
+        )
+    }
+
+    fn inputs() -> (Vec<SectionDefinition>, Vec<HLDVariable>) {
+        let sections = vec![
+            section("executive_summary", "Budget owner: {{budget_owner}}", &[]),
+            section(
+                "commercials",
+                "{{unit_price}} per node for {{project_name}}, approved by {{budget_owner}}",
+                &[SectionSensitivity::Pricing],
+            ),
+            section("access", "Break-glass account {{admin_account}}", &[SectionSensitivity::Credentials]),
+        ];
+        let variables = vec![
+            variable("project_name", "executive_summary"),
+            variable("budget_owner", "executive_summary"),
+            variable("unit_price", "commercials"),
+            variable("support_contract", "commercials"),
+            variable("admin_account", "access"),
+        ];
+        (sections, variables)
+    }
+
+    #[test]
+    fn test_full_audience_sees_everything() {
+        let (sections, variables) = inputs();
+        let full = redact(DocumentAudience::Full, &sections, &variables);
+        assert_eq!(full.sections.len(), 3);
+        assert_eq!(full.variables.len(), 5);
+        assert!(full.redacted_sections.is_empty());
+    }
+
+    #[test]
+    fn test_redaction_keeps_variables_that_visible_content_reads() {
+        let (sections, variables) = inputs();
+
+        let customer = redact(DocumentAudience::Customer, &sections, &variables);
+        assert_eq!(customer.redacted_sections, vec!["access"]);
+        assert_eq!(customer.redacted_variables, vec!["admin_account"]);
+
+        let vendor = redact(DocumentAudience::Vendor, &sections, &variables);
+        assert_eq!(vendor.redacted_sections, vec!["access", "commercials"]);
+        // Read by the title page and the executive summary, so they stay
+        assert_eq!(vendor.redacted_variables, vec!["admin_account", "support_contract", "unit_price"]);
+        let kept: Vec<&str> = vendor.variables.iter().map(|v| v.variable_name.as_str()).collect();
+        assert_eq!(kept, vec!["project_name", "budget_owner"]);
+    }
+
+    #[test]
+    fn test_disabled_sections_are_not_redacted() {
+        let (mut sections, variables) = inputs();
+        sections[2].enabled = false;
+        let customer = redact(DocumentAudience::Customer, &sections, &variables);
+        assert!(customer.redacted_sections.is_empty());
+        assert!(customer.redacted_variables.is_empty());
+    }
+
+    #[test]
+    fn test_redact_record_serves_the_variant() {
+        let document = GeneratedDocument {
+            id: None,
+            project_id: Thing::from(("projects", "p1")),
+            activity_id: Thing::from(("activity", "hld_export")),
+            template_id: Thing::from(("hld_templates", "t1")),
+            document_name: "HLD".to_string(),
+            document_type: DocumentType::Hld,
+            file_path: "generated/hld/p1/v1-full.docx".to_string(),
+            file_size_bytes: 100,
+            file_format: DocumentFormat::Docx,
+            variables_snapshot: HashMap::from([
+                ("project_name".to_string(), json!("Alpha")),
+                ("unit_price".to_string(), json!(1200)),
+            ]),
+            data_sources: Vec::new(),
+            generation_status: DocumentGenerationStatus::Completed,
+            error_message: None,
+            metadata: HashMap::new(),
+            generated_at: Utc::now(),
+            generated_by: "alice".to_string(),
+            expires_at: None,
+            version: 1,
+            previous_version: None,
+            generation_parameters: HashMap::from([(
+                "sections".to_string(),
+                json!(["executive_summary", "commercials"]),
+            )]),
+            section_snapshot: Vec::new(),
+            storage_backend: Default::default(),
+            locked: true,
+            approval_record: None,
+            published_file_path: Some("generated/hld/p1/v1-published.docx".to_string()),
+            variants: vec![DocumentVariant {
+                audience: DocumentAudience::Vendor,
+                file_path: "generated/hld/p1/v1-vendor.docx".to_string(),
+                file_size_bytes: 80,
+                redacted_sections: vec!["commercials".to_string()],
+                redacted_variables: vec!["unit_price".to_string()],
+            }],
+        };
+
+        assert!(redact_record(document.clone(), DocumentAudience::Customer).is_none());
+        let full = redact_record(document.clone(), DocumentAudience::Full).unwrap();
+        assert_eq!(full.variants.len(), 1);
+
+        let vendor = redact_record(document, DocumentAudience::Vendor).unwrap();
+        assert_eq!(vendor.file_path, "generated/hld/p1/v1-vendor.docx");
+        assert_eq!(vendor.file_size_bytes, 80);
+        assert!(!vendor.variables_snapshot.contains_key("unit_price"));
+        assert_eq!(vendor.generation_parameters["sections"], json!(["executive_summary"]));
+        assert!(vendor.published_file_path.is_none());
+        assert!(vendor.variants.is_empty());
+    }
+}
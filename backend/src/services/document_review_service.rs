@@ -0,0 +1,758 @@
+// Archer - Document Review Service
+// Customer sign-off of a generated HLD version. Starting a review runs the
+// review workflow with the assigned reviewers as its approvers; the run's
+// outcome and their individual approvals drive the review status. Comments
+// are anchored to sections of the reviewed version. Publishing an approved
+// review locks the version and stores a copy with the approval page.
+
+use chrono::Utc;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::document_review::*;
+use crate::models::hld::{HLDProject, HLDVariable, SectionDefinition};
+use crate::models::project_models::{DocumentType, GeneratedDocument};
+use crate::models::workflow_engine::{Approval, ApprovalStatus, WorkflowInstanceStatus};
+use crate::services::document_storage::{self, ByteStream, StorageError};
+use crate::services::workflow_engine_service::WorkflowEngineService;
+use crate::services::word_generator::WordGenerator;
+use crate::database::TracedQuery;
+
+const TABLE: &str = "document_review";
+const COMMENT_TABLE: &str = "document_review_comment";
+const DOCX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+#[derive(Debug, Error)]
+pub enum DocumentReviewError {
+    #[error("Review not found")]
+    NotFound,
+
+    #[error("Document version {0} not found")]
+    DocumentNotFound(u32),
+
+    #[error("Comment not found")]
+    CommentNotFound,
+
+    #[error("Invalid state: {0}")]
+    InvalidState(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Workflow error: {0}")]
+    Workflow(String),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("Generation failed: {0}")]
+    Generation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for DocumentReviewError {
+    fn from(e: surrealdb::Error) -> Self {
+        DocumentReviewError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<StorageError> for DocumentReviewError {
+    fn from(e: StorageError) -> Self {
+        DocumentReviewError::Storage(e.to_string())
+    }
+}
+
+pub struct DocumentReviewService {
+    db: Arc<Database>,
+}
+
+impl DocumentReviewService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ========================================================================
+    // REVIEWS
+    // ========================================================================
+
+    pub async fn list_reviews(&self, query: &ReviewListQuery) -> Result<Vec<DocumentReview>, DocumentReviewError> {
+        let reviews: Vec<DocumentReview> = self
+            .db
+            .query("SELECT * FROM document_review ORDER BY created_at DESC")
+            .traced("document_review_service.list_reviews")
+            .await?
+            .take(0)?;
+
+        let project = query.project_id.as_deref().map(|p| parse_thing("projects", p));
+        let mut matching = Vec::new();
+        for review in reviews {
+            if project.as_ref().map_or(true, |p| &review.project_id == p) {
+                let review = self.sync_review(review).await?;
+                if query.status.map_or(true, |s| review.status == s) {
+                    matching.push(review);
+                }
+            }
+        }
+        Ok(matching)
+    }
+
+    pub async fn get_review(&self, id: &str) -> Result<DocumentReviewDetail, DocumentReviewError> {
+        let review = self.sync_review(self.load(id).await?).await?;
+        let comments = self.list_comments(id).await?;
+        let open_comments = comments.iter().filter(|c| c.resolved_at.is_none()).count();
+        Ok(DocumentReviewDetail {
+            review,
+            comments,
+            open_comments,
+        })
+    }
+
+    /// Send an HLD version out for review. Its reviewers become the approvers
+    /// of the review workflow's approval step(s), which read them from the
+    /// `reviewers` context key (`approvers_from_context: "reviewers"`).
+    pub async fn start_review(
+        &self,
+        user: &AuthenticatedUser,
+        request: StartReviewRequest,
+    ) -> Result<DocumentReview, DocumentReviewError> {
+        let reviewers = parse_reviewers(&request.reviewers)?;
+        let project_id = parse_thing("projects", &request.project_id);
+        let document = self.load_version(&project_id, request.document_version).await?;
+        if document.locked {
+            return Err(DocumentReviewError::InvalidState(format!(
+                "Version {} is already published",
+                document.version
+            )));
+        }
+        let document_id = document
+            .id
+            .clone()
+            .ok_or(DocumentReviewError::DocumentNotFound(request.document_version))?;
+
+        let existing = self
+            .list_reviews(&ReviewListQuery {
+                project_id: Some(request.project_id.clone()),
+                status: None,
+            })
+            .await?;
+        if existing.iter().any(|r| r.document_id == document_id && r.status.is_open()) {
+            return Err(DocumentReviewError::InvalidState(format!(
+                "Version {} already has an open review",
+                document.version
+            )));
+        }
+
+        let workflow_id = request
+            .workflow_id
+            .or_else(|| std::env::var("DOCUMENT_REVIEW_WORKFLOW").ok())
+            .filter(|w| !w.trim().is_empty())
+            .ok_or_else(|| {
+                DocumentReviewError::Validation(
+                    "No review workflow given and DOCUMENT_REVIEW_WORKFLOW is not set".to_string(),
+                )
+            })?;
+
+        let record = Thing::from((TABLE, Uuid::new_v4().to_string().as_str()));
+        let context = json!({
+            "reviewers": reviewers,
+            "document_review": {
+                "document_name": document.document_name,
+                "document_version": document.version,
+                "project_id": project_id.to_string(),
+                "requested_by": user.username,
+                "message": request.message,
+            }
+        });
+        let instance = WorkflowEngineService::new(Arc::clone(&self.db))
+            .trigger_workflow(
+                parse_thing("workflow_definition", &workflow_id),
+                TABLE.to_string(),
+                record.clone(),
+                Some(context),
+            )
+            .await
+            .map_err(DocumentReviewError::Workflow)?;
+
+        let now = Utc::now();
+        let review = DocumentReview {
+            id: None,
+            document_id,
+            project_id,
+            document_version: document.version,
+            status: ReviewStatus::InReview,
+            reviewers: reviewers
+                .into_iter()
+                .map(|user_id| ReviewerAssignment {
+                    user_id,
+                    decision: ReviewerDecision::Pending,
+                    decided_at: None,
+                    comments: None,
+                    delegated_to: None,
+                })
+                .collect(),
+            workflow_instance_id: instance.id,
+            requested_by: user.username.clone(),
+            message: request.message,
+            approval_record: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let created: Option<DocumentReview> = self.db.create(record).content(review).await?;
+        let created = created.ok_or_else(|| DocumentReviewError::DatabaseError("Failed to save review".to_string()))?;
+        // The workflow may already have decided, e.g. an empty approval step
+        self.sync_review(created).await
+    }
+
+    pub async fn cancel_review(&self, id: &str) -> Result<DocumentReview, DocumentReviewError> {
+        let mut review = self.sync_review(self.load(id).await?).await?;
+        if matches!(review.status, ReviewStatus::Published | ReviewStatus::Cancelled) {
+            return Err(DocumentReviewError::InvalidState(format!(
+                "A {:?} review cannot be cancelled",
+                review.status
+            )));
+        }
+
+        if review.status == ReviewStatus::InReview {
+            if let Some(instance) = &review.workflow_instance_id {
+                // The workflow may have finished in the meantime; that's fine
+                let _ = WorkflowEngineService::new(Arc::clone(&self.db))
+                    .cancel_workflow_instance(instance.clone())
+                    .await;
+            }
+        }
+        review.status = ReviewStatus::Cancelled;
+        self.save(id, review).await
+    }
+
+    /// Lock an approved review's version and store a copy of the document
+    /// whose approval page carries the sign-off
+    pub async fn publish_review(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<DocumentReview, DocumentReviewError> {
+        let mut review = self.sync_review(self.load(id).await?).await?;
+        if review.status != ReviewStatus::Approved {
+            return Err(DocumentReviewError::InvalidState(format!(
+                "Only approved reviews can be published; this one is {:?}",
+                review.status
+            )));
+        }
+        let document = self.load_document(&review).await?;
+        if document.locked {
+            return Err(DocumentReviewError::InvalidState(format!(
+                "Version {} is already published",
+                document.version
+            )));
+        }
+
+        let store = document_storage::store_for(document.storage_backend)?;
+        let source_key = document_storage::document_key(&document.file_path);
+        let stream = store
+            .get_stream(&source_key)
+            .await?
+            .ok_or_else(|| DocumentReviewError::Storage(format!("The file of version {} is missing", document.version)))?;
+        let reviewed_sha256 = sha256_of(stream).await?;
+
+        let now = Utc::now();
+        let approved_at = review
+            .reviewers
+            .iter()
+            .filter_map(|r| r.decided_at)
+            .max()
+            .unwrap_or(now);
+        let approval = DocumentApprovalRecord {
+            document_name: document.document_name.clone(),
+            document_version: document.version,
+            reviewed_sha256,
+            approvals: review.reviewers.clone(),
+            approved_at,
+            workflow_instance_id: review.workflow_instance_id.as_ref().map(|i| i.to_string()),
+            published_by: user.username.clone(),
+            published_at: now,
+        };
+
+        let bytes = self.render_published(&document, &approval).await?;
+        let key = published_key(&source_key);
+        store.put(&key, &bytes, DOCX_CONTENT_TYPE).await?;
+
+        let document_id = document.id.clone().ok_or(DocumentReviewError::DocumentNotFound(document.version))?;
+        let locked: Vec<GeneratedDocument> = self
+            .db
+            .query(
+                "UPDATE $id SET locked = true, approval_record = $approval, published_file_path = $key \
+                 WHERE locked != true RETURN AFTER",
+            )
+            .bind(("id", document_id))
+            .bind(("approval", approval.clone()))
+            .bind(("key", key.clone()))
+            .traced("document_review_service.publish_review")
+            .await?
+            .take(0)?;
+        if locked.is_empty() {
+            // Another review published the version first
+            let _ = store.delete(&key).await;
+            return Err(DocumentReviewError::InvalidState(format!(
+                "Version {} is already published",
+                document.version
+            )));
+        }
+
+        review.status = ReviewStatus::Published;
+        review.approval_record = Some(approval);
+        self.save(id, review).await
+    }
+
+    /// The published copy of a review's document
+    pub async fn open_published(&self, id: &str) -> Result<(GeneratedDocument, ByteStream), DocumentReviewError> {
+        let review = self.load(id).await?;
+        if review.status != ReviewStatus::Published {
+            return Err(DocumentReviewError::InvalidState("The review has not been published".to_string()));
+        }
+        let document = self.load_document(&review).await?;
+        let key = document
+            .published_file_path
+            .clone()
+            .ok_or_else(|| DocumentReviewError::Storage("The published file is not recorded".to_string()))?;
+        let stream = document_storage::store_for(document.storage_backend)?
+            .get_stream(&key)
+            .await?
+            .ok_or_else(|| DocumentReviewError::Storage("The published file is missing from storage".to_string()))?;
+        Ok((document, stream))
+    }
+
+    /// Pick up the outcome of the review workflow: each reviewer's approval
+    /// sets their decision, a completed run approves the review and a failed
+    /// or cancelled one (a rejected approval fails the run) requests changes
+    async fn sync_review(&self, review: DocumentReview) -> Result<DocumentReview, DocumentReviewError> {
+        if review.status != ReviewStatus::InReview {
+            return Ok(review);
+        }
+        let Some(instance_id) = review.workflow_instance_id.clone() else {
+            return Ok(review);
+        };
+
+        let instance = WorkflowEngineService::new(Arc::clone(&self.db))
+            .get_workflow_instance(instance_id.clone())
+            .await
+            .map_err(DocumentReviewError::Workflow)?;
+        let approvals: Vec<Approval> = self
+            .db
+            .query("SELECT * FROM approval WHERE workflow_instance_id = $instance ORDER BY requested_at")
+            .bind(("instance", instance_id))
+            .traced("document_review_service.sync_review")
+            .await?
+            .take(0)?;
+
+        let reviewers = reviewer_decisions(&review.reviewers, &approvals);
+        let status = review_status(instance.map(|i| i.status));
+        let decisions_changed = reviewers
+            .iter()
+            .zip(&review.reviewers)
+            .any(|(new, old)| new.decision != old.decision || new.delegated_to != old.delegated_to);
+        if status == ReviewStatus::InReview && !decisions_changed {
+            return Ok(review);
+        }
+
+        let id = review.id.clone().ok_or(DocumentReviewError::NotFound)?;
+        let updated: Vec<DocumentReview> = self
+            .db
+            .query(
+                "UPDATE $id SET status = $status, reviewers = $reviewers, updated_at = time::now() \
+                 WHERE status = 'IN_REVIEW' RETURN AFTER",
+            )
+            .bind(("id", id))
+            .bind(("status", status))
+            .bind(("reviewers", reviewers))
+            .traced("document_review_service.sync_review")
+            .await?
+            .take(0)?;
+        Ok(updated.into_iter().next().unwrap_or(review))
+    }
+
+    // ========================================================================
+    // COMMENTS
+    // ========================================================================
+
+    pub async fn list_comments(&self, review_id: &str) -> Result<Vec<ReviewComment>, DocumentReviewError> {
+        let comments: Vec<ReviewComment> = self
+            .db
+            .query("SELECT * FROM document_review_comment WHERE review_id = $review ORDER BY created_at")
+            .bind(("review", parse_thing(TABLE, review_id)))
+            .traced("document_review_service.list_comments")
+            .await?
+            .take(0)?;
+        Ok(comments)
+    }
+
+    /// Comment on a section of the reviewed version
+    pub async fn add_comment(
+        &self,
+        user: &AuthenticatedUser,
+        review_id: &str,
+        request: AddReviewCommentRequest,
+    ) -> Result<ReviewComment, DocumentReviewError> {
+        let review = self.sync_review(self.load(review_id).await?).await?;
+        if matches!(review.status, ReviewStatus::Published | ReviewStatus::Cancelled) {
+            return Err(DocumentReviewError::InvalidState(format!(
+                "A {:?} review takes no more comments",
+                review.status
+            )));
+        }
+        if request.body.trim().is_empty() {
+            return Err(DocumentReviewError::Validation("Comment body is required".to_string()));
+        }
+
+        let document = self.load_document(&review).await?;
+        if !document.section_snapshot.iter().any(|s| s.section_id == request.section_id) {
+            return Err(DocumentReviewError::Validation(format!(
+                "Version {} has no section '{}'",
+                document.version, request.section_id
+            )));
+        }
+
+        let review_thing = parse_thing(TABLE, review_id);
+        let reply_to = match request.reply_to.as_deref() {
+            Some(parent) => {
+                let parent = self.load_comment(&review_thing, parent).await?;
+                if parent.section_id != request.section_id {
+                    return Err(DocumentReviewError::Validation(
+                        "A reply must be on the same section as its comment".to_string(),
+                    ));
+                }
+                parent.id
+            }
+            None => None,
+        };
+
+        let comment = ReviewComment {
+            id: None,
+            review_id: review_thing,
+            section_id: request.section_id,
+            quote: request.quote.filter(|q| !q.trim().is_empty()),
+            body: request.body.trim().to_string(),
+            author: user.username.clone(),
+            reply_to,
+            resolved_by: None,
+            resolved_at: None,
+            created_at: Utc::now(),
+        };
+        let created: Vec<ReviewComment> = self.db.create(COMMENT_TABLE).content(comment).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| DocumentReviewError::DatabaseError("Failed to save comment".to_string()))
+    }
+
+    pub async fn resolve_comment(
+        &self,
+        user: &AuthenticatedUser,
+        review_id: &str,
+        comment_id: &str,
+    ) -> Result<ReviewComment, DocumentReviewError> {
+        let mut comment = self.load_comment(&parse_thing(TABLE, review_id), comment_id).await?;
+        if comment.resolved_at.is_some() {
+            return Ok(comment);
+        }
+        comment.resolved_by = Some(user.username.clone());
+        comment.resolved_at = Some(Utc::now());
+        let updated: Option<ReviewComment> = self
+            .db
+            .update(parse_thing(COMMENT_TABLE, comment_id))
+            .content(comment)
+            .await?;
+        updated.ok_or(DocumentReviewError::CommentNotFound)
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn load(&self, id: &str) -> Result<DocumentReview, DocumentReviewError> {
+        let review: Option<DocumentReview> = self.db.select(parse_thing(TABLE, id)).await?;
+        review.ok_or(DocumentReviewError::NotFound)
+    }
+
+    async fn save(&self, id: &str, mut review: DocumentReview) -> Result<DocumentReview, DocumentReviewError> {
+        review.updated_at = Utc::now();
+        let updated: Option<DocumentReview> = self.db.update(parse_thing(TABLE, id)).content(review).await?;
+        updated.ok_or(DocumentReviewError::NotFound)
+    }
+
+    async fn load_comment(&self, review: &Thing, id: &str) -> Result<ReviewComment, DocumentReviewError> {
+        let comment: Option<ReviewComment> = self.db.select(parse_thing(COMMENT_TABLE, id)).await?;
+        comment
+            .filter(|c| &c.review_id == review)
+            .ok_or(DocumentReviewError::CommentNotFound)
+    }
+
+    async fn load_version(&self, project_id: &Thing, version: u32) -> Result<GeneratedDocument, DocumentReviewError> {
+        let versions: Vec<GeneratedDocument> = self
+            .db
+            .query(
+                "SELECT * FROM generated_document WHERE project_id = $project_id \
+                 AND document_type = $document_type AND version = $version LIMIT 1",
+            )
+            .bind(("project_id", project_id.clone()))
+            .bind(("document_type", DocumentType::Hld))
+            .bind(("version", version))
+            .traced("document_review_service.load_version")
+            .await?
+            .take(0)?;
+        versions
+            .into_iter()
+            .next()
+            .ok_or(DocumentReviewError::DocumentNotFound(version))
+    }
+
+    async fn load_document(&self, review: &DocumentReview) -> Result<GeneratedDocument, DocumentReviewError> {
+        let document: Option<GeneratedDocument> = self.db.select(review.document_id.clone()).await?;
+        document.ok_or(DocumentReviewError::DocumentNotFound(review.document_version))
+    }
+
+    /// Rebuild the reviewed version from its snapshots, with the approval page
+    async fn render_published(
+        &self,
+        document: &GeneratedDocument,
+        approval: &DocumentApprovalRecord,
+    ) -> Result<Vec<u8>, DocumentReviewError> {
+        let projects: Vec<HLDProject> = self
+            .db
+            .query("SELECT * FROM hld_projects WHERE project_id = $project_id LIMIT 1")
+            .bind(("project_id", document.project_id.clone()))
+            .traced("document_review_service.render_published")
+            .await?
+            .take(0)?;
+        let project = projects
+            .into_iter()
+            .next()
+            .ok_or_else(|| DocumentReviewError::Generation("HLD project not found".to_string()))?;
+
+        let variables = snapshot_variables(&project, &document.variables_snapshot);
+        let sections = snapshot_section_definitions(document);
+        WordGenerator::new()
+            .generate_hld_with_approval(&project, &variables, &sections, Some(approval))
+            .map_err(|e| DocumentReviewError::Generation(e.to_string()))
+    }
+}
+
+/// Review status from the review workflow's run
+fn review_status(instance: Option<WorkflowInstanceStatus>) -> ReviewStatus {
+    match instance {
+        Some(WorkflowInstanceStatus::Completed) => ReviewStatus::Approved,
+        Some(WorkflowInstanceStatus::Failed) | Some(WorkflowInstanceStatus::Cancelled) | None => {
+            ReviewStatus::ChangesRequested
+        }
+        Some(_) => ReviewStatus::InReview,
+    }
+}
+
+/// Each reviewer's decision from the latest approval addressed to them,
+/// directly or through a delegation or escalation
+fn reviewer_decisions(reviewers: &[ReviewerAssignment], approvals: &[Approval]) -> Vec<ReviewerAssignment> {
+    reviewers
+        .iter()
+        .map(|reviewer| {
+            let is_reviewer = |t: &Option<Thing>| t.as_ref().map_or(false, |t| t.to_string() == reviewer.user_id);
+            let Some(approval) = approvals.iter().rev().find(|a| {
+                a.approver_id.to_string() == reviewer.user_id
+                    || is_reviewer(&a.delegated_from)
+                    || is_reviewer(&a.escalated_from)
+            }) else {
+                return reviewer.clone();
+            };
+            let decision = match approval.status {
+                ApprovalStatus::Approved => ReviewerDecision::Approved,
+                ApprovalStatus::Rejected => ReviewerDecision::ChangesRequested,
+                ApprovalStatus::Cancelled => ReviewerDecision::NotRequired,
+                ApprovalStatus::Pending | ApprovalStatus::Delegated => ReviewerDecision::Pending,
+            };
+            let approver = approval.approver_id.to_string();
+            ReviewerAssignment {
+                user_id: reviewer.user_id.clone(),
+                decision,
+                decided_at: approval.responded_at,
+                comments: approval.comments.clone(),
+                delegated_to: (approver != reviewer.user_id).then_some(approver),
+            }
+        })
+        .collect()
+}
+
+/// Reviewer ids as `users:...`, without duplicates
+fn parse_reviewers(ids: &[String]) -> Result<Vec<String>, DocumentReviewError> {
+    let mut reviewers: Vec<String> = Vec::new();
+    for id in ids.iter().map(|id| id.trim()).filter(|id| !id.is_empty()) {
+        let user = parse_thing("users", id);
+        if user.tb != "users" {
+            return Err(DocumentReviewError::Validation(format!("'{}' is not a user", id)));
+        }
+        let user = user.to_string();
+        if !reviewers.contains(&user) {
+            reviewers.push(user);
+        }
+    }
+    if reviewers.is_empty() {
+        return Err(DocumentReviewError::Validation("A review needs at least one reviewer".to_string()));
+    }
+    Ok(reviewers)
+}
+
+/// Key of the published copy, next to the reviewed artifact
+fn published_key(key: &str) -> String {
+    match key.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.contains('/') => format!("{}-published.{}", stem, ext),
+        _ => format!("{}-published", key),
+    }
+}
+
+fn snapshot_variables(project: &HLDProject, snapshot: &HashMap<String, serde_json::Value>) -> Vec<HLDVariable> {
+    let hld_project_id = project.id.clone().unwrap_or_else(|| project.project_id.clone());
+    snapshot
+        .iter()
+        .filter_map(|(name, value)| {
+            Some(HLDVariable {
+                id: None,
+                hld_project_id: hld_project_id.clone(),
+                variable_name: name.clone(),
+                variable_value: Some(serde_json::from_value(value.clone()).ok()?),
+                variable_type: String::new(),
+                section: String::new(),
+                source: "snapshot".to_string(),
+                confidence: None,
+                error_message: None,
+                updated_at: None,
+            })
+        })
+        .collect()
+}
+
+fn snapshot_section_definitions(document: &GeneratedDocument) -> Vec<SectionDefinition> {
+    let mut sections: Vec<SectionDefinition> = document
+        .section_snapshot
+        .iter()
+        .map(|s| SectionDefinition {
+            id: None,
+            section_id: s.section_id.clone(),
+            section_name: s.section_id.clone(),
+            display_name: s.display_name.clone(),
+            description: String::new(),
+            required: false,
+            enabled: true,
+            order_index: s.order_index,
+            depends_on: Vec::new(),
+            sensitivity: Vec::new(),
+            created_at: document.generated_at,
+        })
+        .collect();
+    sections.sort_by_key(|s| s.order_index);
+    sections
+}
+
+async fn sha256_of(mut stream: ByteStream) -> Result<String, DocumentReviewError> {
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = stream.next().await {
+        hasher.update(chunk.map_err(|e| DocumentReviewError::Storage(e.to_string()))?);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workflow_engine::ApproverType;
+
+    fn reviewer(user_id: &str) -> ReviewerAssignment {
+        ReviewerAssignment {
+            user_id: user_id.to_string(),
+            decision: ReviewerDecision::Pending,
+            decided_at: None,
+            comments: None,
+            delegated_to: None,
+        }
+    }
+
+    fn approval(approver: &str, status: ApprovalStatus, delegated_from: Option<&str>) -> Approval {
+        Approval {
+            id: None,
+            workflow_instance_id: Thing::from(("workflow_instance", "i1")),
+            step_id: "review".to_string(),
+            approver_id: parse_thing("users", approver),
+            approver_type: ApproverType::User,
+            responded_at: (status != ApprovalStatus::Pending).then(Utc::now),
+            status,
+            requested_at: Utc::now(),
+            comments: None,
+            request_id: Some("r1".to_string()),
+            quorum: Some(3),
+            group_id: None,
+            delegated_from: delegated_from.map(|d| parse_thing("users", d)),
+            escalated_from: None,
+            responded_by: None,
+            remind_at: None,
+            reminder_interval_minutes: None,
+            reminders_sent: 0,
+            escalate_at: None,
+            escalate_to: None,
+        }
+    }
+
+    #[test]
+    fn test_review_status() {
+        assert_eq!(review_status(Some(WorkflowInstanceStatus::Completed)), ReviewStatus::Approved);
+        assert_eq!(review_status(Some(WorkflowInstanceStatus::Failed)), ReviewStatus::ChangesRequested);
+        assert_eq!(review_status(None), ReviewStatus::ChangesRequested);
+        assert_eq!(review_status(Some(WorkflowInstanceStatus::WaitingApproval)), ReviewStatus::InReview);
+    }
+
+    #[test]
+    fn test_reviewer_decisions() {
+        let reviewers = vec![reviewer("users:alice"), reviewer("users:bob"), reviewer("users:carol")];
+        let approvals = vec![
+            approval("users:alice", ApprovalStatus::Approved, None),
+            approval("users:dave", ApprovalStatus::Rejected, Some("users:bob")),
+        ];
+
+        let decisions = reviewer_decisions(&reviewers, &approvals);
+        assert_eq!(decisions[0].decision, ReviewerDecision::Approved);
+        assert!(decisions[0].decided_at.is_some());
+        assert_eq!(decisions[1].decision, ReviewerDecision::ChangesRequested);
+        assert_eq!(decisions[1].delegated_to.as_deref(), Some("users:dave"));
+        assert_eq!(decisions[2].decision, ReviewerDecision::Pending);
+    }
+
+    #[test]
+    fn test_parse_reviewers() {
+        assert_eq!(
+            parse_reviewers(&["alice".to_string(), "users:alice".to_string(), "users:bob".to_string()]).unwrap(),
+            vec!["users:alice".to_string(), "users:bob".to_string()]
+        );
+        assert!(parse_reviewers(&[" ".to_string()]).is_err());
+        assert!(parse_reviewers(&["team:ops".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_published_key() {
+        assert_eq!(published_key("generated/hld/p1/v2-abc.docx"), "generated/hld/p1/v2-abc-published.docx");
+        assert_eq!(published_key("generated/hld/p1/v2"), "generated/hld/p1/v2-published");
+        assert_eq!(published_key("generated.d/v2"), "generated.d/v2-published");
+    }
+}
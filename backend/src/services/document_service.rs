@@ -5,10 +5,20 @@ use docx_rs::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use surrealdb::sql::Thing;
+use tokio::io::AsyncRead;
 use uuid::Uuid;
 
+use crate::services::document_storage::{self, ByteStream, PresignedUrl};
+use crate::models::usage_telemetry::UsageFeature;
+use crate::models::webhook::WebhookEventType;
+use crate::services::usage_telemetry_service;
+use crate::services::webhook_service;
+
+const DOCX_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
 /// Document generation service for project workflow system
 pub struct DocumentService;
 
@@ -73,18 +83,21 @@ pub struct SubnetConfig {
 }
 
 impl DocumentService {
-    /// Initialize document storage directories
-    pub fn init_storage() -> anyhow::Result<()> {
+    /// Initialize document storage directories and check the configured
+    /// backend can be built. Templates are always read from local disk.
+    pub fn init_storage() -> anyhow::Result<StorageBackend> {
         let base_path = Self::get_documents_base_path();
-        fs::create_dir_all(&base_path)?;
         fs::create_dir_all(base_path.join("templates"))?;
-        fs::create_dir_all(base_path.join("generated"))?;
-        Ok(())
+        let store = document_storage::store_from_env()?;
+        if store.backend() == StorageBackend::Disk {
+            fs::create_dir_all(base_path.join("generated"))?;
+        }
+        Ok(store.backend())
     }
 
-    /// Get the base path for document storage
+    /// Get the base path for document storage (`DOCUMENT_STORAGE_DIR`)
     pub fn get_documents_base_path() -> PathBuf {
-        PathBuf::from("./documents")
+        document_storage::documents_dir()
     }
 
     /// Generate a document based on the request
@@ -93,6 +106,8 @@ impl DocumentService {
         project_id: &str,
         request: DocumentGenerationRequest,
     ) -> anyhow::Result<ProjectDocument> {
+        usage_telemetry_service::record(UsageFeature::DocumentGeneration);
+
         // Create unique filename
         let file_id = Uuid::new_v4().to_string();
         let file_name = format!(
@@ -101,8 +116,7 @@ impl DocumentService {
             file_id
         );
 
-        let documents_path = Self::get_documents_base_path().join("generated");
-        let file_path = documents_path.join(&file_name);
+        let key = format!("generated/{}", file_name);
 
         // Generate document content based on type
         let document_bytes = match request.document_type {
@@ -115,8 +129,8 @@ impl DocumentService {
             DocumentType::Custom => Self::generate_custom_document(&request).await?,
         };
 
-        // Write document to file
-        fs::write(&file_path, &document_bytes)?;
+        let store = document_storage::configured_store(app_state.as_ref()).await?;
+        store.put(&key, &document_bytes, DOCX_CONTENT_TYPE).await?;
 
         // Create document record
         let document = ProjectDocument {
@@ -125,7 +139,9 @@ impl DocumentService {
             workflow_id: request.workflow_id,
             document_type: request.document_type,
             document_name: request.document_name,
-            file_path: file_path.to_string_lossy().to_string(),
+            file_path: key,
+            storage_backend: store.backend(),
+            content_type: Some(DOCX_CONTENT_TYPE.to_string()),
             file_size: document_bytes.len() as u64,
             version: "1.0".to_string(),
             status: DocumentStatus::Draft,
@@ -150,6 +166,21 @@ impl DocumentService {
             .next()
             .ok_or_else(|| anyhow::anyhow!("No document created"))?;
 
+        webhook_service::emit_for_project(
+            app_state.as_ref(),
+            WebhookEventType::DocumentGenerated,
+            created_document.project_id.clone(),
+            serde_json::json!({
+                "document_id": created_document.id.as_ref().map(|id| id.to_string()),
+                "project_id": created_document.project_id.to_string(),
+                "document_type": created_document.document_type,
+                "document_name": created_document.document_name,
+                "file_size_bytes": created_document.file_size,
+                "generated_by": created_document.generated_by,
+                "generated_at": created_document.generated_at,
+            }),
+        );
+
         Ok(created_document)
     }
 
@@ -244,14 +275,102 @@ impl DocumentService {
         Ok(document)
     }
 
+    /// Store an uploaded document, streaming it to the configured backend
+    pub async fn store_document(
+        app_state: &AppState,
+        project_id: &str,
+        document_type: DocumentType,
+        document_name: &str,
+        content_type: &str,
+        reader: &mut (dyn AsyncRead + Unpin + Send),
+    ) -> anyhow::Result<ProjectDocument> {
+        let extension = document_name
+            .rsplit_once('.')
+            .map(|(_, ext)| format!(".{}", ext.to_ascii_lowercase()))
+            .unwrap_or_default();
+        let key = format!("uploaded/{}{}", Uuid::new_v4(), extension);
+
+        let store = document_storage::configured_store(app_state.as_ref()).await?;
+        let file_size = store.put_stream(&key, reader, content_type).await?;
+
+        let document = ProjectDocument {
+            id: None,
+            project_id: Thing::from(("project_workflow", project_id)),
+            workflow_id: None,
+            document_type,
+            document_name: document_name.to_string(),
+            file_path: key.clone(),
+            storage_backend: store.backend(),
+            content_type: Some(content_type.to_string()),
+            file_size,
+            version: "1.0".to_string(),
+            status: DocumentStatus::Draft,
+            generated_from_template: None,
+            generation_config: None,
+            generated_at: None,
+            generated_by: None,
+            approved_by: None,
+            approval_date: None,
+            metadata: HashMap::new(),
+            created_at: Utc::now(),
+        };
+
+        let created: Option<ProjectDocument> = match app_state
+            .as_ref()
+            .create("project_document")
+            .content(&document)
+            .await
+        {
+            Ok(created) => created.into_iter().next(),
+            Err(e) => {
+                let _ = store.delete(&key).await;
+                return Err(anyhow::anyhow!("Failed to create document record: {}", e));
+            }
+        };
+        created.ok_or_else(|| anyhow::anyhow!("No document created"))
+    }
+
+    /// Document contents, streamed from the backend the document is kept on
+    pub async fn open_document(
+        app_state: &AppState,
+        document_id: &str,
+    ) -> anyhow::Result<Option<(ProjectDocument, ByteStream)>> {
+        let Some(document) = Self::get_document(app_state, document_id).await? else {
+            return Ok(None);
+        };
+        let store = document_storage::store_for(document.storage_backend)?;
+        let stream = store
+            .get_stream(&document_storage::document_key(&document.file_path))
+            .await?;
+        Ok(stream.map(|stream| (document, stream)))
+    }
+
+    /// A time-limited URL the frontend can download the document from
+    pub async fn document_url(
+        app_state: &AppState,
+        document_id: &str,
+    ) -> anyhow::Result<Option<PresignedUrl>> {
+        let Some(document) = Self::get_document(app_state, document_id).await? else {
+            return Ok(None);
+        };
+        let store = document_storage::store_for(document.storage_backend)?;
+        let url = store
+            .presigned_url(
+                &document_storage::document_key(&document.file_path),
+                document_storage::url_ttl(),
+            )
+            .await?;
+        Ok(Some(url))
+    }
+
     /// Delete document
     pub async fn delete_document(app_state: &AppState, document_id: &str) -> anyhow::Result<()> {
         // Get document to get file path
         if let Some(document) = Self::get_document(app_state, document_id).await? {
-            // Delete file
-            if Path::new(&document.file_path).exists() {
-                fs::remove_file(&document.file_path)?;
-            }
+            let store = document_storage::store_for(document.storage_backend)?;
+            store
+                .delete(&document_storage::document_key(&document.file_path))
+                .await?;
         }
 
         // Delete from database
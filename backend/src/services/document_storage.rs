@@ -0,0 +1,710 @@
+// Archer ITSM - Document Storage
+// Where project documents are kept: a local directory or an S3-compatible
+// bucket, selected by DOCUMENT_STORAGE_BACKEND. Replicas sharing a bucket
+// see the same documents; the disk backend suits single-node installs.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hyper::body::Bytes;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, DuplexStream};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+
+use crate::database::Database;
+use crate::models::settings::DocumentStorageSettings;
+use crate::models::workflow::{ProjectDocument, StorageBackend};
+use crate::database::TracedQuery;
+
+/// Chunk size for streamed reads and writes
+const CHUNK_BYTES: usize = 64 * 1024;
+
+const DEFAULT_URL_TTL_SECS: u64 = 15 * 60;
+
+/// Backend chosen in the settings UI; takes precedence over the environment
+const SETTINGS_RECORD: (&str, &str) = ("document_storage_settings", "default");
+
+/// Audience of the tokens in disk-backend download URLs, so an auth token
+/// cannot be replayed as one
+const DOWNLOAD_TOKEN_AUDIENCE: &str = "archer-document-download";
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Invalid storage key '{0}'")]
+    InvalidKey(String),
+
+    #[error("Storage configuration error: {0}")]
+    Config(String),
+
+    #[error("Invalid or expired download link")]
+    InvalidToken,
+
+    #[error("Storage error: {0}")]
+    Backend(String),
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(e: std::io::Error) -> Self {
+        StorageError::Backend(e.to_string())
+    }
+}
+
+impl From<s3::error::S3Error> for StorageError {
+    fn from(e: s3::error::S3Error) -> Self {
+        StorageError::Backend(e.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, StorageError>;
+
+/// Document contents, read in chunks as the consumer polls
+pub type ByteStream = ReceiverStream<std::io::Result<Bytes>>;
+
+/// A URL the frontend can fetch a document from without an auth header
+#[derive(Debug, Clone, Serialize)]
+pub struct PresignedUrl {
+    pub url: String,
+    pub expires_in_secs: u64,
+}
+
+#[async_trait]
+pub trait DocumentStore: Send + Sync {
+    fn backend(&self) -> StorageBackend;
+
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<()>;
+
+    /// Store everything `reader` yields without buffering the whole document;
+    /// returns the number of bytes written
+    async fn put_stream(
+        &self,
+        key: &str,
+        reader: &mut (dyn AsyncRead + Unpin + Send),
+        content_type: &str,
+    ) -> Result<u64>;
+
+    async fn get_stream(&self, key: &str) -> Result<Option<ByteStream>>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    async fn exists(&self, key: &str) -> Result<bool>;
+
+    async fn presigned_url(&self, key: &str, expires_in: Duration) -> Result<PresignedUrl>;
+}
+
+/// Reject keys that could escape the storage root or bucket prefix
+fn validate_key(key: &str) -> Result<&Path> {
+    let relative = Path::new(key);
+    if key.is_empty()
+        || relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(StorageError::InvalidKey(key.to_string()));
+    }
+    Ok(relative)
+}
+
+/// Storage key of a document. Records from before storage backends hold a
+/// path such as `./documents/generated/hld-1.docx`; the key is the part
+/// below the documents directory.
+pub fn document_key(file_path: &str) -> String {
+    let root = documents_dir();
+    let path = Path::new(file_path);
+    let relative = path
+        .strip_prefix(&root)
+        .or_else(|_| path.strip_prefix(root.strip_prefix(".").unwrap_or(&root)))
+        .unwrap_or(path);
+    relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Feed a stream into the write half of a pipe, so it can be read as an
+/// `AsyncRead` by `put_stream`
+pub fn stream_reader(mut stream: ByteStream) -> DuplexStream {
+    let (mut writer, reader) = tokio::io::duplex(CHUNK_BYTES);
+    tokio::spawn(async move {
+        while let Some(chunk) = stream.next().await {
+            // A failed read or a dropped reader ends the copy; the reader
+            // then sees a short document and the size check catches it
+            let Ok(chunk) = chunk else { break };
+            if writer.write_all(&chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+    reader
+}
+
+// ============================================================================
+// DISK
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DownloadClaims {
+    sub: String,
+    aud: String,
+    exp: usize,
+}
+
+/// Stores documents under `DOCUMENT_STORAGE_DIR`. Download URLs point at
+/// the backend and carry a signed, expiring token.
+pub struct DiskDocumentStore {
+    root: PathBuf,
+    base_url: String,
+    secret: String,
+}
+
+impl DiskDocumentStore {
+    pub fn new(root: impl Into<PathBuf>, base_url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.into(),
+            secret: secret.into(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            documents_dir(),
+            std::env::var("BASE_URL").unwrap_or_else(|_| "http://localhost:3001".to_string()),
+            std::env::var("JWT_SECRET")
+                .unwrap_or_else(|_| "archer-dev-secret-change-in-production-32chars!".to_string()),
+        )
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf> {
+        Ok(self.root.join(validate_key(key)?))
+    }
+
+    /// The key a download token was issued for
+    pub fn verify_download_token(&self, token: &str) -> Result<String> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[DOWNLOAD_TOKEN_AUDIENCE]);
+        let data = jsonwebtoken::decode::<DownloadClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|_| StorageError::InvalidToken)?;
+        validate_key(&data.claims.sub)?;
+        Ok(data.claims.sub)
+    }
+}
+
+#[async_trait]
+impl DocumentStore for DiskDocumentStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Disk
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<()> {
+        let mut reader = bytes;
+        self.put_stream(key, &mut reader, content_type).await.map(|_| ())
+    }
+
+    async fn put_stream(
+        &self,
+        key: &str,
+        reader: &mut (dyn AsyncRead + Unpin + Send),
+        _content_type: &str,
+    ) -> Result<u64> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Write beside the target and rename, so readers never see half a
+        // file; the name is unique so concurrent writes can't share it
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let partial = path.with_file_name(format!("{}.{}.partial", file_name, uuid::Uuid::new_v4()));
+        let mut file = tokio::fs::File::create(&partial).await?;
+        let written = match tokio::io::copy(reader, &mut file).await {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(e.into());
+            }
+        };
+        file.sync_all().await?;
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(written)
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<Option<ByteStream>> {
+        let path = self.path_for(key)?;
+        let mut file = match tokio::fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let (sender, receiver) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; CHUNK_BYTES];
+            loop {
+                let chunk = match file.read(&mut buffer).await {
+                    Ok(0) => break,
+                    Ok(n) => Ok(Bytes::copy_from_slice(&buffer[..n])),
+                    Err(e) => Err(e),
+                };
+                let failed = chunk.is_err();
+                if sender.send(chunk).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Ok(Some(ReceiverStream::new(receiver)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path_for(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(tokio::fs::try_exists(self.path_for(key)?).await?)
+    }
+
+    async fn presigned_url(&self, key: &str, expires_in: Duration) -> Result<PresignedUrl> {
+        validate_key(key)?;
+        let claims = DownloadClaims {
+            sub: key.to_string(),
+            aud: DOWNLOAD_TOKEN_AUDIENCE.to_string(),
+            exp: (chrono::Utc::now().timestamp() as u64 + expires_in.as_secs()) as usize,
+        };
+        let token = jsonwebtoken::encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|e| StorageError::Backend(e.to_string()))?;
+        Ok(PresignedUrl {
+            url: format!(
+                "{}/api/v1/documents/blob?token={}",
+                self.base_url.trim_end_matches('/'),
+                token
+            ),
+            expires_in_secs: expires_in.as_secs(),
+        })
+    }
+}
+
+// ============================================================================
+// S3
+// ============================================================================
+
+/// Bucket configured from `<prefix>_BUCKET`, `_REGION`, `_ENDPOINT`,
+/// `_ACCESS_KEY`, `_SECRET_KEY` and `_PATH_STYLE`
+pub fn s3_bucket_from_env(prefix: &str) -> std::result::Result<Box<s3::Bucket>, String> {
+    let var = |name: &str| {
+        std::env::var(format!("{}_{}", prefix, name))
+            .ok()
+            .filter(|v| !v.is_empty())
+    };
+
+    let bucket_name = var("BUCKET").ok_or_else(|| format!("{}_BUCKET is not set", prefix))?;
+    let region_name = var("REGION").unwrap_or_else(|| "us-east-1".to_string());
+    let region = match var("ENDPOINT") {
+        Some(endpoint) => s3::Region::Custom {
+            region: region_name,
+            endpoint,
+        },
+        None => region_name.parse().map_err(|e| format!("{:?}", e))?,
+    };
+    let credentials = s3::creds::Credentials::new(
+        var("ACCESS_KEY").as_deref(),
+        var("SECRET_KEY").as_deref(),
+        None,
+        None,
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut bucket = s3::Bucket::new(&bucket_name, region, credentials).map_err(|e| e.to_string())?;
+    // MinIO and most self-hosted gateways only support path-style URLs
+    if var("PATH_STYLE").map_or(true, |v| v != "false") {
+        bucket = bucket.with_path_style();
+    }
+    Ok(bucket)
+}
+
+/// Stores documents in an S3-compatible bucket (AWS, MinIO, Ceph RGW, ...);
+/// download URLs are presigned by the bucket
+pub struct S3DocumentStore {
+    bucket: Box<s3::Bucket>,
+}
+
+impl S3DocumentStore {
+    /// Configured from `DOCUMENT_S3_*` environment variables
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            bucket: s3_bucket_from_env("DOCUMENT_S3").map_err(StorageError::Config)?,
+        })
+    }
+}
+
+#[async_trait]
+impl DocumentStore for S3DocumentStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::S3
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<()> {
+        validate_key(key)?;
+        let response = self
+            .bucket
+            .put_object_with_content_type(key, bytes, content_type)
+            .await?;
+        match response.status_code() {
+            200..=299 => Ok(()),
+            code => Err(StorageError::Backend(format!("S3 PUT {} returned {}", key, code))),
+        }
+    }
+
+    async fn put_stream(
+        &self,
+        key: &str,
+        reader: &mut (dyn AsyncRead + Unpin + Send),
+        content_type: &str,
+    ) -> Result<u64> {
+        validate_key(key)?;
+        // Sent as a multipart upload, one part at a time
+        let mut reader = reader;
+        let response = self
+            .bucket
+            .put_object_stream_with_content_type(&mut reader, key, content_type)
+            .await?;
+        match response.status_code() {
+            200..=299 => Ok(response.uploaded_bytes() as u64),
+            code => Err(StorageError::Backend(format!("S3 PUT {} returned {}", key, code))),
+        }
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<Option<ByteStream>> {
+        validate_key(key)?;
+        let mut response = match self.bucket.get_object_stream(key).await {
+            Ok(response) if response.status_code == 404 => return Ok(None),
+            Ok(response) if (200..300).contains(&response.status_code) => response,
+            Ok(response) => {
+                return Err(StorageError::Backend(format!(
+                    "S3 GET {} returned {}",
+                    key, response.status_code
+                )))
+            }
+            Err(s3::error::S3Error::HttpFailWithBody(404, _)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let (sender, receiver) = mpsc::channel(4);
+        tokio::spawn(async move {
+            while let Some(chunk) = response.bytes().next().await {
+                let chunk = chunk.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()));
+                let failed = chunk.is_err();
+                if sender.send(chunk).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Ok(Some(ReceiverStream::new(receiver)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        validate_key(key)?;
+        let response = self.bucket.delete_object(key).await?;
+        match response.status_code() {
+            200..=299 | 404 => Ok(()),
+            code => Err(StorageError::Backend(format!("S3 DELETE {} returned {}", key, code))),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        validate_key(key)?;
+        match self.bucket.head_object(key).await {
+            Ok((_, 404)) => Ok(false),
+            Ok((_, code)) if (200..300).contains(&code) => Ok(true),
+            Ok((_, code)) => Err(StorageError::Backend(format!("S3 HEAD {} returned {}", key, code))),
+            Err(s3::error::S3Error::HttpFailWithBody(404, _)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn presigned_url(&self, key: &str, expires_in: Duration) -> Result<PresignedUrl> {
+        validate_key(key)?;
+        let url = self
+            .bucket
+            .presign_get(key, expires_in.as_secs() as u32, None)
+            .await?;
+        Ok(PresignedUrl {
+            url,
+            expires_in_secs: expires_in.as_secs(),
+        })
+    }
+}
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// `DOCUMENT_STORAGE_DIR`, by default `./documents`
+pub fn documents_dir() -> PathBuf {
+    std::env::var("DOCUMENT_STORAGE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("./documents"))
+}
+
+/// Lifetime of download URLs, `DOCUMENT_URL_TTL_SECS`
+pub fn url_ttl() -> Duration {
+    Duration::from_secs(
+        std::env::var("DOCUMENT_URL_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_URL_TTL_SECS),
+    )
+}
+
+/// The backend from `DOCUMENT_STORAGE_BACKEND`, used until one is chosen
+/// in settings
+pub fn env_backend() -> Result<StorageBackend> {
+    match std::env::var("DOCUMENT_STORAGE_BACKEND").as_deref() {
+        Ok("disk") | Err(_) => Ok(StorageBackend::Disk),
+        Ok("s3") => Ok(StorageBackend::S3),
+        Ok(other) => Err(StorageError::Config(format!(
+            "Unknown DOCUMENT_STORAGE_BACKEND '{}'",
+            other
+        ))),
+    }
+}
+
+/// Store for a backend; documents are read from the backend recorded on
+/// them, which differs from the configured one until they are migrated
+pub fn store_for(backend: StorageBackend) -> Result<Arc<dyn DocumentStore>> {
+    match backend {
+        StorageBackend::Disk => Ok(Arc::new(DiskDocumentStore::from_env())),
+        StorageBackend::S3 => Ok(Arc::new(S3DocumentStore::from_env()?)),
+    }
+}
+
+pub fn store_from_env() -> Result<Arc<dyn DocumentStore>> {
+    store_for(env_backend()?)
+}
+
+/// The backend chosen in settings, if any
+pub async fn backend_setting(db: &Database) -> Result<Option<DocumentStorageSettings>> {
+    db.select(SETTINGS_RECORD)
+        .await
+        .map_err(|e| StorageError::Backend(e.to_string()))
+}
+
+/// The backend new documents are written to: the settings choice, else
+/// `DOCUMENT_STORAGE_BACKEND`
+pub async fn configured_backend(db: &Database) -> Result<StorageBackend> {
+    match backend_setting(db).await? {
+        Some(settings) => Ok(settings.backend),
+        None => env_backend(),
+    }
+}
+
+pub async fn configured_store(db: &Database) -> Result<Arc<dyn DocumentStore>> {
+    store_for(configured_backend(db).await?)
+}
+
+/// Write new documents to `backend` from now on. Fails if the backend isn't
+/// configured, e.g. S3 without `DOCUMENT_S3_BUCKET`. Existing documents stay
+/// where they are until migrated.
+pub async fn save_backend_setting(db: &Database, backend: StorageBackend) -> Result<DocumentStorageSettings> {
+    store_for(backend)?;
+    let saved: Option<DocumentStorageSettings> = db
+        .update(SETTINGS_RECORD)
+        .content(DocumentStorageSettings {
+            backend,
+            updated_at: Utc::now(),
+        })
+        .await
+        .map_err(|e| StorageError::Backend(e.to_string()))?;
+    saved.ok_or_else(|| StorageError::Backend("Failed to store document storage settings".to_string()))
+}
+
+// ============================================================================
+// MIGRATION
+// ============================================================================
+
+#[derive(Debug, Default, Serialize)]
+pub struct StorageMigrationReport {
+    pub migrated: u32,
+    /// Already on the target backend
+    pub skipped: u32,
+    /// Records whose file is missing from the source; left untouched
+    pub missing: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Copy every document not yet on `target` into it and repoint its record.
+/// Source files are only deleted with `delete_source`, after the record is
+/// updated, so an interrupted run can simply be repeated.
+pub async fn migrate_documents(
+    db: &Database,
+    target: &dyn DocumentStore,
+    dry_run: bool,
+    delete_source: bool,
+) -> anyhow::Result<StorageMigrationReport> {
+    let mut report = StorageMigrationReport::default();
+    let documents: Vec<ProjectDocument> = db.select("project_document").await?;
+
+    for document in documents {
+        let Some(id) = document.id.clone() else { continue };
+        let label = id.to_string();
+        if document.storage_backend == target.backend() {
+            report.skipped += 1;
+            continue;
+        }
+
+        let source = store_for(document.storage_backend)?;
+        let key = document_key(&document.file_path);
+        let Some(stream) = source.get_stream(&key).await? else {
+            report.missing.push(label);
+            continue;
+        };
+        if dry_run {
+            report.migrated += 1;
+            continue;
+        }
+
+        let content_type = document
+            .content_type
+            .clone()
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        let mut reader = stream_reader(stream);
+        match target.put_stream(&key, &mut reader, &content_type).await {
+            Ok(written) if written == document.file_size => {}
+            Ok(written) => {
+                report.failed.push(format!(
+                    "{}: copied {} of {} bytes",
+                    label, written, document.file_size
+                ));
+                continue;
+            }
+            Err(e) => {
+                report.failed.push(format!("{}: {}", label, e));
+                continue;
+            }
+        }
+
+        let _: Option<ProjectDocument> = db
+            .query("UPDATE $id SET file_path = $key, storage_backend = $backend")
+            .bind(("id", id))
+            .bind(("key", key.clone()))
+            .bind(("backend", target.backend()))
+            .traced("document_storage.migrate_documents")
+            .await?
+            .take(0)?;
+        report.migrated += 1;
+
+        if delete_source {
+            if let Err(e) = source.delete(&key).await {
+                tracing::warn!("Migrated {} but could not delete the source copy: {}", label, e);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(root: &Path) -> DiskDocumentStore {
+        DiskDocumentStore::new(root, "http://archer.test/", "test-secret")
+    }
+
+    #[test]
+    fn legacy_paths_map_to_keys() {
+        assert_eq!(document_key("./documents/generated/hld-1.docx"), "generated/hld-1.docx");
+        assert_eq!(document_key("documents/generated/hld-1.docx"), "generated/hld-1.docx");
+        assert_eq!(document_key("generated/hld-1.docx"), "generated/hld-1.docx");
+        assert!(validate_key("../etc/passwd").is_err());
+        assert!(validate_key("/etc/passwd").is_err());
+    }
+
+    #[tokio::test]
+    async fn disk_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        let content = vec![7u8; CHUNK_BYTES * 2 + 10];
+
+        let written = store
+            .put_stream("generated/a.docx", &mut content.as_slice(), "application/octet-stream")
+            .await
+            .unwrap();
+        assert_eq!(written, content.len() as u64);
+        assert!(store.exists("generated/a.docx").await.unwrap());
+
+        let mut stream = store.get_stream("generated/a.docx").await.unwrap().unwrap();
+        let mut read = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            read.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(read, content);
+
+        store.delete("generated/a.docx").await.unwrap();
+        assert!(store.get_stream("generated/a.docx").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn concurrent_writes_differing_in_extension_stay_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        let docx = vec![1u8; CHUNK_BYTES * 3];
+        let pdf = vec![2u8; CHUNK_BYTES * 3];
+
+        let (a, b) = tokio::join!(
+            store.put("generated/hld-1.docx", &docx, "application/octet-stream"),
+            store.put("generated/hld-1.pdf", &pdf, "application/pdf"),
+        );
+        a.unwrap();
+        b.unwrap();
+
+        assert_eq!(std::fs::read(dir.path().join("generated/hld-1.docx")).unwrap(), docx);
+        assert_eq!(std::fs::read(dir.path().join("generated/hld-1.pdf")).unwrap(), pdf);
+        assert_eq!(std::fs::read_dir(dir.path().join("generated")).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn download_tokens_name_their_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        let url = store
+            .presigned_url("generated/a.docx", Duration::from_secs(60))
+            .await
+            .unwrap()
+            .url;
+        assert!(url.starts_with("http://archer.test/api/v1/documents/blob?token="));
+
+        let token = url.split("token=").nth(1).unwrap();
+        assert_eq!(store.verify_download_token(token).unwrap(), "generated/a.docx");
+        assert!(DiskDocumentStore::new(dir.path(), "", "other-secret")
+            .verify_download_token(token)
+            .is_err());
+    }
+}
@@ -0,0 +1,529 @@
+// Archer - Generated Document Versions
+// Regenerating a document adds a generated_document record with its own
+// artifact instead of replacing the previous one. Each version keeps the
+// parameters and per-section inputs it was built from, so reviewers can see
+// which sections and variables a regeneration altered.
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::models::hld::{DocumentAudience, SectionDefinition};
+use crate::models::project_models::{
+    DocumentType, DocumentVariant, DocumentVersionDiff, GeneratedDocument, GeneratedSectionSnapshot,
+    SectionVersionChange, ValueVersionChange, VersionChangeKind,
+};
+use crate::models::webhook::WebhookEventType;
+use crate::services::document_storage::{self, ByteStream, StorageError};
+use crate::services::webhook_service;
+use crate::services::word_generator::WordGenerator;
+use crate::database::TracedQuery;
+
+const TABLE: &str = "generated_document";
+
+#[derive(Debug, Error)]
+pub enum DocumentVersionError {
+    #[error("Version {0} not found")]
+    NotFound(u32),
+
+    #[error("The file of version {0} is missing from storage")]
+    ArtifactMissing(u32),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for DocumentVersionError {
+    fn from(e: surrealdb::Error) -> Self {
+        DocumentVersionError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<StorageError> for DocumentVersionError {
+    fn from(e: StorageError) -> Self {
+        DocumentVersionError::Storage(e.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, DocumentVersionError>;
+
+/// A redacted rendering stored alongside a version
+pub struct VariantArtifact {
+    pub audience: DocumentAudience,
+    pub bytes: Vec<u8>,
+    pub redacted_sections: Vec<String>,
+    pub redacted_variables: Vec<String>,
+}
+
+pub struct DocumentVersionService {
+    db: Arc<Database>,
+}
+
+impl DocumentVersionService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Store `bytes` and record them as the next version of the project's
+    /// document of this type. `version`, `previous_version`, `file_path`,
+    /// `file_size_bytes` and `storage_backend` of `draft` are assigned here.
+    pub async fn record_version(
+        &self,
+        draft: GeneratedDocument,
+        bytes: &[u8],
+        content_type: &str,
+    ) -> Result<GeneratedDocument> {
+        self.record_version_with_variants(draft, bytes, content_type, Vec::new())
+            .await
+    }
+
+    /// `record_version`, also storing the redacted variants rendered in the
+    /// same run
+    pub async fn record_version_with_variants(
+        &self,
+        mut draft: GeneratedDocument,
+        bytes: &[u8],
+        content_type: &str,
+        variants: Vec<VariantArtifact>,
+    ) -> Result<GeneratedDocument> {
+        let previous = self
+            .list_versions(&draft.project_id, &draft.document_type)
+            .await?
+            .into_iter()
+            .next();
+        draft.version = previous.as_ref().map(|d| d.version + 1).unwrap_or(1);
+        draft.previous_version = previous.and_then(|d| d.id);
+
+        let base = format!(
+            "generated/{}/{}/v{}-{}",
+            serde_name(&draft.document_type),
+            draft.project_id.id.to_raw(),
+            draft.version,
+            Uuid::new_v4(),
+        );
+        let extension = serde_name(&draft.file_format);
+        let key = format!("{}.{}", base, extension);
+        let store = document_storage::configured_store(&self.db).await?;
+        store.put(&key, bytes, content_type).await?;
+
+        // Don't leave artifacts no version points at
+        let mut stored = vec![key.clone()];
+        draft.variants = Vec::new();
+        for variant in variants {
+            let variant_key = format!("{}-{}.{}", base, variant.audience.as_str(), extension);
+            if let Err(e) = store.put(&variant_key, &variant.bytes, content_type).await {
+                for key in &stored {
+                    let _ = store.delete(key).await;
+                }
+                return Err(e.into());
+            }
+            stored.push(variant_key.clone());
+            draft.variants.push(DocumentVariant {
+                audience: variant.audience,
+                file_path: variant_key,
+                file_size_bytes: variant.bytes.len() as i64,
+                redacted_sections: variant.redacted_sections,
+                redacted_variables: variant.redacted_variables,
+            });
+        }
+
+        draft.id = None;
+        draft.file_path = key.clone();
+        draft.file_size_bytes = bytes.len() as i64;
+        draft.storage_backend = store.backend();
+
+        let created: Vec<GeneratedDocument> = match self.db.create(TABLE).content(&draft).await {
+            Ok(created) => created,
+            Err(e) => {
+                for key in &stored {
+                    let _ = store.delete(key).await;
+                }
+                return Err(e.into());
+            }
+        };
+        let created = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| DocumentVersionError::DatabaseError("Failed to save version".to_string()))?;
+
+        webhook_service::emit_for_project(
+            &self.db,
+            WebhookEventType::DocumentGenerated,
+            created.project_id.clone(),
+            serde_json::json!({
+                "document_id": created.id.as_ref().map(|id| id.to_string()),
+                "project_id": created.project_id.to_string(),
+                "document_type": created.document_type,
+                "document_name": created.document_name,
+                "version": created.version,
+                "file_format": created.file_format,
+                "file_size_bytes": created.file_size_bytes,
+                "generated_by": created.generated_by,
+                "generated_at": created.generated_at,
+            }),
+        );
+        Ok(created)
+    }
+
+    /// Versions of the project's document of this type, newest first
+    pub async fn list_versions(
+        &self,
+        project_id: &Thing,
+        document_type: &DocumentType,
+    ) -> Result<Vec<GeneratedDocument>> {
+        let versions: Vec<GeneratedDocument> = self
+            .db
+            .query(
+                "SELECT * FROM generated_document WHERE project_id = $project_id \
+                 AND document_type = $document_type ORDER BY version DESC",
+            )
+            .bind(("project_id", project_id.clone()))
+            .bind(("document_type", document_type.clone()))
+            .traced("document_version_service.list_versions")
+            .await?
+            .take(0)?;
+        Ok(versions)
+    }
+
+    pub async fn get_version(
+        &self,
+        project_id: &Thing,
+        document_type: &DocumentType,
+        version: u32,
+    ) -> Result<GeneratedDocument> {
+        let versions: Vec<GeneratedDocument> = self
+            .db
+            .query(
+                "SELECT * FROM generated_document WHERE project_id = $project_id \
+                 AND document_type = $document_type AND version = $version LIMIT 1",
+            )
+            .bind(("project_id", project_id.clone()))
+            .bind(("document_type", document_type.clone()))
+            .bind(("version", version))
+            .traced("document_version_service.get_version")
+            .await?
+            .take(0)?;
+        versions
+            .into_iter()
+            .next()
+            .ok_or(DocumentVersionError::NotFound(version))
+    }
+
+    /// A version's record and a stream of its artifact
+    pub async fn open_version(
+        &self,
+        project_id: &Thing,
+        document_type: &DocumentType,
+        version: u32,
+    ) -> Result<(GeneratedDocument, ByteStream)> {
+        let document = self.get_version(project_id, document_type, version).await?;
+        let stream = self.open_artifact(&document).await?;
+        Ok((document, stream))
+    }
+
+    /// A stream of the artifact the record points at
+    pub async fn open_artifact(&self, document: &GeneratedDocument) -> Result<ByteStream> {
+        let store = document_storage::store_for(document.storage_backend)?;
+        store
+            .get_stream(&document_storage::document_key(&document.file_path))
+            .await?
+            .ok_or(DocumentVersionError::ArtifactMissing(document.version))
+    }
+
+    pub async fn diff(
+        &self,
+        project_id: &Thing,
+        document_type: &DocumentType,
+        from: u32,
+        to: u32,
+    ) -> Result<DocumentVersionDiff> {
+        let from = self.get_version(project_id, document_type, from).await?;
+        let to = self.get_version(project_id, document_type, to).await?;
+        Ok(diff_versions(&from, &to))
+    }
+}
+
+/// The serialized name of a unit enum variant, e.g. `hld` or `docx`
+fn serde_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Record what each enabled section is built from: the variables its
+/// generator reads plus any `{{placeholder}}` in its description
+pub fn snapshot_sections(
+    sections: &[SectionDefinition],
+    variables: &HashMap<String, Value>,
+) -> Vec<GeneratedSectionSnapshot> {
+    sections
+        .iter()
+        .filter(|section| section.enabled)
+        .map(|section| {
+            let mut names: BTreeSet<String> = WordGenerator::section_variables(&section.section_id)
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+            names.extend(
+                variables
+                    .keys()
+                    .filter(|name| section.description.contains(&format!("{{{{{}}}}}", name)))
+                    .cloned(),
+            );
+
+            let mut hasher = Sha256::new();
+            hasher.update(section.display_name.as_bytes());
+            hasher.update([0]);
+            hasher.update(section.description.as_bytes());
+            for name in &names {
+                let value = variables.get(name).unwrap_or(&Value::Null);
+                hasher.update([0]);
+                hasher.update(format!("{}={}", name, value).as_bytes());
+            }
+
+            GeneratedSectionSnapshot {
+                section_id: section.section_id.clone(),
+                display_name: section.display_name.clone(),
+                order_index: section.order_index,
+                variables: names.into_iter().collect(),
+                content_hash: format!("{:x}", hasher.finalize()),
+            }
+        })
+        .collect()
+}
+
+/// Compare two versions section by section, then by variable and parameter
+pub fn diff_versions(from: &GeneratedDocument, to: &GeneratedDocument) -> DocumentVersionDiff {
+    let old_sections: HashMap<&str, &GeneratedSectionSnapshot> = from
+        .section_snapshot
+        .iter()
+        .map(|s| (s.section_id.as_str(), s))
+        .collect();
+    let new_ids: BTreeSet<&str> = to.section_snapshot.iter().map(|s| s.section_id.as_str()).collect();
+
+    let old_order = shared_order(&from.section_snapshot, |id| new_ids.contains(id));
+    let new_order = shared_order(&to.section_snapshot, |id| old_sections.contains_key(id));
+
+    let mut sections = Vec::new();
+    for section in &to.section_snapshot {
+        let Some(old) = old_sections.get(section.section_id.as_str()) else {
+            sections.push(SectionVersionChange {
+                section_id: section.section_id.clone(),
+                display_name: section.display_name.clone(),
+                change: VersionChangeKind::Added,
+                changed_variables: Vec::new(),
+            });
+            continue;
+        };
+
+        let change = if old.content_hash != section.content_hash {
+            VersionChangeKind::Modified
+        } else if old_order.iter().position(|id| *id == section.section_id)
+            != new_order.iter().position(|id| *id == section.section_id)
+        {
+            VersionChangeKind::Moved
+        } else {
+            continue;
+        };
+        let changed_variables = old
+            .variables
+            .iter()
+            .chain(section.variables.iter())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|name| from.variables_snapshot.get(*name) != to.variables_snapshot.get(*name))
+            .cloned()
+            .collect();
+        sections.push(SectionVersionChange {
+            section_id: section.section_id.clone(),
+            display_name: section.display_name.clone(),
+            change,
+            changed_variables,
+        });
+    }
+    for section in &from.section_snapshot {
+        if !new_ids.contains(section.section_id.as_str()) {
+            sections.push(SectionVersionChange {
+                section_id: section.section_id.clone(),
+                display_name: section.display_name.clone(),
+                change: VersionChangeKind::Removed,
+                changed_variables: Vec::new(),
+            });
+        }
+    }
+
+    DocumentVersionDiff {
+        from_version: from.version,
+        to_version: to.version,
+        sections,
+        variables: value_changes(&from.variables_snapshot, &to.variables_snapshot),
+        parameters: value_changes(&from.generation_parameters, &to.generation_parameters),
+    }
+}
+
+/// Order of the sections both versions have, so an insertion doesn't mark
+/// every section after it as moved
+fn shared_order(snapshot: &[GeneratedSectionSnapshot], shared: impl Fn(&str) -> bool) -> Vec<&str> {
+    let mut kept: Vec<&GeneratedSectionSnapshot> =
+        snapshot.iter().filter(|s| shared(&s.section_id)).collect();
+    kept.sort_by_key(|s| s.order_index);
+    kept.into_iter().map(|s| s.section_id.as_str()).collect()
+}
+
+fn value_changes(old: &HashMap<String, Value>, new: &HashMap<String, Value>) -> Vec<ValueVersionChange> {
+    old.keys()
+        .chain(new.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|name| {
+            let (old_value, new_value) = (old.get(name), new.get(name));
+            let change = match (old_value, new_value) {
+                (None, Some(_)) => VersionChangeKind::Added,
+                (Some(_), None) => VersionChangeKind::Removed,
+                (Some(a), Some(b)) if a != b => VersionChangeKind::Modified,
+                _ => return None,
+            };
+            Some(ValueVersionChange {
+                name: name.clone(),
+                change,
+                old_value: old_value.cloned(),
+                new_value: new_value.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project_models::{DocumentFormat, DocumentGenerationStatus};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn section(section_id: &str, order_index: i32) -> SectionDefinition {
+        SectionDefinition {
+            id: None,
+            section_id: section_id.to_string(),
+            section_name: section_id.to_string(),
+            display_name: section_id.replace('_', " ").to_uppercase(),
+            description: String::new(),
+            required: false,
+            enabled: true,
+            order_index,
+            depends_on: Vec::new(),
+            sensitivity: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn version(
+        version: u32,
+        sections: &[SectionDefinition],
+        variables: HashMap<String, Value>,
+        anonymize: bool,
+    ) -> GeneratedDocument {
+        GeneratedDocument {
+            id: None,
+            project_id: Thing::from(("projects", "p1")),
+            activity_id: Thing::from(("activity", "hld_export")),
+            template_id: Thing::from(("hld_templates", "default")),
+            document_name: "HLD".to_string(),
+            document_type: DocumentType::Hld,
+            file_path: String::new(),
+            file_size_bytes: 0,
+            file_format: DocumentFormat::Docx,
+            section_snapshot: snapshot_sections(sections, &variables),
+            variables_snapshot: variables,
+            data_sources: Vec::new(),
+            generation_status: DocumentGenerationStatus::Completed,
+            error_message: None,
+            metadata: HashMap::new(),
+            generated_at: Utc::now(),
+            generated_by: "tester".to_string(),
+            expires_at: None,
+            version,
+            previous_version: None,
+            generation_parameters: HashMap::from([("anonymize".to_string(), json!(anonymize))]),
+            storage_backend: Default::default(),
+            locked: false,
+            approval_record: None,
+            published_file_path: None,
+            variants: Vec::new(),
+        }
+    }
+
+    fn vars(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn snapshot_lists_section_inputs() {
+        let mut custom = section("custom_notes", 1);
+        custom.description = "Owner: {{owner}}".to_string();
+        let snapshot = snapshot_sections(
+            &[section("network_design", 0), custom],
+            &vars(&[("owner", json!("Ops")), ("mgmt_vlan_id", json!(10))]),
+        );
+
+        assert_eq!(snapshot[0].variables, vec!["cluster_vlan_id", "lm_vlan_id", "mgmt_vlan_id"]);
+        assert_eq!(snapshot[1].variables, vec!["owner"]);
+        assert_eq!(snapshot[0].content_hash.len(), 64);
+    }
+
+    #[test]
+    fn diff_reports_changed_sections_and_variables() {
+        let v1 = version(
+            1,
+            &[section("executive_summary", 0), section("storage_design", 1), section("network_design", 2)],
+            vars(&[("node_count", json!(4)), ("mgmt_vlan_id", json!(10))]),
+            false,
+        );
+        let v2 = version(
+            2,
+            &[section("executive_summary", 0), section("network_design", 1), section("compute_design", 2)],
+            vars(&[("node_count", json!(6)), ("mgmt_vlan_id", json!(10)), ("cpu_model", json!("EPYC"))]),
+            true,
+        );
+
+        let diff = diff_versions(&v1, &v2);
+        assert_eq!((diff.from_version, diff.to_version), (1, 2));
+
+        let change = |id: &str| diff.sections.iter().find(|s| s.section_id == id).map(|s| s.change);
+        assert_eq!(change("executive_summary"), Some(VersionChangeKind::Modified));
+        assert_eq!(change("network_design"), None);
+        assert_eq!(change("compute_design"), Some(VersionChangeKind::Added));
+        assert_eq!(change("storage_design"), Some(VersionChangeKind::Removed));
+        let summary = diff.sections.iter().find(|s| s.section_id == "executive_summary").unwrap();
+        assert_eq!(summary.changed_variables, vec!["node_count"]);
+
+        let names: Vec<_> = diff.variables.iter().map(|v| (v.name.as_str(), v.change)).collect();
+        assert_eq!(
+            names,
+            vec![("cpu_model", VersionChangeKind::Added), ("node_count", VersionChangeKind::Modified)]
+        );
+        assert_eq!(diff.variables[1].old_value, Some(json!(4)));
+        assert_eq!(diff.parameters[0].name, "anonymize");
+    }
+
+    #[test]
+    fn reordered_sections_are_moved() {
+        let variables = vars(&[]);
+        let v1 = version(1, &[section("storage_design", 0), section("network_design", 1)], variables.clone(), false);
+        let v2 = version(2, &[section("network_design", 0), section("storage_design", 1)], variables, false);
+
+        let diff = diff_versions(&v1, &v2);
+        assert!(diff.sections.iter().all(|s| s.change == VersionChangeKind::Moved));
+        assert_eq!(diff.sections.len(), 2);
+        assert!(diff.variables.is_empty() && diff.parameters.is_empty());
+    }
+}
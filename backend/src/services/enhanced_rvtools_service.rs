@@ -4,11 +4,14 @@ use crate::services::rvtools_service::{RvToolsProcessingError, RvToolsProcessing
 use anyhow::{Context, Result};
 use calamine::{open_workbook, Error as CalamineError, RangeDeserializerBuilder, Reader, Xlsx};
 use chrono::{DateTime, Utc};
+use core_engine::models::{ImportReport, RowError};
+use core_engine::units::{Bytes, SizeConvention};
 use regex::Regex;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::io;
 use surrealdb::sql::Thing;
+use crate::database::TracedQuery;
 
 pub struct EnhancedRvToolsService {
     db: Database,
@@ -64,6 +67,7 @@ impl EnhancedRvToolsService {
             uploaded_at: Utc::now(),
             processed_at: None,
             uploaded_by: "system".to_string(),
+            import_report: None,
         };
 
         let created_upload: Vec<RvToolsUpload> = self
@@ -82,11 +86,16 @@ impl EnhancedRvToolsService {
         {
             Ok(result) => {
                 // Update upload record as completed
+                let status = if result.report.has_warnings() {
+                    "completed_with_warnings"
+                } else {
+                    "processed"
+                };
                 let _: Option<RvToolsUpload> = self
                     .db
                     .update(&upload_id)
                     .merge(json!({
-                        "upload_status": "processed",
+                        "upload_status": status,
                         "total_vms": result.total_vms,
                         "total_hosts": result.total_hosts,
                         "total_clusters": result.total_clusters,
@@ -95,8 +104,11 @@ impl EnhancedRvToolsService {
                             "sheets_processed": result.sheets_processed,
                             "total_rows_processed": result.total_rows_processed,
                             "errors": result.processing_errors.len(),
-                            "warnings": result.warnings.len()
-                        })
+                            "warnings": result.warnings.len(),
+                            "rows_total": result.report.rows_total,
+                            "rows_failed": result.report.rows_failed
+                        }),
+                        "import_report": result.report
                     }))
                     .await?;
 
@@ -143,6 +155,7 @@ impl EnhancedRvToolsService {
             total_clusters: 0,
             processing_errors: Vec::new(),
             warnings: Vec::new(),
+            report: ImportReport::default(),
             storage_analysis: None,
             s2d_compliance: HashMap::new(),
         };
@@ -157,6 +170,10 @@ impl EnhancedRvToolsService {
                     processing_result.sheets_processed += 1;
                 }
                 Err(e) => {
+                    processing_result
+                        .report
+                        .errors
+                        .push(RowError::new(sheet_name, 0, None, e.to_string()));
                     processing_result
                         .processing_errors
                         .push(RvToolsProcessingError {
@@ -203,9 +220,13 @@ impl EnhancedRvToolsService {
         // Get headers from first row
         let headers = self.extract_headers(&range, cols)?;
 
-        // Process each data row
+        // Process each data row. Invalid cells are still stored for
+        // traceability, but the row is reported and counted as failed; a
+        // storage error skips the rest of the row, not the sheet.
         for row_idx in 1..rows {
             result.total_rows_processed += 1;
+            let row_number = row_idx + 1; // 1-based for user reference
+            let mut row_error = None;
 
             for col_idx in 0..cols {
                 let cell_value = range
@@ -234,7 +255,7 @@ impl EnhancedRvToolsService {
                     id: None,
                     upload_id: upload_id.clone(),
                     sheet_name: sheet_name.to_string(),
-                    row_number: row_idx as i32 + 1, // 1-based for user reference
+                    row_number: row_number as i32,
                     column_name: column_name.clone(),
                     column_index: col_idx as i32,
                     raw_value: cell_value,
@@ -254,16 +275,36 @@ impl EnhancedRvToolsService {
                     created_at: Utc::now(),
                 };
 
+                if row_error.is_none() && !validation_result.errors.is_empty() {
+                    row_error = Some(RowError::new(
+                        sheet_name,
+                        row_number,
+                        Some(&column_name),
+                        validation_result.errors.join("; "),
+                    ));
+                }
+
                 // Save to database
-                let _: Vec<RvToolsExcelData> = self
+                let saved: std::result::Result<Vec<RvToolsExcelData>, _> = self
                     .db
                     .create("rvtools_excel_data")
                     .content(excel_data)
-                    .await?;
+                    .await;
+                if let Err(e) = saved {
+                    row_error = Some(RowError::new(
+                        sheet_name,
+                        row_number,
+                        Some(&column_name),
+                        format!("Failed to store cell: {}", e),
+                    ));
+                    break;
+                }
 
                 // Update counters based on sheet type
                 self.update_processing_counters(sheet_name, &column_name, result);
             }
+
+            result.report.record(row_error.map_or(Ok(()), Err));
         }
 
         Ok(())
@@ -374,23 +415,10 @@ impl EnhancedRvToolsService {
 
     fn parse_capacity_to_gb(&self, value: &str) -> Option<f64> {
         let capacity_regex = Regex::new(r"([0-9,]+\.?[0-9]*)\s*(TB|GB|MB|KB)").ok()?;
+        let found = capacity_regex.find(value)?;
 
-        if let Some(caps) = capacity_regex.captures(value) {
-            let number: f64 = caps[1].replace(',', "").parse().ok()?;
-            let unit = &caps[2];
-
-            let gb_value = match unit {
-                "TB" => number * 1024.0,
-                "GB" => number,
-                "MB" => number / 1024.0,
-                "KB" => number / (1024.0 * 1024.0),
-                _ => return None,
-            };
-
-            Some(gb_value)
-        } else {
-            None
-        }
+        // vSphere reports binary sizes under decimal labels
+        Bytes::parse(found.as_str(), SizeConvention::Binary).map(Bytes::as_gib)
     }
 
     fn update_processing_counters(
@@ -566,6 +594,7 @@ impl EnhancedRvToolsService {
             .db
             .query(datastore_query)
             .bind(("upload_id", upload_id))
+            .traced("enhanced_rvtools_service.analyze_datastore_evidence")
             .await?
             .take(0)?;
 
@@ -609,6 +638,7 @@ impl EnhancedRvToolsService {
             .db
             .query(multipath_query)
             .bind(("upload_id", upload_id))
+            .traced("enhanced_rvtools_service.analyze_multipath_evidence")
             .await?
             .take(0)?;
 
@@ -649,6 +679,7 @@ impl EnhancedRvToolsService {
             .db
             .query(hba_query)
             .bind(("upload_id", upload_id))
+            .traced("enhanced_rvtools_service.analyze_hba_evidence")
             .await?
             .take(0)?;
 
@@ -817,6 +848,7 @@ impl EnhancedRvToolsService {
             .db
             .query(host_query)
             .bind(("upload_id", upload_id))
+            .traced("enhanced_rvtools_service.check_min_hosts_requirement")
             .await?
             .take(0)?;
 
@@ -879,6 +911,7 @@ impl EnhancedRvToolsService {
             .db
             .query(network_query)
             .bind(("upload_id", upload_id))
+            .traced("enhanced_rvtools_service.check_network_requirement")
             .await?
             .take(0)?;
 
@@ -1008,6 +1041,7 @@ impl EnhancedRvToolsService {
             .db
             .query(cluster_query)
             .bind(("upload_id", upload_id))
+            .traced("enhanced_rvtools_service.extract_cluster_list")
             .await?
             .take(0)?;
 
@@ -1175,6 +1209,7 @@ pub struct EnhancedRvToolsProcessingResult {
     pub total_clusters: i32,
     pub processing_errors: Vec<RvToolsProcessingError>,
     pub warnings: Vec<RvToolsProcessingError>,
+    pub report: ImportReport,
     pub storage_analysis: Option<StorageArchitectureAnalysis>,
     pub s2d_compliance: HashMap<String, S2dComplianceCheck>,
 }
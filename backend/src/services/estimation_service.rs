@@ -0,0 +1,378 @@
+//! Effort & Cost Estimation Service
+//!
+//! Sizes professional-services effort for a migration project:
+//! - Per-VM effort derived from migration strategy and complexity score
+//! - Fixed assessment, preparation and project management effort
+//! - Pricing via configurable role rate cards, plus contingency
+//!
+//! The resulting total is the one-time implementation services cost used in
+//! TCO analysis and the HLD/proposal document.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::models::estimation::*;
+use crate::services::currency_service::CurrencyService;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::database::TracedQuery;
+
+/// Project management overhead as a fraction of delivery effort
+const PM_OVERHEAD: f64 = 0.15;
+
+pub struct EstimationService {
+    db: Database,
+}
+
+impl EstimationService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    // =========================================================================
+    // RATE CARDS
+    // =========================================================================
+
+    /// Built-in rate card used until a custom one is configured
+    pub fn default_rate_card() -> RateCard {
+        let now = Utc::now();
+        RateCard {
+            id: None,
+            name: "Standard".to_string(),
+            currency: "USD".to_string(),
+            rates: vec![
+                RoleRate { role: "solution_architect".to_string(), hourly_rate: 200.0 },
+                RoleRate { role: "project_manager".to_string(), hourly_rate: 150.0 },
+                RoleRate { role: "infrastructure_engineer".to_string(), hourly_rate: 160.0 },
+                RoleRate { role: "migration_engineer".to_string(), hourly_rate: 140.0 },
+                RoleRate { role: "application_owner".to_string(), hourly_rate: 120.0 },
+            ],
+            contingency_percent: 10.0,
+            is_default: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub async fn list_rate_cards(&self) -> Result<Vec<RateCard>> {
+        let cards: Vec<RateCard> = self
+            .db
+            .select("rate_card")
+            .await
+            .context("Failed to list rate cards")?;
+        Ok(cards)
+    }
+
+    pub async fn get_rate_card(&self, rate_card_id: &str) -> Result<RateCard> {
+        let card: Option<RateCard> = self
+            .db
+            .select(("rate_card", rate_card_id))
+            .await
+            .context("Failed to get rate card")?;
+        card.ok_or_else(|| anyhow!("Rate card not found"))
+    }
+
+    pub async fn create_rate_card(&self, request: CreateRateCardRequest) -> Result<RateCard> {
+        if request.rates.iter().any(|r| r.hourly_rate < 0.0) {
+            return Err(anyhow!("Hourly rates must not be negative"));
+        }
+        let contingency = request.contingency_percent.unwrap_or(10.0);
+        if !(0.0..=100.0).contains(&contingency) {
+            return Err(anyhow!("Contingency must be between 0 and 100 percent"));
+        }
+
+        if request.is_default {
+            self.db
+                .query("UPDATE rate_card SET is_default = false WHERE is_default = true")
+                .traced("estimation_service.create_rate_card")
+                .await
+                .context("Failed to clear default rate card")?;
+        }
+
+        let now = Utc::now();
+        let card = RateCard {
+            id: None,
+            name: request.name,
+            currency: request.currency.unwrap_or_else(|| "USD".to_string()),
+            rates: request.rates,
+            contingency_percent: contingency,
+            is_default: request.is_default,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<RateCard> = self
+            .db
+            .create("rate_card")
+            .content(card)
+            .await
+            .context("Failed to create rate card")?;
+
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No rate card returned after creation"))
+    }
+
+    pub async fn delete_rate_card(&self, rate_card_id: &str) -> Result<()> {
+        let _: Option<RateCard> = self
+            .db
+            .delete(("rate_card", rate_card_id))
+            .await
+            .context("Failed to delete rate card")?;
+        Ok(())
+    }
+
+    /// Resolve the rate card to price with: explicit ID, stored default, or built-in
+    async fn resolve_rate_card(&self, rate_card_id: Option<&str>) -> Result<RateCard> {
+        if let Some(id) = rate_card_id {
+            return self.get_rate_card(id).await;
+        }
+
+        let cards = self.list_rate_cards().await?;
+        Ok(cards
+            .iter()
+            .find(|c| c.is_default)
+            .or_else(|| cards.first())
+            .cloned()
+            .unwrap_or_else(Self::default_rate_card))
+    }
+
+    // =========================================================================
+    // ESTIMATES
+    // =========================================================================
+
+    /// Estimate services effort and cost for a project and store the result
+    pub async fn estimate_project(
+        &self,
+        project_id: &str,
+        request: EstimateServicesRequest,
+    ) -> Result<ServicesEstimate> {
+        let rate_card = self.resolve_rate_card(request.rate_card_id.as_deref()).await?;
+
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let recommendations = wizard.analyze_project_strategy(project_id).await?;
+        let cluster_count = wizard.get_project_clusters(project_id).await?.len();
+
+        let inputs: Vec<VmEffortInput> = recommendations
+            .into_iter()
+            .map(|rec| VmEffortInput {
+                strategy: rec.strategy,
+                complexity_score: (100.0 - rec.confidence_score).clamp(0.0, 100.0),
+            })
+            .collect();
+
+        let mut estimate = Self::calculate_estimate(&inputs, cluster_count, &rate_card);
+        estimate.project_id = Thing::from(("migration_wizard_project", project_id));
+
+        // Keep the rate card total and add the project's display currency view
+        let currency = CurrencyService::new(self.db.clone());
+        if currency.project_currency(project_id).await? != estimate.currency {
+            let total = core_engine::currency::Money::new(estimate.total_cost, &estimate.currency);
+            estimate.display_total = Some(currency.to_project_currency(project_id, &total).await?);
+        }
+
+        self.db
+            .query("DELETE services_estimate WHERE project_id = type::thing('migration_wizard_project', $project_id)")
+            .bind(("project_id", project_id.to_string()))
+            .traced("estimation_service.estimate_project")
+            .await
+            .context("Failed to delete previous services estimate")?;
+
+        let created: Vec<ServicesEstimate> = self
+            .db
+            .create("services_estimate")
+            .content(estimate)
+            .await
+            .context("Failed to store services estimate")?;
+
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No estimate returned after creation"))
+    }
+
+    /// Latest stored estimate for a project, if any
+    pub async fn get_project_estimate(&self, project_id: &str) -> Result<Option<ServicesEstimate>> {
+        let estimates: Vec<ServicesEstimate> = self
+            .db
+            .query("SELECT * FROM services_estimate WHERE project_id = type::thing('migration_wizard_project', $project_id) ORDER BY generated_at DESC LIMIT 1")
+            .bind(("project_id", project_id.to_string()))
+            .traced("estimation_service.get_project_estimate")
+            .await
+            .context("Failed to query services estimate")?
+            .take(0)
+            .context("Failed to parse services estimate")?;
+
+        Ok(estimates.into_iter().next())
+    }
+
+    /// Baseline hours per VM by role for each migration strategy
+    fn strategy_hours(strategy: &str) -> &'static [(&'static str, f64)] {
+        match strategy {
+            "replatform" => &[
+                ("migration_engineer", 6.0),
+                ("infrastructure_engineer", 2.0),
+                ("application_owner", 3.0),
+            ],
+            "rehost" => &[
+                ("migration_engineer", 12.0),
+                ("infrastructure_engineer", 4.0),
+                ("application_owner", 6.0),
+            ],
+            // lift_shift and anything unrecognised
+            _ => &[("migration_engineer", 2.0), ("application_owner", 1.0)],
+        }
+    }
+
+    /// Pure effort/cost model.
+    ///
+    /// Per-VM hours scale linearly with complexity, from 1x at score 0 to 2x at
+    /// score 100. Assessment scales with VM count, preparation with the number
+    /// of destination clusters, and project management is a fixed overhead.
+    pub fn calculate_estimate(
+        vms: &[VmEffortInput],
+        cluster_count: usize,
+        rate_card: &RateCard,
+    ) -> ServicesEstimate {
+        // (task, role) -> hours, kept ordered for stable output
+        let mut hours: BTreeMap<(String, String), f64> = BTreeMap::new();
+        let mut add = |task: &str, role: &str, h: f64| {
+            if h > 0.0 {
+                *hours.entry((task.to_string(), role.to_string())).or_insert(0.0) += h;
+            }
+        };
+
+        let vm_count = vms.len();
+        add("1. Assessment & Design", "solution_architect", 24.0 + 0.25 * vm_count as f64);
+        add("1. Assessment & Design", "project_manager", 8.0);
+        add("2. Infrastructure Preparation", "infrastructure_engineer", 16.0 * cluster_count.max(1) as f64);
+
+        for vm in vms {
+            let multiplier = 1.0 + vm.complexity_score.clamp(0.0, 100.0) / 100.0;
+            let task = format!("3. VM Migration ({})", vm.strategy);
+            for (role, base) in Self::strategy_hours(&vm.strategy) {
+                add(&task, role, base * multiplier);
+            }
+        }
+
+        let delivery_hours: f64 = hours.values().sum();
+        hours.insert(
+            ("4. Project Management".to_string(), "project_manager".to_string()),
+            delivery_hours * PM_OVERHEAD,
+        );
+
+        let lines: Vec<EffortLine> = hours
+            .into_iter()
+            .map(|((task, role), h)| {
+                let rate = rate_card.rate_for(&role);
+                let h = round2(h);
+                EffortLine {
+                    cost: round2(h * rate),
+                    task,
+                    role,
+                    hours: h,
+                    hourly_rate: rate,
+                }
+            })
+            .collect();
+
+        let mut by_role: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+        for line in &lines {
+            let entry = by_role.entry(line.role.as_str()).or_insert((0.0, 0.0));
+            entry.0 += line.hours;
+            entry.1 += line.cost;
+        }
+        let roles = by_role
+            .into_iter()
+            .map(|(role, (h, c))| RoleEffortSummary {
+                role: role.to_string(),
+                hours: round2(h),
+                cost: round2(c),
+            })
+            .collect();
+
+        let total_hours = round2(lines.iter().map(|l| l.hours).sum());
+        let subtotal = round2(lines.iter().map(|l| l.cost).sum());
+        let contingency = round2(subtotal * rate_card.contingency_percent / 100.0);
+        let average_complexity = if vm_count == 0 {
+            0.0
+        } else {
+            round2(vms.iter().map(|v| v.complexity_score).sum::<f64>() / vm_count as f64)
+        };
+
+        ServicesEstimate {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "unassigned")),
+            rate_card_name: rate_card.name.clone(),
+            currency: rate_card.currency.clone(),
+            vm_count,
+            average_complexity,
+            lines,
+            roles,
+            total_hours,
+            subtotal,
+            contingency_percent: rate_card.contingency_percent,
+            contingency,
+            total_cost: round2(subtotal + contingency),
+            display_total: None,
+            generated_at: Utc::now(),
+        }
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vm(strategy: &str, complexity: f64) -> VmEffortInput {
+        VmEffortInput {
+            strategy: strategy.to_string(),
+            complexity_score: complexity,
+        }
+    }
+
+    #[test]
+    fn test_complexity_scales_effort() {
+        let card = EstimationService::default_rate_card();
+        let simple = EstimationService::calculate_estimate(&[vm("lift_shift", 0.0)], 1, &card);
+        let complex = EstimationService::calculate_estimate(&[vm("lift_shift", 100.0)], 1, &card);
+        assert!(complex.total_hours > simple.total_hours);
+        assert!(complex.total_cost > simple.total_cost);
+    }
+
+    #[test]
+    fn test_strategy_affects_effort() {
+        let card = EstimationService::default_rate_card();
+        let lift = EstimationService::calculate_estimate(&[vm("lift_shift", 10.0)], 1, &card);
+        let rehost = EstimationService::calculate_estimate(&[vm("rehost", 10.0)], 1, &card);
+        assert!(rehost.total_hours > lift.total_hours);
+    }
+
+    #[test]
+    fn test_rate_card_pricing_and_contingency() {
+        let mut card = EstimationService::default_rate_card();
+        card.rates = vec![RoleRate { role: "migration_engineer".to_string(), hourly_rate: 100.0 }];
+        card.contingency_percent = 20.0;
+
+        let estimate = EstimationService::calculate_estimate(&[vm("lift_shift", 0.0)], 1, &card);
+        let migration = estimate
+            .roles
+            .iter()
+            .find(|r| r.role == "migration_engineer")
+            .unwrap();
+
+        assert_eq!(migration.hours, 2.0);
+        assert_eq!(migration.cost, 200.0);
+        // Unpriced roles contribute hours but no cost
+        assert_eq!(estimate.subtotal, 200.0);
+        assert_eq!(estimate.contingency, 40.0);
+        assert_eq!(estimate.total_cost, 240.0);
+    }
+}
@@ -0,0 +1,122 @@
+//! Feature Flag Service
+//!
+//! Flags are defined in code ([`FeatureFlag`]) with a default, and are
+//! `features.*` keys in the settings hierarchy. Turning a flag on for one
+//! tenant is a tenant-scope override set through the settings API; a user
+//! override wins over the tenant's, e.g. to let one tester in early.
+
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::settings::{FeatureFlag, FeatureFlagState, ResolvedSetting, SettingContext};
+use crate::services::settings_service::{SettingsError, SettingsService};
+
+pub struct FeatureFlagService {
+    settings: SettingsService,
+}
+
+impl FeatureFlagService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            settings: SettingsService::new(db),
+        }
+    }
+
+    /// Every flag for the caller, optionally within a project they can see
+    pub async fn evaluate_all(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: Option<&str>,
+    ) -> Result<Vec<FeatureFlagState>, SettingsError> {
+        let context = self.settings.context_for(user, project_id).await?;
+        let resolved = self.settings.resolve_all(&context).await?;
+        Ok(resolved
+            .into_iter()
+            .filter_map(|setting| FeatureFlag::from_key(setting.key).map(|flag| flag_state(flag, setting)))
+            .collect())
+    }
+
+    /// Whether one flag is on for the caller's tenant and user
+    pub async fn is_enabled(&self, user: &AuthenticatedUser, flag: FeatureFlag) -> Result<bool, SettingsError> {
+        let context = SettingContext {
+            tenant_id: user.tenant_id.clone(),
+            project_id: None,
+            user_id: Some(user.user_id.clone()),
+        };
+        let resolved = self.settings.resolve(flag.key(), &context).await?;
+        Ok(flag_state(flag, resolved).enabled)
+    }
+}
+
+fn flag_state(flag: FeatureFlag, setting: ResolvedSetting) -> FeatureFlagState {
+    FeatureFlagState {
+        flag,
+        enabled: setting.value.as_bool().unwrap_or_else(|| flag.default_enabled()),
+        source: setting.source,
+        source_id: setting.source_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::{SettingOverride, SettingScope, SettingSource};
+    use crate::services::settings_service::resolve_layers;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn context() -> SettingContext {
+        SettingContext {
+            tenant_id: Some("tenants:acme".to_string()),
+            project_id: None,
+            user_id: Some("users:alice".to_string()),
+        }
+    }
+
+    fn stored(scope: SettingScope, scope_id: &str, flag: FeatureFlag, enabled: bool) -> SettingOverride {
+        SettingOverride {
+            id: None,
+            scope,
+            scope_id: scope_id.to_string(),
+            key: flag.key(),
+            value: json!(enabled),
+            updated_by: "users:admin".to_string(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn evaluate(flag: FeatureFlag, overrides: &[SettingOverride]) -> FeatureFlagState {
+        flag_state(flag, resolve_layers(flag.key(), &context(), overrides, None))
+    }
+
+    #[test]
+    fn test_risky_features_ship_dark() {
+        let state = evaluate(FeatureFlag::AiAssistant, &[]);
+        assert!(!state.enabled);
+        assert_eq!(state.source, SettingSource::Default);
+        assert!(evaluate(FeatureFlag::HardwarePool, &[]).enabled);
+    }
+
+    #[test]
+    fn test_tenant_rollout_and_user_override() {
+        let tenant_on = stored(SettingScope::Tenant, "tenants:acme", FeatureFlag::AiAssistant, true);
+        let other_tenant = stored(SettingScope::Tenant, "tenants:other", FeatureFlag::AutoPlacementV2, true);
+        let user_off = stored(SettingScope::User, "users:alice", FeatureFlag::AiAssistant, false);
+
+        let state = evaluate(FeatureFlag::AiAssistant, &[tenant_on.clone()]);
+        assert!(state.enabled);
+        assert_eq!(state.source, SettingSource::Tenant);
+
+        assert!(!evaluate(FeatureFlag::AiAssistant, &[tenant_on, user_off]).enabled);
+        assert!(!evaluate(FeatureFlag::AutoPlacementV2, &[other_tenant]).enabled);
+    }
+
+    #[test]
+    fn test_every_flag_is_a_feature_setting() {
+        for flag in FeatureFlag::ALL {
+            assert_eq!(FeatureFlag::from_key(flag.key()), Some(flag));
+            assert!(flag.key().as_str().starts_with("features."));
+        }
+    }
+}
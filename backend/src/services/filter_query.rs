@@ -0,0 +1,495 @@
+// Archer ITSM - Filter Spec Compiler
+// Validates a FilterGroup against a per-entity field whitelist and compiles it
+// to a SurrealQL WHERE clause with every user-supplied value bound as a parameter
+
+use chrono::TimeZone;
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::{Database, TracedQuery};
+use crate::models::saved_view::*;
+
+/// Deepest allowed nesting of groups
+pub const MAX_DEPTH: usize = 4;
+/// Most leaf conditions allowed in one filter
+pub const MAX_CONDITIONS: usize = 50;
+
+#[derive(Debug, Error)]
+pub enum FilterError {
+    #[error("Unknown filter field '{0}'")]
+    UnknownField(String),
+
+    #[error("Operator '{operator:?}' is not supported on field '{field}'")]
+    UnsupportedOperator { field: String, operator: FilterOperator },
+
+    #[error("Invalid value for '{field}': {reason}")]
+    InvalidValue { field: String, reason: String },
+
+    #[error("Filter is too complex: {0}")]
+    TooComplex(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for FilterError {
+    fn from(e: surrealdb::Error) -> Self {
+        FilterError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// FIELD WHITELISTS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Text,
+    Number,
+    DateTime,
+    List,
+}
+
+const TICKET_FIELDS: &[(&str, FieldKind)] = &[
+    ("title", FieldKind::Text),
+    ("description", FieldKind::Text),
+    ("type", FieldKind::Text),
+    ("priority", FieldKind::Text),
+    ("status", FieldKind::Text),
+    ("assignee", FieldKind::Text),
+    ("assigned_group", FieldKind::Text),
+    ("created_by", FieldKind::Text),
+    ("category", FieldKind::Text),
+    ("subcategory", FieldKind::Text),
+    ("impact", FieldKind::Text),
+    ("urgency", FieldKind::Text),
+    ("source", FieldKind::Text),
+    ("tier", FieldKind::Text),
+    ("tags", FieldKind::List),
+    ("watchers", FieldKind::List),
+    ("access_count", FieldKind::Number),
+    ("created_at", FieldKind::DateTime),
+    ("updated_at", FieldKind::DateTime),
+    ("sla_breach_at", FieldKind::DateTime),
+    ("response_due", FieldKind::DateTime),
+    ("resolution_due", FieldKind::DateTime),
+    ("resolved_at", FieldKind::DateTime),
+    ("closed_at", FieldKind::DateTime),
+];
+
+const CI_FIELDS: &[(&str, FieldKind)] = &[
+    ("ci_id", FieldKind::Text),
+    ("name", FieldKind::Text),
+    ("description", FieldKind::Text),
+    ("ci_class", FieldKind::Text),
+    ("ci_type", FieldKind::Text),
+    ("status", FieldKind::Text),
+    ("criticality", FieldKind::Text),
+    ("environment", FieldKind::Text),
+    ("location", FieldKind::Text),
+    ("owner_id", FieldKind::Text),
+    ("owner_name", FieldKind::Text),
+    ("support_group", FieldKind::Text),
+    ("vendor", FieldKind::Text),
+    ("model", FieldKind::Text),
+    ("serial_number", FieldKind::Text),
+    ("version", FieldKind::Text),
+    ("ip_address", FieldKind::Text),
+    ("fqdn", FieldKind::Text),
+    ("discovery_source", FieldKind::Text),
+    ("tags", FieldKind::List),
+    ("created_at", FieldKind::DateTime),
+    ("updated_at", FieldKind::DateTime),
+    ("last_discovered", FieldKind::DateTime),
+    ("install_date", FieldKind::DateTime),
+    ("warranty_expiry", FieldKind::DateTime),
+    ("end_of_life", FieldKind::DateTime),
+];
+
+fn fields(entity: FilterEntity) -> &'static [(&'static str, FieldKind)] {
+    match entity {
+        FilterEntity::Tickets => TICKET_FIELDS,
+        FilterEntity::ConfigurationItems => CI_FIELDS,
+    }
+}
+
+fn field_kind(entity: FilterEntity, field: &str) -> Result<FieldKind, FilterError> {
+    fields(entity)
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, kind)| *kind)
+        .ok_or_else(|| FilterError::UnknownField(field.to_string()))
+}
+
+/// Field names a filter may reference, for the UI's field picker
+pub fn filterable_fields(entity: FilterEntity) -> Vec<&'static str> {
+    fields(entity).iter().map(|(name, _)| *name).collect()
+}
+
+// ============================================================================
+// COMPILER
+// ============================================================================
+
+/// WHERE clause plus the parameters it references
+#[derive(Debug, Default)]
+pub struct CompiledFilter {
+    pub where_clause: String,
+    pub bindings: Vec<(String, JsonValue)>,
+}
+
+struct Compiler {
+    entity: FilterEntity,
+    bindings: Vec<(String, JsonValue)>,
+    conditions: usize,
+}
+
+/// Compile a filter; an empty group matches everything
+pub fn compile(entity: FilterEntity, filter: &FilterGroup) -> Result<CompiledFilter, FilterError> {
+    let mut compiler = Compiler {
+        entity,
+        bindings: Vec::new(),
+        conditions: 0,
+    };
+    let where_clause = compiler.group(filter, 1)?.unwrap_or_else(|| "true".to_string());
+    Ok(CompiledFilter {
+        where_clause,
+        bindings: compiler.bindings,
+    })
+}
+
+impl Compiler {
+    fn group(&mut self, group: &FilterGroup, depth: usize) -> Result<Option<String>, FilterError> {
+        if depth > MAX_DEPTH {
+            return Err(FilterError::TooComplex(format!(
+                "groups may be nested at most {} deep",
+                MAX_DEPTH
+            )));
+        }
+
+        let mut parts = Vec::new();
+        for node in &group.conditions {
+            let part = match node {
+                FilterNode::Group(inner) => self.group(inner, depth + 1)?,
+                FilterNode::Condition(condition) => Some(self.condition(condition)?),
+            };
+            parts.extend(part);
+        }
+
+        let joiner = match group.op {
+            LogicalOperator::And => " AND ",
+            LogicalOperator::Or => " OR ",
+        };
+        Ok(match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => Some(format!("({})", parts.join(joiner))),
+        })
+    }
+
+    fn bind(&mut self, value: JsonValue) -> String {
+        let name = format!("f{}", self.bindings.len());
+        self.bindings.push((name.clone(), value));
+        format!("${}", name)
+    }
+
+    fn condition(&mut self, condition: &FilterCondition) -> Result<String, FilterError> {
+        self.conditions += 1;
+        if self.conditions > MAX_CONDITIONS {
+            return Err(FilterError::TooComplex(format!(
+                "at most {} conditions are allowed",
+                MAX_CONDITIONS
+            )));
+        }
+
+        let field = condition.field.as_str();
+        let kind = field_kind(self.entity, field)?;
+        let unsupported = || FilterError::UnsupportedOperator {
+            field: field.to_string(),
+            operator: condition.operator,
+        };
+        let invalid = |reason: &str| FilterError::InvalidValue {
+            field: field.to_string(),
+            reason: reason.to_string(),
+        };
+
+        use FilterOperator::*;
+        match condition.operator {
+            IsEmpty | IsNotEmpty => {
+                let negate = condition.operator == IsNotEmpty;
+                return Ok(match (kind, negate) {
+                    (FieldKind::Text | FieldKind::List, false) => format!("!{}", field),
+                    (FieldKind::Text | FieldKind::List, true) => format!("!!{}", field),
+                    (_, false) => format!("{} = NONE", field),
+                    (_, true) => format!("{} != NONE", field),
+                });
+            }
+            In | NotIn => {
+                let items = condition
+                    .value
+                    .as_array()
+                    .filter(|items| !items.is_empty() && items.iter().all(is_scalar))
+                    .ok_or_else(|| invalid("expected a non-empty array of values"))?;
+                let keyword = match (kind, condition.operator) {
+                    (FieldKind::List, In) => "CONTAINSANY",
+                    (FieldKind::List, _) => "CONTAINSNONE",
+                    (FieldKind::DateTime, _) => return Err(unsupported()),
+                    (_, In) => "INSIDE",
+                    (_, _) => "NOTINSIDE",
+                };
+                let param = self.bind(JsonValue::Array(items.clone()));
+                return Ok(format!("{} {} {}", field, keyword, param));
+            }
+            _ => {}
+        }
+
+        if !is_scalar(&condition.value) {
+            return Err(invalid("expected a single value"));
+        }
+
+        match kind {
+            FieldKind::Text => {
+                let text = || {
+                    condition
+                        .value
+                        .as_str()
+                        .map(|s| JsonValue::String(s.to_string()))
+                        .ok_or_else(|| invalid("expected a string"))
+                };
+                Ok(match condition.operator {
+                    Eq => format!("{} = {}", field, self.bind(condition.value.clone())),
+                    Ne => format!("{} != {}", field, self.bind(condition.value.clone())),
+                    Contains => format!(
+                        "string::lowercase({} ?? '') CONTAINS string::lowercase({})",
+                        field,
+                        self.bind(text()?)
+                    ),
+                    NotContains => format!(
+                        "string::lowercase({} ?? '') CONTAINSNOT string::lowercase({})",
+                        field,
+                        self.bind(text()?)
+                    ),
+                    StartsWith => format!(
+                        "string::starts_with(string::lowercase({} ?? ''), string::lowercase({}))",
+                        field,
+                        self.bind(text()?)
+                    ),
+                    _ => return Err(unsupported()),
+                })
+            }
+            FieldKind::List => match condition.operator {
+                Contains => Ok(format!("{} INSIDE {}", self.bind(condition.value.clone()), field)),
+                NotContains => Ok(format!("{} NOTINSIDE {}", self.bind(condition.value.clone()), field)),
+                _ => Err(unsupported()),
+            },
+            FieldKind::Number => {
+                if !condition.value.is_number() {
+                    return Err(invalid("expected a number"));
+                }
+                let op = comparison(condition.operator).ok_or_else(unsupported)?;
+                Ok(format!("{} {} {}", field, op, self.bind(condition.value.clone())))
+            }
+            FieldKind::DateTime => {
+                let op = comparison(condition.operator).ok_or_else(unsupported)?;
+                let raw = condition
+                    .value
+                    .as_str()
+                    .ok_or_else(|| invalid("expected an RFC 3339 date or a relative offset like -7d"))?;
+                let operand = match relative_offset(raw) {
+                    Some(expr) => expr,
+                    None => {
+                        let parsed = chrono::DateTime::parse_from_rfc3339(raw)
+                            .map(|d| d.with_timezone(&chrono::Utc))
+                            .or_else(|_| {
+                                chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                                    .map(|d| chrono::Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0).unwrap()))
+                            })
+                            .map_err(|_| {
+                                invalid("expected an RFC 3339 date or a relative offset like -7d")
+                            })?;
+                        format!("<datetime> {}", self.bind(JsonValue::String(parsed.to_rfc3339())))
+                    }
+                };
+                Ok(format!("{} {} {}", field, op, operand))
+            }
+        }
+    }
+}
+
+fn is_scalar(value: &JsonValue) -> bool {
+    matches!(value, JsonValue::String(_) | JsonValue::Number(_) | JsonValue::Bool(_))
+}
+
+fn comparison(operator: FilterOperator) -> Option<&'static str> {
+    match operator {
+        FilterOperator::Eq => Some("="),
+        FilterOperator::Ne => Some("!="),
+        FilterOperator::Gt => Some(">"),
+        FilterOperator::Gte => Some(">="),
+        FilterOperator::Lt => Some("<"),
+        FilterOperator::Lte => Some("<="),
+        _ => None,
+    }
+}
+
+/// `-7d`, `+12h`, `-2w` → `time::now() - 7d`; digits are validated so the literal is safe to inline
+fn relative_offset(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let (sign, rest) = match raw.chars().next()? {
+        '-' => ("-", &raw[1..]),
+        '+' => ("+", &raw[1..]),
+        _ => return None,
+    };
+    let unit = rest.chars().last()?;
+    let amount = &rest[..rest.len() - unit.len_utf8()];
+    if amount.is_empty() || amount.len() > 6 || !amount.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    matches!(unit, 'm' | 'h' | 'd' | 'w').then(|| format!("time::now() {} {}{}", sign, amount, unit))
+}
+
+/// Validated ORDER BY clause; defaults to newest first
+pub fn sort_clause(
+    entity: FilterEntity,
+    sort_by: Option<&str>,
+    order: SortOrder,
+) -> Result<String, FilterError> {
+    let field = sort_by.unwrap_or("created_at");
+    if field_kind(entity, field)? == FieldKind::List {
+        return Err(FilterError::InvalidValue {
+            field: field.to_string(),
+            reason: "list fields cannot be sorted".to_string(),
+        });
+    }
+    let direction = match order {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    Ok(format!("ORDER BY {} {}", field, direction))
+}
+
+// ============================================================================
+// EXECUTION
+// ============================================================================
+
+/// Run a filter query against the entity's table, scoped to the caller's tenant
+pub async fn run_filter_query<T: DeserializeOwned>(
+    db: &Database,
+    entity: FilterEntity,
+    request: &FilterQueryRequest,
+    tenant_id: Option<&str>,
+) -> Result<FilterQueryResponse<T>, FilterError> {
+    let page = request.page.unwrap_or(1).max(1);
+    let page_size = request.page_size.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * page_size;
+
+    let compiled = match &request.filter {
+        Some(filter) => compile(entity, filter)?,
+        None => CompiledFilter {
+            where_clause: "true".to_string(),
+            ..Default::default()
+        },
+    };
+    let mut where_clause = compiled.where_clause;
+    if tenant_id.is_some() {
+        where_clause = format!("({}) AND tenant_id = $tenant", where_clause);
+    }
+    let order = sort_clause(entity, request.sort_by.as_deref(), request.sort_order)?;
+
+    let sql = format!(
+        "SELECT count() FROM {table} WHERE {where_clause} GROUP ALL; \
+         SELECT * FROM {table} WHERE {where_clause} {order} LIMIT {page_size} START {offset};",
+        table = entity.table(),
+    );
+
+    let mut query = db.query(sql);
+    for (name, value) in compiled.bindings {
+        query = query.bind((name, value));
+    }
+    if let Some(tenant) = tenant_id {
+        query = query.bind(("tenant", Thing::from(("tenants", tenant))));
+    }
+
+    let mut response = query.traced("filter_query.run").await?;
+    let count: Vec<JsonValue> = response.take(0)?;
+    let items: Vec<T> = response.take(1)?;
+    let total = count
+        .first()
+        .and_then(|v| v.get("count"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    Ok(FilterQueryResponse {
+        items,
+        total,
+        page,
+        page_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(spec: JsonValue) -> FilterGroup {
+        serde_json::from_value(spec).unwrap()
+    }
+
+    #[test]
+    fn test_compile_nested_groups_binds_values() {
+        let filter = parse(json!({
+            "op": "AND",
+            "conditions": [
+                { "field": "status", "operator": "in", "value": ["NEW", "IN_PROGRESS"] },
+                { "op": "OR", "conditions": [
+                    { "field": "priority", "operator": "eq", "value": "P1" },
+                    { "field": "tags", "operator": "contains", "value": "vip" }
+                ]},
+                { "field": "created_at", "operator": "gte", "value": "-7d" }
+            ]
+        }));
+
+        let compiled = compile(FilterEntity::Tickets, &filter).unwrap();
+        assert_eq!(
+            compiled.where_clause,
+            "(status INSIDE $f0 AND (priority = $f1 OR $f2 INSIDE tags) AND created_at >= time::now() - 7d)"
+        );
+        assert_eq!(compiled.bindings.len(), 3);
+        assert_eq!(compiled.bindings[1], ("f1".to_string(), json!("P1")));
+    }
+
+    #[test]
+    fn test_compile_rejects_unknown_fields_and_bad_operators() {
+        let unknown = parse(json!({ "conditions": [
+            { "field": "password_hash", "operator": "eq", "value": "x" }
+        ]}));
+        assert!(matches!(
+            compile(FilterEntity::Tickets, &unknown),
+            Err(FilterError::UnknownField(_))
+        ));
+
+        let bad_operator = parse(json!({ "conditions": [
+            { "field": "tags", "operator": "gt", "value": "x" }
+        ]}));
+        assert!(matches!(
+            compile(FilterEntity::ConfigurationItems, &bad_operator),
+            Err(FilterError::UnsupportedOperator { .. })
+        ));
+
+        let injection = parse(json!({ "conditions": [
+            { "field": "created_at", "operator": "gt", "value": "-1d; DELETE ticket" }
+        ]}));
+        assert!(compile(FilterEntity::Tickets, &injection).is_err());
+    }
+
+    #[test]
+    fn test_sort_clause_validates_field() {
+        assert_eq!(
+            sort_clause(FilterEntity::ConfigurationItems, Some("name"), SortOrder::Asc).unwrap(),
+            "ORDER BY name ASC"
+        );
+        assert!(sort_clause(FilterEntity::Tickets, Some("id; REMOVE TABLE ticket"), SortOrder::Asc).is_err());
+    }
+}
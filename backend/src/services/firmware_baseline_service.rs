@@ -0,0 +1,556 @@
+// Archer ITSM - Firmware Baseline Service
+// Maintains BIOS/firmware/driver baselines per hardware model and
+// hypervisor, imports vendor support matrices, compares destination cluster
+// nodes against them and raises remediation tasks before the build
+
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::models::firmware_baseline::*;
+use crate::models::project_models::{DestinationCluster, HardwarePool};
+use crate::database::TracedQuery;
+
+const TABLE: &str = "firmware_baseline";
+const TASK_TABLE: &str = "firmware_remediation_task";
+
+/// Node metadata key holding versions by component key
+pub const NODE_FIRMWARE_KEY: &str = "firmware";
+
+#[derive(Debug, Error)]
+pub enum FirmwareBaselineError {
+    #[error("Firmware baseline not found")]
+    NotFound,
+
+    #[error("Destination cluster not found")]
+    ClusterNotFound,
+
+    #[error("Hardware node not found")]
+    NodeNotFound,
+
+    #[error("Remediation task not found")]
+    TaskNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for FirmwareBaselineError {
+    fn from(e: surrealdb::Error) -> Self {
+        FirmwareBaselineError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct FirmwareBaselineService {
+    db: Database,
+}
+
+impl FirmwareBaselineService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    // =========================================================================
+    // BASELINES
+    // =========================================================================
+
+    pub async fn list_baselines(&self) -> Result<Vec<FirmwareBaseline>, FirmwareBaselineError> {
+        Ok(self
+            .db
+            .query("SELECT * FROM firmware_baseline ORDER BY vendor, model, hypervisor")
+            .traced("firmware_baseline_service.list_baselines")
+            .await?
+            .take(0)?)
+    }
+
+    pub async fn get_baseline(&self, id: &str) -> Result<FirmwareBaseline, FirmwareBaselineError> {
+        let baseline: Option<FirmwareBaseline> = self.db.select((TABLE, id)).await?;
+        baseline.ok_or(FirmwareBaselineError::NotFound)
+    }
+
+    pub async fn create_baseline(
+        &self,
+        request: CreateFirmwareBaselineRequest,
+    ) -> Result<FirmwareBaseline, FirmwareBaselineError> {
+        validate_request(&request)?;
+        let existing = self.list_baselines().await?;
+        if existing
+            .iter()
+            .any(|b| b.applies_to(&request.vendor, &request.model, &request.hypervisor))
+        {
+            return Err(FirmwareBaselineError::Validation(format!(
+                "A baseline for {} {} on {} already exists",
+                request.vendor, request.model, request.hypervisor
+            )));
+        }
+        self.insert(request).await
+    }
+
+    pub async fn update_baseline(
+        &self,
+        id: &str,
+        request: UpdateFirmwareBaselineRequest,
+    ) -> Result<FirmwareBaseline, FirmwareBaselineError> {
+        let mut baseline = self.get_baseline(id).await?;
+        if let Some(components) = request.components {
+            validate_components(&components)?;
+            baseline.components = components;
+        }
+        if let Some(source) = request.source {
+            baseline.source = Some(source);
+        }
+        baseline.updated_at = Utc::now();
+        let updated: Option<FirmwareBaseline> = self.db.update((TABLE, id)).content(baseline).await?;
+        updated.ok_or(FirmwareBaselineError::NotFound)
+    }
+
+    pub async fn delete_baseline(&self, id: &str) -> Result<(), FirmwareBaselineError> {
+        self.get_baseline(id).await?;
+        let _: Option<FirmwareBaseline> = self.db.delete((TABLE, id)).await?;
+        Ok(())
+    }
+
+    /// Import a vendor support matrix. Rows for a model/hypervisor that
+    /// already has a baseline replace the versions of the components they
+    /// name and leave the others alone.
+    pub async fn import_matrix(
+        &self,
+        request: ImportFirmwareMatrixRequest,
+    ) -> Result<FirmwareMatrixImportResult, FirmwareBaselineError> {
+        let (drafts, errors) = match request.format {
+            MatrixFormat::Csv => parse_csv_matrix(&request.content),
+            MatrixFormat::Json => serde_json::from_str::<Vec<CreateFirmwareBaselineRequest>>(&request.content)
+                .map(|drafts| (drafts, Vec::new()))
+                .map_err(|e| FirmwareBaselineError::Validation(format!("Invalid matrix JSON: {}", e)))?,
+        };
+
+        let mut result = FirmwareMatrixImportResult {
+            errors,
+            ..Default::default()
+        };
+        let mut existing = self.list_baselines().await?;
+        for mut draft in drafts {
+            if draft.source.is_none() {
+                draft.source = request.source.clone();
+            }
+            if let Err(e) = validate_request(&draft) {
+                result.errors.push(format!("{} {}: {}", draft.vendor, draft.model, e));
+                continue;
+            }
+
+            match existing
+                .iter()
+                .position(|b| b.applies_to(&draft.vendor, &draft.model, &draft.hypervisor))
+            {
+                Some(index) => {
+                    let baseline = &mut existing[index];
+                    for component in draft.components {
+                        match baseline.components.iter_mut().find(|c| c.component == component.component) {
+                            Some(current) => current.version = component.version,
+                            None => baseline.components.push(component),
+                        }
+                    }
+                    if draft.source.is_some() {
+                        baseline.source = draft.source;
+                    }
+                    baseline.updated_at = Utc::now();
+                    let id = baseline.id.clone().ok_or(FirmwareBaselineError::NotFound)?;
+                    let _: Option<FirmwareBaseline> = self.db.update(id).content(baseline.clone()).await?;
+                    result.updated += 1;
+                }
+                None => {
+                    existing.push(self.insert(draft).await?);
+                    result.created += 1;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    async fn insert(&self, request: CreateFirmwareBaselineRequest) -> Result<FirmwareBaseline, FirmwareBaselineError> {
+        let now = Utc::now();
+        let baseline = FirmwareBaseline {
+            id: None,
+            vendor: request.vendor.trim().to_string(),
+            model: request.model.trim().to_string(),
+            hypervisor: request.hypervisor.trim().to_lowercase(),
+            components: request.components,
+            source: request.source,
+            created_at: now,
+            updated_at: now,
+        };
+        let created: Vec<FirmwareBaseline> = self.db.create(TABLE).content(baseline).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| FirmwareBaselineError::DatabaseError("Failed to create baseline".to_string()))
+    }
+
+    // =========================================================================
+    // NODE VERSIONS & COMPLIANCE
+    // =========================================================================
+
+    /// Record versions entered for (or discovered on) a hardware node
+    pub async fn record_node_firmware(
+        &self,
+        node_id: &str,
+        request: RecordNodeFirmwareRequest,
+    ) -> Result<HardwarePool, FirmwareBaselineError> {
+        let node: Option<HardwarePool> = self.db.select(("hardware_pool", node_id)).await?;
+        let mut node = node.ok_or(FirmwareBaselineError::NodeNotFound)?;
+
+        let mut firmware = node
+            .metadata
+            .get(NODE_FIRMWARE_KEY)
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        for (component, version) in request.versions {
+            firmware.insert(component.key().to_string(), json!(version.trim()));
+        }
+        node.metadata.insert(NODE_FIRMWARE_KEY.to_string(), Value::Object(firmware));
+
+        let updated: Option<HardwarePool> = self
+            .db
+            .update(("hardware_pool", node_id))
+            .merge(json!({ "metadata": node.metadata, "updated_at": Utc::now() }))
+            .await?;
+        updated.ok_or(FirmwareBaselineError::NodeNotFound)
+    }
+
+    /// Compare every node of a cluster with its baseline. With
+    /// `create_tasks`, open a remediation task for each outdated or missing
+    /// component that does not already have one.
+    pub async fn cluster_compliance(
+        &self,
+        cluster_id: &str,
+        create_tasks: bool,
+    ) -> Result<ClusterFirmwareReport, FirmwareBaselineError> {
+        let cluster: Option<DestinationCluster> = self.db.select(("destination_cluster", cluster_id)).await?;
+        let cluster = cluster.ok_or(FirmwareBaselineError::ClusterNotFound)?;
+        let hypervisor = serde_json::to_value(&cluster.hypervisor)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let baselines = self.list_baselines().await?;
+        let open_tasks = self.list_tasks(cluster_id).await?;
+
+        let mut nodes = Vec::new();
+        let mut tasks_created = 0;
+        for node_thing in &cluster.nodes {
+            let node: Option<HardwarePool> = self.db.select(node_thing.clone()).await?;
+            let Some(node) = node else { continue };
+            let baseline = baselines
+                .iter()
+                .find(|b| b.applies_to(&node.vendor, &node.model, &hypervisor));
+            let findings = baseline
+                .map(|b| compare_node(b, &installed_versions(&node)))
+                .unwrap_or_default();
+
+            if create_tasks {
+                for finding in findings.iter().filter(|f| f.status.needs_remediation()) {
+                    let exists = open_tasks.iter().any(|t| {
+                        t.status == RemediationStatus::Open && &t.node_id == node_thing && t.component == finding.component
+                    });
+                    if !exists {
+                        self.create_task(&cluster, node_thing, &node.asset_tag, finding).await?;
+                        tasks_created += 1;
+                    }
+                }
+            }
+
+            nodes.push(NodeCompliance {
+                node_id: node_thing.id.to_raw(),
+                asset_tag: node.asset_tag,
+                vendor: node.vendor,
+                model: node.model,
+                baseline_id: baseline.and_then(|b| b.id.as_ref()).map(|id| id.id.to_raw()),
+                findings,
+            });
+        }
+
+        Ok(ClusterFirmwareReport {
+            cluster_id: cluster_id.to_string(),
+            cluster_name: cluster.name,
+            hypervisor,
+            compliant: !nodes.is_empty()
+                && nodes.iter().all(|n| {
+                    n.baseline_id.is_some() && !n.findings.iter().any(|f| f.status.needs_remediation())
+                }),
+            nodes,
+            tasks_created,
+        })
+    }
+
+    // =========================================================================
+    // REMEDIATION TASKS
+    // =========================================================================
+
+    pub async fn list_tasks(&self, cluster_id: &str) -> Result<Vec<FirmwareRemediationTask>, FirmwareBaselineError> {
+        Ok(self
+            .db
+            .query("SELECT * FROM firmware_remediation_task WHERE cluster_id = $cluster ORDER BY created_at ASC")
+            .bind(("cluster", Thing::from(("destination_cluster", cluster_id))))
+            .traced("firmware_baseline_service.list_tasks")
+            .await?
+            .take(0)?)
+    }
+
+    pub async fn update_task(
+        &self,
+        task_id: &str,
+        request: UpdateRemediationTaskRequest,
+    ) -> Result<FirmwareRemediationTask, FirmwareBaselineError> {
+        let task: Option<FirmwareRemediationTask> = self.db.select((TASK_TABLE, task_id)).await?;
+        let mut task = task.ok_or(FirmwareBaselineError::TaskNotFound)?;
+        task.status = request.status;
+        task.completed_at = (request.status == RemediationStatus::Done).then(Utc::now);
+
+        let updated: Option<FirmwareRemediationTask> = self.db.update((TASK_TABLE, task_id)).content(task).await?;
+        updated.ok_or(FirmwareBaselineError::TaskNotFound)
+    }
+
+    async fn create_task(
+        &self,
+        cluster: &DestinationCluster,
+        node: &Thing,
+        asset_tag: &str,
+        finding: &FirmwareFinding,
+    ) -> Result<(), FirmwareBaselineError> {
+        let task = FirmwareRemediationTask {
+            id: None,
+            cluster_id: cluster.id.clone().ok_or(FirmwareBaselineError::ClusterNotFound)?,
+            node_id: node.clone(),
+            asset_tag: asset_tag.to_string(),
+            component: finding.component,
+            installed_version: finding.installed.clone(),
+            target_version: finding.expected.clone(),
+            status: RemediationStatus::Open,
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+        let _: Vec<FirmwareRemediationTask> = self.db.create(TASK_TABLE).content(task).await?;
+        Ok(())
+    }
+}
+
+/// Versions recorded on a node, by component
+fn installed_versions(node: &HardwarePool) -> BTreeMap<FirmwareComponent, String> {
+    node.metadata
+        .get(NODE_FIRMWARE_KEY)
+        .and_then(Value::as_object)
+        .map(|firmware| {
+            firmware
+                .iter()
+                .filter_map(|(key, version)| Some((FirmwareComponent::parse(key)?, version.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check each baseline component against what the node runs
+pub fn compare_node(
+    baseline: &FirmwareBaseline,
+    installed: &BTreeMap<FirmwareComponent, String>,
+) -> Vec<FirmwareFinding> {
+    baseline
+        .components
+        .iter()
+        .map(|expected| {
+            let installed = installed.get(&expected.component).filter(|v| !v.is_empty());
+            let status = match installed {
+                None => ComplianceStatus::Missing,
+                Some(version) => match compare_versions(version, &expected.version) {
+                    Ordering::Less => ComplianceStatus::Outdated,
+                    Ordering::Equal => ComplianceStatus::Compliant,
+                    Ordering::Greater => ComplianceStatus::Newer,
+                },
+            };
+            FirmwareFinding {
+                component: expected.component,
+                expected: expected.version.clone(),
+                installed: installed.cloned(),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Compare dotted versions numerically segment by segment ("2.10.1" is
+/// newer than "2.9"); non-numeric segments compare as text
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let segments = |v: &str| -> Vec<String> {
+        v.trim()
+            .trim_start_matches(['v', 'V'])
+            .split(['.', '-', '_'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (segments(a), segments(b));
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).map(String::as_str).unwrap_or("0");
+        let y = b.get(i).map(String::as_str).unwrap_or("0");
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.to_lowercase().cmp(&y.to_lowercase()),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Group support matrix rows (vendor, model, hypervisor, component,
+/// version) into one baseline per model and hypervisor
+fn parse_csv_matrix(content: &str) -> (Vec<CreateFirmwareBaselineRequest>, Vec<String>) {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+    let headers: Vec<String> = match reader.headers() {
+        Ok(headers) => headers
+            .iter()
+            .map(|h| h.trim_start_matches('\u{feff}').to_lowercase())
+            .collect(),
+        Err(e) => return (Vec::new(), vec![format!("Invalid CSV header: {}", e)]),
+    };
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let (Some(vendor), Some(model), Some(hypervisor), Some(component), Some(version)) = (
+        column("vendor"),
+        column("model"),
+        column("hypervisor"),
+        column("component"),
+        column("version"),
+    ) else {
+        return (
+            Vec::new(),
+            vec!["CSV needs vendor, model, hypervisor, component and version columns".to_string()],
+        );
+    };
+
+    let mut grouped: BTreeMap<(String, String, String), CreateFirmwareBaselineRequest> = BTreeMap::new();
+    let mut errors = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let line = index + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(format!("Line {}: {}", line, e));
+                continue;
+            }
+        };
+        let field = |i: usize| record.get(i).unwrap_or("").to_string();
+        let Some(parsed) = FirmwareComponent::parse(&field(component)) else {
+            errors.push(format!("Line {}: unknown component '{}'", line, field(component)));
+            continue;
+        };
+        if field(version).is_empty() {
+            errors.push(format!("Line {}: version is empty", line));
+            continue;
+        }
+
+        let key = (field(vendor).to_lowercase(), field(model).to_lowercase(), field(hypervisor).to_lowercase());
+        let draft = grouped.entry(key).or_insert_with(|| CreateFirmwareBaselineRequest {
+            vendor: field(vendor),
+            model: field(model),
+            hypervisor: field(hypervisor),
+            components: Vec::new(),
+            source: None,
+        });
+        draft.components.retain(|c| c.component != parsed);
+        draft.components.push(BaselineComponent {
+            component: parsed,
+            version: field(version),
+        });
+    }
+    (grouped.into_values().collect(), errors)
+}
+
+fn validate_request(request: &CreateFirmwareBaselineRequest) -> Result<(), FirmwareBaselineError> {
+    for (field, value) in [
+        ("vendor", &request.vendor),
+        ("model", &request.model),
+        ("hypervisor", &request.hypervisor),
+    ] {
+        if value.trim().is_empty() {
+            return Err(FirmwareBaselineError::Validation(format!("{} is required", field)));
+        }
+    }
+    validate_components(&request.components)
+}
+
+fn validate_components(components: &[BaselineComponent]) -> Result<(), FirmwareBaselineError> {
+    if components.is_empty() {
+        return Err(FirmwareBaselineError::Validation("At least one component is required".to_string()));
+    }
+    if let Some(c) = components.iter().find(|c| c.version.trim().is_empty()) {
+        return Err(FirmwareBaselineError::Validation(format!("{} version is empty", c.component.key())));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions_is_numeric() {
+        assert_eq!(compare_versions("2.10.1", "2.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("22.5.7", "22.31.6"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_csv_matrix_groups_rows_by_model() {
+        let csv = "Vendor,Model,Hypervisor,Component,Version\n\
+                   Dell,R760,vmware,BIOS,2.1.0\n\
+                   Dell,R760,vmware,NIC Firmware,22.31.6\n\
+                   Dell,R760,hyper-v,BIOS,2.1.0\n\
+                   Dell,R760,vmware,Flux Capacitor,1.0\n";
+        let (drafts, errors) = parse_csv_matrix(csv);
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(errors, vec!["Line 5: unknown component 'Flux Capacitor'"]);
+
+        let vmware = drafts.iter().find(|d| d.hypervisor == "vmware").unwrap();
+        assert_eq!(vmware.components.len(), 2);
+        assert_eq!(vmware.components[1].component, FirmwareComponent::NicFirmware);
+    }
+
+    #[test]
+    fn test_compare_node_flags_outdated_and_missing() {
+        let baseline = FirmwareBaseline {
+            id: None,
+            vendor: "Dell".to_string(),
+            model: "R760".to_string(),
+            hypervisor: "vmware".to_string(),
+            components: vec![
+                BaselineComponent { component: FirmwareComponent::Bios, version: "2.1.0".to_string() },
+                BaselineComponent { component: FirmwareComponent::NicFirmware, version: "22.31.6".to_string() },
+                BaselineComponent { component: FirmwareComponent::Bmc, version: "7.0".to_string() },
+            ],
+            source: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let installed: BTreeMap<FirmwareComponent, String> = [
+            (FirmwareComponent::Bios, "2.2.0".to_string()),
+            (FirmwareComponent::NicFirmware, "22.5.7".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        let statuses: Vec<ComplianceStatus> = compare_node(&baseline, &installed).iter().map(|f| f.status).collect();
+        assert_eq!(
+            statuses,
+            vec![ComplianceStatus::Newer, ComplianceStatus::Outdated, ComplianceStatus::Missing]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::models::guest_scan::{GuestConfig, GuestIpConfig, GuestMount, InstalledApplication};
+
+#[derive(Debug, Error)]
+pub enum GuestScanError {
+    #[error("Guest credential not found")]
+    CredentialNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Secret handling failed: {0}")]
+    Secret(String),
+
+    #[error("Could not reach the guest: {0}")]
+    Connection(String),
+
+    #[error("The guest rejected the credential")]
+    Unauthorized,
+
+    #[error("Scan script failed: {0}")]
+    Script(String),
+
+    #[error("Database error: {0}")]
+    Database(String),
+}
+
+impl From<surrealdb::Error> for GuestScanError {
+    fn from(e: surrealdb::Error) -> Self {
+        GuestScanError::Database(e.to_string())
+    }
+}
+
+impl From<anyhow::Error> for GuestScanError {
+    fn from(e: anyhow::Error) -> Self {
+        GuestScanError::Database(e.to_string())
+    }
+}
+
+/// Runs the scan script inside one guest
+#[async_trait]
+pub trait GuestCollector: Send + Sync {
+    async fn collect(&self, address: &str) -> Result<GuestConfig, GuestScanError>;
+}
+
+/// Prints the guest's configuration as one JSON object shaped like
+/// [`GuestConfig`]. Arrays are forced with `@()` so single entries are not
+/// unwrapped by `ConvertTo-Json`.
+pub const WINDOWS_SCRIPT: &str = r#"$ErrorActionPreference = 'SilentlyContinue'
+$keys = 'HKLM:\Software\Microsoft\Windows\CurrentVersion\Uninstall\*', 'HKLM:\Software\Wow6432Node\Microsoft\Windows\CurrentVersion\Uninstall\*'
+$apps = @(Get-ItemProperty $keys | Where-Object { $_.DisplayName -and -not $_.SystemComponent } | Sort-Object DisplayName -Unique | ForEach-Object {
+  [pscustomobject]@{ name = $_.DisplayName; version = $_.DisplayVersion; publisher = $_.Publisher } })
+$ips = @(Get-NetIPAddress -AddressFamily IPv4 | Where-Object { $_.PrefixOrigin -eq 'Manual' } | ForEach-Object {
+  $config = Get-NetIPConfiguration -InterfaceIndex $_.InterfaceIndex
+  [pscustomobject]@{ interface = $_.InterfaceAlias; address = $_.IPAddress; prefix_length = [int]$_.PrefixLength
+    gateway = @($config.IPv4DefaultGateway.NextHop)[0]
+    dns_servers = @($config.DNSServer | Where-Object { $_.AddressFamily -eq 2 } | ForEach-Object { $_.ServerAddresses }) } })
+$mounts = @(Get-CimInstance Win32_LogicalDisk | Where-Object { $_.DriveType -in 3, 4 } | ForEach-Object {
+  [pscustomobject]@{ mount_point = $_.DeviceID; source = $_.ProviderName; filesystem = $_.FileSystem
+    size_gb = [math]::Round($_.Size / 1GB, 1); used_gb = [math]::Round(($_.Size - $_.FreeSpace) / 1GB, 1)
+    network = ($_.DriveType -eq 4) } })
+[pscustomobject]@{ hostname = $env:COMPUTERNAME; os = (Get-CimInstance Win32_OperatingSystem).Caption
+  applications = $apps; static_ips = $ips; mounts = $mounts } | ConvertTo-Json -Depth 4 -Compress
+"#;
+
+/// Prints one tab-separated, tagged line per finding; parsed by
+/// [`parse_linux_output`]. Needs only sh, awk, ip and df.
+pub const LINUX_SCRIPT: &str = r#"printf 'HOST\t%s\n' "$(hostname)"
+[ -r /etc/os-release ] && . /etc/os-release && printf 'OS\t%s\n' "$PRETTY_NAME"
+if command -v dpkg-query >/dev/null 2>&1; then
+  dpkg-query -W -f='APP\t${Package}\t${Version}\t${Maintainer}\n' 2>/dev/null
+elif command -v rpm >/dev/null 2>&1; then
+  rpm -qa --qf 'APP\t%{NAME}\t%{VERSION}-%{RELEASE}\t%{VENDOR}\n' 2>/dev/null
+fi
+ip -o -4 addr show scope global 2>/dev/null | awk '{ print "ADDR\t" $2 "\t" $4 "\t" ($0 ~ / dynamic / ? "dynamic" : "static") }'
+ip -4 route show default 2>/dev/null | awk '{ print "GW\t" $5 "\t" $3 }'
+awk '/^nameserver/ { print "DNS\t" $2 }' /etc/resolv.conf 2>/dev/null
+df -PTk -x tmpfs -x devtmpfs -x squashfs -x overlay 2>/dev/null | awk 'NR > 1 { print "MOUNT\t" $7 "\t" $1 "\t" $2 "\t" $3 "\t" $4 }'
+exit 0
+"#;
+
+const NETWORK_FILESYSTEMS: &[&str] = &["nfs", "nfs4", "cifs", "smb3", "smbfs", "glusterfs", "ceph", "fuse.sshfs"];
+
+/// Parse the JSON printed by [`WINDOWS_SCRIPT`]
+pub fn parse_windows_output(output: &str) -> Result<GuestConfig, GuestScanError> {
+    let json = output.trim_start_matches('\u{feff}').trim();
+    serde_json::from_str(json).map_err(|e| GuestScanError::Script(format!("unexpected output: {}", e)))
+}
+
+/// Parse the lines printed by [`LINUX_SCRIPT`]. Addresses marked dynamic
+/// are DHCP leases and skipped; the default gateway and resolvers are
+/// attached to the static addresses.
+pub fn parse_linux_output(output: &str) -> GuestConfig {
+    let mut config = GuestConfig::default();
+    let mut gateways: Vec<(String, String)> = Vec::new();
+    let mut dns_servers = Vec::new();
+    let kib_to_gb = |kib: &str| kib.parse::<f64>().map(|kib| (kib / 1_048_576.0 * 10.0).round() / 10.0).unwrap_or(0.0);
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let field = |i: usize| fields.get(i).map(|f| f.trim()).filter(|f| !f.is_empty());
+        match fields[0] {
+            "HOST" => config.hostname = field(1).map(str::to_string),
+            "OS" => config.os = field(1).map(str::to_string),
+            "APP" => {
+                if let Some(name) = field(1) {
+                    config.applications.push(InstalledApplication {
+                        name: name.to_string(),
+                        version: field(2).map(str::to_string),
+                        publisher: field(3).map(str::to_string),
+                    });
+                }
+            }
+            "ADDR" if field(3) == Some("static") => {
+                let (Some(interface), Some(cidr)) = (field(1), field(2)) else { continue };
+                let (address, prefix) = cidr.split_once('/').unwrap_or((cidr, ""));
+                config.static_ips.push(GuestIpConfig {
+                    interface: interface.to_string(),
+                    address: address.to_string(),
+                    prefix_length: prefix.parse().ok(),
+                    ..Default::default()
+                });
+            }
+            "GW" => {
+                if let (Some(interface), Some(gateway)) = (field(1), field(2)) {
+                    gateways.push((interface.to_string(), gateway.to_string()));
+                }
+            }
+            "DNS" => dns_servers.extend(field(1).map(str::to_string)),
+            "MOUNT" => {
+                let Some(mount_point) = field(1) else { continue };
+                let filesystem = field(3).map(str::to_string);
+                config.mounts.push(GuestMount {
+                    mount_point: mount_point.to_string(),
+                    source: field(2).map(str::to_string),
+                    network: filesystem.as_deref().is_some_and(|fs| NETWORK_FILESYSTEMS.contains(&fs)),
+                    filesystem,
+                    size_gb: field(4).map(kib_to_gb).unwrap_or(0.0),
+                    used_gb: field(5).map(kib_to_gb).unwrap_or(0.0),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    for ip in &mut config.static_ips {
+        ip.gateway = gateways.iter().find(|(interface, _)| *interface == ip.interface).map(|(_, gw)| gw.clone());
+        ip.dns_servers = dns_servers.clone();
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_linux_output_keeping_static_addresses_only() {
+        let output = "HOST\tdb01\n\
+            OS\tRocky Linux 9.3 (Blue Onyx)\n\
+            APP\tpostgresql-server\t15.4-1.el9\tRocky Enterprise Software Foundation\n\
+            APP\tbash\t5.1.8-6.el9\t\n\
+            ADDR\tens192\t10.20.0.15/24\tstatic\n\
+            ADDR\tens224\t192.168.50.9/24\tdynamic\n\
+            GW\tens192\t10.20.0.1\n\
+            DNS\t10.20.0.10\n\
+            MOUNT\t/\t/dev/mapper/rl-root\txfs\t52403200\t10485760\n\
+            MOUNT\t/backup\tnas01:/exports/backup\tnfs4\t1073741824\t536870912\n";
+
+        let config = parse_linux_output(output);
+
+        assert_eq!(config.hostname.as_deref(), Some("db01"));
+        assert_eq!(config.applications.len(), 2);
+        assert_eq!(config.applications[1].publisher, None);
+        assert_eq!(
+            config.static_ips,
+            vec![GuestIpConfig {
+                interface: "ens192".to_string(),
+                address: "10.20.0.15".to_string(),
+                prefix_length: Some(24),
+                gateway: Some("10.20.0.1".to_string()),
+                dns_servers: vec!["10.20.0.10".to_string()],
+            }]
+        );
+        assert_eq!((config.mounts[0].size_gb, config.mounts[0].used_gb), (50.0, 10.0));
+        assert!(!config.mounts[0].network);
+        assert!(config.mounts[1].network);
+        assert_eq!(config.mounts[1].source.as_deref(), Some("nas01:/exports/backup"));
+    }
+
+    #[test]
+    fn test_parses_windows_json_with_bom_and_nulls() {
+        let output = "\u{feff}{\"hostname\":\"APP01\",\"os\":\"Microsoft Windows Server 2019 Standard\",\
+            \"applications\":[{\"name\":\"Microsoft SQL Server 2019 (64-bit)\",\"version\":null,\"publisher\":\"Microsoft Corporation\"}],\
+            \"static_ips\":[{\"interface\":\"Ethernet0\",\"address\":\"10.20.0.21\",\"prefix_length\":24,\"gateway\":\"10.20.0.1\",\"dns_servers\":[\"10.20.0.10\"]}],\
+            \"mounts\":[{\"mount_point\":\"C:\",\"source\":null,\"filesystem\":\"NTFS\",\"size_gb\":99.5,\"used_gb\":40.2,\"network\":false}]}\r\n";
+
+        let config = parse_windows_output(output).unwrap();
+
+        assert_eq!(config.hostname.as_deref(), Some("APP01"));
+        assert_eq!(config.applications[0].version, None);
+        assert_eq!(config.static_ips[0].prefix_length, Some(24));
+        assert_eq!(config.mounts[0].mount_point, "C:");
+        assert!(parse_windows_output("Access is denied.").is_err());
+    }
+}
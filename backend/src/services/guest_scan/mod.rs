@@ -0,0 +1,17 @@
+//! Agentless in-guest scans
+//!
+//! Collectors sign in to migration wizard VMs with a project credential,
+//! WinRM for Windows and SSH for Linux, and run one read-only script that
+//! lists installed applications, static IP settings and mount points. The
+//! results feed strategy scoring and wave runbooks. Credentials are sealed
+//! with the secrets key like the other stored secrets.
+
+pub mod collector;
+pub mod service;
+pub mod ssh;
+pub mod winrm;
+
+pub use collector::{GuestCollector, GuestScanError};
+pub use service::GuestScanService;
+pub use ssh::SshCollector;
+pub use winrm::WinRmCollector;
@@ -0,0 +1,454 @@
+use chrono::Utc;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use super::collector::{GuestCollector, GuestScanError};
+use super::ssh::{SshCollector, SshSecret};
+use super::winrm::WinRmCollector;
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::guest_scan::*;
+use crate::models::migration_wizard_models::{MigrationWizardVM, StrategyRecommendation};
+use crate::models::timeline::RunbookStep;
+use crate::services::llm::SecretBox;
+use crate::services::migration_wizard_service::MigrationWizardService;
+
+const CREDENTIAL_TABLE: &str = "guest_credential";
+const SCAN_TABLE: &str = "guest_scan";
+/// Guests scanned at once; each scan holds a WinRM shell or ssh process
+const MAX_PARALLEL_SCANS: usize = 8;
+
+/// Applications that change how a VM should move: (lowercase name
+/// fragment, category, advice, confidence penalty). Each category counts
+/// once per VM.
+const APPLICATION_HINTS: &[(&str, &str, &str, f64)] = &[
+    ("sql server", "database", "database engine; stop writes or fail over for a consistent cutover", 5.0),
+    ("postgresql", "database", "database engine; stop writes or fail over for a consistent cutover", 5.0),
+    ("mysql", "database", "database engine; stop writes or fail over for a consistent cutover", 5.0),
+    ("mariadb", "database", "database engine; stop writes or fail over for a consistent cutover", 5.0),
+    ("mongodb", "database", "database engine; stop writes or fail over for a consistent cutover", 5.0),
+    ("oracle database", "database", "database engine; stop writes or fail over for a consistent cutover", 5.0),
+    ("exchange server", "mail", "move mailbox databases with a DAG switchover rather than with the VM", 10.0),
+    ("pacemaker", "cluster", "guest cluster; move its nodes together and plan for shared disks and floating IPs", 15.0),
+];
+
+pub struct GuestScanService {
+    db: Database,
+}
+
+impl GuestScanService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    // =========================================================================
+    // CREDENTIALS
+    // =========================================================================
+
+    pub async fn create_credential(
+        &self,
+        project_id: &str,
+        request: CreateGuestCredentialRequest,
+        created_by: &str,
+    ) -> Result<GuestCredentialView, GuestScanError> {
+        let (secret, private_key) = match (request.password, request.private_key) {
+            (Some(password), None) => (password, false),
+            (None, Some(key)) if request.protocol == GuestProtocol::Ssh => (key, true),
+            (None, Some(_)) => return Err(GuestScanError::Validation("private keys are for SSH only".to_string())),
+            _ => return Err(GuestScanError::Validation("set exactly one of password and private_key".to_string())),
+        };
+        let secret_encrypted = SecretBox::from_env()
+            .and_then(|secrets| secrets.encrypt(&secret))
+            .map_err(|e| GuestScanError::Secret(e.to_string()))?;
+
+        let credential = GuestCredential {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", project_id)),
+            name: request.name,
+            protocol: request.protocol,
+            username: request.username,
+            secret_encrypted,
+            private_key,
+            port: request.port,
+            verify_tls: request.verify_tls,
+            created_by: created_by.to_string(),
+            created_at: Utc::now(),
+        };
+        let created: Vec<GuestCredential> = self.db.create(CREDENTIAL_TABLE).content(credential).await?;
+        created
+            .first()
+            .map(GuestCredentialView::from)
+            .ok_or_else(|| GuestScanError::Database("No credential returned after creation".to_string()))
+    }
+
+    pub async fn list_credentials(&self, project_id: &str) -> Result<Vec<GuestCredentialView>, GuestScanError> {
+        let credentials: Vec<GuestCredential> = self
+            .db
+            .query("SELECT * FROM guest_credential WHERE project_id = $project ORDER BY name ASC")
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("guest_scan_service.list_credentials")
+            .await?
+            .take(0)?;
+        Ok(credentials.iter().map(GuestCredentialView::from).collect())
+    }
+
+    /// The credential when it belongs to the project
+    async fn get_credential(&self, project_id: &str, credential_id: &str) -> Result<GuestCredential, GuestScanError> {
+        let credential: Option<GuestCredential> = self.db.select((CREDENTIAL_TABLE, credential_id)).await?;
+        credential
+            .filter(|c| c.project_id.id.to_raw() == project_id)
+            .ok_or(GuestScanError::CredentialNotFound)
+    }
+
+    pub async fn delete_credential(&self, project_id: &str, credential_id: &str) -> Result<(), GuestScanError> {
+        self.get_credential(project_id, credential_id).await?;
+        let _: Option<GuestCredential> = self.db.delete((CREDENTIAL_TABLE, credential_id)).await?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // SCANS
+    // =========================================================================
+
+    /// Scan the selected VMs with one credential and keep each VM's latest
+    /// result. A VM that cannot be reached is recorded as failed rather
+    /// than failing the whole run.
+    pub async fn scan(&self, project_id: &str, request: GuestScanRequest) -> Result<GuestScanSummary, GuestScanError> {
+        let credential = self.get_credential(project_id, &request.credential_id).await?;
+        let vms = MigrationWizardService::new(self.db.clone()).get_project_vms(project_id, None).await?;
+        let selected = select_vms(&vms, &request.vm_ids)?;
+        let collector = collector(&credential)?;
+
+        let permits = Arc::new(Semaphore::new(MAX_PARALLEL_SCANS));
+        let mut running = JoinSet::new();
+        for vm in selected {
+            let collector = collector.clone();
+            let permits = permits.clone();
+            running.spawn(async move {
+                let address = guest_address(&vm);
+                let outcome = match &address {
+                    Some(address) => {
+                        let _permit = permits.acquire_owned().await;
+                        collector.collect(address).await
+                    }
+                    None => Err(GuestScanError::Validation("the VM has no IP address or DNS name".to_string())),
+                };
+                (vm, address, outcome)
+            });
+        }
+
+        let mut scans = Vec::new();
+        while let Some(joined) = running.join_next().await {
+            let (vm, address, outcome) = joined.map_err(|e| GuestScanError::Script(e.to_string()))?;
+            let Some(vm_id) = vm.id.clone() else { continue };
+            let (status, error, config) = match outcome {
+                Ok(config) => (GuestScanStatus::Succeeded, None, config),
+                Err(e) => (GuestScanStatus::Failed, Some(e.to_string()), GuestConfig::default()),
+            };
+            let scan = GuestScan {
+                id: None,
+                project_id: vm.project_id.clone(),
+                vm_name: vm.name.clone(),
+                vm_id: vm_id.clone(),
+                protocol: credential.protocol,
+                address,
+                status,
+                error,
+                config,
+                scanned_at: Utc::now(),
+            };
+            let saved: Option<GuestScan> = self
+                .db
+                .update((SCAN_TABLE, vm_id.id.to_raw()))
+                .content(scan)
+                .await?;
+            scans.extend(saved);
+        }
+        scans.sort_by(|a, b| a.vm_name.cmp(&b.vm_name));
+
+        let succeeded = scans.iter().filter(|s| s.status == GuestScanStatus::Succeeded).count();
+        Ok(GuestScanSummary {
+            requested: request.vm_ids.len(),
+            succeeded,
+            failed: scans.len() - succeeded,
+            scans,
+        })
+    }
+
+    pub async fn list_scans(&self, project_id: &str) -> Result<Vec<GuestScan>, GuestScanError> {
+        Ok(self
+            .db
+            .query("SELECT * FROM guest_scan WHERE project_id = $project ORDER BY vm_name ASC")
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("guest_scan_service.list_scans")
+            .await?
+            .take(0)?)
+    }
+
+    /// Latest scans of the given VMs, e.g. the VMs of one wave
+    pub async fn scans_for_vms(&self, vm_ids: &[Thing]) -> Result<Vec<GuestScan>, GuestScanError> {
+        Ok(self
+            .db
+            .query("SELECT * FROM guest_scan WHERE vm_id INSIDE $ids ORDER BY vm_name ASC")
+            .bind(("ids", vm_ids))
+            .traced("guest_scan_service.scans_for_vms")
+            .await?
+            .take(0)?)
+    }
+}
+
+fn collector(credential: &GuestCredential) -> Result<Arc<dyn GuestCollector>, GuestScanError> {
+    let secret = SecretBox::from_env()
+        .and_then(|secrets| secrets.decrypt(&credential.secret_encrypted))
+        .map_err(|e| GuestScanError::Secret(e.to_string()))?;
+    Ok(match credential.protocol {
+        GuestProtocol::Winrm => Arc::new(WinRmCollector::new(
+            &credential.username,
+            &secret,
+            credential.port(),
+            credential.verify_tls,
+        )),
+        GuestProtocol::Ssh => {
+            let secret = if credential.private_key { SshSecret::PrivateKey(secret) } else { SshSecret::Password(secret) };
+            Arc::new(SshCollector::new(&credential.username, secret, credential.port()))
+        }
+    })
+}
+
+/// The project VMs with the given keys; unknown keys are rejected so a
+/// typo does not silently skip a VM
+fn select_vms(vms: &[MigrationWizardVM], vm_ids: &[String]) -> Result<Vec<MigrationWizardVM>, GuestScanError> {
+    let wanted: HashSet<&str> = vm_ids.iter().map(String::as_str).collect();
+    let selected: Vec<MigrationWizardVM> = vms
+        .iter()
+        .filter(|vm| vm.id.as_ref().is_some_and(|id| wanted.contains(id.id.to_raw().as_str())))
+        .cloned()
+        .collect();
+    if selected.len() < wanted.len() {
+        let found: HashSet<String> = selected.iter().filter_map(|vm| vm.id.as_ref()).map(|id| id.id.to_raw()).collect();
+        let missing: Vec<&str> = vm_ids.iter().map(String::as_str).filter(|id| !found.contains(*id)).collect();
+        return Err(GuestScanError::Validation(format!("VMs not in this project: {}", missing.join(", "))));
+    }
+    Ok(selected)
+}
+
+/// RVTools lists the primary IP first; fall back to the DNS name
+fn guest_address(vm: &MigrationWizardVM) -> Option<String> {
+    vm.primary_ip_address
+        .as_deref()
+        .and_then(|ips| ips.split([',', ';', ' ']).map(str::trim).find(|ip| !ip.is_empty()))
+        .or(vm.dns_name.as_deref().map(str::trim).filter(|name| !name.is_empty()))
+        .map(str::to_string)
+}
+
+fn ip_label(ip: &GuestIpConfig) -> String {
+    let mut label = match ip.prefix_length {
+        Some(prefix) => format!("{}/{}", ip.address, prefix),
+        None => ip.address.clone(),
+    };
+    if let Some(gateway) = &ip.gateway {
+        label.push_str(&format!(" via {}", gateway));
+    }
+    label
+}
+
+/// Adjust strategy recommendations with what successful scans found:
+/// static addresses to carry over, network mounts the target must reach,
+/// and applications that need a coordinated cutover
+pub fn enrich(recommendations: &mut [StrategyRecommendation], scans: &[GuestScan]) {
+    let by_name: HashMap<&str, &GuestScan> = scans
+        .iter()
+        .filter(|scan| scan.status == GuestScanStatus::Succeeded)
+        .map(|scan| (scan.vm_name.as_str(), scan))
+        .collect();
+
+    for recommendation in recommendations {
+        let Some(scan) = by_name.get(recommendation.vm_name.as_str()) else { continue };
+        let config = &scan.config;
+
+        for ip in &config.static_ips {
+            recommendation.recommendations.push(format!(
+                "Static IP {} on {} must be kept on the target network or re-addressed at cutover",
+                ip_label(ip),
+                ip.interface
+            ));
+        }
+        if !config.static_ips.is_empty() {
+            recommendation.confidence_score -= 5.0;
+        }
+
+        let network_mounts: Vec<&GuestMount> = config.mounts.iter().filter(|m| m.network).collect();
+        for mount in &network_mounts {
+            recommendation.warnings.push(format!(
+                "{} is mounted from {}; the share must be reachable from the target",
+                mount.mount_point,
+                mount.source.as_deref().unwrap_or("a network share")
+            ));
+        }
+        recommendation.confidence_score -= (network_mounts.len() as f64 * 5.0).min(15.0);
+
+        let mut categories = BTreeSet::new();
+        for app in &config.applications {
+            let name = app.name.to_ascii_lowercase();
+            for (fragment, category, advice, penalty) in APPLICATION_HINTS {
+                if name.contains(fragment) && categories.insert(*category) {
+                    recommendation.recommendations.push(format!("{}: {}", app.name, advice));
+                    recommendation.confidence_score -= penalty;
+                }
+            }
+        }
+
+        recommendation.confidence_score = recommendation.confidence_score.max(0.0);
+    }
+}
+
+/// Runbook steps from successful scans of a wave's VMs: static addresses
+/// are recorded before the move, and they and the mount points are
+/// verified after it. Returns (pre-migration, post-migration) steps.
+pub fn runbook_steps(scans: &[GuestScan]) -> (Vec<RunbookStep>, Vec<RunbookStep>) {
+    let step = |phase: &str, action: String| RunbookStep {
+        phase: phase.to_string(),
+        action,
+    };
+    let mut pre = Vec::new();
+    let mut post = Vec::new();
+    for scan in scans.iter().filter(|scan| scan.status == GuestScanStatus::Succeeded) {
+        let config = &scan.config;
+        if !config.static_ips.is_empty() {
+            let ips: Vec<String> = config.static_ips.iter().map(ip_label).collect();
+            pre.push(step("pre_migration", format!("Record static IP settings of {}: {}", scan.vm_name, ips.join(", "))));
+            post.push(step("post_migration", format!("Verify {} kept its static IP settings: {}", scan.vm_name, ips.join(", "))));
+        }
+        if !config.mounts.is_empty() {
+            let mounts: Vec<&str> = config.mounts.iter().map(|m| m.mount_point.as_str()).collect();
+            post.push(step("post_migration", format!("Verify mount points on {}: {}", scan.vm_name, mounts.join(", "))));
+        }
+    }
+    (pre, post)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(vm_name: &str, status: GuestScanStatus, config: GuestConfig) -> GuestScan {
+        GuestScan {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            vm_id: Thing::from(("migration_wizard_vm", vm_name)),
+            vm_name: vm_name.to_string(),
+            protocol: GuestProtocol::Ssh,
+            address: Some("10.20.0.15".to_string()),
+            status,
+            error: None,
+            config,
+            scanned_at: Utc::now(),
+        }
+    }
+
+    fn db_server() -> GuestConfig {
+        GuestConfig {
+            applications: vec![
+                InstalledApplication { name: "postgresql15-server".to_string(), ..Default::default() },
+                InstalledApplication { name: "mysql-community-client".to_string(), ..Default::default() },
+            ],
+            static_ips: vec![GuestIpConfig {
+                interface: "ens192".to_string(),
+                address: "10.20.0.15".to_string(),
+                prefix_length: Some(24),
+                gateway: Some("10.20.0.1".to_string()),
+                dns_servers: vec![],
+            }],
+            mounts: vec![
+                GuestMount { mount_point: "/".to_string(), size_gb: 50.0, ..Default::default() },
+                GuestMount {
+                    mount_point: "/backup".to_string(),
+                    source: Some("nas01:/exports/backup".to_string()),
+                    network: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn recommendation(vm_name: &str) -> StrategyRecommendation {
+        StrategyRecommendation {
+            vm_name: vm_name.to_string(),
+            strategy: "lift_shift".to_string(),
+            confidence_score: 100.0,
+            warnings: vec![],
+            recommendations: vec![],
+            migration_mode: None,
+            migration_mode_rationale: None,
+        }
+    }
+
+    #[test]
+    fn test_enrich_scores_static_ips_network_mounts_and_applications_once_per_category() {
+        let scans = vec![
+            scan("db01", GuestScanStatus::Succeeded, db_server()),
+            scan("web01", GuestScanStatus::Failed, db_server()),
+        ];
+        let mut recommendations = vec![recommendation("db01"), recommendation("web01")];
+
+        enrich(&mut recommendations, &scans);
+
+        let db = &recommendations[0];
+        assert_eq!(db.confidence_score, 85.0, "static IP, one network mount and one database category");
+        assert_eq!(db.warnings, vec!["/backup is mounted from nas01:/exports/backup; the share must be reachable from the target"]);
+        assert_eq!(db.recommendations.len(), 2);
+        assert!(db.recommendations[0].starts_with("Static IP 10.20.0.15/24 via 10.20.0.1 on ens192"));
+        assert!(db.recommendations[1].starts_with("postgresql15-server: database engine"));
+
+        assert_eq!(recommendations[1].confidence_score, 100.0, "failed scans change nothing");
+    }
+
+    #[test]
+    fn test_runbook_records_ips_before_and_verifies_ips_and_mounts_after() {
+        let (pre, post) = runbook_steps(&[scan("db01", GuestScanStatus::Succeeded, db_server())]);
+
+        assert_eq!(pre.len(), 1);
+        assert_eq!(pre[0].action, "Record static IP settings of db01: 10.20.0.15/24 via 10.20.0.1");
+        assert!(post.iter().all(|s| s.phase == "post_migration"));
+        assert_eq!(post[1].action, "Verify mount points on db01: /, /backup");
+    }
+
+    #[test]
+    fn test_guest_address_prefers_the_first_ip_then_dns_name() {
+        let vms = vec![MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", "vm1"))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: "app01".to_string(),
+            powerstate: None,
+            template: None,
+            cpus: 2,
+            memory_mb: 4096,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: Some(" 10.0.0.5, 10.0.0.6".to_string()),
+            dns_name: Some("app01.corp.local".to_string()),
+            cluster: None,
+            host: None,
+            datacenter: None,
+            os: None,
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }];
+
+        assert_eq!(guest_address(&vms[0]).as_deref(), Some("10.0.0.5"));
+        let no_ip = MigrationWizardVM { primary_ip_address: None, ..vms[0].clone() };
+        assert_eq!(guest_address(&no_ip).as_deref(), Some("app01.corp.local"));
+
+        assert_eq!(select_vms(&vms, &["vm1".to_string()]).unwrap().len(), 1);
+        let missing = select_vms(&vms, &["vm1".to_string(), "vm9".to_string()]).unwrap_err();
+        assert_eq!(missing.to_string(), "Validation error: VMs not in this project: vm9");
+    }
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::collector::{parse_linux_output, GuestCollector, GuestScanError, LINUX_SCRIPT};
+use crate::models::guest_scan::GuestConfig;
+
+/// Hands the password to ssh through SSH_ASKPASS, so it never shows up on
+/// a command line or in a file
+const ASKPASS_SCRIPT: &str = "#!/bin/sh\nprintf '%s\\n' \"$ARCHER_GUEST_PASSWORD\"\n";
+const SCAN_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub enum SshSecret {
+    Password(String),
+    PrivateKey(String),
+}
+
+/// Runs the Linux scan script with the OpenSSH client, which must be on
+/// the PATH (OpenSSH 8.4 or later for password sign-in). Host keys are
+/// trusted on first use and pinned in the service account's known_hosts.
+pub struct SshCollector {
+    username: String,
+    secret: SshSecret,
+    port: u16,
+}
+
+impl SshCollector {
+    pub fn new(username: &str, secret: SshSecret, port: u16) -> Self {
+        Self {
+            username: username.to_string(),
+            secret,
+            port,
+        }
+    }
+
+    /// ssh arguments and environment; files the secret needs are written
+    /// to `workdir`, which the caller removes afterwards
+    fn command(&self, address: &str, workdir: &Path) -> Result<Command, GuestScanError> {
+        let mut command = Command::new("ssh");
+        command.arg("-p").arg(self.port.to_string()).args([
+            "-o",
+            "StrictHostKeyChecking=accept-new",
+            "-o",
+            "ConnectTimeout=15",
+            "-o",
+            "NumberOfPasswordPrompts=1",
+        ]);
+
+        match &self.secret {
+            SshSecret::PrivateKey(key) => {
+                let key_path = workdir.join("id");
+                write_private(&key_path, &format!("{}\n", key.trim_end()), 0o600)?;
+                command
+                    .args(["-o", "BatchMode=yes", "-o", "IdentitiesOnly=yes", "-o", "PreferredAuthentications=publickey", "-i"])
+                    .arg(&key_path);
+            }
+            SshSecret::Password(password) => {
+                let askpass = workdir.join("askpass");
+                write_private(&askpass, ASKPASS_SCRIPT, 0o700)?;
+                command
+                    .args(["-o", "PreferredAuthentications=password,keyboard-interactive", "-o", "PubkeyAuthentication=no"])
+                    .env("SSH_ASKPASS", &askpass)
+                    .env("SSH_ASKPASS_REQUIRE", "force")
+                    .env("DISPLAY", "archer:0")
+                    .env("ARCHER_GUEST_PASSWORD", password);
+            }
+        }
+
+        command
+            .arg(format!("{}@{}", self.username, address))
+            .arg("sh -s")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        Ok(command)
+    }
+}
+
+#[async_trait]
+impl GuestCollector for SshCollector {
+    async fn collect(&self, address: &str) -> Result<GuestConfig, GuestScanError> {
+        let workdir = tempfile::tempdir().map_err(|e| GuestScanError::Secret(e.to_string()))?;
+        let mut child = self
+            .command(address, workdir.path())?
+            .spawn()
+            .map_err(|e| GuestScanError::Connection(format!("failed to run ssh: {}. Make sure OpenSSH is installed.", e)))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(LINUX_SCRIPT.as_bytes())
+                .await
+                .map_err(|e| GuestScanError::Connection(e.to_string()))?;
+        }
+        let output = tokio::time::timeout(SCAN_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| GuestScanError::Script("the scan script did not finish in time".to_string()))?
+            .map_err(|e| GuestScanError::Connection(e.to_string()))?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match output.status.code() {
+            Some(0) => Ok(parse_linux_output(&String::from_utf8_lossy(&output.stdout))),
+            // ssh itself exits with 255 on connection and sign-in failures
+            Some(255) if stderr.contains("Permission denied") => Err(GuestScanError::Unauthorized),
+            Some(255) => Err(GuestScanError::Connection(stderr.trim().to_string())),
+            code => Err(GuestScanError::Script(format!("exit code {}: {}", code.unwrap_or(-1), stderr.trim()))),
+        }
+    }
+}
+
+/// ssh refuses keys other users can read; the temporary directory is
+/// already private to the service account
+fn write_private(path: &Path, content: &str, mode: u32) -> Result<(), GuestScanError> {
+    std::fs::write(path, content).map_err(|e| GuestScanError::Secret(e.to_string()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+            .map_err(|e| GuestScanError::Secret(e.to_string()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{Client, StatusCode};
+use std::time::Duration;
+
+use super::collector::{parse_windows_output, GuestCollector, GuestScanError, WINDOWS_SCRIPT};
+use crate::models::guest_scan::GuestConfig;
+
+const SHELL_URI: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/cmd";
+const ACTION_CREATE: &str = "http://schemas.xmlsoap.org/ws/2004/09/transfer/Create";
+const ACTION_DELETE: &str = "http://schemas.xmlsoap.org/ws/2004/09/transfer/Delete";
+const ACTION_COMMAND: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Command";
+const ACTION_RECEIVE: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Receive";
+/// WS-Management fault code for a Receive that saw no output before its
+/// operation timeout; the command is still running
+const RECEIVE_TIMED_OUT: &str = "2150858793";
+/// Receive rounds before the scan is given up, each up to the operation timeout
+const MAX_RECEIVES: usize = 10;
+
+static SHELL_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r#"ShellId[^>]*>([^<]+)<"#).unwrap());
+static COMMAND_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<rsp:CommandId>([^<]+)<"#).unwrap());
+static STREAM: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"<rsp:Stream Name="(stdout|stderr)"[^>]*?(?:/>|>([^<]*)</rsp:Stream>)"#).unwrap());
+static EXIT_CODE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<rsp:ExitCode>(-?\d+)<"#).unwrap());
+
+/// Runs the Windows scan script through a WinRM shell over HTTPS with
+/// Basic auth, so the guest needs `winrm set winrm/config/service/auth
+/// @{Basic="true"}` and an HTTPS listener. NTLM and Kerberos are not
+/// supported.
+pub struct WinRmCollector {
+    username: String,
+    password: String,
+    port: u16,
+    client: Client,
+}
+
+/// Output collected by one or more Receive calls
+#[derive(Debug, Default, PartialEq)]
+pub struct Received {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// Set once the command is done
+    pub exit_code: Option<i32>,
+}
+
+impl WinRmCollector {
+    pub fn new(username: &str, password: &str, port: u16, verify_tls: bool) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(90))
+            .danger_accept_invalid_certs(!verify_tls)
+            .build()
+            .unwrap_or_default();
+        Self {
+            username: username.to_string(),
+            password: password.to_string(),
+            port,
+            client,
+        }
+    }
+
+    async fn send(&self, endpoint: &str, envelope: String) -> Result<String, GuestScanError> {
+        let response = self
+            .client
+            .post(endpoint)
+            .basic_auth(&self.username, Some(&self.password))
+            .header("Content-Type", "application/soap+xml;charset=UTF-8")
+            .body(envelope)
+            .send()
+            .await
+            .map_err(|e| GuestScanError::Connection(e.to_string()))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| GuestScanError::Connection(e.to_string()))?;
+        match status {
+            StatusCode::UNAUTHORIZED => Err(GuestScanError::Unauthorized),
+            // Faults come back as 500 with the reason in the body
+            _ if status.is_success() || body.contains(RECEIVE_TIMED_OUT) => Ok(body),
+            _ => Err(GuestScanError::Script(format!("WinRM returned {}: {}", status, fault_reason(&body)))),
+        }
+    }
+
+    async fn run(&self, endpoint: &str, shell_id: &str) -> Result<Received, GuestScanError> {
+        let command = format!(
+            "<rsp:CommandLine><rsp:Command>powershell.exe</rsp:Command><rsp:Arguments>-NoProfile -NonInteractive -EncodedCommand {}</rsp:Arguments></rsp:CommandLine>",
+            encode_powershell(WINDOWS_SCRIPT)
+        );
+        let response = self
+            .send(endpoint, envelope(endpoint, ACTION_COMMAND, Some(shell_id), &command))
+            .await?;
+        let command_id = capture(&COMMAND_ID, &response)
+            .ok_or_else(|| GuestScanError::Script("WinRM did not return a command id".to_string()))?;
+
+        let mut received = Received::default();
+        for _ in 0..MAX_RECEIVES {
+            let body = format!(
+                "<rsp:Receive><rsp:DesiredStream CommandId=\"{}\">stdout stderr</rsp:DesiredStream></rsp:Receive>",
+                command_id
+            );
+            let response = self.send(endpoint, envelope(endpoint, ACTION_RECEIVE, Some(shell_id), &body)).await?;
+            let round = parse_receive(&response);
+            received.stdout.extend(round.stdout);
+            received.stderr.extend(round.stderr);
+            if round.exit_code.is_some() {
+                received.exit_code = round.exit_code;
+                return Ok(received);
+            }
+        }
+        Err(GuestScanError::Script("the scan script did not finish in time".to_string()))
+    }
+}
+
+#[async_trait]
+impl GuestCollector for WinRmCollector {
+    async fn collect(&self, address: &str) -> Result<GuestConfig, GuestScanError> {
+        let endpoint = format!("https://{}:{}/wsman", address, self.port);
+        let body = "<rsp:Shell><rsp:InputStreams>stdin</rsp:InputStreams><rsp:OutputStreams>stdout stderr</rsp:OutputStreams></rsp:Shell>";
+        let response = self.send(&endpoint, envelope(&endpoint, ACTION_CREATE, None, body)).await?;
+        let shell_id = capture(&SHELL_ID, &response)
+            .ok_or_else(|| GuestScanError::Script("WinRM did not return a shell id".to_string()))?;
+
+        let outcome = self.run(&endpoint, &shell_id).await;
+        // Shells count against the user's quota on the guest; close it even
+        // when the command failed
+        if let Err(e) = self.send(&endpoint, envelope(&endpoint, ACTION_DELETE, Some(&shell_id), "")).await {
+            tracing::warn!("Failed to close WinRM shell on {}: {}", address, e);
+        }
+
+        let received = outcome?;
+        if received.exit_code != Some(0) {
+            return Err(GuestScanError::Script(format!(
+                "exit code {}: {}",
+                received.exit_code.unwrap_or_default(),
+                String::from_utf8_lossy(&received.stderr).trim()
+            )));
+        }
+        parse_windows_output(&String::from_utf8_lossy(&received.stdout))
+    }
+}
+
+fn envelope(endpoint: &str, action: &str, shell_id: Option<&str>, body: &str) -> String {
+    let selector = shell_id
+        .map(|id| format!("<w:SelectorSet><w:Selector Name=\"ShellId\">{}</w:Selector></w:SelectorSet>", id))
+        .unwrap_or_default();
+    let options = match action {
+        ACTION_CREATE => "<w:OptionSet><w:Option Name=\"WINRS_NOPROFILE\">TRUE</w:Option><w:Option Name=\"WINRS_CODEPAGE\">65001</w:Option></w:OptionSet>",
+        ACTION_COMMAND => "<w:OptionSet><w:Option Name=\"WINRS_CONSOLEMODE_STDIN\">TRUE</w:Option></w:OptionSet>",
+        _ => "",
+    };
+    format!(
+        concat!(
+            r#"<s:Envelope xmlns:s="http://www.w3.org/2003/05/soap-envelope" xmlns:a="http://schemas.xmlsoap.org/ws/2004/08/addressing" "#,
+            r#"xmlns:w="http://schemas.dmtf.org/wbem/wsman/1/wsman.xsd" xmlns:rsp="http://schemas.microsoft.com/wbem/wsman/1/windows/shell">"#,
+            r#"<s:Header><a:To>{endpoint}</a:To>"#,
+            r#"<a:ReplyTo><a:Address s:mustUnderstand="true">http://schemas.xmlsoap.org/ws/2004/08/addressing/role/anonymous</a:Address></a:ReplyTo>"#,
+            r#"<a:MessageID>uuid:{message_id}</a:MessageID><a:Action s:mustUnderstand="true">{action}</a:Action>"#,
+            r#"<w:ResourceURI s:mustUnderstand="true">{shell_uri}</w:ResourceURI>"#,
+            r#"<w:MaxEnvelopeSize s:mustUnderstand="true">512000</w:MaxEnvelopeSize><w:OperationTimeout>PT60S</w:OperationTimeout>"#,
+            r#"{selector}{options}</s:Header><s:Body>{body}</s:Body></s:Envelope>"#
+        ),
+        endpoint = endpoint,
+        message_id = uuid::Uuid::new_v4(),
+        action = action,
+        shell_uri = SHELL_URI,
+        selector = selector,
+        options = options,
+        body = body,
+    )
+}
+
+/// `-EncodedCommand` takes base64 of the script in UTF-16LE, which spares
+/// quoting it for cmd.exe
+pub fn encode_powershell(script: &str) -> String {
+    let utf16: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    BASE64.encode(utf16)
+}
+
+/// Output streams and, once the command is done, its exit code from a
+/// Receive response
+pub fn parse_receive(response: &str) -> Received {
+    let mut received = Received::default();
+    for stream in STREAM.captures_iter(response) {
+        let Some(data) = stream.get(2) else { continue };
+        let Ok(bytes) = BASE64.decode(data.as_str().trim()) else { continue };
+        match &stream[1] {
+            "stdout" => received.stdout.extend(bytes),
+            _ => received.stderr.extend(bytes),
+        }
+    }
+    if response.contains("CommandState/Done") {
+        received.exit_code = Some(capture(&EXIT_CODE, response).and_then(|code| code.parse().ok()).unwrap_or(0));
+    }
+    received
+}
+
+fn capture(pattern: &Regex, text: &str) -> Option<String> {
+    pattern.captures(text).map(|c| c[1].trim().to_string())
+}
+
+fn fault_reason(body: &str) -> String {
+    static REASON: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<s:Text[^>]*>([^<]+)<"#).unwrap());
+    capture(&REASON, body).unwrap_or_else(|| "no fault reason".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodes_scripts_as_utf16le_base64() {
+        assert_eq!(encode_powershell("dir"), "ZABpAHIA");
+    }
+
+    #[test]
+    fn test_receive_collects_streams_until_done() {
+        let running = r#"<s:Body><rsp:ReceiveResponse>
+            <rsp:Stream Name="stdout" CommandId="C1">eyJob3N0</rsp:Stream>
+            <rsp:Stream Name="stderr" CommandId="C1" End="true"/>
+            <rsp:CommandState CommandId="C1" State="http://schemas.microsoft.com/wbem/wsman/1/windows/shell/CommandState/Running"/>
+            </rsp:ReceiveResponse></s:Body>"#;
+        let done = r#"<s:Body><rsp:ReceiveResponse>
+            <rsp:Stream Name="stdout" CommandId="C1">bmFtZSI6MX0=</rsp:Stream>
+            <rsp:Stream Name="stdout" CommandId="C1" End="true"></rsp:Stream>
+            <rsp:CommandState CommandId="C1" State="http://schemas.microsoft.com/wbem/wsman/1/windows/shell/CommandState/Done">
+            <rsp:ExitCode>0</rsp:ExitCode></rsp:CommandState>
+            </rsp:ReceiveResponse></s:Body>"#;
+
+        let first = parse_receive(running);
+        assert_eq!((first.stdout.as_slice(), first.exit_code), (b"{\"host".as_slice(), None));
+
+        let second = parse_receive(done);
+        assert_eq!(second.stdout, b"name\":1}");
+        assert_eq!(second.exit_code, Some(0));
+    }
+}
@@ -8,7 +8,8 @@
 //! - Disk configuration
 
 use serde::{Deserialize, Serialize};
-use surrealdb::{engine::local::Db, Surreal};
+use crate::database::Db;
+use surrealdb::Surreal;
 
 use crate::models::workflow::{
     CheckResult, CheckStatus, CompatibilityChecks, CompatibilityStatus,
@@ -5,6 +5,9 @@ use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
+use crate::database::TracedQuery;
+
+pub mod parts_inventory;
 
 pub struct HardwarePoolService {
     db: Database,
@@ -129,6 +132,7 @@ impl HardwarePoolService {
             .bind(("server_id", Thing::from(("hardware_pool", server_id))))
             .bind(("start_date", start_date))
             .bind(("end_date", end_date))
+            .traced("hardware_pool_service.get_server_availability")
             .await?
             .take(0)?;
 
@@ -210,7 +214,7 @@ impl HardwarePoolService {
             query.push_str(&format!(" AND {}", conditions.join(" AND ")));
         }
 
-        let servers: Vec<HardwarePool> = self.db.query(query).await?.take(0)?;
+        let servers: Vec<HardwarePool> = self.db.query(query).traced("hardware_pool_service.find_optimal_servers").await?.take(0)?;
 
         let mut recommendations = Vec::new();
 
@@ -604,6 +608,7 @@ impl HardwarePoolService {
                 FROM hardware_pool 
                 GROUP BY availability_status",
             )
+            .traced("hardware_pool_service.get_pool_analytics")
             .await?
             .take(0)?;
 
@@ -615,6 +620,7 @@ impl HardwarePoolService {
                 (SELECT COUNT() FROM hardware_pool WHERE vendor = $parent.vendor AND availability_status = 'allocated') as allocated_servers
                 FROM hardware_pool
                 GROUP BY vendor")
+            .traced("hardware_pool_service.get_pool_analytics")
             .await?
             .take(0)?;
 
@@ -628,6 +634,7 @@ impl HardwarePoolService {
                 (SELECT SUM(memory_gb) FROM hardware_pool WHERE availability_status = 'allocated') as allocated_memory_gb,
                 (SELECT SUM(storage_capacity_gb) FROM hardware_pool WHERE availability_status = 'allocated') as allocated_storage_gb
                 FROM hardware_pool")
+            .traced("hardware_pool_service.get_pool_analytics")
             .await?
             .take(0)?;
 
@@ -649,6 +656,7 @@ impl HardwarePoolService {
                 (SELECT SUM(monthly_cost) FROM hardware_pool WHERE availability_status = 'allocated') as allocated_monthly_cost,
                 (SELECT SUM(monthly_cost) FROM hardware_pool WHERE availability_status = 'available') as available_monthly_cost
                 FROM hardware_pool")
+            .traced("hardware_pool_service.calculate_cost_analysis")
             .await?
             .take(0)?;
 
@@ -689,6 +697,7 @@ impl HardwarePoolService {
                 GROUP BY time::group(allocation_start, '1w')
                 ORDER BY allocation_start",
             )
+            .traced("hardware_pool_service.generate_capacity_forecast")
             .await?
             .take(0)?;
 
@@ -0,0 +1,423 @@
+// Archer ITSM - Hardware Pool Parts Inventory
+// Spare component stock per location, ticket-linked consumption and
+// low-stock alerts sent through the notification service
+
+use chrono::Utc;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tracing::info;
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::spare_part::*;
+use crate::services::notification_service::NotificationService;
+use crate::database::TracedQuery;
+
+const TABLE: &str = "spare_part";
+const CONSUMPTION_TABLE: &str = "spare_part_consumption";
+
+#[derive(Debug, Error)]
+pub enum PartsInventoryError {
+    #[error("Spare part not found")]
+    NotFound,
+
+    #[error("Ticket not found")]
+    TicketNotFound,
+
+    #[error("Insufficient stock: {available} available, {requested} requested")]
+    InsufficientStock { available: u32, requested: u32 },
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for PartsInventoryError {
+    fn from(e: surrealdb::Error) -> Self {
+        PartsInventoryError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct PartsInventoryService {
+    db: Arc<Database>,
+    notifier: NotificationService,
+}
+
+impl PartsInventoryService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            notifier: NotificationService::from_env(),
+        }
+    }
+
+    // ========================================================================
+    // PARTS
+    // ========================================================================
+
+    pub async fn list_parts(&self, query: &SparePartListQuery) -> Result<Vec<SparePart>, PartsInventoryError> {
+        let mut parts: Vec<SparePart> = self
+            .db
+            .query("SELECT * FROM spare_part ORDER BY location, part_number")
+            .traced("parts_inventory.list_parts")
+            .await?
+            .take(0)?;
+
+        parts.retain(|p| {
+            query.category.map_or(true, |c| p.category == c)
+                && query.location.as_ref().map_or(true, |l| p.location.eq_ignore_ascii_case(l))
+                && query
+                    .model
+                    .as_ref()
+                    .map_or(true, |m| p.compatible_models.iter().any(|cm| cm.eq_ignore_ascii_case(m)))
+                && (!query.low_stock || p.is_below_minimum())
+        });
+        Ok(parts)
+    }
+
+    pub async fn get_part(&self, id: &str) -> Result<SparePart, PartsInventoryError> {
+        let part: Option<SparePart> = self.db.select(parse_thing(TABLE, id)).await?;
+        part.ok_or(PartsInventoryError::NotFound)
+    }
+
+    pub async fn create_part(&self, request: CreateSparePartRequest) -> Result<SparePart, PartsInventoryError> {
+        let now = Utc::now();
+        let part = SparePart {
+            id: None,
+            part_number: request.part_number.trim().to_string(),
+            description: request.description,
+            category: request.category,
+            vendor: request.vendor,
+            compatible_models: request.compatible_models,
+            location: request.location.trim().to_string(),
+            datacenter: request.datacenter,
+            quantity: request.quantity,
+            min_quantity: request.min_quantity,
+            unit_cost: request.unit_cost,
+            alert_emails: request.alert_emails,
+            low_stock_alerted: false,
+            created_at: now,
+            updated_at: now,
+        };
+        validate_part(&part)?;
+
+        let existing: Vec<Thing> = self
+            .db
+            .query("SELECT VALUE id FROM spare_part WHERE part_number = $part_number AND location = $location")
+            .bind(("part_number", part.part_number.clone()))
+            .bind(("location", part.location.clone()))
+            .traced("parts_inventory.create_part")
+            .await?
+            .take(0)?;
+        if !existing.is_empty() {
+            return Err(PartsInventoryError::Validation(format!(
+                "Part {} is already stocked at {}",
+                part.part_number, part.location
+            )));
+        }
+
+        let created: Vec<SparePart> = self.db.create(TABLE).content(part).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| PartsInventoryError::DatabaseError("Failed to create spare part".to_string()))
+    }
+
+    pub async fn update_part(
+        &self,
+        id: &str,
+        request: UpdateSparePartRequest,
+    ) -> Result<SparePart, PartsInventoryError> {
+        let mut part = self.get_part(id).await?;
+
+        if let Some(description) = request.description {
+            part.description = description;
+        }
+        if let Some(category) = request.category {
+            part.category = category;
+        }
+        if let Some(vendor) = request.vendor {
+            part.vendor = Some(vendor);
+        }
+        if let Some(models) = request.compatible_models {
+            part.compatible_models = models;
+        }
+        if let Some(location) = request.location {
+            part.location = location.trim().to_string();
+        }
+        if let Some(datacenter) = request.datacenter {
+            part.datacenter = Some(datacenter);
+        }
+        if let Some(min_quantity) = request.min_quantity {
+            part.min_quantity = min_quantity;
+        }
+        if let Some(unit_cost) = request.unit_cost {
+            part.unit_cost = Some(unit_cost);
+        }
+        if let Some(emails) = request.alert_emails {
+            part.alert_emails = emails;
+        }
+        // A lowered threshold re-arms the alert for the next shortfall
+        if !part.is_below_minimum() {
+            part.low_stock_alerted = false;
+        }
+        part.updated_at = Utc::now();
+        validate_part(&part)?;
+
+        let thing = parse_thing(TABLE, id);
+        let updated: Option<SparePart> = self.db.update(thing).content(part).await?;
+        updated.ok_or(PartsInventoryError::NotFound)
+    }
+
+    pub async fn delete_part(&self, id: &str) -> Result<(), PartsInventoryError> {
+        let deleted: Option<SparePart> = self.db.delete(parse_thing(TABLE, id)).await?;
+        deleted.map(|_| ()).ok_or(PartsInventoryError::NotFound)
+    }
+
+    // ========================================================================
+    // STOCK MOVEMENTS
+    // ========================================================================
+
+    /// Take parts out of stock against a ticket. The decrement is conditional
+    /// on enough stock, so concurrent consumers cannot drive it negative.
+    pub async fn consume(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: ConsumeSparePartRequest,
+    ) -> Result<ConsumeSparePartResponse, PartsInventoryError> {
+        if request.quantity == 0 {
+            return Err(PartsInventoryError::Validation("Quantity must be at least 1".to_string()));
+        }
+
+        let ticket = parse_thing("ticket", &request.ticket_id);
+        let found: Vec<Thing> = self
+            .db
+            .query("SELECT VALUE id FROM $ticket")
+            .bind(("ticket", ticket.clone()))
+            .traced("parts_inventory.consume")
+            .await?
+            .take(0)?;
+        if found.is_empty() {
+            return Err(PartsInventoryError::TicketNotFound);
+        }
+
+        let thing = parse_thing(TABLE, id);
+        let updated: Vec<SparePart> = self
+            .db
+            .query("UPDATE $id SET quantity -= $quantity, updated_at = time::now() WHERE quantity >= $quantity RETURN AFTER")
+            .bind(("id", thing.clone()))
+            .bind(("quantity", request.quantity))
+            .traced("parts_inventory.consume")
+            .await?
+            .take(0)?;
+        let Some(mut part) = updated.into_iter().next() else {
+            let part = self.get_part(id).await?;
+            return Err(PartsInventoryError::InsufficientStock {
+                available: part.quantity,
+                requested: request.quantity,
+            });
+        };
+
+        let consumption = SparePartConsumption {
+            id: None,
+            part_id: thing.clone(),
+            part_number: part.part_number.clone(),
+            ticket_id: ticket,
+            server_id: request.server_id.as_deref().map(|s| parse_thing("hardware_pool", s)),
+            quantity: request.quantity,
+            remaining: part.quantity,
+            notes: request.notes,
+            consumed_by: user.user_id.clone(),
+            consumed_at: Utc::now(),
+        };
+        let created: Vec<SparePartConsumption> = self.db.create(CONSUMPTION_TABLE).content(consumption).await?;
+        let consumption = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| PartsInventoryError::DatabaseError("Failed to record consumption".to_string()))?;
+
+        // Only the consumer that flips the flag sends the alert
+        let mut low_stock_alert = false;
+        if part.is_below_minimum() {
+            let flipped: Vec<SparePart> = self
+                .db
+                .query("UPDATE $id SET low_stock_alerted = true WHERE low_stock_alerted = false RETURN AFTER")
+                .bind(("id", thing))
+                .traced("parts_inventory.consume")
+                .await?
+                .take(0)?;
+            if let Some(flagged) = flipped.into_iter().next() {
+                part = flagged;
+                low_stock_alert = true;
+                self.send_low_stock_alert(&part).await;
+            }
+        }
+
+        Ok(ConsumeSparePartResponse {
+            part,
+            consumption,
+            low_stock_alert,
+        })
+    }
+
+    /// Add received stock; back at or above the minimum re-arms the alert
+    pub async fn restock(&self, id: &str, request: RestockSparePartRequest) -> Result<SparePart, PartsInventoryError> {
+        if request.quantity == 0 {
+            return Err(PartsInventoryError::Validation("Quantity must be at least 1".to_string()));
+        }
+
+        let thing = parse_thing(TABLE, id);
+        let updated: Vec<SparePart> = self
+            .db
+            .query("UPDATE $id SET quantity += $quantity, updated_at = time::now() RETURN AFTER")
+            .bind(("id", thing.clone()))
+            .bind(("quantity", request.quantity))
+            .traced("parts_inventory.restock")
+            .await?
+            .take(0)?;
+        let mut part = updated.into_iter().next().ok_or(PartsInventoryError::NotFound)?;
+
+        if part.low_stock_alerted && !part.is_below_minimum() {
+            self.db
+                .query("UPDATE $id SET low_stock_alerted = false")
+                .bind(("id", thing))
+                .traced("parts_inventory.restock")
+                .await?;
+            part.low_stock_alerted = false;
+        }
+        Ok(part)
+    }
+
+    pub async fn list_consumption(
+        &self,
+        query: &ConsumptionListQuery,
+    ) -> Result<Vec<SparePartConsumption>, PartsInventoryError> {
+        let mut conditions = vec!["true"];
+        if query.part_id.is_some() {
+            conditions.push("part_id = $part");
+        }
+        if query.ticket_id.is_some() {
+            conditions.push("ticket_id = $ticket");
+        }
+
+        let sql = format!(
+            "SELECT * FROM spare_part_consumption WHERE {} ORDER BY consumed_at DESC",
+            conditions.join(" AND ")
+        );
+        Ok(self
+            .db
+            .query(sql)
+            .bind(("part", query.part_id.as_deref().map(|p| parse_thing(TABLE, p))))
+            .bind(("ticket", query.ticket_id.as_deref().map(|t| parse_thing("ticket", t))))
+            .traced("parts_inventory.list_consumption")
+            .await?
+            .take(0)?)
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn send_low_stock_alert(&self, part: &SparePart) {
+        if part.alert_emails.is_empty() {
+            return;
+        }
+        let subject = format!("Low stock: {} at {} ({} left)", part.part_number, part.location, part.quantity);
+        let body = render_low_stock_alert(part);
+        for result in self.notifier.send_email(&part.alert_emails, &subject, &body, None).await {
+            info!("Low stock alert to {}: {:?}", result.target, result.status);
+        }
+    }
+}
+
+fn validate_part(part: &SparePart) -> Result<(), PartsInventoryError> {
+    if part.part_number.is_empty() {
+        return Err(PartsInventoryError::Validation("Part number is required".to_string()));
+    }
+    if part.location.is_empty() {
+        return Err(PartsInventoryError::Validation("Location is required".to_string()));
+    }
+    if part.unit_cost.map_or(false, |c| !c.is_finite() || c < 0.0) {
+        return Err(PartsInventoryError::Validation("Unit cost must not be negative".to_string()));
+    }
+    if let Some(email) = part.alert_emails.iter().find(|e| !e.contains('@')) {
+        return Err(PartsInventoryError::Validation(format!("Invalid alert email: {}", email)));
+    }
+    Ok(())
+}
+
+fn render_low_stock_alert(part: &SparePart) -> String {
+    let mut body = format!(
+        "Stock of {} ({}) at {} has fallen below its minimum.\n\nOn hand: {}\nMinimum: {}\n",
+        part.part_number, part.description, part.location, part.quantity, part.min_quantity
+    );
+    if let Some(vendor) = &part.vendor {
+        body.push_str(&format!("Vendor: {}\n", vendor));
+    }
+    if !part.compatible_models.is_empty() {
+        body.push_str(&format!("Fits: {}\n", part.compatible_models.join(", ")));
+    }
+    body.push_str(&format!(
+        "\nReorder at least {} to get back to the minimum.\n",
+        part.min_quantity.saturating_sub(part.quantity)
+    ));
+    body
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(quantity: u32, min_quantity: u32) -> SparePart {
+        SparePart {
+            id: None,
+            part_number: "P04553-B21".to_string(),
+            description: "1.92TB SAS SSD".to_string(),
+            category: SparePartCategory::Drive,
+            vendor: Some("HPE".to_string()),
+            compatible_models: vec!["ProLiant DL380 Gen10".to_string()],
+            location: "DC1 cage 4".to_string(),
+            datacenter: Some("DC1".to_string()),
+            quantity,
+            min_quantity,
+            unit_cost: Some(410.0),
+            alert_emails: vec!["storage-team@example.com".to_string()],
+            low_stock_alerted: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_low_stock_threshold_and_validation() {
+        assert!(!part(2, 2).is_below_minimum());
+        assert!(part(1, 2).is_below_minimum());
+        assert!(!part(0, 0).is_below_minimum());
+
+        let alert = render_low_stock_alert(&part(1, 4));
+        assert!(alert.contains("On hand: 1"));
+        assert!(alert.contains("Reorder at least 3"));
+        // Restocked between the alert flag flip and the re-read
+        assert!(render_low_stock_alert(&part(5, 4)).contains("Reorder at least 0"));
+
+        assert!(validate_part(&part(1, 2)).is_ok());
+        let mut bad = part(1, 2);
+        bad.alert_emails = vec!["storage-team".to_string()];
+        assert!(matches!(validate_part(&bad), Err(PartsInventoryError::Validation(_))));
+        bad = part(1, 2);
+        bad.unit_cost = Some(-1.0);
+        assert!(validate_part(&bad).is_err());
+    }
+}
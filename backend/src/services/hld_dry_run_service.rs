@@ -0,0 +1,360 @@
+use crate::models::hld::{
+    DryRunIssue, DryRunSection, DryRunVariable, HLDDryRunReport, HLDVariable, SectionDefinition,
+    SectionReadiness, VariableDefinition, VariableResolution, VariableValue,
+};
+use crate::services::variable_validator::VariableValidator;
+use crate::services::word_generator::WordGenerator;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// `{{name}}` placeholders, matched the way the Word generator substitutes them
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{(\w+)\}\}").unwrap());
+
+/// Variables an enabled section would read: the ones its built-in generator
+/// uses plus any placeholder in its description
+pub fn section_inputs(section: &SectionDefinition) -> BTreeSet<String> {
+    let mut names: BTreeSet<String> = WordGenerator::section_variables(&section.section_id)
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    names.extend(
+        PLACEHOLDER
+            .captures_iter(&section.description)
+            .map(|captures| captures[1].to_string()),
+    );
+    names
+}
+
+/// Work out what generating the HLD from `sections` and `variables` would
+/// produce, without generating it
+///
+/// A variable is checked against its definition when it has one; required
+/// definitions without a value and values that fail validation are errors.
+/// A section that no project data would reach is an error when the section
+/// is required and a warning otherwise, so `ready` only gates on things the
+/// user has to fix.
+pub fn dry_run(
+    project_id: &str,
+    template_id: &str,
+    sections: &[SectionDefinition],
+    variables: &[HLDVariable],
+    definitions: &HashMap<String, VariableDefinition>,
+) -> HLDDryRunReport {
+    let values: HashMap<String, VariableValue> = variables
+        .iter()
+        .filter_map(|v| {
+            let value = v.variable_value.as_ref().filter(|value| !value.is_null())?;
+            Some((v.variable_name.clone(), value.clone()))
+        })
+        .collect();
+
+    let enabled: Vec<&SectionDefinition> = sections.iter().filter(|s| s.enabled).collect();
+    let inputs: Vec<BTreeSet<String>> = enabled.iter().map(|s| section_inputs(s)).collect();
+
+    // Every variable that matters: required ones, ones a section reads, and
+    // ones the project has set
+    let mut readers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, definition) in definitions {
+        if definition.validation.required {
+            readers.entry(name.clone()).or_default();
+        }
+    }
+    for name in values.keys() {
+        readers.entry(name.clone()).or_default();
+    }
+    for (section, names) in enabled.iter().zip(&inputs) {
+        for name in names {
+            readers.entry(name.clone()).or_default().push(section.section_id.clone());
+        }
+    }
+
+    let mut issues = Vec::new();
+    let report_variables: Vec<DryRunVariable> = readers
+        .into_iter()
+        .map(|(name, sections)| {
+            let definition = definitions.get(&name);
+            let required = definition.is_some_and(|d| d.validation.required);
+            let value = values.get(&name).cloned();
+
+            let (status, messages) = match (&value, definition) {
+                (None, _) => (VariableResolution::Missing, Vec::new()),
+                (Some(_), None) => (VariableResolution::Resolved, Vec::new()),
+                (Some(_), Some(definition)) => {
+                    match VariableValidator::validate_variable(definition, &value, &values) {
+                        Ok(()) => (VariableResolution::Resolved, Vec::new()),
+                        Err(errors) => (
+                            VariableResolution::Invalid,
+                            errors.into_iter().map(|e| e.message).collect(),
+                        ),
+                    }
+                }
+            };
+
+            match status {
+                VariableResolution::Missing if required => issues.push(DryRunIssue {
+                    severity: "error".to_string(),
+                    variable: Some(name.clone()),
+                    section_id: None,
+                    message: format!("Required variable '{}' has no value", name),
+                }),
+                VariableResolution::Invalid => issues.extend(messages.iter().map(|message| DryRunIssue {
+                    severity: "error".to_string(),
+                    variable: Some(name.clone()),
+                    section_id: None,
+                    message: message.clone(),
+                })),
+                _ => {}
+            }
+
+            DryRunVariable {
+                name,
+                status,
+                required,
+                sections,
+                messages,
+            }
+        })
+        .collect();
+
+    let resolved: BTreeSet<&str> = report_variables
+        .iter()
+        .filter(|v| v.status == VariableResolution::Resolved)
+        .map(|v| v.name.as_str())
+        .collect();
+
+    let report_sections: Vec<DryRunSection> = enabled
+        .iter()
+        .zip(inputs)
+        .map(|(section, names)| {
+            let (found, missing): (Vec<String>, Vec<String>) =
+                names.into_iter().partition(|name| resolved.contains(name.as_str()));
+            let status = if found.is_empty() {
+                SectionReadiness::Empty
+            } else if missing.is_empty() {
+                SectionReadiness::Complete
+            } else {
+                SectionReadiness::Partial
+            };
+
+            let severity = if section.required { "error" } else { "warning" };
+            let message = match status {
+                SectionReadiness::Complete => None,
+                SectionReadiness::Empty if missing.is_empty() => Some(format!(
+                    "Section '{}' has no generated content and would only contain a placeholder",
+                    section.display_name
+                )),
+                SectionReadiness::Empty => Some(format!(
+                    "Section '{}' would be empty; set {}",
+                    section.display_name,
+                    missing.join(", ")
+                )),
+                SectionReadiness::Partial => Some(format!(
+                    "Section '{}' would use placeholder text for {}",
+                    section.display_name,
+                    missing.join(", ")
+                )),
+            };
+            if let Some(message) = message {
+                issues.push(DryRunIssue {
+                    severity: if status == SectionReadiness::Partial { "warning" } else { severity }.to_string(),
+                    variable: None,
+                    section_id: Some(section.section_id.clone()),
+                    message,
+                });
+            }
+
+            DryRunSection {
+                section_id: section.section_id.clone(),
+                display_name: section.display_name.clone(),
+                required: section.required,
+                status,
+                resolved: found,
+                missing,
+            }
+        })
+        .collect();
+
+    if report_sections.is_empty() {
+        issues.push(DryRunIssue {
+            severity: "error".to_string(),
+            variable: None,
+            section_id: None,
+            message: "No sections are enabled; the document would only have a title page".to_string(),
+        });
+    }
+
+    let count = |status: VariableResolution| report_variables.iter().filter(|v| v.status == status).count();
+
+    HLDDryRunReport {
+        project_id: project_id.to_string(),
+        template_id: template_id.to_string(),
+        ready: !issues.iter().any(|issue| issue.severity == "error"),
+        resolved_count: count(VariableResolution::Resolved),
+        missing_count: count(VariableResolution::Missing),
+        invalid_count: count(VariableResolution::Invalid),
+        empty_sections: report_sections
+            .iter()
+            .filter(|s| s.status == SectionReadiness::Empty)
+            .count(),
+        variables: report_variables,
+        sections: report_sections,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::hld::{ValidationRule, VariableType};
+    use chrono::Utc;
+    use surrealdb::sql::Thing;
+
+    fn section(section_id: &str, description: &str, required: bool) -> SectionDefinition {
+        SectionDefinition {
+            id: None,
+            section_id: section_id.to_string(),
+            section_name: section_id.to_string(),
+            display_name: section_id.replace('_', " ").to_uppercase(),
+            description: description.to_string(),
+            required,
+            enabled: true,
+            order_index: 0,
+            depends_on: Vec::new(),
+            sensitivity: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn variable(name: &str, value: VariableValue) -> HLDVariable {
+        HLDVariable::new(
+            Thing::from(("hld_projects", "p1")),
+            name.to_string(),
+            Some(value),
+            VariableType::String,
+            "global".to_string(),
+        )
+    }
+
+    fn definition(name: &str, var_type: VariableType, validation: ValidationRule) -> VariableDefinition {
+        VariableDefinition {
+            name: name.to_string(),
+            var_type,
+            section: "global".to_string(),
+            description: String::new(),
+            example_value: String::new(),
+            validation,
+            default_value: None,
+        }
+    }
+
+    fn required() -> ValidationRule {
+        ValidationRule {
+            required: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_section_inputs_include_description_placeholders() {
+        let names = section_inputs(&section("storage_design", "Backed up to {{backup_target}}.", false));
+        assert!(names.contains("total_storage_tb_usable"));
+        assert!(names.contains("backup_target"));
+    }
+
+    #[test]
+    fn test_all_inputs_set_is_ready() {
+        let sections = vec![section("storage_design", "", true)];
+        let variables = vec![variable("total_storage_tb_usable", VariableValue::Float(120.0))];
+
+        let report = dry_run("p1", "t1", &sections, &variables, &HashMap::new());
+
+        assert!(report.ready);
+        assert_eq!(report.sections[0].status, SectionReadiness::Complete);
+        assert_eq!(report.resolved_count, 1);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_required_section_without_data_blocks_generation() {
+        let sections = vec![section("network_design", "", true), section("storage_design", "", false)];
+
+        let report = dry_run("p1", "t1", &sections, &[], &HashMap::new());
+
+        assert!(!report.ready);
+        assert_eq!(report.empty_sections, 2);
+        assert_eq!(report.sections[0].missing, vec!["cluster_vlan_id", "lm_vlan_id", "mgmt_vlan_id"]);
+        let severities: Vec<(&str, &str)> = report
+            .issues
+            .iter()
+            .map(|i| (i.section_id.as_deref().unwrap(), i.severity.as_str()))
+            .collect();
+        assert_eq!(severities, vec![("network_design", "error"), ("storage_design", "warning")]);
+    }
+
+    #[test]
+    fn test_partially_filled_section_is_a_warning() {
+        let sections = vec![section("network_design", "", true)];
+        let variables = vec![variable("mgmt_vlan_id", VariableValue::Integer(10))];
+
+        let report = dry_run("p1", "t1", &sections, &variables, &HashMap::new());
+
+        assert!(report.ready);
+        assert_eq!(report.sections[0].status, SectionReadiness::Partial);
+        assert_eq!(report.sections[0].resolved, vec!["mgmt_vlan_id"]);
+        assert_eq!(report.issues[0].severity, "warning");
+    }
+
+    #[test]
+    fn test_section_without_generator_or_placeholders_is_empty() {
+        let report = dry_run("p1", "t1", &[section("appendix", "", false)], &[], &HashMap::new());
+
+        assert_eq!(report.sections[0].status, SectionReadiness::Empty);
+        assert!(report.issues[0].message.contains("placeholder"));
+    }
+
+    #[test]
+    fn test_required_definition_missing_and_invalid_value() {
+        let definitions = HashMap::from([
+            ("customer_name".to_string(), definition("customer_name", VariableType::String, required())),
+            (
+                "node_count".to_string(),
+                definition(
+                    "node_count",
+                    VariableType::Integer,
+                    ValidationRule {
+                        min_value: Some(2.0),
+                        ..Default::default()
+                    },
+                ),
+            ),
+        ]);
+        let variables = vec![variable("node_count", VariableValue::Integer(1))];
+
+        let report = dry_run("p1", "t1", &[section("executive_summary", "", false)], &variables, &definitions);
+
+        assert!(!report.ready);
+        let status = |name: &str| report.variables.iter().find(|v| v.name == name).unwrap().status;
+        assert_eq!(status("customer_name"), VariableResolution::Missing);
+        assert_eq!(status("node_count"), VariableResolution::Invalid);
+        assert_eq!(status("project_name"), VariableResolution::Missing);
+        assert_eq!(report.invalid_count, 1);
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.variable.as_deref() == Some("customer_name") && i.severity == "error"));
+        // An invalid value does not count as reaching the section
+        assert_eq!(report.sections[0].status, SectionReadiness::Empty);
+    }
+
+    #[test]
+    fn test_disabled_sections_are_skipped() {
+        let mut disabled = section("network_design", "", true);
+        disabled.enabled = false;
+
+        let report = dry_run("p1", "t1", &[disabled], &[], &HashMap::new());
+
+        assert!(report.sections.is_empty());
+        assert!(!report.ready);
+    }
+}
@@ -4,6 +4,9 @@ use std::path::PathBuf;
 use crate::models::project_models::{GeneratedDocument, MigrationProject, MigrationCluster, VMPlacement};
 use crate::database::Database;
 use docx_rs::*;
+use crate::database::TracedQuery;
+use crate::models::usage_telemetry::UsageFeature;
+use crate::services::usage_telemetry_service;
 
 /// HLD Generation Service
 /// 
@@ -76,6 +79,7 @@ impl HLDGenerationService {
         &self,
         request: HLDGenerationRequest,
     ) -> Result<HLDGenerationResult, Box<dyn Error>> {
+        usage_telemetry_service::record(UsageFeature::HldGeneration);
         let start_time = std::time::Instant::now();
 
         // Fetch project data
@@ -198,6 +202,15 @@ impl HLDGenerationService {
             generated_at: now,
             generated_by: "system".to_string(), // TODO: Get from auth context
             expires_at: None,
+            version: 1,
+            previous_version: None,
+            generation_parameters: std::collections::HashMap::new(),
+            section_snapshot: Vec::new(),
+            storage_backend: crate::models::workflow::StorageBackend::Disk,
+            locked: false,
+            approval_record: None,
+            published_file_path: None,
+            variants: Vec::new(),
         };
 
         // Save to database
@@ -232,6 +245,7 @@ impl HLDGenerationService {
             .db
             .query(query)
             .bind(("project_id", project_id))
+            .traced("hld_generation_service.fetch_project_clusters")
             .await?;
 
         let clusters: Vec<MigrationCluster> = result.take(0)?;
@@ -248,6 +262,7 @@ impl HLDGenerationService {
             .db
             .query(query)
             .bind(("project_id", project_id))
+            .traced("hld_generation_service.fetch_vm_placements")
             .await?;
 
         let placements: Vec<VMPlacement> = result.take(0)?;
@@ -0,0 +1,912 @@
+// Archer - Spreadsheet Import Service
+// Reads CSV/XLSX asset lists, maps columns onto configuration_items or
+// hardware_pool fields, validates every row, and upserts by serial number or
+// asset tag. Dry runs report what would change without writing.
+
+use calamine::{DataType, Reader, Xlsx};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::cmdb::{CreateCIRequest, UpdateCIRequest};
+use crate::models::import::*;
+use crate::services::cmdb_service::CMDBService;
+use crate::services::hardware_pool_service::{CreateHardwarePoolRequest, HardwarePoolService};
+use crate::database::TracedQuery;
+use crate::models::usage_telemetry::UsageFeature;
+use crate::services::usage_telemetry_service;
+
+/// Rows returned by a preview
+const PREVIEW_ROWS: usize = 10;
+/// Largest sheet accepted in one import
+pub const MAX_IMPORT_ROWS: usize = 10_000;
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Unsupported file type '{0}'; upload a .csv or .xlsx file")]
+    UnsupportedFormat(String),
+
+    #[error("Could not read spreadsheet: {0}")]
+    Parse(String),
+
+    #[error("Invalid column mapping: {0}")]
+    Mapping(String),
+
+    #[error("Import record not found")]
+    NotFound,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for ImportError {
+    fn from(e: surrealdb::Error) -> Self {
+        ImportError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// TARGET FIELDS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+enum FieldKind {
+    Text,
+    Integer,
+    Number,
+    Date,
+    List,
+    Choice(&'static [&'static str]),
+}
+
+struct FieldSpec {
+    name: &'static str,
+    kind: FieldKind,
+    required: bool,
+    /// Upsert keys, tried in declaration order
+    key: bool,
+}
+
+const fn field(name: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { name, kind, required: false, key: false }
+}
+
+const fn required(name: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { name, kind, required: true, key: false }
+}
+
+const fn key(name: &'static str, required: bool) -> FieldSpec {
+    FieldSpec { name, kind: FieldKind::Text, required, key: true }
+}
+
+const CI_CLASSES: &[&str] = &[
+    "HARDWARE", "SOFTWARE", "SERVICE", "DOCUMENT", "NETWORK", "CLOUD", "CONTAINER", "DATABASE", "VIRTUAL",
+];
+const CI_STATUSES: &[&str] = &[
+    "PLANNED", "ORDERED", "RECEIVED", "IN_STOCK", "DEPLOYED", "ACTIVE", "MAINTENANCE", "RETIRED", "DISPOSED",
+    "MISSING",
+];
+const CI_CRITICALITIES: &[&str] = &["CRITICAL", "HIGH", "MEDIUM", "LOW", "NONE"];
+
+const CI_FIELDS: &[FieldSpec] = &[
+    key("serial_number", false),
+    key("asset_tag", false),
+    key("ci_id", false),
+    required("name", FieldKind::Text),
+    required("ci_class", FieldKind::Choice(CI_CLASSES)),
+    required("ci_type", FieldKind::Text),
+    field("description", FieldKind::Text),
+    field("status", FieldKind::Choice(CI_STATUSES)),
+    field("criticality", FieldKind::Choice(CI_CRITICALITIES)),
+    field("environment", FieldKind::Text),
+    field("location", FieldKind::Text),
+    field("owner_id", FieldKind::Text),
+    field("support_group", FieldKind::Text),
+    field("vendor", FieldKind::Text),
+    field("model", FieldKind::Text),
+    field("version", FieldKind::Text),
+    field("ip_address", FieldKind::Text),
+    field("fqdn", FieldKind::Text),
+    field("tags", FieldKind::List),
+    field("install_date", FieldKind::Date),
+    field("warranty_expiry", FieldKind::Date),
+    field("end_of_life", FieldKind::Date),
+];
+
+const HARDWARE_FIELDS: &[FieldSpec] = &[
+    key("asset_tag", true),
+    key("serial_number", false),
+    required("vendor", FieldKind::Text),
+    required("model", FieldKind::Text),
+    field("form_factor", FieldKind::Text),
+    field("cpu_sockets", FieldKind::Integer),
+    field("cpu_cores_total", FieldKind::Integer),
+    field("memory_gb", FieldKind::Integer),
+    field("storage_type", FieldKind::Text),
+    field("storage_capacity_gb", FieldKind::Integer),
+    field("network_ports", FieldKind::Integer),
+    field("power_consumption_watts", FieldKind::Integer),
+    field("rack_units", FieldKind::Integer),
+    field("location", FieldKind::Text),
+    field("datacenter", FieldKind::Text),
+    field("rack_position", FieldKind::Text),
+    field("acquisition_cost", FieldKind::Number),
+    field("monthly_cost", FieldKind::Number),
+    field("warranty_expires", FieldKind::Date),
+    field("support_level", FieldKind::Text),
+];
+
+fn target_fields(target: ImportTarget) -> &'static [FieldSpec] {
+    match target {
+        ImportTarget::ConfigurationItems => CI_FIELDS,
+        ImportTarget::HardwarePool => HARDWARE_FIELDS,
+    }
+}
+
+/// Common spreadsheet headings that don't normalise to a field name
+const HEADER_ALIASES: &[(&str, &str)] = &[
+    ("serial", "serial_number"),
+    ("serialno", "serial_number"),
+    ("sn", "serial_number"),
+    ("assettag", "asset_tag"),
+    ("tag", "asset_tag"),
+    ("hostname", "name"),
+    ("class", "ci_class"),
+    ("type", "ci_type"),
+    ("manufacturer", "vendor"),
+    ("make", "vendor"),
+    ("ip", "ip_address"),
+    ("site", "location"),
+    ("dc", "datacenter"),
+    ("sockets", "cpu_sockets"),
+    ("cores", "cpu_cores_total"),
+    ("ram", "memory_gb"),
+    ("memory", "memory_gb"),
+    ("storage", "storage_capacity_gb"),
+    ("watts", "power_consumption_watts"),
+    ("ru", "rack_units"),
+    ("warranty", "warranty_expires"),
+];
+
+/// Field list for the mapping UI
+pub fn field_info(target: ImportTarget) -> Vec<ImportFieldInfo> {
+    target_fields(target)
+        .iter()
+        .map(|spec| ImportFieldInfo {
+            name: spec.name,
+            required: spec.required,
+            kind: match spec.kind {
+                FieldKind::Text => "text".to_string(),
+                FieldKind::Integer => "integer".to_string(),
+                FieldKind::Number => "number".to_string(),
+                FieldKind::Date => "date".to_string(),
+                FieldKind::List => "list".to_string(),
+                FieldKind::Choice(values) => values.join("|"),
+            },
+            is_key: spec.key,
+        })
+        .collect()
+}
+
+// ============================================================================
+// SPREADSHEET PARSING
+// ============================================================================
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedSheet {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub sheets: Vec<String>,
+}
+
+/// Read a CSV or XLSX upload into header + string rows
+pub fn parse_spreadsheet(filename: &str, bytes: &[u8], sheet: Option<&str>) -> Result<ParsedSheet, ImportError> {
+    let extension = filename.rsplit('.').next().unwrap_or_default().to_lowercase();
+    let parsed = match extension.as_str() {
+        "csv" | "txt" => parse_csv(bytes)?,
+        "xlsx" | "xlsm" => parse_xlsx(bytes, sheet)?,
+        other => return Err(ImportError::UnsupportedFormat(other.to_string())),
+    };
+
+    if parsed.headers.is_empty() {
+        return Err(ImportError::Parse("the sheet has no header row".to_string()));
+    }
+    if parsed.rows.len() > MAX_IMPORT_ROWS {
+        return Err(ImportError::Parse(format!(
+            "{} rows exceeds the limit of {}",
+            parsed.rows.len(),
+            MAX_IMPORT_ROWS
+        )));
+    }
+    Ok(parsed)
+}
+
+fn parse_csv(bytes: &[u8]) -> Result<ParsedSheet, ImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| ImportError::Parse(e.to_string()))?
+        .iter()
+        .map(|h| h.trim_start_matches('\u{feff}').to_string())
+        .collect();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| ImportError::Parse(e.to_string()))?;
+        rows.push(record.iter().map(str::to_string).collect());
+    }
+
+    Ok(ParsedSheet {
+        headers,
+        rows,
+        sheets: Vec::new(),
+    })
+}
+
+fn parse_xlsx(bytes: &[u8], sheet: Option<&str>) -> Result<ParsedSheet, ImportError> {
+    let mut workbook: Xlsx<_> =
+        Xlsx::new(Cursor::new(bytes.to_vec())).map_err(|e| ImportError::Parse(e.to_string()))?;
+    let sheets = workbook.sheet_names().to_vec();
+    let name = match sheet {
+        Some(name) => name.to_string(),
+        None => sheets
+            .first()
+            .cloned()
+            .ok_or_else(|| ImportError::Parse("workbook has no sheets".to_string()))?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .ok_or_else(|| ImportError::Parse(format!("sheet '{}' not found", name)))?
+        .map_err(|e| ImportError::Parse(e.to_string()))?;
+
+    let mut rows = range.rows().map(|row| row.iter().map(cell_to_string).collect::<Vec<_>>());
+    let headers = rows.next().unwrap_or_default();
+    Ok(ParsedSheet {
+        headers,
+        rows: rows.collect(),
+        sheets,
+    })
+}
+
+fn cell_to_string(cell: &DataType) -> String {
+    match cell {
+        DataType::Empty => String::new(),
+        DataType::String(s) => s.trim().to_string(),
+        DataType::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", *f as i64),
+        DataType::DateTime(serial) => excel_serial_to_date(*serial)
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| serial.to_string()),
+        other => other.to_string(),
+    }
+}
+
+/// Excel stores dates as days since 1899-12-30
+fn excel_serial_to_date(serial: f64) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(1899, 12, 30)?.checked_add_signed(chrono::Duration::days(serial.trunc() as i64))
+}
+
+fn normalise(header: &str) -> String {
+    header.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+/// Match headers to target fields by normalised name or a known alias
+pub fn suggest_mapping(target: ImportTarget, headers: &[String]) -> HashMap<String, String> {
+    let fields = target_fields(target);
+    let mut mapping = HashMap::new();
+    for header in headers {
+        let key = normalise(header);
+        let matched = fields
+            .iter()
+            .find(|spec| normalise(spec.name) == key)
+            .map(|spec| spec.name)
+            .or_else(|| {
+                HEADER_ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == key)
+                    .map(|(_, field)| *field)
+                    .filter(|field| fields.iter().any(|spec| spec.name == *field))
+            });
+        if let Some(field) = matched {
+            mapping.entry(field.to_string()).or_insert_with(|| header.clone());
+        }
+    }
+    mapping
+}
+
+// ============================================================================
+// ROW VALIDATION
+// ============================================================================
+
+/// A validated row ready to write
+#[derive(Debug, Clone)]
+pub struct MappedRow {
+    pub row: usize,
+    pub key_field: &'static str,
+    pub key: String,
+    pub values: Map<String, JsonValue>,
+}
+
+/// Validate every row against the mapping; returns good rows and row-level errors
+pub fn validate_rows(
+    target: ImportTarget,
+    sheet: &ParsedSheet,
+    mapping: &HashMap<String, String>,
+    defaults: &HashMap<String, String>,
+) -> Result<(Vec<MappedRow>, Vec<ImportRowError>), ImportError> {
+    let fields = target_fields(target);
+
+    // Mapping-level problems stop the import before any row is read
+    let mut columns: HashMap<&str, usize> = HashMap::new();
+    for (field, header) in mapping {
+        let spec = fields
+            .iter()
+            .find(|spec| spec.name == field)
+            .ok_or_else(|| ImportError::Mapping(format!("unknown target field '{}'", field)))?;
+        let index = sheet
+            .headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(header.trim()))
+            .ok_or_else(|| ImportError::Mapping(format!("column '{}' not found in the file", header)))?;
+        columns.insert(spec.name, index);
+    }
+    for spec in fields.iter().filter(|s| s.required) {
+        if !columns.contains_key(spec.name) && !defaults.contains_key(spec.name) {
+            return Err(ImportError::Mapping(format!("required field '{}' is not mapped", spec.name)));
+        }
+    }
+    if !fields.iter().any(|s| s.key && (columns.contains_key(s.name) || defaults.contains_key(s.name))) {
+        let keys: Vec<_> = fields.iter().filter(|s| s.key).map(|s| s.name).collect();
+        return Err(ImportError::Mapping(format!("map at least one key column: {}", keys.join(", "))));
+    }
+
+    let mut valid = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen_keys: HashMap<String, usize> = HashMap::new();
+
+    for (index, cells) in sheet.rows.iter().enumerate() {
+        let row = index + 2; // header is row 1
+        if is_blank(cells) {
+            continue;
+        }
+        let mut values = Map::new();
+        let mut row_errors = Vec::new();
+
+        for spec in fields {
+            let raw = columns
+                .get(spec.name)
+                .and_then(|i| cells.get(*i))
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .or_else(|| defaults.get(spec.name).map(|s| s.trim()));
+            let Some(raw) = raw else {
+                if spec.required {
+                    row_errors.push(row_error(row, spec.name, "value is required"));
+                }
+                continue;
+            };
+            match convert(spec.kind, raw) {
+                Ok(value) => {
+                    values.insert(spec.name.to_string(), value);
+                }
+                Err(message) => row_errors.push(row_error(row, spec.name, &message)),
+            }
+        }
+
+        let key = fields
+            .iter()
+            .filter(|s| s.key)
+            .find_map(|s| values.get(s.name).and_then(|v| v.as_str()).map(|v| (s.name, v.to_string())));
+        let Some((key_field, key)) = key else {
+            if row_errors.is_empty() {
+                row_errors.push(ImportRowError {
+                    row,
+                    field: None,
+                    message: "row has no serial number or asset tag to match on".to_string(),
+                });
+            }
+            errors.extend(row_errors);
+            continue;
+        };
+
+        let dedupe_key = format!("{}={}", key_field, key.to_lowercase());
+        if let Some(first) = seen_keys.get(&dedupe_key) {
+            row_errors.push(row_error(row, key_field, &format!("duplicate of row {}", first)));
+        }
+
+        if row_errors.is_empty() {
+            seen_keys.insert(dedupe_key, row);
+            valid.push(MappedRow {
+                row,
+                key_field,
+                key,
+                values,
+            });
+        } else {
+            errors.extend(row_errors);
+        }
+    }
+
+    Ok((valid, errors))
+}
+
+fn is_blank(cells: &[String]) -> bool {
+    cells.iter().all(|cell| cell.trim().is_empty())
+}
+
+fn row_error(row: usize, field: &str, message: &str) -> ImportRowError {
+    ImportRowError {
+        row,
+        field: Some(field.to_string()),
+        message: message.to_string(),
+    }
+}
+
+fn convert(kind: FieldKind, raw: &str) -> Result<JsonValue, String> {
+    match kind {
+        FieldKind::Text => Ok(JsonValue::String(raw.to_string())),
+        FieldKind::Integer => raw
+            .replace(',', "")
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.fract() == 0.0 && n.abs() <= i32::MAX as f64)
+            .map(|n| JsonValue::from(n as i64))
+            .ok_or_else(|| format!("'{}' is not a whole number", raw)),
+        FieldKind::Number => raw
+            .trim_start_matches(['$', '€', '£'])
+            .replace(',', "")
+            .parse::<f64>()
+            .map(JsonValue::from)
+            .map_err(|_| format!("'{}' is not a number", raw)),
+        FieldKind::Date => parse_date(raw)
+            .map(|d| JsonValue::String(d.to_rfc3339()))
+            .ok_or_else(|| format!("'{}' is not a date (use YYYY-MM-DD)", raw)),
+        FieldKind::List => Ok(JsonValue::Array(
+            raw.split([',', ';'])
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| JsonValue::String(s.to_string()))
+                .collect(),
+        )),
+        FieldKind::Choice(allowed) => {
+            let value = raw.trim().to_uppercase().replace([' ', '-'], "_");
+            if allowed.contains(&value.as_str()) {
+                Ok(JsonValue::String(value))
+            } else {
+                Err(format!("'{}' must be one of {}", raw, allowed.join(", ")))
+            }
+        }
+    }
+}
+
+fn parse_date(raw: &str) -> Option<DateTime<Utc>> {
+    if let Ok(d) = DateTime::parse_from_rfc3339(raw) {
+        return Some(d.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y", "%Y/%m/%d"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(raw, fmt).ok())
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| Utc.from_utc_datetime(&d))
+}
+
+// ============================================================================
+// IMPORT SERVICE
+// ============================================================================
+
+pub struct ImportService {
+    db: Arc<Database>,
+}
+
+impl ImportService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub fn preview(
+        &self,
+        target: ImportTarget,
+        filename: &str,
+        bytes: &[u8],
+        sheet: Option<&str>,
+    ) -> Result<ImportPreview, ImportError> {
+        let parsed = parse_spreadsheet(filename, bytes, sheet)?;
+        Ok(ImportPreview {
+            suggested_mapping: suggest_mapping(target, &parsed.headers),
+            sample_rows: parsed.rows.iter().take(PREVIEW_ROWS).cloned().collect(),
+            total_rows: parsed.rows.len(),
+            headers: parsed.headers,
+            sheets: parsed.sheets,
+        })
+    }
+
+    /// Validate and (unless dry-run) upsert every row, then record the run in history
+    pub async fn run_import(
+        &self,
+        user: &AuthenticatedUser,
+        filename: &str,
+        bytes: &[u8],
+        options: ImportOptions,
+    ) -> Result<ImportRun, ImportError> {
+        let started_at = Utc::now();
+        let (mapping, defaults) = self.resolve_mapping(&options).await?;
+        let parsed = parse_spreadsheet(filename, bytes, options.sheet.as_deref())?;
+        usage_telemetry_service::record(UsageFeature::SpreadsheetImport);
+        let (rows, mut errors) = validate_rows(options.target, &parsed, &mapping, &defaults)?;
+        let tenant = user.tenant_id.as_deref();
+
+        let mut outcomes = Vec::new();
+        for row in rows {
+            let existing = self.find_existing(options.target, &row, tenant).await?;
+            let action = if existing.is_some() {
+                ImportRowAction::Update
+            } else {
+                ImportRowAction::Create
+            };
+
+            let record_id = if options.dry_run {
+                existing.map(|t| t.to_string())
+            } else {
+                let result = match options.target {
+                    ImportTarget::ConfigurationItems => self.write_ci(user, &row, existing).await,
+                    ImportTarget::HardwarePool => self.write_hardware(&row, existing).await,
+                };
+                match result {
+                    Ok(id) => Some(id),
+                    Err(message) => {
+                        errors.push(ImportRowError {
+                            row: row.row,
+                            field: None,
+                            message,
+                        });
+                        continue;
+                    }
+                }
+            };
+
+            outcomes.push(ImportRowOutcome {
+                row: row.row,
+                key: row.key.clone(),
+                action,
+                record_id,
+            });
+        }
+
+        errors.sort_by_key(|e| e.row);
+        let failed_rows: HashSet<usize> = errors.iter().map(|e| e.row).collect();
+        let count = |action: ImportRowAction| outcomes.iter().filter(|o| o.action == action).count();
+        let status = match (options.dry_run, outcomes.is_empty(), failed_rows.is_empty()) {
+            (true, _, _) => ImportStatus::Validated,
+            (false, _, true) => ImportStatus::Completed,
+            (false, true, false) => ImportStatus::Failed,
+            (false, false, false) => ImportStatus::CompletedWithErrors,
+        };
+
+        let run = ImportRun {
+            id: None,
+            target: options.target,
+            filename: filename.to_string(),
+            dry_run: options.dry_run,
+            status,
+            mapping,
+            total_rows: parsed.rows.iter().filter(|r| !is_blank(r)).count(),
+            created: count(ImportRowAction::Create),
+            updated: count(ImportRowAction::Update),
+            failed: failed_rows.len(),
+            errors,
+            outcomes,
+            started_by: user.username.clone(),
+            started_at,
+            completed_at: Utc::now(),
+            tenant_id: tenant.map(|t| Thing::from(("tenants", t))),
+        };
+
+        let created: Vec<ImportRun> = self.db.create("import_runs").content(&run).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ImportError::DatabaseError("Failed to record import run".to_string()))
+    }
+
+    pub async fn list_runs(
+        &self,
+        user: &AuthenticatedUser,
+        target: Option<ImportTarget>,
+    ) -> Result<Vec<ImportRun>, ImportError> {
+        let tenant = user.tenant_id.as_deref().map(|t| Thing::from(("tenants", t)));
+        let mut runs: Vec<ImportRun> = self
+            .db
+            .query("SELECT * FROM import_runs ORDER BY started_at DESC LIMIT 500")
+            .traced("import_service.list_runs")
+            .await?
+            .take(0)?;
+        runs.retain(|r| r.tenant_id == tenant);
+        if let Some(target) = target {
+            runs.retain(|r| r.target == target);
+        }
+        Ok(runs)
+    }
+
+    pub async fn get_run(&self, user: &AuthenticatedUser, id: &str) -> Result<ImportRun, ImportError> {
+        let run: Option<ImportRun> = self.db.select(parse_thing("import_runs", id)).await?;
+        let tenant = user.tenant_id.as_deref().map(|t| Thing::from(("tenants", t)));
+        run.filter(|r| r.tenant_id == tenant).ok_or(ImportError::NotFound)
+    }
+
+    // ========================================================================
+    // MAPPING PROFILES
+    // ========================================================================
+
+    pub async fn list_mappings(&self, target: Option<ImportTarget>) -> Result<Vec<ImportMappingProfile>, ImportError> {
+        let mut profiles: Vec<ImportMappingProfile> =
+            self.db.query("SELECT * FROM import_mappings ORDER BY name ASC").traced("import_service.list_mappings").await?.take(0)?;
+        if let Some(target) = target {
+            profiles.retain(|p| p.target == target);
+        }
+        Ok(profiles)
+    }
+
+    pub async fn create_mapping(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateMappingProfileRequest,
+    ) -> Result<ImportMappingProfile, ImportError> {
+        if request.name.trim().is_empty() {
+            return Err(ImportError::Mapping("profile name is required".to_string()));
+        }
+        let fields = target_fields(request.target);
+        for field in request.mapping.keys().chain(request.defaults.keys()) {
+            if !fields.iter().any(|spec| spec.name == field) {
+                return Err(ImportError::Mapping(format!("unknown target field '{}'", field)));
+            }
+        }
+
+        let profile = ImportMappingProfile {
+            id: None,
+            name: request.name.trim().to_string(),
+            target: request.target,
+            mapping: request.mapping,
+            defaults: request.defaults,
+            created_by: user.username.clone(),
+            created_at: Utc::now(),
+        };
+        let created: Vec<ImportMappingProfile> = self.db.create("import_mappings").content(&profile).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ImportError::DatabaseError("Failed to create mapping profile".to_string()))
+    }
+
+    pub async fn delete_mapping(&self, id: &str) -> Result<(), ImportError> {
+        let deleted: Option<ImportMappingProfile> = self.db.delete(parse_thing("import_mappings", id)).await?;
+        deleted.map(|_| ()).ok_or(ImportError::NotFound)
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    /// Saved profile (if any) overlaid with the request's own mapping and defaults
+    async fn resolve_mapping(
+        &self,
+        options: &ImportOptions,
+    ) -> Result<(HashMap<String, String>, HashMap<String, String>), ImportError> {
+        let (mut mapping, mut defaults) = match &options.mapping_id {
+            Some(id) => {
+                let profile: Option<ImportMappingProfile> =
+                    self.db.select(parse_thing("import_mappings", id)).await?;
+                let profile = profile.ok_or(ImportError::NotFound)?;
+                if profile.target != options.target {
+                    return Err(ImportError::Mapping("mapping profile is for a different target".to_string()));
+                }
+                (profile.mapping, profile.defaults)
+            }
+            None => (HashMap::new(), HashMap::new()),
+        };
+        mapping.extend(options.mapping.clone());
+        defaults.extend(options.defaults.clone());
+        Ok((mapping, defaults))
+    }
+
+    async fn find_existing(
+        &self,
+        target: ImportTarget,
+        row: &MappedRow,
+        tenant: Option<&str>,
+    ) -> Result<Option<Thing>, ImportError> {
+        let (table, condition) = match (target, row.key_field) {
+            (ImportTarget::ConfigurationItems, "asset_tag") => ("configuration_items", "attributes.asset_tag = $key"),
+            (ImportTarget::ConfigurationItems, "ci_id") => ("configuration_items", "ci_id = $key"),
+            (ImportTarget::ConfigurationItems, _) => ("configuration_items", "serial_number = $key"),
+            (ImportTarget::HardwarePool, "serial_number") => ("hardware_pool", "serial_number = $key"),
+            (ImportTarget::HardwarePool, _) => ("hardware_pool", "asset_tag = $key"),
+        };
+        // CIs are tenant-scoped; the hardware pool is shared
+        let tenant = tenant.filter(|_| target == ImportTarget::ConfigurationItems);
+        let sql = format!(
+            "SELECT VALUE id FROM {} WHERE {}{} LIMIT 1",
+            table,
+            condition,
+            if tenant.is_some() { " AND tenant_id = $tenant" } else { "" }
+        );
+
+        let mut query = self.db.query(sql).bind(("key", row.key.clone()));
+        if let Some(tenant) = tenant {
+            query = query.bind(("tenant", Thing::from(("tenants", tenant))));
+        }
+        let ids: Vec<Thing> = query.await?.take(0)?;
+        Ok(ids.into_iter().next())
+    }
+
+    async fn write_ci(
+        &self,
+        user: &AuthenticatedUser,
+        row: &MappedRow,
+        existing: Option<Thing>,
+    ) -> Result<String, String> {
+        let mut values = row.values.clone();
+        let asset_tag = values.remove("asset_tag");
+
+        match existing {
+            Some(id) => {
+                values.insert(
+                    "change_reason".to_string(),
+                    JsonValue::String("Spreadsheet import".to_string()),
+                );
+                let request: UpdateCIRequest =
+                    serde_json::from_value(JsonValue::Object(values)).map_err(|e| e.to_string())?;
+                CMDBService::update_ci(self.db.clone(), &id.to_string(), request, &user.user_id, &user.username)
+                    .await?;
+                Ok(id.to_string())
+            }
+            None => {
+                if let Some(tag) = asset_tag {
+                    values.insert("attributes".to_string(), serde_json::json!({ "asset_tag": tag }));
+                }
+                let request: CreateCIRequest =
+                    serde_json::from_value(JsonValue::Object(values)).map_err(|e| e.to_string())?;
+                let created = CMDBService::create_ci(
+                    self.db.clone(),
+                    request,
+                    &user.user_id,
+                    &user.username,
+                    user.tenant_id.as_deref(),
+                )
+                .await?;
+                let id = created.id.ok_or("CI was created without an id")?;
+                self.db
+                    .query("UPDATE $id SET discovery_source = 'IMPORT', last_discovered = time::now()")
+                    .bind(("id", id.clone()))
+                    .traced("import_service.write_ci")
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(id.to_string())
+            }
+        }
+    }
+
+    async fn write_hardware(&self, row: &MappedRow, existing: Option<Thing>) -> Result<String, String> {
+        match existing {
+            Some(id) => {
+                let mut values = row.values.clone();
+                values.insert("updated_at".to_string(), JsonValue::String(Utc::now().to_rfc3339()));
+                let _: Option<JsonValue> = self
+                    .db
+                    .update(id.clone())
+                    .merge(JsonValue::Object(values))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(id.to_string())
+            }
+            None => {
+                let request: CreateHardwarePoolRequest =
+                    serde_json::from_value(JsonValue::Object(row.values.clone())).map_err(|e| e.to_string())?;
+                let created = HardwarePoolService::new((*self.db).clone())
+                    .add_server_to_pool(request)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                created
+                    .id
+                    .map(|id| id.to_string())
+                    .ok_or_else(|| "Hardware was created without an id".to_string())
+            }
+        }
+    }
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "Serial,Hostname,Class,Type,Criticality,Warranty Expiry\n\
+        SN-1,web01,hardware,Server,high,2027-03-31\n\
+        SN-2,web02,HARDWARE,Server,urgent,31/03/2027\n\
+        ,db01,HARDWARE,Server,LOW,\n\
+        SN-1,web01-dup,HARDWARE,Server,LOW,\n";
+
+    fn mapping(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(f, h)| (f.to_string(), h.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_csv_and_suggest_mapping() {
+        let sheet = parse_spreadsheet("assets.csv", CSV.as_bytes(), None).unwrap();
+        assert_eq!(sheet.headers.len(), 6);
+        assert_eq!(sheet.rows.len(), 4);
+
+        let suggested = suggest_mapping(ImportTarget::ConfigurationItems, &sheet.headers);
+        assert_eq!(suggested["serial_number"], "Serial");
+        assert_eq!(suggested["name"], "Hostname");
+        assert_eq!(suggested["ci_class"], "Class");
+        assert!(parse_spreadsheet("assets.pdf", b"", None).is_err());
+    }
+
+    #[test]
+    fn test_validate_rows_reports_row_level_errors() {
+        let sheet = parse_spreadsheet("assets.csv", CSV.as_bytes(), None).unwrap();
+        let mapping = mapping(&[
+            ("serial_number", "Serial"),
+            ("name", "Hostname"),
+            ("ci_class", "Class"),
+            ("ci_type", "Type"),
+            ("criticality", "Criticality"),
+            ("warranty_expiry", "Warranty Expiry"),
+        ]);
+
+        let (valid, errors) =
+            validate_rows(ImportTarget::ConfigurationItems, &sheet, &mapping, &HashMap::new()).unwrap();
+        assert_eq!(valid.len(), 1);
+        assert_eq!(valid[0].key, "SN-1");
+        assert_eq!(valid[0].values["ci_class"], "HARDWARE");
+        assert_eq!(valid[0].values["criticality"], "HIGH");
+
+        let rows: Vec<_> = errors.iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![3, 4, 5]);
+        assert_eq!(errors[0].field.as_deref(), Some("criticality"));
+        assert!(errors[2].message.contains("duplicate of row 2"));
+    }
+
+    #[test]
+    fn test_mapping_errors_stop_the_import() {
+        let sheet = parse_spreadsheet("pool.csv", b"Tag,Make\nA1,Dell\n", None).unwrap();
+        let err = validate_rows(
+            ImportTarget::HardwarePool,
+            &sheet,
+            &mapping(&[("asset_tag", "Tag"), ("vendor", "Make")]),
+            &HashMap::new(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'model'"));
+
+        let defaults = mapping(&[("model", "PowerEdge R760")]);
+        let (valid, errors) = validate_rows(
+            ImportTarget::HardwarePool,
+            &sheet,
+            &mapping(&[("asset_tag", "Tag"), ("vendor", "Make")]),
+            &defaults,
+        )
+        .unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(valid[0].values["model"], "PowerEdge R760");
+    }
+}
@@ -0,0 +1,451 @@
+use async_trait::async_trait;
+use ldap3::adapters::{Adapter, EntriesOnly, PagedResults};
+use ldap3::{Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use super::connector::{
+    AssetLink, ConnectorError, DiscoveredAsset, IntegrationConfig, IntegrationConnector, InventorySnapshot,
+    LinkResolution,
+};
+
+/// AD answers at most 1000 entries per page by default
+const PAGE_SIZE: i32 = 500;
+
+pub const KIND_DOMAIN: &str = "DOMAIN";
+pub const KIND_DOMAIN_CONTROLLER: &str = "DOMAIN_CONTROLLER";
+pub const KIND_AD_SITE: &str = "AD_SITE";
+pub const KIND_AD_SUBNET: &str = "AD_SUBNET";
+
+/// `options` flag on NTDS Settings marking a global catalog
+const NTDSDSA_OPT_IS_GC: u32 = 1;
+
+/// Active Directory collector over LDAP. Everything it needs lives in the
+/// forest-wide configuration partition, so a bind against any DC sees all
+/// domains, domain controllers, sites and site subnets.
+pub struct ActiveDirectoryClient {
+    config: IntegrationConfig,
+}
+
+/// The parts of the directory a snapshot is built from
+#[derive(Debug, Default)]
+struct DirectoryData {
+    root_dse: Option<SearchEntry>,
+    domains: Vec<SearchEntry>,
+    servers: Vec<SearchEntry>,
+    ntds_settings: Vec<SearchEntry>,
+    sites: Vec<SearchEntry>,
+    subnets: Vec<SearchEntry>,
+}
+
+impl ActiveDirectoryClient {
+    fn is_mock(&self) -> bool {
+        self.config.base_url.starts_with("mock://")
+    }
+
+    async fn connect(&self) -> Result<Ldap, ConnectorError> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(Duration::from_secs(30))
+            .set_no_tls_verify(!self.config.verify_tls);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &self.config.base_url).await?;
+        ldap3::drive!(conn);
+
+        let username = self.config.username.as_deref().unwrap_or_default();
+        ldap.simple_bind(username, &self.config.auth_token)
+            .await?
+            .success()
+            .map_err(|e| format!("Active Directory rejected the bind: {}", e))?;
+        Ok(ldap)
+    }
+
+    async fn search(
+        ldap: &mut Ldap,
+        base: &str,
+        scope: Scope,
+        filter: &str,
+        attrs: &[&str],
+    ) -> Result<Vec<SearchEntry>, ConnectorError> {
+        let adapters: Vec<Box<dyn Adapter<_, _>>> =
+            vec![Box::new(EntriesOnly::new()), Box::new(PagedResults::new(PAGE_SIZE))];
+        let mut stream = ldap
+            .streaming_search_with(adapters, base, scope, filter, attrs.to_vec())
+            .await?;
+        let mut entries = Vec::new();
+        while let Some(entry) = stream.next().await? {
+            entries.push(SearchEntry::construct(entry));
+        }
+        stream.finish().await.success()?;
+        Ok(entries)
+    }
+
+    async fn read_directory(&self) -> Result<DirectoryData, ConnectorError> {
+        let mut ldap = self.connect().await?;
+        let root_dse = Self::search(
+            &mut ldap,
+            "",
+            Scope::Base,
+            "(objectClass=*)",
+            &["configurationNamingContext", "rootDomainNamingContext", "forestFunctionality"],
+        )
+        .await?
+        .into_iter()
+        .next();
+        let configuration = root_dse
+            .as_ref()
+            .and_then(|r| first(r, "configurationNamingContext"))
+            .ok_or("RootDSE has no configurationNamingContext")?
+            .to_string();
+        let sites_base = format!("CN=Sites,{}", configuration);
+
+        let data = DirectoryData {
+            domains: Self::search(
+                &mut ldap,
+                &format!("CN=Partitions,{}", configuration),
+                Scope::OneLevel,
+                // systemFlags bit 2 marks domain naming contexts
+                "(&(objectClass=crossRef)(systemFlags:1.2.840.113556.1.4.803:=2))",
+                &["nCName", "dnsRoot", "nETBIOSName", "msDS-Behavior-Version"],
+            )
+            .await?,
+            servers: Self::search(
+                &mut ldap,
+                &sites_base,
+                Scope::Subtree,
+                "(objectClass=server)",
+                &["name", "dNSHostName", "serverReference"],
+            )
+            .await?,
+            ntds_settings: Self::search(
+                &mut ldap,
+                &sites_base,
+                Scope::Subtree,
+                "(|(objectCategory=nTDSDSA)(objectCategory=nTDSDSARO))",
+                &["options", "objectCategory"],
+            )
+            .await?,
+            sites: Self::search(&mut ldap, &sites_base, Scope::OneLevel, "(objectClass=site)", &["name", "description", "location"])
+                .await?,
+            subnets: Self::search(
+                &mut ldap,
+                &format!("CN=Subnets,{}", sites_base),
+                Scope::OneLevel,
+                "(objectClass=subnet)",
+                &["name", "siteObject", "description", "location"],
+            )
+            .await?,
+            root_dse,
+        };
+        let _ = ldap.unbind().await;
+        Ok(data)
+    }
+
+    fn mock_directory() -> DirectoryData {
+        let entry = |dn: &str, attrs: &[(&str, &str)]| {
+            let mut map: HashMap<String, Vec<String>> = HashMap::new();
+            for (k, v) in attrs {
+                map.entry(k.to_string()).or_default().push(v.to_string());
+            }
+            SearchEntry {
+                dn: dn.to_string(),
+                attrs: map,
+                bin_attrs: HashMap::new(),
+            }
+        };
+        let config = "CN=Configuration,DC=corp,DC=example,DC=com";
+        DirectoryData {
+            root_dse: Some(entry("", &[("configurationNamingContext", config), ("forestFunctionality", "7")])),
+            domains: vec![entry(
+                &format!("CN=CORP,CN=Partitions,{}", config),
+                &[("nCName", "DC=corp,DC=example,DC=com"), ("dnsRoot", "corp.example.com"), ("nETBIOSName", "CORP")],
+            )],
+            servers: vec![
+                entry(
+                    &format!("CN=DC01,CN=Servers,CN=HQ,CN=Sites,{}", config),
+                    &[
+                        ("name", "DC01"),
+                        ("dNSHostName", "dc01.corp.example.com"),
+                        ("serverReference", "CN=DC01,OU=Domain Controllers,DC=corp,DC=example,DC=com"),
+                    ],
+                ),
+                entry(
+                    &format!("CN=DC02,CN=Servers,CN=Branch,CN=Sites,{}", config),
+                    &[
+                        ("name", "DC02"),
+                        ("dNSHostName", "dc02.corp.example.com"),
+                        ("serverReference", "CN=DC02,OU=Domain Controllers,DC=corp,DC=example,DC=com"),
+                    ],
+                ),
+            ],
+            ntds_settings: vec![
+                entry(&format!("CN=NTDS Settings,CN=DC01,CN=Servers,CN=HQ,CN=Sites,{}", config), &[("options", "1")]),
+                entry(
+                    &format!("CN=NTDS Settings,CN=DC02,CN=Servers,CN=Branch,CN=Sites,{}", config),
+                    &[("options", "0"), ("objectCategory", &format!("CN=NTDS-DSA-RO,CN=Schema,{}", config))],
+                ),
+            ],
+            sites: vec![
+                entry(&format!("CN=HQ,CN=Sites,{}", config), &[("name", "HQ"), ("location", "Amsterdam")]),
+                entry(&format!("CN=Branch,CN=Sites,{}", config), &[("name", "Branch")]),
+            ],
+            subnets: vec![entry(
+                &format!("CN=10.100.0.0/24,CN=Subnets,CN=Sites,{}", config),
+                &[("name", "10.100.0.0/24"), ("siteObject", &format!("CN=HQ,CN=Sites,{}", config))],
+            )],
+        }
+    }
+}
+
+#[async_trait]
+impl IntegrationConnector for ActiveDirectoryClient {
+    fn new(config: IntegrationConfig) -> Self {
+        Self { config }
+    }
+
+    async fn test_connection(&self) -> Result<bool, ConnectorError> {
+        if self.is_mock() {
+            return Ok(true);
+        }
+        let mut ldap = self.connect().await?;
+        let _ = ldap.unbind().await;
+        Ok(true)
+    }
+
+    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, ConnectorError> {
+        Ok(self.fetch_snapshot().await?.assets)
+    }
+
+    async fn fetch_snapshot(&self) -> Result<InventorySnapshot, ConnectorError> {
+        let data = if self.is_mock() {
+            Self::mock_directory()
+        } else {
+            self.read_directory().await?
+        };
+
+        let mut assets = Vec::new();
+        assets.extend(data.domains.iter().filter_map(|d| map_domain(d, data.root_dse.as_ref())));
+        assets.extend(data.sites.iter().filter_map(map_site));
+        assets.extend(data.subnets.iter().filter_map(map_subnet));
+        for server in &data.servers {
+            let settings = data
+                .ntds_settings
+                .iter()
+                .find(|s| parent_dn(&s.dn).eq_ignore_ascii_case(&server.dn));
+            // Servers without NTDS Settings are demoted or never promoted
+            let Some(settings) = settings else { continue };
+            let ip_address = match (self.is_mock(), first(server, "dNSHostName")) {
+                (false, Some(host)) => resolve_ip(host).await,
+                _ => None,
+            };
+            assets.extend(map_domain_controller(server, settings, ip_address));
+        }
+
+        Ok(InventorySnapshot {
+            assets,
+            complete_kinds: [KIND_DOMAIN, KIND_DOMAIN_CONTROLLER, KIND_AD_SITE, KIND_AD_SUBNET]
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+            failed_kinds: Vec::new(),
+        })
+    }
+
+    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, ConnectorError> {
+        Ok(vec![])
+    }
+}
+
+/// Domain controllers register in DNS; their address is not in the directory
+async fn resolve_ip(host: &str) -> Option<String> {
+    tokio::net::lookup_host((host, 389))
+        .await
+        .ok()?
+        .find(|a| a.is_ipv4())
+        .map(|a| a.ip().to_string())
+}
+
+// ============================================================================
+// MAPPING
+// ============================================================================
+
+fn first<'a>(entry: &'a SearchEntry, attr: &str) -> Option<&'a str> {
+    entry.attrs.get(attr).and_then(|v| v.first()).map(String::as_str)
+}
+
+/// RDN values of a DN, outermost first: `CN=DC01,CN=Servers` -> [DC01, Servers]
+fn rdn_values(dn: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in dn.chars() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            ',' if !escaped => values.push(std::mem::take(&mut current)),
+            _ => {
+                current.push(c);
+                escaped = false;
+            }
+        }
+    }
+    values.push(current);
+    values
+        .into_iter()
+        .filter_map(|rdn| rdn.split_once('=').map(|(_, v)| v.trim().to_string()))
+        .collect()
+}
+
+fn parent_dn(dn: &str) -> &str {
+    let mut escaped = false;
+    for (i, c) in dn.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            ',' if !escaped => return &dn[i + 1..],
+            _ => escaped = false,
+        }
+    }
+    ""
+}
+
+/// `...,DC=corp,DC=example,DC=com` -> `corp.example.com`
+fn dns_from_dn(dn: &str) -> String {
+    dn.split(',')
+        .filter_map(|rdn| rdn.trim().strip_prefix("DC=").or_else(|| rdn.trim().strip_prefix("dc=")))
+        .collect::<Vec<_>>()
+        .join(".")
+        .to_lowercase()
+}
+
+fn site_id(name: &str) -> String {
+    format!("site:{}", name.to_lowercase())
+}
+
+fn link(relationship: &str, target: String) -> AssetLink {
+    AssetLink {
+        relationship: relationship.to_string(),
+        target_external_id: target,
+        resolve_by: LinkResolution::ExternalId,
+    }
+}
+
+fn map_domain(entry: &SearchEntry, root_dse: Option<&SearchEntry>) -> Option<DiscoveredAsset> {
+    let dns_name = first(entry, "dnsRoot")?.to_lowercase();
+    let raw = json!({
+        "dns_name": dns_name,
+        "netbios_name": first(entry, "nETBIOSName"),
+        "naming_context": first(entry, "nCName"),
+        "domain_functional_level": first(entry, "msDS-Behavior-Version"),
+        "forest_functional_level": root_dse.and_then(|r| first(r, "forestFunctionality")),
+    });
+    Some(DiscoveredAsset {
+        external_id: dns_name.clone(),
+        name: dns_name,
+        asset_type: KIND_DOMAIN.to_string(),
+        raw_data: raw,
+        links: Vec::new(),
+    })
+}
+
+fn map_site(entry: &SearchEntry) -> Option<DiscoveredAsset> {
+    let name = first(entry, "name")?.to_string();
+    Some(DiscoveredAsset {
+        external_id: site_id(&name),
+        name: name.clone(),
+        asset_type: KIND_AD_SITE.to_string(),
+        raw_data: json!({
+            "description": first(entry, "description"),
+            "location": first(entry, "location"),
+        }),
+        links: Vec::new(),
+    })
+}
+
+fn map_subnet(entry: &SearchEntry) -> Option<DiscoveredAsset> {
+    let cidr = first(entry, "name")?.to_string();
+    let site = first(entry, "siteObject").and_then(|dn| rdn_values(dn).into_iter().next());
+    Some(DiscoveredAsset {
+        external_id: format!("subnet:{}", cidr),
+        name: cidr.clone(),
+        asset_type: KIND_AD_SUBNET.to_string(),
+        raw_data: json!({
+            "cidr": cidr,
+            "site": site,
+            "description": first(entry, "description"),
+            "location": first(entry, "location"),
+        }),
+        links: site.iter().map(|s| link("CONTAINED_BY", site_id(s))).collect(),
+    })
+}
+
+/// Server objects sit at `CN=<dc>,CN=Servers,CN=<site>,CN=Sites,...`
+fn map_domain_controller(
+    server: &SearchEntry,
+    settings: &SearchEntry,
+    ip_address: Option<String>,
+) -> Option<DiscoveredAsset> {
+    let rdns = rdn_values(&server.dn);
+    let name = first(server, "name").map(str::to_string).or_else(|| rdns.first().cloned())?;
+    let site = rdns.get(2).cloned();
+    let hostname = first(server, "dNSHostName").map(str::to_lowercase);
+    let domain = first(server, "serverReference").map(dns_from_dn).filter(|d| !d.is_empty());
+    let options: u32 = first(settings, "options").and_then(|o| o.parse().ok()).unwrap_or(0);
+    let is_read_only = first(settings, "objectCategory").is_some_and(|c| c.starts_with("CN=NTDS-DSA-RO"));
+
+    let mut links = Vec::new();
+    if let Some(domain) = &domain {
+        links.push(link("MEMBER_OF", domain.clone()));
+    }
+    if let Some(site) = &site {
+        links.push(link("CONTAINED_BY", site_id(site)));
+    }
+    // The server the DC role runs on, as imported from the hypervisor
+    links.push(AssetLink {
+        relationship: "RUNS_ON".to_string(),
+        target_external_id: name.clone(),
+        resolve_by: LinkResolution::Name,
+    });
+
+    Some(DiscoveredAsset {
+        external_id: hostname.clone().unwrap_or_else(|| name.to_lowercase()),
+        name: name.clone(),
+        asset_type: KIND_DOMAIN_CONTROLLER.to_string(),
+        raw_data: json!({
+            "hostname": hostname,
+            "ip_address": ip_address,
+            "site": site,
+            "domain_name": domain,
+            "is_global_catalog": options & NTDSDSA_OPT_IS_GC != 0,
+            "is_read_only": is_read_only,
+        }),
+        links,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dn_helpers() {
+        assert_eq!(rdn_values("CN=DC01,CN=Servers,CN=HQ,CN=Sites"), vec!["DC01", "Servers", "HQ", "Sites"]);
+        assert_eq!(rdn_values(r"CN=Smith\, John,OU=Users"), vec!["Smith, John", "Users"]);
+        assert_eq!(parent_dn("CN=NTDS Settings,CN=DC01,CN=Servers"), "CN=DC01,CN=Servers");
+        assert_eq!(dns_from_dn("CN=DC01,OU=Domain Controllers,DC=corp,DC=example,DC=com"), "corp.example.com");
+    }
+
+    #[test]
+    fn test_mock_directory_maps_sites_and_dcs() {
+        let data = ActiveDirectoryClient::mock_directory();
+        let dc = map_domain_controller(&data.servers[1], &data.ntds_settings[1], None).unwrap();
+        assert_eq!(dc.external_id, "dc02.corp.example.com");
+        assert_eq!(dc.raw_data["site"], "Branch");
+        assert_eq!(dc.raw_data["is_read_only"], true);
+        assert_eq!(dc.raw_data["is_global_catalog"], false);
+        assert_eq!(
+            dc.links[..2],
+            [link("MEMBER_OF", "corp.example.com".to_string()), link("CONTAINED_BY", "site:branch".to_string())]
+        );
+
+        let subnet = map_subnet(&data.subnets[0]).unwrap();
+        assert_eq!(subnet.raw_data["site"], "HQ");
+        assert_eq!(subnet.links, vec![link("CONTAINED_BY", "site:hq".to_string())]);
+    }
+}
@@ -2,19 +2,32 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 
+pub type ConnectorError = Box<dyn Error + Send + Sync>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntegrationConfig {
     pub id: String,
     pub name: String,
     pub provider_type: ProviderType,
     pub base_url: String,
-    pub auth_token: String, // In real app, use a secure vault
+    /// Basic-auth user for providers that need one (Prism Central, Catalyst Center)
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Password or API token
+    pub auth_token: String,
     pub poll_interval_seconds: u64,
+    /// Internal appliances commonly run self-signed certificates
+    #[serde(default)]
+    pub verify_tls: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ProviderType {
     NutanixPrism,
+    CiscoCatalystCenter,
+    F5BigIp,
+    VeeamBackup,
+    ActiveDirectory,
     CiscoACI,
     Splunk,
     GenericRest,
@@ -26,19 +39,69 @@ pub struct DiscoveredAsset {
     pub name: String,
     pub asset_type: String, // "VM", "HOST", "CLUSTER"
     pub raw_data: serde_json::Value,
+    /// References to other discovered assets (by external id)
+    #[serde(default)]
+    pub links: Vec<AssetLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AssetLink {
+    /// CMDB relationship type from this asset to the target, e.g. "RUNS_ON"
+    pub relationship: String,
+    pub target_external_id: String,
+    /// How `target_external_id` is matched to a CI
+    #[serde(default)]
+    pub resolve_by: LinkResolution,
+}
+
+/// Links normally point at another asset from the same source. Links to
+/// workloads the source only knows by address or name (load-balancer pool
+/// members, backup job contents) are resolved against existing VM/host CIs.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LinkResolution {
+    #[default]
+    ExternalId,
+    IpAddress,
+    Name,
+}
+
+/// Result of a full inventory pass. Kinds that failed to load are reported
+/// separately so a sync does not retire objects it simply could not see.
+#[derive(Debug, Default)]
+pub struct InventorySnapshot {
+    pub assets: Vec<DiscoveredAsset>,
+    /// Asset types fetched successfully
+    pub complete_kinds: Vec<String>,
+    /// (asset type, error) for kinds that could not be fetched
+    pub failed_kinds: Vec<(String, String)>,
 }
 
 #[async_trait]
 pub trait IntegrationConnector: Send + Sync {
     /// Initialize the connector with configuration
     fn new(config: IntegrationConfig) -> Self where Self: Sized;
-    
+
     /// Test connectivity to the target API
-    async fn test_connection(&self) -> Result<bool, Box<dyn Error>>;
-    
+    async fn test_connection(&self) -> Result<bool, ConnectorError>;
+
     /// Fetch inventory (Assets) from the provider
-    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, Box<dyn Error>>;
-    
+    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, ConnectorError>;
+
+    /// Fetch inventory with per-kind outcomes. Connectors that load all kinds
+    /// in one call can rely on the default.
+    async fn fetch_snapshot(&self) -> Result<InventorySnapshot, ConnectorError> {
+        let assets = self.fetch_inventory().await?;
+        let mut complete_kinds: Vec<String> = assets.iter().map(|a| a.asset_type.clone()).collect();
+        complete_kinds.sort();
+        complete_kinds.dedup();
+        Ok(InventorySnapshot {
+            assets,
+            complete_kinds,
+            failed_kinds: Vec::new(),
+        })
+    }
+
     /// Fetch active alerts/incidents
-    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, Box<dyn Error>>;
+    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, ConnectorError>;
 }
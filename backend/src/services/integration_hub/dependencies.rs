@@ -0,0 +1,247 @@
+//! Application dependencies contributed by integrations
+//!
+//! Load-balancer pools imported from BIG-IP describe which servers carry the
+//! same service; wave planning uses them to keep those servers together.
+//! Backup jobs imported from Veeam describe which VMs need their protection
+//! reconfigured once they move. Splunk forwarders and indexers describe which
+//! log sources go quiet, and which forwarders need re-pointing, in a wave.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+
+/// Servers behind one load-balancer pool
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LoadBalancedGroup {
+    pub pool: String,
+    pub virtual_servers: Vec<String>,
+    /// Member IP addresses
+    pub addresses: Vec<String>,
+    /// Names of the VM/host CIs the members resolved to
+    pub hosts: Vec<String>,
+}
+
+impl LoadBalancedGroup {
+    /// Whether a VM (by name or primary IP) is one of the pool's servers
+    pub fn contains(&self, name: &str, ip: Option<&str>) -> bool {
+        ip.is_some_and(|ip| self.addresses.iter().any(|a| a == ip))
+            || self.hosts.iter().any(|h| h.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Active F5 pools with at least two enabled members; a single server is
+/// no constraint on wave planning
+pub async fn load_balanced_groups(db: &Database) -> Result<Vec<LoadBalancedGroup>, surrealdb::Error> {
+    let mut response = db
+        .query(
+            "SELECT pool_path, address, ci FROM f5_pool_member \
+             WHERE status = 'active' AND enabled != false",
+        )
+        .query("SELECT name, default_pool_path FROM f5_virtual_server WHERE status = 'active'")
+        .query(
+            "SELECT source_id, target_id.name AS host FROM ci_relationships \
+             WHERE relationship_type = 'RUNS_ON' AND is_active = true \
+             AND integration_asset != NONE AND source_id.ci_type = 'Load Balancer Pool Member'",
+        )
+        .traced("dependencies.load_balanced_groups")
+        .await?;
+    let members: Vec<Value> = response.take(0)?;
+    let virtuals: Vec<Value> = response.take(1)?;
+    let hosts: Vec<Value> = response.take(2)?;
+
+    let host_by_member: BTreeMap<String, String> = hosts
+        .iter()
+        .filter_map(|h| Some((h["source_id"].as_str()?.to_string(), h["host"].as_str()?.to_string())))
+        .collect();
+
+    let mut groups: BTreeMap<String, LoadBalancedGroup> = BTreeMap::new();
+    for member in &members {
+        let Some(pool) = member["pool_path"].as_str() else {
+            continue;
+        };
+        let group = groups.entry(pool.to_string()).or_insert_with(|| LoadBalancedGroup {
+            pool: pool.to_string(),
+            ..Default::default()
+        });
+        if let Some(address) = member["address"].as_str().filter(|a| !a.is_empty()) {
+            group.addresses.push(address.to_string());
+        }
+        // Untyped selects return record links as "table:key" strings
+        if let Some(host) = member["ci"].as_str().and_then(|ci| host_by_member.get(ci)) {
+            group.hosts.push(host.clone());
+        }
+    }
+    for virtual_server in &virtuals {
+        let (Some(name), Some(pool)) = (virtual_server["name"].as_str(), virtual_server["default_pool_path"].as_str())
+        else {
+            continue;
+        };
+        if let Some(group) = groups.get_mut(pool) {
+            group.virtual_servers.push(name.to_string());
+        }
+    }
+
+    Ok(groups
+        .into_values()
+        .filter(|g| g.addresses.len().max(g.hosts.len()) > 1)
+        .collect())
+}
+
+/// A backup job and the VMs it protects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupJobCoverage {
+    pub name: String,
+    #[serde(default)]
+    pub job_type: Option<String>,
+    #[serde(default)]
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Outcome of the job's last run (Success, Warning, Failed, None)
+    #[serde(default)]
+    pub last_result: Option<String>,
+    #[serde(default)]
+    pub protected_vms: Vec<ProtectedVm>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProtectedVm {
+    pub name: String,
+    /// Hypervisor object id the job references (e.g. a vSphere moref)
+    #[serde(default)]
+    pub object_id: Option<String>,
+    #[serde(default)]
+    pub included_directly: bool,
+    /// Container in the job scope the VM is picked up through
+    #[serde(default)]
+    pub via: Option<String>,
+}
+
+impl BackupJobCoverage {
+    pub fn protects(&self, name: &str) -> Option<&ProtectedVm> {
+        self.protected_vms.iter().find(|vm| vm.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Active backup jobs imported from Veeam
+pub async fn backup_coverage(db: &Database) -> Result<Vec<BackupJobCoverage>, surrealdb::Error> {
+    db.query(
+        "SELECT name, job_type, repository, enabled, last_result, protected_vms FROM veeam_backup_job \
+         WHERE status = 'active' ORDER BY name",
+    )
+    .traced("dependencies.backup_coverage")
+    .await?
+    .take(0)
+}
+
+/// A domain controller imported from Active Directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DomainControllerInfo {
+    pub name: String,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub ip_address: Option<String>,
+    #[serde(default)]
+    pub site: Option<String>,
+    #[serde(default)]
+    pub domain_name: Option<String>,
+}
+
+impl DomainControllerInfo {
+    /// Whether a VM (by name or primary IP) is this domain controller
+    pub fn is_vm(&self, name: &str, ip: Option<&str>) -> bool {
+        same_host(&self.name, self.hostname.as_deref(), self.ip_address.as_deref(), name, ip)
+    }
+}
+
+/// Whether a server known by name, hostname and IP is the given VM; the
+/// hostname may be fully qualified while VM names rarely are
+fn same_host(server: &str, hostname: Option<&str>, address: Option<&str>, name: &str, ip: Option<&str>) -> bool {
+    let short = hostname.and_then(|h| h.split('.').next());
+    server.eq_ignore_ascii_case(name)
+        || short.is_some_and(|s| s.eq_ignore_ascii_case(name))
+        || hostname.is_some_and(|h| h.eq_ignore_ascii_case(name))
+        || (ip.is_some() && address == ip)
+}
+
+/// Active domain controllers imported from Active Directory
+pub async fn domain_controllers(db: &Database) -> Result<Vec<DomainControllerInfo>, surrealdb::Error> {
+    db.query(
+        "SELECT name, hostname, ip_address, site, domain_name FROM ad_domain_controller \
+         WHERE status = 'active' ORDER BY name",
+    )
+    .traced("dependencies.domain_controllers")
+    .await?
+    .take(0)
+}
+
+/// A Splunk forwarder and the indexers it sends to
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogForwarder {
+    pub name: String,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub ip_address: Option<String>,
+    #[serde(default)]
+    pub forwarder_type: Option<String>,
+    /// Indexer server names
+    #[serde(default)]
+    pub outputs: Vec<String>,
+}
+
+impl LogForwarder {
+    pub fn is_vm(&self, name: &str, ip: Option<&str>) -> bool {
+        same_host(&self.name, self.hostname.as_deref(), self.ip_address.as_deref(), name, ip)
+    }
+
+    pub fn sends_to(&self, indexer: &str) -> bool {
+        self.outputs.iter().any(|o| o.eq_ignore_ascii_case(indexer))
+    }
+}
+
+/// A Splunk indexer or search head
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogServer {
+    pub name: String,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub ip_address: Option<String>,
+}
+
+impl LogServer {
+    pub fn is_vm(&self, name: &str, ip: Option<&str>) -> bool {
+        same_host(&self.name, self.hostname.as_deref(), self.ip_address.as_deref(), name, ip)
+    }
+}
+
+/// Splunk deployment imported from a search head
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LogPipeline {
+    pub forwarders: Vec<LogForwarder>,
+    pub indexers: Vec<LogServer>,
+    pub search_heads: Vec<LogServer>,
+}
+
+/// Active forwarders, indexers and search heads imported from Splunk
+pub async fn log_pipeline(db: &Database) -> Result<LogPipeline, surrealdb::Error> {
+    let mut response = db
+        .query(
+            "SELECT name, hostname, ip_address, forwarder_type, outputs FROM splunk_forwarder \
+             WHERE status = 'active' ORDER BY name",
+        )
+        .query("SELECT name, hostname, ip_address FROM splunk_indexer WHERE status = 'active' ORDER BY name")
+        .query("SELECT name, hostname, ip_address FROM splunk_search_head WHERE status = 'active' ORDER BY name")
+        .traced("dependencies.log_pipeline")
+        .await?;
+    Ok(LogPipeline {
+        forwarders: response.take(0)?,
+        indexers: response.take(1)?,
+        search_heads: response.take(2)?,
+    })
+}
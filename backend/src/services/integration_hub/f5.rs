@@ -0,0 +1,359 @@
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::time::Duration;
+use super::connector::{
+    AssetLink, ConnectorError, LinkResolution, DiscoveredAsset, IntegrationConfig, IntegrationConnector, InventorySnapshot,
+};
+use crate::utils::telemetry::TracedRequest;
+
+/// iControl REST page size for `$top`
+const PAGE_SIZE: usize = 500;
+
+pub const KIND_VIRTUAL_SERVER: &str = "VIRTUAL_SERVER";
+pub const KIND_POOL: &str = "POOL";
+pub const KIND_POOL_MEMBER: &str = "POOL_MEMBER";
+
+/// F5 BIG-IP collector over iControl REST. Virtual servers and pools (with
+/// their members expanded inline) come from `/mgmt/tm/ltm`. Pool members
+/// carry a by-IP link so the sync can attach them to the VM or host CI
+/// actually serving the traffic.
+pub struct F5BigIpClient {
+    config: IntegrationConfig,
+    client: Client,
+}
+
+impl F5BigIpClient {
+    fn is_mock(&self) -> bool {
+        self.config.base_url.starts_with("mock://")
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    async fn get(&self, path: &str) -> Result<Value, ConnectorError> {
+        let username = self.config.username.as_deref().unwrap_or("admin");
+        let response = self
+            .client
+            .get(self.url(path))
+            .basic_auth(username, Some(&self.config.auth_token))
+            .send_traced("f5_bigip")
+            .await?;
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(format!("BIG-IP rejected the credentials ({})", status).into());
+        }
+        if !status.is_success() {
+            return Err(format!("BIG-IP {} returned {}", path, status).into());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Page through an `/mgmt/tm` collection with `$top`/`$skip`
+    async fn list(&self, path: &str, query: &str) -> Result<Vec<Value>, ConnectorError> {
+        let mut items = Vec::new();
+        let mut skip = 0;
+        loop {
+            let page = self
+                .get(&format!("{}?$top={}&$skip={}{}", path, PAGE_SIZE, skip, query))
+                .await?;
+            let batch = page["items"].as_array().cloned().unwrap_or_default();
+            let fetched = batch.len();
+            items.extend(batch);
+            if fetched < PAGE_SIZE {
+                break;
+            }
+            skip += fetched;
+        }
+        Ok(items)
+    }
+
+    fn mock_config() -> (Vec<Value>, Vec<Value>) {
+        let virtuals = vec![json!({
+            "name": "vs_web_https", "partition": "Common", "fullPath": "/Common/vs_web_https",
+            "destination": "/Common/10.200.0.10:443", "pool": "/Common/pool_web", "ipProtocol": "tcp",
+            "enabled": true, "description": "Public web frontend",
+            "rules": ["/Common/redirect_http"],
+        })];
+        let pools = vec![json!({
+            "name": "pool_web", "partition": "Common", "fullPath": "/Common/pool_web",
+            "loadBalancingMode": "round-robin", "monitor": "/Common/https ",
+            "membersReference": { "items": [
+                { "name": "10.100.0.10:443", "fullPath": "/Common/10.100.0.10:443", "address": "10.100.0.10",
+                  "state": "up", "session": "monitor-enabled", "ratio": 1, "priorityGroup": 0 },
+                { "name": "10.100.0.11:443", "fullPath": "/Common/10.100.0.11:443", "address": "10.100.0.11",
+                  "state": "up", "session": "user-disabled", "ratio": 1, "priorityGroup": 0 },
+            ] }
+        })];
+        (virtuals, pools)
+    }
+}
+
+#[async_trait]
+impl IntegrationConnector for F5BigIpClient {
+    fn new(config: IntegrationConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .danger_accept_invalid_certs(!config.verify_tls)
+            .build()
+            .unwrap_or_default();
+
+        Self { config, client }
+    }
+
+    async fn test_connection(&self) -> Result<bool, ConnectorError> {
+        if self.is_mock() {
+            return Ok(true);
+        }
+        self.get("/mgmt/tm/sys/version").await?;
+        Ok(true)
+    }
+
+    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, ConnectorError> {
+        let snapshot = self.fetch_snapshot().await?;
+        if let Some((kind, error)) = snapshot.failed_kinds.first() {
+            return Err(format!("failed to fetch {}: {}", kind, error).into());
+        }
+        Ok(snapshot.assets)
+    }
+
+    async fn fetch_snapshot(&self) -> Result<InventorySnapshot, ConnectorError> {
+        let mut snapshot = InventorySnapshot::default();
+
+        let (virtuals, pools) = if self.is_mock() {
+            let (virtuals, pools) = Self::mock_config();
+            (Ok(virtuals), Ok(pools))
+        } else {
+            (
+                self.list("/mgmt/tm/ltm/virtual", "").await,
+                self.list("/mgmt/tm/ltm/pool", "&expandSubcollections=true").await,
+            )
+        };
+
+        match virtuals {
+            Ok(virtuals) => {
+                snapshot.assets.extend(virtuals.iter().filter_map(map_virtual_server));
+                snapshot.complete_kinds.push(KIND_VIRTUAL_SERVER.to_string());
+            }
+            Err(e) => snapshot
+                .failed_kinds
+                .push((KIND_VIRTUAL_SERVER.to_string(), e.to_string())),
+        }
+
+        // Members arrive inside their pool, so both kinds share one outcome
+        match pools {
+            Ok(pools) => {
+                for pool in &pools {
+                    snapshot.assets.extend(map_pool(pool));
+                }
+                snapshot.complete_kinds.push(KIND_POOL.to_string());
+                snapshot.complete_kinds.push(KIND_POOL_MEMBER.to_string());
+            }
+            Err(e) => {
+                snapshot.failed_kinds.push((KIND_POOL.to_string(), e.to_string()));
+                snapshot.failed_kinds.push((KIND_POOL_MEMBER.to_string(), e.to_string()));
+            }
+        }
+
+        if snapshot.complete_kinds.is_empty() {
+            let (_, error) = &snapshot.failed_kinds[0];
+            return Err(error.clone().into());
+        }
+        Ok(snapshot)
+    }
+
+    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, ConnectorError> {
+        Ok(vec![])
+    }
+}
+
+// ============================================================================
+// MAPPING
+// ============================================================================
+
+fn link(relationship: &str, target: String) -> AssetLink {
+    AssetLink {
+        relationship: relationship.to_string(),
+        target_external_id: target,
+        resolve_by: LinkResolution::ExternalId,
+    }
+}
+
+/// Strip the partition and route domain from a BIG-IP address:
+/// `/Common/10.1.1.10%2:443` -> (`10.1.1.10`, 443). IPv6 destinations
+/// separate the port with a dot.
+pub fn parse_destination(destination: &str) -> (String, Option<u16>) {
+    let value = destination.rsplit('/').next().unwrap_or(destination);
+    let (address, port) = if value.matches(':').count() > 1 {
+        match value.rsplit_once('.') {
+            Some((address, port)) => (address, port.parse().ok()),
+            None => (value, None),
+        }
+    } else {
+        match value.rsplit_once(':') {
+            Some((address, port)) => (address, port.parse().ok()),
+            None => (value, None),
+        }
+    };
+    (strip_route_domain(address).to_string(), port)
+}
+
+fn strip_route_domain(address: &str) -> &str {
+    address.split('%').next().unwrap_or(address)
+}
+
+fn full_path(item: &Value) -> Option<String> {
+    item["fullPath"]
+        .as_str()
+        .map(str::to_string)
+        .or_else(|| {
+            let name = item["name"].as_str()?;
+            let partition = item["partition"].as_str().unwrap_or("Common");
+            Some(format!("/{}/{}", partition, name))
+        })
+}
+
+fn map_virtual_server(item: &Value) -> Option<DiscoveredAsset> {
+    let path = full_path(item)?;
+    let (address, port) = item["destination"]
+        .as_str()
+        .map(parse_destination)
+        .unwrap_or_default();
+    let enabled = item["disabled"].as_bool() != Some(true);
+    let raw = json!({
+        "partition": item["partition"],
+        "destination_address": address,
+        "destination_port": port,
+        "ip_protocol": item["ipProtocol"],
+        "default_pool_path": item["pool"],
+        "irules": item["rules"].as_array().cloned().unwrap_or_default(),
+        "state": if enabled { "enabled" } else { "disabled" },
+        "description": item["description"],
+    });
+
+    let links = item["pool"]
+        .as_str()
+        .map(|pool| link("DEPENDS_ON", pool.to_string()))
+        .into_iter()
+        .collect();
+
+    Some(DiscoveredAsset {
+        external_id: path.clone(),
+        name: item["name"].as_str().unwrap_or(&path).to_string(),
+        asset_type: KIND_VIRTUAL_SERVER.to_string(),
+        raw_data: raw,
+        links,
+    })
+}
+
+/// One pool plus one asset per member. Members are keyed under their pool
+/// because the same node:port can sit in several pools.
+fn map_pool(item: &Value) -> Vec<DiscoveredAsset> {
+    let Some(path) = full_path(item) else {
+        return Vec::new();
+    };
+    let members = item["membersReference"]["items"].as_array().cloned().unwrap_or_default();
+    let monitors: Vec<String> = item["monitor"]
+        .as_str()
+        .map(|m| m.split(" and ").map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    let mut assets = vec![DiscoveredAsset {
+        external_id: path.clone(),
+        name: item["name"].as_str().unwrap_or(&path).to_string(),
+        asset_type: KIND_POOL.to_string(),
+        raw_data: json!({
+            "partition": item["partition"],
+            "lb_method": item["loadBalancingMode"],
+            "monitors": monitors,
+            "member_count": members.len(),
+            "description": item["description"],
+        }),
+        links: Vec::new(),
+    }];
+
+    for member in &members {
+        let Some(member_path) = full_path(member) else {
+            continue;
+        };
+        let name = member["name"].as_str().unwrap_or(&member_path).to_string();
+        let (name_address, port) = parse_destination(&name);
+        let address = member["address"]
+            .as_str()
+            .map(|a| strip_route_domain(a).to_string())
+            .unwrap_or(name_address);
+        // Forced-offline and disabled members still describe the dependency
+        let active = member["session"].as_str() != Some("user-disabled")
+            && member["state"].as_str() != Some("user-down");
+
+        let mut links = vec![link("MEMBER_OF", path.clone())];
+        if !address.is_empty() {
+            links.push(AssetLink {
+                relationship: "RUNS_ON".to_string(),
+                target_external_id: address.clone(),
+                resolve_by: LinkResolution::IpAddress,
+            });
+        }
+
+        assets.push(DiscoveredAsset {
+            external_id: format!("{}/{}", path, member_path),
+            name: format!("{} ({})", name, item["name"].as_str().unwrap_or(&path)),
+            asset_type: KIND_POOL_MEMBER.to_string(),
+            raw_data: json!({
+                "pool_path": path,
+                "address": address,
+                "port": port,
+                "ratio": member["ratio"],
+                "priority_group": member["priorityGroup"],
+                "state": member["state"],
+                "session": member["session"],
+                "enabled": active,
+            }),
+            links,
+        });
+    }
+    assets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_destination() {
+        assert_eq!(parse_destination("/Common/10.1.1.10:443"), ("10.1.1.10".to_string(), Some(443)));
+        assert_eq!(parse_destination("/Tenant/10.1.1.10%2:80"), ("10.1.1.10".to_string(), Some(80)));
+        assert_eq!(parse_destination("/Common/2001:db8::10.443"), ("2001:db8::10".to_string(), Some(443)));
+        assert_eq!(parse_destination("10.0.0.5:any"), ("10.0.0.5".to_string(), None));
+    }
+
+    #[test]
+    fn test_pool_members_link_to_pool_and_by_address() {
+        let (virtuals, pools) = F5BigIpClient::mock_config();
+        let vs = map_virtual_server(&virtuals[0]).unwrap();
+        assert_eq!(vs.raw_data["destination_address"], "10.200.0.10");
+        assert_eq!(vs.links, vec![link("DEPENDS_ON", "/Common/pool_web".to_string())]);
+
+        let assets = map_pool(&pools[0]);
+        let kinds: Vec<&str> = assets.iter().map(|a| a.asset_type.as_str()).collect();
+        assert_eq!(kinds, vec![KIND_POOL, KIND_POOL_MEMBER, KIND_POOL_MEMBER]);
+        assert_eq!(assets[0].raw_data["monitors"], json!(["/Common/https"]));
+
+        let member = &assets[1];
+        assert_eq!(member.external_id, "/Common/pool_web//Common/10.100.0.10:443");
+        assert_eq!(member.raw_data["port"], 443);
+        assert_eq!(
+            member.links,
+            vec![
+                link("MEMBER_OF", "/Common/pool_web".to_string()),
+                AssetLink {
+                    relationship: "RUNS_ON".to_string(),
+                    target_external_id: "10.100.0.10".to_string(),
+                    resolve_by: LinkResolution::IpAddress,
+                },
+            ]
+        );
+        assert_eq!(assets[2].raw_data["enabled"], false);
+    }
+}
@@ -1,7 +1,22 @@
+pub mod active_directory;
+pub mod catalyst;
 pub mod connector;
+pub mod dependencies;
+pub mod f5;
 pub mod nutanix;
 pub mod service;
+pub mod simulation;
+pub mod splunk;
+pub mod sync;
+pub mod veeam;
 
 pub use connector::{IntegrationConnector, IntegrationConfig, ProviderType};
+pub use active_directory::ActiveDirectoryClient;
+pub use catalyst::CatalystCenterClient;
+pub use f5::F5BigIpClient;
 pub use nutanix::NutanixClient;
-pub use service::IntegrationService;
+pub use service::{IntegrationError, IntegrationService};
+pub use simulation::{SimulatedConnector, SimulationService};
+pub use splunk::SplunkClient;
+pub use sync::{IntegrationSyncScheduler, InventorySyncService};
+pub use veeam::VeeamClient;
@@ -1,65 +1,540 @@
 use async_trait::async_trait;
-use reqwest::Client;
-use std::error::Error;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
 use std::time::Duration;
-use super::connector::{IntegrationConnector, IntegrationConfig, DiscoveredAsset, ProviderType};
+use super::connector::{
+    AssetLink, ConnectorError, LinkResolution, DiscoveredAsset, IntegrationConfig, IntegrationConnector, InventorySnapshot,
+};
+use crate::utils::telemetry::TracedRequest;
+use core_engine::units::Bytes;
 
+/// Prism Central v3 list endpoints accept at most 500 entities per call
+const PAGE_SIZE: u64 = 250;
+
+pub const KIND_CLUSTER: &str = "CLUSTER";
+pub const KIND_HOST: &str = "HOST";
+pub const KIND_VM: &str = "VM";
+pub const KIND_SUBNET: &str = "SUBNET";
+pub const KIND_STORAGE_CONTAINER: &str = "STORAGE_CONTAINER";
+
+/// Prism Central client. Clusters, hosts, VMs and subnets come from the v3
+/// `<kind>/list` endpoints; storage containers only exist in the v3 groups API.
+/// A `mock://` base URL serves a small fixed inventory for demos and tests.
 pub struct NutanixClient {
     config: IntegrationConfig,
     client: Client,
 }
 
+impl NutanixClient {
+    fn is_mock(&self) -> bool {
+        self.config.base_url.starts_with("mock://")
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/nutanix/v3/{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        let username = self.config.username.as_deref().unwrap_or("admin");
+        self.client
+            .post(self.url(path))
+            .basic_auth(username, Some(&self.config.auth_token))
+    }
+
+    async fn send(&self, path: &str, body: &Value) -> Result<Value, ConnectorError> {
+        let response = self.post(path).json(body).send_traced("nutanix").await?;
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(format!("Prism Central rejected the credentials ({})", status).into());
+        }
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Prism Central {} returned {}: {}", path, status, truncate(&text, 300)).into());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Page through a v3 `<kind>/list` endpoint
+    async fn list_entities(&self, endpoint: &str, kind: &str) -> Result<Vec<Value>, ConnectorError> {
+        let mut entities = Vec::new();
+        let mut offset = 0u64;
+        loop {
+            let body = json!({ "kind": kind, "length": PAGE_SIZE, "offset": offset });
+            let page = self.send(&format!("{}/list", endpoint), &body).await?;
+            let batch = page["entities"].as_array().cloned().unwrap_or_default();
+            let fetched = batch.len() as u64;
+            entities.extend(batch);
+
+            let total = page["metadata"]["total_matches"].as_u64().unwrap_or(0);
+            offset += fetched;
+            if fetched == 0 || offset >= total {
+                break;
+            }
+        }
+        Ok(entities)
+    }
+
+    /// Page through storage containers via the groups API
+    async fn list_storage_containers(&self) -> Result<Vec<Value>, ConnectorError> {
+        let attributes: Vec<Value> = STORAGE_CONTAINER_ATTRIBUTES
+            .iter()
+            .map(|a| json!({ "attribute": a }))
+            .collect();
+        let mut rows = Vec::new();
+        let mut offset = 0u64;
+        loop {
+            let body = json!({
+                "entity_type": "storage_container",
+                "group_member_attributes": attributes,
+                "group_member_count": PAGE_SIZE,
+                "group_member_offset": offset,
+            });
+            let page = self.send("groups", &body).await?;
+            let batch = parse_group_rows(&page);
+            let fetched = batch.len() as u64;
+            rows.extend(batch);
+
+            let total = page["filtered_entity_count"].as_u64().unwrap_or(0);
+            offset += fetched;
+            if fetched == 0 || offset >= total {
+                break;
+            }
+        }
+        Ok(rows)
+    }
+
+    fn mock_snapshot(&self) -> InventorySnapshot {
+        let cluster = json!({
+            "metadata": { "uuid": "mock-cluster-1" },
+            "status": { "name": "NX-Cluster-01", "resources": { "config": {
+                "software_map": { "NOS": { "version": "6.5.2" } },
+                "redundancy_factor": 2,
+                "service_list": ["AOS"]
+            }, "nodes": { "hypervisor_server_list": [{ "type": "AHV", "version": "20220304" }] } } }
+        });
+        let host = json!({
+            "metadata": { "uuid": "mock-host-1" },
+            "status": { "name": "NX-Node-A", "cluster_reference": { "uuid": "mock-cluster-1" }, "resources": {
+                "serial_number": "MOCK0001", "num_cpu_sockets": 2, "num_cpu_cores": 32,
+                "memory_capacity_mib": 524288, "hypervisor": { "hypervisor_full_name": "AHV 20220304", "ip": "10.0.0.11" },
+                "controller_vm": { "ip": "10.0.0.21" }, "block": { "block_model": "NX-3170-G8" }
+            } }
+        });
+        let subnet = json!({
+            "metadata": { "uuid": "mock-subnet-1" },
+            "status": { "name": "VLAN100-Prod", "cluster_reference": { "uuid": "mock-cluster-1" }, "resources": {
+                "vlan_id": 100, "subnet_type": "VLAN",
+                "ip_config": { "subnet_ip": "10.100.0.0", "prefix_length": 24, "default_gateway_ip": "10.100.0.1" }
+            } }
+        });
+        let vm = json!({
+            "metadata": { "uuid": "mock-vm-1" },
+            "spec": { "name": "VM-Web-01" },
+            "status": { "name": "VM-Web-01", "cluster_reference": { "uuid": "mock-cluster-1" }, "resources": {
+                "power_state": "ON", "num_sockets": 2, "num_vcpus_per_socket": 2, "memory_size_mib": 16384,
+                "host_reference": { "uuid": "mock-host-1" },
+                "nic_list": [{ "subnet_reference": { "uuid": "mock-subnet-1" }, "mac_address": "50:6b:8d:00:00:01",
+                    "ip_endpoint_list": [{ "ip": "10.100.0.10" }] }],
+                "disk_list": [{ "disk_size_bytes": 107374182400u64, "device_properties": { "device_type": "DISK" } }]
+            } }
+        });
+        let container = json!({
+            "uuid": "mock-container-1",
+            "container_name": "default-container",
+            "cluster": "mock-cluster-1",
+            "storage.capacity_bytes": "21990232555520",
+            "storage.usage_bytes": "4398046511104",
+            "replication_factor": "2",
+        });
+
+        let assets = vec![
+            map_cluster(&cluster),
+            map_host(&host),
+            map_subnet(&subnet),
+            map_vm(&vm),
+            map_storage_container(&container),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        InventorySnapshot {
+            assets,
+            complete_kinds: ALL_KINDS.iter().map(|k| k.to_string()).collect(),
+            failed_kinds: Vec::new(),
+        }
+    }
+}
+
+const ALL_KINDS: [&str; 5] = [KIND_CLUSTER, KIND_HOST, KIND_VM, KIND_SUBNET, KIND_STORAGE_CONTAINER];
+
+const STORAGE_CONTAINER_ATTRIBUTES: [&str; 6] = [
+    "container_name",
+    "cluster",
+    "storage.capacity_bytes",
+    "storage.usage_bytes",
+    "replication_factor",
+    "compression_enabled",
+];
+
 #[async_trait]
 impl IntegrationConnector for NutanixClient {
     fn new(config: IntegrationConfig) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .danger_accept_invalid_certs(true) // Common for internal Prism Central
+            .timeout(Duration::from_secs(30))
+            .danger_accept_invalid_certs(!config.verify_tls) // Common for internal Prism Central
             .build()
             .unwrap_or_default();
 
         Self { config, client }
     }
 
-    async fn test_connection(&self) -> Result<bool, Box<dyn Error>> {
-        let url = format!("{}/api/nutanix/v3/clusters/list", self.config.base_url);
-        
-        // Mocking the request for now since we don't have a real endpoint
-        // In production:
-        // let res = self.client.post(&url)
-        //     .basic_auth("admin", Some(&self.config.auth_token))
-        //     .json(&serde_json::json!({ "kind": "cluster" }))
-        //     .send()
-        //     .await?;
-        // Ok(res.status().is_success())
-        
-        println!("🔌 [Nutanix] Testing connection to {}", url);
-        Ok(true) 
-    }
-
-    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, Box<dyn Error>> {
-        println!("📦 [Nutanix] Fetching inventory from {}", self.config.base_url);
-        
-        // Mock Data Return
-        let mock_assets = vec![
-            DiscoveredAsset {
-                external_id: "uuid-1".to_string(),
-                name: "NX-Cluster-01".to_string(),
-                asset_type: "CLUSTER".to_string(),
-                raw_data: serde_json::json!({ "aos": "6.5.2", "hypervisor": "AHV" }),
-            },
-            DiscoveredAsset {
-                external_id: "uuid-2".to_string(),
-                name: "VM-Web-01".to_string(),
-                asset_type: "VM".to_string(),
-                raw_data: serde_json::json!({ "vcpus": 4, "memory_gb": 16 }),
-            }
+    async fn test_connection(&self) -> Result<bool, ConnectorError> {
+        if self.is_mock() {
+            return Ok(true);
+        }
+        // The smallest authenticated call; fails fast on bad credentials
+        self.send("clusters/list", &json!({ "kind": "cluster", "length": 1, "offset": 0 }))
+            .await?;
+        Ok(true)
+    }
+
+    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, ConnectorError> {
+        let snapshot = self.fetch_snapshot().await?;
+        if let Some((kind, error)) = snapshot.failed_kinds.first() {
+            return Err(format!("failed to fetch {}: {}", kind, error).into());
+        }
+        Ok(snapshot.assets)
+    }
+
+    async fn fetch_snapshot(&self) -> Result<InventorySnapshot, ConnectorError> {
+        if self.is_mock() {
+            return Ok(self.mock_snapshot());
+        }
+
+        let mut snapshot = InventorySnapshot::default();
+        let lists: [(&str, &str, &str, fn(&Value) -> Option<DiscoveredAsset>); 4] = [
+            (KIND_CLUSTER, "clusters", "cluster", map_cluster),
+            (KIND_HOST, "hosts", "host", map_host),
+            (KIND_SUBNET, "subnets", "subnet", map_subnet),
+            (KIND_VM, "vms", "vm", map_vm),
         ];
 
-        Ok(mock_assets)
+        for (asset_kind, endpoint, kind, map) in lists {
+            match self.list_entities(endpoint, kind).await {
+                Ok(entities) => {
+                    snapshot.assets.extend(entities.iter().filter_map(map));
+                    snapshot.complete_kinds.push(asset_kind.to_string());
+                }
+                Err(e) => snapshot.failed_kinds.push((asset_kind.to_string(), e.to_string())),
+            }
+        }
+
+        match self.list_storage_containers().await {
+            Ok(rows) => {
+                snapshot.assets.extend(rows.iter().filter_map(map_storage_container));
+                snapshot.complete_kinds.push(KIND_STORAGE_CONTAINER.to_string());
+            }
+            Err(e) => snapshot
+                .failed_kinds
+                .push((KIND_STORAGE_CONTAINER.to_string(), e.to_string())),
+        }
+
+        // Nothing loaded at all is a connection problem, not a partial sync
+        if snapshot.complete_kinds.is_empty() {
+            let (_, error) = &snapshot.failed_kinds[0];
+            return Err(error.clone().into());
+        }
+        Ok(snapshot)
     }
 
-    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
+    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, ConnectorError> {
         Ok(vec![])
     }
 }
+
+// ============================================================================
+// MAPPING
+// ============================================================================
+
+fn entity_uuid(entity: &Value) -> Option<String> {
+    entity["metadata"]["uuid"].as_str().map(str::to_string)
+}
+
+fn entity_name(entity: &Value, fallback: &str) -> String {
+    entity["status"]["name"]
+        .as_str()
+        .or_else(|| entity["spec"]["name"].as_str())
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+fn link(relationship: &str, target: &Value) -> Option<AssetLink> {
+    target["uuid"].as_str().map(|uuid| AssetLink {
+        relationship: relationship.to_string(),
+        target_external_id: uuid.to_string(),
+        resolve_by: LinkResolution::ExternalId,
+    })
+}
+
+fn map_cluster(entity: &Value) -> Option<DiscoveredAsset> {
+    let uuid = entity_uuid(entity)?;
+    let resources = &entity["status"]["resources"];
+    // Prism Central registers itself as a cluster; it is not part of the estate
+    let is_prism_central = resources["config"]["service_list"]
+        .as_array()
+        .is_some_and(|services| services.iter().any(|s| s == "PRISM_CENTRAL"));
+    if is_prism_central {
+        return None;
+    }
+
+    let hypervisor = &resources["nodes"]["hypervisor_server_list"][0];
+    let raw = json!({
+        "version": resources["config"]["software_map"]["NOS"]["version"],
+        "hypervisor_type": hypervisor["type"],
+        "redundancy_factor": resources["config"]["redundancy_factor"],
+        "cluster_vip": resources["network"]["external_ip"],
+        "external_subnet": resources["network"]["external_subnet"],
+        "timezone": resources["config"]["timezone"],
+    });
+
+    Some(DiscoveredAsset {
+        external_id: uuid.clone(),
+        name: entity_name(entity, &uuid),
+        asset_type: KIND_CLUSTER.to_string(),
+        raw_data: raw,
+        links: Vec::new(),
+    })
+}
+
+fn map_host(entity: &Value) -> Option<DiscoveredAsset> {
+    let uuid = entity_uuid(entity)?;
+    let status = &entity["status"];
+    let resources = &status["resources"];
+    let raw = json!({
+        "serial_number": resources["serial_number"],
+        "block_model": resources["block"]["block_model"],
+        "cpu_model": resources["cpu_model"],
+        "cpu_sockets": resources["num_cpu_sockets"],
+        "cpu_cores": resources["num_cpu_cores"],
+        "memory_capacity_bytes": resources["memory_capacity_mib"].as_f64().map(|mib| Bytes::from_mib(mib).bytes()),
+        "hypervisor_full_name": resources["hypervisor"]["hypervisor_full_name"],
+        "hypervisor_ip": resources["hypervisor"]["ip"],
+        "cvm_ip": resources["controller_vm"]["ip"],
+        "ipmi_ip": resources["ipmi"]["ip"],
+        "host_status": resources["host_type"],
+    });
+
+    Some(DiscoveredAsset {
+        external_id: uuid.clone(),
+        name: entity_name(entity, &uuid),
+        asset_type: KIND_HOST.to_string(),
+        raw_data: raw,
+        links: link("CONTAINED_BY", &status["cluster_reference"]).into_iter().collect(),
+    })
+}
+
+fn map_subnet(entity: &Value) -> Option<DiscoveredAsset> {
+    let uuid = entity_uuid(entity)?;
+    let status = &entity["status"];
+    let resources = &status["resources"];
+    let ip_config = &resources["ip_config"];
+    let cidr = match (ip_config["subnet_ip"].as_str(), ip_config["prefix_length"].as_u64()) {
+        (Some(ip), Some(prefix)) => Some(format!("{}/{}", ip, prefix)),
+        _ => None,
+    };
+    let raw = json!({
+        "vlan_id": resources["vlan_id"],
+        "subnet_type": resources["subnet_type"],
+        "cidr": cidr,
+        "gateway": ip_config["default_gateway_ip"],
+    });
+
+    Some(DiscoveredAsset {
+        external_id: uuid.clone(),
+        name: entity_name(entity, &uuid),
+        asset_type: KIND_SUBNET.to_string(),
+        raw_data: raw,
+        links: link("CONTAINED_BY", &status["cluster_reference"]).into_iter().collect(),
+    })
+}
+
+fn map_vm(entity: &Value) -> Option<DiscoveredAsset> {
+    let uuid = entity_uuid(entity)?;
+    let status = &entity["status"];
+    let resources = &status["resources"];
+
+    let sockets = resources["num_sockets"].as_u64().unwrap_or(0);
+    let per_socket = resources["num_vcpus_per_socket"].as_u64().unwrap_or(1);
+    let nics = resources["nic_list"].as_array().cloned().unwrap_or_default();
+    let disks: Vec<&Value> = resources["disk_list"]
+        .as_array()
+        .map(|d| {
+            d.iter()
+                .filter(|disk| disk["device_properties"]["device_type"].as_str().unwrap_or("DISK") == "DISK")
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let ip_addresses: Vec<String> = nics
+        .iter()
+        .flat_map(|nic| nic["ip_endpoint_list"].as_array().cloned().unwrap_or_default())
+        .filter_map(|ip| ip["ip"].as_str().map(str::to_string))
+        .collect();
+    let mac_addresses: Vec<&str> = nics.iter().filter_map(|nic| nic["mac_address"].as_str()).collect();
+
+    let raw = json!({
+        "power_state": resources["power_state"],
+        "vcpu_count": sockets * per_socket,
+        "cores_per_vcpu": resources["num_vcpus_per_socket"],
+        "memory_capacity_bytes": resources["memory_size_mib"].as_f64().map(|mib| Bytes::from_mib(mib).bytes()),
+        "ip_addresses": ip_addresses,
+        "mac_addresses": mac_addresses,
+        "nic_count": nics.len(),
+        "disk_count": disks.len(),
+        "disk_capacity_bytes": disks.iter().filter_map(|d| d["disk_size_bytes"].as_u64()).sum::<u64>(),
+        "guest_os": resources["guest_tools"]["nutanix_guest_tools"]["guest_os_version"],
+        "description": entity["spec"]["description"],
+    });
+
+    let mut links: Vec<AssetLink> = Vec::new();
+    links.extend(link("RUNS_ON", &resources["host_reference"]));
+    links.extend(link("CONTAINED_BY", &status["cluster_reference"]));
+    for nic in &nics {
+        if let Some(l) = link("CONNECTS_TO", &nic["subnet_reference"]) {
+            if !links.contains(&l) {
+                links.push(l);
+            }
+        }
+    }
+
+    Some(DiscoveredAsset {
+        external_id: uuid.clone(),
+        name: entity_name(entity, &uuid),
+        asset_type: KIND_VM.to_string(),
+        raw_data: raw,
+        links,
+    })
+}
+
+fn map_storage_container(row: &Value) -> Option<DiscoveredAsset> {
+    let uuid = row["uuid"].as_str()?.to_string();
+    let number = |key: &str| row[key].as_str().and_then(|v| v.parse::<u64>().ok());
+    let raw = json!({
+        "capacity_bytes": number("storage.capacity_bytes"),
+        "usage_bytes": number("storage.usage_bytes"),
+        "replication_factor": number("replication_factor"),
+        "compression_enabled": row["compression_enabled"].as_str().map(|v| v == "true"),
+    });
+    let cluster = json!({ "uuid": row["cluster"] });
+
+    Some(DiscoveredAsset {
+        external_id: uuid.clone(),
+        name: row["container_name"].as_str().unwrap_or(&uuid).to_string(),
+        asset_type: KIND_STORAGE_CONTAINER.to_string(),
+        raw_data: raw,
+        links: link("CONTAINED_BY", &cluster).into_iter().collect(),
+    })
+}
+
+/// Flatten groups API results into `{ "uuid": ..., "<attribute>": "<first value>" }` rows
+fn parse_group_rows(page: &Value) -> Vec<Value> {
+    page["group_results"]
+        .as_array()
+        .map(|groups| {
+            groups
+                .iter()
+                .flat_map(|g| g["entity_results"].as_array().cloned().unwrap_or_default())
+                .map(|entity| {
+                    let mut row = serde_json::Map::new();
+                    row.insert("uuid".to_string(), entity["entity_id"].clone());
+                    for attr in entity["data"].as_array().cloned().unwrap_or_default() {
+                        if let Some(name) = attr["name"].as_str() {
+                            let value = attr["values"][0]["values"][0].clone();
+                            row.insert(name.to_string(), value);
+                        }
+                    }
+                    Value::Object(row)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn truncate(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vm_mapping_links_host_cluster_and_subnets() {
+        let vm = json!({
+            "metadata": { "uuid": "vm-1" },
+            "spec": { "name": "app-01" },
+            "status": { "cluster_reference": { "uuid": "c-1" }, "resources": {
+                "num_sockets": 2, "num_vcpus_per_socket": 4, "memory_size_mib": 8192,
+                "host_reference": { "uuid": "h-1" },
+                "nic_list": [
+                    { "subnet_reference": { "uuid": "s-1" }, "ip_endpoint_list": [{ "ip": "10.0.0.5" }] },
+                    { "subnet_reference": { "uuid": "s-1" } }
+                ],
+                "disk_list": [
+                    { "disk_size_bytes": 1000, "device_properties": { "device_type": "DISK" } },
+                    { "disk_size_bytes": 5000, "device_properties": { "device_type": "CDROM" } }
+                ]
+            } }
+        });
+
+        let asset = map_vm(&vm).unwrap();
+        assert_eq!(asset.name, "app-01");
+        assert_eq!(asset.raw_data["vcpu_count"], 8);
+        assert_eq!(asset.raw_data["memory_capacity_bytes"], 8192u64 * 1024 * 1024);
+        assert_eq!(asset.raw_data["disk_count"], 1);
+        assert_eq!(asset.raw_data["disk_capacity_bytes"], 1000);
+        assert_eq!(asset.raw_data["ip_addresses"], json!(["10.0.0.5"]));
+
+        let links: Vec<(&str, &str)> = asset
+            .links
+            .iter()
+            .map(|l| (l.relationship.as_str(), l.target_external_id.as_str()))
+            .collect();
+        assert_eq!(links, vec![("RUNS_ON", "h-1"), ("CONTAINED_BY", "c-1"), ("CONNECTS_TO", "s-1")]);
+    }
+
+    #[test]
+    fn test_prism_central_is_not_mapped_as_cluster() {
+        let pc = json!({
+            "metadata": { "uuid": "pc" },
+            "status": { "name": "PC", "resources": { "config": { "service_list": ["PRISM_CENTRAL"] } } }
+        });
+        assert!(map_cluster(&pc).is_none());
+    }
+
+    #[test]
+    fn test_group_rows_flatten_storage_containers() {
+        let page = json!({
+            "filtered_entity_count": 1,
+            "group_results": [{ "entity_results": [{
+                "entity_id": "sc-1",
+                "data": [
+                    { "name": "container_name", "values": [{ "values": ["default"] }] },
+                    { "name": "cluster", "values": [{ "values": ["c-1"] }] },
+                    { "name": "storage.capacity_bytes", "values": [{ "values": ["2048"] }] }
+                ]
+            }] }]
+        });
+
+        let rows = parse_group_rows(&page);
+        assert_eq!(rows.len(), 1);
+        let asset = map_storage_container(&rows[0]).unwrap();
+        assert_eq!(asset.name, "default");
+        assert_eq!(asset.raw_data["capacity_bytes"], 2048);
+        assert_eq!(asset.links[0].target_external_id, "c-1");
+    }
+}
@@ -5,6 +5,7 @@ use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 use std::time::Duration;
+use crate::utils::telemetry::TracedRequest;
 
 pub struct NutanixClient {
     config: IntegrationConfig,
@@ -29,7 +30,7 @@ impl IntegrationConnector for NutanixClient {
         }
 
         let url = format!("{}/PrismGateway/services/rest/v2.0/cluster", self.config.base_url);
-        let response = self.request_builder(&url).send().await?;
+        let response = self.request_builder(&url).send_traced("nutanix").await?;
 
         Ok(response.status().is_success())
     }
@@ -41,12 +42,12 @@ impl IntegrationConnector for NutanixClient {
 
         // 1. Fetch Hosts
         let hosts_url = format!("{}/PrismGateway/services/rest/v2.0/hosts", self.config.base_url);
-        let hosts_resp = self.request_builder(&hosts_url).send().await?;
+        let hosts_resp = self.request_builder(&hosts_url).send_traced("nutanix").await?;
         let hosts_json: Value = hosts_resp.json().await?;
         
         // 2. Fetch VMs
         let vms_url = format!("{}/PrismGateway/services/rest/v2.0/vms", self.config.base_url);
-        let vms_resp = self.request_builder(&vms_url).send().await?;
+        let vms_resp = self.request_builder(&vms_url).send_traced("nutanix").await?;
         let vms_json: Value = vms_resp.json().await?;
 
         let mut assets = Vec::new();
@@ -86,7 +87,7 @@ impl IntegrationConnector for NutanixClient {
         }
         
         let url = format!("{}/PrismGateway/services/rest/v2.0/alerts", self.config.base_url);
-        let response = self.request_builder(&url).send().await?;
+        let response = self.request_builder(&url).send_traced("nutanix").await?;
         let json: Value = response.json().await?;
         
         Ok(json.get("entities")
@@ -1,9 +1,60 @@
+use chrono::Utc;
 use std::sync::Arc;
-use surrealdb::engine::remote::ws::Client;
-use surrealdb::Surreal;
+use surrealdb::sql::Thing;
+use thiserror::Error;
 use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::integration::*;
+use crate::services::llm::SecretBox;
 use super::connector::{IntegrationConfig, ProviderType, IntegrationConnector};
+use super::active_directory::ActiveDirectoryClient;
+use super::catalyst::CatalystCenterClient;
+use super::f5::F5BigIpClient;
 use super::nutanix::NutanixClient;
+use super::splunk::SplunkClient;
+use super::simulation::SimulationService;
+use super::veeam::VeeamClient;
+use crate::database::TracedQuery;
+
+#[derive(Debug, Error)]
+pub enum IntegrationError {
+    #[error("Integration connection not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("Connector error: {0}")]
+    Connector(String),
+
+    #[error("Secret storage error: {0}")]
+    Secret(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for IntegrationError {
+    fn from(e: surrealdb::Error) -> Self {
+        IntegrationError::DatabaseError(e.to_string())
+    }
+}
+
+/// Build the connector for a provider
+pub fn connector_for(config: IntegrationConfig) -> Result<Box<dyn IntegrationConnector>, IntegrationError> {
+    match config.provider_type {
+        ProviderType::NutanixPrism => Ok(Box::new(NutanixClient::new(config))),
+        ProviderType::CiscoCatalystCenter => Ok(Box::new(CatalystCenterClient::new(config))),
+        ProviderType::F5BigIp => Ok(Box::new(F5BigIpClient::new(config))),
+        ProviderType::VeeamBackup => Ok(Box::new(VeeamClient::new(config))),
+        ProviderType::ActiveDirectory => Ok(Box::new(ActiveDirectoryClient::new(config))),
+        ProviderType::Splunk => Ok(Box::new(SplunkClient::new(config))),
+        ref other => Err(IntegrationError::Validation(format!("Provider {:?} is not supported yet", other))),
+    }
+}
 
 pub struct IntegrationService {
     db: Arc<Database>,
@@ -14,6 +65,237 @@ impl IntegrationService {
         Self { db }
     }
 
+    // ========================================================================
+    // CONNECTIONS
+    // ========================================================================
+
+    pub async fn list_connections(
+        &self,
+        user: &AuthenticatedUser,
+    ) -> Result<Vec<IntegrationConnection>, IntegrationError> {
+        let mut connections: Vec<IntegrationConnection> = self
+            .db
+            .query("SELECT * FROM integration_connections ORDER BY name ASC")
+            .traced("integration_hub.list_connections")
+            .await?
+            .take(0)?;
+        let tenant = user_tenant(user);
+        connections.retain(|c| c.tenant_id == tenant);
+        Ok(connections)
+    }
+
+    pub async fn get_connection(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<IntegrationConnection, IntegrationError> {
+        let connection: Option<IntegrationConnection> =
+            self.db.select(parse_thing("integration_connections", id)).await?;
+        let tenant = user_tenant(user);
+        connection
+            .filter(|c| c.tenant_id == tenant)
+            .ok_or(IntegrationError::NotFound)
+    }
+
+    pub async fn create_connection(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateIntegrationConnectionRequest,
+    ) -> Result<IntegrationConnection, IntegrationError> {
+        if request.name.trim().is_empty() {
+            return Err(IntegrationError::Validation("Connection name is required".to_string()));
+        }
+        validate_base_url(&request.base_url)?;
+
+        let password_encrypted = match request.password.as_deref().filter(|p| !p.is_empty()) {
+            Some(password) => Some(encrypt(password)?),
+            None => None,
+        };
+
+        let now = Utc::now();
+        let connection = IntegrationConnection {
+            id: None,
+            name: request.name.trim().to_string(),
+            provider_type: request.provider_type,
+            base_url: request.base_url.trim_end_matches('/').to_string(),
+            username: request.username,
+            password_encrypted,
+            verify_tls: request.verify_tls,
+            sync_interval_minutes: request.sync_interval_minutes.filter(|m| *m > 0),
+            full_sync_interval_hours: request.full_sync_interval_hours.filter(|h| *h > 0),
+            enabled: request.enabled,
+            tenant_id: user_tenant(user),
+            last_sync_at: None,
+            last_full_sync_at: None,
+            last_sync_status: None,
+            last_error: None,
+            created_by: user.user_id.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<IntegrationConnection> =
+            self.db.create("integration_connections").content(&connection).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| IntegrationError::DatabaseError("Connection was not created".to_string()))
+    }
+
+    pub async fn update_connection(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: UpdateIntegrationConnectionRequest,
+    ) -> Result<IntegrationConnection, IntegrationError> {
+        let mut connection = self.get_connection(user, id).await?;
+
+        if let Some(name) = request.name {
+            if name.trim().is_empty() {
+                return Err(IntegrationError::Validation("Connection name is required".to_string()));
+            }
+            connection.name = name.trim().to_string();
+        }
+        if let Some(base_url) = request.base_url {
+            validate_base_url(&base_url)?;
+            connection.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        if let Some(username) = request.username {
+            connection.username = Some(username).filter(|u| !u.is_empty());
+        }
+        if let Some(password) = request.password {
+            connection.password_encrypted = match password.is_empty() {
+                true => None,
+                false => Some(encrypt(&password)?),
+            };
+        }
+        if let Some(verify_tls) = request.verify_tls {
+            connection.verify_tls = verify_tls;
+        }
+        if let Some(minutes) = request.sync_interval_minutes {
+            connection.sync_interval_minutes = Some(minutes).filter(|m| *m > 0);
+        }
+        if let Some(hours) = request.full_sync_interval_hours {
+            connection.full_sync_interval_hours = Some(hours).filter(|h| *h > 0);
+        }
+        if let Some(enabled) = request.enabled {
+            connection.enabled = enabled;
+        }
+        connection.updated_at = Utc::now();
+
+        let thing = connection.id.clone().ok_or(IntegrationError::NotFound)?;
+        let updated: Option<IntegrationConnection> = self.db.update(thing).content(&connection).await?;
+        updated.ok_or(IntegrationError::NotFound)
+    }
+
+    /// Delete a connection and its sync history. Synced CIs stay in the CMDB.
+    pub async fn delete_connection(&self, user: &AuthenticatedUser, id: &str) -> Result<(), IntegrationError> {
+        let connection = self.get_connection(user, id).await?;
+        let thing = connection.id.ok_or(IntegrationError::NotFound)?;
+        self.db
+            .query("DELETE integration_sync_runs WHERE connection_id = $id")
+            .query("DELETE integration_assets WHERE connection_id = $id")
+            .query("DELETE $id")
+            .bind(("id", thing))
+            .traced("integration_hub.delete_connection")
+            .await?;
+        Ok(())
+    }
+
+    /// Check connectivity and credentials without syncing
+    pub async fn test_connection(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<(), IntegrationError> {
+        let connection = self.get_connection(user, id).await?;
+        let connector = SimulationService::new(self.db.clone()).connector(&connection).await?;
+        match connector.test_connection().await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(IntegrationError::Connector("connection test failed".to_string())),
+            Err(e) => Err(IntegrationError::Connector(e.to_string())),
+        }
+    }
+
+    // ========================================================================
+    // SYNC HISTORY
+    // ========================================================================
+
+    pub async fn list_runs(
+        &self,
+        user: &AuthenticatedUser,
+        connection_id: &str,
+        limit: usize,
+    ) -> Result<Vec<IntegrationSyncRun>, IntegrationError> {
+        let connection = self.get_connection(user, connection_id).await?;
+        let runs: Vec<IntegrationSyncRun> = self
+            .db
+            .query("SELECT * FROM integration_sync_runs WHERE connection_id = $id ORDER BY started_at DESC LIMIT $limit")
+            .bind(("id", connection.id))
+            .bind(("limit", limit.clamp(1, 200)))
+            .traced("integration_hub.list_runs")
+            .await?
+            .take(0)?;
+        Ok(runs)
+    }
+
+    pub async fn get_run(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<IntegrationSyncRun, IntegrationError> {
+        let run: Option<IntegrationSyncRun> = self.db.select(parse_thing("integration_sync_runs", id)).await?;
+        let run = run.ok_or(IntegrationError::NotFound)?;
+        // Tenant check goes through the owning connection
+        self.get_connection(user, &run.connection_id.to_string()).await?;
+        Ok(run)
+    }
+
+    pub async fn sync_status(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<IntegrationSyncStatus, IntegrationError> {
+        let connection = self.get_connection(user, id).await?;
+        let last_run: Option<IntegrationSyncRun> = self
+            .db
+            .query("SELECT * FROM integration_sync_runs WHERE connection_id = $id ORDER BY started_at DESC LIMIT 1")
+            .bind(("id", connection.id.clone()))
+            .traced("integration_hub.sync_status")
+            .await?
+            .take(0)?;
+
+        let counts: Vec<serde_json::Value> = self
+            .db
+            .query("SELECT asset_type, count() AS total FROM integration_assets WHERE connection_id = $id AND removed = false GROUP BY asset_type")
+            .bind(("id", connection.id.clone()))
+            .traced("integration_hub.sync_status")
+            .await?
+            .take(0)?;
+        let asset_counts = counts
+            .iter()
+            .filter_map(|row| Some((row["asset_type"].as_str()?.to_string(), row["total"].as_u64()?)))
+            .collect();
+
+        let next_scheduled_at = match (connection.enabled, connection.sync_interval_minutes) {
+            (true, Some(minutes)) => Some(
+                connection
+                    .last_sync_at
+                    .map(|at| at + chrono::Duration::minutes(minutes as i64))
+                    .unwrap_or_else(Utc::now),
+            ),
+            _ => None,
+        };
+
+        Ok(IntegrationSyncStatus {
+            running: last_run.as_ref().is_some_and(|r| r.status == SyncRunStatus::Running),
+            connection: IntegrationConnectionView::from(&connection),
+            last_run,
+            next_scheduled_at,
+            asset_counts,
+        })
+    }
+
     pub async fn run_scan(&self, config: IntegrationConfig) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         // 1. Select Connector
         let connector: Box<dyn IntegrationConnector> = match config.provider_type {
@@ -37,9 +319,9 @@ impl IntegrationService {
 
             // Create record
             let record_id = format!("{}:{}", table, asset.external_id);
-            
+
             // We use a generic query to upsert
-            // In a real app, we would map fields strictly. 
+            // In a real app, we would map fields strictly.
             // Here we dump raw_data + metadata.
             let content = serde_json::json!({
                 "name": asset.name,
@@ -60,3 +342,53 @@ impl IntegrationService {
         Ok(saved_ids)
     }
 }
+
+/// Connector configuration for a stored connection, with the password decrypted
+pub fn connector_config(connection: &IntegrationConnection) -> Result<IntegrationConfig, IntegrationError> {
+    let auth_token = match &connection.password_encrypted {
+        Some(sealed) => SecretBox::from_env()
+            .and_then(|secrets| secrets.decrypt(sealed))
+            .map_err(|e| IntegrationError::Secret(e.to_string()))?,
+        None => String::new(),
+    };
+    Ok(IntegrationConfig {
+        id: connection.id.as_ref().map(|id| id.to_string()).unwrap_or_default(),
+        name: connection.name.clone(),
+        provider_type: connection.provider_type.clone(),
+        base_url: connection.base_url.clone(),
+        username: connection.username.clone(),
+        auth_token,
+        poll_interval_seconds: connection.sync_interval_minutes.unwrap_or(0) as u64 * 60,
+        verify_tls: connection.verify_tls,
+    })
+}
+
+fn encrypt(password: &str) -> Result<String, IntegrationError> {
+    SecretBox::from_env()
+        .and_then(|secrets| secrets.encrypt(password))
+        .map_err(|e| IntegrationError::Secret(e.to_string()))
+}
+
+fn validate_base_url(url: &str) -> Result<(), IntegrationError> {
+    let url = url.trim();
+    let schemes = ["https://", "http://", "ldaps://", "ldap://", "mock://"];
+    if schemes.iter().any(|scheme| url.starts_with(scheme)) {
+        Ok(())
+    } else {
+        Err(IntegrationError::Validation(
+            "base_url must start with https:// (ldaps:// for Active Directory)".to_string(),
+        ))
+    }
+}
+
+pub(crate) fn user_tenant(user: &AuthenticatedUser) -> Option<Thing> {
+    user.tenant_id.as_deref().map(|t| Thing::from(("tenants", t)))
+}
+
+/// Parse a string ID into a SurrealDB Thing
+pub(crate) fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
@@ -0,0 +1,262 @@
+//! Simulation mode
+//!
+//! Serves a connector's recorded fixtures instead of calling the real system,
+//! so demos and E2E runs work fully offline. The real client is used with a
+//! `mock://` base URL: fixtures are in the provider's raw API shape and go
+//! through the same mappers as live responses. Every call is delayed by a
+//! random latency so the UI shows its loading states as it would in production.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::Rng;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::database::Database;
+use crate::models::integration::{
+    IntegrationConnection, SimulationSettings, SimulationSettingsResponse, UpdateSimulationSettingsRequest,
+};
+
+use super::connector::{
+    ConnectorError, DiscoveredAsset, IntegrationConfig, IntegrationConnector, InventorySnapshot,
+};
+use super::service::{connector_config, connector_for, IntegrationError};
+
+const SETTINGS_RECORD: (&str, &str) = ("integration_simulation", "default");
+const SIMULATION_URL: &str = "mock://simulation";
+/// Upper bound on the configurable delay; keeps E2E suites from timing out
+const MAX_LATENCY_MS: u64 = 10_000;
+
+/// `INTEGRATION_SIMULATION=true` simulates every connection regardless of
+/// the stored settings
+pub fn forced_by_env() -> bool {
+    std::env::var("INTEGRATION_SIMULATION")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Wraps the provider's client pointed at its fixtures
+pub struct SimulatedConnector {
+    inner: Result<Box<dyn IntegrationConnector>, String>,
+    min_latency_ms: u64,
+    max_latency_ms: u64,
+}
+
+impl SimulatedConnector {
+    pub fn with_latency(mut config: IntegrationConfig, min_latency_ms: u64, max_latency_ms: u64) -> Self {
+        config.base_url = SIMULATION_URL.to_string();
+        let inner = connector_for(config).map_err(|e| e.to_string());
+        Self {
+            inner,
+            min_latency_ms: min_latency_ms.min(max_latency_ms),
+            max_latency_ms,
+        }
+    }
+
+    async fn delay(&self) {
+        let ms = rand::thread_rng().gen_range(self.min_latency_ms..=self.max_latency_ms);
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+    }
+
+    fn inner(&self) -> Result<&dyn IntegrationConnector, ConnectorError> {
+        self.inner
+            .as_ref()
+            .map(|c| c.as_ref())
+            .map_err(|e| format!("No simulation fixtures: {}", e).into())
+    }
+}
+
+#[async_trait]
+impl IntegrationConnector for SimulatedConnector {
+    fn new(config: IntegrationConfig) -> Self {
+        let defaults = SimulationSettings::default();
+        Self::with_latency(config, defaults.min_latency_ms, defaults.max_latency_ms)
+    }
+
+    async fn test_connection(&self) -> Result<bool, ConnectorError> {
+        self.delay().await;
+        self.inner()?.test_connection().await
+    }
+
+    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, ConnectorError> {
+        self.delay().await;
+        self.inner()?.fetch_inventory().await
+    }
+
+    async fn fetch_snapshot(&self) -> Result<InventorySnapshot, ConnectorError> {
+        self.delay().await;
+        self.inner()?.fetch_snapshot().await
+    }
+
+    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, ConnectorError> {
+        self.delay().await;
+        self.inner()?.fetch_alerts().await
+    }
+}
+
+pub struct SimulationService {
+    db: Arc<Database>,
+}
+
+impl SimulationService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_settings(&self) -> Result<SimulationSettings, IntegrationError> {
+        let stored: Option<SimulationSettings> = self.db.select(SETTINGS_RECORD).await?;
+        Ok(stored.unwrap_or_default())
+    }
+
+    pub async fn settings_response(&self) -> Result<SimulationSettingsResponse, IntegrationError> {
+        Ok(SimulationSettingsResponse {
+            settings: self.get_settings().await?,
+            forced_by_env: forced_by_env(),
+        })
+    }
+
+    pub async fn update_settings(
+        &self,
+        request: UpdateSimulationSettingsRequest,
+    ) -> Result<SimulationSettingsResponse, IntegrationError> {
+        let mut settings = self.get_settings().await?;
+        if let Some(enabled) = request.enabled {
+            settings.enabled = enabled;
+        }
+        if let Some(mut providers) = request.providers {
+            providers.dedup();
+            settings.providers = providers;
+        }
+        if let Some(ids) = request.connection_ids {
+            settings.connection_ids = ids
+                .into_iter()
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .collect();
+        }
+        if let Some(min) = request.min_latency_ms {
+            settings.min_latency_ms = min;
+        }
+        if let Some(max) = request.max_latency_ms {
+            settings.max_latency_ms = max;
+        }
+        if settings.min_latency_ms > settings.max_latency_ms {
+            return Err(IntegrationError::Validation(
+                "min_latency_ms must not exceed max_latency_ms".to_string(),
+            ));
+        }
+        if settings.max_latency_ms > MAX_LATENCY_MS {
+            return Err(IntegrationError::Validation(format!(
+                "max_latency_ms must not exceed {}",
+                MAX_LATENCY_MS
+            )));
+        }
+        settings.updated_at = Utc::now();
+
+        let saved: Option<SimulationSettings> = self.db.update(SETTINGS_RECORD).content(settings).await?;
+        let settings = saved
+            .ok_or_else(|| IntegrationError::DatabaseError("Failed to store simulation settings".to_string()))?;
+        Ok(SimulationSettingsResponse {
+            settings,
+            forced_by_env: forced_by_env(),
+        })
+    }
+
+    /// Connector for a connection: the real client, or its simulation when the
+    /// settings (or `INTEGRATION_SIMULATION`) say so
+    pub async fn connector(
+        &self,
+        connection: &IntegrationConnection,
+    ) -> Result<Box<dyn IntegrationConnector>, IntegrationError> {
+        let settings = self.get_settings().await?;
+        if !forced_by_env() && !settings.applies_to(connection) {
+            return connector_config(connection).and_then(connector_for);
+        }
+
+        // Credentials are never needed, and may not be decryptable on a demo box
+        let config = IntegrationConfig {
+            id: connection.id.as_ref().map(|id| id.to_string()).unwrap_or_default(),
+            name: connection.name.clone(),
+            provider_type: connection.provider_type.clone(),
+            base_url: SIMULATION_URL.to_string(),
+            username: connection.username.clone(),
+            auth_token: String::new(),
+            poll_interval_seconds: connection.sync_interval_minutes.unwrap_or(0) as u64 * 60,
+            verify_tls: connection.verify_tls,
+        };
+        let simulated = SimulatedConnector::with_latency(config, settings.min_latency_ms, settings.max_latency_ms);
+        if let Err(e) = &simulated.inner {
+            return Err(IntegrationError::Validation(e.clone()));
+        }
+        Ok(Box::new(simulated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::integration_hub::ProviderType;
+    use surrealdb::sql::Thing;
+
+    fn connection(provider_type: ProviderType, id: &str) -> IntegrationConnection {
+        IntegrationConnection {
+            id: Some(Thing::from(("integration_connections", id))),
+            name: "prism-lab".to_string(),
+            provider_type,
+            base_url: "https://prism.example.internal:9440".to_string(),
+            username: Some("admin".to_string()),
+            password_encrypted: None,
+            verify_tls: false,
+            sync_interval_minutes: None,
+            full_sync_interval_hours: None,
+            enabled: true,
+            tenant_id: None,
+            last_sync_at: None,
+            last_full_sync_at: None,
+            last_sync_status: None,
+            last_error: None,
+            created_by: "tester".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn settings_select_connections_by_provider_or_id() {
+        let mut settings = SimulationSettings::default();
+        let prism = connection(ProviderType::NutanixPrism, "prism");
+        let f5 = connection(ProviderType::F5BigIp, "lb01");
+        assert!(!settings.applies_to(&prism));
+
+        settings.providers = vec![ProviderType::NutanixPrism];
+        assert!(settings.applies_to(&prism));
+        assert!(!settings.applies_to(&f5));
+
+        settings.connection_ids = vec!["lb01".to_string()];
+        assert!(settings.applies_to(&f5));
+
+        settings.providers.clear();
+        settings.connection_ids.clear();
+        settings.enabled = true;
+        assert!(settings.applies_to(&f5));
+    }
+
+    #[tokio::test]
+    async fn simulated_connector_serves_fixtures_through_the_real_client() {
+        let config = IntegrationConfig {
+            id: "integration_connections:prism".to_string(),
+            name: "prism-lab".to_string(),
+            provider_type: ProviderType::NutanixPrism,
+            base_url: "https://prism.example.internal:9440".to_string(),
+            username: None,
+            auth_token: String::new(),
+            poll_interval_seconds: 0,
+            verify_tls: false,
+        };
+        let connector = SimulatedConnector::with_latency(config, 0, 0);
+        assert!(connector.test_connection().await.unwrap());
+        let snapshot = connector.fetch_snapshot().await.unwrap();
+        assert!(!snapshot.assets.is_empty());
+        assert!(snapshot.failed_kinds.is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use super::connector::{
+    AssetLink, ConnectorError, DiscoveredAsset, IntegrationConfig, IntegrationConnector, InventorySnapshot,
+    LinkResolution,
+};
+use crate::utils::telemetry::TracedRequest;
+
+pub const KIND_SEARCH_HEAD: &str = "SEARCH_HEAD";
+pub const KIND_INDEXER: &str = "INDEXER";
+pub const KIND_FORWARDER: &str = "FORWARDER";
+
+/// Forwarders that sent data to an indexer in the last day, with the
+/// indexers they sent to
+const FORWARDER_SEARCH: &str = "search index=_internal source=*metrics.log* group=tcpin_connections earliest=-24h \
+     | stats latest(sourceIp) as ip latest(fwdType) as fwd_type latest(version) as version values(host) as indexers by hostname";
+
+/// Splunk collector over the management REST API (port 8089), pointed at a
+/// search head. Indexers come from its distributed search peers; forwarders
+/// from the deployment server client list (when the search head is one) and
+/// from indexer `tcpin_connections` metrics, which also show where each
+/// forwarder sends its data.
+pub struct SplunkClient {
+    config: IntegrationConfig,
+    client: Client,
+}
+
+impl SplunkClient {
+    fn is_mock(&self) -> bool {
+        self.config.base_url.starts_with("mock://")
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    /// Basic auth with a username, otherwise the secret is an auth token
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.config.username {
+            Some(username) => request.basic_auth(username, Some(&self.config.auth_token)),
+            None => request.bearer_auth(&self.config.auth_token),
+        }
+    }
+
+    async fn check(response: reqwest::Response, path: &str) -> Result<Option<Value>, ConnectorError> {
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(format!("Splunk rejected the credentials ({})", status).into());
+        }
+        // Endpoints for roles the instance does not have answer 404
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(format!("Splunk {} returned {}", path, status).into());
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    /// `content` of every entry in a REST collection
+    async fn entries(&self, path: &str) -> Result<Vec<Value>, ConnectorError> {
+        let request = self
+            .client
+            .get(self.url(path))
+            .query(&[("output_mode", "json"), ("count", "0")]);
+        let response = self.authorize(request).send_traced("splunk").await?;
+        Ok(Self::check(response, path)
+            .await?
+            .and_then(|body| body["entry"].as_array().cloned())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| entry["content"].clone())
+            .collect())
+    }
+
+    async fn oneshot(&self, search: &str) -> Result<Vec<Value>, ConnectorError> {
+        let path = "/services/search/jobs";
+        let request = self.client.post(self.url(path)).form(&[
+            ("exec_mode", "oneshot"),
+            ("output_mode", "json"),
+            ("count", "0"),
+            ("search", search),
+        ]);
+        let response = self.authorize(request).send_traced("splunk").await?;
+        Ok(Self::check(response, path)
+            .await?
+            .and_then(|body| body["results"].as_array().cloned())
+            .unwrap_or_default())
+    }
+
+    fn mock_topology() -> SplunkTopology {
+        SplunkTopology {
+            server_info: Some(json!({
+                "serverName": "splunk-sh01", "host_fqdn": "splunk-sh01.corp.example.com", "version": "9.1.2",
+                "server_roles": ["search_head", "deployment_server"], "guid": "mock-sh-guid"
+            })),
+            peers: vec![json!({
+                "peerName": "splunk-idx01", "host": "splunk-idx01", "host_fqdn": "splunk-idx01.corp.example.com",
+                "version": "9.1.2", "server_roles": ["indexer"], "status": "Up", "guid": "mock-idx-guid"
+            })],
+            deployment_clients: vec![json!({
+                "hostname": "VM-Web-01", "ip": "10.100.0.10", "dns": "vm-web-01.corp.example.com",
+                "utsname": "linux-x86_64", "lastPhoneHomeTime": 1704146400,
+                "serverClasses": { "linux_base": {}, "web_logs": {} }
+            })],
+            forwarder_connections: vec![json!({
+                "hostname": "VM-Web-01", "ip": "10.100.0.10", "fwd_type": "uf", "version": "9.1.2",
+                "indexers": "splunk-idx01"
+            })],
+        }
+    }
+}
+
+/// What the REST API reports, before mapping
+#[derive(Debug, Default)]
+struct SplunkTopology {
+    server_info: Option<Value>,
+    peers: Vec<Value>,
+    deployment_clients: Vec<Value>,
+    forwarder_connections: Vec<Value>,
+}
+
+#[async_trait]
+impl IntegrationConnector for SplunkClient {
+    fn new(config: IntegrationConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .danger_accept_invalid_certs(!config.verify_tls)
+            .build()
+            .unwrap_or_default();
+
+        Self { config, client }
+    }
+
+    async fn test_connection(&self) -> Result<bool, ConnectorError> {
+        if self.is_mock() {
+            return Ok(true);
+        }
+        self.entries("/services/server/info").await?;
+        Ok(true)
+    }
+
+    async fn fetch_inventory(&self) -> Result<Vec<DiscoveredAsset>, ConnectorError> {
+        let snapshot = self.fetch_snapshot().await?;
+        if let Some((kind, error)) = snapshot.failed_kinds.first() {
+            return Err(format!("failed to fetch {}: {}", kind, error).into());
+        }
+        Ok(snapshot.assets)
+    }
+
+    async fn fetch_snapshot(&self) -> Result<InventorySnapshot, ConnectorError> {
+        let mut snapshot = InventorySnapshot::default();
+        let topology = if self.is_mock() {
+            snapshot.complete_kinds = [KIND_SEARCH_HEAD, KIND_INDEXER, KIND_FORWARDER]
+                .iter()
+                .map(|k| k.to_string())
+                .collect();
+            Self::mock_topology()
+        } else {
+            let server_info = self.entries("/services/server/info").await?.into_iter().next();
+            let peers = self.entries("/services/search/distributed/peers").await?;
+            snapshot.complete_kinds.extend([KIND_SEARCH_HEAD.to_string(), KIND_INDEXER.to_string()]);
+
+            // Forwarders are optional extras; a failure must not retire them
+            let forwarders = async {
+                let clients = self.entries("/services/deployment/server/clients").await?;
+                let connections = self.oneshot(FORWARDER_SEARCH).await?;
+                Ok::<_, ConnectorError>((clients, connections))
+            };
+            let (deployment_clients, forwarder_connections) = match forwarders.await {
+                Ok(found) => {
+                    snapshot.complete_kinds.push(KIND_FORWARDER.to_string());
+                    found
+                }
+                Err(e) => {
+                    snapshot.failed_kinds.push((KIND_FORWARDER.to_string(), e.to_string()));
+                    (Vec::new(), Vec::new())
+                }
+            };
+            SplunkTopology {
+                server_info,
+                peers,
+                deployment_clients,
+                forwarder_connections,
+            }
+        };
+
+        let indexers: Vec<DiscoveredAsset> = topology.peers.iter().filter_map(map_indexer).collect();
+        if let Some(info) = &topology.server_info {
+            snapshot.assets.extend(map_search_head(info, &indexers));
+        }
+        snapshot.assets.extend(map_forwarders(&topology.deployment_clients, &topology.forwarder_connections));
+        snapshot.assets.extend(indexers);
+        Ok(snapshot)
+    }
+
+    async fn fetch_alerts(&self) -> Result<Vec<serde_json::Value>, ConnectorError> {
+        Ok(vec![])
+    }
+}
+
+// ============================================================================
+// MAPPING
+// ============================================================================
+
+fn link(relationship: &str, target: String, resolve_by: LinkResolution) -> AssetLink {
+    AssetLink {
+        relationship: relationship.to_string(),
+        target_external_id: target,
+        resolve_by,
+    }
+}
+
+fn indexer_id(name: &str) -> String {
+    format!("idx:{}", name.to_lowercase())
+}
+
+fn short_host(host: &str) -> String {
+    host.split('.').next().unwrap_or(host).to_string()
+}
+
+/// Splunk returns single-valued multivalue fields as plain strings
+fn string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().filter_map(|i| i.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn map_indexer(peer: &Value) -> Option<DiscoveredAsset> {
+    let name = peer["peerName"].as_str().or_else(|| peer["host"].as_str())?.to_string();
+    Some(DiscoveredAsset {
+        external_id: indexer_id(&name),
+        name: name.clone(),
+        asset_type: KIND_INDEXER.to_string(),
+        raw_data: json!({
+            "hostname": peer["host_fqdn"].as_str().unwrap_or(&name),
+            "version": peer["version"],
+            "server_roles": peer["server_roles"],
+            "peer_status": peer["status"],
+            "guid": peer["guid"],
+        }),
+        links: vec![link("RUNS_ON", short_host(&name), LinkResolution::Name)],
+    })
+}
+
+fn map_search_head(info: &Value, indexers: &[DiscoveredAsset]) -> Option<DiscoveredAsset> {
+    let name = info["serverName"].as_str()?.to_string();
+    let mut links: Vec<AssetLink> = indexers
+        .iter()
+        .map(|i| link("USES", i.external_id.clone(), LinkResolution::ExternalId))
+        .collect();
+    links.push(link("RUNS_ON", short_host(&name), LinkResolution::Name));
+    Some(DiscoveredAsset {
+        external_id: format!("sh:{}", name.to_lowercase()),
+        name: name.clone(),
+        asset_type: KIND_SEARCH_HEAD.to_string(),
+        raw_data: json!({
+            "hostname": info["host_fqdn"].as_str().unwrap_or(&name),
+            "version": info["version"],
+            "server_roles": info["server_roles"],
+            "guid": info["guid"],
+            "indexer_count": indexers.len(),
+        }),
+        links,
+    })
+}
+
+/// Merge deployment clients and tcpin connections by hostname
+fn map_forwarders(clients: &[Value], connections: &[Value]) -> Vec<DiscoveredAsset> {
+    let mut forwarders: BTreeMap<String, Value> = BTreeMap::new();
+    for client in clients {
+        let Some(hostname) = client["hostname"].as_str() else { continue };
+        let server_classes: Vec<&String> = client["serverClasses"]
+            .as_object()
+            .map(|classes| classes.keys().collect())
+            .unwrap_or_default();
+        forwarders.insert(
+            hostname.to_lowercase(),
+            json!({
+                "hostname": hostname,
+                "ip_address": client["ip"],
+                "dns_name": client["dns"],
+                "platform": client["utsname"],
+                "server_classes": server_classes,
+                "last_phone_home": client["lastPhoneHomeTime"],
+                "outputs": [],
+            }),
+        );
+    }
+    for connection in connections {
+        let Some(hostname) = connection["hostname"].as_str() else { continue };
+        let entry = forwarders
+            .entry(hostname.to_lowercase())
+            .or_insert_with(|| json!({ "hostname": hostname, "server_classes": [] }));
+        if entry["ip_address"].is_null() {
+            entry["ip_address"] = connection["ip"].clone();
+        }
+        entry["version"] = connection["version"].clone();
+        entry["forwarder_type"] = json!(match connection["fwd_type"].as_str() {
+            Some("uf") => "universal",
+            Some("full") => "heavy",
+            Some(other) => other,
+            None => "unknown",
+        });
+        entry["outputs"] = json!(string_list(&connection["indexers"]));
+    }
+
+    forwarders
+        .into_iter()
+        .map(|(key, raw)| {
+            let hostname = raw["hostname"].as_str().unwrap_or(&key).to_string();
+            let mut links: Vec<AssetLink> = string_list(&raw["outputs"])
+                .iter()
+                .map(|indexer| link("CONNECTS_TO", indexer_id(indexer), LinkResolution::ExternalId))
+                .collect();
+            links.push(match raw["ip_address"].as_str() {
+                Some(ip) => link("RUNS_ON", ip.to_string(), LinkResolution::IpAddress),
+                None => link("RUNS_ON", short_host(&hostname), LinkResolution::Name),
+            });
+            DiscoveredAsset {
+                external_id: format!("fwd:{}", key),
+                name: hostname,
+                asset_type: KIND_FORWARDER.to_string(),
+                raw_data: raw,
+                links,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarders_merge_clients_and_connections() {
+        let topology = SplunkClient::mock_topology();
+        let forwarders = map_forwarders(&topology.deployment_clients, &topology.forwarder_connections);
+        assert_eq!(forwarders.len(), 1);
+
+        let fwd = &forwarders[0];
+        assert_eq!(fwd.external_id, "fwd:vm-web-01");
+        assert_eq!(fwd.raw_data["forwarder_type"], "universal");
+        assert_eq!(fwd.raw_data["outputs"], json!(["splunk-idx01"]));
+        assert_eq!(fwd.raw_data["server_classes"], json!(["linux_base", "web_logs"]));
+        assert_eq!(
+            fwd.links,
+            vec![
+                link("CONNECTS_TO", "idx:splunk-idx01".to_string(), LinkResolution::ExternalId),
+                link("RUNS_ON", "10.100.0.10".to_string(), LinkResolution::IpAddress),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_head_uses_its_peers() {
+        let topology = SplunkClient::mock_topology();
+        let indexers: Vec<DiscoveredAsset> = topology.peers.iter().filter_map(map_indexer).collect();
+        let sh = map_search_head(topology.server_info.as_ref().unwrap(), &indexers).unwrap();
+        assert_eq!(sh.links[0], link("USES", "idx:splunk-idx01".to_string(), LinkResolution::ExternalId));
+        assert_eq!(sh.links[1].target_external_id, "splunk-sh01");
+    }
+}
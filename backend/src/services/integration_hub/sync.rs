@@ -0,0 +1,1016 @@
+//! Inventory sync
+//!
+//! Pulls a connector's inventory into the CMDB (configuration_items and
+//! ci_relationships) and the provider's environment tables (nutanix_vm,
+//! cisco_switch, ...). Every discovered object is tracked in `integration_assets`
+//! with a fingerprint of its payload: delta syncs skip unchanged objects, and
+//! objects that disappear from a fully-loaded kind are retired.
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use tracing::{error, info, warn};
+
+use crate::database::Database;
+use crate::models::cmdb::{CIClass, CIStatus, CreateCIRequest, UpdateCIRequest};
+use crate::models::integration::*;
+use crate::services::cmdb_service::CMDBService;
+
+use super::connector::{DiscoveredAsset, InventorySnapshot, LinkResolution, ProviderType};
+use super::active_directory::{KIND_AD_SITE, KIND_AD_SUBNET, KIND_DOMAIN, KIND_DOMAIN_CONTROLLER};
+use super::catalyst::{KIND_INTERFACE, KIND_SWITCH, KIND_VLAN};
+use super::f5::{KIND_POOL, KIND_POOL_MEMBER, KIND_VIRTUAL_SERVER};
+use super::nutanix::{KIND_CLUSTER, KIND_HOST, KIND_STORAGE_CONTAINER, KIND_SUBNET, KIND_VM};
+use super::service::IntegrationError;
+use super::simulation::SimulationService;
+use super::splunk::{KIND_FORWARDER, KIND_INDEXER, KIND_SEARCH_HEAD};
+use super::veeam::KIND_BACKUP_JOB;
+use crate::database::TracedQuery;
+
+/// Runs left in RUNNING longer than this are assumed to have died with the process
+const STALE_RUN_HOURS: i64 = 2;
+const DEFAULT_FULL_SYNC_HOURS: u32 = 24;
+const SYNC_USER: &str = "integration-sync";
+/// `integration_asset` marker for VLAN↔subnet relationships, which belong to
+/// the connection rather than a single asset
+const VLAN_CORRELATION_KEY: &str = "vlan-subnet-correlation";
+
+/// How a discovered kind lands in the CMDB and the environment model
+struct KindMapping {
+    ci_class: CIClass,
+    ci_type: &'static str,
+    ci_prefix: &'static str,
+    environment_table: Option<&'static str>,
+}
+
+fn kind_mapping(provider: &ProviderType, asset_type: &str) -> KindMapping {
+    let (ci_class, ci_type, ci_prefix, table) = match (provider, asset_type) {
+        (ProviderType::NutanixPrism, KIND_CLUSTER) => (CIClass::Hardware, "Nutanix Cluster", "NX-CL", Some("nutanix_cluster")),
+        (ProviderType::NutanixPrism, KIND_HOST) => (CIClass::Hardware, "Hyperconverged Node", "NX-HOST", Some("nutanix_host")),
+        (ProviderType::NutanixPrism, KIND_VM) => (CIClass::Virtual, "Virtual Machine", "NX-VM", Some("nutanix_vm")),
+        (ProviderType::NutanixPrism, KIND_SUBNET) => (CIClass::Network, "Subnet", "NX-NET", Some("nutanix_network")),
+        (ProviderType::NutanixPrism, KIND_STORAGE_CONTAINER) => {
+            (CIClass::Hardware, "Storage Container", "NX-SC", Some("nutanix_storage_container"))
+        }
+        (ProviderType::CiscoCatalystCenter, KIND_SWITCH) => (CIClass::Network, "Switch", "CAT-SW", Some("cisco_switch")),
+        (ProviderType::CiscoCatalystCenter, KIND_INTERFACE) => {
+            (CIClass::Network, "Switch Interface", "CAT-IF", Some("cisco_switch_interface"))
+        }
+        (ProviderType::CiscoCatalystCenter, KIND_VLAN) => (CIClass::Network, "VLAN", "CAT-VLAN", Some("cisco_vlan")),
+        (ProviderType::F5BigIp, KIND_VIRTUAL_SERVER) => {
+            (CIClass::Service, "Load Balancer Virtual Server", "F5-VS", Some("f5_virtual_server"))
+        }
+        (ProviderType::F5BigIp, KIND_POOL) => (CIClass::Service, "Load Balancer Pool", "F5-POOL", Some("f5_pool")),
+        (ProviderType::F5BigIp, KIND_POOL_MEMBER) => {
+            (CIClass::Service, "Load Balancer Pool Member", "F5-PM", Some("f5_pool_member"))
+        }
+        (ProviderType::VeeamBackup, KIND_BACKUP_JOB) => (CIClass::Service, "Backup Job", "VBR-JOB", Some("veeam_backup_job")),
+        (ProviderType::ActiveDirectory, KIND_DOMAIN) => (CIClass::Service, "AD Domain", "AD-DOM", Some("ad_domain")),
+        (ProviderType::ActiveDirectory, KIND_DOMAIN_CONTROLLER) => {
+            (CIClass::Service, "Domain Controller", "AD-DC", Some("ad_domain_controller"))
+        }
+        (ProviderType::ActiveDirectory, KIND_AD_SITE) => (CIClass::Network, "AD Site", "AD-SITE", Some("ad_site")),
+        (ProviderType::ActiveDirectory, KIND_AD_SUBNET) => (CIClass::Network, "AD Subnet", "AD-NET", Some("ad_subnet")),
+        (ProviderType::Splunk, KIND_SEARCH_HEAD) => {
+            (CIClass::Software, "Splunk Search Head", "SPL-SH", Some("splunk_search_head"))
+        }
+        (ProviderType::Splunk, KIND_INDEXER) => (CIClass::Software, "Splunk Indexer", "SPL-IDX", Some("splunk_indexer")),
+        (ProviderType::Splunk, KIND_FORWARDER) => {
+            (CIClass::Software, "Splunk Forwarder", "SPL-FWD", Some("splunk_forwarder"))
+        }
+        _ => (CIClass::Hardware, "Discovered Asset", "DISC", None),
+    };
+    KindMapping {
+        ci_class,
+        ci_type,
+        ci_prefix,
+        environment_table: table,
+    }
+}
+
+/// Environment-record field and table a parent link is stored under,
+/// e.g. a VM's RUNS_ON link becomes `nutanix_vm.host`
+fn parent_field(provider: &ProviderType, relationship: &str) -> Option<(&'static str, &'static str)> {
+    match (provider, relationship) {
+        (ProviderType::NutanixPrism, "CONTAINED_BY") => Some(("cluster", "nutanix_cluster")),
+        (ProviderType::NutanixPrism, "RUNS_ON") => Some(("host", "nutanix_host")),
+        (ProviderType::CiscoCatalystCenter, "CONTAINED_BY") => Some(("switch", "cisco_switch")),
+        (ProviderType::F5BigIp, "MEMBER_OF") => Some(("pool", "f5_pool")),
+        (ProviderType::F5BigIp, "DEPENDS_ON") => Some(("default_pool", "f5_pool")),
+        (ProviderType::ActiveDirectory, "MEMBER_OF") => Some(("domain", "ad_domain")),
+        (ProviderType::ActiveDirectory, "CONTAINED_BY") => Some(("ad_site", "ad_site")),
+        _ => None,
+    }
+}
+
+fn vendor(provider: &ProviderType) -> Option<String> {
+    match provider {
+        ProviderType::NutanixPrism => Some("Nutanix".to_string()),
+        ProviderType::CiscoCatalystCenter | ProviderType::CiscoACI => Some("Cisco".to_string()),
+        ProviderType::F5BigIp => Some("F5".to_string()),
+        ProviderType::VeeamBackup => Some("Veeam".to_string()),
+        ProviderType::ActiveDirectory => Some("Microsoft".to_string()),
+        ProviderType::Splunk => Some("Splunk".to_string()),
+        _ => None,
+    }
+}
+
+/// Fingerprint of everything a sync writes for an asset
+pub fn fingerprint(asset: &DiscoveredAsset) -> String {
+    let payload = json!({
+        "name": asset.name,
+        "raw": asset.raw_data,
+        "links": asset.links,
+    });
+    let mut hasher = Sha256::new();
+    hasher.update(payload.to_string().as_bytes());
+    hex(&hasher.finalize())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Record keys are derived from the connection and external id so re-syncs
+/// land on the same rows
+fn record_key(connection: &Thing, external_id: &str) -> String {
+    format!("{}_{}", connection.id.to_raw(), external_id)
+}
+
+pub struct InventorySyncService {
+    db: Arc<Database>,
+}
+
+impl InventorySyncService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Create the run record; fails if another sync of the connection is in flight
+    pub async fn start_run(
+        &self,
+        connection: &IntegrationConnection,
+        mode: SyncMode,
+        triggered_by: &str,
+    ) -> Result<IntegrationSyncRun, IntegrationError> {
+        let connection_id = connection.id.clone().ok_or(IntegrationError::NotFound)?;
+        let running: Vec<IntegrationSyncRun> = self
+            .db
+            .query("SELECT * FROM integration_sync_runs WHERE connection_id = $id AND status = 'RUNNING'")
+            .bind(("id", connection_id.clone()))
+            .traced("sync.start_run")
+            .await?
+            .take(0)?;
+
+        let stale_before = Utc::now() - Duration::hours(STALE_RUN_HOURS);
+        for run in running {
+            if run.started_at > stale_before {
+                return Err(IntegrationError::Conflict("A sync is already running for this connection".to_string()));
+            }
+            let mut run = run;
+            run.status = SyncRunStatus::Failed;
+            run.errors.push("Sync did not finish (server restarted?)".to_string());
+            run.finished_at = Some(Utc::now());
+            self.save_run(&run).await?;
+        }
+
+        let run = IntegrationSyncRun {
+            id: None,
+            connection_id,
+            provider_type: connection.provider_type.clone(),
+            mode,
+            status: SyncRunStatus::Running,
+            triggered_by: triggered_by.to_string(),
+            counts: SyncCounts::default(),
+            by_kind: BTreeMap::new(),
+            errors: Vec::new(),
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        let created: Vec<IntegrationSyncRun> = self.db.create("integration_sync_runs").content(&run).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| IntegrationError::DatabaseError("Sync run was not created".to_string()))
+    }
+
+    /// Execute a started run to completion. Failures are recorded on the run
+    /// and the connection rather than returned.
+    pub async fn execute_run(&self, connection: IntegrationConnection, mut run: IntegrationSyncRun) -> IntegrationSyncRun {
+        let outcome = match SimulationService::new(self.db.clone()).connector(&connection).await {
+            Ok(connector) => connector
+                .fetch_snapshot()
+                .await
+                .map_err(|e| IntegrationError::Connector(e.to_string())),
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(snapshot) => {
+                if let Err(e) = self.apply_snapshot(&connection, &mut run, snapshot).await {
+                    run.errors.push(e.to_string());
+                    run.status = SyncRunStatus::Failed;
+                }
+            }
+            Err(e) => {
+                run.errors.push(e.to_string());
+                run.status = SyncRunStatus::Failed;
+            }
+        }
+        run.finished_at = Some(Utc::now());
+
+        if let Err(e) = self.save_run(&run).await {
+            error!("❌ Failed to save sync run: {}", e);
+        }
+        if let Err(e) = self.record_outcome(&connection, &run).await {
+            error!("❌ Failed to update integration connection: {}", e);
+        }
+        run
+    }
+
+    async fn apply_snapshot(
+        &self,
+        connection: &IntegrationConnection,
+        run: &mut IntegrationSyncRun,
+        snapshot: InventorySnapshot,
+    ) -> Result<(), IntegrationError> {
+        let connection_id = run.connection_id.clone();
+        let now = Utc::now();
+
+        let ledger: Vec<IntegrationAssetRecord> = self
+            .db
+            .query("SELECT * FROM integration_assets WHERE connection_id = $id")
+            .bind(("id", connection_id.clone()))
+            .traced("sync.apply_snapshot")
+            .await?
+            .take(0)?;
+        let mut ledger: HashMap<String, IntegrationAssetRecord> =
+            ledger.into_iter().map(|r| (r.external_id.clone(), r)).collect();
+
+        for (kind, error) in &snapshot.failed_kinds {
+            run.errors.push(format!("{}: {}", kind, error));
+        }
+
+        // Pass 1: CIs, environment records and ledger entries
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut unchanged: Vec<String> = Vec::new();
+        let mut changed: Vec<&DiscoveredAsset> = Vec::new();
+        for asset in &snapshot.assets {
+            if !seen.insert(asset.external_id.clone()) {
+                continue;
+            }
+            let counts = run.by_kind.entry(asset.asset_type.clone()).or_default();
+            counts.discovered += 1;
+
+            let print = fingerprint(asset);
+            let previous = ledger.get(&asset.external_id);
+            let is_unchanged = run.mode == SyncMode::Delta
+                && previous.is_some_and(|p| p.fingerprint == print && p.ci.is_some() && !p.removed);
+            if is_unchanged {
+                counts.unchanged += 1;
+                unchanged.push(asset.external_id.clone());
+                continue;
+            }
+
+            match self.write_asset(connection, &connection_id, asset, previous, &print, now).await {
+                Ok(record) => {
+                    let counts = run.by_kind.entry(asset.asset_type.clone()).or_default();
+                    if previous.is_some_and(|p| !p.removed) {
+                        counts.updated += 1;
+                    } else {
+                        counts.created += 1;
+                    }
+                    ledger.insert(asset.external_id.clone(), record);
+                    changed.push(asset);
+                }
+                Err(e) => run.errors.push(format!("{} {}: {}", asset.asset_type, asset.name, e)),
+            }
+        }
+
+        if !unchanged.is_empty() {
+            self.db
+                .query("UPDATE integration_assets SET last_seen = $now WHERE connection_id = $id AND external_id INSIDE $ids")
+                .bind(("now", now))
+                .bind(("id", connection_id.clone()))
+                .bind(("ids", unchanged.clone()))
+                .traced("sync.apply_snapshot")
+                .await?;
+        }
+
+        // Pass 2: relationships of changed assets, now that every CI exists.
+        // Links resolved by address or name are re-resolved every time since
+        // their targets may have been imported by another connection since.
+        let external = snapshot.assets.iter().filter(|a| {
+            unchanged.contains(&a.external_id)
+                && a.links.iter().any(|l| l.resolve_by != LinkResolution::ExternalId)
+        });
+        let tenant = connection.tenant_id.as_ref();
+        for asset in changed.into_iter().chain(external) {
+            match self.write_relationships(&connection_id, tenant, asset, &ledger, now).await {
+                Ok(count) => run.by_kind.entry(asset.asset_type.clone()).or_default().relationships += count,
+                Err(e) => run.errors.push(format!("relationships of {}: {}", asset.name, e)),
+            }
+        }
+
+        if connection.provider_type == ProviderType::CiscoCatalystCenter {
+            match self.correlate_vlans(connection, &connection_id, &ledger, now).await {
+                Ok(count) => run.by_kind.entry(KIND_VLAN.to_string()).or_default().relationships += count,
+                Err(e) => run.errors.push(format!("VLAN correlation: {}", e)),
+            }
+        }
+
+        // Pass 3: retire objects that vanished from kinds we loaded completely
+        for record in ledger.values() {
+            if record.removed
+                || seen.contains(&record.external_id)
+                || !snapshot.complete_kinds.contains(&record.asset_type)
+            {
+                continue;
+            }
+            match self.retire_asset(&connection_id, record, now).await {
+                Ok(()) => run.by_kind.entry(record.asset_type.clone()).or_default().removed += 1,
+                Err(e) => run.errors.push(format!("retire {}: {}", record.name, e)),
+            }
+        }
+
+        run.counts = run.by_kind.values().fold(SyncCounts::default(), |mut total, c| {
+            total.discovered += c.discovered;
+            total.created += c.created;
+            total.updated += c.updated;
+            total.unchanged += c.unchanged;
+            total.removed += c.removed;
+            total.relationships += c.relationships;
+            total
+        });
+        run.status = if run.errors.is_empty() {
+            SyncRunStatus::Succeeded
+        } else {
+            SyncRunStatus::Partial
+        };
+        Ok(())
+    }
+
+    async fn write_asset(
+        &self,
+        connection: &IntegrationConnection,
+        connection_id: &Thing,
+        asset: &DiscoveredAsset,
+        previous: Option<&IntegrationAssetRecord>,
+        print: &str,
+        now: DateTime<Utc>,
+    ) -> Result<IntegrationAssetRecord, String> {
+        let mapping = kind_mapping(&connection.provider_type, &asset.asset_type);
+
+        let mut attributes: HashMap<String, Value> = match &asset.raw_data {
+            Value::Object(map) => map.iter().filter(|(_, v)| !v.is_null()).map(|(k, v)| (k.clone(), v.clone())).collect(),
+            _ => HashMap::new(),
+        };
+        attributes.insert("external_id".to_string(), json!(asset.external_id));
+        attributes.insert("integration_connection".to_string(), json!(connection_id.to_string()));
+
+        let ip_address = first_string(&asset.raw_data, &["hypervisor_ip", "ip_address", "cluster_vip", "destination_address"])
+            .or_else(|| asset.raw_data["ip_addresses"][0].as_str().map(str::to_string));
+        let serial_number = first_string(&asset.raw_data, &["serial_number"]);
+        let model = first_string(&asset.raw_data, &["block_model", "model"]);
+        let version = first_string(&asset.raw_data, &["version", "software_version", "hypervisor_full_name"]);
+        let vendor = vendor(&connection.provider_type);
+
+        let existing_ci = match previous.and_then(|p| p.ci.clone()) {
+            Some(ci) => CMDBService::get_ci(self.db.clone(), &ci.id.to_raw()).await?.and(Some(ci)),
+            None => None,
+        };
+
+        let ci = match existing_ci {
+            Some(ci) => {
+                let request = UpdateCIRequest {
+                    name: Some(asset.name.clone()),
+                    description: None,
+                    ci_type: Some(mapping.ci_type.to_string()),
+                    // Re-appearing objects come back to life
+                    status: previous.filter(|p| p.removed).map(|_| CIStatus::Active),
+                    criticality: None,
+                    environment: None,
+                    location: None,
+                    owner_id: None,
+                    support_group: None,
+                    vendor,
+                    model,
+                    serial_number,
+                    version,
+                    ip_address,
+                    fqdn: None,
+                    attributes: Some(attributes),
+                    tags: None,
+                    install_date: None,
+                    warranty_expiry: None,
+                    end_of_life: None,
+                    financials: None,
+                    change_reason: Some(format!("Discovered by {}", connection.name)),
+                };
+                CMDBService::update_ci(self.db.clone(), &ci.id.to_raw(), request, SYNC_USER, &connection.name).await?;
+                ci
+            }
+            None => {
+                let request = CreateCIRequest {
+                    ci_id: Some(format!("{}-{}", mapping.ci_prefix, asset.external_id)),
+                    name: asset.name.clone(),
+                    description: None,
+                    ci_class: mapping.ci_class,
+                    ci_type: mapping.ci_type.to_string(),
+                    status: CIStatus::Active,
+                    criticality: Default::default(),
+                    environment: None,
+                    location: None,
+                    owner_id: None,
+                    support_group: None,
+                    vendor,
+                    model,
+                    serial_number,
+                    version,
+                    ip_address,
+                    fqdn: None,
+                    attributes,
+                    tags: vec!["discovered".to_string()],
+                    install_date: None,
+                    warranty_expiry: None,
+                    end_of_life: None,
+                    financials: None,
+                };
+                let tenant = connection.tenant_id.as_ref().map(|t| t.id.to_raw());
+                let created =
+                    CMDBService::create_ci(self.db.clone(), request, SYNC_USER, &connection.name, tenant.as_deref())
+                        .await?;
+                created.id.ok_or("CI was created without an id")?
+            }
+        };
+
+        self.db
+            .query("UPDATE $ci SET discovery_source = 'DISCOVERY', discovery_id = $external_id, last_discovered = $now")
+            .bind(("ci", ci.clone()))
+            .bind(("external_id", asset.external_id.clone()))
+            .bind(("now", now))
+            .traced("sync.write_asset")
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let environment_record = match mapping.environment_table {
+            Some(table) => Some(
+                self.write_environment_record(&connection.provider_type, table, connection_id, asset, &ci, now)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let record = IntegrationAssetRecord {
+            id: Some(Thing::from(("integration_assets", record_key(connection_id, &asset.external_id).as_str()))),
+            connection_id: connection_id.clone(),
+            external_id: asset.external_id.clone(),
+            asset_type: asset.asset_type.clone(),
+            name: asset.name.clone(),
+            fingerprint: print.to_string(),
+            ci: Some(ci),
+            environment_record,
+            removed: false,
+            first_seen: previous.map(|p| p.first_seen).unwrap_or(now),
+            last_seen: now,
+        };
+        let thing = record.id.clone().ok_or("missing ledger id")?;
+        let _: Option<IntegrationAssetRecord> = self
+            .db
+            .update(thing)
+            .content(&record)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(record)
+    }
+
+    /// Upsert the provider-specific environment record (keyed by external id)
+    async fn write_environment_record(
+        &self,
+        provider: &ProviderType,
+        table: &str,
+        connection_id: &Thing,
+        asset: &DiscoveredAsset,
+        ci: &Thing,
+        now: DateTime<Utc>,
+    ) -> Result<Thing, String> {
+        let mut content: Map<String, Value> = match &asset.raw_data {
+            Value::Object(map) => map.clone(),
+            _ => Map::new(),
+        };
+        content.insert("uuid".to_string(), json!(asset.external_id));
+        content.insert("external_id".to_string(), json!(asset.external_id));
+        content.insert("name".to_string(), json!(asset.name));
+        content.insert("status".to_string(), json!("active"));
+
+        // Parent references become record links, e.g. nutanix_vm.cluster
+        let parents: Vec<(&str, Thing)> = asset
+            .links
+            .iter()
+            .filter(|l| l.resolve_by == LinkResolution::ExternalId)
+            .filter_map(|l| {
+                parent_field(provider, &l.relationship)
+                    .map(|(field, parent_table)| (field, Thing::from((parent_table, l.target_external_id.as_str()))))
+            })
+            .collect();
+        let mut set = "integration_connection = $connection, ci = $ci, last_sync = $now, updated_at = $now".to_string();
+        for (i, (field, _)) in parents.iter().enumerate() {
+            set.push_str(&format!(", {} = $parent{}", field, i));
+        }
+
+        let thing = Thing::from((table, asset.external_id.as_str()));
+        let mut query = self
+            .db
+            .query("UPDATE $thing MERGE $content")
+            .query(format!("UPDATE $thing SET {}", set))
+            .bind(("thing", thing.clone()))
+            .bind(("content", Value::Object(content)))
+            .bind(("connection", connection_id.clone()))
+            .bind(("ci", ci.clone()))
+            .bind(("now", now));
+        for (i, (_, parent)) in parents.into_iter().enumerate() {
+            query = query.bind((format!("parent{}", i), parent));
+        }
+        query.await.map_err(|e| e.to_string())?;
+        Ok(thing)
+    }
+
+    /// Make the asset's relationships match its links. CONTAINED_BY links are
+    /// stored the CMDB way round (cluster CONTAINS host).
+    async fn write_relationships(
+        &self,
+        connection_id: &Thing,
+        tenant: Option<&Thing>,
+        asset: &DiscoveredAsset,
+        ledger: &HashMap<String, IntegrationAssetRecord>,
+        now: DateTime<Utc>,
+    ) -> Result<u32, String> {
+        let Some(own_ci) = ledger.get(&asset.external_id).and_then(|r| r.ci.clone()) else {
+            return Ok(0);
+        };
+
+        let mut keep: Vec<Thing> = Vec::new();
+        for asset_link in &asset.links {
+            let target_ci = match asset_link.resolve_by {
+                LinkResolution::ExternalId => ledger
+                    .get(&asset_link.target_external_id)
+                    .filter(|r| !r.removed)
+                    .and_then(|r| r.ci.clone()),
+                by => self.resolve_workload(by, &asset_link.target_external_id, tenant).await?,
+            };
+            let Some(target_ci) = target_ci else {
+                continue;
+            };
+            let (source, target, relationship_type) = match asset_link.relationship.as_str() {
+                "CONTAINED_BY" => (target_ci, own_ci.clone(), "CONTAINS"),
+                other => (own_ci.clone(), target_ci, other),
+            };
+
+            let thing = self
+                .upsert_relationship(connection_id, &asset.external_id, source, target, relationship_type, now)
+                .await?;
+            keep.push(thing);
+        }
+
+        // Links the asset no longer has
+        self.deactivate_relationships_except(connection_id, &asset.external_id, &keep, now)
+            .await?;
+
+        Ok(keep.len() as u32)
+    }
+
+    /// The VM or host CI in the tenant with the given IP address or name.
+    /// VMs win over hosts so a pool member lands on the guest, not the
+    /// hypervisor.
+    async fn resolve_workload(
+        &self,
+        resolve_by: LinkResolution,
+        value: &str,
+        tenant: Option<&Thing>,
+    ) -> Result<Option<Thing>, String> {
+        let matcher = match resolve_by {
+            LinkResolution::IpAddress => "(ip_address = $value OR attributes.ip_addresses CONTAINS $value)",
+            LinkResolution::Name => "(string::lowercase(name) = $value OR string::lowercase(fqdn ?? '') = $value)",
+            LinkResolution::ExternalId => return Ok(None),
+        };
+        let value = match resolve_by {
+            LinkResolution::Name => value.to_lowercase(),
+            _ => value.to_string(),
+        };
+        let matches: Vec<Value> = self
+            .db
+            .query(format!(
+                "SELECT id, ci_class FROM configuration_items WHERE {} \
+                 AND ci_class INSIDE ['VIRTUAL', 'HARDWARE'] AND status != 'RETIRED' AND tenant_id = $tenant",
+                matcher
+            ))
+            .bind(("value", value))
+            .bind(("tenant", tenant.cloned()))
+            .traced("sync.resolve_workload")
+            .await
+            .and_then(|mut r| r.take(0))
+            .map_err(|e| e.to_string())?;
+        let preferred = matches
+            .iter()
+            .find(|m| m["ci_class"] == "VIRTUAL")
+            .or_else(|| matches.first());
+        Ok(preferred.and_then(|m| record_id(&m["id"])))
+    }
+
+    /// Upsert a sync-maintained relationship under a deterministic id
+    async fn upsert_relationship(
+        &self,
+        connection_id: &Thing,
+        asset_key: &str,
+        source: Thing,
+        target: Thing,
+        relationship_type: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Thing, String> {
+        let key = format!(
+            "{}_{}_{}",
+            source.id.to_raw(),
+            relationship_type.to_lowercase(),
+            target.id.to_raw()
+        );
+        let thing = Thing::from(("ci_relationships", key.as_str()));
+        self.db
+            .query(
+                "UPDATE $thing MERGE { source_id: $source, target_id: $target, relationship_type: $type, \
+                 direction: 'OUTBOUND', is_active: true, discovery_source: 'DISCOVERY', \
+                 integration_connection: $connection, integration_asset: $asset, \
+                 created_by: $user, updated_at: $now }",
+            )
+            .bind(("thing", thing.clone()))
+            .bind(("source", source))
+            .bind(("target", target))
+            .bind(("type", relationship_type.to_string()))
+            .bind(("connection", connection_id.clone()))
+            .bind(("asset", asset_key.to_string()))
+            .bind(("user", SYNC_USER))
+            .bind(("now", now))
+            .traced("sync.upsert_relationship")
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(thing)
+    }
+
+    async fn deactivate_relationships_except(
+        &self,
+        connection_id: &Thing,
+        asset_key: &str,
+        keep: &[Thing],
+        now: DateTime<Utc>,
+    ) -> Result<(), String> {
+        self.db
+            .query(
+                "UPDATE ci_relationships SET is_active = false, updated_at = $now \
+                 WHERE integration_connection = $connection AND integration_asset = $asset \
+                 AND is_active = true AND id NOTINSIDE $keep",
+            )
+            .bind(("now", now))
+            .bind(("connection", connection_id.clone()))
+            .bind(("asset", asset_key.to_string()))
+            .bind(("keep", keep.to_vec()))
+            .traced("sync.deactivate_relationships_except")
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Link switch VLANs to virtual subnets (any connection, same tenant)
+    /// carrying the same VLAN id, so topology shows the physical network
+    /// next to the virtual one
+    async fn correlate_vlans(
+        &self,
+        connection: &IntegrationConnection,
+        connection_id: &Thing,
+        ledger: &HashMap<String, IntegrationAssetRecord>,
+        now: DateTime<Utc>,
+    ) -> Result<u32, String> {
+        let vlan_cis: Vec<Thing> = ledger
+            .values()
+            .filter(|r| r.asset_type == KIND_VLAN && !r.removed)
+            .filter_map(|r| r.ci.clone())
+            .collect();
+
+        let mut keep: Vec<Thing> = Vec::new();
+        if !vlan_cis.is_empty() {
+            let vlans: Vec<Value> = self
+                .db
+                .query("SELECT id, attributes.vlan_id AS vlan_id FROM configuration_items WHERE id INSIDE $cis")
+                .bind(("cis", vlan_cis))
+                .traced("sync.correlate_vlans")
+                .await
+                .and_then(|mut r| r.take(0))
+                .map_err(|e| e.to_string())?;
+            let subnets: Vec<Value> = self
+                .db
+                .query(
+                    "SELECT id, attributes.vlan_id AS vlan_id FROM configuration_items \
+                     WHERE ci_type = 'Subnet' AND attributes.vlan_id != NONE AND status != 'RETIRED' \
+                     AND tenant_id = $tenant",
+                )
+                .bind(("tenant", connection.tenant_id.clone()))
+                .traced("sync.correlate_vlans")
+                .await
+                .and_then(|mut r| r.take(0))
+                .map_err(|e| e.to_string())?;
+
+            let mut by_vlan: HashMap<u64, Vec<Thing>> = HashMap::new();
+            for subnet in &subnets {
+                if let (Some(vlan_id), Some(id)) = (subnet["vlan_id"].as_u64(), record_id(&subnet["id"])) {
+                    by_vlan.entry(vlan_id).or_default().push(id);
+                }
+            }
+            for vlan in &vlans {
+                let (Some(vlan_id), Some(vlan_ci)) = (vlan["vlan_id"].as_u64(), record_id(&vlan["id"])) else {
+                    continue;
+                };
+                for subnet_ci in by_vlan.get(&vlan_id).into_iter().flatten() {
+                    let thing = self
+                        .upsert_relationship(connection_id, VLAN_CORRELATION_KEY, vlan_ci.clone(), subnet_ci.clone(), "CONNECTS_TO", now)
+                        .await?;
+                    keep.push(thing);
+                }
+            }
+        }
+
+        self.deactivate_relationships_except(connection_id, VLAN_CORRELATION_KEY, &keep, now)
+            .await?;
+        Ok(keep.len() as u32)
+    }
+
+    async fn retire_asset(
+        &self,
+        connection_id: &Thing,
+        record: &IntegrationAssetRecord,
+        now: DateTime<Utc>,
+    ) -> Result<(), String> {
+        if let Some(ci) = &record.ci {
+            let exists = CMDBService::get_ci(self.db.clone(), &ci.id.to_raw()).await?.is_some();
+            if exists {
+                let request = UpdateCIRequest {
+                    name: None,
+                    description: None,
+                    ci_type: None,
+                    status: Some(CIStatus::Retired),
+                    criticality: None,
+                    environment: None,
+                    location: None,
+                    owner_id: None,
+                    support_group: None,
+                    vendor: None,
+                    model: None,
+                    serial_number: None,
+                    version: None,
+                    ip_address: None,
+                    fqdn: None,
+                    attributes: None,
+                    tags: None,
+                    install_date: None,
+                    warranty_expiry: None,
+                    end_of_life: None,
+                    financials: None,
+                    change_reason: Some("No longer reported by the integration source".to_string()),
+                };
+                CMDBService::update_ci(self.db.clone(), &ci.id.to_raw(), request, SYNC_USER, SYNC_USER).await?;
+            }
+            self.db
+                .query(
+                    "UPDATE ci_relationships SET is_active = false, updated_at = $now \
+                     WHERE integration_connection = $connection AND is_active = true \
+                     AND (integration_asset = $asset OR source_id = $ci OR target_id = $ci)",
+                )
+                .bind(("now", now))
+                .bind(("connection", connection_id.clone()))
+                .bind(("asset", record.external_id.clone()))
+                .bind(("ci", ci.clone()))
+                .traced("sync.retire_asset")
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        if let Some(environment_record) = &record.environment_record {
+            self.db
+                .query("UPDATE $thing SET status = 'removed', updated_at = $now")
+                .bind(("thing", environment_record.clone()))
+                .bind(("now", now))
+                .traced("sync.retire_asset")
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        if let Some(id) = &record.id {
+            self.db
+                .query("UPDATE $id SET removed = true")
+                .bind(("id", id.clone()))
+                .traced("sync.retire_asset")
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    async fn save_run(&self, run: &IntegrationSyncRun) -> Result<(), IntegrationError> {
+        if let Some(id) = run.id.clone() {
+            let _: Option<IntegrationSyncRun> = self.db.update(id).content(run).await?;
+        }
+        Ok(())
+    }
+
+    async fn record_outcome(
+        &self,
+        connection: &IntegrationConnection,
+        run: &IntegrationSyncRun,
+    ) -> Result<(), IntegrationError> {
+        let Some(id) = connection.id.clone() else {
+            return Ok(());
+        };
+        let finished = run.finished_at.unwrap_or_else(Utc::now);
+        let full_synced = run.mode == SyncMode::Full && run.status != SyncRunStatus::Failed;
+        self.db
+            .query(
+                "UPDATE $id SET last_sync_at = $finished, last_sync_status = $status, last_error = $error, \
+                 last_full_sync_at = IF $full THEN $finished ELSE last_full_sync_at END",
+            )
+            .bind(("id", id))
+            .bind(("finished", finished))
+            .bind(("status", run.status))
+            .bind(("error", run.errors.first().cloned()))
+            .bind(("full", full_synced))
+            .traced("sync.record_outcome")
+            .await?;
+        Ok(())
+    }
+
+    /// Start scheduled syncs for enabled connections whose interval has
+    /// elapsed. A full sync replaces the delta once the full interval is up.
+    pub async fn run_due(&self) -> anyhow::Result<usize> {
+        let connections: Vec<IntegrationConnection> = self
+            .db
+            .query("SELECT * FROM integration_connections WHERE enabled = true AND sync_interval_minutes != NONE")
+            .traced("sync.run_due")
+            .await?
+            .take(0)?;
+
+        let now = Utc::now();
+        let mut started = 0;
+        for connection in connections {
+            let Some(minutes) = connection.sync_interval_minutes else {
+                continue;
+            };
+            let due = connection
+                .last_sync_at
+                .is_none_or(|at| at + Duration::minutes(minutes as i64) <= now);
+            if !due {
+                continue;
+            }
+
+            let full_hours = connection.full_sync_interval_hours.unwrap_or(DEFAULT_FULL_SYNC_HOURS);
+            let mode = match connection.last_full_sync_at {
+                Some(at) if at + Duration::hours(full_hours as i64) > now => SyncMode::Delta,
+                _ => SyncMode::Full,
+            };
+
+            match self.start_run(&connection, mode, "scheduler").await {
+                Ok(run) => {
+                    let run = self.execute_run(connection.clone(), run).await;
+                    if run.status == SyncRunStatus::Failed {
+                        warn!("⚠️ Scheduled sync of '{}' failed: {:?}", connection.name, run.errors.first());
+                    }
+                    started += 1;
+                }
+                Err(IntegrationError::Conflict(_)) => {}
+                Err(e) => warn!("⚠️ Could not start sync of '{}': {}", connection.name, e),
+            }
+        }
+        Ok(started)
+    }
+}
+
+/// Record ids come back from untyped SELECTs as "table:key" strings
+fn record_id(value: &Value) -> Option<Thing> {
+    let (tb, key) = value.as_str()?.split_once(':')?;
+    Some(Thing::from((tb, key.trim_start_matches('⟨').trim_end_matches('⟩'))))
+}
+
+fn first_string(raw: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|k| raw[*k].as_str().filter(|s| !s.is_empty()))
+        .map(str::to_string)
+}
+
+// ============================================================================
+// SCHEDULER
+// ============================================================================
+
+use tokio_cron_scheduler::{Job, JobScheduler};
+
+use crate::database::locks;
+
+/// Lease on a sync pass; renewed while long inventory pulls run
+const SYNC_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(120);
+
+pub struct IntegrationSyncScheduler {
+    db: Arc<Database>,
+}
+
+impl IntegrationSyncScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow!("Failed to create scheduler: {}", e))?;
+
+        let db = Arc::clone(&self.db);
+        let service = Arc::new(InventorySyncService::new(Arc::clone(&self.db)));
+        let job = Job::new_async("0 * * * * *", move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                let run = locks::run_exclusive(&db, locks::INTEGRATION_SYNC_LOCK, SYNC_LOCK_TTL, service.run_due());
+                match run.await {
+                    Ok(None) | Ok(Some(Ok(0))) => {}
+                    Ok(Some(Ok(count))) => info!("🔄 Ran {} scheduled integration sync(s)", count),
+                    Ok(Some(Err(e))) => error!("❌ Scheduled integration sync failed: {}", e),
+                    Err(e) => error!("❌ Integration sync lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow!("Failed to create integration sync job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow!("Failed to add integration sync job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow!("Failed to start scheduler: {}", e))?;
+
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::connector::AssetLink;
+
+    fn asset(raw: Value, links: Vec<AssetLink>) -> DiscoveredAsset {
+        DiscoveredAsset {
+            external_id: "vm-1".to_string(),
+            name: "app-01".to_string(),
+            asset_type: KIND_VM.to_string(),
+            raw_data: raw,
+            links,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_payload_and_links() {
+        let base = asset(json!({ "vcpu_count": 4 }), vec![]);
+        assert_eq!(fingerprint(&base), fingerprint(&base.clone()));
+
+        let resized = asset(json!({ "vcpu_count": 8 }), vec![]);
+        assert_ne!(fingerprint(&base), fingerprint(&resized));
+
+        let moved = asset(
+            json!({ "vcpu_count": 4 }),
+            vec![AssetLink {
+                relationship: "RUNS_ON".to_string(),
+                target_external_id: "h-2".to_string(),
+                resolve_by: LinkResolution::ExternalId,
+            }],
+        );
+        assert_ne!(fingerprint(&base), fingerprint(&moved));
+    }
+
+    #[test]
+    fn test_kind_mapping_environment_tables() {
+        let vm = kind_mapping(&ProviderType::NutanixPrism, KIND_VM);
+        assert_eq!(vm.ci_class, CIClass::Virtual);
+        assert_eq!(vm.environment_table, Some("nutanix_vm"));
+        assert_eq!(kind_mapping(&ProviderType::NutanixPrism, KIND_SUBNET).environment_table, Some("nutanix_network"));
+        assert_eq!(
+            kind_mapping(&ProviderType::CiscoCatalystCenter, KIND_INTERFACE).environment_table,
+            Some("cisco_switch_interface")
+        );
+        assert_eq!(parent_field(&ProviderType::CiscoCatalystCenter, "CONTAINED_BY"), Some(("switch", "cisco_switch")));
+        assert_eq!(
+            kind_mapping(&ProviderType::F5BigIp, KIND_POOL_MEMBER).environment_table,
+            Some("f5_pool_member")
+        );
+        assert_eq!(parent_field(&ProviderType::F5BigIp, "MEMBER_OF"), Some(("pool", "f5_pool")));
+        assert!(kind_mapping(&ProviderType::GenericRest, KIND_VM).environment_table.is_none());
+    }
+}
@@ -0,0 +1,649 @@
+// Archer ITSM - Knowledge Base Attachment Service
+// File and inline image storage for KB articles, with disk or S3-compatible backends
+
+use crate::models::knowledge::*;
+use async_trait::async_trait;
+use chrono::Utc;
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat};
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use crate::database::Db;
+use crate::services::document_storage::s3_bucket_from_env;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+use crate::database::TracedQuery;
+
+/// Renditions generated for uploaded images: (label, longest edge in pixels)
+pub const IMAGE_VARIANTS: &[(&str, u32)] = &[("thumb", 320), ("medium", 800), ("large", 1600)];
+
+/// Images above this pixel count are stored but not decoded for resizing
+const MAX_DECODE_PIXELS: u64 = 40_000_000;
+
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_PER_ARTICLE: u32 = 50;
+
+// ============================================================================
+// STORAGE BACKENDS
+// ============================================================================
+
+#[async_trait]
+pub trait AttachmentStore: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), String>;
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Stores attachments under a local directory (`KB_ATTACHMENT_DIR`)
+pub struct DiskAttachmentStore {
+    root: PathBuf,
+}
+
+impl DiskAttachmentStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, String> {
+        let relative = Path::new(key);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err(format!("Invalid storage key '{}'", key));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl AttachmentStore for DiskAttachmentStore {
+    async fn put(&self, key: &str, bytes: &[u8], _content_type: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let path = self.path_for(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        // Prune the now-empty per-attachment directory; ignore failures
+        if let Some(parent) = path.parent() {
+            let _ = tokio::fs::remove_dir(parent).await;
+        }
+        Ok(())
+    }
+}
+
+/// Stores attachments in an S3-compatible bucket (AWS, MinIO, Ceph RGW, ...)
+pub struct S3AttachmentStore {
+    bucket: Box<s3::Bucket>,
+}
+
+impl S3AttachmentStore {
+    /// Configured from `KB_ATTACHMENT_S3_*` environment variables
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Self {
+            bucket: s3_bucket_from_env("KB_ATTACHMENT_S3")?,
+        })
+    }
+}
+
+#[async_trait]
+impl AttachmentStore for S3AttachmentStore {
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), String> {
+        let response = self
+            .bucket
+            .put_object_with_content_type(key, bytes, content_type)
+            .await
+            .map_err(|e| e.to_string())?;
+        match response.status_code() {
+            200..=299 => Ok(()),
+            code => Err(format!("S3 PUT {} returned {}", key, code)),
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        match self.bucket.get_object(key).await {
+            Ok(response) if response.status_code() == 404 => Ok(None),
+            Ok(response) if (200..300).contains(&response.status_code()) => {
+                Ok(Some(response.bytes().to_vec()))
+            }
+            Ok(response) => Err(format!("S3 GET {} returned {}", key, response.status_code())),
+            Err(s3::error::S3Error::HttpFailWithBody(404, _)) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self
+            .bucket
+            .delete_object(key)
+            .await
+            .map_err(|e| e.to_string())?;
+        match response.status_code() {
+            200..=299 | 404 => Ok(()),
+            code => Err(format!("S3 DELETE {} returned {}", key, code)),
+        }
+    }
+}
+
+/// Select the backend from `KB_ATTACHMENT_BACKEND` (`disk` or `s3`)
+pub fn store_from_env() -> Result<Arc<dyn AttachmentStore>, String> {
+    match std::env::var("KB_ATTACHMENT_BACKEND").as_deref() {
+        Ok("s3") => Ok(Arc::new(S3AttachmentStore::from_env()?)),
+        Ok("disk") | Err(_) => {
+            let root = std::env::var("KB_ATTACHMENT_DIR")
+                .unwrap_or_else(|_| "./data/kb_attachments".to_string());
+            Ok(Arc::new(DiskAttachmentStore::new(root)))
+        }
+        Ok(other) => Err(format!("Unknown KB_ATTACHMENT_BACKEND '{}'", other)),
+    }
+}
+
+// ============================================================================
+// LIMITS AND CONTENT TYPES
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+pub struct AttachmentLimits {
+    pub max_bytes: u64,
+    pub max_per_article: u32,
+}
+
+impl AttachmentLimits {
+    pub fn from_env() -> Self {
+        let parse = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            max_bytes: parse("KB_ATTACHMENT_MAX_BYTES").unwrap_or(DEFAULT_MAX_BYTES),
+            max_per_article: parse("KB_ATTACHMENT_MAX_PER_ARTICLE")
+                .map(|v| v as u32)
+                .unwrap_or(DEFAULT_MAX_PER_ARTICLE),
+        }
+    }
+}
+
+/// Work out the content type from the file's magic bytes (and, for text and
+/// OOXML files, its extension). Anything not on the allowlist returns `None`;
+/// SVG and HTML are deliberately excluded since they can carry script.
+pub fn detect_content_type(filename: &str, bytes: &[u8]) -> Option<&'static str> {
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("image/png");
+    }
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if bytes.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+    if bytes.starts_with(b"PK\x03\x04") {
+        return Some(match extension.as_str() {
+            "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            _ => "application/zip",
+        });
+    }
+
+    let text_type = match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        _ => return None,
+    };
+    if !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok() {
+        Some(text_type)
+    } else {
+        None
+    }
+}
+
+/// Strip directory components and control characters from a client filename
+pub fn sanitize_filename(filename: &str) -> String {
+    let base = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .collect::<String>();
+    let trimmed = base.trim().trim_start_matches('.');
+    if trimmed.is_empty() {
+        "attachment".to_string()
+    } else {
+        trimmed.chars().take(200).collect()
+    }
+}
+
+/// Dimensions that fit within `max_edge` on the longest side, keeping the
+/// aspect ratio. Returns `None` when the image is already small enough.
+pub fn scaled_dimensions(width: u32, height: u32, max_edge: u32) -> Option<(u32, u32)> {
+    let longest = width.max(height);
+    if longest <= max_edge || width == 0 || height == 0 {
+        return None;
+    }
+    let scale = max_edge as f64 / longest as f64;
+    let w = ((width as f64 * scale).round() as u32).max(1);
+    let h = ((height as f64 * scale).round() as u32).max(1);
+    Some((w, h))
+}
+
+struct RenderedVariant {
+    label: &'static str,
+    width: u32,
+    height: u32,
+    content_type: &'static str,
+    bytes: Vec<u8>,
+}
+
+/// Decode an image and produce the downscaled renditions. Returns the
+/// original dimensions and any variants smaller than the original.
+fn render_variants(bytes: &[u8]) -> Result<((u32, u32), Vec<RenderedVariant>), String> {
+    let reader = image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+    let (width, height) = reader.into_dimensions().map_err(|e| e.to_string())?;
+    if width as u64 * height as u64 > MAX_DECODE_PIXELS {
+        return Ok(((width, height), Vec::new()));
+    }
+
+    let decoded: DynamicImage = image::load_from_memory(bytes).map_err(|e| e.to_string())?;
+    let source_format = image::guess_format(bytes).ok();
+
+    let mut variants = Vec::new();
+    for (label, max_edge) in IMAGE_VARIANTS {
+        let Some((w, h)) = scaled_dimensions(width, height, *max_edge) else {
+            continue;
+        };
+        let resized = decoded.resize(w, h, FilterType::Lanczos3);
+        // JPEG stays JPEG; everything else (incl. animated GIF first frame) becomes PNG
+        let (format, content_type) = if source_format == Some(ImageFormat::Jpeg) {
+            (ImageFormat::Jpeg, "image/jpeg")
+        } else {
+            (ImageFormat::Png, "image/png")
+        };
+        let resized = if format == ImageFormat::Jpeg {
+            DynamicImage::ImageRgb8(resized.to_rgb8())
+        } else {
+            resized
+        };
+        let mut out = Cursor::new(Vec::new());
+        resized.write_to(&mut out, format).map_err(|e| e.to_string())?;
+        let (vw, vh) = resized.dimensions();
+        variants.push(RenderedVariant {
+            label,
+            width: vw,
+            height: vh,
+            content_type,
+            bytes: out.into_inner(),
+        });
+    }
+    Ok(((width, height), variants))
+}
+
+// ============================================================================
+// SERVICE
+// ============================================================================
+
+pub struct KbAttachmentService {
+    db: Arc<Surreal<Db>>,
+    store: Arc<dyn AttachmentStore>,
+    limits: AttachmentLimits,
+}
+
+impl KbAttachmentService {
+    pub fn new(db: Arc<Surreal<Db>>) -> Result<Self, String> {
+        Ok(Self {
+            db,
+            store: store_from_env()?,
+            limits: AttachmentLimits::from_env(),
+        })
+    }
+
+    pub fn with_store(
+        db: Arc<Surreal<Db>>,
+        store: Arc<dyn AttachmentStore>,
+        limits: AttachmentLimits,
+    ) -> Self {
+        Self { db, store, limits }
+    }
+
+    pub fn limits(&self) -> AttachmentLimits {
+        self.limits
+    }
+
+    /// Validate, store and register an upload against an article
+    pub async fn upload(
+        &self,
+        article_id: &str,
+        filename: &str,
+        bytes: Vec<u8>,
+        uploaded_by: &str,
+    ) -> Result<KBAttachmentUploadResponse, String> {
+        if bytes.is_empty() {
+            return Err("Uploaded file is empty".to_string());
+        }
+        if bytes.len() as u64 > self.limits.max_bytes {
+            return Err(format!(
+                "Attachment exceeds the {} byte limit",
+                self.limits.max_bytes
+            ));
+        }
+
+        let filename = sanitize_filename(filename);
+        let content_type = detect_content_type(&filename, &bytes)
+            .ok_or_else(|| format!("File type of '{}' is not allowed", filename))?;
+
+        let article: Option<KBArticle> = self
+            .db
+            .select(("kb_articles", article_id))
+            .await
+            .map_err(|e| e.to_string())?;
+        let article = article.ok_or_else(|| "Article not found".to_string())?;
+        let article_thing = Thing::from(("kb_articles", article_id));
+
+        let existing = self.list_for_article(article_id).await?;
+        if existing.len() as u32 >= self.limits.max_per_article {
+            return Err(format!(
+                "Article already has the maximum of {} attachments",
+                self.limits.max_per_article
+            ));
+        }
+
+        let attachment_key = uuid::Uuid::new_v4().simple().to_string();
+        let prefix = format!("kb/{}/{}", article_id, attachment_key);
+        let is_image = content_type.starts_with("image/");
+
+        // Decode off the async runtime; a bad image is still stored as a plain file
+        let (dimensions, rendered) = if is_image {
+            let source = bytes.clone();
+            match tokio::task::spawn_blocking(move || render_variants(&source)).await {
+                Ok(Ok((dims, variants))) => (Some(dims), variants),
+                Ok(Err(e)) => {
+                    tracing::warn!("Could not resize KB image '{}': {}", filename, e);
+                    (None, Vec::new())
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        } else {
+            (None, Vec::new())
+        };
+
+        let storage_key = format!("{}/original", prefix);
+        let mut written = vec![storage_key.clone()];
+        self.store.put(&storage_key, &bytes, content_type).await?;
+
+        let mut variants = Vec::with_capacity(rendered.len());
+        for variant in rendered {
+            let key = format!("{}/{}", prefix, variant.label);
+            if let Err(e) = self.store.put(&key, &variant.bytes, variant.content_type).await {
+                self.delete_blobs(&written).await;
+                return Err(e);
+            }
+            written.push(key.clone());
+            variants.push(KBImageVariant {
+                label: variant.label.to_string(),
+                width: variant.width,
+                height: variant.height,
+                content_type: variant.content_type.to_string(),
+                storage_key: key,
+                size_bytes: variant.bytes.len() as u64,
+            });
+        }
+
+        let record = KBAttachment {
+            id: None,
+            article_id: article_thing.clone(),
+            filename: filename.clone(),
+            content_type: content_type.to_string(),
+            size_bytes: bytes.len() as u64,
+            storage_key,
+            checksum: format!("{:x}", md5::compute(&bytes)),
+            is_image,
+            width: dimensions.map(|d| d.0),
+            height: dimensions.map(|d| d.1),
+            variants,
+            uploaded_by: uploaded_by.to_string(),
+            uploaded_at: Utc::now(),
+            tenant_id: article.tenant_id,
+        };
+
+        let created: Vec<KBAttachment> = match self.db.create("kb_attachments").content(&record).await {
+            Ok(created) => created,
+            Err(e) => {
+                self.delete_blobs(&written).await;
+                return Err(e.to_string());
+            }
+        };
+        let attachment = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Failed to create attachment".to_string())?;
+
+        if let Some(id) = attachment.id.clone() {
+            self.db
+                .query("UPDATE $article SET attachments += $attachment")
+                .bind(("article", article_thing))
+                .bind(("attachment", id))
+                .traced("kb_attachment_service.upload")
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let markdown = markdown_reference(&attachment);
+        Ok(KBAttachmentUploadResponse { attachment, markdown })
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<KBAttachment>, String> {
+        self.db
+            .select(("kb_attachments", id))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn list_for_article(&self, article_id: &str) -> Result<Vec<KBAttachment>, String> {
+        let mut response = self
+            .db
+            .query("SELECT * FROM kb_attachments WHERE article_id = $article ORDER BY uploaded_at ASC")
+            .bind(("article", Thing::from(("kb_articles", article_id))))
+            .traced("kb_attachment_service.list_for_article")
+            .await
+            .map_err(|e| e.to_string())?;
+        response.take(0).map_err(|e| e.to_string())
+    }
+
+    /// Load the bytes for an attachment, optionally a resized variant.
+    /// Unknown or missing variants fall back to the original.
+    pub async fn read_content(
+        &self,
+        attachment: &KBAttachment,
+        size: Option<&str>,
+    ) -> Result<Option<(Vec<u8>, String)>, String> {
+        let (key, content_type) = match size
+            .and_then(|label| attachment.variants.iter().find(|v| v.label == label))
+        {
+            Some(variant) => (&variant.storage_key, &variant.content_type),
+            None => (&attachment.storage_key, &attachment.content_type),
+        };
+        Ok(self
+            .store
+            .get(key)
+            .await?
+            .map(|bytes| (bytes, content_type.clone())))
+    }
+
+    /// Delete one attachment, its blobs and the article back-reference
+    pub async fn delete(&self, id: &str) -> Result<(), String> {
+        let attachment = self
+            .get(id)
+            .await?
+            .ok_or_else(|| "Attachment not found".to_string())?;
+        self.remove(&attachment).await
+    }
+
+    /// Remove every attachment of an article (used when the article is deleted)
+    pub async fn delete_for_article(&self, article_id: &str) -> Result<u32, String> {
+        let attachments = self.list_for_article(article_id).await?;
+        let mut removed = 0;
+        for attachment in &attachments {
+            self.remove(attachment).await?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Remove attachments whose article no longer exists, e.g. left behind by
+    /// an article deleted before attachments were tracked or a failed delete
+    pub async fn cleanup_orphans(&self) -> Result<KBAttachmentCleanupReport, String> {
+        let mut response = self
+            .db
+            .query("SELECT * FROM kb_attachments WHERE article_id.id IS NONE")
+            .traced("kb_attachment_service.cleanup_orphans")
+            .await
+            .map_err(|e| e.to_string())?;
+        let orphans: Vec<KBAttachment> = response.take(0).map_err(|e| e.to_string())?;
+
+        let mut report = KBAttachmentCleanupReport {
+            attachments_removed: 0,
+            blobs_removed: 0,
+            errors: Vec::new(),
+        };
+        for attachment in &orphans {
+            let keys = blob_keys(attachment);
+            match self.remove(attachment).await {
+                Ok(()) => {
+                    report.attachments_removed += 1;
+                    report.blobs_removed += keys.len() as u32;
+                }
+                Err(e) => report.errors.push(format!("{}: {}", attachment.filename, e)),
+            }
+        }
+        Ok(report)
+    }
+
+    async fn remove(&self, attachment: &KBAttachment) -> Result<(), String> {
+        for key in blob_keys(attachment) {
+            self.store.delete(&key).await?;
+        }
+        // Filter by id rather than UPDATE $article so an already deleted
+        // article isn't recreated as an empty record
+        if let Some(id) = &attachment.id {
+            self.db
+                .query("DELETE $attachment; UPDATE kb_articles SET attachments -= $attachment WHERE id = $article")
+                .bind(("attachment", id.clone()))
+                .bind(("article", attachment.article_id.clone()))
+                .traced("kb_attachment_service.remove")
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Best-effort rollback of blobs written during a failed upload
+    async fn delete_blobs(&self, keys: &[String]) {
+        for key in keys {
+            if let Err(e) = self.store.delete(key).await {
+                tracing::warn!("Failed to roll back KB attachment blob {}: {}", key, e);
+            }
+        }
+    }
+}
+
+fn blob_keys(attachment: &KBAttachment) -> Vec<String> {
+    std::iter::once(attachment.storage_key.clone())
+        .chain(attachment.variants.iter().map(|v| v.storage_key.clone()))
+        .collect()
+}
+
+/// Markdown snippet for embedding the attachment in article content. Images
+/// reference the medium rendition when one exists so large photos don't
+/// bloat the article.
+pub fn markdown_reference(attachment: &KBAttachment) -> String {
+    let id = attachment
+        .id
+        .as_ref()
+        .map(|t| t.id.to_raw())
+        .unwrap_or_default();
+    let url = format!("/api/v1/knowledge/attachments/{}/content", id);
+    if attachment.is_image {
+        let sized = if attachment.variants.iter().any(|v| v.label == "medium") {
+            format!("{}?size=medium", url)
+        } else {
+            url
+        };
+        format!("![{}]({})", attachment.filename.replace(['[', ']'], ""), sized)
+    } else {
+        format!("[{}]({})", attachment.filename.replace(['[', ']'], ""), url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_allowed_types_by_content() {
+        assert_eq!(detect_content_type("x.bin", b"\x89PNG\r\n\x1a\nrest"), Some("image/png"));
+        assert_eq!(detect_content_type("photo.png", &[0xFF, 0xD8, 0xFF, 0xE0]), Some("image/jpeg"));
+        assert_eq!(detect_content_type("doc.pdf", b"%PDF-1.7"), Some("application/pdf"));
+        assert_eq!(
+            detect_content_type("report.xlsx", b"PK\x03\x04...."),
+            Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        );
+        assert_eq!(detect_content_type("notes.md", b"# Title"), Some("text/markdown"));
+    }
+
+    #[test]
+    fn rejects_scriptable_and_unknown_types() {
+        assert_eq!(detect_content_type("logo.svg", b"<svg onload=alert(1)>"), None);
+        assert_eq!(detect_content_type("page.html", b"<html></html>"), None);
+        assert_eq!(detect_content_type("tool.exe", b"MZ\x90\x00"), None);
+        assert_eq!(detect_content_type("notes.txt", b"abc\x00def"), None);
+    }
+
+    #[test]
+    fn scales_to_longest_edge_and_skips_small_images() {
+        assert_eq!(scaled_dimensions(3200, 1600, 800), Some((800, 400)));
+        assert_eq!(scaled_dimensions(1000, 4000, 320), Some((80, 320)));
+        assert_eq!(scaled_dimensions(640, 480, 800), None);
+    }
+
+    #[test]
+    fn sanitizes_client_filenames() {
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename("C:\\Users\\me\\shot.png"), "shot.png");
+        assert_eq!(sanitize_filename(".."), "attachment");
+    }
+}
@@ -4,99 +4,457 @@
 use crate::models::knowledge::*;
 use std::collections::HashMap;
 use std::sync::Arc;
-use surrealdb::engine::local::Db;
+use crate::database::Db;
 use surrealdb::sql::Thing;
 use surrealdb::Surreal;
 use chrono::Utc;
+use crate::database::TracedQuery;
 
 pub struct KBSuggestionService;
 
+/// Recorded on suggestion logs so future ranking methods can be compared
+const SUGGESTION_METHOD: &str = "tfidf-v1";
+
+/// Articles below this cosine similarity are never suggested
+const MIN_SIMILARITY: f32 = 0.05;
+
+const SIMILARITY_WEIGHT: f32 = 0.75;
+const RESOLUTION_WEIGHT: f32 = 0.10;
+const HELPFULNESS_WEIGHT: f32 = 0.05;
+const ACCEPTANCE_WEIGHT: f32 = 0.10;
+
+#[derive(serde::Deserialize)]
+struct TicketText {
+    title: String,
+    description: Option<String>,
+}
+
+struct RankedArticle {
+    similarity: f32,
+    matched_terms: Vec<String>,
+    suggestion: ArticleSuggestion,
+}
+
+/// TF-IDF vectors for a corpus, L2-normalised so cosine similarity is a dot product
+struct TfIdfIndex {
+    idf: HashMap<String, f32>,
+    documents: Vec<HashMap<String, f32>>,
+}
+
+impl TfIdfIndex {
+    fn build(documents: &[String]) -> Self {
+        let tokenized: Vec<Vec<String>> = documents.iter().map(|d| KBSuggestionService::tokenize(d)).collect();
+
+        let mut document_frequency: HashMap<&str, u32> = HashMap::new();
+        for tokens in &tokenized {
+            let unique: std::collections::HashSet<&str> = tokens.iter().map(String::as_str).collect();
+            for term in unique {
+                *document_frequency.entry(term).or_default() += 1;
+            }
+        }
+
+        // Smoothed IDF: terms present in every document still carry a little weight
+        let n = documents.len() as f32;
+        let idf: HashMap<String, f32> = document_frequency
+            .into_iter()
+            .map(|(term, df)| (term.to_string(), ((n + 1.0) / (df as f32 + 1.0)).ln() + 1.0))
+            .collect();
+
+        let mut index = Self { idf, documents: Vec::new() };
+        index.documents = tokenized.iter().map(|tokens| index.weigh(tokens)).collect();
+        index
+    }
+
+    fn vectorize(&self, text: &str) -> HashMap<String, f32> {
+        self.weigh(&KBSuggestionService::tokenize(text))
+    }
+
+    /// Sublinear TF x IDF; terms unknown to the corpus are dropped
+    fn weigh(&self, tokens: &[String]) -> HashMap<String, f32> {
+        let mut counts: HashMap<&str, u32> = HashMap::new();
+        for token in tokens {
+            *counts.entry(token.as_str()).or_default() += 1;
+        }
+
+        let mut vector: HashMap<String, f32> = counts
+            .into_iter()
+            .filter_map(|(term, count)| {
+                let idf = self.idf.get(term)?;
+                Some((term.to_string(), (1.0 + (count as f32).ln()) * idf))
+            })
+            .collect();
+
+        let norm = vector.values().map(|w| w * w).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.values_mut().for_each(|w| *w /= norm);
+        }
+        vector
+    }
+
+    fn cosine(a: &HashMap<String, f32>, b: &HashMap<String, f32>) -> f32 {
+        let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        small
+            .iter()
+            .filter_map(|(term, w)| large.get(term).map(|v| w * v))
+            .sum()
+    }
+
+    fn top_terms(a: &HashMap<String, f32>, b: &HashMap<String, f32>, n: usize) -> Vec<String> {
+        let mut shared: Vec<(&String, f32)> = a
+            .iter()
+            .filter_map(|(term, w)| b.get(term).map(|v| (term, w * v)))
+            .collect();
+        shared.sort_by(|x, y| y.1.total_cmp(&x.1));
+        shared.into_iter().take(n).map(|(term, _)| term.clone()).collect()
+    }
+}
+
 impl KBSuggestionService {
     /// Suggest KB articles based on ticket title and description
-    /// Uses keyword-based text similarity for MVP (semantic search in AI module)
+    /// Uses TF-IDF cosine similarity (semantic search in AI module)
     pub async fn suggest_articles(
         db: Arc<Surreal<Db>>,
         request: KBSuggestionRequest,
     ) -> Result<Vec<ArticleSuggestion>, String> {
         let limit = request.limit.unwrap_or(5).min(10);
+        let query_text = Self::query_text(request.title.as_deref(), request.description.as_deref());
+
+        let ranked = Self::rank_published(db, &query_text, request.category.as_deref(), true, limit).await?;
+        Ok(ranked.into_iter().map(|r| r.suggestion).collect())
+    }
+
+    /// Rank published articles for a ticket and log what was shown so
+    /// acceptance can be measured and fed back into the ranking
+    pub async fn suggest_for_ticket(
+        db: Arc<Surreal<Db>>,
+        ticket_id: &str,
+        include_internal: bool,
+        limit: u32,
+        user_id: &str,
+    ) -> Result<TicketSuggestionResponse, String> {
+        let ticket_thing = Thing::from(("ticket", ticket_id));
+
+        let ticket: Option<TicketText> = db
+            .query("SELECT title, description FROM $ticket")
+            .bind(("ticket", &ticket_thing))
+            .traced("kb_suggestion_service.suggest_for_ticket")
+            .await
+            .map_err(|e| e.to_string())?
+            .take(0)
+            .map_err(|e| e.to_string())?;
+        let ticket = ticket.ok_or_else(|| format!("Ticket {} not found", ticket_id))?;
+
+        let query_text = Self::query_text(Some(&ticket.title), ticket.description.as_deref());
+        let ranked = Self::rank_published(db.clone(), &query_text, None, include_internal, limit.clamp(1, 10)).await?;
 
-        // Extract keywords from title and description
-        let mut keywords = Vec::new();
-        if let Some(ref title) = request.title {
-            keywords.extend(Self::extract_keywords(title));
+        // One log row per (ticket, article); re-opening the ticket refreshes it
+        let existing: Vec<KBSuggestionLog> = db
+            .query("SELECT * FROM kb_suggestion_log WHERE ticket_id = $ticket")
+            .bind(("ticket", &ticket_thing))
+            .traced("kb_suggestion_service.suggest_for_ticket")
+            .await
+            .map_err(|e| e.to_string())?
+            .take(0)
+            .map_err(|e| e.to_string())?;
+        let existing: HashMap<Thing, KBSuggestionLog> = existing
+            .into_iter()
+            .map(|log| (log.article_id.clone(), log))
+            .collect();
+
+        let now = Utc::now();
+        let mut suggestions = Vec::with_capacity(ranked.len());
+        for (index, ranked) in ranked.into_iter().enumerate() {
+            let rank = index as u32 + 1;
+            let article_thing = Thing::from(("kb_articles", ranked.suggestion.article_id.as_str()));
+
+            let log_id = match existing.get(&article_thing).and_then(|log| log.id.clone()) {
+                Some(id) => {
+                    db.query("UPDATE $id SET rank = $rank, score = $score, similarity = $similarity, shown_to = $user, shown_at = $now")
+                        .bind(("id", &id))
+                        .bind(("rank", rank))
+                        .bind(("score", ranked.suggestion.relevance_score))
+                        .bind(("similarity", ranked.similarity))
+                        .bind(("user", user_id))
+                        .bind(("now", now))
+                        .traced("kb_suggestion_service.suggest_for_ticket")
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    id
+                }
+                None => {
+                    let log = KBSuggestionLog {
+                        id: None,
+                        ticket_id: ticket_thing.clone(),
+                        article_id: article_thing,
+                        rank,
+                        score: ranked.suggestion.relevance_score,
+                        similarity: ranked.similarity,
+                        method: SUGGESTION_METHOD.to_string(),
+                        shown_to: user_id.to_string(),
+                        shown_at: now,
+                        outcome: SuggestionOutcome::Shown,
+                        responded_at: None,
+                    };
+                    let created: Vec<KBSuggestionLog> = db
+                        .create("kb_suggestion_log")
+                        .content(&log)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    created
+                        .into_iter()
+                        .next()
+                        .and_then(|log| log.id)
+                        .ok_or_else(|| "Failed to log suggestion".to_string())?
+                }
+            };
+
+            suggestions.push(TicketArticleSuggestion {
+                suggestion_id: log_id.id.to_raw(),
+                rank,
+                suggestion: ranked.suggestion,
+                matched_terms: ranked.matched_terms,
+            });
         }
-        if let Some(ref description) = request.description {
-            keywords.extend(Self::extract_keywords(description));
+
+        Ok(TicketSuggestionResponse {
+            ticket_id: ticket_id.to_string(),
+            method: SUGGESTION_METHOD.to_string(),
+            suggestions,
+        })
+    }
+
+    /// Record what the agent did with a suggestion. Accepting one attaches
+    /// the article to the ticket.
+    pub async fn record_feedback(
+        db: Arc<Surreal<Db>>,
+        suggestion_id: &str,
+        request: SuggestionFeedbackRequest,
+        user_id: &str,
+    ) -> Result<KBSuggestionLog, String> {
+        if request.outcome == SuggestionOutcome::Shown {
+            return Err("Outcome must be VIEWED, ACCEPTED or DISMISSED".to_string());
         }
 
-        if keywords.is_empty() {
-            return Ok(vec![]);
+        let log: Option<KBSuggestionLog> = db
+            .select(("kb_suggestion_log", suggestion_id))
+            .await
+            .map_err(|e| e.to_string())?;
+        let log = log.ok_or_else(|| "Suggestion not found".to_string())?;
+
+        // Don't let a later view downgrade an accept/dismiss
+        if request.outcome == SuggestionOutcome::Viewed && log.outcome != SuggestionOutcome::Shown {
+            return Ok(log);
         }
 
-        // Build search query for published articles only
-        let mut conditions = vec!["status = 'PUBLISHED'".to_string()];
-        
-        // Add category filter if provided
-        if let Some(ref category) = request.category {
-            conditions.push(format!("category_id.id = '{}'", category));
+        let updated: Option<KBSuggestionLog> = db
+            .query("UPDATE $id SET outcome = $outcome, responded_at = $now RETURN AFTER")
+            .bind(("id", Thing::from(("kb_suggestion_log", suggestion_id))))
+            .bind(("outcome", &request.outcome))
+            .bind(("now", Utc::now()))
+            .traced("kb_suggestion_service.record_feedback")
+            .await
+            .map_err(|e| e.to_string())?
+            .take(0)
+            .map_err(|e| e.to_string())?;
+        let updated = updated.ok_or_else(|| "Suggestion not found".to_string())?;
+
+        if request.outcome == SuggestionOutcome::Accepted && log.outcome != SuggestionOutcome::Accepted {
+            Self::link_article_to_ticket(
+                db,
+                &log.ticket_id.id.to_raw(),
+                &log.article_id.id.to_raw(),
+                KBLinkType::AttachedByAgent,
+                request.was_helpful,
+                user_id,
+            )
+            .await?;
         }
 
-        // Build keyword search conditions (title OR content contains keywords)
-        let keyword_conditions: Vec<String> = keywords
-            .iter()
-            .map(|kw| format!("(title CONTAINS '{}' OR content CONTAINS '{}')", kw, kw))
-            .collect();
-        
-        if !keyword_conditions.is_empty() {
-            conditions.push(format!("({})", keyword_conditions.join(" OR ")));
+        Ok(updated)
+    }
+
+    /// Acceptance statistics over the last `days`, overall and per rank
+    pub async fn suggestion_metrics(
+        db: Arc<Surreal<Db>>,
+        days: u32,
+    ) -> Result<SuggestionMetrics, String> {
+        let since = Utc::now() - chrono::Duration::days(days.max(1) as i64);
+
+        let logs: Vec<KBSuggestionLog> = db
+            .query("SELECT * FROM kb_suggestion_log WHERE shown_at >= $since")
+            .bind(("since", since))
+            .traced("kb_suggestion_service.suggestion_metrics")
+            .await
+            .map_err(|e| e.to_string())?
+            .take(0)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self::summarize_logs(&logs, since))
+    }
+
+    fn summarize_logs(logs: &[KBSuggestionLog], since: chrono::DateTime<Utc>) -> SuggestionMetrics {
+        let count = |outcome: SuggestionOutcome| logs.iter().filter(|l| l.outcome == outcome).count() as u64;
+        let shown = logs.len() as u64;
+        let accepted = count(SuggestionOutcome::Accepted);
+
+        let mut by_rank: std::collections::BTreeMap<u32, (u64, u64)> = std::collections::BTreeMap::new();
+        for log in logs {
+            let entry = by_rank.entry(log.rank).or_default();
+            entry.0 += 1;
+            if log.outcome == SuggestionOutcome::Accepted {
+                entry.1 += 1;
+            }
         }
 
-        let where_clause = conditions.join(" AND ");
+        let rate = |accepted: u64, shown: u64| if shown == 0 { 0.0 } else { accepted as f64 / shown as f64 };
+        SuggestionMetrics {
+            since,
+            shown,
+            viewed: count(SuggestionOutcome::Viewed),
+            accepted,
+            dismissed: count(SuggestionOutcome::Dismissed),
+            acceptance_rate: rate(accepted, shown),
+            by_rank: by_rank
+                .into_iter()
+                .map(|(rank, (shown, accepted))| SuggestionRankStat {
+                    rank,
+                    shown,
+                    accepted,
+                    acceptance_rate: rate(accepted, shown),
+                })
+                .collect(),
+        }
+    }
 
-        // Query articles
-        let query = format!(
-            "SELECT * FROM kb_articles WHERE {} ORDER BY view_count DESC, resolution_count DESC LIMIT {}",
-            where_clause, limit * 2 // Fetch more to rank and filter
-        );
+    fn query_text(title: Option<&str>, description: Option<&str>) -> String {
+        // Titles are short and precise, so count them twice
+        let title = title.unwrap_or("");
+        format!("{} {} {}", title, title, description.unwrap_or(""))
+    }
+
+    /// Score every published article against the query text
+    async fn rank_published(
+        db: Arc<Surreal<Db>>,
+        query_text: &str,
+        category: Option<&str>,
+        include_internal: bool,
+        limit: u32,
+    ) -> Result<Vec<RankedArticle>, String> {
+        if Self::tokenize(query_text).is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut conditions = vec!["status = 'PUBLISHED'"];
+        if category.is_some() {
+            conditions.push("category_id = $category");
+        }
+        if !include_internal {
+            conditions.push("visibility = 'PUBLIC'");
+        }
+        let query = format!("SELECT * FROM kb_articles WHERE {}", conditions.join(" AND "));
 
         let articles: Vec<KBArticle> = db
             .query(&query)
+            .bind(("category", category.map(|c| Thing::from(("kb_categories", c)))))
+            .traced("kb_suggestion_service.rank_published")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
             .map_err(|e| e.to_string())?;
 
-        // Calculate relevance scores and convert to suggestions
-        let mut suggestions: Vec<ArticleSuggestion> = articles
+        let acceptance = Self::acceptance_by_article(db).await?;
+
+        let documents: Vec<String> = articles.iter().map(Self::document_text).collect();
+        let index = TfIdfIndex::build(&documents);
+        let query_vector = index.vectorize(query_text);
+
+        let mut ranked: Vec<RankedArticle> = articles
             .into_iter()
-            .filter_map(|article| {
-                let relevance = Self::calculate_relevance(&article, &keywords, request.title.as_deref(), request.description.as_deref());
-                
-                if relevance < 0.1 {
-                    return None; // Filter out very low relevance
+            .enumerate()
+            .filter_map(|(i, article)| {
+                let similarity = TfIdfIndex::cosine(&query_vector, &index.documents[i]);
+                if similarity < MIN_SIMILARITY {
+                    return None;
                 }
-
-                let excerpt = Self::generate_excerpt(&article.content, &keywords, 200);
-                
-                Some(ArticleSuggestion {
-                    article_id: article.id.as_ref()?.id.to_string(),
-                    title: article.title.clone(),
-                    summary: article.summary.clone(),
-                    excerpt,
-                    relevance_score: relevance,
-                    resolution_count: article.resolution_count,
-                    helpful_count: article.helpful_count,
-                    view_count: article.view_count,
-                    category: article.category_id.as_ref().map(|c| c.id.to_string()),
+                let id = article.id.as_ref()?;
+                let (shown, accepted) = acceptance.get(id).copied().unwrap_or((0, 0));
+                let relevance = Self::blend_score(similarity, &article, shown, accepted);
+                let matched_terms = TfIdfIndex::top_terms(&query_vector, &index.documents[i], 5);
+                let excerpt = Self::generate_excerpt(&article.content, &matched_terms, 200);
+
+                Some(RankedArticle {
+                    similarity,
+                    matched_terms,
+                    suggestion: ArticleSuggestion {
+                        article_id: id.id.to_raw(),
+                        title: article.title.clone(),
+                        summary: article.summary.clone(),
+                        excerpt,
+                        relevance_score: relevance,
+                        resolution_count: article.resolution_count,
+                        helpful_count: article.helpful_count,
+                        view_count: article.view_count,
+                        category: article.category_id.as_ref().map(|c| c.id.to_string()),
+                    },
                 })
             })
             .collect();
 
-        // Sort by relevance score descending
-        suggestions.sort_by(|a, b| b.relevance_score.partial_cmp(&a.relevance_score).unwrap());
-        suggestions.truncate(limit as usize);
+        ranked.sort_by(|a, b| b.suggestion.relevance_score.total_cmp(&a.suggestion.relevance_score));
+        ranked.truncate(limit as usize);
+        Ok(ranked)
+    }
 
-        Ok(suggestions)
+    /// (shown, accepted) counts per article from the suggestion log
+    async fn acceptance_by_article(db: Arc<Surreal<Db>>) -> Result<HashMap<Thing, (u64, u64)>, String> {
+        #[derive(serde::Deserialize)]
+        struct Row {
+            article_id: Thing,
+            count: u64,
+        }
+
+        let mut response = db
+            .query("SELECT article_id, count() AS count FROM kb_suggestion_log GROUP BY article_id")
+            .query("SELECT article_id, count() AS count FROM kb_suggestion_log WHERE outcome = 'ACCEPTED' GROUP BY article_id")
+            .traced("kb_suggestion_service.acceptance_by_article")
+            .await
+            .map_err(|e| e.to_string())?;
+        let shown: Vec<Row> = response.take(0).map_err(|e| e.to_string())?;
+        let accepted: Vec<Row> = response.take(1).map_err(|e| e.to_string())?;
+
+        let mut counts: HashMap<Thing, (u64, u64)> = shown
+            .into_iter()
+            .map(|row| (row.article_id, (row.count, 0)))
+            .collect();
+        for row in accepted {
+            counts.entry(row.article_id).or_default().1 = row.count;
+        }
+        Ok(counts)
+    }
+
+    /// Text used to index an article (title weighted double)
+    fn document_text(article: &KBArticle) -> String {
+        format!(
+            "{} {} {} {} {}",
+            article.title,
+            article.title,
+            article.summary.as_deref().unwrap_or(""),
+            article.tags.join(" "),
+            article.content
+        )
+    }
+
+    /// Combine text similarity with usage signals. The acceptance prior is
+    /// Laplace-smoothed so articles that have never been shown start at 0.5.
+    fn blend_score(similarity: f32, article: &KBArticle, shown: u64, accepted: u64) -> f32 {
+        let resolution = article.resolution_count.clamp(0, 10) as f32 / 10.0;
+        let helpfulness = (article.helpfulness_score / 100.0).clamp(0.0, 1.0);
+        let acceptance = (accepted as f32 + 1.0) / (shown as f32 + 2.0);
+
+        SIMILARITY_WEIGHT * similarity
+            + RESOLUTION_WEIGHT * resolution
+            + HELPFULNESS_WEIGHT * helpfulness
+            + ACCEPTANCE_WEIGHT * acceptance
     }
 
     /// Get KB articles that resolved similar tickets
@@ -117,6 +475,7 @@ impl KBSuggestionService {
         let links: Vec<TicketKBLink> = db
             .query(query)
             .bind(("ticket", &ticket_thing))
+            .traced("kb_suggestion_service.get_articles_for_ticket")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -167,6 +526,7 @@ impl KBSuggestionService {
 
         let articles: Vec<KBArticle> = db
             .query(&query)
+            .traced("kb_suggestion_service.get_top_resolution_articles")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -227,6 +587,7 @@ impl KBSuggestionService {
             let _: Option<KBArticle> = db
                 .query("UPDATE kb_articles SET resolution_count += 1 WHERE id = $id")
                 .bind(("id", &article_thing))
+                .traced("kb_suggestion_service.link_article_to_ticket")
                 .await
                 .map_err(|e| e.to_string())?
                 .take(0)
@@ -273,6 +634,7 @@ impl KBSuggestionService {
                 .query("UPDATE kb_articles SET helpfulness_score = $score WHERE id = $id")
                 .bind(("id", Thing::from(("kb_articles", article_id))))
                 .bind(("score", new_score))
+                .traced("kb_suggestion_service.update_helpfulness_score")
                 .await
                 .map_err(|e| e.to_string())?
                 .take(0)
@@ -282,73 +644,37 @@ impl KBSuggestionService {
         Ok(())
     }
 
-    /// Extract keywords from text (simple tokenization)
-    fn extract_keywords(text: &str) -> Vec<String> {
+    /// Lowercase, split on non-alphanumerics, drop stop words and apply a
+    /// light suffix stemmer so "printers"/"printing" match "printer"
+    fn tokenize(text: &str) -> Vec<String> {
         text.to_lowercase()
-            .split_whitespace()
-            .filter(|word| word.len() > 3) // Ignore short words
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| word.len() > 2)
             .filter(|word| !Self::is_stop_word(word))
-            .map(|word| {
-                // Remove punctuation
-                word.chars()
-                    .filter(|c| c.is_alphanumeric())
-                    .collect::<String>()
-            })
-            .filter(|word| !word.is_empty())
-            .take(10) // Limit to top 10 keywords per field
+            .map(Self::stem)
             .collect()
     }
 
+    fn stem(word: &str) -> String {
+        for (suffix, replacement, min_len) in [("ies", "y", 5), ("ing", "", 6), ("ed", "", 5), ("s", "", 4)] {
+            if word.len() >= min_len && word.ends_with(suffix) && !word.ends_with("ss") {
+                return format!("{}{}", &word[..word.len() - suffix.len()], replacement);
+            }
+        }
+        word.to_string()
+    }
+
     /// Check if word is a common stop word
     fn is_stop_word(word: &str) -> bool {
         const STOP_WORDS: &[&str] = &[
             "the", "and", "for", "with", "from", "this", "that", "have", "been",
             "not", "are", "was", "but", "can", "will", "has", "had", "more",
+            "you", "your", "our", "all", "any", "when", "what", "how", "does",
+            "into", "after", "also", "there", "they", "then", "than", "http", "https", "www",
         ];
         STOP_WORDS.contains(&word)
     }
 
-    /// Calculate relevance score for an article
-    fn calculate_relevance(
-        article: &KBArticle,
-        keywords: &[String],
-        title: Option<&str>,
-        description: Option<&str>,
-    ) -> f32 {
-        let mut score = 0.0;
-
-        let article_text = format!("{} {}", article.title.to_lowercase(), article.content.to_lowercase());
-        let query_text = format!(
-            "{} {}",
-            title.unwrap_or("").to_lowercase(),
-            description.unwrap_or("").to_lowercase()
-        );
-
-        // Keyword match score (0-50 points)
-        let matched_keywords: usize = keywords
-            .iter()
-            .filter(|kw| article_text.contains(kw.as_str()))
-            .count();
-        score += (matched_keywords as f32 / keywords.len().max(1) as f32) * 50.0;
-
-        // Title match bonus (0-20 points)
-        if let Some(title) = title {
-            let title_lower = title.to_lowercase();
-            if article.title.to_lowercase().contains(&title_lower) {
-                score += 20.0;
-            }
-        }
-
-        // Resolution count bonus (0-15 points)
-        score += (article.resolution_count.min(10) as f32 / 10.0) * 15.0;
-
-        // Helpfulness score bonus (0-15 points)
-        score += (article.helpfulness_score / 100.0) * 15.0;
-
-        // Normalize to 0-1 range
-        score / 100.0
-    }
-
     /// Generate excerpt from content highlighting keywords
     fn generate_excerpt(content: &str, keywords: &[String], max_length: usize) -> String {
         if content.len() <= max_length {
@@ -374,3 +700,63 @@ impl KBSuggestionService {
         format!("{}...", &content[..max_length.min(content.len())])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizer_drops_stop_words_and_stems() {
+        let tokens = KBSuggestionService::tokenize("The printers are not printing: see https://wiki");
+        assert_eq!(tokens, vec!["printer", "print", "see", "wiki"]);
+        assert_eq!(KBSuggestionService::stem("policies"), "policy");
+        assert_eq!(KBSuggestionService::stem("access"), "access");
+    }
+
+    #[test]
+    fn tfidf_ranks_matching_document_first() {
+        let documents = vec![
+            "Reset your VPN password from the self-service portal".to_string(),
+            "Configure the office printer queue and drivers".to_string(),
+            "Outlook calendar sharing permissions".to_string(),
+        ];
+        let index = TfIdfIndex::build(&documents);
+        let query = index.vectorize("Printer queue stuck, drivers missing");
+
+        let scores: Vec<f32> = index.documents.iter().map(|d| TfIdfIndex::cosine(&query, d)).collect();
+        assert!(scores[1] > 0.5);
+        assert_eq!(scores[0], 0.0);
+        assert_eq!(scores[2], 0.0);
+        assert_eq!(TfIdfIndex::top_terms(&query, &index.documents[1], 5).len(), 3);
+    }
+
+    #[test]
+    fn metrics_report_acceptance_by_rank() {
+        let log = |rank: u32, outcome: SuggestionOutcome| KBSuggestionLog {
+            id: None,
+            ticket_id: Thing::from(("ticket", "t1")),
+            article_id: Thing::from(("kb_articles", format!("a{}", rank).as_str())),
+            rank,
+            score: 0.5,
+            similarity: 0.5,
+            method: SUGGESTION_METHOD.to_string(),
+            shown_to: "users:agent".to_string(),
+            shown_at: Utc::now(),
+            outcome,
+            responded_at: None,
+        };
+        let logs = vec![
+            log(1, SuggestionOutcome::Accepted),
+            log(1, SuggestionOutcome::Shown),
+            log(2, SuggestionOutcome::Dismissed),
+            log(2, SuggestionOutcome::Viewed),
+        ];
+
+        let metrics = KBSuggestionService::summarize_logs(&logs, Utc::now());
+        assert_eq!(metrics.shown, 4);
+        assert_eq!(metrics.accepted, 1);
+        assert_eq!(metrics.acceptance_rate, 0.25);
+        assert_eq!(metrics.by_rank[0].acceptance_rate, 0.5);
+        assert_eq!(metrics.by_rank[1].accepted, 0);
+    }
+}
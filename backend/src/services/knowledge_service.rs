@@ -2,13 +2,16 @@
 // Article management, search, versioning, and ratings
 
 use crate::models::knowledge::*;
+use crate::services::kb_attachment_service::KbAttachmentService;
+use crate::utils::pagination::{count_total, Page, Pagination};
 use chrono::Utc;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
-use surrealdb::engine::local::Db;
+use crate::database::Db;
 use surrealdb::sql::Thing;
 use surrealdb::Surreal;
+use crate::database::TracedQuery;
 
 pub struct KnowledgeService;
 
@@ -32,6 +35,7 @@ impl KnowledgeService {
         let existing: Option<KBArticle> = db
             .query("SELECT * FROM kb_articles WHERE slug = $slug LIMIT 1")
             .bind(("slug", &slug))
+            .traced("knowledge_service.create_article")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -62,6 +66,7 @@ impl KnowledgeService {
             resolution_count: 0,
             helpfulness_score: 0.0,
             related_articles: vec![],
+            attachments: vec![],
             seo_title: request.seo_title,
             seo_description: request.seo_description,
             author_id: user_id.to_string(),
@@ -140,6 +145,7 @@ impl KnowledgeService {
         let article: Option<KBArticle> = db
             .query("SELECT * FROM kb_articles WHERE slug = $slug LIMIT 1")
             .bind(("slug", slug))
+            .traced("knowledge_service.get_article_by_slug")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -155,6 +161,7 @@ impl KnowledgeService {
         let _: Option<KBArticle> = db
             .query("UPDATE kb_articles SET view_count += 1 WHERE id = $id")
             .bind(("id", Thing::from(("kb_articles", id))))
+            .traced("knowledge_service.increment_view_count")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -206,6 +213,7 @@ impl KnowledgeService {
             .bind(("expires_at", request.expires_at.or(existing.expires_at)))
             .bind(("version", new_version))
             .bind(("updated_at", now))
+            .traced("knowledge_service.update_article")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -235,6 +243,11 @@ impl KnowledgeService {
         db: Arc<Surreal<Db>>,
         id: &str,
     ) -> Result<(), String> {
+        // Remove stored files first so they don't outlive the article
+        KbAttachmentService::new(db.clone())?
+            .delete_for_article(id)
+            .await?;
+
         let _: Option<KBArticle> = db
             .delete(("kb_articles", id))
             .await
@@ -264,6 +277,7 @@ impl KnowledgeService {
             .bind(("id", Thing::from(("kb_articles", id))))
             .bind(("approver", approver_id))
             .bind(("now", now))
+            .traced("knowledge_service.publish_article")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -276,11 +290,8 @@ impl KnowledgeService {
     pub async fn search_articles(
         db: Arc<Surreal<Db>>,
         request: KBSearchRequest,
-    ) -> Result<KBArticleListResponse, String> {
-        let page = request.page.unwrap_or(1).max(1);
-        let page_size = request.page_size.unwrap_or(20).min(100);
-        let offset = (page - 1) * page_size;
-
+        pagination: &Pagination<ArticleSort>,
+    ) -> Result<Page<KBArticle>, String> {
         let mut conditions = vec!["status != 'ARCHIVED'".to_string()];
 
         if let Some(ref query) = request.query {
@@ -319,36 +330,31 @@ impl KnowledgeService {
 
         let count_result: Vec<JsonValue> = db
             .query(&count_query)
+            .traced("knowledge_service.search_articles")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
             .map_err(|e| e.to_string())?;
 
-        let total = count_result
-            .first()
-            .and_then(|v| v.get("count"))
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
+        let total = count_total(&count_result);
 
         // Get items
         let items_query = format!(
-            "SELECT * FROM kb_articles WHERE {} ORDER BY updated_at DESC LIMIT {} START {}",
-            where_clause, page_size, offset
+            "SELECT * FROM kb_articles WHERE {} {} {}",
+            where_clause,
+            pagination.order_clause(),
+            pagination.limit_clause()
         );
 
         let items: Vec<KBArticle> = db
             .query(&items_query)
+            .traced("knowledge_service.search_articles")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
             .map_err(|e| e.to_string())?;
 
-        Ok(KBArticleListResponse {
-            items,
-            total,
-            page,
-            page_size,
-        })
+        Ok(pagination.page(items, total))
     }
 
     // ========================================================================
@@ -398,6 +404,7 @@ impl KnowledgeService {
         let versions: Vec<KBArticleVersion> = db
             .query("SELECT * FROM kb_article_versions WHERE article_id = $id ORDER BY version DESC")
             .bind(("id", &article_thing))
+            .traced("knowledge_service.get_article_versions")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -425,6 +432,7 @@ impl KnowledgeService {
             .query("SELECT * FROM kb_article_ratings WHERE article_id = $article AND user_id = $user LIMIT 1")
             .bind(("article", &article_thing))
             .bind(("user", user_id))
+            .traced("knowledge_service.rate_article")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -454,6 +462,7 @@ impl KnowledgeService {
         let _: Option<KBArticle> = db
             .query(&format!("UPDATE kb_articles SET {} += 1 WHERE id = $id", field))
             .bind(("id", &article_thing))
+            .traced("knowledge_service.rate_article")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -528,6 +537,7 @@ impl KnowledgeService {
 
         let categories: Vec<KBCategory> = db
             .query(query)
+            .traced("knowledge_service.list_categories")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -568,6 +578,7 @@ impl KnowledgeService {
             .bind(("display_order", request.display_order.unwrap_or(existing.display_order)))
             .bind(("is_active", request.is_active.unwrap_or(existing.is_active)))
             .bind(("updated_at", now))
+            .traced("knowledge_service.update_category")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -586,6 +597,7 @@ impl KnowledgeService {
         let count_result: Vec<JsonValue> = db
             .query("SELECT count() FROM kb_articles WHERE category_id = $cat GROUP ALL")
             .bind(("cat", &cat_thing))
+            .traced("knowledge_service.delete_category")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -619,6 +631,7 @@ impl KnowledgeService {
         // Total articles
         let total_result: Vec<JsonValue> = db
             .query("SELECT count() FROM kb_articles GROUP ALL")
+            .traced("knowledge_service.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -633,6 +646,7 @@ impl KnowledgeService {
         // Published articles
         let published_result: Vec<JsonValue> = db
             .query("SELECT count() FROM kb_articles WHERE status = 'PUBLISHED' GROUP ALL")
+            .traced("knowledge_service.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -647,6 +661,7 @@ impl KnowledgeService {
         // Draft articles
         let draft_result: Vec<JsonValue> = db
             .query("SELECT count() FROM kb_articles WHERE status = 'DRAFT' GROUP ALL")
+            .traced("knowledge_service.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -661,6 +676,7 @@ impl KnowledgeService {
         // Total categories
         let cat_result: Vec<JsonValue> = db
             .query("SELECT count() FROM kb_categories WHERE is_active = true GROUP ALL")
+            .traced("knowledge_service.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -675,6 +691,7 @@ impl KnowledgeService {
         // Total views
         let views_result: Vec<JsonValue> = db
             .query("SELECT math::sum(view_count) as total_views FROM kb_articles GROUP ALL")
+            .traced("knowledge_service.get_statistics")
             .await
             .map_err(|e| e.to_string())?
             .take(0)
@@ -0,0 +1,422 @@
+use chrono::Utc;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Instant;
+use surrealdb::sql::Thing;
+
+use super::provider::LlmError;
+use super::service::{LlmCaller, LlmService};
+use super::tools::{self, AgentReply, AgentTool, ToolContext};
+use crate::database::Database;
+use crate::models::ai::*;
+use crate::database::TracedQuery;
+
+/// Agent name recorded on thought logs and actions
+pub const AGENT_NAME: &str = "archer_assistant";
+const DEFAULT_MAX_STEPS: u32 = 6;
+const MAX_STEPS_LIMIT: u32 = 12;
+
+/// Tool-calling assistant over the core engine. Every model turn is written
+/// to `ai_thought_log` and every tool invocation to `agent_action`; mutating
+/// tools stop the run until someone decides on the pending action.
+pub struct AgentService {
+    db: Arc<Database>,
+    llm: LlmService,
+}
+
+impl AgentService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            llm: LlmService::new(db.clone()),
+            db,
+        }
+    }
+
+    /// Run the assistant until it answers, asks for approval or runs out of
+    /// steps. `tools` is what the caller is allowed to use.
+    pub async fn run(
+        &self,
+        caller: &LlmCaller<'_>,
+        tools: &[AgentTool],
+        request: AgentRunRequest,
+    ) -> Result<AgentRunResponse, LlmError> {
+        if request.message.trim().is_empty() {
+            return Err(LlmError::InvalidRequest("Message is empty".to_string()));
+        }
+        let session_id = request
+            .session_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let max_steps = request.max_steps.unwrap_or(DEFAULT_MAX_STEPS).clamp(1, MAX_STEPS_LIMIT);
+
+        // The system prompt is rebuilt every run so the tool list always
+        // reflects the caller's current permissions
+        let mut transcript: Vec<ChatMessage> = request
+            .history
+            .into_iter()
+            .filter(|m| m.role != ChatRole::System)
+            .collect();
+        transcript.push(ChatMessage { role: ChatRole::User, content: request.message });
+
+        let mut steps = Vec::new();
+        let mut usage = TokenUsage::default();
+
+        for _ in 0..max_steps {
+            let mut messages = vec![ChatMessage {
+                role: ChatRole::System,
+                content: tools::system_prompt(tools),
+            }];
+            messages.extend(transcript.iter().cloned());
+            let input = transcript.last().map(|m| m.content.clone()).unwrap_or_default();
+
+            let started = Instant::now();
+            let response = self
+                .llm
+                .chat(
+                    caller,
+                    ChatCompletionRequest {
+                        messages,
+                        provider_id: request.provider_id.clone(),
+                        model: request.model.clone(),
+                        temperature: Some(0.0),
+                        max_tokens: None,
+                        feature: Some("agent".to_string()),
+                    },
+                )
+                .await?;
+            usage.prompt_tokens += response.usage.prompt_tokens;
+            usage.completion_tokens += response.usage.completion_tokens;
+            usage.total_tokens += response.usage.total_tokens;
+            usage.estimated |= response.usage.estimated;
+            transcript.push(ChatMessage { role: ChatRole::Assistant, content: response.content.clone() });
+
+            match tools::parse_reply(&response.content) {
+                AgentReply::Answer { thought, answer } => {
+                    self.log_thought(caller, &session_id, &input, &thought, &answer, &response, started)
+                        .await;
+                    return Ok(AgentRunResponse {
+                        session_id,
+                        status: AgentRunStatus::Completed,
+                        answer: Some(answer),
+                        steps,
+                        pending_action: None,
+                        transcript,
+                        usage,
+                    });
+                }
+                AgentReply::ToolCall { thought, tool, arguments } => {
+                    let thought_log = self
+                        .log_thought(caller, &session_id, &input, &thought, &response.content, &response, started)
+                        .await;
+
+                    let Some(agent_tool) = AgentTool::from_name(&tool).filter(|t| tools.contains(t)) else {
+                        let error = format!("Tool '{}' is not available", tool);
+                        let action = self
+                            .record_unavailable(caller, &session_id, thought_log, &tool, arguments.clone(), &error)
+                            .await?;
+                        steps.push(step(&action));
+                        transcript.push(tool_message(&tool, &format!("ERROR: {}", error)));
+                        continue;
+                    };
+
+                    let action = self
+                        .create_action(caller, &session_id, thought_log, agent_tool, arguments)
+                        .await?;
+                    if agent_tool.mutating() {
+                        steps.push(step(&action));
+                        return Ok(AgentRunResponse {
+                            session_id,
+                            status: AgentRunStatus::AwaitingApproval,
+                            answer: None,
+                            steps,
+                            pending_action: Some(action),
+                            transcript,
+                            usage,
+                        });
+                    }
+
+                    let action = self.execute_action(action, agent_tool, caller.user_id).await?;
+                    let feedback = match (&action.result, &action.error) {
+                        (Some(result), _) => result.clone(),
+                        (None, Some(error)) => format!("ERROR: {}", error),
+                        (None, None) => "{}".to_string(),
+                    };
+                    steps.push(step(&action));
+                    transcript.push(tool_message(&tool, &feedback));
+                }
+            }
+        }
+
+        Ok(AgentRunResponse {
+            session_id,
+            status: AgentRunStatus::StepLimitReached,
+            answer: None,
+            steps,
+            pending_action: None,
+            transcript,
+            usage,
+        })
+    }
+
+    /// Approve (and run) or reject a pending action
+    pub async fn decide(
+        &self,
+        action_id: &str,
+        approver_id: &str,
+        decision: AgentActionDecisionRequest,
+    ) -> Result<AgentAction, LlmError> {
+        let mut action = self
+            .get_action(action_id)
+            .await?
+            .ok_or_else(|| LlmError::NotFound(format!("Agent action {} not found", action_id)))?;
+        if action.status != AgentActionStatus::Pending {
+            return Err(LlmError::InvalidRequest(format!(
+                "Agent action {} is not pending",
+                action_id
+            )));
+        }
+        if action.requested_by == approver_id {
+            return Err(LlmError::Forbidden(
+                "Actions must be approved by someone other than the requester".to_string(),
+            ));
+        }
+        let tool = AgentTool::from_name(&action.action_type)
+            .ok_or_else(|| LlmError::InvalidRequest(format!("Unknown tool {}", action.action_type)))?;
+
+        action.approver = Some(approver_id.to_string());
+        action.approved_at = Some(Utc::now());
+        action.decision_comment = decision.comment;
+        if !decision.approve {
+            action.status = AgentActionStatus::Rejected;
+            return self.save_action(action).await;
+        }
+
+        action.status = AgentActionStatus::Approved;
+        let action = self.save_action(action).await?;
+        // Runs on behalf of the requester; the approver only unblocks it
+        let requester = action.requested_by.clone();
+        self.execute_action(action, tool, &requester).await
+    }
+
+    pub async fn get_action(&self, id: &str) -> Result<Option<AgentAction>, LlmError> {
+        Ok(self.db.select(("agent_action", id)).await?)
+    }
+
+    pub async fn list_actions(
+        &self,
+        tenant_id: Option<&str>,
+        status: Option<AgentActionStatus>,
+        session_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<AgentAction>, LlmError> {
+        let mut conditions = vec!["tenant_id = $tenant"];
+        if status.is_some() {
+            conditions.push("status = $status");
+        }
+        if session_id.is_some() {
+            conditions.push("session_id = $session");
+        }
+        let sql = format!(
+            "SELECT * FROM agent_action WHERE {} ORDER BY created_at DESC LIMIT $limit",
+            conditions.join(" AND ")
+        );
+        let actions: Vec<AgentAction> = self
+            .db
+            .query(sql)
+            .bind(("tenant", tenant_id))
+            .bind(("status", status))
+            .bind(("session", session_id))
+            .bind(("limit", limit.clamp(1, 500)))
+            .traced("agent.list_actions")
+            .await?
+            .take(0)?;
+        Ok(actions)
+    }
+
+    pub async fn list_thoughts(&self, tenant_id: Option<&str>, session_id: &str) -> Result<Vec<AiThoughtLog>, LlmError> {
+        let thoughts: Vec<AiThoughtLog> = self
+            .db
+            .query("SELECT * FROM ai_thought_log WHERE tenant_id = $tenant AND session_id = $session ORDER BY created_at ASC")
+            .bind(("tenant", tenant_id))
+            .bind(("session", session_id))
+            .traced("agent.list_thoughts")
+            .await?
+            .take(0)?;
+        Ok(thoughts)
+    }
+
+    // ========================================================================
+    // RECORDS
+    // ========================================================================
+
+    #[allow(clippy::too_many_arguments)]
+    async fn log_thought(
+        &self,
+        caller: &LlmCaller<'_>,
+        session_id: &str,
+        input: &str,
+        thought: &str,
+        output: &str,
+        response: &ChatCompletionResponse,
+        started: Instant,
+    ) -> Option<Thing> {
+        let log = AiThoughtLog {
+            id: None,
+            session_id: session_id.to_string(),
+            agent: AGENT_NAME.to_string(),
+            user: Some(caller.user_id.to_string()),
+            tenant_id: caller.tenant_id.map(str::to_string),
+            input: input.to_string(),
+            chain_of_thought: thought.to_string(),
+            output: output.to_string(),
+            model: response.model.clone(),
+            tokens_input: response.usage.prompt_tokens,
+            tokens_output: response.usage.completion_tokens,
+            latency_ms: started.elapsed().as_millis() as u64,
+            context_chunks: Vec::new(),
+            feedback: None,
+            created_at: Utc::now(),
+        };
+
+        // Like usage accounting, a failed log write doesn't fail the run
+        let created: Result<Vec<AiThoughtLog>, _> = self.db.create("ai_thought_log").content(&log).await;
+        match created {
+            Ok(created) => created.into_iter().next().and_then(|log| log.id),
+            Err(e) => {
+                tracing::warn!("Failed to write AI thought log: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn create_action(
+        &self,
+        caller: &LlmCaller<'_>,
+        session_id: &str,
+        thought_log: Option<Thing>,
+        tool: AgentTool,
+        arguments: Value,
+    ) -> Result<AgentAction, LlmError> {
+        let (risk_score, risk_factors) = tool.risk();
+        let action = AgentAction {
+            id: None,
+            agent: AGENT_NAME.to_string(),
+            action_type: tool.name().to_string(),
+            target: tool.target(&arguments),
+            parameters: arguments,
+            risk_score,
+            risk_factors,
+            status: AgentActionStatus::Pending,
+            requires_approval: tool.mutating(),
+            session_id: session_id.to_string(),
+            thought_log,
+            requested_by: caller.user_id.to_string(),
+            tenant_id: caller.tenant_id.map(str::to_string),
+            approver: None,
+            approved_at: None,
+            decision_comment: None,
+            executed_at: None,
+            result: None,
+            error: None,
+            created_at: Utc::now(),
+        };
+        self.insert_action(action).await
+    }
+
+    /// Calls to unknown or withheld tools are recorded as failed actions
+    async fn record_unavailable(
+        &self,
+        caller: &LlmCaller<'_>,
+        session_id: &str,
+        thought_log: Option<Thing>,
+        tool: &str,
+        arguments: Value,
+        error: &str,
+    ) -> Result<AgentAction, LlmError> {
+        let action = AgentAction {
+            id: None,
+            agent: AGENT_NAME.to_string(),
+            action_type: tool.to_string(),
+            target: "unknown".to_string(),
+            parameters: arguments,
+            risk_score: 1,
+            risk_factors: vec!["tool not available to caller".to_string()],
+            status: AgentActionStatus::Failed,
+            requires_approval: false,
+            session_id: session_id.to_string(),
+            thought_log,
+            requested_by: caller.user_id.to_string(),
+            tenant_id: caller.tenant_id.map(str::to_string),
+            approver: None,
+            approved_at: None,
+            decision_comment: None,
+            executed_at: None,
+            result: None,
+            error: Some(error.to_string()),
+            created_at: Utc::now(),
+        };
+        self.insert_action(action).await
+    }
+
+    async fn insert_action(&self, action: AgentAction) -> Result<AgentAction, LlmError> {
+        let created: Vec<AgentAction> = self.db.create("agent_action").content(&action).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| LlmError::Database("Failed to record agent action".to_string()))
+    }
+
+    async fn save_action(&self, action: AgentAction) -> Result<AgentAction, LlmError> {
+        let id = action
+            .id
+            .clone()
+            .ok_or_else(|| LlmError::Database("Agent action has no id".to_string()))?;
+        let saved: Option<AgentAction> = self.db.update(id).content(&action).await?;
+        saved.ok_or_else(|| LlmError::Database("Failed to update agent action".to_string()))
+    }
+
+    /// Run the tool and store the outcome; tool failures are recorded on the
+    /// action rather than returned as errors
+    async fn execute_action(&self, mut action: AgentAction, tool: AgentTool, user_id: &str) -> Result<AgentAction, LlmError> {
+        let ctx = ToolContext {
+            db: self.db.clone(),
+            user_id,
+            tenant_id: action.tenant_id.as_deref(),
+            action: action.id.clone(),
+        };
+        let outcome = tools::execute(tool, &action.parameters, &ctx).await;
+
+        action.executed_at = Some(Utc::now());
+        match outcome {
+            Ok(result) => {
+                action.status = AgentActionStatus::Executed;
+                action.result = Some(result);
+            }
+            Err(e) => {
+                action.status = AgentActionStatus::Failed;
+                action.error = Some(e.to_string());
+            }
+        }
+        self.save_action(action).await
+    }
+}
+
+fn step(action: &AgentAction) -> AgentStep {
+    AgentStep {
+        tool: action.action_type.clone(),
+        arguments: action.parameters.clone(),
+        action_id: action.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+        status: action.status,
+        result: action.result.clone(),
+        error: action.error.clone(),
+    }
+}
+
+/// Tool output goes back to the model as a user turn; not every provider
+/// accepts a dedicated tool role without native tool calling
+fn tool_message(tool: &str, content: &str) -> ChatMessage {
+    ChatMessage {
+        role: ChatRole::User,
+        content: format!("TOOL RESULT ({}):\n{}", tool, content),
+    }
+}
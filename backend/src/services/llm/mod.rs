@@ -0,0 +1,21 @@
+//! LLM provider layer
+//!
+//! A small provider abstraction over OpenAI-compatible HTTP APIs and local
+//! Ollama, plus the service that resolves a tenant's configured model,
+//! enforces token budgets and records usage. `agent` adds a tool-calling
+//! assistant over the core engine with an approval gate for mutating tools.
+
+pub mod agent;
+pub mod ollama;
+pub mod openai;
+pub mod provider;
+pub mod secrets;
+pub mod service;
+pub mod tools;
+
+pub use agent::AgentService;
+pub use ollama::OllamaProvider;
+pub use openai::OpenAiCompatibleProvider;
+pub use provider::{ChatRequest, LlmError, LlmProvider};
+pub use secrets::SecretBox;
+pub use service::LlmService;
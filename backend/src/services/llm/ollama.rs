@@ -0,0 +1,203 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::provider::{ensure_usage, provider_error, ChatRequest, LineBuffer, LlmError, LlmProvider};
+use crate::models::ai::{ChatCompletionResponse, TokenUsage};
+use crate::utils::telemetry::TracedRequest;
+
+/// Client for a local Ollama daemon (`/api/chat`, newline-delimited JSON streaming)
+pub struct OllamaProvider {
+    client: reqwest::Client,
+    name: String,
+    base_url: String,
+}
+
+impl OllamaProvider {
+    /// `base_url` is the daemon root, e.g. `http://localhost:11434`
+    pub fn new(name: &str, base_url: &str, timeout: Duration) -> Result<Self, LlmError> {
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            client,
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn body(request: &ChatRequest, stream: bool) -> Value {
+        let mut options = json!({});
+        if let Some(temperature) = request.temperature {
+            options["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            options["num_predict"] = json!(max_tokens);
+        }
+        json!({
+            "model": request.model,
+            "messages": request.messages,
+            "stream": stream,
+            "options": options,
+        })
+    }
+}
+
+/// Ollama reports prompt/completion counts on the final (`done`) message
+fn parse_usage(value: &Value) -> Option<TokenUsage> {
+    let prompt_tokens = value.get("prompt_eval_count").and_then(Value::as_u64);
+    let completion_tokens = value.get("eval_count").and_then(Value::as_u64);
+    if prompt_tokens.is_none() && completion_tokens.is_none() {
+        return None;
+    }
+    let (prompt_tokens, completion_tokens) = (prompt_tokens.unwrap_or(0), completion_tokens.unwrap_or(0));
+    Some(TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+        estimated: false,
+    })
+}
+
+fn parse_line(line: &str) -> Result<Value, LlmError> {
+    let value: Value = serde_json::from_str(line).map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+    if let Some(error) = value.get("error").and_then(Value::as_str) {
+        return Err(LlmError::Provider { status: 500, message: error.to_string() });
+    }
+    Ok(value)
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn chat(&self, request: &ChatRequest) -> Result<ChatCompletionResponse, LlmError> {
+        let response = self
+            .client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&Self::body(request, false))
+            .send_traced("ollama")
+            .await?;
+        if !response.status().is_success() {
+            return Err(provider_error(response).await);
+        }
+
+        let value: Value = response.json().await?;
+        let content = value
+            .pointer("/message/content")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(ChatCompletionResponse {
+            usage: ensure_usage(parse_usage(&value), &request.messages, &content),
+            content,
+            provider: self.name.clone(),
+            model: value
+                .get("model")
+                .and_then(Value::as_str)
+                .unwrap_or(&request.model)
+                .to_string(),
+            finish_reason: value.get("done_reason").and_then(Value::as_str).map(str::to_string),
+        })
+    }
+
+    async fn stream_chat(
+        &self,
+        request: &ChatRequest,
+        deltas: mpsc::Sender<String>,
+    ) -> Result<ChatCompletionResponse, LlmError> {
+        let mut response = self
+            .client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&Self::body(request, true))
+            .send_traced("ollama")
+            .await?;
+        if !response.status().is_success() {
+            return Err(provider_error(response).await);
+        }
+
+        let mut content = String::new();
+        let mut usage = None;
+        let mut finish_reason = None;
+        let mut lines = LineBuffer::default();
+
+        let mut body_done = false;
+        'body: while !body_done {
+            let batch = match response.chunk().await? {
+                Some(chunk) => lines.push(&chunk),
+                None => {
+                    // Flush a final line sent without a trailing newline
+                    body_done = true;
+                    std::mem::take(&mut lines).finish().into_iter().collect()
+                }
+            };
+            for line in batch {
+                let value = parse_line(&line)?;
+                if let Some(delta) = value.pointer("/message/content").and_then(Value::as_str) {
+                    if !delta.is_empty() {
+                        content.push_str(delta);
+                        if deltas.send(delta.to_string()).await.is_err() {
+                            break 'body;
+                        }
+                    }
+                }
+                if value.get("done").and_then(Value::as_bool) == Some(true) {
+                    usage = parse_usage(&value);
+                    finish_reason = value.get("done_reason").and_then(Value::as_str).map(str::to_string);
+                    break 'body;
+                }
+            }
+        }
+
+        Ok(ChatCompletionResponse {
+            usage: ensure_usage(usage, &request.messages, &content),
+            content,
+            provider: self.name.clone(),
+            model: request.model.clone(),
+            finish_reason,
+        })
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        let response = self.client.get(format!("{}/api/tags", self.base_url)).send_traced("ollama").await?;
+        if !response.status().is_success() {
+            return Err(provider_error(response).await);
+        }
+
+        let value: Value = response.json().await?;
+        let mut models: Vec<String> = value
+            .get("models")
+            .and_then(Value::as_array)
+            .map(|models| {
+                models
+                    .iter()
+                    .filter_map(|m| m.get("name").and_then(Value::as_str).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        models.sort();
+        Ok(models)
+    }
+
+    async fn health_check(&self) -> bool {
+        self.list_models().await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_usage_from_final_message() {
+        let done = parse_line(r#"{"model":"llama3.1","message":{"role":"assistant","content":""},"done":true,"prompt_eval_count":26,"eval_count":290}"#).unwrap();
+        assert_eq!(parse_usage(&done).map(|u| u.total_tokens), Some(316));
+
+        let partial = parse_line(r#"{"model":"llama3.1","message":{"role":"assistant","content":"The"},"done":false}"#).unwrap();
+        assert_eq!(parse_usage(&partial), None);
+
+        assert!(parse_line(r#"{"error":"model 'x' not found"}"#).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::provider::{ensure_usage, provider_error, ChatRequest, LineBuffer, LlmError, LlmProvider};
+use crate::models::ai::{ChatCompletionResponse, TokenUsage};
+use crate::utils::telemetry::TracedRequest;
+
+/// Client for servers implementing the OpenAI `/v1/chat/completions` API
+pub struct OpenAiCompatibleProvider {
+    client: reqwest::Client,
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiCompatibleProvider {
+    /// `base_url` includes the version prefix, e.g. `https://api.openai.com/v1`
+    pub fn new(name: &str, base_url: &str, api_key: Option<String>, timeout: Duration) -> Result<Self, LlmError> {
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            client,
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        })
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        let builder = self.client.post(format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    fn body(request: &ChatRequest, stream: bool) -> Value {
+        let mut body = json!({
+            "model": request.model,
+            "messages": request.messages,
+            "stream": stream,
+        });
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if stream {
+            // Ask for a final usage chunk; servers that don't support it ignore the flag
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+        body
+    }
+}
+
+fn parse_usage(value: &Value) -> Option<TokenUsage> {
+    let usage = value.get("usage").filter(|u| !u.is_null())?;
+    let prompt_tokens = usage.get("prompt_tokens").and_then(Value::as_u64).unwrap_or(0);
+    let completion_tokens = usage.get("completion_tokens").and_then(Value::as_u64).unwrap_or(0);
+    Some(TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: usage
+            .get("total_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(prompt_tokens + completion_tokens),
+        estimated: false,
+    })
+}
+
+/// One parsed `data:` line of a streaming response
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct StreamFrame {
+    pub delta: Option<String>,
+    pub finish_reason: Option<String>,
+    pub usage: Option<TokenUsage>,
+    pub model: Option<String>,
+    pub done: bool,
+}
+
+pub(crate) fn parse_stream_line(line: &str) -> Result<Option<StreamFrame>, LlmError> {
+    // Comments (": keep-alive") and event/id fields carry nothing we need
+    let Some(data) = line.strip_prefix("data:") else {
+        return Ok(None);
+    };
+    let data = data.trim();
+    if data == "[DONE]" {
+        return Ok(Some(StreamFrame { done: true, ..Default::default() }));
+    }
+
+    let value: Value = serde_json::from_str(data).map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+    if let Some(error) = value.get("error") {
+        return Err(LlmError::Provider {
+            status: 500,
+            message: error.get("message").and_then(Value::as_str).unwrap_or("stream error").to_string(),
+        });
+    }
+
+    let choice = value.get("choices").and_then(|c| c.get(0));
+    Ok(Some(StreamFrame {
+        delta: choice
+            .and_then(|c| c.pointer("/delta/content"))
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        finish_reason: choice
+            .and_then(|c| c.get("finish_reason"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        usage: parse_usage(&value),
+        model: value.get("model").and_then(Value::as_str).map(str::to_string),
+        done: false,
+    }))
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn chat(&self, request: &ChatRequest) -> Result<ChatCompletionResponse, LlmError> {
+        let response = self.post("/chat/completions").json(&Self::body(request, false)).send_traced("openai").await?;
+        if !response.status().is_success() {
+            return Err(provider_error(response).await);
+        }
+
+        let value: Value = response.json().await?;
+        let choice = value
+            .get("choices")
+            .and_then(|c| c.get(0))
+            .ok_or_else(|| LlmError::InvalidResponse("no choices in response".to_string()))?;
+        let content = choice
+            .pointer("/message/content")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(ChatCompletionResponse {
+            usage: ensure_usage(parse_usage(&value), &request.messages, &content),
+            content,
+            provider: self.name.clone(),
+            model: value
+                .get("model")
+                .and_then(Value::as_str)
+                .unwrap_or(&request.model)
+                .to_string(),
+            finish_reason: choice.get("finish_reason").and_then(Value::as_str).map(str::to_string),
+        })
+    }
+
+    async fn stream_chat(
+        &self,
+        request: &ChatRequest,
+        deltas: mpsc::Sender<String>,
+    ) -> Result<ChatCompletionResponse, LlmError> {
+        let mut response = self
+            .post("/chat/completions")
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(&Self::body(request, true))
+            .send_traced("openai")
+            .await?;
+        if !response.status().is_success() {
+            return Err(provider_error(response).await);
+        }
+
+        let mut content = String::new();
+        let mut usage = None;
+        let mut finish_reason = None;
+        let mut model = request.model.clone();
+        let mut lines = LineBuffer::default();
+
+        let mut body_done = false;
+        'body: while !body_done {
+            let batch = match response.chunk().await? {
+                Some(chunk) => lines.push(&chunk),
+                None => {
+                    // Flush a final line sent without a trailing newline
+                    body_done = true;
+                    std::mem::take(&mut lines).finish().into_iter().collect()
+                }
+            };
+            for line in batch {
+                let Some(frame) = parse_stream_line(&line)? else {
+                    continue;
+                };
+                if frame.done {
+                    break 'body;
+                }
+                if let Some(delta) = frame.delta {
+                    content.push_str(&delta);
+                    if deltas.send(delta).await.is_err() {
+                        // Client went away; stop pulling tokens we'd be billed for
+                        break 'body;
+                    }
+                }
+                usage = frame.usage.or(usage);
+                finish_reason = frame.finish_reason.or(finish_reason);
+                if let Some(m) = frame.model {
+                    model = m;
+                }
+            }
+        }
+
+        Ok(ChatCompletionResponse {
+            usage: ensure_usage(usage, &request.messages, &content),
+            content,
+            provider: self.name.clone(),
+            model,
+            finish_reason,
+        })
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        let mut builder = self.client.get(format!("{}/models", self.base_url));
+        if let Some(key) = &self.api_key {
+            builder = builder.bearer_auth(key);
+        }
+        let response = builder.send_traced("openai").await?;
+        if !response.status().is_success() {
+            return Err(provider_error(response).await);
+        }
+
+        let value: Value = response.json().await?;
+        let mut models: Vec<String> = value
+            .get("data")
+            .and_then(Value::as_array)
+            .map(|data| {
+                data.iter()
+                    .filter_map(|m| m.get("id").and_then(Value::as_str).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        models.sort();
+        Ok(models)
+    }
+
+    async fn health_check(&self) -> bool {
+        self.list_models().await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stream_deltas_usage_and_done() {
+        let frame = parse_stream_line(r#"data: {"model":"gpt-4o-mini","choices":[{"delta":{"content":"Hel"},"finish_reason":null}]}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.delta.as_deref(), Some("Hel"));
+        assert_eq!(frame.model.as_deref(), Some("gpt-4o-mini"));
+
+        let frame = parse_stream_line(r#"data: {"choices":[],"usage":{"prompt_tokens":9,"completion_tokens":3,"total_tokens":12}}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.usage.map(|u| u.total_tokens), Some(12));
+
+        assert!(parse_stream_line("data: [DONE]").unwrap().unwrap().done);
+        assert!(parse_stream_line(": keep-alive").unwrap().is_none());
+        assert!(parse_stream_line(r#"data: {"error":{"message":"rate limited"}}"#).is_err());
+    }
+}
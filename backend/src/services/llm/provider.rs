@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+use crate::models::ai::{ChatCompletionResponse, ChatMessage, TokenUsage};
+
+#[derive(Debug, Error)]
+pub enum LlmError {
+    #[error("LLM not configured: {0}")]
+    NotConfigured(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("Monthly token budget of {budget} exhausted ({used} used)")]
+    BudgetExceeded { budget: u64, used: u64 },
+    #[error("Request to provider failed: {0}")]
+    Http(String),
+    #[error("Provider returned {status}: {message}")]
+    Provider { status: u16, message: String },
+    #[error("Unexpected provider response: {0}")]
+    InvalidResponse(String),
+    #[error("Secret handling failed: {0}")]
+    Secret(String),
+    #[error("Database error: {0}")]
+    Database(String),
+}
+
+impl From<reqwest::Error> for LlmError {
+    fn from(e: reqwest::Error) -> Self {
+        LlmError::Http(e.to_string())
+    }
+}
+
+impl From<surrealdb::Error> for LlmError {
+    fn from(e: surrealdb::Error) -> Self {
+        LlmError::Database(e.to_string())
+    }
+}
+
+/// Provider-agnostic chat request
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Provider name used in responses and logs
+    fn name(&self) -> &str;
+
+    /// Single-shot chat completion
+    async fn chat(&self, request: &ChatRequest) -> Result<ChatCompletionResponse, LlmError>;
+
+    /// Streaming chat completion. Text deltas are sent on `deltas` as they
+    /// arrive; the assembled response (with usage) is returned at the end.
+    /// A closed receiver stops the stream early.
+    async fn stream_chat(
+        &self,
+        request: &ChatRequest,
+        deltas: mpsc::Sender<String>,
+    ) -> Result<ChatCompletionResponse, LlmError>;
+
+    /// Models the provider reports as available
+    async fn list_models(&self) -> Result<Vec<String>, LlmError>;
+
+    /// Whether the backend is reachable and answering
+    async fn health_check(&self) -> bool;
+}
+
+/// Rough token estimate (~4 characters per token) for providers that don't
+/// report usage, e.g. OpenAI-compatible servers that ignore `include_usage`
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Fill in usage from the prompt and completion text when the provider
+/// didn't return any
+pub fn ensure_usage(usage: Option<TokenUsage>, messages: &[ChatMessage], completion: &str) -> TokenUsage {
+    match usage {
+        Some(usage) if usage.total_tokens > 0 => usage,
+        _ => {
+            // Each message carries a few tokens of role/framing overhead
+            let prompt_tokens = messages
+                .iter()
+                .map(|m| estimate_tokens(&m.content) + 4)
+                .sum::<u64>();
+            let completion_tokens = estimate_tokens(completion);
+            TokenUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                estimated: true,
+            }
+        }
+    }
+}
+
+/// Turn an error response into `LlmError::Provider`, keeping the body short
+pub async fn provider_error(response: reqwest::Response) -> LlmError {
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    let message: String = body.chars().take(500).collect();
+    LlmError::Provider { status, message }
+}
+
+/// Splits a chunked HTTP body into complete lines (SSE and NDJSON framing)
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(pos) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if !line.is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+        lines
+    }
+
+    /// Whatever is left once the body ends without a trailing newline
+    pub fn finish(self) -> Option<String> {
+        let rest = String::from_utf8_lossy(&self.pending).trim().to_string();
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ai::ChatRole;
+
+    #[test]
+    fn line_buffer_reassembles_split_lines() {
+        let mut buffer = LineBuffer::default();
+        assert!(buffer.push(b"data: {\"a\":").is_empty());
+        assert_eq!(buffer.push(b"1}\r\n\r\ndata: [DO"), vec!["data: {\"a\":1}"]);
+        assert_eq!(buffer.push(b"NE]\n"), vec!["data: [DONE]"]);
+        assert_eq!(buffer.finish(), None);
+    }
+
+    #[test]
+    fn usage_is_estimated_when_missing() {
+        let messages = vec![ChatMessage {
+            role: ChatRole::User,
+            content: "12345678".to_string(),
+        }];
+        let usage = ensure_usage(None, &messages, "abcd");
+        assert_eq!(usage.prompt_tokens, 6);
+        assert_eq!(usage.completion_tokens, 1);
+        assert!(usage.estimated);
+
+        let reported = TokenUsage { prompt_tokens: 3, completion_tokens: 2, total_tokens: 5, estimated: false };
+        assert_eq!(ensure_usage(Some(reported), &messages, "abcd"), reported);
+    }
+}
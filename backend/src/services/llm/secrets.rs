@@ -0,0 +1,105 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use super::provider::LlmError;
+
+const PREFIX: &str = "v1:";
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM wrapper for secrets stored in the database (provider API keys).
+///
+/// The key comes from `ARCHER_SECRETS_KEY` (32 bytes, base64). Without it the
+/// key is derived from `JWT_SECRET` so development setups work, at the cost of
+/// tying stored secrets to the JWT secret.
+pub struct SecretBox {
+    cipher: Aes256Gcm,
+}
+
+impl SecretBox {
+    pub fn from_env() -> Result<Self, LlmError> {
+        match std::env::var("ARCHER_SECRETS_KEY") {
+            Ok(encoded) => {
+                let bytes = BASE64
+                    .decode(encoded.trim())
+                    .map_err(|e| LlmError::Secret(format!("ARCHER_SECRETS_KEY is not base64: {}", e)))?;
+                if bytes.len() != 32 {
+                    return Err(LlmError::Secret("ARCHER_SECRETS_KEY must decode to 32 bytes".to_string()));
+                }
+                Ok(Self::with_key(&bytes))
+            }
+            Err(_) => {
+                let jwt_secret = std::env::var("JWT_SECRET")
+                    .unwrap_or_else(|_| "archer-dev-secret-change-in-production-32chars!".to_string());
+                let mut hasher = Sha256::new();
+                hasher.update(b"archer-secrets:");
+                hasher.update(jwt_secret.as_bytes());
+                Ok(Self::with_key(&hasher.finalize()))
+            }
+        }
+    }
+
+    pub fn with_key(key: &[u8]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        }
+    }
+
+    /// Encrypt to `v1:<base64(nonce || ciphertext)>`
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, LlmError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| LlmError::Secret(e.to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", PREFIX, BASE64.encode(sealed)))
+    }
+
+    pub fn decrypt(&self, sealed: &str) -> Result<String, LlmError> {
+        let encoded = sealed
+            .strip_prefix(PREFIX)
+            .ok_or_else(|| LlmError::Secret("unknown secret format".to_string()))?;
+        let bytes = BASE64.decode(encoded).map_err(|e| LlmError::Secret(e.to_string()))?;
+        if bytes.len() <= NONCE_LEN {
+            return Err(LlmError::Secret("secret is truncated".to_string()));
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            // Wrong key or tampered value; don't leak details
+            .map_err(|_| LlmError::Secret("secret could not be decrypted".to_string()))?;
+        String::from_utf8(plaintext).map_err(|e| LlmError::Secret(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_tampering() {
+        let secrets = SecretBox::with_key(&[7u8; 32]);
+        let sealed = secrets.encrypt("sk-test-123").unwrap();
+        assert!(sealed.starts_with("v1:"));
+        assert!(!sealed.contains("sk-test-123"));
+        assert_eq!(secrets.decrypt(&sealed).unwrap(), "sk-test-123");
+
+        // Fresh nonce per encryption
+        assert_ne!(secrets.encrypt("sk-test-123").unwrap(), sealed);
+
+        let mut bytes = BASE64.decode(&sealed[3..]).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(secrets.decrypt(&format!("v1:{}", BASE64.encode(bytes))).is_err());
+
+        let other = SecretBox::with_key(&[8u8; 32]);
+        assert!(other.decrypt(&sealed).is_err());
+    }
+}
@@ -0,0 +1,489 @@
+use chrono::{Datelike, TimeZone, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use surrealdb::sql::Thing;
+use tokio::sync::mpsc;
+
+use super::ollama::OllamaProvider;
+use super::openai::OpenAiCompatibleProvider;
+use super::provider::{ChatRequest, LlmError, LlmProvider};
+use super::secrets::SecretBox;
+use crate::database::Database;
+use crate::models::ai::*;
+use crate::database::TracedQuery;
+
+const DEFAULT_TIMEOUT_SECONDS: u64 = 120;
+const DEFAULT_SELECTION_ID: &str = "default";
+
+/// Events sent to streaming clients
+#[derive(Debug)]
+pub enum StreamEvent {
+    Delta(String),
+    Done(ChatCompletionResponse),
+    Error(String),
+}
+
+/// A provider ready to serve one request, with the request filled in from
+/// the tenant's selection
+struct ResolvedChat {
+    provider: Box<dyn LlmProvider>,
+    provider_id: Thing,
+    request: ChatRequest,
+}
+
+/// Who is asking, for model selection and usage accounting
+pub struct LlmCaller<'a> {
+    pub user_id: &'a str,
+    pub tenant_id: Option<&'a str>,
+    /// Callers allowed to pick a provider/model other than the tenant's
+    pub can_override: bool,
+}
+
+pub struct LlmService {
+    db: Arc<Database>,
+}
+
+impl LlmService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ========================================================================
+    // PROVIDER CONFIGURATION
+    // ========================================================================
+
+    pub async fn list_providers(&self) -> Result<Vec<LlmProviderConfig>, LlmError> {
+        let mut response = self
+            .db
+            .query("SELECT * FROM llm_providers ORDER BY created_at ASC")
+            .traced("llm.list_providers")
+            .await?;
+        Ok(response.take(0)?)
+    }
+
+    pub async fn get_provider(&self, id: &str) -> Result<Option<LlmProviderConfig>, LlmError> {
+        Ok(self.db.select(("llm_providers", id)).await?)
+    }
+
+    pub async fn create_provider(
+        &self,
+        request: CreateLlmProviderRequest,
+        created_by: &str,
+    ) -> Result<LlmProviderConfig, LlmError> {
+        validate_base_url(&request.base_url)?;
+        if request.name.trim().is_empty() || request.default_model.trim().is_empty() {
+            return Err(LlmError::InvalidRequest("name and default_model are required".to_string()));
+        }
+
+        let api_key_encrypted = match request.api_key.as_deref().filter(|k| !k.is_empty()) {
+            Some(key) => Some(SecretBox::from_env()?.encrypt(key)?),
+            None => None,
+        };
+
+        let now = Utc::now();
+        let config = LlmProviderConfig {
+            id: None,
+            name: request.name.trim().to_string(),
+            kind: request.kind,
+            base_url: request.base_url.trim_end_matches('/').to_string(),
+            api_key_encrypted,
+            default_model: request.default_model,
+            models: request.models,
+            enabled: request.enabled,
+            timeout_seconds: request.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECONDS),
+            created_by: created_by.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<LlmProviderConfig> = self.db.create("llm_providers").content(&config).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| LlmError::Database("Failed to create provider".to_string()))
+    }
+
+    pub async fn update_provider(
+        &self,
+        id: &str,
+        request: UpdateLlmProviderRequest,
+    ) -> Result<LlmProviderConfig, LlmError> {
+        let mut config = self
+            .get_provider(id)
+            .await?
+            .ok_or_else(|| LlmError::NotFound(format!("Provider {} not found", id)))?;
+
+        if let Some(name) = request.name {
+            config.name = name;
+        }
+        if let Some(base_url) = request.base_url {
+            validate_base_url(&base_url)?;
+            config.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        if let Some(api_key) = request.api_key {
+            config.api_key_encrypted = if api_key.is_empty() {
+                None
+            } else {
+                Some(SecretBox::from_env()?.encrypt(&api_key)?)
+            };
+        }
+        if let Some(default_model) = request.default_model {
+            config.default_model = default_model;
+        }
+        if let Some(models) = request.models {
+            config.models = models;
+        }
+        if let Some(enabled) = request.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(timeout_seconds) = request.timeout_seconds {
+            config.timeout_seconds = timeout_seconds;
+        }
+        config.updated_at = Utc::now();
+
+        let updated: Option<LlmProviderConfig> = self
+            .db
+            .update(("llm_providers", id))
+            .content(&config)
+            .await?;
+        updated.ok_or_else(|| LlmError::NotFound(format!("Provider {} not found", id)))
+    }
+
+    /// Delete a provider that no tenant selection refers to
+    pub async fn delete_provider(&self, id: &str) -> Result<(), LlmError> {
+        let provider = Thing::from(("llm_providers", id));
+        let in_use: Vec<TenantModelSelection> = self
+            .db
+            .query("SELECT * FROM llm_tenant_settings WHERE provider_id = $provider")
+            .bind(("provider", &provider))
+            .traced("llm.delete_provider")
+            .await?
+            .take(0)?;
+        if !in_use.is_empty() {
+            return Err(LlmError::InvalidRequest(format!(
+                "Provider is selected by {} tenant setting(s)",
+                in_use.len()
+            )));
+        }
+
+        let _: Option<LlmProviderConfig> = self.db.delete(("llm_providers", id)).await?;
+        Ok(())
+    }
+
+    /// Instantiate the client for a stored provider
+    pub fn build_provider(config: &LlmProviderConfig) -> Result<Box<dyn LlmProvider>, LlmError> {
+        let timeout = Duration::from_secs(config.timeout_seconds.max(1));
+        Ok(match config.kind {
+            LlmProviderKind::OpenaiCompatible => {
+                let api_key = match &config.api_key_encrypted {
+                    Some(sealed) => Some(SecretBox::from_env()?.decrypt(sealed)?),
+                    None => None,
+                };
+                Box::new(OpenAiCompatibleProvider::new(&config.name, &config.base_url, api_key, timeout)?)
+            }
+            LlmProviderKind::Ollama => Box::new(OllamaProvider::new(&config.name, &config.base_url, timeout)?),
+        })
+    }
+
+    // ========================================================================
+    // TENANT MODEL SELECTION
+    // ========================================================================
+
+    /// The tenant's own selection, falling back to the global default
+    pub async fn get_selection(&self, tenant_id: Option<&str>) -> Result<Option<TenantModelSelection>, LlmError> {
+        if let Some(tenant) = tenant_id {
+            let selection: Option<TenantModelSelection> = self.db.select(("llm_tenant_settings", tenant)).await?;
+            if selection.is_some() {
+                return Ok(selection);
+            }
+        }
+        Ok(self.db.select(("llm_tenant_settings", DEFAULT_SELECTION_ID)).await?)
+    }
+
+    /// Set the selection for a tenant, or the global default when `tenant_id` is None
+    pub async fn set_selection(
+        &self,
+        tenant_id: Option<&str>,
+        request: SetTenantModelRequest,
+        updated_by: &str,
+    ) -> Result<TenantModelSelection, LlmError> {
+        let provider = self
+            .get_provider(&request.provider_id)
+            .await?
+            .ok_or_else(|| LlmError::NotFound(format!("Provider {} not found", request.provider_id)))?;
+        if let Some(model) = &request.model {
+            if !provider.models.is_empty() && !provider.models.contains(model) {
+                return Err(LlmError::InvalidRequest(format!(
+                    "Model '{}' is not offered by provider '{}'",
+                    model, provider.name
+                )));
+            }
+        }
+
+        let selection = TenantModelSelection {
+            id: None,
+            provider_id: Thing::from(("llm_providers", request.provider_id.as_str())),
+            model: request.model.unwrap_or(provider.default_model),
+            temperature: request.temperature,
+            max_tokens: request.max_tokens,
+            monthly_token_budget: request.monthly_token_budget,
+            updated_by: updated_by.to_string(),
+            updated_at: Utc::now(),
+        };
+
+        let key = tenant_id.unwrap_or(DEFAULT_SELECTION_ID);
+        let saved: Option<TenantModelSelection> = self
+            .db
+            .update(("llm_tenant_settings", key))
+            .content(&selection)
+            .await?;
+        saved.ok_or_else(|| LlmError::Database("Failed to save model selection".to_string()))
+    }
+
+    // ========================================================================
+    // CHAT
+    // ========================================================================
+
+    pub async fn chat(
+        &self,
+        caller: &LlmCaller<'_>,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, LlmError> {
+        let feature = request.feature.clone();
+        let resolved = self.resolve(caller, request).await?;
+
+        let started = Instant::now();
+        let result = resolved.provider.chat(&resolved.request).await;
+        self.record_usage(caller, &resolved, feature, false, started, &result).await;
+        result
+    }
+
+    /// Start a streaming completion. Configuration and budget errors are
+    /// returned directly; failures once streaming has begun arrive as
+    /// `StreamEvent::Error`.
+    pub async fn stream_chat(
+        self: Arc<Self>,
+        caller: LlmCaller<'_>,
+        request: ChatCompletionRequest,
+    ) -> Result<mpsc::Receiver<StreamEvent>, LlmError> {
+        let feature = request.feature.clone();
+        let resolved = self.resolve(&caller, request).await?;
+        let user_id = caller.user_id.to_string();
+        let tenant_id = caller.tenant_id.map(str::to_string);
+
+        let (events_tx, events_rx) = mpsc::channel::<StreamEvent>(64);
+        tokio::spawn(async move {
+            let (delta_tx, mut delta_rx) = mpsc::channel::<String>(64);
+            let started = Instant::now();
+
+            let forward = async {
+                while let Some(delta) = delta_rx.recv().await {
+                    if events_tx.send(StreamEvent::Delta(delta)).await.is_err() {
+                        // Dropping delta_rx makes the provider stop reading
+                        break;
+                    }
+                }
+            };
+            let (result, _) = tokio::join!(resolved.provider.stream_chat(&resolved.request, delta_tx), forward);
+
+            let caller = LlmCaller {
+                user_id: &user_id,
+                tenant_id: tenant_id.as_deref(),
+                can_override: false,
+            };
+            self.record_usage(&caller, &resolved, feature, true, started, &result).await;
+
+            let event = match result {
+                Ok(response) => StreamEvent::Done(response),
+                Err(e) => StreamEvent::Error(e.to_string()),
+            };
+            let _ = events_tx.send(event).await;
+        });
+
+        Ok(events_rx)
+    }
+
+    async fn resolve(&self, caller: &LlmCaller<'_>, request: ChatCompletionRequest) -> Result<ResolvedChat, LlmError> {
+        if request.messages.is_empty() {
+            return Err(LlmError::InvalidRequest("At least one message is required".to_string()));
+        }
+        if !caller.can_override && (request.provider_id.is_some() || request.model.is_some()) {
+            return Err(LlmError::Forbidden(
+                "Choosing a provider or model requires ai:manage".to_string(),
+            ));
+        }
+
+        let selection = self.get_selection(caller.tenant_id).await?;
+
+        let provider_config = match (&request.provider_id, &selection) {
+            (Some(id), _) => self.get_provider(id).await?,
+            (None, Some(selection)) => self.db.select(selection.provider_id.clone()).await?,
+            (None, None) => self
+                .list_providers()
+                .await?
+                .into_iter()
+                .find(|p| p.enabled),
+        }
+        .ok_or_else(|| LlmError::NotConfigured("No LLM provider is configured".to_string()))?;
+        if !provider_config.enabled {
+            return Err(LlmError::NotConfigured(format!("Provider '{}' is disabled", provider_config.name)));
+        }
+
+        // The tenant's model only applies when using the tenant's provider
+        let uses_selection = selection
+            .as_ref()
+            .is_some_and(|s| Some(&s.provider_id) == provider_config.id.as_ref());
+        let model = request
+            .model
+            .or_else(|| selection.as_ref().filter(|_| uses_selection).map(|s| s.model.clone()))
+            .unwrap_or_else(|| provider_config.default_model.clone());
+
+        if let Some(budget) = selection.as_ref().and_then(|s| s.monthly_token_budget) {
+            let used = self.month_to_date_tokens(caller.tenant_id).await?;
+            if used >= budget {
+                return Err(LlmError::BudgetExceeded { budget, used });
+            }
+        }
+
+        Ok(ResolvedChat {
+            provider: Self::build_provider(&provider_config)?,
+            provider_id: provider_config
+                .id
+                .clone()
+                .ok_or_else(|| LlmError::Database("Provider has no id".to_string()))?,
+            request: ChatRequest {
+                model,
+                messages: request.messages,
+                temperature: request.temperature.or(selection.as_ref().and_then(|s| s.temperature)),
+                max_tokens: request.max_tokens.or(selection.as_ref().and_then(|s| s.max_tokens)),
+            },
+        })
+    }
+
+    // ========================================================================
+    // USAGE ACCOUNTING
+    // ========================================================================
+
+    async fn record_usage(
+        &self,
+        caller: &LlmCaller<'_>,
+        resolved: &ResolvedChat,
+        feature: Option<String>,
+        streamed: bool,
+        started: Instant,
+        result: &Result<ChatCompletionResponse, LlmError>,
+    ) {
+        let usage = result.as_ref().map(|r| r.usage).unwrap_or_default();
+        let record = LlmUsageRecord {
+            id: None,
+            tenant_id: caller.tenant_id.map(str::to_string),
+            user_id: caller.user_id.to_string(),
+            provider_id: resolved.provider_id.clone(),
+            model: result
+                .as_ref()
+                .map(|r| r.model.clone())
+                .unwrap_or_else(|_| resolved.request.model.clone()),
+            feature,
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            estimated: usage.estimated,
+            streamed,
+            latency_ms: started.elapsed().as_millis() as u64,
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            created_at: Utc::now(),
+        };
+
+        // Accounting must never fail the user's request
+        let created: Result<Vec<LlmUsageRecord>, _> = self.db.create("llm_usage").content(&record).await;
+        if let Err(e) = created {
+            tracing::warn!("Failed to record LLM usage: {}", e);
+        }
+    }
+
+    async fn month_to_date_tokens(&self, tenant_id: Option<&str>) -> Result<u64, LlmError> {
+        #[derive(Deserialize)]
+        struct Total {
+            total: Option<u64>,
+        }
+
+        let now = Utc::now();
+        let month_start = Utc
+            .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+            .single()
+            .unwrap_or(now);
+        let total: Option<Total> = self
+            .db
+            .query("SELECT math::sum(total_tokens) AS total FROM llm_usage WHERE tenant_id = $tenant AND created_at >= $since GROUP ALL")
+            .bind(("tenant", tenant_id))
+            .bind(("since", month_start))
+            .traced("llm.month_to_date_tokens")
+            .await?
+            .take(0)?;
+        Ok(total.and_then(|t| t.total).unwrap_or(0))
+    }
+
+    pub async fn usage_summary(&self, tenant_id: Option<&str>, days: u32) -> Result<LlmUsageSummary, LlmError> {
+        #[derive(Deserialize)]
+        struct ModelRow {
+            model: String,
+            requests: u64,
+            prompt_tokens: Option<u64>,
+            completion_tokens: Option<u64>,
+            total_tokens: Option<u64>,
+        }
+        #[derive(Deserialize)]
+        struct FailedRow {
+            failed: u64,
+        }
+
+        let since = Utc::now() - chrono::Duration::days(days.max(1) as i64);
+        let mut response = self
+            .db
+            .query(
+                "SELECT model, count() AS requests, math::sum(prompt_tokens) AS prompt_tokens, \
+                 math::sum(completion_tokens) AS completion_tokens, math::sum(total_tokens) AS total_tokens \
+                 FROM llm_usage WHERE tenant_id = $tenant AND created_at >= $since GROUP BY model",
+            )
+            .query("SELECT count() AS failed FROM llm_usage WHERE tenant_id = $tenant AND created_at >= $since AND success = false GROUP ALL")
+            .bind(("tenant", tenant_id))
+            .bind(("since", since))
+            .traced("llm.usage_summary")
+            .await?;
+        let rows: Vec<ModelRow> = response.take(0)?;
+        let failed: Option<FailedRow> = response.take(1)?;
+
+        let mut by_model: Vec<ModelUsage> = rows
+            .into_iter()
+            .map(|row| ModelUsage {
+                model: row.model,
+                requests: row.requests,
+                prompt_tokens: row.prompt_tokens.unwrap_or(0),
+                completion_tokens: row.completion_tokens.unwrap_or(0),
+                total_tokens: row.total_tokens.unwrap_or(0),
+            })
+            .collect();
+        by_model.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+
+        let selection = self.get_selection(tenant_id).await?;
+        Ok(LlmUsageSummary {
+            tenant_id: tenant_id.map(str::to_string),
+            since,
+            requests: by_model.iter().map(|m| m.requests).sum(),
+            failed_requests: failed.map(|f| f.failed).unwrap_or(0),
+            total_tokens: by_model.iter().map(|m| m.total_tokens).sum(),
+            monthly_token_budget: selection.and_then(|s| s.monthly_token_budget),
+            month_to_date_tokens: self.month_to_date_tokens(tenant_id).await?,
+            by_model,
+        })
+    }
+}
+
+fn validate_base_url(url: &str) -> Result<(), LlmError> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(LlmError::InvalidRequest(format!("Invalid base URL '{}'", url))),
+    }
+}
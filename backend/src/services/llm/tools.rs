@@ -0,0 +1,469 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+
+use super::provider::LlmError;
+use crate::database::Database;
+use crate::models::ai::AgentToolSpec;
+use crate::models::cmdb::CISearchRequest;
+use crate::models::hld::{HLDProject, HLDSection};
+use crate::services::capacity_overview_service::{CapacityOverviewQuery, CapacityOverviewService};
+use crate::services::capacity_planner_service::{CapacityPlannerService, ConsolidationRequest};
+use crate::services::cmdb_service::CMDBService;
+use crate::database::TracedQuery;
+
+/// Tool output fed back to the model is cut to this many characters
+const MAX_RESULT_CHARS: usize = 6000;
+/// CMDB rows returned per query
+const MAX_CMDB_ROWS: u32 = 25;
+/// Sizing designs returned to the model
+const MAX_SIZING_DESIGNS: usize = 3;
+
+/// The fixed set of functions the assistant may call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentTool {
+    GetEnvironmentSummary,
+    RunSizing,
+    QueryCmdb,
+    DraftHldSection,
+}
+
+impl AgentTool {
+    pub const ALL: [AgentTool; 4] = [
+        AgentTool::GetEnvironmentSummary,
+        AgentTool::RunSizing,
+        AgentTool::QueryCmdb,
+        AgentTool::DraftHldSection,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AgentTool::GetEnvironmentSummary => "get_environment_summary",
+            AgentTool::RunSizing => "run_sizing",
+            AgentTool::QueryCmdb => "query_cmdb",
+            AgentTool::DraftHldSection => "draft_hld_section",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tool| tool.name() == name)
+    }
+
+    /// Tools that change stored data wait for approval before running
+    pub fn mutating(&self) -> bool {
+        matches!(self, AgentTool::DraftHldSection)
+    }
+
+    /// Permission the requesting user needs for the tool to be offered
+    pub fn required_permission(&self) -> &'static str {
+        match self {
+            AgentTool::QueryCmdb => "cmdb:read",
+            _ => "ai:use",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            AgentTool::GetEnvironmentSummary => {
+                "Capacity utilization, headroom and bottlenecks across migration projects, plus CMDB totals by class and status."
+            }
+            AgentTool::RunSizing => {
+                "Size target hardware for a migration: searches vendor node builds for the cheapest cluster designs that absorb the source workload. Give project_id, or source_clusters with explicit demand."
+            }
+            AgentTool::QueryCmdb => {
+                "Search configuration items by text, class, type, environment, location or support group. Read-only."
+            }
+            AgentTool::DraftHldSection => {
+                "Save a drafted section of a project's High-Level Design. The draft is stored for review; it does not change the published document. Requires human approval."
+            }
+        }
+    }
+
+    pub fn parameters(&self) -> Value {
+        match self {
+            AgentTool::GetEnvironmentSummary => json!({
+                "type": "object",
+                "properties": {
+                    "threshold_percent": { "type": "number", "description": "Utilization treated as full (default 80)" }
+                }
+            }),
+            AgentTool::RunSizing => json!({
+                "type": "object",
+                "properties": {
+                    "project_id": { "type": "string", "description": "Migration wizard project whose VMs are sized" },
+                    "source_clusters": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string" },
+                                "vm_count": { "type": "integer" },
+                                "vcpus": { "type": "integer" },
+                                "memory_gb": { "type": "number" },
+                                "storage_gb": { "type": "number" }
+                            },
+                            "required": ["name", "vm_count", "vcpus", "memory_gb", "storage_gb"]
+                        }
+                    },
+                    "constraints": {
+                        "type": "object",
+                        "description": "vcpu_to_core_ratio, memory_overcommit_ratio, *_headroom_percent, ha_spare_nodes, min/max_nodes_per_cluster"
+                    }
+                }
+            }),
+            AgentTool::QueryCmdb => json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "ci_class": { "type": "string", "description": "e.g. HARDWARE, SOFTWARE, SERVICE, NETWORK" },
+                    "ci_type": { "type": "string" },
+                    "environment": { "type": "string" },
+                    "location": { "type": "string" },
+                    "support_group": { "type": "string" },
+                    "page_size": { "type": "integer", "maximum": MAX_CMDB_ROWS }
+                }
+            }),
+            AgentTool::DraftHldSection => json!({
+                "type": "object",
+                "properties": {
+                    "project_id": { "type": "string" },
+                    "section_id": { "type": "string", "description": "Section id from the project's HLD template" },
+                    "content": { "type": "string", "description": "Section text in Markdown" }
+                },
+                "required": ["project_id", "section_id", "content"]
+            }),
+        }
+    }
+
+    pub fn spec(&self) -> AgentToolSpec {
+        AgentToolSpec {
+            name: self.name().to_string(),
+            description: self.description().to_string(),
+            parameters: self.parameters(),
+            mutating: self.mutating(),
+        }
+    }
+
+    /// Static risk assessment recorded on the agent action (1-100)
+    pub fn risk(&self) -> (u32, Vec<String>) {
+        match self {
+            AgentTool::GetEnvironmentSummary | AgentTool::QueryCmdb => (5, vec!["read-only".to_string()]),
+            AgentTool::RunSizing => (
+                10,
+                vec!["read-only".to_string(), "loads vendor catalog data".to_string()],
+            ),
+            AgentTool::DraftHldSection => (
+                35,
+                vec![
+                    "writes project design data".to_string(),
+                    "content is model-generated".to_string(),
+                ],
+            ),
+        }
+    }
+
+    /// What the tool touches, for the action's `target` field
+    pub fn target(&self, arguments: &Value) -> String {
+        let project = arguments.get("project_id").and_then(Value::as_str);
+        match (self, project) {
+            (AgentTool::GetEnvironmentSummary, _) => "capacity".to_string(),
+            (AgentTool::QueryCmdb, _) => "cmdb".to_string(),
+            (AgentTool::RunSizing, Some(project)) => format!("migration_wizard_project:{}", project),
+            (AgentTool::RunSizing, None) => "sizing".to_string(),
+            (AgentTool::DraftHldSection, Some(project)) => format!("projects:{}", project),
+            (AgentTool::DraftHldSection, None) => "hld".to_string(),
+        }
+    }
+}
+
+/// Who the tool runs on behalf of
+pub struct ToolContext<'a> {
+    pub db: Arc<Database>,
+    pub user_id: &'a str,
+    pub tenant_id: Option<&'a str>,
+    pub action: Option<Thing>,
+}
+
+/// A drafted HLD section awaiting review (table `hld_section_drafts`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HldSectionDraft {
+    pub id: Option<Thing>,
+    pub hld_project_id: Thing,
+    pub section_id: String,
+    pub content: String,
+    pub agent_action: Option<Thing>,
+    pub created_by: String,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnvironmentSummaryArgs {
+    threshold_percent: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DraftHldSectionArgs {
+    project_id: String,
+    section_id: String,
+    content: String,
+}
+
+fn invalid_arguments(tool: AgentTool, e: serde_json::Error) -> LlmError {
+    LlmError::InvalidRequest(format!("Invalid arguments for {}: {}", tool.name(), e))
+}
+
+/// Run a tool and return its output as compact JSON text
+pub async fn execute(tool: AgentTool, arguments: &Value, ctx: &ToolContext<'_>) -> Result<String, LlmError> {
+    let output = match tool {
+        AgentTool::GetEnvironmentSummary => environment_summary(arguments, ctx).await?,
+        AgentTool::RunSizing => run_sizing(arguments, ctx).await?,
+        AgentTool::QueryCmdb => query_cmdb(arguments, ctx).await?,
+        AgentTool::DraftHldSection => draft_hld_section(arguments, ctx).await?,
+    };
+    Ok(truncate(&output.to_string(), MAX_RESULT_CHARS))
+}
+
+async fn environment_summary(arguments: &Value, ctx: &ToolContext<'_>) -> Result<Value, LlmError> {
+    let args: EnvironmentSummaryArgs = serde_json::from_value(arguments.clone())
+        .map_err(|e| invalid_arguments(AgentTool::GetEnvironmentSummary, e))?;
+
+    let query = CapacityOverviewQuery {
+        threshold_percent: args.threshold_percent,
+        top: Some(5),
+    };
+    let overview = CapacityOverviewService::new((*ctx.db).clone())
+        .overview(ctx.tenant_id, &query)
+        .await
+        .map_err(|e| LlmError::Database(e.to_string()))?;
+    let cmdb = CMDBService::get_statistics(ctx.db.clone())
+        .await
+        .map_err(LlmError::Database)?;
+
+    Ok(json!({
+        "capacity": {
+            "threshold_percent": overview.threshold_percent,
+            "totals": overview.totals,
+            "bottlenecks": overview.bottlenecks,
+        },
+        "cmdb": cmdb,
+    }))
+}
+
+async fn run_sizing(arguments: &Value, ctx: &ToolContext<'_>) -> Result<Value, LlmError> {
+    let request: ConsolidationRequest =
+        serde_json::from_value(arguments.clone()).map_err(|e| invalid_arguments(AgentTool::RunSizing, e))?;
+    if request.project_id.is_none() && request.source_clusters.is_none() {
+        return Err(LlmError::InvalidRequest(
+            "run_sizing needs project_id or source_clusters".to_string(),
+        ));
+    }
+
+    let mut result = CapacityPlannerService::new((*ctx.db).clone())
+        .optimize_consolidation(request)
+        .await
+        .map_err(|e| LlmError::InvalidRequest(e.to_string()))?;
+    result.designs.truncate(MAX_SIZING_DESIGNS);
+    serde_json::to_value(result).map_err(|e| LlmError::InvalidResponse(e.to_string()))
+}
+
+async fn query_cmdb(arguments: &Value, ctx: &ToolContext<'_>) -> Result<Value, LlmError> {
+    let mut request: CISearchRequest =
+        serde_json::from_value(arguments.clone()).map_err(|e| invalid_arguments(AgentTool::QueryCmdb, e))?;
+    request.page = Some(1);
+    request.page_size = Some(request.page_size.unwrap_or(10).clamp(1, MAX_CMDB_ROWS));
+
+    let response = CMDBService::search_cis(ctx.db.clone(), request)
+        .await
+        .map_err(LlmError::Database)?;
+
+    // Only the fields an answer needs; full records would crowd the context
+    let items: Vec<Value> = response
+        .items
+        .iter()
+        .map(|ci| {
+            json!({
+                "ci_id": ci.ci_id,
+                "name": ci.name,
+                "ci_class": ci.ci_class,
+                "ci_type": ci.ci_type,
+                "status": ci.status,
+                "criticality": ci.criticality,
+                "environment": ci.environment,
+                "location": ci.location,
+                "support_group": ci.support_group,
+            })
+        })
+        .collect();
+    Ok(json!({ "total": response.total, "items": items }))
+}
+
+async fn draft_hld_section(arguments: &Value, ctx: &ToolContext<'_>) -> Result<Value, LlmError> {
+    let args: DraftHldSectionArgs = serde_json::from_value(arguments.clone())
+        .map_err(|e| invalid_arguments(AgentTool::DraftHldSection, e))?;
+    if args.content.trim().is_empty() {
+        return Err(LlmError::InvalidRequest("Draft content is empty".to_string()));
+    }
+
+    let hld_project: Option<HLDProject> = ctx
+        .db
+        .query("SELECT * FROM hld_projects WHERE project_id = $project LIMIT 1")
+        .bind(("project", Thing::from(("projects", args.project_id.as_str()))))
+        .traced("tools.draft_hld_section")
+        .await?
+        .take(0)?;
+    let hld_project = hld_project
+        .ok_or_else(|| LlmError::NotFound(format!("No HLD project for project {}", args.project_id)))?;
+    let hld_project_id = hld_project
+        .id
+        .clone()
+        .ok_or_else(|| LlmError::Database("HLD project has no id".to_string()))?;
+
+    let section: Option<HLDSection> = ctx
+        .db
+        .query("SELECT * FROM hld_sections WHERE template_id = $template AND section_id = $section LIMIT 1")
+        .bind(("template", &hld_project.template_id))
+        .bind(("section", &args.section_id))
+        .traced("tools.draft_hld_section")
+        .await?
+        .take(0)?;
+    let section = section.ok_or_else(|| {
+        LlmError::NotFound(format!("Section {} is not part of the project's HLD template", args.section_id))
+    })?;
+
+    let draft = HldSectionDraft {
+        id: None,
+        hld_project_id,
+        section_id: section.section_id.clone(),
+        content: args.content,
+        agent_action: ctx.action.clone(),
+        created_by: ctx.user_id.to_string(),
+        created_at: Utc::now(),
+    };
+    let created: Vec<HldSectionDraft> = ctx.db.create("hld_section_drafts").content(&draft).await?;
+    let created = created
+        .into_iter()
+        .next()
+        .ok_or_else(|| LlmError::Database("Failed to save HLD section draft".to_string()))?;
+
+    Ok(json!({
+        "draft_id": created.id.map(|id| id.to_string()),
+        "section": section.name,
+        "characters": created.content.chars().count(),
+    }))
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max_chars).collect();
+    cut.push_str("…[truncated]");
+    cut
+}
+
+/// What the model asked for in one turn
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentReply {
+    ToolCall {
+        thought: String,
+        tool: String,
+        arguments: Value,
+    },
+    Answer {
+        thought: String,
+        answer: String,
+    },
+}
+
+/// Read a model turn. The protocol is one JSON object per reply; anything
+/// that isn't a tool call is treated as the final answer so a model that
+/// ignores the protocol still produces a usable response.
+pub fn parse_reply(content: &str) -> AgentReply {
+    let json_text = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if end > start => &content[start..=end],
+        _ => "",
+    };
+    let value: Option<Value> = serde_json::from_str(json_text).ok();
+    let Some(value) = value.filter(Value::is_object) else {
+        return AgentReply::Answer {
+            thought: String::new(),
+            answer: content.trim().to_string(),
+        };
+    };
+
+    let thought = value
+        .get("thought")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if let Some(tool) = value.get("tool").and_then(Value::as_str) {
+        return AgentReply::ToolCall {
+            thought,
+            tool: tool.to_string(),
+            arguments: value.get("arguments").cloned().unwrap_or_else(|| json!({})),
+        };
+    }
+
+    let answer = value
+        .get("answer")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| content.trim().to_string());
+    AgentReply::Answer { thought, answer }
+}
+
+/// System prompt describing the tool protocol and the tools on offer
+pub fn system_prompt(tools: &[AgentTool]) -> String {
+    let specs: Vec<AgentToolSpec> = tools.iter().map(AgentTool::spec).collect();
+    format!(
+        "You are the Archer infrastructure assistant. You help engineers understand their environment, \
+         size migrations and write design documents.\n\n\
+         You can call these tools:\n{}\n\n\
+         Reply with exactly one JSON object and nothing else.\n\
+         To call a tool: {{\"thought\": \"why\", \"tool\": \"<name>\", \"arguments\": {{...}}}}\n\
+         To answer the user: {{\"thought\": \"why\", \"answer\": \"<markdown>\"}}\n\
+         Tool results arrive as messages starting with \"TOOL RESULT\". Call one tool per reply. \
+         Tools marked mutating need human approval and may be rejected. Never invent data a tool did not return.",
+        serde_json::to_string_pretty(&specs).unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tool_calls_and_answers() {
+        let reply = parse_reply(
+            "```json\n{\"thought\": \"need data\", \"tool\": \"query_cmdb\", \"arguments\": {\"query\": \"sql\"}}\n```",
+        );
+        assert_eq!(
+            reply,
+            AgentReply::ToolCall {
+                thought: "need data".to_string(),
+                tool: "query_cmdb".to_string(),
+                arguments: json!({ "query": "sql" }),
+            }
+        );
+
+        let reply = parse_reply(r#"{"thought": "done", "answer": "Three clusters."}"#);
+        assert_eq!(
+            reply,
+            AgentReply::Answer { thought: "done".to_string(), answer: "Three clusters.".to_string() }
+        );
+
+        // Plain prose falls back to an answer
+        assert!(matches!(parse_reply("Hello there"), AgentReply::Answer { answer, .. } if answer == "Hello there"));
+    }
+
+    #[test]
+    fn only_drafting_is_gated() {
+        for tool in AgentTool::ALL {
+            assert_eq!(AgentTool::from_name(tool.name()), Some(tool));
+            assert_eq!(tool.mutating(), tool == AgentTool::DraftHldSection);
+        }
+        assert_eq!(AgentTool::from_name("delete_everything"), None);
+    }
+}
@@ -1,12 +1,119 @@
 // Migration Wizard Service - RVTools Processing and Project Management
 use anyhow::{Result, Context};
 use calamine::{Reader, Xlsx, open_workbook, DataType};
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
+use std::time::{Duration, Instant};
 use surrealdb::sql::Thing;
 
 use crate::database::Database;
+use crate::database::TracedQuery;
 use crate::models::migration_wizard_models::*;
+use core_engine::units::Bytes;
+use crate::utils::pagination::{count_total, Page, Pagination};
+use crate::models::os_lifecycle::{OsFamily, SupportStatus};
+use crate::services::os_lifecycle_service::{OsLifecycleCatalog, OsLifecycleService};
+use crate::services::network_conflict_service::NetworkConflictService;
+use crate::services::sustainability_service::SustainabilityService;
+use crate::services::source_inventory;
+use crate::services::cpu_compatibility_service::{self, CpuCompatibilityService};
+use crate::services::guest_scan::{service as guest_scan_service, GuestScanService};
+use crate::services::naming_policy_service::NamingPolicyService;
+use crate::utils::cidr::Ipv4Cidr;
+use crate::models::usage_telemetry::UsageFeature;
+use crate::services::usage_telemetry_service;
+
+/// VMs written per INSERT statement when `RVTOOLS_INGEST_CHUNK_SIZE` is not set
+const DEFAULT_INGEST_CHUNK_SIZE: usize = 500;
+
+/// How RVTools VMs are written to the database
+#[derive(Debug, Clone)]
+pub struct IngestOptions {
+    /// VMs per batch; each batch is its own transaction
+    pub chunk_size: usize,
+}
+
+impl Default for IngestOptions {
+    fn default() -> Self {
+        Self { chunk_size: DEFAULT_INGEST_CHUNK_SIZE }
+    }
+}
+
+impl IngestOptions {
+    pub fn from_env() -> Self {
+        let chunk_size = std::env::var("RVTOOLS_INGEST_CHUNK_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_INGEST_CHUNK_SIZE);
+        Self { chunk_size }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct InsertedRecord {
+    id: Thing,
+}
+
+/// Below this many VMs, strategy scoring and placement demand stay on one thread
+const PARALLEL_MIN_VMS: usize = 256;
+
+/// From this many destination clusters, each VM's best-fit search is split across threads
+const PARALLEL_MIN_CLUSTERS: usize = 64;
+
+/// Where auto placement put one VM; indexes refer to the slices that were planned
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedPlacement {
+    pub vm_index: usize,
+    /// None when no cluster had room left for the VM
+    pub cluster_index: Option<usize>,
+}
+
+/// CPU, memory (MB) and storage (GB), as tracked for placements
+#[derive(Debug, Clone, Copy, Default)]
+struct Resources {
+    cpu: i32,
+    memory_mb: i32,
+    storage_gb: f64,
+}
+
+impl Resources {
+    /// What a cluster can hold after oversubscription
+    fn capacity_of(cluster: &MigrationWizardCluster) -> Self {
+        Self {
+            cpu: (cluster.total_cores as f64 * cluster.cpu_oversubscription_ratio) as i32,
+            memory_mb: cluster.allocatable_memory().as_mib() as i32,
+            storage_gb: cluster.storage_capacity().as_gib(),
+        }
+    }
+}
+
+/// Resources held by one existing placement
+#[derive(serde::Deserialize)]
+struct Allocation {
+    allocated_cpu: i32,
+    allocated_memory_mb: i32,
+    allocated_storage_gb: f64,
+}
+
+/// Stored in `migration_wizard_source_inventory`, one record per project
+#[derive(serde::Serialize)]
+struct StoredSourceInventory<'a> {
+    project_id: Thing,
+    hosts: &'a [SourceHost],
+    clusters: &'a [SourceClusterConfig],
+    datastores: &'a [SourceDatastore],
+    resource_pools: &'a [SourceResourcePool],
+    snapshots: &'a [SourceSnapshot],
+    tools: &'a [SourceVmTools],
+    updated_at: chrono::DateTime<Utc>,
+}
+
+/// A cluster with its allocated CPU, memory (MB), storage (GB) and VM count
+pub type ClusterUtilization = (MigrationWizardCluster, i32, i32, f64, usize);
 
 pub struct MigrationWizardService {
     db: Database,
@@ -40,6 +147,8 @@ impl MigrationWizardService {
             total_vms: 0,
             total_clusters: 0,
             wizard_step: 1,
+            tenant_id: None,
+            display_currency: None,
         };
 
         let created: Vec<MigrationWizardProject> = self
@@ -84,6 +193,7 @@ impl MigrationWizardService {
         let projects: Vec<MigrationWizardProject> = self
             .db
             .query(&query)
+            .traced("migration_wizard.list_projects")
             .await
             .context("Failed to list projects")?
             .take(0)
@@ -146,47 +256,166 @@ impl MigrationWizardService {
         project_id: &str,
         file_path: &Path,
         filename: String,
-    ) -> Result<usize> {
+    ) -> Result<RvToolsIngestMetrics> {
         tracing::info!("Processing RVTools file: {}", filename);
+        usage_telemetry_service::record(UsageFeature::RvtoolsParse);
+        let started = Instant::now();
 
         // Parse Excel file
-        let vms = self.parse_rvtools_excel(file_path)?;
-        let vm_count = vms.len();
+        let (vms, inventory) = self.parse_rvtools_excel(file_path)?;
+        let parse_time = started.elapsed();
+
+        tracing::info!(
+            "Parsed {} VMs, {} hosts, {} clusters, {} datastores and {} resource pools from RVTools file",
+            vms.len(),
+            inventory.hosts.len(),
+            inventory.clusters.len(),
+            inventory.datastores.len(),
+            inventory.resource_pools.len()
+        );
 
-        tracing::info!("Parsed {} VMs from RVTools file", vm_count);
+        let file_path = file_path.to_string_lossy().to_string();
+        let mut metrics = self.ingest_inventory(project_id, vms, &inventory, filename, Some(file_path)).await?;
+
+        metrics.parse_ms = parse_time.as_millis() as u64;
+        metrics.total_ms = started.elapsed().as_millis() as u64;
+        tracing::info!(
+            vms = metrics.vms_inserted,
+            batches = metrics.batches,
+            parse_ms = metrics.parse_ms,
+            insert_ms = metrics.insert_ms,
+            vms_per_second = metrics.vms_per_second,
+            "RVTools import written"
+        );
 
-        // Get project Thing ID
-        let project_thing = Thing::from(("migration_wizard_project", project_id));
+        Ok(metrics)
+    }
 
-        // Save VMs to database
-        for vm in vms {
-            let mut vm_data = vm;
-            vm_data.project_id = project_thing.clone();
-            
-            let _: Vec<MigrationWizardVM> = self
-                .db
-                .create("migration_wizard_vm")
-                .content(vm_data)
-                .await
-                .context("Failed to create VM record")?;
+    /// Write parsed VMs and source inventory to a project and record where
+    /// they came from. Shared by RVTools uploads and collector snapshots;
+    /// nothing is kept if a step fails.
+    pub async fn ingest_inventory(
+        &self,
+        project_id: &str,
+        vms: Vec<MigrationWizardVM>,
+        inventory: &SourceInventory,
+        source_name: String,
+        source_path: Option<String>,
+    ) -> Result<RvToolsIngestMetrics> {
+        let (inserted, mut metrics) = self.ingest_vms(project_id, vms, &IngestOptions::from_env()).await?;
+
+        if let Err(e) = self.save_source_inventory(project_id, inventory).await {
+            self.delete_records(&inserted).await;
+            return Err(e);
         }
-
-        // Update project with RVTools metadata
+        metrics.hosts_parsed = inventory.hosts.len();
+        metrics.clusters_parsed = inventory.clusters.len();
+        metrics.datastores_parsed = inventory.datastores.len();
+        metrics.resource_pools_parsed = inventory.resource_pools.len();
+        metrics.snapshots_parsed = inventory.snapshots.len();
+        metrics.tools_parsed = inventory.tools.len();
+
+        // Update project with the import source
         let update_data = serde_json::json!({
-            "rvtools_filename": filename,
+            "rvtools_filename": source_name,
             "rvtools_upload_date": Utc::now(),
-            "rvtools_file_path": file_path.to_string_lossy().to_string(),
-            "total_vms": vm_count as i32,
+            "rvtools_file_path": source_path,
+            "total_vms": metrics.vms_inserted as i32,
             "updated_at": Utc::now(),
         });
 
-        self.update_project(project_id, update_data).await?;
+        if let Err(e) = self.update_project(project_id, update_data).await {
+            self.delete_records(&inserted).await;
+            return Err(e);
+        }
+
+        Ok(metrics)
+    }
+
+    /// Write VMs to a project in batches of `options.chunk_size`.
+    ///
+    /// Each batch is one transaction. If a batch fails, the batches already
+    /// written by this call are deleted again, so the project never holds
+    /// half an import. Returns the IDs written, for rolling back later steps.
+    pub async fn ingest_vms(
+        &self,
+        project_id: &str,
+        mut vms: Vec<MigrationWizardVM>,
+        options: &IngestOptions,
+    ) -> Result<(Vec<Thing>, RvToolsIngestMetrics)> {
+        let chunk_size = options.chunk_size.max(1);
+        let project_thing = Thing::from(("migration_wizard_project", project_id));
+        for vm in &mut vms {
+            vm.project_id = project_thing.clone();
+        }
+
+        let started = Instant::now();
+        let mut inserted: Vec<Thing> = Vec::with_capacity(vms.len());
+        let mut batches = 0;
+
+        for (batch, chunk) in vms.chunks(chunk_size).enumerate() {
+            let written = self
+                .db
+                .query("BEGIN TRANSACTION; INSERT INTO migration_wizard_vm $vms RETURN id; COMMIT TRANSACTION;")
+                .bind(("vms", chunk))
+                .traced("migration_wizard.ingest_vms")
+                .await
+                .and_then(|mut response| response.take::<Vec<InsertedRecord>>(0));
+
+            match written {
+                Ok(records) => {
+                    inserted.extend(records.into_iter().map(|r| r.id));
+                    batches += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        batch,
+                        rolled_back = inserted.len(),
+                        "RVTools import batch failed; removing VMs already written: {}",
+                        e
+                    );
+                    self.delete_records(&inserted).await;
+                    return Err(anyhow::Error::new(e).context(format!(
+                        "Failed to write VM batch {} of {}; import rolled back",
+                        batch + 1,
+                        vms.len().div_ceil(chunk_size)
+                    )));
+                }
+            }
+        }
 
-        Ok(vm_count)
+        let insert_time = started.elapsed();
+        let metrics = RvToolsIngestMetrics {
+            vms_parsed: vms.len(),
+            vms_inserted: inserted.len(),
+            chunk_size,
+            batches,
+            parse_ms: 0,
+            insert_ms: insert_time.as_millis() as u64,
+            total_ms: insert_time.as_millis() as u64,
+            vms_per_second: throughput(inserted.len(), insert_time),
+            ..Default::default()
+        };
+        Ok((inserted, metrics))
+    }
+
+    /// Delete records written by a batch operation that did not complete
+    async fn delete_records(&self, inserted: &[Thing]) {
+        for chunk in inserted.chunks(DEFAULT_INGEST_CHUNK_SIZE) {
+            if let Err(e) = self
+                .db
+                .query("DELETE $ids")
+                .bind(("ids", chunk))
+                .traced("migration_wizard.delete_records")
+                .await
+            {
+                tracing::error!("Failed to roll back a partial batch write: {}", e);
+            }
+        }
     }
 
     /// Parse RVTools Excel file using calamine
-    fn parse_rvtools_excel(&self, file_path: &Path) -> Result<Vec<MigrationWizardVM>> {
+    fn parse_rvtools_excel(&self, file_path: &Path) -> Result<(Vec<MigrationWizardVM>, SourceInventory)> {
         let mut workbook: Xlsx<_> = open_workbook(file_path)
             .context("Failed to open Excel file")?;
 
@@ -217,7 +446,10 @@ impl MigrationWizardService {
             vms.push(vm);
         }
 
-        Ok(vms)
+        // vHost, vCluster, vDatastore, vRP, vSnapshot and vTools are optional
+        let inventory = source_inventory::parse_inventory(&mut workbook);
+
+        Ok((vms, inventory))
     }
 
     /// Parse a single VM row from Excel
@@ -289,34 +521,19 @@ impl MigrationWizardService {
         project_id: &str,
         filter: Option<VMFilter>,
     ) -> Result<Vec<MigrationWizardVM>> {
-        let mut query = format!(
-            "SELECT * FROM migration_wizard_vm WHERE project_id = type::thing('migration_wizard_project', '{}')",
-            project_id
+        let filter = filter.unwrap_or_default();
+        let query = format!(
+            "SELECT * FROM migration_wizard_vm WHERE {} ORDER BY name ASC",
+            Self::vm_conditions(&filter)
         );
 
-        if let Some(f) = &filter {
-            if let Some(cluster) = &f.cluster {
-                query.push_str(&format!(" AND cluster = '{}'", cluster));
-            }
-            if let Some(powerstate) = &f.powerstate {
-                query.push_str(&format!(" AND powerstate = '{}'", powerstate));
-            }
-        }
-
-        query.push_str(" ORDER BY name ASC");
-
-        if let Some(f) = &filter {
-            if let Some(limit) = f.limit {
-                query.push_str(&format!(" LIMIT {}", limit));
-            }
-            if let Some(offset) = f.offset {
-                query.push_str(&format!(" START {}", offset));
-            }
-        }
-
         let vms: Vec<MigrationWizardVM> = self
             .db
             .query(&query)
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .bind(("cluster", filter.cluster))
+            .bind(("powerstate", filter.powerstate))
+            .traced("migration_wizard.get_project_vms")
             .await
             .context("Failed to get VMs")?
             .take(0)
@@ -325,6 +542,47 @@ impl MigrationWizardService {
         Ok(vms)
     }
 
+    /// One page of a project's VMs with the total across all pages
+    pub async fn list_project_vms(
+        &self,
+        project_id: &str,
+        filter: VMFilter,
+        pagination: &Pagination<VmSort>,
+    ) -> Result<Page<MigrationWizardVM>> {
+        let where_clause = Self::vm_conditions(&filter);
+        let query = format!(
+            "SELECT count() FROM migration_wizard_vm WHERE {where_clause} GROUP ALL; \
+             SELECT * FROM migration_wizard_vm WHERE {where_clause} {} {};",
+            pagination.order_clause(),
+            pagination.limit_clause()
+        );
+
+        let mut response = self
+            .db
+            .query(&query)
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .bind(("cluster", filter.cluster))
+            .bind(("powerstate", filter.powerstate))
+            .traced("migration_wizard.list_project_vms")
+            .await
+            .context("Failed to list VMs")?;
+        let count: Vec<serde_json::Value> = response.take(0).context("Failed to count VMs")?;
+        let vms: Vec<MigrationWizardVM> = response.take(1).context("Failed to parse VMs")?;
+
+        Ok(pagination.page(vms, count_total(&count)))
+    }
+
+    fn vm_conditions(filter: &VMFilter) -> String {
+        let mut conditions = vec!["project_id = $project"];
+        if filter.cluster.is_some() {
+            conditions.push("cluster = $cluster");
+        }
+        if filter.powerstate.is_some() {
+            conditions.push("powerstate = $powerstate");
+        }
+        conditions.join(" AND ")
+    }
+
     /// Delete all VMs for a project (used when re-uploading RVTools)
     pub async fn delete_project_vms(&self, project_id: &str) -> Result<()> {
         let query = format!(
@@ -334,12 +592,63 @@ impl MigrationWizardService {
 
         self.db
             .query(&query)
+            .traced("migration_wizard.delete_project_vms")
             .await
             .context("Failed to delete VMs")?;
 
         Ok(())
     }
 
+    // =========================================================================
+    // SOURCE INVENTORY
+    // =========================================================================
+
+    /// Replace the hosts, clusters, datastores and resource pools stored for a
+    /// project; the record is keyed by the project id
+    async fn save_source_inventory(&self, project_id: &str, inventory: &SourceInventory) -> Result<()> {
+        let content = StoredSourceInventory {
+            project_id: Thing::from(("migration_wizard_project", project_id)),
+            hosts: &inventory.hosts,
+            clusters: &inventory.clusters,
+            datastores: &inventory.datastores,
+            resource_pools: &inventory.resource_pools,
+            snapshots: &inventory.snapshots,
+            tools: &inventory.tools,
+            updated_at: Utc::now(),
+        };
+
+        let _: Option<serde_json::Value> = self
+            .db
+            .update(("migration_wizard_source_inventory", project_id))
+            .content(content)
+            .await
+            .context("Failed to save source inventory")?;
+
+        Ok(())
+    }
+
+    /// Source inventory of a project; empty for imports without the sheets
+    pub async fn get_source_inventory(&self, project_id: &str) -> Result<SourceInventory> {
+        let inventory: Option<SourceInventory> = self
+            .db
+            .query("SELECT * FROM type::thing('migration_wizard_source_inventory', $id)")
+            .bind(("id", project_id))
+            .traced("migration_wizard.get_source_inventory")
+            .await
+            .context("Failed to get source inventory")?
+            .take(0)
+            .context("Failed to parse source inventory")?;
+
+        Ok(inventory.unwrap_or_default())
+    }
+
+    /// Per-cluster and per-storage-type summary of the source environment
+    pub async fn get_source_summary(&self, project_id: &str) -> Result<SourceEnvironmentSummary> {
+        let inventory = self.get_source_inventory(project_id).await?;
+        let vms = self.get_project_vms(project_id, None).await?;
+        Ok(source_inventory::summarize(&inventory, &vms))
+    }
+
     // =========================================================================
     // WIZARD STATE PERSISTENCE
     // =========================================================================
@@ -376,6 +685,7 @@ impl MigrationWizardService {
             .db
             .query("DELETE wizard_state_snapshot WHERE project_id = $project_id")
             .bind(("project_id", project_id))
+            .traced("migration_wizard.save_wizard_state")
             .await
             .context("Failed to delete old wizard state")?
             .take(0)
@@ -417,6 +727,7 @@ impl MigrationWizardService {
             .db
             .query("SELECT * FROM wizard_state_snapshot WHERE project_id = $project_id ORDER BY last_saved_at DESC LIMIT 1")
             .bind(("project_id", project_id))
+            .traced("migration_wizard.load_wizard_state")
             .await
             .context("Failed to load wizard state")?
             .take(0)
@@ -470,6 +781,7 @@ impl MigrationWizardService {
         let clusters: Vec<MigrationWizardCluster> = self
             .db
             .query(&query)
+            .traced("migration_wizard.get_project_clusters")
             .await
             .context("Failed to get clusters")?
             .take(0)
@@ -512,8 +824,8 @@ impl MigrationWizardService {
         updated.ok_or_else(|| anyhow::anyhow!("Cluster not found after update"))
     }
 
-    /// Delete a cluster
-    pub async fn delete_cluster(&self, cluster_id: &str) -> Result<()> {
+    /// Delete a cluster, returning the deleted record
+    pub async fn delete_cluster(&self, cluster_id: &str) -> Result<MigrationWizardCluster> {
         // Get cluster to find project_id
         let cluster = self.get_cluster(cluster_id).await?;
         let project_id = cluster.project_id.id.to_string();
@@ -530,7 +842,7 @@ impl MigrationWizardService {
         // TODO: Fix this - temporarily disabled for testing
         // self.update_cluster_count(project_id_str).await?;
 
-        Ok(())
+        Ok(cluster)
     }
 
     /// Update project's cluster count
@@ -553,40 +865,84 @@ impl MigrationWizardService {
 
     /// Analyze a single VM and recommend migration strategy
     pub async fn analyze_vm_strategy(&self, vm: &MigrationWizardVM) -> Result<StrategyRecommendation> {
+        let catalog = OsLifecycleService::new(self.db.clone()).catalog().await?;
+        Ok(Self::recommend_strategy(vm, &catalog, Utc::now().date_naive()))
+    }
+
+    /// Score a VM and pick a strategy using the OS lifecycle dataset
+    fn recommend_strategy(
+        vm: &MigrationWizardVM,
+        catalog: &OsLifecycleCatalog,
+        as_of: chrono::NaiveDate,
+    ) -> StrategyRecommendation {
         let mut score = 100.0;
         let mut warnings = Vec::new();
         let mut recommendations = Vec::new();
 
         // Analyze OS compatibility
-        if let Some(os) = &vm.os {
-            let os_lower = os.to_lowercase();
-            
-            // Check for known incompatible or legacy OS
-            if os_lower.contains("windows xp") || os_lower.contains("windows 2003") {
-                score -= 50.0;
-                warnings.push("Legacy OS detected - may require upgrade before migration".to_string());
-                recommendations.push("Consider upgrading OS to supported version".to_string());
-            } else if os_lower.contains("windows server 2008") {
-                score -= 30.0;
-                warnings.push("End-of-life OS - upgrade recommended".to_string());
-                recommendations.push("Upgrade to Windows Server 2019 or 2022".to_string());
-            } else if os_lower.contains("windows server") && (os_lower.contains("2012") || os_lower.contains("2016") || os_lower.contains("2019") || os_lower.contains("2022")) {
-                score += 0.0; // Fully compatible
-                recommendations.push("OS is compatible with Hyper-V - can proceed with Lift & Shift".to_string());
-            } else if os_lower.contains("linux") {
-                // Linux VMs - check for Integration Services compatibility
-                if os_lower.contains("ubuntu") || os_lower.contains("rhel") || os_lower.contains("centos") {
-                    score -= 5.0; // Minimal adjustment needed
-                    recommendations.push("Ensure Linux Integration Services are installed post-migration".to_string());
-                } else {
+        let os = catalog.classify(vm.os.as_deref(), as_of);
+        match &os.normalized {
+            None if vm.os.is_none() => {
+                score -= 20.0;
+                warnings.push("OS information not available - manual review required".to_string());
+            }
+            None => {
+                if vm.os.as_deref().unwrap_or_default().to_lowercase().contains("linux") {
                     score -= 15.0;
                     warnings.push("Linux distribution may require manual configuration".to_string());
                     recommendations.push("Verify Hyper-V Integration Services compatibility".to_string());
                 }
             }
-        } else {
-            score -= 20.0;
-            warnings.push("OS information not available - manual review required".to_string());
+            Some(normalized) => {
+                let is_windows = matches!(normalized.family, OsFamily::WindowsServer | OsFamily::WindowsClient);
+                let name = os
+                    .entry
+                    .as_ref()
+                    .map(|e| e.display_name())
+                    .unwrap_or_else(|| normalized.family.label().to_string());
+
+                match os.status {
+                    SupportStatus::EndOfSupport
+                        if matches!(normalized.version.as_deref(), Some("XP" | "2000" | "2003" | "2003 R2")) =>
+                    {
+                        score -= 50.0;
+                        warnings.push(format!("Legacy OS detected ({}) - may require upgrade before migration", name));
+                        recommendations.push("Consider upgrading OS to supported version".to_string());
+                    }
+                    SupportStatus::EndOfSupport => {
+                        score -= 30.0;
+                        warnings.push(format!("End-of-life OS ({}) - upgrade recommended", name));
+                        if is_windows {
+                            recommendations.push("Upgrade to Windows Server 2019 or 2022".to_string());
+                        } else {
+                            recommendations.push(format!("Upgrade to a supported {} release", normalized.family.label()));
+                        }
+                    }
+                    SupportStatus::ExtendedSupport => {
+                        score -= 10.0;
+                        let until = os
+                            .entry
+                            .as_ref()
+                            .and_then(|e| e.extended_end)
+                            .map(|d| d.format("%Y-%m-%d").to_string())
+                            .unwrap_or_else(|| "an unannounced date".to_string());
+                        warnings.push(format!("{} is only covered by extended support until {}", name, until));
+                        recommendations.push("Plan an OS upgrade alongside or soon after migration".to_string());
+                    }
+                    SupportStatus::Unknown if is_windows => {
+                        score -= 10.0;
+                        warnings.push("Windows release could not be identified - confirm version".to_string());
+                    }
+                    _ if is_windows => {
+                        recommendations.push("OS is compatible with Hyper-V - can proceed with Lift & Shift".to_string());
+                    }
+                    _ => {
+                        // Linux VMs - Integration Services ship in-box on current distributions
+                        score -= 5.0;
+                        recommendations.push("Ensure Linux Integration Services are installed post-migration".to_string());
+                    }
+                }
+            }
         }
 
         // Analyze resource configuration
@@ -640,28 +996,49 @@ impl MigrationWizardService {
             _ => {}
         }
 
-        Ok(StrategyRecommendation {
+        StrategyRecommendation {
             vm_name: vm.name.clone(),
             strategy: strategy.to_string(),
             confidence_score: score,
             warnings,
             recommendations,
-        })
+            migration_mode: None,
+            migration_mode_rationale: None,
+        }
     }
 
-    /// Analyze all VMs in a project and generate strategy recommendations
+    /// Analyze all VMs in a project and generate strategy recommendations,
+    /// each noting whether its VM can move live or must move cold
     pub async fn analyze_project_strategy(&self, project_id: &str) -> Result<Vec<StrategyRecommendation>> {
         let vms = self.get_project_vms(project_id, None).await?;
-        
-        let mut recommendations = Vec::new();
-        for vm in vms {
-            let recommendation = self.analyze_vm_strategy(&vm).await?;
-            recommendations.push(recommendation);
-        }
-
+        let catalog = OsLifecycleService::new(self.db.clone()).catalog().await?;
+        let as_of = Utc::now().date_naive();
+
+        let mut recommendations = Self::recommend_strategies(&vms, &catalog, as_of);
+        let compatibility = CpuCompatibilityService::new(self.db.clone()).analyze_vms(project_id, &vms).await?;
+        cpu_compatibility_service::annotate(&mut recommendations, &compatibility);
+        let scans = GuestScanService::new(self.db.clone()).list_scans(project_id).await?;
+        guest_scan_service::enrich(&mut recommendations, &scans);
         Ok(recommendations)
     }
 
+    /// Score every VM in parallel; results are ordered by VM name
+    pub fn recommend_strategies(
+        vms: &[MigrationWizardVM],
+        catalog: &OsLifecycleCatalog,
+        as_of: NaiveDate,
+    ) -> Vec<StrategyRecommendation> {
+        let mut recommendations: Vec<StrategyRecommendation> = vms
+            .par_iter()
+            .with_min_len(PARALLEL_MIN_VMS)
+            .map(|vm| Self::recommend_strategy(vm, catalog, as_of))
+            .collect();
+        // The database returns VMs in no particular order; the stable sort
+        // keeps repeated names in input order
+        recommendations.par_sort_by(|a, b| a.vm_name.cmp(&b.vm_name));
+        recommendations
+    }
+
     /// Get strategy statistics for a project
     pub async fn get_project_strategy_stats(&self, project_id: &str) -> Result<StrategyStats> {
         let recommendations = self.analyze_project_strategy(project_id).await?;
@@ -701,83 +1078,107 @@ impl MigrationWizardService {
     // VM PLACEMENT
     // =========================================================================
 
-    /// Create or update a manual VM placement
+    /// Create or update a manual VM placement.
+    ///
+    /// Takes two round trips: one read for the VM, the cluster and what the
+    /// cluster already holds, and one transaction that replaces the VM's
+    /// placement.
     pub async fn create_manual_placement(
         &self,
         project_id: &str,
         vm_id: &str,
         cluster_id: &str,
         strategy: Option<String>,
+        tags: BTreeMap<String, String>,
     ) -> Result<(MigrationWizardPlacement, Vec<String>)> {
-        let mut warnings = Vec::new();
+        let project = Thing::from(("migration_wizard_project", project_id));
+        let vm_thing = Thing::from(("migration_wizard_vm", vm_id));
+        let cluster_thing = Thing::from(("migration_wizard_cluster", cluster_id));
+
+        // The VM's own placement is about to be replaced, so it does not count
+        // against the cluster
+        let mut response = self
+            .db
+            .query(
+                "SELECT * FROM $vm; \
+                 SELECT * FROM $cluster; \
+                 SELECT allocated_cpu, allocated_memory_mb, allocated_storage_gb FROM migration_wizard_placement \
+                 WHERE cluster_id = $cluster AND vm_id != $vm",
+            )
+            .bind(("vm", &vm_thing))
+            .bind(("cluster", &cluster_thing))
+            .traced("migration_wizard.create_manual_placement")
+            .await?;
+        let vm: Option<MigrationWizardVM> = response.take(0)?;
+        let cluster: Option<MigrationWizardCluster> = response.take(1)?;
+        let allocations: Vec<Allocation> = response.take(2)?;
+        let vm = vm.ok_or_else(|| anyhow::anyhow!("VM not found"))?;
+        let cluster = cluster.ok_or_else(|| anyhow::anyhow!("Cluster not found"))?;
 
-        // Verify VM and cluster exist and belong to the project
-        let vm = self.get_vm_by_id(vm_id).await?;
-        let cluster = self.get_cluster(cluster_id).await?;
-        
         // Verify they belong to the same project
-        let vm_project_id = vm.project_id.id.to_string();
-        let cluster_project_id = cluster.project_id.id.to_string();
-        
-        if !vm_project_id.contains(project_id) || !cluster_project_id.contains(project_id) {
+        if vm.project_id.id.to_raw() != project_id || cluster.project_id.id.to_raw() != project_id {
             return Err(anyhow::anyhow!("VM and cluster must belong to the same project"));
         }
 
-        // Check if placement already exists and delete it
-        let existing_query = format!(
-            "SELECT * FROM migration_wizard_placement WHERE project_id = type::thing('migration_wizard_project', '{}') AND vm_id = type::thing('migration_wizard_vm', '{}')",
-            project_id, vm_id
-        );
-        
-        let existing: Vec<MigrationWizardPlacement> = self.db.query(&existing_query).await?.take(0)?;
-        
-        if let Some(old_placement) = existing.first() {
-            if let Some(ref old_id) = old_placement.id {
-                let id_str = old_id.id.to_string();
-                let _: Option<MigrationWizardPlacement> = self.db.delete(("migration_wizard_placement", id_str.as_str())).await?;
-            }
-        }
-
-        // Validate capacity (check current utilization)
-        let (capacity_ok, capacity_warnings) = self.validate_cluster_capacity(cluster_id, &vm).await?;
-        warnings.extend(capacity_warnings);
+        let used = allocations.iter().fold(Resources::default(), |total, a| Resources {
+            cpu: total.cpu + a.allocated_cpu,
+            memory_mb: total.memory_mb + a.allocated_memory_mb,
+            storage_gb: total.storage_gb + a.allocated_storage_gb,
+        });
+        let (capacity_ok, warnings) = Self::check_capacity(&cluster, &used, &vm);
 
         // Create placement
         let placement = MigrationWizardPlacement {
             id: None,
-            project_id: Thing::from(("migration_wizard_project", project_id)),
-            vm_id: Thing::from(("migration_wizard_vm", vm_id)),
-            cluster_id: Thing::from(("migration_wizard_cluster", cluster_id)),
+            project_id: project.clone(),
+            vm_id: vm_thing.clone(),
+            cluster_id: cluster_thing,
             strategy: strategy.unwrap_or_else(|| "manual".to_string()),
             confidence_score: Some(if capacity_ok { 100.0 } else { 70.0 }),
             warnings: if warnings.is_empty() { None } else { Some(warnings.clone()) },
             allocated_cpu: vm.cpus,
             allocated_memory_mb: vm.memory_mb,
-            allocated_storage_gb: (vm.provisioned_mb.unwrap_or(vm.memory_mb) as f64) / 1024.0,
+            allocated_storage_gb: vm.placement_storage().as_gib(),
+            tags,
             created_at: Utc::now(),
         };
 
-        let created: Vec<MigrationWizardPlacement> = self
+        let created: Option<MigrationWizardPlacement> = self
             .db
-            .create("migration_wizard_placement")
-            .content(placement)
-            .await?;
-
-        let created_placement = created
-            .into_iter()
-            .next()
-            .ok_or_else(|| anyhow::anyhow!("No placement returned after creation"))?;
+            .query(
+                "BEGIN TRANSACTION; \
+                 DELETE migration_wizard_placement WHERE project_id = $project AND vm_id = $vm; \
+                 CREATE migration_wizard_placement CONTENT $placement; \
+                 COMMIT TRANSACTION;",
+            )
+            .bind(("project", &project))
+            .bind(("vm", &vm_thing))
+            .bind(("placement", placement))
+            .traced("migration_wizard.create_manual_placement")
+            .await?
+            .take(1)?;
+
+        let created_placement = created.ok_or_else(|| anyhow::anyhow!("No placement returned after creation"))?;
 
         Ok((created_placement, warnings))
     }
 
     /// Delete a VM placement
-    pub async fn delete_placement(&self, placement_id: &str) -> Result<()> {
-        let _: Option<MigrationWizardPlacement> = self
+    pub async fn delete_placement(&self, placement_id: &str) -> Result<Option<MigrationWizardPlacement>> {
+        let deleted: Option<MigrationWizardPlacement> = self
             .db
             .delete(("migration_wizard_placement", placement_id))
             .await?;
-        Ok(())
+        Ok(deleted)
+    }
+
+    /// Get a single placement by ID
+    pub async fn get_placement(&self, placement_id: &str) -> Result<Option<MigrationWizardPlacement>> {
+        let placement: Option<MigrationWizardPlacement> = self
+            .db
+            .select(("migration_wizard_placement", placement_id))
+            .await?;
+        Ok(placement)
     }
 
     /// Get all placements for a project
@@ -786,226 +1187,350 @@ impl MigrationWizardService {
             "SELECT * FROM migration_wizard_placement WHERE project_id = type::thing('migration_wizard_project', '{}')",
             project_id
         );
-        let mut result = self.db.query(&query).await?;
+        let mut result = self.db.query(&query).traced("migration_wizard.get_project_placements").await?;
         let placements: Vec<MigrationWizardPlacement> = result.take(0)?;
         Ok(placements)
     }
 
-    /// Get a single VM by ID
-    async fn get_vm_by_id(&self, vm_id: &str) -> Result<MigrationWizardVM> {
-        let vm: Option<MigrationWizardVM> = self
+    /// VMs, destination clusters and placements of a project, in one round trip
+    pub async fn get_project_plan(
+        &self,
+        project_id: &str,
+    ) -> Result<(Vec<MigrationWizardVM>, Vec<MigrationWizardCluster>, Vec<MigrationWizardPlacement>)> {
+        let mut response = self
             .db
-            .select(("migration_wizard_vm", vm_id))
-            .await?;
-        vm.ok_or_else(|| anyhow::anyhow!("VM not found"))
+            .query(
+                "SELECT * FROM migration_wizard_vm WHERE project_id = $project ORDER BY name ASC; \
+                 SELECT * FROM migration_wizard_cluster WHERE project_id = $project ORDER BY created_at ASC; \
+                 SELECT * FROM migration_wizard_placement WHERE project_id = $project",
+            )
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("migration_wizard.get_project_plan")
+            .await
+            .context("Failed to load project plan")?;
+
+        Ok((response.take(0)?, response.take(1)?, response.take(2)?))
+    }
+
+    /// VMs, destination clusters, placements and source inventory of a
+    /// project, in one round trip
+    async fn load_placement_inputs(
+        &self,
+        project_id: &str,
+    ) -> Result<(Vec<MigrationWizardVM>, Vec<MigrationWizardCluster>, Vec<MigrationWizardPlacement>, SourceInventory)> {
+        let mut response = self
+            .db
+            .query(
+                "SELECT * FROM migration_wizard_vm WHERE project_id = $project ORDER BY name ASC; \
+                 SELECT * FROM migration_wizard_cluster WHERE project_id = $project ORDER BY created_at ASC; \
+                 SELECT * FROM migration_wizard_placement WHERE project_id = $project; \
+                 SELECT * FROM type::thing('migration_wizard_source_inventory', $id)",
+            )
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .bind(("id", project_id))
+            .traced("migration_wizard.load_placement_inputs")
+            .await
+            .context("Failed to load placement inputs")?;
+
+        let inventory: Option<SourceInventory> = response.take(3)?;
+        Ok((response.take(0)?, response.take(1)?, response.take(2)?, inventory.unwrap_or_default()))
     }
 
-    /// Validate if a cluster has capacity for a VM
-    async fn validate_cluster_capacity(&self, cluster_id: &str, vm: &MigrationWizardVM) -> Result<(bool, Vec<String>)> {
+    /// Whether a VM fits next to what a cluster already holds; one warning per resource that does not
+    fn check_capacity(cluster: &MigrationWizardCluster, used: &Resources, vm: &MigrationWizardVM) -> (bool, Vec<String>) {
         let mut warnings = Vec::new();
-        let cluster = self.get_cluster(cluster_id).await?;
-        
-        // Get current placements for this cluster
-        let cluster_id_str = cluster.id.as_ref()
-            .and_then(|thing| thing.id.to_string().split(':').nth(1).map(|s| s.to_string()))
-            .unwrap_or_else(|| cluster_id.to_string());
-        
-        let query = format!(
-            "SELECT * FROM migration_wizard_placement WHERE cluster_id = type::thing('migration_wizard_cluster', '{}')",
-            cluster_id_str
-        );
-        
-        let mut result = self.db.query(&query).await?;
-        let placements: Vec<MigrationWizardPlacement> = result.take(0)?;
-        
-        // Calculate current utilization
-        let mut total_cpu = 0;
-        let mut total_memory = 0;
-        let mut total_storage = 0.0;
-        
-        for p in &placements {
-            total_cpu += p.allocated_cpu;
-            total_memory += p.allocated_memory_mb;
-            total_storage += p.allocated_storage_gb;
-        }
-        
-        // Apply oversubscription
-        let available_cpu = (cluster.total_cores as f64 * cluster.cpu_oversubscription_ratio) as i32;
-        let available_memory = (cluster.memory_gb as f64 * 1024.0 * cluster.memory_oversubscription_ratio) as i32;
-        let available_storage = cluster.storage_tb * 1024.0;
-        
-        // Check capacity
-        let mut capacity_ok = true;
-        
-        if total_cpu + vm.cpus > available_cpu {
+        let available = Resources::capacity_of(cluster);
+
+        if used.cpu + vm.cpus > available.cpu {
             warnings.push(format!(
                 "CPU capacity warning: {} + {} > {} (with {}x oversubscription)",
-                total_cpu, vm.cpus, available_cpu, cluster.cpu_oversubscription_ratio
+                used.cpu, vm.cpus, available.cpu, cluster.cpu_oversubscription_ratio
             ));
-            capacity_ok = false;
         }
-        
-        if total_memory + vm.memory_mb > available_memory {
+
+        if used.memory_mb + vm.memory_mb > available.memory_mb {
             warnings.push(format!(
                 "Memory capacity warning: {} MB + {} MB > {} MB (with {}x oversubscription)",
-                total_memory, vm.memory_mb, available_memory, cluster.memory_oversubscription_ratio
+                used.memory_mb, vm.memory_mb, available.memory_mb, cluster.memory_oversubscription_ratio
             ));
-            capacity_ok = false;
         }
-        
-        let vm_storage = (vm.provisioned_mb.unwrap_or(vm.memory_mb) as f64) / 1024.0;
-        if total_storage + vm_storage > available_storage {
+
+        let vm_storage = vm.placement_storage().as_gib();
+        if used.storage_gb + vm_storage > available.storage_gb {
             warnings.push(format!(
                 "Storage capacity warning: {:.2} GB + {:.2} GB > {:.2} GB",
-                total_storage, vm_storage, available_storage
+                used.storage_gb, vm_storage, available.storage_gb
             ));
-            capacity_ok = false;
         }
-        
-        Ok((capacity_ok, warnings))
-    }
 
-    /// Automatic VM placement using Best Fit Decreasing bin-packing algorithm
-    pub async fn auto_place_vms(&self, project_id: &str) -> Result<(Vec<MigrationWizardPlacement>, Vec<String>)> {
-        let mut all_warnings = Vec::new();
-        let mut placements = Vec::new();
+        (warnings.is_empty(), warnings)
+    }
 
-        // Get all VMs and clusters for the project
-        let vms = self.get_project_vms(project_id, None).await?;
-        let clusters = self.get_project_clusters(project_id).await?;
+    /// Automatic VM placement using Best Fit Decreasing bin-packing algorithm.
+    ///
+    /// Reads the project once, plans against in-memory usage totals and
+    /// writes the new placements in batches, so the number of round trips
+    /// does not grow with the number of VMs. Also returns the resulting
+    /// cluster utilization.
+    pub async fn auto_place_vms(
+        &self,
+        project_id: &str,
+    ) -> Result<(Vec<MigrationWizardPlacement>, Vec<String>, Vec<ClusterUtilization>)> {
+        let mut warnings = Vec::new();
+        let (vms, clusters, mut existing_placements, inventory) = self.load_placement_inputs(project_id).await?;
 
         if clusters.is_empty() {
             return Err(anyhow::anyhow!("No destination clusters defined for this project"));
         }
 
-        // Sort VMs by resource requirements (descending) - Best Fit Decreasing
-        let mut sorted_vms = vms.clone();
-        sorted_vms.sort_by(|a, b| {
-            let a_score = a.cpus * 1000 + a.memory_mb;
-            let b_score = b.cpus * 1000 + b.memory_mb;
-            b_score.cmp(&a_score)
-        });
-
-        // Track cluster utilization
-        let mut cluster_usage: std::collections::HashMap<String, (i32, i32, f64)> = std::collections::HashMap::new();
-        for cluster in &clusters {
-            let cluster_id = cluster.id.as_ref()
-                .and_then(|thing| thing.id.to_string().split(':').nth(1).map(|s| s.to_string()))
-                .unwrap_or_default();
-            cluster_usage.insert(cluster_id, (0, 0, 0.0));
-        }
-
-        // Load existing placements to track current usage
-        let existing_placements = self.get_project_placements(project_id).await?;
-        for placement in &existing_placements {
-            let cluster_id = placement.cluster_id.id.to_string().split(':').nth(1).unwrap_or("").to_string();
-            if let Some(usage) = cluster_usage.get_mut(&cluster_id) {
-                usage.0 += placement.allocated_cpu;
-                usage.1 += placement.allocated_memory_mb;
-                usage.2 += placement.allocated_storage_gb;
-            }
-        }
-
-        // Place each VM in the best-fit cluster
-        for vm in &sorted_vms {
-            let vm_id = vm.id.as_ref()
-                .and_then(|thing| thing.id.to_string().split(':').nth(1).map(|s| s.to_string()))
-                .unwrap_or_default();
+        let source = source_inventory::summarize(&inventory, &vms);
 
-            // Skip if already placed
-            if existing_placements.iter().any(|p| {
-                p.vm_id.id.to_string().contains(&vm_id)
-            }) {
+        let project = Thing::from(("migration_wizard_project", project_id));
+        let now = Utc::now();
+        let mut new_placements = Vec::new();
+        for planned in Self::plan_auto_placement(&vms, &clusters, &existing_placements) {
+            let vm = &vms[planned.vm_index];
+            let cluster_id = planned.cluster_index.and_then(|i| clusters[i].id.clone());
+            let (Some(vm_id), Some(cluster_id)) = (vm.id.clone(), cluster_id) else {
+                warnings.push(format!("No suitable cluster found for VM: {} (CPU: {}, Memory: {} MB)",
+                    vm.name, vm.cpus, vm.memory_mb));
                 continue;
-            }
+            };
+            new_placements.push(MigrationWizardPlacement {
+                id: None,
+                project_id: project.clone(),
+                vm_id,
+                cluster_id,
+                strategy: "auto_placement".to_string(),
+                // The plan only uses clusters with room for the VM
+                confidence_score: Some(100.0),
+                warnings: None,
+                allocated_cpu: vm.cpus,
+                allocated_memory_mb: vm.memory_mb,
+                allocated_storage_gb: vm.placement_storage().as_gib(),
+                tags: Default::default(),
+                created_at: now,
+            });
+        }
 
-            // Find best-fit cluster (cluster with minimum remaining capacity after placing this VM)
-            let mut best_cluster: Option<(&MigrationWizardCluster, String)> = None;
-            let mut best_score = f64::MAX;
+        let placements = self.insert_placements(&new_placements).await?;
+        existing_placements.extend(placements.iter().cloned());
+        let utilization = Self::cluster_utilization(clusters, &existing_placements);
+        warnings.extend(Self::ha_reserve_warnings(&source, &vms, &existing_placements, &utilization));
 
-            for cluster in &clusters {
-                let cluster_id = cluster.id.as_ref()
-                    .and_then(|thing| thing.id.to_string().split(':').nth(1).map(|s| s.to_string()))
-                    .unwrap_or_default();
+        Ok((placements, warnings, utilization))
+    }
 
-                let usage = cluster_usage.get(&cluster_id).unwrap_or(&(0, 0, 0.0));
-                
-                let available_cpu = (cluster.total_cores as f64 * cluster.cpu_oversubscription_ratio) as i32;
-                let available_memory = (cluster.memory_gb as f64 * 1024.0 * cluster.memory_oversubscription_ratio) as i32;
-                let available_storage = cluster.storage_tb * 1024.0;
+    /// Destination clusters left without the failover headroom of the source
+    /// clusters their VMs come from. A VM from an HA cluster tolerating N
+    /// host failures needs N average source hosts of room at its destination.
+    pub fn ha_reserve_warnings(
+        source: &SourceEnvironmentSummary,
+        vms: &[MigrationWizardVM],
+        placements: &[MigrationWizardPlacement],
+        utilization: &[ClusterUtilization],
+    ) -> Vec<String> {
+        // Reserved cores and memory (GB) per HA source cluster
+        let reserves: HashMap<&str, (f64, f64)> = source
+            .clusters
+            .iter()
+            .filter(|c| c.ha_enabled == Some(true))
+            .filter_map(|c| {
+                let (cores, memory_gb) = c.average_host()?;
+                let hosts = c.failover_level.unwrap_or(1).max(1) as f64;
+                Some((c.name.as_str(), (cores * hosts, memory_gb * hosts)))
+            })
+            .collect();
+        if reserves.is_empty() {
+            return Vec::new();
+        }
 
-                let vm_storage = (vm.provisioned_mb.unwrap_or(vm.memory_mb) as f64) / 1024.0;
+        let source_of: HashMap<String, &str> = vms
+            .iter()
+            .filter_map(|vm| Some((vm.id.as_ref()?.id.to_raw(), vm.cluster.as_deref()?)))
+            .collect();
+
+        // Largest reserve among the source clusters feeding each destination
+        let mut needed: HashMap<String, (&str, (f64, f64))> = HashMap::new();
+        for placement in placements {
+            let Some(&name) = source_of.get(&placement.vm_id.id.to_raw()) else { continue };
+            let Some(&reserve) = reserves.get(name) else { continue };
+            let entry = needed.entry(placement.cluster_id.id.to_raw()).or_insert((name, reserve));
+            if reserve.1 > entry.1 .1 {
+                *entry = (name, reserve);
+            }
+        }
 
-                // Check if VM fits
-                if usage.0 + vm.cpus <= available_cpu &&
-                   usage.1 + vm.memory_mb <= available_memory &&
-                   usage.2 + vm_storage <= available_storage {
-                    
-                    // Calculate fit score (lower is better - tighter fit)
-                    let cpu_remaining = available_cpu - (usage.0 + vm.cpus);
-                    let memory_remaining = available_memory - (usage.1 + vm.memory_mb);
-                    let fit_score = (cpu_remaining as f64 / available_cpu as f64) + 
-                                   (memory_remaining as f64 / available_memory as f64);
-
-                    if fit_score < best_score {
-                        best_score = fit_score;
-                        best_cluster = Some((cluster, cluster_id.clone()));
-                    }
-                }
+        let mut warnings = Vec::new();
+        for (cluster, cpu_used, memory_used_mb, _, _) in utilization {
+            let key = cluster.id.as_ref().map(|thing| thing.id.to_raw()).unwrap_or_default();
+            let Some((name, (cores, memory_gb))) = needed.get(&key) else { continue };
+            let capacity = Resources::capacity_of(cluster);
+            let free_vcpu = (capacity.cpu - cpu_used) as f64;
+            let free_memory_gb = Bytes::from_mib((capacity.memory_mb - memory_used_mb) as f64).as_gib();
+            let reserve_vcpu = cores * cluster.cpu_oversubscription_ratio;
+            if free_vcpu < reserve_vcpu || free_memory_gb < *memory_gb {
+                warnings.push(format!(
+                    "Cluster {} has {:.0} vCPU and {:.0} GB memory free after placement, less than the HA reserve of source cluster {} ({:.0} vCPU, {:.0} GB)",
+                    cluster.name, free_vcpu, free_memory_gb, name, reserve_vcpu, memory_gb
+                ));
             }
+        }
+        warnings
+    }
 
-            // Place VM in best-fit cluster
-            if let Some((cluster, cluster_id)) = best_cluster {
-                match self.create_manual_placement(project_id, &vm_id, &cluster_id, Some("auto_placement".to_string())).await {
-                    Ok((placement, warnings)) => {
-                        // Update usage tracking
-                        if let Some(usage) = cluster_usage.get_mut(&cluster_id) {
-                            usage.0 += vm.cpus;
-                            usage.1 += vm.memory_mb;
-                            usage.2 += (vm.provisioned_mb.unwrap_or(vm.memory_mb) as f64) / 1024.0;
-                        }
-                        
-                        placements.push(placement);
-                        all_warnings.extend(warnings);
-                    }
-                    Err(e) => {
-                        all_warnings.push(format!("Failed to place VM {}: {}", vm.name, e));
-                    }
+    /// Write placements in batches, each in its own transaction. If a batch
+    /// fails, the batches already written are deleted again.
+    async fn insert_placements(&self, placements: &[MigrationWizardPlacement]) -> Result<Vec<MigrationWizardPlacement>> {
+        let mut inserted: Vec<MigrationWizardPlacement> = Vec::with_capacity(placements.len());
+        for chunk in placements.chunks(DEFAULT_INGEST_CHUNK_SIZE) {
+            let written = self
+                .db
+                .query("BEGIN TRANSACTION; INSERT INTO migration_wizard_placement $placements; COMMIT TRANSACTION;")
+                .bind(("placements", chunk))
+                .traced("migration_wizard.insert_placements")
+                .await
+                .and_then(|mut response| response.take::<Vec<MigrationWizardPlacement>>(0));
+
+            match written {
+                Ok(records) => inserted.extend(records),
+                Err(e) => {
+                    let ids: Vec<Thing> = inserted.into_iter().filter_map(|p| p.id).collect();
+                    self.delete_records(&ids).await;
+                    return Err(anyhow::Error::new(e).context("Failed to write placements; auto placement rolled back"));
                 }
-            } else {
-                all_warnings.push(format!("No suitable cluster found for VM: {} (CPU: {}, Memory: {} MB)", 
-                    vm.name, vm.cpus, vm.memory_mb));
             }
         }
-
-        Ok((placements, all_warnings))
+        Ok(inserted)
     }
 
-    /// Get cluster utilization statistics
-    pub async fn get_cluster_utilization(&self, project_id: &str) -> Result<Vec<(MigrationWizardCluster, i32, i32, f64, usize)>> {
-        let clusters = self.get_project_clusters(project_id).await?;
-        let placements = self.get_project_placements(project_id).await?;
+    /// Best Fit Decreasing over VMs not yet placed. Demand and each VM's
+    /// cluster search run in parallel; the packing itself is sequential, so
+    /// the plan is the same on every run. Ties go to the larger VM, then the
+    /// lower name, and to the first cluster.
+    pub fn plan_auto_placement(
+        vms: &[MigrationWizardVM],
+        clusters: &[MigrationWizardCluster],
+        existing: &[MigrationWizardPlacement],
+    ) -> Vec<PlannedPlacement> {
+        let cluster_keys: Vec<String> = clusters
+            .iter()
+            .map(|c| c.id.as_ref().map(|thing| thing.id.to_raw()).unwrap_or_default())
+            .collect();
+        let capacity: Vec<Resources> = clusters.iter().map(Resources::capacity_of).collect();
+
+        // Current usage from placements already made
+        let mut usage = vec![Resources::default(); clusters.len()];
+        let cluster_index: HashMap<&str, usize> =
+            cluster_keys.iter().enumerate().map(|(i, key)| (key.as_str(), i)).collect();
+        for placement in existing {
+            if let Some(&i) = cluster_index.get(placement.cluster_id.id.to_raw().as_str()) {
+                usage[i].cpu += placement.allocated_cpu;
+                usage[i].memory_mb += placement.allocated_memory_mb;
+                usage[i].storage_gb += placement.allocated_storage_gb;
+            }
+        }
+        let placed: HashSet<String> = existing.iter().map(|p| p.vm_id.id.to_raw()).collect();
+
+        let mut demand: Vec<(usize, Resources)> = vms
+            .par_iter()
+            .with_min_len(PARALLEL_MIN_VMS)
+            .enumerate()
+            .filter(|(_, vm)| {
+                vm.id.as_ref().map_or(true, |thing| !placed.contains(&thing.id.to_raw()))
+            })
+            .map(|(i, vm)| {
+                (i, Resources { cpu: vm.cpus, memory_mb: vm.memory_mb, storage_gb: vm.placement_storage().as_gib() })
+            })
+            .collect();
 
-        let mut result = Vec::new();
+        // Sort VMs by resource requirements (descending) - Best Fit Decreasing
+        demand.par_sort_by(|(a, a_demand), (b, b_demand)| {
+            let a_score = a_demand.cpu * 1000 + a_demand.memory_mb;
+            let b_score = b_demand.cpu * 1000 + b_demand.memory_mb;
+            b_score.cmp(&a_score).then_with(|| vms[*a].name.cmp(&vms[*b].name)).then(a.cmp(b))
+        });
 
-        for cluster in clusters {
-            let cluster_id = cluster.id.as_ref()
-                .and_then(|thing| thing.id.to_string().split(':').nth(1).map(|s| s.to_string()))
-                .unwrap_or_default();
+        let mut plan = Vec::with_capacity(demand.len());
+        for (vm_index, vm) in demand {
+            let cluster_index = Self::best_fit_cluster(&vm, &capacity, &usage);
+            if let Some(i) = cluster_index {
+                usage[i].cpu += vm.cpu;
+                usage[i].memory_mb += vm.memory_mb;
+                usage[i].storage_gb += vm.storage_gb;
+            }
+            plan.push(PlannedPlacement { vm_index, cluster_index });
+        }
+        plan
+    }
+
+    /// Cluster with the least headroom left after taking the VM (tightest fit)
+    fn best_fit_cluster(vm: &Resources, capacity: &[Resources], usage: &[Resources]) -> Option<usize> {
+        let fit = |i: usize| -> Option<(usize, f64)> {
+            let (available, used) = (&capacity[i], &usage[i]);
+            let fits = used.cpu + vm.cpu <= available.cpu
+                && used.memory_mb + vm.memory_mb <= available.memory_mb
+                && used.storage_gb + vm.storage_gb <= available.storage_gb;
+            if !fits {
+                return None;
+            }
+            let cpu_remaining = available.cpu - (used.cpu + vm.cpu);
+            let memory_remaining = available.memory_mb - (used.memory_mb + vm.memory_mb);
+            let fit_score = (cpu_remaining as f64 / available.cpu as f64)
+                + (memory_remaining as f64 / available.memory_mb as f64);
+            Some((i, fit_score))
+        };
+        let tightest = |a: &(usize, f64), b: &(usize, f64)| -> Ordering { a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)) };
 
-            let cluster_placements: Vec<_> = placements.iter()
-                .filter(|p| p.cluster_id.id.to_string().contains(&cluster_id))
-                .collect();
+        let best = if capacity.len() >= PARALLEL_MIN_CLUSTERS {
+            (0..capacity.len()).into_par_iter().filter_map(fit).min_by(tightest)
+        } else {
+            (0..capacity.len()).filter_map(fit).min_by(tightest)
+        };
+        best.map(|(i, _)| i)
+    }
 
-            let total_cpu: i32 = cluster_placements.iter().map(|p| p.allocated_cpu).sum();
-            let total_memory: i32 = cluster_placements.iter().map(|p| p.allocated_memory_mb).sum();
-            let total_storage: f64 = cluster_placements.iter().map(|p| p.allocated_storage_gb).sum();
-            let vm_count = cluster_placements.len();
+    /// Get cluster utilization statistics
+    pub async fn get_cluster_utilization(&self, project_id: &str) -> Result<Vec<ClusterUtilization>> {
+        let mut response = self
+            .db
+            .query(
+                "SELECT * FROM migration_wizard_cluster WHERE project_id = $project ORDER BY created_at ASC; \
+                 SELECT * FROM migration_wizard_placement WHERE project_id = $project",
+            )
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("migration_wizard.get_cluster_utilization")
+            .await
+            .context("Failed to load cluster utilization")?;
+        let clusters: Vec<MigrationWizardCluster> = response.take(0)?;
+        let placements: Vec<MigrationWizardPlacement> = response.take(1)?;
+        Ok(Self::cluster_utilization(clusters, &placements))
+    }
 
-            result.push((cluster, total_cpu, total_memory, total_storage, vm_count));
+    /// Allocated CPU, memory (MB), storage (GB) and VM count per cluster, in cluster order
+    pub fn cluster_utilization(
+        clusters: Vec<MigrationWizardCluster>,
+        placements: &[MigrationWizardPlacement],
+    ) -> Vec<ClusterUtilization> {
+        // One pass over the placements, in their own order, so storage sums
+        // come out the same on every run
+        let mut totals: HashMap<String, (i32, i32, f64, usize)> = HashMap::new();
+        for p in placements {
+            let total = totals.entry(p.cluster_id.id.to_raw()).or_default();
+            total.0 += p.allocated_cpu;
+            total.1 += p.allocated_memory_mb;
+            total.2 += p.allocated_storage_gb;
+            total.3 += 1;
         }
 
-        Ok(result)
+        clusters
+            .into_iter()
+            .map(|cluster| {
+                let key = cluster.id.as_ref().map(|thing| thing.id.to_raw()).unwrap_or_default();
+                let (cpu, memory, storage, vm_count) = totals.get(&key).copied().unwrap_or_default();
+                (cluster, cpu, memory, storage, vm_count)
+            })
+            .collect()
     }
 
     // =========================================================================
@@ -1046,6 +1571,7 @@ impl MigrationWizardService {
             } else {
                 Some(validation_errors)
             },
+            tags: request.tags,
             created_at: Utc::now(),
         };
 
@@ -1075,6 +1601,7 @@ impl MigrationWizardService {
         let mappings: Vec<crate::models::migration_wizard_models::MigrationWizardNetworkMapping> = self
             .db
             .query(&query)
+            .traced("migration_wizard.get_project_network_mappings")
             .await
             .context("Failed to query network mappings")?
             .take(0)
@@ -1098,6 +1625,7 @@ impl MigrationWizardService {
         let mut existing: Vec<crate::models::migration_wizard_models::MigrationWizardNetworkMapping> = self
             .db
             .query(&query)
+            .traced("migration_wizard.update_network_mapping")
             .await
             .context("Failed to query existing network mapping")?
             .take(0)
@@ -1124,19 +1652,22 @@ impl MigrationWizardService {
     }
 
     /// Delete a network mapping
-    pub async fn delete_network_mapping(&self, mapping_id: &str) -> Result<()> {
+    pub async fn delete_network_mapping(
+        &self,
+        mapping_id: &str,
+    ) -> Result<Option<crate::models::migration_wizard_models::MigrationWizardNetworkMapping>> {
         let mapping_thing = surrealdb::sql::Thing {
             tb: "migration_wizard_network_mapping".to_string(),
             id: surrealdb::sql::Id::String(mapping_id.to_string()),
         };
 
-        let _deleted: Option<crate::models::migration_wizard_models::MigrationWizardNetworkMapping> = self
+        let deleted: Option<crate::models::migration_wizard_models::MigrationWizardNetworkMapping> = self
             .db
             .delete(mapping_thing)
             .await
             .context("Failed to delete network mapping")?;
 
-        Ok(())
+        Ok(deleted)
     }
 
     /// Validate all network mappings for a project
@@ -1177,12 +1708,12 @@ impl MigrationWizardService {
             }
         }
 
-        // Check for subnet overlaps
+        // Check for subnet overlaps, including one subnet nested in another
         for (i, mapping1) in mappings.iter().enumerate() {
             if let Some(ref subnet1) = mapping1.destination_subnet {
                 for mapping2 in mappings.iter().skip(i + 1) {
                     if let Some(ref subnet2) = mapping2.destination_subnet {
-                        if self.subnets_overlap(subnet1, subnet2) {
+                        if subnets_overlap(subnet1, subnet2) {
                             let mapping_id = mapping1.id.as_ref()
                                 .and_then(|t| match &t.id {
                                     surrealdb::sql::Id::String(s) => Some(s.clone()),
@@ -1214,16 +1745,28 @@ impl MigrationWizardService {
             }
         }
 
+        // Other projects in the tenant targeting the same VLANs or subnets
+        let cross_project_conflicts = NetworkConflictService::new(self.db.clone())
+            .conflicts_for(project_id, &mappings)
+            .await?;
+        warnings.extend(cross_project_conflicts.iter().map(|c| c.message.clone()));
+
+        let naming_violations = NamingPolicyService::new(self.db.clone())
+            .network_violations(project_id, &mappings)
+            .await?;
+
         let total = mappings.len();
         let invalid_count = total - valid_count;
 
         Ok(NetworkValidationResult {
-            is_valid: errors.is_empty(),
+            is_valid: errors.is_empty() && naming_violations.is_empty(),
             total_mappings: total,
             valid_mappings: valid_count,
             invalid_mappings: invalid_count,
             errors,
             warnings,
+            cross_project_conflicts,
+            naming_violations,
         })
     }
 
@@ -1267,39 +1810,14 @@ impl MigrationWizardService {
         (errors.is_empty(), errors)
     }
 
-    /// Check if two subnets overlap
-    fn subnets_overlap(&self, subnet1: &str, subnet2: &str) -> bool {
-        // Simple overlap check (for production, use ipnetwork crate)
-        // This is a simplified version
-        if let (Some(net1), Some(net2)) = (self.parse_cidr(subnet1), self.parse_cidr(subnet2)) {
-            // Check if network addresses match (simplified check)
-            net1.0 == net2.0 && net1.1 == net2.1
-        } else {
-            false
-        }
-    }
-
-    /// Parse CIDR notation (simplified)
-    fn parse_cidr(&self, cidr: &str) -> Option<(String, u8)> {
-        let parts: Vec<&str> = cidr.split('/').collect();
-        if parts.len() == 2 {
-            if let Ok(prefix) = parts[1].parse::<u8>() {
-                return Some((parts[0].to_string(), prefix));
-            }
-        }
-        None
-    }
-
     /// Validate CIDR notation
     fn is_valid_cidr(&self, cidr: &str) -> bool {
-        self.parse_cidr(cidr).is_some()
+        cidr.parse::<Ipv4Cidr>().is_ok()
     }
 
-    /// Validate IP address (simplified)
+    /// Validate IP address
     fn is_valid_ip(&self, ip: &str) -> bool {
-        ip.split('.').count() == 4 && ip.split('.').all(|part| {
-            part.parse::<u8>().is_ok()
-        })
+        ip.trim().parse::<std::net::Ipv4Addr>().is_ok()
     }
 
     /// Discover networks from RVTools data for auto-populating VLAN dropdowns
@@ -1926,7 +2444,27 @@ impl MigrationWizardService {
             hld.push_str("5. Network Design\n");
         }
         hld.push_str("6. Migration Approach\n");
-        hld.push_str("7. Risks and Mitigation\n\n");
+        hld.push_str("7. Risks and Mitigation\n");
+
+        let services_estimate = crate::services::estimation_service::EstimationService::new(self.db.clone())
+            .get_project_estimate(project_id)
+            .await?;
+        if services_estimate.is_some() {
+            hld.push_str("8. Professional Services Estimate\n");
+        }
+
+        let sustainability = match SustainabilityService::new(self.db.clone()).generate_report(project_id).await {
+            Ok(report) if report.current.hosts > 0 || report.proposed.hosts > 0 => Some(report),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Skipping sustainability section: {}", e);
+                None
+            }
+        };
+        if sustainability.is_some() {
+            hld.push_str("9. Sustainability Impact\n");
+        }
+        hld.push_str("\n");
         hld.push_str("---\n\n");
         
         // Executive Summary
@@ -1956,8 +2494,8 @@ impl MigrationWizardService {
             
             // Calculate totals
             let total_cpu: i32 = vms.iter().map(|vm| vm.cpus).sum();
-            let total_memory_gb: f64 = vms.iter().map(|vm| vm.memory_mb as f64 / 1024.0).sum();
-            let total_storage_gb: f64 = vms.iter().map(|vm| vm.provisioned_mb.unwrap_or(0) as f64 / 1024.0).sum();
+            let total_memory_gb = vms.iter().map(|vm| vm.memory()).sum::<Bytes>().as_gib();
+            let total_storage_gb = vms.iter().filter_map(|vm| vm.provisioned()).sum::<Bytes>().as_gib();
             
             hld.push_str("#### Resource Summary\n\n");
             hld.push_str(&format!("- **Total vCPUs:** {} cores\n", total_cpu));
@@ -1971,6 +2509,83 @@ impl MigrationWizardService {
             hld.push_str("#### Power State Distribution\n\n");
             hld.push_str(&format!("- **Powered On:** {}\n", powered_on));
             hld.push_str(&format!("- **Powered Off:** {}\n\n", powered_off));
+
+            // Guest OS support exposure
+            let catalog = OsLifecycleService::new(self.db.clone()).catalog().await?;
+            let exposure = OsLifecycleService::build_exposure_report(project_id, &vms, &catalog, Utc::now().date_naive());
+
+            hld.push_str("#### Operating System Support Status\n\n");
+            hld.push_str(&format!(
+                "As of {}: **{}** supported, **{}** on extended support only, **{}** past end of support, **{}** unknown.\n\n",
+                exposure.as_of.format("%Y-%m-%d"),
+                exposure.supported,
+                exposure.extended_support,
+                exposure.end_of_support,
+                exposure.unknown
+            ));
+            hld.push_str("| Operating System | Status | VMs | Mainstream End | Extended End |\n");
+            hld.push_str("|------------------|--------|-----|----------------|--------------|\n");
+            let fmt_date = |d: Option<chrono::NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string());
+            for row in &exposure.by_os {
+                hld.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    row.os,
+                    row.status.label(),
+                    row.vm_count,
+                    fmt_date(row.mainstream_end),
+                    fmt_date(row.extended_end)
+                ));
+            }
+            hld.push_str("\n");
+
+            // Hosts, HA/DRS settings and storage from the vHost, vCluster
+            // and vDatastore sheets
+            let source = source_inventory::summarize(&self.get_source_inventory(project_id).await?, &vms);
+            if source.hosts > 0 || source.datastores > 0 {
+                hld.push_str("### Source Clusters
+
+");
+                hld.push_str("| Cluster | Hosts | Cores | Memory (GB) | CPU Models | HA | DRS | VMs | vCPU:Core |\n");
+                hld.push_str("|---------|-------|-------|-------------|------------|----|-----|-----|-----------|\n");
+                let on_off = |flag: Option<bool>| match flag {
+                    Some(true) => "On",
+                    Some(false) => "Off",
+                    None => "-",
+                };
+                for cluster in &source.clusters {
+                    let ha = match (cluster.ha_enabled, cluster.failover_level) {
+                        (Some(true), Some(level)) => format!("On (N+{})", level),
+                        (flag, _) => on_off(flag).to_string(),
+                    };
+                    hld.push_str(&format!(
+                        "| {} | {} | {} | {:.0} | {} | {} | {} | {} | {:.1} |\n",
+                        cluster.name,
+                        cluster.hosts,
+                        cluster.cores,
+                        cluster.memory_gb,
+                        if cluster.cpu_models.is_empty() { "-".to_string() } else { cluster.cpu_models.join(", ") },
+                        ha,
+                        cluster.drs_behavior.as_deref().filter(|_| cluster.drs_enabled == Some(true)).unwrap_or(on_off(cluster.drs_enabled)),
+                        cluster.vm_count,
+                        cluster.vcpu_per_core
+                    ));
+                }
+                hld.push_str("\n");
+            }
+            if !source.storage.is_empty() {
+                hld.push_str("### Source Storage
+
+");
+                hld.push_str("| Type | Datastores | Capacity (GB) | Provisioned (GB) | Free (GB) |\n");
+                hld.push_str("|------|------------|---------------|------------------|-----------|\n");
+                for storage in &source.storage {
+                    hld.push_str(&format!(
+                        "| {} | {} | {:.0} | {:.0} | {:.0} |\n",
+                        storage.datastore_type, storage.datastores, storage.capacity_gb, storage.provisioned_gb, storage.free_gb
+                    ));
+                }
+                hld.push_str("\n");
+            }
         }
         
         // Target Architecture
@@ -2002,6 +2617,37 @@ impl MigrationWizardService {
                 hld.push_str(&format!("- Memory: {}:1\n\n", cluster.memory_oversubscription_ratio));
             }
         }
+
+        let naming = NamingPolicyService::new(self.db.clone()).check_project(project_id).await?;
+        if naming.policy_found {
+            hld.push_str("### Naming and Tagging Compliance\n\n");
+            hld.push_str(&format!(
+                "Checked {} cluster(s), {} placed VM(s) and {} network(s) against {}.\n\n",
+                naming.clusters_checked,
+                naming.vms_checked,
+                naming.networks_checked,
+                naming
+                    .policy_tenant_id
+                    .as_deref()
+                    .map(|t| format!("the naming policy of tenant {}", t))
+                    .unwrap_or_else(|| "the default naming policy".to_string())
+            ));
+            if naming.violations.is_empty() {
+                hld.push_str("*All names and mandatory tags comply.*\n\n");
+            } else {
+                hld.push_str("| Type | Object | Violation |\n");
+                hld.push_str("|------|--------|-----------|\n");
+                for violation in &naming.violations {
+                    hld.push_str(&format!(
+                        "| {} | {} | {} |\n",
+                        violation.target.label(),
+                        violation.object_name,
+                        violation.message
+                    ));
+                }
+                hld.push_str("\n");
+            }
+        }
         
         // VM Placement Strategy
         if include_vm_placements {
@@ -2060,6 +2706,18 @@ impl MigrationWizardService {
                     ));
                 }
                 hld.push_str("\n");
+
+                let conflicts = NetworkConflictService::new(self.db.clone())
+                    .conflicts_for(project_id, &mappings)
+                    .await?;
+                if !conflicts.is_empty() {
+                    hld.push_str("### Conflicts With Other Projects\n\n");
+                    hld.push_str("Other migrations in this tenant target the same destination VLANs or subnets; resolve these before cutover.\n\n");
+                    for conflict in &conflicts {
+                        hld.push_str(&format!("- ⚠️ {}\n", conflict.message));
+                    }
+                    hld.push_str("\n");
+                }
                 
                 // Network topology visualization
                 hld.push_str("### Network Topology\n\n");
@@ -2096,13 +2754,68 @@ impl MigrationWizardService {
         // Risks and Mitigation
         hld.push_str("---\n\n");
         hld.push_str("## 7. Risks and Mitigation\n\n");
-        hld.push_str("| Risk | Impact | Likelihood | Mitigation Strategy |\n");
-        hld.push_str("|------|--------|------------|---------------------|\n");
-        hld.push_str("| Network connectivity issues | High | Medium | Pre-migration network testing and validation |\n");
-        hld.push_str("| Capacity constraints | High | Low | Oversubscription ratios and capacity monitoring |\n");
-        hld.push_str("| Application compatibility | Medium | Medium | Pilot migration and thorough testing |\n");
-        hld.push_str("| Data loss during migration | High | Low | Backup verification and rollback procedures |\n");
-        hld.push_str("| Extended downtime | Medium | Medium | Migration windows and phased approach |\n\n");
+        let risks: Vec<_> = crate::services::risk_register_service::RiskRegisterService::new(self.db.clone())
+            .list_risks(project_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.status != crate::models::risk::RiskStatus::Closed)
+            .collect();
+        
+        if !risks.is_empty() {
+            hld.push_str("| Risk | Impact | Likelihood | Owner | Mitigation Strategy | Status |\n");
+            hld.push_str("|------|--------|------------|-------|---------------------|--------|\n");
+            for risk in &risks {
+                hld.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {:?} |\n",
+                    risk.title,
+                    risk.impact.label(),
+                    risk.likelihood.label(),
+                    risk.owner.as_deref().unwrap_or("Unassigned"),
+                    risk.mitigation,
+                    risk.status
+                ));
+            }
+            hld.push_str("\n");
+        } else {
+            hld.push_str("*No open risks in the project risk register.*\n\n");
+        }
+        
+        // Professional Services Estimate
+        if let Some(estimate) = &services_estimate {
+            hld.push_str("---\n\n");
+            hld.push_str("## 8. Professional Services Estimate\n\n");
+            hld.push_str(&format!(
+                "Effort is sized from the complexity and migration strategy of **{}** VMs (average complexity {:.0}/100), priced with the **{}** rate card.\n\n",
+                estimate.vm_count, estimate.average_complexity, estimate.rate_card_name
+            ));
+            hld.push_str("| Role | Hours | Cost |\n");
+            hld.push_str("|------|-------|------|\n");
+            for role in &estimate.roles {
+                hld.push_str(&format!("| {} | {:.1} | {:.2} {} |\n", role.role, role.hours, role.cost, estimate.currency));
+            }
+            hld.push_str("\n");
+            hld.push_str(&format!("- **Total Effort:** {:.1} hours\n", estimate.total_hours));
+            hld.push_str(&format!("- **Subtotal:** {:.2} {}\n", estimate.subtotal, estimate.currency));
+            hld.push_str(&format!("- **Contingency ({:.0}%):** {:.2} {}\n", estimate.contingency_percent, estimate.contingency, estimate.currency));
+            hld.push_str(&format!("- **Total Services Cost:** {:.2} {}\n\n", estimate.total_cost, estimate.currency));
+        }
+
+        // Sustainability Impact
+        if let Some(report) = &sustainability {
+            hld.push_str("---\n\n");
+            hld.push_str("## 9. Sustainability Impact\n\n");
+            hld.push_str(&format!(
+                "Consolidating onto the proposed clusters changes annual emissions by **{:+.1} t CO2e** ({:+.0}%).\n\n",
+                -report.annual_co2e_saved_tonnes,
+                -report.reduction_percent
+            ));
+            hld.push_str(&SustainabilityService::comparison_table(report));
+            hld.push_str("**Assumptions:**\n\n");
+            for assumption in &report.assumptions {
+                hld.push_str(&format!("- {}\n", assumption));
+            }
+            hld.push_str("\n");
+        }
         
         // Footer
         hld.push_str("---\n\n");
@@ -2111,3 +2824,370 @@ impl MigrationWizardService {
         Ok(hld)
     }
 }
+
+/// Whether two CIDR subnets share any address; unparseable ones never do
+fn subnets_overlap(subnet1: &str, subnet2: &str) -> bool {
+    match (subnet1.parse::<Ipv4Cidr>(), subnet2.parse::<Ipv4Cidr>()) {
+        (Ok(net1), Ok(net2)) => net1.overlaps(&net2),
+        _ => false,
+    }
+}
+
+/// Records per second, or 0 when nothing measurable happened
+fn throughput(records: usize, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if records == 0 || seconds == 0.0 {
+        return 0.0;
+    }
+    (records as f64 / seconds * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::RoundTripCounter;
+    use surrealdb::engine::local::Mem;
+    use surrealdb::Surreal;
+
+    async fn service() -> MigrationWizardService {
+        let db = Surreal::new::<Mem>(()).await.expect("Failed to create in-memory db");
+        db.use_ns("test").use_db("test").await.expect("Failed to use test ns/db");
+        MigrationWizardService::new(db)
+    }
+
+    fn vm(key: &str, cpus: i32, memory_mb: i32) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_string(),
+            powerstate: Some("poweredOn".to_string()),
+            template: Some(false),
+            cpus,
+            memory_mb,
+            provisioned_mb: Some(102_400),
+            in_use_mb: None,
+            primary_ip_address: None,
+            dns_name: None,
+            cluster: None,
+            host: None,
+            datacenter: None,
+            os: Some("Microsoft Windows Server 2019 (64-bit)".to_string()),
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn cluster(key: &str, total_cores: i32, memory_gb: i32) -> MigrationWizardCluster {
+        MigrationWizardCluster {
+            id: Some(Thing::from(("migration_wizard_cluster", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_string(),
+            description: None,
+            cpu_ghz: 2.4,
+            total_cores,
+            memory_gb,
+            storage_tb: 100.0,
+            network_bandwidth_gbps: 25.0,
+            cpu_oversubscription_ratio: 1.0,
+            memory_oversubscription_ratio: 1.0,
+            strategy: "hyperv".to_string(),
+            cpu_model: None,
+            destination_cluster_id: None,
+            tags: Default::default(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn placement(vm_key: &str, cluster_key: &str, cpu: i32, memory_mb: i32) -> MigrationWizardPlacement {
+        MigrationWizardPlacement {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            vm_id: Thing::from(("migration_wizard_vm", vm_key)),
+            cluster_id: Thing::from(("migration_wizard_cluster", cluster_key)),
+            strategy: "manual".to_string(),
+            confidence_score: None,
+            warnings: None,
+            allocated_cpu: cpu,
+            allocated_memory_mb: memory_mb,
+            allocated_storage_gb: 0.0,
+            tags: Default::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_plan_is_best_fit_decreasing_and_skips_placed_vms() {
+        let vms = vec![vm("small", 2, 4096), vm("big", 16, 65536), vm("done", 4, 8192), vm("huge", 64, 4096)];
+        let clusters = vec![cluster("wide", 32, 256), cluster("narrow", 20, 128)];
+        let existing = vec![placement("done", "narrow", 4, 8192)];
+
+        let plan = MigrationWizardService::plan_auto_placement(&vms, &clusters, &existing);
+
+        assert_eq!(
+            plan,
+            vec![
+                // Taking all 16 cores left on "narrow" is the tightest fit
+                PlannedPlacement { vm_index: 1, cluster_index: Some(1) },
+                // Fits nowhere: 64 vCPUs is more than either cluster has
+                PlannedPlacement { vm_index: 3, cluster_index: None },
+                PlannedPlacement { vm_index: 0, cluster_index: Some(0) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_and_strategies_do_not_depend_on_input_order() {
+        let vms: Vec<_> = (0..600).map(|i| vm(&format!("vm{:03}", i), 1 + i % 8, 2048 * (1 + i % 4))).collect();
+        let clusters: Vec<_> = (0..80).map(|i| cluster(&format!("c{:02}", i), 16, 128)).collect();
+        let mut reversed = vms.clone();
+        reversed.reverse();
+
+        let names = |vms: &[MigrationWizardVM], plan: Vec<PlannedPlacement>| -> Vec<(String, Option<usize>)> {
+            plan.into_iter().map(|p| (vms[p.vm_index].name.clone(), p.cluster_index)).collect()
+        };
+        assert_eq!(
+            names(&vms, MigrationWizardService::plan_auto_placement(&vms, &clusters, &[])),
+            names(&reversed, MigrationWizardService::plan_auto_placement(&reversed, &clusters, &[]))
+        );
+
+        let catalog = OsLifecycleCatalog::builtin();
+        let as_of = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let forward = MigrationWizardService::recommend_strategies(&vms, &catalog, as_of);
+        let backward = MigrationWizardService::recommend_strategies(&reversed, &catalog, as_of);
+        assert_eq!(forward.len(), 600);
+        assert!(forward.iter().zip(&backward).all(|(a, b)| a.vm_name == b.vm_name && a.strategy == b.strategy));
+        assert!(forward.windows(2).all(|w| w[0].vm_name <= w[1].vm_name));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_writes_vms_in_batches() {
+        let service = service().await;
+        let vms: Vec<_> = (0..7).map(|i| MigrationWizardVM { id: None, ..vm(&format!("vm{}", i), 2, 4096) }).collect();
+
+        let (ids, metrics) = service.ingest_vms("p1", vms, &IngestOptions { chunk_size: 3 }).await.unwrap();
+
+        assert_eq!(ids.len(), 7);
+        assert_eq!((metrics.vms_parsed, metrics.vms_inserted, metrics.batches), (7, 7, 3));
+        assert_eq!(service.get_project_vms("p1", None).await.unwrap().len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_rolls_back_the_whole_import() {
+        let service = service().await;
+        service.db.query("DEFINE FIELD cpus ON migration_wizard_vm ASSERT $value <= 64").await.unwrap();
+        let vms: Vec<_> = (0..6)
+            .map(|i| MigrationWizardVM { id: None, ..vm(&format!("vm{}", i), if i == 4 { 128 } else { 2 }, 4096) })
+            .collect();
+
+        let result = service.ingest_vms("p1", vms, &IngestOptions { chunk_size: 2 }).await;
+
+        assert!(result.unwrap_err().to_string().contains("batch 3 of 3"));
+        assert!(service.get_project_vms("p1", None).await.unwrap().is_empty());
+    }
+
+    /// A project "p1" with `vm_count` 2 vCPU VMs and four roomy clusters c0..c3
+    async fn seeded(vm_count: usize) -> MigrationWizardService {
+        let service = service().await;
+        let vms = (0..vm_count).map(|i| vm(&format!("vm{:04}", i), 2, 4096)).collect();
+        service.ingest_vms("p1", vms, &IngestOptions::default()).await.unwrap();
+        let clusters: Vec<_> = (0..4).map(|i| cluster(&format!("c{}", i), 2048, 16384)).collect();
+        service
+            .db
+            .query("INSERT INTO migration_wizard_cluster $clusters")
+            .bind(("clusters", clusters))
+            .await
+            .unwrap();
+        service
+    }
+
+    #[tokio::test]
+    async fn test_auto_placement_round_trips_do_not_grow_with_vm_count() {
+        for (vm_count, round_trips) in [(40, 2), (1200, 4)] {
+            let service = seeded(vm_count).await;
+            let counter = RoundTripCounter::default();
+            let _guard = counter.install();
+
+            let (placements, warnings, utilization) = service.auto_place_vms("p1").await.unwrap();
+
+            // One read, then one write per batch of 500 placements
+            assert_eq!(counter.take(), round_trips);
+            assert_eq!(placements.len(), vm_count);
+            assert!(warnings.is_empty());
+            assert_eq!(utilization.iter().map(|u| u.4).sum::<usize>(), vm_count);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_source_inventory_is_replaced_per_project() {
+        let service = service().await;
+        let host = |name: &str| SourceHost { name: name.to_string(), cluster: Some("Prod".to_string()), ..Default::default() };
+
+        let first = SourceInventory { hosts: vec![host("esx01"), host("esx02")], ..Default::default() };
+        service.save_source_inventory("p1", &first).await.unwrap();
+        let second = SourceInventory { hosts: vec![host("esx03")], ..Default::default() };
+        service.save_source_inventory("p1", &second).await.unwrap();
+
+        let stored = service.get_source_inventory("p1").await.unwrap();
+        assert_eq!(stored.hosts.len(), 1);
+        assert_eq!(stored.hosts[0].name, "esx03");
+        assert!(service.get_source_inventory("p2").await.unwrap().hosts.is_empty());
+    }
+
+    #[test]
+    fn test_ha_reserve_warns_when_destination_cannot_absorb_a_host_failure() {
+        let source = SourceEnvironmentSummary {
+            clusters: vec![SourceClusterSummary {
+                name: "Prod".to_string(),
+                hosts: 4,
+                cores: 64,
+                memory_gb: 1024.0,
+                ha_enabled: Some(true),
+                failover_level: Some(1),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let vms = vec![MigrationWizardVM { cluster: Some("Prod".to_string()), ..vm("app", 8, 32_768) }];
+        let placements = vec![placement("app", "tight", 8, 32_768), placement("app", "roomy", 8, 32_768)];
+        // One average source host is 16 cores and 256 GB
+        let utilization = vec![
+            (cluster("tight", 20, 256), 8, 32_768, 0.0, 1),
+            (cluster("roomy", 64, 1024), 8, 32_768, 0.0, 1),
+        ];
+
+        let warnings = MigrationWizardService::ha_reserve_warnings(&source, &vms, &placements, &utilization);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Cluster tight"));
+    }
+
+    #[tokio::test]
+    async fn test_manual_placement_takes_two_round_trips_and_replaces_the_old_one() {
+        let service = seeded(3).await;
+        let counter = RoundTripCounter::default();
+        let _guard = counter.install();
+
+        service.create_manual_placement("p1", "vm0000", "c0", None, BTreeMap::new()).await.unwrap();
+        let (placement, warnings) = service.create_manual_placement("p1", "vm0000", "c1", None, BTreeMap::new()).await.unwrap();
+        assert_eq!(counter.take(), 4);
+        assert!(warnings.is_empty());
+        assert_eq!(placement.cluster_id.id.to_raw(), "c1");
+
+        let utilization = service.get_cluster_utilization("p1").await.unwrap();
+        assert_eq!(counter.take(), 1);
+        let occupied: Vec<_> = utilization.iter().filter(|u| u.4 > 0).map(|u| (u.0.name.as_str(), u.4)).collect();
+        assert_eq!(occupied, vec![("c1", 1)]);
+    }
+
+    #[test]
+    fn test_check_capacity_warns_per_exhausted_resource() {
+        let small = cluster("small", 4, 8);
+        let used = Resources { cpu: 3, memory_mb: 1024, storage_gb: 0.0 };
+
+        let (fits, warnings) = MigrationWizardService::check_capacity(&small, &used, &vm("app", 2, 4096));
+
+        assert!(!fits);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("CPU capacity warning: 3 + 2 > 4"));
+    }
+
+    #[test]
+    fn test_throughput_rounds_and_handles_empty_imports() {
+        assert_eq!(throughput(1500, Duration::from_millis(1200)), 1250.0);
+        assert_eq!(throughput(0, Duration::from_secs(1)), 0.0);
+        assert_eq!(throughput(10, Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_subnets_overlap_uses_cidr_math() {
+        assert!(subnets_overlap("10.0.0.0/16", "10.0.42.0/24"));
+        assert!(subnets_overlap("10.0.42.9/24", "10.0.42.0/24"));
+        assert!(!subnets_overlap("10.0.42.0/24", "10.0.43.0/24"));
+        assert!(!subnets_overlap("10.0.42.0", "10.0.42.0/24"));
+    }
+
+    fn project(key: &str, tenant: Option<&str>, status: ProjectStatus) -> MigrationWizardProject {
+        MigrationWizardProject {
+            id: Some(Thing::from(("migration_wizard_project", key))),
+            name: key.to_uppercase(),
+            description: None,
+            status,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            rvtools_filename: None,
+            rvtools_upload_date: None,
+            rvtools_file_path: None,
+            total_vms: 0,
+            total_clusters: 0,
+            wizard_step: 1,
+            tenant_id: tenant.map(str::to_string),
+            display_currency: None,
+        }
+    }
+
+    fn network_mapping(project: &str, name: &str, vlan_id: i32, subnet: &str) -> MigrationWizardNetworkMapping {
+        MigrationWizardNetworkMapping {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", project)),
+            source_vlan_name: name.to_string(),
+            source_vlan_id: None,
+            source_subnet: None,
+            destination_vlan_name: name.to_string(),
+            destination_vlan_id: Some(vlan_id),
+            destination_subnet: Some(subnet.to_string()),
+            destination_gateway: None,
+            destination_dns: None,
+            is_valid: true,
+            validation_errors: None,
+            tags: Default::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_network_validation_warns_about_other_projects_in_the_tenant() {
+        let service = service().await;
+        let projects = vec![
+            project("p1", Some("acme"), ProjectStatus::InProgress),
+            project("p2", Some("acme"), ProjectStatus::Draft),
+            project("p3", Some("globex"), ProjectStatus::Draft),
+            project("p4", Some("acme"), ProjectStatus::Archived),
+            project("p5", None, ProjectStatus::Draft),
+        ];
+        let mappings = vec![
+            network_mapping("p1", "Prod", 100, "10.10.0.0/16"),
+            network_mapping("p2", "Web", 100, "192.168.1.0/24"),
+            network_mapping("p3", "Other tenant", 100, "10.10.1.0/24"),
+            network_mapping("p4", "Archived", 100, "10.10.2.0/24"),
+            network_mapping("p5", "Shared", 200, "10.10.3.0/24"),
+        ];
+        service.db.query("INSERT INTO migration_wizard_project $projects").bind(("projects", projects)).await.unwrap();
+        service.db.query("INSERT INTO migration_wizard_network_mapping $mappings").bind(("mappings", mappings)).await.unwrap();
+
+        let result = service.validate_network_mappings("p1").await.unwrap();
+
+        let mut conflicting: Vec<_> = result.cross_project_conflicts.iter().map(|c| c.other_project_id.as_str()).collect();
+        conflicting.sort();
+        // p3 belongs to another tenant and p4 is archived; p5 is shared
+        assert_eq!(conflicting, vec!["p2", "p5"]);
+        assert!(result.is_valid);
+        assert!(result.warnings.iter().any(|w| w.contains("VLAN 100 is also targeted by 'Web' in project 'P2'")));
+        assert!(result.warnings.iter().any(|w| w.contains("10.10.0.0/16 contains 10.10.3.0/24")));
+    }
+
+    #[test]
+    fn test_cluster_utilization_sums_placements_per_cluster() {
+        let clusters = vec![cluster("a", 32, 256), cluster("b", 32, 256)];
+        let placements = vec![placement("x", "a", 4, 1024), placement("y", "a", 2, 512)];
+
+        let utilization = MigrationWizardService::cluster_utilization(clusters, &placements);
+
+        assert_eq!((utilization[0].1, utilization[0].2, utilization[0].4), (6, 1536, 2));
+        assert_eq!((utilization[1].1, utilization[1].2, utilization[1].4), (0, 0, 0));
+    }
+}
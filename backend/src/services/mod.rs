@@ -4,31 +4,57 @@ pub mod auth_service;
 // Core ITSM Services (Phase 1)
 pub mod sla_service;
 pub mod ticket_service;
+pub mod ticket_watch_service;  // Watcher notifications & daily digest
+pub mod ticket_template_service;  // Ticket templates, macros & checklist tasks
+pub mod ticket_schedule_service;  // Recurring ticket schedules
 pub mod team_service;  // Team Management
 pub mod tiering_service;  // Hot/Cold Data Tiering
+pub mod ticket_analytics_service;  // Dashboard ticket/SLA aggregates
 
 // Knowledge Base (Phase 1.5)
 pub mod knowledge_service;
 pub mod kb_suggestion_service;
+pub mod kb_attachment_service;
+
+// Saved Views & Advanced Filtering
+pub mod filter_query;
+pub mod saved_view_service;
 
 // CMDB/Assets (Phase 2)
 pub mod cmdb_service;
+pub mod depreciation_service;  // Hardware book value & refresh forecasting
+pub mod contract_service;  // Vendor contracts & expiry alerts
+pub mod import_service;  // CSV/XLSX imports
+pub mod assessment_service;  // Rule pack assessments
 
 // Workflow Engine (Phase 3)
 pub mod workflow_engine_service;
+pub mod workflow_steps;  // Step executors: webhooks, conditions, timers, scripts
+pub mod approval_service;  // Delegation, quorum and approval reminders
 
 // Monitoring & Alerting (Phase 4)
 pub mod monitoring_service;
 
+// AI / LLM providers
+pub mod llm;
+
 // Reporting (Phase 6)
 pub mod reporting_service;
+pub mod report_scheduler_service;  // Cron-scheduled report distribution
+pub mod notification_service;  // Email & Teams delivery
 
 pub mod dependency_validator;
 pub mod document_service;
 pub mod enhanced_rvtools_service; // TODO: Fix compilation errors
 pub mod hardware_pool_service;
+pub mod procurement_service;  // BOM purchase requests, PO tracking & receipt
 pub mod integration_hub;
 pub mod migration_wizard_service;
+pub mod source_inventory;  // RVTools vHost, vCluster, vDatastore, vRP, vSnapshot and vTools sheets
+pub mod vm_hygiene_service;  // Snapshot & VMware Tools hygiene, pre-migration remediation tasks
+pub mod cpu_compatibility_service;  // Live vs cold migration from source and target CPU generations
+pub mod guest_scan;  // Agentless WinRM/SSH scans of installed apps, static IPs & mounts
+pub mod naming_policy_service;  // Naming conventions & mandatory tags for clusters, VMs & networks
 pub mod project_management_service;
 pub mod rvtools_service;
 pub mod analytics_service;
@@ -36,11 +62,46 @@ pub mod analytics_service;
 // Activity Wizard Services
 pub mod capacity_validation_service;
 pub mod capacity_planner_service;
+pub mod capacity_overview_service;
+pub mod overcommit_policy_service;
+pub mod settings_service;  // Layered system/tenant/project/user settings
+pub mod feature_flag_service;  // Feature flags evaluated through the settings hierarchy
+pub mod license_service;  // License key verification, seats & grace handling
+pub mod usage_telemetry_service;  // Opt-in feature usage counts & daily submission
+pub mod retention_service;  // Per-tenant retention policies & scheduled purges
+pub mod webhook_service;  // Signed outbound webhooks with retries & dead letters
+pub mod collector_ingest;  // gRPC ingestion of collector inventory snapshots & metrics
+pub mod privacy_service;  // User data export & approval-gated erasure
+pub mod build_checklist_service;
+pub mod firmware_baseline_service;
 pub mod hardware_compatibility_service;
 pub mod timeline_estimation_service;
+pub mod timeline_service;
+pub mod cutover_readiness_service;  // Red/amber/green readiness scorecards per wave
+pub mod rollback_plan_service;  // Reverse cutover checklists per wave
+pub mod automation_export;  // IaC scaffolds & cutover automation bundles
+pub mod reconciliation_service;  // Planned placements vs discovered target VMs
+pub mod estimation_service;
+pub mod currency_service;
+pub mod anonymization_service;
+pub mod diagnostics_service;
+pub mod component_classification_service;
+pub mod risk_register_service;
+pub mod os_lifecycle_service;
+pub mod sustainability_service;
+pub mod search_service;
+pub mod upload_session_service;
+pub mod document_storage;
+pub mod document_version_service;
+pub mod document_redaction_service;
+pub mod document_review_service;
+pub mod project_access_service;
+pub mod nl_query_service;
 pub mod wizard_service;
 pub mod vm_placement_service;
 pub mod network_template_service;
+pub mod network_profile_service;
+pub mod network_conflict_service;
 pub mod hld_generation_service;
 
 // HLD Generation Services (Week 1)
@@ -52,3 +113,6 @@ pub mod rvtools_hld_mapper;
 
 // HLD Generation Services (Week 3)
 pub mod word_generator;
+pub mod hld_dry_run_service;
+pub mod data_expression;
+pub mod data_variable_service;
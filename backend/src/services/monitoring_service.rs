@@ -17,6 +17,7 @@ use crate::models::ticket::{
     CreateTicketRequest, TicketImpact, TicketSource, TicketType, TicketUrgency,
 };
 use crate::services::ticket_service::TicketService;
+use crate::database::TracedQuery;
 
 // ============================================================================
 // MONITORING SERVICE
@@ -170,7 +171,7 @@ impl MonitoringService {
 
         // Get total count
         let count_query = "SELECT count() FROM alert GROUP ALL".to_string();
-        let mut count_response = self.db.query(&count_query).await?;
+        let mut count_response = self.db.query(&count_query).traced("monitoring_service.list_alerts").await?;
         let count_result: Option<serde_json::Value> = count_response.take(0)?;
         let total = count_result
             .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
@@ -201,6 +202,7 @@ impl MonitoringService {
             .bind(("status", format!("{:?}", AlertStatus::Acknowledged)))
             .bind(("acknowledged_at", now))
             .bind(("acknowledged_by", request.acknowledged_by))
+            .traced("monitoring_service.acknowledge_alert")
             .await?;
 
         let updated: Option<Alert> = response.take(0)?;
@@ -229,6 +231,7 @@ impl MonitoringService {
             .bind(("status", format!("{:?}", AlertStatus::Resolved)))
             .bind(("resolved_at", now))
             .bind(("resolved_by", request.resolved_by))
+            .traced("monitoring_service.resolve_alert")
             .await?;
 
         let updated: Option<Alert> = response.take(0)?;
@@ -313,6 +316,7 @@ impl MonitoringService {
             .query("UPDATE $alert_id SET auto_ticket_id = $ticket_id")
             .bind(("alert_id", alert_thing))
             .bind(("ticket_id", ticket_id.clone()))
+            .traced("monitoring_service.create_ticket_from_alert")
             .await?
             .take(0)?;
 
@@ -456,6 +460,7 @@ impl MonitoringService {
                 .bind(("source", &alert.source))
                 .bind(("source_alert_id", source_alert_id))
                 .bind(("resolved", format!("{:?}", AlertStatus::Resolved)))
+                .traced("monitoring_service.check_duplicate_alert")
                 .await?;
 
             let existing: Option<Alert> = response.take(0)?;
@@ -477,6 +482,7 @@ impl MonitoringService {
             .db
             .query(query)
             .bind(("severity", format!("{:?}", alert.severity)))
+            .traced("monitoring_service.auto_create_ticket")
             .await?;
 
         let rule: Option<AlertRule> = response.take(0)?;
@@ -512,6 +518,7 @@ impl MonitoringService {
                 .query("UPDATE $alert_id SET auto_ticket_id = $ticket_id")
                 .bind(("alert_id", alert_id.clone()))
                 .bind(("ticket_id", ticket_id.clone()))
+                .traced("monitoring_service.update_alert_ticket_link")
                 .await?
                 .take(0)?;
         }
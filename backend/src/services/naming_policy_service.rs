@@ -0,0 +1,516 @@
+//! Naming Policy Service
+//!
+//! Checks target names and tags against the naming policy of their tenant:
+//! - A policy holds naming rules, regular expressions or token patterns,
+//!   and mandatory tags for clusters, VMs and networks
+//! - A tenant's own policy replaces the installation default for that
+//!   tenant; without any policy nothing is checked
+//! - Migration wizard projects find their tenant through `tenant_id`,
+//!   destination clusters through their project; objects without a tenant
+//!   fall under the default
+//! - Violations are reported by network mapping and destination cluster
+//!   validation, by the project's naming compliance report and in its HLD
+
+use chrono::Utc;
+use regex::{Regex, RegexBuilder};
+use std::collections::{BTreeMap, HashMap};
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::migration_wizard_models::{
+    MigrationWizardCluster, MigrationWizardNetworkMapping, MigrationWizardPlacement, MigrationWizardProject,
+    MigrationWizardVM,
+};
+use crate::models::naming_policy::*;
+use crate::models::project_models::{DestinationCluster, ValidationIssue, ValidationSeverity};
+use crate::services::migration_wizard_service::MigrationWizardService;
+
+const TABLE: &str = "naming_policy";
+/// Key of the installation default policy
+const DEFAULT_POLICY: &str = "default";
+pub const NAMING_POLICY_CATEGORY: &str = "Naming Policy";
+/// Longest pattern accepted, to keep compiled regexes small
+const MAX_PATTERN_LENGTH: usize = 500;
+
+#[derive(Debug, Error)]
+pub enum NamingPolicyError {
+    #[error("Naming policy not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for NamingPolicyError {
+    fn from(e: surrealdb::Error) -> Self {
+        NamingPolicyError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<anyhow::Error> for NamingPolicyError {
+    fn from(e: anyhow::Error) -> Self {
+        NamingPolicyError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct NamingPolicyService {
+    db: Database,
+}
+
+impl NamingPolicyService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<NamingPolicy>, NamingPolicyError> {
+        Ok(self
+            .db
+            .query(format!("SELECT * FROM {TABLE} ORDER BY tenant_id"))
+            .traced("naming_policy_service.list_policies")
+            .await?
+            .take(0)?)
+    }
+
+    /// Create or replace the policy of a tenant (or the default). Every
+    /// pattern must compile.
+    pub async fn upsert_policy(
+        &self,
+        request: UpsertNamingPolicyRequest,
+        updated_by: &str,
+    ) -> Result<NamingPolicy, NamingPolicyError> {
+        let tenant_id = request.tenant_id.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        let policy = NamingPolicy {
+            id: None,
+            tenant_id,
+            rules: request.rules,
+            mandatory_tags: request.mandatory_tags,
+            updated_by: updated_by.to_string(),
+            updated_at: Utc::now(),
+        };
+        CompiledPolicy::new(&policy).map_err(NamingPolicyError::Validation)?;
+
+        let stored: Option<NamingPolicy> = self
+            .db
+            .query("UPDATE type::thing($table, $id) CONTENT $policy RETURN AFTER")
+            .bind(("table", TABLE))
+            .bind(("id", policy.tenant_id.clone().unwrap_or_else(|| DEFAULT_POLICY.to_string())))
+            .bind(("policy", policy))
+            .traced("naming_policy_service.upsert_policy")
+            .await?
+            .take(0)?;
+        stored.ok_or_else(|| NamingPolicyError::DatabaseError("Policy was not stored".to_string()))
+    }
+
+    pub async fn delete_policy(&self, id: &str) -> Result<(), NamingPolicyError> {
+        let key = id.strip_prefix("naming_policy:").unwrap_or(id);
+        let key = key.trim_start_matches('⟨').trim_end_matches('⟩');
+        let deleted: Option<NamingPolicy> = self.db.delete((TABLE, key)).await?;
+        deleted.map(|_| ()).ok_or(NamingPolicyError::NotFound)
+    }
+
+    /// The tenant's own policy, else the installation default
+    pub async fn policy_for_tenant(&self, tenant_id: Option<&str>) -> Result<Option<NamingPolicy>, NamingPolicyError> {
+        if let Some(tenant_id) = tenant_id {
+            let policy: Option<NamingPolicy> = self.db.select((TABLE, tenant_id)).await?;
+            if policy.is_some() {
+                return Ok(policy);
+            }
+        }
+        Ok(self.db.select((TABLE, DEFAULT_POLICY)).await?)
+    }
+
+    async fn project_policy(&self, project_id: &str) -> Result<Option<CompiledPolicy>, NamingPolicyError> {
+        let project: Option<MigrationWizardProject> = self.db.select(("migration_wizard_project", project_id)).await?;
+        let tenant_id = project.and_then(|p| p.tenant_id);
+        self.compiled(tenant_id.as_deref()).await
+    }
+
+    async fn compiled(&self, tenant_id: Option<&str>) -> Result<Option<CompiledPolicy>, NamingPolicyError> {
+        match self.policy_for_tenant(tenant_id).await? {
+            Some(policy) => Ok(Some(CompiledPolicy::new(&policy).map_err(NamingPolicyError::Validation)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Check a migration wizard project's target clusters, placed VMs and
+    /// destination networks
+    pub async fn check_project(&self, project_id: &str) -> Result<NamingComplianceReport, NamingPolicyError> {
+        let policy = self.project_policy(project_id).await?;
+        let service = MigrationWizardService::new(self.db.clone());
+        let (clusters, placements, mappings) = match &policy {
+            Some(_) => (
+                service.get_project_clusters(project_id).await?,
+                service.get_project_placements(project_id).await?,
+                service.get_project_network_mappings(project_id).await?,
+            ),
+            None => Default::default(),
+        };
+        let vms = if placements.is_empty() {
+            Vec::new()
+        } else {
+            service.get_project_vms(project_id, None).await?
+        };
+
+        let violations = policy
+            .as_ref()
+            .map(|policy| project_violations(policy, &clusters, &placements, &vms, &mappings))
+            .unwrap_or_default();
+        Ok(NamingComplianceReport {
+            project_id: project_id.to_string(),
+            policy_tenant_id: policy.as_ref().and_then(|p| p.tenant_id.clone()),
+            policy_found: policy.is_some(),
+            clusters_checked: clusters.len(),
+            vms_checked: placements.len(),
+            networks_checked: mappings.len(),
+            violations,
+            evaluated_at: Utc::now(),
+        })
+    }
+
+    /// Violations of a project's network mappings, for network validation
+    pub async fn network_violations(
+        &self,
+        project_id: &str,
+        mappings: &[MigrationWizardNetworkMapping],
+    ) -> Result<Vec<NamingViolation>, NamingPolicyError> {
+        let Some(policy) = self.project_policy(project_id).await? else {
+            return Ok(Vec::new());
+        };
+        Ok(mappings.iter().flat_map(|m| mapping_violations(&policy, m)).collect())
+    }
+
+    /// Validation issues of a destination cluster's name and tags. Its tags
+    /// are the string values of the `tags` object in its metadata.
+    pub async fn destination_cluster_issues(
+        &self,
+        cluster: &DestinationCluster,
+    ) -> Result<Vec<ValidationIssue>, NamingPolicyError> {
+        let tenant_id: Option<String> = self
+            .db
+            .query("SELECT VALUE tenant_id FROM $project")
+            .bind(("project", cluster.project_id.clone()))
+            .traced("naming_policy_service.destination_cluster_issues")
+            .await?
+            .take::<Vec<Option<String>>>(0)?
+            .into_iter()
+            .flatten()
+            .next();
+        let Some(policy) = self.compiled(tenant_id.as_deref()).await? else {
+            return Ok(Vec::new());
+        };
+
+        let tags: BTreeMap<String, String> = cluster
+            .metadata
+            .get("tags")
+            .and_then(|tags| tags.as_object())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|(key, value)| value.as_str().map(|v| (key.clone(), v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let object_id = cluster.id.as_ref().map(|id| id.id.to_raw());
+        Ok(issues(&policy.check(NamingTarget::Cluster, object_id, &cluster.name, &tags)))
+    }
+}
+
+/// A policy with its patterns compiled
+pub struct CompiledPolicy {
+    pub tenant_id: Option<String>,
+    rules: Vec<(NamingRule, Regex)>,
+    tags: Vec<TagRequirement>,
+}
+
+impl CompiledPolicy {
+    pub fn new(policy: &NamingPolicy) -> Result<Self, String> {
+        let rules = policy
+            .rules
+            .iter()
+            .map(|rule| compile(rule).map(|regex| (rule.clone(), regex)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(tag) = policy.mandatory_tags.iter().find(|t| t.key.trim().is_empty()) {
+            return Err(format!("{} tag keys must not be blank", tag.target.label()));
+        }
+        Ok(Self {
+            tenant_id: policy.tenant_id.clone(),
+            rules,
+            tags: policy.mandatory_tags.clone(),
+        })
+    }
+
+    /// Violations of one object; names are unchecked when its target has
+    /// no rules
+    pub fn check(
+        &self,
+        target: NamingTarget,
+        object_id: Option<String>,
+        name: &str,
+        tags: &BTreeMap<String, String>,
+    ) -> Vec<NamingViolation> {
+        let violation = |kind, message| NamingViolation {
+            target,
+            object_id: object_id.clone(),
+            object_name: name.to_string(),
+            kind,
+            message,
+        };
+        let mut violations = Vec::new();
+
+        let rules: Vec<&(NamingRule, Regex)> = self.rules.iter().filter(|(rule, _)| rule.target == target).collect();
+        if !rules.is_empty() && !rules.iter().any(|(_, regex)| regex.is_match(name)) {
+            let expected: Vec<&str> = rules.iter().map(|(rule, _)| rule.label()).collect();
+            violations.push(violation(
+                NamingViolationKind::Name,
+                format!("{} name '{}' does not match {}", target.label(), name, expected.join(" or ")),
+            ));
+        }
+
+        for requirement in self.tags.iter().filter(|t| t.target == target) {
+            match tags.get(&requirement.key).map(|v| v.trim()).filter(|v| !v.is_empty()) {
+                None => violations.push(violation(
+                    NamingViolationKind::MissingTag,
+                    format!("{} '{}' is missing mandatory tag '{}'", target.label(), name, requirement.key),
+                )),
+                Some(value)
+                    if !requirement.allowed_values.is_empty()
+                        && !requirement.allowed_values.iter().any(|allowed| allowed == value) =>
+                {
+                    violations.push(violation(
+                        NamingViolationKind::TagValue,
+                        format!(
+                            "{} '{}' has tag {}={}; allowed values are {}",
+                            target.label(),
+                            name,
+                            requirement.key,
+                            value,
+                            requirement.allowed_values.join(", ")
+                        ),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        violations
+    }
+}
+
+/// Compile a rule into a regex anchored to the whole name
+pub fn compile(rule: &NamingRule) -> Result<Regex, String> {
+    if rule.pattern.trim().is_empty() || rule.pattern.len() > MAX_PATTERN_LENGTH {
+        return Err(format!(
+            "{} patterns must be 1 to {} characters",
+            rule.target.label(),
+            MAX_PATTERN_LENGTH
+        ));
+    }
+    let body = match rule.kind {
+        NamingPatternKind::Regex => rule.pattern.clone(),
+        NamingPatternKind::Tokens => token_regex(&rule.pattern)?,
+    };
+    RegexBuilder::new(&format!("^(?:{})$", body))
+        .case_insensitive(rule.case_insensitive)
+        .build()
+        .map_err(|e| format!("invalid {} pattern '{}': {}", rule.target.label(), rule.pattern, e))
+}
+
+/// Translate a token pattern: `{name}` is one or more letters or digits,
+/// `{name:a|b}` one of the listed values and `{###}` that many digits.
+/// Everything else is literal.
+pub fn token_regex(pattern: &str) -> Result<String, String> {
+    let mut regex = String::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find(['{', '}']) {
+        regex.push_str(&regex::escape(&rest[..open]));
+        if rest[open..].starts_with('}') {
+            return Err(format!("unmatched '}}' in token pattern '{}'", pattern));
+        }
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            return Err(format!("unclosed '{{' in token pattern '{}'", pattern));
+        };
+        let token = rest[open + 1..close].trim();
+        match token.split_once(':') {
+            _ if token.is_empty() => return Err(format!("empty token in pattern '{}'", pattern)),
+            Some((_, values)) => {
+                let values: Vec<String> = values
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(regex::escape)
+                    .collect();
+                if values.is_empty() {
+                    return Err(format!("token '{}' lists no values", token));
+                }
+                regex.push_str(&format!("(?:{})", values.join("|")));
+            }
+            None if token.chars().all(|c| c == '#') => regex.push_str(&format!("[0-9]{{{}}}", token.len())),
+            None => regex.push_str("[A-Za-z0-9]+"),
+        }
+        rest = &rest[close + 1..];
+    }
+    regex.push_str(&regex::escape(rest));
+    Ok(regex)
+}
+
+fn record_key(thing: &Option<Thing>) -> Option<String> {
+    thing.as_ref().map(|t| t.id.to_raw())
+}
+
+fn mapping_violations(policy: &CompiledPolicy, mapping: &MigrationWizardNetworkMapping) -> Vec<NamingViolation> {
+    policy.check(
+        NamingTarget::Network,
+        record_key(&mapping.id),
+        &mapping.destination_vlan_name,
+        &mapping.tags,
+    )
+}
+
+/// Violations of a project's target clusters, placed VMs and destination
+/// networks, in that order
+pub fn project_violations(
+    policy: &CompiledPolicy,
+    clusters: &[MigrationWizardCluster],
+    placements: &[MigrationWizardPlacement],
+    vms: &[MigrationWizardVM],
+    mappings: &[MigrationWizardNetworkMapping],
+) -> Vec<NamingViolation> {
+    let vm_names: HashMap<&Thing, &str> = vms
+        .iter()
+        .filter_map(|vm| vm.id.as_ref().map(|id| (id, vm.name.as_str())))
+        .collect();
+    let mut violations: Vec<NamingViolation> = clusters
+        .iter()
+        .flat_map(|c| policy.check(NamingTarget::Cluster, record_key(&c.id), &c.name, &c.tags))
+        .collect();
+    for placement in placements {
+        let Some(name) = vm_names.get(&placement.vm_id) else { continue };
+        violations.extend(policy.check(
+            NamingTarget::Vm,
+            Some(placement.vm_id.id.to_raw()),
+            name,
+            &placement.tags,
+        ));
+    }
+    violations.extend(mappings.iter().flat_map(|m| mapping_violations(policy, m)));
+    violations
+}
+
+/// Violations as destination cluster validation issues
+pub fn issues(violations: &[NamingViolation]) -> Vec<ValidationIssue> {
+    violations
+        .iter()
+        .map(|v| ValidationIssue {
+            severity: ValidationSeverity::Error,
+            category: NAMING_POLICY_CATEGORY.to_string(),
+            message: v.message.clone(),
+            recommendation: Some(match v.kind {
+                NamingViolationKind::Name => "Rename the object to match the tenant's naming convention".to_string(),
+                NamingViolationKind::MissingTag | NamingViolationKind::TagValue => {
+                    "Set the tag in the object's metadata tags".to_string()
+                }
+            }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(target: NamingTarget, kind: NamingPatternKind, pattern: &str) -> NamingRule {
+        NamingRule {
+            target,
+            kind,
+            pattern: pattern.to_string(),
+            case_insensitive: false,
+            description: None,
+        }
+    }
+
+    fn policy(rules: Vec<NamingRule>, mandatory_tags: Vec<TagRequirement>) -> CompiledPolicy {
+        CompiledPolicy::new(&NamingPolicy {
+            id: None,
+            tenant_id: Some("acme".to_string()),
+            rules,
+            mandatory_tags,
+            updated_by: "admin".to_string(),
+            updated_at: Utc::now(),
+        })
+        .unwrap()
+    }
+
+    fn tags(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_token_patterns_translate_to_anchored_regexes() {
+        assert_eq!(token_regex("{site}-{env:prd|tst}-CL{##}").unwrap(), "[A-Za-z0-9]+-(?:prd|tst)-CL[0-9]{2}");
+        assert_eq!(token_regex("vlan.{###}").unwrap(), r"vlan\.[0-9]{3}");
+        assert!(token_regex("{site").is_err());
+        assert!(token_regex("site}").is_err());
+        assert!(token_regex("{env:|}").is_err());
+
+        let cluster = compile(&rule(NamingTarget::Cluster, NamingPatternKind::Tokens, "{site}-{env:prd|tst}-CL{##}")).unwrap();
+        assert!(cluster.is_match("AMS1-prd-CL01"));
+        assert!(!cluster.is_match("AMS1-prd-CL1"));
+        assert!(!cluster.is_match("AMS1-dev-CL01"));
+        assert!(!cluster.is_match("x-AMS1-prd-CL01"), "patterns match the whole name");
+    }
+
+    #[test]
+    fn test_invalid_regexes_are_rejected() {
+        let error = compile(&rule(NamingTarget::Vm, NamingPatternKind::Regex, "vm-(")).unwrap_err();
+        assert!(error.starts_with("invalid VM pattern 'vm-('"));
+    }
+
+    #[test]
+    fn test_check_reports_name_missing_tags_and_disallowed_values() {
+        let mut insensitive = rule(NamingTarget::Vm, NamingPatternKind::Regex, "[a-z]{3}-(app|db)[0-9]{2}");
+        insensitive.case_insensitive = true;
+        let policy = policy(
+            vec![insensitive, rule(NamingTarget::Cluster, NamingPatternKind::Regex, "cl-.+")],
+            vec![
+                TagRequirement { target: NamingTarget::Vm, key: "owner".to_string(), allowed_values: vec![] },
+                TagRequirement {
+                    target: NamingTarget::Vm,
+                    key: "env".to_string(),
+                    allowed_values: vec!["prod".to_string(), "test".to_string()],
+                },
+            ],
+        );
+
+        assert!(policy.check(NamingTarget::Vm, None, "AMS-DB01", &tags(&[("owner", "dba"), ("env", "prod")])).is_empty());
+
+        let violations = policy.check(NamingTarget::Vm, Some("vm1".to_string()), "sqlserver", &tags(&[("env", "dev")]));
+        let kinds: Vec<NamingViolationKind> = violations.iter().map(|v| v.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![NamingViolationKind::Name, NamingViolationKind::MissingTag, NamingViolationKind::TagValue]
+        );
+        assert_eq!(violations[0].message, "VM name 'sqlserver' does not match [a-z]{3}-(app|db)[0-9]{2}");
+        assert_eq!(violations[2].message, "VM 'sqlserver' has tag env=dev; allowed values are prod, test");
+
+        assert!(
+            policy.check(NamingTarget::Network, None, "anything", &BTreeMap::new()).is_empty(),
+            "targets without rules or tags are not checked"
+        );
+    }
+
+    #[test]
+    fn test_blank_tag_keys_are_rejected() {
+        let result = CompiledPolicy::new(&NamingPolicy {
+            id: None,
+            tenant_id: None,
+            rules: vec![],
+            mandatory_tags: vec![TagRequirement { target: NamingTarget::Network, key: " ".to_string(), allowed_values: vec![] }],
+            updated_by: "admin".to_string(),
+            updated_at: Utc::now(),
+        });
+        assert_eq!(result.err().as_deref(), Some("Network tag keys must not be blank"));
+    }
+}
@@ -0,0 +1,249 @@
+//! Network Conflict Service
+//!
+//! Finds network mappings of different migration projects that target the
+//! same destination VLAN or overlapping destination subnets. A project is
+//! compared with the projects of its tenant and with shared projects (those
+//! without a tenant), the same rule the capacity overview uses. Archived
+//! projects are skipped.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use surrealdb::sql::{Id, Thing};
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::migration_wizard_models::{
+    CrossProjectNetworkConflict, MigrationWizardNetworkMapping, MigrationWizardProject, NetworkErrorType,
+};
+use crate::utils::cidr::{CidrRelation, Ipv4Cidr};
+
+pub struct NetworkConflictService {
+    db: Database,
+}
+
+impl NetworkConflictService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Conflicts between a project's mappings and those of the other
+    /// projects it shares a tenant with
+    pub async fn project_conflicts(&self, project_id: &str) -> Result<Vec<CrossProjectNetworkConflict>> {
+        let mappings: Vec<MigrationWizardNetworkMapping> = self
+            .db
+            .query("SELECT * FROM migration_wizard_network_mapping WHERE project_id = $project")
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("network_conflict_service.project_conflicts")
+            .await
+            .context("Failed to query network mappings")?
+            .take(0)
+            .context("Failed to parse network mappings")?;
+        self.conflicts_for(project_id, &mappings).await
+    }
+
+    /// Conflicts between the given mappings of a project and those of the
+    /// other projects it shares a tenant with
+    pub async fn conflicts_for(
+        &self,
+        project_id: &str,
+        mappings: &[MigrationWizardNetworkMapping],
+    ) -> Result<Vec<CrossProjectNetworkConflict>> {
+        if mappings.is_empty() {
+            return Ok(Vec::new());
+        }
+        let project: Option<MigrationWizardProject> = self
+            .db
+            .select(("migration_wizard_project", project_id))
+            .await
+            .context("Failed to fetch project")?;
+        let Some(project) = project else {
+            return Ok(Vec::new());
+        };
+
+        let peers: Vec<MigrationWizardProject> = self
+            .db
+            .query("SELECT * FROM migration_wizard_project WHERE status != 'archived' AND id != $project")
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("network_conflict_service.conflicts_for")
+            .await
+            .context("Failed to query projects")?
+            .take(0)
+            .context("Failed to parse projects")?;
+        let peers: Vec<MigrationWizardProject> = peers
+            .into_iter()
+            .filter(|p| shares_tenant(&project, p))
+            .collect();
+        if peers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let peer_ids: Vec<Thing> = peers.iter().filter_map(|p| p.id.clone()).collect();
+        let peer_mappings: Vec<MigrationWizardNetworkMapping> = self
+            .db
+            .query("SELECT * FROM migration_wizard_network_mapping WHERE project_id INSIDE $projects")
+            .bind(("projects", peer_ids))
+            .traced("network_conflict_service.conflicts_for")
+            .await
+            .context("Failed to query network mappings")?
+            .take(0)
+            .context("Failed to parse network mappings")?;
+
+        let names: HashMap<Thing, String> = peers
+            .into_iter()
+            .filter_map(|p| Some((p.id?, p.name)))
+            .collect();
+        Ok(find_conflicts(mappings, &peer_mappings, &names))
+    }
+}
+
+/// Projects are compared when they have the same tenant or either is shared
+fn shares_tenant(project: &MigrationWizardProject, other: &MigrationWizardProject) -> bool {
+    match (&project.tenant_id, &other.tenant_id) {
+        (Some(tenant), Some(owner)) => tenant == owner,
+        _ => true,
+    }
+}
+
+/// Pair every mapping with every mapping of the other projects and report
+/// the ones targeting the same destination VLAN or an overlapping subnet.
+/// Subnets that don't parse are left to the per-mapping validation.
+pub fn find_conflicts(
+    mappings: &[MigrationWizardNetworkMapping],
+    others: &[MigrationWizardNetworkMapping],
+    project_names: &HashMap<Thing, String>,
+) -> Vec<CrossProjectNetworkConflict> {
+    let subnet = |m: &MigrationWizardNetworkMapping| {
+        m.destination_subnet
+            .as_deref()
+            .and_then(|s| s.parse::<Ipv4Cidr>().ok())
+    };
+
+    let mut conflicts = Vec::new();
+    for mapping in mappings {
+        let own_subnet = subnet(mapping);
+        for other in others {
+            let project_name = project_names
+                .get(&other.project_id)
+                .map(String::as_str)
+                .unwrap_or("another project");
+            let conflict = |conflict_type, message: String| CrossProjectNetworkConflict {
+                mapping_id: mapping_key(mapping),
+                conflict_type,
+                other_project_id: key(&other.project_id),
+                other_project_name: project_name.to_string(),
+                other_mapping_id: mapping_key(other),
+                message,
+            };
+
+            if let (Some(vlan_id), Some(other_vlan_id)) = (mapping.destination_vlan_id, other.destination_vlan_id) {
+                if vlan_id == other_vlan_id {
+                    conflicts.push(conflict(
+                        NetworkErrorType::VlanConflict,
+                        format!(
+                            "{}: destination VLAN {} is also targeted by '{}' in project '{}'",
+                            mapping.source_vlan_name, vlan_id, other.source_vlan_name, project_name
+                        ),
+                    ));
+                }
+            }
+
+            if let (Some(own), Some(theirs)) = (own_subnet, subnet(other)) {
+                let relation = match own.relation(&theirs) {
+                    CidrRelation::Disjoint => continue,
+                    CidrRelation::Equal => "is also".to_string(),
+                    CidrRelation::Contains => format!("contains {}, which is", theirs),
+                    CidrRelation::ContainedBy => format!("lies inside {}, which is", theirs),
+                };
+                conflicts.push(conflict(
+                    NetworkErrorType::SubnetOverlap,
+                    format!(
+                        "{}: destination subnet {} {} targeted by '{}' in project '{}'",
+                        mapping.source_vlan_name, own, relation, other.source_vlan_name, project_name
+                    ),
+                ));
+            }
+        }
+    }
+    conflicts
+}
+
+fn mapping_key(mapping: &MigrationWizardNetworkMapping) -> String {
+    mapping.id.as_ref().map(key).unwrap_or_else(|| "unknown".to_string())
+}
+
+fn key(thing: &Thing) -> String {
+    match &thing.id {
+        Id::String(s) => s.clone(),
+        Id::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn mapping(project: &str, id: &str, vlan_id: Option<i32>, subnet: Option<&str>) -> MigrationWizardNetworkMapping {
+        MigrationWizardNetworkMapping {
+            id: Some(Thing::from(("migration_wizard_network_mapping", id))),
+            project_id: Thing::from(("migration_wizard_project", project)),
+            source_vlan_name: format!("VLAN-{}", id),
+            source_vlan_id: None,
+            source_subnet: None,
+            destination_vlan_name: String::new(),
+            destination_vlan_id: vlan_id,
+            destination_subnet: subnet.map(str::to_string),
+            destination_gateway: None,
+            destination_dns: None,
+            is_valid: true,
+            validation_errors: None,
+            tags: Default::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn names() -> HashMap<Thing, String> {
+        HashMap::from([(Thing::from(("migration_wizard_project", "b")), "Branch".to_string())])
+    }
+
+    #[test]
+    fn test_same_vlan_and_nested_subnets_conflict_across_projects() {
+        let own = [mapping("a", "m1", Some(120), Some("10.20.0.0/16"))];
+        let others = [
+            mapping("b", "m2", Some(120), Some("10.20.30.0/24")),
+            mapping("b", "m3", Some(121), Some("10.21.0.0/24")),
+        ];
+
+        let conflicts = find_conflicts(&own, &others, &names());
+
+        assert_eq!(conflicts.len(), 2);
+        assert!(matches!(conflicts[0].conflict_type, NetworkErrorType::VlanConflict));
+        assert_eq!(conflicts[0].other_mapping_id, "m2");
+        assert_eq!(conflicts[0].other_project_name, "Branch");
+        assert!(matches!(conflicts[1].conflict_type, NetworkErrorType::SubnetOverlap));
+        assert!(conflicts[1].message.contains("10.20.0.0/16 contains 10.20.30.0/24"));
+    }
+
+    #[test]
+    fn test_contained_and_equal_subnets_are_described() {
+        let own = [
+            mapping("a", "m1", None, Some("10.20.30.128/25")),
+            mapping("a", "m2", None, Some("10.20.30.7/24")),
+        ];
+        let others = [mapping("b", "m3", None, Some("10.20.30.0/24"))];
+
+        let conflicts = find_conflicts(&own, &others, &names());
+
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts[0].message.contains("lies inside 10.20.30.0/24"));
+        assert!(conflicts[1].message.contains("10.20.30.0/24 is also targeted"));
+    }
+
+    #[test]
+    fn test_unparseable_subnets_and_missing_vlans_are_ignored() {
+        let own = [mapping("a", "m1", None, Some("10.20.30.0"))];
+        let others = [mapping("b", "m2", None, Some("10.20.30.0/24"))];
+        assert!(find_conflicts(&own, &others, &names()).is_empty());
+    }
+}
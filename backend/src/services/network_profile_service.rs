@@ -0,0 +1,818 @@
+//! Network Profile Service
+//!
+//! Library of network profile templates: the seeded standard ones and custom
+//! ones users author. The validation engine checks a destination cluster
+//! against a template: its nodes' NIC count, bandwidth and RDMA capability,
+//! and the VLANs of its networks. Failures are written into the cluster's
+//! `validation_results`.
+//!
+//! Node facts come from the hardware pool: `network_ports` is the NIC count.
+//! Hardware imports record `nic_speed_gbps` and `rdma_capable` in the node's
+//! metadata.
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::project_models::*;
+
+const TABLE: &str = "network_profile_template";
+
+/// Category of the issues this engine writes into `validation_results`
+pub const NETWORK_PROFILE_CATEGORY: &str = "Network Profile";
+
+const MAX_NICS: i32 = 64;
+
+#[derive(Debug, Error)]
+pub enum NetworkProfileError {
+    #[error("Network profile template not found")]
+    NotFound,
+
+    #[error("Destination cluster not found")]
+    ClusterNotFound,
+
+    #[error("Standard templates cannot be changed; create a custom template instead")]
+    Standard,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Template is used by {0} network profile(s)")]
+    InUse(usize),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for NetworkProfileError {
+    fn from(e: surrealdb::Error) -> Self {
+        NetworkProfileError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct NetworkProfileService {
+    db: Database,
+}
+
+impl NetworkProfileService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_templates(
+        &self,
+        filter: &NetworkProfileTemplateFilter,
+    ) -> Result<Vec<NetworkProfileTemplate>, NetworkProfileError> {
+        let templates: Vec<NetworkProfileTemplate> = self
+            .db
+            .query(
+                "SELECT * FROM network_profile_template WHERE $all OR is_active = true \
+                 ORDER BY is_standard DESC, name ASC",
+            )
+            .bind(("all", filter.include_inactive))
+            .traced("network_profile_service.list_templates")
+            .await?
+            .take(0)?;
+        Ok(templates
+            .into_iter()
+            .filter(|t| filter.hypervisor.is_none() || filter.hypervisor == Some(t.hypervisor))
+            .collect())
+    }
+
+    pub async fn get_template(&self, id: &str) -> Result<NetworkProfileTemplate, NetworkProfileError> {
+        let template: Option<NetworkProfileTemplate> = self.db.select(template_thing(id)).await?;
+        template.ok_or(NetworkProfileError::NotFound)
+    }
+
+    pub async fn create_template(
+        &self,
+        user_id: &str,
+        request: SaveNetworkProfileTemplateRequest,
+    ) -> Result<NetworkProfileTemplate, NetworkProfileError> {
+        let mut template = build_template(request)?;
+        template.created_by = Some(user_id.to_string());
+
+        let created: Vec<NetworkProfileTemplate> = self.db.create(TABLE).content(template).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| NetworkProfileError::DatabaseError("Failed to create template".to_string()))
+    }
+
+    /// Replace a custom template's definition
+    pub async fn update_template(
+        &self,
+        id: &str,
+        request: SaveNetworkProfileTemplateRequest,
+    ) -> Result<NetworkProfileTemplate, NetworkProfileError> {
+        let existing = self.get_template(id).await?;
+        if existing.is_standard {
+            return Err(NetworkProfileError::Standard);
+        }
+        let mut template = build_template(request)?;
+        template.created_by = existing.created_by;
+        template.created_at = existing.created_at;
+
+        let updated: Option<NetworkProfileTemplate> =
+            self.db.update(template_thing(id)).content(template).await?;
+        updated.ok_or(NetworkProfileError::NotFound)
+    }
+
+    /// Delete a custom template no network profile uses
+    pub async fn delete_template(&self, id: &str) -> Result<(), NetworkProfileError> {
+        let existing = self.get_template(id).await?;
+        if existing.is_standard {
+            return Err(NetworkProfileError::Standard);
+        }
+        let profiles: Vec<Value> = self
+            .db
+            .query("SELECT id FROM network_profile_instance WHERE template_id = $template")
+            .bind(("template", template_thing(id)))
+            .traced("network_profile_service.delete_template")
+            .await?
+            .take(0)?;
+        if !profiles.is_empty() {
+            return Err(NetworkProfileError::InUse(profiles.len()));
+        }
+        let _: Option<NetworkProfileTemplate> = self.db.delete(template_thing(id)).await?;
+        Ok(())
+    }
+
+    /// Check a cluster against a template and write the failures into its
+    /// `validation_results`, replacing those of the previous check
+    pub async fn validate_cluster(
+        &self,
+        cluster_id: &str,
+        request: &ValidateClusterNetworkRequest,
+    ) -> Result<ClusterNetworkValidation, NetworkProfileError> {
+        let cluster: Option<DestinationCluster> = self.db.select(("destination_cluster", cluster_id)).await?;
+        let mut cluster = cluster.ok_or(NetworkProfileError::ClusterNotFound)?;
+
+        let template = match &request.template_id {
+            Some(template_id) => self.get_template(template_id).await?,
+            None => self.applied_template(&cluster).await?.ok_or_else(|| {
+                NetworkProfileError::Validation(
+                    "The cluster has no network profile applied; pass a template_id".to_string(),
+                )
+            })?,
+        };
+        let validation = self.run(cluster_id, &cluster, &template).await?;
+
+        cluster
+            .validation_results
+            .retain(|issue| issue.category != NETWORK_PROFILE_CATEGORY);
+        cluster.validation_results.extend(issues(&validation.results));
+        cluster.updated_at = Utc::now();
+        let updated: Option<DestinationCluster> = self
+            .db
+            .update(("destination_cluster", cluster_id))
+            .content(cluster)
+            .await?;
+        updated.ok_or(NetworkProfileError::ClusterNotFound)?;
+
+        Ok(validation)
+    }
+
+    /// Issues from checking a cluster against the template of its applied
+    /// network profile; empty when none is applied
+    pub async fn applied_profile_issues(
+        &self,
+        cluster_id: &str,
+        cluster: &DestinationCluster,
+    ) -> Result<Vec<ValidationIssue>, NetworkProfileError> {
+        let Some(template) = self.applied_template(cluster).await? else {
+            return Ok(Vec::new());
+        };
+        let validation = self.run(cluster_id, cluster, &template).await?;
+        Ok(issues(&validation.results))
+    }
+
+    async fn run(
+        &self,
+        cluster_id: &str,
+        cluster: &DestinationCluster,
+        template: &NetworkProfileTemplate,
+    ) -> Result<ClusterNetworkValidation, NetworkProfileError> {
+        let nodes = self.node_facts(cluster).await?;
+        let results = evaluate(template, &nodes, &cluster_networks(cluster));
+        Ok(ClusterNetworkValidation {
+            cluster_id: cluster_id.to_string(),
+            template_id: template.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+            template_name: template.name.clone(),
+            passed: !results.iter().any(is_blocking),
+            results,
+            nodes,
+            checked_at: Utc::now(),
+        })
+    }
+
+    async fn applied_template(
+        &self,
+        cluster: &DestinationCluster,
+    ) -> Result<Option<NetworkProfileTemplate>, NetworkProfileError> {
+        #[derive(Deserialize)]
+        struct ProfileRef {
+            template_id: Thing,
+        }
+
+        let Some(profile_id) = cluster.network_profile_id.clone() else {
+            return Ok(None);
+        };
+        let profile: Option<ProfileRef> = self.db.select(profile_id).await?;
+        let Some(profile) = profile else {
+            return Ok(None);
+        };
+        let template: Option<NetworkProfileTemplate> = self.db.select(profile.template_id).await?;
+        Ok(template)
+    }
+
+    async fn node_facts(&self, cluster: &DestinationCluster) -> Result<Vec<NodeNetworkFacts>, NetworkProfileError> {
+        let mut facts = Vec::new();
+        for node in &cluster.nodes {
+            let pool: Option<HardwarePool> = self.db.select(node.clone()).await?;
+            if let Some(pool) = pool {
+                facts.push(node_network_facts(&pool));
+            }
+        }
+        Ok(facts)
+    }
+}
+
+/// Accepts "network_profile_template:abc" as well as the bare key
+fn template_thing(id: &str) -> Thing {
+    Thing::from((TABLE, id.strip_prefix("network_profile_template:").unwrap_or(id)))
+}
+
+pub fn node_network_facts(pool: &HardwarePool) -> NodeNetworkFacts {
+    NodeNetworkFacts {
+        asset_tag: pool.asset_tag.clone(),
+        nic_count: pool.network_ports,
+        nic_speed_gbps: pool.metadata.get("nic_speed_gbps").and_then(Value::as_f64),
+        rdma_capable: pool.metadata.get("rdma_capable").and_then(Value::as_bool),
+    }
+}
+
+/// The cluster's configured networks by purpose
+pub fn cluster_networks(cluster: &DestinationCluster) -> Vec<(NetworkPurpose, &NetworkConfig)> {
+    let mut networks = vec![
+        (NetworkPurpose::Management, &cluster.management_network),
+        (NetworkPurpose::Workload, &cluster.workload_network),
+    ];
+    if let Some(storage) = &cluster.storage_network {
+        networks.push((NetworkPurpose::Storage, storage));
+    }
+    if let Some(migration) = &cluster.migration_network {
+        networks.push((NetworkPurpose::Migration, migration));
+    }
+    networks
+}
+
+fn build_template(request: SaveNetworkProfileTemplateRequest) -> Result<NetworkProfileTemplate, NetworkProfileError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(NetworkProfileError::Validation("Template name is required".to_string()));
+    }
+    if !(1..=MAX_NICS).contains(&request.required_nics) {
+        return Err(NetworkProfileError::Validation(format!(
+            "required_nics must be between 1 and {}",
+            MAX_NICS
+        )));
+    }
+    let recommended_nics = request.recommended_nics.unwrap_or(request.required_nics);
+    if !(request.required_nics..=MAX_NICS).contains(&recommended_nics) {
+        return Err(NetworkProfileError::Validation(
+            "recommended_nics must be at least required_nics".to_string(),
+        ));
+    }
+    if !(request.min_bandwidth_gbps > 0.0 && request.min_bandwidth_gbps <= 800.0) {
+        return Err(NetworkProfileError::Validation(
+            "min_bandwidth_gbps must be greater than 0 and at most 800".to_string(),
+        ));
+    }
+    validate_vlan_requirements(&request.vlan_requirements)?;
+    for rule in &request.validation_rules {
+        validate_rule(rule)?;
+    }
+
+    let now = Utc::now();
+    Ok(NetworkProfileTemplate {
+        id: None,
+        name,
+        description: request.description,
+        hypervisor: request.hypervisor,
+        storage_type: request.storage_type,
+        required_nics: request.required_nics,
+        recommended_nics,
+        requires_rdma: request.requires_rdma,
+        requires_teaming: request.requires_teaming,
+        min_bandwidth_gbps: request.min_bandwidth_gbps,
+        network_topology: request.network_topology,
+        vlan_requirements: request.vlan_requirements,
+        validation_rules: request.validation_rules,
+        example_configuration: request.example_configuration,
+        documentation_url: request.documentation_url,
+        is_standard: false,
+        is_active: request.is_active.unwrap_or(true),
+        metadata: request.metadata.unwrap_or_default(),
+        created_by: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+fn validate_vlan_requirements(requirements: &[VlanRequirement]) -> Result<(), NetworkProfileError> {
+    let mut purposes = HashSet::new();
+    for requirement in requirements {
+        if !purposes.insert(requirement.purpose.clone()) {
+            return Err(NetworkProfileError::Validation(format!(
+                "More than one VLAN requirement for {}",
+                requirement.purpose.key()
+            )));
+        }
+        if let Some(range) = &requirement.vlan_id_range {
+            if !(1 <= range.min && range.min <= range.max && range.max <= 4094) {
+                return Err(NetworkProfileError::Validation(format!(
+                    "VLAN range for {} must lie within 1-4094 with min <= max",
+                    requirement.purpose.key()
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check a rule's parameters are the ones its type reads
+fn validate_rule(rule: &NetworkValidationRule) -> Result<(), NetworkProfileError> {
+    let invalid = |message: &str| {
+        NetworkProfileError::Validation(format!("{} rule: {}", rule.rule_type.key(), message))
+    };
+    if rule.error_message.trim().is_empty() {
+        return Err(invalid("error_message is required"));
+    }
+    let allowed: &[&str] = match rule.rule_type {
+        NetworkRuleType::MinBandwidth => &["min_gbps"],
+        NetworkRuleType::NicCount => &["min"],
+        NetworkRuleType::RdmaSupport => &[],
+        NetworkRuleType::VlanSeparation | NetworkRuleType::TeamingConfig => &["purposes"],
+    };
+    if let Some(unknown) = rule.parameters.keys().find(|k| !allowed.contains(&k.as_str())) {
+        return Err(invalid(&format!("unknown parameter '{}'", unknown)));
+    }
+    if let Some(value) = rule.parameters.get("min_gbps") {
+        if !value.as_f64().is_some_and(|v| v > 0.0) {
+            return Err(invalid("min_gbps must be a positive number"));
+        }
+    }
+    if let Some(value) = rule.parameters.get("min") {
+        if !value.as_i64().is_some_and(|v| v >= 1) {
+            return Err(invalid("min must be a positive integer"));
+        }
+    }
+    if rule.parameters.contains_key("purposes") {
+        rule_purposes(rule).map_err(|message| invalid(&message))?;
+    }
+    Ok(())
+}
+
+/// `purposes` parameter of a rule; None when the rule doesn't narrow them
+fn rule_purposes(rule: &NetworkValidationRule) -> Result<Option<Vec<NetworkPurpose>>, String> {
+    let Some(value) = rule.parameters.get("purposes") else {
+        return Ok(None);
+    };
+    serde_json::from_value(value.clone())
+        .map(Some)
+        .map_err(|_| "purposes must be a list of management, workload, storage, migration or backup".to_string())
+}
+
+// ============================================================================
+// VALIDATION ENGINE
+// ============================================================================
+
+/// Check nodes and networks against a template's requirements, then run its
+/// validation rules. Every requirement and rule yields one result.
+pub fn evaluate(
+    template: &NetworkProfileTemplate,
+    nodes: &[NodeNetworkFacts],
+    networks: &[(NetworkPurpose, &NetworkConfig)],
+) -> Vec<NetworkValidationResult> {
+    let error = ValidationSeverity::Error;
+    let mut results = vec![
+        check_nic_count("nic_count", nodes, template.required_nics, error.clone()),
+        check_bandwidth("min_bandwidth", nodes, template.min_bandwidth_gbps, error.clone()),
+    ];
+    if template.recommended_nics > template.required_nics {
+        results.push(check_nic_count(
+            "recommended_nic_count",
+            nodes,
+            template.recommended_nics,
+            ValidationSeverity::Warning,
+        ));
+    }
+    if template.requires_rdma {
+        results.push(check_rdma("rdma_support", nodes, error.clone()));
+    }
+    if template.requires_teaming {
+        results.push(check_teaming("teaming_config", networks, None, error.clone()));
+    }
+    for requirement in &template.vlan_requirements {
+        results.push(check_vlan_requirement(requirement, networks));
+    }
+
+    for rule in &template.validation_rules {
+        let name = format!("rule:{}", rule.rule_type.key());
+        let severity = rule.severity.clone();
+        let mut result = match rule.rule_type {
+            NetworkRuleType::MinBandwidth => {
+                let min = rule.parameters.get("min_gbps").and_then(Value::as_f64);
+                check_bandwidth(&name, nodes, min.unwrap_or(template.min_bandwidth_gbps), severity)
+            }
+            NetworkRuleType::NicCount => {
+                let min = rule.parameters.get("min").and_then(Value::as_i64).map(|v| v as i32);
+                check_nic_count(&name, nodes, min.unwrap_or(template.required_nics), severity)
+            }
+            NetworkRuleType::RdmaSupport => check_rdma(&name, nodes, severity),
+            NetworkRuleType::VlanSeparation => {
+                check_vlan_separation(&name, networks, rule_purposes(rule).ok().flatten(), severity)
+            }
+            NetworkRuleType::TeamingConfig => {
+                check_teaming(&name, networks, rule_purposes(rule).ok().flatten(), severity)
+            }
+        };
+        if !result.passed {
+            result.message = format!("{}: {}", rule.error_message, result.message);
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Failed results as cluster validation issues
+pub fn issues(results: &[NetworkValidationResult]) -> Vec<ValidationIssue> {
+    results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| ValidationIssue {
+            severity: r.severity.clone(),
+            category: NETWORK_PROFILE_CATEGORY.to_string(),
+            message: r.message.clone(),
+            recommendation: r.recommendation.clone(),
+        })
+        .collect()
+}
+
+fn is_blocking(result: &NetworkValidationResult) -> bool {
+    !result.passed && matches!(result.severity, ValidationSeverity::Error | ValidationSeverity::Critical)
+}
+
+fn result(
+    rule_name: &str,
+    severity: ValidationSeverity,
+    outcome: Result<String, (String, String)>,
+) -> NetworkValidationResult {
+    let (passed, message, recommendation) = match outcome {
+        Ok(message) => (true, message, None),
+        Err((message, recommendation)) => (false, message, Some(recommendation)),
+    };
+    NetworkValidationResult {
+        rule_name: rule_name.to_string(),
+        passed,
+        message,
+        severity,
+        recommendation,
+    }
+}
+
+/// Pass when every node's value satisfies `ok`. A node without the value
+/// fails too, since the requirement can't be confirmed.
+fn check_nodes<T: Copy>(
+    nodes: &[NodeNetworkFacts],
+    value: impl Fn(&NodeNetworkFacts) -> Option<T>,
+    ok: impl Fn(T) -> bool,
+) -> (Vec<&str>, Vec<&str>) {
+    let mut failing = Vec::new();
+    let mut unknown = Vec::new();
+    for node in nodes {
+        match value(node) {
+            Some(v) if ok(v) => {}
+            Some(_) => failing.push(node.asset_tag.as_str()),
+            None => unknown.push(node.asset_tag.as_str()),
+        }
+    }
+    (failing, unknown)
+}
+
+fn node_outcome(
+    nodes: &[NodeNetworkFacts],
+    (failing, unknown): (Vec<&str>, Vec<&str>),
+    requirement: String,
+    unknown_fact: &str,
+) -> Result<String, (String, String)> {
+    if nodes.is_empty() {
+        return Err((
+            format!("The cluster has no nodes to check for {}", requirement),
+            "Assign hardware pool nodes to the cluster".to_string(),
+        ));
+    }
+    if !failing.is_empty() {
+        return Err((
+            format!("{} of {} node(s) lack {}: {}", failing.len(), nodes.len(), requirement, failing.join(", ")),
+            "Replace or upgrade the network adapters of these nodes".to_string(),
+        ));
+    }
+    if !unknown.is_empty() {
+        return Err((
+            format!("{} is not recorded for {}", unknown_fact, unknown.join(", ")),
+            format!("Record {} for these nodes in the hardware pool", unknown_fact),
+        ));
+    }
+    Ok(format!("All {} node(s) have {}", nodes.len(), requirement))
+}
+
+fn check_nic_count(
+    rule_name: &str,
+    nodes: &[NodeNetworkFacts],
+    min: i32,
+    severity: ValidationSeverity,
+) -> NetworkValidationResult {
+    let split = check_nodes(nodes, |n| n.nic_count, |count| count >= min);
+    result(
+        rule_name,
+        severity,
+        node_outcome(nodes, split, format!("{} or more NICs", min), "the NIC count"),
+    )
+}
+
+fn check_bandwidth(
+    rule_name: &str,
+    nodes: &[NodeNetworkFacts],
+    min_gbps: f64,
+    severity: ValidationSeverity,
+) -> NetworkValidationResult {
+    let split = check_nodes(nodes, |n| n.nic_speed_gbps, |speed| speed >= min_gbps);
+    result(
+        rule_name,
+        severity,
+        node_outcome(nodes, split, format!("{} Gbps or faster NICs", min_gbps), "nic_speed_gbps"),
+    )
+}
+
+fn check_rdma(rule_name: &str, nodes: &[NodeNetworkFacts], severity: ValidationSeverity) -> NetworkValidationResult {
+    let split = check_nodes(nodes, |n| n.rdma_capable, |rdma| rdma);
+    result(
+        rule_name,
+        severity,
+        node_outcome(nodes, split, "RDMA-capable NICs".to_string(), "rdma_capable"),
+    )
+}
+
+fn selected<'a>(
+    networks: &'a [(NetworkPurpose, &'a NetworkConfig)],
+    purposes: &Option<Vec<NetworkPurpose>>,
+) -> impl Iterator<Item = &'a (NetworkPurpose, &'a NetworkConfig)> + 'a {
+    let purposes = purposes.clone();
+    networks
+        .iter()
+        .filter(move |(purpose, _)| purposes.as_ref().map(|p| p.contains(purpose)).unwrap_or(true))
+}
+
+fn check_teaming(
+    rule_name: &str,
+    networks: &[(NetworkPurpose, &NetworkConfig)],
+    purposes: Option<Vec<NetworkPurpose>>,
+    severity: ValidationSeverity,
+) -> NetworkValidationResult {
+    let unteamed: Vec<&str> = selected(networks, &purposes)
+        .filter(|(_, config)| !config.nic_teaming)
+        .map(|(purpose, _)| purpose.key())
+        .collect();
+    let outcome = if unteamed.is_empty() {
+        Ok("NIC teaming is enabled on the checked networks".to_string())
+    } else {
+        Err((
+            format!("NIC teaming is off on the {} network(s)", unteamed.join(", ")),
+            "Enable NIC teaming (SET or LACP) on these networks".to_string(),
+        ))
+    };
+    result(rule_name, severity, outcome)
+}
+
+fn check_vlan_separation(
+    rule_name: &str,
+    networks: &[(NetworkPurpose, &NetworkConfig)],
+    purposes: Option<Vec<NetworkPurpose>>,
+    severity: ValidationSeverity,
+) -> NetworkValidationResult {
+    let mut by_vlan: HashMap<i32, Vec<&str>> = HashMap::new();
+    for (purpose, config) in selected(networks, &purposes) {
+        if let Some(vlan_id) = config.vlan_id {
+            by_vlan.entry(vlan_id).or_default().push(purpose.key());
+        }
+    }
+    let mut shared: Vec<String> = by_vlan
+        .iter()
+        .filter(|(_, purposes)| purposes.len() > 1)
+        .map(|(vlan_id, purposes)| format!("VLAN {} ({})", vlan_id, purposes.join(", ")))
+        .collect();
+    shared.sort();
+    let outcome = if shared.is_empty() {
+        Ok("Each checked network has its own VLAN".to_string())
+    } else {
+        Err((
+            format!("Networks share a VLAN: {}", shared.join("; ")),
+            "Give each network its own VLAN".to_string(),
+        ))
+    };
+    result(rule_name, severity, outcome)
+}
+
+fn check_vlan_requirement(
+    requirement: &VlanRequirement,
+    networks: &[(NetworkPurpose, &NetworkConfig)],
+) -> NetworkValidationResult {
+    let purpose = requirement.purpose.key();
+    let vlan_id = networks
+        .iter()
+        .find(|(p, _)| *p == requirement.purpose)
+        .and_then(|(_, config)| config.vlan_id);
+    let (severity, outcome) = match (vlan_id, &requirement.vlan_id_range) {
+        (None, _) if requirement.is_required => (
+            ValidationSeverity::Error,
+            Err((
+                format!("The {} network has no VLAN assigned", purpose),
+                format!("Assign a VLAN to the {} network", purpose),
+            )),
+        ),
+        (None, _) => (
+            ValidationSeverity::Info,
+            Ok(format!("The optional {} network is not configured", purpose)),
+        ),
+        (Some(id), Some(range)) if id < range.min || id > range.max => (
+            ValidationSeverity::Error,
+            Err((
+                format!("The {} VLAN {} is outside {}-{}", purpose, id, range.min, range.max),
+                format!("Pick a {} VLAN between {} and {}", purpose, range.min, range.max),
+            )),
+        ),
+        (Some(id), _) => (ValidationSeverity::Error, Ok(format!("The {} network uses VLAN {}", purpose, id))),
+    };
+    result(&format!("vlan:{}", purpose), severity, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> NetworkProfileTemplate {
+        NetworkProfileTemplate {
+            id: None,
+            name: "S2D".to_string(),
+            description: String::new(),
+            hypervisor: HypervisorType::HyperV,
+            storage_type: DestinationStorageType::S2D,
+            required_nics: 4,
+            recommended_nics: 6,
+            requires_rdma: true,
+            requires_teaming: false,
+            min_bandwidth_gbps: 25.0,
+            network_topology: NetworkTopology::Converged,
+            vlan_requirements: vec![VlanRequirement {
+                purpose: NetworkPurpose::Management,
+                vlan_id_range: Some(VlanRange { min: 100, max: 199 }),
+                is_required: true,
+                description: String::new(),
+            }],
+            validation_rules: Vec::new(),
+            example_configuration: None,
+            documentation_url: None,
+            is_standard: false,
+            is_active: true,
+            metadata: HashMap::new(),
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn node(tag: &str, nics: i32, speed: f64, rdma: bool) -> NodeNetworkFacts {
+        NodeNetworkFacts {
+            asset_tag: tag.to_string(),
+            nic_count: Some(nics),
+            nic_speed_gbps: Some(speed),
+            rdma_capable: Some(rdma),
+        }
+    }
+
+    fn network(vlan_id: Option<i32>, nic_teaming: bool) -> NetworkConfig {
+        NetworkConfig {
+            vlan_id,
+            subnet: None,
+            gateway: None,
+            dns_servers: Vec::new(),
+            mtu: None,
+            nic_teaming,
+        }
+    }
+
+    fn outcome<'a>(results: &'a [NetworkValidationResult], name: &str) -> &'a NetworkValidationResult {
+        results.iter().find(|r| r.rule_name == name).unwrap()
+    }
+
+    #[test]
+    fn test_evaluate_checks_nodes_against_template_requirements() {
+        let management = network(Some(150), true);
+        let networks = [(NetworkPurpose::Management, &management)];
+        let nodes = [node("n1", 6, 25.0, true), node("n2", 4, 10.0, false)];
+
+        let results = evaluate(&template(), &nodes, &networks);
+
+        assert!(outcome(&results, "nic_count").passed);
+        let recommended = outcome(&results, "recommended_nic_count");
+        assert!(!recommended.passed);
+        assert!(matches!(recommended.severity, ValidationSeverity::Warning));
+        assert!(outcome(&results, "min_bandwidth").message.contains("n2"));
+        assert!(!outcome(&results, "rdma_support").passed);
+        assert!(outcome(&results, "vlan:management").passed);
+        assert!(results.iter().all(|r| r.rule_name != "teaming_config"));
+    }
+
+    #[test]
+    fn test_unrecorded_node_facts_fail_rather_than_pass() {
+        let management = network(Some(150), true);
+        let mut unknown = node("n1", 6, 25.0, true);
+        unknown.rdma_capable = None;
+
+        let results = evaluate(&template(), &[unknown], &[(NetworkPurpose::Management, &management)]);
+        let rdma = outcome(&results, "rdma_support");
+        assert!(!rdma.passed);
+        assert!(rdma.message.contains("not recorded"));
+    }
+
+    #[test]
+    fn test_vlan_requirements_check_presence_and_range() {
+        let nodes = [node("n1", 6, 25.0, true)];
+        let outside = network(Some(250), true);
+        let results = evaluate(&template(), &nodes, &[(NetworkPurpose::Management, &outside)]);
+        assert!(outcome(&results, "vlan:management").message.contains("outside 100-199"));
+
+        let missing = network(None, true);
+        let results = evaluate(&template(), &nodes, &[(NetworkPurpose::Management, &missing)]);
+        assert!(!outcome(&results, "vlan:management").passed);
+    }
+
+    #[test]
+    fn test_validation_rules_are_executed_with_their_parameters() {
+        let mut template = template();
+        template.validation_rules = vec![
+            NetworkValidationRule {
+                rule_type: NetworkRuleType::VlanSeparation,
+                parameters: HashMap::new(),
+                error_message: "Storage must be isolated".to_string(),
+                severity: ValidationSeverity::Critical,
+            },
+            NetworkValidationRule {
+                rule_type: NetworkRuleType::TeamingConfig,
+                parameters: HashMap::from([("purposes".to_string(), serde_json::json!(["workload"]))]),
+                error_message: "Workload traffic must be teamed".to_string(),
+                severity: ValidationSeverity::Warning,
+            },
+        ];
+        let management = network(Some(150), false);
+        let workload = network(Some(150), true);
+        let networks = [(NetworkPurpose::Management, &management), (NetworkPurpose::Workload, &workload)];
+
+        let results = evaluate(&template, &[node("n1", 6, 25.0, true)], &networks);
+
+        let separation = outcome(&results, "rule:vlan_separation");
+        assert!(!separation.passed);
+        assert!(separation.message.starts_with("Storage must be isolated: "));
+        assert!(separation.message.contains("VLAN 150 (management, workload)"));
+        assert!(outcome(&results, "rule:teaming_config").passed);
+        assert_eq!(issues(&results).len(), 1);
+    }
+
+    #[test]
+    fn test_rules_with_unknown_or_invalid_parameters_are_rejected() {
+        let rule = |rule_type, parameters: serde_json::Value| NetworkValidationRule {
+            rule_type,
+            parameters: serde_json::from_value(parameters).unwrap(),
+            error_message: "failed".to_string(),
+            severity: ValidationSeverity::Error,
+        };
+        assert!(validate_rule(&rule(NetworkRuleType::MinBandwidth, serde_json::json!({"min_gbps": 25}))).is_ok());
+        assert!(validate_rule(&rule(NetworkRuleType::MinBandwidth, serde_json::json!({"min": 25}))).is_err());
+        assert!(validate_rule(&rule(NetworkRuleType::NicCount, serde_json::json!({"min": 0}))).is_err());
+        assert!(
+            validate_rule(&rule(NetworkRuleType::VlanSeparation, serde_json::json!({"purposes": ["dmz"]}))).is_err()
+        );
+    }
+
+    #[test]
+    fn test_template_thing_accepts_full_and_bare_ids() {
+        assert_eq!(template_thing("network_profile_template:abc"), template_thing("abc"));
+    }
+}
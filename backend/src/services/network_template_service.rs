@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::error::Error;
 use crate::models::project_models::NetworkTemplate;
 use crate::database::Database;
+use crate::database::TracedQuery;
 
 /// Network Template Service
 /// 
@@ -162,6 +163,7 @@ impl NetworkTemplateService {
             .db
             .query(&query)
             .bind(("user_id", user_id))
+            .traced("network_template_service.list_templates")
             .await?;
 
         let templates: Vec<NetworkTemplate> = result.take(0)?;
@@ -344,6 +346,7 @@ impl NetworkTemplateService {
             .query(query)
             .bind(("user_id", user_id))
             .bind(("network_query", network_query))
+            .traced("network_template_service.search_by_network")
             .await?;
 
         let templates: Vec<NetworkTemplate> = result.take(0)?;
@@ -354,7 +357,7 @@ impl NetworkTemplateService {
     pub async fn list_global_templates(&self) -> Result<Vec<NetworkTemplate>, Box<dyn Error>> {
         let query = "SELECT * FROM network_template WHERE is_global = true ORDER BY created_at DESC";
         
-        let mut result = self.db.query(query).await?;
+        let mut result = self.db.query(query).traced("network_template_service.list_global_templates").await?;
         let templates: Vec<NetworkTemplate> = result.take(0)?;
         
         Ok(templates)
@@ -371,6 +374,7 @@ impl NetworkTemplateService {
             .db
             .query(query)
             .bind(("user_id", user_id))
+            .traced("network_template_service.list_user_templates")
             .await?;
         
         let templates: Vec<NetworkTemplate> = result.take(0)?;
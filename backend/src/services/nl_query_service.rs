@@ -0,0 +1,823 @@
+//! Natural-Language Query Service
+//!
+//! Answers planner questions such as "which clusters exceed 80% memory?"
+//! against the parsed environment and the CMDB.
+//!
+//! Questions are read by a small rule-based grammar into a `StructuredQuery`
+//! over a fixed set of fields per dataset. Field names only ever come from
+//! that whitelist and every value is bound as a parameter, so nothing the
+//! user types reaches SurrealQL. The structured query and the generated
+//! SurrealQL are returned with the rows so users can check how the question
+//! was understood.
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::services::capacity_overview_service::{CapacityOverviewQuery, CapacityOverviewService};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 500;
+
+pub struct NlQueryService {
+    db: Database,
+}
+
+// =============================================================================
+// REQUEST/RESPONSE TYPES
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct NlQueryRequest {
+    pub question: String,
+    /// Restrict VM and cluster questions to one migration wizard project
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NlDataset {
+    Clusters,
+    Vms,
+    ConfigurationItems,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    Contains,
+}
+
+impl FilterOp {
+    fn symbol(&self) -> &'static str {
+        match self {
+            FilterOp::Gt => ">",
+            FilterOp::Gte => ">=",
+            FilterOp::Lt => "<",
+            FilterOp::Lte => "<=",
+            FilterOp::Eq => "=",
+            FilterOp::Contains => "CONTAINS",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueryFilter {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuerySort {
+    pub field: String,
+    pub descending: bool,
+}
+
+/// What the grammar understood from the question
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StructuredQuery {
+    pub dataset: NlDataset,
+    pub filters: Vec<QueryFilter>,
+    pub sort: Option<QuerySort>,
+    pub limit: usize,
+    /// Parts of the question that were recognised but couldn't be applied
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NlQueryResponse {
+    pub question: String,
+    pub interpretation: String,
+    pub query: StructuredQuery,
+    /// SurrealQL sent to the database, or a description of the in-memory
+    /// evaluation for computed datasets
+    pub generated_query: String,
+    pub parameters: Map<String, Value>,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub total: usize,
+    pub truncated: bool,
+}
+
+// =============================================================================
+// FIELD CATALOG
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Number,
+    Text,
+}
+
+struct FieldDef {
+    name: &'static str,
+    kind: FieldKind,
+    /// Stored column (database datasets only)
+    column: &'static str,
+    /// Value in the field's unit times `scale` gives the stored value
+    scale: f64,
+    /// Projection expression (database datasets only)
+    select: &'static str,
+}
+
+const fn number(name: &'static str, column: &'static str, scale: f64, select: &'static str) -> FieldDef {
+    FieldDef { name, kind: FieldKind::Number, column, scale, select }
+}
+
+const fn text(name: &'static str, column: &'static str) -> FieldDef {
+    FieldDef { name, kind: FieldKind::Text, column, scale: 1.0, select: column }
+}
+
+/// Clusters come from the capacity overview, so columns are row keys
+const CLUSTER_FIELDS: &[FieldDef] = &[
+    text("cluster", "cluster"),
+    text("project", "project"),
+    number("vm_count", "vm_count", 1.0, ""),
+    number("cpu_percent", "cpu_percent", 1.0, ""),
+    number("memory_percent", "memory_percent", 1.0, ""),
+    number("storage_percent", "storage_percent", 1.0, ""),
+    number("cpu_used_vcpus", "cpu_used_vcpus", 1.0, ""),
+    number("memory_used_gb", "memory_used_gb", 1.0, ""),
+    number("storage_used_gb", "storage_used_gb", 1.0, ""),
+];
+
+const VM_FIELDS: &[FieldDef] = &[
+    text("name", "name"),
+    text("cluster", "cluster"),
+    text("host", "host"),
+    text("datacenter", "datacenter"),
+    text("os", "os"),
+    text("powerstate", "powerstate"),
+    number("vcpus", "cpus", 1.0, "cpus AS vcpus"),
+    number("memory_gb", "memory_mb", 1024.0, "math::fixed(memory_mb / 1024, 1) AS memory_gb"),
+    number("storage_gb", "provisioned_mb", 1024.0, "math::fixed((provisioned_mb OR 0) / 1024, 1) AS storage_gb"),
+    number("disks", "num_disks", 1.0, "num_disks AS disks"),
+    number("nics", "num_nics", 1.0, "num_nics AS nics"),
+];
+
+const CI_FIELDS: &[FieldDef] = &[
+    text("ci_id", "ci_id"),
+    text("name", "name"),
+    text("ci_class", "ci_class"),
+    text("ci_type", "ci_type"),
+    text("status", "status"),
+    text("criticality", "criticality"),
+    text("environment", "environment"),
+    text("location", "location"),
+    text("support_group", "support_group"),
+    text("vendor", "vendor"),
+];
+
+impl NlDataset {
+    fn fields(&self) -> &'static [FieldDef] {
+        match self {
+            NlDataset::Clusters => CLUSTER_FIELDS,
+            NlDataset::Vms => VM_FIELDS,
+            NlDataset::ConfigurationItems => CI_FIELDS,
+        }
+    }
+
+    fn field(&self, name: &str) -> Option<&'static FieldDef> {
+        self.fields().iter().find(|f| f.name == name)
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            NlDataset::Clusters => "clusters",
+            NlDataset::Vms => "virtual machines",
+            NlDataset::ConfigurationItems => "configuration items",
+        }
+    }
+
+    /// Field a metric word refers to. A size unit (GB/MB/TB) selects the
+    /// absolute figure; otherwise cluster resources read as utilization.
+    fn metric_field(&self, keyword: &str, unit: Option<&str>) -> Option<&'static str> {
+        let sized = matches!(unit, Some("gb" | "mb" | "tb"));
+        match (self, keyword) {
+            (NlDataset::Clusters, "cpu" | "vcpu" | "vcpus" | "cores") => {
+                Some(if sized { "cpu_used_vcpus" } else { "cpu_percent" })
+            }
+            (NlDataset::Clusters, "memory" | "ram") => Some(if sized { "memory_used_gb" } else { "memory_percent" }),
+            (NlDataset::Clusters, "storage" | "disk" | "disks") => {
+                Some(if sized { "storage_used_gb" } else { "storage_percent" })
+            }
+            (NlDataset::Clusters, "vms" | "vm" | "virtual machines") => Some("vm_count"),
+            (NlDataset::Vms, "cpu" | "cpus" | "vcpu" | "vcpus" | "cores") => Some("vcpus"),
+            (NlDataset::Vms, "memory" | "ram") => Some("memory_gb"),
+            (NlDataset::Vms, "storage" | "provisioned") => Some("storage_gb"),
+            (NlDataset::Vms, "disk" | "disks") => Some(if sized { "storage_gb" } else { "disks" }),
+            (NlDataset::Vms, "nic" | "nics" | "network adapters") => Some("nics"),
+            _ => None,
+        }
+    }
+
+    /// Sort field when the question says "top 5" without naming a metric
+    fn default_sort(&self) -> Option<&'static str> {
+        match self {
+            NlDataset::Clusters => Some("memory_percent"),
+            NlDataset::Vms => Some("memory_gb"),
+            NlDataset::ConfigurationItems => None,
+        }
+    }
+}
+
+// =============================================================================
+// GRAMMAR
+// =============================================================================
+
+static COMPARISON: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?P<op>more than|greater than|higher than|bigger than|larger than|exceeds?|exceeding|above|over|at least|less than|lower than|smaller than|fewer than|below|under|at most|equal to|equals?|>=|<=|>|<|=)\s*(?P<num>\d+(?:\.\d+)?)\s*(?P<unit>%|percent\b|tb\b|gb\b|mb\b)?",
+    )
+    .unwrap()
+});
+
+static METRIC: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(virtual machines|network adapters|memory|ram|cpus?|vcpus?|cores|storage|provisioned|disks?|nics?|vms?)\b")
+        .unwrap()
+});
+
+static TOP_N: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(?P<dir>top|largest|biggest|highest|first|bottom|smallest|lowest)\s+(?P<n>\d{1,3})\b").unwrap());
+
+static SORT_BY: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?:sorted by|ordered by|order by|sort by|by)\s+(?:their\s+)?(?P<metric>\w+)").unwrap());
+
+static QUOTED: Lazy<Regex> = Lazy::new(|| Regex::new(r#""(?P<value>[^"]{1,100})""#).unwrap());
+
+/// `<phrase> <value>` patterns for text filters, per dataset
+static TEXT_PATTERNS: Lazy<Vec<(Vec<NlDataset>, &'static str, Regex)>> = Lazy::new(|| {
+    use NlDataset::*;
+    let value = r"(?P<value>[a-z0-9][a-z0-9._\-]*)";
+    let pattern = |prefix: &str| Regex::new(&format!(r"\b{}\s+{}", prefix, value)).unwrap();
+    vec![
+        (vec![Vms], "cluster", pattern(r"(?:in|on|from) cluster")),
+        (vec![Clusters], "cluster", pattern(r"(?:cluster|clusters) named")),
+        (vec![Vms], "host", pattern(r"(?:on|in) host")),
+        (vec![Vms], "datacenter", pattern(r"(?:in|at) (?:datacenter|data center|site)")),
+        (vec![Vms], "os", pattern(r"(?:running|with os|os is|on os)")),
+        (vec![Clusters], "project", pattern(r"(?:in|for|from) project")),
+        (vec![ConfigurationItems], "environment", pattern(r"(?:in environment|environment is|in env)")),
+        (vec![ConfigurationItems], "location", pattern(r"(?:at location|located in|location is)")),
+        (vec![ConfigurationItems], "support_group", pattern(r"(?:support group|supported by|owned by team)")),
+        (vec![ConfigurationItems], "ci_type", pattern(r"(?:of type|type is)")),
+        (vec![ConfigurationItems], "vendor", pattern(r"(?:from vendor|vendor is|made by)")),
+        (vec![Vms, ConfigurationItems, Clusters], "name", pattern(r"(?:named|called|name contains)")),
+    ]
+});
+
+fn parse_op(op: &str) -> FilterOp {
+    match op {
+        "at least" | ">=" => FilterOp::Gte,
+        "at most" | "<=" => FilterOp::Lte,
+        "less than" | "lower than" | "smaller than" | "fewer than" | "below" | "under" | "<" => FilterOp::Lt,
+        "equal to" | "equal" | "equals" | "=" => FilterOp::Eq,
+        _ => FilterOp::Gt,
+    }
+}
+
+fn detect_dataset(question: &str) -> Option<NlDataset> {
+    // CMDB wording wins: "CIs in cluster x" is about CIs
+    static CI_WORDS: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\b(cis?|configuration items?|cmdb|assets?)\b").unwrap());
+    static VM_WORDS: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\b(vms?|virtual machines?|guests?|workloads?)\b").unwrap());
+    static CLUSTER_WORDS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bclusters?\b").unwrap());
+
+    let positions = [
+        (CI_WORDS.find(question).map(|m| m.start()), NlDataset::ConfigurationItems),
+        (VM_WORDS.find(question).map(|m| m.start()), NlDataset::Vms),
+        (CLUSTER_WORDS.find(question).map(|m| m.start()), NlDataset::Clusters),
+    ];
+    if positions[0].0.is_some() {
+        return Some(NlDataset::ConfigurationItems);
+    }
+    // Otherwise the first entity named is the subject ("VMs in cluster x",
+    // "clusters with more than 50 VMs")
+    positions
+        .iter()
+        .filter_map(|(pos, dataset)| pos.map(|p| (p, *dataset)))
+        .min_by_key(|(pos, _)| *pos)
+        .map(|(_, dataset)| dataset)
+}
+
+/// Read a question into a structured query, or explain what's missing
+pub fn parse_question(question: &str) -> Result<StructuredQuery, String> {
+    let original = question.trim();
+    if original.is_empty() {
+        return Err("Question is empty".to_string());
+    }
+    if original.len() > 500 {
+        return Err("Question is too long (500 characters max)".to_string());
+    }
+    // ASCII lowercasing keeps byte offsets aligned with the original text
+    let text = original.to_ascii_lowercase();
+
+    let dataset = detect_dataset(&text).ok_or_else(|| {
+        "Couldn't tell what to search; mention clusters, VMs or configuration items (CIs)".to_string()
+    })?;
+    let mut query = StructuredQuery {
+        dataset,
+        filters: Vec::new(),
+        sort: None,
+        limit: DEFAULT_LIMIT,
+        warnings: Vec::new(),
+    };
+
+    let metrics: Vec<(usize, usize, &str)> = METRIC
+        .find_iter(&text)
+        .map(|m| (m.start(), m.end(), m.as_str()))
+        .collect();
+
+    // Numeric comparisons: the metric is the word right after the number
+    // ("exceed 80% memory") or the closest one before the comparator
+    // ("memory above 80%")
+    let mut numeric_fields = Vec::new();
+    for caps in COMPARISON.captures_iter(&text) {
+        let whole = caps.get(0).unwrap();
+        let op = parse_op(caps.name("op").unwrap().as_str());
+        let mut value: f64 = caps["num"].parse().unwrap_or(0.0);
+        let unit = caps.name("unit").map(|u| u.as_str().trim());
+
+        let keyword = metrics
+            .iter()
+            .find(|(start, _, _)| *start >= whole.end() && *start - whole.end() <= 12)
+            .or_else(|| metrics.iter().rev().find(|(_, end, _)| *end <= whole.start()))
+            .map(|(_, _, word)| *word);
+        let Some(keyword) = keyword else {
+            query.warnings.push(format!("Ignored \"{}\": no metric named", whole.as_str().trim()));
+            continue;
+        };
+        let Some(field) = dataset.metric_field(keyword, unit) else {
+            query
+                .warnings
+                .push(format!("Ignored \"{}\": {} can't be compared by {}", whole.as_str().trim(), dataset.label(), keyword));
+            continue;
+        };
+        match unit {
+            Some("tb") => value *= 1024.0,
+            Some("mb") => value /= 1024.0,
+            _ => {}
+        }
+        numeric_fields.push(field);
+        query.filters.push(QueryFilter { field: field.to_string(), op, value: json!(value) });
+    }
+
+    for (datasets, field, pattern) in TEXT_PATTERNS.iter() {
+        if !datasets.contains(&dataset) {
+            continue;
+        }
+        if let Some(caps) = pattern.captures(&text) {
+            let m = caps.name("value").unwrap();
+            let value = original[m.start()..m.end()].trim_end_matches(['.', ',', '?', '!']);
+            query.filters.push(QueryFilter {
+                field: field.to_string(),
+                op: FilterOp::Contains,
+                value: json!(value),
+            });
+        }
+    }
+
+    for caps in QUOTED.captures_iter(original) {
+        if !query.filters.iter().any(|f| f.field == "name") {
+            query.filters.push(QueryFilter {
+                field: "name".to_string(),
+                op: FilterOp::Contains,
+                value: json!(caps["value"].to_string()),
+            });
+        }
+    }
+
+    // Keyword filters that need no value
+    match dataset {
+        NlDataset::Vms => {
+            if text.contains("powered off") || text.contains("powered-off") {
+                query.filters.push(QueryFilter { field: "powerstate".to_string(), op: FilterOp::Eq, value: json!("poweredOff") });
+            } else if text.contains("powered on") || text.contains("running vms") {
+                query.filters.push(QueryFilter { field: "powerstate".to_string(), op: FilterOp::Eq, value: json!("poweredOn") });
+            }
+        }
+        NlDataset::ConfigurationItems => {
+            static CRITICALITY: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(critical|high|medium|low)[\s-]+critical(?:ity)?\b|\bbusiness[\s-]critical\b").unwrap());
+            static CLASS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(hardware|software|service|network|document)\b").unwrap());
+            static STATUS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(active|retired|maintenance|planned|deployed|in stock)\b").unwrap());
+            if let Some(caps) = CRITICALITY.captures(&text) {
+                let level = caps.get(1).map(|m| m.as_str()).unwrap_or("critical");
+                query.filters.push(QueryFilter { field: "criticality".to_string(), op: FilterOp::Eq, value: json!(level.to_uppercase()) });
+            }
+            if let Some(caps) = CLASS.captures(&text) {
+                query.filters.push(QueryFilter { field: "ci_class".to_string(), op: FilterOp::Eq, value: json!(caps[1].to_uppercase()) });
+            }
+            if let Some(caps) = STATUS.captures(&text) {
+                query.filters.push(QueryFilter {
+                    field: "status".to_string(),
+                    op: FilterOp::Eq,
+                    value: json!(caps[1].to_uppercase().replace(' ', "_")),
+                });
+            }
+            for (env, value) in [("production", "prod"), ("staging", "stag"), ("development", "dev"), ("test", "test")] {
+                if text.contains(env) && !query.filters.iter().any(|f| f.field == "environment") {
+                    query.filters.push(QueryFilter { field: "environment".to_string(), op: FilterOp::Contains, value: json!(value) });
+                }
+            }
+        }
+        NlDataset::Clusters => {}
+    }
+
+    // Ordering and limits
+    let mut descending = true;
+    let mut wants_sort = false;
+    if let Some(caps) = TOP_N.captures(&text) {
+        query.limit = caps["n"].parse::<usize>().unwrap_or(DEFAULT_LIMIT);
+        descending = !matches!(&caps["dir"], "bottom" | "smallest" | "lowest");
+        wants_sort = &caps["dir"] != "first";
+    } else if ["most", "largest", "biggest", "highest", "busiest"].iter().any(|w| contains_word(&text, w)) {
+        wants_sort = true;
+    } else if ["least", "smallest", "lowest"].iter().any(|w| contains_word(&text, w)) {
+        wants_sort = true;
+        descending = false;
+    }
+    let sort_field = SORT_BY
+        .captures(&text)
+        .and_then(|caps| dataset.metric_field(&caps["metric"], None).map(str::to_string).or_else(|| {
+            dataset.field(&caps["metric"]).map(|f| f.name.to_string())
+        }));
+    if let Some(field) = sort_field {
+        query.sort = Some(QuerySort { field, descending });
+    } else if wants_sort {
+        let field = numeric_fields
+            .first()
+            .copied()
+            .or_else(|| metrics.iter().find_map(|(_, _, word)| dataset.metric_field(word, None)))
+            .or_else(|| dataset.default_sort());
+        match field {
+            Some(field) => query.sort = Some(QuerySort { field: field.to_string(), descending }),
+            None => query.warnings.push(format!("{} have no numeric field to rank by", dataset.label())),
+        }
+    } else if let Some(field) = numeric_fields.first() {
+        // "exceed 80% memory" reads best worst-first
+        query.sort = Some(QuerySort { field: field.to_string(), descending: true });
+    }
+    query.limit = query.limit.clamp(1, MAX_LIMIT);
+
+    Ok(query)
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    text.split(|c: char| !c.is_ascii_alphanumeric()).any(|w| w == word)
+}
+
+/// Plain-English restatement of the structured query
+pub fn describe(query: &StructuredQuery) -> String {
+    let mut description = format!("List {}", query.dataset.label());
+    if !query.filters.is_empty() {
+        let conditions: Vec<String> = query
+            .filters
+            .iter()
+            .map(|f| match (&f.op, &f.value) {
+                (FilterOp::Contains, Value::String(v)) => format!("{} contains \"{}\"", f.field, v),
+                (op, Value::String(v)) => format!("{} {} \"{}\"", f.field, op.symbol(), v),
+                (op, v) => format!("{} {} {}", f.field, op.symbol(), v),
+            })
+            .collect();
+        description.push_str(" where ");
+        description.push_str(&conditions.join(" and "));
+    }
+    if let Some(sort) = &query.sort {
+        description.push_str(&format!(
+            ", {} by {}",
+            if sort.descending { "highest first" } else { "lowest first" },
+            sort.field
+        ));
+    }
+    description.push_str(&format!(", up to {} rows", query.limit));
+    description
+}
+
+// =============================================================================
+// SERVICE IMPLEMENTATION
+// =============================================================================
+
+impl NlQueryService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(
+        &self,
+        question: &str,
+        query: StructuredQuery,
+        tenant_id: Option<&str>,
+        project_id: Option<&str>,
+    ) -> Result<NlQueryResponse> {
+        let interpretation = describe(&query);
+        let (generated_query, parameters, columns, rows, total) = match query.dataset {
+            NlDataset::Clusters => self.query_clusters(&query, tenant_id, project_id).await?,
+            NlDataset::Vms | NlDataset::ConfigurationItems => self.query_table(&query, tenant_id, project_id).await?,
+        };
+
+        Ok(NlQueryResponse {
+            question: question.trim().to_string(),
+            interpretation,
+            truncated: total > rows.len(),
+            query,
+            generated_query,
+            parameters,
+            columns,
+            rows,
+            total,
+        })
+    }
+
+    /// Cluster utilization is computed, not stored, so filters run over the
+    /// capacity overview rows
+    async fn query_clusters(
+        &self,
+        query: &StructuredQuery,
+        tenant_id: Option<&str>,
+        project_id: Option<&str>,
+    ) -> Result<(String, Map<String, Value>, Vec<String>, Vec<Vec<Value>>, usize)> {
+        let overview = CapacityOverviewService::new(self.db.clone())
+            .overview(tenant_id, &CapacityOverviewQuery { threshold_percent: None, top: Some(0) })
+            .await?;
+
+        let mut rows: Vec<Map<String, Value>> = overview
+            .clusters
+            .iter()
+            .filter(|c| project_id.is_none_or(|p| c.project_id.ends_with(p)))
+            .map(|c| {
+                let row = json!({
+                    "cluster": c.cluster_name,
+                    "project": c.project_name,
+                    "vm_count": c.vm_count,
+                    "cpu_percent": round1(c.cpu.utilization_percent),
+                    "memory_percent": round1(c.memory.utilization_percent),
+                    "storage_percent": round1(c.storage.utilization_percent),
+                    "cpu_used_vcpus": round1(c.cpu.used),
+                    "memory_used_gb": round1(c.memory.used),
+                    "storage_used_gb": round1(c.storage.used),
+                });
+                match row {
+                    Value::Object(map) => map,
+                    _ => Map::new(),
+                }
+            })
+            .filter(|row| query.filters.iter().all(|f| matches_filter(row, f)))
+            .collect();
+
+        if let Some(sort) = &query.sort {
+            rows.sort_by(|a, b| {
+                let ordering = compare_values(a.get(&sort.field), b.get(&sort.field));
+                if sort.descending { ordering.reverse() } else { ordering }
+            });
+        }
+        let total = rows.len();
+        rows.truncate(query.limit);
+
+        let columns: Vec<String> = CLUSTER_FIELDS.iter().map(|f| f.name.to_string()).collect();
+        let table = rows
+            .iter()
+            .map(|row| columns.iter().map(|c| row.get(c).cloned().unwrap_or(Value::Null)).collect())
+            .collect();
+        let generated = format!("capacity overview (computed per cluster): {}", describe(query));
+        Ok((generated, Map::new(), columns, table, total))
+    }
+
+    async fn query_table(
+        &self,
+        query: &StructuredQuery,
+        tenant_id: Option<&str>,
+        project_id: Option<&str>,
+    ) -> Result<(String, Map<String, Value>, Vec<String>, Vec<Vec<Value>>, usize)> {
+        let compiled = compile(query, tenant_id, project_id)?;
+
+        let mut request = self.db.query(compiled.select.clone()).query(compiled.count.clone());
+        for (name, value) in &compiled.parameters {
+            request = request.bind((name.clone(), value.clone()));
+        }
+        if let Some(tenant) = &compiled.tenant {
+            request = request.bind(("tenant", tenant.clone()));
+        }
+        if let Some(project) = &compiled.project {
+            request = request.bind(("project", project.clone()));
+        }
+        let mut response = request.await.context("Query failed")?;
+        let rows: Vec<Map<String, Value>> = response.take(0).context("Failed to read rows")?;
+        let count: Option<Value> = response.take(1).context("Failed to read count")?;
+        let total = count
+            .and_then(|c| c.get("total").and_then(Value::as_u64))
+            .unwrap_or(rows.len() as u64) as usize;
+
+        let columns: Vec<String> = query.dataset.fields().iter().map(|f| f.name.to_string()).collect();
+        let table = rows
+            .iter()
+            .map(|row| columns.iter().map(|c| row.get(c).cloned().unwrap_or(Value::Null)).collect())
+            .collect();
+
+        let mut parameters = compiled.parameters;
+        if let Some(tenant) = compiled.tenant {
+            parameters.insert("tenant".to_string(), json!(tenant.to_string()));
+        }
+        if let Some(project) = compiled.project {
+            parameters.insert("project".to_string(), json!(project.to_string()));
+        }
+        Ok((compiled.select, parameters, columns, table, total))
+    }
+}
+
+/// SurrealQL for a database-backed query
+struct CompiledQuery {
+    select: String,
+    count: String,
+    parameters: Map<String, Value>,
+    tenant: Option<Thing>,
+    project: Option<Thing>,
+}
+
+fn compile(query: &StructuredQuery, tenant_id: Option<&str>, project_id: Option<&str>) -> Result<CompiledQuery> {
+    let dataset = query.dataset;
+    let (table, mut conditions, tenant, project) = match dataset {
+        NlDataset::Vms => (
+            "migration_wizard_vm",
+            vec!["template != true".to_string()],
+            None,
+            project_id.map(|p| Thing::from(("migration_wizard_project", p))),
+        ),
+        NlDataset::ConfigurationItems => (
+            "configuration_items",
+            vec!["status != 'DISPOSED'".to_string()],
+            tenant_id.map(|t| Thing::from(("tenants", t))),
+            None,
+        ),
+        NlDataset::Clusters => anyhow::bail!("Clusters are not a stored dataset"),
+    };
+
+    let mut parameters = Map::new();
+    if let (NlDataset::Vms, Some(tenant)) = (dataset, tenant_id) {
+        // VMs inherit their project's tenant; shared projects have none
+        conditions.push("(project_id.tenant_id = $tenant_name OR project_id.tenant_id IS NONE)".to_string());
+        parameters.insert("tenant_name".to_string(), json!(tenant));
+    }
+    if tenant.is_some() {
+        conditions.push("(tenant_id = $tenant OR tenant_id IS NONE)".to_string());
+    }
+    if project.is_some() {
+        conditions.push("project_id = $project".to_string());
+    }
+
+    for (i, filter) in query.filters.iter().enumerate() {
+        let field = dataset
+            .field(&filter.field)
+            .with_context(|| format!("Unknown field {}", filter.field))?;
+        let name = format!("p{}", i);
+        match (field.kind, filter.op) {
+            (FieldKind::Number, FilterOp::Contains) => anyhow::bail!("{} is numeric", field.name),
+            (FieldKind::Number, op) => {
+                let value = filter.value.as_f64().with_context(|| format!("{} needs a number", field.name))?;
+                conditions.push(format!("{} {} ${}", field.column, op.symbol(), name));
+                parameters.insert(name, json!(value * field.scale));
+            }
+            (FieldKind::Text, op) => {
+                let value = filter
+                    .value
+                    .as_str()
+                    .with_context(|| format!("{} needs text", field.name))?
+                    .to_lowercase();
+                let op = if op == FilterOp::Contains { "CONTAINS" } else { "=" };
+                conditions.push(format!("string::lowercase({} OR '') {} ${}", field.column, op, name));
+                parameters.insert(name, json!(value));
+            }
+        }
+    }
+
+    let projection: Vec<&str> = dataset.fields().iter().map(|f| f.select).collect();
+    let where_clause = conditions.join(" AND ");
+    let order = match &query.sort {
+        Some(sort) => {
+            let field = dataset
+                .field(&sort.field)
+                .with_context(|| format!("Unknown sort field {}", sort.field))?;
+            format!(" ORDER BY {} {}", field.column, if sort.descending { "DESC" } else { "ASC" })
+        }
+        None => String::new(),
+    };
+
+    // ORDER BY needs the raw column selected when the projection renames it
+    let sort_column = query
+        .sort
+        .as_ref()
+        .and_then(|s| dataset.field(&s.field))
+        .filter(|f| f.select != f.column)
+        .map(|f| format!(", {}", f.column))
+        .unwrap_or_default();
+
+    Ok(CompiledQuery {
+        select: format!(
+            "SELECT {}{} FROM {} WHERE {}{} LIMIT {}",
+            projection.join(", "),
+            sort_column,
+            table,
+            where_clause,
+            order,
+            query.limit
+        ),
+        count: format!("SELECT count() AS total FROM {} WHERE {} GROUP ALL", table, where_clause),
+        parameters,
+        tenant,
+        project,
+    })
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn compare_values(a: Option<&Value>, b: Option<&Value>) -> std::cmp::Ordering {
+    match (a.and_then(Value::as_f64), b.and_then(Value::as_f64)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+        _ => {
+            let a = a.and_then(Value::as_str).unwrap_or_default();
+            let b = b.and_then(Value::as_str).unwrap_or_default();
+            a.cmp(b)
+        }
+    }
+}
+
+fn matches_filter(row: &Map<String, Value>, filter: &QueryFilter) -> bool {
+    let Some(actual) = row.get(&filter.field) else {
+        return false;
+    };
+    match (actual.as_f64(), filter.value.as_f64()) {
+        (Some(actual), Some(expected)) => match filter.op {
+            FilterOp::Gt => actual > expected,
+            FilterOp::Gte => actual >= expected,
+            FilterOp::Lt => actual < expected,
+            FilterOp::Lte => actual <= expected,
+            FilterOp::Eq => (actual - expected).abs() < f64::EPSILON,
+            FilterOp::Contains => false,
+        },
+        _ => {
+            let actual = actual.as_str().unwrap_or_default().to_lowercase();
+            let expected = filter.value.as_str().unwrap_or_default().to_lowercase();
+            match filter.op {
+                FilterOp::Contains => actual.contains(&expected),
+                FilterOp::Eq => actual == expected,
+                _ => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(field: &str, op: FilterOp, value: Value) -> QueryFilter {
+        QueryFilter { field: field.to_string(), op, value }
+    }
+
+    #[test]
+    fn reads_cluster_utilization_questions() {
+        let query = parse_question("Which clusters exceed 80% memory?").unwrap();
+        assert_eq!(query.dataset, NlDataset::Clusters);
+        assert_eq!(query.filters, vec![filter("memory_percent", FilterOp::Gt, json!(80.0))]);
+        assert_eq!(query.sort, Some(QuerySort { field: "memory_percent".to_string(), descending: true }));
+
+        let query = parse_question("clusters where cpu is below 20 percent").unwrap();
+        assert_eq!(query.filters, vec![filter("cpu_percent", FilterOp::Lt, json!(20.0))]);
+    }
+
+    #[test]
+    fn reads_vm_questions_with_units_and_text() {
+        let query = parse_question("top 10 VMs in cluster Prod-01 with more than 2 TB storage").unwrap();
+        assert_eq!(query.dataset, NlDataset::Vms);
+        assert_eq!(query.limit, 10);
+        assert!(query.filters.contains(&filter("storage_gb", FilterOp::Gt, json!(2048.0))));
+        assert!(query.filters.contains(&filter("cluster", FilterOp::Contains, json!("Prod-01"))));
+
+        let compiled = compile(&query, Some("acme"), None).unwrap();
+        assert!(compiled.select.contains("provisioned_mb > $p0"));
+        assert_eq!(compiled.parameters["p0"], json!(2048.0 * 1024.0));
+        // User text is only ever bound, never spliced
+        assert!(!compiled.select.to_lowercase().contains("prod-01"));
+    }
+
+    #[test]
+    fn injection_attempts_stay_in_parameters() {
+        let query = parse_question(r#"VMs named "x'; DELETE FROM users""#).unwrap();
+        let compiled = compile(&query, None, None).unwrap();
+        assert!(!compiled.select.contains("DELETE"));
+        assert!(compiled.parameters.values().any(|v| v.as_str().unwrap_or_default().contains("delete")));
+
+        assert!(parse_question("how is the weather").is_err());
+    }
+}
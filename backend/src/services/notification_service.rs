@@ -0,0 +1,188 @@
+// Archer - Notification Service
+// Outbound notification channels: SMTP email (with attachments) and
+// Microsoft Teams incoming webhooks
+
+use lettre::message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::json;
+use tracing::{info, warn, Instrument};
+
+use crate::models::report_schedule::{DeliveryChannel, DeliveryResult, DeliveryStatus};
+use crate::utils::telemetry::TracedRequest;
+
+/// SMTP settings, read from `SMTP_HOST`, `SMTP_PORT`, `SMTP_USERNAME`,
+/// `SMTP_PASSWORD` and `SMTP_FROM`
+#[derive(Debug, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+}
+
+impl SmtpSettings {
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("SMTP_HOST").ok().filter(|h| !h.is_empty())?;
+        Some(Self {
+            host,
+            port: std::env::var("SMTP_PORT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(587),
+            username: std::env::var("SMTP_USERNAME").ok(),
+            password: std::env::var("SMTP_PASSWORD").ok(),
+            from: std::env::var("SMTP_FROM").unwrap_or_else(|_| "archer@localhost".to_string()),
+        })
+    }
+}
+
+/// File attached to an outgoing email
+pub struct EmailAttachment<'a> {
+    pub filename: &'a str,
+    pub content_type: &'a str,
+    pub bytes: Vec<u8>,
+}
+
+pub struct NotificationService {
+    smtp: Option<SmtpSettings>,
+    http: reqwest::Client,
+}
+
+impl NotificationService {
+    pub fn new(smtp: Option<SmtpSettings>) -> Self {
+        Self {
+            smtp,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(SmtpSettings::from_env())
+    }
+
+    /// Send one email per recipient so a bad address doesn't block the rest
+    pub async fn send_email(
+        &self,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+        attachment: Option<EmailAttachment<'_>>,
+    ) -> Vec<DeliveryResult> {
+        let Some(smtp) = &self.smtp else {
+            info!("SMTP not configured; skipping email to {} recipient(s)", recipients.len());
+            return recipients
+                .iter()
+                .map(|r| delivery(DeliveryChannel::Email, r, DeliveryStatus::Skipped, Some("SMTP not configured")))
+                .collect();
+        };
+
+        let mailer = match build_mailer(smtp) {
+            Ok(mailer) => mailer,
+            Err(e) => {
+                warn!("Failed to build SMTP transport: {}", e);
+                return recipients
+                    .iter()
+                    .map(|r| delivery(DeliveryChannel::Email, r, DeliveryStatus::Failed, Some(&e)))
+                    .collect();
+            }
+        };
+
+        let mut results = Vec::with_capacity(recipients.len());
+        for recipient in recipients {
+            let outcome = async {
+                let to: Mailbox = recipient.parse().map_err(|e| format!("invalid address: {}", e))?;
+                let from: Mailbox = smtp.from.parse().map_err(|e| format!("invalid SMTP_FROM: {}", e))?;
+                let builder = Message::builder().from(from).to(to).subject(subject);
+                let message = match &attachment {
+                    Some(file) => {
+                        let content_type =
+                            ContentType::parse(file.content_type).map_err(|e| e.to_string())?;
+                        builder.multipart(
+                            MultiPart::mixed()
+                                .singlepart(SinglePart::plain(body.to_string()))
+                                .singlepart(
+                                    Attachment::new(file.filename.to_string())
+                                        .body(file.bytes.clone(), content_type),
+                                ),
+                        )
+                    }
+                    None => builder.body(body.to_string()),
+                }
+                .map_err(|e| e.to_string())?;
+                mailer
+                    .send(message)
+                    .instrument(tracing::info_span!("smtp.send", otel.kind = "client", peer.service = "smtp"))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok::<(), String>(())
+            }
+            .await;
+
+            results.push(match outcome {
+                Ok(()) => delivery(DeliveryChannel::Email, recipient, DeliveryStatus::Sent, None),
+                Err(e) => {
+                    warn!("Email to {} failed: {}", recipient, e);
+                    delivery(DeliveryChannel::Email, recipient, DeliveryStatus::Failed, Some(&e))
+                }
+            });
+        }
+        results
+    }
+
+    /// Post a message card with a single link button to a Teams incoming webhook
+    pub async fn post_teams_link(&self, webhook_url: &str, title: &str, text: &str, link: &str) -> DeliveryResult {
+        let target = reqwest::Url::parse(webhook_url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| "invalid webhook".to_string());
+
+        let card = json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": title,
+            "title": title,
+            "text": text,
+            "potentialAction": [{
+                "@type": "OpenUri",
+                "name": "Open report",
+                "targets": [{ "os": "default", "uri": link }]
+            }]
+        });
+
+        match self.http.post(webhook_url).json(&card).send_traced("teams_webhook").await {
+            Ok(response) if response.status().is_success() => {
+                delivery(DeliveryChannel::Teams, &target, DeliveryStatus::Sent, None)
+            }
+            Ok(response) => {
+                let message = format!("webhook returned {}", response.status());
+                warn!("Teams notification failed: {}", message);
+                delivery(DeliveryChannel::Teams, &target, DeliveryStatus::Failed, Some(&message))
+            }
+            Err(e) => {
+                warn!("Teams notification failed: {}", e);
+                delivery(DeliveryChannel::Teams, &target, DeliveryStatus::Failed, Some(&e.to_string()))
+            }
+        }
+    }
+}
+
+fn build_mailer(smtp: &SmtpSettings) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host)
+        .map_err(|e| e.to_string())?
+        .port(smtp.port);
+    if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+    Ok(builder.build())
+}
+
+fn delivery(channel: DeliveryChannel, target: &str, status: DeliveryStatus, message: Option<&str>) -> DeliveryResult {
+    DeliveryResult {
+        channel,
+        target: target.to_string(),
+        status,
+        message: message.map(str::to_string),
+    }
+}
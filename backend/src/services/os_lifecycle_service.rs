@@ -0,0 +1,425 @@
+//! OS Lifecycle Service
+//!
+//! Maintains guest OS support dates and classifies VM operating systems:
+//! - Built-in dataset for Windows, RHEL, CentOS, Ubuntu, SLES and Debian
+//! - JSON imports that add or override entries (stored in `os_lifecycle_entry`)
+//! - Normalisation of VMware/RVTools guest OS strings
+//! - Per-project exposure reports by support status
+
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::database::Database;
+use crate::models::migration_wizard_models::MigrationWizardVM;
+use crate::models::os_lifecycle::*;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::database::TracedQuery;
+
+static WINDOWS_SERVER_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"windows\s+(?:server\s+)?(2000|2003|2008|2012|2016|2019|2022|2025)(?:\s+(r2))?").unwrap());
+static WINDOWS_CLIENT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"windows\s+(xp|vista|7|8\.1|8|10|11)\b").unwrap());
+static RHEL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:red\s*hat\s+enterprise\s+linux|rhel)(?:\s+server)?\s*(\d+)").unwrap());
+static CENTOS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"centos(?:\s+linux)?\s*(\d+)").unwrap());
+static UBUNTU_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"ubuntu(?:\s+linux)?\s*(\d{2}\.\d{2})").unwrap());
+static SLES_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:suse\s+linux\s+enterprise(?:\s+server)?|sles)\s*(\d+)").unwrap());
+static DEBIAN_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"debian(?:\s+gnu/linux)?\s*(\d+)").unwrap());
+
+/// Built-in lifecycle data: (family, version, mainstream end, extended end)
+const BUILTIN_LIFECYCLE: &[(OsFamily, &str, Option<&str>, Option<&str>)] = &[
+    (OsFamily::WindowsServer, "2000", Some("2005-06-30"), Some("2010-07-13")),
+    (OsFamily::WindowsServer, "2003", Some("2010-07-13"), Some("2015-07-14")),
+    (OsFamily::WindowsServer, "2003 R2", Some("2010-07-13"), Some("2015-07-14")),
+    (OsFamily::WindowsServer, "2008", Some("2015-01-13"), Some("2020-01-14")),
+    (OsFamily::WindowsServer, "2008 R2", Some("2015-01-13"), Some("2020-01-14")),
+    (OsFamily::WindowsServer, "2012", Some("2018-10-09"), Some("2023-10-10")),
+    (OsFamily::WindowsServer, "2012 R2", Some("2018-10-09"), Some("2023-10-10")),
+    (OsFamily::WindowsServer, "2016", Some("2022-01-11"), Some("2027-01-12")),
+    (OsFamily::WindowsServer, "2019", Some("2024-01-09"), Some("2029-01-09")),
+    (OsFamily::WindowsServer, "2022", Some("2026-10-13"), Some("2031-10-14")),
+    (OsFamily::WindowsServer, "2025", Some("2029-11-13"), Some("2034-11-14")),
+    (OsFamily::WindowsClient, "XP", Some("2009-04-14"), Some("2014-04-08")),
+    (OsFamily::WindowsClient, "Vista", Some("2012-04-10"), Some("2017-04-11")),
+    (OsFamily::WindowsClient, "7", Some("2015-01-13"), Some("2020-01-14")),
+    (OsFamily::WindowsClient, "8.1", Some("2018-01-09"), Some("2023-01-10")),
+    (OsFamily::WindowsClient, "10", Some("2025-10-14"), Some("2028-10-10")),
+    (OsFamily::WindowsClient, "11", None, None),
+    (OsFamily::Rhel, "5", Some("2017-03-31"), Some("2020-11-30")),
+    (OsFamily::Rhel, "6", Some("2020-11-30"), Some("2024-06-30")),
+    (OsFamily::Rhel, "7", Some("2024-06-30"), Some("2028-06-30")),
+    (OsFamily::Rhel, "8", Some("2029-05-31"), Some("2032-05-31")),
+    (OsFamily::Rhel, "9", Some("2032-05-31"), Some("2035-05-31")),
+    (OsFamily::Centos, "5", Some("2017-03-31"), None),
+    (OsFamily::Centos, "6", Some("2020-11-30"), None),
+    (OsFamily::Centos, "7", Some("2024-06-30"), None),
+    (OsFamily::Centos, "8", Some("2021-12-31"), None),
+    (OsFamily::Ubuntu, "14.04", Some("2019-04-25"), Some("2024-04-25")),
+    (OsFamily::Ubuntu, "16.04", Some("2021-04-30"), Some("2026-04-30")),
+    (OsFamily::Ubuntu, "18.04", Some("2023-05-31"), Some("2028-05-31")),
+    (OsFamily::Ubuntu, "20.04", Some("2025-05-29"), Some("2030-05-31")),
+    (OsFamily::Ubuntu, "22.04", Some("2027-06-01"), Some("2032-04-30")),
+    (OsFamily::Ubuntu, "24.04", Some("2029-05-31"), Some("2034-04-25")),
+    (OsFamily::Sles, "11", Some("2019-03-31"), Some("2022-03-31")),
+    (OsFamily::Sles, "12", Some("2024-10-31"), Some("2027-10-31")),
+    (OsFamily::Sles, "15", Some("2031-07-31"), Some("2034-07-31")),
+    (OsFamily::Debian, "9", Some("2020-07-06"), Some("2022-06-30")),
+    (OsFamily::Debian, "10", Some("2022-09-10"), Some("2024-06-30")),
+    (OsFamily::Debian, "11", Some("2024-08-14"), Some("2026-08-31")),
+    (OsFamily::Debian, "12", Some("2026-06-10"), Some("2028-06-30")),
+];
+
+/// In-memory lifecycle dataset used for classification
+#[derive(Debug, Clone)]
+pub struct OsLifecycleCatalog {
+    entries: Vec<OsLifecycleEntry>,
+}
+
+impl OsLifecycleCatalog {
+    pub fn builtin() -> Self {
+        let date = |s: Option<&str>| s.and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+        Self {
+            entries: BUILTIN_LIFECYCLE
+                .iter()
+                .map(|(family, version, mainstream, extended)| OsLifecycleEntry {
+                    id: None,
+                    family: *family,
+                    version: version.to_string(),
+                    mainstream_end: date(*mainstream),
+                    extended_end: date(*extended),
+                })
+                .collect(),
+        }
+    }
+
+    /// Add entries, replacing any existing entry for the same release
+    pub fn apply_overrides(&mut self, overrides: Vec<OsLifecycleEntry>) {
+        for entry in overrides {
+            match self
+                .entries
+                .iter_mut()
+                .find(|e| e.family == entry.family && e.version.eq_ignore_ascii_case(&entry.version))
+            {
+                Some(existing) => *existing = entry,
+                None => self.entries.push(entry),
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &[OsLifecycleEntry] {
+        &self.entries
+    }
+
+    pub fn lookup(&self, os: &NormalizedOs) -> Option<&OsLifecycleEntry> {
+        let version = os.version.as_deref()?;
+        self.entries
+            .iter()
+            .find(|e| e.family == os.family && e.version.eq_ignore_ascii_case(version))
+    }
+
+    /// Classify a raw guest OS string as of the given date
+    pub fn classify(&self, raw: Option<&str>, as_of: NaiveDate) -> OsClassification {
+        let normalized = raw.and_then(normalize_os);
+        let entry = normalized.as_ref().and_then(|os| self.lookup(os)).cloned();
+        let status = entry
+            .as_ref()
+            .map(|e| e.status_on(as_of))
+            .unwrap_or(SupportStatus::Unknown);
+
+        OsClassification {
+            raw: raw.map(|s| s.to_string()),
+            normalized,
+            entry,
+            status,
+        }
+    }
+}
+
+/// Normalise a VMware/RVTools guest OS string to family and release.
+///
+/// Handles the usual guest identifiers ("Microsoft Windows Server 2008 R2
+/// (64-bit)", "Red Hat Enterprise Linux 7 (64-bit)", "SUSE Linux Enterprise
+/// 12 (64-bit)") as well as strings reported by VMware Tools ("Ubuntu
+/// 20.04.6 LTS"). Ranges such as "Windows Server 2016 or later" resolve to
+/// the lowest release named.
+pub fn normalize_os(raw: &str) -> Option<NormalizedOs> {
+    let os = raw.to_lowercase().replace(['(', ')', ','], " ");
+    let os = os.split_whitespace().collect::<Vec<_>>().join(" ");
+    if os.is_empty() {
+        return None;
+    }
+
+    let found = |family, version: Option<String>| Some(NormalizedOs { family, version });
+
+    if let Some(caps) = WINDOWS_SERVER_RE.captures(&os) {
+        let year = caps[1].to_string();
+        let version = match caps.get(2) {
+            Some(_) => format!("{} R2", year),
+            None => year,
+        };
+        return found(OsFamily::WindowsServer, Some(version));
+    }
+    if let Some(caps) = WINDOWS_CLIENT_RE.captures(&os) {
+        let version = match &caps[1] {
+            "xp" => "XP".to_string(),
+            "vista" => "Vista".to_string(),
+            other => other.to_string(),
+        };
+        return found(OsFamily::WindowsClient, Some(version));
+    }
+    if os.contains("windows") && os.contains("server") {
+        return found(OsFamily::WindowsServer, None);
+    }
+    if let Some(caps) = RHEL_RE.captures(&os) {
+        return found(OsFamily::Rhel, Some(caps[1].to_string()));
+    }
+    if os.contains("red hat") || os.contains("rhel") {
+        return found(OsFamily::Rhel, None);
+    }
+    if let Some(caps) = CENTOS_RE.captures(&os) {
+        return found(OsFamily::Centos, Some(caps[1].to_string()));
+    }
+    if os.contains("centos") {
+        return found(OsFamily::Centos, None);
+    }
+    if let Some(caps) = UBUNTU_RE.captures(&os) {
+        return found(OsFamily::Ubuntu, Some(caps[1].to_string()));
+    }
+    if os.contains("ubuntu") {
+        return found(OsFamily::Ubuntu, None);
+    }
+    if let Some(caps) = SLES_RE.captures(&os) {
+        return found(OsFamily::Sles, Some(caps[1].to_string()));
+    }
+    if os.contains("suse") || os.contains("sles") {
+        return found(OsFamily::Sles, None);
+    }
+    if let Some(caps) = DEBIAN_RE.captures(&os) {
+        return found(OsFamily::Debian, Some(caps[1].to_string()));
+    }
+    if os.contains("debian") {
+        return found(OsFamily::Debian, None);
+    }
+
+    None
+}
+
+pub struct OsLifecycleService {
+    db: Database,
+}
+
+impl OsLifecycleService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Built-in dataset merged with imported entries
+    pub async fn catalog(&self) -> Result<OsLifecycleCatalog> {
+        let imported: Vec<OsLifecycleEntry> = self
+            .db
+            .select("os_lifecycle_entry")
+            .await
+            .context("Failed to load imported OS lifecycle entries")?;
+
+        let mut catalog = OsLifecycleCatalog::builtin();
+        catalog.apply_overrides(imported);
+        Ok(catalog)
+    }
+
+    /// Import lifecycle entries from JSON, overriding built-in dates where they overlap
+    pub async fn import_entries(&self, request: OsLifecycleImportRequest) -> Result<usize> {
+        for entry in &request.entries {
+            if entry.version.trim().is_empty() {
+                bail!("Lifecycle entry for {} is missing a version", entry.family.label());
+            }
+            if let (Some(mainstream), Some(extended)) = (entry.mainstream_end, entry.extended_end) {
+                if extended < mainstream {
+                    bail!("{}: extended support ends before mainstream support", entry.display_name());
+                }
+            }
+        }
+
+        if request.replace {
+            self.db
+                .query("DELETE os_lifecycle_entry")
+                .traced("os_lifecycle_service.import_entries")
+                .await
+                .context("Failed to clear imported OS lifecycle entries")?;
+        }
+
+        let count = request.entries.len();
+        for mut entry in request.entries {
+            self.db
+                .query("DELETE os_lifecycle_entry WHERE family = $family AND string::lowercase(version) = string::lowercase($version)")
+                .bind(("family", entry.family))
+                .bind(("version", entry.version.clone()))
+                .traced("os_lifecycle_service.import_entries")
+                .await
+                .context("Failed to replace OS lifecycle entry")?;
+
+            entry.id = None;
+            let _: Vec<OsLifecycleEntry> = self
+                .db
+                .create("os_lifecycle_entry")
+                .content(entry)
+                .await
+                .context("Failed to store OS lifecycle entry")?;
+        }
+
+        Ok(count)
+    }
+
+    /// Count a project's VMs by OS support status
+    pub async fn exposure_report(&self, project_id: &str) -> Result<OsExposureReport> {
+        let catalog = self.catalog().await?;
+        let vms = MigrationWizardService::new(self.db.clone())
+            .get_project_vms(project_id, None)
+            .await?;
+
+        Ok(Self::build_exposure_report(project_id, &vms, &catalog, Utc::now().date_naive()))
+    }
+
+    pub fn build_exposure_report(
+        project_id: &str,
+        vms: &[MigrationWizardVM],
+        catalog: &OsLifecycleCatalog,
+        as_of: NaiveDate,
+    ) -> OsExposureReport {
+        let mut rows: BTreeMap<(SupportStatus, String), OsExposureRow> = BTreeMap::new();
+        let mut unrecognized = BTreeSet::new();
+        let (mut supported, mut extended, mut eos, mut unknown) = (0, 0, 0, 0);
+
+        for vm in vms {
+            let classification = catalog.classify(vm.os.as_deref(), as_of);
+            match classification.status {
+                SupportStatus::Supported => supported += 1,
+                SupportStatus::ExtendedSupport => extended += 1,
+                SupportStatus::EndOfSupport => eos += 1,
+                SupportStatus::Unknown => unknown += 1,
+            }
+
+            let name = match (&classification.entry, &classification.normalized) {
+                (Some(entry), _) => entry.display_name(),
+                (None, Some(os)) => match &os.version {
+                    Some(version) => format!("{} {}", os.family.label(), version),
+                    None => format!("{} (release not stated)", os.family.label()),
+                },
+                (None, None) => {
+                    if let Some(raw) = &vm.os {
+                        unrecognized.insert(raw.clone());
+                    }
+                    vm.os.clone().unwrap_or_else(|| "Not reported".to_string())
+                }
+            };
+
+            rows.entry((classification.status, name.clone()))
+                .or_insert_with(|| OsExposureRow {
+                    os: name,
+                    status: classification.status,
+                    vm_count: 0,
+                    mainstream_end: classification.entry.as_ref().and_then(|e| e.mainstream_end),
+                    extended_end: classification.entry.as_ref().and_then(|e| e.extended_end),
+                })
+                .vm_count += 1;
+        }
+
+        // Worst exposure first, then largest groups
+        let mut by_os: Vec<OsExposureRow> = rows.into_values().collect();
+        by_os.sort_by(|a, b| {
+            exposure_rank(b.status)
+                .cmp(&exposure_rank(a.status))
+                .then_with(|| b.vm_count.cmp(&a.vm_count))
+                .then_with(|| a.os.cmp(&b.os))
+        });
+
+        OsExposureReport {
+            project_id: project_id.to_string(),
+            as_of,
+            total_vms: vms.len(),
+            supported,
+            extended_support: extended,
+            end_of_support: eos,
+            unknown,
+            by_os,
+            unrecognized: unrecognized.into_iter().collect(),
+            generated_at: Utc::now(),
+        }
+    }
+}
+
+fn exposure_rank(status: SupportStatus) -> u8 {
+    match status {
+        SupportStatus::EndOfSupport => 3,
+        SupportStatus::Unknown => 2,
+        SupportStatus::ExtendedSupport => 1,
+        SupportStatus::Supported => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn norm(family: OsFamily, version: &str) -> Option<NormalizedOs> {
+        Some(NormalizedOs {
+            family,
+            version: Some(version.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_normalize_vmware_guest_strings() {
+        assert_eq!(
+            normalize_os("Microsoft Windows Server 2008 R2 (64-bit)"),
+            norm(OsFamily::WindowsServer, "2008 R2")
+        );
+        assert_eq!(
+            normalize_os("Microsoft Windows Server 2016 or later (64-bit)"),
+            norm(OsFamily::WindowsServer, "2016")
+        );
+        assert_eq!(normalize_os("Microsoft Windows XP Professional (32-bit)"), norm(OsFamily::WindowsClient, "XP"));
+        assert_eq!(normalize_os("Red Hat Enterprise Linux 7 (64-bit)"), norm(OsFamily::Rhel, "7"));
+        assert_eq!(normalize_os("CentOS 7 (64-bit)"), norm(OsFamily::Centos, "7"));
+        assert_eq!(normalize_os("Ubuntu 20.04.6 LTS"), norm(OsFamily::Ubuntu, "20.04"));
+        assert_eq!(normalize_os("SUSE Linux Enterprise 12 (64-bit)"), norm(OsFamily::Sles, "12"));
+        assert_eq!(normalize_os("Debian GNU/Linux 10 (64-bit)"), norm(OsFamily::Debian, "10"));
+        assert_eq!(
+            normalize_os("Ubuntu Linux (64-bit)"),
+            Some(NormalizedOs { family: OsFamily::Ubuntu, version: None })
+        );
+        assert_eq!(normalize_os("Other 3.x Linux (64-bit)"), None);
+    }
+
+    #[test]
+    fn test_support_status_by_date() {
+        let catalog = OsLifecycleCatalog::builtin();
+        let as_of = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        let status = |os: &str| catalog.classify(Some(os), as_of).status;
+        assert_eq!(status("Microsoft Windows Server 2003 Standard (32-bit)"), SupportStatus::EndOfSupport);
+        assert_eq!(status("Microsoft Windows Server 2016 (64-bit)"), SupportStatus::ExtendedSupport);
+        assert_eq!(status("Microsoft Windows Server 2022 (64-bit)"), SupportStatus::Supported);
+        assert_eq!(status("CentOS 7 (64-bit)"), SupportStatus::EndOfSupport);
+        assert_eq!(status("Ubuntu Linux (64-bit)"), SupportStatus::Unknown);
+        assert_eq!(catalog.classify(None, as_of).status, SupportStatus::Unknown);
+    }
+
+    #[test]
+    fn test_overrides_replace_builtin_dates() {
+        let mut catalog = OsLifecycleCatalog::builtin();
+        let as_of = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        catalog.apply_overrides(vec![OsLifecycleEntry {
+            id: None,
+            family: OsFamily::Centos,
+            version: "7".to_string(),
+            mainstream_end: NaiveDate::from_ymd_opt(2024, 6, 30),
+            extended_end: NaiveDate::from_ymd_opt(2028, 6, 30),
+        }]);
+
+        assert_eq!(catalog.classify(Some("CentOS 7 (64-bit)"), as_of).status, SupportStatus::ExtendedSupport);
+    }
+}
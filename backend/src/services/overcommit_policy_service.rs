@@ -0,0 +1,287 @@
+//! Overcommit Policy Service
+//!
+//! Library of named overcommit ratios that destination clusters reference.
+//! Clusters keep a copy of their policy's ratios in `overcommit_ratios` so
+//! capacity math reads them as before; changing a policy rewrites that copy
+//! on every cluster that references it.
+
+use chrono::Utc;
+use serde_json::Value;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::models::project_models::{DestinationCluster, OvercommitRatios};
+use crate::models::settings::*;
+use crate::database::TracedQuery;
+
+const TABLE: &str = "overcommit_policy";
+
+/// Highest ratio accepted for either resource; anything above is a typo
+const MAX_RATIO: f32 = 32.0;
+
+#[derive(Debug, Error)]
+pub enum OvercommitPolicyError {
+    #[error("Overcommit policy not found")]
+    NotFound,
+
+    #[error("Destination cluster not found")]
+    ClusterNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Policy is applied to {0} cluster(s); move them to another policy first")]
+    InUse(usize),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for OvercommitPolicyError {
+    fn from(e: surrealdb::Error) -> Self {
+        OvercommitPolicyError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct OvercommitPolicyService {
+    db: Database,
+}
+
+impl OvercommitPolicyService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<OvercommitPolicy>, OvercommitPolicyError> {
+        Ok(self
+            .db
+            .query("SELECT * FROM overcommit_policy ORDER BY name ASC")
+            .traced("overcommit_policy_service.list_policies")
+            .await?
+            .take(0)?)
+    }
+
+    pub async fn get_policy(&self, id: &str) -> Result<OvercommitPolicy, OvercommitPolicyError> {
+        let policy: Option<OvercommitPolicy> = self.db.select(policy_thing(id)).await?;
+        policy.ok_or(OvercommitPolicyError::NotFound)
+    }
+
+    pub async fn create_policy(
+        &self,
+        request: CreateOvercommitPolicyRequest,
+    ) -> Result<OvercommitPolicy, OvercommitPolicyError> {
+        validate_name(&request.name)?;
+        validate_ratios(request.cpu_ratio, request.memory_ratio)?;
+
+        let now = Utc::now();
+        let policy = OvercommitPolicy {
+            id: None,
+            name: request.name.trim().to_string(),
+            description: request.description,
+            cpu_ratio: request.cpu_ratio,
+            memory_ratio: request.memory_ratio,
+            updated_by: request.updated_by,
+            created_at: now,
+            updated_at: now,
+        };
+        let created: Vec<OvercommitPolicy> = self.db.create(TABLE).content(policy).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| OvercommitPolicyError::DatabaseError("Failed to create policy".to_string()))
+    }
+
+    /// Update a policy and re-rate every cluster that references it
+    pub async fn update_policy(
+        &self,
+        id: &str,
+        request: UpdateOvercommitPolicyRequest,
+    ) -> Result<OvercommitPolicyUpdate, OvercommitPolicyError> {
+        let mut policy = self.get_policy(id).await?;
+        if let Some(name) = request.name {
+            validate_name(&name)?;
+            policy.name = name.trim().to_string();
+        }
+        if let Some(description) = request.description {
+            policy.description = Some(description);
+        }
+        policy.cpu_ratio = request.cpu_ratio.unwrap_or(policy.cpu_ratio);
+        policy.memory_ratio = request.memory_ratio.unwrap_or(policy.memory_ratio);
+        validate_ratios(policy.cpu_ratio, policy.memory_ratio)?;
+        policy.updated_by = request.updated_by;
+        policy.updated_at = Utc::now();
+
+        let thing = policy_thing(id);
+        let updated: Option<OvercommitPolicy> = self.db.update(thing.clone()).content(policy).await?;
+        let policy = updated.ok_or(OvercommitPolicyError::NotFound)?;
+
+        let rerated: Vec<Value> = self
+            .db
+            .query(
+                "UPDATE destination_cluster SET overcommit_ratios = $ratios, updated_at = $now \
+                 WHERE overcommit_policy_id = $policy RETURN id",
+            )
+            .bind(("ratios", policy.ratios()))
+            .bind(("now", Utc::now()))
+            .bind(("policy", thing))
+            .traced("overcommit_policy_service.update_policy")
+            .await?
+            .take(0)?;
+
+        Ok(OvercommitPolicyUpdate {
+            policy,
+            clusters_updated: rerated.len(),
+        })
+    }
+
+    /// Delete a policy no cluster references
+    pub async fn delete_policy(&self, id: &str) -> Result<(), OvercommitPolicyError> {
+        self.get_policy(id).await?;
+        let clusters = self.clusters_using(&policy_thing(id)).await?;
+        if !clusters.is_empty() {
+            return Err(OvercommitPolicyError::InUse(clusters.len()));
+        }
+        let _: Option<OvercommitPolicy> = self.db.delete(policy_thing(id)).await?;
+        Ok(())
+    }
+
+    /// Capacity of the clusters using a policy if the request were applied
+    pub async fn preview_update(
+        &self,
+        id: &str,
+        request: &UpdateOvercommitPolicyRequest,
+    ) -> Result<OvercommitImpactPreview, OvercommitPolicyError> {
+        let policy = self.get_policy(id).await?;
+        let proposed = OvercommitRatios {
+            cpu_ratio: request.cpu_ratio.unwrap_or(policy.cpu_ratio),
+            memory_ratio: request.memory_ratio.unwrap_or(policy.memory_ratio),
+        };
+        validate_ratios(proposed.cpu_ratio, proposed.memory_ratio)?;
+        let clusters = self.clusters_using(&policy_thing(id)).await?;
+        Ok(preview(&clusters, proposed))
+    }
+
+    /// Capacity of a cluster if it switched to the given policy
+    pub async fn preview_apply(
+        &self,
+        cluster_id: &str,
+        policy_id: &str,
+    ) -> Result<OvercommitImpactPreview, OvercommitPolicyError> {
+        let policy = self.get_policy(policy_id).await?;
+        let cluster = self.get_cluster(cluster_id).await?;
+        Ok(preview(&[cluster], policy.ratios()))
+    }
+
+    /// Point a cluster at a policy and take over its ratios
+    pub async fn apply_to_cluster(
+        &self,
+        cluster_id: &str,
+        policy_id: &str,
+    ) -> Result<DestinationCluster, OvercommitPolicyError> {
+        let policy = self.get_policy(policy_id).await?;
+        let mut cluster = self.get_cluster(cluster_id).await?;
+        cluster.overcommit_ratios = policy.ratios();
+        cluster.overcommit_policy_id = policy.id;
+        cluster.updated_at = Utc::now();
+
+        let updated: Option<DestinationCluster> = self
+            .db
+            .update(("destination_cluster", cluster_id))
+            .content(cluster)
+            .await?;
+        updated.ok_or(OvercommitPolicyError::ClusterNotFound)
+    }
+
+    async fn get_cluster(&self, cluster_id: &str) -> Result<DestinationCluster, OvercommitPolicyError> {
+        let cluster: Option<DestinationCluster> = self.db.select(("destination_cluster", cluster_id)).await?;
+        cluster.ok_or(OvercommitPolicyError::ClusterNotFound)
+    }
+
+    async fn clusters_using(&self, policy: &Thing) -> Result<Vec<DestinationCluster>, OvercommitPolicyError> {
+        Ok(self
+            .db
+            .query("SELECT * FROM destination_cluster WHERE overcommit_policy_id = $policy ORDER BY name ASC")
+            .bind(("policy", policy.clone()))
+            .traced("overcommit_policy_service.clusters_using")
+            .await?
+            .take(0)?)
+    }
+}
+
+/// Accepts "overcommit_policy:prod" as well as the bare key
+fn policy_thing(id: &str) -> Thing {
+    Thing::from((TABLE, id.strip_prefix("overcommit_policy:").unwrap_or(id)))
+}
+
+fn validate_name(name: &str) -> Result<(), OvercommitPolicyError> {
+    if name.trim().is_empty() {
+        return Err(OvercommitPolicyError::Validation("Policy name is required".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_ratios(cpu_ratio: f32, memory_ratio: f32) -> Result<(), OvercommitPolicyError> {
+    for (resource, ratio) in [("CPU", cpu_ratio), ("memory", memory_ratio)] {
+        if !(ratio > 0.0 && ratio <= MAX_RATIO) {
+            return Err(OvercommitPolicyError::Validation(format!(
+                "{} ratio must be greater than 0 and at most {}:1",
+                resource, MAX_RATIO
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn preview(clusters: &[DestinationCluster], proposed: OvercommitRatios) -> OvercommitImpactPreview {
+    let clusters: Vec<ClusterOvercommitImpact> = clusters.iter().map(|c| cluster_impact(c, &proposed)).collect();
+    OvercommitImpactPreview {
+        clusters_over_capacity: clusters.iter().filter(|c| c.over_capacity).count(),
+        proposed_ratios: proposed,
+        clusters,
+    }
+}
+
+/// Effective capacity of a cluster under its current and proposed ratios,
+/// against what is already reserved on it
+pub fn cluster_impact(cluster: &DestinationCluster, proposed: &OvercommitRatios) -> ClusterOvercommitImpact {
+    let scaled = |amount: i32, ratio: f32| (amount as f64 * ratio as f64).floor() as i64;
+    let current = &cluster.overcommit_ratios;
+    let totals = &cluster.capacity_totals;
+    let reserved = &cluster.capacity_reserved;
+
+    let proposed_vcpu_capacity = scaled(totals.cpu_cores, proposed.cpu_ratio);
+    let proposed_memory_gb_capacity = scaled(totals.memory_gb, proposed.memory_ratio);
+    ClusterOvercommitImpact {
+        cluster_id: cluster.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+        cluster_name: cluster.name.clone(),
+        current_ratios: current.clone(),
+        current_vcpu_capacity: scaled(totals.cpu_cores, current.cpu_ratio),
+        proposed_vcpu_capacity,
+        current_memory_gb_capacity: scaled(totals.memory_gb, current.memory_ratio),
+        proposed_memory_gb_capacity,
+        reserved_vcpus: reserved.cpu_cores as i64,
+        reserved_memory_gb: reserved.memory_gb as i64,
+        over_capacity: reserved.cpu_cores as i64 > proposed_vcpu_capacity
+            || reserved.memory_gb as i64 > proposed_memory_gb_capacity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ratios_rejects_zero_and_absurd_values() {
+        assert!(validate_ratios(4.0, 1.0).is_ok());
+        assert!(validate_ratios(0.0, 1.0).is_err());
+        assert!(validate_ratios(4.0, 64.0).is_err());
+        assert!(validate_ratios(f32::NAN, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_policy_thing_accepts_full_and_bare_ids() {
+        assert_eq!(policy_thing("overcommit_policy:vdi"), policy_thing("vdi"));
+        assert_eq!(policy_thing("vdi").tb, "overcommit_policy");
+    }
+}
@@ -0,0 +1,480 @@
+//! Personal Data Service
+//!
+//! Subject access and erasure for user accounts:
+//! - An export gathers the account, the tickets a user raised or is assigned,
+//!   their comments and their audit trail as JSON
+//! - An erasure replaces a departed user's name, email and network details
+//!   with a pseudonym wherever they were copied. The account record and every
+//!   link to it stay in place, so tickets, comments and audit entries keep
+//!   pointing at the (now anonymous) account.
+//!
+//! Erasure is irreversible, so it only runs once an approval workflow
+//! (`USER_ERASURE_WORKFLOW`) has completed. Both exports and erasures are
+//! written to the audit log.
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::auth::{AuditEventType, AuditLog};
+use crate::models::privacy::*;
+use crate::models::workflow_engine::WorkflowInstanceStatus;
+use crate::services::workflow_engine_service::WorkflowEngineService;
+
+const TABLE: &str = "user_erasure_request";
+const USER_TABLE: &str = "users";
+/// Display name erased accounts are given
+const ERASED_DISPLAY_NAME: &str = "Erased user";
+
+#[derive(Debug, Error)]
+pub enum PrivacyError {
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("Erasure request not found")]
+    NotFound,
+
+    #[error("Invalid state: {0}")]
+    InvalidState(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Workflow error: {0}")]
+    Workflow(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for PrivacyError {
+    fn from(e: surrealdb::Error) -> Self {
+        PrivacyError::DatabaseError(e.to_string())
+    }
+}
+
+/// The parts of an account the export and erasure need
+#[derive(Debug, Deserialize)]
+struct Account {
+    id: Thing,
+    username: String,
+    #[serde(default)]
+    tenant_id: Option<Thing>,
+}
+
+pub struct PrivacyService {
+    db: Arc<Database>,
+}
+
+impl PrivacyService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ========================================================================
+    // EXPORT
+    // ========================================================================
+
+    /// Everything tied to a user, in machine-readable form
+    pub async fn export_user(&self, user_id: &str, actor: &AuthenticatedUser) -> Result<UserDataExport, PrivacyError> {
+        let account = self.account(user_id).await?;
+        let user_key = account.id.to_string();
+
+        let mut response = self
+            .db
+            .query("SELECT * FROM $user")
+            .query("SELECT * FROM ticket WHERE created_by = $user_key ORDER BY created_at")
+            .query("SELECT * FROM ticket_archive WHERE created_by = $user_key ORDER BY created_at")
+            .query("SELECT * FROM ticket WHERE assignee INSIDE $assignee ORDER BY created_at")
+            .query("SELECT * FROM ticket_comments WHERE author_id = $user_key ORDER BY created_at")
+            .query("SELECT * FROM ticket_comments_archive WHERE author_id = $user_key ORDER BY created_at")
+            .query("SELECT * FROM audit_logs WHERE user_id = $user ORDER BY created_at")
+            .bind(("user", account.id.clone()))
+            .bind(("user_key", user_key.clone()))
+            .bind(("assignee", vec![user_key.clone(), account.username.clone()]))
+            .traced("privacy_service.export_user")
+            .await?;
+
+        let accounts: Vec<serde_json::Value> = response.take(0)?;
+        let mut tickets_authored: Vec<serde_json::Value> = response.take(1)?;
+        let archived_tickets: Vec<serde_json::Value> = response.take(2)?;
+        let tickets_assigned: Vec<serde_json::Value> = response.take(3)?;
+        let mut comments: Vec<serde_json::Value> = response.take(4)?;
+        let archived_comments: Vec<serde_json::Value> = response.take(5)?;
+        let audit_entries: Vec<serde_json::Value> = response.take(6)?;
+        tickets_authored.extend(archived_tickets);
+        comments.extend(archived_comments);
+
+        let mut account_record = accounts.into_iter().next().ok_or(PrivacyError::UserNotFound)?;
+        if let Some(fields) = account_record.as_object_mut() {
+            fields.remove("password_hash");
+        }
+
+        self.audit(
+            AuditEventType::DataExport,
+            "export_user_data",
+            &account,
+            actor,
+            json!({
+                "tickets_authored": tickets_authored.len(),
+                "tickets_assigned": tickets_assigned.len(),
+                "comments": comments.len(),
+                "audit_entries": audit_entries.len(),
+            }),
+        )
+        .await;
+
+        Ok(UserDataExport {
+            exported_at: Utc::now(),
+            account: account_record,
+            tickets_authored,
+            tickets_assigned,
+            comments,
+            audit_entries,
+        })
+    }
+
+    // ========================================================================
+    // ERASURE REQUESTS
+    // ========================================================================
+
+    pub async fn list_requests(&self) -> Result<Vec<ErasureRequest>, PrivacyError> {
+        let requests: Vec<ErasureRequest> = self
+            .db
+            .query(format!("SELECT * FROM {TABLE} ORDER BY created_at DESC"))
+            .traced("privacy_service.list_requests")
+            .await?
+            .take(0)?;
+
+        let mut synced = Vec::with_capacity(requests.len());
+        for request in requests {
+            synced.push(self.sync_approval(request).await?);
+        }
+        Ok(synced)
+    }
+
+    pub async fn get_request(&self, id: &str) -> Result<ErasureRequest, PrivacyError> {
+        let request = self.load(id).await?;
+        self.sync_approval(request).await
+    }
+
+    /// Record an erasure request and start its approval workflow
+    pub async fn create_request(
+        &self,
+        request: CreateErasureRequest,
+        actor: &AuthenticatedUser,
+    ) -> Result<ErasureRequest, PrivacyError> {
+        let account = self.account(&request.user_id).await?;
+        if account.id.to_string() == actor.user_id {
+            return Err(PrivacyError::Validation("You cannot request erasure of your own account".to_string()));
+        }
+
+        let open: Vec<ErasureRequest> = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE} WHERE user_id = $user AND status INSIDE ['PENDING_APPROVAL', 'APPROVED', 'COMPLETED']"
+            ))
+            .bind(("user", account.id.clone()))
+            .traced("privacy_service.open_requests")
+            .await?
+            .take(0)?;
+        let mut open_statuses = Vec::new();
+        for existing in open {
+            open_statuses.push(self.sync_approval(existing).await?.status);
+        }
+        if let Some(status) = open_statuses.iter().find(|s| **s != ErasureStatus::Rejected) {
+            return Err(PrivacyError::InvalidState(match status {
+                ErasureStatus::Completed => "This user has already been erased".to_string(),
+                _ => "An erasure request for this user is already open".to_string(),
+            }));
+        }
+
+        let workflow_id = request
+            .workflow_id
+            .or_else(|| std::env::var("USER_ERASURE_WORKFLOW").ok())
+            .filter(|w| !w.trim().is_empty())
+            .ok_or_else(|| {
+                PrivacyError::Validation("No approval workflow given and USER_ERASURE_WORKFLOW is not set".to_string())
+            })?;
+
+        let now = Utc::now();
+        let erasure = ErasureRequest {
+            id: None,
+            user_id: account.id.clone(),
+            username: account.username.clone(),
+            reason: request.reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+            status: ErasureStatus::PendingApproval,
+            workflow_instance_id: None,
+            requested_by: actor.user_id.clone(),
+            executed_by: None,
+            summary: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        };
+        let created: Vec<ErasureRequest> = self.db.create(TABLE).content(erasure).await?;
+        let mut created = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| PrivacyError::DatabaseError("Failed to create erasure request".to_string()))?;
+        let record = created.id.clone().ok_or(PrivacyError::NotFound)?;
+
+        let context = json!({
+            "user_erasure": {
+                "username": created.username,
+                "reason": created.reason,
+                "requested_by": actor.username,
+            }
+        });
+        let instance = WorkflowEngineService::new(Arc::clone(&self.db))
+            .trigger_workflow(parse_thing("workflow_definition", &workflow_id), TABLE.to_string(), record.clone(), Some(context))
+            .await;
+        let instance = match instance {
+            Ok(instance) => instance,
+            Err(e) => {
+                // Nothing to approve; don't leave a request that can never move
+                let _: Option<ErasureRequest> = self.db.delete(record).await?;
+                return Err(PrivacyError::Workflow(e));
+            }
+        };
+
+        created.workflow_instance_id = instance.id;
+        self.save(created).await
+    }
+
+    /// Withdraw a request that hasn't run yet
+    pub async fn cancel_request(&self, id: &str) -> Result<ErasureRequest, PrivacyError> {
+        let mut request = self.get_request(id).await?;
+        if !matches!(request.status, ErasureStatus::PendingApproval | ErasureStatus::Approved) {
+            return Err(PrivacyError::InvalidState(format!(
+                "A {:?} erasure request cannot be cancelled",
+                request.status
+            )));
+        }
+        if request.status == ErasureStatus::PendingApproval {
+            if let Some(instance) = &request.workflow_instance_id {
+                // The approval may have finished in the meantime; that's fine
+                let _ = WorkflowEngineService::new(Arc::clone(&self.db))
+                    .cancel_workflow_instance(instance.clone())
+                    .await;
+            }
+        }
+        request.status = ErasureStatus::Cancelled;
+        self.save(request).await
+    }
+
+    /// Anonymize the user of an approved request
+    pub async fn execute_request(&self, id: &str, actor: &AuthenticatedUser) -> Result<ErasureRequest, PrivacyError> {
+        let mut request = self.get_request(id).await?;
+        if request.status != ErasureStatus::Approved {
+            return Err(PrivacyError::InvalidState(
+                "Only approved erasure requests can be executed".to_string(),
+            ));
+        }
+        let account = self.account(&request.user_id.to_string()).await?;
+        let summary = self.erase(&account).await?;
+
+        self.audit(
+            AuditEventType::DataErasure,
+            "erase_user",
+            &account,
+            actor,
+            json!({
+                "erasure_request": request.id.as_ref().map(|id| id.to_string()),
+                "summary": summary,
+            }),
+        )
+        .await;
+        info!(
+            "🧹 Erased personal data of {} ({} comments, {} audit entries rewritten)",
+            account.id, summary.comments, summary.audit_entries
+        );
+
+        request.username = summary.pseudonym.clone();
+        request.status = ErasureStatus::Completed;
+        request.executed_by = Some(actor.user_id.clone());
+        request.completed_at = Some(Utc::now());
+        request.summary = Some(summary);
+        self.save(request).await
+    }
+
+    /// Replace the user's identifiers everywhere they were copied. Links to
+    /// the account (`user_id`, `author_id`, `created_by`, ...) are kept.
+    async fn erase(&self, account: &Account) -> Result<ErasureSummary, PrivacyError> {
+        let pseudonym = pseudonym();
+        let user_key = account.id.to_string();
+
+        let mut response = self
+            .db
+            .query(
+                "BEGIN TRANSACTION; \
+                 UPDATE $user SET username = $pseudonym, email = $email, display_name = $display_name, \
+                     password_hash = '', status = 'INACTIVE', updated_at = time::now() RETURN id; \
+                 UPDATE ticket_comments SET author_name = $pseudonym WHERE author_id = $user_key RETURN id; \
+                 UPDATE ticket_comments_archive SET author_name = $pseudonym WHERE author_id = $user_key RETURN id; \
+                 UPDATE ticket_history SET changed_by_name = $pseudonym WHERE changed_by = $user_key RETURN id; \
+                 UPDATE ticket SET assignee = $user_key WHERE assignee = $username RETURN id; \
+                 UPDATE ticket_archive SET assignee = $user_key WHERE assignee = $username RETURN id; \
+                 UPDATE audit_logs SET username = $pseudonym, ip_address = NONE, user_agent = NONE \
+                     WHERE user_id = $user OR username = $username RETURN id; \
+                 UPDATE kb_articles SET author_name = $pseudonym WHERE author_id = $user_key RETURN id; \
+                 DELETE refresh_tokens WHERE user_id = $user RETURN BEFORE; \
+                 UPDATE user_erasure_request SET username = $pseudonym WHERE user_id = $user RETURN id; \
+                 COMMIT TRANSACTION;",
+            )
+            .bind(("user", account.id.clone()))
+            .bind(("user_key", user_key))
+            .bind(("username", account.username.clone()))
+            .bind(("pseudonym", pseudonym.clone()))
+            .bind(("email", format!("{}@erased.invalid", pseudonym)))
+            .bind(("display_name", ERASED_DISPLAY_NAME))
+            .traced("privacy_service.erase")
+            .await?;
+
+        let mut counts = [0u64; 10];
+        for (index, count) in counts.iter_mut().enumerate() {
+            let rows: Vec<serde_json::Value> = response.take(index)?;
+            *count = rows.len() as u64;
+        }
+        if counts[0] == 0 {
+            return Err(PrivacyError::UserNotFound);
+        }
+
+        Ok(ErasureSummary {
+            pseudonym,
+            comments: counts[1] + counts[2],
+            ticket_history: counts[3],
+            tickets_reassigned: counts[4] + counts[5],
+            audit_entries: counts[6],
+            kb_articles: counts[7],
+            sessions_revoked: counts[8],
+        })
+    }
+
+    /// Pick up the outcome of the approval workflow: a completed run approves
+    /// the request, a failed or cancelled one rejects it
+    async fn sync_approval(&self, request: ErasureRequest) -> Result<ErasureRequest, PrivacyError> {
+        if request.status != ErasureStatus::PendingApproval {
+            return Ok(request);
+        }
+        let Some(instance_id) = request.workflow_instance_id.clone() else {
+            return Ok(request);
+        };
+
+        let instance = WorkflowEngineService::new(Arc::clone(&self.db))
+            .get_workflow_instance(instance_id)
+            .await
+            .map_err(PrivacyError::Workflow)?;
+        let status = match instance.map(|i| i.status) {
+            Some(WorkflowInstanceStatus::Completed) => ErasureStatus::Approved,
+            Some(WorkflowInstanceStatus::Failed) | Some(WorkflowInstanceStatus::Cancelled) | None => {
+                ErasureStatus::Rejected
+            }
+            Some(_) => return Ok(request),
+        };
+
+        let id = request.id.clone().ok_or(PrivacyError::NotFound)?;
+        let updated: Vec<ErasureRequest> = self
+            .db
+            .query("UPDATE $id SET status = $status, updated_at = time::now() WHERE status = 'PENDING_APPROVAL' RETURN AFTER")
+            .bind(("id", id))
+            .bind(("status", status))
+            .traced("privacy_service.sync_approval")
+            .await?
+            .take(0)?;
+        Ok(updated.into_iter().next().unwrap_or(request))
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn account(&self, user_id: &str) -> Result<Account, PrivacyError> {
+        let account: Option<Account> = self.db.select(parse_thing(USER_TABLE, user_id)).await?;
+        account.ok_or(PrivacyError::UserNotFound)
+    }
+
+    async fn load(&self, id: &str) -> Result<ErasureRequest, PrivacyError> {
+        let request: Option<ErasureRequest> = self.db.select(parse_thing(TABLE, id)).await?;
+        request.ok_or(PrivacyError::NotFound)
+    }
+
+    async fn save(&self, mut request: ErasureRequest) -> Result<ErasureRequest, PrivacyError> {
+        let id = request.id.take().ok_or(PrivacyError::NotFound)?;
+        request.updated_at = Utc::now();
+        let saved: Option<ErasureRequest> = self.db.update(id).content(request).await?;
+        saved.ok_or(PrivacyError::NotFound)
+    }
+
+    async fn audit(
+        &self,
+        event_type: AuditEventType,
+        action: &str,
+        account: &Account,
+        actor: &AuthenticatedUser,
+        details: serde_json::Value,
+    ) {
+        let log = AuditLog {
+            id: None,
+            event_type,
+            user_id: Some(parse_thing(USER_TABLE, &actor.user_id)),
+            username: Some(actor.username.clone()),
+            resource_type: Some("user".to_string()),
+            resource_id: Some(account.id.to_string()),
+            action: action.to_string(),
+            details: Some(details),
+            ip_address: None,
+            user_agent: None,
+            success: true,
+            error_message: None,
+            tenant_id: account.tenant_id.clone(),
+            created_at: Utc::now(),
+        };
+        let stored: Result<Vec<AuditLog>, _> = self.db.create("audit_logs").content(log).await;
+        if let Err(e) = stored {
+            warn!("Could not write the audit entry of {}: {}", action, e);
+        }
+    }
+}
+
+/// Name an erased account and its records carry from then on
+fn pseudonym() -> String {
+    let id = Uuid::new_v4().simple().to_string();
+    format!("erased-{}", &id[..12])
+}
+
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key.trim_start_matches('⟨').trim_end_matches('⟩'))),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudonyms_are_unique_and_carry_no_identity() {
+        let first = pseudonym();
+        let second = pseudonym();
+        assert_ne!(first, second);
+        assert!(first.starts_with("erased-"));
+        assert_eq!(first.len(), "erased-".len() + 12);
+    }
+
+    #[test]
+    fn test_parse_thing_accepts_bare_and_qualified_ids() {
+        assert_eq!(parse_thing(USER_TABLE, "abc"), Thing::from(("users", "abc")));
+        assert_eq!(parse_thing(USER_TABLE, "users:abc"), Thing::from(("users", "abc")));
+        assert_eq!(parse_thing(TABLE, "user_erasure_request:⟨a-b⟩"), Thing::from((TABLE, "a-b")));
+    }
+}
@@ -0,0 +1,574 @@
+// Archer ITSM - Procurement Service
+// Turns an approved BOM into a purchase request, routes it through the
+// workflow engine for approval, tracks the purchase order per line and
+// updates hardware pool stock as lines are received
+
+use chrono::Utc;
+use core_engine::currency::{normalize_code, Money};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::procurement::*;
+use crate::models::spare_part::RestockSparePartRequest;
+use crate::models::workflow_engine::WorkflowInstanceStatus;
+use crate::services::hardware_pool_service::parts_inventory::{PartsInventoryError, PartsInventoryService};
+use crate::services::hardware_pool_service::{CreateHardwarePoolRequest, HardwarePoolService};
+use crate::services::workflow_engine_service::WorkflowEngineService;
+use crate::database::TracedQuery;
+
+const TABLE: &str = "purchase_request";
+/// Projects BOMs are totalled for (see the estimation BOM endpoint)
+const PROJECT_TABLE: &str = "migration_wizard_project";
+
+#[derive(Debug, Error)]
+pub enum ProcurementError {
+    #[error("Purchase request not found")]
+    NotFound,
+
+    #[error("Purchase request line {0} not found")]
+    LineNotFound(u32),
+
+    #[error("Invalid state: {0}")]
+    InvalidState(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Workflow error: {0}")]
+    Workflow(String),
+
+    #[error("Receipt failed: {0}")]
+    Receipt(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for ProcurementError {
+    fn from(e: surrealdb::Error) -> Self {
+        ProcurementError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct ProcurementService {
+    db: Arc<Database>,
+}
+
+impl ProcurementService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ========================================================================
+    // PURCHASE REQUESTS
+    // ========================================================================
+
+    pub async fn list_requests(
+        &self,
+        query: &PurchaseRequestListQuery,
+    ) -> Result<Vec<PurchaseRequest>, ProcurementError> {
+        let requests: Vec<PurchaseRequest> = self
+            .db
+            .query("SELECT * FROM purchase_request ORDER BY created_at DESC")
+            .traced("procurement_service.list_requests")
+            .await?
+            .take(0)?;
+
+        let project = query.project_id.as_deref().map(|p| parse_thing(PROJECT_TABLE, p));
+        let mut matching = Vec::new();
+        for request in requests {
+            if query.vendor.as_ref().map_or(true, |v| request.vendor.eq_ignore_ascii_case(v))
+                && project.as_ref().map_or(true, |p| request.project_id.as_ref() == Some(p))
+            {
+                let request = self.sync_approval(request).await?;
+                if query.status.map_or(true, |s| request.status == s) {
+                    matching.push(request);
+                }
+            }
+        }
+        Ok(matching)
+    }
+
+    pub async fn get_request(&self, id: &str) -> Result<PurchaseRequest, ProcurementError> {
+        let request = self.load(id).await?;
+        self.sync_approval(request).await
+    }
+
+    /// Convert BOM lines into a draft purchase request
+    pub async fn create_request(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreatePurchaseRequest,
+    ) -> Result<PurchaseRequest, ProcurementError> {
+        if request.title.trim().is_empty() {
+            return Err(ProcurementError::Validation("Title is required".to_string()));
+        }
+        if request.vendor.trim().is_empty() {
+            return Err(ProcurementError::Validation("Vendor is required".to_string()));
+        }
+        if request.lines.is_empty() {
+            return Err(ProcurementError::Validation("A purchase request needs at least one line".to_string()));
+        }
+
+        let mut lines = Vec::with_capacity(request.lines.len());
+        for (index, line) in request.lines.into_iter().enumerate() {
+            let bom = line.bom_line;
+            if bom.quantity == 0 {
+                return Err(ProcurementError::Validation(format!("Line {} has no quantity", index + 1)));
+            }
+            if !bom.unit_price.amount.is_finite() || bom.unit_price.amount < 0.0 {
+                return Err(ProcurementError::Validation(format!("Line {} has an invalid price", index + 1)));
+            }
+            let currency = normalize_code(&bom.unit_price.currency)
+                .map_err(|e| ProcurementError::Validation(format!("Line {}: {}", index + 1, e)))?;
+            if let Some(ReceiptTarget::SparePart { spare_part_id }) = &line.receive_into {
+                PartsInventoryService::new(Arc::clone(&self.db))
+                    .get_part(spare_part_id)
+                    .await
+                    .map_err(|_| {
+                        ProcurementError::Validation(format!("Line {}: spare part {} not found", index + 1, spare_part_id))
+                    })?;
+            }
+
+            lines.push(PurchaseRequestLine {
+                line_no: index as u32 + 1,
+                part_number: bom.part_number,
+                description: bom.description,
+                quantity: bom.quantity,
+                unit_price: Money::new(bom.unit_price.amount, &currency),
+                received_quantity: 0,
+                status: LineStatus::Pending,
+                expected_delivery: None,
+                receive_into: line.receive_into,
+            });
+        }
+
+        let now = Utc::now();
+        let purchase = PurchaseRequest {
+            id: None,
+            title: request.title.trim().to_string(),
+            project_id: request.project_id.as_deref().map(|p| parse_thing(PROJECT_TABLE, p)),
+            vendor: request.vendor.trim().to_string(),
+            totals_by_currency: totals_by_currency(&lines),
+            lines,
+            status: PurchaseRequestStatus::Draft,
+            workflow_instance_id: None,
+            po_number: None,
+            order_date: None,
+            notes: request.notes,
+            requested_by: user.user_id.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<PurchaseRequest> = self.db.create(TABLE).content(purchase).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProcurementError::DatabaseError("Failed to create purchase request".to_string()))
+    }
+
+    /// Delete a draft; anything submitted is cancelled instead
+    pub async fn delete_request(&self, id: &str) -> Result<(), ProcurementError> {
+        let request = self.load(id).await?;
+        if request.status != PurchaseRequestStatus::Draft {
+            return Err(ProcurementError::InvalidState("Only drafts can be deleted".to_string()));
+        }
+        let _: Option<PurchaseRequest> = self.db.delete(parse_thing(TABLE, id)).await?;
+        Ok(())
+    }
+
+    pub async fn cancel_request(&self, id: &str) -> Result<PurchaseRequest, ProcurementError> {
+        let mut request = self.get_request(id).await?;
+        if matches!(
+            request.status,
+            PurchaseRequestStatus::PartiallyReceived | PurchaseRequestStatus::Received | PurchaseRequestStatus::Cancelled
+        ) {
+            return Err(ProcurementError::InvalidState(format!(
+                "A {:?} purchase request cannot be cancelled",
+                request.status
+            )));
+        }
+
+        if request.status == PurchaseRequestStatus::PendingApproval {
+            if let Some(instance) = &request.workflow_instance_id {
+                // The approval may have finished in the meantime; that's fine
+                let _ = WorkflowEngineService::new(Arc::clone(&self.db))
+                    .cancel_workflow_instance(instance.clone())
+                    .await;
+            }
+        }
+        request.status = PurchaseRequestStatus::Cancelled;
+        for line in &mut request.lines {
+            line.status = LineStatus::Cancelled;
+        }
+        self.save(id, request).await
+    }
+
+    // ========================================================================
+    // APPROVAL
+    // ========================================================================
+
+    /// Start the approval workflow for a draft (or a rejected request being
+    /// resubmitted)
+    pub async fn submit_request(
+        &self,
+        id: &str,
+        submit: SubmitPurchaseRequest,
+    ) -> Result<PurchaseRequest, ProcurementError> {
+        let mut request = self.get_request(id).await?;
+        if !matches!(request.status, PurchaseRequestStatus::Draft | PurchaseRequestStatus::Rejected) {
+            return Err(ProcurementError::InvalidState(
+                "Only draft or rejected purchase requests can be submitted".to_string(),
+            ));
+        }
+
+        let workflow_id = submit
+            .workflow_id
+            .or_else(|| std::env::var("PROCUREMENT_APPROVAL_WORKFLOW").ok())
+            .filter(|w| !w.trim().is_empty())
+            .ok_or_else(|| {
+                ProcurementError::Validation(
+                    "No approval workflow given and PROCUREMENT_APPROVAL_WORKFLOW is not set".to_string(),
+                )
+            })?;
+
+        let record = parse_thing(TABLE, id);
+        let context = json!({
+            "purchase_request": {
+                "title": request.title,
+                "vendor": request.vendor,
+                "totals_by_currency": request.totals_by_currency,
+                "line_count": request.lines.len(),
+                "requested_by": request.requested_by,
+            }
+        });
+        let instance = WorkflowEngineService::new(Arc::clone(&self.db))
+            .trigger_workflow(
+                parse_thing("workflow_definition", &workflow_id),
+                TABLE.to_string(),
+                record,
+                Some(context),
+            )
+            .await
+            .map_err(ProcurementError::Workflow)?;
+
+        request.status = PurchaseRequestStatus::PendingApproval;
+        request.workflow_instance_id = instance.id;
+        self.save(id, request).await
+    }
+
+    /// Pick up the outcome of the approval workflow: a completed run approves
+    /// the request, a failed or cancelled one (a rejected approval step fails
+    /// the run) rejects it
+    async fn sync_approval(&self, request: PurchaseRequest) -> Result<PurchaseRequest, ProcurementError> {
+        if request.status != PurchaseRequestStatus::PendingApproval {
+            return Ok(request);
+        }
+        let Some(instance_id) = request.workflow_instance_id.clone() else {
+            return Ok(request);
+        };
+
+        let instance = WorkflowEngineService::new(Arc::clone(&self.db))
+            .get_workflow_instance(instance_id)
+            .await
+            .map_err(ProcurementError::Workflow)?;
+        let status = match instance.map(|i| i.status) {
+            Some(WorkflowInstanceStatus::Completed) => PurchaseRequestStatus::Approved,
+            Some(WorkflowInstanceStatus::Failed) | Some(WorkflowInstanceStatus::Cancelled) | None => {
+                PurchaseRequestStatus::Rejected
+            }
+            Some(_) => return Ok(request),
+        };
+
+        let id = request.id.clone().ok_or(ProcurementError::NotFound)?;
+        let updated: Vec<PurchaseRequest> = self
+            .db
+            .query("UPDATE $id SET status = $status, updated_at = time::now() WHERE status = 'PENDING_APPROVAL' RETURN AFTER")
+            .bind(("id", id))
+            .bind(("status", status))
+            .traced("procurement_service.sync_approval")
+            .await?
+            .take(0)?;
+        Ok(updated.into_iter().next().unwrap_or(request))
+    }
+
+    // ========================================================================
+    // ORDERING & RECEIPT
+    // ========================================================================
+
+    /// Record the purchase order placed for an approved request
+    pub async fn record_order(&self, id: &str, order: RecordOrderRequest) -> Result<PurchaseRequest, ProcurementError> {
+        let mut request = self.get_request(id).await?;
+        if request.status != PurchaseRequestStatus::Approved {
+            return Err(ProcurementError::InvalidState(
+                "A purchase order can only be recorded for an approved request".to_string(),
+            ));
+        }
+        if order.po_number.trim().is_empty() {
+            return Err(ProcurementError::Validation("PO number is required".to_string()));
+        }
+
+        request.po_number = Some(order.po_number.trim().to_string());
+        request.order_date = Some(order.order_date.unwrap_or_else(Utc::now));
+        for line in &mut request.lines {
+            line.status = LineStatus::Ordered;
+            line.expected_delivery = order.expected_delivery;
+        }
+        request.status = PurchaseRequestStatus::Ordered;
+        self.save(id, request).await
+    }
+
+    /// Mark a line shipped or cancelled, or move its expected delivery
+    pub async fn update_line(
+        &self,
+        id: &str,
+        line_no: u32,
+        update: UpdateLineRequest,
+    ) -> Result<PurchaseRequest, ProcurementError> {
+        let mut request = self.get_request(id).await?;
+        ensure_ordered(&request)?;
+        let line = find_line(&mut request, line_no)?;
+
+        if let Some(status) = update.status {
+            match status {
+                LineStatus::Shipped if matches!(line.status, LineStatus::Ordered | LineStatus::Shipped) => {}
+                LineStatus::Cancelled if line.received_quantity == 0 => {}
+                LineStatus::Shipped | LineStatus::Cancelled => {
+                    return Err(ProcurementError::InvalidState(format!(
+                        "Line {} is {:?} and cannot become {:?}",
+                        line_no, line.status, status
+                    )));
+                }
+                _ => {
+                    return Err(ProcurementError::Validation(
+                        "Line status can only be set to SHIPPED or CANCELLED".to_string(),
+                    ));
+                }
+            }
+            line.status = status;
+        }
+        if let Some(expected) = update.expected_delivery {
+            line.expected_delivery = Some(expected);
+        }
+
+        request.status = rollup_status(&request.lines);
+        self.save(id, request).await
+    }
+
+    /// Receive units of a line and put them into hardware pool stock
+    pub async fn receive_line(
+        &self,
+        id: &str,
+        line_no: u32,
+        receipt: ReceiveLineRequest,
+    ) -> Result<PurchaseRequest, ProcurementError> {
+        let mut request = self.get_request(id).await?;
+        ensure_ordered(&request)?;
+        let vendor = request.vendor.clone();
+        let po_number = request.po_number.clone();
+        let line = find_line(&mut request, line_no)?;
+
+        if line.status == LineStatus::Cancelled {
+            return Err(ProcurementError::InvalidState(format!("Line {} is cancelled", line_no)));
+        }
+        if receipt.quantity == 0 || receipt.quantity > line.outstanding() {
+            return Err(ProcurementError::Validation(format!(
+                "Line {} has {} outstanding; cannot receive {}",
+                line_no,
+                line.outstanding(),
+                receipt.quantity
+            )));
+        }
+
+        match &line.receive_into {
+            Some(ReceiptTarget::SparePart { spare_part_id }) => {
+                PartsInventoryService::new(Arc::clone(&self.db))
+                    .restock(spare_part_id, RestockSparePartRequest { quantity: receipt.quantity })
+                    .await
+                    .map_err(|e| match e {
+                        PartsInventoryError::NotFound => {
+                            ProcurementError::Receipt(format!("Spare part {} no longer exists", spare_part_id))
+                        }
+                        e => ProcurementError::Receipt(e.to_string()),
+                    })?;
+            }
+            Some(ReceiptTarget::Server {
+                model,
+                asset_tag_prefix,
+                location,
+                datacenter,
+            }) => {
+                let pool = HardwarePoolService::new((*self.db).clone());
+                for unit in 0..receipt.quantity {
+                    let sequence = line.received_quantity + unit + 1;
+                    let server = CreateHardwarePoolRequest {
+                        asset_tag: format!("{}-{:03}", asset_tag_prefix, sequence),
+                        serial_number: receipt.serial_numbers.get(unit as usize).cloned(),
+                        hardware_lot_id: None,
+                        vendor: vendor.clone(),
+                        model: model.clone(),
+                        form_factor: None,
+                        cpu_sockets: None,
+                        cpu_cores_total: None,
+                        memory_gb: None,
+                        storage_type: None,
+                        storage_capacity_gb: None,
+                        network_ports: None,
+                        power_consumption_watts: None,
+                        rack_units: None,
+                        location: location.clone(),
+                        datacenter: datacenter.clone(),
+                        rack_position: None,
+                        available_until_date: None,
+                        acquisition_cost: Some(line.unit_price.amount),
+                        monthly_cost: None,
+                        warranty_expires: None,
+                        support_level: None,
+                    };
+                    let created = pool
+                        .add_server_to_pool(server)
+                        .await
+                        .map_err(|e| ProcurementError::Receipt(e.to_string()))?;
+                    if let (Some(server_id), Some(po)) = (created.id, &po_number) {
+                        self.db
+                            .query("UPDATE $id SET metadata.purchase_order = $po, metadata.purchase_request = $request")
+                            .bind(("id", server_id))
+                            .bind(("po", po.clone()))
+                            .bind(("request", parse_thing(TABLE, id)))
+                            .traced("procurement_service.receive_line")
+                            .await?;
+                    }
+                }
+            }
+            None => {}
+        }
+
+        line.received_quantity += receipt.quantity;
+        line.status = if line.outstanding() == 0 {
+            LineStatus::Received
+        } else {
+            LineStatus::PartiallyReceived
+        };
+        request.status = rollup_status(&request.lines);
+        self.save(id, request).await
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn load(&self, id: &str) -> Result<PurchaseRequest, ProcurementError> {
+        let request: Option<PurchaseRequest> = self.db.select(parse_thing(TABLE, id)).await?;
+        request.ok_or(ProcurementError::NotFound)
+    }
+
+    async fn save(&self, id: &str, mut request: PurchaseRequest) -> Result<PurchaseRequest, ProcurementError> {
+        request.updated_at = Utc::now();
+        let updated: Option<PurchaseRequest> = self.db.update(parse_thing(TABLE, id)).content(request).await?;
+        updated.ok_or(ProcurementError::NotFound)
+    }
+}
+
+fn ensure_ordered(request: &PurchaseRequest) -> Result<(), ProcurementError> {
+    match request.status {
+        PurchaseRequestStatus::Ordered | PurchaseRequestStatus::PartiallyReceived => Ok(()),
+        status => Err(ProcurementError::InvalidState(format!(
+            "Purchase request is {:?}; lines can only change once ordered",
+            status
+        ))),
+    }
+}
+
+fn find_line(request: &mut PurchaseRequest, line_no: u32) -> Result<&mut PurchaseRequestLine, ProcurementError> {
+    request
+        .lines
+        .iter_mut()
+        .find(|l| l.line_no == line_no)
+        .ok_or(ProcurementError::LineNotFound(line_no))
+}
+
+/// Request status once ordered, from its lines: received when every line
+/// still on order is in, partially received once anything has arrived
+fn rollup_status(lines: &[PurchaseRequestLine]) -> PurchaseRequestStatus {
+    let open: Vec<&PurchaseRequestLine> = lines.iter().filter(|l| l.status != LineStatus::Cancelled).collect();
+    if open.is_empty() {
+        PurchaseRequestStatus::Cancelled
+    } else if open.iter().all(|l| l.status == LineStatus::Received) {
+        PurchaseRequestStatus::Received
+    } else if open.iter().any(|l| l.received_quantity > 0) {
+        PurchaseRequestStatus::PartiallyReceived
+    } else {
+        PurchaseRequestStatus::Ordered
+    }
+}
+
+/// Extended line prices summed per quote currency, never converted
+fn totals_by_currency(lines: &[PurchaseRequestLine]) -> BTreeMap<String, f64> {
+    let mut totals = BTreeMap::new();
+    for line in lines {
+        *totals.entry(line.unit_price.currency.clone()).or_insert(0.0) += line.unit_price.amount * line.quantity as f64;
+    }
+    for total in totals.values_mut() {
+        *total = (*total * 100.0).round() / 100.0;
+    }
+    totals
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(line_no: u32, quantity: u32, received: u32, status: LineStatus, price: Money) -> PurchaseRequestLine {
+        PurchaseRequestLine {
+            line_no,
+            part_number: format!("PN-{}", line_no),
+            description: String::new(),
+            quantity,
+            unit_price: price,
+            received_quantity: received,
+            status,
+            expected_delivery: None,
+            receive_into: None,
+        }
+    }
+
+    #[test]
+    fn test_rollup_status_and_totals() {
+        let eur = Money::new(1200.5, "EUR");
+        let usd = Money::new(89.99, "USD");
+
+        let mut lines = vec![
+            line(1, 4, 0, LineStatus::Ordered, eur.clone()),
+            line(2, 10, 0, LineStatus::Shipped, usd.clone()),
+        ];
+        assert_eq!(rollup_status(&lines), PurchaseRequestStatus::Ordered);
+
+        lines[0] = line(1, 4, 2, LineStatus::PartiallyReceived, eur.clone());
+        assert_eq!(rollup_status(&lines), PurchaseRequestStatus::PartiallyReceived);
+
+        lines[0] = line(1, 4, 4, LineStatus::Received, eur.clone());
+        lines[1] = line(2, 10, 0, LineStatus::Cancelled, usd.clone());
+        assert_eq!(rollup_status(&lines), PurchaseRequestStatus::Received);
+
+        lines[0] = line(1, 4, 0, LineStatus::Cancelled, eur);
+        assert_eq!(rollup_status(&lines), PurchaseRequestStatus::Cancelled);
+
+        let totals = totals_by_currency(&lines);
+        assert_eq!(totals.get("EUR"), Some(&4802.0));
+        assert_eq!(totals.get("USD"), Some(&899.9));
+    }
+}
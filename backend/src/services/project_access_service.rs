@@ -0,0 +1,702 @@
+// Archer - Project Access Service
+// Per-project roles on top of the global RBAC. Users and teams become members
+// of a project with an owner, editor or viewer role, either when they create
+// it or by accepting an invitation. Admins (and `projects:manage`) see every
+// project. Projects without members predate sharing and stay open to every
+// signed-in user until someone shares them.
+
+use chrono::{Duration, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::project_access::*;
+use crate::models::team::{TeamMembership, TeamRole};
+
+const TABLE: &str = "project_membership";
+const INVITATION_TABLE: &str = "project_invitation";
+/// Days an invitation can be accepted
+const INVITATION_DAYS: i64 = 14;
+
+#[derive(Debug, Error)]
+pub enum ProjectAccessError {
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("Member not found")]
+    MemberNotFound,
+
+    #[error("Invitation not found")]
+    InvitationNotFound,
+
+    #[error("A project needs at least one owner")]
+    LastOwner,
+
+    #[error("Invalid state: {0}")]
+    InvalidState(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for ProjectAccessError {
+    fn from(e: surrealdb::Error) -> Self {
+        ProjectAccessError::DatabaseError(e.to_string())
+    }
+}
+
+pub struct ProjectAccessService {
+    db: Arc<Database>,
+}
+
+impl ProjectAccessService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ========================================================================
+    // ACCESS CHECKS
+    // ========================================================================
+
+    /// The caller's role on a project, `None` without access
+    pub async fn effective_role(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+    ) -> Result<Option<ProjectRole>, ProjectAccessError> {
+        Ok(self.summary(user, project_id).await?.role)
+    }
+
+    pub async fn summary(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+    ) -> Result<ProjectAccessSummary, ProjectAccessError> {
+        let project_id = project_key(project_id);
+        let members = self.members_of(&project_id).await?;
+        let open = members.is_empty();
+        let (role, audience) = if is_admin(user) || open {
+            (Some(ProjectRole::Owner), DocumentAudience::Full)
+        } else {
+            let teams = self.user_teams(&user.user_id).await?;
+            (
+                resolve_role(&members, &user.user_id, &teams),
+                resolve_audience(&members, &user.user_id, &teams),
+            )
+        };
+        Ok(ProjectAccessSummary {
+            project_id,
+            role,
+            open,
+            audience,
+        })
+    }
+
+    /// Variant of the project's generated documents the caller gets
+    pub async fn effective_audience(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+    ) -> Result<DocumentAudience, ProjectAccessError> {
+        Ok(self.summary(user, project_id).await?.audience)
+    }
+
+    /// Fails unless the caller's role on the project allows `required`
+    pub async fn authorize(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+        required: ProjectRole,
+    ) -> Result<ProjectRole, ProjectAccessError> {
+        match self.effective_role(user, project_id).await? {
+            Some(role) if role.allows(required) => Ok(role),
+            Some(role) => Err(ProjectAccessError::Forbidden(format!(
+                "Your role on this project is {:?}; {:?} is required",
+                role, required
+            ))),
+            None => Err(ProjectAccessError::Forbidden("You are not a member of this project".to_string())),
+        }
+    }
+
+    /// The subset of `project_ids` the caller can see
+    pub async fn visible_projects(
+        &self,
+        user: &AuthenticatedUser,
+        project_ids: &[String],
+    ) -> Result<HashSet<String>, ProjectAccessError> {
+        let keys: Vec<String> = project_ids.iter().map(|id| project_key(id)).collect();
+        if is_admin(user) {
+            return Ok(keys.into_iter().collect());
+        }
+        let members: Vec<ProjectMembership> = self
+            .db
+            .query("SELECT * FROM project_membership WHERE project_id INSIDE $projects")
+            .bind(("projects", keys.clone()))
+            .traced("project_access.visible_projects")
+            .await?
+            .take(0)?;
+        let teams = self.user_teams(&user.user_id).await?;
+
+        Ok(keys
+            .into_iter()
+            .filter(|key| {
+                let project_members: Vec<ProjectMembership> =
+                    members.iter().filter(|m| &m.project_id == key).cloned().collect();
+                project_members.is_empty() || resolve_role(&project_members, &user.user_id, &teams).is_some()
+            })
+            .collect())
+    }
+
+    /// Make the creator of a new project its owner
+    pub async fn add_owner(&self, user: &AuthenticatedUser, project_id: &str) -> Result<(), ProjectAccessError> {
+        self.upsert_member(
+            &project_key(project_id),
+            PrincipalType::User,
+            &user.user_id,
+            ProjectRole::Owner,
+            DocumentAudience::Full,
+            &user.user_id,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    // ========================================================================
+    // MEMBERS
+    // ========================================================================
+
+    pub async fn list_members(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+    ) -> Result<Vec<ProjectMembership>, ProjectAccessError> {
+        self.authorize(user, project_id, ProjectRole::Viewer).await?;
+        self.members_of(&project_key(project_id)).await
+    }
+
+    pub async fn update_member(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+        member_id: &str,
+        request: UpdateMemberRequest,
+    ) -> Result<ProjectMembership, ProjectAccessError> {
+        self.authorize(user, project_id, ProjectRole::Owner).await?;
+        let project_id = project_key(project_id);
+        let members = self.members_of(&project_id).await?;
+        let mut member = find_member(&members, member_id)?.clone();
+
+        if member.role == ProjectRole::Owner && request.role != ProjectRole::Owner && owner_count(&members) == 1 {
+            return Err(ProjectAccessError::LastOwner);
+        }
+        member.role = request.role;
+        if let Some(audience) = request.audience {
+            member.audience = audience;
+        }
+        member.updated_at = Utc::now();
+
+        let updated: Option<ProjectMembership> = self
+            .db
+            .update(thing_of(&member)?)
+            .content(&member)
+            .await?;
+        updated.ok_or(ProjectAccessError::MemberNotFound)
+    }
+
+    /// Owners remove anyone; every member can leave
+    pub async fn remove_member(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+        member_id: &str,
+    ) -> Result<(), ProjectAccessError> {
+        self.authorize(user, project_id, ProjectRole::Viewer).await?;
+        let project_id = project_key(project_id);
+        let members = self.members_of(&project_id).await?;
+        let member = find_member(&members, member_id)?;
+
+        let leaving = member.principal_type == PrincipalType::User && member.principal_id == user.user_id;
+        if !leaving {
+            self.authorize(user, &project_id, ProjectRole::Owner).await?;
+        }
+        if member.role == ProjectRole::Owner && owner_count(&members) == 1 {
+            return Err(ProjectAccessError::LastOwner);
+        }
+
+        let _: Option<ProjectMembership> = self.db.delete(thing_of(member)?).await?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // INVITATIONS
+    // ========================================================================
+
+    /// Invite a user, a team or an email address. Inviting on a project that
+    /// predates sharing makes the inviter its owner first, so the project
+    /// does not close on them when the invitation is accepted.
+    pub async fn invite(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+        request: InviteRequest,
+    ) -> Result<ProjectInvitation, ProjectAccessError> {
+        self.authorize(user, project_id, ProjectRole::Owner).await?;
+        let project_id = project_key(project_id);
+        let invitee = normalize_invitee(request.invitee)?;
+
+        if self.members_of(&project_id).await?.is_empty() {
+            self.add_owner(user, &project_id).await?;
+        }
+
+        let pending: Vec<ProjectInvitation> = self.project_invitations(&project_id).await?;
+        if pending
+            .iter()
+            .any(|i| i.status == InvitationStatus::Pending && i.invitee == invitee && !i.is_expired(Utc::now()))
+        {
+            return Err(ProjectAccessError::InvalidState(
+                "An invitation for them is already pending".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        let invitation = ProjectInvitation {
+            id: None,
+            project_id,
+            invitee,
+            role: request.role,
+            audience: request.audience,
+            status: InvitationStatus::Pending,
+            invited_by: user.user_id.clone(),
+            message: request.message,
+            created_at: now,
+            expires_at: now + Duration::days(INVITATION_DAYS),
+            responded_by: None,
+            responded_at: None,
+        };
+        let created: Vec<ProjectInvitation> = self.db.create(INVITATION_TABLE).content(&invitation).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProjectAccessError::DatabaseError("Failed to create invitation".to_string()))
+    }
+
+    pub async fn list_invitations(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+    ) -> Result<Vec<ProjectInvitation>, ProjectAccessError> {
+        self.authorize(user, project_id, ProjectRole::Owner).await?;
+        self.project_invitations(&project_key(project_id)).await
+    }
+
+    pub async fn revoke_invitation(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: &str,
+        invitation_id: &str,
+    ) -> Result<ProjectInvitation, ProjectAccessError> {
+        self.authorize(user, project_id, ProjectRole::Owner).await?;
+        let invitation = self.load_invitation(invitation_id).await?;
+        if invitation.project_id != project_key(project_id) {
+            return Err(ProjectAccessError::InvitationNotFound);
+        }
+        self.respond(invitation, InvitationStatus::Revoked, user).await
+    }
+
+    /// Pending invitations the caller can accept: their own, their email's,
+    /// and those of teams they lead
+    pub async fn my_invitations(&self, user: &AuthenticatedUser) -> Result<Vec<ProjectInvitation>, ProjectAccessError> {
+        let invitations: Vec<ProjectInvitation> = self
+            .db
+            .query("SELECT * FROM project_invitation WHERE status = 'PENDING' ORDER BY created_at DESC")
+            .traced("project_access.my_invitations")
+            .await?
+            .take(0)?;
+        let led_teams = self.led_teams(&user.user_id).await?;
+        let now = Utc::now();
+        Ok(invitations
+            .into_iter()
+            .filter(|i| !i.is_expired(now) && can_respond(&i.invitee, user, &led_teams))
+            .collect())
+    }
+
+    pub async fn accept_invitation(
+        &self,
+        user: &AuthenticatedUser,
+        invitation_id: &str,
+    ) -> Result<ProjectMembership, ProjectAccessError> {
+        let invitation = self.pending_for(user, invitation_id).await?;
+        let (principal_type, principal_id) = match &invitation.invitee {
+            Invitee::Team { id } => (PrincipalType::Team, id.clone()),
+            Invitee::User { .. } | Invitee::Email { .. } => (PrincipalType::User, user.user_id.clone()),
+        };
+        let membership = self
+            .upsert_member(
+                &invitation.project_id,
+                principal_type,
+                &principal_id,
+                invitation.role,
+                invitation.audience,
+                &invitation.invited_by,
+            )
+            .await?;
+        self.respond(invitation, InvitationStatus::Accepted, user).await?;
+        Ok(membership)
+    }
+
+    pub async fn decline_invitation(
+        &self,
+        user: &AuthenticatedUser,
+        invitation_id: &str,
+    ) -> Result<ProjectInvitation, ProjectAccessError> {
+        let invitation = self.pending_for(user, invitation_id).await?;
+        self.respond(invitation, InvitationStatus::Declined, user).await
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn members_of(&self, project_id: &str) -> Result<Vec<ProjectMembership>, ProjectAccessError> {
+        let members: Vec<ProjectMembership> = self
+            .db
+            .query("SELECT * FROM project_membership WHERE project_id = $project ORDER BY created_at")
+            .bind(("project", project_id.to_string()))
+            .traced("project_access.members_of")
+            .await?
+            .take(0)?;
+        Ok(members)
+    }
+
+    async fn project_invitations(&self, project_id: &str) -> Result<Vec<ProjectInvitation>, ProjectAccessError> {
+        let invitations: Vec<ProjectInvitation> = self
+            .db
+            .query("SELECT * FROM project_invitation WHERE project_id = $project ORDER BY created_at DESC")
+            .bind(("project", project_id.to_string()))
+            .traced("project_access.project_invitations")
+            .await?
+            .take(0)?;
+        Ok(invitations)
+    }
+
+    /// `teams:...` ids of the user's teams
+    async fn user_teams(&self, user_id: &str) -> Result<Vec<String>, ProjectAccessError> {
+        Ok(self
+            .team_memberships(user_id)
+            .await?
+            .into_iter()
+            .map(|m| m.team_id.to_string())
+            .collect())
+    }
+
+    async fn led_teams(&self, user_id: &str) -> Result<Vec<String>, ProjectAccessError> {
+        Ok(self
+            .team_memberships(user_id)
+            .await?
+            .into_iter()
+            .filter(|m| m.role == TeamRole::Lead)
+            .map(|m| m.team_id.to_string())
+            .collect())
+    }
+
+    async fn team_memberships(&self, user_id: &str) -> Result<Vec<TeamMembership>, ProjectAccessError> {
+        let memberships: Vec<TeamMembership> = self
+            .db
+            .query("SELECT * FROM team_memberships WHERE user_id = $user")
+            .bind(("user", parse_thing("users", user_id)))
+            .traced("project_access.team_memberships")
+            .await?
+            .take(0)?;
+        Ok(memberships)
+    }
+
+    async fn upsert_member(
+        &self,
+        project_id: &str,
+        principal_type: PrincipalType,
+        principal_id: &str,
+        role: ProjectRole,
+        audience: DocumentAudience,
+        added_by: &str,
+    ) -> Result<ProjectMembership, ProjectAccessError> {
+        let members = self.members_of(project_id).await?;
+        let now = Utc::now();
+        let existing = members
+            .iter()
+            .find(|m| m.principal_type == principal_type && m.principal_id == principal_id);
+
+        let saved: Option<ProjectMembership> = match existing {
+            // An invitation never lowers a role or narrows an audience
+            // someone already has
+            Some(member) => {
+                let mut member = member.clone();
+                member.role = member.role.max(role);
+                member.audience = member.audience.min(audience);
+                member.updated_at = now;
+                self.db.update(thing_of(&member)?).content(&member).await?
+            }
+            None => {
+                let member = ProjectMembership {
+                    id: None,
+                    project_id: project_id.to_string(),
+                    principal_type,
+                    principal_id: principal_id.to_string(),
+                    role,
+                    audience,
+                    added_by: added_by.to_string(),
+                    created_at: now,
+                    updated_at: now,
+                };
+                let created: Vec<ProjectMembership> = self.db.create(TABLE).content(&member).await?;
+                created.into_iter().next()
+            }
+        };
+        saved.ok_or_else(|| ProjectAccessError::DatabaseError("Failed to save project member".to_string()))
+    }
+
+    async fn load_invitation(&self, invitation_id: &str) -> Result<ProjectInvitation, ProjectAccessError> {
+        let invitation: Option<ProjectInvitation> = self
+            .db
+            .select(parse_thing(INVITATION_TABLE, invitation_id))
+            .await?;
+        invitation.ok_or(ProjectAccessError::InvitationNotFound)
+    }
+
+    /// A pending, unexpired invitation the caller may respond to
+    async fn pending_for(
+        &self,
+        user: &AuthenticatedUser,
+        invitation_id: &str,
+    ) -> Result<ProjectInvitation, ProjectAccessError> {
+        let invitation = self.load_invitation(invitation_id).await?;
+        let led_teams = self.led_teams(&user.user_id).await?;
+        if !can_respond(&invitation.invitee, user, &led_teams) {
+            return Err(ProjectAccessError::Forbidden("This invitation is for someone else".to_string()));
+        }
+        if invitation.status != InvitationStatus::Pending {
+            return Err(ProjectAccessError::InvalidState(format!(
+                "The invitation is {:?}",
+                invitation.status
+            )));
+        }
+        if invitation.is_expired(Utc::now()) {
+            return Err(ProjectAccessError::InvalidState("The invitation has expired".to_string()));
+        }
+        Ok(invitation)
+    }
+
+    async fn respond(
+        &self,
+        mut invitation: ProjectInvitation,
+        status: InvitationStatus,
+        user: &AuthenticatedUser,
+    ) -> Result<ProjectInvitation, ProjectAccessError> {
+        let id = invitation.id.clone().ok_or(ProjectAccessError::InvitationNotFound)?;
+        invitation.status = status;
+        invitation.responded_by = Some(user.user_id.clone());
+        invitation.responded_at = Some(Utc::now());
+        let updated: Option<ProjectInvitation> = self.db.update(id).content(&invitation).await?;
+        updated.ok_or(ProjectAccessError::InvitationNotFound)
+    }
+}
+
+// ============================================================================
+// PURE HELPERS
+// ============================================================================
+
+/// Raw id of a project, whichever table prefix the caller used
+pub fn project_key(project_id: &str) -> String {
+    project_id.rsplit(':').next().unwrap_or(project_id).to_string()
+}
+
+fn is_admin(user: &AuthenticatedUser) -> bool {
+    user.has_any_role(&["super_admin", "admin"]) || user.has_permission("projects:manage")
+}
+
+/// Memberships that apply to the user, directly or through one of their teams
+fn memberships_of<'a>(
+    members: &'a [ProjectMembership],
+    user_id: &'a str,
+    teams: &'a [String],
+) -> impl Iterator<Item = &'a ProjectMembership> {
+    members.iter().filter(move |m| match m.principal_type {
+        PrincipalType::User => m.principal_id == user_id,
+        PrincipalType::Team => teams.contains(&m.principal_id),
+    })
+}
+
+/// Highest role the user holds directly or through one of their teams
+pub fn resolve_role(members: &[ProjectMembership], user_id: &str, teams: &[String]) -> Option<ProjectRole> {
+    memberships_of(members, user_id, teams).map(|m| m.role).max()
+}
+
+/// Least redacted audience among the user's memberships; the most redacted
+/// one when they have none
+pub fn resolve_audience(members: &[ProjectMembership], user_id: &str, teams: &[String]) -> DocumentAudience {
+    memberships_of(members, user_id, teams)
+        .map(|m| m.audience)
+        .min()
+        .unwrap_or(DocumentAudience::Vendor)
+}
+
+fn can_respond(invitee: &Invitee, user: &AuthenticatedUser, led_teams: &[String]) -> bool {
+    match invitee {
+        Invitee::User { id } => id == &user.user_id,
+        Invitee::Team { id } => led_teams.contains(id),
+        Invitee::Email { address } => address.eq_ignore_ascii_case(&user.email),
+    }
+}
+
+fn normalize_invitee(invitee: Invitee) -> Result<Invitee, ProjectAccessError> {
+    match invitee {
+        Invitee::User { id } if !id.trim().is_empty() => Ok(Invitee::User {
+            id: parse_thing("users", id.trim()).to_string(),
+        }),
+        Invitee::Team { id } if !id.trim().is_empty() => Ok(Invitee::Team {
+            id: parse_thing("teams", id.trim()).to_string(),
+        }),
+        Invitee::Email { address } if address.contains('@') => Ok(Invitee::Email {
+            address: address.trim().to_lowercase(),
+        }),
+        _ => Err(ProjectAccessError::Validation(
+            "Invite a user id, a team id or an email address".to_string(),
+        )),
+    }
+}
+
+fn find_member<'a>(
+    members: &'a [ProjectMembership],
+    member_id: &str,
+) -> Result<&'a ProjectMembership, ProjectAccessError> {
+    let key = project_key(member_id);
+    members
+        .iter()
+        .find(|m| m.id.as_ref().map_or(false, |id| id.id.to_raw() == key))
+        .ok_or(ProjectAccessError::MemberNotFound)
+}
+
+fn owner_count(members: &[ProjectMembership]) -> usize {
+    members.iter().filter(|m| m.role == ProjectRole::Owner).count()
+}
+
+fn thing_of(member: &ProjectMembership) -> Result<Thing, ProjectAccessError> {
+    member.id.clone().ok_or(ProjectAccessError::MemberNotFound)
+}
+
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(principal_type: PrincipalType, principal_id: &str, role: ProjectRole) -> ProjectMembership {
+        ProjectMembership {
+            id: Some(Thing::from((TABLE, principal_id))),
+            project_id: "p1".to_string(),
+            principal_type,
+            principal_id: principal_id.to_string(),
+            role,
+            audience: DocumentAudience::Full,
+            added_by: "users:alex".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn user(id: &str, email: &str) -> AuthenticatedUser {
+        AuthenticatedUser {
+            user_id: id.to_string(),
+            email: email.to_string(),
+            username: id.to_string(),
+            roles: vec!["user".to_string()],
+            permissions: Vec::new(),
+            tenant_id: None,
+        }
+    }
+
+    #[test]
+    fn test_role_through_team_or_directly() {
+        let members = vec![
+            member(PrincipalType::User, "users:alex", ProjectRole::Owner),
+            member(PrincipalType::User, "users:sam", ProjectRole::Viewer),
+            member(PrincipalType::Team, "teams:migration", ProjectRole::Editor),
+        ];
+        assert_eq!(resolve_role(&members, "users:alex", &[]), Some(ProjectRole::Owner));
+        assert_eq!(resolve_role(&members, "users:kim", &[]), None);
+        assert_eq!(
+            resolve_role(&members, "users:kim", &["teams:migration".to_string()]),
+            Some(ProjectRole::Editor)
+        );
+        // The higher of the direct and the team role wins
+        assert_eq!(
+            resolve_role(&members, "users:sam", &["teams:migration".to_string()]),
+            Some(ProjectRole::Editor)
+        );
+    }
+
+    #[test]
+    fn test_least_redacted_audience_wins() {
+        let mut members = vec![
+            member(PrincipalType::User, "users:sam", ProjectRole::Viewer),
+            member(PrincipalType::Team, "teams:vendors", ProjectRole::Viewer),
+        ];
+        members[0].audience = DocumentAudience::Customer;
+        members[1].audience = DocumentAudience::Vendor;
+        let vendors = vec!["teams:vendors".to_string()];
+
+        assert_eq!(resolve_audience(&members, "users:sam", &[]), DocumentAudience::Customer);
+        assert_eq!(resolve_audience(&members, "users:sam", &vendors), DocumentAudience::Customer);
+        assert_eq!(resolve_audience(&members, "users:kim", &vendors), DocumentAudience::Vendor);
+        assert_eq!(resolve_audience(&members, "users:kim", &[]), DocumentAudience::Vendor);
+    }
+
+    #[test]
+    fn test_who_can_respond_to_an_invitation() {
+        let sam = user("users:sam", "Sam@Contoso.com");
+        let lead_of = vec!["teams:migration".to_string()];
+        assert!(can_respond(&Invitee::User { id: "users:sam".to_string() }, &sam, &[]));
+        assert!(!can_respond(&Invitee::User { id: "users:kim".to_string() }, &sam, &[]));
+        assert!(can_respond(&Invitee::Email { address: "sam@contoso.com".to_string() }, &sam, &[]));
+        assert!(can_respond(&Invitee::Team { id: "teams:migration".to_string() }, &sam, &lead_of));
+        assert!(!can_respond(&Invitee::Team { id: "teams:migration".to_string() }, &sam, &[]));
+    }
+
+    #[test]
+    fn test_invitee_ids_are_normalized() {
+        assert_eq!(
+            normalize_invitee(Invitee::User { id: "sam".to_string() }).unwrap(),
+            Invitee::User { id: "users:sam".to_string() }
+        );
+        assert_eq!(
+            normalize_invitee(Invitee::Email { address: " Sam@Contoso.com ".to_string() }).unwrap(),
+            Invitee::Email { address: "sam@contoso.com".to_string() }
+        );
+        assert!(normalize_invitee(Invitee::Email { address: "sam".to_string() }).is_err());
+    }
+
+    #[test]
+    fn test_project_key_strips_table() {
+        assert_eq!(project_key("migration_wizard_project:abc"), "abc");
+        assert_eq!(project_key("abc"), "abc");
+    }
+
+    #[test]
+    fn test_find_member_by_raw_or_full_id() {
+        let members = vec![member(PrincipalType::User, "m1", ProjectRole::Owner)];
+        assert!(find_member(&members, "m1").is_ok());
+        assert!(find_member(&members, "project_membership:m1").is_ok());
+        assert!(matches!(find_member(&members, "m2"), Err(ProjectAccessError::MemberNotFound)));
+    }
+}
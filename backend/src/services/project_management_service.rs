@@ -4,6 +4,7 @@ use anyhow::{Context, Result};
 use chrono::Utc;
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
+use crate::database::TracedQuery;
 
 pub struct ProjectManagementService {
     db: Database,
@@ -104,6 +105,7 @@ impl ProjectManagementService {
         let projects: Vec<serde_json::Value> = self
             .db
             .query(query)
+            .traced("project_management_service.list_projects")
             .await
             .context("Failed to list projects")?
             .take(0)
@@ -217,6 +219,7 @@ impl ProjectManagementService {
         let active_workflows: Vec<ProjectWorkflow> = self.db
             .query("SELECT * FROM project_workflow WHERE project_id = $project_id AND status != 'completed'")
             .bind(("project_id", Thing::from(("project", project_id))))
+            .traced("project_management_service.delete_project")
             .await?
             .take(0)?;
 
@@ -300,6 +303,7 @@ impl ProjectManagementService {
                 (SELECT COUNT() FROM workflow_activity WHERE workflow_id = $parent.id AND status = 'completed') AS completed_activities
                 FROM project_workflow WHERE project_id = $project_id ORDER BY priority ASC, created_at ASC")
             .bind(("project_id", project_thing))
+            .traced("project_management_service.list_project_workflows")
             .await?
             .take(0)?;
 
@@ -381,6 +385,7 @@ impl ProjectManagementService {
         let hardware: Vec<HardwarePool> = self
             .db
             .query(query)
+            .traced("project_management_service.list_available_hardware")
             .await
             .context("Failed to list hardware")?
             .take(0)
@@ -490,6 +495,7 @@ impl ProjectManagementService {
                 .query("UPDATE hardware_allocation SET allocation_end = $now WHERE server_id = $server_id AND allocation_end IS NONE")
                 .bind(("server_id", server_thing))
                 .bind(("now", Utc::now()))
+                .traced("project_management_service.release_hardware")
                 .await?;
         }
 
@@ -499,6 +505,7 @@ impl ProjectManagementService {
     pub async fn hardware_pool_status(&self) -> Result<serde_json::Value> {
         let status: Vec<serde_json::Value> = self.db
             .query("SELECT availability_status, COUNT() AS count FROM hardware_pool GROUP BY availability_status")
+            .traced("project_management_service.hardware_pool_status")
             .await?
             .take(0)?;
 
@@ -530,6 +537,7 @@ impl ProjectManagementService {
                 (SELECT COUNT() FROM project_workflow WHERE project_id = $project_id AND status = 'completed') AS completed_workflows
                 FROM project_workflow WHERE project_id = $project_id")
             .bind(("project_id", project_thing))
+            .traced("project_management_service.calculate_project_progress")
             .await?
             .take(0)?;
 
@@ -560,6 +568,7 @@ impl ProjectManagementService {
         let workflows: Vec<ProjectWorkflow> = self.db
             .query("SELECT * FROM project_workflow WHERE project_id = $project_id ORDER BY priority ASC, start_date ASC")
             .bind(("project_id", project_thing))
+            .traced("project_management_service.get_project_timeline")
             .await?
             .take(0)?;
 
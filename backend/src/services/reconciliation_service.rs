@@ -0,0 +1,541 @@
+//! Post-Migration Reconciliation Service
+//!
+//! Compares a project's placement plan with the VMs found on the target
+//! platform after cutover. The target state comes from VMs synced from
+//! Nutanix Prism Central, from an inventory sent with the request (e.g. a
+//! Hyper-V export), or from an uploaded workbook in the RVTools vInfo layout.
+//! The report lists planned VMs that are missing, VMs on another cluster,
+//! CPU/RAM drift against the placement, and VMs on the wrong network.
+
+use calamine::{Reader, Xlsx};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::automation_export::{AutomationPlan, PlanVm};
+use crate::models::project_models::HypervisorType;
+use crate::models::reconciliation::*;
+use crate::services::automation_export::{AutomationExportError, AutomationExportService};
+use crate::utils::cidr::Ipv4Cidr;
+use core_engine::units::Bytes;
+
+/// vInfo columns holding the networks of a VM's adapters
+const NETWORK_COLUMNS: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum ReconciliationError {
+    #[error("Project not found")]
+    ProjectNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Failed to load the placement plan: {0}")]
+    Plan(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for ReconciliationError {
+    fn from(e: surrealdb::Error) -> Self {
+        ReconciliationError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<AutomationExportError> for ReconciliationError {
+    fn from(e: AutomationExportError) -> Self {
+        match e {
+            AutomationExportError::ProjectNotFound => ReconciliationError::ProjectNotFound,
+            AutomationExportError::DatabaseError(message) => ReconciliationError::DatabaseError(message),
+            other => ReconciliationError::Plan(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NutanixVmRow {
+    name: String,
+    #[serde(default)]
+    cluster: Option<String>,
+    #[serde(default)]
+    vcpu_count: Option<i32>,
+    #[serde(default)]
+    memory_capacity_bytes: Option<f64>,
+    #[serde(default)]
+    ip_addresses: Vec<String>,
+    #[serde(default)]
+    power_state: Option<String>,
+}
+
+pub struct ReconciliationService {
+    db: Arc<Database>,
+}
+
+impl ReconciliationService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Reconcile a project against synced Nutanix VMs or the request's inventory
+    pub async fn reconcile(
+        &self,
+        project_id: &str,
+        request: ReconciliationRequest,
+    ) -> Result<ReconciliationReport, ReconciliationError> {
+        let targets = match request.source {
+            TargetInventorySource::Nutanix => self.nutanix_vms(request.connection_id.as_deref()).await?,
+            TargetInventorySource::Inventory => request.vms,
+            TargetInventorySource::Workbook => {
+                return Err(ReconciliationError::Validation(
+                    "Upload workbooks to the /upload endpoint".to_string(),
+                ))
+            }
+        };
+        self.reconcile_with(project_id, request.source, request.wave_id.as_deref(), &targets)
+            .await
+    }
+
+    /// Reconcile a project against an uploaded vInfo workbook or CSV
+    pub async fn reconcile_workbook(
+        &self,
+        project_id: &str,
+        filename: &str,
+        bytes: &[u8],
+        wave_id: Option<&str>,
+    ) -> Result<ReconciliationReport, ReconciliationError> {
+        let targets = parse_target_inventory(filename, bytes)?;
+        self.reconcile_with(project_id, TargetInventorySource::Workbook, wave_id, &targets)
+            .await
+    }
+
+    async fn reconcile_with(
+        &self,
+        project_id: &str,
+        source: TargetInventorySource,
+        wave_id: Option<&str>,
+        targets: &[TargetVm],
+    ) -> Result<ReconciliationReport, ReconciliationError> {
+        let plan = AutomationExportService::new(self.db.clone())
+            .load_plan(project_id, HypervisorType::HyperV)
+            .await?;
+        let wave_id = wave_id.map(|w| w.strip_prefix("migration_wave:").unwrap_or(w));
+        if let Some(wave) = wave_id {
+            if !plan.waves.iter().any(|w| w.key == wave) {
+                return Err(ReconciliationError::Validation(format!(
+                    "Wave '{}' is not part of this project",
+                    wave
+                )));
+            }
+        }
+        Ok(reconcile(&plan, source, wave_id, targets))
+    }
+
+    /// Active VMs synced from Prism Central
+    async fn nutanix_vms(&self, connection_id: Option<&str>) -> Result<Vec<TargetVm>, ReconciliationError> {
+        let mut sql = "SELECT name, cluster.name AS cluster, vcpu_count, memory_capacity_bytes, ip_addresses, \
+                       power_state FROM nutanix_vm WHERE status = 'active'"
+            .to_string();
+        if connection_id.is_some() {
+            sql.push_str(" AND integration_connection = $connection");
+        }
+        let mut query = self.db.query(sql);
+        if let Some(connection) = connection_id {
+            let connection = connection.strip_prefix("integration_connections:").unwrap_or(connection);
+            query = query.bind(("connection", Thing::from(("integration_connections", connection))));
+        }
+        let rows: Vec<NutanixVmRow> = query
+            .traced("reconciliation_service.nutanix_vms")
+            .await?
+            .take(0)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TargetVm {
+                name: row.name,
+                cluster: row.cluster,
+                cpus: row.vcpu_count,
+                memory_mb: row
+                    .memory_capacity_bytes
+                    .map(|bytes| Bytes::new(bytes as u64).as_mib().round() as i32),
+                ip_addresses: row.ip_addresses,
+                networks: Vec::new(),
+                power_state: row.power_state,
+            })
+            .collect())
+    }
+}
+
+/// Compare the placed VMs of a plan (or of one of its waves) with the VMs
+/// found on the target. VMs match by name, ignoring case and a domain suffix.
+pub fn reconcile(
+    plan: &AutomationPlan,
+    source: TargetInventorySource,
+    wave_id: Option<&str>,
+    targets: &[TargetVm],
+) -> ReconciliationReport {
+    let mut by_name: HashMap<String, &TargetVm> = HashMap::new();
+    for target in targets {
+        by_name.entry(target.name.to_lowercase()).or_insert(target);
+    }
+    for target in targets {
+        by_name.entry(short_name(&target.name)).or_insert(target);
+    }
+    let cluster_names: HashMap<&str, &str> = plan
+        .clusters
+        .iter()
+        .map(|c| (c.key.as_str(), c.name.as_str()))
+        .collect();
+
+    let planned: Vec<&PlanVm> = plan
+        .vms
+        .iter()
+        .filter(|vm| wave_id.is_none() || vm.wave.as_deref() == wave_id)
+        .collect();
+
+    let mut report = ReconciliationReport {
+        project_id: plan.project_id.clone(),
+        wave_id: wave_id.map(str::to_string),
+        source,
+        planned_vms: planned.len(),
+        found_vms: 0,
+        missing_vms: Vec::new(),
+        wrong_cluster: Vec::new(),
+        spec_drift: Vec::new(),
+        network_mismatches: Vec::new(),
+        unverified_networks: Vec::new(),
+        passed: true,
+        generated_at: Utc::now(),
+    };
+
+    for vm in planned {
+        let planned_cluster = cluster_names.get(vm.cluster.as_str()).copied().unwrap_or(&vm.cluster);
+        let found = by_name
+            .get(&vm.name.to_lowercase())
+            .or_else(|| by_name.get(&short_name(&vm.name)));
+        let Some(target) = found else {
+            report.missing_vms.push(MissingVm {
+                vm_id: vm.key.clone(),
+                vm_name: vm.name.clone(),
+                planned_cluster: planned_cluster.to_string(),
+            });
+            continue;
+        };
+        report.found_vms += 1;
+
+        if let Some(actual) = &target.cluster {
+            if !actual.eq_ignore_ascii_case(planned_cluster) {
+                report.wrong_cluster.push(ClusterMismatch {
+                    vm_id: vm.key.clone(),
+                    vm_name: vm.name.clone(),
+                    planned_cluster: planned_cluster.to_string(),
+                    actual_cluster: actual.clone(),
+                });
+            }
+        }
+
+        for (field, planned, actual) in [("cpus", vm.cpus, target.cpus), ("memory_mb", vm.memory_mb, target.memory_mb)] {
+            if let Some(actual) = actual.filter(|&a| a != planned) {
+                report.spec_drift.push(SpecDrift {
+                    vm_id: vm.key.clone(),
+                    vm_name: vm.name.clone(),
+                    field: field.to_string(),
+                    planned,
+                    actual,
+                });
+            }
+        }
+
+        match check_network(vm, target) {
+            NetworkCheck::Matches => {}
+            NetworkCheck::Unverified => report.unverified_networks.push(vm.name.clone()),
+            NetworkCheck::Mismatch(message) => report.network_mismatches.push(NetworkMismatch {
+                vm_id: vm.key.clone(),
+                vm_name: vm.name.clone(),
+                planned_network: vm.network.clone(),
+                planned_subnet: planned_subnet(vm).map(|s| s.to_string()),
+                actual_ip_addresses: target.ip_addresses.clone(),
+                actual_networks: target.networks.clone(),
+                message,
+            }),
+        }
+    }
+
+    report.passed = report.missing_vms.is_empty()
+        && report.wrong_cluster.is_empty()
+        && report.spec_drift.is_empty()
+        && report.network_mismatches.is_empty();
+    report
+}
+
+enum NetworkCheck {
+    Matches,
+    Unverified,
+    Mismatch(String),
+}
+
+/// A VM is on the right network when one of its IPv4 addresses lies in the
+/// planned subnet. Without an address to go by, the network names are
+/// compared instead.
+fn check_network(vm: &PlanVm, target: &TargetVm) -> NetworkCheck {
+    let addresses: Vec<Ipv4Addr> = target
+        .ip_addresses
+        .iter()
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+
+    if let (Some(subnet), false) = (planned_subnet(vm), addresses.is_empty()) {
+        return if addresses.iter().any(|ip| subnet.contains(*ip)) {
+            NetworkCheck::Matches
+        } else {
+            NetworkCheck::Mismatch(format!(
+                "Has {} but none in the planned subnet {}",
+                target.ip_addresses.join(", "),
+                subnet
+            ))
+        };
+    }
+    match (&vm.network, target.networks.is_empty()) {
+        (Some(planned), false) => {
+            if target.networks.iter().any(|n| n.eq_ignore_ascii_case(planned)) {
+                NetworkCheck::Matches
+            } else {
+                NetworkCheck::Mismatch(format!(
+                    "Connected to {} instead of the planned network {}",
+                    target.networks.join(", "),
+                    planned
+                ))
+            }
+        }
+        _ => NetworkCheck::Unverified,
+    }
+}
+
+/// The destination subnet of the VM's planned address
+fn planned_subnet(vm: &PlanVm) -> Option<Ipv4Cidr> {
+    let ip: Ipv4Addr = vm.new_ip.as_deref()?.parse().ok()?;
+    Ipv4Cidr::new(ip, vm.prefix_length?)
+}
+
+fn short_name(name: &str) -> String {
+    name.split('.').next().unwrap_or(name).to_lowercase()
+}
+
+// ============================================================================
+// TARGET INVENTORY FILES
+// ============================================================================
+
+/// Parse a target inventory in the RVTools vInfo layout: an `.xlsx` workbook
+/// with a `tabvInfo` or `vInfo` sheet, or a `.csv` file with the same columns
+pub fn parse_target_inventory(filename: &str, bytes: &[u8]) -> Result<Vec<TargetVm>, ReconciliationError> {
+    let (headers, rows) = if filename.to_lowercase().ends_with(".csv") {
+        read_csv(bytes)?
+    } else {
+        read_vinfo_sheet(bytes)?
+    };
+
+    let column = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let vm_column = column("VM").ok_or_else(|| {
+        ReconciliationError::Validation("The inventory has no 'VM' column".to_string())
+    })?;
+    let cluster = column("Cluster");
+    let cpus = column("CPUs");
+    let memory = column("Memory");
+    let ip = column("Primary IP Address");
+    let power = column("Powerstate");
+    let networks: Vec<usize> = (1..=NETWORK_COLUMNS)
+        .filter_map(|n| column(&format!("Network #{}", n)))
+        .collect();
+
+    let cell = |row: &[String], index: Option<usize>| {
+        index
+            .and_then(|i| row.get(i))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let number = |row: &[String], index: Option<usize>| {
+        cell(row, index)
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|v| v.round() as i32)
+    };
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let name = cell(row, Some(vm_column))?;
+            Some(TargetVm {
+                name,
+                cluster: cell(row, cluster),
+                cpus: number(row, cpus),
+                memory_mb: number(row, memory),
+                ip_addresses: cell(row, ip)
+                    .map(|v| v.split([',', ';']).map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+                    .unwrap_or_default(),
+                networks: networks.iter().filter_map(|&i| cell(row, Some(i))).collect(),
+                power_state: cell(row, power),
+            })
+        })
+        .collect())
+}
+
+type Table = (Vec<String>, Vec<Vec<String>>);
+
+fn read_vinfo_sheet(bytes: &[u8]) -> Result<Table, ReconciliationError> {
+    let invalid = |e: String| ReconciliationError::Validation(format!("Failed to read the workbook: {}", e));
+    let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes.to_vec())).map_err(|e| invalid(e.to_string()))?;
+    let sheet = workbook
+        .sheet_names()
+        .iter()
+        .find(|s| s.eq_ignore_ascii_case("tabvInfo") || s.eq_ignore_ascii_case("vInfo"))
+        .cloned()
+        .ok_or_else(|| ReconciliationError::Validation("The workbook has no vInfo sheet".to_string()))?;
+    let range = workbook
+        .worksheet_range(&sheet)
+        .ok_or_else(|| invalid(format!("sheet '{}' not found", sheet)))?
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let mut rows = range
+        .rows()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect::<Vec<_>>());
+    let headers = rows.next().unwrap_or_default();
+    Ok((headers, rows.collect()))
+}
+
+fn read_csv(bytes: &[u8]) -> Result<Table, ReconciliationError> {
+    let invalid = |e: csv::Error| ReconciliationError::Validation(format!("Failed to read the CSV file: {}", e));
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(bytes);
+    let headers = reader
+        .headers()
+        .map_err(invalid)?
+        .iter()
+        .map(|h| h.trim_start_matches('\u{feff}').to_string())
+        .collect();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        rows.push(record.map_err(invalid)?.iter().map(str::to_string).collect());
+    }
+    Ok((headers, rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::automation_export::PlanCluster;
+
+    fn plan_vm(key: &str, wave: &str, network: Option<&str>, new_ip: Option<&str>) -> PlanVm {
+        PlanVm {
+            key: key.to_string(),
+            name: key.to_uppercase(),
+            cluster: "c1".to_string(),
+            wave: Some(wave.to_string()),
+            cpus: 4,
+            memory_mb: 8192,
+            storage_gb: 80.0,
+            os: None,
+            source_ip: None,
+            network: network.map(str::to_string),
+            vlan_id: None,
+            new_ip: new_ip.map(str::to_string),
+            prefix_length: new_ip.map(|_| 24),
+            gateway: None,
+            dns_servers: Vec::new(),
+        }
+    }
+
+    fn plan(vms: Vec<PlanVm>) -> AutomationPlan {
+        AutomationPlan {
+            project_id: "p1".to_string(),
+            project_name: "Project".to_string(),
+            clusters: vec![PlanCluster {
+                key: "c1".to_string(),
+                name: "HCI-01".to_string(),
+                hypervisor: HypervisorType::HyperV,
+                destination_cluster_id: None,
+                total_cores: 64,
+                memory_gb: 1024,
+                storage_tb: 50.0,
+            }],
+            networks: Vec::new(),
+            waves: Vec::new(),
+            vms,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn target(name: &str, cluster: &str, cpus: i32, ips: &[&str], networks: &[&str]) -> TargetVm {
+        TargetVm {
+            name: name.to_string(),
+            cluster: Some(cluster.to_string()),
+            cpus: Some(cpus),
+            memory_mb: Some(8192),
+            ip_addresses: ips.iter().map(|s| s.to_string()).collect(),
+            networks: networks.iter().map(|s| s.to_string()).collect(),
+            power_state: None,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_reports_missing_drifted_and_misplaced_vms() {
+        let plan = plan(vec![
+            plan_vm("web01", "w1", Some("Prod"), Some("10.20.0.11")),
+            plan_vm("web02", "w1", Some("Prod"), Some("10.20.0.12")),
+            plan_vm("db01", "w1", Some("Data"), None),
+            plan_vm("app01", "w1", Some("Prod"), Some("10.20.0.13")),
+            plan_vm("gone01", "w1", None, None),
+            plan_vm("later01", "w2", None, None),
+        ]);
+        let targets = [
+            // Address in the planned subnet, but not the planned one
+            target("web01.corp.example.com", "hci-01", 4, &["10.20.0.99"], &[]),
+            target("web02", "HCI-01", 8, &["10.30.0.12"], &[]),
+            target("db01", "HCI-02", 4, &[], &["Backup"]),
+            target("app01", "HCI-01", 4, &["fe80::1"], &[]),
+        ];
+
+        let report = reconcile(&plan, TargetInventorySource::Inventory, Some("w1"), &targets);
+
+        assert_eq!(report.planned_vms, 5);
+        assert_eq!(report.found_vms, 4);
+        assert_eq!(report.missing_vms.len(), 1);
+        assert_eq!(report.missing_vms[0].vm_name, "GONE01");
+        assert_eq!(report.missing_vms[0].planned_cluster, "HCI-01");
+        assert_eq!(report.wrong_cluster.len(), 1);
+        assert_eq!(report.wrong_cluster[0].actual_cluster, "HCI-02");
+        assert_eq!(report.spec_drift.len(), 1);
+        assert_eq!((report.spec_drift[0].field.as_str(), report.spec_drift[0].actual), ("cpus", 8));
+
+        let mismatched: Vec<&str> = report.network_mismatches.iter().map(|m| m.vm_name.as_str()).collect();
+        assert_eq!(mismatched, vec!["WEB02", "DB01"]);
+        assert_eq!(report.network_mismatches[0].planned_subnet.as_deref(), Some("10.20.0.0/24"));
+        assert!(report.network_mismatches[1].message.contains("planned network Data"));
+        assert_eq!(report.unverified_networks, vec!["APP01"]);
+        assert!(!report.passed);
+    }
+
+    #[test]
+    fn test_csv_inventory_uses_vinfo_columns() {
+        let csv = "\u{feff}VM,Powerstate,CPUs,Memory,Primary IP Address,Network #1,Network #2,Cluster\n\
+                   web01,poweredOn,4,8192.0,10.20.0.11,Prod,,HCI-01\n\
+                   ,poweredOff,2,4096,,,,HCI-01\n";
+        let vms = parse_target_inventory("target.CSV", csv.as_bytes()).unwrap();
+
+        assert_eq!(vms.len(), 1);
+        assert_eq!(vms[0].cpus, Some(4));
+        assert_eq!(vms[0].memory_mb, Some(8192));
+        assert_eq!(vms[0].ip_addresses, vec!["10.20.0.11"]);
+        assert_eq!(vms[0].networks, vec!["Prod"]);
+        assert_eq!(vms[0].cluster.as_deref(), Some("HCI-01"));
+
+        assert!(matches!(
+            parse_target_inventory("target.csv", b"Name,CPUs\nweb01,4\n"),
+            Err(ReconciliationError::Validation(_))
+        ));
+    }
+}
@@ -10,6 +10,7 @@ use std::process::Command;
 
 use crate::database::Database;
 use crate::models::project_models::*;
+use crate::services::data_variable_service::{upload_thing, DataVariableService};
 use crate::utils::{EnhancedRvToolsError, EnhancedRvToolsResult};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -713,7 +714,7 @@ impl ReportExportService {
     async fn get_report_data(&self, upload_id: &str, template_id: &str) -> EnhancedRvToolsResult<Value> {
         // In a real implementation, this would query the database for the report data
         // For now, return mock data structure
-        Ok(json!({
+        let mut report_data = json!({
             "variables": {
                 "total_vms": 150,
                 "total_hosts": 12,
@@ -729,7 +730,25 @@ impl ReportExportService {
                 "total_records": 1250,
                 "confidence_level": 0.87
             }
-        }))
+        });
+
+        // User-defined variables are computed from the upload itself, so a
+        // template section can list them like any built-in variable
+        let evaluated = DataVariableService::new(self.db.clone())
+            .evaluate_custom(&upload_thing(upload_id))
+            .await
+            .map_err(|e| EnhancedRvToolsError::DatabaseError {
+                operation: "evaluate".to_string(),
+                table: "data_variable_schema".to_string(),
+                error: e.to_string(),
+            })?;
+        for variable in evaluated {
+            if let Some(value) = variable.value {
+                report_data["variables"][variable.variable_name] = json!(value);
+            }
+        }
+
+        Ok(report_data)
     }
 
     /// Get report template from database
@@ -0,0 +1,975 @@
+// Archer - Report Scheduler Service
+// Runs report templates on cron schedules, writes the artifacts to disk and
+// distributes them to each schedule's email recipients and Teams channel
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tracing::{error, info, warn};
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::report_schedule::*;
+use crate::services::document_service::DocumentService;
+use crate::services::notification_service::{EmailAttachment, NotificationService};
+use crate::database::TracedQuery;
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum ReportScheduleError {
+    #[error("Report schedule not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Report generation failed: {0}")]
+    Generation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for ReportScheduleError {
+    fn from(e: surrealdb::Error) -> Self {
+        ReportScheduleError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// CRON HELPERS
+// ============================================================================
+
+/// Accept standard 5-field cron (minute precision) as well as the 6/7-field
+/// form with seconds used by tokio-cron-scheduler
+pub fn parse_cron(expr: &str) -> Result<cron::Schedule, ReportScheduleError> {
+    let normalized = match expr.split_whitespace().count() {
+        5 => format!("0 {}", expr.trim()),
+        6 | 7 => expr.trim().to_string(),
+        _ => {
+            return Err(ReportScheduleError::Validation(format!(
+                "cron expression '{}' must have 5, 6 or 7 fields",
+                expr
+            )))
+        }
+    };
+    cron::Schedule::from_str(&normalized)
+        .map_err(|e| ReportScheduleError::Validation(format!("invalid cron expression '{}': {}", expr, e)))
+}
+
+pub fn next_run_after(expr: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>, ReportScheduleError> {
+    parse_cron(expr)?
+        .after(&after)
+        .next()
+        .ok_or_else(|| ReportScheduleError::Validation(format!("cron expression '{}' never fires", expr)))
+}
+
+// ============================================================================
+// REPORT SCHEDULER SERVICE
+// ============================================================================
+
+pub struct ReportSchedulerService {
+    db: Arc<Database>,
+    notifier: NotificationService,
+    artifact_dir: PathBuf,
+    base_url: String,
+}
+
+impl ReportSchedulerService {
+    pub fn new(db: Arc<Database>) -> Self {
+        let artifact_dir = std::env::var("REPORT_ARTIFACT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| DocumentService::get_documents_base_path().join("reports"));
+        let base_url = std::env::var("BASE_URL").unwrap_or_else(|_| "http://localhost:3001".to_string());
+
+        Self {
+            db,
+            notifier: NotificationService::from_env(),
+            artifact_dir,
+            base_url,
+        }
+    }
+
+    // ========================================================================
+    // SCHEDULES
+    // ========================================================================
+
+    pub async fn list_schedules(
+        &self,
+        user: &AuthenticatedUser,
+    ) -> Result<Vec<ReportScheduleDefinition>, ReportScheduleError> {
+        let mut schedules: Vec<ReportScheduleDefinition> = self
+            .db
+            .query("SELECT * FROM report_schedules ORDER BY name ASC")
+            .traced("report_scheduler_service.list_schedules")
+            .await?
+            .take(0)?;
+        let tenant = user_tenant(user);
+        schedules.retain(|s| s.tenant_id == tenant);
+        Ok(schedules)
+    }
+
+    pub async fn get_schedule(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<ReportScheduleDefinition, ReportScheduleError> {
+        let schedule: Option<ReportScheduleDefinition> =
+            self.db.select(parse_thing("report_schedules", id)).await?;
+        let tenant = user_tenant(user);
+        schedule
+            .filter(|s| s.tenant_id == tenant)
+            .ok_or(ReportScheduleError::NotFound)
+    }
+
+    pub async fn create_schedule(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateReportScheduleRequest,
+    ) -> Result<ReportScheduleDefinition, ReportScheduleError> {
+        if request.name.trim().is_empty() {
+            return Err(ReportScheduleError::Validation("Schedule name is required".to_string()));
+        }
+        validate_distribution(&request.distribution)?;
+        validate_period(request.period_days)?;
+
+        let now = Utc::now();
+        let next_run_at = if request.enabled {
+            Some(next_run_after(&request.cron, now)?)
+        } else {
+            parse_cron(&request.cron)?;
+            None
+        };
+
+        let schedule = ReportScheduleDefinition {
+            id: None,
+            name: request.name.trim().to_string(),
+            template: request.template,
+            cron: request.cron.trim().to_string(),
+            format: request.format,
+            period_days: request.period_days,
+            distribution: request.distribution,
+            enabled: request.enabled,
+            next_run_at,
+            last_run_at: None,
+            last_status: None,
+            created_by: user.user_id.clone(),
+            created_at: now,
+            updated_at: now,
+            tenant_id: user_tenant(user),
+        };
+
+        let created: Vec<ReportScheduleDefinition> =
+            self.db.create("report_schedules").content(&schedule).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ReportScheduleError::DatabaseError("Failed to create report schedule".to_string()))
+    }
+
+    pub async fn update_schedule(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: UpdateReportScheduleRequest,
+    ) -> Result<ReportScheduleDefinition, ReportScheduleError> {
+        let mut schedule = self.get_schedule(user, id).await?;
+
+        if let Some(name) = request.name {
+            if name.trim().is_empty() {
+                return Err(ReportScheduleError::Validation("Schedule name is required".to_string()));
+            }
+            schedule.name = name.trim().to_string();
+        }
+        if let Some(cron) = request.cron {
+            schedule.cron = cron.trim().to_string();
+        }
+        if let Some(format) = request.format {
+            schedule.format = format;
+        }
+        if let Some(period_days) = request.period_days {
+            validate_period(period_days)?;
+            schedule.period_days = period_days;
+        }
+        if let Some(distribution) = request.distribution {
+            validate_distribution(&distribution)?;
+            schedule.distribution = distribution;
+        }
+        if let Some(enabled) = request.enabled {
+            schedule.enabled = enabled;
+        }
+
+        let now = Utc::now();
+        schedule.next_run_at = if schedule.enabled {
+            Some(next_run_after(&schedule.cron, now)?)
+        } else {
+            parse_cron(&schedule.cron)?;
+            None
+        };
+        schedule.updated_at = now;
+
+        let thing = schedule.id.clone().ok_or(ReportScheduleError::NotFound)?;
+        let updated: Option<ReportScheduleDefinition> = self.db.update(thing).content(&schedule).await?;
+        updated.ok_or(ReportScheduleError::NotFound)
+    }
+
+    /// Delete a schedule; its artifacts are kept for history
+    pub async fn delete_schedule(&self, user: &AuthenticatedUser, id: &str) -> Result<(), ReportScheduleError> {
+        let schedule = self.get_schedule(user, id).await?;
+        let thing = schedule.id.ok_or(ReportScheduleError::NotFound)?;
+        let _: Option<ReportScheduleDefinition> = self.db.delete(thing).await?;
+        Ok(())
+    }
+
+    /// Generate and distribute a schedule's report immediately, outside its cron
+    pub async fn run_now(&self, user: &AuthenticatedUser, id: &str) -> Result<ReportArtifact, ReportScheduleError> {
+        let schedule = self.get_schedule(user, id).await?;
+        let artifact = self.execute(&schedule).await?;
+        self.record_run(&schedule, &artifact, schedule.next_run_at).await?;
+        Ok(artifact)
+    }
+
+    // ========================================================================
+    // ARTIFACTS
+    // ========================================================================
+
+    pub async fn list_artifacts(
+        &self,
+        user: &AuthenticatedUser,
+        schedule_id: Option<&str>,
+    ) -> Result<Vec<ReportArtifact>, ReportScheduleError> {
+        let mut artifacts: Vec<ReportArtifact> = self
+            .db
+            .query("SELECT * FROM report_artifacts ORDER BY generated_at DESC LIMIT 500")
+            .traced("report_scheduler_service.list_artifacts")
+            .await?
+            .take(0)?;
+        let tenant = user_tenant(user);
+        artifacts.retain(|a| a.tenant_id == tenant);
+        if let Some(schedule_id) = schedule_id {
+            let schedule = parse_thing("report_schedules", schedule_id);
+            artifacts.retain(|a| a.schedule_id.as_ref() == Some(&schedule));
+        }
+        Ok(artifacts)
+    }
+
+    pub async fn get_artifact(&self, user: &AuthenticatedUser, id: &str) -> Result<ReportArtifact, ReportScheduleError> {
+        let artifact: Option<ReportArtifact> = self.db.select(parse_thing("report_artifacts", id)).await?;
+        let tenant = user_tenant(user);
+        artifact
+            .filter(|a| a.tenant_id == tenant)
+            .ok_or(ReportScheduleError::NotFound)
+    }
+
+    /// Artifact metadata plus the file contents for download
+    pub async fn read_artifact(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<(ReportArtifact, Vec<u8>), ReportScheduleError> {
+        let artifact = self.get_artifact(user, id).await?;
+        if artifact.file_path.is_empty() {
+            return Err(ReportScheduleError::NotFound);
+        }
+        let bytes = tokio::fs::read(&artifact.file_path)
+            .await
+            .map_err(|_| ReportScheduleError::NotFound)?;
+        Ok((artifact, bytes))
+    }
+
+    // ========================================================================
+    // EXECUTION
+    // ========================================================================
+
+    /// Run every enabled schedule whose next run time has passed
+    pub async fn run_due(&self) -> Result<usize, ReportScheduleError> {
+        let now = Utc::now();
+        let due: Vec<ReportScheduleDefinition> = self
+            .db
+            .query(
+                "SELECT * FROM report_schedules WHERE enabled = true AND next_run_at != NONE AND next_run_at <= $now",
+            )
+            .bind(("now", now))
+            .traced("report_scheduler_service.run_due")
+            .await?
+            .take(0)?;
+
+        for schedule in &due {
+            let next_run_at = match next_run_after(&schedule.cron, now) {
+                Ok(next) => Some(next),
+                Err(e) => {
+                    warn!("Disabling report schedule '{}': {}", schedule.name, e);
+                    None
+                }
+            };
+            // Claim the slot before generating so a slow run isn't picked up twice
+            if let Some(id) = &schedule.id {
+                self.db
+                    .query("UPDATE $id SET next_run_at = $next, enabled = $enabled")
+                    .bind(("id", id.clone()))
+                    .bind(("next", next_run_at))
+                    .bind(("enabled", next_run_at.is_some()))
+                    .traced("report_scheduler_service.run_due")
+                    .await?;
+            }
+
+            match self.execute(schedule).await {
+                Ok(artifact) => self.record_run(schedule, &artifact, next_run_at).await?,
+                Err(e) => error!("Scheduled report '{}' failed: {}", schedule.name, e),
+            }
+        }
+        Ok(due.len())
+    }
+
+    /// Generate, store and distribute one report; generation failures are
+    /// recorded as a FAILED artifact rather than returned as errors
+    async fn execute(&self, schedule: &ReportScheduleDefinition) -> Result<ReportArtifact, ReportScheduleError> {
+        let generated_at = Utc::now();
+        let filename = artifact_filename(&schedule.name, schedule.format, generated_at);
+
+        let mut artifact = ReportArtifact {
+            id: None,
+            schedule_id: schedule.id.clone(),
+            schedule_name: schedule.name.clone(),
+            template: schedule.template,
+            format: schedule.format,
+            filename: filename.clone(),
+            file_path: String::new(),
+            size_bytes: 0,
+            row_count: 0,
+            status: ArtifactStatus::Generated,
+            error: None,
+            deliveries: Vec::new(),
+            generated_at,
+            tenant_id: schedule.tenant_id.clone(),
+        };
+
+        let generated = async {
+            let table = self
+                .generate(schedule.template, schedule.period_days, schedule.tenant_id.as_ref())
+                .await?;
+            let bytes = render(&table, schedule.format)?;
+            let path = self.write_file(&filename, &bytes).await?;
+            Ok::<_, ReportScheduleError>((table, bytes, path))
+        }
+        .await;
+
+        let (table, bytes) = match generated {
+            Ok((table, bytes, path)) => {
+                artifact.file_path = path.to_string_lossy().to_string();
+                artifact.size_bytes = bytes.len() as u64;
+                artifact.row_count = table.rows.len();
+                (table, bytes)
+            }
+            Err(e) => {
+                warn!("Report '{}' generation failed: {}", schedule.name, e);
+                artifact.status = ArtifactStatus::Failed;
+                artifact.error = Some(e.to_string());
+                return self.save_artifact(artifact).await;
+            }
+        };
+
+        // Persist first so the Teams card can link to the artifact
+        let mut artifact = self.save_artifact(artifact).await?;
+        let artifact_key = artifact.id.as_ref().map(|id| id.id.to_string()).unwrap_or_default();
+        let link = format!("{}/api/v1/report-schedules/artifacts/{}/download", self.base_url, artifact_key);
+        let subject = format!("{}: {}", schedule.template.title(), schedule.name);
+        let body = notification_body(schedule, &table, &link);
+
+        let distribution = &schedule.distribution;
+        if !distribution.email_recipients.is_empty() {
+            let attachment = EmailAttachment {
+                filename: &artifact.filename,
+                content_type: schedule.format.content_type(),
+                bytes,
+            };
+            let results = self
+                .notifier
+                .send_email(&distribution.email_recipients, &subject, &body, Some(attachment))
+                .await;
+            artifact.deliveries.extend(results);
+        }
+        if let Some(webhook) = &distribution.teams_webhook_url {
+            let result = self.notifier.post_teams_link(webhook, &subject, &body, &link).await;
+            artifact.deliveries.push(result);
+        }
+        artifact.status = delivery_status(&artifact.deliveries);
+
+        let thing = artifact.id.clone().ok_or(ReportScheduleError::NotFound)?;
+        let updated: Option<ReportArtifact> = self.db.update(thing).content(&artifact).await?;
+        updated.ok_or(ReportScheduleError::NotFound)
+    }
+
+    async fn save_artifact(&self, artifact: ReportArtifact) -> Result<ReportArtifact, ReportScheduleError> {
+        let created: Vec<ReportArtifact> = self.db.create("report_artifacts").content(&artifact).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| ReportScheduleError::DatabaseError("Failed to store report artifact".to_string()))
+    }
+
+    async fn record_run(
+        &self,
+        schedule: &ReportScheduleDefinition,
+        artifact: &ReportArtifact,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ReportScheduleError> {
+        if let Some(id) = &schedule.id {
+            self.db
+                .query("UPDATE $id SET last_run_at = $ran, last_status = $status, next_run_at = $next")
+                .bind(("id", id.clone()))
+                .bind(("ran", artifact.generated_at))
+                .bind(("status", artifact.status))
+                .bind(("next", next_run_at))
+                .traced("report_scheduler_service.record_run")
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn write_file(&self, filename: &str, bytes: &[u8]) -> Result<PathBuf, ReportScheduleError> {
+        tokio::fs::create_dir_all(&self.artifact_dir)
+            .await
+            .map_err(|e| ReportScheduleError::Generation(format!("cannot create artifact directory: {}", e)))?;
+        let path = self
+            .artifact_dir
+            .join(format!("{}-{}", uuid::Uuid::new_v4(), filename));
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|e| ReportScheduleError::Generation(format!("cannot write artifact: {}", e)))?;
+        Ok(path)
+    }
+
+    // ========================================================================
+    // TEMPLATES
+    // ========================================================================
+
+    async fn generate(
+        &self,
+        template: ReportTemplate,
+        period_days: u32,
+        tenant: Option<&Thing>,
+    ) -> Result<ReportTable, ReportScheduleError> {
+        match template {
+            ReportTemplate::CapacitySummary => {
+                let rows: Vec<CapacityRow> = self
+                    .db
+                    .query("SELECT datacenter, availability_status, cpu_cores_total, memory_gb, storage_capacity_gb FROM hardware_pool")
+                    .traced("report_scheduler_service.generate")
+                    .await?
+                    .take(0)?;
+                Ok(capacity_table(&rows))
+            }
+            ReportTemplate::SlaCompliance => {
+                let since = Utc::now() - Duration::days(i64::from(period_days));
+                let mut query = self
+                    .db
+                    .query(format!(
+                        "SELECT priority, response_sla_met, resolution_sla_met, sla_breach_at, resolved_at FROM ticket WHERE created_at >= $since{}",
+                        if tenant.is_some() { " AND tenant_id = $tenant" } else { "" }
+                    ))
+                    .bind(("since", since));
+                if let Some(tenant) = tenant {
+                    query = query.bind(("tenant", tenant.clone()));
+                }
+                let rows: Vec<SlaRow> = query.await?.take(0)?;
+                Ok(sla_compliance_table(&rows, period_days, Utc::now()))
+            }
+            ReportTemplate::LicensePosition => {
+                let mut query = self.db.query(format!(
+                    "SELECT name, vendor, attributes FROM configuration_items WHERE ci_class = 'SOFTWARE'{}",
+                    if tenant.is_some() { " AND tenant_id = $tenant" } else { "" }
+                ));
+                if let Some(tenant) = tenant {
+                    query = query.bind(("tenant", tenant.clone()));
+                }
+                let rows: Vec<SoftwareRow> = query.await?.take(0)?;
+                Ok(license_position_table(&rows))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// TEMPLATE AGGREGATION
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct CapacityRow {
+    datacenter: Option<String>,
+    availability_status: Option<String>,
+    cpu_cores_total: Option<i64>,
+    memory_gb: Option<i64>,
+    storage_capacity_gb: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlaRow {
+    priority: String,
+    response_sla_met: Option<bool>,
+    resolution_sla_met: Option<bool>,
+    sla_breach_at: Option<DateTime<Utc>>,
+    resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SoftwareRow {
+    name: String,
+    vendor: Option<String>,
+    #[serde(default)]
+    attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+fn capacity_table(rows: &[CapacityRow]) -> ReportTable {
+    // (datacenter, status) → [servers, cores, memory, storage]
+    let mut groups: BTreeMap<(String, String), [i64; 4]> = BTreeMap::new();
+    for row in rows {
+        let key = (
+            row.datacenter.clone().unwrap_or_else(|| "Unassigned".to_string()),
+            row.availability_status.clone().unwrap_or_else(|| "unknown".to_string()),
+        );
+        let totals = groups.entry(key).or_default();
+        totals[0] += 1;
+        totals[1] += row.cpu_cores_total.unwrap_or(0);
+        totals[2] += row.memory_gb.unwrap_or(0);
+        totals[3] += row.storage_capacity_gb.unwrap_or(0);
+    }
+
+    let available = |status: &str| status.eq_ignore_ascii_case("available");
+    let mut summary = [0i64; 4];
+    let mut available_servers = 0;
+    let rows = groups
+        .into_iter()
+        .map(|((datacenter, status), totals)| {
+            for (sum, value) in summary.iter_mut().zip(totals) {
+                *sum += value;
+            }
+            if available(&status) {
+                available_servers += totals[0];
+            }
+            let mut row = vec![datacenter, status];
+            row.extend(totals.iter().map(i64::to_string));
+            row
+        })
+        .collect();
+
+    ReportTable {
+        columns: ["datacenter", "status", "servers", "cpu_cores", "memory_gb", "storage_gb"]
+            .map(String::from)
+            .to_vec(),
+        rows,
+        summary: serde_json::json!({
+            "servers": summary[0],
+            "available_servers": available_servers,
+            "cpu_cores": summary[1],
+            "memory_gb": summary[2],
+            "storage_gb": summary[3],
+        }),
+    }
+}
+
+fn sla_compliance_table(rows: &[SlaRow], period_days: u32, now: DateTime<Utc>) -> ReportTable {
+    #[derive(Default)]
+    struct Counts {
+        tickets: i64,
+        response_measured: i64,
+        response_met: i64,
+        resolution_measured: i64,
+        resolution_met: i64,
+        breached: i64,
+    }
+
+    let mut groups: BTreeMap<String, Counts> = BTreeMap::new();
+    for row in rows {
+        let counts = groups.entry(row.priority.clone()).or_default();
+        counts.tickets += 1;
+        if let Some(met) = row.response_sla_met {
+            counts.response_measured += 1;
+            counts.response_met += i64::from(met);
+        }
+        if let Some(met) = row.resolution_sla_met {
+            counts.resolution_measured += 1;
+            counts.resolution_met += i64::from(met);
+        }
+        // Open tickets past their breach time count even before resolution is recorded
+        let open_and_overdue = row.resolved_at.is_none() && row.sla_breach_at.is_some_and(|at| at < now);
+        if row.resolution_sla_met == Some(false) || open_and_overdue {
+            counts.breached += 1;
+        }
+    }
+
+    let pct = |met: i64, measured: i64| {
+        if measured == 0 {
+            "n/a".to_string()
+        } else {
+            format!("{:.1}", met as f64 * 100.0 / measured as f64)
+        }
+    };
+
+    let mut total = Counts::default();
+    let rows = groups
+        .into_iter()
+        .map(|(priority, c)| {
+            total.tickets += c.tickets;
+            total.response_measured += c.response_measured;
+            total.response_met += c.response_met;
+            total.resolution_measured += c.resolution_measured;
+            total.resolution_met += c.resolution_met;
+            total.breached += c.breached;
+            vec![
+                priority,
+                c.tickets.to_string(),
+                pct(c.response_met, c.response_measured),
+                pct(c.resolution_met, c.resolution_measured),
+                c.breached.to_string(),
+            ]
+        })
+        .collect();
+
+    ReportTable {
+        columns: ["priority", "tickets", "response_met_pct", "resolution_met_pct", "breached"]
+            .map(String::from)
+            .to_vec(),
+        rows,
+        summary: serde_json::json!({
+            "period_days": period_days,
+            "tickets": total.tickets,
+            "response_met_pct": pct(total.response_met, total.response_measured),
+            "resolution_met_pct": pct(total.resolution_met, total.resolution_measured),
+            "breached": total.breached,
+        }),
+    }
+}
+
+/// Software CIs are grouped by vendor and product name. Each CI consumes
+/// `attributes.license_in_use` seats (1 if absent) and may carry
+/// `attributes.license_entitlement` purchased seats.
+fn license_position_table(rows: &[SoftwareRow]) -> ReportTable {
+    let seats = |row: &SoftwareRow, key: &str| {
+        row.attributes.get(key).and_then(|v| {
+            v.as_f64()
+                .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+        })
+    };
+
+    // (vendor, product) → (installs, consumed, entitled)
+    let mut groups: BTreeMap<(String, String), (i64, f64, f64)> = BTreeMap::new();
+    for row in rows {
+        let key = (
+            row.vendor.clone().unwrap_or_else(|| "Unknown".to_string()),
+            row.name.clone(),
+        );
+        let entry = groups.entry(key).or_default();
+        entry.0 += 1;
+        entry.1 += seats(row, "license_in_use").unwrap_or(1.0);
+        entry.2 += seats(row, "license_entitlement").unwrap_or(0.0);
+    }
+
+    let mut shortfalls = 0;
+    let rows = groups
+        .into_iter()
+        .map(|((vendor, product), (installs, consumed, entitled))| {
+            let position = entitled - consumed;
+            let status = if entitled == 0.0 {
+                "UNLICENSED"
+            } else if position < 0.0 {
+                "SHORTFALL"
+            } else {
+                "COMPLIANT"
+            };
+            if status != "COMPLIANT" {
+                shortfalls += 1;
+            }
+            vec![
+                vendor,
+                product,
+                installs.to_string(),
+                consumed.to_string(),
+                entitled.to_string(),
+                position.to_string(),
+                status.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    ReportTable {
+        columns: ["vendor", "product", "installs", "consumed", "entitled", "position", "status"]
+            .map(String::from)
+            .to_vec(),
+        summary: serde_json::json!({
+            "products": rows.len(),
+            "non_compliant_products": shortfalls,
+        }),
+        rows,
+    }
+}
+
+// ============================================================================
+// RENDERING & DELIVERY HELPERS
+// ============================================================================
+
+fn render(table: &ReportTable, format: ArtifactFormat) -> Result<Vec<u8>, ReportScheduleError> {
+    match format {
+        ArtifactFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer
+                .write_record(&table.columns)
+                .map_err(|e| ReportScheduleError::Generation(e.to_string()))?;
+            for row in &table.rows {
+                writer
+                    .write_record(row)
+                    .map_err(|e| ReportScheduleError::Generation(e.to_string()))?;
+            }
+            writer
+                .into_inner()
+                .map_err(|e| ReportScheduleError::Generation(e.to_string()))
+        }
+        ArtifactFormat::Json => {
+            serde_json::to_vec_pretty(table).map_err(|e| ReportScheduleError::Generation(e.to_string()))
+        }
+    }
+}
+
+fn artifact_filename(name: &str, format: ArtifactFormat, at: DateTime<Utc>) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    format!(
+        "{}-{}.{}",
+        if slug.is_empty() { "report" } else { &slug },
+        at.format("%Y%m%d-%H%M"),
+        format.extension()
+    )
+}
+
+fn notification_body(schedule: &ReportScheduleDefinition, table: &ReportTable, link: &str) -> String {
+    let mut body = format!(
+        "{} report '{}' generated at {}.\n\n",
+        schedule.template.title(),
+        schedule.name,
+        Utc::now().format("%Y-%m-%d %H:%M UTC")
+    );
+    if let Some(summary) = table.summary.as_object() {
+        for (key, value) in summary {
+            let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+            body.push_str(&format!("- {}: {}\n", key.replace('_', " "), value));
+        }
+    }
+    body.push_str(&format!("\nDownload: {}\n", link));
+    body
+}
+
+fn delivery_status(deliveries: &[DeliveryResult]) -> ArtifactStatus {
+    if deliveries.iter().any(|d| d.status == DeliveryStatus::Failed) {
+        ArtifactStatus::PartiallyDelivered
+    } else if deliveries.iter().any(|d| d.status == DeliveryStatus::Sent) {
+        ArtifactStatus::Delivered
+    } else {
+        ArtifactStatus::Generated
+    }
+}
+
+fn validate_distribution(distribution: &ReportDistribution) -> Result<(), ReportScheduleError> {
+    if let Some(bad) = distribution
+        .email_recipients
+        .iter()
+        .find(|r| !r.contains('@') || r.trim() != r.as_str())
+    {
+        return Err(ReportScheduleError::Validation(format!("invalid email recipient '{}'", bad)));
+    }
+    if let Some(url) = &distribution.teams_webhook_url {
+        if !url.starts_with("https://") {
+            return Err(ReportScheduleError::Validation(
+                "teams_webhook_url must be an https URL".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_period(period_days: u32) -> Result<(), ReportScheduleError> {
+    if !(1..=366).contains(&period_days) {
+        return Err(ReportScheduleError::Validation(
+            "period_days must be between 1 and 366".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn user_tenant(user: &AuthenticatedUser) -> Option<Thing> {
+    user.tenant_id.as_deref().map(|t| Thing::from(("tenants", t)))
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+// ============================================================================
+// REPORT SCHEDULER
+// ============================================================================
+
+use tokio_cron_scheduler::{Job, JobScheduler};
+
+use crate::database::locks;
+
+/// Lease on the report run, renewed while rendering and delivery take longer
+const REPORT_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Background job that checks for due report schedules once a minute
+pub struct ReportScheduler {
+    db: Arc<Database>,
+}
+
+impl ReportScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow!("Failed to create scheduler: {}", e))?;
+
+        let db = Arc::clone(&self.db);
+        let service = Arc::new(ReportSchedulerService::new(Arc::clone(&self.db)));
+        let job = Job::new_async("0 * * * * *", move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                // Only one replica claims a given minute's due schedules
+                let run = locks::run_exclusive(&db, locks::REPORT_SCHEDULER_LOCK, REPORT_LOCK_TTL, service.run_due());
+                match run.await {
+                    Ok(None) | Ok(Some(Ok(0))) => {}
+                    Ok(Some(Ok(count))) => info!("📨 Ran {} scheduled report(s)", count),
+                    Ok(Some(Err(e))) => error!("❌ Scheduled report run failed: {}", e),
+                    Err(e) => error!("❌ Scheduled report lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow!("Failed to create report job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow!("Failed to add report job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow!("Failed to start scheduler: {}", e))?;
+
+        // Same as the tiering scheduler: keep the scheduler alive for the process lifetime
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_run_accepts_five_and_six_field_cron() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 4, 10, 15, 30).unwrap();
+        // Weekdays at 07:00
+        assert_eq!(
+            next_run_after("0 7 * * Mon-Fri", at).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 5, 7, 0, 0).unwrap()
+        );
+        assert_eq!(
+            next_run_after("0 0 7 * * *", at).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 5, 7, 0, 0).unwrap()
+        );
+        assert!(parse_cron("every day").is_err());
+        assert!(parse_cron("0 99 * * *").is_err());
+    }
+
+    #[test]
+    fn test_sla_compliance_counts_overdue_open_tickets() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap();
+        let row = |priority: &str, response, resolution, breach_hours: Option<i64>, resolved: bool| SlaRow {
+            priority: priority.to_string(),
+            response_sla_met: response,
+            resolution_sla_met: resolution,
+            sla_breach_at: breach_hours.map(|h| now + Duration::hours(h)),
+            resolved_at: resolved.then_some(now),
+        };
+        let rows = vec![
+            row("P1", Some(true), Some(true), Some(-5), true),
+            row("P1", Some(false), Some(false), Some(-1), true),
+            row("P1", Some(true), None, Some(-2), false),
+            row("P2", None, None, Some(4), false),
+        ];
+
+        let table = sla_compliance_table(&rows, 30, now);
+        assert_eq!(table.rows[0], vec!["P1", "3", "66.7", "50.0", "2"]);
+        assert_eq!(table.rows[1], vec!["P2", "1", "n/a", "n/a", "0"]);
+        assert_eq!(table.summary["breached"], 2);
+    }
+
+    #[test]
+    fn test_license_position_and_rendering() {
+        let row = |vendor: &str, name: &str, attrs: serde_json::Value| SoftwareRow {
+            name: name.to_string(),
+            vendor: Some(vendor.to_string()),
+            attributes: attrs.as_object().cloned().unwrap_or_default(),
+        };
+        let rows = vec![
+            row("Microsoft", "SQL Server", serde_json::json!({ "license_entitlement": 4, "license_in_use": 2 })),
+            row("Microsoft", "SQL Server", serde_json::json!({ "license_in_use": "4" })),
+            row("Oracle", "Database", serde_json::json!({})),
+        ];
+
+        let table = license_position_table(&rows);
+        assert_eq!(table.rows[0][5..], ["-2".to_string(), "SHORTFALL".to_string()]);
+        assert_eq!(table.rows[1][6], "UNLICENSED");
+        assert_eq!(table.summary["non_compliant_products"], 2);
+
+        let csv = String::from_utf8(render(&table, ArtifactFormat::Csv).unwrap()).unwrap();
+        assert!(csv.starts_with("vendor,product,installs,consumed,entitled,position,status\n"));
+        assert!(csv.contains("Microsoft,SQL Server,2,6,4,-2,SHORTFALL"));
+    }
+
+    #[test]
+    fn test_artifact_filename_and_status() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 4, 7, 0, 0).unwrap();
+        assert_eq!(
+            artifact_filename("Weekly  SLA / EMEA", ArtifactFormat::Csv, at),
+            "weekly-sla-emea-20240304-0700.csv"
+        );
+
+        let result = |status| DeliveryResult {
+            channel: DeliveryChannel::Email,
+            target: "ops@example.com".to_string(),
+            status,
+            message: None,
+        };
+        assert_eq!(delivery_status(&[]), ArtifactStatus::Generated);
+        assert_eq!(delivery_status(&[result(DeliveryStatus::Skipped)]), ArtifactStatus::Generated);
+        assert_eq!(
+            delivery_status(&[result(DeliveryStatus::Sent), result(DeliveryStatus::Failed)]),
+            ArtifactStatus::PartiallyDelivered
+        );
+    }
+}
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
+use crate::database::TracedQuery;
 
 pub struct ReportingService {
     db: Database,
@@ -641,7 +642,7 @@ impl ReportingService {
             query_str.push_str(&format!(" LIMIT {}", limit));
         }
 
-        let reports: Vec<ReportSummary> = self.db.query(query_str).await?.take(0)?;
+        let reports: Vec<ReportSummary> = self.db.query(query_str).traced("reporting_service.list_reports").await?.take(0)?;
         Ok(reports)
     }
 
@@ -894,6 +895,7 @@ pub async fn get_ticket_metrics(
     let mut result = db.query(total_query)
         .bind(("start", start))
         .bind(("end", end))
+        .traced("reporting_service.get_ticket_metrics")
         .await?;
     
     let total_tickets: i64 = result.take::<Option<HashMap<String, i64>>>(0)
@@ -903,7 +905,7 @@ pub async fn get_ticket_metrics(
 
     // Tickets by status
     let status_query = "SELECT status, count() as count FROM ticket GROUP BY status";
-    let mut result = db.query(status_query).await?;
+    let mut result = db.query(status_query).traced("reporting_service.get_ticket_metrics").await?;
     let status_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
     
     let tickets_by_status: Vec<ChartDataPoint> = status_data.iter()
@@ -921,7 +923,7 @@ pub async fn get_ticket_metrics(
 
     // Tickets by priority
     let priority_query = "SELECT priority, count() as count FROM ticket GROUP BY priority";
-    let mut result = db.query(priority_query).await?;
+    let mut result = db.query(priority_query).traced("reporting_service.get_ticket_metrics").await?;
     let priority_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
     
     let tickets_by_priority: Vec<ChartDataPoint> = priority_data.iter()
@@ -939,7 +941,7 @@ pub async fn get_ticket_metrics(
 
     // Tickets by type
     let type_query = "SELECT type, count() as count FROM ticket GROUP BY type";
-    let mut result = db.query(type_query).await?;
+    let mut result = db.query(type_query).traced("reporting_service.get_ticket_metrics").await?;
     let type_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
     
     let tickets_by_type: Vec<ChartDataPoint> = type_data.iter()
@@ -957,7 +959,7 @@ pub async fn get_ticket_metrics(
 
     // Average resolution time (for resolved tickets)
     let resolution_query = "SELECT math::mean(time::unix(resolved_at) - time::unix(created_at)) as avg_seconds FROM ticket WHERE resolved_at != NONE";
-    let mut result = db.query(resolution_query).await?;
+    let mut result = db.query(resolution_query).traced("reporting_service.get_ticket_metrics").await?;
     let avg_seconds: f64 = result.take::<Option<HashMap<String, f64>>>(0)
         .unwrap_or(None)
         .and_then(|m| m.get("avg_seconds").copied())
@@ -974,7 +976,7 @@ pub async fn get_ticket_metrics(
 
     // SLA compliance
     let sla_query = "SELECT count() as total, math::sum(CASE WHEN resolution_sla_met = true THEN 1 ELSE 0 END) as met FROM ticket WHERE resolution_sla_met != NONE GROUP ALL";
-    let mut result = db.query(sla_query).await?;
+    let mut result = db.query(sla_query).traced("reporting_service.get_ticket_metrics").await?;
     let sla_data: Option<HashMap<String, i64>> = result.take(0).unwrap_or(None);
     
     let sla_compliance_percentage = if let Some(data) = sla_data {
@@ -1026,7 +1028,7 @@ pub async fn get_user_performance_metrics(db: &Database) -> Result<Vec<UserPerfo
         GROUP BY assignee
     ";
 
-    let mut result = db.query(query).await?;
+    let mut result = db.query(query).traced("reporting_service.get_user_performance_metrics").await?;
     let user_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
 
     let metrics: Vec<UserPerformanceMetrics> = user_data.iter()
@@ -1059,7 +1061,7 @@ pub async fn get_user_performance_metrics(db: &Database) -> Result<Vec<UserPerfo
 pub async fn get_asset_inventory_metrics(db: &Database) -> Result<AssetInventoryMetrics> {
     // Total assets
     let total_query = "SELECT count() as total FROM ci GROUP ALL";
-    let mut result = db.query(total_query).await?;
+    let mut result = db.query(total_query).traced("reporting_service.get_asset_inventory_metrics").await?;
     let total_assets: i64 = result.take::<Option<HashMap<String, i64>>>(0)
         .unwrap_or(None)
         .and_then(|m| m.get("total").copied())
@@ -1067,7 +1069,7 @@ pub async fn get_asset_inventory_metrics(db: &Database) -> Result<AssetInventory
 
     // Assets by type
     let type_query = "SELECT ci_type, count() as count FROM ci GROUP BY ci_type";
-    let mut result = db.query(type_query).await?;
+    let mut result = db.query(type_query).traced("reporting_service.get_asset_inventory_metrics").await?;
     let type_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
     
     let assets_by_type: Vec<ChartDataPoint> = type_data.iter()
@@ -1085,7 +1087,7 @@ pub async fn get_asset_inventory_metrics(db: &Database) -> Result<AssetInventory
 
     // Assets by status
     let status_query = "SELECT status, count() as count FROM ci GROUP BY status";
-    let mut result = db.query(status_query).await?;
+    let mut result = db.query(status_query).traced("reporting_service.get_asset_inventory_metrics").await?;
     let status_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
     
     let assets_by_status: Vec<ChartDataPoint> = status_data.iter()
@@ -1122,7 +1124,7 @@ pub async fn get_kb_usage_metrics(db: &Database) -> Result<KbUsageMetrics> {
 
     // Total articles
     let total_query = "SELECT count() as total FROM kb_article GROUP ALL";
-    let mut result = db.query(total_query).await?;
+    let mut result = db.query(total_query).traced("reporting_service.get_kb_usage_metrics").await?;
     let total_articles: i64 = result.take::<Option<HashMap<String, i64>>>(0)
         .unwrap_or(None)
         .and_then(|m| m.get("total").copied())
@@ -1130,7 +1132,7 @@ pub async fn get_kb_usage_metrics(db: &Database) -> Result<KbUsageMetrics> {
 
     // Total views (sum of view_count)
     let views_query = "SELECT math::sum(view_count) as total_views FROM kb_article GROUP ALL";
-    let mut result = db.query(views_query).await?;
+    let mut result = db.query(views_query).traced("reporting_service.get_kb_usage_metrics").await?;
     let total_views: i64 = result.take::<Option<HashMap<String, i64>>>(0)
         .unwrap_or(None)
         .and_then(|m| m.get("total_views").copied())
@@ -1138,7 +1140,7 @@ pub async fn get_kb_usage_metrics(db: &Database) -> Result<KbUsageMetrics> {
 
     // Top articles by views
     let top_query = "SELECT id, title, view_count FROM kb_article ORDER BY view_count DESC LIMIT 10";
-    let mut result = db.query(top_query).await?;
+    let mut result = db.query(top_query).traced("reporting_service.get_kb_usage_metrics").await?;
     let top_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
     
     let top_articles: Vec<KbArticleStats> = top_data.iter()
@@ -1161,7 +1163,7 @@ pub async fn get_kb_usage_metrics(db: &Database) -> Result<KbUsageMetrics> {
 
     // Articles by category
     let category_query = "SELECT category_id, count() as count FROM kb_article GROUP BY category_id";
-    let mut result = db.query(category_query).await?;
+    let mut result = db.query(category_query).traced("reporting_service.get_kb_usage_metrics").await?;
     let category_data: Vec<HashMap<String, Value>> = result.take(0).unwrap_or_default();
     
     let articles_by_category: Vec<ChartDataPoint> = category_data.iter()
@@ -1179,7 +1181,7 @@ pub async fn get_kb_usage_metrics(db: &Database) -> Result<KbUsageMetrics> {
 
     // Helpful rating percentage
     let rating_query = "SELECT count() as total, math::sum(CASE WHEN is_helpful = true THEN 1 ELSE 0 END) as helpful FROM kb_rating GROUP ALL";
-    let mut result = db.query(rating_query).await?;
+    let mut result = db.query(rating_query).traced("reporting_service.get_kb_usage_metrics").await?;
     let rating_data: Option<HashMap<String, i64>> = result.take(0).unwrap_or(None);
     
     let helpful_rating_percentage = if let Some(data) = rating_data {
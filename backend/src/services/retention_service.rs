@@ -0,0 +1,539 @@
+//! Data Retention Service
+//!
+//! Purges customer data once it is older than its retention policy allows:
+//! - Policies are set per data class, as an installation default and per
+//!   tenant; a tenant's own policy replaces the default for that tenant,
+//!   and a disabled one keeps its data forever
+//! - Without any policy nothing is purged
+//! - Records find their tenant through their own `tenant_id` (audit logs) or
+//!   through their project (RVTools rows, documents); records without a
+//!   tenant fall under the default
+//! - Every purge that removes records writes a `DATA_PURGE` audit entry.
+//!   Those entries are never purged themselves.
+//!
+//! [`RetentionScheduler`] runs the purge nightly; a dry run counts the same
+//! records without deleting anything.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{info, warn};
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::auth::{AuditEventType, AuditLog};
+use crate::models::retention::*;
+use crate::models::workflow::StorageBackend;
+use crate::services::document_storage;
+
+const TABLE: &str = "retention_policy";
+/// Purge at 03:30 every night
+const DEFAULT_PURGE_CRON: &str = "0 30 3 * * *";
+/// Longest retention that can be configured (100 years)
+const MAX_RETAIN_DAYS: u32 = 36_500;
+
+#[derive(Debug, Error)]
+pub enum RetentionError {
+    #[error("Retention policy not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for RetentionError {
+    fn from(e: surrealdb::Error) -> Self {
+        RetentionError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// PURGE TARGETS
+// ============================================================================
+
+/// How a table's records are tied to a tenant
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TenantLink {
+    /// A `tenants:<id>` record link on the record itself
+    Record(&'static str),
+    /// The tenant name on the record's project, reached through this path
+    Project(&'static str),
+    /// Not tied to a tenant; only the default policy applies
+    Unscoped,
+}
+
+/// A table holding records of a data class
+#[derive(Debug, Clone, Copy)]
+struct PurgeTarget {
+    table: &'static str,
+    timestamp: &'static str,
+    tenant: TenantLink,
+    /// Records point at a stored document file that goes with them
+    has_files: bool,
+    /// Condition every purged record must also meet
+    only: Option<&'static str>,
+}
+
+fn targets(class: RetentionDataClass) -> Vec<PurgeTarget> {
+    match class {
+        RetentionDataClass::RvtoolsRows => vec![
+            PurgeTarget {
+                table: "rvtools_excel_data",
+                timestamp: "created_at",
+                tenant: TenantLink::Project("upload_id.project_id.tenant_id"),
+                has_files: false,
+                only: None,
+            },
+            PurgeTarget {
+                table: "rvtools_data",
+                timestamp: "created_at",
+                tenant: TenantLink::Unscoped,
+                has_files: false,
+                only: None,
+            },
+        ],
+        RetentionDataClass::AuditLogs => vec![PurgeTarget {
+            table: "audit_logs",
+            timestamp: "created_at",
+            tenant: TenantLink::Record("tenant_id"),
+            has_files: false,
+            only: Some("event_type != 'DATA_PURGE'"),
+        }],
+        RetentionDataClass::GeneratedDocuments => vec![
+            PurgeTarget {
+                table: "project_document",
+                timestamp: "created_at",
+                tenant: TenantLink::Project("project_id.tenant_id"),
+                has_files: true,
+                only: None,
+            },
+            PurgeTarget {
+                table: "generated_document",
+                timestamp: "generated_at",
+                tenant: TenantLink::Project("project_id.tenant_id"),
+                has_files: true,
+                only: None,
+            },
+        ],
+    }
+}
+
+/// The `WHERE` condition selecting what `policy` purges from `target`, or
+/// `None` when the policy can't reach the table. `overridden` lists the
+/// tenants with their own policy for the class; the default skips them.
+/// Binds `$cutoff`, `$tenant`, `$tenant_record`, `$overridden` and
+/// `$overridden_records`.
+fn purge_condition(target: &PurgeTarget, policy: &RetentionPolicy, overridden: &[String]) -> Option<String> {
+    let mut conditions = vec![format!("{} < $cutoff", target.timestamp)];
+    match (&policy.tenant_id, target.tenant) {
+        (Some(_), TenantLink::Unscoped) => return None,
+        (Some(_), TenantLink::Record(field)) => conditions.push(format!("{} = $tenant_record", field)),
+        (Some(_), TenantLink::Project(path)) => conditions.push(format!("{} = $tenant", path)),
+        (None, TenantLink::Record(field)) if !overridden.is_empty() => {
+            conditions.push(format!("({0} IS NONE OR {0} NOTINSIDE $overridden_records)", field))
+        }
+        (None, TenantLink::Project(path)) if !overridden.is_empty() => {
+            conditions.push(format!("({0} IS NONE OR {0} NOTINSIDE $overridden)", path))
+        }
+        (None, _) => {}
+    }
+    if let Some(only) = target.only {
+        conditions.push(only.to_string());
+    }
+    Some(conditions.join(" AND "))
+}
+
+/// Tenants with their own policy for a class, enabled or not
+fn overridden_tenants(policies: &[RetentionPolicy], class: RetentionDataClass) -> Vec<String> {
+    let tenants: HashSet<&String> = policies
+        .iter()
+        .filter(|p| p.data_class == class)
+        .filter_map(|p| p.tenant_id.as_ref())
+        .collect();
+    let mut tenants: Vec<String> = tenants.into_iter().cloned().collect();
+    tenants.sort();
+    tenants
+}
+
+fn policy_id(tenant_id: Option<&str>, class: RetentionDataClass) -> String {
+    format!("{}_{}", tenant_id.unwrap_or("default"), class.key())
+}
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredFile {
+    #[serde(default)]
+    file_path: Option<String>,
+    #[serde(default)]
+    storage_backend: StorageBackend,
+    /// Per-audience copies of an HLD export
+    #[serde(default)]
+    variants: Option<Vec<StoredVariant>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredVariant {
+    file_path: String,
+}
+
+// ============================================================================
+// SERVICE
+// ============================================================================
+
+pub struct RetentionService {
+    db: Arc<Database>,
+}
+
+impl RetentionService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<RetentionPolicy>, RetentionError> {
+        Ok(self
+            .db
+            .query(format!("SELECT * FROM {TABLE} ORDER BY tenant_id, data_class"))
+            .traced("retention_service.list_policies")
+            .await?
+            .take(0)?)
+    }
+
+    /// Create or replace the policy of a data class for a tenant (or the default)
+    pub async fn upsert_policy(
+        &self,
+        request: UpsertRetentionPolicyRequest,
+        updated_by: &str,
+    ) -> Result<RetentionPolicy, RetentionError> {
+        if request.retain_days == 0 || request.retain_days > MAX_RETAIN_DAYS {
+            return Err(RetentionError::Validation(format!(
+                "retain_days must be between 1 and {}",
+                MAX_RETAIN_DAYS
+            )));
+        }
+        let tenant_id = request.tenant_id.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        let policy = RetentionPolicy {
+            id: None,
+            tenant_id,
+            data_class: request.data_class,
+            retain_days: request.retain_days,
+            enabled: request.enabled,
+            updated_by: updated_by.to_string(),
+            updated_at: Utc::now(),
+        };
+        let id = policy_id(policy.tenant_id.as_deref(), policy.data_class);
+        let stored: Option<RetentionPolicy> = self
+            .db
+            .query("UPDATE type::thing($table, $id) CONTENT $policy RETURN AFTER")
+            .bind(("table", TABLE))
+            .bind(("id", id))
+            .bind(("policy", policy))
+            .traced("retention_service.upsert_policy")
+            .await?
+            .take(0)?;
+        stored.ok_or_else(|| RetentionError::DatabaseError("Policy was not stored".to_string()))
+    }
+
+    pub async fn delete_policy(&self, id: &str) -> Result<(), RetentionError> {
+        let key = id.strip_prefix("retention_policy:").unwrap_or(id);
+        let key = key.trim_start_matches('⟨').trim_end_matches('⟩');
+        let deleted: Option<RetentionPolicy> = self.db.delete((TABLE, key)).await?;
+        deleted.map(|_| ()).ok_or(RetentionError::NotFound)
+    }
+
+    /// Purge everything past its retention, or count it when `dry_run` is set
+    pub async fn run(&self, dry_run: bool, actor: &str) -> Result<PurgeReport, RetentionError> {
+        let started_at = Utc::now();
+        let policies = self.list_policies().await?;
+        let mut items = Vec::new();
+
+        for policy in policies.iter().filter(|p| p.enabled) {
+            let overridden = overridden_tenants(&policies, policy.data_class);
+            let cutoff = started_at - Duration::days(policy.retain_days as i64);
+            let mut policy_items = Vec::new();
+
+            for target in targets(policy.data_class) {
+                let Some(condition) = purge_condition(&target, policy, &overridden) else {
+                    continue;
+                };
+                let records = self.count(&target, &condition, policy, &overridden, cutoff).await?;
+                let mut files = 0;
+                if !dry_run && records > 0 {
+                    if target.has_files {
+                        files = self.delete_files(&target, &condition, policy, &overridden, cutoff).await?;
+                    }
+                    self.bound(format!("DELETE {} WHERE {}", target.table, condition), policy, &overridden, cutoff)
+                        .traced("retention_service.purge")
+                        .await?;
+                }
+                policy_items.push(PurgeItem {
+                    data_class: policy.data_class,
+                    tenant_id: policy.tenant_id.clone(),
+                    table: target.table.to_string(),
+                    retain_days: policy.retain_days,
+                    cutoff,
+                    records,
+                    files,
+                });
+            }
+
+            if !dry_run && policy_items.iter().any(|i| i.records > 0) {
+                self.audit_purge(policy, &policy_items, actor).await;
+            }
+            items.extend(policy_items);
+        }
+
+        Ok(PurgeReport {
+            dry_run,
+            total_records: items.iter().map(|i| i.records).sum(),
+            items,
+            started_at,
+            finished_at: Utc::now(),
+        })
+    }
+
+    /// A query with the parameters of [`purge_condition`] bound
+    fn bound<'a>(
+        &'a self,
+        sql: String,
+        policy: &RetentionPolicy,
+        overridden: &[String],
+        cutoff: DateTime<Utc>,
+    ) -> surrealdb::method::Query<'a, crate::database::Db> {
+        let overridden_records: Vec<Thing> = overridden.iter().map(|t| Thing::from(("tenants", t.as_str()))).collect();
+        self.db
+            .query(sql)
+            .bind(("cutoff", cutoff))
+            .bind(("tenant", policy.tenant_id.clone()))
+            .bind((
+                "tenant_record",
+                policy.tenant_id.as_deref().map(|t| Thing::from(("tenants", t))),
+            ))
+            .bind(("overridden", overridden.to_vec()))
+            .bind(("overridden_records", overridden_records))
+    }
+
+    async fn count(
+        &self,
+        target: &PurgeTarget,
+        condition: &str,
+        policy: &RetentionPolicy,
+        overridden: &[String],
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, RetentionError> {
+        let sql = format!("SELECT count() AS count FROM {} WHERE {} GROUP ALL", target.table, condition);
+        let rows: Vec<CountRow> = self
+            .bound(sql, policy, overridden, cutoff)
+            .traced("retention_service.count")
+            .await?
+            .take(0)?;
+        Ok(rows.first().map(|r| r.count).unwrap_or(0))
+    }
+
+    /// Delete the stored files of the documents about to be purged
+    async fn delete_files(
+        &self,
+        target: &PurgeTarget,
+        condition: &str,
+        policy: &RetentionPolicy,
+        overridden: &[String],
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, RetentionError> {
+        let sql = format!("SELECT file_path, storage_backend, variants FROM {} WHERE {}", target.table, condition);
+        let stored: Vec<StoredFile> = self
+            .bound(sql, policy, overridden, cutoff)
+            .traced("retention_service.files")
+            .await?
+            .take(0)?;
+
+        let mut deleted = 0;
+        for file in stored {
+            let paths = file
+                .file_path
+                .into_iter()
+                .chain(file.variants.into_iter().flatten().map(|v| v.file_path))
+                .filter(|p| !p.is_empty());
+            let store = document_storage::store_for(file.storage_backend)
+                .map_err(|e| RetentionError::Storage(e.to_string()))?;
+            for path in paths {
+                match store.delete(&document_storage::document_key(&path)).await {
+                    Ok(()) => deleted += 1,
+                    Err(e) => warn!("🗑️ Could not delete purged document file {}: {}", path, e),
+                }
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Record a purge in the audit log
+    async fn audit_purge(&self, policy: &RetentionPolicy, items: &[PurgeItem], actor: &str) {
+        let records: u64 = items.iter().map(|i| i.records).sum();
+        let log = AuditLog {
+            id: None,
+            event_type: AuditEventType::DataPurge,
+            user_id: None,
+            username: Some(actor.to_string()),
+            resource_type: Some(policy.data_class.key().to_string()),
+            resource_id: policy.id.as_ref().map(|id| id.to_string()),
+            action: "retention_purge".to_string(),
+            details: Some(serde_json::json!({
+                "retain_days": policy.retain_days,
+                "cutoff": items.first().map(|i| i.cutoff),
+                "records": records,
+                "tables": items,
+            })),
+            ip_address: None,
+            user_agent: None,
+            success: true,
+            error_message: None,
+            tenant_id: policy.tenant_id.as_deref().map(|t| Thing::from(("tenants", t))),
+            created_at: Utc::now(),
+        };
+        let stored: Result<Vec<AuditLog>, _> = self.db.create("audit_logs").content(log).await;
+        if let Err(e) = stored {
+            warn!("🗑️ Could not write the audit entry of a retention purge: {}", e);
+        }
+        info!(
+            "🗑️ Purged {} {} record(s) older than {} days{}",
+            records,
+            policy.data_class.key(),
+            policy.retain_days,
+            policy.tenant_id.as_deref().map(|t| format!(" for tenant {}", t)).unwrap_or_default()
+        );
+    }
+}
+
+/// Runs the retention purge every night
+pub struct RetentionScheduler {
+    db: Arc<Database>,
+}
+
+impl RetentionScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+
+        let cron = std::env::var("RETENTION_PURGE_CRON").unwrap_or_else(|_| DEFAULT_PURGE_CRON.to_string());
+        let service = Arc::new(RetentionService::new(Arc::clone(&self.db)));
+        let job = Job::new_async(cron.as_str(), move |_uuid, _lock| {
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                if let Err(e) = service.run(false, "Retention schedule").await {
+                    warn!("🗑️ Retention purge failed: {}", e);
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create retention purge job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add retention purge job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+
+        // Keep the scheduler alive for the process lifetime
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(tenant: Option<&str>, class: RetentionDataClass, enabled: bool) -> RetentionPolicy {
+        RetentionPolicy {
+            id: None,
+            tenant_id: tenant.map(str::to_string),
+            data_class: class,
+            retain_days: 180,
+            enabled,
+            updated_by: "admin".to_string(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn target(class: RetentionDataClass, table: &str) -> PurgeTarget {
+        targets(class).into_iter().find(|t| t.table == table).unwrap()
+    }
+
+    #[test]
+    fn test_tenant_policies_replace_the_default_for_their_tenant() {
+        let policies = vec![
+            policy(None, RetentionDataClass::AuditLogs, true),
+            policy(Some("acme"), RetentionDataClass::AuditLogs, true),
+            policy(Some("globex"), RetentionDataClass::AuditLogs, false),
+            policy(Some("initech"), RetentionDataClass::RvtoolsRows, true),
+        ];
+        let overridden = overridden_tenants(&policies, RetentionDataClass::AuditLogs);
+        assert_eq!(overridden, vec!["acme".to_string(), "globex".to_string()]);
+
+        let audit = target(RetentionDataClass::AuditLogs, "audit_logs");
+        assert_eq!(
+            purge_condition(&audit, &policies[0], &overridden).unwrap(),
+            "created_at < $cutoff AND (tenant_id IS NONE OR tenant_id NOTINSIDE $overridden_records) \
+             AND event_type != 'DATA_PURGE'"
+        );
+        assert_eq!(
+            purge_condition(&audit, &policies[1], &overridden).unwrap(),
+            "created_at < $cutoff AND tenant_id = $tenant_record AND event_type != 'DATA_PURGE'"
+        );
+
+        // Without tenant overrides the default covers everything
+        assert_eq!(
+            purge_condition(&audit, &policies[0], &[]).unwrap(),
+            "created_at < $cutoff AND event_type != 'DATA_PURGE'"
+        );
+    }
+
+    #[test]
+    fn test_records_reach_their_tenant_through_the_project() {
+        let tenant_policy = policy(Some("initech"), RetentionDataClass::RvtoolsRows, true);
+        let default_policy = policy(None, RetentionDataClass::RvtoolsRows, true);
+        let overridden = vec!["initech".to_string()];
+
+        let excel = target(RetentionDataClass::RvtoolsRows, "rvtools_excel_data");
+        assert_eq!(
+            purge_condition(&excel, &tenant_policy, &overridden).unwrap(),
+            "created_at < $cutoff AND upload_id.project_id.tenant_id = $tenant"
+        );
+        assert_eq!(
+            purge_condition(&excel, &default_policy, &overridden).unwrap(),
+            "created_at < $cutoff AND (upload_id.project_id.tenant_id IS NONE \
+             OR upload_id.project_id.tenant_id NOTINSIDE $overridden)"
+        );
+
+        // Legacy rows carry no project link; only the default reaches them
+        let legacy = target(RetentionDataClass::RvtoolsRows, "rvtools_data");
+        assert!(purge_condition(&legacy, &tenant_policy, &overridden).is_none());
+        assert_eq!(
+            purge_condition(&legacy, &default_policy, &overridden).unwrap(),
+            "created_at < $cutoff"
+        );
+
+        let exports = target(RetentionDataClass::GeneratedDocuments, "generated_document");
+        assert!(exports.has_files);
+        assert!(purge_condition(&exports, &default_policy, &[]).unwrap().starts_with("generated_at < $cutoff"));
+    }
+}
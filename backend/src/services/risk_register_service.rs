@@ -0,0 +1,571 @@
+//! Risk Register Service
+//!
+//! Maintains the per-project risk register:
+//! - CRUD for manually raised risks
+//! - Seeding from analysis findings (legacy OS, capacity, hardware compatibility,
+//!   domain controller placement across waves)
+//! - Refreshing seeded risks in place so owner/status/mitigation edits survive
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, Utc};
+use std::collections::BTreeMap;
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::models::migration_wizard_models::{MigrationWizardCluster, MigrationWizardVM};
+use crate::models::os_lifecycle::SupportStatus;
+use crate::models::risk::*;
+use crate::models::timeline::MigrationWave;
+use crate::models::workflow::{CheckResult, CheckStatus, HardwareCompatibilityResult};
+use crate::services::integration_hub::dependencies::{domain_controllers, DomainControllerInfo};
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::os_lifecycle_service::{OsLifecycleCatalog, OsLifecycleService};
+use crate::services::timeline_service::TimelineService;
+use crate::database::TracedQuery;
+
+/// Utilisation above which a destination cluster is flagged as a capacity risk
+const CAPACITY_WARNING_PERCENT: f64 = 80.0;
+
+/// Per-cluster allocation totals: (cluster, vCPU, memory MB, storage GB)
+pub type ClusterAllocation<'a> = (&'a MigrationWizardCluster, i32, i32, f64);
+
+pub struct RiskRegisterService {
+    db: Database,
+}
+
+impl RiskRegisterService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    // =========================================================================
+    // CRUD
+    // =========================================================================
+
+    /// List risks for a project, most severe first
+    pub async fn list_risks(&self, project_id: &str) -> Result<Vec<RiskEntry>> {
+        let mut risks: Vec<RiskEntry> = self
+            .db
+            .query("SELECT * FROM risk_entry WHERE project_id = type::thing('migration_wizard_project', $project_id)")
+            .bind(("project_id", project_id.to_string()))
+            .traced("risk_register_service.list_risks")
+            .await
+            .context("Failed to query risk register")?
+            .take(0)
+            .context("Failed to parse risk register")?;
+
+        risks.sort_by(|a, b| {
+            b.severity_score()
+                .cmp(&a.severity_score())
+                .then_with(|| b.impact.cmp(&a.impact))
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        Ok(risks)
+    }
+
+    pub async fn create_risk(&self, project_id: &str, request: CreateRiskRequest) -> Result<RiskEntry> {
+        if request.title.trim().is_empty() {
+            return Err(anyhow!("Risk title is required"));
+        }
+
+        let now = Utc::now();
+        let risk = RiskEntry {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", project_id)),
+            title: request.title,
+            description: request.description,
+            category: request.category,
+            impact: request.impact,
+            likelihood: request.likelihood,
+            owner: request.owner,
+            mitigation: request.mitigation,
+            status: RiskStatus::Open,
+            source: RiskSource::Manual,
+            finding_key: None,
+            affected_items: request.affected_items,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.insert(risk).await
+    }
+
+    pub async fn update_risk(&self, risk_id: &str, request: UpdateRiskRequest) -> Result<RiskEntry> {
+        let mut risk: RiskEntry = self
+            .db
+            .select(("risk_entry", risk_id))
+            .await
+            .context("Failed to get risk")?
+            .ok_or_else(|| anyhow!("Risk not found"))?;
+
+        if let Some(title) = request.title {
+            risk.title = title;
+        }
+        if let Some(description) = request.description {
+            risk.description = description;
+        }
+        if let Some(category) = request.category {
+            risk.category = category;
+        }
+        if let Some(impact) = request.impact {
+            risk.impact = impact;
+        }
+        if let Some(likelihood) = request.likelihood {
+            risk.likelihood = likelihood;
+        }
+        if let Some(owner) = request.owner {
+            risk.owner = Some(owner);
+        }
+        if let Some(mitigation) = request.mitigation {
+            risk.mitigation = mitigation;
+        }
+        if let Some(status) = request.status {
+            risk.status = status;
+        }
+        risk.updated_at = Utc::now();
+
+        let updated: Option<RiskEntry> = self
+            .db
+            .update(("risk_entry", risk_id))
+            .content(risk)
+            .await
+            .context("Failed to update risk")?;
+
+        updated.ok_or_else(|| anyhow!("Risk not found"))
+    }
+
+    pub async fn delete_risk(&self, risk_id: &str) -> Result<()> {
+        let _: Option<RiskEntry> = self
+            .db
+            .delete(("risk_entry", risk_id))
+            .await
+            .context("Failed to delete risk")?;
+        Ok(())
+    }
+
+    async fn insert(&self, risk: RiskEntry) -> Result<RiskEntry> {
+        let created: Vec<RiskEntry> = self
+            .db
+            .create("risk_entry")
+            .content(risk)
+            .await
+            .context("Failed to create risk")?;
+
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No risk returned after creation"))
+    }
+
+    // =========================================================================
+    // SEEDING FROM ANALYSIS
+    // =========================================================================
+
+    /// Seed the register from the project's current analysis findings.
+    ///
+    /// Existing analysis risks are matched on `finding_key`; their description
+    /// and affected items are refreshed while user-edited fields are kept.
+    pub async fn seed_from_analysis(
+        &self,
+        project_id: &str,
+        request: SeedRisksRequest,
+    ) -> Result<SeedRisksResponse> {
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let vms = wizard.get_project_vms(project_id, None).await?;
+        let catalog = OsLifecycleService::new(self.db.clone()).catalog().await?;
+        let utilization = wizard.get_cluster_utilization(project_id).await?;
+        let allocations: Vec<ClusterAllocation> = utilization
+            .iter()
+            .map(|(cluster, cpu, memory, storage, _)| (cluster, *cpu, *memory, *storage))
+            .collect();
+
+        let mut findings = Self::baseline_findings();
+        findings.extend(Self::os_findings(&vms, &catalog, Utc::now().date_naive()));
+        findings.extend(Self::capacity_findings(&allocations));
+        let waves = TimelineService::new(self.db.clone()).list_waves(project_id).await?;
+        let controllers = domain_controllers(&self.db)
+            .await
+            .context("Failed to load domain controllers")?;
+        findings.extend(Self::identity_findings(&vms, &waves, &controllers));
+        if let Some(hardware) = &request.hardware_compatibility {
+            findings.extend(Self::hardware_findings(hardware));
+        }
+
+        let existing = self.list_risks(project_id).await?;
+        let mut created = 0;
+        let mut refreshed = 0;
+
+        for finding in findings {
+            let current = existing
+                .iter()
+                .find(|r| r.finding_key.as_deref() == Some(finding.key.as_str()));
+
+            match current.and_then(|r| r.id.clone()) {
+                Some(id) => {
+                    let _: Option<RiskEntry> = self
+                        .db
+                        .update(id)
+                        .merge(serde_json::json!({
+                            "description": finding.description,
+                            "affected_items": finding.affected_items,
+                            "updated_at": Utc::now(),
+                        }))
+                        .await
+                        .context("Failed to refresh risk")?;
+                    refreshed += 1;
+                }
+                None => {
+                    let now = Utc::now();
+                    self.insert(RiskEntry {
+                        id: None,
+                        project_id: Thing::from(("migration_wizard_project", project_id)),
+                        title: finding.title,
+                        description: finding.description,
+                        category: finding.category,
+                        impact: finding.impact,
+                        likelihood: finding.likelihood,
+                        owner: None,
+                        mitigation: finding.mitigation,
+                        status: RiskStatus::Open,
+                        source: RiskSource::Analysis,
+                        finding_key: Some(finding.key),
+                        affected_items: finding.affected_items,
+                        created_at: now,
+                        updated_at: now,
+                    })
+                    .await?;
+                    created += 1;
+                }
+            }
+        }
+
+        Ok(SeedRisksResponse {
+            created,
+            refreshed,
+            risks: self.list_risks(project_id).await?,
+        })
+    }
+
+    /// Risks that apply to every migration regardless of findings
+    pub fn baseline_findings() -> Vec<RiskFinding> {
+        let baseline = |key: &str, title: &str, category, impact, likelihood, mitigation: &str| RiskFinding {
+            key: format!("baseline:{}", key),
+            title: title.to_string(),
+            description: String::new(),
+            category,
+            impact,
+            likelihood,
+            mitigation: mitigation.to_string(),
+            affected_items: Vec::new(),
+        };
+
+        vec![
+            baseline("network", "Network connectivity issues", RiskCategory::Network, RiskRating::High, RiskRating::Medium, "Pre-migration network testing and validation"),
+            baseline("compatibility", "Application compatibility", RiskCategory::Compatibility, RiskRating::Medium, RiskRating::Medium, "Pilot migration and thorough testing"),
+            baseline("data_loss", "Data loss during migration", RiskCategory::Data, RiskRating::High, RiskRating::Low, "Backup verification and rollback procedures"),
+            baseline("downtime", "Extended downtime", RiskCategory::Operational, RiskRating::Medium, RiskRating::Medium, "Migration windows and phased approach"),
+        ]
+    }
+
+    /// Guest operating systems that are out of support, on extended support or unidentified
+    pub fn os_findings(vms: &[MigrationWizardVM], catalog: &OsLifecycleCatalog, as_of: NaiveDate) -> Vec<RiskFinding> {
+        let mut legacy = Vec::new();
+        let mut end_of_life = Vec::new();
+        let mut extended = Vec::new();
+        let mut unknown = Vec::new();
+
+        for vm in vms {
+            let os = catalog.classify(vm.os.as_deref(), as_of);
+            let version = os.normalized.as_ref().and_then(|n| n.version.as_deref());
+            match os.status {
+                SupportStatus::EndOfSupport if matches!(version, Some("XP" | "2000" | "2003" | "2003 R2")) => {
+                    legacy.push(vm.name.clone())
+                }
+                SupportStatus::EndOfSupport => end_of_life.push(vm.name.clone()),
+                SupportStatus::ExtendedSupport => extended.push(vm.name.clone()),
+                SupportStatus::Unknown => unknown.push(vm.name.clone()),
+                SupportStatus::Supported => {}
+            }
+        }
+
+        let share = |count: usize| {
+            if !vms.is_empty() && count * 10 >= vms.len() {
+                RiskRating::High
+            } else {
+                RiskRating::Medium
+            }
+        };
+
+        let mut findings = Vec::new();
+        if !legacy.is_empty() {
+            findings.push(RiskFinding {
+                key: "analysis:legacy_os".to_string(),
+                title: "Legacy guest operating systems".to_string(),
+                description: format!("{} VMs run Windows XP/2003, which lack Hyper-V integration support", legacy.len()),
+                category: RiskCategory::Compatibility,
+                impact: RiskRating::High,
+                likelihood: share(legacy.len()),
+                mitigation: "Upgrade or rebuild before migration; isolate any VMs that must move as-is".to_string(),
+                affected_items: legacy,
+            });
+        }
+        if !end_of_life.is_empty() {
+            findings.push(RiskFinding {
+                key: "analysis:eol_os".to_string(),
+                title: "End-of-life guest operating systems".to_string(),
+                description: format!("{} VMs run an OS release that is past vendor end of support", end_of_life.len()),
+                category: RiskCategory::Compatibility,
+                impact: RiskRating::Medium,
+                likelihood: share(end_of_life.len()),
+                mitigation: "Plan in-place upgrades to a supported release or document a risk acceptance".to_string(),
+                affected_items: end_of_life,
+            });
+        }
+        if !extended.is_empty() {
+            findings.push(RiskFinding {
+                key: "analysis:extended_support_os".to_string(),
+                title: "Guest operating systems on extended support".to_string(),
+                description: format!("{} VMs rely on paid extended support for security updates", extended.len()),
+                category: RiskCategory::Compatibility,
+                impact: RiskRating::Low,
+                likelihood: share(extended.len()),
+                mitigation: "Confirm extended support coverage and schedule upgrades before it lapses".to_string(),
+                affected_items: extended,
+            });
+        }
+        if !unknown.is_empty() {
+            findings.push(RiskFinding {
+                key: "analysis:unknown_os".to_string(),
+                title: "Unidentified guest operating systems".to_string(),
+                description: format!("{} VMs have missing or unrecognised OS information", unknown.len()),
+                category: RiskCategory::Compatibility,
+                impact: RiskRating::Medium,
+                likelihood: RiskRating::Medium,
+                mitigation: "Review VMs manually and confirm OS before wave assignment".to_string(),
+                affected_items: unknown,
+            });
+        }
+        findings
+    }
+
+    /// Destination clusters whose placed load exceeds the warning threshold
+    pub fn capacity_findings(allocations: &[ClusterAllocation]) -> Vec<RiskFinding> {
+        let mut findings = Vec::new();
+
+        for (cluster, cpu, memory_mb, storage_gb) in allocations {
+            let cpu_capacity = cluster.total_cores as f64 * cluster.cpu_oversubscription_ratio;
+            let memory_capacity = cluster.allocatable_memory().as_mib();
+            let storage_capacity = cluster.storage_capacity().as_gib();
+
+            let percent = |used: f64, capacity: f64| if capacity > 0.0 { used / capacity * 100.0 } else { 0.0 };
+            let dimensions = [
+                ("CPU", percent(*cpu as f64, cpu_capacity)),
+                ("memory", percent(*memory_mb as f64, memory_capacity)),
+                ("storage", percent(*storage_gb, storage_capacity)),
+            ];
+
+            let over: Vec<String> = dimensions
+                .iter()
+                .filter(|(_, p)| *p > CAPACITY_WARNING_PERCENT)
+                .map(|(name, p)| format!("{} {:.0}%", name, p))
+                .collect();
+            if over.is_empty() {
+                continue;
+            }
+
+            let exceeded = dimensions.iter().any(|(_, p)| *p > 100.0);
+            findings.push(RiskFinding {
+                key: format!("analysis:capacity:{}", cluster.name),
+                title: format!("Capacity pressure on cluster {}", cluster.name),
+                description: format!("Planned placements reach {}", over.join(", ")),
+                category: RiskCategory::Capacity,
+                impact: RiskRating::High,
+                likelihood: if exceeded { RiskRating::High } else { RiskRating::Medium },
+                mitigation: "Rebalance placements, add hosts or adjust oversubscription ratios".to_string(),
+                affected_items: vec![cluster.name.clone()],
+            });
+        }
+        findings
+    }
+
+    /// AD sites whose domain controllers are all project VMs cut over in the
+    /// same wave, leaving the site without a DC during the move
+    pub fn identity_findings(
+        vms: &[MigrationWizardVM],
+        waves: &[MigrationWave],
+        controllers: &[DomainControllerInfo],
+    ) -> Vec<RiskFinding> {
+        let mut by_site: BTreeMap<&str, Vec<&DomainControllerInfo>> = BTreeMap::new();
+        for dc in controllers {
+            by_site.entry(dc.site.as_deref().unwrap_or("Default-First-Site-Name")).or_default().push(dc);
+        }
+
+        let wave_of = |dc: &DomainControllerInfo| {
+            let vm = vms.iter().find(|vm| dc.is_vm(&vm.name, vm.primary_ip_address.as_deref()))?;
+            let id = vm.id.as_ref()?;
+            waves.iter().find(|w| w.vm_ids.contains(id))
+        };
+
+        let mut findings = Vec::new();
+        for (site, dcs) in by_site {
+            let waves_of_site: Vec<Option<&MigrationWave>> = dcs.iter().map(|&dc| wave_of(dc)).collect();
+            let Some(Some(wave)) = waves_of_site.first() else {
+                continue;
+            };
+            if !waves_of_site.iter().all(|w| w.is_some_and(|w| w.id == wave.id)) {
+                continue;
+            }
+
+            findings.push(RiskFinding {
+                key: format!("analysis:identity:site:{}", site.to_lowercase()),
+                title: format!("All domain controllers of AD site {} move in {}", site, wave.name),
+                description: format!(
+                    "{} domain controller(s) serving site {} are cut over together; authentication and DNS in the site depend on them during the move",
+                    dcs.len(),
+                    site
+                ),
+                category: RiskCategory::Operational,
+                impact: RiskRating::High,
+                likelihood: RiskRating::High,
+                mitigation: "Move at least one domain controller to another wave, or stand up a temporary DC in the site".to_string(),
+                affected_items: dcs.iter().map(|dc| dc.name.clone()).collect(),
+            });
+        }
+        findings
+    }
+
+    /// Failed or warning hardware compatibility checks
+    pub fn hardware_findings(result: &HardwareCompatibilityResult) -> Vec<RiskFinding> {
+        let checks: [(&str, &str, &CheckResult); 4] = [
+            ("rdma_nics", "RDMA-capable NICs", &result.checks.rdma_nics),
+            ("jbod_hba", "HBA/JBOD storage controllers", &result.checks.jbod_hba),
+            ("network_speed", "Network speed", &result.checks.network_speed),
+            ("jbod_disks", "Disk configuration", &result.checks.jbod_disks),
+        ];
+
+        checks
+            .iter()
+            .filter(|(_, _, check)| check.status != CheckStatus::Passed)
+            .map(|(key, name, check)| RiskFinding {
+                key: format!("analysis:hardware:{}", key),
+                title: format!("Unsupported hardware: {}", name),
+                description: check.message.clone(),
+                category: RiskCategory::Hardware,
+                impact: RiskRating::High,
+                likelihood: if check.status == CheckStatus::Failed { RiskRating::High } else { RiskRating::Medium },
+                mitigation: "Replace or reconfigure the affected components before cluster build".to_string(),
+                affected_items: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vm(name: &str, os: Option<&str>) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: name.to_string(),
+            powerstate: None,
+            template: None,
+            cpus: 2,
+            memory_mb: 4096,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: None,
+            dns_name: None,
+            cluster: None,
+            host: None,
+            datacenter: None,
+            os: os.map(|s| s.to_string()),
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_os_findings_group_by_support_status() {
+        let vms = vec![
+            vm("legacy", Some("Microsoft Windows Server 2003 (32-bit)")),
+            vm("eol", Some("Microsoft Windows Server 2008 R2 (64-bit)")),
+            vm("modern", Some("Microsoft Windows Server 2022 (64-bit)")),
+            vm("mystery", None),
+        ];
+
+        let as_of = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let findings = RiskRegisterService::os_findings(&vms, &OsLifecycleCatalog::builtin(), as_of);
+        let keys: Vec<&str> = findings.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, vec!["analysis:legacy_os", "analysis:eol_os", "analysis:unknown_os"]);
+        assert_eq!(findings[0].affected_items, vec!["legacy".to_string()]);
+    }
+
+    #[test]
+    fn test_identity_findings_flag_sites_moving_in_one_wave() {
+        let mut vms = vec![vm("DC01", None), vm("DC02", None), vm("DC03", None)];
+        for vm in &mut vms {
+            vm.id = Some(Thing::from(("migration_wizard_vm", vm.name.to_lowercase().as_str())));
+        }
+        let now = Utc::now();
+        let wave = |key: &str, members: &[&str]| MigrationWave {
+            id: Some(Thing::from(("migration_wave", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: format!("Wave {}", key),
+            sequence: 1,
+            vm_ids: members.iter().map(|m| Thing::from(("migration_wizard_vm", *m))).collect(),
+            target_cluster_ids: vec![],
+            planned_start: None,
+            planned_end: None,
+            change_window: None,
+            status: crate::models::timeline::WaveStatus::Planned,
+            created_at: now,
+            updated_at: now,
+        };
+        let dc = |name: &str, site: &str| DomainControllerInfo {
+            name: name.to_string(),
+            hostname: Some(format!("{}.corp.example.com", name.to_lowercase())),
+            site: Some(site.to_string()),
+            ..Default::default()
+        };
+        let controllers = vec![dc("DC01", "HQ"), dc("DC02", "HQ"), dc("DC03", "Branch"), dc("DC04", "Branch")];
+
+        let waves = vec![wave("1", &["dc01", "dc02", "dc03"])];
+        let findings = RiskRegisterService::identity_findings(&vms, &waves, &controllers);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].key, "analysis:identity:site:hq");
+        assert_eq!(findings[0].affected_items, vec!["DC01", "DC02"]);
+
+        // Splitting the site's DCs across waves clears the finding
+        let waves = vec![wave("1", &["dc01", "dc03"]), wave("2", &["dc02"])];
+        assert!(RiskRegisterService::identity_findings(&vms, &waves, &controllers).is_empty());
+    }
+
+    #[test]
+    fn test_severity_score() {
+        let now = Utc::now();
+        let risk = RiskEntry {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            title: "t".to_string(),
+            description: String::new(),
+            category: RiskCategory::Capacity,
+            impact: RiskRating::High,
+            likelihood: RiskRating::Medium,
+            owner: None,
+            mitigation: String::new(),
+            status: RiskStatus::Open,
+            source: RiskSource::Manual,
+            finding_key: None,
+            affected_items: vec![],
+            created_at: now,
+            updated_at: now,
+        };
+        assert_eq!(risk.severity_score(), 6);
+    }
+}
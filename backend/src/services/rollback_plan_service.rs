@@ -0,0 +1,507 @@
+//! Wave Rollback Plan Service
+//!
+//! Generates the reverse of a wave's cutover as a checklist: power off the
+//! target VMs, re-register and power on the source VMs, revert DNS from the
+//! IP plan, and undo backup and Splunk changes. The plan sets the decision
+//! criteria and the latest moment to call the rollback so it still ends
+//! within the wave's planned window. It is part of the wave runbook and can
+//! be posted to the wave's change tickets.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::automation_export::{AutomationPlan, PlanVm};
+use crate::models::migration_wizard_models::MigrationWizardVM;
+use crate::models::project_models::HypervisorType;
+use crate::models::ticket::{CommentType, CreateCommentRequest, TicketStatus};
+use crate::models::timeline::*;
+use crate::services::automation_export::AutomationExportService;
+use crate::services::integration_hub::dependencies::{backup_coverage, log_pipeline};
+use crate::services::ticket_service::TicketService;
+use crate::services::timeline_service::TimelineService;
+
+/// Deciding, verifying and updating the ticket, whatever the wave size
+const ROLLBACK_BASE_MINUTES: i64 = 30;
+/// Powering off the target VM and powering on the source VM
+const ROLLBACK_MINUTES_PER_VM: i64 = 5;
+/// Reverting one DNS record
+const ROLLBACK_MINUTES_PER_DNS_RECORD: i64 = 2;
+
+#[derive(Debug, Deserialize)]
+struct TicketRow {
+    id: Thing,
+    status: TicketStatus,
+}
+
+pub struct RollbackPlanService {
+    db: Database,
+}
+
+impl RollbackPlanService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Rollback plan of one wave
+    pub async fn wave_rollback_plan(&self, wave_id: &str) -> Result<RollbackPlan> {
+        let timeline = TimelineService::new(self.db.clone());
+        let wave = timeline.get_wave(wave_id).await?;
+        let vms = timeline.wave_vms(&wave).await?;
+        let jobs = backup_coverage(&self.db)
+            .await
+            .context("Failed to load backup jobs")?;
+        let pipeline = log_pipeline(&self.db)
+            .await
+            .context("Failed to load Splunk forwarders")?;
+        let backup = TimelineService::assess_backup_impact(&vms, &jobs);
+        let logs = TimelineService::assess_log_sources(&vms, &pipeline);
+        self.for_wave(&wave, &vms, &backup, &logs).await
+    }
+
+    /// Rollback plan of a wave whose VMs and impacts are already loaded
+    pub async fn for_wave(
+        &self,
+        wave: &MigrationWave,
+        vms: &[MigrationWizardVM],
+        backup: &BackupImpact,
+        logs: &LogSourceImpact,
+    ) -> Result<RollbackPlan> {
+        let plan = AutomationExportService::new(Arc::new(self.db.clone()))
+            .load_plan(&wave.project_id.id.to_raw(), HypervisorType::HyperV)
+            .await
+            .context("Failed to load the IP plan")?;
+        Ok(build_rollback_plan(wave, vms, &plan, backup, logs, Utc::now()))
+    }
+
+    /// Post the rollback plan as an internal note on each open change ticket
+    /// of the wave (linked through its `wave_id` custom field)
+    pub async fn attach_to_change_tickets(&self, wave_id: &str) -> Result<RollbackAttachment> {
+        let plan = self.wave_rollback_plan(wave_id).await?;
+        let key = wave_id.strip_prefix("migration_wave:").unwrap_or(wave_id);
+        let tickets: Vec<TicketRow> = self
+            .db
+            .query("SELECT id, status FROM ticket WHERE type = 'CHANGE' AND custom_fields.wave_id INSIDE $waves")
+            .bind(("waves", vec![key.to_string(), format!("migration_wave:{}", key)]))
+            .traced("rollback_plan_service.attach_to_change_tickets")
+            .await
+            .context("Failed to query change tickets")?
+            .take(0)
+            .context("Failed to parse change tickets")?;
+
+        let actor = AuthenticatedUser {
+            user_id: "system".to_string(),
+            email: String::new(),
+            username: "Rollback plan".to_string(),
+            roles: Vec::new(),
+            permissions: Vec::new(),
+            tenant_id: None,
+        };
+        let content = format!("{}\n{}", rollback_title(&plan), rollback_markdown(&plan));
+        let tickets_service = TicketService::new(Arc::new(self.db.clone()));
+        let mut ticket_ids = Vec::new();
+        for ticket in tickets
+            .into_iter()
+            .filter(|t| !matches!(t.status, TicketStatus::Cancelled | TicketStatus::Closed))
+        {
+            let ticket_id = ticket.id.id.to_raw();
+            let request = CreateCommentRequest {
+                content: content.clone(),
+                is_internal: true,
+                comment_type: Some(CommentType::Note),
+            };
+            tickets_service.add_comment(&ticket_id, request, &actor).await?;
+            ticket_ids.push(ticket_id);
+        }
+
+        Ok(RollbackAttachment {
+            wave_id: key.to_string(),
+            ticket_ids,
+        })
+    }
+}
+
+/// Reverse a wave's cutover. The rollback must end by the wave's planned
+/// end, so the decision is due the estimated rollback time before it.
+pub fn build_rollback_plan(
+    wave: &MigrationWave,
+    vms: &[MigrationWizardVM],
+    plan: &AutomationPlan,
+    backup: &BackupImpact,
+    logs: &LogSourceImpact,
+    now: DateTime<Utc>,
+) -> RollbackPlan {
+    let planned: HashMap<String, &PlanVm> = plan.vms.iter().map(|vm| (vm.key.clone(), vm)).collect();
+    let cluster_names: HashMap<&str, &str> = plan
+        .clusters
+        .iter()
+        .map(|c| (c.key.as_str(), c.name.as_str()))
+        .collect();
+    let planned_vm = |vm: &MigrationWizardVM| {
+        vm.id
+            .as_ref()
+            .and_then(|id| planned.get(&id.id.to_raw()).copied())
+    };
+
+    let mut steps: Vec<RollbackStep> = Vec::new();
+    let mut add = |phase: &str, action: String, vm_name: Option<&str>| {
+        steps.push(RollbackStep {
+            order: steps.len() as u32 + 1,
+            phase: phase.to_string(),
+            action,
+            vm_name: vm_name.map(str::to_string),
+        });
+    };
+
+    add(
+        "decision",
+        "Cutover lead calls the rollback and records the reason on the change ticket".to_string(),
+        None,
+    );
+    add(
+        "decision",
+        "Notify application owners and the service desk that the wave is rolling back".to_string(),
+        None,
+    );
+
+    for vm in vms {
+        let target = planned_vm(vm)
+            .map(|p| cluster_names.get(p.cluster.as_str()).copied().unwrap_or(p.cluster.as_str()))
+            .unwrap_or("the target cluster");
+        add(
+            "target",
+            format!("Power off {} on {} and disconnect its network adapters", vm.name, target),
+            Some(vm.name.as_str()),
+        );
+    }
+    for vm in vms {
+        let source = vm.cluster.as_deref().unwrap_or("the source cluster");
+        add(
+            "source",
+            format!(
+                "Re-register {} in the inventory of {} if it was removed, then power it on",
+                vm.name, source
+            ),
+            Some(vm.name.as_str()),
+        );
+    }
+
+    let mut dns_records = 0;
+    for vm in vms {
+        let Some(new_ip) = planned_vm(vm).and_then(|p| p.new_ip.as_deref()) else {
+            continue;
+        };
+        let record = vm.dns_name.as_deref().unwrap_or(&vm.name);
+        let source_ip = planned_vm(vm)
+            .and_then(|p| p.source_ip.as_deref())
+            .or(vm.primary_ip_address.as_deref());
+        let action = match source_ip {
+            Some(source_ip) if source_ip == new_ip => format!(
+                "Confirm {} answers from {} on the source network again; the target kept the same address",
+                new_ip, vm.name
+            ),
+            Some(source_ip) => {
+                dns_records += 1;
+                format!("Point DNS for {} back from {} to {}", record, new_ip, source_ip)
+            }
+            None => {
+                dns_records += 1;
+                format!("Remove the DNS record pointing {} at {}", record, new_ip)
+            }
+        };
+        add("network", action, Some(vm.name.as_str()));
+    }
+
+    for job in &backup.jobs_to_reconfigure {
+        add(
+            "backups",
+            format!("Point backup job '{}' back at the source VMs if it was reconfigured", job),
+            None,
+        );
+    }
+    for repoint in &logs.forwarders_to_repoint {
+        add(
+            "log_sources",
+            format!(
+                "Point {} forwarder(s) back to indexer '{}' at its source address",
+                repoint.forwarders.len(),
+                repoint.indexer
+            ),
+            None,
+        );
+    }
+
+    add(
+        "verification",
+        "Confirm the source VMs respond at their source addresses and run the application smoke tests".to_string(),
+        None,
+    );
+    add(
+        "verification",
+        "Set the wave status to rolled back and update the change ticket".to_string(),
+        None,
+    );
+
+    let rollback_minutes = ROLLBACK_BASE_MINUTES
+        + ROLLBACK_MINUTES_PER_VM * vms.len() as i64
+        + ROLLBACK_MINUTES_PER_DNS_RECORD * dns_records;
+    let decide_by = wave.planned_end.map(|end| end - Duration::minutes(rollback_minutes));
+    let time_to_decide_minutes = match (wave.planned_start, decide_by) {
+        (Some(start), Some(decide_by)) => Some((decide_by - start).num_minutes().max(0)),
+        _ => None,
+    };
+
+    let mut warnings = Vec::new();
+    match (wave.planned_start, decide_by) {
+        (_, None) => warnings.push("The wave has no planned end, so there is no deadline to decide by".to_string()),
+        (Some(start), Some(decide_by)) if decide_by <= start => warnings.push(format!(
+            "The planned window is too short to roll back: the rollback takes about {} minutes",
+            rollback_minutes
+        )),
+        _ => {}
+    }
+    let unplaced: Vec<&str> = vms
+        .iter()
+        .filter(|&vm| planned_vm(vm).is_none())
+        .map(|vm| vm.name.as_str())
+        .collect();
+    if !unplaced.is_empty() {
+        warnings.push(format!(
+            "{} VM(s) have no placement, so their target cluster and DNS changes are unknown: {}",
+            unplaced.len(),
+            unplaced.join(", ")
+        ));
+    }
+
+    let mut decision_criteria = vec![
+        "Replication or the final sync of a VM fails".to_string(),
+        "A VM fails to boot on the target or can't reach its gateway".to_string(),
+        "Application smoke tests fail and the owners can't fix the cause in time".to_string(),
+    ];
+    if !logs.forwarders_to_repoint.is_empty() {
+        decision_criteria.push("Forwarders can't reach a moved indexer at its new address".to_string());
+    }
+    if let Some(decide_by) = decide_by {
+        decision_criteria.push(format!(
+            "The cutover isn't validated by {}",
+            decide_by.format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+
+    RollbackPlan {
+        wave_id: wave.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+        wave_name: wave.name.clone(),
+        sequence: wave.sequence,
+        decision_criteria,
+        rollback_minutes,
+        decide_by,
+        time_to_decide_minutes,
+        steps,
+        warnings,
+        generated_at: now,
+    }
+}
+
+fn rollback_title(plan: &RollbackPlan) -> String {
+    format!("# Rollback plan: {} (wave {})\n", plan.wave_name, plan.sequence)
+}
+
+/// Render a rollback plan as a Markdown runbook section
+pub fn rollback_markdown(plan: &RollbackPlan) -> String {
+    let mut out = String::from("## Rollback plan\n\n");
+    match (plan.decide_by, plan.time_to_decide_minutes) {
+        (Some(decide_by), Some(minutes)) => out.push_str(&format!(
+            "- Decide by: **{}** ({} minutes after the planned start)\n",
+            decide_by.format("%Y-%m-%d %H:%M UTC"),
+            minutes
+        )),
+        (Some(decide_by), None) => {
+            out.push_str(&format!("- Decide by: **{}**\n", decide_by.format("%Y-%m-%d %H:%M UTC")))
+        }
+        _ => {}
+    }
+    out.push_str(&format!("- Estimated rollback time: {} minutes\n", plan.rollback_minutes));
+
+    out.push_str("\nRoll back if any of these holds:\n\n");
+    for criterion in &plan.decision_criteria {
+        out.push_str(&format!("- {}\n", criterion));
+    }
+    if !plan.warnings.is_empty() {
+        out.push_str("\nWarnings:\n\n");
+        for warning in &plan.warnings {
+            out.push_str(&format!("- {}\n", warning));
+        }
+    }
+
+    out.push_str("\nChecklist:\n\n");
+    for step in &plan.steps {
+        out.push_str(&format!("{}. [ ] {}\n", step.order, step.action));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::automation_export::PlanCluster;
+
+    fn wave(vm_keys: &[&str], hours: Option<i64>) -> MigrationWave {
+        let start = Utc::now();
+        MigrationWave {
+            id: Some(Thing::from(("migration_wave", "w1"))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: "Wave 1".to_string(),
+            sequence: 1,
+            vm_ids: vm_keys.iter().map(|k| Thing::from(("migration_wizard_vm", *k))).collect(),
+            target_cluster_ids: Vec::new(),
+            planned_start: Some(start),
+            planned_end: hours.map(|h| start + Duration::hours(h)),
+            change_window: None,
+            status: WaveStatus::Scheduled,
+            created_at: start,
+            updated_at: start,
+        }
+    }
+
+    fn vm(key: &str, ip: Option<&str>) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_string(),
+            powerstate: None,
+            template: None,
+            cpus: 2,
+            memory_mb: 4096,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: ip.map(str::to_string),
+            dns_name: None,
+            cluster: Some("VMW-01".to_string()),
+            host: None,
+            datacenter: None,
+            os: None,
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn plan(vms: &[(&str, Option<&str>)]) -> AutomationPlan {
+        AutomationPlan {
+            project_id: "p1".to_string(),
+            project_name: "Project".to_string(),
+            clusters: vec![PlanCluster {
+                key: "c1".to_string(),
+                name: "HCI-01".to_string(),
+                hypervisor: HypervisorType::HyperV,
+                destination_cluster_id: None,
+                total_cores: 64,
+                memory_gb: 1024,
+                storage_tb: 50.0,
+            }],
+            networks: Vec::new(),
+            waves: Vec::new(),
+            vms: vms
+                .iter()
+                .map(|(key, new_ip)| PlanVm {
+                    key: key.to_string(),
+                    name: key.to_string(),
+                    cluster: "c1".to_string(),
+                    wave: Some("w1".to_string()),
+                    cpus: 2,
+                    memory_mb: 4096,
+                    storage_gb: 40.0,
+                    os: None,
+                    source_ip: None,
+                    network: None,
+                    vlan_id: None,
+                    new_ip: new_ip.map(str::to_string),
+                    prefix_length: Some(24),
+                    gateway: None,
+                    dns_servers: Vec::new(),
+                })
+                .collect(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_rollback_reverses_the_cutover_in_order() {
+        let vms = vec![vm("web01", Some("10.1.0.11")), vm("web02", Some("10.2.0.12")), vm("db01", None)];
+        let plan = plan(&[("web01", Some("10.9.0.11")), ("web02", Some("10.2.0.12")), ("db01", Some("10.9.0.20"))]);
+        let backup = BackupImpact {
+            jobs_to_reconfigure: vec!["Daily".to_string()],
+            ..Default::default()
+        };
+        let wave = wave(&["web01", "web02", "db01"], Some(4));
+
+        let rollback = build_rollback_plan(&wave, &vms, &plan, &backup, &LogSourceImpact::default(), Utc::now());
+
+        let phases: Vec<&str> = rollback.steps.iter().map(|s| s.phase.as_str()).collect();
+        assert_eq!(
+            phases,
+            vec![
+                "decision", "decision", "target", "target", "target", "source", "source", "source", "network",
+                "network", "network", "backups", "verification", "verification",
+            ]
+        );
+        assert!(rollback.steps.iter().enumerate().all(|(i, s)| s.order == i as u32 + 1));
+        assert_eq!(rollback.steps[2].action, "Power off web01 on HCI-01 and disconnect its network adapters");
+        assert!(rollback.steps[5].action.contains("inventory of VMW-01"));
+
+        let network: Vec<&str> = rollback.steps[8..11].iter().map(|s| s.action.as_str()).collect();
+        assert_eq!(network[0], "Point DNS for web01 back from 10.9.0.11 to 10.1.0.11");
+        assert!(network[1].starts_with("Confirm 10.2.0.12 answers from web02"));
+        assert_eq!(network[2], "Remove the DNS record pointing db01 at 10.9.0.20");
+
+        // 30 + 3 VMs x 5 + 2 DNS records x 2
+        assert_eq!(rollback.rollback_minutes, 49);
+        assert_eq!(rollback.time_to_decide_minutes, Some(240 - 49));
+        assert_eq!(rollback.decide_by, wave.planned_end.map(|end| end - Duration::minutes(49)));
+        assert!(rollback.warnings.is_empty());
+        assert!(rollback.decision_criteria.last().unwrap().starts_with("The cutover isn't validated by"));
+    }
+
+    #[test]
+    fn test_rollback_warns_when_it_cannot_fit_the_window() {
+        let vms: Vec<MigrationWizardVM> = (0..10).map(|i| vm(&format!("vm{}", i), None)).collect();
+        let plan = plan(&[]);
+        let short = wave(&[], Some(1));
+
+        let rollback = build_rollback_plan(
+            &short,
+            &vms,
+            &plan,
+            &BackupImpact::default(),
+            &LogSourceImpact::default(),
+            Utc::now(),
+        );
+        assert_eq!(rollback.rollback_minutes, 80);
+        assert_eq!(rollback.time_to_decide_minutes, Some(0));
+        assert!(rollback.warnings[0].contains("too short to roll back"));
+        assert!(rollback.warnings[1].starts_with("10 VM(s) have no placement"));
+        assert!(rollback.steps[2].action.contains("on the target cluster"));
+
+        let open = wave(&[], None);
+        let rollback = build_rollback_plan(
+            &open,
+            &vms,
+            &plan,
+            &BackupImpact::default(),
+            &LogSourceImpact::default(),
+            Utc::now(),
+        );
+        assert!(rollback.decide_by.is_none());
+        assert!(rollback.warnings[0].contains("no planned end"));
+        assert!(rollback_markdown(&rollback).contains("1. [ ] Cutover lead calls the rollback"));
+    }
+}
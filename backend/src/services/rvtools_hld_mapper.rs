@@ -1,5 +1,6 @@
 use crate::models::hld::{VariableValue, VariableConfidence};
 use crate::models::project_models::RvToolsData;
+use core_engine::units::Bytes;
 use std::collections::{HashMap, HashSet};
 
 /// Represents a single variable that has been mapped from RVTools data
@@ -105,7 +106,7 @@ impl<'a> RVToolsHLDMapper<'a> {
         );
 
         // Map: total_storage_tb_usable (MEDIUM confidence - calculated from VM disks)
-        let storage_tb = total_disk_gb as f64 / 1024.0;
+        let storage_tb = Bytes::from_gib(total_disk_gb as f64).as_tib();
         mapped.insert(
             "total_storage_tb_usable".to_string(),
             MappedVariable {
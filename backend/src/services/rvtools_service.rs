@@ -2,9 +2,15 @@ use crate::database::Database;
 use crate::models::project_models::*;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use core_engine::models::{ImportReport, RowError};
+use core_engine::units::Bytes;
 use serde_json::json;
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
+use crate::database::TracedQuery;
+
+/// Sheet name used in row errors for CSV exports
+const CSV_SHEET: &str = "CSV";
 
 pub struct RvToolsService {
     db: Database,
@@ -46,6 +52,7 @@ impl RvToolsService {
             uploaded_at: Utc::now(),
             processed_at: None,
             uploaded_by: "system".to_string(),
+            import_report: None,
         };
 
         let created_upload: Vec<RvToolsUpload> = self
@@ -64,18 +71,26 @@ impl RvToolsService {
         {
             Ok(result) => {
                 // Update upload record as completed
+                let status = if result.report.has_warnings() {
+                    "completed_with_warnings"
+                } else {
+                    "processed"
+                };
                 let _: Option<RvToolsUpload> = self
                     .db
                     .update(&upload_id)
                     .merge(json!({
-                        "upload_status": "processed",
+                        "upload_status": status,
                         "total_vms": result.servers_processed,
                         "processed_at": Utc::now(),
                         "processing_results": json!({
                             "servers_processed": result.servers_processed,
                             "servers_added_to_pool": result.servers_added_to_pool,
-                            "errors": result.processing_errors.len()
-                        })
+                            "errors": result.processing_errors.len(),
+                            "rows_total": result.report.rows_total,
+                            "rows_failed": result.report.rows_failed
+                        }),
+                        "import_report": result.report
                     }))
                     .await?;
 
@@ -108,6 +123,7 @@ impl RvToolsService {
         let mut servers_processed = 0;
         let mut servers_added_to_pool = 0;
         let mut processing_errors = Vec::new();
+        let mut report = ImportReport::default();
 
         // Parse CSV data (simplified - in real implementation, use proper CSV parser)
         let lines: Vec<&str> = upload_data.csv_content.lines().collect();
@@ -116,26 +132,37 @@ impl RvToolsService {
             return Err(anyhow::anyhow!("Empty RVTools CSV file"));
         }
 
-        // Skip header row, process data rows
+        // Skip header row, process data rows. A bad row is reported and the
+        // rest of the file is still imported.
         for (line_num, line) in lines.iter().skip(1).enumerate() {
-            match self
-                .process_rvtools_line(line, upload_id, line_num + 2)
-                .await
-            {
-                Ok(added_to_pool) => {
-                    servers_processed += 1;
-                    if added_to_pool {
-                        servers_added_to_pool += 1;
-                    }
-                }
-                Err(e) => {
-                    processing_errors.push(RvToolsProcessingError {
-                        line_number: line_num + 2,
-                        server_name: self
-                            .extract_server_name(line)
-                            .unwrap_or_else(|| format!("Line {}", line_num + 2)),
-                        error: e.to_string(),
-                    });
+            let line_number = line_num + 2;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let stored = match Self::parse_rvtools_line(line, line_number) {
+                Ok(server_info) => self
+                    .store_rvtools_row(&server_info, upload_id, line_number)
+                    .await
+                    .map_err(|e| {
+                        RowError::new(CSV_SHEET, line_number, None, format!("Failed to store row: {}", e))
+                    }),
+                Err(error) => Err(error),
+            };
+
+            if let Err(error) = &stored {
+                processing_errors.push(RvToolsProcessingError {
+                    line_number,
+                    server_name: self
+                        .extract_server_name(line)
+                        .unwrap_or_else(|| format!("Line {}", line_number)),
+                    error: error.reason.clone(),
+                });
+            }
+            if let Some(added_to_pool) = report.record(stored) {
+                servers_processed += 1;
+                if added_to_pool {
+                    servers_added_to_pool += 1;
                 }
             }
         }
@@ -145,6 +172,7 @@ impl RvToolsService {
             servers_processed,
             servers_added_to_pool,
             processing_errors,
+            report,
             summary: RvToolsProcessingSummary {
                 total_cpu_cores: self.calculate_total_cpu_cores(upload_id).await?,
                 total_memory_gb: self.calculate_total_memory_gb(upload_id).await?,
@@ -156,33 +184,66 @@ impl RvToolsService {
         })
     }
 
-    async fn process_rvtools_line(
-        &self,
+    /// Validate one CSV line. Blank numeric fields fall back to defaults;
+    /// text where a number is expected rejects the row.
+    fn parse_rvtools_line(
         line: &str,
-        upload_id: &surrealdb::sql::Thing,
         line_number: usize,
-    ) -> Result<bool> {
+    ) -> std::result::Result<RvToolsServerInfo, RowError> {
         // Parse RVTools CSV line (simplified parsing)
         let fields: Vec<&str> = line.split(',').collect();
 
         if fields.len() < 10 {
-            return Err(anyhow::anyhow!("Insufficient fields in RVTools data"));
+            return Err(RowError::new(
+                CSV_SHEET,
+                line_number,
+                None,
+                format!("Expected at least 10 fields, found {}", fields.len()),
+            ));
         }
 
-        // Extract server information from RVTools format
-        let server_info = RvToolsServerInfo {
-            vm_name: fields.get(0).unwrap_or(&"").trim_matches('"').to_string(),
-            host_name: fields.get(1).unwrap_or(&"").trim_matches('"').to_string(),
-            cpu_cores: fields.get(2).unwrap_or(&"0").parse().unwrap_or(0),
-            memory_gb: fields.get(3).unwrap_or(&"0").parse::<f64>().unwrap_or(0.0) / 1024.0, // Convert MB to GB
-            disk_gb: fields.get(4).unwrap_or(&"0").parse::<f64>().unwrap_or(0.0) / 1024.0, // Convert MB to GB
-            operating_system: fields.get(5).unwrap_or(&"").trim_matches('"').to_string(),
-            power_state: fields.get(6).unwrap_or(&"").trim_matches('"').to_string(),
-            cluster: fields.get(7).unwrap_or(&"").trim_matches('"').to_string(),
-            datacenter: fields.get(8).unwrap_or(&"").trim_matches('"').to_string(),
-            network_adapters: fields.get(9).unwrap_or(&"1").parse().unwrap_or(1),
+        let text = |index: usize| fields[index].trim().trim_matches('"').to_string();
+        let number = |index: usize, column: &str, default: f64| {
+            let value = text(index);
+            if value.is_empty() {
+                return Ok(default);
+            }
+            value.parse::<f64>().map_err(|_| {
+                RowError::new(
+                    CSV_SHEET,
+                    line_number,
+                    Some(column),
+                    format!("Expected a number, found \"{}\"", value),
+                )
+            })
         };
 
+        let vm_name = text(0);
+        if vm_name.is_empty() {
+            return Err(RowError::new(CSV_SHEET, line_number, Some("VM"), "Missing VM name"));
+        }
+
+        // Extract server information from RVTools format
+        Ok(RvToolsServerInfo {
+            vm_name,
+            host_name: text(1),
+            cpu_cores: number(2, "CPUs", 0.0)? as i32,
+            memory_gb: Bytes::from_mib(number(3, "Memory", 0.0)?).as_gib(),
+            disk_gb: Bytes::from_mib(number(4, "Provisioned MB", 0.0)?).as_gib(),
+            operating_system: text(5),
+            power_state: text(6),
+            cluster: text(7),
+            datacenter: text(8),
+            network_adapters: number(9, "NICs", 1.0)? as i32,
+        })
+    }
+
+    async fn store_rvtools_row(
+        &self,
+        server_info: &RvToolsServerInfo,
+        upload_id: &surrealdb::sql::Thing,
+        line_number: usize,
+    ) -> Result<bool> {
         // Store raw RVTools data
         let rvtools_data = RvToolsData {
             id: None,
@@ -206,10 +267,10 @@ impl RvToolsService {
         let _: Vec<RvToolsData> = self.db.create("rvtools_data").content(rvtools_data).await?;
 
         // Determine if this should be added to hardware pool
-        let should_add_to_pool = self.should_add_to_hardware_pool(&server_info).await;
+        let should_add_to_pool = self.should_add_to_hardware_pool(server_info).await;
 
         if should_add_to_pool {
-            self.create_hardware_pool_entry(&server_info, &upload_id.id.to_string())
+            self.create_hardware_pool_entry(server_info, &upload_id.id.to_string())
                 .await?;
             return Ok(true);
         }
@@ -302,6 +363,7 @@ impl RvToolsService {
             .db
             .query("SELECT SUM(cpu_cores) as total FROM rvtools_data WHERE upload_id = $upload_id")
             .bind(("upload_id", upload_id))
+            .traced("rvtools_service.calculate_total_cpu_cores")
             .await?
             .take(0)?;
 
@@ -317,6 +379,7 @@ impl RvToolsService {
             .db
             .query("SELECT SUM(memory_gb) as total FROM rvtools_data WHERE upload_id = $upload_id")
             .bind(("upload_id", upload_id))
+            .traced("rvtools_service.calculate_total_memory_gb")
             .await?
             .take(0)?;
 
@@ -331,6 +394,7 @@ impl RvToolsService {
         let result: Vec<serde_json::Value> = self.db
             .query("SELECT array::distinct(cluster) as vendors FROM rvtools_data WHERE upload_id = $upload_id GROUP ALL")
             .bind(("upload_id", upload_id))
+            .traced("rvtools_service.get_unique_vendors")
             .await?
             .take(0)?;
 
@@ -365,6 +429,7 @@ impl RvToolsService {
                 FROM rvtools_data WHERE upload_id = $upload_id",
             )
             .bind(("upload_id", upload_id))
+            .traced("rvtools_service.generate_deployment_recommendations")
             .await?
             .take(0)?;
 
@@ -434,6 +499,7 @@ impl RvToolsService {
         let rvtools_data: Vec<RvToolsData> = self.db
             .query("SELECT * FROM rvtools_data WHERE upload_id = $upload_id AND processed_to_pool = false")
             .bind(("upload_id", &upload_thing))
+            .traced("rvtools_service.sync_rvtools_to_hardware_pool")
             .await?
             .take(0)?;
 
@@ -507,6 +573,7 @@ impl RvToolsService {
             .db
             .query("SELECT * FROM hardware_pool WHERE metadata.original_vm_name = $vm_name")
             .bind(("vm_name", &data.vm_name))
+            .traced("rvtools_service.sync_server_to_pool")
             .await?
             .take(0)?;
 
@@ -565,6 +632,7 @@ impl RvToolsService {
                 FROM rvtools_upload {}",
                 condition_str
             ))
+            .traced("rvtools_service.get_rvtools_analytics")
             .await?
             .take(0)?;
 
@@ -582,6 +650,7 @@ impl RvToolsService {
                 ORDER BY date",
                 condition_str
             ))
+            .traced("rvtools_service.get_rvtools_analytics")
             .await?
             .take(0)?;
 
@@ -599,6 +668,7 @@ impl RvToolsService {
                 JOIN rvtools_upload ON rvtools_data.upload_id = rvtools_upload.id {}",
                 &condition_str
             ))
+            .traced("rvtools_service.get_rvtools_analytics")
             .await?
             .take(0)?;
 
@@ -616,6 +686,7 @@ impl RvToolsService {
         // Check for recent uploads
         let recent_uploads: Vec<serde_json::Value> = self.db
             .query("SELECT COUNT() as count FROM rvtools_upload WHERE upload_timestamp >= (time::now() - 7d)")
+            .traced("rvtools_service.generate_analytics_recommendations")
             .await?
             .take(0)?;
 
@@ -658,6 +729,7 @@ pub struct RvToolsProcessingResult {
     pub servers_processed: usize,
     pub servers_added_to_pool: usize,
     pub processing_errors: Vec<RvToolsProcessingError>,
+    pub report: ImportReport,
     pub summary: RvToolsProcessingSummary,
 }
 
@@ -718,3 +790,28 @@ pub struct RvToolsAnalytics {
     pub resource_distribution: serde_json::Value,
     pub recommendations: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_rows_are_validated_with_column_and_reason() {
+        let good = "\"app01\",esx01,4,8192,40960,Linux,poweredOn,CL01,DC1,2";
+        let server = RvToolsService::parse_rvtools_line(good, 2).unwrap();
+        assert_eq!(server.vm_name, "app01");
+        assert_eq!(server.cpu_cores, 4);
+        assert_eq!(server.memory_gb, 8.0);
+
+        let bad_cpus = "app02,esx01,four,8192,40960,Linux,poweredOn,CL01,DC1,2";
+        let error = RvToolsService::parse_rvtools_line(bad_cpus, 3).unwrap_err();
+        assert_eq!((error.sheet.as_str(), error.row), ("CSV", 3));
+        assert_eq!(error.column.as_deref(), Some("CPUs"));
+
+        let short = RvToolsService::parse_rvtools_line("app03,esx01,4", 4).unwrap_err();
+        assert_eq!(short.column, None);
+
+        let unnamed = RvToolsService::parse_rvtools_line(",esx01,4,8192,0,Linux,poweredOn,CL01,DC1,1", 5);
+        assert_eq!(unnamed.unwrap_err().reason, "Missing VM name");
+    }
+}
@@ -0,0 +1,343 @@
+// Archer ITSM - Saved View Service
+// Persists named filter views per user or team and applies them to the
+// ticket and CMDB list queries
+
+use chrono::Utc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::cmdb::ConfigurationItem;
+use crate::models::saved_view::*;
+use crate::models::ticket::Ticket;
+use crate::services::filter_query::{self, FilterError};
+use crate::database::TracedQuery;
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum SavedViewError {
+    #[error("Saved view not found")]
+    NotFound,
+
+    #[error("Only the owner can modify this view")]
+    NotOwner,
+
+    #[error("You are not a member of the team this view is shared with")]
+    NotTeamMember,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error(transparent)]
+    Filter(#[from] FilterError),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for SavedViewError {
+    fn from(e: surrealdb::Error) -> Self {
+        SavedViewError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// SAVED VIEW SERVICE
+// ============================================================================
+
+pub struct SavedViewService {
+    db: Database,
+}
+
+impl SavedViewService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Views the user owns plus those shared with any of their teams
+    pub async fn list_views(
+        &self,
+        user: &AuthenticatedUser,
+        entity: Option<FilterEntity>,
+    ) -> Result<Vec<SavedView>, SavedViewError> {
+        let teams = self.user_team_ids(user).await?;
+
+        let mut views: Vec<SavedView> = self
+            .db
+            .query(
+                r#"
+                SELECT * FROM saved_views
+                WHERE owner_id = $owner
+                    OR (visibility = 'TEAM' AND team_id INSIDE $teams)
+                ORDER BY name ASC
+                "#,
+            )
+            .bind(("owner", user.user_id.clone()))
+            .bind(("teams", teams))
+            .traced("saved_view_service.list_views")
+            .await?
+            .take(0)?;
+
+        if let Some(entity) = entity {
+            views.retain(|v| v.entity == entity);
+        }
+        Ok(views)
+    }
+
+    pub async fn get_view(&self, user: &AuthenticatedUser, id: &str) -> Result<SavedView, SavedViewError> {
+        let view: Option<SavedView> = self.db.select(parse_thing("saved_views", id)).await?;
+        let view = view.ok_or(SavedViewError::NotFound)?;
+
+        if view.owner_id == user.user_id {
+            return Ok(view);
+        }
+        let teams = self.user_team_ids(user).await?;
+        match (&view.visibility, &view.team_id) {
+            (ViewVisibility::Team, Some(team)) if teams.contains(team) => Ok(view),
+            // Don't reveal that a private view exists
+            _ => Err(SavedViewError::NotFound),
+        }
+    }
+
+    pub async fn create_view(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateSavedViewRequest,
+    ) -> Result<SavedView, SavedViewError> {
+        if request.name.trim().is_empty() {
+            return Err(SavedViewError::Validation("View name is required".to_string()));
+        }
+        validate_view(request.entity, &request.filter, request.sort_by.as_deref())?;
+        let team_id = self
+            .resolve_team(user, request.visibility, request.team_id.as_deref())
+            .await?;
+
+        let now = Utc::now();
+        let view = SavedView {
+            id: None,
+            name: request.name.trim().to_string(),
+            description: request.description,
+            entity: request.entity,
+            filter: request.filter,
+            sort_by: request.sort_by,
+            sort_order: request.sort_order,
+            columns: request.columns,
+            visibility: request.visibility,
+            owner_id: user.user_id.clone(),
+            team_id,
+            is_default: request.is_default,
+            tenant_id: user.tenant_id.as_deref().map(|t| Thing::from(("tenants", t))),
+            created_at: now,
+            updated_at: now,
+        };
+
+        if view.is_default {
+            self.clear_default(user, view.entity).await?;
+        }
+
+        let created: Vec<SavedView> = self.db.create("saved_views").content(&view).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| SavedViewError::DatabaseError("Failed to create saved view".to_string()))
+    }
+
+    pub async fn update_view(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: UpdateSavedViewRequest,
+    ) -> Result<SavedView, SavedViewError> {
+        let mut view = self.get_view(user, id).await?;
+        if view.owner_id != user.user_id {
+            return Err(SavedViewError::NotOwner);
+        }
+
+        if let Some(name) = request.name {
+            if name.trim().is_empty() {
+                return Err(SavedViewError::Validation("View name is required".to_string()));
+            }
+            view.name = name.trim().to_string();
+        }
+        if let Some(description) = request.description {
+            view.description = Some(description);
+        }
+        if let Some(filter) = request.filter {
+            view.filter = filter;
+        }
+        if let Some(sort_by) = request.sort_by {
+            view.sort_by = Some(sort_by);
+        }
+        if let Some(sort_order) = request.sort_order {
+            view.sort_order = sort_order;
+        }
+        if let Some(columns) = request.columns {
+            view.columns = columns;
+        }
+        if request.visibility.is_some() || request.team_id.is_some() {
+            let visibility = request.visibility.unwrap_or(view.visibility);
+            let team_id = match request.team_id {
+                Some(team_id) => Some(team_id),
+                None => view.team_id.as_ref().map(|t| t.to_string()),
+            };
+            view.team_id = self.resolve_team(user, visibility, team_id.as_deref()).await?;
+            view.visibility = visibility;
+        }
+        validate_view(view.entity, &view.filter, view.sort_by.as_deref())?;
+
+        if let Some(is_default) = request.is_default {
+            if is_default && !view.is_default {
+                self.clear_default(user, view.entity).await?;
+            }
+            view.is_default = is_default;
+        }
+        view.updated_at = Utc::now();
+
+        let thing = view.id.clone().ok_or(SavedViewError::NotFound)?;
+        let updated: Option<SavedView> = self.db.update(thing).content(&view).await?;
+        updated.ok_or(SavedViewError::NotFound)
+    }
+
+    pub async fn delete_view(&self, user: &AuthenticatedUser, id: &str) -> Result<(), SavedViewError> {
+        let view = self.get_view(user, id).await?;
+        if view.owner_id != user.user_id {
+            return Err(SavedViewError::NotOwner);
+        }
+        let thing = view.id.ok_or(SavedViewError::NotFound)?;
+        let _: Option<SavedView> = self.db.delete(thing).await?;
+        Ok(())
+    }
+
+    /// Run a view's filter and sort, returning one page of matching records
+    pub async fn apply_view(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<(SavedView, FilterQueryResponse<serde_json::Value>), SavedViewError> {
+        let view = self.get_view(user, id).await?;
+        let request = FilterQueryRequest {
+            filter: Some(view.filter.clone()),
+            sort_by: view.sort_by.clone(),
+            sort_order: view.sort_order,
+            page,
+            page_size,
+        };
+        let tenant = user.tenant_id.as_deref();
+
+        let results = match view.entity {
+            FilterEntity::Tickets => {
+                let r = filter_query::run_filter_query::<Ticket>(&self.db, view.entity, &request, tenant).await?;
+                to_json_page(r)?
+            }
+            FilterEntity::ConfigurationItems => {
+                let r = filter_query::run_filter_query::<ConfigurationItem>(&self.db, view.entity, &request, tenant)
+                    .await?;
+                to_json_page(r)?
+            }
+        };
+        Ok((view, results))
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn user_team_ids(&self, user: &AuthenticatedUser) -> Result<Vec<Thing>, SavedViewError> {
+        let teams: Vec<Thing> = self
+            .db
+            .query("SELECT VALUE team_id FROM team_memberships WHERE user_id = $user")
+            .bind(("user", parse_thing("users", &user.user_id)))
+            .traced("saved_view_service.user_team_ids")
+            .await?
+            .take(0)?;
+        Ok(teams)
+    }
+
+    /// Team views must name a team the creator belongs to; private views carry no team
+    async fn resolve_team(
+        &self,
+        user: &AuthenticatedUser,
+        visibility: ViewVisibility,
+        team_id: Option<&str>,
+    ) -> Result<Option<Thing>, SavedViewError> {
+        match visibility {
+            ViewVisibility::Private => Ok(None),
+            ViewVisibility::Team => {
+                let team = team_id
+                    .map(|t| parse_thing("teams", t))
+                    .ok_or_else(|| SavedViewError::Validation("team_id is required for team views".to_string()))?;
+                if !self.user_team_ids(user).await?.contains(&team) {
+                    return Err(SavedViewError::NotTeamMember);
+                }
+                Ok(Some(team))
+            }
+        }
+    }
+
+    /// Only one default view per user and entity
+    async fn clear_default(&self, user: &AuthenticatedUser, entity: FilterEntity) -> Result<(), SavedViewError> {
+        self.db
+            .query("UPDATE saved_views SET is_default = false WHERE owner_id = $owner AND entity = $entity")
+            .bind(("owner", user.user_id.clone()))
+            .bind(("entity", entity))
+            .traced("saved_view_service.clear_default")
+            .await?;
+        Ok(())
+    }
+}
+
+/// Compile the filter and sort once so invalid views are rejected on save
+fn validate_view(entity: FilterEntity, filter: &FilterGroup, sort_by: Option<&str>) -> Result<(), SavedViewError> {
+    filter_query::compile(entity, filter)?;
+    filter_query::sort_clause(entity, sort_by, SortOrder::Desc)?;
+    Ok(())
+}
+
+fn to_json_page<T: serde::Serialize>(
+    page: FilterQueryResponse<T>,
+) -> Result<FilterQueryResponse<serde_json::Value>, SavedViewError> {
+    let items = page
+        .items
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| SavedViewError::DatabaseError(e.to_string()))?;
+    Ok(FilterQueryResponse {
+        items,
+        total: page.total,
+        page: page.page,
+        page_size: page.page_size,
+    })
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_view_rejects_bad_filters() {
+        let ok: FilterGroup = serde_json::from_value(json!({ "conditions": [
+            { "field": "criticality", "operator": "in", "value": ["HIGH", "CRITICAL"] }
+        ]}))
+        .unwrap();
+        assert!(validate_view(FilterEntity::ConfigurationItems, &ok, Some("name")).is_ok());
+        // CI field on a ticket view
+        assert!(validate_view(FilterEntity::Tickets, &ok, None).is_err());
+        assert!(validate_view(FilterEntity::ConfigurationItems, &ok, Some("bogus")).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+//! Workspace Search Service
+//!
+//! Full-text search over the BM25 indexes defined by `SearchMigrations`:
+//! - One query per entity type, matching the title and body fields
+//! - Scores summed across both fields and merged into one ranked list
+//! - Highlighted excerpts for the command palette
+//! - Tenant scoping for ITSM records
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::models::search::*;
+
+pub const DEFAULT_LIMIT: usize = 20;
+pub const MAX_LIMIT: usize = 100;
+/// Words kept either side of the first highlighted term
+const EXCERPT_WORDS: usize = 12;
+
+/// Where an entity type lives and which fields are searched
+struct SearchTarget {
+    table: &'static str,
+    title_field: &'static str,
+    body_field: &'static str,
+    tenant_scoped: bool,
+}
+
+fn target(entity_type: SearchEntityType) -> SearchTarget {
+    match entity_type {
+        SearchEntityType::Ticket => SearchTarget {
+            table: "ticket",
+            title_field: "title",
+            body_field: "description",
+            tenant_scoped: true,
+        },
+        SearchEntityType::KbArticle => SearchTarget {
+            table: "kb_articles",
+            title_field: "title",
+            body_field: "content",
+            tenant_scoped: true,
+        },
+        SearchEntityType::ConfigurationItem => SearchTarget {
+            table: "configuration_items",
+            title_field: "name",
+            body_field: "description",
+            tenant_scoped: true,
+        },
+        SearchEntityType::Project => SearchTarget {
+            table: "migration_wizard_project",
+            title_field: "name",
+            body_field: "description",
+            tenant_scoped: false,
+        },
+        SearchEntityType::Document => SearchTarget {
+            table: "generated_document",
+            title_field: "document_name",
+            body_field: "document_type",
+            tenant_scoped: false,
+        },
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawHit {
+    id: Thing,
+    title: Option<String>,
+    score: Option<f64>,
+    title_highlight: Option<serde_json::Value>,
+    body_highlight: Option<serde_json::Value>,
+    /// Owning project, for generated documents
+    parent: Option<Thing>,
+}
+
+pub struct SearchService {
+    db: Database,
+}
+
+impl SearchService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Search the given entity types and return one ranked list
+    pub async fn search(
+        &self,
+        query: &str,
+        types: &[SearchEntityType],
+        tenant_id: Option<&str>,
+        limit: usize,
+    ) -> Result<SearchResponse> {
+        let query = query.trim();
+        if query.is_empty() {
+            anyhow::bail!("Search query must not be empty");
+        }
+        let limit = limit.clamp(1, MAX_LIMIT);
+
+        let mut hits = Vec::new();
+        for entity_type in types {
+            hits.extend(
+                self.search_type(*entity_type, query, tenant_id, limit)
+                    .await
+                    .with_context(|| format!("Failed to search {}", entity_type.as_str()))?,
+            );
+        }
+
+        let (hits, counts) = rank_hits(hits, limit);
+        Ok(SearchResponse {
+            query: query.to_string(),
+            total: counts.values().sum(),
+            hits,
+            counts,
+        })
+    }
+
+    async fn search_type(
+        &self,
+        entity_type: SearchEntityType,
+        query: &str,
+        tenant_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let target = target(entity_type);
+        let tenant = if target.tenant_scoped { tenant_id } else { None };
+
+        let sql = format!(
+            r#"
+            SELECT
+                id,
+                {title} AS title,
+                math::sum([search::score(0), search::score(1)]) AS score,
+                search::highlight('<mark>', '</mark>', 0) AS title_highlight,
+                search::highlight('<mark>', '</mark>', 1) AS body_highlight,
+                {parent} AS parent
+            FROM {table}
+            WHERE ({title} @0@ $query OR {body} @1@ $query){tenant_filter}
+            ORDER BY score DESC
+            LIMIT $limit
+            "#,
+            title = target.title_field,
+            body = target.body_field,
+            table = target.table,
+            parent = if entity_type == SearchEntityType::Document { "project_id" } else { "NONE" },
+            tenant_filter = if tenant.is_some() { " AND tenant_id = $tenant" } else { "" },
+        );
+
+        let mut request = self
+            .db
+            .query(sql)
+            .bind(("query", query.to_string()))
+            .bind(("limit", limit));
+        if let Some(tenant) = tenant {
+            request = request.bind(("tenant", Thing::from(("tenants", tenant))));
+        }
+        let rows: Vec<RawHit> = request.await?.take(0)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let id = row.id.id.to_string();
+                let snippet = highlighted_text(&row.body_highlight)
+                    .or_else(|| highlighted_text(&row.title_highlight))
+                    .map(|text| excerpt(&text, EXCERPT_WORDS));
+                SearchHit {
+                    entity_type,
+                    url: entity_url(entity_type, &id, row.parent.as_ref()),
+                    title: row.title.unwrap_or_else(|| id.clone()),
+                    id,
+                    snippet,
+                    score: row.score.unwrap_or(0.0),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Frontend route for a hit
+fn entity_url(entity_type: SearchEntityType, id: &str, parent: Option<&Thing>) -> String {
+    match entity_type {
+        SearchEntityType::Ticket => format!("/app/service-desk/ticket/{}", id),
+        SearchEntityType::KbArticle => format!("/app/knowledge-base/{}", id),
+        SearchEntityType::ConfigurationItem => format!("/app/inventory/asset/{}", id),
+        SearchEntityType::Project => format!("/app/projects/{}", id),
+        SearchEntityType::Document => match parent {
+            Some(project) => format!("/app/projects/{}", project.id),
+            None => "/app/document-templates".to_string(),
+        },
+    }
+}
+
+/// Highlight output that actually contains a match (string or array of strings)
+fn highlighted_text(value: &Option<serde_json::Value>) -> Option<String> {
+    let text = match value.as_ref()? {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(" … "),
+        _ => return None,
+    };
+    text.contains("<mark>").then_some(text)
+}
+
+/// Trim highlighted text to a window of words around the first match
+fn excerpt(text: &str, radius: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let first_match = words.iter().position(|w| w.contains("<mark>")).unwrap_or(0);
+    let start = first_match.saturating_sub(radius);
+    let end = (first_match + radius + 1).min(words.len());
+
+    let mut out = words[start..end].join(" ");
+    if start > 0 {
+        out.insert_str(0, "… ");
+    }
+    if end < words.len() {
+        out.push_str(" …");
+    }
+    out
+}
+
+/// Merge per-type hits into one list ordered by score, keeping per-type counts
+fn rank_hits(
+    mut hits: Vec<SearchHit>,
+    limit: usize,
+) -> (Vec<SearchHit>, HashMap<SearchEntityType, usize>) {
+    let mut counts = HashMap::new();
+    for hit in &hits {
+        *counts.entry(hit.entity_type).or_insert(0) += 1;
+    }
+
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    hits.truncate(limit);
+    (hits, counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(entity_type: SearchEntityType, title: &str, score: f64) -> SearchHit {
+        SearchHit {
+            entity_type,
+            id: title.to_lowercase(),
+            title: title.to_string(),
+            snippet: None,
+            score,
+            url: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_rank_hits_merges_types_by_score() {
+        let hits = vec![
+            hit(SearchEntityType::Ticket, "VPN outage", 1.2),
+            hit(SearchEntityType::KbArticle, "Reset VPN token", 3.4),
+            hit(SearchEntityType::Ticket, "VPN slow", 0.4),
+            hit(SearchEntityType::Project, "VPN migration", 2.0),
+        ];
+
+        let (ranked, counts) = rank_hits(hits, 3);
+        let titles: Vec<_> = ranked.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["Reset VPN token", "VPN migration", "VPN outage"]);
+        assert_eq!(counts[&SearchEntityType::Ticket], 2);
+        assert_eq!(counts.values().sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn test_excerpt_centres_on_first_match() {
+        let text = "one two three four five six <mark>vpn</mark> seven eight nine ten";
+        assert_eq!(excerpt(text, 2), "… five six <mark>vpn</mark> seven eight …");
+        assert_eq!(excerpt("<mark>vpn</mark> down", 5), "<mark>vpn</mark> down");
+        assert!(highlighted_text(&Some(serde_json::json!("no match here"))).is_none());
+    }
+}
@@ -0,0 +1,537 @@
+//! Settings Service
+//!
+//! Resolves layered settings: built-in defaults ← system ← tenant ← project
+//! ← user. Every override lives in `setting_override`, one row per layer and
+//! key, and every change to one is recorded in `setting_change`.
+//!
+//! The legacy `global_settings:default` record still feeds the system layer
+//! for the keys it covers, so `PATCH /settings` keeps working; a system
+//! override set here takes precedence over it.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::database::{Database, TracedQuery};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::project_access::ProjectRole;
+use crate::models::settings::*;
+use crate::services::project_access_service::{ProjectAccessError, ProjectAccessService};
+
+const TABLE: &str = "setting_override";
+const CHANGE_TABLE: &str = "setting_change";
+const DEFAULT_CHANGE_LIMIT: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("Unknown setting: {0}")]
+    UnknownKey(String),
+
+    #[error("Access denied: {0}")]
+    Forbidden(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("No override is set for this setting at that scope")]
+    NotFound,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for SettingsError {
+    fn from(e: surrealdb::Error) -> Self {
+        SettingsError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<ProjectAccessError> for SettingsError {
+    fn from(e: ProjectAccessError) -> Self {
+        match e {
+            ProjectAccessError::Forbidden(message) => SettingsError::Forbidden(message),
+            other => SettingsError::DatabaseError(other.to_string()),
+        }
+    }
+}
+
+pub struct SettingsService {
+    db: Arc<Database>,
+}
+
+impl SettingsService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Every key with its type, default and deepest layer
+    pub fn definitions() -> Vec<SettingDefinition> {
+        SettingKey::ALL.iter().map(|key| key.definition()).collect()
+    }
+
+    pub fn parse_key(key: &str) -> Result<SettingKey, SettingsError> {
+        SettingKey::parse(key).ok_or_else(|| SettingsError::UnknownKey(key.to_string()))
+    }
+
+    // ========================================================================
+    // RESOLUTION
+    // ========================================================================
+
+    /// Layers that apply to the caller, optionally within a project they can see
+    pub async fn context_for(
+        &self,
+        user: &AuthenticatedUser,
+        project_id: Option<&str>,
+    ) -> Result<SettingContext, SettingsError> {
+        if let Some(project_id) = project_id {
+            ProjectAccessService::new(self.db.clone())
+                .authorize(user, project_id, ProjectRole::Viewer)
+                .await?;
+        }
+        Ok(SettingContext {
+            tenant_id: user.tenant_id.clone(),
+            project_id: project_id.map(str::to_string),
+            user_id: Some(user.user_id.clone()),
+        })
+    }
+
+    pub async fn resolve(&self, key: SettingKey, context: &SettingContext) -> Result<ResolvedSetting, SettingsError> {
+        let overrides = self.overrides_for(context, Some(key)).await?;
+        let legacy = self.legacy_settings().await?;
+        Ok(resolve_layers(key, context, &overrides, legacy.as_ref()))
+    }
+
+    pub async fn resolve_all(&self, context: &SettingContext) -> Result<Vec<ResolvedSetting>, SettingsError> {
+        let overrides = self.overrides_for(context, None).await?;
+        let legacy = self.legacy_settings().await?;
+        Ok(SettingKey::ALL
+            .iter()
+            .map(|key| resolve_layers(*key, context, &overrides, legacy.as_ref()))
+            .collect())
+    }
+
+    /// Effective value as a number, for services that size or schedule with it
+    pub async fn number(&self, key: SettingKey, context: &SettingContext) -> Result<f64, SettingsError> {
+        let resolved = self.resolve(key, context).await?;
+        Ok(resolved
+            .value
+            .as_f64()
+            .or_else(|| key.definition().default.as_f64())
+            .unwrap_or_default())
+    }
+
+    async fn overrides_for(
+        &self,
+        context: &SettingContext,
+        key: Option<SettingKey>,
+    ) -> Result<Vec<SettingOverride>, SettingsError> {
+        let key_filter = if key.is_some() { "key = $key AND " } else { "" };
+        let sql = format!(
+            "SELECT * FROM {TABLE} WHERE {key_filter}(scope = 'system' \
+             OR (scope = 'tenant' AND scope_id = $tenant) \
+             OR (scope = 'project' AND scope_id = $project) \
+             OR (scope = 'user' AND scope_id = $user))"
+        );
+        Ok(self
+            .db
+            .query(sql)
+            .bind(("key", key))
+            .bind(("tenant", context.tenant_id.clone()))
+            .bind(("project", context.project_id.clone()))
+            .bind(("user", context.user_id.clone()))
+            .traced("settings_service.overrides_for")
+            .await?
+            .take(0)?)
+    }
+
+    async fn legacy_settings(&self) -> Result<Option<GlobalSettings>, SettingsError> {
+        Ok(self.db.select(("global_settings", "default")).await?)
+    }
+
+    // ========================================================================
+    // OVERRIDES
+    // ========================================================================
+
+    /// Overrides stored at one layer
+    pub async fn list_overrides(
+        &self,
+        user: &AuthenticatedUser,
+        scope: SettingScope,
+        scope_id: Option<String>,
+    ) -> Result<Vec<SettingOverride>, SettingsError> {
+        let scope_id = self.authorize_scope(user, scope, scope_id, false).await?;
+        Ok(self
+            .db
+            .query(format!("SELECT * FROM {TABLE} WHERE scope = $scope AND scope_id = $scope_id ORDER BY key ASC"))
+            .bind(("scope", scope))
+            .bind(("scope_id", scope_id))
+            .traced("settings_service.list_overrides")
+            .await?
+            .take(0)?)
+    }
+
+    /// Set a key at one layer, recording the change
+    pub async fn set_override(
+        &self,
+        user: &AuthenticatedUser,
+        key: SettingKey,
+        request: SetSettingOverrideRequest,
+    ) -> Result<SettingOverride, SettingsError> {
+        validate_override(key, request.scope, &request.value)?;
+        let scope_id = self.authorize_scope(user, request.scope, request.scope_id, true).await?;
+        let existing = self.find_override(key, request.scope, &scope_id).await?;
+
+        let stored = SettingOverride {
+            id: None,
+            scope: request.scope,
+            scope_id: scope_id.clone(),
+            key,
+            value: request.value.clone(),
+            updated_by: user.user_id.clone(),
+            updated_at: Utc::now(),
+        };
+        let saved: Option<SettingOverride> = match existing.as_ref().and_then(|o| o.id.clone()) {
+            Some(id) => self.db.update(id).content(stored).await?,
+            None => {
+                let created: Vec<SettingOverride> = self.db.create(TABLE).content(stored).await?;
+                created.into_iter().next()
+            }
+        };
+        let saved = saved.ok_or_else(|| SettingsError::DatabaseError("Failed to save setting".to_string()))?;
+
+        let old_value = existing.map(|o| o.value);
+        if old_value.as_ref() != Some(&request.value) {
+            self.record_change(user, key, request.scope, scope_id, old_value, Some(request.value))
+                .await?;
+        }
+        Ok(saved)
+    }
+
+    /// Remove a key from one layer so the layer below applies again
+    pub async fn clear_override(
+        &self,
+        user: &AuthenticatedUser,
+        key: SettingKey,
+        scope: SettingScope,
+        scope_id: Option<String>,
+    ) -> Result<(), SettingsError> {
+        let scope_id = self.authorize_scope(user, scope, scope_id, true).await?;
+        let existing = self
+            .find_override(key, scope, &scope_id)
+            .await?
+            .ok_or(SettingsError::NotFound)?;
+        if let Some(id) = existing.id.clone() {
+            let _: Option<SettingOverride> = self.db.delete(id).await?;
+        }
+        self.record_change(user, key, scope, scope_id, Some(existing.value), None)
+            .await
+    }
+
+    async fn find_override(
+        &self,
+        key: SettingKey,
+        scope: SettingScope,
+        scope_id: &str,
+    ) -> Result<Option<SettingOverride>, SettingsError> {
+        let mut found: Vec<SettingOverride> = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE} WHERE key = $key AND scope = $scope AND scope_id = $scope_id LIMIT 1"
+            ))
+            .bind(("key", key))
+            .bind(("scope", scope))
+            .bind(("scope_id", scope_id.to_string()))
+            .traced("settings_service.find_override")
+            .await?
+            .take(0)?;
+        Ok(found.pop())
+    }
+
+    // ========================================================================
+    // AUDIT
+    // ========================================================================
+
+    async fn record_change(
+        &self,
+        user: &AuthenticatedUser,
+        key: SettingKey,
+        scope: SettingScope,
+        scope_id: String,
+        old_value: Option<Value>,
+        new_value: Option<Value>,
+    ) -> Result<(), SettingsError> {
+        let change = SettingChange {
+            id: None,
+            scope,
+            scope_id,
+            key,
+            old_value,
+            new_value,
+            changed_by: user.user_id.clone(),
+            changed_at: Utc::now(),
+        };
+        let _: Vec<SettingChange> = self.db.create(CHANGE_TABLE).content(change).await?;
+        Ok(())
+    }
+
+    /// Change history, newest first. Without a scope, only settings managers
+    /// see every layer; everyone else sees changes to their own user layer.
+    pub async fn changes(
+        &self,
+        user: &AuthenticatedUser,
+        query: SettingChangeQuery,
+    ) -> Result<Vec<SettingChange>, SettingsError> {
+        let key = query.key.as_deref().map(Self::parse_key).transpose()?;
+        let (scope, scope_id) = match query.scope {
+            Some(scope) => {
+                let scope_id = self.authorize_scope(user, scope, query.scope_id, false).await?;
+                (Some(scope), Some(scope_id))
+            }
+            None if can_manage(user) => (None, None),
+            None => (Some(SettingScope::User), Some(user.user_id.clone())),
+        };
+
+        let mut conditions = Vec::new();
+        if key.is_some() {
+            conditions.push("key = $key");
+        }
+        if scope.is_some() {
+            conditions.push("scope = $scope AND scope_id = $scope_id");
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let limit = query.limit.unwrap_or(DEFAULT_CHANGE_LIMIT).clamp(1, 1000);
+
+        Ok(self
+            .db
+            .query(format!(
+                "SELECT * FROM {CHANGE_TABLE} {filter} ORDER BY changed_at DESC LIMIT {limit}"
+            ))
+            .bind(("key", key))
+            .bind(("scope", scope))
+            .bind(("scope_id", scope_id))
+            .traced("settings_service.changes")
+            .await?
+            .take(0)?)
+    }
+
+    // ========================================================================
+    // ACCESS
+    // ========================================================================
+
+    /// Check the caller may read or write a layer and fill in its id.
+    ///
+    /// - system: anyone reads, settings managers write
+    /// - tenant: members of the tenant read, its settings managers write;
+    ///   only super admins reach other tenants
+    /// - project: viewers read, editors write
+    /// - user: each user owns their own layer; settings managers reach others
+    async fn authorize_scope(
+        &self,
+        user: &AuthenticatedUser,
+        scope: SettingScope,
+        scope_id: Option<String>,
+        write: bool,
+    ) -> Result<String, SettingsError> {
+        let scope_id = scope_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty());
+        match scope {
+            SettingScope::System => {
+                if write && !can_manage(user) {
+                    return Err(SettingsError::Forbidden("settings:manage is required".to_string()));
+                }
+                Ok(String::new())
+            }
+            SettingScope::Tenant => {
+                let tenant_id = scope_id
+                    .or_else(|| user.tenant_id.clone())
+                    .ok_or_else(|| SettingsError::Validation("scope_id is required for tenant settings".to_string()))?;
+                if user.tenant_id.as_deref() != Some(tenant_id.as_str()) && !user.has_role("super_admin") {
+                    return Err(SettingsError::Forbidden("Not a member of this tenant".to_string()));
+                }
+                if write && !can_manage(user) {
+                    return Err(SettingsError::Forbidden("settings:manage is required".to_string()));
+                }
+                Ok(tenant_id)
+            }
+            SettingScope::Project => {
+                let project_id = scope_id
+                    .ok_or_else(|| SettingsError::Validation("scope_id is required for project settings".to_string()))?;
+                let required = if write { ProjectRole::Editor } else { ProjectRole::Viewer };
+                ProjectAccessService::new(self.db.clone())
+                    .authorize(user, &project_id, required)
+                    .await?;
+                Ok(project_id)
+            }
+            SettingScope::User => {
+                let user_id = scope_id.unwrap_or_else(|| user.user_id.clone());
+                if user_id != user.user_id && !can_manage(user) {
+                    return Err(SettingsError::Forbidden("Cannot access another user's settings".to_string()));
+                }
+                Ok(user_id)
+            }
+        }
+    }
+}
+
+fn can_manage(user: &AuthenticatedUser) -> bool {
+    user.has_permission("settings:manage") || user.has_role("admin")
+}
+
+/// Check a value may be stored for a key at a layer
+pub fn validate_override(key: SettingKey, scope: SettingScope, value: &Value) -> Result<(), SettingsError> {
+    let definition = key.definition();
+    if scope > definition.max_scope {
+        return Err(SettingsError::Validation(format!(
+            "{} cannot be set at {} scope; the most specific scope is {}",
+            key.as_str(),
+            scope.as_str(),
+            definition.max_scope.as_str()
+        )));
+    }
+    definition
+        .value_type
+        .validate(value)
+        .map_err(|message| SettingsError::Validation(format!("{}: {}", key.as_str(), message)))
+}
+
+/// Pick the effective value of a key from the overrides that apply to a
+/// context. The most specific layer with a value wins; layers deeper than the
+/// key's `max_scope` are ignored even if a row exists.
+pub fn resolve_layers(
+    key: SettingKey,
+    context: &SettingContext,
+    overrides: &[SettingOverride],
+    legacy: Option<&GlobalSettings>,
+) -> ResolvedSetting {
+    let definition = key.definition();
+    let mut resolved = ResolvedSetting {
+        key,
+        value: definition.default.clone(),
+        source: SettingSource::Default,
+        source_id: None,
+        layers: Vec::new(),
+    };
+
+    for scope in [
+        SettingScope::System,
+        SettingScope::Tenant,
+        SettingScope::Project,
+        SettingScope::User,
+    ] {
+        if scope > definition.max_scope {
+            break;
+        }
+        let Some(scope_id) = context.id_for(scope) else {
+            continue;
+        };
+        let stored = overrides
+            .iter()
+            .find(|o| o.key == key && o.scope == scope && o.scope_id == scope_id)
+            .map(|o| o.value.clone());
+        let value = match scope {
+            SettingScope::System => stored.or_else(|| legacy.and_then(|global| key.legacy_value(global))),
+            _ => stored,
+        };
+        let scope_id = (scope != SettingScope::System).then(|| scope_id.to_string());
+        if let Some(value) = value.as_ref() {
+            resolved.value = value.clone();
+            resolved.source = scope.into();
+            resolved.source_id = scope_id.clone();
+        }
+        resolved.layers.push(SettingLayer { scope, scope_id, value });
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn stored(scope: SettingScope, scope_id: &str, key: SettingKey, value: Value) -> SettingOverride {
+        SettingOverride {
+            id: None,
+            scope,
+            scope_id: scope_id.to_string(),
+            key,
+            value,
+            updated_by: "users:admin".to_string(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn context() -> SettingContext {
+        SettingContext {
+            tenant_id: Some("tenants:acme".to_string()),
+            project_id: Some("p1".to_string()),
+            user_id: Some("users:alice".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_most_specific_layer_wins() {
+        let overrides = vec![
+            stored(SettingScope::System, "", SettingKey::Theme, json!("light")),
+            stored(SettingScope::Tenant, "tenants:acme", SettingKey::Theme, json!("dark")),
+            stored(SettingScope::User, "users:alice", SettingKey::Theme, json!("system")),
+            stored(SettingScope::User, "users:bob", SettingKey::Theme, json!("light")),
+        ];
+        let resolved = resolve_layers(SettingKey::Theme, &context(), &overrides, None);
+
+        assert_eq!(resolved.value, json!("system"));
+        assert_eq!(resolved.source, SettingSource::User);
+        assert_eq!(resolved.source_id.as_deref(), Some("users:alice"));
+        assert_eq!(resolved.layers.len(), 4);
+        assert_eq!(resolved.layers[2].value, None);
+    }
+
+    #[test]
+    fn test_falls_back_to_legacy_global_settings_then_default() {
+        let mut global = GlobalSettings::default();
+        global.default_overcommit_ratios.cpu_ratio = 6.0;
+
+        let resolved = resolve_layers(SettingKey::CpuOvercommitRatio, &context(), &[], Some(&global));
+        assert_eq!(resolved.value, json!(6.0));
+        assert_eq!(resolved.source, SettingSource::System);
+
+        let resolved = resolve_layers(SettingKey::TargetUtilizationPercent, &context(), &[], Some(&global));
+        assert_eq!(resolved.value, json!(80.0));
+        assert_eq!(resolved.source, SettingSource::Default);
+    }
+
+    #[test]
+    fn test_layers_deeper_than_max_scope_are_ignored() {
+        let overrides = vec![
+            stored(SettingScope::Project, "p1", SettingKey::CpuOvercommitRatio, json!(3.0)),
+            stored(SettingScope::User, "users:alice", SettingKey::CpuOvercommitRatio, json!(8.0)),
+        ];
+        let resolved = resolve_layers(SettingKey::CpuOvercommitRatio, &context(), &overrides, None);
+
+        assert_eq!(resolved.value, json!(3.0));
+        assert_eq!(resolved.source, SettingSource::Project);
+        assert!(resolved.layers.iter().all(|l| l.scope != SettingScope::User));
+    }
+
+    #[test]
+    fn test_validate_override() {
+        assert!(validate_override(SettingKey::CpuOvercommitRatio, SettingScope::Tenant, &json!(4)).is_ok());
+        assert!(validate_override(SettingKey::CpuOvercommitRatio, SettingScope::Tenant, &json!(0.5)).is_err());
+        assert!(validate_override(SettingKey::CpuOvercommitRatio, SettingScope::User, &json!(4)).is_err());
+        assert!(validate_override(SettingKey::Theme, SettingScope::User, &json!("dark")).is_ok());
+        assert!(validate_override(SettingKey::Theme, SettingScope::User, &json!("neon")).is_err());
+        assert!(validate_override(SettingKey::AnonymizeExports, SettingScope::System, &json!("yes")).is_err());
+    }
+
+    #[test]
+    fn test_keys_round_trip_through_their_names() {
+        for key in SettingKey::ALL {
+            assert_eq!(SettingKey::parse(key.as_str()), Some(key));
+            assert_eq!(serde_json::to_value(key).unwrap(), json!(key.as_str()));
+        }
+    }
+}
@@ -9,6 +9,7 @@ use crate::database::Database;
 use crate::models::ticket::{
     BusinessHours, EscalationRule, SlaPolicy, SlaStatus, Ticket, TicketPriority, TicketType,
 };
+use crate::database::TracedQuery;
 
 // ============================================================================
 // SLA SERVICE
@@ -41,6 +42,7 @@ impl SlaService {
         let policy: Option<SlaPolicy> = self
             .db
             .query(&format!("SELECT * FROM sla_policies:{}", id))
+            .traced("sla_service.get_sla_policy")
             .await?
             .take(0)?;
         Ok(policy)
@@ -66,7 +68,7 @@ impl SlaService {
             conditions.join(" AND ")
         );
 
-        let policies: Vec<SlaPolicy> = self.db.query(&query).await?.take(0)?;
+        let policies: Vec<SlaPolicy> = self.db.query(&query).traced("sla_service.find_applicable_policy").await?.take(0)?;
 
         // Filter in-memory by priority and type (SurrealDB array matching is tricky)
         for policy in policies {
@@ -94,7 +96,7 @@ impl SlaService {
             "SELECT * FROM sla_policies ORDER BY name".to_string()
         };
 
-        let policies: Vec<SlaPolicy> = self.db.query(&query).await?.take(0)?;
+        let policies: Vec<SlaPolicy> = self.db.query(&query).traced("sla_service.list_sla_policies").await?.take(0)?;
         Ok(policies)
     }
 
@@ -214,7 +216,7 @@ impl SlaService {
             ORDER BY resolution_due ASC
         "#;
 
-        let tickets: Vec<Ticket> = self.db.query(query).await?.take(0)?;
+        let tickets: Vec<Ticket> = self.db.query(query).traced("sla_service.check_all_sla_breaches").await?.take(0)?;
 
         for ticket in tickets {
             // Check response SLA
@@ -326,6 +328,7 @@ impl SlaService {
         let hours: Option<BusinessHours> = self
             .db
             .query(&format!("SELECT * FROM business_hours:{}", id))
+            .traced("sla_service.get_business_hours")
             .await?
             .take(0)?;
         Ok(hours)
@@ -336,6 +339,7 @@ impl SlaService {
         let hours: Vec<BusinessHours> = self
             .db
             .query("SELECT * FROM business_hours WHERE is_default = true LIMIT 1")
+            .traced("sla_service.get_default_business_hours")
             .await?
             .take(0)?;
         Ok(hours.into_iter().next())
@@ -351,6 +355,7 @@ impl SlaService {
         let existing: Vec<SlaPolicy> = self
             .db
             .query("SELECT * FROM sla_policies WHERE name = 'P1 - Critical' LIMIT 1")
+            .traced("sla_service.seed_default_policies")
             .await?
             .take(0)?;
 
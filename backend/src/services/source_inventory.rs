@@ -0,0 +1,495 @@
+//! Source Inventory Parsing
+//!
+//! Reads the vHost, vCluster, vDatastore, vRP, vSnapshot and vTools sheets
+//! of an RVTools export into a [`SourceInventory`], and rolls it up with the
+//! project's VMs into per-cluster and per-storage-type summaries for sizing,
+//! placement and the HLD. Every sheet is optional; older exports without
+//! them still import.
+
+use calamine::{DataType, Reader, Xlsx};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Seek};
+
+use crate::models::migration_wizard_models::*;
+use core_engine::units::Bytes;
+
+/// Cluster name used for hosts, VMs and datastores without one
+const UNCLUSTERED: &str = "Unclustered";
+
+/// Parse every inventory sheet the workbook has
+pub fn parse_inventory<R: Read + Seek>(workbook: &mut Xlsx<R>) -> SourceInventory {
+    SourceInventory {
+        hosts: read_sheet(workbook, "vHost", parse_host),
+        clusters: read_sheet(workbook, "vCluster", parse_cluster),
+        datastores: read_sheet(workbook, "vDatastore", parse_datastore),
+        resource_pools: read_sheet(workbook, "vRP", parse_resource_pool),
+        snapshots: read_sheet(workbook, "vSnapshot", parse_snapshot),
+        tools: read_sheet(workbook, "vTools", parse_tools),
+    }
+}
+
+/// Rows of a sheet named `vX` or `tabvX`; nothing when the export lacks it
+fn read_sheet<R: Read + Seek, T>(workbook: &mut Xlsx<R>, sheet: &str, parse: fn(&Row) -> Option<T>) -> Vec<T> {
+    let range = [sheet.to_string(), format!("tab{}", sheet)]
+        .iter()
+        .find_map(|name| workbook.worksheet_range(name).and_then(|r| r.ok()));
+    match range {
+        Some(range) => parse_rows(range.rows(), parse),
+        None => {
+            tracing::debug!("RVTools export has no {} sheet", sheet);
+            Vec::new()
+        }
+    }
+}
+
+/// First row is the header; rows the parser rejects are skipped
+fn parse_rows<'a, T>(mut rows: impl Iterator<Item = &'a [DataType]>, parse: fn(&Row) -> Option<T>) -> Vec<T> {
+    let Some(header) = rows.next() else {
+        return Vec::new();
+    };
+    let columns: HashMap<String, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| (cell.to_string().trim().to_ascii_lowercase(), i))
+        .collect();
+    rows.filter_map(|cells| parse(&Row { columns: &columns, cells })).collect()
+}
+
+/// One sheet row, read by column name. Each accessor takes the names a
+/// column has had across RVTools versions.
+struct Row<'a> {
+    columns: &'a HashMap<String, usize>,
+    cells: &'a [DataType],
+}
+
+impl Row<'_> {
+    fn text(&self, names: &[&str]) -> Option<String> {
+        names.iter().find_map(|name| {
+            let cell = self.cells.get(*self.columns.get(&name.to_ascii_lowercase())?)?;
+            let value = cell.to_string().trim().to_string();
+            (!value.is_empty()).then_some(value)
+        })
+    }
+
+    fn number(&self, names: &[&str]) -> Option<f64> {
+        self.text(names)?.replace(',', "").parse::<f64>().ok().filter(|n| n.is_finite())
+    }
+
+    fn int(&self, names: &[&str]) -> Option<i64> {
+        self.number(names).map(|n| n.round() as i64)
+    }
+
+    /// Excel date cells, or text in the formats RVTools writes per locale
+    fn date(&self, names: &[&str]) -> Option<DateTime<Utc>> {
+        names.iter().find_map(|name| {
+            match self.cells.get(*self.columns.get(&name.to_ascii_lowercase())?)? {
+                DataType::DateTime(serial) => excel_date(*serial),
+                cell => parse_date(cell.to_string().trim()),
+            }
+        })
+    }
+
+    fn flag(&self, names: &[&str]) -> Option<bool> {
+        match self.text(names)?.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" | "enabled" => Some(true),
+            "false" | "no" | "0" | "disabled" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+fn parse_host(row: &Row) -> Option<SourceHost> {
+    let sockets = row.int(&["# CPU", "CPUs"]).unwrap_or(0) as i32;
+    let cores = row
+        .int(&["# Cores", "Cores"])
+        .or_else(|| Some(row.int(&["Cores per CPU"])? * sockets as i64))
+        .unwrap_or(0) as i32;
+    Some(SourceHost {
+        name: row.text(&["Host", "Name"])?,
+        datacenter: row.text(&["Datacenter"]),
+        cluster: row.text(&["Cluster"]),
+        cpu_model: row.text(&["CPU Model"]),
+        cpu_mhz: row.int(&["Speed", "CPU Speed"]).map(|mhz| mhz as i32),
+        sockets,
+        cores,
+        memory_mb: row.int(&["# Memory", "Memory"]).unwrap_or(0),
+        vm_count: row.int(&["# VMs", "# VMs total"]).unwrap_or(0) as i32,
+        esx_version: row.text(&["ESX Version"]),
+        current_evc: row.text(&["Current EVC"]).filter(|mode| !mode.eq_ignore_ascii_case("disabled")),
+        max_evc: row.text(&["Max EVC"]),
+    })
+}
+
+fn parse_cluster(row: &Row) -> Option<SourceClusterConfig> {
+    Some(SourceClusterConfig {
+        name: row.text(&["Name", "Cluster"])?,
+        datacenter: row.text(&["Datacenter"]),
+        num_hosts: row.int(&["NumHosts", "# Hosts"]).unwrap_or(0) as i32,
+        ha_enabled: row.flag(&["HA enabled"]),
+        failover_level: row.int(&["Failover Level"]).map(|level| level as i32),
+        admission_control_enabled: row.flag(&["AdmissionControlEnabled", "Admission Control Enabled"]),
+        failover_policy: row.text(&["Admission Control Policy", "Failover Policy", "Admission Policy"]),
+        isolation_response: row.text(&["Isolation Response"]),
+        drs_enabled: row.flag(&["DRS enabled"]),
+        drs_behavior: row.text(&["DRS default VM behavior", "DRS automation level"]),
+    })
+}
+
+fn parse_datastore(row: &Row) -> Option<SourceDatastore> {
+    let capacity_mb = row.int(&["Capacity MB", "Capacity MiB"]).unwrap_or(0);
+    let free_mb = row.int(&["Free MB", "Free MiB"]).unwrap_or(0);
+    Some(SourceDatastore {
+        name: row.text(&["Name", "Datastore"])?,
+        datastore_type: row.text(&["Type"]),
+        capacity_mb,
+        provisioned_mb: row.int(&["Provisioned MB", "Provisioned MiB"]).unwrap_or(0),
+        in_use_mb: row
+            .int(&["In Use MB", "In Use MiB"])
+            .unwrap_or((capacity_mb - free_mb).max(0)),
+        free_mb,
+        cluster: row.text(&["Cluster name", "Cluster"]),
+        num_hosts: row.int(&["# Hosts"]).unwrap_or(0) as i32,
+    })
+}
+
+fn parse_resource_pool(row: &Row) -> Option<SourceResourcePool> {
+    let path = row.text(&["Resource Pool path", "Path"]);
+    Some(SourceResourcePool {
+        name: row.text(&["Resource Pool name", "Name"])?,
+        cluster: path.as_deref().and_then(cluster_from_path),
+        path,
+        vm_count: row.int(&["# VMs", "# VMs total"]).unwrap_or(0) as i32,
+        cpu_reservation_mhz: row.int(&["CPU reservation"]).unwrap_or(0),
+        // -1 means unlimited
+        cpu_limit_mhz: row.int(&["CPU limit"]).filter(|limit| *limit >= 0),
+        memory_reservation_mb: row.int(&["Mem reservation", "Memory reservation"]).unwrap_or(0),
+        memory_limit_mb: row.int(&["Mem limit", "Memory limit"]).filter(|limit| *limit >= 0),
+    })
+}
+
+fn parse_snapshot(row: &Row) -> Option<SourceSnapshot> {
+    Some(SourceSnapshot {
+        vm_name: row.text(&["VM"])?,
+        name: row.text(&["Name", "Snapshot"]).unwrap_or_default(),
+        description: row.text(&["Description"]),
+        cluster: row.text(&["Cluster"]),
+        created_at: row.date(&["Date / time", "Date/time", "Created"]),
+        size_mb: row.int(&["Size MB (total)", "Size MiB (total)", "Size MB"]).unwrap_or(0),
+    })
+}
+
+fn parse_tools(row: &Row) -> Option<SourceVmTools> {
+    Some(SourceVmTools {
+        vm_name: row.text(&["VM"])?,
+        status: row.text(&["Tools", "Tools Status"]),
+        version: row.text(&["Tools Version"]),
+        required_version: row.text(&["Required Version"]),
+        upgradeable: row.flag(&["Upgradeable"]),
+    })
+}
+
+/// Days since 1899-12-30, the epoch of the 1900 date system
+fn excel_date(serial: f64) -> Option<DateTime<Utc>> {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let at = epoch + Duration::milliseconds((serial * 86_400_000.0).round() as i64);
+    Some(Utc.from_utc_datetime(&at))
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    const FORMATS: [&str; 5] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y/%m/%d %H:%M:%S",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %I:%M:%S %p",
+        "%d.%m.%Y %H:%M:%S",
+    ];
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|at| Utc.from_utc_datetime(&at))
+}
+
+/// `/DC1/host/Cluster01/Resources/Prod` → `Cluster01`
+fn cluster_from_path(path: &str) -> Option<String> {
+    let mut parts = path.split('/').filter(|p| !p.is_empty());
+    parts.by_ref().find(|p| *p == "host")?;
+    parts.next().map(str::to_string)
+}
+
+/// Roll the inventory and the project's VMs up per cluster and storage type
+pub fn summarize(inventory: &SourceInventory, vms: &[MigrationWizardVM]) -> SourceEnvironmentSummary {
+    let cluster_of = |name: &Option<String>| name.clone().unwrap_or_else(|| UNCLUSTERED.to_string());
+    let mut clusters: BTreeMap<String, SourceClusterSummary> = BTreeMap::new();
+    let mut cpu_models: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for config in &inventory.clusters {
+        let summary = cluster_entry(&mut clusters, config.name.clone());
+        summary.datacenter = config.datacenter.clone();
+        summary.hosts = config.num_hosts;
+        summary.ha_enabled = config.ha_enabled;
+        summary.failover_level = config.failover_level;
+        summary.admission_control_enabled = config.admission_control_enabled;
+        summary.failover_policy = config.failover_policy.clone();
+        summary.drs_enabled = config.drs_enabled;
+        summary.drs_behavior = config.drs_behavior.clone();
+    }
+
+    // Host rows replace the vCluster host count
+    let mut counted: BTreeSet<String> = BTreeSet::new();
+    for host in &inventory.hosts {
+        let name = cluster_of(&host.cluster);
+        let summary = cluster_entry(&mut clusters, name.clone());
+        if counted.insert(name.clone()) {
+            summary.hosts = 0;
+        }
+        summary.hosts += 1;
+        summary.sockets += host.sockets;
+        summary.cores += host.cores;
+        summary.memory_gb += host.memory().as_gib();
+        if summary.datacenter.is_none() {
+            summary.datacenter = host.datacenter.clone();
+        }
+        if let Some(model) = &host.cpu_model {
+            cpu_models.entry(name).or_default().insert(model.clone());
+        }
+    }
+
+    for vm in vms.iter().filter(|vm| vm.template != Some(true)) {
+        let summary = cluster_entry(&mut clusters, cluster_of(&vm.cluster));
+        summary.vm_count += 1;
+        summary.vcpus += vm.cpus.max(0);
+        summary.vm_memory_gb += vm.memory().as_gib();
+    }
+
+    for datastore in &inventory.datastores {
+        if let Some(summary) = datastore.cluster.as_ref().and_then(|c| clusters.get_mut(c)) {
+            summary.datastores += 1;
+            summary.storage_capacity_gb += mib_to_gib(datastore.capacity_mb);
+            summary.storage_free_gb += mib_to_gib(datastore.free_mb);
+        }
+    }
+
+    for pool in &inventory.resource_pools {
+        if let Some(summary) = pool.cluster.as_ref().and_then(|c| clusters.get_mut(c)) {
+            summary.resource_pools += 1;
+        }
+    }
+
+    for (name, summary) in clusters.iter_mut() {
+        summary.cpu_models = cpu_models.remove(name).map(|m| m.into_iter().collect()).unwrap_or_default();
+        if summary.cores > 0 {
+            summary.vcpu_per_core = summary.vcpus as f64 / summary.cores as f64;
+        }
+    }
+
+    let mut storage: BTreeMap<String, DatastoreTypeSummary> = BTreeMap::new();
+    for datastore in &inventory.datastores {
+        let kind = datastore.datastore_type.clone().unwrap_or_else(|| "Unknown".to_string());
+        let summary = storage
+            .entry(kind.clone())
+            .or_insert_with(|| DatastoreTypeSummary { datastore_type: kind, ..Default::default() });
+        summary.datastores += 1;
+        summary.capacity_gb += mib_to_gib(datastore.capacity_mb);
+        summary.provisioned_gb += mib_to_gib(datastore.provisioned_mb);
+        summary.free_gb += mib_to_gib(datastore.free_mb);
+    }
+
+    SourceEnvironmentSummary {
+        hosts: inventory.hosts.len(),
+        datastores: inventory.datastores.len(),
+        resource_pools: inventory.resource_pools.len(),
+        clusters: clusters.into_values().collect(),
+        storage: storage.into_values().collect(),
+    }
+}
+
+fn cluster_entry(clusters: &mut BTreeMap<String, SourceClusterSummary>, name: String) -> &mut SourceClusterSummary {
+    clusters
+        .entry(name.clone())
+        .or_insert_with(|| SourceClusterSummary { name, ..Default::default() })
+}
+
+/// Storage in use on the datastores of each cluster, in GiB
+pub fn datastore_usage_by_cluster(inventory: &SourceInventory) -> HashMap<String, f64> {
+    let mut usage = HashMap::new();
+    for datastore in &inventory.datastores {
+        if let Some(cluster) = &datastore.cluster {
+            *usage.entry(cluster.clone()).or_insert(0.0) += mib_to_gib(datastore.in_use_mb);
+        }
+    }
+    usage
+}
+
+fn mib_to_gib(mib: i64) -> f64 {
+    Bytes::from_mib(mib.max(0) as f64).as_gib()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use surrealdb::sql::Thing;
+
+    fn s(value: &str) -> DataType {
+        DataType::String(value.to_string())
+    }
+
+    fn f(value: f64) -> DataType {
+        DataType::Float(value)
+    }
+
+    fn rows<T>(sheet: &[Vec<DataType>], parse: fn(&Row) -> Option<T>) -> Vec<T> {
+        parse_rows(sheet.iter().map(|r| r.as_slice()), parse)
+    }
+
+    fn vm(cluster: &str, cpus: i32, memory_mb: i32) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p")),
+            name: format!("vm-{}", cpus),
+            powerstate: Some("poweredOn".to_string()),
+            template: None,
+            cpus,
+            memory_mb,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: None,
+            dns_name: None,
+            cluster: Some(cluster.to_string()),
+            host: None,
+            datacenter: None,
+            os: None,
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_sheets_are_read_by_column_name_across_versions() {
+        let hosts = rows(
+            &[
+                vec![s("Host"), s("Cluster"), s("CPU Model"), s("# CPU"), s("Cores per CPU"), s("# Memory"), s("Current EVC"), s("Max EVC")],
+                vec![s("esx01"), s("Prod"), s("Intel Xeon Gold 6248"), f(2.0), f(20.0), f(786_432.0), s("intel-broadwell"), s("intel-cascadelake")],
+                vec![s("esx02"), s("Dev"), s("Intel Xeon Gold 6248"), f(2.0), f(20.0), f(786_432.0), s("Disabled"), s("intel-cascadelake")],
+                vec![s(""), s("Prod"), s(""), f(2.0), f(20.0), f(0.0), s(""), s("")],
+            ],
+            parse_host,
+        );
+        assert_eq!(hosts.len(), 2, "rows without a host name are skipped");
+        assert_eq!((hosts[0].sockets, hosts[0].cores), (2, 40));
+        assert_eq!(hosts[0].memory().as_gib(), 768.0);
+        assert_eq!(hosts[0].current_evc.as_deref(), Some("intel-broadwell"));
+        assert_eq!(hosts[1].current_evc, None, "a disabled EVC mode is no mode");
+        assert_eq!(hosts[1].max_evc.as_deref(), Some("intel-cascadelake"));
+
+        let clusters = rows(
+            &[
+                vec![s("Name"), s("HA enabled"), s("Failover Level"), s("DRS enabled"), s("DRS default VM behavior")],
+                vec![s("Prod"), s("True"), f(1.0), s("False"), s("fullyAutomated")],
+            ],
+            parse_cluster,
+        );
+        assert_eq!(clusters[0].ha_enabled, Some(true));
+        assert_eq!(clusters[0].failover_level, Some(1));
+        assert_eq!(clusters[0].drs_enabled, Some(false));
+
+        let pools = rows(
+            &[
+                vec![s("Resource Pool name"), s("Resource Pool path"), s("CPU limit"), s("Mem limit"), s("Mem reservation")],
+                vec![s("Gold"), s("/DC1/host/Prod/Resources/Gold"), f(-1.0), f(65_536.0), f(32_768.0)],
+            ],
+            parse_resource_pool,
+        );
+        assert_eq!(pools[0].cluster.as_deref(), Some("Prod"));
+        assert_eq!(pools[0].cpu_limit_mhz, None);
+        assert_eq!(pools[0].memory_limit_mb, Some(65_536));
+
+        let snapshots = rows(
+            &[
+                vec![s("VM"), s("Name"), s("Date / time"), s("Size MB (total)")],
+                vec![s("app01"), s("before patch"), DataType::DateTime(45_658.5), f(20_480.0)],
+                vec![s("app02"), s("upgrade"), s("2025/01/01 12:00:00"), f(512.0)],
+            ],
+            parse_snapshot,
+        );
+        let noon = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(snapshots[0].created_at, Some(noon));
+        assert_eq!(snapshots[1].created_at, Some(noon));
+        assert_eq!(snapshots[0].size().as_gib(), 20.0);
+    }
+
+    #[test]
+    fn test_summary_combines_hosts_settings_vms_and_storage() {
+        let inventory = SourceInventory {
+            hosts: vec![
+                SourceHost {
+                    name: "esx01".into(),
+                    cluster: Some("Prod".into()),
+                    cpu_model: Some("Gold 6248".into()),
+                    sockets: 2,
+                    cores: 40,
+                    memory_mb: 524_288,
+                    ..Default::default()
+                },
+                SourceHost {
+                    name: "esx02".into(),
+                    cluster: Some("Prod".into()),
+                    cpu_model: Some("Gold 6248".into()),
+                    sockets: 2,
+                    cores: 40,
+                    memory_mb: 524_288,
+                    ..Default::default()
+                },
+            ],
+            clusters: vec![SourceClusterConfig {
+                name: "Prod".into(),
+                num_hosts: 5,
+                ha_enabled: Some(true),
+                failover_level: Some(1),
+                ..Default::default()
+            }],
+            datastores: vec![
+                SourceDatastore {
+                    name: "ds01".into(),
+                    datastore_type: Some("VMFS".into()),
+                    capacity_mb: 4_194_304,
+                    free_mb: 1_048_576,
+                    in_use_mb: 3_145_728,
+                    cluster: Some("Prod".into()),
+                    ..Default::default()
+                },
+                SourceDatastore {
+                    name: "nfs01".into(),
+                    datastore_type: Some("NFS".into()),
+                    capacity_mb: 1_048_576,
+                    ..Default::default()
+                },
+            ],
+            resource_pools: vec![],
+        };
+        let vms = vec![vm("Prod", 8, 32_768), vm("Prod", 4, 16_384), vm("Lab", 2, 4_096)];
+
+        let summary = summarize(&inventory, &vms);
+        assert_eq!(summary.clusters.len(), 2);
+        let prod = summary.clusters.iter().find(|c| c.name == "Prod").unwrap();
+        assert_eq!(prod.hosts, 2, "vHost rows win over the vCluster host count");
+        assert_eq!(prod.cores, 80);
+        assert_eq!(prod.cpu_models, vec!["Gold 6248".to_string()]);
+        assert_eq!((prod.vm_count, prod.vcpus), (2, 12));
+        assert_eq!(prod.vcpu_per_core, 0.15);
+        assert_eq!((prod.datastores, prod.storage_capacity_gb), (1, 4096.0));
+        assert_eq!(prod.average_host(), Some((40.0, 512.0)));
+        assert_eq!(prod.ha_enabled, Some(true));
+
+        assert_eq!(summary.storage.len(), 2);
+        assert_eq!(datastore_usage_by_cluster(&inventory).get("Prod"), Some(&3072.0));
+    }
+}
@@ -0,0 +1,377 @@
+//! Sustainability Service
+//!
+//! Estimates the energy use and CO2e emissions of the source estate and the
+//! proposed destination clusters so consolidation benefits can be reported:
+//! - Typical power draw per host, from vendor specs where a model is chosen
+//! - Facility overhead via PUE
+//! - Grid carbon intensity per region, overridable per project
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::HashSet;
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::models::migration_wizard_models::{MigrationWizardCluster, MigrationWizardVM};
+use crate::models::sustainability::*;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use core_engine::vendor_data::VendorDataManager;
+
+const HOURS_PER_YEAR: f64 = 8760.0;
+
+/// Approximate grid carbon intensity (g CO2e/kWh) by region
+const GRID_CARBON_INTENSITY: &[(&str, f64)] = &[
+    ("global", 440.0),
+    ("eu-average", 250.0),
+    ("uk", 200.0),
+    ("ie", 290.0),
+    ("de", 380.0),
+    ("fr", 60.0),
+    ("nl", 330.0),
+    ("se", 40.0),
+    ("no", 30.0),
+    ("pl", 650.0),
+    ("ro", 260.0),
+    ("us-average", 370.0),
+    ("us-east", 380.0),
+    ("us-west", 220.0),
+    ("ca", 120.0),
+    ("au", 600.0),
+    ("in", 700.0),
+    ("sg", 400.0),
+    ("jp", 450.0),
+];
+
+pub struct SustainabilityService {
+    db: Database,
+}
+
+impl SustainabilityService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Built-in intensity for a region key, if known
+    pub fn region_intensity(region: &str) -> Option<f64> {
+        let region = region.trim().to_lowercase();
+        GRID_CARBON_INTENSITY
+            .iter()
+            .find(|(key, _)| *key == region)
+            .map(|(_, intensity)| *intensity)
+    }
+
+    pub fn regions() -> Vec<(&'static str, f64)> {
+        GRID_CARBON_INTENSITY.to_vec()
+    }
+
+    // =========================================================================
+    // SETTINGS
+    // =========================================================================
+
+    /// Project settings, or the defaults when none have been saved
+    pub async fn get_settings(&self, project_id: &str) -> Result<SustainabilitySettings> {
+        let settings: Option<SustainabilitySettings> = self
+            .db
+            .select(("sustainability_settings", project_id))
+            .await
+            .context("Failed to load sustainability settings")?;
+
+        Ok(settings.unwrap_or_else(|| SustainabilitySettings {
+            project_id: Some(Thing::from(("migration_wizard_project", project_id))),
+            ..Default::default()
+        }))
+    }
+
+    pub async fn update_settings(
+        &self,
+        project_id: &str,
+        request: UpdateSustainabilitySettingsRequest,
+    ) -> Result<SustainabilitySettings> {
+        let mut settings = self.get_settings(project_id).await?;
+
+        if let Some(region) = request.region {
+            settings.region = region.trim().to_lowercase();
+        }
+        if let Some(intensity) = request.carbon_intensity_g_per_kwh {
+            anyhow::ensure!(intensity >= 0.0, "Carbon intensity cannot be negative");
+            settings.carbon_intensity_g_per_kwh = Some(intensity);
+        }
+        if let Some(pue) = request.pue {
+            anyhow::ensure!(pue >= 1.0, "PUE must be at least 1.0");
+            settings.pue = pue;
+        }
+        if request.current_host_count.is_some() {
+            settings.current_host_count = request.current_host_count;
+        }
+        if let Some(watts) = request.current_host_watts {
+            settings.current_host_watts = watts;
+        }
+        if request.proposed_vendor.is_some() {
+            settings.proposed_vendor = request.proposed_vendor;
+        }
+        if request.proposed_model_id.is_some() {
+            settings.proposed_model_id = request.proposed_model_id;
+        }
+        if request.proposed_node_count.is_some() {
+            settings.proposed_node_count = request.proposed_node_count;
+        }
+        if let Some(watts) = request.proposed_node_watts {
+            settings.proposed_node_watts = watts;
+        }
+        if let Some(cores) = request.proposed_cores_per_node {
+            anyhow::ensure!(cores > 0, "Cores per node must be greater than zero");
+            settings.proposed_cores_per_node = cores;
+        }
+
+        if settings.carbon_intensity_g_per_kwh.is_none() && Self::region_intensity(&settings.region).is_none() {
+            anyhow::bail!(
+                "Unknown region '{}'; set carbon_intensity_g_per_kwh explicitly",
+                settings.region
+            );
+        }
+
+        settings.id = None;
+        settings.project_id = Some(Thing::from(("migration_wizard_project", project_id)));
+        settings.updated_at = Utc::now();
+
+        let saved: Option<SustainabilitySettings> = self
+            .db
+            .update(("sustainability_settings", project_id))
+            .content(settings)
+            .await
+            .context("Failed to save sustainability settings")?;
+
+        saved.context("Sustainability settings were not saved")
+    }
+
+    // =========================================================================
+    // REPORT
+    // =========================================================================
+
+    pub async fn generate_report(&self, project_id: &str) -> Result<SustainabilityReport> {
+        let settings = self.get_settings(project_id).await?;
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let vms = wizard.get_project_vms(project_id, None).await?;
+        let clusters = wizard.get_project_clusters(project_id).await?;
+
+        // Typical watts and cores per node for the chosen vendor model
+        let proposed_spec = match (&settings.proposed_vendor, &settings.proposed_model_id) {
+            (Some(vendor), Some(model_id)) => {
+                match VendorDataManager::new().get_model_specifications(vendor, model_id).await {
+                    Ok(specs) => Some((
+                        specs.power_cooling.typical_power_consumption_watts as f64,
+                        specs.supported_cpus.iter().map(|c| c.cores).max().unwrap_or(0)
+                            * specs.model.cpu_sockets.max(1),
+                        specs.model.model_name,
+                    )),
+                    Err(e) => {
+                        tracing::warn!("No vendor specs for {} {}: {}", vendor, model_id, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        Self::build_report(project_id, &settings, &vms, &clusters, proposed_spec)
+    }
+
+    pub fn build_report(
+        project_id: &str,
+        settings: &SustainabilitySettings,
+        vms: &[MigrationWizardVM],
+        clusters: &[MigrationWizardCluster],
+        proposed_spec: Option<(f64, u32, String)>,
+    ) -> Result<SustainabilityReport> {
+        let mut assumptions = Vec::new();
+
+        let intensity = match settings.carbon_intensity_g_per_kwh {
+            Some(intensity) => {
+                assumptions.push(format!("Grid carbon intensity set to {:.0} g CO2e/kWh", intensity));
+                intensity
+            }
+            None => {
+                let intensity = Self::region_intensity(&settings.region)
+                    .with_context(|| format!("Unknown region '{}'", settings.region))?;
+                assumptions.push(format!(
+                    "Grid carbon intensity {:.0} g CO2e/kWh for region '{}'",
+                    intensity, settings.region
+                ));
+                intensity
+            }
+        };
+        assumptions.push(format!("PUE {:.2} applied to both estates", settings.pue));
+
+        let current_hosts = match settings.current_host_count {
+            Some(count) => count,
+            None => {
+                let hosts: HashSet<&str> = vms.iter().filter_map(|vm| vm.host.as_deref()).collect();
+                assumptions.push(format!("{} source hosts counted from the inventory", hosts.len()));
+                hosts.len() as u32
+            }
+        };
+        assumptions.push(format!("Existing hosts draw {:.0} W typical", settings.current_host_watts));
+
+        let (proposed_watts, cores_per_node) = match &proposed_spec {
+            Some((watts, cores, model)) if *watts > 0.0 && *cores > 0 => {
+                assumptions.push(format!("Proposed nodes are {} at {:.0} W typical, {} cores", model, watts, cores));
+                (*watts, *cores)
+            }
+            _ => {
+                assumptions.push(format!(
+                    "Proposed nodes draw {:.0} W typical with {} cores",
+                    settings.proposed_node_watts, settings.proposed_cores_per_node
+                ));
+                (settings.proposed_node_watts, settings.proposed_cores_per_node)
+            }
+        };
+
+        let proposed_nodes = match settings.proposed_node_count {
+            Some(count) => count,
+            None => {
+                let nodes: u32 = clusters
+                    .iter()
+                    .map(|c| (c.total_cores.max(0) as f64 / cores_per_node.max(1) as f64).ceil() as u32)
+                    .sum();
+                assumptions.push(format!(
+                    "{} proposed nodes derived from destination cluster cores",
+                    nodes
+                ));
+                nodes
+            }
+        };
+
+        let current = Self::estate_energy(current_hosts, settings.current_host_watts, settings.pue, intensity);
+        let proposed = Self::estate_energy(proposed_nodes, proposed_watts, settings.pue, intensity);
+
+        let annual_kwh_saved = current.annual_kwh - proposed.annual_kwh;
+        let annual_co2e_saved_tonnes = current.annual_co2e_tonnes - proposed.annual_co2e_tonnes;
+        let reduction_percent = if current.annual_co2e_tonnes > 0.0 {
+            annual_co2e_saved_tonnes / current.annual_co2e_tonnes * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(SustainabilityReport {
+            project_id: project_id.to_string(),
+            region: settings.region.clone(),
+            carbon_intensity_g_per_kwh: intensity,
+            pue: settings.pue,
+            current,
+            proposed,
+            annual_kwh_saved,
+            annual_co2e_saved_tonnes,
+            reduction_percent,
+            assumptions,
+            generated_at: Utc::now(),
+        })
+    }
+
+    fn estate_energy(hosts: u32, watts_per_host: f64, pue: f64, intensity_g_per_kwh: f64) -> EstateEnergy {
+        let facility_power_kw = hosts as f64 * watts_per_host * pue / 1000.0;
+        let annual_kwh = facility_power_kw * HOURS_PER_YEAR;
+
+        EstateEnergy {
+            hosts,
+            watts_per_host,
+            facility_power_kw,
+            annual_kwh,
+            annual_co2e_tonnes: annual_kwh * intensity_g_per_kwh / 1_000_000.0,
+        }
+    }
+
+    /// Comparison table shared by the standalone report and the HLD section
+    pub fn comparison_table(report: &SustainabilityReport) -> String {
+        let mut md = String::new();
+        md.push_str("| Metric | Current Estate | Proposed Estate | Change |\n");
+        md.push_str("|--------|----------------|-----------------|--------|\n");
+        md.push_str(&format!(
+            "| Hosts | {} | {} | {:+} |\n",
+            report.current.hosts,
+            report.proposed.hosts,
+            report.proposed.hosts as i64 - report.current.hosts as i64
+        ));
+        md.push_str(&format!(
+            "| Facility Power (kW) | {:.1} | {:.1} | {:+.1} |\n",
+            report.current.facility_power_kw,
+            report.proposed.facility_power_kw,
+            report.proposed.facility_power_kw - report.current.facility_power_kw
+        ));
+        md.push_str(&format!(
+            "| Annual Energy (MWh) | {:.1} | {:.1} | {:+.1} |\n",
+            report.current.annual_kwh / 1000.0,
+            report.proposed.annual_kwh / 1000.0,
+            -report.annual_kwh_saved / 1000.0
+        ));
+        md.push_str(&format!(
+            "| Annual Emissions (t CO2e) | {:.1} | {:.1} | {:+.1} |\n\n",
+            report.current.annual_co2e_tonnes,
+            report.proposed.annual_co2e_tonnes,
+            -report.annual_co2e_saved_tonnes
+        ));
+        md
+    }
+
+    /// Standalone Markdown report
+    pub fn render_markdown(report: &SustainabilityReport) -> String {
+        let mut md = String::new();
+        md.push_str("# Sustainability Impact Report\n\n");
+        md.push_str(&format!("**Generated:** {}\n\n", report.generated_at.format("%Y-%m-%d")));
+        md.push_str("## Summary\n\n");
+        if report.annual_co2e_saved_tonnes >= 0.0 {
+            md.push_str(&format!(
+                "The proposed estate is expected to reduce annual emissions by **{:.1} t CO2e** ({:.0}%), saving **{:.1} MWh** of energy per year.\n\n",
+                report.annual_co2e_saved_tonnes,
+                report.reduction_percent,
+                report.annual_kwh_saved / 1000.0
+            ));
+        } else {
+            md.push_str(&format!(
+                "The proposed estate is expected to increase annual emissions by **{:.1} t CO2e**.\n\n",
+                -report.annual_co2e_saved_tonnes
+            ));
+        }
+        md.push_str("## Current vs Proposed\n\n");
+        md.push_str(&Self::comparison_table(report));
+        md.push_str("## Assumptions\n\n");
+        for assumption in &report.assumptions {
+            md.push_str(&format!("- {}\n", assumption));
+        }
+        md.push('\n');
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estate_energy() {
+        // 10 hosts × 500 W × PUE 1.5 = 7.5 kW → 65,700 kWh/yr → 26.28 t at 400 g/kWh
+        let energy = SustainabilityService::estate_energy(10, 500.0, 1.5, 400.0);
+        assert!((energy.facility_power_kw - 7.5).abs() < 1e-9);
+        assert!((energy.annual_kwh - 65_700.0).abs() < 1e-6);
+        assert!((energy.annual_co2e_tonnes - 26.28).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_report_uses_overrides_and_region_table() {
+        let settings = SustainabilitySettings {
+            region: "fr".to_string(),
+            current_host_count: Some(20),
+            proposed_node_count: Some(8),
+            ..Default::default()
+        };
+        let report = SustainabilityService::build_report("p1", &settings, &[], &[], None).unwrap();
+
+        assert_eq!(report.carbon_intensity_g_per_kwh, 60.0);
+        assert_eq!(report.current.hosts, 20);
+        assert_eq!(report.proposed.hosts, 8);
+        assert!(report.annual_co2e_saved_tonnes > 0.0);
+        assert!(report.reduction_percent > 50.0);
+
+        let unknown = SustainabilitySettings { region: "atlantis".to_string(), ..Default::default() };
+        assert!(SustainabilityService::build_report("p1", &unknown, &[], &[], None).is_err());
+    }
+}
@@ -12,6 +12,7 @@ use crate::models::auth::User;
 use chrono::Utc;
 use surrealdb::sql::Thing;
 use thiserror::Error;
+use crate::database::TracedQuery;
 
 // ============================================================================
 // ERROR TYPES
@@ -87,6 +88,7 @@ impl TeamService {
             .db
             .query("SELECT * FROM teams WHERE name = $name LIMIT 1")
             .bind(("name", &request.name))
+            .traced("team_service.create_team")
             .await?
             .take(0)?;
 
@@ -204,6 +206,7 @@ impl TeamService {
                 .query("SELECT * FROM teams WHERE name = $name AND id != $id LIMIT 1")
                 .bind(("name", &name))
                 .bind(("id", team.id.as_ref().unwrap()))
+                .traced("team_service.update_team")
                 .await?
                 .take(0)?;
 
@@ -308,6 +311,7 @@ impl TeamService {
             )
             .bind(("team", team_thing.clone()))
             .bind(("user", user_thing.clone()))
+            .traced("team_service.add_member")
             .await?
             .take(0)?;
 
@@ -337,6 +341,7 @@ impl TeamService {
             )
             .bind(("team", team_thing))
             .bind(("user", user_thing))
+            .traced("team_service.remove_member")
             .await?
             .take(0)?;
 
@@ -369,6 +374,7 @@ impl TeamService {
             )
             .bind(("team", team_thing))
             .bind(("user", user_thing))
+            .traced("team_service.update_member_role")
             .await?
             .take(0)?;
 
@@ -409,6 +415,7 @@ impl TeamService {
             .db
             .query(query)
             .bind(("team", team_thing))
+            .traced("team_service.get_team_members")
             .await?
             .take(0)?;
 
@@ -424,6 +431,7 @@ impl TeamService {
                     .db
                     .query("SELECT count() as count FROM ticket WHERE assignee = $user AND status IN ['NEW', 'ASSIGNED', 'IN_PROGRESS']")
                     .bind(("user", user.username.clone()))
+                    .traced("team_service.get_team_members")
                     .await?
                     .take(0)?;
 
@@ -559,6 +567,7 @@ impl TeamService {
             .db
             .query(ticket_query)
             .bind(("team", team_thing))
+            .traced("team_service.calculate_team_workload")
             .await?
             .take(0)?;
 
@@ -603,6 +612,7 @@ impl TeamService {
             .db
             .query(sla_query)
             .bind(("team", parse_thing("teams", team_id)?))
+            .traced("team_service.calculate_team_workload")
             .await?
             .take(0)?;
 
@@ -650,6 +660,7 @@ impl TeamService {
             .db
             .query(query)
             .bind(("user", user_thing))
+            .traced("team_service.get_user_teams")
             .await?
             .take(0)?;
 
@@ -0,0 +1,569 @@
+// Archer ITSM - Ticket Analytics Service
+// Dashboard aggregates over arbitrary date ranges: volume trends, MTTR,
+// first-response compliance, SLA breach rates and backlog ageing.
+// Results are cached per tenant/range/granularity for a short TTL.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Instant;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+use crate::database::Database;
+use crate::models::reporting::*;
+
+/// Longest range accepted, to keep day-granularity series bounded
+const MAX_RANGE_DAYS: i64 = 731;
+const DEFAULT_RANGE_DAYS: i64 = 30;
+const MAX_CACHE_ENTRIES: usize = 256;
+
+/// Backlog age buckets in days: [min, max)
+const BACKLOG_BUCKETS: [(&str, i64, Option<i64>); 5] = [
+    ("< 1 day", 0, Some(1)),
+    ("1-3 days", 1, Some(3)),
+    ("3-7 days", 3, Some(7)),
+    ("7-30 days", 7, Some(30)),
+    ("30+ days", 30, None),
+];
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum AnalyticsError {
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for AnalyticsError {
+    fn from(e: surrealdb::Error) -> Self {
+        AnalyticsError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// TICKET ANALYTICS SERVICE
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    tenant: Option<String>,
+    from: i64,
+    to: i64,
+    granularity: AnalyticsGranularity,
+}
+
+/// Projection of the ticket fields the aggregates need
+#[derive(Debug, Clone, Deserialize)]
+struct TicketFacts {
+    priority: String,
+    status: String,
+    assigned_group: Option<String>,
+    created_at: DateTime<Utc>,
+    first_response_at: Option<DateTime<Utc>>,
+    resolved_at: Option<DateTime<Utc>>,
+    closed_at: Option<DateTime<Utc>>,
+    response_sla_met: Option<bool>,
+    resolution_sla_met: Option<bool>,
+    resolution_due: Option<DateTime<Utc>>,
+    sla_breach_at: Option<DateTime<Utc>>,
+}
+
+const FACT_FIELDS: &str = "priority, status, assigned_group, created_at, first_response_at, resolved_at, closed_at, \
+     response_sla_met, resolution_sla_met, resolution_due, sla_breach_at";
+
+pub struct TicketAnalyticsService {
+    db: Arc<Database>,
+    cache: RwLock<HashMap<CacheKey, (Instant, Arc<TicketAnalytics>)>>,
+    ttl: std::time::Duration,
+}
+
+impl TicketAnalyticsService {
+    /// Cache TTL comes from `TICKET_ANALYTICS_CACHE_SECS` (default 60; 0 disables)
+    pub fn new(db: Arc<Database>) -> Self {
+        let ttl = std::env::var("TICKET_ANALYTICS_CACHE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(60);
+        Self {
+            db,
+            cache: RwLock::new(HashMap::new()),
+            ttl: std::time::Duration::from_secs(ttl),
+        }
+    }
+
+    /// Compute (or return cached) analytics for the user's tenant
+    pub async fn analytics(
+        &self,
+        tenant: Option<&str>,
+        query: &TicketAnalyticsQuery,
+    ) -> Result<Arc<TicketAnalytics>, AnalyticsError> {
+        let (from, to) = resolve_range(query.from, query.to, Utc::now())?;
+        let key = CacheKey {
+            tenant: tenant.map(str::to_string),
+            from: from.timestamp(),
+            to: to.timestamp(),
+            granularity: query.granularity,
+        };
+
+        if !query.refresh {
+            if let Some((at, cached)) = self.cache.read().await.get(&key) {
+                if at.elapsed() < self.ttl {
+                    return Ok(Arc::clone(cached));
+                }
+            }
+        }
+
+        let tenant_thing = tenant.map(|t| Thing::from(("tenants", t)));
+        let tenant_clause = if tenant_thing.is_some() { " AND tenant_id = $tenant" } else { "" };
+
+        let mut range_query = self
+            .db
+            .query(format!(
+                "SELECT {} FROM ticket WHERE ((created_at >= $from AND created_at <= $to) OR (resolved_at >= $from AND resolved_at <= $to)){}",
+                FACT_FIELDS, tenant_clause
+            ))
+            .bind(("from", from))
+            .bind(("to", to));
+        let mut backlog_query = self
+            .db
+            .query(format!(
+                "SELECT {} FROM ticket WHERE created_at <= $to AND (resolved_at = NONE OR resolved_at = NULL OR resolved_at > $to){}",
+                FACT_FIELDS, tenant_clause
+            ))
+            .bind(("to", to));
+        if let Some(tenant) = tenant_thing {
+            range_query = range_query.bind(("tenant", tenant.clone()));
+            backlog_query = backlog_query.bind(("tenant", tenant));
+        }
+
+        let in_range: Vec<TicketFacts> = range_query.await?.take(0)?;
+        let open: Vec<TicketFacts> = backlog_query.await?.take(0)?;
+
+        let analytics = Arc::new(aggregate(&in_range, &open, from, to, query.granularity, Utc::now()));
+
+        if !self.ttl.is_zero() {
+            let mut cache = self.cache.write().await;
+            if cache.len() >= MAX_CACHE_ENTRIES {
+                let ttl = self.ttl;
+                cache.retain(|_, (at, _)| at.elapsed() < ttl);
+                if cache.len() >= MAX_CACHE_ENTRIES {
+                    cache.clear();
+                }
+            }
+            cache.insert(key, (Instant::now(), Arc::clone(&analytics)));
+        }
+        Ok(analytics)
+    }
+}
+
+/// Defaults: `to` = now (truncated to the minute so repeated dashboard loads
+/// share a cache entry), `from` = 30 days earlier
+fn resolve_range(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), AnalyticsError> {
+    let to = to.unwrap_or_else(|| now.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(now));
+    let from = from.unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS));
+    if from >= to {
+        return Err(AnalyticsError::Validation("'from' must be before 'to'".to_string()));
+    }
+    if to - from > Duration::days(MAX_RANGE_DAYS) {
+        return Err(AnalyticsError::Validation(format!(
+            "date range may not exceed {} days",
+            MAX_RANGE_DAYS
+        )));
+    }
+    Ok((from, to))
+}
+
+// ============================================================================
+// AGGREGATION
+// ============================================================================
+
+fn aggregate(
+    in_range: &[TicketFacts],
+    open: &[TicketFacts],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    granularity: AnalyticsGranularity,
+    now: DateTime<Utc>,
+) -> TicketAnalytics {
+    let within = |t: DateTime<Utc>| t >= from && t <= to;
+    let created: Vec<&TicketFacts> = in_range.iter().filter(|t| within(t.created_at)).collect();
+    let resolved: Vec<&TicketFacts> = in_range
+        .iter()
+        .filter(|t| t.resolved_at.is_some_and(within))
+        .collect();
+
+    TicketAnalytics {
+        from,
+        to,
+        granularity,
+        volume: volume_trend(&created, &resolved, from, to, granularity),
+        mttr: mttr(&resolved),
+        first_response: first_response(&created),
+        sla_breaches: sla_breaches(&created, now.min(to)),
+        backlog: backlog(open, to),
+        generated_at: now,
+    }
+}
+
+fn bucket_start(at: DateTime<Utc>, granularity: AnalyticsGranularity) -> NaiveDate {
+    let date = at.date_naive();
+    match granularity {
+        AnalyticsGranularity::Day => date,
+        AnalyticsGranularity::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+        AnalyticsGranularity::Month => date.with_day(1).unwrap_or(date),
+    }
+}
+
+fn next_bucket(start: NaiveDate, granularity: AnalyticsGranularity) -> NaiveDate {
+    match granularity {
+        AnalyticsGranularity::Day => start + Duration::days(1),
+        AnalyticsGranularity::Week => start + Duration::days(7),
+        AnalyticsGranularity::Month => {
+            let (year, month) = if start.month() == 12 {
+                (start.year() + 1, 1)
+            } else {
+                (start.year(), start.month() + 1)
+            };
+            NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(start)
+        }
+    }
+}
+
+/// Every bucket in the range is present, including empty ones, so charts don't skip periods
+fn volume_trend(
+    created: &[&TicketFacts],
+    resolved: &[&TicketFacts],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    granularity: AnalyticsGranularity,
+) -> Vec<VolumeTrendPoint> {
+    let mut buckets: BTreeMap<NaiveDate, (i64, i64)> = BTreeMap::new();
+    let last = bucket_start(to, granularity);
+    let mut cursor = bucket_start(from, granularity);
+    while cursor <= last {
+        buckets.insert(cursor, (0, 0));
+        cursor = next_bucket(cursor, granularity);
+    }
+
+    for ticket in created {
+        if let Some(entry) = buckets.get_mut(&bucket_start(ticket.created_at, granularity)) {
+            entry.0 += 1;
+        }
+    }
+    for ticket in resolved {
+        if let Some(at) = ticket.resolved_at {
+            if let Some(entry) = buckets.get_mut(&bucket_start(at, granularity)) {
+                entry.1 += 1;
+            }
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|(date, (created, resolved))| VolumeTrendPoint {
+            period_start: Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight is a valid time")),
+            created,
+            resolved,
+        })
+        .collect()
+}
+
+fn resolution_stats(label: &str, mut hours: Vec<f64>) -> ResolutionTimeStats {
+    hours.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: f64| {
+        if hours.is_empty() {
+            return None;
+        }
+        // Nearest-rank percentile
+        let rank = ((p * hours.len() as f64).ceil() as usize).clamp(1, hours.len());
+        Some(round2(hours[rank - 1]))
+    };
+
+    ResolutionTimeStats {
+        label: label.to_string(),
+        resolved: hours.len() as i64,
+        mean_hours: mean(&hours).map(round2),
+        median_hours: percentile(0.5),
+        p90_hours: percentile(0.9),
+    }
+}
+
+fn mttr(resolved: &[&TicketFacts]) -> MttrStats {
+    let hours = |t: &TicketFacts| {
+        t.resolved_at
+            .map(|r| (r - t.created_at).num_seconds().max(0) as f64 / 3600.0)
+    };
+
+    let mut by_priority: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    let mut all = Vec::with_capacity(resolved.len());
+    for ticket in resolved {
+        if let Some(h) = hours(ticket) {
+            all.push(h);
+            by_priority.entry(ticket.priority.as_str()).or_default().push(h);
+        }
+    }
+
+    MttrStats {
+        overall: resolution_stats("all", all),
+        by_priority: by_priority
+            .into_iter()
+            .map(|(priority, hours)| resolution_stats(priority, hours))
+            .collect(),
+    }
+}
+
+fn first_response(created: &[&TicketFacts]) -> FirstResponseStats {
+    let mut by_priority: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    let mut minutes = Vec::new();
+    let (mut measured, mut met) = (0, 0);
+
+    for ticket in created {
+        if let Some(responded) = ticket.first_response_at {
+            minutes.push((responded - ticket.created_at).num_seconds().max(0) as f64 / 60.0);
+        }
+        if let Some(ok) = ticket.response_sla_met {
+            let entry = by_priority.entry(ticket.priority.as_str()).or_default();
+            entry.0 += 1;
+            measured += 1;
+            if ok {
+                entry.1 += 1;
+                met += 1;
+            }
+        }
+    }
+
+    FirstResponseStats {
+        measured,
+        met,
+        compliance_pct: pct(met, measured),
+        mean_minutes: mean(&minutes).map(round2),
+        by_priority: breakdown(by_priority),
+    }
+}
+
+/// `Some(true)` when the resolution SLA was (or, for open tickets, already is)
+/// breached; `None` for tickets without an SLA
+fn resolution_breached(ticket: &TicketFacts, as_of: DateTime<Utc>) -> Option<bool> {
+    if let Some(met) = ticket.resolution_sla_met {
+        return Some(!met);
+    }
+    let due = ticket.resolution_due.or(ticket.sla_breach_at)?;
+    Some(match ticket.resolved_at {
+        Some(resolved) => resolved > due,
+        None => due < as_of,
+    })
+}
+
+fn sla_breaches(created: &[&TicketFacts], as_of: DateTime<Utc>) -> SlaBreachStats {
+    let mut by_priority: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    let mut by_group: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    let (mut measured, mut breached) = (0, 0);
+
+    for ticket in created {
+        let Some(is_breached) = resolution_breached(ticket, as_of) else {
+            continue;
+        };
+        let group = ticket.assigned_group.as_deref().unwrap_or("Unassigned");
+        for entry in [
+            by_priority.entry(ticket.priority.as_str()).or_default(),
+            by_group.entry(group).or_default(),
+        ] {
+            entry.0 += 1;
+            entry.1 += i64::from(is_breached);
+        }
+        measured += 1;
+        breached += i64::from(is_breached);
+    }
+
+    SlaBreachStats {
+        measured,
+        breached,
+        breach_rate_pct: pct(breached, measured),
+        by_priority: breakdown(by_priority),
+        by_group: breakdown(by_group),
+    }
+}
+
+fn backlog(open: &[TicketFacts], as_of: DateTime<Utc>) -> BacklogAgeing {
+    let mut buckets: Vec<BacklogBucket> = BACKLOG_BUCKETS
+        .iter()
+        .map(|(label, min_days, max_days)| BacklogBucket {
+            label: label.to_string(),
+            min_days: *min_days,
+            max_days: *max_days,
+            count: 0,
+        })
+        .collect();
+    let mut by_priority: BTreeMap<&str, i64> = BTreeMap::new();
+    let mut oldest: Option<f64> = None;
+    let mut count = 0;
+
+    for ticket in open {
+        // Closed/cancelled without a resolution timestamp: ended at closed_at
+        let ended = ticket.resolved_at.or(ticket.closed_at).is_some_and(|at| at <= as_of);
+        let terminal_without_date = ticket.closed_at.is_none()
+            && ticket.resolved_at.is_none()
+            && matches!(ticket.status.as_str(), "CLOSED" | "CANCELLED");
+        if ticket.created_at > as_of || ended || terminal_without_date {
+            continue;
+        }
+
+        let age_days = (as_of - ticket.created_at).num_seconds() as f64 / 86_400.0;
+        if let Some(bucket) = buckets
+            .iter_mut()
+            .find(|b| age_days >= b.min_days as f64 && b.max_days.is_none_or(|max| age_days < max as f64))
+        {
+            bucket.count += 1;
+        }
+        *by_priority.entry(ticket.priority.as_str()).or_default() += 1;
+        oldest = Some(oldest.map_or(age_days, |o| o.max(age_days)));
+        count += 1;
+    }
+
+    BacklogAgeing {
+        open: count,
+        buckets,
+        by_priority: by_priority
+            .into_iter()
+            .map(|(label, value)| ChartDataPoint {
+                label: label.to_string(),
+                value: value as f64,
+                category: None,
+            })
+            .collect(),
+        oldest_days: oldest.map(round2),
+    }
+}
+
+fn breakdown(groups: BTreeMap<&str, (i64, i64)>) -> Vec<ComplianceBreakdown> {
+    groups
+        .into_iter()
+        .map(|(label, (measured, count))| ComplianceBreakdown {
+            label: label.to_string(),
+            measured,
+            count,
+            rate_pct: pct(count, measured),
+        })
+        .collect()
+}
+
+fn pct(part: i64, whole: i64) -> Option<f64> {
+    (whole > 0).then(|| round2(part as f64 * 100.0 / whole as f64))
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn ticket(priority: &str, created: DateTime<Utc>) -> TicketFacts {
+        TicketFacts {
+            priority: priority.to_string(),
+            status: "NEW".to_string(),
+            assigned_group: None,
+            created_at: created,
+            first_response_at: None,
+            resolved_at: None,
+            closed_at: None,
+            response_sla_met: None,
+            resolution_sla_met: None,
+            resolution_due: None,
+            sla_breach_at: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_range_defaults_and_limits() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 34, 56).unwrap();
+        let (from, to) = resolve_range(None, None, now).unwrap();
+        assert_eq!(to, Utc.with_ymd_and_hms(2024, 3, 10, 12, 34, 0).unwrap());
+        assert_eq!(to - from, Duration::days(30));
+        assert!(resolve_range(Some(now), Some(now), now).is_err());
+        assert!(resolve_range(Some(now - Duration::days(800)), Some(now), now).is_err());
+    }
+
+    #[test]
+    fn test_volume_trend_fills_empty_weeks() {
+        // 2024-03-04 is a Monday
+        let mut resolved = ticket("P2", at(5, 9));
+        resolved.resolved_at = Some(at(19, 9));
+        let created = [ticket("P1", at(4, 9)), ticket("P1", at(6, 9)), resolved.clone()];
+        let created_refs: Vec<&TicketFacts> = created.iter().collect();
+
+        let trend = volume_trend(&created_refs, &[&resolved], at(4, 0), at(20, 0), AnalyticsGranularity::Week);
+        let counts: Vec<(i64, i64)> = trend.iter().map(|p| (p.created, p.resolved)).collect();
+        assert_eq!(counts, vec![(3, 0), (0, 0), (0, 1)]);
+        assert_eq!(trend[1].period_start, at(11, 0));
+    }
+
+    #[test]
+    fn test_mttr_and_sla_breaches() {
+        let mut fast = ticket("P1", at(1, 0));
+        fast.resolved_at = Some(at(1, 2));
+        fast.resolution_sla_met = Some(true);
+        fast.assigned_group = Some("Network".to_string());
+        let mut slow = ticket("P1", at(1, 0));
+        slow.resolved_at = Some(at(2, 0));
+        slow.resolution_due = Some(at(1, 8));
+        slow.assigned_group = Some("Network".to_string());
+        let mut overdue = ticket("P3", at(1, 0));
+        overdue.sla_breach_at = Some(at(3, 0));
+        let no_sla = ticket("P4", at(1, 0));
+
+        let stats = mttr(&[&fast, &slow]);
+        assert_eq!(stats.overall.mean_hours, Some(13.0));
+        assert_eq!(stats.overall.median_hours, Some(2.0));
+        assert_eq!(stats.overall.p90_hours, Some(24.0));
+
+        let breaches = sla_breaches(&[&fast, &slow, &overdue, &no_sla], at(5, 0));
+        assert_eq!((breaches.measured, breaches.breached), (3, 2));
+        assert_eq!(breaches.by_priority[0].rate_pct, Some(50.0));
+        assert_eq!(breaches.by_group[0].label, "Network");
+        assert_eq!(breaches.by_group[1].label, "Unassigned");
+    }
+
+    #[test]
+    fn test_backlog_buckets_skip_closed_tickets() {
+        let as_of = at(31, 0);
+        let mut cancelled = ticket("P2", at(1, 0));
+        cancelled.status = "CANCELLED".to_string();
+        let mut resolved_later = ticket("P2", at(29, 0));
+        resolved_later.resolved_at = Some(Utc.with_ymd_and_hms(2024, 4, 2, 0, 0, 0).unwrap());
+        let open = vec![
+            ticket("P1", at(30, 12)),
+            ticket("P3", at(25, 0)),
+            ticket("P3", at(1, 0)),
+            cancelled,
+            resolved_later,
+        ];
+
+        let ageing = backlog(&open, as_of);
+        assert_eq!(ageing.open, 4);
+        let counts: Vec<i64> = ageing.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 1, 0, 1]);
+        assert_eq!(ageing.oldest_days, Some(30.0));
+    }
+}
@@ -0,0 +1,711 @@
+// Archer ITSM - Recurring Ticket Schedule Service
+// Creates tickets from templates on cron schedules, applying weekend/holiday
+// rules, assignee rotation and CI linkage, and keeps a run history
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tracing::{error, info, warn};
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::ticket::TicketSource;
+use crate::models::ticket_schedule::*;
+use crate::models::ticket_template::{CreateTicketFromTemplateRequest, TicketTemplate};
+use crate::services::report_scheduler_service::{parse_cron, ReportScheduleError};
+use crate::services::ticket_template_service::TicketTemplateService;
+use crate::database::TracedQuery;
+
+/// Cron occurrences examined when looking for the next business day run
+const MAX_LOOKAHEAD: usize = 1000;
+/// Longest holiday stretch a run is moved across before it is skipped
+const MAX_DEFERRAL_DAYS: i64 = 31;
+/// Upper bound for the preview endpoint
+pub const MAX_PREVIEW: usize = 50;
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum TicketScheduleError {
+    #[error("Ticket schedule not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for TicketScheduleError {
+    fn from(e: surrealdb::Error) -> Self {
+        TicketScheduleError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// PLANNING
+// ============================================================================
+
+fn schedule_cron(expr: &str) -> Result<cron::Schedule, TicketScheduleError> {
+    parse_cron(expr).map_err(|e| match e {
+        ReportScheduleError::Validation(message) => TicketScheduleError::Validation(message),
+        other => TicketScheduleError::Validation(other.to_string()),
+    })
+}
+
+/// Upcoming occurrences after `after` with the calendar applied, up to and
+/// including the `runs`-th one that will create tickets. Skipped occurrences
+/// in between are included with no `run_at`.
+pub fn plan_occurrences(
+    cron: &str,
+    calendar: &ScheduleCalendar,
+    after: DateTime<Utc>,
+    runs: usize,
+) -> Result<Vec<PlannedOccurrence>, TicketScheduleError> {
+    let schedule = schedule_cron(cron)?;
+    let mut planned = Vec::new();
+    let mut found = 0;
+
+    for occurrence_at in schedule.after(&after).take(MAX_LOOKAHEAD) {
+        let run_at = if calendar.is_business_day(occurrence_at.date_naive()) {
+            Some(occurrence_at)
+        } else {
+            match calendar.policy {
+                HolidayPolicy::Skip => None,
+                HolidayPolicy::NextBusinessDay => (1..=MAX_DEFERRAL_DAYS)
+                    .map(|days| occurrence_at + Duration::days(days))
+                    .find(|at| calendar.is_business_day(at.date_naive())),
+            }
+        };
+        planned.push(PlannedOccurrence { occurrence_at, run_at });
+        if run_at.is_some() {
+            found += 1;
+            if found == runs {
+                return Ok(planned);
+            }
+        }
+    }
+
+    if found == 0 {
+        return Err(TicketScheduleError::Validation(format!(
+            "cron expression '{}' has no occurrence outside the skipped days",
+            cron
+        )));
+    }
+    Ok(planned)
+}
+
+/// Replace `{date}`, `{month}` and `{ci}` in a schedule's title
+pub fn render_title(title: &str, occurrence_at: DateTime<Utc>, ci_name: Option<&str>) -> String {
+    title
+        .replace("{date}", &occurrence_at.format("%Y-%m-%d").to_string())
+        .replace("{month}", &occurrence_at.format("%B %Y").to_string())
+        .replace("{ci}", ci_name.unwrap_or("all systems"))
+}
+
+// ============================================================================
+// TICKET SCHEDULE SERVICE
+// ============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+struct CiSummary {
+    id: Thing,
+    name: String,
+    ci_id: Option<String>,
+}
+
+pub struct TicketScheduleService {
+    db: Arc<Database>,
+    templates: TicketTemplateService,
+}
+
+impl TicketScheduleService {
+    pub fn new(db: Arc<Database>) -> Self {
+        let templates = TicketTemplateService::new(db.clone());
+        Self { db, templates }
+    }
+
+    // ========================================================================
+    // SCHEDULES
+    // ========================================================================
+
+    pub async fn list_schedules(&self, user: &AuthenticatedUser) -> Result<Vec<TicketSchedule>, TicketScheduleError> {
+        let mut schedules: Vec<TicketSchedule> = self
+            .db
+            .query("SELECT * FROM ticket_schedules ORDER BY name ASC")
+            .traced("ticket_schedule_service.list_schedules")
+            .await?
+            .take(0)?;
+        let tenant = user_tenant(user);
+        schedules.retain(|s| s.tenant_id == tenant);
+        Ok(schedules)
+    }
+
+    pub async fn get_schedule(&self, user: &AuthenticatedUser, id: &str) -> Result<TicketSchedule, TicketScheduleError> {
+        let schedule: Option<TicketSchedule> = self.db.select(parse_thing("ticket_schedules", id)).await?;
+        let tenant = user_tenant(user);
+        schedule
+            .filter(|s| s.tenant_id == tenant)
+            .ok_or(TicketScheduleError::NotFound)
+    }
+
+    pub async fn create_schedule(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateTicketScheduleRequest,
+    ) -> Result<TicketSchedule, TicketScheduleError> {
+        let now = Utc::now();
+        let mut schedule = TicketSchedule {
+            id: None,
+            name: request.name.trim().to_string(),
+            description: request.description,
+            template_id: parse_thing("ticket_templates", &request.template_id),
+            title: request.title.trim().to_string(),
+            cron: request.cron.trim().to_string(),
+            ci_ids: request
+                .ci_ids
+                .iter()
+                .map(|id| parse_thing("configuration_items", id))
+                .collect(),
+            ticket_per_ci: request.ticket_per_ci,
+            assignment: request.assignment,
+            calendar: request.calendar,
+            enabled: request.enabled,
+            next_run_at: None,
+            next_occurrence_at: None,
+            last_run_at: None,
+            occurrence_count: 0,
+            created_by: user.user_id.clone(),
+            created_at: now,
+            updated_at: now,
+            tenant_id: user_tenant(user),
+        };
+        self.validate(user, &schedule).await?;
+        self.reschedule(&mut schedule, now)?;
+
+        let created: Vec<TicketSchedule> = self.db.create("ticket_schedules").content(&schedule).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| TicketScheduleError::DatabaseError("Failed to create ticket schedule".to_string()))
+    }
+
+    pub async fn update_schedule(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: UpdateTicketScheduleRequest,
+    ) -> Result<TicketSchedule, TicketScheduleError> {
+        let mut schedule = self.get_schedule(user, id).await?;
+
+        if let Some(name) = request.name {
+            schedule.name = name.trim().to_string();
+        }
+        if let Some(description) = request.description {
+            schedule.description = Some(description);
+        }
+        if let Some(template_id) = request.template_id {
+            schedule.template_id = parse_thing("ticket_templates", &template_id);
+        }
+        if let Some(title) = request.title {
+            schedule.title = title.trim().to_string();
+        }
+        if let Some(cron) = request.cron {
+            schedule.cron = cron.trim().to_string();
+        }
+        if let Some(ci_ids) = request.ci_ids {
+            schedule.ci_ids = ci_ids.iter().map(|id| parse_thing("configuration_items", id)).collect();
+        }
+        if let Some(ticket_per_ci) = request.ticket_per_ci {
+            schedule.ticket_per_ci = ticket_per_ci;
+        }
+        if let Some(assignment) = request.assignment {
+            schedule.assignment = assignment;
+        }
+        if let Some(calendar) = request.calendar {
+            schedule.calendar = calendar;
+        }
+        if let Some(enabled) = request.enabled {
+            schedule.enabled = enabled;
+        }
+        self.validate(user, &schedule).await?;
+
+        let now = Utc::now();
+        self.reschedule(&mut schedule, now)?;
+        schedule.updated_at = now;
+
+        let thing = schedule.id.clone().ok_or(TicketScheduleError::NotFound)?;
+        let updated: Option<TicketSchedule> = self.db.update(thing).content(&schedule).await?;
+        updated.ok_or(TicketScheduleError::NotFound)
+    }
+
+    /// Delete a schedule; tickets it created and its run history are kept
+    pub async fn delete_schedule(&self, user: &AuthenticatedUser, id: &str) -> Result<(), TicketScheduleError> {
+        let schedule = self.get_schedule(user, id).await?;
+        let thing = schedule.id.ok_or(TicketScheduleError::NotFound)?;
+        let _: Option<TicketSchedule> = self.db.delete(thing).await?;
+        Ok(())
+    }
+
+    /// Upcoming occurrences, including those the calendar skips
+    pub async fn preview(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        runs: usize,
+    ) -> Result<Vec<PlannedOccurrence>, TicketScheduleError> {
+        let schedule = self.get_schedule(user, id).await?;
+        plan_occurrences(&schedule.cron, &schedule.calendar, Utc::now(), runs.clamp(1, MAX_PREVIEW))
+    }
+
+    /// Create the tickets for a schedule immediately, outside its cron
+    pub async fn run_now(&self, user: &AuthenticatedUser, id: &str) -> Result<TicketScheduleRun, TicketScheduleError> {
+        let schedule = self.get_schedule(user, id).await?;
+        self.execute(&schedule, Utc::now()).await
+    }
+
+    /// Skip the next occurrence, e.g. during a change freeze not in the
+    /// holiday list
+    pub async fn skip_next(&self, user: &AuthenticatedUser, id: &str) -> Result<TicketSchedule, TicketScheduleError> {
+        let mut schedule = self.get_schedule(user, id).await?;
+        let (Some(run_at), Some(occurrence_at)) = (schedule.next_run_at, schedule.next_occurrence_at) else {
+            return Err(TicketScheduleError::Validation("Schedule has no upcoming occurrence".to_string()));
+        };
+
+        self.record_run(
+            &schedule,
+            occurrence_at,
+            ScheduleRunStatus::Skipped,
+            Vec::new(),
+            Some(format!("Skipped on request by {}", user.username)),
+        )
+        .await?;
+        self.reschedule(&mut schedule, run_at)?;
+        schedule.updated_at = Utc::now();
+
+        let thing = schedule.id.clone().ok_or(TicketScheduleError::NotFound)?;
+        let updated: Option<TicketSchedule> = self.db.update(thing).content(&schedule).await?;
+        updated.ok_or(TicketScheduleError::NotFound)
+    }
+
+    pub async fn list_runs(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<Vec<TicketScheduleRun>, TicketScheduleError> {
+        let schedule = self.get_schedule(user, id).await?;
+        let runs: Vec<TicketScheduleRun> = self
+            .db
+            .query("SELECT * FROM ticket_schedule_runs WHERE schedule_id = $schedule ORDER BY occurrence_at DESC LIMIT 200")
+            .bind(("schedule", schedule.id))
+            .traced("ticket_schedule_service.list_runs")
+            .await?
+            .take(0)?;
+        Ok(runs)
+    }
+
+    // ========================================================================
+    // EXECUTION
+    // ========================================================================
+
+    /// Create tickets for every enabled schedule whose next run has passed
+    pub async fn run_due(&self) -> Result<usize, TicketScheduleError> {
+        let now = Utc::now();
+        let due: Vec<TicketSchedule> = self
+            .db
+            .query("SELECT * FROM ticket_schedules WHERE enabled = true AND next_run_at != NONE AND next_run_at <= $now")
+            .bind(("now", now))
+            .traced("ticket_schedule_service.run_due")
+            .await?
+            .take(0)?;
+
+        for schedule in &due {
+            let occurrence_at = schedule.next_occurrence_at.or(schedule.next_run_at).unwrap_or(now);
+            let mut next = schedule.clone();
+            let skipped = match self.reschedule(&mut next, now) {
+                Ok(skipped) => skipped,
+                Err(e) => {
+                    warn!("Disabling ticket schedule '{}': {}", schedule.name, e);
+                    next.enabled = false;
+                    next.next_run_at = None;
+                    next.next_occurrence_at = None;
+                    Vec::new()
+                }
+            };
+            // Claim the slot before creating tickets so a slow run isn't picked up twice
+            if let Some(id) = &schedule.id {
+                self.db
+                    .query("UPDATE $id SET next_run_at = $next, next_occurrence_at = $occurrence, enabled = $enabled")
+                    .bind(("id", id.clone()))
+                    .bind(("next", next.next_run_at))
+                    .bind(("occurrence", next.next_occurrence_at))
+                    .bind(("enabled", next.enabled))
+                    .traced("ticket_schedule_service.run_due")
+                    .await?;
+            }
+
+            if let Err(e) = self.execute(schedule, occurrence_at).await {
+                error!("Ticket schedule '{}' failed: {}", schedule.name, e);
+            }
+            for skipped_at in skipped {
+                self.record_run(
+                    schedule,
+                    skipped_at,
+                    ScheduleRunStatus::Skipped,
+                    Vec::new(),
+                    Some("Weekend or holiday".to_string()),
+                )
+                .await?;
+            }
+        }
+        Ok(due.len())
+    }
+
+    /// Create the tickets for one occurrence. Failures are recorded as a
+    /// FAILED run rather than returned as errors.
+    async fn execute(
+        &self,
+        schedule: &TicketSchedule,
+        occurrence_at: DateTime<Utc>,
+    ) -> Result<TicketScheduleRun, TicketScheduleError> {
+        let template: Option<TicketTemplate> = self.db.select(schedule.template_id.clone()).await?;
+        let Some(template) = template.filter(|t| t.is_active) else {
+            return self
+                .record_run(
+                    schedule,
+                    occurrence_at,
+                    ScheduleRunStatus::Failed,
+                    Vec::new(),
+                    Some("Template is missing or inactive".to_string()),
+                )
+                .await;
+        };
+
+        let cis = self.load_cis(&schedule.ci_ids).await?;
+        let targets: Vec<Vec<&CiSummary>> = if schedule.ticket_per_ci {
+            cis.iter().map(|ci| vec![ci]).collect()
+        } else {
+            vec![cis.iter().collect()]
+        };
+        let targets = if targets.is_empty() { vec![Vec::new()] } else { targets };
+
+        let actor = schedule_actor(schedule);
+        let assignee = schedule.assignment.assignee_for(schedule.occurrence_count);
+        let mut ticket_ids = Vec::new();
+        let mut failure = None;
+
+        for covered in targets {
+            let ci_name = match covered.as_slice() {
+                [ci] => Some(ci.name.as_str()),
+                _ => None,
+            };
+            let request = CreateTicketFromTemplateRequest {
+                title: render_title(&schedule.title, occurrence_at, ci_name),
+                description: Some(ticket_description(&template, &covered)),
+                priority: None,
+                assignee: assignee.clone(),
+                assigned_group: schedule.assignment.assigned_group.clone(),
+                related_asset: covered.first().map(|ci| ci.id.to_string()),
+                related_project: None,
+                source: Some(TicketSource::Scheduled),
+                tags: vec!["scheduled".to_string()],
+                watchers: Vec::new(),
+                custom_fields: Some(serde_json::json!({
+                    "schedule_id": schedule.id.as_ref().map(|id| id.to_string()),
+                    "occurrence_at": occurrence_at,
+                    "ci_ids": covered.iter().map(|ci| ci.id.to_string()).collect::<Vec<_>>(),
+                })),
+            };
+            match self.templates.instantiate(&template, request, &actor).await {
+                Ok(created) => ticket_ids.extend(created.ticket.ticket.id),
+                Err(e) => {
+                    failure = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+
+        if let Some(id) = &schedule.id {
+            self.db
+                .query("UPDATE $id SET last_run_at = $ran, occurrence_count += 1")
+                .bind(("id", id.clone()))
+                .bind(("ran", Utc::now()))
+                .traced("ticket_schedule_service.execute")
+                .await?;
+        }
+        let status = if failure.is_some() {
+            ScheduleRunStatus::Failed
+        } else {
+            ScheduleRunStatus::Created
+        };
+        self.record_run(schedule, occurrence_at, status, ticket_ids, failure).await
+    }
+
+    async fn record_run(
+        &self,
+        schedule: &TicketSchedule,
+        occurrence_at: DateTime<Utc>,
+        status: ScheduleRunStatus,
+        ticket_ids: Vec<Thing>,
+        reason: Option<String>,
+    ) -> Result<TicketScheduleRun, TicketScheduleError> {
+        let schedule_id = schedule.id.clone().ok_or(TicketScheduleError::NotFound)?;
+        let run = TicketScheduleRun {
+            id: None,
+            schedule_id,
+            occurrence_at,
+            status,
+            ticket_ids,
+            reason,
+            created_at: Utc::now(),
+            tenant_id: schedule.tenant_id.clone(),
+        };
+        let created: Vec<TicketScheduleRun> = self.db.create("ticket_schedule_runs").content(&run).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| TicketScheduleError::DatabaseError("Failed to record schedule run".to_string()))
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    /// Set the next run after `after`, returning the occurrences skipped on the way
+    fn reschedule(
+        &self,
+        schedule: &mut TicketSchedule,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<DateTime<Utc>>, TicketScheduleError> {
+        if !schedule.enabled {
+            schedule_cron(&schedule.cron)?;
+            schedule.next_run_at = None;
+            schedule.next_occurrence_at = None;
+            return Ok(Vec::new());
+        }
+
+        let planned = plan_occurrences(&schedule.cron, &schedule.calendar, after, 1)?;
+        let next = planned.iter().find(|p| p.run_at.is_some());
+        schedule.next_run_at = next.and_then(|p| p.run_at);
+        schedule.next_occurrence_at = next.map(|p| p.occurrence_at);
+        Ok(planned
+            .iter()
+            .filter(|p| p.run_at.is_none())
+            .map(|p| p.occurrence_at)
+            .collect())
+    }
+
+    async fn validate(&self, user: &AuthenticatedUser, schedule: &TicketSchedule) -> Result<(), TicketScheduleError> {
+        if schedule.name.is_empty() {
+            return Err(TicketScheduleError::Validation("Schedule name is required".to_string()));
+        }
+        if schedule.title.is_empty() {
+            return Err(TicketScheduleError::Validation("Ticket title is required".to_string()));
+        }
+        if schedule.ticket_per_ci && schedule.ci_ids.is_empty() {
+            return Err(TicketScheduleError::Validation(
+                "ticket_per_ci needs at least one CI".to_string(),
+            ));
+        }
+
+        // The template must exist and be usable by whoever sets up the schedule
+        let template_id = schedule.template_id.to_string();
+        self.templates
+            .get_template(user, &template_id)
+            .await
+            .map_err(|e| TicketScheduleError::Validation(format!("template {}: {}", template_id, e)))?;
+
+        let cis = self.load_cis(&schedule.ci_ids).await?;
+        if let Some(missing) = schedule.ci_ids.iter().find(|id| !cis.iter().any(|ci| &ci.id == *id)) {
+            return Err(TicketScheduleError::Validation(format!("CI {} not found", missing)));
+        }
+        Ok(())
+    }
+
+    async fn load_cis(&self, ids: &[Thing]) -> Result<Vec<CiSummary>, TicketScheduleError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut cis: Vec<CiSummary> = self
+            .db
+            .query("SELECT id, name, ci_id FROM configuration_items WHERE id INSIDE $ids")
+            .bind(("ids", ids.to_vec()))
+            .traced("ticket_schedule_service.load_cis")
+            .await?
+            .take(0)?;
+        // Keep the schedule's order so "first CI" is predictable
+        cis.sort_by_key(|ci| ids.iter().position(|id| id == &ci.id));
+        Ok(cis)
+    }
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Template description skeleton followed by the CIs the ticket covers
+fn ticket_description(template: &TicketTemplate, cis: &[&CiSummary]) -> String {
+    let mut description = template.description_template.clone().unwrap_or_default();
+    if !cis.is_empty() {
+        if !description.is_empty() {
+            description.push_str("\n\n");
+        }
+        description.push_str("Configuration items:\n");
+        for ci in cis {
+            match &ci.ci_id {
+                Some(ci_id) => description.push_str(&format!("- {} ({})\n", ci.name, ci_id)),
+                None => description.push_str(&format!("- {}\n", ci.name)),
+            }
+        }
+    }
+    description
+}
+
+/// Scheduled tickets are created on behalf of the schedule's owner
+fn schedule_actor(schedule: &TicketSchedule) -> AuthenticatedUser {
+    AuthenticatedUser {
+        user_id: schedule.created_by.clone(),
+        email: String::new(),
+        username: format!("Schedule: {}", schedule.name),
+        roles: Vec::new(),
+        permissions: Vec::new(),
+        tenant_id: schedule.tenant_id.as_ref().map(|t| t.id.to_raw()),
+    }
+}
+
+fn user_tenant(user: &AuthenticatedUser) -> Option<Thing> {
+    user.tenant_id.as_deref().map(|t| Thing::from(("tenants", t)))
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+// ============================================================================
+// TICKET SCHEDULER
+// ============================================================================
+
+use tokio_cron_scheduler::{Job, JobScheduler};
+
+use crate::database::locks;
+
+/// Lease on the ticket schedule run, renewed while tickets are created
+const TICKET_SCHEDULE_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Background job that checks for due ticket schedules once a minute
+pub struct TicketScheduler {
+    db: Arc<Database>,
+}
+
+impl TicketScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow!("Failed to create scheduler: {}", e))?;
+
+        let db = Arc::clone(&self.db);
+        let service = Arc::new(TicketScheduleService::new(Arc::clone(&self.db)));
+        let job = Job::new_async("30 * * * * *", move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                let run = locks::run_exclusive(
+                    &db,
+                    locks::TICKET_SCHEDULER_LOCK,
+                    TICKET_SCHEDULE_LOCK_TTL,
+                    service.run_due(),
+                );
+                match run.await {
+                    Ok(None) | Ok(Some(Ok(0))) => {}
+                    Ok(Some(Ok(count))) => info!("🗓️ Ran {} ticket schedule(s)", count),
+                    Ok(Some(Err(e))) => error!("❌ Ticket schedule run failed: {}", e),
+                    Err(e) => error!("❌ Ticket schedule lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow!("Failed to create ticket schedule job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow!("Failed to add ticket schedule job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow!("Failed to start scheduler: {}", e))?;
+
+        // Keep the scheduler alive for the process lifetime
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[test]
+    fn test_plan_skips_or_defers_holidays() {
+        // First Monday of each month at 06:00; 2024-04-01 is Easter Monday
+        let cron = "0 6 1-7 * Mon";
+        let after = Utc.with_ymd_and_hms(2024, 3, 20, 0, 0, 0).unwrap();
+        let mut calendar = ScheduleCalendar {
+            skip_weekends: true,
+            holidays: vec![NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()],
+            policy: HolidayPolicy::Skip,
+        };
+
+        let planned = plan_occurrences(cron, &calendar, after, 1).unwrap();
+        assert_eq!(
+            planned,
+            vec![
+                PlannedOccurrence {
+                    occurrence_at: Utc.with_ymd_and_hms(2024, 4, 1, 6, 0, 0).unwrap(),
+                    run_at: None,
+                },
+                PlannedOccurrence {
+                    occurrence_at: Utc.with_ymd_and_hms(2024, 5, 6, 6, 0, 0).unwrap(),
+                    run_at: Some(Utc.with_ymd_and_hms(2024, 5, 6, 6, 0, 0).unwrap()),
+                },
+            ]
+        );
+
+        calendar.policy = HolidayPolicy::NextBusinessDay;
+        let planned = plan_occurrences(cron, &calendar, after, 1).unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].run_at, Some(Utc.with_ymd_and_hms(2024, 4, 2, 6, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_rotation_and_title() {
+        let assignment = ScheduleAssignment {
+            assignee: Some("users:fallback".to_string()),
+            rotation: vec!["users:ana".to_string(), "users:ben".to_string()],
+            assigned_group: None,
+        };
+        assert_eq!(assignment.assignee_for(0).as_deref(), Some("users:ana"));
+        assert_eq!(assignment.assignee_for(3).as_deref(), Some("users:ben"));
+
+        let at = Utc.with_ymd_and_hms(2024, 3, 4, 6, 0, 0).unwrap();
+        assert_eq!(
+            render_title("Patching {ci} - {month}", at, Some("CL-PROD-01")),
+            "Patching CL-PROD-01 - March 2024"
+        );
+        assert_eq!(render_title("DR test {date}", at, None), "DR test 2024-03-04");
+    }
+}
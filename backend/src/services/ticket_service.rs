@@ -3,6 +3,7 @@
 
 use anyhow::{anyhow, Result};
 use chrono::Utc;
+use serde_json::{json, Value};
 use std::sync::Arc;
 use surrealdb::sql::Thing;
 
@@ -13,7 +14,11 @@ use crate::models::ticket::{
     Ticket, TicketComment, TicketHistory, TicketResponse, TicketStatus, TransitionTicketRequest,
     UpdateTicketRequest,
 };
+use crate::models::webhook::WebhookEventType;
 use crate::services::sla_service::SlaService;
+use crate::services::ticket_watch_service::notify_watchers;
+use crate::services::webhook_service;
+use crate::database::TracedQuery;
 
 // ============================================================================
 // TICKET SERVICE
@@ -115,6 +120,8 @@ impl TicketService {
         )
         .await?;
 
+        emit_ticket_event(&self.db, WebhookEventType::TicketCreated, &created_ticket, json!({}));
+
         // Return full ticket response
         self.get_ticket_response(&created_ticket).await
     }
@@ -145,6 +152,7 @@ impl TicketService {
                 WHERE id = $id
             "#)
             .bind(("id", ticket_id))
+            .traced("ticket_service.record_ticket_access")
             .await?;
         Ok(())
     }
@@ -161,6 +169,7 @@ impl TicketService {
         // Get existing ticket
         let existing: Option<Ticket> = self.db.select(ticket_thing.clone()).await?;
         let mut ticket = existing.ok_or_else(|| anyhow!("Ticket not found"))?;
+        let old_status = ticket.status.clone();
 
         // Track changes for history
         let mut changes = Vec::new();
@@ -245,6 +254,24 @@ impl TicketService {
         let updated: Option<Ticket> = self.db.update(ticket_thing).content(ticket.clone()).await?;
         let updated_ticket = updated.ok_or_else(|| anyhow!("Failed to update ticket"))?;
 
+        if !changes.is_empty() {
+            let changed_fields: Vec<&str> = changes.iter().map(|(field, _, _)| *field).collect();
+            emit_ticket_event(
+                &self.db,
+                WebhookEventType::TicketUpdated,
+                &updated_ticket,
+                json!({ "changed_fields": changed_fields }),
+            );
+        }
+        if updated_ticket.status != old_status {
+            emit_ticket_event(
+                &self.db,
+                WebhookEventType::TicketStatusChanged,
+                &updated_ticket,
+                json!({ "previous_status": old_status }),
+            );
+        }
+
         // Create history entries for changes
         for (field, old_value, new_value) in changes {
             self.create_history_entry(
@@ -277,6 +304,7 @@ impl TicketService {
 
         let existing: Option<Ticket> = self.db.select(ticket_thing.clone()).await?;
         let mut ticket = existing.ok_or_else(|| anyhow!("Ticket not found"))?;
+        let old_status = ticket.status.clone();
 
         // Perform transition
         self.transition_status(&mut ticket, request.target_status, user, request.comment.as_deref())
@@ -288,6 +316,17 @@ impl TicketService {
         let updated: Option<Ticket> = self.db.update(ticket_thing).content(ticket.clone()).await?;
         let updated_ticket = updated.ok_or_else(|| anyhow!("Failed to update ticket"))?;
 
+        if updated_ticket.status != old_status {
+            let summary = format!("Status changed from {:?} to {:?}", old_status, updated_ticket.status);
+            notify_watchers(self.db.clone(), updated_ticket.clone(), HistoryChangeType::StatusChange, summary, user);
+            emit_ticket_event(
+                &self.db,
+                WebhookEventType::TicketStatusChanged,
+                &updated_ticket,
+                json!({ "previous_status": old_status }),
+            );
+        }
+
         self.get_ticket_response(&updated_ticket).await
     }
 
@@ -410,6 +449,12 @@ impl TicketService {
         )
         .await?;
 
+        let ticket: Option<Ticket> = self.db.select(ticket_thing).await?;
+        if let Some(ticket) = ticket {
+            let summary = format!("New comment by {}", user.username);
+            notify_watchers(self.db.clone(), ticket, HistoryChangeType::Comment, summary, user);
+        }
+
         Ok(created_comment)
     }
 
@@ -433,7 +478,7 @@ impl TicketService {
             )
         };
 
-        let comments: Vec<TicketComment> = self.db.query(&query).await?.take(0)?;
+        let comments: Vec<TicketComment> = self.db.query(&query).traced("ticket_service.get_ticket_comments").await?.take(0)?;
         Ok(comments)
     }
 
@@ -486,7 +531,7 @@ impl TicketService {
             ticket_thing
         );
 
-        let history: Vec<TicketHistory> = self.db.query(&query).await?.take(0)?;
+        let history: Vec<TicketHistory> = self.db.query(&query).traced("ticket_service.get_ticket_history").await?.take(0)?;
         Ok(history)
     }
 
@@ -550,6 +595,29 @@ impl TicketService {
 // HELPER FUNCTIONS
 // ============================================================================
 
+/// Queue a ticket webhook event for the ticket's tenant. The event data is a
+/// summary of the ticket plus the fields of `extra`.
+pub fn emit_ticket_event(db: &Database, event_type: WebhookEventType, ticket: &Ticket, extra: Value) {
+    let mut data = json!({
+        "ticket_id": ticket.id.as_ref().map(|id| id.to_string()),
+        "title": ticket.title,
+        "ticket_type": ticket.ticket_type,
+        "priority": ticket.priority,
+        "status": ticket.status,
+        "assignee": ticket.assignee,
+        "category": ticket.category,
+        "related_project": ticket.related_project.as_ref().map(|p| p.to_string()),
+        "created_by": ticket.created_by,
+        "created_at": ticket.created_at,
+        "updated_at": ticket.updated_at,
+    });
+    if let (Some(data), Value::Object(extra)) = (data.as_object_mut(), extra) {
+        data.extend(extra);
+    }
+    let tenant_id = ticket.tenant_id.as_ref().map(|t| t.id.to_raw());
+    webhook_service::emit(db, event_type, tenant_id, data);
+}
+
 fn parse_thing(id: &str) -> Option<Thing> {
     let parts: Vec<&str> = id.split(':').collect();
     if parts.len() == 2 {
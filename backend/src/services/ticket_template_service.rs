@@ -0,0 +1,802 @@
+// Archer ITSM - Ticket Template Service
+// Team-managed ticket templates and macros, applied on creation or to
+// existing tickets, plus the checklist tasks templates add
+
+use chrono::Utc;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::ticket::{
+    CommentType, CreateCommentRequest, CreateTicketRequest, HistoryChangeType, Ticket,
+    UpdateTicketRequest,
+};
+use crate::models::ticket_template::*;
+use crate::services::ticket_service::TicketService;
+use crate::services::ticket_watch_service::notify_watchers;
+use crate::database::TracedQuery;
+
+/// Permission that allows managing templates and macros of any team, and
+/// the global ones
+const MANAGE_PERMISSION: &str = "tickets:manage";
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+#[derive(Debug, Error)]
+pub enum TicketTemplateError {
+    #[error("Template not found")]
+    TemplateNotFound,
+
+    #[error("Macro not found")]
+    MacroNotFound,
+
+    #[error("Ticket not found")]
+    TicketNotFound,
+
+    #[error("Task not found")]
+    TaskNotFound,
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    /// The ticket rejected the change, e.g. an invalid status transition
+    #[error("Could not apply to ticket: {0}")]
+    TicketRejected(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for TicketTemplateError {
+    fn from(e: surrealdb::Error) -> Self {
+        TicketTemplateError::DatabaseError(e.to_string())
+    }
+}
+
+// ============================================================================
+// TICKET TEMPLATE SERVICE
+// ============================================================================
+
+pub struct TicketTemplateService {
+    db: Arc<Database>,
+    tickets: TicketService,
+}
+
+impl TicketTemplateService {
+    pub fn new(db: Arc<Database>) -> Self {
+        let tickets = TicketService::new(db.clone());
+        Self { db, tickets }
+    }
+
+    // ========================================================================
+    // TEMPLATES
+    // ========================================================================
+
+    /// Global templates plus those of the user's teams
+    pub async fn list_templates(
+        &self,
+        user: &AuthenticatedUser,
+        include_inactive: bool,
+    ) -> Result<Vec<TicketTemplate>, TicketTemplateError> {
+        let mut templates: Vec<TicketTemplate> = self
+            .db
+            .query(
+                r#"
+                SELECT * FROM ticket_templates
+                WHERE (team_id = NONE OR team_id INSIDE $teams OR $all_teams)
+                    AND (tenant_id = NONE OR tenant_id = $tenant)
+                ORDER BY name ASC
+                "#,
+            )
+            .bind(("teams", self.user_team_ids(user).await?))
+            .bind(("all_teams", user.has_permission(MANAGE_PERMISSION)))
+            .bind(("tenant", tenant_thing(user)))
+            .traced("ticket_template_service.list_templates")
+            .await?
+            .take(0)?;
+
+        if !include_inactive {
+            templates.retain(|t| t.is_active);
+        }
+        Ok(templates)
+    }
+
+    pub async fn get_template(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<TicketTemplate, TicketTemplateError> {
+        let template: Option<TicketTemplate> = self.db.select(parse_thing("ticket_templates", id)).await?;
+        let template = template.ok_or(TicketTemplateError::TemplateNotFound)?;
+        if !self.can_see(user, template.team_id.as_ref(), template.tenant_id.as_ref()).await? {
+            return Err(TicketTemplateError::TemplateNotFound);
+        }
+        Ok(template)
+    }
+
+    pub async fn create_template(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateTicketTemplateRequest,
+    ) -> Result<TicketTemplate, TicketTemplateError> {
+        let name = required_name(&request.name)?;
+        validate_checklist(&request.checklist)?;
+        let team_id = request.team_id.as_deref().map(|t| parse_thing("teams", t));
+        self.ensure_can_manage(user, team_id.as_ref()).await?;
+
+        let now = Utc::now();
+        let template = TicketTemplate {
+            id: None,
+            name,
+            description: request.description,
+            team_id,
+            ticket_type: request.ticket_type,
+            priority: request.priority,
+            impact: request.impact,
+            urgency: request.urgency,
+            category: request.category,
+            subcategory: request.subcategory,
+            assigned_group: request.assigned_group,
+            title_prefix: request.title_prefix,
+            description_template: request.description_template,
+            tags: request.tags,
+            checklist: request.checklist,
+            is_active: true,
+            created_by: user.user_id.clone(),
+            tenant_id: tenant_thing(user),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<TicketTemplate> = self.db.create("ticket_templates").content(&template).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| TicketTemplateError::DatabaseError("Failed to create template".to_string()))
+    }
+
+    pub async fn update_template(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: UpdateTicketTemplateRequest,
+    ) -> Result<TicketTemplate, TicketTemplateError> {
+        let mut template = self.get_template(user, id).await?;
+        self.ensure_can_manage(user, template.team_id.as_ref()).await?;
+
+        if let Some(name) = request.name {
+            template.name = required_name(&name)?;
+        }
+        if let Some(description) = request.description {
+            template.description = Some(description);
+        }
+        if let Some(ticket_type) = request.ticket_type {
+            template.ticket_type = ticket_type;
+        }
+        if let Some(priority) = request.priority {
+            template.priority = priority;
+        }
+        if let Some(impact) = request.impact {
+            template.impact = Some(impact);
+        }
+        if let Some(urgency) = request.urgency {
+            template.urgency = Some(urgency);
+        }
+        if let Some(category) = request.category {
+            template.category = Some(category);
+        }
+        if let Some(subcategory) = request.subcategory {
+            template.subcategory = Some(subcategory);
+        }
+        if let Some(assigned_group) = request.assigned_group {
+            template.assigned_group = Some(assigned_group);
+        }
+        if let Some(title_prefix) = request.title_prefix {
+            template.title_prefix = Some(title_prefix);
+        }
+        if let Some(description_template) = request.description_template {
+            template.description_template = Some(description_template);
+        }
+        if let Some(tags) = request.tags {
+            template.tags = tags;
+        }
+        if let Some(checklist) = request.checklist {
+            validate_checklist(&checklist)?;
+            template.checklist = checklist;
+        }
+        if let Some(is_active) = request.is_active {
+            template.is_active = is_active;
+        }
+        template.updated_at = Utc::now();
+
+        let thing = template.id.clone().ok_or(TicketTemplateError::TemplateNotFound)?;
+        let updated: Option<TicketTemplate> = self.db.update(thing).content(&template).await?;
+        updated.ok_or(TicketTemplateError::TemplateNotFound)
+    }
+
+    pub async fn delete_template(&self, user: &AuthenticatedUser, id: &str) -> Result<(), TicketTemplateError> {
+        let template = self.get_template(user, id).await?;
+        self.ensure_can_manage(user, template.team_id.as_ref()).await?;
+        let thing = template.id.ok_or(TicketTemplateError::TemplateNotFound)?;
+        let _: Option<TicketTemplate> = self.db.delete(thing).await?;
+        Ok(())
+    }
+
+    /// Create a ticket pre-filled from the template, with its checklist tasks
+    pub async fn create_ticket_from_template(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: CreateTicketFromTemplateRequest,
+    ) -> Result<TicketWithTasksResponse, TicketTemplateError> {
+        let template = self.active_template(user, id).await?;
+        self.instantiate(&template, request, user).await
+    }
+
+    /// Create a ticket from an already loaded template. No visibility check:
+    /// callers such as the recurring ticket scheduler act on stored config.
+    pub async fn instantiate(
+        &self,
+        template: &TicketTemplate,
+        request: CreateTicketFromTemplateRequest,
+        user: &AuthenticatedUser,
+    ) -> Result<TicketWithTasksResponse, TicketTemplateError> {
+        if request.title.trim().is_empty() {
+            return Err(TicketTemplateError::Validation("Ticket title is required".to_string()));
+        }
+
+        let ticket = self
+            .tickets
+            .create_ticket(template_ticket_request(template, request, user), user)
+            .await
+            .map_err(|e| TicketTemplateError::TicketRejected(e.to_string()))?;
+        let ticket_id = ticket.ticket.id.clone().ok_or(TicketTemplateError::TicketNotFound)?;
+        let tasks = self.add_checklist(&ticket_id, template).await?;
+
+        Ok(TicketWithTasksResponse { ticket, tasks })
+    }
+
+    /// Apply a template to an existing ticket: fields the ticket has not set
+    /// yet are filled in and the checklist tasks are appended
+    pub async fn apply_template(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        ticket_id: &str,
+    ) -> Result<TicketWithTasksResponse, TicketTemplateError> {
+        let template = self.active_template(user, id).await?;
+        let ticket = self.load_ticket(ticket_id).await?;
+        let ticket_thing = ticket.id.clone().ok_or(TicketTemplateError::TicketNotFound)?;
+
+        let ticket = self
+            .tickets
+            .update_ticket(&ticket_thing.to_string(), template_fill(&template, &ticket), user)
+            .await
+            .map_err(|e| TicketTemplateError::TicketRejected(e.to_string()))?;
+        self.add_checklist(&ticket_thing, &template).await?;
+        let tasks = self.list_tasks(&ticket_thing.to_string()).await?;
+
+        Ok(TicketWithTasksResponse { ticket, tasks })
+    }
+
+    async fn active_template(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+    ) -> Result<TicketTemplate, TicketTemplateError> {
+        let template = self.get_template(user, id).await?;
+        if !template.is_active {
+            return Err(TicketTemplateError::Validation("Template is inactive".to_string()));
+        }
+        Ok(template)
+    }
+
+    // ========================================================================
+    // MACROS
+    // ========================================================================
+
+    /// Global macros plus those of the user's teams
+    pub async fn list_macros(
+        &self,
+        user: &AuthenticatedUser,
+        include_inactive: bool,
+    ) -> Result<Vec<TicketMacro>, TicketTemplateError> {
+        let mut macros: Vec<TicketMacro> = self
+            .db
+            .query(
+                r#"
+                SELECT * FROM ticket_macros
+                WHERE (team_id = NONE OR team_id INSIDE $teams OR $all_teams)
+                    AND (tenant_id = NONE OR tenant_id = $tenant)
+                ORDER BY name ASC
+                "#,
+            )
+            .bind(("teams", self.user_team_ids(user).await?))
+            .bind(("all_teams", user.has_permission(MANAGE_PERMISSION)))
+            .bind(("tenant", tenant_thing(user)))
+            .traced("ticket_template_service.list_macros")
+            .await?
+            .take(0)?;
+
+        if !include_inactive {
+            macros.retain(|m| m.is_active);
+        }
+        Ok(macros)
+    }
+
+    pub async fn get_macro(&self, user: &AuthenticatedUser, id: &str) -> Result<TicketMacro, TicketTemplateError> {
+        let ticket_macro: Option<TicketMacro> = self.db.select(parse_thing("ticket_macros", id)).await?;
+        let ticket_macro = ticket_macro.ok_or(TicketTemplateError::MacroNotFound)?;
+        if !self.can_see(user, ticket_macro.team_id.as_ref(), ticket_macro.tenant_id.as_ref()).await? {
+            return Err(TicketTemplateError::MacroNotFound);
+        }
+        Ok(ticket_macro)
+    }
+
+    pub async fn create_macro(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateTicketMacroRequest,
+    ) -> Result<TicketMacro, TicketTemplateError> {
+        let name = required_name(&request.name)?;
+        validate_actions(&request.actions)?;
+        let team_id = request.team_id.as_deref().map(|t| parse_thing("teams", t));
+        self.ensure_can_manage(user, team_id.as_ref()).await?;
+
+        let now = Utc::now();
+        let ticket_macro = TicketMacro {
+            id: None,
+            name,
+            description: request.description,
+            team_id,
+            actions: request.actions,
+            is_active: true,
+            created_by: user.user_id.clone(),
+            tenant_id: tenant_thing(user),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<TicketMacro> = self.db.create("ticket_macros").content(&ticket_macro).await?;
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| TicketTemplateError::DatabaseError("Failed to create macro".to_string()))
+    }
+
+    pub async fn update_macro(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        request: UpdateTicketMacroRequest,
+    ) -> Result<TicketMacro, TicketTemplateError> {
+        let mut ticket_macro = self.get_macro(user, id).await?;
+        self.ensure_can_manage(user, ticket_macro.team_id.as_ref()).await?;
+
+        if let Some(name) = request.name {
+            ticket_macro.name = required_name(&name)?;
+        }
+        if let Some(description) = request.description {
+            ticket_macro.description = Some(description);
+        }
+        if let Some(actions) = request.actions {
+            validate_actions(&actions)?;
+            ticket_macro.actions = actions;
+        }
+        if let Some(is_active) = request.is_active {
+            ticket_macro.is_active = is_active;
+        }
+        ticket_macro.updated_at = Utc::now();
+
+        let thing = ticket_macro.id.clone().ok_or(TicketTemplateError::MacroNotFound)?;
+        let updated: Option<TicketMacro> = self.db.update(thing).content(&ticket_macro).await?;
+        updated.ok_or(TicketTemplateError::MacroNotFound)
+    }
+
+    pub async fn delete_macro(&self, user: &AuthenticatedUser, id: &str) -> Result<(), TicketTemplateError> {
+        let ticket_macro = self.get_macro(user, id).await?;
+        self.ensure_can_manage(user, ticket_macro.team_id.as_ref()).await?;
+        let thing = ticket_macro.id.ok_or(TicketTemplateError::MacroNotFound)?;
+        let _: Option<TicketMacro> = self.db.delete(thing).await?;
+        Ok(())
+    }
+
+    /// Apply a macro's field changes, then post its comment
+    pub async fn apply_macro(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        ticket_id: &str,
+    ) -> Result<TicketWithTasksResponse, TicketTemplateError> {
+        let ticket_macro = self.get_macro(user, id).await?;
+        if !ticket_macro.is_active {
+            return Err(TicketTemplateError::Validation("Macro is inactive".to_string()));
+        }
+        let ticket = self.load_ticket(ticket_id).await?;
+        let ticket_thing = ticket.id.clone().ok_or(TicketTemplateError::TicketNotFound)?;
+        let old_status = ticket.status.clone();
+
+        let mut response = self
+            .tickets
+            .update_ticket(&ticket_thing.to_string(), macro_update(&ticket_macro.actions, &ticket), user)
+            .await
+            .map_err(|e| TicketTemplateError::TicketRejected(e.to_string()))?;
+
+        if response.ticket.status != old_status {
+            let summary = format!(
+                "Status changed from {:?} to {:?} by macro '{}'",
+                old_status, response.ticket.status, ticket_macro.name
+            );
+            notify_watchers(
+                self.db.clone(),
+                response.ticket.clone(),
+                HistoryChangeType::StatusChange,
+                summary,
+                user,
+            );
+        }
+
+        if let Some(comment) = ticket_macro.actions.comment {
+            let created = self
+                .tickets
+                .add_comment(
+                    &ticket_thing.to_string(),
+                    CreateCommentRequest {
+                        content: comment.content,
+                        is_internal: comment.is_internal,
+                        comment_type: Some(CommentType::Note),
+                    },
+                    user,
+                )
+                .await
+                .map_err(|e| TicketTemplateError::TicketRejected(e.to_string()))?;
+            response.comments.push(created);
+        }
+
+        let tasks = self.list_tasks(&ticket_thing.to_string()).await?;
+        Ok(TicketWithTasksResponse { ticket: response, tasks })
+    }
+
+    // ========================================================================
+    // CHECKLIST TASKS
+    // ========================================================================
+
+    pub async fn list_tasks(&self, ticket_id: &str) -> Result<Vec<TicketTask>, TicketTemplateError> {
+        let tasks: Vec<TicketTask> = self
+            .db
+            .query("SELECT * FROM ticket_tasks WHERE ticket_id = $ticket ORDER BY position ASC")
+            .bind(("ticket", parse_thing("ticket", ticket_id)))
+            .traced("ticket_template_service.list_tasks")
+            .await?
+            .take(0)?;
+        Ok(tasks)
+    }
+
+    pub async fn set_task_completed(
+        &self,
+        user: &AuthenticatedUser,
+        ticket_id: &str,
+        task_id: &str,
+        completed: bool,
+    ) -> Result<TicketTask, TicketTemplateError> {
+        let task: Option<TicketTask> = self.db.select(parse_thing("ticket_tasks", task_id)).await?;
+        let mut task = task
+            .filter(|t| t.ticket_id == parse_thing("ticket", ticket_id))
+            .ok_or(TicketTemplateError::TaskNotFound)?;
+
+        if task.completed != completed {
+            task.completed = completed;
+            task.completed_by = completed.then(|| user.user_id.clone());
+            task.completed_at = completed.then(Utc::now);
+        }
+
+        let thing = task.id.clone().ok_or(TicketTemplateError::TaskNotFound)?;
+        let updated: Option<TicketTask> = self.db.update(thing).content(&task).await?;
+        updated.ok_or(TicketTemplateError::TaskNotFound)
+    }
+
+    /// Append the template's checklist after any tasks the ticket already has
+    async fn add_checklist(
+        &self,
+        ticket_id: &Thing,
+        template: &TicketTemplate,
+    ) -> Result<Vec<TicketTask>, TicketTemplateError> {
+        let existing = self.list_tasks(&ticket_id.to_string()).await?;
+        let first_position = existing.iter().map(|t| t.position + 1).max().unwrap_or(0);
+        let now = Utc::now();
+
+        let mut tasks = Vec::with_capacity(template.checklist.len());
+        for (offset, item) in template.checklist.iter().enumerate() {
+            let task = TicketTask {
+                id: None,
+                ticket_id: ticket_id.clone(),
+                title: item.title.clone(),
+                description: item.description.clone(),
+                position: first_position + offset as u32,
+                completed: false,
+                completed_by: None,
+                completed_at: None,
+                template_id: template.id.clone(),
+                created_at: now,
+            };
+            let created: Vec<TicketTask> = self.db.create("ticket_tasks").content(&task).await?;
+            tasks.extend(created);
+        }
+        Ok(tasks)
+    }
+
+    // ========================================================================
+    // HELPERS
+    // ========================================================================
+
+    async fn load_ticket(&self, ticket_id: &str) -> Result<Ticket, TicketTemplateError> {
+        let ticket: Option<Ticket> = self.db.select(parse_thing("ticket", ticket_id)).await?;
+        ticket.ok_or(TicketTemplateError::TicketNotFound)
+    }
+
+    async fn user_team_ids(&self, user: &AuthenticatedUser) -> Result<Vec<Thing>, TicketTemplateError> {
+        let teams: Vec<Thing> = self
+            .db
+            .query("SELECT VALUE team_id FROM team_memberships WHERE user_id = $user")
+            .bind(("user", parse_thing("users", &user.user_id)))
+            .traced("ticket_template_service.user_team_ids")
+            .await?
+            .take(0)?;
+        Ok(teams)
+    }
+
+    async fn can_see(
+        &self,
+        user: &AuthenticatedUser,
+        team_id: Option<&Thing>,
+        tenant_id: Option<&Thing>,
+    ) -> Result<bool, TicketTemplateError> {
+        if tenant_id.is_some() && tenant_id != tenant_thing(user).as_ref() {
+            return Ok(false);
+        }
+        match team_id {
+            None => Ok(true),
+            Some(_) if user.has_permission(MANAGE_PERMISSION) => Ok(true),
+            Some(team) => Ok(self.user_team_ids(user).await?.contains(team)),
+        }
+    }
+
+    /// Team members manage their team's templates and macros; global ones
+    /// need `tickets:manage`
+    async fn ensure_can_manage(
+        &self,
+        user: &AuthenticatedUser,
+        team_id: Option<&Thing>,
+    ) -> Result<(), TicketTemplateError> {
+        if user.has_permission(MANAGE_PERMISSION) {
+            return Ok(());
+        }
+        match team_id {
+            None => Err(TicketTemplateError::Forbidden(format!(
+                "Permission '{}' required to manage global templates and macros",
+                MANAGE_PERMISSION
+            ))),
+            Some(team) if self.user_team_ids(user).await?.contains(team) => Ok(()),
+            Some(_) => Err(TicketTemplateError::Forbidden(
+                "You are not a member of the owning team".to_string(),
+            )),
+        }
+    }
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Merge the template with the caller's values into a create request
+fn template_ticket_request(
+    template: &TicketTemplate,
+    request: CreateTicketFromTemplateRequest,
+    user: &AuthenticatedUser,
+) -> CreateTicketRequest {
+    let title = match &template.title_prefix {
+        Some(prefix) if !prefix.trim().is_empty() => format!("{} {}", prefix.trim(), request.title.trim()),
+        _ => request.title.trim().to_string(),
+    };
+
+    CreateTicketRequest {
+        title,
+        description: request.description.or_else(|| template.description_template.clone()),
+        ticket_type: template.ticket_type.clone(),
+        priority: request.priority.unwrap_or_else(|| template.priority.clone()),
+        related_asset: request.related_asset,
+        related_project: request.related_project,
+        assignee: request.assignee,
+        created_by: user.user_id.clone(),
+        impact: template.impact.clone(),
+        urgency: template.urgency.clone(),
+        source: request.source,
+        category: template.category.clone(),
+        subcategory: template.subcategory.clone(),
+        assigned_group: request.assigned_group.or_else(|| template.assigned_group.clone()),
+        assignment_team_id: template.team_id.as_ref().map(|t| t.to_string()),
+        tags: merge_tags(&template.tags, &request.tags, &[]),
+        watchers: request.watchers,
+        custom_fields: request.custom_fields,
+    }
+}
+
+/// Fields of an existing ticket the template may fill: only those still
+/// unset, so an agent's edits are never overwritten
+fn template_fill(template: &TicketTemplate, ticket: &Ticket) -> UpdateTicketRequest {
+    let description_empty = ticket.description.as_deref().map_or(true, |d| d.trim().is_empty());
+    let tags = merge_tags(&ticket.tags, &template.tags, &[]);
+
+    UpdateTicketRequest {
+        title: None,
+        description: template.description_template.clone().filter(|_| description_empty),
+        status: None,
+        priority: None,
+        assignee: None,
+        impact: template.impact.clone().filter(|_| ticket.impact.is_none()),
+        urgency: template.urgency.clone().filter(|_| ticket.urgency.is_none()),
+        category: template.category.clone().filter(|_| ticket.category.is_none()),
+        subcategory: template.subcategory.clone().filter(|_| ticket.subcategory.is_none()),
+        assigned_group: template.assigned_group.clone().filter(|_| ticket.assigned_group.is_none()),
+        assignment_team_id: None,
+        tags: (tags != ticket.tags).then_some(tags),
+        custom_fields: None,
+    }
+}
+
+/// A macro's field changes as an update request
+fn macro_update(actions: &MacroActions, ticket: &Ticket) -> UpdateTicketRequest {
+    let tags = merge_tags(&ticket.tags, &actions.add_tags, &actions.remove_tags);
+
+    UpdateTicketRequest {
+        title: None,
+        description: None,
+        status: actions.status.clone(),
+        priority: actions.priority.clone(),
+        assignee: actions.assignee.clone(),
+        impact: None,
+        urgency: None,
+        category: actions.category.clone(),
+        subcategory: actions.subcategory.clone(),
+        assigned_group: actions.assigned_group.clone(),
+        assignment_team_id: None,
+        tags: (tags != ticket.tags).then_some(tags),
+        custom_fields: None,
+    }
+}
+
+/// `base` plus `add` without duplicates, minus `remove`, keeping order
+fn merge_tags(base: &[String], add: &[String], remove: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::with_capacity(base.len() + add.len());
+    for tag in base.iter().chain(add) {
+        if !tags.contains(tag) && !remove.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
+fn required_name(name: &str) -> Result<String, TicketTemplateError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(TicketTemplateError::Validation("Name is required".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_checklist(checklist: &[ChecklistItem]) -> Result<(), TicketTemplateError> {
+    if checklist.iter().any(|item| item.title.trim().is_empty()) {
+        return Err(TicketTemplateError::Validation("Checklist tasks need a title".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_actions(actions: &MacroActions) -> Result<(), TicketTemplateError> {
+    if actions.is_empty() {
+        return Err(TicketTemplateError::Validation("A macro needs at least one action".to_string()));
+    }
+    if actions.comment.as_ref().map_or(false, |c| c.content.trim().is_empty()) {
+        return Err(TicketTemplateError::Validation("Macro comment cannot be empty".to_string()));
+    }
+    Ok(())
+}
+
+fn tenant_thing(user: &AuthenticatedUser) -> Option<Thing> {
+    user.tenant_id.as_deref().map(|t| parse_thing("tenants", t))
+}
+
+/// Parse a string ID into a SurrealDB Thing
+fn parse_thing(table: &str, id: &str) -> Thing {
+    match id.split_once(':') {
+        Some((tb, key)) => Thing::from((tb, key)),
+        None => Thing::from((table, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ticket::{TicketPriority, TicketStatus};
+    use serde_json::json;
+
+    fn template() -> TicketTemplate {
+        serde_json::from_value(json!({
+            "id": null,
+            "name": "Storage outage",
+            "description": null,
+            "team_id": null,
+            "type": "INCIDENT",
+            "priority": "P2",
+            "category": "storage",
+            "title_prefix": "[Storage]",
+            "description_template": "## Impact\n\n## Timeline\n",
+            "tags": ["storage", "outage"],
+            "checklist": [{ "title": "Check array health" }],
+            "created_by": "users:lead",
+            "tenant_id": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    fn ticket(value: serde_json::Value) -> Ticket {
+        let mut base = json!({
+            "id": null,
+            "title": "Datastore offline",
+            "description": null,
+            "type": "INCIDENT",
+            "priority": "P3",
+            "status": "NEW",
+            "related_asset": null,
+            "related_project": null,
+            "assignee": null,
+            "created_by": "users:agent",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+        });
+        base.as_object_mut().unwrap().extend(value.as_object().unwrap().clone());
+        serde_json::from_value(base).unwrap()
+    }
+
+    #[test]
+    fn test_template_fill_only_sets_missing_fields() {
+        let fill = template_fill(&template(), &ticket(json!({ "category": "network", "tags": ["outage"] })));
+        assert_eq!(fill.description.as_deref(), Some("## Impact\n\n## Timeline\n"));
+        // The agent already categorised the ticket
+        assert!(fill.category.is_none());
+        assert_eq!(fill.tags, Some(vec!["outage".to_string(), "storage".to_string()]));
+        assert!(fill.priority.is_none());
+
+        let written = ticket(json!({ "description": "Array B lost both controllers", "tags": ["storage", "outage"] }));
+        let fill = template_fill(&template(), &written);
+        assert!(fill.description.is_none());
+        assert!(fill.tags.is_none());
+    }
+
+    #[test]
+    fn test_macro_update_merges_tags() {
+        let actions: MacroActions = serde_json::from_value(json!({
+            "status": "ON_HOLD",
+            "priority": "P1",
+            "add_tags": ["vendor-case", "outage"],
+            "remove_tags": ["triage"],
+            "comment": { "content": "Escalated to vendor", "is_internal": true },
+        }))
+        .unwrap();
+        let update = macro_update(&actions, &ticket(json!({ "tags": ["outage", "triage"] })));
+
+        assert_eq!(update.status, Some(TicketStatus::OnHold));
+        assert_eq!(update.priority, Some(TicketPriority::P1));
+        assert_eq!(update.tags, Some(vec!["outage".to_string(), "vendor-case".to_string()]));
+        assert!(validate_actions(&actions).is_ok());
+        assert!(validate_actions(&MacroActions::default()).is_err());
+    }
+}
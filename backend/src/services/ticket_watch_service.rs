@@ -0,0 +1,473 @@
+// Archer ITSM - Ticket Watch Service
+// Tells a ticket's watchers about new comments and status changes, either as
+// they happen or in a once-a-day digest, per each watcher's preferences
+
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{error, info, warn};
+
+use crate::database::{locks, Database};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::ticket::{
+    HistoryChangeType, Ticket, TicketWatchEvent, UpdateWatchPreferencesRequest,
+    WatchNotificationPreferences,
+};
+use crate::services::notification_service::NotificationService;
+use crate::database::TracedQuery;
+
+/// Lookback for a user's first digest
+const FIRST_DIGEST_WINDOW_HOURS: i64 = 24;
+/// Daily at 07:00 UTC unless `WATCH_DIGEST_CRON` says otherwise
+const DEFAULT_DIGEST_CRON: &str = "0 0 7 * * *";
+const DIGEST_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum TicketWatchError {
+    #[error("Ticket not found")]
+    NotFound,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for TicketWatchError {
+    fn from(e: surrealdb::Error) -> Self {
+        TicketWatchError::DatabaseError(e.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UserEmail {
+    id: Thing,
+    email: String,
+}
+
+pub struct TicketWatchService {
+    db: Arc<Database>,
+    notifier: NotificationService,
+}
+
+impl TicketWatchService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            notifier: NotificationService::from_env(),
+        }
+    }
+
+    // ========================================================================
+    // WATCHING
+    // ========================================================================
+
+    pub async fn watch(&self, ticket_id: &Thing, user_id: &str) -> Result<Ticket, TicketWatchError> {
+        self.update_watchers(ticket_id, user_id, "array::union(watchers, [$user])").await
+    }
+
+    pub async fn unwatch(&self, ticket_id: &Thing, user_id: &str) -> Result<Ticket, TicketWatchError> {
+        self.update_watchers(ticket_id, user_id, "array::complement(watchers, [$user])").await
+    }
+
+    async fn update_watchers(&self, ticket_id: &Thing, user_id: &str, expr: &str) -> Result<Ticket, TicketWatchError> {
+        let updated: Vec<Ticket> = self
+            .db
+            .query(format!("UPDATE $ticket SET watchers = {}", expr))
+            .bind(("ticket", ticket_id.clone()))
+            .bind(("user", user_id.to_string()))
+            .traced("ticket_watch_service.update_watchers")
+            .await?
+            .take(0)?;
+        updated.into_iter().next().ok_or(TicketWatchError::NotFound)
+    }
+
+    /// Open and closed tickets the user watches, most recently updated first
+    pub async fn watched_tickets(&self, user_id: &str) -> Result<Vec<Ticket>, TicketWatchError> {
+        let tickets: Vec<Ticket> = self
+            .db
+            .query("SELECT * FROM ticket WHERE watchers CONTAINS $user ORDER BY updated_at DESC")
+            .bind(("user", user_id.to_string()))
+            .traced("ticket_watch_service.watched_tickets")
+            .await?
+            .take(0)?;
+        Ok(tickets)
+    }
+
+    // ========================================================================
+    // PREFERENCES
+    // ========================================================================
+
+    pub async fn preferences(&self, user_id: &str) -> Result<WatchNotificationPreferences, TicketWatchError> {
+        let stored: Vec<WatchNotificationPreferences> = self
+            .db
+            .query("SELECT * FROM ticket_watch_preferences WHERE user_id = $user")
+            .bind(("user", user_id.to_string()))
+            .traced("ticket_watch_service.preferences")
+            .await?
+            .take(0)?;
+        Ok(stored
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| WatchNotificationPreferences::defaults_for(user_id)))
+    }
+
+    pub async fn update_preferences(
+        &self,
+        user_id: &str,
+        request: UpdateWatchPreferencesRequest,
+    ) -> Result<WatchNotificationPreferences, TicketWatchError> {
+        let mut preferences = self.preferences(user_id).await?;
+        if let Some(immediate) = request.immediate {
+            preferences.immediate = immediate;
+        }
+        if let Some(daily_digest) = request.daily_digest {
+            preferences.daily_digest = daily_digest;
+        }
+        self.save_preferences(&preferences).await?;
+        Ok(preferences)
+    }
+
+    async fn save_preferences(&self, preferences: &WatchNotificationPreferences) -> Result<(), TicketWatchError> {
+        let _: Vec<WatchNotificationPreferences> = self
+            .db
+            .query("UPDATE type::thing('ticket_watch_preferences', $user) CONTENT $preferences")
+            .bind(("user", preferences.user_id.clone()))
+            .bind(("preferences", preferences.clone()))
+            .traced("ticket_watch_service.save_preferences")
+            .await?
+            .take(0)?;
+        Ok(())
+    }
+
+    async fn preferences_for(
+        &self,
+        user_ids: &[String],
+    ) -> Result<HashMap<String, WatchNotificationPreferences>, TicketWatchError> {
+        let stored: Vec<WatchNotificationPreferences> = self
+            .db
+            .query("SELECT * FROM ticket_watch_preferences WHERE user_id IN $users")
+            .bind(("users", user_ids.to_vec()))
+            .traced("ticket_watch_service.preferences_for")
+            .await?
+            .take(0)?;
+        let mut by_user: HashMap<String, WatchNotificationPreferences> =
+            stored.into_iter().map(|p| (p.user_id.clone(), p)).collect();
+        for user_id in user_ids {
+            by_user
+                .entry(user_id.clone())
+                .or_insert_with(|| WatchNotificationPreferences::defaults_for(user_id));
+        }
+        Ok(by_user)
+    }
+
+    /// Email addresses keyed by user id. Watchers that aren't user records are
+    /// skipped.
+    async fn emails_for(&self, user_ids: &[String]) -> Result<HashMap<String, String>, TicketWatchError> {
+        let records: Vec<Thing> = user_ids
+            .iter()
+            .filter_map(|id| surrealdb::sql::thing(id).ok())
+            .collect();
+        if records.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let users: Vec<UserEmail> = self
+            .db
+            .query("SELECT id, email FROM $users")
+            .bind(("users", records))
+            .traced("ticket_watch_service.emails_for")
+            .await?
+            .take(0)?;
+        Ok(users.into_iter().map(|u| (u.id.to_string(), u.email)).collect())
+    }
+
+    // ========================================================================
+    // NOTIFICATIONS
+    // ========================================================================
+
+    /// Record an event on `ticket` and email the watchers who want immediate
+    /// notifications. The actor is never notified about their own change.
+    pub async fn notify(
+        &self,
+        ticket: &Ticket,
+        event_type: HistoryChangeType,
+        summary: &str,
+        actor: &AuthenticatedUser,
+    ) -> Result<(), TicketWatchError> {
+        let Some(ticket_id) = ticket.id.clone() else { return Ok(()) };
+        if ticket.watchers.is_empty() {
+            return Ok(());
+        }
+
+        let event = TicketWatchEvent {
+            id: None,
+            ticket_id: ticket_id.clone(),
+            ticket_title: ticket.title.clone(),
+            event_type,
+            summary: summary.to_string(),
+            actor_id: actor.user_id.clone(),
+            actor_name: actor.username.clone(),
+            watchers: ticket.watchers.clone(),
+            created_at: Utc::now(),
+        };
+        let _: Vec<TicketWatchEvent> = self.db.create("ticket_watch_events").content(&event).await?;
+
+        let recipients: Vec<String> = ticket
+            .watchers
+            .iter()
+            .filter(|w| **w != actor.user_id)
+            .cloned()
+            .collect();
+        if recipients.is_empty() {
+            return Ok(());
+        }
+        let preferences = self.preferences_for(&recipients).await?;
+        let immediate: Vec<String> = recipients
+            .into_iter()
+            .filter(|r| preferences.get(r).map_or(true, |p| p.immediate))
+            .collect();
+        let emails: Vec<String> = self.emails_for(&immediate).await?.into_values().collect();
+        if emails.is_empty() {
+            return Ok(());
+        }
+
+        let subject = format!("[{}] {}", ticket_id, ticket.title);
+        let body = format!("{}\n\n{} · {}", summary, actor.username, event.created_at.format("%Y-%m-%d %H:%M UTC"));
+        for result in self.notifier.send_email(&emails, &subject, &body, None).await {
+            info!("Watch notification to {}: {:?}", result.target, result.status);
+        }
+        Ok(())
+    }
+
+    /// Send each digest subscriber a summary of watched-ticket activity since
+    /// their last digest. Returns the number of digests sent.
+    pub async fn send_daily_digests(&self) -> Result<usize, TicketWatchError> {
+        let subscribers: Vec<WatchNotificationPreferences> = self
+            .db
+            .query("SELECT * FROM ticket_watch_preferences WHERE daily_digest = true")
+            .traced("ticket_watch_service.send_daily_digests")
+            .await?
+            .take(0)?;
+        if subscribers.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        let user_ids: Vec<String> = subscribers.iter().map(|s| s.user_id.clone()).collect();
+        let emails = self.emails_for(&user_ids).await?;
+        let mut sent = 0;
+
+        for mut subscriber in subscribers {
+            let since = subscriber
+                .last_digest_at
+                .unwrap_or_else(|| now - Duration::hours(FIRST_DIGEST_WINDOW_HOURS));
+            let events: Vec<TicketWatchEvent> = self
+                .db
+                .query(
+                    "SELECT * FROM ticket_watch_events \
+                     WHERE created_at > $since AND created_at <= $now \
+                     AND watchers CONTAINS $user AND actor_id != $user \
+                     ORDER BY created_at ASC",
+                )
+                .bind(("since", since))
+                .bind(("now", now))
+                .bind(("user", subscriber.user_id.clone()))
+                .traced("ticket_watch_service.send_daily_digests")
+                .await?
+                .take(0)?;
+
+            if let (Some(body), Some(email)) = (render_digest(&events), emails.get(&subscriber.user_id)) {
+                let subject = format!("Daily digest: activity on {} watched ticket(s)", count_tickets(&events));
+                for result in self.notifier.send_email(&[email.clone()], &subject, &body, None).await {
+                    info!("Watch digest to {}: {:?}", result.target, result.status);
+                }
+                sent += 1;
+            }
+
+            subscriber.last_digest_at = Some(now);
+            self.save_preferences(&subscriber).await?;
+        }
+        Ok(sent)
+    }
+}
+
+/// Fire-and-forget [`TicketWatchService::notify`], so the request that made
+/// the change doesn't wait on SMTP
+pub fn notify_watchers(
+    db: Arc<Database>,
+    ticket: Ticket,
+    event_type: HistoryChangeType,
+    summary: String,
+    actor: &AuthenticatedUser,
+) {
+    if ticket.watchers.is_empty() {
+        return;
+    }
+    let actor = actor.clone();
+    tokio::spawn(async move {
+        if let Err(e) = TicketWatchService::new(db).notify(&ticket, event_type, &summary, &actor).await {
+            warn!("Failed to notify ticket watchers: {}", e);
+        }
+    });
+}
+
+fn count_tickets(events: &[TicketWatchEvent]) -> usize {
+    events
+        .iter()
+        .map(|e| e.ticket_id.to_string())
+        .collect::<std::collections::HashSet<_>>()
+        .len()
+}
+
+/// Plain-text digest grouped by ticket; `None` when there is nothing to report
+fn render_digest(events: &[TicketWatchEvent]) -> Option<String> {
+    if events.is_empty() {
+        return None;
+    }
+    let mut by_ticket: BTreeMap<String, (String, Vec<&TicketWatchEvent>)> = BTreeMap::new();
+    for event in events {
+        by_ticket
+            .entry(event.ticket_id.to_string())
+            .or_insert_with(|| (event.ticket_title.clone(), Vec::new()))
+            .1
+            .push(event);
+    }
+
+    let mut body = String::from("Activity on tickets you watch:\n");
+    for (ticket_id, (title, events)) in by_ticket {
+        body.push_str(&format!("\n{} - {}\n", ticket_id, title));
+        for event in events {
+            body.push_str(&format!(
+                "  {} {}: {}\n",
+                event.created_at.format("%Y-%m-%d %H:%M"),
+                event.actor_name,
+                event.summary
+            ));
+        }
+    }
+    Some(body)
+}
+
+// ============================================================================
+// DIGEST SCHEDULER
+// ============================================================================
+
+pub struct WatchDigestScheduler {
+    db: Arc<Database>,
+}
+
+impl WatchDigestScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+
+        let cron = std::env::var("WATCH_DIGEST_CRON").unwrap_or_else(|_| DEFAULT_DIGEST_CRON.to_string());
+        let db = Arc::clone(&self.db);
+        let service = Arc::new(TicketWatchService::new(Arc::clone(&self.db)));
+        let job = Job::new_async(cron.as_str(), move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                let run = locks::run_exclusive(&db, locks::WATCH_DIGEST_LOCK, DIGEST_LOCK_TTL, service.send_daily_digests());
+                match run.await {
+                    Ok(None) => {}
+                    Ok(Some(Ok(count))) => info!("👀 Sent {} watch digest(s)", count),
+                    Ok(Some(Err(e))) => error!("❌ Watch digest run failed: {}", e),
+                    Err(e) => error!("❌ Watch digest lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create watch digest job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add watch digest job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+
+        // Same as the report scheduler: keep the scheduler alive for the process lifetime
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(ticket: &str, title: &str, summary: &str, minute: u32) -> TicketWatchEvent {
+        TicketWatchEvent {
+            id: None,
+            ticket_id: Thing::from(("ticket", ticket)),
+            ticket_title: title.to_string(),
+            event_type: HistoryChangeType::Comment,
+            summary: summary.to_string(),
+            actor_id: "users:alice".to_string(),
+            actor_name: "alice".to_string(),
+            watchers: vec!["users:bob".to_string()],
+            created_at: Utc.with_ymd_and_hms(2024, 3, 4, 9, minute, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_digest_groups_events_by_ticket() {
+        assert!(render_digest(&[]).is_none());
+
+        let events = vec![
+            event("a", "VPN down", "New comment", 0),
+            event("b", "Printer jam", "Status changed from New to Assigned", 5),
+            event("a", "VPN down", "Status changed from Assigned to InProgress", 10),
+        ];
+        let body = render_digest(&events).unwrap();
+        assert_eq!(count_tickets(&events), 2);
+        assert_eq!(body.matches("VPN down").count(), 1);
+        let vpn = body.find("ticket:a - VPN down").unwrap();
+        let printer = body.find("ticket:b - Printer jam").unwrap();
+        assert!(vpn < body.find("InProgress").unwrap() && body.find("InProgress").unwrap() < printer);
+    }
+
+    #[tokio::test]
+    async fn test_watch_unwatch_and_digest_preferences() {
+        let db = Arc::new(crate::database::new_test().await.unwrap());
+        db.query("CREATE ticket:w1 SET title = 'Disk full', type = 'INCIDENT', priority = 'P2', status = 'NEW', created_by = 'users:alice', watchers = [], created_at = time::now(), updated_at = time::now()")
+            .traced("ticket_watch_service.test_watch_unwatch_and_digest_preferences")
+            .await
+            .unwrap();
+        let service = TicketWatchService::new(db.clone());
+        let ticket = Thing::from(("ticket", "w1"));
+
+        service.watch(&ticket, "users:bob").await.unwrap();
+        let watched = service.watch(&ticket, "users:bob").await.unwrap();
+        assert_eq!(watched.watchers, vec!["users:bob".to_string()]);
+        assert_eq!(service.watched_tickets("users:bob").await.unwrap().len(), 1);
+
+        let unwatched = service.unwatch(&ticket, "users:bob").await.unwrap();
+        assert!(unwatched.watchers.is_empty());
+
+        let defaults = service.preferences("users:bob").await.unwrap();
+        assert!(defaults.immediate && !defaults.daily_digest);
+        service
+            .update_preferences(
+                "users:bob",
+                UpdateWatchPreferencesRequest {
+                    immediate: Some(false),
+                    daily_digest: Some(true),
+                },
+            )
+            .await
+            .unwrap();
+        let stored = service.preferences("users:bob").await.unwrap();
+        assert!(!stored.immediate && stored.daily_digest);
+    }
+}
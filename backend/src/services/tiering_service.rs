@@ -9,6 +9,7 @@ use surrealdb::sql::Thing;
 use tracing::{info, warn, error};
 
 use crate::database::Database;
+use crate::database::TracedQuery;
 
 // ============================================================================
 // TIER CONFIGURATION
@@ -241,6 +242,7 @@ impl TieringService {
         let result: Vec<serde_json::Value> = self.db
             .query(query)
             .bind(("cutoff", cutoff_date))
+            .traced("tiering_service.transition_hot_to_warm")
             .await?
             .take(0)?;
         
@@ -290,6 +292,7 @@ impl TieringService {
             "#)
             .bind(("cutoff", cutoff_date))
             .bind(("batch_size", self.config.batch_size))
+            .traced("tiering_service.run_archival_job")
             .await?
             .take(0)?;
         
@@ -339,6 +342,7 @@ impl TieringService {
         let ticket: Option<serde_json::Value> = self.db
             .query("SELECT * FROM ticket WHERE id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?
             .take(0)?;
         
@@ -352,6 +356,7 @@ impl TieringService {
                 GROUP ALL
             "#)
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?
             .take::<Option<serde_json::Value>>(0)?
             .and_then(|v| v.get("cnt").and_then(|c| c.as_i64()))
@@ -365,6 +370,7 @@ impl TieringService {
                 GROUP ALL
             "#)
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?
             .take::<Option<serde_json::Value>>(0)?
             .and_then(|v| v.get("cnt").and_then(|c| c.as_i64()))
@@ -379,6 +385,7 @@ impl TieringService {
                 LIMIT 10
             "#)
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?
             .take(0)?;
         
@@ -433,22 +440,26 @@ impl TieringService {
                 WHERE ticket_id = $id
             "#)
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?;
         
         // Delete from hot tables
         self.db
             .query("DELETE FROM ticket_comments WHERE ticket_id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?;
         
         self.db
             .query("DELETE FROM ticket_history WHERE ticket_id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?;
         
         self.db
             .query("DELETE FROM ticket WHERE id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.archive_single_ticket")
             .await?;
         
         // Log the operation
@@ -475,6 +486,7 @@ impl TieringService {
         let archived: Option<serde_json::Value> = self.db
             .query("SELECT * FROM ticket_archive WHERE original_id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.reheat_ticket")
             .await?
             .take(0)?;
         
@@ -546,17 +558,20 @@ impl TieringService {
                 WHERE original_ticket_id = $id
             "#)
             .bind(("id", ticket_id))
+            .traced("tiering_service.reheat_ticket")
             .await?;
         
         // Delete from archive tables
         self.db
             .query("DELETE FROM ticket_archive WHERE original_id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.reheat_ticket")
             .await?;
         
         self.db
             .query("DELETE FROM ticket_comments_archive WHERE original_ticket_id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.reheat_ticket")
             .await?;
         
         // Log the operation
@@ -590,6 +605,7 @@ impl TieringService {
                 WHERE id = $id
             "#)
             .bind(("id", ticket_id))
+            .traced("tiering_service.record_access")
             .await?;
         
         Ok(())
@@ -604,6 +620,7 @@ impl TieringService {
         let current: Option<serde_json::Value> = self.db
             .query("SELECT tier FROM ticket WHERE id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.on_status_change")
             .await?
             .take(0)?;
         
@@ -622,6 +639,7 @@ impl TieringService {
                 "#)
                 .bind(("id", ticket_id))
                 .bind(("tier", new_tier))
+                .traced("tiering_service.on_status_change")
                 .await?;
             
             self.log_operation(
@@ -657,6 +675,7 @@ impl TieringService {
                 WHERE tier = 'hot' {}
                 GROUP ALL
             "#, namespace_filter))
+            .traced("tiering_service.get_stats")
             .await?
             .take::<Option<serde_json::Value>>(0)?
             .and_then(|v| v.get("cnt").and_then(|c| c.as_i64()))
@@ -668,6 +687,7 @@ impl TieringService {
                 WHERE tier = 'warm' {}
                 GROUP ALL
             "#, namespace_filter))
+            .traced("tiering_service.get_stats")
             .await?
             .take::<Option<serde_json::Value>>(0)?
             .and_then(|v| v.get("cnt").and_then(|c| c.as_i64()))
@@ -678,6 +698,7 @@ impl TieringService {
                 SELECT count() as cnt FROM ticket_archive {}
                 GROUP ALL
             "#, if namespace.is_some() { format!("WHERE namespace = '{}'", namespace.unwrap()) } else { String::new() }))
+            .traced("tiering_service.get_stats")
             .await?
             .take::<Option<serde_json::Value>>(0)?
             .and_then(|v| v.get("cnt").and_then(|c| c.as_i64()))
@@ -694,6 +715,7 @@ impl TieringService {
                 GROUP ALL
             "#)
             .bind(("since", yesterday))
+            .traced("tiering_service.get_stats")
             .await?
             .take::<Option<serde_json::Value>>(0)?
             .and_then(|v| v.get("cnt").and_then(|c| c.as_i64()))
@@ -707,6 +729,7 @@ impl TieringService {
                 GROUP ALL
             "#)
             .bind(("since", yesterday))
+            .traced("tiering_service.get_stats")
             .await?
             .take::<Option<serde_json::Value>>(0)?
             .and_then(|v| v.get("cnt").and_then(|c| c.as_i64()))
@@ -720,6 +743,7 @@ impl TieringService {
                 ORDER BY created_at DESC 
                 LIMIT 1
             "#)
+            .traced("tiering_service.get_stats")
             .await?
             .take(0)?;
         
@@ -783,6 +807,7 @@ impl TieringService {
         let result: Option<serde_json::Value> = self.db
             .query("SELECT id FROM ticket_archive WHERE original_id = $id LIMIT 1")
             .bind(("id", ticket_id))
+            .traced("tiering_service.is_archived")
             .await?
             .take(0)?;
         
@@ -794,6 +819,7 @@ impl TieringService {
         let archived: Option<TicketArchive> = self.db
             .query("SELECT * FROM ticket_archive WHERE original_id = $id")
             .bind(("id", ticket_id))
+            .traced("tiering_service.get_from_archive")
             .await?
             .take(0)?;
         
@@ -822,6 +848,7 @@ impl TieringService {
                 LIMIT 50
             "#, namespace_filter))
             .bind(("query", query))
+            .traced("tiering_service.search_all_tiers")
             .await?
             .take(0)?;
         
@@ -837,6 +864,7 @@ impl TieringService {
                     LIMIT 50
                 "#, if namespace.is_some() { format!("AND namespace = '{}'", namespace.unwrap()) } else { String::new() }))
                 .bind(("query", query))
+                .traced("tiering_service.search_all_tiers")
                 .await?
                 .take(0)?;
             
@@ -853,6 +881,11 @@ impl TieringService {
 
 use tokio_cron_scheduler::{Job, JobScheduler};
 
+use crate::database::locks;
+
+/// Lease on the nightly archival run; renewed while it runs
+const ARCHIVAL_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
 /// Background scheduler for automatic ticket archival
 pub struct TieringScheduler {
     db: Arc<Database>,
@@ -888,10 +921,15 @@ impl TieringScheduler {
             let db = Arc::clone(&db);
             let config = config.clone();
             Box::pin(async move {
-                info!("🗄️ Starting scheduled archival job");
-                let service = TieringService::with_config(db, config);
-                match service.run_archival_job().await {
-                    Ok(report) => {
+                let service = TieringService::with_config(Arc::clone(&db), config);
+                let run = locks::run_exclusive(&db, locks::TIERING_ARCHIVAL_LOCK, ARCHIVAL_LOCK_TTL, async {
+                    info!("🗄️ Starting scheduled archival job");
+                    service.run_archival_job().await
+                });
+                match run.await {
+                    Ok(None) => info!("🗄️ Archival job already running on another replica"),
+                    Err(e) => error!("❌ Archival job lock failed: {}", e),
+                    Ok(Some(Ok(report))) => {
                         info!(
                             "✅ Archival job completed: {} processed, {} transitioned to warm, {} archived, {} errors",
                             report.processed,
@@ -900,7 +938,7 @@ impl TieringScheduler {
                             report.errors.len()
                         );
                     }
-                    Err(e) => {
+                    Ok(Some(Err(e))) => {
                         error!("❌ Archival job failed: {}", e);
                     }
                 }
@@ -7,7 +7,8 @@
 //! - Historical data (future enhancement)
 
 use serde::{Deserialize, Serialize};
-use surrealdb::{engine::local::Db, Surreal};
+use crate::database::Db;
+use surrealdb::Surreal;
 
 use crate::models::workflow::{
     EstimationConfidence, InfrastructureType, TaskEstimate, TimelineEstimationResult,
@@ -67,7 +68,7 @@ impl TimelineEstimationService {
     /// - Traditional: 7 days (network, storage, compute setup)
     /// - HCI S2D: 10 days (additional S2D configuration)
     /// - Azure Local: 14 days (Azure Arc registration, cloud integration)
-    fn calculate_prep_time(request: &TimelineEstimationRequest) -> u32 {
+    pub(crate) fn calculate_prep_time(request: &TimelineEstimationRequest) -> u32 {
         let base_days = match request.infrastructure_type {
             InfrastructureType::Traditional => 7,
             InfrastructureType::HciS2d => 10,
@@ -92,7 +93,7 @@ impl TimelineEstimationService {
     /// Adjusted for:
     /// - Compatibility issues (25% slower)
     /// - Minimum 1 day even for small workloads
-    fn calculate_migration_time(request: &TimelineEstimationRequest) -> u32 {
+    pub(crate) fn calculate_migration_time(request: &TimelineEstimationRequest) -> u32 {
         // Base migration rate (VMs per day)
         let base_vm_rate = 10.0;
 
@@ -121,7 +122,7 @@ impl TimelineEstimationService {
     /// - Base: 7 days (1 week)
     /// - Large workloads (>200 VMs): +3 days
     /// - Medium workloads (>100 VMs): +2 days
-    fn calculate_validation_time(request: &TimelineEstimationRequest) -> u32 {
+    pub(crate) fn calculate_validation_time(request: &TimelineEstimationRequest) -> u32 {
         let base_days = 7;
 
         // Scale with complexity
@@ -0,0 +1,1545 @@
+//! Migration Timeline Service
+//!
+//! Turns wave plans into a durable, Gantt-style project schedule:
+//! - Wave plan management (manual waves or auto-generated from placements)
+//! - Per-wave runbooks, including backup jobs affected by the move, the
+//!   wave's readiness scorecard on the cover page, its rollback plan and
+//!   the open snapshot and VMware Tools remediation tasks of its VMs, and
+//!   static IP and mount checks from in-guest scans
+//! - Timeline generation using the timeline estimation heuristics
+//! - Critical path calculation (forward/backward pass with FS/SS/FF links and lag)
+//! - Export to MS Project XML (MSPDI) and CSV
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+use surrealdb::sql::{Id, Thing};
+
+use crate::database::Database;
+use crate::models::timeline::*;
+use crate::models::workflow::{DependencyType, InfrastructureType};
+use crate::models::migration_wizard_models::MigrationWizardVM;
+use crate::models::webhook::WebhookEventType;
+use crate::services::integration_hub::dependencies::{
+    backup_coverage, load_balanced_groups, log_pipeline, BackupJobCoverage, LogPipeline,
+};
+use crate::services::build_checklist_service::BuildChecklistService;
+use crate::services::cutover_readiness_service::CutoverReadinessService;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::rollback_plan_service::{rollback_markdown, RollbackPlanService};
+use crate::services::vm_hygiene_service::{self, VmHygieneService};
+use crate::services::guest_scan::{service as guest_scan_service, GuestScanService};
+use crate::services::webhook_service;
+use crate::services::timeline_estimation_service::{
+    TimelineEstimationRequest, TimelineEstimationService,
+};
+use crate::database::TracedQuery;
+
+const DEFAULT_VMS_PER_WAVE: usize = 25;
+const HOURS_PER_DAY: i64 = 8;
+
+/// Lightweight wave descriptor used when building the task graph
+#[derive(Debug, Clone)]
+pub struct WaveSummary {
+    pub id: String,
+    pub name: String,
+    pub vm_count: usize,
+}
+
+pub struct TimelineService {
+    db: Database,
+}
+
+impl TimelineService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    // =========================================================================
+    // WAVE PLAN MANAGEMENT
+    // =========================================================================
+
+    /// List waves for a project in execution order
+    pub async fn list_waves(&self, project_id: &str) -> Result<Vec<MigrationWave>> {
+        let waves: Vec<MigrationWave> = self
+            .db
+            .query("SELECT * FROM migration_wave WHERE project_id = type::thing('migration_wizard_project', $project_id) ORDER BY sequence ASC")
+            .bind(("project_id", project_id.to_string()))
+            .traced("timeline_service.list_waves")
+            .await
+            .context("Failed to query migration waves")?
+            .take(0)
+            .context("Failed to parse migration waves")?;
+
+        Ok(waves)
+    }
+
+    /// Get a single wave by ID
+    pub async fn get_wave(&self, wave_id: &str) -> Result<MigrationWave> {
+        let wave: Option<MigrationWave> = self
+            .db
+            .select(("migration_wave", wave_id))
+            .await
+            .context("Failed to get migration wave")?;
+
+        wave.ok_or_else(|| anyhow!("Migration wave not found"))
+    }
+
+    /// Create a wave manually
+    pub async fn create_wave(
+        &self,
+        project_id: &str,
+        request: CreateWaveRequest,
+    ) -> Result<MigrationWave> {
+        let existing = self.list_waves(project_id).await?;
+        let sequence = request
+            .sequence
+            .unwrap_or_else(|| existing.iter().map(|w| w.sequence).max().unwrap_or(0) + 1);
+
+        let now = Utc::now();
+        let wave = MigrationWave {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", project_id)),
+            name: request.name,
+            sequence,
+            vm_ids: request
+                .vm_ids
+                .iter()
+                .map(|id| Thing::from(("migration_wizard_vm", id.as_str())))
+                .collect(),
+            target_cluster_ids: request
+                .target_cluster_ids
+                .iter()
+                .map(|id| Thing::from(("migration_wizard_cluster", id.as_str())))
+                .collect(),
+            planned_start: request.planned_start,
+            planned_end: None,
+            change_window: request.change_window,
+            status: WaveStatus::Planned,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<MigrationWave> = self
+            .db
+            .create("migration_wave")
+            .content(wave)
+            .await
+            .context("Failed to create migration wave")?;
+
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No wave returned after creation"))
+    }
+
+    /// Delete a wave
+    /// Move a wave to a new status. A wave only starts once the build
+    /// checklist of every destination cluster it targets is complete.
+    pub async fn update_wave_status(&self, wave_id: &str, status: WaveStatus) -> Result<WaveTransition> {
+        let mut wave = self.get_wave(wave_id).await?;
+        if status == WaveStatus::InProgress && wave.status != WaveStatus::InProgress {
+            let blockers = BuildChecklistService::new(self.db.clone())
+                .wave_blockers(&wave.target_cluster_ids)
+                .await
+                .context("Failed to check destination cluster builds")?;
+            if !blockers.is_empty() {
+                return Ok(WaveTransition::Blocked(blockers));
+            }
+        }
+
+        let previous_status = std::mem::replace(&mut wave.status, status);
+        wave.updated_at = Utc::now();
+        let updated: Option<MigrationWave> = self
+            .db
+            .update(("migration_wave", wave_id))
+            .content(wave)
+            .await
+            .context("Failed to update migration wave")?;
+        let updated = updated.ok_or_else(|| anyhow!("Migration wave not found"))?;
+
+        if updated.status != previous_status {
+            webhook_service::emit_for_project(
+                &self.db,
+                WebhookEventType::WaveStatusChanged,
+                updated.project_id.clone(),
+                serde_json::json!({
+                    "wave_id": updated.id.as_ref().map(|id| id.to_string()),
+                    "project_id": updated.project_id.to_string(),
+                    "name": updated.name,
+                    "sequence": updated.sequence,
+                    "status": updated.status,
+                    "previous_status": previous_status,
+                    "vm_count": updated.vm_ids.len(),
+                    "planned_start": updated.planned_start,
+                    "planned_end": updated.planned_end,
+                }),
+            );
+        }
+        Ok(WaveTransition::Updated(updated))
+    }
+
+    pub async fn delete_wave(&self, wave_id: &str) -> Result<()> {
+        let _: Option<MigrationWave> = self
+            .db
+            .delete(("migration_wave", wave_id))
+            .await
+            .context("Failed to delete migration wave")?;
+        Ok(())
+    }
+
+    /// Generate waves from the project's placements.
+    ///
+    /// VMs are grouped by destination cluster and chunked into waves of at most
+    /// `max_vms_per_wave`. Projects without placements fall back to chunking the
+    /// raw VM inventory so a schedule can still be produced early in planning.
+    /// VMs serving the same load-balancer pool (imported from BIG-IP) are kept
+    /// in one wave unless `ignore_load_balancing` is set.
+    pub async fn generate_waves(
+        &self,
+        project_id: &str,
+        request: GenerateWavesRequest,
+    ) -> Result<Vec<MigrationWave>> {
+        let existing = self.list_waves(project_id).await?;
+        if !existing.is_empty() {
+            if !request.replace_existing {
+                bail!("Project already has {} waves; set replace_existing to regenerate", existing.len());
+            }
+            self.db
+                .query("DELETE migration_wave WHERE project_id = type::thing('migration_wizard_project', $project_id)")
+                .bind(("project_id", project_id.to_string()))
+                .traced("timeline_service.generate_waves")
+                .await
+                .context("Failed to delete existing waves")?;
+        }
+
+        let max_per_wave = request.max_vms_per_wave.unwrap_or(DEFAULT_VMS_PER_WAVE).max(1);
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let placements = wizard.get_project_placements(project_id).await?;
+        let project_vms = wizard.get_project_vms(project_id, None).await?;
+
+        // (label, target cluster, vm ids) in a stable order
+        let mut groups: Vec<(String, Option<Thing>, Vec<Thing>)> = Vec::new();
+
+        if placements.is_empty() {
+            let vm_ids: Vec<Thing> = project_vms.iter().filter_map(|vm| vm.id.clone()).collect();
+            groups.push(("Unplaced".to_string(), None, vm_ids));
+        } else {
+            let clusters = wizard.get_project_clusters(project_id).await?;
+            let cluster_names: HashMap<String, String> = clusters
+                .iter()
+                .filter_map(|c| c.id.as_ref().map(|id| (id.to_string(), c.name.clone())))
+                .collect();
+
+            let mut index: HashMap<String, usize> = HashMap::new();
+            for placement in placements {
+                let key = placement.cluster_id.to_string();
+                let slot = *index.entry(key.clone()).or_insert_with(|| {
+                    let label = cluster_names
+                        .get(&key)
+                        .cloned()
+                        .unwrap_or_else(|| record_key(&placement.cluster_id));
+                    groups.push((label, Some(placement.cluster_id.clone()), Vec::new()));
+                    groups.len() - 1
+                });
+                groups[slot].2.push(placement.vm_id);
+            }
+        }
+
+        // VM -> index of the load-balancer pool it serves
+        let mut affinity: HashMap<String, usize> = HashMap::new();
+        if !request.ignore_load_balancing {
+            let pools = load_balanced_groups(&self.db)
+                .await
+                .context("Failed to load load-balancer dependencies")?;
+            for vm in &project_vms {
+                let Some(id) = &vm.id else { continue };
+                if let Some(index) = pools
+                    .iter()
+                    .position(|p| p.contains(&vm.name, vm.primary_ip_address.as_deref()))
+                {
+                    affinity.insert(id.to_string(), index);
+                }
+            }
+        }
+
+        let mut waves = Vec::new();
+        let mut sequence = 1u32;
+        for (label, cluster, vm_ids) in groups {
+            for chunk in pack_waves(vm_ids, &affinity, max_per_wave) {
+                let now = Utc::now();
+                let wave = MigrationWave {
+                    id: None,
+                    project_id: Thing::from(("migration_wizard_project", project_id)),
+                    name: format!("Wave {} - {}", sequence, label),
+                    sequence,
+                    vm_ids: chunk,
+                    target_cluster_ids: cluster.iter().cloned().collect(),
+                    planned_start: None,
+                    planned_end: None,
+                    change_window: None,
+                    status: WaveStatus::Planned,
+                    created_at: now,
+                    updated_at: now,
+                };
+
+                let created: Vec<MigrationWave> = self
+                    .db
+                    .create("migration_wave")
+                    .content(wave)
+                    .await
+                    .context("Failed to create migration wave")?;
+                waves.extend(created);
+                sequence += 1;
+            }
+        }
+
+        Ok(waves)
+    }
+
+    // =========================================================================
+    // WAVE RUNBOOK
+    // =========================================================================
+
+    /// Backup jobs that protect the wave's VMs and must follow them
+    pub async fn wave_backup_impact(&self, wave_id: &str) -> Result<BackupImpact> {
+        let wave = self.get_wave(wave_id).await?;
+        let vms = self.wave_vms(&wave).await?;
+        let jobs = backup_coverage(&self.db)
+            .await
+            .context("Failed to load backup jobs")?;
+        Ok(Self::assess_backup_impact(&vms, &jobs))
+    }
+
+    /// Splunk log sources that pause or need re-pointing when the wave moves
+    pub async fn wave_log_source_impact(&self, wave_id: &str) -> Result<LogSourceImpact> {
+        let wave = self.get_wave(wave_id).await?;
+        let vms = self.wave_vms(&wave).await?;
+        let pipeline = log_pipeline(&self.db)
+            .await
+            .context("Failed to load Splunk forwarders")?;
+        Ok(Self::assess_log_sources(&vms, &pipeline))
+    }
+
+    /// Build the cutover runbook for a wave
+    pub async fn wave_runbook(&self, wave_id: &str) -> Result<WaveRunbook> {
+        let wave = self.get_wave(wave_id).await?;
+        let vms = self.wave_vms(&wave).await?;
+        let jobs = backup_coverage(&self.db)
+            .await
+            .context("Failed to load backup jobs")?;
+        let backup_impact = Self::assess_backup_impact(&vms, &jobs);
+        let pipeline = log_pipeline(&self.db)
+            .await
+            .context("Failed to load Splunk forwarders")?;
+        let log_source_impact = Self::assess_log_sources(&vms, &pipeline);
+        let readiness = CutoverReadinessService::new(self.db.clone())
+            .wave_readiness(wave_id)
+            .await?;
+        let rollback_plan = RollbackPlanService::new(self.db.clone())
+            .for_wave(&wave, &vms, &backup_impact, &log_source_impact)
+            .await?;
+        let remediation = VmHygieneService::new(self.db.clone())
+            .open_tasks_for_vms(&wave.vm_ids)
+            .await?;
+
+        let project_id = record_key(&wave.project_id);
+        let clusters = MigrationWizardService::new(self.db.clone())
+            .get_project_clusters(&project_id)
+            .await?;
+        let target_clusters: Vec<String> = wave
+            .target_cluster_ids
+            .iter()
+            .map(|id| {
+                clusters
+                    .iter()
+                    .find(|c| c.id.as_ref() == Some(id))
+                    .map(|c| c.name.clone())
+                    .unwrap_or_else(|| record_key(id))
+            })
+            .collect();
+
+        let scans = GuestScanService::new(self.db.clone()).scans_for_vms(&wave.vm_ids).await?;
+        let (record_guests, verify_guests) = guest_scan_service::runbook_steps(&scans);
+
+        // Remediation and guest settings come last among the pre-migration
+        // steps; guest checks go just before the rollback-window step
+        let mut steps = Self::runbook_steps(vms.len(), &target_clusters, &backup_impact, &log_source_impact);
+        let at = steps.iter().take_while(|s| s.phase == "pre_migration").count();
+        steps.splice(at..at, vm_hygiene_service::runbook_steps(&remediation).into_iter().chain(record_guests));
+        let at = steps.len() - 1;
+        steps.splice(at..at, verify_guests);
+
+        Ok(WaveRunbook {
+            wave_id: wave_id.to_string(),
+            wave_name: wave.name.clone(),
+            sequence: wave.sequence,
+            planned_start: wave.planned_start,
+            change_window: wave.change_window.clone(),
+            steps,
+            target_clusters,
+            vms: vms
+                .iter()
+                .map(|vm| RunbookVm {
+                    id: vm.id.as_ref().map(record_key).unwrap_or_default(),
+                    name: vm.name.clone(),
+                    ip_address: vm.primary_ip_address.clone(),
+                    source_cluster: vm.cluster.clone(),
+                })
+                .collect(),
+            backup_impact,
+            log_source_impact,
+            readiness,
+            rollback_plan,
+        })
+    }
+
+    pub(crate) async fn wave_vms(&self, wave: &MigrationWave) -> Result<Vec<MigrationWizardVM>> {
+        let vms: Vec<MigrationWizardVM> = self
+            .db
+            .query("SELECT * FROM migration_wizard_vm WHERE id INSIDE $ids ORDER BY name")
+            .bind(("ids", wave.vm_ids.clone()))
+            .traced("timeline_service.wave_vms")
+            .await
+            .context("Failed to query wave VMs")?
+            .take(0)
+            .context("Failed to parse wave VMs")?;
+        Ok(vms)
+    }
+
+    /// Match wave VMs against imported backup jobs. Jobs track VMs by
+    /// hypervisor object id, which changes when a VM lands on a new cluster,
+    /// so every protecting job needs attention after cutover.
+    pub fn assess_backup_impact(vms: &[MigrationWizardVM], jobs: &[BackupJobCoverage]) -> BackupImpact {
+        let mut impact = BackupImpact::default();
+        for vm in vms {
+            let actions: Vec<BackupJobAction> = jobs
+                .iter()
+                .filter_map(|job| {
+                    let protected = job.protects(&vm.name)?;
+                    let action = if protected.included_directly {
+                        "Re-add the VM to the job after cutover; the job references the source VM object".to_string()
+                    } else {
+                        format!(
+                            "Confirm the target location is covered by the job scope (currently via {})",
+                            protected.via.as_deref().unwrap_or("a container")
+                        )
+                    };
+                    Some(BackupJobAction {
+                        job_name: job.name.clone(),
+                        repository: job.repository.clone(),
+                        action,
+                    })
+                })
+                .collect();
+
+            if actions.is_empty() {
+                impact.unprotected_vms.push(vm.name.clone());
+                continue;
+            }
+            for action in &actions {
+                if !impact.jobs_to_reconfigure.contains(&action.job_name) {
+                    impact.jobs_to_reconfigure.push(action.job_name.clone());
+                }
+            }
+            impact.affected_vms.push(VmBackupImpact {
+                vm_id: vm.id.as_ref().map(record_key).unwrap_or_default(),
+                vm_name: vm.name.clone(),
+                jobs: actions,
+            });
+        }
+        impact.jobs_to_reconfigure.sort();
+        impact
+    }
+
+    /// Match wave VMs against imported Splunk servers. Forwarders on wave VMs
+    /// stop sending during cutover; forwarders elsewhere that send to an
+    /// indexer in the wave must be re-pointed if its address changes.
+    pub fn assess_log_sources(vms: &[MigrationWizardVM], pipeline: &LogPipeline) -> LogSourceImpact {
+        let mut impact = LogSourceImpact::default();
+
+        for forwarder in &pipeline.forwarders {
+            if let Some(vm) = find_vm(vms, |name, ip| forwarder.is_vm(name, ip)) {
+                impact.forwarders.push(AffectedForwarder {
+                    vm_name: vm.name.clone(),
+                    forwarder: forwarder.name.clone(),
+                    forwarder_type: forwarder.forwarder_type.clone(),
+                    indexers: forwarder.outputs.clone(),
+                });
+            }
+        }
+        for server in &pipeline.search_heads {
+            if find_vm(vms, |name, ip| server.is_vm(name, ip)).is_some() {
+                impact.moving_servers.push(server.name.clone());
+            }
+        }
+        for indexer in &pipeline.indexers {
+            if find_vm(vms, |name, ip| indexer.is_vm(name, ip)).is_none() {
+                continue;
+            }
+            impact.moving_servers.push(indexer.name.clone());
+            let forwarders: Vec<String> = pipeline
+                .forwarders
+                .iter()
+                .filter(|f| f.sends_to(&indexer.name))
+                .map(|f| f.name.clone())
+                .collect();
+            if !forwarders.is_empty() {
+                impact.forwarders_to_repoint.push(ForwarderRepoint {
+                    indexer: indexer.name.clone(),
+                    forwarders,
+                });
+            }
+        }
+        impact
+    }
+
+    fn runbook_steps(
+        vm_count: usize,
+        target_clusters: &[String],
+        backup: &BackupImpact,
+        logs: &LogSourceImpact,
+    ) -> Vec<RunbookStep> {
+        let step = |phase: &str, action: String| RunbookStep {
+            phase: phase.to_string(),
+            action,
+        };
+        let targets = if target_clusters.is_empty() {
+            "the target cluster".to_string()
+        } else {
+            target_clusters.join(", ")
+        };
+
+        let mut steps = vec![
+            step("pre_migration", "Confirm the change window and notify application owners".to_string()),
+            step("pre_migration", format!("Verify capacity and network mappings on {}", targets)),
+        ];
+        if !backup.affected_vms.is_empty() {
+            steps.push(step(
+                "pre_migration",
+                format!("Confirm a successful backup of {} protected VM(s) within 24 hours", backup.affected_vms.len()),
+            ));
+        }
+        if !backup.unprotected_vms.is_empty() {
+            steps.push(step(
+                "pre_migration",
+                format!("Take a manual backup or snapshot of {} unprotected VM(s)", backup.unprotected_vms.len()),
+            ));
+        }
+        if !logs.forwarders.is_empty() {
+            steps.push(step(
+                "pre_migration",
+                format!(
+                    "Notify the security team that {} Splunk log source(s) pause during cutover",
+                    logs.forwarders.len()
+                ),
+            ));
+        }
+        steps.extend([
+            step("migration", format!("Replicate {} VM(s) to {}", vm_count, targets)),
+            step("migration", "Shut down source VMs and run final sync".to_string()),
+            step("migration", "Power on VMs on the target and validate connectivity".to_string()),
+            step("post_migration", "Run application smoke tests with the owners".to_string()),
+        ]);
+        for job in &backup.jobs_to_reconfigure {
+            steps.push(step("post_migration", format!("Reconfigure backup job '{}' and run it once", job)));
+        }
+        for repoint in &logs.forwarders_to_repoint {
+            steps.push(step(
+                "post_migration",
+                format!(
+                    "Re-point {} forwarder(s) to indexer '{}' at its new address (outputs.conf or deployment server)",
+                    repoint.forwarders.len(),
+                    repoint.indexer
+                ),
+            ));
+        }
+        if !logs.forwarders.is_empty() {
+            steps.push(step(
+                "post_migration",
+                format!(
+                    "Confirm the {} migrated forwarder(s) phone home and their events reach the indexers",
+                    logs.forwarders.len()
+                ),
+            ));
+        }
+        steps.push(step("post_migration", "Keep source VMs powered off until the rollback window ends".to_string()));
+        steps
+    }
+
+    /// Render a runbook as Markdown for change tickets and wikis
+    pub fn runbook_markdown(runbook: &WaveRunbook) -> String {
+        let mut out = format!("# {} (wave {})\n\n", runbook.wave_name, runbook.sequence);
+        if let Some(start) = runbook.planned_start {
+            out.push_str(&format!("- Planned start: {}\n", start.format("%Y-%m-%d %H:%M UTC")));
+        }
+        if let Some(window) = &runbook.change_window {
+            out.push_str(&format!("- Change window: {}\n", window));
+        }
+        if !runbook.target_clusters.is_empty() {
+            out.push_str(&format!("- Target: {}\n", runbook.target_clusters.join(", ")));
+        }
+        out.push_str(&format!("- VMs: {}\n", runbook.vms.len()));
+
+        let readiness = &runbook.readiness;
+        out.push_str(&format!(
+            "- Readiness: **{}** ({}/100)\n\n## Readiness\n\n| Signal | Rating | Status |\n|---|---|---|\n",
+            readiness.rating.label(),
+            readiness.score
+        ));
+        for signal in &readiness.signals {
+            out.push_str(&format!("| {} | {} | {} |\n", signal.name, signal.rating.label(), signal.summary));
+        }
+        if readiness.blocking_items.is_empty() {
+            out.push_str("\nNo blocking items.\n");
+        } else {
+            out.push_str("\nBlocking items:\n\n");
+            for item in &readiness.blocking_items {
+                out.push_str(&format!("- {}\n", item.message));
+            }
+        }
+        let warnings: Vec<&String> = readiness.signals.iter().flat_map(|s| &s.warnings).collect();
+        if !warnings.is_empty() {
+            out.push_str("\nWarnings:\n\n");
+            for warning in warnings {
+                out.push_str(&format!("- {}\n", warning));
+            }
+        }
+
+        out.push_str("\n## Virtual machines\n\n");
+        out.push_str("| VM | IP address | Source cluster |\n|---|---|---|\n");
+        for vm in &runbook.vms {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                vm.name,
+                vm.ip_address.as_deref().unwrap_or("-"),
+                vm.source_cluster.as_deref().unwrap_or("-")
+            ));
+        }
+
+        let phases = [
+            ("pre_migration", "Pre-migration"),
+            ("migration", "Migration"),
+            ("post_migration", "Post-migration"),
+        ];
+        for (phase, title) in phases {
+            out.push_str(&format!("\n## {}\n\n", title));
+            for step in runbook.steps.iter().filter(|s| s.phase == phase) {
+                out.push_str(&format!("- [ ] {}\n", step.action));
+            }
+        }
+
+        let backup = &runbook.backup_impact;
+        out.push_str("\n## Backup impact\n\n");
+        if backup.affected_vms.is_empty() {
+            out.push_str("No imported backup job protects the VMs in this wave.\n");
+        } else {
+            out.push_str("| VM | Backup job | Repository | Action |\n|---|---|---|---|\n");
+            for vm in &backup.affected_vms {
+                for job in &vm.jobs {
+                    out.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        vm.vm_name,
+                        job.job_name,
+                        job.repository.as_deref().unwrap_or("-"),
+                        job.action
+                    ));
+                }
+            }
+        }
+        if !backup.unprotected_vms.is_empty() {
+            out.push_str(&format!("\nNot protected by any imported job: {}\n", backup.unprotected_vms.join(", ")));
+        }
+
+        let logs = &runbook.log_source_impact;
+        out.push_str("\n## Log sources\n\n");
+        if logs.forwarders.is_empty() {
+            out.push_str("No imported Splunk forwarder runs on the VMs in this wave.\n");
+        } else {
+            out.push_str("| VM | Forwarder | Type | Indexers |\n|---|---|---|---|\n");
+            for forwarder in &logs.forwarders {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    forwarder.vm_name,
+                    forwarder.forwarder,
+                    forwarder.forwarder_type.as_deref().unwrap_or("-"),
+                    forwarder.indexers.join(", ")
+                ));
+            }
+        }
+        for repoint in &logs.forwarders_to_repoint {
+            out.push_str(&format!(
+                "\nForwarders to re-point from {}: {}\n",
+                repoint.indexer,
+                repoint.forwarders.join(", ")
+            ));
+        }
+
+        out.push('\n');
+        out.push_str(&rollback_markdown(&runbook.rollback_plan));
+        out
+    }
+
+    // =========================================================================
+    // TIMELINE GENERATION
+    // =========================================================================
+
+    /// Get the stored timeline for a project, if one has been generated
+    pub async fn get_timeline(&self, project_id: &str) -> Result<Option<ProjectTimeline>> {
+        let timelines: Vec<ProjectTimeline> = self
+            .db
+            .query("SELECT * FROM project_timeline WHERE project_id = type::thing('migration_wizard_project', $project_id) ORDER BY updated_at DESC LIMIT 1")
+            .bind(("project_id", project_id.to_string()))
+            .traced("timeline_service.get_timeline")
+            .await
+            .context("Failed to query project timeline")?
+            .take(0)
+            .context("Failed to parse project timeline")?;
+
+        Ok(timelines.into_iter().next())
+    }
+
+    /// Generate (or regenerate) the project timeline from its wave plan
+    pub async fn generate_timeline(
+        &self,
+        project_id: &str,
+        request: GenerateTimelineRequest,
+    ) -> Result<ProjectTimeline> {
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let project = wizard.get_project(project_id).await?;
+
+        let waves = self.list_waves(project_id).await?;
+        if waves.is_empty() {
+            bail!("No migration waves defined for this project; generate or create waves first");
+        }
+
+        let summaries: Vec<WaveSummary> = waves
+            .iter()
+            .map(|w| WaveSummary {
+                id: w.id.as_ref().map(record_key).unwrap_or_default(),
+                name: w.name.clone(),
+                vm_count: w.vm_ids.len(),
+            })
+            .collect();
+
+        let start_date = request.start_date.unwrap_or_else(Utc::now);
+        let resources = request.resources.clone().unwrap_or_else(Self::default_resources);
+        let infrastructure_type = request
+            .infrastructure_type
+            .clone()
+            .unwrap_or(InfrastructureType::Traditional);
+
+        let (phases, mut tasks) = Self::build_tasks(
+            &summaries,
+            &resources,
+            infrastructure_type,
+            request.has_compatibility_issues,
+        );
+        let (critical_path, total_days) = Self::calculate_critical_path(&mut tasks)?;
+        Self::apply_dates(&mut tasks, start_date);
+
+        // Keep wave records in sync with their scheduled window
+        for (wave, summary) in waves.iter().zip(summaries.iter()) {
+            let wave_tasks: Vec<&TimelineTask> = tasks
+                .iter()
+                .filter(|t| t.wave_id.as_deref() == Some(summary.id.as_str()))
+                .collect();
+            let start = wave_tasks.iter().map(|t| t.start_date).min();
+            let end = wave_tasks.iter().map(|t| t.end_date).max();
+            if let (Some(id), Some(start), Some(end)) = (wave.id.clone(), start, end) {
+                let _: Option<MigrationWave> = self
+                    .db
+                    .update(id)
+                    .merge(serde_json::json!({
+                        "planned_start": start,
+                        "planned_end": end,
+                        "status": "scheduled",
+                        "updated_at": Utc::now(),
+                    }))
+                    .await
+                    .context("Failed to update wave schedule")?;
+            }
+        }
+
+        self.db
+            .query("DELETE project_timeline WHERE project_id = type::thing('migration_wizard_project', $project_id)")
+            .bind(("project_id", project_id.to_string()))
+            .traced("timeline_service.generate_timeline")
+            .await
+            .context("Failed to delete previous timeline")?;
+
+        let now = Utc::now();
+        let timeline = ProjectTimeline {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", project_id)),
+            name: format!("{} - Migration Timeline", project.name),
+            start_date,
+            end_date: start_date + Duration::days(total_days),
+            total_duration_days: total_days.max(0) as u32,
+            phases,
+            tasks,
+            resources,
+            critical_path,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let created: Vec<ProjectTimeline> = self
+            .db
+            .create("project_timeline")
+            .content(timeline)
+            .await
+            .context("Failed to store project timeline")?;
+
+        created
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No timeline returned after creation"))
+    }
+
+    /// Default role-based resource pool used when the request doesn't supply one
+    pub fn default_resources() -> Vec<TimelineResource> {
+        vec![
+            TimelineResource {
+                id: "res-infra".to_string(),
+                name: "Infrastructure Engineer".to_string(),
+                role: "infrastructure_engineer".to_string(),
+                allocation_percent: 100,
+            },
+            TimelineResource {
+                id: "res-migration".to_string(),
+                name: "Migration Engineer".to_string(),
+                role: "migration_engineer".to_string(),
+                allocation_percent: 100,
+            },
+            TimelineResource {
+                id: "res-app".to_string(),
+                name: "Application Owner".to_string(),
+                role: "application_owner".to_string(),
+                allocation_percent: 50,
+            },
+            TimelineResource {
+                id: "res-pm".to_string(),
+                name: "Project Manager".to_string(),
+                role: "project_manager".to_string(),
+                allocation_percent: 25,
+            },
+        ]
+    }
+
+    /// Build phases and tasks for a wave plan using the estimation heuristics
+    pub fn build_tasks(
+        waves: &[WaveSummary],
+        resources: &[TimelineResource],
+        infrastructure_type: InfrastructureType,
+        has_compatibility_issues: bool,
+    ) -> (Vec<TimelinePhase>, Vec<TimelineTask>) {
+        let total_vms: usize = waves.iter().map(|w| w.vm_count).sum();
+        let estimate_for = |vm_count: usize| TimelineEstimationRequest {
+            vm_count: vm_count as u32,
+            host_count: 0,
+            infrastructure_type: infrastructure_type.clone(),
+            has_compatibility_issues,
+        };
+        let assign = |role: &str| -> Vec<String> {
+            resources
+                .iter()
+                .filter(|r| r.role == role)
+                .map(|r| r.id.clone())
+                .collect()
+        };
+
+        let phases = vec![
+            TimelinePhase { id: "prep".to_string(), name: "Infrastructure Preparation".to_string(), order: 1 },
+            TimelinePhase { id: "migration".to_string(), name: "Migration Waves".to_string(), order: 2 },
+            TimelinePhase { id: "closeout".to_string(), name: "Validation & Handoff".to_string(), order: 3 },
+        ];
+
+        let mut tasks = Vec::new();
+        let infra = assign("infrastructure_engineer");
+        let prep_days = TimelineEstimationService::calculate_prep_time(&estimate_for(total_vms));
+
+        tasks.push(new_task("prep-infra", "Infrastructure Preparation", "prep", None, prep_days, vec![], infra.clone()));
+        tasks.push(new_task("prep-hw", "Hardware Deployment", "prep", None, 5, vec![fs("prep-infra")], infra.clone()));
+        tasks.push(new_task("prep-net", "Network Configuration", "prep", None, 3, vec![fs("prep-hw")], infra.clone()));
+        tasks.push(new_task("prep-storage", "Storage Configuration", "prep", None, 3, vec![fs("prep-hw")], infra.clone()));
+        tasks.push(new_task("prep-cluster", "Cluster Configuration", "prep", None, 3, vec![fs("prep-net"), fs("prep-storage")], infra.clone()));
+        tasks.push(new_task("prep-hypervisor", "Hypervisor Configuration", "prep", None, 2, vec![fs("prep-cluster")], infra));
+        tasks.push(new_task("prep-ready", "Migration Readiness", "prep", None, 0, vec![fs("prep-hypervisor")], vec![]));
+
+        let migration = assign("migration_engineer");
+        let app = assign("application_owner");
+        let mut previous_migrate: Option<String> = None;
+        let mut wave_validations = Vec::new();
+
+        for (idx, wave) in waves.iter().enumerate() {
+            let n = idx + 1;
+            let precheck_id = format!("wave-{}-precheck", n);
+            let migrate_id = format!("wave-{}-migrate", n);
+            let validate_id = format!("wave-{}-validate", n);
+            let wave_id = Some(wave.id.clone());
+
+            let precheck_dep = previous_migrate.clone().unwrap_or_else(|| "prep-ready".to_string());
+            let migrate_days = TimelineEstimationService::calculate_migration_time(&estimate_for(wave.vm_count));
+            let validate_days = if wave.vm_count > 50 { 2 } else { 1 };
+
+            tasks.push(new_task(&precheck_id, &format!("{}: Pre-migration Checks", wave.name), "migration", wave_id.clone(), 1, vec![fs(&precheck_dep)], migration.clone()));
+            tasks.push(new_task(&migrate_id, &format!("{}: VM Migration", wave.name), "migration", wave_id.clone(), migrate_days, vec![fs(&precheck_id)], migration.clone()));
+            tasks.push(new_task(&validate_id, &format!("{}: Post-migration Validation", wave.name), "migration", wave_id, validate_days, vec![fs(&migrate_id)], app.clone()));
+
+            previous_migrate = Some(migrate_id);
+            wave_validations.push(validate_id);
+        }
+
+        let after_waves: Vec<TaskLink> = if wave_validations.is_empty() {
+            vec![fs("prep-ready")]
+        } else {
+            wave_validations.iter().map(|id| fs(id)).collect()
+        };
+        let testing_days = TimelineEstimationService::calculate_validation_time(&estimate_for(total_vms));
+
+        tasks.push(new_task("close-testing", "Application Testing", "closeout", None, testing_days, after_waves.clone(), app.clone()));
+        tasks.push(new_task("close-performance", "Performance Validation", "closeout", None, 2, after_waves, app));
+        tasks.push(new_task("close-handoff", "Documentation & Handoff", "closeout", None, 2, vec![fs("close-testing"), fs("close-performance")], assign("project_manager")));
+        tasks.push(new_task("close-complete", "Migration Complete", "closeout", None, 0, vec![fs("close-handoff")], vec![]));
+
+        (phases, tasks)
+    }
+
+    /// Run the critical path method over the task graph.
+    ///
+    /// Populates early/late start and finish, total float and the critical flag
+    /// on every task, returning the critical task IDs and the total duration.
+    pub fn calculate_critical_path(tasks: &mut [TimelineTask]) -> Result<(Vec<String>, i64)> {
+        let n = tasks.len();
+        let index: HashMap<String, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.id.clone(), i))
+            .collect();
+
+        // successors[p] = (successor index, link index on the successor)
+        let mut successors: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        let mut in_degree = vec![0usize; n];
+        for (i, task) in tasks.iter().enumerate() {
+            for (l, link) in task.dependencies.iter().enumerate() {
+                let p = *index.get(&link.predecessor_id).ok_or_else(|| {
+                    anyhow!("Task '{}' depends on unknown task '{}'", task.id, link.predecessor_id)
+                })?;
+                successors[p].push((i, l));
+                in_degree[i] += 1;
+            }
+        }
+
+        // Topological order (Kahn's algorithm)
+        let mut queue: VecDeque<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+        while let Some(i) = queue.pop_front() {
+            order.push(i);
+            for &(s, _) in &successors[i] {
+                in_degree[s] -= 1;
+                if in_degree[s] == 0 {
+                    queue.push_back(s);
+                }
+            }
+        }
+        if order.len() != n {
+            bail!("Timeline contains a dependency cycle");
+        }
+
+        // Forward pass
+        for &i in &order {
+            let duration = tasks[i].duration_days as i64;
+            let mut early_start = 0i64;
+            for link in &tasks[i].dependencies {
+                let pred = &tasks[index[&link.predecessor_id]];
+                let lag = link.lag_days as i64;
+                let candidate = match link.dependency_type {
+                    DependencyType::FinishToStart => pred.early_finish + lag,
+                    DependencyType::StartToStart => pred.early_start + lag,
+                    DependencyType::FinishToFinish => pred.early_finish + lag - duration,
+                };
+                early_start = early_start.max(candidate);
+            }
+            tasks[i].early_start = early_start;
+            tasks[i].early_finish = early_start + duration;
+        }
+
+        let project_finish = tasks.iter().map(|t| t.early_finish).max().unwrap_or(0);
+
+        // Backward pass
+        for &i in order.iter().rev() {
+            let duration = tasks[i].duration_days as i64;
+            let mut late_finish = project_finish;
+            for &(s, l) in &successors[i] {
+                let succ = &tasks[s];
+                let link = &succ.dependencies[l];
+                let lag = link.lag_days as i64;
+                let candidate = match link.dependency_type {
+                    DependencyType::FinishToStart => succ.late_start - lag,
+                    DependencyType::StartToStart => succ.late_start - lag + duration,
+                    DependencyType::FinishToFinish => succ.late_finish - lag,
+                };
+                late_finish = late_finish.min(candidate);
+            }
+            let task = &mut tasks[i];
+            task.late_finish = late_finish;
+            task.late_start = late_finish - duration;
+            task.total_float = task.late_start - task.early_start;
+            task.is_critical = task.total_float <= 0;
+        }
+
+        let mut critical: Vec<&TimelineTask> = tasks.iter().filter(|t| t.is_critical).collect();
+        critical.sort_by_key(|t| (t.early_start, t.early_finish));
+
+        Ok((critical.into_iter().map(|t| t.id.clone()).collect(), project_finish))
+    }
+
+    /// Convert day offsets into calendar dates relative to the timeline start
+    pub fn apply_dates(tasks: &mut [TimelineTask], start: DateTime<Utc>) {
+        for task in tasks.iter_mut() {
+            task.start_date = start + Duration::days(task.early_start);
+            task.end_date = start + Duration::days(task.early_finish);
+        }
+    }
+
+    // =========================================================================
+    // EXPORT
+    // =========================================================================
+
+    pub fn export(timeline: &ProjectTimeline, format: TimelineExportFormat) -> String {
+        match format {
+            TimelineExportFormat::MsProjectXml => Self::to_ms_project_xml(timeline),
+            TimelineExportFormat::Csv => Self::to_csv(timeline),
+        }
+    }
+
+    /// Render the timeline as MS Project XML (MSPDI), importable by Project and most Gantt tools
+    pub fn to_ms_project_xml(timeline: &ProjectTimeline) -> String {
+        let mut phases = timeline.phases.clone();
+        phases.sort_by_key(|p| p.order);
+
+        // Assign UIDs: phases become summary tasks, followed by their children
+        let mut uids: HashMap<String, usize> = HashMap::new();
+        let mut rows: Vec<(usize, Option<&TimelinePhase>, Option<&TimelineTask>)> = Vec::new();
+        let mut next_uid = 1usize;
+        for phase in &phases {
+            rows.push((next_uid, Some(phase), None));
+            next_uid += 1;
+            for task in timeline.tasks.iter().filter(|t| t.phase_id == phase.id) {
+                uids.insert(task.id.clone(), next_uid);
+                rows.push((next_uid, None, Some(task)));
+                next_uid += 1;
+            }
+        }
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+        xml.push_str("<Project xmlns=\"http://schemas.microsoft.com/project\">\n");
+        xml.push_str(&format!("  <Name>{}</Name>\n", xml_escape(&timeline.name)));
+        xml.push_str(&format!("  <Title>{}</Title>\n", xml_escape(&timeline.name)));
+        xml.push_str(&format!("  <StartDate>{}</StartDate>\n", ms_start(&timeline.start_date)));
+        xml.push_str(&format!("  <FinishDate>{}</FinishDate>\n", ms_finish(&timeline.start_date, &timeline.end_date)));
+        xml.push_str("  <MinutesPerDay>480</MinutesPerDay>\n");
+        xml.push_str("  <MinutesPerWeek>2400</MinutesPerWeek>\n");
+        xml.push_str("  <Tasks>\n");
+
+        for (row, (uid, phase, task)) in rows.iter().enumerate() {
+            xml.push_str("    <Task>\n");
+            xml.push_str(&format!("      <UID>{}</UID>\n", uid));
+            xml.push_str(&format!("      <ID>{}</ID>\n", row + 1));
+
+            if let Some(phase) = phase {
+                let children: Vec<&TimelineTask> = timeline.tasks.iter().filter(|t| t.phase_id == phase.id).collect();
+                let start = children.iter().map(|t| t.start_date).min().unwrap_or(timeline.start_date);
+                let end = children.iter().map(|t| t.end_date).max().unwrap_or(start);
+                let days = (end - start).num_days();
+                xml.push_str(&format!("      <Name>{}</Name>\n", xml_escape(&phase.name)));
+                xml.push_str("      <OutlineLevel>1</OutlineLevel>\n");
+                xml.push_str("      <Summary>1</Summary>\n");
+                xml.push_str(&format!("      <Start>{}</Start>\n", ms_start(&start)));
+                xml.push_str(&format!("      <Finish>{}</Finish>\n", ms_finish(&start, &end)));
+                xml.push_str(&format!("      <Duration>PT{}H0M0S</Duration>\n", days * HOURS_PER_DAY));
+            } else if let Some(task) = task {
+                xml.push_str(&format!("      <Name>{}</Name>\n", xml_escape(&task.name)));
+                xml.push_str("      <OutlineLevel>2</OutlineLevel>\n");
+                xml.push_str(&format!("      <Start>{}</Start>\n", ms_start(&task.start_date)));
+                xml.push_str(&format!("      <Finish>{}</Finish>\n", ms_finish(&task.start_date, &task.end_date)));
+                xml.push_str(&format!("      <Duration>PT{}H0M0S</Duration>\n", task.duration_days as i64 * HOURS_PER_DAY));
+                xml.push_str("      <DurationFormat>7</DurationFormat>\n");
+                xml.push_str(&format!("      <Milestone>{}</Milestone>\n", task.is_milestone as u8));
+                xml.push_str(&format!("      <Critical>{}</Critical>\n", task.is_critical as u8));
+                // Slack is expressed in tenths of a minute
+                xml.push_str(&format!("      <TotalSlack>{}</TotalSlack>\n", task.total_float * HOURS_PER_DAY * 600));
+                for link in &task.dependencies {
+                    if let Some(pred_uid) = uids.get(&link.predecessor_id) {
+                        let link_type = match link.dependency_type {
+                            DependencyType::FinishToFinish => 0,
+                            DependencyType::FinishToStart => 1,
+                            DependencyType::StartToStart => 3,
+                        };
+                        xml.push_str("      <PredecessorLink>\n");
+                        xml.push_str(&format!("        <PredecessorUID>{}</PredecessorUID>\n", pred_uid));
+                        xml.push_str(&format!("        <Type>{}</Type>\n", link_type));
+                        xml.push_str(&format!("        <LinkLag>{}</LinkLag>\n", link.lag_days as i64 * HOURS_PER_DAY * 600));
+                        xml.push_str("        <LagFormat>7</LagFormat>\n");
+                        xml.push_str("      </PredecessorLink>\n");
+                    }
+                }
+            }
+            xml.push_str("    </Task>\n");
+        }
+        xml.push_str("  </Tasks>\n");
+
+        let mut resource_uids: HashMap<&str, usize> = HashMap::new();
+        xml.push_str("  <Resources>\n");
+        for (idx, resource) in timeline.resources.iter().enumerate() {
+            resource_uids.insert(resource.id.as_str(), idx + 1);
+            xml.push_str("    <Resource>\n");
+            xml.push_str(&format!("      <UID>{}</UID>\n", idx + 1));
+            xml.push_str(&format!("      <ID>{}</ID>\n", idx + 1));
+            xml.push_str(&format!("      <Name>{}</Name>\n", xml_escape(&resource.name)));
+            xml.push_str(&format!("      <Group>{}</Group>\n", xml_escape(&resource.role)));
+            xml.push_str("      <Type>1</Type>\n");
+            xml.push_str("    </Resource>\n");
+        }
+        xml.push_str("  </Resources>\n");
+
+        xml.push_str("  <Assignments>\n");
+        let mut assignment_uid = 1usize;
+        for task in &timeline.tasks {
+            let Some(task_uid) = uids.get(&task.id) else { continue };
+            for resource_id in &task.resource_ids {
+                let Some(resource_uid) = resource_uids.get(resource_id.as_str()) else { continue };
+                let units = timeline
+                    .resources
+                    .iter()
+                    .find(|r| &r.id == resource_id)
+                    .map(|r| r.allocation_percent as f64 / 100.0)
+                    .unwrap_or(1.0);
+                xml.push_str("    <Assignment>\n");
+                xml.push_str(&format!("      <UID>{}</UID>\n", assignment_uid));
+                xml.push_str(&format!("      <TaskUID>{}</TaskUID>\n", task_uid));
+                xml.push_str(&format!("      <ResourceUID>{}</ResourceUID>\n", resource_uid));
+                xml.push_str(&format!("      <Units>{:.2}</Units>\n", units));
+                xml.push_str("    </Assignment>\n");
+                assignment_uid += 1;
+            }
+        }
+        xml.push_str("  </Assignments>\n");
+        xml.push_str("</Project>\n");
+
+        xml
+    }
+
+    /// Render the timeline as a flat CSV task list
+    pub fn to_csv(timeline: &ProjectTimeline) -> String {
+        let phase_names: HashMap<&str, &str> = timeline
+            .phases
+            .iter()
+            .map(|p| (p.id.as_str(), p.name.as_str()))
+            .collect();
+        let resource_names: HashMap<&str, &str> = timeline
+            .resources
+            .iter()
+            .map(|r| (r.id.as_str(), r.name.as_str()))
+            .collect();
+
+        let mut csv = String::from(
+            "Task ID,Task Name,Phase,Wave,Start,Finish,Duration (days),Predecessors,Resources,Critical,Total Float (days)\n",
+        );
+
+        for task in &timeline.tasks {
+            let predecessors: Vec<String> = task
+                .dependencies
+                .iter()
+                .map(|link| {
+                    let kind = match link.dependency_type {
+                        DependencyType::FinishToStart => "FS",
+                        DependencyType::StartToStart => "SS",
+                        DependencyType::FinishToFinish => "FF",
+                    };
+                    if link.lag_days == 0 {
+                        format!("{} {}", link.predecessor_id, kind)
+                    } else {
+                        format!("{} {}{:+}d", link.predecessor_id, kind, link.lag_days)
+                    }
+                })
+                .collect();
+            let resources: Vec<&str> = task
+                .resource_ids
+                .iter()
+                .map(|id| resource_names.get(id.as_str()).copied().unwrap_or(id.as_str()))
+                .collect();
+
+            let fields = [
+                task.id.clone(),
+                task.name.clone(),
+                phase_names.get(task.phase_id.as_str()).copied().unwrap_or(&task.phase_id).to_string(),
+                task.wave_id.clone().unwrap_or_default(),
+                task.start_date.format("%Y-%m-%d").to_string(),
+                task.end_date.format("%Y-%m-%d").to_string(),
+                task.duration_days.to_string(),
+                predecessors.join("; "),
+                resources.join("; "),
+                if task.is_critical { "yes" } else { "no" }.to_string(),
+                task.total_float.to_string(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_escape(f)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+fn new_task(
+    id: &str,
+    name: &str,
+    phase_id: &str,
+    wave_id: Option<String>,
+    duration_days: u32,
+    dependencies: Vec<TaskLink>,
+    resource_ids: Vec<String>,
+) -> TimelineTask {
+    let epoch = DateTime::<Utc>::default();
+    TimelineTask {
+        id: id.to_string(),
+        name: name.to_string(),
+        phase_id: phase_id.to_string(),
+        wave_id,
+        duration_days,
+        dependencies,
+        resource_ids,
+        is_milestone: duration_days == 0,
+        early_start: 0,
+        early_finish: 0,
+        late_start: 0,
+        late_finish: 0,
+        total_float: 0,
+        is_critical: false,
+        start_date: epoch,
+        end_date: epoch,
+    }
+}
+
+fn fs(predecessor_id: &str) -> TaskLink {
+    TaskLink {
+        predecessor_id: predecessor_id.to_string(),
+        dependency_type: DependencyType::FinishToStart,
+        lag_days: 0,
+    }
+}
+
+/// Extract the record key from a Thing without the table prefix
+/// Chunk VMs into waves of at most `max_per_wave`, keeping VMs that share an
+/// affinity group in the same wave. Groups are placed first-fit in order of
+/// their first VM; a group larger than a wave fills consecutive waves.
+fn pack_waves(vm_ids: Vec<Thing>, affinity: &HashMap<String, usize>, max_per_wave: usize) -> Vec<Vec<Thing>> {
+    let mut units: Vec<Vec<Thing>> = Vec::new();
+    let mut unit_of_group: HashMap<usize, usize> = HashMap::new();
+    for vm in vm_ids {
+        match affinity.get(&vm.to_string()) {
+            Some(group) => {
+                let slot = *unit_of_group.entry(*group).or_insert_with(|| {
+                    units.push(Vec::new());
+                    units.len() - 1
+                });
+                units[slot].push(vm);
+            }
+            None => units.push(vec![vm]),
+        }
+    }
+
+    let mut waves: Vec<Vec<Thing>> = Vec::new();
+    for unit in units {
+        if unit.len() > max_per_wave {
+            waves.extend(unit.chunks(max_per_wave).map(|c| c.to_vec()));
+            continue;
+        }
+        match waves.iter_mut().find(|w| w.len() + unit.len() <= max_per_wave) {
+            Some(wave) => wave.extend(unit),
+            None => waves.push(unit),
+        }
+    }
+    waves
+}
+
+/// First VM a matcher taking (name, primary IP) accepts
+fn find_vm(vms: &[MigrationWizardVM], is_vm: impl Fn(&str, Option<&str>) -> bool) -> Option<&MigrationWizardVM> {
+    vms.iter().find(|vm| is_vm(&vm.name, vm.primary_ip_address.as_deref()))
+}
+
+fn record_key(thing: &Thing) -> String {
+    match &thing.id {
+        Id::String(s) => s.clone(),
+        Id::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn ms_start(date: &DateTime<Utc>) -> String {
+    date.format("%Y-%m-%dT08:00:00").to_string()
+}
+
+/// MSPDI finish dates are inclusive, so an N-day task ends on the evening of day N
+fn ms_finish(start: &DateTime<Utc>, end: &DateTime<Utc>) -> String {
+    if end <= start {
+        start.format("%Y-%m-%dT08:00:00").to_string()
+    } else {
+        (*end - Duration::days(1)).format("%Y-%m-%dT17:00:00").to_string()
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waves(counts: &[usize]) -> Vec<WaveSummary> {
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, &vm_count)| WaveSummary {
+                id: format!("w{}", i + 1),
+                name: format!("Wave {}", i + 1),
+                vm_count,
+            })
+            .collect()
+    }
+
+    fn scheduled(counts: &[usize]) -> (Vec<TimelineTask>, Vec<String>, i64) {
+        let (_, mut tasks) = TimelineService::build_tasks(
+            &waves(counts),
+            &TimelineService::default_resources(),
+            InfrastructureType::Traditional,
+            false,
+        );
+        let (critical, total) = TimelineService::calculate_critical_path(&mut tasks).unwrap();
+        (tasks, critical, total)
+    }
+
+    #[test]
+    fn test_critical_path_runs_through_all_waves() {
+        let (tasks, critical, total) = scheduled(&[20, 40]);
+
+        assert!(critical.contains(&"prep-infra".to_string()));
+        assert!(critical.contains(&"wave-1-migrate".to_string()));
+        assert!(critical.contains(&"wave-2-migrate".to_string()));
+        assert!(critical.contains(&"close-complete".to_string()));
+
+        let complete = tasks.iter().find(|t| t.id == "close-complete").unwrap();
+        assert_eq!(complete.early_finish, total);
+    }
+
+    #[test]
+    fn test_parallel_branch_has_float() {
+        let (tasks, _, _) = scheduled(&[10]);
+        // Performance validation (2 days) runs alongside application testing (7 days)
+        let perf = tasks.iter().find(|t| t.id == "close-performance").unwrap();
+        assert!(!perf.is_critical);
+        assert_eq!(perf.total_float, 5);
+    }
+
+    #[test]
+    fn test_start_to_start_and_lag() {
+        let mut tasks = vec![
+            new_task("a", "A", "p", None, 5, vec![], vec![]),
+            new_task(
+                "b",
+                "B",
+                "p",
+                None,
+                3,
+                vec![TaskLink {
+                    predecessor_id: "a".to_string(),
+                    dependency_type: DependencyType::StartToStart,
+                    lag_days: 2,
+                }],
+                vec![],
+            ),
+        ];
+        let (critical, total) = TimelineService::calculate_critical_path(&mut tasks).unwrap();
+        assert_eq!(tasks[1].early_start, 2);
+        assert_eq!(total, 5);
+        // B finishes with A, so both sit on the critical path
+        assert_eq!(tasks[1].total_float, 0);
+        assert_eq!(critical, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn test_cycle_is_rejected() {
+        let mut tasks = vec![
+            new_task("a", "A", "p", None, 1, vec![fs("b")], vec![]),
+            new_task("b", "B", "p", None, 1, vec![fs("a")], vec![]),
+        ];
+        assert!(TimelineService::calculate_critical_path(&mut tasks).is_err());
+    }
+
+    #[test]
+    fn test_exports_contain_every_task() {
+        let (phases, mut tasks) = TimelineService::build_tasks(
+            &waves(&[5]),
+            &TimelineService::default_resources(),
+            InfrastructureType::HciS2d,
+            false,
+        );
+        let (critical_path, total) = TimelineService::calculate_critical_path(&mut tasks).unwrap();
+        let start = Utc::now();
+        TimelineService::apply_dates(&mut tasks, start);
+
+        let timeline = ProjectTimeline {
+            id: None,
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: "Acme & Co".to_string(),
+            start_date: start,
+            end_date: start + Duration::days(total),
+            total_duration_days: total as u32,
+            phases,
+            tasks: tasks.clone(),
+            resources: TimelineService::default_resources(),
+            critical_path,
+            created_at: start,
+            updated_at: start,
+        };
+
+        let xml = TimelineService::export(&timeline, TimelineExportFormat::MsProjectXml);
+        assert!(xml.contains("<Name>Acme &amp; Co</Name>"));
+        assert_eq!(xml.matches("<Task>").count(), tasks.len() + timeline.phases.len());
+        assert!(xml.contains("<PredecessorLink>"));
+
+        let csv = TimelineService::export(&timeline, TimelineExportFormat::Csv);
+        assert_eq!(csv.lines().count(), tasks.len() + 1);
+        assert!(csv.contains("wave-1-migrate FS"));
+    }
+
+    fn wizard_vm(key: &str, name: &str, ip: Option<&str>) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: name.to_string(),
+            powerstate: None,
+            template: None,
+            cpus: 2,
+            memory_mb: 4096,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: ip.map(str::to_string),
+            dns_name: None,
+            cluster: None,
+            host: None,
+            datacenter: None,
+            os: None,
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_backup_impact_flags_protected_vms() {
+        use crate::services::integration_hub::dependencies::ProtectedVm;
+
+        let vm = |key: &str, name: &str| wizard_vm(key, name, None);
+        let job = BackupJobCoverage {
+            name: "Prod Web Daily".to_string(),
+            repository: Some("Primary".to_string()),
+            protected_vms: vec![
+                ProtectedVm { name: "web-01".to_string(), included_directly: true, ..Default::default() },
+                ProtectedVm { name: "web-02".to_string(), via: Some("Web".to_string()), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        let impact = TimelineService::assess_backup_impact(
+            &[vm("a", "WEB-01"), vm("b", "web-02"), vm("c", "db-01")],
+            &[job],
+        );
+        assert_eq!(impact.jobs_to_reconfigure, vec!["Prod Web Daily"]);
+        assert_eq!(impact.unprotected_vms, vec!["db-01"]);
+        assert_eq!(impact.affected_vms.len(), 2);
+        assert!(impact.affected_vms[0].jobs[0].action.starts_with("Re-add"));
+        assert!(impact.affected_vms[1].jobs[0].action.contains("via Web"));
+
+        let steps = TimelineService::runbook_steps(3, &[], &impact, &LogSourceImpact::default());
+        assert!(steps.iter().any(|s| s.action == "Reconfigure backup job 'Prod Web Daily' and run it once"));
+    }
+
+    #[test]
+    fn test_pack_waves_keeps_load_balanced_vms_together() {
+        let vm = |key: &str| Thing::from(("migration_wizard_vm", key));
+        let ids = vec![vm("a"), vm("web1"), vm("b"), vm("c"), vm("web2")];
+        let affinity: HashMap<String, usize> =
+            [(vm("web1").to_string(), 0), (vm("web2").to_string(), 0)].into_iter().collect();
+
+        let waves = pack_waves(ids, &affinity, 3);
+        let keys: Vec<Vec<String>> = waves.iter().map(|w| w.iter().map(record_key).collect()).collect();
+        assert_eq!(keys, vec![vec!["a", "web1", "web2"], vec!["b", "c"]]);
+
+        // A pool larger than a wave spills into consecutive waves
+        let big: Vec<Thing> = (0..5).map(|i| vm(&format!("n{}", i))).collect();
+        let affinity: HashMap<String, usize> = big.iter().map(|t| (t.to_string(), 0)).collect();
+        assert_eq!(pack_waves(big, &affinity, 2).iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_log_sources_repoint_forwarders_of_moving_indexers() {
+        use crate::services::integration_hub::dependencies::{LogForwarder, LogServer};
+
+        let forwarder = |name: &str, ip: &str| LogForwarder {
+            name: name.to_string(),
+            ip_address: Some(ip.to_string()),
+            forwarder_type: Some("universal".to_string()),
+            outputs: vec!["splunk-idx01".to_string()],
+            ..Default::default()
+        };
+        let pipeline = LogPipeline {
+            forwarders: vec![forwarder("web-01", "10.0.0.10"), forwarder("app-01", "10.0.0.20")],
+            indexers: vec![LogServer {
+                name: "splunk-idx01".to_string(),
+                hostname: Some("splunk-idx01.corp.example.com".to_string()),
+                ip_address: None,
+            }],
+            search_heads: Vec::new(),
+        };
+
+        let impact = TimelineService::assess_log_sources(
+            &[
+                wizard_vm("a", "WEB-01-renamed", Some("10.0.0.10")),
+                wizard_vm("b", "SPLUNK-IDX01", None),
+            ],
+            &pipeline,
+        );
+        assert_eq!(impact.forwarders.len(), 1);
+        assert_eq!(impact.forwarders[0].vm_name, "WEB-01-renamed");
+        assert_eq!(impact.moving_servers, vec!["splunk-idx01"]);
+        assert_eq!(impact.forwarders_to_repoint[0].forwarders, vec!["web-01", "app-01"]);
+
+        let steps = TimelineService::runbook_steps(2, &[], &BackupImpact::default(), &impact);
+        assert!(steps.iter().any(|s| s.action.starts_with("Re-point 2 forwarder(s) to indexer 'splunk-idx01'")));
+    }
+}
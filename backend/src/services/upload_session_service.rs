@@ -0,0 +1,555 @@
+// Archer ITSM - Resumable Upload Service
+// Chunked uploads for large RVTools and hardware basket files. Chunks are
+// appended to a part file at the offset the server last acknowledged, so a
+// client on a flaky link resumes instead of starting over. The assembled
+// file is checksummed before it is handed to the importer.
+
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+use crate::database::Database;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::upload_session::*;
+use crate::database::TracedQuery;
+
+const TABLE: &str = "upload_session";
+
+const DEFAULT_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+/// Stays under the 50 MB request size limit
+const DEFAULT_MAX_CHUNK_BYTES: u64 = 16 * 1024 * 1024;
+const DEFAULT_TTL_HOURS: i64 = 24;
+/// Read size when checksumming a part file
+const HASH_BUFFER_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum UploadSessionError {
+    #[error("Upload session not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Upload is at byte {expected}, chunk starts at {received}")]
+    OffsetMismatch { expected: u64, received: u64 },
+
+    #[error("Upload incomplete: {received} of {total} bytes received")]
+    Incomplete { received: u64, total: u64 },
+
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("Upload session already completed")]
+    AlreadyCompleted,
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for UploadSessionError {
+    fn from(e: surrealdb::Error) -> Self {
+        UploadSessionError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for UploadSessionError {
+    fn from(e: std::io::Error) -> Self {
+        UploadSessionError::Storage(e.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, UploadSessionError>;
+
+/// Where part files live and how large uploads may get
+#[derive(Debug, Clone)]
+pub struct UploadLimits {
+    pub dir: PathBuf,
+    pub max_bytes: u64,
+    pub max_chunk_bytes: u64,
+    /// Idle time after which an unfinished upload is abandoned
+    pub ttl: Duration,
+}
+
+impl UploadLimits {
+    pub fn from_env() -> Self {
+        let parse = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            dir: std::env::var("UPLOAD_SESSION_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| std::env::temp_dir().join("archer-uploads")),
+            max_bytes: parse("UPLOAD_MAX_BYTES").unwrap_or(DEFAULT_MAX_BYTES),
+            max_chunk_bytes: parse("UPLOAD_MAX_CHUNK_BYTES").unwrap_or(DEFAULT_MAX_CHUNK_BYTES),
+            ttl: Duration::hours(parse("UPLOAD_SESSION_TTL_HOURS").map(|h| h as i64).unwrap_or(DEFAULT_TTL_HOURS)),
+        }
+    }
+}
+
+/// A completed upload, ready for the target's importer
+pub struct AssembledUpload {
+    pub session: UploadSession,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct UploadCleanupReport {
+    pub sessions_removed: u32,
+    pub part_files_removed: u32,
+}
+
+pub struct UploadSessionService {
+    db: Arc<Database>,
+    limits: UploadLimits,
+}
+
+impl UploadSessionService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self::with_limits(db, UploadLimits::from_env())
+    }
+
+    pub fn with_limits(db: Arc<Database>, limits: UploadLimits) -> Self {
+        Self { db, limits }
+    }
+
+    pub fn limits(&self) -> &UploadLimits {
+        &self.limits
+    }
+
+    pub fn response(&self, session: &UploadSession) -> UploadSessionResponse {
+        UploadSessionResponse {
+            id: session.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+            target: session.target,
+            file_name: session.file_name.clone(),
+            total_bytes: session.total_bytes,
+            received_bytes: session.received_bytes,
+            status: session.status,
+            max_chunk_bytes: self.limits.max_chunk_bytes,
+            expires_at: session.expires_at,
+        }
+    }
+
+    fn part_path(&self, key: &str) -> PathBuf {
+        self.limits.dir.join(format!("{}.part", key))
+    }
+
+    pub async fn create(
+        &self,
+        user: &AuthenticatedUser,
+        request: CreateUploadSessionRequest,
+    ) -> Result<UploadSession> {
+        let sha256 = validate_request(&request, &self.limits)?;
+
+        let key = uuid::Uuid::new_v4().simple().to_string();
+        tokio::fs::create_dir_all(&self.limits.dir).await?;
+        tokio::fs::File::create(self.part_path(&key)).await?;
+
+        let now = Utc::now();
+        let session = UploadSession {
+            id: None,
+            target: request.target,
+            file_name: request.file_name.trim().to_string(),
+            total_bytes: request.total_bytes,
+            received_bytes: 0,
+            sha256,
+            status: UploadSessionStatus::InProgress,
+            metadata: request.metadata,
+            created_by: user.user_id.clone(),
+            created_at: now,
+            updated_at: now,
+            expires_at: now + self.limits.ttl,
+        };
+
+        let created: Option<UploadSession> = self.db.create((TABLE, key.as_str())).content(session).await?;
+        created.ok_or_else(|| UploadSessionError::DatabaseError("Failed to create upload session".to_string()))
+    }
+
+    /// A session of the calling user; other users' sessions are not found
+    pub async fn get(&self, user: &AuthenticatedUser, id: &str) -> Result<UploadSession> {
+        let session: Option<UploadSession> = self.db.select((TABLE, id)).await?;
+        session
+            .filter(|s| s.created_by == user.user_id)
+            .ok_or(UploadSessionError::NotFound)
+    }
+
+    /// Write a chunk at `offset`, which must be where the upload stands.
+    /// Bytes past the acknowledged offset, left by a chunk whose response
+    /// never reached the client, are overwritten.
+    pub async fn append(
+        &self,
+        user: &AuthenticatedUser,
+        id: &str,
+        offset: u64,
+        chunk: &[u8],
+        chunk_sha256: Option<&str>,
+    ) -> Result<UploadSession> {
+        let session = self.get(user, id).await?;
+        if session.status == UploadSessionStatus::Completed {
+            return Err(UploadSessionError::AlreadyCompleted);
+        }
+        if offset != session.received_bytes {
+            return Err(UploadSessionError::OffsetMismatch {
+                expected: session.received_bytes,
+                received: offset,
+            });
+        }
+        let length = chunk.len() as u64;
+        if length == 0 {
+            return Err(UploadSessionError::Validation("Chunk is empty".to_string()));
+        }
+        if length > self.limits.max_chunk_bytes {
+            return Err(UploadSessionError::Validation(format!(
+                "Chunk of {} bytes exceeds the {} byte limit",
+                length, self.limits.max_chunk_bytes
+            )));
+        }
+        if offset + length > session.total_bytes {
+            return Err(UploadSessionError::Validation(format!(
+                "Chunk ends at byte {}, past the declared size of {}",
+                offset + length,
+                session.total_bytes
+            )));
+        }
+        if let Some(expected) = chunk_sha256 {
+            let actual = sha256_hex(chunk);
+            if !expected.eq_ignore_ascii_case(&actual) {
+                return Err(UploadSessionError::ChecksumMismatch {
+                    expected: expected.to_string(),
+                    actual,
+                });
+            }
+        }
+
+        let mut file = tokio::fs::OpenOptions::new().write(true).open(self.part_path(id)).await?;
+        file.set_len(offset).await?;
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        file.write_all(chunk).await?;
+        file.sync_data().await?;
+
+        // Conditional on the offset so two clients racing on one session
+        // cannot both advance it
+        let now = Utc::now();
+        let mut response = self
+            .db
+            .query("UPDATE $id SET received_bytes = $received, updated_at = $now, expires_at = $expires WHERE received_bytes = $offset")
+            .bind(("id", Thing::from((TABLE, id))))
+            .bind(("received", offset + length))
+            .bind(("offset", offset))
+            .bind(("now", now))
+            .bind(("expires", now + self.limits.ttl))
+            .traced("upload_session_service.append")
+            .await?;
+        let updated: Option<UploadSession> = response.take(0)?;
+        match updated {
+            Some(session) => Ok(session),
+            None => {
+                // Another chunk won; drop whatever this one wrote past the
+                // offset that was actually acknowledged
+                let current = self.get(user, id).await?;
+                if file.metadata().await?.len() > current.received_bytes {
+                    file.set_len(current.received_bytes).await?;
+                }
+                Err(UploadSessionError::OffsetMismatch {
+                    expected: current.received_bytes,
+                    received: offset,
+                })
+            }
+        }
+    }
+
+    /// Verify the assembled file and mark the session completed. A checksum
+    /// mismatch discards the session; the client has to upload again.
+    pub async fn complete(&self, user: &AuthenticatedUser, id: &str) -> Result<AssembledUpload> {
+        let session = self.get(user, id).await?;
+        if session.status == UploadSessionStatus::Completed {
+            return Err(UploadSessionError::AlreadyCompleted);
+        }
+        if session.received_bytes != session.total_bytes {
+            return Err(UploadSessionError::Incomplete {
+                received: session.received_bytes,
+                total: session.total_bytes,
+            });
+        }
+
+        let path = self.part_path(id);
+        let length = tokio::fs::metadata(&path).await?.len();
+        if length != session.total_bytes {
+            return Err(UploadSessionError::Storage(format!(
+                "Part file holds {} bytes, expected {}",
+                length, session.total_bytes
+            )));
+        }
+        if let Some(expected) = &session.sha256 {
+            let actual = file_sha256(&path).await?;
+            if &actual != expected {
+                self.remove(id).await?;
+                return Err(UploadSessionError::ChecksumMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        let mut response = self
+            .db
+            .query("UPDATE $id SET status = $status, updated_at = $now")
+            .bind(("id", Thing::from((TABLE, id))))
+            .bind(("status", UploadSessionStatus::Completed))
+            .bind(("now", Utc::now()))
+            .traced("upload_session_service.complete")
+            .await?;
+        let completed: Option<UploadSession> = response.take(0)?;
+        // Only a verified file is loaded, and only for the importer
+        let bytes = tokio::fs::read(&path).await?;
+        remove_file(&path).await?;
+
+        Ok(AssembledUpload {
+            session: completed.ok_or(UploadSessionError::NotFound)?,
+            bytes,
+        })
+    }
+
+    pub async fn abort(&self, user: &AuthenticatedUser, id: &str) -> Result<()> {
+        self.get(user, id).await?;
+        self.remove(id).await
+    }
+
+    async fn remove(&self, id: &str) -> Result<()> {
+        remove_file(&self.part_path(id)).await?;
+        let _: Option<UploadSession> = self.db.delete((TABLE, id)).await?;
+        Ok(())
+    }
+
+    /// Remove sessions idle past their expiry, and part files no session
+    /// refers to (e.g. left behind by a crash between file and record writes)
+    pub async fn cleanup_expired(&self) -> Result<UploadCleanupReport> {
+        let mut report = UploadCleanupReport::default();
+
+        let mut response = self
+            .db
+            .query("SELECT * FROM type::table($table) WHERE expires_at < $now")
+            .bind(("table", TABLE))
+            .bind(("now", Utc::now()))
+            .traced("upload_session_service.cleanup_expired")
+            .await?;
+        let expired: Vec<UploadSession> = response.take(0)?;
+        for session in expired {
+            if let Some(id) = &session.id {
+                self.remove(&id.id.to_raw()).await?;
+                report.sessions_removed += 1;
+            }
+        }
+
+        let sessions: Vec<UploadSession> = self.db.select(TABLE).await?;
+        let live: HashSet<String> = sessions
+            .iter()
+            .filter_map(|s| s.id.as_ref().map(|id| id.id.to_raw()))
+            .collect();
+        let cutoff = std::time::SystemTime::now() - self.limits.ttl.to_std().unwrap_or_default();
+
+        let mut entries = match tokio::fs::read_dir(&self.limits.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let key = match path.file_name().and_then(|n| n.to_str()).and_then(|n| n.strip_suffix(".part")) {
+                Some(key) => key.to_string(),
+                None => continue,
+            };
+            let modified = entry.metadata().await?.modified()?;
+            if !live.contains(&key) && modified < cutoff {
+                remove_file(&path).await?;
+                report.part_files_removed += 1;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Lowercase hex SHA-256
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Lowercase hex SHA-256 of a file, read in fixed-size chunks so
+/// multi-gigabyte part files are never held in memory to be hashed
+async fn file_sha256(path: &std::path::Path) -> Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_BYTES];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Check a new session's declared size, name and checksum; returns the
+/// normalised checksum
+fn validate_request(request: &CreateUploadSessionRequest, limits: &UploadLimits) -> Result<Option<String>> {
+    let file_name = request.file_name.trim();
+    if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name == ".." {
+        return Err(UploadSessionError::Validation("file_name must be a plain file name".to_string()));
+    }
+    if request.total_bytes == 0 || request.total_bytes > limits.max_bytes {
+        return Err(UploadSessionError::Validation(format!(
+            "total_bytes must be between 1 and {}",
+            limits.max_bytes
+        )));
+    }
+
+    match &request.sha256 {
+        None => Ok(None),
+        Some(sum) if sum.len() == 64 && sum.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Ok(Some(sum.to_ascii_lowercase()))
+        }
+        Some(_) => Err(UploadSessionError::Validation(
+            "sha256 must be 64 hexadecimal characters".to_string(),
+        )),
+    }
+}
+
+async fn remove_file(path: &std::path::Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// ============================================================================
+// SCHEDULER
+// ============================================================================
+
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{error, info};
+
+use crate::database::locks;
+
+const CLEANUP_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+pub struct UploadCleanupScheduler {
+    db: Arc<Database>,
+}
+
+impl UploadCleanupScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Sweeps abandoned uploads once an hour
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+
+        let db = Arc::clone(&self.db);
+        let job = Job::new_async("0 15 * * * *", move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            Box::pin(async move {
+                let service = UploadSessionService::new(Arc::clone(&db));
+                let run = locks::run_exclusive(&db, locks::UPLOAD_CLEANUP_LOCK, CLEANUP_LOCK_TTL, service.cleanup_expired());
+                match run.await {
+                    Ok(None) => {}
+                    Ok(Some(Ok(report))) => {
+                        if report.sessions_removed > 0 || report.part_files_removed > 0 {
+                            info!(
+                                "🧹 Removed {} abandoned upload(s) and {} orphaned part file(s)",
+                                report.sessions_removed, report.part_files_removed
+                            );
+                        }
+                    }
+                    Ok(Some(Err(e))) => error!("❌ Upload cleanup failed: {}", e),
+                    Err(e) => error!("❌ Upload cleanup lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create upload cleanup job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add upload cleanup job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn limits() -> UploadLimits {
+        UploadLimits {
+            dir: std::env::temp_dir(),
+            max_bytes: 1024,
+            max_chunk_bytes: 256,
+            ttl: Duration::hours(1),
+        }
+    }
+
+    fn request(file_name: &str, total_bytes: u64, sha256: Option<&str>) -> CreateUploadSessionRequest {
+        CreateUploadSessionRequest {
+            target: UploadTarget::Rvtools,
+            file_name: file_name.to_string(),
+            total_bytes,
+            sha256: sha256.map(str::to_string),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_sha256_streams_whole_file() {
+        // Spans several hash buffers and ends mid-buffer
+        let bytes: Vec<u8> = (0..(HASH_BUFFER_BYTES * 2 + 12345)).map(|i| (i % 251) as u8).collect();
+        let path = std::env::temp_dir().join(format!("upload-hash-{}.part", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, &bytes).await.unwrap();
+
+        assert_eq!(file_sha256(&path).await.unwrap(), sha256_hex(&bytes));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let limits = limits();
+        let upper = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+        assert_eq!(
+            validate_request(&request("export.xlsx", 512, Some(upper)), &limits).unwrap(),
+            Some(upper.to_ascii_lowercase())
+        );
+        assert!(validate_request(&request("export.xlsx", 512, None), &limits).unwrap().is_none());
+
+        assert!(validate_request(&request("../export.xlsx", 512, None), &limits).is_err());
+        assert!(validate_request(&request("export.xlsx", 0, None), &limits).is_err());
+        assert!(validate_request(&request("export.xlsx", 2048, None), &limits).is_err());
+        assert!(validate_request(&request("export.xlsx", 512, Some("abc")), &limits).is_err());
+    }
+}
@@ -0,0 +1,415 @@
+//! Usage Telemetry Service
+//!
+//! Opt-in, anonymized feature usage for the product team:
+//! - [`record`] counts an invocation in memory; it does nothing until an
+//!   administrator opts in, and nothing at all under the hard off switch
+//! - Counts are flushed into daily aggregates (`usage_daily`) every few
+//!   minutes, adding up across replicas
+//! - Once a day, finished days are submitted to `TELEMETRY_ENDPOINT` as
+//!   batches of feature counts under a random installation id
+//!
+//! The hard off switch is `ARCHER_TELEMETRY_DISABLED` (or `DO_NOT_TRACK`).
+//! With it set nothing is recorded, flushed or sent, the scheduler doesn't
+//! start and telemetry can't be enabled. Opting out drops everything not yet
+//! submitted.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{info, warn};
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::usage_telemetry::*;
+use crate::utils::telemetry::TracedRequest;
+
+/// Flush in-memory counts every five minutes
+const DEFAULT_FLUSH_CRON: &str = "0 */5 * * * *";
+/// Submit finished days at 01:15 every night
+const DEFAULT_SUBMIT_CRON: &str = "0 15 1 * * *";
+/// Days of aggregates shown in the local viewer
+const VIEWER_DAYS: i64 = 90;
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static HARD_DISABLED: Lazy<bool> =
+    Lazy::new(|| env_flag_set("ARCHER_TELEMETRY_DISABLED") || env_flag_set("DO_NOT_TRACK"));
+static COUNTERS: Lazy<Mutex<BTreeMap<(NaiveDate, UsageFeature), u64>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+#[derive(Debug, Error)]
+pub enum UsageTelemetryError {
+    #[error("Telemetry is switched off for this installation (ARCHER_TELEMETRY_DISABLED or DO_NOT_TRACK)")]
+    HardDisabled,
+
+    #[error("Submission failed: {0}")]
+    Submission(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for UsageTelemetryError {
+    fn from(e: surrealdb::Error) -> Self {
+        UsageTelemetryError::DatabaseError(e.to_string())
+    }
+}
+
+fn env_flag_set(name: &str) -> bool {
+    std::env::var(name).map(|v| flag_value(&v)).unwrap_or(false)
+}
+
+fn flag_value(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+/// Whether the hard off switch is set
+pub fn hard_disabled() -> bool {
+    *HARD_DISABLED
+}
+
+/// Where batches are sent, if anywhere
+pub fn endpoint() -> Option<String> {
+    std::env::var("TELEMETRY_ENDPOINT")
+        .ok()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// Count one invocation of a feature; free when telemetry is off
+pub fn record(feature: UsageFeature) {
+    if !ENABLED.load(Ordering::Relaxed) || hard_disabled() {
+        return;
+    }
+    if let Ok(mut counters) = COUNTERS.lock() {
+        *counters.entry((Utc::now().date_naive(), feature)).or_insert(0) += 1;
+    }
+}
+
+fn set_enabled_flag(enabled: bool) {
+    ENABLED.store(enabled && !hard_disabled(), Ordering::Relaxed);
+    if !enabled {
+        if let Ok(mut counters) = COUNTERS.lock() {
+            counters.clear();
+        }
+    }
+}
+
+fn take_counts() -> BTreeMap<(NaiveDate, UsageFeature), u64> {
+    COUNTERS.lock().map(|mut counters| std::mem::take(&mut *counters)).unwrap_or_default()
+}
+
+fn restore_counts(counts: BTreeMap<(NaiveDate, UsageFeature), u64>) {
+    if let Ok(mut counters) = COUNTERS.lock() {
+        for (key, count) in counts {
+            *counters.entry(key).or_insert(0) += count;
+        }
+    }
+}
+
+/// One batch per finished day that hasn't been submitted yet. Today is left
+/// out until it's complete.
+pub fn build_batches(installation_id: &str, rows: &[DailyUsage], today: NaiveDate) -> Vec<TelemetryBatch> {
+    let mut days: BTreeMap<NaiveDate, BTreeMap<String, u64>> = BTreeMap::new();
+    for row in rows.iter().filter(|r| r.submitted_at.is_none() && r.day < today && r.count > 0) {
+        *days
+            .entry(row.day)
+            .or_default()
+            .entry(row.feature.key().to_string())
+            .or_insert(0) += row.count;
+    }
+    days.into_iter()
+        .map(|(day, counts)| TelemetryBatch {
+            installation_id: installation_id.to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            day,
+            counts,
+        })
+        .collect()
+}
+
+fn usage_id(day: NaiveDate, feature: UsageFeature) -> String {
+    format!("{}_{}", day.format("%Y%m%d"), feature.key())
+}
+
+pub struct UsageTelemetryService {
+    db: Arc<Database>,
+}
+
+impl UsageTelemetryService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Read the opt-in state into this replica's recorder
+    pub async fn load(&self) -> Result<bool, UsageTelemetryError> {
+        let enabled = self.config().await?.is_some_and(|c| c.enabled) && !hard_disabled();
+        set_enabled_flag(enabled);
+        Ok(enabled)
+    }
+
+    async fn config(&self) -> Result<Option<TelemetryConfig>, UsageTelemetryError> {
+        Ok(self.db.select(("usage_telemetry", "config")).await?)
+    }
+
+    /// Opt in or out. Opting out drops every count not yet submitted.
+    pub async fn set_enabled(&self, enabled: bool, updated_by: &str) -> Result<TelemetryOverview, UsageTelemetryError> {
+        if enabled && hard_disabled() {
+            return Err(UsageTelemetryError::HardDisabled);
+        }
+
+        let existing = self.config().await?;
+        let config = TelemetryConfig {
+            id: None,
+            enabled,
+            installation_id: existing
+                .as_ref()
+                .map(|c| c.installation_id.clone())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            updated_by: updated_by.to_string(),
+            updated_at: Utc::now(),
+            last_submitted_at: existing.and_then(|c| c.last_submitted_at),
+        };
+        self.db
+            .query("UPDATE usage_telemetry:config CONTENT $config")
+            .bind(("config", config))
+            .traced("usage_telemetry_service.set_enabled")
+            .await?;
+
+        set_enabled_flag(enabled);
+        if !enabled {
+            self.db
+                .query("DELETE usage_daily WHERE submitted_at = NONE")
+                .traced("usage_telemetry_service.discard_pending")
+                .await?;
+        }
+        info!("📈 Usage telemetry {} by {}", if enabled { "enabled" } else { "disabled" }, updated_by);
+        self.overview().await
+    }
+
+    /// Add the in-memory counts to the daily aggregates
+    pub async fn flush(&self) -> Result<usize, UsageTelemetryError> {
+        let counts: Vec<_> = take_counts().into_iter().collect();
+        for (index, &((day, feature), count)) in counts.iter().enumerate() {
+            let result = self
+                .db
+                .query(
+                    "UPDATE type::thing('usage_daily', $id) SET day = $day, feature = $feature, \
+                     count = (count OR 0) + $count",
+                )
+                .bind(("id", usage_id(day, feature)))
+                .bind(("day", day))
+                .bind(("feature", feature))
+                .bind(("count", count))
+                .traced("usage_telemetry_service.flush")
+                .await;
+            if let Err(e) = result {
+                // Keep what wasn't written for the next flush
+                restore_counts(counts[index..].iter().copied().collect());
+                return Err(e.into());
+            }
+        }
+        Ok(counts.len())
+    }
+
+    /// Recent aggregates, opt-in state and what the next submission sends
+    pub async fn overview(&self) -> Result<TelemetryOverview, UsageTelemetryError> {
+        let config = self.config().await?;
+        let since = Utc::now().date_naive() - chrono::Duration::days(VIEWER_DAYS);
+        let days: Vec<DailyUsage> = self
+            .db
+            .query("SELECT * FROM usage_daily WHERE day >= $since ORDER BY day DESC, feature")
+            .bind(("since", since))
+            .traced("usage_telemetry_service.overview")
+            .await?
+            .take(0)?;
+
+        let enabled = config.as_ref().is_some_and(|c| c.enabled) && !hard_disabled();
+        let pending = match (&config, enabled) {
+            (Some(config), true) => build_batches(&config.installation_id, &days, Utc::now().date_naive()),
+            _ => Vec::new(),
+        };
+        Ok(TelemetryOverview {
+            enabled,
+            hard_disabled: hard_disabled(),
+            endpoint: endpoint(),
+            installation_id: config.as_ref().map(|c| c.installation_id.clone()),
+            last_submitted_at: config.and_then(|c| c.last_submitted_at),
+            days,
+            pending,
+        })
+    }
+
+    /// Send finished days to the endpoint; returns the number of batches sent
+    pub async fn submit(&self) -> Result<usize, UsageTelemetryError> {
+        let Some(config) = self.config().await?.filter(|c| c.enabled && !hard_disabled()) else {
+            return Ok(0);
+        };
+        let Some(url) = endpoint() else {
+            return Ok(0);
+        };
+        self.flush().await?;
+
+        let rows: Vec<DailyUsage> = self
+            .db
+            .query("SELECT * FROM usage_daily WHERE submitted_at = NONE")
+            .traced("usage_telemetry_service.submit")
+            .await?
+            .take(0)?;
+        let batches = build_batches(&config.installation_id, &rows, Utc::now().date_naive());
+        if batches.is_empty() {
+            return Ok(0);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(SUBMIT_TIMEOUT)
+            .build()
+            .map_err(|e| UsageTelemetryError::Submission(e.to_string()))?;
+        let response = client
+            .post(&url)
+            .json(&batches)
+            .send_traced("telemetry")
+            .await
+            .map_err(|e| UsageTelemetryError::Submission(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(UsageTelemetryError::Submission(format!("endpoint returned {}", response.status())));
+        }
+
+        let now: DateTime<Utc> = Utc::now();
+        let days: Vec<NaiveDate> = batches.iter().map(|b| b.day).collect();
+        self.db
+            .query("UPDATE usage_daily SET submitted_at = $now WHERE submitted_at = NONE AND day IN $days")
+            .query("UPDATE usage_telemetry:config SET last_submitted_at = $now")
+            .bind(("now", now))
+            .bind(("days", days))
+            .traced("usage_telemetry_service.mark_submitted")
+            .await?;
+        Ok(batches.len())
+    }
+
+    /// Delete every local aggregate, submitted or not
+    pub async fn purge(&self) -> Result<(), UsageTelemetryError> {
+        take_counts();
+        self.db
+            .query("DELETE usage_daily")
+            .traced("usage_telemetry_service.purge")
+            .await?;
+        Ok(())
+    }
+}
+
+/// Flushes counts every few minutes and submits finished days nightly
+pub struct UsageTelemetryScheduler {
+    db: Arc<Database>,
+}
+
+impl UsageTelemetryScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+        let service = Arc::new(UsageTelemetryService::new(Arc::clone(&self.db)));
+
+        // Re-reading the opt-in state picks up changes made on other replicas
+        let flush_service = Arc::clone(&service);
+        let flush = Job::new_async(DEFAULT_FLUSH_CRON, move |_uuid, _lock| {
+            let service = Arc::clone(&flush_service);
+            Box::pin(async move {
+                if let Err(e) = service.flush().await {
+                    warn!("📈 Usage telemetry flush failed: {}", e);
+                }
+                if let Err(e) = service.load().await {
+                    warn!("📈 Usage telemetry reload failed: {}", e);
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create telemetry flush job: {}", e))?;
+
+        let cron = std::env::var("TELEMETRY_SUBMIT_CRON").unwrap_or_else(|_| DEFAULT_SUBMIT_CRON.to_string());
+        let submit = Job::new_async(cron.as_str(), move |_uuid, _lock| {
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                match service.submit().await {
+                    Ok(0) => {}
+                    Ok(sent) => info!("📈 Submitted {} day(s) of usage telemetry", sent),
+                    Err(e) => warn!("📈 Usage telemetry submission failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create telemetry submit job: {}", e))?;
+
+        for job in [flush, submit] {
+            scheduler
+                .add(job)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to add telemetry job: {}", e))?;
+        }
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+
+        // Keep the scheduler alive for the process lifetime
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: NaiveDate, feature: UsageFeature, count: u64, submitted: bool) -> DailyUsage {
+        DailyUsage {
+            id: None,
+            day,
+            feature,
+            count,
+            submitted_at: submitted.then(Utc::now),
+        }
+    }
+
+    #[test]
+    fn test_batches_cover_finished_unsubmitted_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let earlier = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let rows = vec![
+            row(today, UsageFeature::RvtoolsParse, 4, false),
+            row(yesterday, UsageFeature::RvtoolsParse, 2, false),
+            row(yesterday, UsageFeature::HldGeneration, 1, false),
+            row(earlier, UsageFeature::VmPlacement, 7, true),
+            row(earlier, UsageFeature::CapacitySizing, 3, false),
+            row(earlier, UsageFeature::SpreadsheetImport, 0, false),
+        ];
+
+        let batches = build_batches("install-1", &rows, today);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].day, earlier);
+        assert_eq!(batches[0].counts, BTreeMap::from([("capacity_sizing".to_string(), 3)]));
+        assert_eq!(batches[1].day, yesterday);
+        assert_eq!(
+            batches[1].counts,
+            BTreeMap::from([("hld_generation".to_string(), 1), ("rvtools_parse".to_string(), 2)])
+        );
+        assert!(batches.iter().all(|b| b.installation_id == "install-1"));
+    }
+
+    #[test]
+    fn test_off_switch_values() {
+        for value in ["1", "true", "YES", " on "] {
+            assert!(flag_value(value), "{value}");
+        }
+        for value in ["", "0", "false", "off"] {
+            assert!(!flag_value(value), "{value}");
+        }
+    }
+}
@@ -0,0 +1,471 @@
+//! VM Hygiene Service
+//!
+//! Checks a project's source VMs against the vSnapshot and vTools sheets of
+//! its RVTools import: snapshots that are old or large, and VMware Tools that
+//! are out of date or missing. Estimates the storage snapshots hold, raises
+//! one pre-migration remediation task per VM and fix, and feeds the open
+//! tasks of a wave into its runbook.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use surrealdb::sql::Thing;
+
+use crate::database::Database;
+use crate::database::TracedQuery;
+use crate::models::migration_wizard_models::{MigrationWizardVM, SourceInventory, SourceVmTools};
+use crate::models::timeline::{MigrationWave, RunbookStep};
+use crate::models::vm_hygiene::*;
+use crate::services::migration_wizard_service::MigrationWizardService;
+use crate::services::timeline_service::TimelineService;
+
+const TASK_TABLE: &str = "vm_remediation_task";
+
+pub struct VmHygieneService {
+    db: Database,
+}
+
+impl VmHygieneService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Assess a project's snapshots and VMware Tools. With `create_tasks`,
+    /// raise a remediation task for each VM and fix that has no open task
+    /// yet; open tasks are moved to the VM's current wave.
+    pub async fn assess(
+        &self,
+        project_id: &str,
+        thresholds: HygieneThresholds,
+        create_tasks: bool,
+    ) -> Result<VmHygieneReport> {
+        let wizard = MigrationWizardService::new(self.db.clone());
+        let inventory = wizard.get_source_inventory(project_id).await?;
+        let vms = wizard.get_project_vms(project_id, None).await?;
+        let waves = TimelineService::new(self.db.clone()).list_waves(project_id).await?;
+
+        let now = Utc::now();
+        let mut report = assess(project_id, &inventory, &vms, &waves, thresholds, now);
+        if create_tasks {
+            report.tasks_created = self.raise_tasks(plan_tasks(project_id, &report.findings, now)).await?;
+        }
+        Ok(report)
+    }
+
+    // =========================================================================
+    // REMEDIATION TASKS
+    // =========================================================================
+
+    pub async fn list_tasks(&self, project_id: &str) -> Result<Vec<VmRemediationTask>> {
+        let tasks: Vec<VmRemediationTask> = self
+            .db
+            .query("SELECT * FROM vm_remediation_task WHERE project_id = $project ORDER BY vm_name ASC, created_at ASC")
+            .bind(("project", Thing::from(("migration_wizard_project", project_id))))
+            .traced("vm_hygiene_service.list_tasks")
+            .await
+            .context("Failed to query remediation tasks")?
+            .take(0)
+            .context("Failed to parse remediation tasks")?;
+        Ok(tasks)
+    }
+
+    /// Open tasks of the given VMs, e.g. the VMs of one wave
+    pub async fn open_tasks_for_vms(&self, vm_ids: &[Thing]) -> Result<Vec<VmRemediationTask>> {
+        let tasks: Vec<VmRemediationTask> = self
+            .db
+            .query("SELECT * FROM vm_remediation_task WHERE vm_id INSIDE $ids AND status = 'open' ORDER BY vm_name ASC, created_at ASC")
+            .bind(("ids", vm_ids))
+            .traced("vm_hygiene_service.open_tasks_for_vms")
+            .await
+            .context("Failed to query remediation tasks")?
+            .take(0)
+            .context("Failed to parse remediation tasks")?;
+        Ok(tasks)
+    }
+
+    pub async fn get_task(&self, task_id: &str) -> Result<Option<VmRemediationTask>> {
+        self.db
+            .select((TASK_TABLE, task_id))
+            .await
+            .context("Failed to get remediation task")
+    }
+
+    /// Mark a task done or reopen it; None when it does not exist
+    pub async fn update_task(
+        &self,
+        task_id: &str,
+        request: UpdateRemediationTaskRequest,
+    ) -> Result<Option<VmRemediationTask>> {
+        let Some(mut task) = self.get_task(task_id).await? else {
+            return Ok(None);
+        };
+        task.status = request.status;
+        task.completed_at = (request.status == RemediationStatus::Done).then(Utc::now);
+
+        self.db
+            .update((TASK_TABLE, task_id))
+            .content(task)
+            .await
+            .context("Failed to update remediation task")
+    }
+
+    /// Create the planned tasks that have no open counterpart, and move
+    /// open ones to the wave and detail of the latest assessment. Returns
+    /// how many were created.
+    async fn raise_tasks(&self, planned: Vec<VmRemediationTask>) -> Result<usize> {
+        let vm_ids: Vec<Thing> = planned.iter().map(|t| t.vm_id.clone()).collect();
+        let open = self.open_tasks_for_vms(&vm_ids).await?;
+
+        let mut created = 0;
+        for task in planned {
+            let existing = open.iter().find(|t| t.vm_id == task.vm_id && t.action == task.action);
+            match existing.and_then(|t| t.id.clone()) {
+                Some(id) => {
+                    let _: Option<VmRemediationTask> = self
+                        .db
+                        .update(id)
+                        .merge(serde_json::json!({ "wave_id": task.wave_id, "detail": task.detail }))
+                        .await
+                        .context("Failed to update remediation task")?;
+                }
+                None => {
+                    let _: Vec<VmRemediationTask> = self
+                        .db
+                        .create(TASK_TABLE)
+                        .content(task)
+                        .await
+                        .context("Failed to create remediation task")?;
+                    created += 1;
+                }
+            }
+        }
+        Ok(created)
+    }
+}
+
+/// Findings and snapshot storage of a project's source VMs
+pub fn assess(
+    project_id: &str,
+    inventory: &SourceInventory,
+    vms: &[MigrationWizardVM],
+    waves: &[MigrationWave],
+    thresholds: HygieneThresholds,
+    now: DateTime<Utc>,
+) -> VmHygieneReport {
+    let by_name: HashMap<&str, &MigrationWizardVM> = vms.iter().map(|vm| (vm.name.as_str(), vm)).collect();
+    let wave_of: HashMap<String, &MigrationWave> = waves
+        .iter()
+        .flat_map(|wave| wave.vm_ids.iter().map(move |id| (id.id.to_raw(), wave)))
+        .collect();
+    let finding = |vm_name: &str, issue: HygieneIssue, detail: String| {
+        let vm_id = by_name
+            .get(vm_name)
+            .and_then(|vm| vm.id.as_ref())
+            .map(|id| id.id.to_raw());
+        let wave = vm_id.as_ref().and_then(|id| wave_of.get(id));
+        HygieneFinding {
+            vm_name: vm_name.to_string(),
+            issue,
+            detail,
+            snapshot_name: None,
+            snapshot_age_days: None,
+            snapshot_size_gb: None,
+            tools_version: None,
+            wave_id: wave.and_then(|w| w.id.as_ref()).map(|id| id.id.to_raw()),
+            wave_name: wave.map(|w| w.name.clone()),
+            vm_id,
+        }
+    };
+
+    let mut findings = Vec::new();
+    let mut by_cluster: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+    let mut vms_with_snapshots = BTreeSet::new();
+    let mut snapshot_storage_gb = 0.0;
+    for snapshot in &inventory.snapshots {
+        let size_gb = snapshot.size().as_gib();
+        let cluster = snapshot
+            .cluster
+            .clone()
+            .or_else(|| by_name.get(snapshot.vm_name.as_str()).and_then(|vm| vm.cluster.clone()))
+            .unwrap_or_else(|| "Unclustered".to_string());
+        let storage = by_cluster.entry(cluster).or_default();
+        storage.0 += 1;
+        storage.1 += size_gb;
+        snapshot_storage_gb += size_gb;
+        vms_with_snapshots.insert(snapshot.vm_name.as_str());
+
+        let age_days = snapshot.created_at.map(|at| (now - at).num_days());
+        let flagged = [
+            (
+                age_days.is_some_and(|age| age > thresholds.snapshot_max_age_days),
+                HygieneIssue::OldSnapshot,
+                format!("Snapshot '{}' is {} days old", snapshot.name, age_days.unwrap_or_default()),
+            ),
+            (
+                size_gb > thresholds.snapshot_max_size_gb,
+                HygieneIssue::LargeSnapshot,
+                format!("Snapshot '{}' holds {:.1} GB", snapshot.name, size_gb),
+            ),
+        ];
+        for (_, issue, detail) in flagged.into_iter().filter(|(hit, _, _)| *hit) {
+            findings.push(HygieneFinding {
+                snapshot_name: Some(snapshot.name.clone()),
+                snapshot_age_days: age_days,
+                snapshot_size_gb: Some(size_gb),
+                ..finding(&snapshot.vm_name, issue, detail)
+            });
+        }
+    }
+
+    for tools in &inventory.tools {
+        if by_name.get(tools.vm_name.as_str()).is_some_and(|vm| vm.template == Some(true)) {
+            continue;
+        }
+        let Some(issue) = tools_issue(tools) else { continue };
+        let detail = match (issue, &tools.version, &tools.required_version) {
+            (HygieneIssue::ToolsMissing, _, _) => "VMware Tools is not installed".to_string(),
+            (_, Some(version), Some(required)) if version != required => {
+                format!("VMware Tools {} is older than {}", version, required)
+            }
+            (_, Some(version), _) => format!("VMware Tools {} is out of date", version),
+            _ => "VMware Tools is out of date".to_string(),
+        };
+        findings.push(HygieneFinding {
+            tools_version: tools.version.clone(),
+            ..finding(&tools.vm_name, issue, detail)
+        });
+    }
+    findings.sort_by(|a, b| a.vm_name.cmp(&b.vm_name));
+
+    VmHygieneReport {
+        project_id: project_id.to_string(),
+        thresholds,
+        snapshots: inventory.snapshots.len(),
+        vms_with_snapshots: vms_with_snapshots.len(),
+        snapshot_storage_gb,
+        snapshot_storage_by_cluster: by_cluster
+            .into_iter()
+            .map(|(cluster, (snapshots, size_gb))| ClusterSnapshotStorage { cluster, snapshots, size_gb })
+            .collect(),
+        tools_outdated: findings.iter().filter(|f| f.issue == HygieneIssue::ToolsOutdated).count(),
+        tools_missing: findings.iter().filter(|f| f.issue == HygieneIssue::ToolsMissing).count(),
+        findings,
+        tasks_created: 0,
+        assessed_at: now,
+    }
+}
+
+/// Tools managed by the guest OS (open-vm-tools) are left to the OS
+fn tools_issue(tools: &SourceVmTools) -> Option<HygieneIssue> {
+    let status = tools.status.as_deref().unwrap_or_default().to_ascii_lowercase();
+    if status.contains("notinstalled") {
+        return Some(HygieneIssue::ToolsMissing);
+    }
+    if status.contains("unmanaged") {
+        return None;
+    }
+    let behind = match (&tools.version, &tools.required_version) {
+        (Some(version), Some(required)) => match (version.parse::<i64>(), required.parse::<i64>()) {
+            (Ok(version), Ok(required)) => version < required,
+            _ => false,
+        },
+        _ => false,
+    };
+    (status == "toolsold" || tools.upgradeable == Some(true) || behind).then_some(HygieneIssue::ToolsOutdated)
+}
+
+/// One task per VM and fix; all flagged snapshots of a VM are consolidated
+/// together. Findings without a project VM raise nothing.
+pub fn plan_tasks(project_id: &str, findings: &[HygieneFinding], now: DateTime<Utc>) -> Vec<VmRemediationTask> {
+    let mut grouped: BTreeMap<(String, &str), Vec<&HygieneFinding>> = BTreeMap::new();
+    for finding in findings {
+        let Some(vm_id) = &finding.vm_id else { continue };
+        let action = match finding.issue {
+            HygieneIssue::OldSnapshot | HygieneIssue::LargeSnapshot => "consolidate",
+            HygieneIssue::ToolsOutdated => "update",
+            HygieneIssue::ToolsMissing => "install",
+        };
+        grouped.entry((vm_id.clone(), action)).or_default().push(finding);
+    }
+
+    grouped
+        .into_iter()
+        .map(|((vm_id, _), group)| {
+            let first = group[0];
+            let (action, detail) = match first.issue {
+                HygieneIssue::OldSnapshot | HygieneIssue::LargeSnapshot => {
+                    let mut sizes: BTreeMap<&str, f64> = BTreeMap::new();
+                    for f in &group {
+                        sizes.insert(f.snapshot_name.as_deref().unwrap_or_default(), f.snapshot_size_gb.unwrap_or_default());
+                    }
+                    let oldest = group.iter().filter_map(|f| f.snapshot_age_days).max();
+                    let mut detail = format!(
+                        "Consolidate or delete {} snapshot(s) holding {:.1} GB",
+                        sizes.len(),
+                        sizes.values().sum::<f64>()
+                    );
+                    if let Some(days) = oldest {
+                        detail.push_str(&format!("; the oldest is {} days old", days));
+                    }
+                    (RemediationAction::ConsolidateSnapshots, detail)
+                }
+                HygieneIssue::ToolsOutdated => (RemediationAction::UpdateTools, first.detail.clone()),
+                HygieneIssue::ToolsMissing => (RemediationAction::InstallTools, first.detail.clone()),
+            };
+            VmRemediationTask {
+                id: None,
+                project_id: Thing::from(("migration_wizard_project", project_id)),
+                vm_id: Thing::from(("migration_wizard_vm", vm_id.as_str())),
+                vm_name: first.vm_name.clone(),
+                wave_id: first.wave_id.as_deref().map(|id| Thing::from(("migration_wave", id))),
+                action,
+                detail,
+                status: RemediationStatus::Open,
+                created_at: now,
+                completed_at: None,
+            }
+        })
+        .collect()
+}
+
+/// Pre-migration runbook steps for a wave's open tasks
+pub fn runbook_steps(tasks: &[VmRemediationTask]) -> Vec<RunbookStep> {
+    tasks
+        .iter()
+        .filter(|task| task.status == RemediationStatus::Open)
+        .map(|task| RunbookStep {
+            phase: "pre_migration".to_string(),
+            action: format!("{} on {}: {}", task.action.label(), task.vm_name, task.detail),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::migration_wizard_models::SourceSnapshot;
+    use crate::models::timeline::WaveStatus;
+    use chrono::Duration;
+
+    fn vm(key: &str, cluster: &str) -> MigrationWizardVM {
+        MigrationWizardVM {
+            id: Some(Thing::from(("migration_wizard_vm", key))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: key.to_string(),
+            powerstate: Some("poweredOn".to_string()),
+            template: None,
+            cpus: 2,
+            memory_mb: 4096,
+            provisioned_mb: None,
+            in_use_mb: None,
+            primary_ip_address: None,
+            dns_name: None,
+            cluster: Some(cluster.to_string()),
+            host: None,
+            datacenter: None,
+            os: None,
+            version: None,
+            num_disks: 1,
+            num_nics: 1,
+            annotation: None,
+            folder: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn snapshot(vm: &str, name: &str, age_days: i64, size_mb: i64, now: DateTime<Utc>) -> SourceSnapshot {
+        SourceSnapshot {
+            vm_name: vm.to_string(),
+            name: name.to_string(),
+            created_at: Some(now - Duration::days(age_days)),
+            size_mb,
+            ..Default::default()
+        }
+    }
+
+    fn tools(vm: &str, status: &str, version: &str, required: &str) -> SourceVmTools {
+        SourceVmTools {
+            vm_name: vm.to_string(),
+            status: Some(status.to_string()),
+            version: Some(version.to_string()),
+            required_version: Some(required.to_string()),
+            upgradeable: None,
+        }
+    }
+
+    #[test]
+    fn test_flags_old_and_large_snapshots_and_outdated_or_missing_tools() {
+        let now = Utc::now();
+        let inventory = SourceInventory {
+            snapshots: vec![
+                snapshot("app01", "before patch", 90, 81_920, now),
+                snapshot("app01", "fresh", 1, 1_024, now),
+                snapshot("db01", "upgrade", 10, 2_048, now),
+            ],
+            tools: vec![
+                tools("app01", "toolsOk", "12352", "12352"),
+                tools("db01", "toolsOld", "11333", "12352"),
+                tools("web01", "toolsNotInstalled", "0", "12352"),
+                tools("lnx01", "guestToolsUnmanaged", "10346", "12352"),
+            ],
+            ..Default::default()
+        };
+        let vms = vec![vm("app01", "Prod"), vm("db01", "Prod"), vm("web01", "Dev"), vm("lnx01", "Dev")];
+        let wave = MigrationWave {
+            id: Some(Thing::from(("migration_wave", "w1"))),
+            project_id: Thing::from(("migration_wizard_project", "p1")),
+            name: "Wave 1".to_string(),
+            sequence: 1,
+            vm_ids: vec![Thing::from(("migration_wizard_vm", "db01"))],
+            target_cluster_ids: vec![],
+            planned_start: None,
+            planned_end: None,
+            change_window: None,
+            status: WaveStatus::Planned,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let report = assess("p1", &inventory, &vms, &[wave], HygieneThresholds::default(), now);
+
+        let issues: Vec<(&str, HygieneIssue)> = report.findings.iter().map(|f| (f.vm_name.as_str(), f.issue)).collect();
+        assert_eq!(
+            issues,
+            vec![
+                ("app01", HygieneIssue::OldSnapshot),
+                ("app01", HygieneIssue::LargeSnapshot),
+                ("db01", HygieneIssue::OldSnapshot),
+                ("db01", HygieneIssue::ToolsOutdated),
+                ("web01", HygieneIssue::ToolsMissing),
+            ]
+        );
+        assert_eq!((report.snapshots, report.vms_with_snapshots), (3, 2));
+        assert_eq!(report.snapshot_storage_gb, 83.0);
+        assert_eq!(report.snapshot_storage_by_cluster[0].cluster, "Prod");
+        assert_eq!((report.tools_outdated, report.tools_missing), (1, 1));
+        assert_eq!(report.findings[3].wave_name.as_deref(), Some("Wave 1"));
+        assert_eq!(report.findings[3].detail, "VMware Tools 11333 is older than 12352");
+    }
+
+    #[test]
+    fn test_one_task_per_vm_and_fix() {
+        let now = Utc::now();
+        let inventory = SourceInventory {
+            snapshots: vec![snapshot("app01", "old and big", 30, 102_400, now), snapshot("app01", "old", 8, 1_024, now)],
+            tools: vec![tools("app01", "toolsOld", "11333", "12352"), tools("gone", "toolsNotInstalled", "0", "12352")],
+            ..Default::default()
+        };
+        let report = assess("p1", &inventory, &[vm("app01", "Prod")], &[], HygieneThresholds::default(), now);
+
+        let tasks = plan_tasks("p1", &report.findings, now);
+
+        assert_eq!(tasks.len(), 2, "the VM without a project record raises nothing");
+        assert_eq!(tasks[0].action, RemediationAction::ConsolidateSnapshots);
+        assert_eq!(tasks[0].detail, "Consolidate or delete 2 snapshot(s) holding 101.0 GB; the oldest is 30 days old");
+        assert_eq!(tasks[1].action, RemediationAction::UpdateTools);
+
+        let steps = runbook_steps(&tasks);
+        assert!(steps.iter().all(|s| s.phase == "pre_migration"));
+        assert!(steps[1].action.starts_with("Update VMware Tools on app01"));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::models::project_models::{MigrationCluster, VMPlacement};
+use crate::models::usage_telemetry::UsageFeature;
+use crate::services::usage_telemetry_service;
 
 /// VM Placement Strategy
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -126,6 +128,7 @@ impl VMPlacementService {
         strategy: PlacementStrategy,
         project_id: &str,
     ) -> PlacementResult {
+        usage_telemetry_service::record(UsageFeature::VmPlacement);
         let total_vms = vms.len();
         let mut placed_vms = Vec::new();
         let mut unplaced_vms = Vec::new();
@@ -0,0 +1,1012 @@
+//! Webhook Service
+//!
+//! Outbound webhooks that let external systems react to Archer events:
+//! - Admins subscribe an https URL to event types, for one tenant or for
+//!   every tenant; shared records without a tenant only reach the latter
+//! - [`emit`] queues an event as one `webhook_delivery` per matching
+//!   subscription and returns at once, so a slow or broken subscriber never
+//!   holds up the ticket, wave or document that raised it
+//! - [`WebhookScheduler`] sends due deliveries every ten seconds. Each body
+//!   is signed with HMAC-SHA256 over `<timestamp>.<body>`; transport errors,
+//!   408, 429 and 5xx are retried with exponential backoff and anything else,
+//!   or the last failed attempt, dead-letters the delivery. Dead letters can
+//!   be redelivered.
+//! - Capacity alerts come from a daily sweep of the capacity overview
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{error, info, warn};
+
+use crate::database::{locks, Database, TracedQuery};
+use crate::models::webhook::*;
+use crate::services::capacity_overview_service::{CapacityOverviewQuery, CapacityOverviewService};
+use crate::services::llm::SecretBox;
+use crate::utils::telemetry::TracedRequest;
+
+const SUBSCRIPTION_TABLE: &str = "webhook_subscription";
+const DELIVERY_TABLE: &str = "webhook_delivery";
+/// Retry policies are capped regardless of what the subscription asks for
+const MAX_ATTEMPTS: u32 = 10;
+/// Longest wait between two attempts (6 hours)
+const MAX_BACKOFF_SECONDS: u64 = 6 * 60 * 60;
+/// Shortest signing secret accepted from a caller
+const MIN_SECRET_LEN: usize = 16;
+/// Due deliveries picked up per dispatcher run
+const DISPATCH_BATCH: usize = 50;
+/// Subscribers called at once
+const MAX_PARALLEL_DELIVERIES: usize = 8;
+/// Failed responses keep this much of their body in the delivery log
+const MAX_ERROR_BYTES: usize = 1024;
+const MAX_LISTED_DELIVERIES: usize = 500;
+/// Send due deliveries every ten seconds
+const DISPATCH_CRON: &str = "*/10 * * * * *";
+/// Sweep capacity bottlenecks at 07:00 every day
+const CAPACITY_ALERT_CRON: &str = "0 0 7 * * *";
+const DISPATCH_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+const CAPACITY_ALERT_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+pub const EVENT_HEADER: &str = "X-Archer-Event";
+pub const EVENT_ID_HEADER: &str = "X-Archer-Event-Id";
+pub const DELIVERY_HEADER: &str = "X-Archer-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-Archer-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Archer-Signature";
+
+/// Redirects are not followed: a POST would be replayed as a GET
+static HTTP: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent("Archer-Webhooks/1.0")
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Webhook subscription not found")]
+    NotFound,
+
+    #[error("Webhook delivery not found")]
+    DeliveryNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Secret error: {0}")]
+    Secret(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<surrealdb::Error> for WebhookError {
+    fn from(e: surrealdb::Error) -> Self {
+        WebhookError::DatabaseError(e.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectTenant {
+    id: Thing,
+    #[serde(default)]
+    tenant_id: Option<String>,
+}
+
+// ============================================================================
+// EMITTING
+// ============================================================================
+
+/// Queue an event for every matching subscription in the background;
+/// webhook failures are logged and never reach the caller
+pub fn emit(db: &Database, event_type: WebhookEventType, tenant_id: Option<String>, data: Value) {
+    let service = WebhookService::new(Arc::new(db.clone()));
+    tokio::spawn(async move {
+        if let Err(e) = service.enqueue(event_type, tenant_id.as_deref(), data).await {
+            warn!("Failed to queue {} webhooks: {}", event_type.key(), e);
+        }
+    });
+}
+
+/// Like [`emit`], with the tenant taken from the project's `tenant_id`
+pub fn emit_for_project(db: &Database, event_type: WebhookEventType, project_id: Thing, data: Value) {
+    let service = WebhookService::new(Arc::new(db.clone()));
+    tokio::spawn(async move {
+        let tenant_id = service.project_tenant(&project_id).await;
+        if let Err(e) = service.enqueue(event_type, tenant_id.as_deref(), data).await {
+            warn!("Failed to queue {} webhooks: {}", event_type.key(), e);
+        }
+    });
+}
+
+// ============================================================================
+// SERVICE
+// ============================================================================
+
+pub struct WebhookService {
+    db: Arc<Database>,
+}
+
+impl WebhookService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    // ------------------------------------------------------------------------
+    // Subscriptions
+    // ------------------------------------------------------------------------
+
+    pub async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscriptionView>, WebhookError> {
+        let subscriptions: Vec<WebhookSubscription> = self
+            .db
+            .query(format!("SELECT * FROM {SUBSCRIPTION_TABLE} ORDER BY tenant_id, name"))
+            .traced("webhook_service.list_subscriptions")
+            .await?
+            .take(0)?;
+        Ok(subscriptions.iter().map(WebhookSubscriptionView::from).collect())
+    }
+
+    pub async fn get_subscription(&self, id: &str) -> Result<WebhookSubscriptionView, WebhookError> {
+        Ok(WebhookSubscriptionView::from(&self.load_subscription(id).await?))
+    }
+
+    /// The response carries the signing secret; it is not shown again
+    pub async fn create_subscription(
+        &self,
+        request: CreateWebhookSubscriptionRequest,
+        created_by: &str,
+    ) -> Result<WebhookSubscriptionView, WebhookError> {
+        let retry_policy = request.retry_policy.unwrap_or_default();
+        validate_subscription(&request.name, &request.url, &request.event_types, &retry_policy)?;
+        let secret = match request.secret {
+            Some(secret) => validate_secret(secret)?,
+            None => generate_secret(),
+        };
+
+        let now = Utc::now();
+        let subscription = WebhookSubscription {
+            id: None,
+            name: request.name.trim().to_string(),
+            tenant_id: request.tenant_id.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()),
+            url: request.url.trim().to_string(),
+            event_types: dedup_events(request.event_types),
+            secret_encrypted: seal(&secret)?,
+            retry_policy,
+            enabled: request.enabled,
+            created_by: created_by.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        let created: Vec<WebhookSubscription> = self.db.create(SUBSCRIPTION_TABLE).content(subscription).await?;
+        let created = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| WebhookError::DatabaseError("Subscription was not stored".to_string()))?;
+
+        let mut view = WebhookSubscriptionView::from(&created);
+        view.signing_secret = Some(secret);
+        Ok(view)
+    }
+
+    /// A rotated secret is returned once, like a generated one
+    pub async fn update_subscription(
+        &self,
+        id: &str,
+        request: UpdateWebhookSubscriptionRequest,
+    ) -> Result<WebhookSubscriptionView, WebhookError> {
+        let mut subscription = self.load_subscription(id).await?;
+        if let Some(name) = request.name {
+            subscription.name = name.trim().to_string();
+        }
+        if let Some(url) = request.url {
+            subscription.url = url.trim().to_string();
+        }
+        if let Some(event_types) = request.event_types {
+            subscription.event_types = dedup_events(event_types);
+        }
+        if let Some(retry_policy) = request.retry_policy {
+            subscription.retry_policy = retry_policy;
+        }
+        if let Some(enabled) = request.enabled {
+            subscription.enabled = enabled;
+        }
+        validate_subscription(
+            &subscription.name,
+            &subscription.url,
+            &subscription.event_types,
+            &subscription.retry_policy,
+        )?;
+
+        let new_secret = match (request.secret, request.rotate_secret) {
+            (Some(_), true) => {
+                return Err(WebhookError::Validation("set either secret or rotate_secret".to_string()))
+            }
+            (Some(secret), false) => Some((validate_secret(secret)?, false)),
+            (None, true) => Some((generate_secret(), true)),
+            (None, false) => None,
+        };
+        if let Some((secret, _)) = &new_secret {
+            subscription.secret_encrypted = seal(secret)?;
+        }
+        subscription.updated_at = Utc::now();
+
+        let key = record_key(id, SUBSCRIPTION_TABLE);
+        let updated: Option<WebhookSubscription> =
+            self.db.update((SUBSCRIPTION_TABLE, key)).content(subscription).await?;
+        let updated = updated.ok_or(WebhookError::NotFound)?;
+
+        let mut view = WebhookSubscriptionView::from(&updated);
+        view.signing_secret = new_secret.filter(|(_, generated)| *generated).map(|(secret, _)| secret);
+        Ok(view)
+    }
+
+    /// Removes the subscription together with its delivery log
+    pub async fn delete_subscription(&self, id: &str) -> Result<(), WebhookError> {
+        let key = record_key(id, SUBSCRIPTION_TABLE);
+        let deleted: Option<WebhookSubscription> = self.db.delete((SUBSCRIPTION_TABLE, key)).await?;
+        let deleted = deleted.ok_or(WebhookError::NotFound)?;
+        self.db
+            .query(format!("DELETE {DELIVERY_TABLE} WHERE subscription_id = $subscription"))
+            .bind(("subscription", deleted.id))
+            .traced("webhook_service.delete_subscription")
+            .await?;
+        Ok(())
+    }
+
+    async fn load_subscription(&self, id: &str) -> Result<WebhookSubscription, WebhookError> {
+        let key = record_key(id, SUBSCRIPTION_TABLE);
+        let subscription: Option<WebhookSubscription> = self.db.select((SUBSCRIPTION_TABLE, key)).await?;
+        subscription.ok_or(WebhookError::NotFound)
+    }
+
+    // ------------------------------------------------------------------------
+    // Deliveries
+    // ------------------------------------------------------------------------
+
+    /// Delivery log of a subscription, newest first
+    pub async fn list_deliveries(
+        &self,
+        subscription_id: &str,
+        query: &WebhookDeliveryQuery,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        let subscription = self.load_subscription(subscription_id).await?;
+        let limit = query.limit.unwrap_or(100).clamp(1, MAX_LISTED_DELIVERIES);
+        let status_filter = if query.status.is_some() { " AND status = $status" } else { "" };
+        let deliveries: Vec<WebhookDelivery> = self
+            .db
+            .query(format!(
+                "SELECT * FROM {DELIVERY_TABLE} WHERE subscription_id = $subscription{status_filter} \
+                 ORDER BY created_at DESC LIMIT $limit"
+            ))
+            .bind(("subscription", subscription.id))
+            .bind(("status", query.status))
+            .bind(("limit", limit))
+            .traced("webhook_service.list_deliveries")
+            .await?
+            .take(0)?;
+        Ok(deliveries)
+    }
+
+    /// Queue a delivered or dead-lettered event again with a fresh set of attempts
+    pub async fn redeliver(&self, subscription_id: &str, delivery_id: &str) -> Result<WebhookDelivery, WebhookError> {
+        let subscription = self.load_subscription(subscription_id).await?;
+        let key = record_key(delivery_id, DELIVERY_TABLE);
+        let delivery: Option<WebhookDelivery> = self.db.select((DELIVERY_TABLE, key.clone())).await?;
+        let mut delivery = delivery
+            .filter(|d| Some(&d.subscription_id) == subscription.id.as_ref())
+            .ok_or(WebhookError::DeliveryNotFound)?;
+        if delivery.status == WebhookDeliveryStatus::Pending {
+            return Err(WebhookError::Validation("the delivery is still pending".to_string()));
+        }
+
+        delivery.status = WebhookDeliveryStatus::Pending;
+        delivery.attempt_count = 0;
+        delivery.next_attempt_at = Utc::now();
+        delivery.delivered_at = None;
+        delivery.dead_lettered_at = None;
+        let updated: Option<WebhookDelivery> = self.db.update((DELIVERY_TABLE, key)).content(delivery).await?;
+        updated.ok_or(WebhookError::DeliveryNotFound)
+    }
+
+    /// Record the event for every enabled subscription that receives it.
+    /// Returns the number of deliveries queued.
+    pub async fn enqueue(
+        &self,
+        event_type: WebhookEventType,
+        tenant_id: Option<&str>,
+        data: Value,
+    ) -> Result<usize, WebhookError> {
+        let subscriptions = self.receiving(event_type, tenant_id).await?;
+        if subscriptions.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        let event = WebhookEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            event_type,
+            tenant_id: tenant_id.map(str::to_string),
+            occurred_at: now,
+            data,
+        };
+        let body = serde_json::to_string(&event).map_err(|e| WebhookError::Validation(e.to_string()))?;
+
+        let mut queued = 0;
+        for subscription in subscriptions {
+            let Some(subscription_id) = subscription.id else { continue };
+            let delivery = WebhookDelivery {
+                id: None,
+                subscription_id,
+                event_id: event.id.clone(),
+                event_type,
+                tenant_id: event.tenant_id.clone(),
+                body: body.clone(),
+                status: WebhookDeliveryStatus::Pending,
+                attempt_count: 0,
+                attempts: Vec::new(),
+                next_attempt_at: now,
+                created_at: now,
+                delivered_at: None,
+                dead_lettered_at: None,
+            };
+            let _: Vec<WebhookDelivery> = self.db.create(DELIVERY_TABLE).content(delivery).await?;
+            queued += 1;
+        }
+        Ok(queued)
+    }
+
+    async fn receiving(
+        &self,
+        event_type: WebhookEventType,
+        tenant_id: Option<&str>,
+    ) -> Result<Vec<WebhookSubscription>, WebhookError> {
+        let mut subscriptions: Vec<WebhookSubscription> = self
+            .db
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TABLE} WHERE enabled = true AND $event_type INSIDE event_types"
+            ))
+            .bind(("event_type", event_type))
+            .traced("webhook_service.receiving")
+            .await?
+            .take(0)?;
+        subscriptions.retain(|s| s.receives(event_type, tenant_id));
+        Ok(subscriptions)
+    }
+
+    async fn project_tenant(&self, project_id: &Thing) -> Option<String> {
+        let tenants: Vec<Option<String>> = self
+            .db
+            .query("SELECT VALUE tenant_id FROM $project")
+            .bind(("project", project_id.clone()))
+            .traced("webhook_service.project_tenant")
+            .await
+            .ok()?
+            .take(0)
+            .ok()?;
+        tenants.into_iter().next().flatten()
+    }
+
+    // ------------------------------------------------------------------------
+    // Dispatch
+    // ------------------------------------------------------------------------
+
+    /// Attempt every due delivery once. Returns the number attempted.
+    pub async fn dispatch_due(&self) -> Result<usize, WebhookError> {
+        let now = Utc::now();
+        let due: Vec<WebhookDelivery> = self
+            .db
+            .query(format!(
+                "SELECT * FROM {DELIVERY_TABLE} WHERE status = 'pending' AND next_attempt_at <= $now \
+                 ORDER BY next_attempt_at ASC LIMIT $limit"
+            ))
+            .bind(("now", now))
+            .bind(("limit", DISPATCH_BATCH))
+            .traced("webhook_service.dispatch_due")
+            .await?
+            .take(0)?;
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let subscription_ids: Vec<Thing> = due.iter().map(|d| d.subscription_id.clone()).collect();
+        let subscriptions: Vec<WebhookSubscription> = self
+            .db
+            .query(format!("SELECT * FROM {SUBSCRIPTION_TABLE} WHERE id INSIDE $ids"))
+            .bind(("ids", subscription_ids))
+            .traced("webhook_service.dispatch_due")
+            .await?
+            .take(0)?;
+        let subscriptions: HashMap<Thing, WebhookSubscription> = subscriptions
+            .into_iter()
+            .filter_map(|s| s.id.clone().map(|id| (id, s)))
+            .collect();
+        let secrets = SecretBox::from_env().map_err(|e| WebhookError::Secret(e.to_string()))?;
+
+        let permits = Arc::new(Semaphore::new(MAX_PARALLEL_DELIVERIES));
+        let mut running = JoinSet::new();
+        let attempted = due.len();
+        for delivery in due {
+            let subscription = subscriptions.get(&delivery.subscription_id);
+            let target = match subscription {
+                None => Err("the subscription no longer exists".to_string()),
+                Some(s) if !s.enabled => Err("the subscription is disabled".to_string()),
+                Some(s) => secrets
+                    .decrypt(&s.secret_encrypted)
+                    .map(|secret| (s.url.clone(), secret))
+                    .map_err(|e| e.to_string()),
+            };
+            // Deliveries without a usable subscription go straight to the dead letters
+            let policy = match (&target, subscription) {
+                (Ok(_), Some(s)) => s.retry_policy,
+                _ => WebhookRetryPolicy {
+                    max_attempts: 1,
+                    ..Default::default()
+                },
+            };
+            let permits = permits.clone();
+            running.spawn(async move {
+                let attempt = match target {
+                    Ok((url, secret)) => {
+                        let _permit = permits.acquire_owned().await;
+                        send(&url, &secret, &delivery).await
+                    }
+                    Err(reason) => WebhookDeliveryAttempt {
+                        attempted_at: Utc::now(),
+                        response_status: None,
+                        error: Some(reason),
+                        duration_ms: 0,
+                    },
+                };
+                (delivery, policy, attempt)
+            });
+        }
+
+        while let Some(joined) = running.join_next().await {
+            let (mut delivery, policy, attempt) = match joined {
+                Ok(result) => result,
+                Err(e) => {
+                    error!("❌ Webhook delivery task failed: {}", e);
+                    continue;
+                }
+            };
+            record_attempt(&mut delivery, &policy, attempt, Utc::now());
+            let Some(id) = delivery.id.clone() else { continue };
+            let result: Result<Option<WebhookDelivery>, _> = self.db.update(id).content(&delivery).await;
+            if let Err(e) = result {
+                warn!("Failed to record webhook delivery attempt: {}", e);
+            }
+        }
+        Ok(attempted)
+    }
+
+    // ------------------------------------------------------------------------
+    // Capacity alerts
+    // ------------------------------------------------------------------------
+
+    /// Queue a `capacity.alert` for every current capacity bottleneck.
+    /// Returns the number of alerts raised.
+    pub async fn raise_capacity_alerts(&self) -> Result<usize, WebhookError> {
+        let subscribed: Vec<Thing> = self
+            .db
+            .query(format!(
+                "SELECT VALUE id FROM {SUBSCRIPTION_TABLE} WHERE enabled = true AND $event_type INSIDE event_types"
+            ))
+            .bind(("event_type", WebhookEventType::CapacityAlert))
+            .traced("webhook_service.raise_capacity_alerts")
+            .await?
+            .take(0)?;
+        if subscribed.is_empty() {
+            return Ok(0);
+        }
+
+        let query = CapacityOverviewQuery {
+            threshold_percent: None,
+            top: Some(100),
+        };
+        let overview = CapacityOverviewService::new(self.db.as_ref().clone())
+            .overview(None, &query)
+            .await
+            .map_err(|e| WebhookError::DatabaseError(e.to_string()))?;
+
+        let projects: Vec<ProjectTenant> = self
+            .db
+            .query("SELECT id, tenant_id FROM migration_wizard_project")
+            .traced("webhook_service.raise_capacity_alerts")
+            .await?
+            .take(0)?;
+        let tenants: HashMap<String, Option<String>> =
+            projects.into_iter().map(|p| (p.id.to_string(), p.tenant_id)).collect();
+        let cluster_projects: HashMap<&str, &str> = overview
+            .clusters
+            .iter()
+            .map(|c| (c.cluster_id.as_str(), c.project_id.as_str()))
+            .collect();
+
+        let mut raised = 0;
+        for bottleneck in &overview.bottlenecks {
+            let project_id = cluster_projects.get(bottleneck.cluster_id.as_str()).copied();
+            let tenant_id = project_id.and_then(|p| tenants.get(p).cloned().flatten());
+            let mut data = serde_json::to_value(bottleneck).map_err(|e| WebhookError::Validation(e.to_string()))?;
+            data["project_id"] = json!(project_id);
+            data["threshold_percent"] = json!(overview.threshold_percent);
+            if self
+                .enqueue(WebhookEventType::CapacityAlert, tenant_id.as_deref(), data)
+                .await?
+                > 0
+            {
+                raised += 1;
+            }
+        }
+        Ok(raised)
+    }
+}
+
+// ============================================================================
+// SENDING
+// ============================================================================
+
+/// What an attempt's outcome means for the delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttemptOutcome {
+    Delivered,
+    Retry,
+    /// The subscriber refused the event; retrying won't change that
+    Reject,
+}
+
+/// `None` is a transport error or timeout
+fn classify(response_status: Option<u16>) -> AttemptOutcome {
+    match response_status {
+        Some(200..=299) => AttemptOutcome::Delivered,
+        None | Some(408) | Some(429) | Some(500..=599) => AttemptOutcome::Retry,
+        Some(_) => AttemptOutcome::Reject,
+    }
+}
+
+/// Wait before the attempt after `attempt_count` failed ones
+fn backoff(policy: &WebhookRetryPolicy, attempt_count: u32) -> Duration {
+    let exponent = attempt_count.saturating_sub(1).min(16);
+    let seconds = policy
+        .backoff_seconds
+        .saturating_mul(1 << exponent)
+        .min(MAX_BACKOFF_SECONDS);
+    Duration::seconds(seconds as i64)
+}
+
+/// Log the attempt and move the delivery on: delivered, retried later or dead-lettered
+fn record_attempt(
+    delivery: &mut WebhookDelivery,
+    policy: &WebhookRetryPolicy,
+    attempt: WebhookDeliveryAttempt,
+    now: DateTime<Utc>,
+) {
+    let outcome = classify(attempt.response_status);
+    delivery.attempt_count += 1;
+    delivery.attempts.push(attempt);
+
+    match outcome {
+        AttemptOutcome::Delivered => {
+            delivery.status = WebhookDeliveryStatus::Delivered;
+            delivery.delivered_at = Some(now);
+        }
+        AttemptOutcome::Retry if delivery.attempt_count < policy.max_attempts.clamp(1, MAX_ATTEMPTS) => {
+            delivery.next_attempt_at = now + backoff(policy, delivery.attempt_count);
+        }
+        AttemptOutcome::Retry | AttemptOutcome::Reject => {
+            delivery.status = WebhookDeliveryStatus::DeadLettered;
+            delivery.dead_lettered_at = Some(now);
+        }
+    }
+}
+
+/// POST the delivery's body once
+async fn send(url: &str, secret: &str, delivery: &WebhookDelivery) -> WebhookDeliveryAttempt {
+    let started = std::time::Instant::now();
+    let attempted_at = Utc::now();
+    let timestamp = attempted_at.timestamp();
+    let delivery_id = delivery.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default();
+
+    let result = HTTP
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, delivery.event_type.key())
+        .header(EVENT_ID_HEADER, delivery.event_id.as_str())
+        .header(DELIVERY_HEADER, delivery_id)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, signature(secret, timestamp, &delivery.body))
+        .body(delivery.body.clone())
+        .send_traced("webhook")
+        .await;
+
+    let (response_status, error) = match result {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                (Some(status.as_u16()), None)
+            } else {
+                let body = response.text().await.unwrap_or_default();
+                let error = if body.is_empty() {
+                    format!("HTTP {}", status.as_u16())
+                } else {
+                    truncate(&body, MAX_ERROR_BYTES).to_string()
+                };
+                (Some(status.as_u16()), Some(error))
+            }
+        }
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    WebhookDeliveryAttempt {
+        attempted_at,
+        response_status,
+        error,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// HMAC-SHA256 keyed with `key`, ready for the message
+fn hmac_sha256(key: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+/// `sha256=<hex>` of HMAC-SHA256 over `<timestamp>.<body>`
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let message = format!("{}.{}", timestamp, body);
+    format!("sha256={}", hmac_sha256_hex(secret.as_bytes(), message.as_bytes()))
+}
+
+/// Check an `X-Archer-Signature` value against `<timestamp>.<body>`; the
+/// MAC comparison is constant-time
+pub fn verify_signature(secret: &str, timestamp: i64, body: &str, signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix("sha256=").and_then(decode_hex) else {
+        return false;
+    };
+    let mut mac = hmac_sha256(secret.as_bytes());
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    let mut mac = hmac_sha256(key);
+    mac.update(message);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+// ============================================================================
+// VALIDATION
+// ============================================================================
+
+fn validate_subscription(
+    name: &str,
+    url: &str,
+    event_types: &[WebhookEventType],
+    retry_policy: &WebhookRetryPolicy,
+) -> Result<(), WebhookError> {
+    if name.trim().is_empty() {
+        return Err(WebhookError::Validation("name must not be blank".to_string()));
+    }
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| WebhookError::Validation(format!("url is not valid: {}", e)))?;
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        return Err(WebhookError::Validation("url must be an https URL".to_string()));
+    }
+    if event_types.is_empty() {
+        return Err(WebhookError::Validation("subscribe to at least one event type".to_string()));
+    }
+    if retry_policy.max_attempts == 0 || retry_policy.max_attempts > MAX_ATTEMPTS {
+        return Err(WebhookError::Validation(format!(
+            "retry_policy.max_attempts must be between 1 and {}",
+            MAX_ATTEMPTS
+        )));
+    }
+    if retry_policy.backoff_seconds == 0 || retry_policy.backoff_seconds > MAX_BACKOFF_SECONDS {
+        return Err(WebhookError::Validation(format!(
+            "retry_policy.backoff_seconds must be between 1 and {}",
+            MAX_BACKOFF_SECONDS
+        )));
+    }
+    Ok(())
+}
+
+fn validate_secret(secret: String) -> Result<String, WebhookError> {
+    if secret.trim().len() < MIN_SECRET_LEN {
+        return Err(WebhookError::Validation(format!(
+            "secret must be at least {} characters",
+            MIN_SECRET_LEN
+        )));
+    }
+    Ok(secret)
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn seal(secret: &str) -> Result<String, WebhookError> {
+    SecretBox::from_env()
+        .and_then(|secrets| secrets.encrypt(secret))
+        .map_err(|e| WebhookError::Secret(e.to_string()))
+}
+
+fn dedup_events(mut event_types: Vec<WebhookEventType>) -> Vec<WebhookEventType> {
+    let mut seen = Vec::with_capacity(event_types.len());
+    event_types.retain(|e| {
+        let first = !seen.contains(e);
+        seen.push(*e);
+        first
+    });
+    event_types
+}
+
+/// Accepts `table:key` as well as the bare key
+fn record_key(id: &str, table: &str) -> String {
+    let key = id.strip_prefix(table).and_then(|k| k.strip_prefix(':')).unwrap_or(id);
+    key.trim_start_matches('⟨').trim_end_matches('⟩').to_string()
+}
+
+// ============================================================================
+// SCHEDULER
+// ============================================================================
+
+/// Sends due deliveries every ten seconds and sweeps capacity alerts daily
+pub struct WebhookScheduler {
+    db: Arc<Database>,
+}
+
+impl WebhookScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+
+        let db = Arc::clone(&self.db);
+        let service = Arc::new(WebhookService::new(Arc::clone(&self.db)));
+        let dispatch = Job::new_async(DISPATCH_CRON, move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                // One replica sends a given delivery
+                let run = locks::run_exclusive(
+                    &db,
+                    locks::WEBHOOK_DISPATCH_LOCK,
+                    DISPATCH_LOCK_TTL,
+                    service.dispatch_due(),
+                );
+                match run.await {
+                    Ok(None) | Ok(Some(Ok(0))) => {}
+                    Ok(Some(Ok(count))) => info!("🪝 Attempted {} webhook deliver(ies)", count),
+                    Ok(Some(Err(e))) => error!("❌ Webhook dispatch failed: {}", e),
+                    Err(e) => error!("❌ Webhook dispatch lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create webhook dispatch job: {}", e))?;
+
+        let db = Arc::clone(&self.db);
+        let service = Arc::new(WebhookService::new(Arc::clone(&self.db)));
+        let capacity = Job::new_async(CAPACITY_ALERT_CRON, move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let service = Arc::clone(&service);
+            Box::pin(async move {
+                let run = locks::run_exclusive(
+                    &db,
+                    locks::CAPACITY_ALERTS_LOCK,
+                    CAPACITY_ALERT_LOCK_TTL,
+                    service.raise_capacity_alerts(),
+                );
+                match run.await {
+                    Ok(None) | Ok(Some(Ok(0))) => {}
+                    Ok(Some(Ok(count))) => info!("🪝 Raised {} capacity alert(s)", count),
+                    Ok(Some(Err(e))) => error!("❌ Capacity alert sweep failed: {}", e),
+                    Err(e) => error!("❌ Capacity alert lock failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to create capacity alert job: {}", e))?;
+
+        for job in [dispatch, capacity] {
+            scheduler
+                .add(job)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to add webhook job: {}", e))?;
+        }
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+
+        // Keep the scheduler alive for the process lifetime
+        std::mem::forget(scheduler);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delivery() -> WebhookDelivery {
+        let now = Utc::now();
+        WebhookDelivery {
+            id: None,
+            subscription_id: Thing::from((SUBSCRIPTION_TABLE, "s1")),
+            event_id: "e1".to_string(),
+            event_type: WebhookEventType::TicketCreated,
+            tenant_id: None,
+            body: "{}".to_string(),
+            status: WebhookDeliveryStatus::Pending,
+            attempt_count: 0,
+            attempts: Vec::new(),
+            next_attempt_at: now,
+            created_at: now,
+            delivered_at: None,
+            dead_lettered_at: None,
+        }
+    }
+
+    fn attempt(status: Option<u16>) -> WebhookDeliveryAttempt {
+        WebhookDeliveryAttempt {
+            attempted_at: Utc::now(),
+            response_status: status,
+            error: None,
+            duration_ms: 12,
+        }
+    }
+
+    #[test]
+    fn test_hmac_matches_rfc_4231() {
+        // Test case 2 of RFC 4231
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test case 6: a key longer than the block size is hashed first
+        assert_eq!(
+            hmac_sha256_hex(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First"),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+        assert!(signature("secret", 1_700_000_000, "{}").starts_with("sha256="));
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let signed = signature("secret", 1_700_000_000, r#"{"id":"evt_1"}"#);
+        assert!(verify_signature("secret", 1_700_000_000, r#"{"id":"evt_1"}"#, &signed));
+        let upper_hex = format!("sha256={}", signed["sha256=".len()..].to_uppercase());
+        assert!(verify_signature("secret", 1_700_000_000, r#"{"id":"evt_1"}"#, &upper_hex));
+
+        assert!(!verify_signature("other", 1_700_000_000, r#"{"id":"evt_1"}"#, &signed));
+        assert!(!verify_signature("secret", 1_700_000_001, r#"{"id":"evt_1"}"#, &signed));
+        assert!(!verify_signature("secret", 1_700_000_000, r#"{"id":"evt_2"}"#, &signed));
+        assert!(!verify_signature("secret", 1_700_000_000, r#"{"id":"evt_1"}"#, &signed[..signed.len() - 2]));
+        assert!(!verify_signature("secret", 1_700_000_000, r#"{"id":"evt_1"}"#, signed.trim_start_matches("sha256=")));
+        assert!(!verify_signature("secret", 1_700_000_000, r#"{"id":"evt_1"}"#, "sha256=zz"));
+    }
+
+    #[test]
+    fn test_retries_with_backoff_then_dead_letters() {
+        let policy = WebhookRetryPolicy {
+            max_attempts: 3,
+            backoff_seconds: 30,
+        };
+        let now = Utc::now();
+        let mut d = delivery();
+
+        record_attempt(&mut d, &policy, attempt(Some(503)), now);
+        assert_eq!(d.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(d.next_attempt_at, now + Duration::seconds(30));
+
+        record_attempt(&mut d, &policy, attempt(None), now);
+        assert_eq!(d.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(d.next_attempt_at, now + Duration::seconds(60));
+
+        record_attempt(&mut d, &policy, attempt(Some(429)), now);
+        assert_eq!(d.status, WebhookDeliveryStatus::DeadLettered);
+        assert_eq!(d.attempts.len(), 3);
+        assert!(d.dead_lettered_at.is_some());
+    }
+
+    #[test]
+    fn test_client_errors_dead_letter_at_once_and_success_delivers() {
+        let policy = WebhookRetryPolicy::default();
+        let mut rejected = delivery();
+        record_attempt(&mut rejected, &policy, attempt(Some(404)), Utc::now());
+        assert_eq!(rejected.status, WebhookDeliveryStatus::DeadLettered);
+
+        let mut delivered = delivery();
+        record_attempt(&mut delivered, &policy, attempt(Some(204)), Utc::now());
+        assert_eq!(delivered.status, WebhookDeliveryStatus::Delivered);
+        assert!(delivered.delivered_at.is_some());
+
+        let long = WebhookRetryPolicy {
+            max_attempts: 10,
+            backoff_seconds: 3600,
+        };
+        assert_eq!(backoff(&long, 9), Duration::seconds(MAX_BACKOFF_SECONDS as i64));
+    }
+
+    #[test]
+    fn test_subscription_tenant_scoping() {
+        let now = Utc::now();
+        let subscription = |tenant: Option<&str>| WebhookSubscription {
+            id: None,
+            name: "itsm".to_string(),
+            tenant_id: tenant.map(str::to_string),
+            url: "https://hooks.example.com/archer".to_string(),
+            event_types: vec![WebhookEventType::TicketCreated],
+            secret_encrypted: String::new(),
+            retry_policy: WebhookRetryPolicy::default(),
+            enabled: true,
+            created_by: "admin".to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let everyone = subscription(None);
+        assert!(everyone.receives(WebhookEventType::TicketCreated, Some("acme")));
+        assert!(everyone.receives(WebhookEventType::TicketCreated, None));
+        assert!(!everyone.receives(WebhookEventType::WaveStatusChanged, None));
+
+        let acme = subscription(Some("acme"));
+        assert!(acme.receives(WebhookEventType::TicketCreated, Some("acme")));
+        assert!(!acme.receives(WebhookEventType::TicketCreated, Some("globex")));
+        assert!(!acme.receives(WebhookEventType::TicketCreated, None));
+    }
+
+    #[test]
+    fn test_validation() {
+        let policy = WebhookRetryPolicy::default();
+        let events = [WebhookEventType::CapacityAlert];
+        assert!(validate_subscription("ops", "https://hooks.example.com/x", &events, &policy).is_ok());
+        assert!(validate_subscription("ops", "http://hooks.example.com/x", &events, &policy).is_err());
+        assert!(validate_subscription("ops", "https://hooks.example.com/x", &[], &policy).is_err());
+        let too_many = WebhookRetryPolicy {
+            max_attempts: MAX_ATTEMPTS + 1,
+            ..policy
+        };
+        assert!(validate_subscription("ops", "https://hooks.example.com/x", &events, &too_many).is_err());
+        assert!(validate_secret("short".to_string()).is_err());
+        assert_eq!(generate_secret().len(), 64);
+        assert_eq!(record_key("webhook_subscription:⟨a-b⟩", SUBSCRIPTION_TABLE), "a-b");
+    }
+}
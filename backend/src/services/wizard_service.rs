@@ -11,12 +11,14 @@ use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
-use surrealdb::{engine::local::Db, Surreal};
+use crate::database::Db;
+use surrealdb::Surreal;
 
 use crate::database::AppState;
 use crate::models::workflow::{
     Activity, ActivityStatus, ActivityType, MigrationMetadata, WizardState,
 };
+use crate::database::TracedQuery;
 
 /// Request to start a new wizard session
 #[derive(Debug, Serialize, Deserialize)]
@@ -291,7 +293,7 @@ impl WizardService {
             now.timestamp()
         );
 
-        let mut response = db.query(&query).await?;
+        let mut response = db.query(&query).traced("wizard_service.cleanup_expired_drafts").await?;
         let expired: Vec<Activity> = response.take(0)?;
 
         let mut deleted_count = 0;
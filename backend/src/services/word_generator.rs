@@ -1,3 +1,4 @@
+use crate::models::document_review::{DocumentApprovalRecord, ReviewerDecision};
 use crate::models::hld::{HLDProject, HLDVariable, VariableValue, SectionDefinition};
 use anyhow::{Result, Context};
 use docx_rs::*;
@@ -30,6 +31,18 @@ impl WordGenerator {
         project: &HLDProject,
         variables: &[HLDVariable],
         sections: &[SectionDefinition],
+    ) -> Result<Vec<u8>> {
+        self.generate_hld_with_approval(project, variables, sections, None)
+    }
+
+    /// Generate the HLD; a published version carries its sign-off on a
+    /// document approval page after the title page
+    pub fn generate_hld_with_approval(
+        &mut self,
+        project: &HLDProject,
+        variables: &[HLDVariable],
+        sections: &[SectionDefinition],
+        approval: Option<&DocumentApprovalRecord>,
     ) -> Result<Vec<u8>> {
         // Convert variables to lookup map
         let var_map: HashMap<String, String> = variables
@@ -44,6 +57,10 @@ impl WordGenerator {
         // Add title page
         self.add_title_page(project, &var_map)?;
 
+        if let Some(approval) = approval {
+            self.add_approval_page(approval)?;
+        }
+
         // Add table of contents placeholder
         self.add_table_of_contents()?;
 
@@ -134,6 +151,68 @@ impl WordGenerator {
         Ok(())
     }
 
+    /// Add the document approval (metadata) page of a published version
+    fn add_approval_page(&mut self, approval: &DocumentApprovalRecord) -> Result<()> {
+        self.document = self.document.clone().add_paragraph(
+            Paragraph::new()
+                .add_run(
+                    Run::new()
+                        .add_text("Document Approval")
+                        .size(32)
+                        .bold()
+                        .fonts(RunFonts::new().ascii("Poppins"))
+                )
+                .style("Heading1"),
+        );
+
+        for line in Self::approval_lines(approval) {
+            self.document = self.document.clone().add_paragraph(
+                Paragraph::new().add_run(Run::new().add_text(&line).size(20)),
+            );
+        }
+
+        // Page break
+        self.document = self.document.clone().add_paragraph(
+            Paragraph::new().add_run(Run::new().add_break(BreakType::Page)),
+        );
+
+        Ok(())
+    }
+
+    /// Text of the approval page: the reviewed version, then one line per reviewer
+    fn approval_lines(approval: &DocumentApprovalRecord) -> Vec<String> {
+        let mut lines = vec![
+            format!("Document: {} (version {})", approval.document_name, approval.document_version),
+            format!("Approved: {}", approval.approved_at.format("%Y-%m-%d %H:%M UTC")),
+            format!(
+                "Published: {} by {}",
+                approval.published_at.format("%Y-%m-%d %H:%M UTC"),
+                approval.published_by
+            ),
+            format!("Reviewed content SHA-256: {}", approval.reviewed_sha256),
+        ];
+        for reviewer in &approval.approvals {
+            let decision = match reviewer.decision {
+                ReviewerDecision::Approved => "Approved",
+                ReviewerDecision::ChangesRequested => "Changes requested",
+                ReviewerDecision::NotRequired => "Not required",
+                ReviewerDecision::Pending => "Pending",
+            };
+            let mut line = format!("{}: {}", reviewer.user_id, decision);
+            if let Some(delegate) = &reviewer.delegated_to {
+                line.push_str(&format!(" (delegated to {})", delegate));
+            }
+            if let Some(at) = reviewer.decided_at {
+                line.push_str(&format!(" on {}", at.format("%Y-%m-%d")));
+            }
+            if let Some(comments) = reviewer.comments.as_deref().filter(|c| !c.is_empty()) {
+                line.push_str(&format!(" - {}", comments));
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
     /// Add table of contents placeholder
     fn add_table_of_contents(&mut self) -> Result<()> {
         self.document = self.document.clone().add_paragraph(
@@ -445,6 +524,30 @@ impl WordGenerator {
         Ok(())
     }
 
+    /// Variables the title page reads
+    pub const TITLE_PAGE_VARIABLES: &'static [&'static str] = &["project_name", "customer_name"];
+
+    /// Variables a built-in section reads, so a regeneration can tell which
+    /// sections a variable change reached
+    pub fn section_variables(section_id: &str) -> &'static [&'static str] {
+        match section_id {
+            "executive_summary" => &["project_name", "node_count"],
+            "infrastructure_overview" => &["cluster_name", "node_count", "cpu_model", "ram_gb_per_host"],
+            "compute_design" => &[
+                "template_small_vcpus",
+                "template_small_ram_gb",
+                "template_medium_vcpus",
+                "template_medium_ram_gb",
+                "template_large_vcpus",
+                "template_large_ram_gb",
+            ],
+            "storage_design" => &["total_storage_tb_usable"],
+            "network_design" => &["mgmt_vlan_id", "cluster_vlan_id", "lm_vlan_id"],
+            "migration_strategy" => &["migration_approach", "management_framework", "backup_software"],
+            _ => &[],
+        }
+    }
+
     /// Format a variable value for display
     fn format_variable_value(value: &VariableValue) -> String {
         match value {
@@ -532,6 +635,7 @@ mod tests {
                 enabled: true,
                 order_index: 0,
                 depends_on: vec![],
+                sensitivity: Vec::new(),
                 created_at: Utc::now(),
             },
         ]
@@ -579,4 +683,41 @@ mod tests {
         let bytes = result.unwrap();
         assert!(!bytes.is_empty());
     }
+
+    #[test]
+    fn test_approval_lines() {
+        use crate::models::document_review::{DocumentApprovalRecord, ReviewerAssignment};
+
+        let approval = DocumentApprovalRecord {
+            document_name: "Project p1 HLD".to_string(),
+            document_version: 3,
+            reviewed_sha256: "abc123".to_string(),
+            approvals: vec![ReviewerAssignment {
+                user_id: "users:alice".to_string(),
+                decision: ReviewerDecision::Approved,
+                decided_at: Some(Utc::now()),
+                comments: Some("Looks good".to_string()),
+                delegated_to: None,
+            }],
+            approved_at: Utc::now(),
+            workflow_instance_id: None,
+            published_by: "bob".to_string(),
+            published_at: Utc::now(),
+        };
+
+        let lines = WordGenerator::approval_lines(&approval);
+        assert_eq!(lines[0], "Document: Project p1 HLD (version 3)");
+        assert!(lines.iter().any(|l| l.ends_with("abc123")));
+        assert!(lines.last().unwrap().starts_with("users:alice: Approved on "));
+        assert!(lines.last().unwrap().ends_with(" - Looks good"));
+
+        let mut generator = WordGenerator::new();
+        let result = generator.generate_hld_with_approval(
+            &create_test_project(),
+            &create_test_variables(),
+            &create_test_sections(),
+            Some(&approval),
+        );
+        assert!(result.is_ok());
+    }
 }
@@ -1,15 +1,43 @@
 // Archer ITSM - Workflow Engine Service
 // Core workflow execution engine with step handlers
 
-use chrono::Utc;
-use serde_json::json;
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
 use std::sync::Arc;
 use surrealdb::sql::Thing;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{error, info};
 
 use crate::{
     database::Database,
     models::workflow_engine::*,
+    services::approval_service::{ApprovalService, QuorumDecision},
+    services::workflow_steps::{self, StepRun},
 };
+use crate::database::TracedQuery;
+
+/// Steps executed per run before the instance is failed; guards against
+/// on_success/on_failure cycles
+const MAX_STEPS_PER_RUN: usize = 100;
+
+/// Identifies workflow export documents
+const EXPORT_FORMAT: &str = "archer.workflow";
+const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// What the engine does once a step has executed
+#[derive(Debug)]
+enum StepOutcome {
+    /// Follow on_success, or the next step in the definition
+    Continue,
+    /// Condition result: true follows on_success (or the next step), false
+    /// follows on_failure and completes the workflow when there is none
+    Branch(bool),
+    WaitApproval,
+    WaitUntil(DateTime<Utc>),
+    /// Follow on_failure, or fail the instance
+    Failed,
+}
 
 /// Workflow execution service
 pub struct WorkflowEngineService {
@@ -30,9 +58,13 @@ impl WorkflowEngineService {
         &self,
         req: CreateWorkflowRequest,
         created_by: String,
+        tenant_id: Option<String>,
     ) -> Result<WorkflowDefinition, String> {
         let now = Utc::now();
-        
+        if !req.draft {
+            validate_steps(&req.steps)?;
+        }
+
         let workflow = WorkflowDefinition {
             id: None,
             name: req.name,
@@ -44,6 +76,11 @@ impl WorkflowEngineService {
             created_by,
             created_at: now,
             updated_at: now,
+            workflow_key: Some(uuid::Uuid::new_v4().to_string()),
+            version: 1,
+            status: if req.draft { WorkflowVersionStatus::Draft } else { WorkflowVersionStatus::Published },
+            published_at: if req.draft { None } else { Some(now) },
+            tenant_id,
         };
 
         let created: Vec<WorkflowDefinition> = self.db
@@ -64,12 +101,18 @@ impl WorkflowEngineService {
             .map_err(|e| format!("Failed to get workflow: {}", e))
     }
 
-    /// List all workflow definitions
-    pub async fn list_workflows(&self) -> Result<Vec<WorkflowDefinition>, String> {
-        self.db
+    /// List workflow definitions (every version) visible to a tenant;
+    /// definitions without a tenant are shared
+    pub async fn list_workflows(&self, tenant_id: Option<&str>) -> Result<Vec<WorkflowDefinition>, String> {
+        let mut workflows: Vec<WorkflowDefinition> = self.db
             .select("workflow_definition")
             .await
-            .map_err(|e| format!("Failed to list workflows: {}", e))
+            .map_err(|e| format!("Failed to list workflows: {}", e))?;
+
+        if let Some(tenant) = tenant_id {
+            workflows.retain(|w| w.tenant_id.as_deref().is_none_or(|t| t == tenant));
+        }
+        Ok(workflows)
     }
 
     /// Update workflow definition
@@ -84,6 +127,15 @@ impl WorkflowEngineService {
             .map_err(|e| format!("Failed to fetch workflow: {}", e))?
             .ok_or_else(|| "Workflow not found".to_string())?;
 
+        if workflow.status != WorkflowVersionStatus::Draft
+            && (req.steps.is_some() || req.trigger_conditions.is_some())
+        {
+            return Err(format!(
+                "Version {} is {:?}; create a new version to change its steps or trigger conditions",
+                workflow.version, workflow.status
+            ));
+        }
+
         if let Some(name) = req.name {
             workflow.name = name;
         }
@@ -118,6 +170,253 @@ impl WorkflowEngineService {
         Ok(())
     }
 
+    // ========================================================================
+    // WORKFLOW VERSIONING
+    // ========================================================================
+
+    /// All versions sharing this workflow's key, oldest first
+    pub async fn list_versions(&self, id: Thing) -> Result<(String, Vec<WorkflowDefinition>), String> {
+        let mut workflow = self.load_workflow(&id).await?;
+        let key = self.ensure_workflow_key(&id, &mut workflow).await?;
+        let versions = self.versions_of(&key, workflow.tenant_id.as_deref()).await?;
+        Ok((key, versions))
+    }
+
+    /// Start a new draft version from an existing version's content
+    pub async fn create_version(&self, id: Thing, created_by: String) -> Result<WorkflowDefinition, String> {
+        let mut source = self.load_workflow(&id).await?;
+        let key = self.ensure_workflow_key(&id, &mut source).await?;
+        let latest = self
+            .versions_of(&key, source.tenant_id.as_deref())
+            .await?
+            .iter()
+            .map(|w| w.version)
+            .max()
+            .unwrap_or(source.version);
+
+        let now = Utc::now();
+        let draft = WorkflowDefinition {
+            id: None,
+            version: latest + 1,
+            status: WorkflowVersionStatus::Draft,
+            published_at: None,
+            created_by,
+            created_at: now,
+            updated_at: now,
+            ..source
+        };
+
+        let created: Vec<WorkflowDefinition> = self.db
+            .create("workflow_definition")
+            .content(&draft)
+            .await
+            .map_err(|e| format!("Failed to create workflow version: {}", e))?;
+
+        created.into_iter().next()
+            .ok_or_else(|| "Failed to create workflow version".to_string())
+    }
+
+    /// Publish a draft (or roll back to an archived version). The previously
+    /// published version is archived; its in-flight instances either finish
+    /// on it or move to this version, per `req.in_flight`.
+    pub async fn publish_workflow(
+        &self,
+        id: Thing,
+        req: PublishWorkflowRequest,
+    ) -> Result<PublishWorkflowResponse, String> {
+        let mut workflow = self.load_workflow(&id).await?;
+        if workflow.status == WorkflowVersionStatus::Published {
+            return Err(format!("Version {} is already published", workflow.version));
+        }
+        validate_steps(&workflow.steps)?;
+
+        let key = self.ensure_workflow_key(&id, &mut workflow).await?;
+        let now = Utc::now();
+        let others: Vec<WorkflowDefinition> = self
+            .versions_of(&key, workflow.tenant_id.as_deref())
+            .await?
+            .into_iter()
+            .filter(|w| w.id.as_ref() != Some(&id))
+            .collect();
+
+        let mut archived_versions = Vec::new();
+        for mut previous in others.iter().filter(|w| w.status == WorkflowVersionStatus::Published).cloned() {
+            let Some(previous_id) = previous.id.clone() else { continue };
+            previous.status = WorkflowVersionStatus::Archived;
+            previous.updated_at = now;
+            let _: Option<WorkflowDefinition> = self.db
+                .update(previous_id)
+                .content(&previous)
+                .await
+                .map_err(|e| format!("Failed to archive version {}: {}", previous.version, e))?;
+            archived_versions.push(previous.version);
+        }
+
+        workflow.status = WorkflowVersionStatus::Published;
+        workflow.published_at = Some(now);
+        workflow.updated_at = now;
+        let workflow: WorkflowDefinition = self.db
+            .update(id.clone())
+            .content(&workflow)
+            .await
+            .map_err(|e| format!("Failed to publish workflow: {}", e))?
+            .ok_or_else(|| "Failed to publish workflow".to_string())?;
+
+        // Instances still running on any other version of this workflow
+        let other_ids: Vec<Thing> = others.iter().filter_map(|w| w.id.clone()).collect();
+        let mut result = self.db
+            .query(
+                "SELECT * FROM workflow_instance WHERE workflow_id INSIDE $versions \
+                 AND status INSIDE ['RUNNING', 'WAITING_APPROVAL', 'WAITING_TIMER']",
+            )
+            .bind(("versions", other_ids))
+            .traced("workflow_engine_service.publish_workflow")
+            .await
+            .map_err(|e| format!("Failed to fetch in-flight instances: {}", e))?;
+        let in_flight: Vec<WorkflowInstance> = result.take(0)
+            .map_err(|e| format!("Failed to parse in-flight instances: {}", e))?;
+
+        let mut migrated_instances = Vec::new();
+        let mut unmigrated_instances = Vec::new();
+        for instance in in_flight {
+            let Some(instance_id) = instance.id.clone() else { continue };
+            let blocker = match req.in_flight {
+                InFlightPolicy::FinishOnOld => Some("finishing on its original version".to_string()),
+                InFlightPolicy::Migrate => migration_blocker(&instance, &workflow),
+            };
+            if let Some(reason) = blocker {
+                unmigrated_instances.push(UnmigratedInstance {
+                    instance_id: instance_id.to_string(),
+                    from_version: instance.workflow_version,
+                    reason,
+                });
+                continue;
+            }
+
+            self.db
+                .query("UPDATE $instance SET workflow_id = $workflow, workflow_version = $version")
+                .bind(("instance", instance_id.clone()))
+                .bind(("workflow", id.clone()))
+                .bind(("version", workflow.version))
+                .traced("workflow_engine_service.publish_workflow")
+                .await
+                .map_err(|e| format!("Failed to migrate instance {}: {}", instance_id, e))?;
+            migrated_instances.push(instance_id.to_string());
+        }
+
+        Ok(PublishWorkflowResponse {
+            workflow,
+            archived_versions,
+            in_flight: req.in_flight,
+            migrated_instances,
+            unmigrated_instances,
+        })
+    }
+
+    /// Archive a version so it can no longer be triggered or edited
+    pub async fn archive_workflow(&self, id: Thing) -> Result<WorkflowDefinition, String> {
+        let mut workflow = self.load_workflow(&id).await?;
+        workflow.status = WorkflowVersionStatus::Archived;
+        workflow.updated_at = Utc::now();
+
+        self.db
+            .update(id)
+            .content(&workflow)
+            .await
+            .map_err(|e| format!("Failed to archive workflow: {}", e))?
+            .ok_or_else(|| "Failed to archive workflow".to_string())
+    }
+
+    /// Export a version as a portable JSON document
+    pub async fn export_workflow(&self, id: Thing) -> Result<WorkflowExport, String> {
+        let mut workflow = self.load_workflow(&id).await?;
+        self.ensure_workflow_key(&id, &mut workflow).await?;
+        Ok(export_document(&workflow, Utc::now()))
+    }
+
+    /// Import an exported workflow as a draft. When the tenant already has the
+    /// same workflow (matching key), the import becomes its next version.
+    pub async fn import_workflow(
+        &self,
+        export: WorkflowExport,
+        created_by: String,
+        tenant_id: Option<String>,
+    ) -> Result<WorkflowDefinition, String> {
+        check_export(&export)?;
+        let exported = export.workflow;
+
+        let key = exported.workflow_key.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let existing = self.versions_of(&key, tenant_id.as_deref()).await?;
+        let version = existing.iter().map(|w| w.version + 1).max().unwrap_or(1);
+
+        let now = Utc::now();
+        let draft = WorkflowDefinition {
+            id: None,
+            name: exported.name,
+            description: exported.description,
+            trigger_type: exported.trigger_type,
+            trigger_conditions: exported.trigger_conditions,
+            steps: exported.steps,
+            is_active: true,
+            created_by,
+            created_at: now,
+            updated_at: now,
+            workflow_key: Some(key),
+            version,
+            status: WorkflowVersionStatus::Draft,
+            published_at: None,
+            tenant_id,
+        };
+
+        let created: Vec<WorkflowDefinition> = self.db
+            .create("workflow_definition")
+            .content(&draft)
+            .await
+            .map_err(|e| format!("Failed to import workflow: {}", e))?;
+
+        created.into_iter().next()
+            .ok_or_else(|| "Failed to import workflow".to_string())
+    }
+
+    async fn load_workflow(&self, id: &Thing) -> Result<WorkflowDefinition, String> {
+        self.db
+            .select(id.clone())
+            .await
+            .map_err(|e| format!("Failed to fetch workflow: {}", e))?
+            .ok_or_else(|| "Workflow not found".to_string())
+    }
+
+    /// Versions of a workflow within one tenant, oldest first
+    async fn versions_of(&self, key: &str, tenant_id: Option<&str>) -> Result<Vec<WorkflowDefinition>, String> {
+        let mut result = self.db
+            .query("SELECT * FROM workflow_definition WHERE workflow_key = $key ORDER BY version ASC")
+            .bind(("key", key.to_string()))
+            .traced("workflow_engine_service.versions_of")
+            .await
+            .map_err(|e| format!("Failed to fetch workflow versions: {}", e))?;
+        let mut versions: Vec<WorkflowDefinition> = result.take(0)
+            .map_err(|e| format!("Failed to parse workflow versions: {}", e))?;
+
+        versions.retain(|w| w.tenant_id.as_deref() == tenant_id);
+        Ok(versions)
+    }
+
+    /// Definitions created before versioning have no key; adopt their record id
+    async fn ensure_workflow_key(&self, id: &Thing, workflow: &mut WorkflowDefinition) -> Result<String, String> {
+        if let Some(key) = &workflow.workflow_key {
+            return Ok(key.clone());
+        }
+
+        let key = id.id.to_string();
+        workflow.workflow_key = Some(key.clone());
+        let _: Option<WorkflowDefinition> = self.db
+            .update(id.clone())
+            .content(&*workflow)
+            .await
+            .map_err(|e| format!("Failed to update workflow: {}", e))?;
+        Ok(key)
+    }
+
     // ========================================================================
     // WORKFLOW INSTANCE MANAGEMENT
     // ========================================================================
@@ -139,11 +438,23 @@ impl WorkflowEngineService {
         if !workflow.is_active {
             return Err("Workflow is not active".to_string());
         }
+        if workflow.status != WorkflowVersionStatus::Published {
+            return Err(format!("Workflow version {} is not published", workflow.version));
+        }
+
+        // Step handlers read the trigger record from the context
+        let mut context = match context {
+            Some(Value::Object(map)) => Value::Object(map),
+            _ => json!({}),
+        };
+        context["trigger_record_type"] = json!(trigger_record_type);
+        context["trigger_record_id"] = json!(trigger_record_id.to_string());
 
         let now = Utc::now();
         let instance = WorkflowInstance {
             id: None,
             workflow_id,
+            workflow_version: Some(workflow.version),
             trigger_record_type,
             trigger_record_id,
             status: WorkflowInstanceStatus::Running,
@@ -151,7 +462,8 @@ impl WorkflowEngineService {
             step_history: vec![],
             started_at: now,
             completed_at: None,
-            context: context.unwrap_or(json!({})),
+            context,
+            resume_at: None,
         };
 
         let created_instance: Vec<WorkflowInstance> = self.db
@@ -163,8 +475,9 @@ impl WorkflowEngineService {
         let created_instance = created_instance.into_iter().next()
             .ok_or_else(|| "Failed to create workflow instance".to_string())?;
 
-        // Start executing the workflow asynchronously
-        // In a real implementation, this would be done in a background task
+        if let Some(id) = created_instance.id.clone() {
+            self.spawn_run(id);
+        }
         Ok(created_instance)
     }
 
@@ -209,30 +522,197 @@ impl WorkflowEngineService {
     }
 
     // ========================================================================
-    // STEP EXECUTION
+    // WORKFLOW EXECUTION
     // ========================================================================
 
+    /// Run an instance in the background from its current step
+    fn spawn_run(&self, instance_id: Thing) {
+        let engine = WorkflowEngineService::new(Arc::clone(&self.db));
+        tokio::spawn(async move {
+            if let Err(e) = engine.run_instance(instance_id.clone()).await {
+                error!("Workflow instance {} failed to run: {}", instance_id, e);
+            }
+        });
+    }
+
+    /// Execute steps from `current_step_id` until the workflow completes,
+    /// fails or has to wait for an approval or timer. The instance is saved
+    /// after every step so its step history doubles as the execution log.
+    pub async fn run_instance(&self, instance_id: Thing) -> Result<WorkflowInstance, String> {
+        let mut instance = self.load_instance(&instance_id).await?;
+        let workflow: WorkflowDefinition = self.db
+            .select(instance.workflow_id.clone())
+            .await
+            .map_err(|e| format!("Failed to fetch workflow: {}", e))?
+            .ok_or_else(|| "Workflow not found".to_string())?;
+        let record = self.load_trigger_record(&instance.trigger_record_id).await?;
+
+        for _ in 0..MAX_STEPS_PER_RUN {
+            if instance.status != WorkflowInstanceStatus::Running {
+                return Ok(instance);
+            }
+
+            let Some(step_id) = instance.current_step_id.clone() else {
+                instance.status = WorkflowInstanceStatus::Completed;
+                instance.completed_at = Some(Utc::now());
+                return self.save_instance(&instance_id, &instance).await;
+            };
+            let Some(step) = workflow.steps.iter().find(|s| s.step_id == step_id) else {
+                instance.status = WorkflowInstanceStatus::Failed;
+                instance.completed_at = Some(Utc::now());
+                instance.context["error"] = json!(format!("Step '{}' not found in workflow", step_id));
+                return self.save_instance(&instance_id, &instance).await;
+            };
+
+            // Cancellation happens through the API while we run
+            let latest = self.load_instance(&instance_id).await?;
+            if latest.status == WorkflowInstanceStatus::Cancelled {
+                return Ok(latest);
+            }
+
+            let scope = json!({
+                "record": record,
+                "context": instance.context,
+                "instance": {
+                    "id": instance_id.to_string(),
+                    "workflow_id": instance.workflow_id.to_string(),
+                    "workflow_name": workflow.name,
+                    "trigger_record_type": instance.trigger_record_type,
+                    "trigger_record_id": instance.trigger_record_id.to_string(),
+                },
+            });
+            let (execution, outcome) = self
+                .execute_step(instance_id.clone(), step, &scope, &mut instance.context)
+                .await;
+            if let Some(result) = &execution.result {
+                record_step_result(&mut instance.context, &step.step_id, result.clone());
+            }
+            instance.step_history.push(execution);
+
+            match outcome {
+                StepOutcome::Continue | StepOutcome::Branch(true) => {
+                    instance.current_step_id = next_step(&workflow, step);
+                }
+                StepOutcome::Branch(false) => {
+                    instance.current_step_id = step.on_failure.clone();
+                }
+                StepOutcome::WaitApproval => {
+                    instance.status = WorkflowInstanceStatus::WaitingApproval;
+                }
+                StepOutcome::WaitUntil(resume_at) => {
+                    instance.current_step_id = next_step(&workflow, step);
+                    instance.status = WorkflowInstanceStatus::WaitingTimer;
+                    instance.resume_at = Some(resume_at);
+                }
+                StepOutcome::Failed => match step.on_failure.clone() {
+                    Some(fallback) => instance.current_step_id = Some(fallback),
+                    None => {
+                        instance.status = WorkflowInstanceStatus::Failed;
+                        instance.completed_at = Some(Utc::now());
+                    }
+                },
+            }
+            instance = self.save_instance(&instance_id, &instance).await?;
+        }
+
+        instance.status = WorkflowInstanceStatus::Failed;
+        instance.completed_at = Some(Utc::now());
+        instance.context["error"] = json!(format!("Exceeded {} steps in one run", MAX_STEPS_PER_RUN));
+        self.save_instance(&instance_id, &instance).await
+    }
+
+    /// Resume instances whose delay step has elapsed
+    pub async fn resume_due_timers(&self) -> Result<usize, String> {
+        let mut result = self.db
+            .query("SELECT * FROM workflow_instance WHERE status = 'WAITING_TIMER' AND resume_at <= $now")
+            .bind(("now", Utc::now()))
+            .traced("workflow_engine_service.resume_due_timers")
+            .await
+            .map_err(|e| format!("Failed to fetch waiting instances: {}", e))?;
+        let due: Vec<WorkflowInstance> = result.take(0)
+            .map_err(|e| format!("Failed to parse waiting instances: {}", e))?;
+
+        let mut resumed = 0;
+        for instance in due {
+            let Some(id) = instance.id else { continue };
+
+            // Claim the instance so overlapping polls don't run it twice
+            let mut claim = self.db
+                .query("UPDATE $id SET status = 'RUNNING', resume_at = NONE WHERE status = 'WAITING_TIMER'")
+                .bind(("id", id.clone()))
+                .traced("workflow_engine_service.resume_due_timers")
+                .await
+                .map_err(|e| format!("Failed to claim workflow instance: {}", e))?;
+            let claimed: Vec<WorkflowInstance> = claim.take(0)
+                .map_err(|e| format!("Failed to parse workflow instance: {}", e))?;
+            if claimed.is_empty() {
+                continue;
+            }
+
+            match self.run_instance(id.clone()).await {
+                Ok(_) => resumed += 1,
+                Err(e) => error!("Workflow instance {} failed after timer: {}", id, e),
+            }
+        }
+        Ok(resumed)
+    }
+
+    async fn load_instance(&self, id: &Thing) -> Result<WorkflowInstance, String> {
+        self.db
+            .select(id.clone())
+            .await
+            .map_err(|e| format!("Failed to fetch workflow instance: {}", e))?
+            .ok_or_else(|| "Workflow instance not found".to_string())
+    }
+
+    async fn save_instance(&self, id: &Thing, instance: &WorkflowInstance) -> Result<WorkflowInstance, String> {
+        self.db
+            .update(id.clone())
+            .content(instance)
+            .await
+            .map_err(|e| format!("Failed to update workflow instance: {}", e))?
+            .ok_or_else(|| "Failed to update workflow instance".to_string())
+    }
+
+    /// The record that triggered the workflow, as seen by conditions, templates and scripts
+    async fn load_trigger_record(&self, record_id: &Thing) -> Result<Value, String> {
+        let mut result = self.db
+            .query("SELECT * FROM $record")
+            .bind(("record", record_id.clone()))
+            .traced("workflow_engine_service.load_trigger_record")
+            .await
+            .map_err(|e| format!("Failed to fetch trigger record: {}", e))?;
+        let record: Option<Value> = result.take(0)
+            .map_err(|e| format!("Failed to parse trigger record: {}", e))?;
+        Ok(record.unwrap_or(Value::Null))
+    }
+
     /// Execute a workflow step
     async fn execute_step(
         &self,
         instance_id: Thing,
         step: &WorkflowStep,
+        scope: &Value,
         context: &mut serde_json::Value,
-    ) -> Result<StepExecution, String> {
-        let now = Utc::now();
+    ) -> (StepExecution, StepOutcome) {
         let mut execution = StepExecution {
             step_id: step.step_id.clone(),
             step_name: step.name.clone(),
             status: StepExecutionStatus::Running,
-            started_at: now,
+            started_at: Utc::now(),
             completed_at: None,
             result: None,
             error_message: None,
+            attempts: 1,
+            logs: vec![],
         };
+        let mut run = StepRun::default();
+        let mut outcome = StepOutcome::Continue;
 
         // Execute based on step type
         let result = match step.step_type {
             WorkflowStepType::Approval => {
+                outcome = StepOutcome::WaitApproval;
                 self.handle_approval_step(instance_id.clone(), step, context).await
             }
             WorkflowStepType::Notification => {
@@ -248,87 +728,58 @@ impl WorkflowEngineService {
                 self.handle_create_record_step(step, context).await
             }
             WorkflowStepType::Condition => {
-                self.handle_condition_step(step, context).await
+                self.handle_condition_step(step, scope).map(|(matched, result)| {
+                    outcome = StepOutcome::Branch(matched);
+                    result
+                })
             }
             WorkflowStepType::Delay => {
-                self.handle_delay_step(step).await
+                self.handle_delay_step(step, scope).map(|(resume_at, result)| {
+                    outcome = StepOutcome::WaitUntil(resume_at);
+                    result
+                })
             }
             WorkflowStepType::HttpCall => {
-                Err("HTTP calls not implemented yet".to_string())
+                workflow_steps::call_webhook(&step.config, scope, &mut run).await
+            }
+            WorkflowStepType::Script => {
+                self.handle_script_step(step, scope, context, &mut run)
             }
         };
 
         match result {
             Ok(result_data) => {
                 execution.status = StepExecutionStatus::Completed;
-                execution.completed_at = Some(Utc::now());
                 execution.result = Some(result_data);
             }
             Err(e) => {
+                run.error(e.clone());
                 execution.status = StepExecutionStatus::Failed;
-                execution.completed_at = Some(Utc::now());
                 execution.error_message = Some(e);
+                outcome = StepOutcome::Failed;
             }
         }
+        execution.completed_at = Some(Utc::now());
+        execution.attempts = run.attempts.max(1);
+        execution.logs = run.logs;
 
-        Ok(execution)
+        (execution, outcome)
     }
 
     // ========================================================================
     // STEP HANDLERS
     // ========================================================================
 
-    /// Handle approval step - creates approval record and waits
+    /// Handle approval step - creates the approval request and waits
     async fn handle_approval_step(
         &self,
         instance_id: Thing,
         step: &WorkflowStep,
-        _context: &serde_json::Value,
+        context: &serde_json::Value,
     ) -> Result<serde_json::Value, String> {
-        // Extract approver configuration
-        let approver_id = step.config
-            .get("approver_id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| "Approver ID not specified in step config".to_string())?;
-
-        let approver_type_str = step.config
-            .get("approver_type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("USER");
-
-        let approver_type = match approver_type_str {
-            "ROLE" => ApproverType::Role,
-            "GROUP" => ApproverType::Group,
-            _ => ApproverType::User,
-        };
-
-        // Parse approver ID as Thing
-        let approver_thing: Thing = approver_id.parse()
-            .map_err(|_| format!("Invalid approver ID format: {}", approver_id))?;
-
-        // Create approval record
-        let approval = Approval {
-            id: None,
-            workflow_instance_id: instance_id,
-            step_id: step.step_id.clone(),
-            approver_id: approver_thing,
-            approver_type,
-            status: ApprovalStatus::Pending,
-            requested_at: Utc::now(),
-            responded_at: None,
-            comments: None,
-        };
-
-        let created: Vec<Approval> = self.db
-            .create("approval")
-            .content(&approval)
+        ApprovalService::new(Arc::clone(&self.db))
+            .create_request(instance_id, step, context)
             .await
-            .map_err(|e| format!("Failed to create approval: {}", e))?;
-
-        let _ = created.into_iter().next()
-            .ok_or_else(|| "Failed to create approval".to_string())?;
-
-        Ok(json!({ "status": "approval_requested" }))
     }
 
     /// Handle notification step - logs to console for now
@@ -392,6 +843,7 @@ impl WorkflowEngineService {
             .query(query)
             .bind(("record_id", record_thing))
             .bind(("updates", updates))
+            .traced("workflow_engine_service.handle_field_update_step")
             .await
             .map_err(|e| format!("Failed to update record: {}", e))?;
 
@@ -428,6 +880,7 @@ impl WorkflowEngineService {
             .query(query)
             .bind(("record_id", record_thing))
             .bind(("assignee", assignee))
+            .traced("workflow_engine_service.handle_assignment_step")
             .await
             .map_err(|e| format!("Failed to assign record: {}", e))?;
 
@@ -459,6 +912,7 @@ impl WorkflowEngineService {
         let mut result = self.db
             .query(query)
             .bind(("data", record_data))
+            .traced("workflow_engine_service.handle_create_record_step")
             .await
             .map_err(|e| format!("Failed to create record: {}", e))?;
 
@@ -472,55 +926,52 @@ impl WorkflowEngineService {
         }))
     }
 
-    /// Handle condition step - evaluates condition and returns next step
-    async fn handle_condition_step(
+    /// Handle condition step - evaluates record fields and picks a branch
+    fn handle_condition_step(
         &self,
         step: &WorkflowStep,
-        context: &serde_json::Value,
-    ) -> Result<serde_json::Value, String> {
-        let condition = step.config
-            .get("condition")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| "Condition not specified in step config".to_string())?;
+        scope: &Value,
+    ) -> Result<(bool, serde_json::Value), String> {
+        let matched = workflow_steps::evaluate_condition(&step.config, scope)?;
 
-        // Simple condition evaluation (in production, use a proper expression evaluator)
-        let result = self.evaluate_condition(condition, context)?;
-
-        Ok(json!({
+        Ok((matched, json!({
             "status": "condition_evaluated",
-            "result": result,
-            "next_step": if result { step.on_success.clone() } else { step.on_failure.clone() }
-        }))
+            "result": matched,
+            "next_step": if matched { step.on_success.clone() } else { step.on_failure.clone() }
+        })))
     }
 
-    /// Handle delay step - waits for specified duration
-    async fn handle_delay_step(
+    /// Handle delay step - the instance waits until the timer poller resumes it
+    fn handle_delay_step(
         &self,
         step: &WorkflowStep,
-    ) -> Result<serde_json::Value, String> {
-        let delay_minutes = step.config
-            .get("delay_minutes")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as u64;
+        scope: &Value,
+    ) -> Result<(DateTime<Utc>, serde_json::Value), String> {
+        let resume_at = workflow_steps::resolve_delay(&step.config, scope, Utc::now())?;
 
-        // In production, this would schedule the workflow to resume after delay
-        println!("[WORKFLOW DELAY] Delaying for {} minutes", delay_minutes);
-
-        Ok(json!({
+        Ok((resume_at, json!({
             "status": "delay_started",
-            "delay_minutes": delay_minutes
-        }))
+            "resume_at": resume_at
+        })))
     }
 
-    /// Simple condition evaluator (placeholder)
-    fn evaluate_condition(
+    /// Handle script step - runs Rhai with the record and context in scope
+    fn handle_script_step(
         &self,
-        _condition: &str,
-        _context: &serde_json::Value,
-    ) -> Result<bool, String> {
-        // TODO: Implement proper condition evaluation
-        // For now, just return true
-        Ok(true)
+        step: &WorkflowStep,
+        scope: &Value,
+        context: &mut serde_json::Value,
+        run: &mut StepRun,
+    ) -> Result<serde_json::Value, String> {
+        let (output, updated_context) = workflow_steps::run_script(&step.config, scope, run)?;
+        if updated_context.is_object() {
+            *context = updated_context;
+        }
+
+        Ok(json!({
+            "status": "script_completed",
+            "output": output
+        }))
     }
 
     // ========================================================================
@@ -543,6 +994,7 @@ impl WorkflowEngineService {
         let mut result = self.db
             .query(query)
             .bind(("user_id", user_thing))
+            .traced("workflow_engine_service.get_pending_approvals")
             .await
             .map_err(|e| format!("Failed to fetch approvals: {}", e))?;
 
@@ -567,81 +1019,51 @@ impl WorkflowEngineService {
         Ok(enriched_approvals)
     }
 
-    /// Approve an approval request
+    /// Approve an approval request; the workflow resumes once the request's quorum is met
     pub async fn approve_approval(
         &self,
         approval_id: Thing,
+        responder_id: &str,
         comments: Option<String>,
     ) -> Result<Approval, String> {
-        let mut approval: Approval = self.db
-            .select(approval_id.clone())
+        self.respond_to_approval(approval_id, responder_id, ApprovalDecision::Approve, comments)
             .await
-            .map_err(|e| format!("Failed to fetch approval: {}", e))?
-            .ok_or_else(|| "Approval not found".to_string())?;
-
-        if approval.status != ApprovalStatus::Pending {
-            return Err("Approval is not in pending state".to_string());
-        }
-
-        approval.status = ApprovalStatus::Approved;
-        approval.responded_at = Some(Utc::now());
-        approval.comments = comments;
-
-        let updated: Approval = self.db
-            .update(approval_id)
-            .content(&approval)
-            .await
-            .map_err(|e| format!("Failed to update approval: {}", e))?
-            .ok_or_else(|| "Failed to update approval".to_string())?;
-
-        // Resume workflow execution
-        self.resume_workflow_after_approval(updated.workflow_instance_id.clone()).await?;
-
-        Ok(updated)
     }
 
-    /// Reject an approval request
+    /// Reject an approval request; the workflow fails once the quorum can no longer be met
     pub async fn reject_approval(
         &self,
         approval_id: Thing,
+        responder_id: &str,
         comments: Option<String>,
     ) -> Result<Approval, String> {
-        let mut approval: Approval = self.db
-            .select(approval_id.clone())
-            .await
-            .map_err(|e| format!("Failed to fetch approval: {}", e))?
-            .ok_or_else(|| "Approval not found".to_string())?;
-
-        if approval.status != ApprovalStatus::Pending {
-            return Err("Approval is not in pending state".to_string());
-        }
-
-        approval.status = ApprovalStatus::Rejected;
-        approval.responded_at = Some(Utc::now());
-        approval.comments = comments;
-
-        let updated: Approval = self.db
-            .update(approval_id)
-            .content(&approval)
-            .await
-            .map_err(|e| format!("Failed to update approval: {}", e))?
-            .ok_or_else(|| "Failed to update approval".to_string())?;
-
-        // Mark workflow as failed
-        let mut instance: WorkflowInstance = self.db
-            .select(updated.workflow_instance_id.clone())
+        self.respond_to_approval(approval_id, responder_id, ApprovalDecision::Reject, comments)
             .await
-            .map_err(|e| format!("Failed to fetch workflow instance: {}", e))?
-            .ok_or_else(|| "Workflow instance not found".to_string())?;
+    }
 
-        instance.status = WorkflowInstanceStatus::Failed;
-        instance.completed_at = Some(Utc::now());
+    async fn respond_to_approval(
+        &self,
+        approval_id: Thing,
+        responder_id: &str,
+        decision: ApprovalDecision,
+        comments: Option<String>,
+    ) -> Result<Approval, String> {
+        let (updated, outcome) = ApprovalService::new(Arc::clone(&self.db))
+            .respond(approval_id, responder_id, decision, comments)
+            .await?;
 
-        let _: Option<WorkflowInstance> = self.db
-            .update(updated.workflow_instance_id.clone())
-            .content(&instance)
-            .await
-            .map_err(|e| format!("Failed to update workflow instance: {}", e))?;
+        match outcome {
+            QuorumDecision::Approved => {
+                self.resume_workflow_after_approval(updated.workflow_instance_id.clone()).await?;
+            }
+            QuorumDecision::Rejected => {
+                let mut instance = self.load_instance(&updated.workflow_instance_id).await?;
+                instance.status = WorkflowInstanceStatus::Failed;
+                instance.completed_at = Some(Utc::now());
+                self.save_instance(&updated.workflow_instance_id, &instance).await?;
+            }
+            QuorumDecision::Pending => {}
+        }
 
         Ok(updated)
     }
@@ -658,15 +1080,283 @@ impl WorkflowEngineService {
             return Ok(()); // Already processed
         }
 
+        let workflow: WorkflowDefinition = self.db
+            .select(instance.workflow_id.clone())
+            .await
+            .map_err(|e| format!("Failed to fetch workflow: {}", e))?
+            .ok_or_else(|| "Workflow not found".to_string())?;
+
+        // Move past the approval step that was waiting
+        instance.current_step_id = instance
+            .current_step_id
+            .as_deref()
+            .and_then(|id| workflow.steps.iter().find(|s| s.step_id == id))
+            .and_then(|step| next_step(&workflow, step));
         instance.status = WorkflowInstanceStatus::Running;
 
-        let _: Option<WorkflowInstance> = self.db
-            .update(instance_id)
-            .content(&instance)
+        self.save_instance(&instance_id, &instance).await?;
+        self.spawn_run(instance_id);
+        Ok(())
+    }
+}
+
+/// Step ids must be unique and every on_success/on_failure must point at one
+fn validate_steps(steps: &[WorkflowStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("Workflow has no steps".to_string());
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for step in steps {
+        if step.step_id.trim().is_empty() {
+            return Err(format!("Step '{}' has an empty step_id", step.name));
+        }
+        if !seen.insert(step.step_id.as_str()) {
+            return Err(format!("Duplicate step_id '{}'", step.step_id));
+        }
+    }
+    for step in steps {
+        for target in [&step.on_success, &step.on_failure].into_iter().flatten() {
+            if !seen.contains(target.as_str()) {
+                return Err(format!("Step '{}' points to unknown step '{}'", step.step_id, target));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn export_document(workflow: &WorkflowDefinition, exported_at: DateTime<Utc>) -> WorkflowExport {
+    WorkflowExport {
+        format: EXPORT_FORMAT.to_string(),
+        format_version: EXPORT_FORMAT_VERSION,
+        exported_at,
+        workflow: ExportedWorkflow {
+            workflow_key: workflow.workflow_key.clone(),
+            name: workflow.name.clone(),
+            description: workflow.description.clone(),
+            version: workflow.version,
+            trigger_type: workflow.trigger_type.clone(),
+            trigger_conditions: workflow.trigger_conditions.clone(),
+            steps: workflow.steps.clone(),
+        },
+    }
+}
+
+fn check_export(export: &WorkflowExport) -> Result<(), String> {
+    if export.format != EXPORT_FORMAT {
+        return Err(format!("Not a workflow export (format '{}')", export.format));
+    }
+    if export.format_version > EXPORT_FORMAT_VERSION {
+        return Err(format!(
+            "Export format version {} is newer than supported version {}",
+            export.format_version, EXPORT_FORMAT_VERSION
+        ));
+    }
+    validate_steps(&export.workflow.steps)
+}
+
+/// Why an in-flight instance can't move to `target`, if it can't
+fn migration_blocker(instance: &WorkflowInstance, target: &WorkflowDefinition) -> Option<String> {
+    let step_id = instance.current_step_id.as_deref()?;
+    if target.steps.iter().any(|s| s.step_id == step_id) {
+        None
+    } else {
+        Some(format!("current step '{}' does not exist in version {}", step_id, target.version))
+    }
+}
+
+/// Step to run after `step` succeeds: on_success, else the next step in order
+fn next_step(workflow: &WorkflowDefinition, step: &WorkflowStep) -> Option<String> {
+    step.on_success.clone().or_else(|| {
+        let position = workflow.steps.iter().position(|s| s.step_id == step.step_id)?;
+        workflow.steps.get(position + 1).map(|s| s.step_id.clone())
+    })
+}
+
+/// Keep each step's result under `context.steps.<step_id>` for later templates
+fn record_step_result(context: &mut Value, step_id: &str, result: Value) {
+    if !context.is_object() {
+        *context = json!({});
+    }
+    if !context.get("steps").is_some_and(|v| v.is_object()) {
+        context["steps"] = json!({});
+    }
+    context["steps"][step_id] = result;
+}
+
+// ============================================================================
+// TIMER SCHEDULER
+// ============================================================================
+
+/// Lease on one timer poll; shorter than the 30 second tick
+const TIMER_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(25);
+
+/// Polls for workflow instances whose delay step has elapsed and for
+/// approval reminders/escalations that are due
+pub struct WorkflowTimerScheduler {
+    db: Arc<Database>,
+}
+
+impl WorkflowTimerScheduler {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let scheduler = JobScheduler::new()
+            .await
+            .map_err(|e| anyhow!("Failed to create scheduler: {}", e))?;
+
+        let db = Arc::clone(&self.db);
+        let engine = Arc::new(WorkflowEngineService::new(Arc::clone(&self.db)));
+        let approvals = Arc::new(ApprovalService::new(Arc::clone(&self.db)));
+        let job = Job::new_async("*/30 * * * * *", move |_uuid, _lock| {
+            let db = Arc::clone(&db);
+            let engine = Arc::clone(&engine);
+            let approvals = Arc::clone(&approvals);
+            Box::pin(async move {
+                // One replica per tick, so delays resume and escalations fire once
+                let poll = async {
+                    match engine.resume_due_timers().await {
+                        Ok(0) => {}
+                        Ok(count) => info!("⏱️ Resumed {} workflow instance(s) after delay", count),
+                        Err(e) => error!("❌ Workflow timer poll failed: {}", e),
+                    }
+                    match approvals.process_timers().await {
+                        Ok((0, 0)) => {}
+                        Ok((reminded, escalated)) => {
+                            info!("⏱️ Sent {} approval reminder(s), escalated {} approval(s)", reminded, escalated)
+                        }
+                        Err(e) => error!("❌ Approval timer poll failed: {}", e),
+                    }
+                };
+                if let Err(e) = locks::run_exclusive(&db, locks::WORKFLOW_TIMERS_LOCK, TIMER_LOCK_TTL, poll).await {
+                    error!("❌ Workflow timer lock failed: {}", e);
+                }
+            })
+        })
+        .map_err(|e| anyhow!("Failed to create workflow timer job: {}", e))?;
+
+        scheduler
+            .add(job)
+            .await
+            .map_err(|e| anyhow!("Failed to add workflow timer job: {}", e))?;
+        scheduler
+            .start()
             .await
-            .map_err(|e| format!("Failed to update workflow instance: {}", e))?;
+            .map_err(|e| anyhow!("Failed to start scheduler: {}", e))?;
 
-        // Continue execution (in production, this would be done asynchronously)
+        std::mem::forget(scheduler);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, on_success: Option<&str>) -> WorkflowStep {
+        WorkflowStep {
+            step_id: id.to_string(),
+            name: id.to_string(),
+            step_type: WorkflowStepType::Notification,
+            config: json!({}),
+            on_success: on_success.map(String::from),
+            on_failure: None,
+            timeout_minutes: None,
+        }
+    }
+
+    fn workflow(steps: Vec<WorkflowStep>) -> WorkflowDefinition {
+        WorkflowDefinition {
+            id: None,
+            name: "Escalation".to_string(),
+            description: None,
+            trigger_type: WorkflowTrigger::Manual,
+            trigger_conditions: json!({}),
+            steps,
+            is_active: true,
+            created_by: "users:admin".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            workflow_key: Some("escalation".to_string()),
+            version: 2,
+            status: WorkflowVersionStatus::Draft,
+            published_at: None,
+            tenant_id: None,
+        }
+    }
+
+    #[test]
+    fn test_next_step_prefers_on_success_then_definition_order() {
+        let workflow = workflow(vec![step("a", None), step("b", Some("d")), step("c", None), step("d", None)]);
+
+        assert_eq!(next_step(&workflow, &workflow.steps[0]).as_deref(), Some("b"));
+        assert_eq!(next_step(&workflow, &workflow.steps[1]).as_deref(), Some("d"));
+        assert_eq!(next_step(&workflow, &workflow.steps[3]), None);
+    }
+
+    #[test]
+    fn test_record_step_result_keeps_existing_context() {
+        let mut context = json!({ "trigger_record_id": "ticket:1" });
+        record_step_result(&mut context, "notify", json!({ "status": "notification_sent" }));
+        record_step_result(&mut context, "call", json!({ "http_status": 200 }));
+        assert_eq!(context["trigger_record_id"], "ticket:1");
+        assert_eq!(context["steps"]["notify"]["status"], "notification_sent");
+        assert_eq!(context["steps"]["call"]["http_status"], 200);
+    }
+
+    #[test]
+    fn test_validate_steps() {
+        assert!(validate_steps(&[step("a", Some("b")), step("b", None)]).is_ok());
+        assert!(validate_steps(&[]).is_err());
+        assert!(validate_steps(&[step("a", None), step("a", None)]).is_err());
+        assert!(validate_steps(&[step("a", Some("missing"))]).is_err());
+    }
+
+    #[test]
+    fn test_export_round_trip_and_format_checks() {
+        let source = workflow(vec![step("a", None), step("b", None)]);
+        let export = export_document(&source, Utc::now());
+        let json = serde_json::to_value(&export).unwrap();
+        assert_eq!(json["format"], "archer.workflow");
+        assert!(json["workflow"].get("tenant_id").is_none());
+
+        let parsed: WorkflowExport = serde_json::from_value(json).unwrap();
+        assert!(check_export(&parsed).is_ok());
+        assert_eq!(parsed.workflow.workflow_key.as_deref(), Some("escalation"));
+        assert_eq!(parsed.workflow.steps.len(), 2);
+
+        let mut wrong = parsed.clone();
+        wrong.format = "something.else".to_string();
+        assert!(check_export(&wrong).is_err());
+        let mut newer = parsed;
+        newer.format_version = EXPORT_FORMAT_VERSION + 1;
+        assert!(check_export(&newer).is_err());
+    }
+
+    #[test]
+    fn test_migration_blocker_requires_current_step_in_target() {
+        let target = workflow(vec![step("a", None), step("c", None)]);
+        let instance = |current: Option<&str>| WorkflowInstance {
+            id: None,
+            workflow_id: "workflow_definition:v1".parse().unwrap(),
+            workflow_version: Some(1),
+            trigger_record_type: "ticket".to_string(),
+            trigger_record_id: "ticket:1".parse().unwrap(),
+            status: WorkflowInstanceStatus::WaitingApproval,
+            current_step_id: current.map(String::from),
+            step_history: vec![],
+            started_at: Utc::now(),
+            completed_at: None,
+            context: json!({}),
+            resume_at: None,
+        };
+
+        assert!(migration_blocker(&instance(Some("a")), &target).is_none());
+        assert!(migration_blocker(&instance(None), &target).is_none());
+        assert!(migration_blocker(&instance(Some("b")), &target)
+            .unwrap()
+            .contains("does not exist in version 2"));
+    }
+}
@@ -0,0 +1,604 @@
+// Archer ITSM - Workflow Step Executors
+// Templating, condition evaluation, timers, outbound webhooks and Rhai scripts
+// used by the workflow engine. Everything here works on a JSON "scope":
+//   { "record": <trigger record>, "context": <instance context>, "instance": {...} }
+
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+use crate::models::workflow_engine::{StepLogEntry, StepLogLevel};
+use crate::utils::telemetry::TracedRequest;
+
+/// Webhook attempts are capped regardless of step config
+const MAX_WEBHOOK_ATTEMPTS: u32 = 10;
+/// Per-attempt webhook timeout ceiling, regardless of step config
+const MAX_WEBHOOK_TIMEOUT_SECONDS: u64 = 60;
+/// Response bodies kept in the step result are truncated to this many bytes
+const MAX_RESPONSE_BYTES: usize = 4096;
+/// Default operation budget for script and expression evaluation
+const DEFAULT_SCRIPT_OPERATIONS: u64 = 100_000;
+/// Script operation budget ceiling, regardless of step config. Rhai treats
+/// 0 as unlimited, so the budget is never allowed below 1 either.
+const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_.\-]+)\s*\}\}").expect("valid placeholder regex"));
+
+/// Execution log and attempt count collected while a step runs
+#[derive(Debug, Default)]
+pub struct StepRun {
+    pub attempts: u32,
+    pub logs: Vec<StepLogEntry>,
+}
+
+impl StepRun {
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(StepLogLevel::Info, message.into());
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.push(StepLogLevel::Warn, message.into());
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(StepLogLevel::Error, message.into());
+    }
+
+    fn push(&mut self, level: StepLogLevel, message: String) {
+        self.logs.push(StepLogEntry {
+            at: Utc::now(),
+            level,
+            message,
+        });
+    }
+}
+
+// ============================================================================
+// TEMPLATING
+// ============================================================================
+
+/// Resolve a dotted path ("record.assignee.name", "context.items.0") in the scope
+pub fn lookup<'a>(scope: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(scope, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+/// Substitute `{{path}}` placeholders throughout a JSON template.
+///
+/// A string that is exactly one placeholder takes the referenced value with its
+/// JSON type intact; placeholders embedded in longer strings are interpolated
+/// as text. Unknown paths render as null / empty string.
+pub fn render_template(template: &Value, scope: &Value) -> Value {
+    match template {
+        Value::String(text) => {
+            if let Some(caps) = PLACEHOLDER.captures(text) {
+                if caps.get(0).map(|m| m.as_str().len()) == Some(text.len()) {
+                    return lookup(scope, &caps[1]).cloned().unwrap_or(Value::Null);
+                }
+            }
+            Value::String(render_text(text, scope))
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| render_template(v, scope)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_template(v, scope)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Interpolate `{{path}}` placeholders into a string
+pub fn render_text(text: &str, scope: &Value) -> String {
+    PLACEHOLDER
+        .replace_all(text, |caps: &regex::Captures| match lookup(scope, &caps[1]) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        })
+        .into_owned()
+}
+
+// ============================================================================
+// CONDITIONS
+// ============================================================================
+
+/// Evaluate a condition step's config against the scope.
+///
+/// Accepted forms:
+/// - `{"field": "record.priority", "operator": "eq", "value": "P1"}`
+/// - `{"match": "all" | "any", "conditions": [ ...field conditions... ]}`
+/// - `{"expression": "record.priority == \"P1\" && context.retries < 3"}` (Rhai)
+///
+/// Field paths without a `record.` / `context.` / `instance.` prefix are read
+/// from the trigger record.
+pub fn evaluate_condition(config: &Value, scope: &Value) -> Result<bool, String> {
+    if let Some(expression) = config
+        .get("expression")
+        .or_else(|| config.get("condition"))
+        .and_then(|v| v.as_str())
+    {
+        return evaluate_expression(expression, scope);
+    }
+
+    if let Some(conditions) = config.get("conditions").and_then(|v| v.as_array()) {
+        let any = config.get("match").and_then(|v| v.as_str()) == Some("any");
+        let mut results = conditions.iter().map(|c| evaluate_field_condition(c, scope));
+        return if any {
+            results.try_fold(false, |acc, r| r.map(|ok| acc || ok))
+        } else {
+            results.try_fold(true, |acc, r| r.map(|ok| acc && ok))
+        };
+    }
+
+    if config.get("field").is_some() {
+        return evaluate_field_condition(config, scope);
+    }
+
+    Err("Condition step needs 'field', 'conditions' or 'expression' in config".to_string())
+}
+
+fn evaluate_field_condition(condition: &Value, scope: &Value) -> Result<bool, String> {
+    let field = condition
+        .get("field")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Condition is missing 'field'".to_string())?;
+    let operator = condition.get("operator").and_then(|v| v.as_str()).unwrap_or("eq");
+    let expected = condition
+        .get("value")
+        .map(|v| render_template(v, scope))
+        .unwrap_or(Value::Null);
+
+    let path = if ["record.", "context.", "instance."].iter().any(|p| field.starts_with(p)) {
+        field.to_string()
+    } else {
+        format!("record.{}", field)
+    };
+    let actual = lookup(scope, &path);
+
+    let result = match operator {
+        "exists" => actual.is_some_and(|v| !v.is_null()),
+        "not_exists" => actual.is_none_or(|v| v.is_null()),
+        "eq" => actual.is_some_and(|v| values_equal(v, &expected)),
+        "ne" => !actual.is_some_and(|v| values_equal(v, &expected)),
+        "gt" | "gte" | "lt" | "lte" => match actual.and_then(|v| compare(v, &expected)) {
+            Some(ordering) => match operator {
+                "gt" => ordering.is_gt(),
+                "gte" => ordering.is_ge(),
+                "lt" => ordering.is_lt(),
+                _ => ordering.is_le(),
+            },
+            None => false,
+        },
+        "contains" => match actual {
+            Some(Value::String(s)) => expected.as_str().is_some_and(|e| s.contains(e)),
+            Some(Value::Array(items)) => items.iter().any(|v| values_equal(v, &expected)),
+            _ => false,
+        },
+        "in" => match (&expected, actual) {
+            (Value::Array(options), Some(v)) => options.iter().any(|o| values_equal(v, o)),
+            _ => false,
+        },
+        "matches" => {
+            let pattern = expected
+                .as_str()
+                .ok_or_else(|| "'matches' needs a string pattern".to_string())?;
+            let re = Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+            actual.and_then(|v| v.as_str()).is_some_and(|s| re.is_match(s))
+        }
+        other => return Err(format!("Unknown condition operator '{}'", other)),
+    };
+
+    Ok(result)
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+/// Numbers compare numerically, strings lexically (which orders RFC 3339 timestamps)
+fn compare(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// TIMERS
+// ============================================================================
+
+/// When a delay step should resume.
+///
+/// `until` (RFC 3339, templated, e.g. `"{{record.sla_due}}"`) takes precedence;
+/// otherwise `delay_seconds`, `delay_minutes` and `delay_hours` are summed.
+/// A timestamp in the past resumes immediately.
+pub fn resolve_delay(config: &Value, scope: &Value, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    if let Some(until) = config.get("until") {
+        let rendered = render_template(until, scope);
+        let text = rendered
+            .as_str()
+            .ok_or_else(|| "'until' must resolve to an RFC 3339 timestamp".to_string())?;
+        let at = DateTime::parse_from_rfc3339(text)
+            .map_err(|e| format!("Invalid 'until' timestamp '{}': {}", text, e))?
+            .with_timezone(&Utc);
+        return Ok(at.max(now));
+    }
+
+    let part = |key: &str| config.get(key).and_then(|v| v.as_i64()).unwrap_or(0);
+    let seconds = part("delay_seconds") + part("delay_minutes") * 60 + part("delay_hours") * 3600;
+    if seconds <= 0 {
+        return Err("Delay step needs 'until' or a positive delay_seconds/minutes/hours".to_string());
+    }
+    Ok(now + Duration::seconds(seconds))
+}
+
+// ============================================================================
+// WEBHOOKS
+// ============================================================================
+
+/// Call an outbound webhook, retrying transport errors, 429 and 5xx responses
+/// with exponential backoff.
+///
+/// Config: `url`, `method` (default POST), `headers`, `payload` (all templated),
+/// `max_attempts` (default 3, at most 10), `backoff_seconds` (default 2),
+/// `timeout_seconds` (default 10, at most 60).
+pub async fn call_webhook(config: &Value, scope: &Value, run: &mut StepRun) -> Result<Value, String> {
+    let url = config
+        .get("url")
+        .and_then(|v| v.as_str())
+        .map(|u| render_text(u, scope))
+        .ok_or_else(|| "Webhook URL not specified in step config".to_string())?;
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(format!("Webhook URL must be http(s): {}", url));
+    }
+
+    let method_name = config
+        .get("method")
+        .and_then(|v| v.as_str())
+        .unwrap_or("POST")
+        .to_uppercase();
+    let method = match method_name.as_str() {
+        "GET" | "POST" | "PUT" | "PATCH" | "DELETE" => reqwest::Method::from_bytes(method_name.as_bytes())
+            .map_err(|e| format!("Invalid method: {}", e))?,
+        other => return Err(format!("Unsupported webhook method '{}'", other)),
+    };
+
+    let headers: Vec<(String, String)> = config
+        .get("headers")
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), render_text(v, scope))))
+                .collect()
+        })
+        .unwrap_or_default();
+    let payload = config.get("payload").map(|p| render_template(p, scope));
+
+    let max_attempts = config
+        .get("max_attempts")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(3)
+        .clamp(1, MAX_WEBHOOK_ATTEMPTS as u64) as u32;
+    let backoff_seconds = config.get("backoff_seconds").and_then(|v| v.as_u64()).unwrap_or(2);
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(webhook_timeout_seconds(config)))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let mut last_error = String::new();
+    for attempt in 1..=max_attempts {
+        run.attempts = attempt;
+
+        let mut request = client.request(method.clone(), &url);
+        for (name, value) in &headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(payload) = &payload {
+            request = request.json(payload);
+        }
+
+        match request.send_traced("workflow_webhook").await {
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                if status.is_success() {
+                    run.info(format!("{} {} → {} (attempt {})", method, url, status.as_u16(), attempt));
+                    return Ok(json!({
+                        "status": "webhook_delivered",
+                        "http_status": status.as_u16(),
+                        "response": response_body(&body),
+                        "attempts": attempt
+                    }));
+                }
+
+                last_error = format!("HTTP {}", status.as_u16());
+                if !(status.is_server_error() || status.as_u16() == 429) {
+                    run.error(format!("{} {} → {}, not retrying", method, url, status.as_u16()));
+                    return Err(format!("Webhook rejected with HTTP {}", status.as_u16()));
+                }
+                run.warn(format!("{} {} → {} (attempt {})", method, url, status.as_u16(), attempt));
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                run.warn(format!("{} {} failed (attempt {}): {}", method, url, attempt, e));
+            }
+        }
+
+        if attempt < max_attempts {
+            let wait = backoff_seconds.saturating_mul(1 << (attempt - 1).min(16));
+            tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+        }
+    }
+
+    run.error(format!("Giving up after {} attempt(s)", max_attempts));
+    Err(format!("Webhook failed after {} attempt(s): {}", max_attempts, last_error))
+}
+
+/// The step's per-attempt timeout, kept within the server's ceiling
+fn webhook_timeout_seconds(config: &Value) -> u64 {
+    config
+        .get("timeout_seconds")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(10)
+        .clamp(1, MAX_WEBHOOK_TIMEOUT_SECONDS)
+}
+
+/// JSON bodies are kept as JSON, anything else as (truncated) text
+fn response_body(body: &str) -> Value {
+    if let Ok(parsed) = serde_json::from_str::<Value>(body) {
+        return parsed;
+    }
+    let mut end = body.len().min(MAX_RESPONSE_BYTES);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    Value::String(body[..end].to_string())
+}
+
+// ============================================================================
+// SCRIPTS
+// ============================================================================
+
+/// Run a Rhai script with `record` (read-only copy), `context` and `instance`
+/// in scope. Changes the script makes to `context` are returned alongside its
+/// result; `print`/`debug` output goes to the step log.
+///
+/// Config: `script`, `max_operations` (default 100k, at most 1M).
+pub fn run_script(config: &Value, scope: &Value, run: &mut StepRun) -> Result<(Value, Value), String> {
+    let script = config
+        .get("script")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Script not specified in step config".to_string())?;
+    let max_operations = script_operations(config);
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut engine = sandboxed_engine(max_operations);
+    let printed = Arc::clone(&output);
+    engine.on_print(move |text| {
+        if let Ok(mut lines) = printed.lock() {
+            lines.push(text.to_string());
+        }
+    });
+    let debugged = Arc::clone(&output);
+    engine.on_debug(move |text, _source, pos| {
+        if let Ok(mut lines) = debugged.lock() {
+            lines.push(format!("[debug {}] {}", pos, text));
+        }
+    });
+
+    let mut rhai_scope = script_scope(scope)?;
+    let result = engine.eval_with_scope::<rhai::Dynamic>(&mut rhai_scope, script);
+
+    for line in output.lock().map(|lines| lines.clone()).unwrap_or_default() {
+        run.info(line);
+    }
+
+    let result = result.map_err(|e| format!("Script error: {}", e))?;
+    let value: Value = rhai::serde::from_dynamic(&result).map_err(|e| format!("Script result: {}", e))?;
+    let context = rhai_scope
+        .get_value::<rhai::Dynamic>("context")
+        .map(|c| rhai::serde::from_dynamic::<Value>(&c))
+        .transpose()
+        .map_err(|e| format!("Script context: {}", e))?
+        .unwrap_or_else(|| scope.get("context").cloned().unwrap_or(json!({})));
+
+    Ok((value, context))
+}
+
+/// Evaluate a boolean Rhai expression against the scope
+fn evaluate_expression(expression: &str, scope: &Value) -> Result<bool, String> {
+    let engine = sandboxed_engine(DEFAULT_SCRIPT_OPERATIONS);
+    let mut rhai_scope = script_scope(scope)?;
+    engine
+        .eval_expression_with_scope::<bool>(&mut rhai_scope, expression)
+        .map_err(|e| format!("Condition expression error: {}", e))
+}
+
+/// The step's operation budget, kept within the server's sandbox limits
+fn script_operations(config: &Value) -> u64 {
+    config
+        .get("max_operations")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_SCRIPT_OPERATIONS)
+        .clamp(1, MAX_SCRIPT_OPERATIONS)
+}
+
+/// Engine with resource limits and no filesystem module loading
+fn sandboxed_engine(max_operations: u64) -> rhai::Engine {
+    let mut engine = rhai::Engine::new();
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(max_operations);
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(1 << 20);
+    engine.set_max_array_size(10_000);
+    engine.set_max_map_size(10_000);
+    engine
+}
+
+fn script_scope(scope: &Value) -> Result<rhai::Scope<'static>, String> {
+    let mut rhai_scope = rhai::Scope::new();
+    for name in ["record", "context", "instance"] {
+        let value = scope.get(name).cloned().unwrap_or(Value::Null);
+        let dynamic = rhai::serde::to_dynamic(value).map_err(|e| format!("Script input '{}': {}", name, e))?;
+        if name == "record" {
+            rhai_scope.push_constant_dynamic(name, dynamic);
+        } else {
+            rhai_scope.push_dynamic(name, dynamic);
+        }
+    }
+    Ok(rhai_scope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn scope() -> Value {
+        json!({
+            "record": {
+                "id": "ticket:abc",
+                "title": "Disk full",
+                "priority": "P1",
+                "impact": 3,
+                "tags": ["storage", "prod"],
+                "assignee": { "name": "ops" },
+                "sla_due": "2024-03-05T12:00:00Z"
+            },
+            "context": { "retries": 1 },
+            "instance": { "id": "workflow_instance:i1" }
+        })
+    }
+
+    #[test]
+    fn test_render_template_keeps_types_and_interpolates() {
+        let rendered = render_template(
+            &json!({
+                "text": "[{{record.priority}}] {{ record.title }} for {{record.assignee.name}}",
+                "impact": "{{record.impact}}",
+                "tags": ["{{record.tags.0}}", "{{record.missing}}"],
+                "static": true
+            }),
+            &scope(),
+        );
+        assert_eq!(
+            rendered,
+            json!({
+                "text": "[P1] Disk full for ops",
+                "impact": 3,
+                "tags": ["storage", null],
+                "static": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_field_conditions() {
+        let s = scope();
+        let check = |config: Value| evaluate_condition(&config, &s).unwrap();
+
+        assert!(check(json!({"field": "priority", "operator": "eq", "value": "P1"})));
+        assert!(check(json!({"field": "impact", "operator": "gte", "value": 3})));
+        assert!(!check(json!({"field": "context.retries", "operator": "gt", "value": 2})));
+        assert!(check(json!({"field": "tags", "operator": "contains", "value": "prod"})));
+        assert!(check(json!({"field": "priority", "operator": "in", "value": ["P1", "P2"]})));
+        assert!(check(json!({"field": "resolved_at", "operator": "not_exists"})));
+        assert!(check(json!({
+            "match": "any",
+            "conditions": [
+                {"field": "priority", "value": "P4"},
+                {"field": "title", "operator": "matches", "value": "(?i)disk"}
+            ]
+        })));
+        assert!(!check(json!({
+            "conditions": [
+                {"field": "priority", "value": "P1"},
+                {"field": "impact", "operator": "lt", "value": 2}
+            ]
+        })));
+        assert!(evaluate_condition(&json!({"field": "priority", "operator": "like"}), &s).is_err());
+        assert!(evaluate_condition(&json!({}), &s).is_err());
+    }
+
+    #[test]
+    fn test_expression_condition() {
+        let s = scope();
+        assert!(evaluate_condition(&json!({"expression": "record.priority == \"P1\" && context.retries < 3"}), &s).unwrap());
+        assert!(!evaluate_condition(&json!({"expression": "record.impact > 5"}), &s).unwrap());
+        assert!(evaluate_condition(&json!({"expression": "record.priority +"}), &s).is_err());
+    }
+
+    #[test]
+    fn test_resolve_delay() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 5, 10, 0, 0).unwrap();
+        let s = scope();
+        assert_eq!(
+            resolve_delay(&json!({"delay_minutes": 30, "delay_seconds": 15}), &s, now).unwrap(),
+            now + Duration::seconds(1815)
+        );
+        assert_eq!(
+            resolve_delay(&json!({"until": "{{record.sla_due}}"}), &s, now).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 5, 12, 0, 0).unwrap()
+        );
+        // Past timestamps resume immediately
+        assert_eq!(resolve_delay(&json!({"until": "2020-01-01T00:00:00Z"}), &s, now).unwrap(), now);
+        assert!(resolve_delay(&json!({}), &s, now).is_err());
+    }
+
+    #[test]
+    fn test_script_updates_context_and_logs_output() {
+        let mut run = StepRun::default();
+        let config = json!({
+            "script": r#"
+                print("escalating " + record.title);
+                context.retries += 1;
+                context.escalated = record.impact >= 3;
+                #{ level: if record.priority == "P1" { 2 } else { 1 } }
+            "#
+        });
+        let (result, context) = run_script(&config, &scope(), &mut run).unwrap();
+        assert_eq!(result, json!({"level": 2}));
+        assert_eq!(context, json!({"retries": 2, "escalated": true}));
+        assert_eq!(run.logs.len(), 1);
+        assert_eq!(run.logs[0].message, "escalating Disk full");
+
+        // Runaway scripts hit the operation limit; the record is read-only
+        let looping = json!({"script": "loop {}", "max_operations": 1000});
+        assert!(run_script(&looping, &scope(), &mut StepRun::default()).is_err());
+        let mutate = json!({"script": "record.priority = \"P4\";"});
+        assert!(run_script(&mutate, &scope(), &mut StepRun::default()).is_err());
+    }
+
+    #[test]
+    fn test_sandbox_limits_are_clamped() {
+        assert_eq!(webhook_timeout_seconds(&json!({})), 10);
+        assert_eq!(webhook_timeout_seconds(&json!({"timeout_seconds": 30})), 30);
+        assert_eq!(webhook_timeout_seconds(&json!({"timeout_seconds": 86_400})), MAX_WEBHOOK_TIMEOUT_SECONDS);
+        assert_eq!(webhook_timeout_seconds(&json!({"timeout_seconds": 0})), 1);
+
+        assert_eq!(script_operations(&json!({})), DEFAULT_SCRIPT_OPERATIONS);
+        assert_eq!(script_operations(&json!({"max_operations": 5000})), 5000);
+        assert_eq!(script_operations(&json!({"max_operations": u64::MAX})), MAX_SCRIPT_OPERATIONS);
+        // 0 would switch Rhai's limit off
+        assert_eq!(script_operations(&json!({"max_operations": 0})), 1);
+
+        // An oversized budget still stops a runaway script
+        let looping = json!({"script": "loop {}", "max_operations": u64::MAX});
+        let error = run_script(&looping, &scope(), &mut StepRun::default()).unwrap_err();
+        assert!(error.contains("operations"), "{}", error);
+    }
+}
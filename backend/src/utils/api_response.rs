@@ -1,9 +1,12 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json, Response};
 use chrono::{DateTime, Utc};
+use core_engine::error::{codes, ErrorCategory, ErrorCode, ErrorDetail};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use crate::utils::telemetry::current_trace_id;
+
 /// Standard API response format for all endpoints
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ApiResponse<T> {
@@ -12,16 +15,62 @@ pub struct ApiResponse<T> {
     pub error: Option<ApiError>,
     pub metadata: Option<Value>,
     pub timestamp: DateTime<Utc>,
+    /// Trace of the request, for correlating with logs and traces in support cases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
 }
 
-/// Standard error structure
+/// Standard error structure. `code` is a stable identifier from the error
+/// registry (`GET /api/v1/error-codes`); `message` is safe to show to users
+/// and `detail` carries scrubbed technical detail where there is any.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ApiError {
     pub code: String,
+    #[serde(default)]
+    pub category: ErrorCategory,
+    #[serde(default)]
+    pub retryable: bool,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
     pub details: Option<Value>,
 }
 
+impl ApiError {
+    /// Error for a code; category and retryability come from the registry
+    pub fn new(code: &str, message: &str) -> Self {
+        let entry = ErrorCode::lookup(code);
+        ApiError {
+            code: code.to_string(),
+            category: entry.map(|e| e.category).unwrap_or_default(),
+            retryable: entry.map(|e| e.retryable).unwrap_or(false),
+            message: message.to_string(),
+            detail: None,
+            details: None,
+        }
+    }
+
+    /// HTTP status registered for the code; unknown codes are 500
+    pub fn status(&self) -> StatusCode {
+        ErrorCode::lookup(&self.code)
+            .and_then(|e| StatusCode::from_u16(e.http_status).ok())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl From<ErrorDetail> for ApiError {
+    fn from(error: ErrorDetail) -> Self {
+        ApiError {
+            code: error.code,
+            category: error.category,
+            retryable: error.retryable,
+            message: error.message,
+            detail: error.detail,
+            details: None,
+        }
+    }
+}
+
 impl<T> ApiResponse<T>
 where
     T: Serialize,
@@ -34,6 +83,7 @@ where
             error: None,
             metadata: None,
             timestamp: Utc::now(),
+            trace_id: current_trace_id(),
         }
     }
 
@@ -45,6 +95,7 @@ where
             error: None,
             metadata: Some(metadata),
             timestamp: Utc::now(),
+            trace_id: current_trace_id(),
         }
     }
 }
@@ -55,13 +106,10 @@ impl ApiResponse<()> {
         ApiResponse {
             success: false,
             data: None,
-            error: Some(ApiError {
-                code: code.to_string(),
-                message: message.to_string(),
-                details: None,
-            }),
+            error: Some(ApiError::new(code, message)),
             metadata: None,
             timestamp: Utc::now(),
+            trace_id: current_trace_id(),
         }
     }
 
@@ -71,12 +119,25 @@ impl ApiResponse<()> {
             success: false,
             data: None,
             error: Some(ApiError {
-                code: code.to_string(),
-                message: message.to_string(),
                 details: Some(details),
+                ..ApiError::new(code, message)
             }),
             metadata: None,
             timestamp: Utc::now(),
+            trace_id: current_trace_id(),
+        }
+    }
+
+    /// Error response for a structured error, e.g. one converted from a
+    /// `CoreEngineError`
+    pub fn from_error_detail(error: ErrorDetail) -> Self {
+        ApiResponse {
+            success: false,
+            data: None,
+            error: Some(error.into()),
+            metadata: None,
+            timestamp: Utc::now(),
+            trace_id: current_trace_id(),
         }
     }
 }
@@ -97,13 +158,10 @@ where
             Err(err) => ApiResponse {
                 success: false,
                 data: None,
-                error: Some(ApiError {
-                    code: "INTERNAL_ERROR".to_string(),
-                    message: err.to_string(),
-                    details: None,
-                }),
+                error: Some(ApiError::new(codes::INTERNAL_ERROR.code, &err.to_string())),
                 metadata: None,
                 timestamp: Utc::now(),
+                trace_id: current_trace_id(),
             },
         }
     }
@@ -118,16 +176,10 @@ where
         let status = if self.success {
             StatusCode::OK
         } else {
-            match self.error.as_ref().map(|e| e.code.as_str()) {
-                Some("NOT_FOUND") => StatusCode::NOT_FOUND,
-                Some("BAD_REQUEST") | Some("VALIDATION_ERROR") => StatusCode::BAD_REQUEST,
-                Some("UNAUTHORIZED") => StatusCode::UNAUTHORIZED,
-                Some("FORBIDDEN") => StatusCode::FORBIDDEN,
-                Some("CONFLICT") => StatusCode::CONFLICT,
-                Some("PAYLOAD_TOO_LARGE") => StatusCode::PAYLOAD_TOO_LARGE,
-                Some("RATE_LIMITED") => StatusCode::TOO_MANY_REQUESTS,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            }
+            self.error
+                .as_ref()
+                .map(ApiError::status)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
         };
 
         (status, Json(self)).into_response()
@@ -169,6 +221,30 @@ pub mod helpers {
     pub fn unauthorized(message: &str) -> ApiResponse<()> {
         ApiResponse::error("UNAUTHORIZED", message)
     }
+
+    pub fn engine_error(error: core_engine::CoreEngineError) -> ApiResponse<()> {
+        ApiResponse::from_error_detail(error.into())
+    }
+
+    /// Error envelope as a `(status, body)` pair, for handlers that build
+    /// their own responses. The status is the one registered for `code`.
+    pub fn error_body(code: ErrorCode, message: &str) -> (StatusCode, Json<Value>) {
+        envelope(ApiResponse::error(code.code, message))
+    }
+
+    /// As `error_body`, with structured `details` such as blocking items
+    pub fn error_body_with_details(code: ErrorCode, message: &str, details: Value) -> (StatusCode, Json<Value>) {
+        envelope(ApiResponse::error_with_details(code.code, message, details))
+    }
+
+    fn envelope(response: ApiResponse<()>) -> (StatusCode, Json<Value>) {
+        let status = response
+            .error
+            .as_ref()
+            .map(ApiError::status)
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(serde_json::to_value(response).unwrap_or_default()))
+    }
 }
 
 #[cfg(test)]
@@ -205,4 +281,34 @@ mod tests {
         let response = error_result.into_api_response();
         assert!(!response.success);
     }
+
+    #[test]
+    fn test_error_taxonomy_fields() {
+        let error = ApiResponse::<()>::error("RATE_LIMITED", "Slow down").error.unwrap();
+        assert_eq!(error.category, ErrorCategory::External);
+        assert!(error.retryable);
+        assert_eq!(error.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let response = helpers::engine_error(core_engine::CoreEngineError::not_found("Model R750"));
+        let error = response.error.unwrap();
+        assert_eq!(error.code, "NOT_FOUND");
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+        assert_eq!(ApiError::new("TEST_ERROR", "x").status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_error_body_carries_registry_fields() {
+        let (status, Json(body)) = helpers::error_body(codes::NOT_CONFIGURED, "No LLM provider is configured");
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["error"]["code"], json!("NOT_CONFIGURED"));
+        assert_eq!(body["error"]["category"], json!("configuration"));
+        assert_eq!(body["error"]["retryable"], json!(false));
+        assert_eq!(body["error"]["message"], json!("No LLM provider is configured"));
+
+        let (status, Json(body)) =
+            helpers::error_body_with_details(codes::PRECONDITION_FAILED, "Blocked", json!({ "blockers": ["a"] }));
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        assert_eq!(body["error"]["details"]["blockers"], json!(["a"]));
+    }
 }
@@ -0,0 +1,180 @@
+//! IPv4 CIDR arithmetic for network mappings and re-IP planning
+
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+/// How two networks relate; CIDR blocks either nest or are disjoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CidrRelation {
+    Equal,
+    /// The first network contains the second
+    Contains,
+    /// The first network lies inside the second
+    ContainedBy,
+    Disjoint,
+}
+
+/// An IPv4 network in CIDR notation, normalized to its network address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv4Cidr {
+    network: Ipv4Addr,
+    prefix: u8,
+}
+
+impl Ipv4Cidr {
+    pub fn new(address: Ipv4Addr, prefix: u8) -> Option<Self> {
+        if prefix > 32 {
+            return None;
+        }
+        let network = Ipv4Addr::from(u32::from(address) & mask(prefix));
+        Some(Self { network, prefix })
+    }
+
+    pub fn network(&self) -> Ipv4Addr {
+        self.network
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    pub fn contains(&self, address: Ipv4Addr) -> bool {
+        u32::from(address) & mask(self.prefix) == u32::from(self.network)
+    }
+
+    pub fn overlaps(&self, other: &Ipv4Cidr) -> bool {
+        let shorter = self.prefix.min(other.prefix);
+        u32::from(self.network) & mask(shorter) == u32::from(other.network) & mask(shorter)
+    }
+
+    /// Whether `other` lies entirely inside this network
+    pub fn contains_network(&self, other: &Ipv4Cidr) -> bool {
+        self.prefix <= other.prefix && self.contains(other.network)
+    }
+
+    pub fn relation(&self, other: &Ipv4Cidr) -> CidrRelation {
+        if self == other {
+            CidrRelation::Equal
+        } else if self.contains_network(other) {
+            CidrRelation::Contains
+        } else if other.contains_network(self) {
+            CidrRelation::ContainedBy
+        } else {
+            CidrRelation::Disjoint
+        }
+    }
+
+    /// Number of addresses in the network
+    pub fn size(&self) -> u64 {
+        1u64 << (32 - self.prefix as u32)
+    }
+
+    /// Last address of the network (its broadcast address)
+    pub fn last(&self) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(self.network) | !mask(self.prefix))
+    }
+
+    /// Position of `address` inside the network; `None` if outside it
+    pub fn host_offset(&self, address: Ipv4Addr) -> Option<u32> {
+        self.contains(address)
+            .then(|| u32::from(address) & !mask(self.prefix))
+    }
+
+    /// Address at `offset` inside the network; `None` if the network is too small
+    pub fn address_at(&self, offset: u32) -> Option<Ipv4Addr> {
+        (offset & mask(self.prefix) == 0).then(|| Ipv4Addr::from(u32::from(self.network) | offset))
+    }
+
+    /// Move `address` from `source` to the same host position in `self`
+    pub fn translate(&self, source: &Ipv4Cidr, address: Ipv4Addr) -> Option<Ipv4Addr> {
+        source.host_offset(address).and_then(|offset| self.address_at(offset))
+    }
+}
+
+impl FromStr for Ipv4Cidr {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = value
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("'{}' is not in CIDR notation", value))?;
+        let address: Ipv4Addr = address
+            .parse()
+            .map_err(|_| format!("'{}' is not an IPv4 address", address))?;
+        let prefix: u8 = prefix
+            .parse()
+            .map_err(|_| format!("'{}' is not a prefix length", prefix))?;
+        Ipv4Cidr::new(address, prefix).ok_or_else(|| format!("Prefix /{} is longer than 32", prefix))
+    }
+}
+
+impl fmt::Display for Ipv4Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+fn mask(prefix: u8) -> u32 {
+    if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidr(value: &str) -> Ipv4Cidr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_normalizes_network() {
+        assert_eq!(cidr("10.1.2.77/24").to_string(), "10.1.2.0/24");
+        assert_eq!(cidr("0.0.0.0/0").to_string(), "0.0.0.0/0");
+        assert!("10.1.2.0".parse::<Ipv4Cidr>().is_err());
+        assert!("10.1.2.0/33".parse::<Ipv4Cidr>().is_err());
+        assert!("fe80::/64".parse::<Ipv4Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_contains_and_overlaps() {
+        let net = cidr("10.1.0.0/16");
+        assert!(net.contains("10.1.200.3".parse().unwrap()));
+        assert!(!net.contains("10.2.0.1".parse().unwrap()));
+        assert!(net.overlaps(&cidr("10.1.4.0/24")));
+        assert!(cidr("10.1.4.0/24").overlaps(&net));
+        assert!(!net.overlaps(&cidr("10.2.0.0/16")));
+    }
+
+    #[test]
+    fn test_containment_and_relation() {
+        let wide = cidr("10.1.0.0/16");
+        let narrow = cidr("10.1.4.0/24");
+        assert!(wide.contains_network(&narrow));
+        assert!(!narrow.contains_network(&wide));
+        assert_eq!(wide.relation(&narrow), CidrRelation::Contains);
+        assert_eq!(narrow.relation(&wide), CidrRelation::ContainedBy);
+        assert_eq!(narrow.relation(&cidr("10.1.4.99/24")), CidrRelation::Equal);
+        assert_eq!(narrow.relation(&cidr("10.1.5.0/24")), CidrRelation::Disjoint);
+        assert_eq!(cidr("0.0.0.0/0").relation(&narrow), CidrRelation::Contains);
+        assert_eq!(narrow.size(), 256);
+        assert_eq!(narrow.last(), "10.1.4.255".parse::<Ipv4Addr>().unwrap());
+        assert_eq!(cidr("0.0.0.0/0").size(), 1 << 32);
+    }
+
+    #[test]
+    fn test_translate_keeps_host_part() {
+        let source = cidr("10.1.2.0/24");
+        let ip = "10.1.2.34".parse().unwrap();
+        assert_eq!(cidr("172.16.9.0/24").translate(&source, ip), Some("172.16.9.34".parse().unwrap()));
+        assert_eq!(cidr("172.16.8.0/22").translate(&source, ip), Some("172.16.8.34".parse().unwrap()));
+        // .34 does not fit a /27
+        assert_eq!(cidr("172.16.9.0/27").translate(&source, ip), None);
+        assert_eq!(cidr("172.16.9.0/24").translate(&source, "10.9.9.9".parse().unwrap()), None);
+    }
+}
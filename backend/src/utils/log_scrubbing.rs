@@ -0,0 +1,112 @@
+//! Log scrubbing for the tracing pipeline
+//!
+//! Fields with secret-looking names (`password`, `token`, `api_key`, ...) are
+//! redacted when events and spans are formatted, and every finished line then
+//! goes through `core_engine::redaction::scrub` before it reaches stdout. The
+//! most recent scrubbed lines are kept in memory for the diagnostics bundle.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use core_engine::redaction::{is_sensitive_field, scrub, REDACTED};
+use once_cell::sync::Lazy;
+use tracing_subscriber::field::MakeExt;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::fmt::format::debug_fn;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+use crate::utils::telemetry;
+
+/// Number of log lines kept for diagnostics bundles
+const RECENT_LOG_CAPACITY: usize = 2000;
+
+static RECENT_LOGS: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(RECENT_LOG_CAPACITY)));
+
+/// Install the global subscriber; honours `RUST_LOG`. Colours are off so
+/// escape codes cannot split a value the scrubber has to recognise. Spans
+/// also go to OpenTelemetry (see `utils::telemetry`).
+pub fn init_tracing() {
+    let fields = debug_fn(|writer, field, value| {
+        let name = field.name();
+        if is_sensitive_field(name) {
+            write!(writer, "{}={}", name, REDACTED)
+        } else if name == "message" {
+            write!(writer, "{:?}", value)
+        } else {
+            write!(writer, "{}={:?}", name, value)
+        }
+    })
+    .delimited(" ");
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .fmt_fields(fields)
+                .with_writer(ScrubbingMakeWriter),
+        )
+        // Spans only: log events stay in the scrubbed local output
+        .with(
+            tracing_opentelemetry::layer()
+                .with_tracer(telemetry::tracer())
+                .with_filter(filter_fn(|metadata| metadata.is_span())),
+        )
+        .init();
+}
+
+/// Most recent scrubbed log lines, oldest first
+pub fn recent_logs(limit: usize) -> Vec<String> {
+    let logs = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    logs.iter().skip(logs.len().saturating_sub(limit)).cloned().collect()
+}
+
+/// Hands the fmt layer one buffer per event; the buffer is scrubbed and
+/// written out as a whole when the layer is done with it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScrubbingMakeWriter;
+
+impl<'a> MakeWriter<'a> for ScrubbingMakeWriter {
+    type Writer = ScrubbingWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ScrubbingWriter { buffer: Vec::new() }
+    }
+}
+
+pub struct ScrubbingWriter {
+    buffer: Vec<u8>,
+}
+
+impl Write for ScrubbingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for ScrubbingWriter {
+    fn drop(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let line = scrub(&String::from_utf8_lossy(&self.buffer));
+        let _ = io::stdout().lock().write_all(line.as_bytes());
+
+        let mut logs = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+        if logs.len() == RECENT_LOG_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(line.trim_end().to_string());
+    }
+}
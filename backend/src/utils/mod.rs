@@ -1,5 +1,9 @@
 pub mod api_response;
+pub mod cidr;
 pub mod error_handling;
+pub mod log_scrubbing;
+pub mod pagination;
+pub mod telemetry;
 
 // Re-export commonly used error types and utilities
 pub use error_handling::{EnhancedRvToolsError, EnhancedRvToolsLogger, EnhancedRvToolsResult};
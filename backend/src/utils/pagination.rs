@@ -0,0 +1,244 @@
+//! List pagination shared by every list endpoint
+//!
+//! Clients page with `limit` + `offset`, or follow the opaque `next_cursor`
+//! from the previous page. `page`/`page_size` are still accepted from older
+//! clients. `sort_by` must name one of the endpoint's sortable fields, so sort
+//! input never reaches a query unchecked.
+
+use std::marker::PhantomData;
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::middleware::validation::{FieldErrors, ValidationRejection};
+pub use crate::models::saved_view::SortOrder;
+
+pub const DEFAULT_LIMIT: u32 = 50;
+pub const MAX_LIMIT: u32 = 500;
+
+/// Fields an endpoint may be sorted by, and its default order. Implemented on
+/// a marker type per list.
+pub trait Sortable {
+    const SORT_FIELDS: &'static [&'static str];
+    const DEFAULT_SORT: &'static str;
+    const DEFAULT_ORDER: SortOrder;
+}
+
+/// Raw paging query parameters
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageQuery {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    pub cursor: Option<String>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<SortOrder>,
+}
+
+/// Validated paging and sort for a list of `S`
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination<S> {
+    pub limit: u32,
+    pub offset: u32,
+    pub sort_by: &'static str,
+    pub sort_order: SortOrder,
+    sortable: PhantomData<S>,
+}
+
+impl<S: Sortable> Default for Pagination<S> {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            offset: 0,
+            sort_by: S::DEFAULT_SORT,
+            sort_order: S::DEFAULT_ORDER,
+            sortable: PhantomData,
+        }
+    }
+}
+
+impl<S: Sortable> Pagination<S> {
+    pub fn from_query(query: &PageQuery) -> Result<Self, ValidationRejection> {
+        let mut errors = FieldErrors::default();
+        let mut pagination = Self::default();
+
+        if let Some(limit) = query.limit.or(query.page_size) {
+            errors.range("limit", limit, 1, MAX_LIMIT);
+            pagination.limit = limit.clamp(1, MAX_LIMIT);
+        }
+
+        if let Some(cursor) = &query.cursor {
+            match decode_cursor(cursor) {
+                Some(offset) => pagination.offset = offset,
+                None => errors.add("cursor", "format", "cursor is not a value returned by this API"),
+            }
+        } else if let Some(offset) = query.offset {
+            pagination.offset = offset;
+        } else if let Some(page) = query.page {
+            errors.range("page", page, 1, u32::MAX);
+            pagination.offset = page.saturating_sub(1).saturating_mul(pagination.limit);
+        }
+
+        if let Some(sort_by) = &query.sort_by {
+            match S::SORT_FIELDS.iter().find(|field| **field == sort_by.as_str()) {
+                Some(field) => pagination.sort_by = field,
+                None => errors.add(
+                    "sort_by",
+                    "enum",
+                    format!("sort_by must be one of: {}", S::SORT_FIELDS.join(", ")),
+                ),
+            }
+        }
+        if let Some(order) = query.sort_order {
+            pagination.sort_order = order;
+        }
+
+        errors.into_result().map(|_| pagination)
+    }
+
+    /// `ORDER BY` for the validated sort field
+    pub fn order_clause(&self) -> String {
+        let direction = match self.sort_order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        format!("ORDER BY {} {}", self.sort_by, direction)
+    }
+
+    pub fn limit_clause(&self) -> String {
+        format!("LIMIT {} START {}", self.limit, self.offset)
+    }
+
+    /// Wrap one page of results in the standard envelope
+    pub fn page<T>(&self, items: Vec<T>, total: u64) -> Page<T> {
+        let end = u64::from(self.offset) + items.len() as u64;
+        let next_cursor = (!items.is_empty() && end < total).then(|| encode_cursor(end as u32));
+        Page {
+            items,
+            total,
+            limit: self.limit,
+            offset: self.offset,
+            next_cursor,
+        }
+    }
+}
+
+#[axum::async_trait]
+impl<S, St> FromRequestParts<St> for Pagination<S>
+where
+    S: Sortable + Send,
+    St: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &St) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<PageQuery>::from_request_parts(parts, state).await.map_err(|e| {
+            let mut errors = FieldErrors::default();
+            errors.add("query", "format", e.body_text());
+            errors.into_result().unwrap_err().into_response()
+        })?;
+        Self::from_query(&query).map_err(IntoResponse::into_response)
+    }
+}
+
+/// Standard list envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Matching rows across all pages
+    pub total: u64,
+    pub limit: u32,
+    pub offset: u32,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Total from a `SELECT count() ... GROUP ALL` result
+pub fn count_total(rows: &[JsonValue]) -> u64 {
+    rows.first()
+        .and_then(|v| v.get("count"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+}
+
+fn encode_cursor(offset: u32) -> String {
+    URL_SAFE_NO_PAD.encode(format!("o:{}", offset))
+}
+
+fn decode_cursor(cursor: &str) -> Option<u32> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    String::from_utf8(bytes).ok()?.strip_prefix("o:")?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct ByName;
+
+    impl Sortable for ByName {
+        const SORT_FIELDS: &'static [&'static str] = &["name", "created_at"];
+        const DEFAULT_SORT: &'static str = "name";
+        const DEFAULT_ORDER: SortOrder = SortOrder::Asc;
+    }
+
+    #[test]
+    fn test_cursor_follows_previous_page() {
+        let first = Pagination::<ByName>::from_query(&PageQuery {
+            limit: Some(2),
+            ..Default::default()
+        })
+        .unwrap();
+        let page = first.page(vec!["a", "b"], 5);
+        assert_eq!(page.offset, 0);
+
+        let next = Pagination::<ByName>::from_query(&PageQuery {
+            limit: Some(2),
+            cursor: page.next_cursor,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(next.offset, 2);
+        assert_eq!(next.limit_clause(), "LIMIT 2 START 2");
+
+        let last = Pagination::<ByName>::from_query(&PageQuery {
+            offset: Some(4),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(last.page(vec!["e"], 5).next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_legacy_page_and_sort_validation() {
+        let legacy = Pagination::<ByName>::from_query(&PageQuery {
+            page: Some(3),
+            page_size: Some(20),
+            sort_by: Some("created_at".to_string()),
+            sort_order: Some(SortOrder::Desc),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!((legacy.limit, legacy.offset), (20, 40));
+        assert_eq!(legacy.order_clause(), "ORDER BY created_at DESC");
+
+        let rejected = Pagination::<ByName>::from_query(&PageQuery {
+            limit: Some(0),
+            cursor: Some("not-a-cursor".to_string()),
+            sort_by: Some("password_hash".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        let fields: Vec<&str> = rejected.0.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["limit", "cursor", "sort_by"]);
+    }
+}
@@ -0,0 +1,244 @@
+//! OpenTelemetry tracing
+//!
+//! Every `tracing` span, including the ones core-engine opens around
+//! parsing, sizing, document generation and vendor calls, is turned into an
+//! OpenTelemetry span. Spans are exported over OTLP when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set; without it trace ids are still
+//! generated so responses can be correlated with the local logs.
+//!
+//! `trace_requests` opens the server span for each request, continues an
+//! incoming W3C `traceparent` and returns the trace id in `x-trace-id`.
+//! Outbound HTTP calls made with [`TracedRequest::send_traced`] get a client
+//! span and pass the trace on in their own `traceparent`.
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use opentelemetry::global;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::{TraceContextExt, TraceId, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{self as sdktrace, Tracer, TracerProvider};
+use opentelemetry_sdk::Resource;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::{Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Response header carrying the trace id of the request
+pub const TRACE_ID_HEADER: HeaderName = HeaderName::from_static("x-trace-id");
+
+const DEFAULT_SERVICE_NAME: &str = "archer-backend";
+
+/// Tracer for the OpenTelemetry layer; exports over OTLP when an endpoint is
+/// configured and keeps spans in-process otherwise
+pub fn tracer() -> Tracer {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let service_name = std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| DEFAULT_SERVICE_NAME.to_string());
+    let resource = Resource::new(vec![
+        KeyValue::new("service.name", service_name.clone()),
+        KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+    ]);
+
+    if std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok() {
+        // The exporter reads the endpoint and headers from the standard
+        // OTEL_EXPORTER_OTLP_* variables
+        match opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic())
+            .with_trace_config(sdktrace::config().with_resource(resource.clone()))
+            .install_batch(opentelemetry_sdk::runtime::Tokio)
+        {
+            Ok(tracer) => return tracer,
+            Err(e) => eprintln!("⚠️ OTLP export disabled, failed to start exporter: {}", e),
+        }
+    }
+
+    let provider = TracerProvider::builder()
+        .with_config(sdktrace::config().with_resource(resource))
+        .build();
+    let tracer = provider.tracer(service_name);
+    global::set_tracer_provider(provider);
+    tracer
+}
+
+/// Flush spans still queued for export
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+/// Trace id of the current span, if it belongs to a trace
+pub fn current_trace_id() -> Option<String> {
+    trace_id_of(&Span::current()).map(|id| id.to_string())
+}
+
+fn trace_id_of(span: &Span) -> Option<TraceId> {
+    let trace_id = span.context().span().span_context().trace_id();
+    (trace_id != TraceId::INVALID).then_some(trace_id)
+}
+
+/// Server span for one request
+pub async fn trace_requests<B>(request: Request<B>, next: Next<B>) -> Response {
+    let parent = global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(request.headers())));
+
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = %format!("{} {}", request.method(), request.uri().path()),
+        otel.kind = "server",
+        http.method = %request.method(),
+        http.target = %request.uri().path(),
+        http.status_code = tracing::field::Empty,
+    );
+    span.set_parent(parent);
+    let trace_id = trace_id_of(&span);
+
+    let mut response = next.run(request).instrument(span.clone()).await;
+    span.record("http.status_code", response.status().as_u16());
+
+    if let Some(value) = trace_id.and_then(|id| HeaderValue::from_str(&id.to_string()).ok()) {
+        response.headers_mut().insert(TRACE_ID_HEADER, value);
+    }
+    response
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Client span for one outbound HTTP call to `peer` (e.g. `nutanix`). The
+/// query string is left out, as it can carry credentials.
+pub fn http_client_span(peer: &'static str, method: &reqwest::Method, url: &reqwest::Url) -> Span {
+    tracing::info_span!(
+        "http.client",
+        otel.name = %format!("{} {}", method, peer),
+        otel.kind = "client",
+        peer.service = peer,
+        http.method = %method,
+        http.url = %format!("{}{}", url.origin().ascii_serialization(), url.path()),
+        http.status_code = tracing::field::Empty,
+    )
+}
+
+/// Add the W3C `traceparent` of `span` to outbound request headers
+pub fn inject_trace_context(span: &Span, headers: &mut reqwest::header::HeaderMap) {
+    let context = span.context();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut HeaderInjector(headers)));
+}
+
+pub type SendFuture = Pin<Box<dyn Future<Output = reqwest::Result<reqwest::Response>> + Send>>;
+
+/// `.send_traced("veeam")` in place of `.send()`
+pub trait TracedRequest {
+    fn send_traced(self, peer: &'static str) -> SendFuture;
+}
+
+impl TracedRequest for reqwest::RequestBuilder {
+    fn send_traced(self, peer: &'static str) -> SendFuture {
+        let (client, request) = self.build_split();
+        Box::pin(async move {
+            let mut request = request?;
+            let span = http_client_span(peer, request.method(), request.url());
+            inject_trace_context(&span, request.headers_mut());
+
+            let response = client.execute(request).instrument(span.clone()).await;
+            if let Ok(response) = &response {
+                span.record("http.status_code", response.status().as_u16());
+            }
+            response
+        })
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut reqwest::header::HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            reqwest::header::HeaderName::from_bytes(key.as_bytes()),
+            reqwest::header::HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::api_response::ApiResponse;
+    use axum::body::Body;
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    const PARENT_TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+    /// Subscriber bridging spans to OpenTelemetry, as `main` installs it
+    fn subscriber() -> impl tracing::Subscriber + Send + Sync {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = TracerProvider::builder().build().tracer("test");
+        tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+
+    async fn get(traceparent: Option<&str>) -> (Option<String>, serde_json::Value) {
+        let app = axum::Router::new()
+            .route("/", axum::routing::get(|| async { ApiResponse::success("ok") }))
+            .layer(axum::middleware::from_fn(trace_requests));
+
+        let mut request = axum::http::Request::builder().uri("/");
+        if let Some(traceparent) = traceparent {
+            request = request.header("traceparent", traceparent);
+        }
+        let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+
+        let header = response
+            .headers()
+            .get(TRACE_ID_HEADER)
+            .map(|value| value.to_str().unwrap().to_string());
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (header, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn response_carries_the_request_trace_id() {
+        let _guard = tracing::subscriber::set_default(subscriber());
+
+        let (header, body) = get(None).await;
+        let trace_id = header.expect("x-trace-id header");
+        assert_eq!(trace_id.len(), 32);
+        assert_eq!(body["trace_id"], serde_json::json!(trace_id));
+    }
+
+    #[tokio::test]
+    async fn incoming_traceparent_is_continued() {
+        let _guard = tracing::subscriber::set_default(subscriber());
+
+        let traceparent = format!("00-{}-00f067aa0ba902b7-01", PARENT_TRACE_ID);
+        let (header, body) = get(Some(&traceparent)).await;
+        assert_eq!(header.as_deref(), Some(PARENT_TRACE_ID));
+        assert_eq!(body["trace_id"], serde_json::json!(PARENT_TRACE_ID));
+    }
+
+    #[test]
+    fn outbound_requests_carry_the_current_trace() {
+        let _guard = tracing::subscriber::set_default(subscriber());
+
+        let url = reqwest::Url::parse("https://prism.example:9440/api/nutanix/v3/vms/list?token=secret").unwrap();
+        let span = http_client_span("nutanix", &reqwest::Method::POST, &url);
+        let mut headers = reqwest::header::HeaderMap::new();
+        inject_trace_context(&span, &mut headers);
+
+        let traceparent = headers.get("traceparent").unwrap().to_str().unwrap();
+        let trace_id = trace_id_of(&span).unwrap().to_string();
+        assert!(traceparent.starts_with(&format!("00-{}-", trace_id)));
+    }
+}
@@ -221,6 +221,7 @@ mod enhanced_rvtools_integration_tests {
             uploaded_at: chrono::Utc::now(),
             processed_at: Some(chrono::Utc::now()),
             uploaded_by: "test-user".to_string(),
+            import_report: None,
         };
 
         let _: Vec<RvToolsUpload> = db
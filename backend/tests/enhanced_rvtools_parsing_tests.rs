@@ -200,6 +200,7 @@ mod enhanced_rvtools_parsing_tests {
             total_clusters: 0,
             processing_errors: Vec::new(),
             warnings: Vec::new(),
+            report: Default::default(),
             storage_analysis: None,
             s2d_compliance: HashMap::new(),
         };
@@ -0,0 +1,239 @@
+//! Struct vs columnar environment model on a synthetic 100k-VM estate
+//!
+//! Run with `cargo bench -p core-engine --bench columnar`. Besides the
+//! timings, the bench prints an estimate of the heap each model holds.
+//! The `session_*` cases time an interactive sizing session recalculating
+//! after a slider move, with and without a cached packing.
+
+use std::collections::HashMap;
+use std::mem::size_of;
+
+use chrono::Utc;
+use core_engine::analysis::AnalysisEngine;
+use core_engine::columnar::ColumnarEnvironment;
+use core_engine::models::*;
+use core_engine::rvtools_generator::{generate, GeneratorConfig, SyntheticVm};
+use core_engine::sizing::{HardwareBasket, SizingEngine, SizingParameters};
+use core_engine::sizing_session::{SizingDelta, SizingSession};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use uuid::Uuid;
+
+const VMS: u32 = 100_000;
+
+fn to_vm(synthetic: &SyntheticVm) -> VirtualMachine {
+    let power_state = match synthetic.power_state.as_str() {
+        "poweredOn" => PowerState::PoweredOn,
+        "suspended" => PowerState::Suspended,
+        _ => PowerState::PoweredOff,
+    };
+    VirtualMachine {
+        name: synthetic.name.clone(),
+        cluster_name: synthetic.cluster.clone(),
+        host_name: synthetic.host.clone(),
+        power_state: power_state.clone(),
+        num_vcpu: synthetic.cpus,
+        memory_gb: (synthetic.memory_mb / 1024) as u32,
+        guest_os: Some(synthetic.guest_os.clone()),
+        vm_version: Some(synthetic.vm_version.clone()),
+        tools_status: Some(synthetic.tools_status.clone()),
+        tools_version: None,
+        is_template: synthetic.template,
+        disks: synthetic
+            .disks
+            .iter()
+            .map(|d| VirtualDisk {
+                vm_name: synthetic.name.clone(),
+                disk_label: d.label.clone(),
+                provisioned_gb: d.capacity_mb as f64 / 1024.0,
+                consumed_in_guest_gb: d.consumed_mb as f64 / 1024.0,
+                consumed_on_datastore_gb: if d.thin { d.consumed_mb } else { d.capacity_mb } as f64 / 1024.0,
+                is_rdm: d.raw,
+                disk_mode: None,
+                provisioning_type: if d.thin { ProvisioningType::Thin } else { ProvisioningType::Thick },
+                datastore_name: Some(d.datastore.clone()),
+            })
+            .collect(),
+        nics: Vec::new(),
+        notes: None,
+        annotation: Some(synthetic.annotation.clone()),
+        folder: Some(synthetic.folder.clone()),
+        resource_pool: None,
+        created_date: None,
+        last_powered_on: None,
+        special_flags: VmSpecialFlags {
+            is_zombie: power_state == PowerState::PoweredOff,
+            ..Default::default()
+        },
+    }
+}
+
+fn large_environment() -> VsphereEnvironment {
+    let synthetic = generate(&GeneratorConfig {
+        clusters: 40,
+        hosts: 2_000,
+        vms: VMS,
+        ..Default::default()
+    })
+    .expect("generator config is valid");
+
+    let mut by_cluster: HashMap<&str, Vec<VirtualMachine>> = HashMap::new();
+    for vm in &synthetic.vms {
+        by_cluster.entry(vm.cluster.as_str()).or_default().push(to_vm(vm));
+    }
+
+    let clusters: Vec<Cluster> = synthetic
+        .clusters
+        .iter()
+        .map(|name| {
+            let vms = by_cluster.remove(name.as_str()).unwrap_or_default();
+            let hosts = synthetic.hosts.iter().filter(|h| &h.cluster == name).count();
+            let total_pcpu_cores: u32 = synthetic
+                .hosts
+                .iter()
+                .filter(|h| &h.cluster == name)
+                .map(|h| h.sockets * h.cores)
+                .sum();
+            let total_vcpus: u32 = vms.iter().map(|v| v.num_vcpu).sum();
+            Cluster {
+                name: name.clone(),
+                hosts: Vec::new(),
+                metrics: ClusterMetrics {
+                    total_hosts: hosts,
+                    total_vms: vms.len(),
+                    total_pcpu_cores,
+                    total_vcpus,
+                    current_vcpu_pcpu_ratio: total_vcpus as f32 / total_pcpu_cores.max(1) as f32,
+                    total_memory_gb: hosts as u32 * 1024,
+                    provisioned_memory_gb: vms.iter().map(|v| v.memory_gb as f64).sum(),
+                    memory_overcommit_ratio: 0.0,
+                    total_storage_gb: vms.iter().flat_map(|v| &v.disks).map(|d| d.provisioned_gb).sum(),
+                    consumed_storage_gb: vms.iter().flat_map(|v| &v.disks).map(|d| d.consumed_in_guest_gb).sum(),
+                },
+                health_status: ClusterHealth {
+                    zombie_vms: vms.iter().filter(|v| v.special_flags.is_zombie).map(|v| v.name.clone()).collect(),
+                    outdated_tools: Vec::new(),
+                    rdm_vms: Vec::new(),
+                    ft_enabled_vms: Vec::new(),
+                    warnings: Vec::new(),
+                },
+                vms,
+            }
+        })
+        .collect();
+
+    let total_vcpus: u32 = clusters.iter().map(|c| c.metrics.total_vcpus).sum();
+    let total_pcores: u32 = clusters.iter().map(|c| c.metrics.total_pcpu_cores).sum();
+    let provisioned_memory_gb: f64 = clusters.iter().map(|c| c.metrics.provisioned_memory_gb).sum();
+    let summary_metrics = EnvironmentSummary {
+        total_vcpus,
+        total_pcores,
+        total_provisioned_memory_gb: provisioned_memory_gb,
+        total_consumed_memory_gb: provisioned_memory_gb,
+        total_provisioned_storage_gb: clusters.iter().map(|c| c.metrics.total_storage_gb).sum(),
+        total_consumed_storage_gb: clusters.iter().map(|c| c.metrics.consumed_storage_gb).sum(),
+        overall_vcpu_pcpu_ratio: total_vcpus as f32 / total_pcores.max(1) as f32,
+        health_issues: Vec::new(),
+    };
+
+    VsphereEnvironment {
+        id: Uuid::new_v4(),
+        name: "bench".to_string(),
+        parsed_at: Utc::now(),
+        total_vms: VMS as usize,
+        total_hosts: synthetic.hosts.len(),
+        clusters,
+        standalone_hosts: Vec::new(),
+        summary_metrics,
+        import_report: ImportReport::default(),
+    }
+}
+
+/// Rough heap held by the VM structs: the structs themselves plus their strings and disks
+fn struct_heap_bytes(environment: &VsphereEnvironment) -> usize {
+    let text = |s: &Option<String>| s.as_ref().map_or(0, |s| s.capacity());
+    environment
+        .clusters
+        .iter()
+        .flat_map(|c| &c.vms)
+        .map(|vm| {
+            size_of::<VirtualMachine>()
+                + vm.name.capacity()
+                + vm.cluster_name.capacity()
+                + vm.host_name.capacity()
+                + text(&vm.guest_os)
+                + text(&vm.vm_version)
+                + text(&vm.tools_status)
+                + text(&vm.annotation)
+                + text(&vm.folder)
+                + vm.disks
+                    .iter()
+                    .map(|d| {
+                        size_of::<VirtualDisk>() + d.vm_name.capacity() + d.disk_label.capacity() + text(&d.datastore_name)
+                    })
+                    .sum::<usize>()
+        })
+        .sum()
+}
+
+fn bench_columnar(c: &mut Criterion) {
+    let environment = large_environment();
+    let columnar = ColumnarEnvironment::from_environment(&environment);
+    let profile = HardwareBasket::new().get_profiles()[0].clone();
+    let parameters = SizingParameters::default();
+
+    println!(
+        "heap for {} VMs: structs ~{} MiB, columnar ~{} MiB",
+        columnar.vms.len(),
+        struct_heap_bytes(&environment) / (1024 * 1024),
+        columnar.heap_bytes() / (1024 * 1024)
+    );
+
+    let mut group = c.benchmark_group("100k_vms");
+    group.sample_size(10);
+
+    group.bench_function("build_columnar", |b| {
+        b.iter(|| ColumnarEnvironment::from_environment(black_box(&environment)))
+    });
+    group.bench_function("analyze_structs", |b| {
+        b.iter(|| AnalysisEngine::analyze_environment(black_box(&environment)).unwrap())
+    });
+    group.bench_function("analyze_columnar", |b| {
+        b.iter(|| AnalysisEngine::analyze_columnar(black_box(&columnar)).unwrap())
+    });
+    group.bench_function("size_structs", |b| {
+        b.iter(|| {
+            let all_vms: Vec<VirtualMachine> = environment.clusters.iter().flat_map(|c| c.vms.clone()).collect();
+            SizingEngine::calculate_sizing(black_box(&all_vms), &profile, &parameters).unwrap()
+        })
+    });
+    group.bench_function("size_columnar", |b| {
+        b.iter(|| {
+            SizingEngine::calculate_sizing_columnar(
+                black_box(&columnar.vms),
+                columnar.clustered_rows(),
+                &profile,
+                &parameters,
+            )
+            .unwrap()
+        })
+    });
+
+    let mut session = SizingSession::new(&environment, profile.clone(), parameters.clone()).unwrap();
+    let mut step = 0u32;
+    let mut slide = |session: &mut SizingSession, positions: u32| {
+        step += 1;
+        let delta = SizingDelta {
+            target_vcpu_pcpu_ratio: Some(2.0 + (step % positions) as f32 * 0.05),
+            ..Default::default()
+        };
+        session.apply(&delta).unwrap();
+        session.calculate(false).unwrap()
+    };
+    group.bench_function("session_cached", |b| b.iter(|| slide(&mut session, 2)));
+    group.bench_function("session_repack", |b| b.iter(|| slide(&mut session, 1_000)));
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_columnar);
+criterion_main!(benches);
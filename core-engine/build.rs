@@ -0,0 +1,15 @@
+// Generates the collector gRPC client from the proto the backend serves.
+// Only the `collector` feature needs it.
+
+fn main() {
+    #[cfg(feature = "collector")]
+    {
+        println!("cargo:rerun-if-changed=../proto/collector.proto");
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::configure()
+            .build_server(false)
+            .compile(&["../proto/collector.proto"], &["../proto"])
+            .expect("compile collector.proto");
+    }
+}
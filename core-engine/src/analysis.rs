@@ -1,45 +1,111 @@
+use crate::columnar::{vm_flags, ColumnarEnvironment};
 use crate::models::*;
 use crate::Result;
 
 /// Analysis engine for "as-is" environment assessment
 pub struct AnalysisEngine;
 
+/// What the analysis reads from one cluster, from either environment model
+struct ClusterView<'a> {
+    name: &'a str,
+    metrics: &'a ClusterMetrics,
+    host_count: usize,
+    vm_count: usize,
+    zombie_vms: Vec<&'a str>,
+    oversized_vms: usize,
+    thick_disks: usize,
+}
+
+fn is_oversized(vcpu: u32, memory_gb: u32) -> bool {
+    vcpu > 8 || memory_gb > 64
+}
+
 impl AnalysisEngine {
     /// Perform comprehensive analysis of the vSphere environment
+    #[tracing::instrument(name = "analysis.analyze_environment", skip_all, fields(clusters = environment.clusters.len()))]
     pub fn analyze_environment(environment: &VsphereEnvironment) -> Result<AnalysisReport> {
-        let capacity_analysis = Self::analyze_capacity(environment);
-        let performance_analysis = Self::analyze_performance(environment);
-        let health_analysis = Self::analyze_health(environment);
-        let optimization_recommendations = Self::generate_optimization_recommendations(environment);
-
-        Ok(AnalysisReport {
-            environment_id: environment.id,
-            capacity_analysis,
-            performance_analysis,
-            health_analysis,
-            optimization_recommendations,
+        let clusters: Vec<ClusterView> = environment
+            .clusters
+            .iter()
+            .map(|cluster| ClusterView {
+                name: &cluster.name,
+                metrics: &cluster.metrics,
+                host_count: cluster.hosts.len(),
+                vm_count: cluster.vms.len(),
+                zombie_vms: cluster.health_status.zombie_vms.iter().map(String::as_str).collect(),
+                oversized_vms: cluster.vms.iter().filter(|vm| is_oversized(vm.num_vcpu, vm.memory_gb)).count(),
+                thick_disks: cluster
+                    .vms
+                    .iter()
+                    .flat_map(|vm| &vm.disks)
+                    .filter(|disk| matches!(disk.provisioning_type, ProvisioningType::Thick))
+                    .count(),
+            })
+            .collect();
+
+        Ok(Self::analyze_clusters(environment.id, &clusters, &environment.summary_metrics))
+    }
+
+    /// Same analysis over the columnar model, for very large estates
+    #[tracing::instrument(name = "analysis.analyze_columnar", skip_all, fields(clusters = environment.clusters.len(), vms = environment.vms.len()))]
+    pub fn analyze_columnar(environment: &ColumnarEnvironment) -> Result<AnalysisReport> {
+        let vms = &environment.vms;
+        let clusters: Vec<ClusterView> = environment
+            .clusters
+            .iter()
+            .map(|cluster| {
+                let rows = cluster.rows.clone();
+                ClusterView {
+                    name: &cluster.name,
+                    metrics: &cluster.metrics,
+                    host_count: cluster.host_count,
+                    vm_count: rows.len(),
+                    zombie_vms: rows
+                        .clone()
+                        .filter(|&row| vms.has_flag(row, vm_flags::ZOMBIE))
+                        .map(|row| vms.name(row))
+                        .collect(),
+                    oversized_vms: rows
+                        .clone()
+                        .filter(|&row| is_oversized(vms.vcpu[row], vms.memory_gb[row]))
+                        .count(),
+                    thick_disks: vms.thick_disks[rows].iter().map(|&n| n as usize).sum(),
+                }
+            })
+            .collect();
+
+        Ok(Self::analyze_clusters(environment.id, &clusters, &environment.summary_metrics))
+    }
+
+    fn analyze_clusters(environment_id: uuid::Uuid, clusters: &[ClusterView], summary: &EnvironmentSummary) -> AnalysisReport {
+        AnalysisReport {
+            environment_id,
+            capacity_analysis: Self::analyze_capacity(clusters),
+            performance_analysis: Self::analyze_performance(clusters),
+            health_analysis: Self::analyze_health(clusters, summary),
+            optimization_recommendations: Self::generate_optimization_recommendations(clusters),
             generated_at: chrono::Utc::now(),
-        })
+        }
     }
 
     /// Analyze capacity utilization and trends
-    fn analyze_capacity(environment: &VsphereEnvironment) -> CapacityAnalysis {
+    fn analyze_capacity(clusters: &[ClusterView]) -> CapacityAnalysis {
         let mut cluster_utilization = Vec::new();
 
-        for cluster in &environment.clusters {
+        for cluster in clusters {
             let cpu_utilization = Self::calculate_cpu_utilization(cluster);
             let memory_utilization = Self::calculate_memory_utilization(cluster);
             let storage_utilization = Self::calculate_storage_utilization(cluster);
 
             cluster_utilization.push(ClusterUtilization {
-                cluster_name: cluster.name.clone(),
+                cluster_name: cluster.name.to_string(),
                 cpu_utilization_percent: cpu_utilization,
                 memory_utilization_percent: memory_utilization,
                 storage_utilization_percent: storage_utilization,
                 vcpu_pcpu_ratio: cluster.metrics.current_vcpu_pcpu_ratio,
                 memory_overcommit_ratio: cluster.metrics.memory_overcommit_ratio,
-                host_count: cluster.hosts.len(),
-                vm_count: cluster.vms.len(),
+                host_count: cluster.host_count,
+                vm_count: cluster.vm_count,
             });
         }
 
@@ -54,7 +120,7 @@ impl AnalysisEngine {
     }
 
     /// Calculate CPU utilization for a cluster
-    fn calculate_cpu_utilization(cluster: &Cluster) -> f32 {
+    fn calculate_cpu_utilization(cluster: &ClusterView) -> f32 {
         if cluster.metrics.total_pcpu_cores == 0 {
             return 0.0;
         }
@@ -65,7 +131,7 @@ impl AnalysisEngine {
     }
 
     /// Calculate memory utilization for a cluster
-    fn calculate_memory_utilization(cluster: &Cluster) -> f32 {
+    fn calculate_memory_utilization(cluster: &ClusterView) -> f32 {
         if cluster.metrics.total_memory_gb == 0 {
             return 0.0;
         }
@@ -74,7 +140,7 @@ impl AnalysisEngine {
     }
 
     /// Calculate storage utilization for a cluster
-    fn calculate_storage_utilization(cluster: &Cluster) -> f32 {
+    fn calculate_storage_utilization(cluster: &ClusterView) -> f32 {
         if cluster.metrics.total_storage_gb == 0.0 {
             return 0.0;
         }
@@ -201,12 +267,12 @@ impl AnalysisEngine {
     }
 
     /// Analyze performance characteristics
-    fn analyze_performance(environment: &VsphereEnvironment) -> PerformanceAnalysis {
+    fn analyze_performance(clusters: &[ClusterView]) -> PerformanceAnalysis {
         let mut cluster_performance = Vec::new();
 
-        for cluster in &environment.clusters {
+        for cluster in clusters {
             let performance = ClusterPerformance {
-                cluster_name: cluster.name.clone(),
+                cluster_name: cluster.name.to_string(),
                 avg_vcpu_per_vm: cluster.metrics.total_vcpus as f32 / cluster.metrics.total_vms as f32,
                 avg_memory_per_vm_gb: cluster.metrics.provisioned_memory_gb / cluster.metrics.total_vms as f64,
                 vcpu_pcpu_ratio: cluster.metrics.current_vcpu_pcpu_ratio,
@@ -225,7 +291,7 @@ impl AnalysisEngine {
     }
 
     /// Calculate performance score for a cluster
-    fn calculate_performance_score(cluster: &Cluster) -> f32 {
+    fn calculate_performance_score(cluster: &ClusterView) -> f32 {
         let mut score = 100.0;
 
         // Penalize high vCPU:pCPU ratios
@@ -251,7 +317,7 @@ impl AnalysisEngine {
     }
 
     /// Identify performance bottlenecks
-    fn identify_bottlenecks(cluster: &Cluster) -> Vec<String> {
+    fn identify_bottlenecks(cluster: &ClusterView) -> Vec<String> {
         let mut bottlenecks = Vec::new();
 
         if cluster.metrics.current_vcpu_pcpu_ratio > 6.0 {
@@ -262,7 +328,7 @@ impl AnalysisEngine {
             bottlenecks.push("Memory overcommitment may trigger ballooning or swapping".to_string());
         }
 
-        if cluster.hosts.len() < 3 {
+        if cluster.host_count < 3 {
             bottlenecks.push("Small cluster size limits HA capabilities".to_string());
         }
 
@@ -293,17 +359,17 @@ impl AnalysisEngine {
     }
 
     /// Analyze health status
-    fn analyze_health(environment: &VsphereEnvironment) -> HealthAnalysis {
-        let mut all_issues = environment.summary_metrics.health_issues.clone();
+    fn analyze_health(clusters: &[ClusterView], summary: &EnvironmentSummary) -> HealthAnalysis {
+        let mut all_issues = summary.health_issues.clone();
 
         // Add cluster-specific health issues
-        for cluster in &environment.clusters {
-            for vm_name in &cluster.health_status.zombie_vms {
+        for cluster in clusters {
+            for vm_name in &cluster.zombie_vms {
                 all_issues.push(HealthIssue {
                     severity: Severity::Warning,
                     category: "Resource Optimization".to_string(),
                     description: format!("Zombie VM '{}' consuming resources unnecessarily", vm_name),
-                    affected_vm: Some(vm_name.clone()),
+                    affected_vm: Some(vm_name.to_string()),
                     affected_host: None,
                     recommendation: "Remove or power on if needed".to_string(),
                 });
@@ -319,7 +385,7 @@ impl AnalysisEngine {
             warning_issues: warning_count,
             info_issues: all_issues.iter().filter(|i| matches!(i.severity, Severity::Info)).count(),
             health_issues: all_issues,
-            remediation_plan: Self::generate_remediation_plan(&environment.summary_metrics.health_issues),
+            remediation_plan: Self::generate_remediation_plan(&summary.health_issues),
         }
     }
 
@@ -408,12 +474,12 @@ impl AnalysisEngine {
     }
 
     /// Generate optimization recommendations
-    fn generate_optimization_recommendations(environment: &VsphereEnvironment) -> Vec<OptimizationRecommendation> {
+    fn generate_optimization_recommendations(clusters: &[ClusterView]) -> Vec<OptimizationRecommendation> {
         let mut recommendations = Vec::new();
 
-        for cluster in &environment.clusters {
+        for cluster in clusters {
             // Check for unbalanced clusters
-            if cluster.hosts.len() == 2 {
+            if cluster.host_count == 2 {
                 recommendations.push(OptimizationRecommendation {
                     category: "High Availability".to_string(),
                     priority: Priority::Medium,
@@ -425,26 +491,21 @@ impl AnalysisEngine {
             }
 
             // Check for oversized VMs
-            let oversized_vms: Vec<_> = cluster.vms.iter()
-                .filter(|vm| vm.num_vcpu > 8 || vm.memory_gb > 64)
-                .collect();
+            let oversized_vms = cluster.oversized_vms;
 
-            if !oversized_vms.is_empty() {
+            if oversized_vms > 0 {
                 recommendations.push(OptimizationRecommendation {
                     category: "Resource Optimization".to_string(),
                     priority: Priority::Low,
-                    description: format!("Found {} oversized VMs in cluster '{}'", oversized_vms.len(), cluster.name),
+                    description: format!("Found {} oversized VMs in cluster '{}'", oversized_vms, cluster.name),
                     recommendation: "Review VM sizing and right-size if possible".to_string(),
-                    estimated_savings: Some(oversized_vms.len() as f64 * 500.0), // Estimated cost savings
+                    estimated_savings: Some(oversized_vms as f64 * 500.0), // Estimated cost savings
                     implementation_effort: "Low".to_string(),
                 });
             }
 
             // Check for thin provisioning opportunities
-            let thick_disks: usize = cluster.vms.iter()
-                .flat_map(|vm| &vm.disks)
-                .filter(|disk| matches!(disk.provisioning_type, ProvisioningType::Thick))
-                .count();
+            let thick_disks = cluster.thick_disks;
 
             if thick_disks > 0 {
                 recommendations.push(OptimizationRecommendation {
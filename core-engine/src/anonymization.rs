@@ -0,0 +1,521 @@
+//! Anonymization of customer data in shared documents
+//!
+//! HLDs, analysis exports and workbooks sent to vendors must not carry the
+//! customer's VM names, hostnames, IPv4 addresses or datastore names. Each
+//! value is replaced by a pseudonym derived from a per-installation secret,
+//! so the same VM gets the same pseudonym in every export. The mapping of an
+//! export is written to a local file and never leaves the installation; it
+//! is used to translate vendor feedback back to the real names.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::CoreEngineError;
+use crate::Result;
+
+/// Top-level domains that are never public, so any FQDN under them is
+/// treated as a customer hostname even if it was not seen in a host field
+pub(crate) const INTERNAL_TLDS: &[&str] = &["local", "localdomain", "lan", "corp", "internal", "intranet", "home", "ad"];
+
+/// Suffix of pseudonymous domains; reserved so it can never resolve
+const PSEUDONYM_DOMAIN_SUFFIX: &str = "invalid";
+
+const IPV4: &str = r"\b(?:\d{1,3}\.){3}\d{1,3}\b";
+static MAP_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9-]{1,64}$").unwrap());
+
+/// Kind of customer identifier a pseudonym stands for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum PseudonymKind {
+    VmName,
+    Hostname,
+    IpAddress,
+    Datastore,
+}
+
+impl PseudonymKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            PseudonymKind::VmName => "vm",
+            PseudonymKind::Hostname => "host",
+            PseudonymKind::IpAddress => "ip",
+            PseudonymKind::Datastore => "ds",
+        }
+    }
+
+    /// Kind of identifier a field holds, judged from its name.
+    /// Matches RVTools column headers as well as snake_case model fields.
+    pub fn for_field(name: &str) -> Option<Self> {
+        let name: String = name
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        if name == "ip" || name == "ips" || name.ends_with("_ip") || name.contains("ip_address") {
+            Some(PseudonymKind::IpAddress)
+        } else if name.contains("datastore") {
+            Some(PseudonymKind::Datastore)
+        } else if matches!(name.as_str(), "vm" | "vms" | "vm_name" | "vm_names" | "virtual_machine") {
+            Some(PseudonymKind::VmName)
+        } else if matches!(name.as_str(), "host" | "hosts" | "esxi_host" | "dns_name" | "fqdn")
+            || name.contains("hostname")
+            || name.contains("host_name")
+        {
+            Some(PseudonymKind::Hostname)
+        } else {
+            None
+        }
+    }
+}
+
+/// Settings for an anonymization pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnonymizationConfig {
+    /// Key for pseudonym derivation; keep it stable to keep pseudonyms stable
+    pub secret: String,
+    /// Values left as they are. Case-insensitive; `*` matches any run of
+    /// characters, e.g. `*.vmware.com` or `10.0.0.*`
+    #[serde(default)]
+    pub preserve: Vec<String>,
+}
+
+impl AnonymizationConfig {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self {
+            secret: secret.into(),
+            preserve: Vec::new(),
+        }
+    }
+
+    pub fn with_preserve(mut self, patterns: impl IntoIterator<Item = String>) -> Self {
+        self.preserve.extend(patterns);
+        self
+    }
+
+    pub fn is_preserved(&self, value: &str) -> bool {
+        let value = value.trim().to_lowercase();
+        self.preserve
+            .iter()
+            .any(|pattern| wildcard_match(&pattern.trim().to_lowercase(), &value))
+    }
+}
+
+/// One original value and the pseudonym it was replaced with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MappingEntry {
+    pub kind: PseudonymKind,
+    pub original: String,
+    pub pseudonym: String,
+}
+
+/// Pseudonyms used by one export, kept locally to reverse the anonymization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnonymizationMap {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub entries: Vec<MappingEntry>,
+}
+
+impl AnonymizationMap {
+    /// Location of a map file; ids are restricted so they cannot leave `dir`
+    pub fn path_in(dir: &Path, id: &str) -> Result<PathBuf> {
+        if !MAP_ID.is_match(id) {
+            return Err(CoreEngineError::validation(format!("Invalid anonymization map id '{}'", id)));
+        }
+        Ok(dir.join(format!("{}.json", id)))
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        let path = Self::path_in(dir, &self.id)?;
+        fs::create_dir_all(dir)
+            .map_err(|e| CoreEngineError::io(format!("Failed to create {}: {}", dir.display(), e)))?;
+        fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .map_err(|e| CoreEngineError::io(format!("Failed to write {}: {}", path.display(), e)))?;
+        Ok(path)
+    }
+
+    pub fn load(dir: &Path, id: &str) -> Result<Self> {
+        let path = Self::path_in(dir, id)?;
+        let bytes = fs::read(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => CoreEngineError::not_found(format!("Anonymization map '{}' not found", id)),
+            _ => CoreEngineError::io(format!("Failed to read {}: {}", path.display(), e)),
+        })?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Replace every pseudonym in `text` with the original value
+    pub fn deanonymize_text(&self, text: &str) -> String {
+        let lookup: HashMap<String, &str> = self
+            .entries
+            .iter()
+            .map(|e| (e.pseudonym.to_lowercase(), e.original.as_str()))
+            .collect();
+        let pattern = match alternation(self.entries.iter().map(|e| e.pseudonym.as_str())) {
+            Some(names) => names,
+            None => return text.to_string(),
+        };
+        let re = Regex::new(&format!("(?i){}", pattern)).expect("escaped alternation is a valid regex");
+        re.replace_all(text, |caps: &regex::Captures| {
+            let found = &caps[0];
+            lookup.get(&found.to_lowercase()).map(|s| s.to_string()).unwrap_or_else(|| found.to_string())
+        })
+        .into_owned()
+    }
+}
+
+/// Replaces customer identifiers with deterministic pseudonyms and records
+/// every replacement it makes
+pub struct Anonymizer {
+    config: AnonymizationConfig,
+    /// (kind, lowercased original) to entry
+    entries: BTreeMap<(PseudonymKind, String), MappingEntry>,
+    /// Lowercased original to pseudonym, for free-text replacement
+    lookup: HashMap<String, String>,
+    used: HashSet<String>,
+    /// Lowercased domain to pseudonymous domain
+    domains: HashMap<String, String>,
+    /// First three octets of a real network to those of its pseudonym
+    networks: HashMap<[u8; 3], [u8; 3]>,
+}
+
+impl Anonymizer {
+    pub fn new(config: AnonymizationConfig) -> Self {
+        Self {
+            config,
+            entries: BTreeMap::new(),
+            lookup: HashMap::new(),
+            used: HashSet::new(),
+            domains: HashMap::new(),
+            networks: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &AnonymizationConfig {
+        &self.config
+    }
+
+    /// Pseudonym for a value, or the value itself if it is preserved or
+    /// carries no customer information (loopback, netmask, empty)
+    pub fn pseudonym(&mut self, kind: PseudonymKind, original: &str) -> String {
+        let value = original.trim();
+        if value.is_empty() || self.config.is_preserved(value) {
+            return original.to_string();
+        }
+        let key = (kind, value.to_lowercase());
+        if let Some(entry) = self.entries.get(&key) {
+            return entry.pseudonym.clone();
+        }
+
+        let pseudonym = match kind {
+            PseudonymKind::IpAddress => match value.parse::<Ipv4Addr>() {
+                Ok(ip) if is_customer_address(&ip) => self.ip_pseudonym(&ip),
+                Ok(_) => return original.to_string(),
+                Err(_) => self.unique_name(kind, value),
+            },
+            PseudonymKind::Hostname => match value.split_once('.') {
+                Some((_, domain)) if !domain.is_empty() && self.config.is_preserved(domain) => {
+                    format!("{}.{}", self.unique_name(kind, value), domain)
+                }
+                Some((host, domain)) if !domain.is_empty() && !host.is_empty() => {
+                    let domain = self.domain_pseudonym(domain);
+                    format!("{}.{}", self.unique_name(kind, value), domain)
+                }
+                _ => self.unique_name(kind, value),
+            },
+            _ => self.unique_name(kind, value),
+        };
+        self.record(kind, value, pseudonym.clone());
+        pseudonym
+    }
+
+    /// Learn a value up front so later free text mentioning it is replaced
+    pub fn register(&mut self, kind: PseudonymKind, value: &str) {
+        self.pseudonym(kind, value);
+    }
+
+    /// Replace known values, IPv4 addresses and internal FQDNs in free text
+    pub fn anonymize_text(&mut self, text: &str) -> String {
+        let mut domains: Vec<String> = self.domains.keys().map(|d| regex::escape(d)).collect();
+        domains.extend(INTERNAL_TLDS.iter().map(|t| t.to_string()));
+        let fqdn = format!(
+            r"\b[a-z0-9](?:[a-z0-9-]*[a-z0-9])?(?:\.[a-z0-9-]+)*\.(?:{})\b",
+            domains.join("|")
+        );
+        // FQDNs come first so a known short name does not split a longer host
+        let mut pattern = format!("(?i){}|{}", fqdn, IPV4);
+        let known: Vec<String> = self.lookup.keys().cloned().collect();
+        if let Some(names) = alternation(known.iter().map(|s| s.as_str())) {
+            pattern.push('|');
+            pattern.push_str(&names);
+        }
+        let re = Regex::new(&pattern).expect("escaped alternation is a valid regex");
+
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        let matches: Vec<(usize, usize)> = re.find_iter(text).map(|m| (m.start(), m.end())).collect();
+        for (start, end) in matches {
+            let found = &text[start..end];
+            let replacement = if let Some(pseudonym) = self.lookup.get(&found.to_lowercase()) {
+                pseudonym.clone()
+            } else if found.parse::<Ipv4Addr>().is_ok() {
+                self.pseudonym(PseudonymKind::IpAddress, found)
+            } else if found.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                // Version strings and other dotted numbers that are not addresses
+                found.to_string()
+            } else {
+                self.pseudonym(PseudonymKind::Hostname, found)
+            };
+            out.push_str(&text[last..start]);
+            out.push_str(&replacement);
+            last = end;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+
+    /// Anonymize a JSON document. Fields named like identifiers are replaced
+    /// as a whole; every other string gets the free-text pass. All fields are
+    /// registered first so names are also caught in free text that precedes them.
+    pub fn anonymize_json(&mut self, value: &mut Value) {
+        self.anonymize_field("", value);
+    }
+
+    /// Anonymize the value of a single named field, e.g. an HLD variable
+    pub fn anonymize_field(&mut self, field: &str, value: &mut Value) {
+        self.register_field(field, value);
+        self.rewrite_field(field, value);
+    }
+
+    fn register_field(&mut self, field: &str, value: &Value) {
+        match value {
+            Value::String(s) => match PseudonymKind::for_field(field) {
+                Some(PseudonymKind::IpAddress) | None => {}
+                Some(kind) => self.register(kind, s),
+            },
+            Value::Array(items) => items.iter().for_each(|item| self.register_field(field, item)),
+            Value::Object(map) => map.iter().for_each(|(key, item)| self.register_field(key, item)),
+            _ => {}
+        }
+    }
+
+    fn rewrite_field(&mut self, field: &str, value: &mut Value) {
+        match value {
+            Value::String(s) => {
+                *s = match PseudonymKind::for_field(field) {
+                    Some(PseudonymKind::IpAddress) | None => self.anonymize_text(s),
+                    Some(kind) => self.pseudonym(kind, s),
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.rewrite_field(field, item)),
+            Value::Object(map) => map.iter_mut().for_each(|(key, item)| self.rewrite_field(key, item)),
+            _ => {}
+        }
+    }
+
+    /// Replacements made so far, to be saved next to the export
+    pub fn mapping(&self) -> AnonymizationMap {
+        AnonymizationMap {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+            entries: self.entries.values().cloned().collect(),
+        }
+    }
+
+    fn record(&mut self, kind: PseudonymKind, original: &str, pseudonym: String) {
+        self.lookup.entry(original.to_lowercase()).or_insert_with(|| pseudonym.clone());
+        self.used.insert(pseudonym.to_lowercase());
+        self.entries.insert(
+            (kind, original.to_lowercase()),
+            MappingEntry {
+                kind,
+                original: original.to_string(),
+                pseudonym,
+            },
+        );
+    }
+
+    fn digest(&self, scope: &str, value: &str, attempt: u32) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.config.secret.as_bytes());
+        hasher.update([0]);
+        hasher.update(scope.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.to_lowercase().as_bytes());
+        hasher.update(attempt.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    fn unique_name(&self, kind: PseudonymKind, value: &str) -> String {
+        (0..)
+            .map(|attempt| format!("{}-{}", kind.prefix(), hex(&self.digest(kind.prefix(), value, attempt)[..3])))
+            .find(|name| !self.used.contains(name))
+            .expect("pseudonym space is not exhausted")
+    }
+
+    fn domain_pseudonym(&mut self, domain: &str) -> String {
+        let key = domain.to_lowercase();
+        if let Some(pseudonym) = self.domains.get(&key) {
+            return pseudonym.clone();
+        }
+        let pseudonym = (0..)
+            .map(|attempt| format!("dom-{}.{}", hex(&self.digest("domain", &key, attempt)[..3]), PSEUDONYM_DOMAIN_SUFFIX))
+            .find(|name| !self.domains.values().any(|d| d == name))
+            .expect("pseudonym space is not exhausted");
+        self.domains.insert(key, pseudonym.clone());
+        self.record(PseudonymKind::Hostname, domain, pseudonym.clone());
+        pseudonym
+    }
+
+    /// Addresses keep their host octet and subnet grouping: every address of
+    /// a real /24 lands in the same pseudonymous 10.x.y.0/24
+    fn ip_pseudonym(&mut self, ip: &Ipv4Addr) -> String {
+        let octets = ip.octets();
+        let network = [octets[0], octets[1], octets[2]];
+        let mapped = match self.networks.get(&network) {
+            Some(mapped) => *mapped,
+            None => {
+                let key = format!("{}.{}.{}", network[0], network[1], network[2]);
+                let mapped = (0..)
+                    .map(|attempt| {
+                        let d = self.digest("network", &key, attempt);
+                        [10, d[0], d[1]]
+                    })
+                    .find(|candidate| !self.networks.values().any(|n| n == candidate))
+                    .expect("pseudonym space is not exhausted");
+                self.networks.insert(network, mapped);
+                mapped
+            }
+        };
+        Ipv4Addr::new(mapped[0], mapped[1], mapped[2], octets[3]).to_string()
+    }
+}
+
+/// Loopback, netmasks, broadcast and multicast say nothing about the customer
+fn is_customer_address(ip: &Ipv4Addr) -> bool {
+    !(ip.is_loopback() || ip.is_unspecified() || ip.is_broadcast() || ip.is_multicast() || ip.octets()[0] == 255)
+}
+
+/// Case-insensitive alternation of literal values, longest first, with word
+/// boundaries wherever the value starts or ends with a word character
+fn alternation<'a>(values: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut values: Vec<&str> = values.filter(|v| !v.trim().is_empty()).collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    values.dedup();
+    let is_word = |c: Option<char>| c.map_or(false, |c| c.is_alphanumeric() || c == '_');
+    let alternatives: Vec<String> = values
+        .iter()
+        .map(|v| {
+            format!(
+                "{}{}{}",
+                if is_word(v.chars().next()) { r"\b" } else { "" },
+                regex::escape(v),
+                if is_word(v.chars().last()) { r"\b" } else { "" }
+            )
+        })
+        .collect();
+    Some(format!("(?:{})", alternatives.join("|")))
+}
+
+/// `*` matches any run of characters; both sides are expected lowercased
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    let mut rest = match value.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn anonymizer() -> Anonymizer {
+        Anonymizer::new(AnonymizationConfig::new("test-secret").with_preserve(vec!["*.vmware.com".to_string()]))
+    }
+
+    #[test]
+    fn test_pseudonyms_are_deterministic_and_keep_subnets() {
+        let mut a = anonymizer();
+        let mut b = anonymizer();
+        assert_eq!(a.pseudonym(PseudonymKind::VmName, "SQL-PROD-01"), b.pseudonym(PseudonymKind::VmName, "sql-prod-01"));
+        assert!(a.pseudonym(PseudonymKind::VmName, "SQL-PROD-01").starts_with("vm-"));
+
+        let first = a.pseudonym(PseudonymKind::IpAddress, "192.168.40.11");
+        let second = a.pseudonym(PseudonymKind::IpAddress, "192.168.40.12");
+        assert!(first.starts_with("10.") && first.ends_with(".11"));
+        assert_eq!(first.rsplit_once('.').unwrap().0, second.rsplit_once('.').unwrap().0);
+        assert_eq!(a.pseudonym(PseudonymKind::IpAddress, "255.255.255.0"), "255.255.255.0");
+
+        let host = a.pseudonym(PseudonymKind::Hostname, "esx01.corp.acme.local");
+        assert!(host.starts_with("host-") && host.ends_with(".invalid"));
+        assert_eq!(a.pseudonym(PseudonymKind::Hostname, "vcsa.vmware.com"), "vcsa.vmware.com");
+    }
+
+    #[test]
+    fn test_json_and_text_are_field_aware() {
+        let mut anonymizer = anonymizer();
+        let mut doc = json!({
+            "notes": "SQL-PROD-01 moves off esx01.corp.acme.local (10.1.2.3), see build 7.0.3",
+            "rows": [{ "VM": "SQL-PROD-01", "Host": "esx01.corp.acme.local", "Datastore": "DS_GOLD_01", "CPUs": 4 }],
+            "Path": "[DS_GOLD_01] SQL-PROD-01/SQL-PROD-01.vmx"
+        });
+        anonymizer.anonymize_json(&mut doc);
+
+        let text = doc.to_string();
+        for secret in ["SQL-PROD-01", "esx01", "acme", "10.1.2.3", "DS_GOLD_01"] {
+            assert!(!text.contains(secret), "{} leaked: {}", secret, text);
+        }
+        assert!(text.contains("7.0.3"));
+        assert_eq!(doc["rows"][0]["CPUs"], 4);
+        let vm = doc["rows"][0]["VM"].as_str().unwrap();
+        assert!(doc["notes"].as_str().unwrap().starts_with(vm));
+    }
+
+    #[test]
+    fn test_mapping_round_trip() {
+        let mut anonymizer = anonymizer();
+        let original = "Migrate APP01 on 172.16.5.20 to datastore NFS-02";
+        anonymizer.register(PseudonymKind::VmName, "APP01");
+        anonymizer.register(PseudonymKind::Datastore, "NFS-02");
+        let masked = anonymizer.anonymize_text(original);
+        assert!(!masked.contains("APP01") && !masked.contains("172.16.5.20") && !masked.contains("NFS-02"));
+
+        let dir = std::env::temp_dir().join(format!("anonymization-test-{}", uuid::Uuid::new_v4()));
+        let map = anonymizer.mapping();
+        map.save(&dir).unwrap();
+        let loaded = AnonymizationMap::load(&dir, &map.id).unwrap();
+        assert_eq!(loaded.deanonymize_text(&masked), original);
+        assert!(AnonymizationMap::load(&dir, "../etc/passwd").is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,80 @@
+use core_engine::collector::{Agent, CollectorConfig, Uplink, AGENT_VERSION};
+use core_engine::project_crypto::ProjectKey;
+use std::env;
+use std::path::PathBuf;
+use std::process;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_CONFIG: &str = "/etc/archer/collector.yaml";
+
+fn usage(program: &str) -> ! {
+    eprintln!("Usage: {} [run|once|check] [--config <collector.yaml>]", program);
+    eprintln!("       {} keygen", program);
+    eprintln!();
+    eprintln!("  run     discover and upload on the schedule set in Archer (default)");
+    eprintln!("  once    take one snapshot now and upload it if the backend is reachable");
+    eprintln!("  check   validate the config and pull settings from the backend");
+    eprintln!("  keygen  print a new spool encryption key");
+    eprintln!();
+    eprintln!("The config path defaults to $ARCHER_COLLECTOR_CONFIG, then {}", DEFAULT_CONFIG);
+    process::exit(1);
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let args: Vec<String> = env::args().collect();
+    let mut command = "run";
+    let mut config_path = env::var("ARCHER_COLLECTOR_CONFIG")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_CONFIG));
+    let mut options = args[1..].iter();
+    while let Some(arg) = options.next() {
+        match arg.as_str() {
+            "--config" => match options.next() {
+                Some(path) => config_path = PathBuf::from(path),
+                None => usage(&args[0]),
+            },
+            "run" | "once" | "check" | "keygen" => command = arg.as_str(),
+            _ => usage(&args[0]),
+        }
+    }
+
+    if command == "keygen" {
+        println!("{}", ProjectKey::generate().to_base64());
+        return;
+    }
+
+    let config = CollectorConfig::load(&config_path).unwrap_or_else(|e| fail(e));
+    tracing::info!(version = AGENT_VERSION, config = %config_path.display(), "Archer collector starting");
+    let mut agent = Agent::new(config.clone()).unwrap_or_else(|e| fail(e));
+
+    match command {
+        "check" => {
+            let mut uplink = Uplink::connect(&config.backend).await.unwrap_or_else(|e| fail(e));
+            let settings = uplink.fetch_settings().await.unwrap_or_else(|e| fail(e));
+            println!("Config OK, backend reachable");
+            println!("{:#?}", settings);
+        }
+        "once" => {
+            let report = agent.cycle(true).await.unwrap_or_else(|e| fail(e));
+            println!("{:#?}", report);
+        }
+        _ => {
+            agent
+                .run(async {
+                    let _ = tokio::signal::ctrl_c().await;
+                })
+                .await;
+            tracing::info!("Archer collector stopped");
+        }
+    }
+}
+
+fn fail(error: core_engine::CoreEngineError) -> ! {
+    eprintln!("Error: {}", error);
+    process::exit(1);
+}
@@ -1,18 +1,32 @@
 use core_engine::parser::RvToolsParser;
+use core_engine::rvtools_generator::{generate, GeneratorConfig};
 use serde_json;
 use std::env;
+use std::path::Path;
 use std::process;
 
+fn usage(program: &str) -> ! {
+    eprintln!("Usage: {} <rvtools_file_path>", program);
+    eprintln!("       {} parse <rvtools_file_path>", program);
+    eprintln!(
+        "       {} generate <output.xlsx> [--clusters N] [--hosts M] [--vms K] [--seed S] [--prefix NAME]",
+        program
+    );
+    process::exit(1);
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    
-    if args.len() != 2 {
-        eprintln!("Usage: {} <rvtools_file_path>", args[0]);
-        process::exit(1);
+
+    match args.get(1).map(String::as_str) {
+        Some("generate") => run_generate(&args),
+        Some("parse") if args.len() == 3 => run_parse(&args[2]),
+        Some(path) if args.len() == 2 => run_parse(path),
+        _ => usage(&args[0]),
     }
-    
-    let file_path = &args[1];
-    
+}
+
+fn run_parse(file_path: &str) {
     match RvToolsParser::new(file_path).and_then(|mut parser| parser.parse()) {
         Ok(environment) => {
             match serde_json::to_string_pretty(&environment) {
@@ -29,3 +43,44 @@ fn main() {
         }
     }
 }
+
+fn run_generate(args: &[String]) {
+    let output = match args.get(2) {
+        Some(path) if !path.starts_with("--") => path,
+        _ => usage(&args[0]),
+    };
+
+    let mut config = GeneratorConfig::default();
+    let mut options = args[3..].iter();
+    while let Some(flag) = options.next() {
+        let value = options.next().unwrap_or_else(|| usage(&args[0]));
+        let number = || value.parse::<u64>().unwrap_or_else(|_| usage(&args[0]));
+        match flag.as_str() {
+            "--clusters" => config.clusters = number() as u32,
+            "--hosts" => config.hosts = number() as u32,
+            "--vms" => config.vms = number() as u32,
+            "--seed" => config.seed = number(),
+            "--prefix" => config.name_prefix = value.clone(),
+            _ => usage(&args[0]),
+        }
+    }
+
+    let result = generate(&config).and_then(|environment| {
+        environment.write_xlsx(Path::new(output))?;
+        Ok(environment)
+    });
+    match result {
+        Ok(environment) => println!(
+            "Wrote {} clusters, {} hosts, {} VMs to {} (seed {})",
+            environment.clusters.len(),
+            environment.hosts.len(),
+            environment.vms.len(),
+            output,
+            config.seed
+        ),
+        Err(e) => {
+            eprintln!("Error generating RVTools workbook: {}", e);
+            process::exit(1);
+        }
+    }
+}
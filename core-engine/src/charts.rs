@@ -0,0 +1,763 @@
+//! Chart rendering for generated documents
+//!
+//! Bar, line and pie charts are drawn with plotters into SVG (HTML and PDF
+//! reports) or PNG (DOCX). Layout is done here rather than with plotters'
+//! chart builder, so every chart shares one look: the same palette, title,
+//! gridlines, legend and value formatting. SVG output needs no fonts; PNG
+//! output rasterizes text and needs a TrueType font, found through
+//! `CHART_FONT_PATH` or the usual system font locations.
+
+use std::io::Cursor;
+
+use once_cell::sync::Lazy;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use serde::{Deserialize, Serialize};
+
+use crate::forecasting::ForecastResult;
+use crate::models::{TcoAnalysis, UtilizationMetrics};
+use crate::{CoreEngineError, Result};
+
+/// Family the chart font is registered under; SVG viewers resolve it themselves
+const FONT_FAMILY: &str = "sans-serif";
+
+/// Fonts tried when `CHART_FONT_PATH` is not set
+const FONT_CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+static FONT: Lazy<std::result::Result<(), String>> = Lazy::new(register_font);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartKind {
+    Bar,
+    Line,
+    Pie,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+/// One series; `None` leaves a gap (e.g. a projection that starts where the
+/// history ends)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChartSeries {
+    pub name: String,
+    pub values: Vec<Option<f64>>,
+    /// Line charts draw the series dashed, e.g. for projections
+    #[serde(default)]
+    pub dashed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChartSpec {
+    pub kind: ChartKind,
+    pub title: String,
+    /// X axis categories, or pie slices
+    pub categories: Vec<String>,
+    /// A pie chart uses the first series
+    pub series: Vec<ChartSeries>,
+    /// Suffix of axis and slice values, e.g. `%` or `USD`
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Fixed top of the value axis, e.g. 100 for percentages
+    #[serde(default)]
+    pub y_max: Option<f64>,
+}
+
+/// Shared chart styling
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChartTheme {
+    pub width: u32,
+    pub height: u32,
+    pub background: [u8; 3],
+    pub text: [u8; 3],
+    pub axis: [u8; 3],
+    pub grid: [u8; 3],
+    /// Series colors, in order
+    pub palette: Vec<[u8; 3]>,
+    pub title_size: u32,
+    pub label_size: u32,
+}
+
+impl Default for ChartTheme {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 420,
+            background: [0xff, 0xff, 0xff],
+            text: [0x1f, 0x29, 0x37],
+            axis: [0x6b, 0x72, 0x80],
+            grid: [0xe5, 0xe7, 0xeb],
+            palette: vec![
+                [0x63, 0x66, 0xf1],
+                [0x8b, 0x5c, 0xf6],
+                [0x06, 0xb6, 0xd4],
+                [0x10, 0xb9, 0x81],
+                [0xf5, 0x9e, 0x0b],
+                [0xef, 0x44, 0x44],
+            ],
+            title_size: 20,
+            label_size: 13,
+        }
+    }
+}
+
+impl ChartTheme {
+    fn series_color(&self, index: usize) -> RGBColor {
+        let [r, g, b] = self.palette[index % self.palette.len().max(1)];
+        RGBColor(r, g, b)
+    }
+}
+
+fn rgb([r, g, b]: [u8; 3]) -> RGBColor {
+    RGBColor(r, g, b)
+}
+
+impl ChartSpec {
+    pub fn validate(&self) -> Result<()> {
+        if self.categories.is_empty() {
+            return Err(CoreEngineError::validation(format!("Chart '{}' has no categories", self.title)));
+        }
+        if self.series.is_empty() {
+            return Err(CoreEngineError::validation(format!("Chart '{}' has no series", self.title)));
+        }
+        for series in &self.series {
+            if series.values.len() != self.categories.len() {
+                return Err(CoreEngineError::validation(format!(
+                    "Series '{}' has {} values for {} categories",
+                    series.name,
+                    series.values.len(),
+                    self.categories.len()
+                )));
+            }
+            if series.values.iter().flatten().any(|v| !v.is_finite()) {
+                return Err(CoreEngineError::validation(format!("Series '{}' has a non-finite value", series.name)));
+            }
+        }
+        if self.kind == ChartKind::Pie {
+            let values = self.series[0].values.iter().flatten();
+            if values.clone().any(|v| *v < 0.0) || values.sum::<f64>() <= 0.0 {
+                return Err(CoreEngineError::validation(format!(
+                    "Pie chart '{}' needs non-negative values with a positive total",
+                    self.title
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// RENDERING
+// ============================================================================
+
+pub fn render(spec: &ChartSpec, format: ChartFormat, theme: &ChartTheme) -> Result<Vec<u8>> {
+    match format {
+        ChartFormat::Svg => render_svg(spec, theme).map(String::into_bytes),
+        ChartFormat::Png => render_png(spec, theme),
+    }
+}
+
+#[tracing::instrument(name = "charts.render_svg", skip_all, fields(title = %spec.title))]
+pub fn render_svg(spec: &ChartSpec, theme: &ChartTheme) -> Result<String> {
+    spec.validate()?;
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, (theme.width, theme.height)).into_drawing_area();
+        draw(&root, spec, theme).map_err(chart_error)?;
+        root.present().map_err(chart_error)?;
+    }
+    Ok(svg)
+}
+
+#[tracing::instrument(name = "charts.render_png", skip_all, fields(title = %spec.title))]
+pub fn render_png(spec: &ChartSpec, theme: &ChartTheme) -> Result<Vec<u8>> {
+    spec.validate()?;
+    FONT.as_ref().map_err(|e| CoreEngineError::config(e.clone()))?;
+
+    let mut pixels = vec![0u8; theme.width as usize * theme.height as usize * 3];
+    {
+        let root = BitMapBackend::with_buffer(&mut pixels, (theme.width, theme.height)).into_drawing_area();
+        draw(&root, spec, theme).map_err(chart_error)?;
+        root.present().map_err(chart_error)?;
+    }
+
+    let image = image::RgbImage::from_raw(theme.width, theme.height, pixels)
+        .ok_or_else(|| CoreEngineError::document("Chart buffer does not match its size"))?;
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| CoreEngineError::document(format!("Failed to encode chart: {}", e)))?;
+    Ok(png.into_inner())
+}
+
+fn chart_error<E: std::fmt::Display>(e: E) -> CoreEngineError {
+    CoreEngineError::document(format!("Failed to draw chart: {}", e))
+}
+
+fn register_font() -> std::result::Result<(), String> {
+    let configured = std::env::var("CHART_FONT_PATH").ok().filter(|p| !p.trim().is_empty());
+    let candidates = configured.iter().map(String::as_str).chain(FONT_CANDIDATES.iter().copied());
+    for path in candidates {
+        let Ok(bytes) = std::fs::read(path) else { continue };
+        // plotters keeps registered fonts for the life of the process
+        let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+        let registered = plotters::style::register_font(FONT_FAMILY, FontStyle::Normal, bytes)
+            .and_then(|_| plotters::style::register_font(FONT_FAMILY, FontStyle::Bold, bytes));
+        if registered.is_ok() {
+            tracing::debug!(path, "Registered chart font");
+            return Ok(());
+        }
+    }
+    Err("No font available for PNG charts; set CHART_FONT_PATH to a TrueType font".to_string())
+}
+
+type DrawResult<DB> = std::result::Result<(), DrawingAreaErrorKind<<DB as DrawingBackend>::ErrorType>>;
+
+/// Pixel box the data is drawn in
+#[derive(Debug, Clone, Copy)]
+struct PlotArea {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+}
+
+impl PlotArea {
+    fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+}
+
+fn draw<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, spec: &ChartSpec, theme: &ChartTheme) -> DrawResult<DB> {
+    root.fill(&rgb(theme.background))?;
+
+    let width = theme.width as i32;
+    let height = theme.height as i32;
+    let title_style = FontDesc::new(FontFamily::Name(FONT_FAMILY), theme.title_size as f64, FontStyle::Bold)
+        .color(&rgb(theme.text))
+        .pos(Pos::new(HPos::Center, VPos::Top));
+    root.draw(&Text::new(spec.title.clone(), (width / 2, 12), title_style))?;
+
+    let legend_top = 20 + theme.title_size as i32;
+    match spec.kind {
+        ChartKind::Pie => draw_pie(root, spec, theme, legend_top),
+        ChartKind::Bar | ChartKind::Line => {
+            let mut top = legend_top + 10;
+            if spec.series.len() > 1 {
+                draw_legend_row(root, spec, theme, legend_top)?;
+                top += theme.label_size as i32 + 12;
+            }
+            let area = PlotArea {
+                left: 16 + 8 * theme.label_size as i32 * 6 / 10,
+                top,
+                right: width - 24,
+                bottom: height - 16 - 2 * theme.label_size as i32,
+            };
+            draw_axes(root, spec, theme, area)?;
+            if spec.kind == ChartKind::Bar {
+                draw_bars(root, spec, theme, area)
+            } else {
+                draw_lines(root, spec, theme, area)
+            }
+        }
+    }
+}
+
+fn label_style(theme: &ChartTheme, pos: Pos) -> TextStyle<'static> {
+    FontDesc::new(FontFamily::Name(FONT_FAMILY), theme.label_size as f64, FontStyle::Normal)
+        .color(&rgb(theme.text))
+        .pos(pos)
+}
+
+fn draw_legend_row<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    spec: &ChartSpec,
+    theme: &ChartTheme,
+    top: i32,
+) -> DrawResult<DB> {
+    let swatch = theme.label_size as i32;
+    // Names are measured roughly so no font metrics are needed
+    let entry_widths: Vec<i32> = spec
+        .series
+        .iter()
+        .map(|s| swatch + 6 + s.name.chars().count() as i32 * theme.label_size as i32 * 6 / 10 + 18)
+        .collect();
+    let mut x = (theme.width as i32 - entry_widths.iter().sum::<i32>()) / 2;
+    for (index, (series, entry_width)) in spec.series.iter().zip(entry_widths).enumerate() {
+        root.draw(&Rectangle::new(
+            [(x, top), (x + swatch, top + swatch)],
+            theme.series_color(index).filled(),
+        ))?;
+        root.draw(&Text::new(
+            series.name.clone(),
+            (x + swatch + 6, top + swatch / 2),
+            label_style(theme, Pos::new(HPos::Left, VPos::Center)),
+        ))?;
+        x += entry_width;
+    }
+    Ok(())
+}
+
+/// Gridlines with value labels, the category axis and its labels
+fn draw_axes<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    spec: &ChartSpec,
+    theme: &ChartTheme,
+    area: PlotArea,
+) -> DrawResult<DB> {
+    const TICKS: i32 = 5;
+    let y_max = axis_max(spec);
+    for tick in 0..=TICKS {
+        let value = y_max * tick as f64 / TICKS as f64;
+        let y = area.bottom - area.height() * tick / TICKS;
+        let color = if tick == 0 { rgb(theme.axis) } else { rgb(theme.grid) };
+        root.draw(&PathElement::new(vec![(area.left, y), (area.right, y)], color.stroke_width(1)))?;
+        root.draw(&Text::new(
+            format_value(value, spec.unit.as_deref()),
+            (area.left - 8, y),
+            label_style(theme, Pos::new(HPos::Right, VPos::Center)),
+        ))?;
+    }
+
+    let slot = area.width() as f64 / spec.categories.len() as f64;
+    // Thin out labels that would overlap
+    let max_labels = (area.width() / 80).max(1) as usize;
+    let step = spec.categories.len().div_ceil(max_labels);
+    for (index, category) in spec.categories.iter().enumerate().step_by(step) {
+        let x = area.left + (slot * (index as f64 + 0.5)) as i32;
+        root.draw(&Text::new(
+            category.clone(),
+            (x, area.bottom + 8),
+            label_style(theme, Pos::new(HPos::Center, VPos::Top)),
+        ))?;
+    }
+    Ok(())
+}
+
+fn draw_bars<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    spec: &ChartSpec,
+    theme: &ChartTheme,
+    area: PlotArea,
+) -> DrawResult<DB> {
+    let y_max = axis_max(spec);
+    let slot = area.width() as f64 / spec.categories.len() as f64;
+    let bar = slot * 0.7 / spec.series.len() as f64;
+    for (series_index, series) in spec.series.iter().enumerate() {
+        let color = theme.series_color(series_index);
+        for (index, value) in series.values.iter().enumerate() {
+            let Some(value) = value else { continue };
+            let x0 = area.left as f64 + slot * (index as f64 + 0.15) + bar * series_index as f64;
+            let y = value_y(*value, y_max, area);
+            root.draw(&Rectangle::new(
+                [(x0.round() as i32 + 1, y), ((x0 + bar).round() as i32 - 1, area.bottom)],
+                color.filled(),
+            ))?;
+            if spec.series.len() == 1 {
+                root.draw(&Text::new(
+                    format_value(*value, spec.unit.as_deref()),
+                    ((x0 + bar / 2.0) as i32, y - 4),
+                    label_style(theme, Pos::new(HPos::Center, VPos::Bottom)),
+                ))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn draw_lines<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    spec: &ChartSpec,
+    theme: &ChartTheme,
+    area: PlotArea,
+) -> DrawResult<DB> {
+    let y_max = axis_max(spec);
+    let slot = area.width() as f64 / spec.categories.len() as f64;
+    for (series_index, series) in spec.series.iter().enumerate() {
+        let style = theme.series_color(series_index).stroke_width(2);
+        let points: Vec<Option<(i32, i32)>> = series
+            .values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                value.map(|v| (area.left + (slot * (index as f64 + 0.5)) as i32, value_y(v, y_max, area)))
+            })
+            .collect();
+
+        for pair in points.windows(2) {
+            if let [Some(from), Some(to)] = pair {
+                if series.dashed {
+                    for (a, b) in dashes(*from, *to) {
+                        root.draw(&PathElement::new(vec![a, b], style))?;
+                    }
+                } else {
+                    root.draw(&PathElement::new(vec![*from, *to], style))?;
+                }
+            }
+        }
+        for point in points.into_iter().flatten() {
+            root.draw(&Circle::new(point, 3, theme.series_color(series_index).filled()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Slices clockwise from twelve o'clock, legend with shares on the right
+fn draw_pie<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    spec: &ChartSpec,
+    theme: &ChartTheme,
+    top: i32,
+) -> DrawResult<DB> {
+    let values: Vec<f64> = spec.series[0].values.iter().map(|v| v.unwrap_or(0.0)).collect();
+    let total: f64 = values.iter().sum();
+    let height = theme.height as i32;
+    let radius = ((height - top - 24) / 2).min(theme.width as i32 / 4).max(10);
+    let center = (theme.width as i32 / 3, top + 12 + radius);
+
+    let mut start = -std::f64::consts::FRAC_PI_2;
+    for (index, value) in values.iter().enumerate() {
+        let sweep = value / total * std::f64::consts::TAU;
+        if sweep <= 0.0 {
+            continue;
+        }
+        let steps = ((sweep.to_degrees() / 2.0).ceil() as usize).max(2);
+        let mut outline = vec![center];
+        outline.extend((0..=steps).map(|step| {
+            let angle = start + sweep * step as f64 / steps as f64;
+            (
+                center.0 + (radius as f64 * angle.cos()).round() as i32,
+                center.1 + (radius as f64 * angle.sin()).round() as i32,
+            )
+        }));
+        root.draw(&Polygon::new(outline, theme.series_color(index).filled()))?;
+        start += sweep;
+    }
+
+    let swatch = theme.label_size as i32;
+    let legend_x = center.0 + radius + 40;
+    let line_height = swatch + 10;
+    let mut y = center.1 - line_height * values.len() as i32 / 2;
+    for (index, (category, value)) in spec.categories.iter().zip(&values).enumerate() {
+        root.draw(&Rectangle::new(
+            [(legend_x, y), (legend_x + swatch, y + swatch)],
+            theme.series_color(index).filled(),
+        ))?;
+        root.draw(&Text::new(
+            format!(
+                "{}: {} ({:.0}%)",
+                category,
+                format_value(*value, spec.unit.as_deref()),
+                value / total * 100.0
+            ),
+            (legend_x + swatch + 8, y + swatch / 2),
+            label_style(theme, Pos::new(HPos::Left, VPos::Center)),
+        ))?;
+        y += line_height;
+    }
+    Ok(())
+}
+
+fn value_y(value: f64, y_max: f64, area: PlotArea) -> i32 {
+    let share = (value / y_max).clamp(0.0, 1.0);
+    area.bottom - (area.height() as f64 * share).round() as i32
+}
+
+/// Top of the value axis: the fixed maximum, else a round number above the data
+fn axis_max(spec: &ChartSpec) -> f64 {
+    if let Some(max) = spec.y_max.filter(|m| *m > 0.0) {
+        return max;
+    }
+    let largest = spec
+        .series
+        .iter()
+        .flat_map(|s| s.values.iter().flatten())
+        .fold(0.0_f64, |a, b| a.max(*b));
+    nice_ceiling(largest * 1.05)
+}
+
+/// Smallest of 1, 2, 2.5, 5 or 10 times a power of ten at or above `value`
+fn nice_ceiling(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 2.5, 5.0, 10.0]
+        .iter()
+        .map(|step| step * magnitude)
+        .find(|candidate| *candidate >= value)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Compact value with its unit: `1.25M USD`, `85%`, `12k`
+fn format_value(value: f64, unit: Option<&str>) -> String {
+    let abs = value.abs();
+    let number = if abs >= 1e9 {
+        format!("{}B", trim_decimals(value / 1e9))
+    } else if abs >= 1e6 {
+        format!("{}M", trim_decimals(value / 1e6))
+    } else if abs >= 1e4 {
+        format!("{}k", trim_decimals(value / 1e3))
+    } else {
+        trim_decimals(value)
+    };
+    match unit {
+        Some("%") => format!("{}%", number),
+        Some(unit) if !unit.is_empty() => format!("{} {}", number, unit),
+        _ => number,
+    }
+}
+
+fn trim_decimals(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Dash segments along a line
+fn dashes(from: (i32, i32), to: (i32, i32)) -> Vec<((i32, i32), (i32, i32))> {
+    const DASH: f64 = 8.0;
+    const GAP: f64 = 5.0;
+    let (dx, dy) = ((to.0 - from.0) as f64, (to.1 - from.1) as f64);
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        return Vec::new();
+    }
+    let at = |distance: f64| {
+        let t = (distance / length).min(1.0);
+        (from.0 + (dx * t).round() as i32, from.1 + (dy * t).round() as i32)
+    };
+    let mut segments = Vec::new();
+    let mut distance = 0.0;
+    while distance < length {
+        segments.push((at(distance), at(distance + DASH)));
+        distance += DASH + GAP;
+    }
+    segments
+}
+
+// ============================================================================
+// DOCUMENT CHARTS
+// ============================================================================
+
+/// CPU, memory and storage utilization of the sized cluster
+pub fn capacity_utilization(metrics: &UtilizationMetrics) -> ChartSpec {
+    ChartSpec {
+        kind: ChartKind::Bar,
+        title: "Capacity Utilization".to_string(),
+        categories: vec!["CPU".to_string(), "Memory".to_string(), "Storage".to_string()],
+        series: vec![ChartSeries {
+            name: "Utilization".to_string(),
+            values: vec![
+                Some(metrics.cpu_utilization_percent as f64),
+                Some(metrics.memory_utilization_percent as f64),
+                Some(metrics.storage_utilization_percent as f64),
+            ],
+            dashed: false,
+        }],
+        unit: Some("%".to_string()),
+        y_max: Some(100.0),
+    }
+}
+
+/// Cumulative cost of staying on the current platform against moving, over
+/// `years`; the target line starts at the one-time costs
+pub fn tco_comparison(tco: &TcoAnalysis, years: u32) -> ChartSpec {
+    let current = &tco.current_environment_costs;
+    let target = &tco.target_environment_costs;
+    let one_time = target.hardware_acquisition + target.implementation_services;
+    ChartSpec {
+        kind: ChartKind::Line,
+        title: format!("{}-Year Cumulative Cost", years),
+        categories: (0..=years).map(|year| format!("Year {}", year)).collect(),
+        series: vec![
+            ChartSeries {
+                name: "Current environment".to_string(),
+                values: (0..=years).map(|year| Some(current.total_annual * year as f64)).collect(),
+                dashed: false,
+            },
+            ChartSeries {
+                name: "Proposed solution".to_string(),
+                values: (0..=years).map(|year| Some(one_time + target.total_annual * year as f64)).collect(),
+                dashed: false,
+            },
+        ],
+        unit: Some(tco.currency.clone()),
+        y_max: None,
+    }
+}
+
+/// Where the current environment's annual cost goes
+pub fn current_cost_breakdown(tco: &TcoAnalysis) -> ChartSpec {
+    let current = &tco.current_environment_costs;
+    ChartSpec {
+        kind: ChartKind::Pie,
+        title: "Current Annual Cost Breakdown".to_string(),
+        categories: vec![
+            "Hardware".to_string(),
+            "Software licensing".to_string(),
+            "Datacenter".to_string(),
+            "Personnel".to_string(),
+        ],
+        series: vec![ChartSeries {
+            name: "Annual cost".to_string(),
+            values: vec![
+                Some(current.hardware_annual),
+                Some(current.software_licensing_annual),
+                Some(current.datacenter_annual),
+                Some(current.personnel_annual),
+            ],
+            dashed: false,
+        }],
+        unit: Some(tco.currency.clone()),
+        y_max: None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastMetric {
+    Vcpu,
+    MemoryGb,
+    StorageGb,
+}
+
+/// Observed values of a resource and the projection to the forecast's target
+/// date, drawn dashed from the last observation
+pub fn growth_forecast(forecast: &ForecastResult, metric: ForecastMetric) -> ChartSpec {
+    let trends = &forecast.historical_trends;
+    let projected = &forecast.projections.projected_metrics;
+    let (title, trend, target, unit) = match metric {
+        ForecastMetric::Vcpu => ("vCPU Growth Forecast", &trends.vcpu_trend, projected.total_vcpus as f64, "vCPU"),
+        ForecastMetric::MemoryGb => (
+            "Memory Growth Forecast",
+            &trends.memory_trend,
+            projected.total_provisioned_memory_gb,
+            "GB",
+        ),
+        ForecastMetric::StorageGb => (
+            "Storage Growth Forecast",
+            &trends.storage_trend,
+            projected.total_consumed_storage_gb,
+            "GB",
+        ),
+    };
+
+    let history = &trend.data_points;
+    let mut categories: Vec<String> = history.iter().map(|p| p.timestamp.format("%Y-%m").to_string()).collect();
+    categories.push(forecast.projections.target_date.format("%Y-%m").to_string());
+
+    let mut observed: Vec<Option<f64>> = history.iter().map(|p| Some(p.value)).collect();
+    observed.push(None);
+    let mut projection = vec![None; history.len() + 1];
+    if let Some(last) = history.last() {
+        projection[history.len() - 1] = Some(last.value);
+    }
+    projection[history.len()] = Some(target);
+
+    ChartSpec {
+        kind: ChartKind::Line,
+        title: title.to_string(),
+        categories,
+        series: vec![
+            ChartSeries {
+                name: "Observed".to_string(),
+                values: observed,
+                dashed: false,
+            },
+            ChartSeries {
+                name: "Projected".to_string(),
+                values: projection,
+                dashed: true,
+            },
+        ],
+        unit: Some(unit.to_string()),
+        y_max: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar_chart() -> ChartSpec {
+        capacity_utilization(&UtilizationMetrics {
+            cpu_utilization_percent: 62.5,
+            memory_utilization_percent: 71.0,
+            storage_utilization_percent: 48.0,
+            n_plus_x_compliance: true,
+        })
+    }
+
+    #[test]
+    fn renders_each_kind_as_svg() {
+        let theme = ChartTheme::default();
+        let svg = render_svg(&bar_chart(), &theme).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Capacity Utilization"));
+        assert!(svg.contains("62.5%"));
+
+        let mut line = bar_chart();
+        line.kind = ChartKind::Line;
+        line.series.push(ChartSeries {
+            name: "Target".to_string(),
+            values: vec![Some(50.0), None, Some(40.0)],
+            dashed: true,
+        });
+        assert!(render_svg(&line, &theme).unwrap().contains("Target"));
+
+        let mut pie = bar_chart();
+        pie.kind = ChartKind::Pie;
+        assert!(render_svg(&pie, &theme).unwrap().contains("CPU: 62.5% (34%)"));
+    }
+
+    #[test]
+    fn rejects_malformed_specs() {
+        let mut short = bar_chart();
+        short.series[0].values.pop();
+        assert!(short.validate().is_err());
+
+        let mut empty_pie = bar_chart();
+        empty_pie.kind = ChartKind::Pie;
+        empty_pie.series[0].values = vec![Some(0.0), None, Some(0.0)];
+        assert!(empty_pie.validate().is_err());
+
+        let mut infinite = bar_chart();
+        infinite.series[0].values[0] = Some(f64::INFINITY);
+        assert!(infinite.validate().is_err());
+    }
+
+    #[test]
+    fn axis_and_value_formatting() {
+        assert_eq!(nice_ceiling(87.0), 100.0);
+        assert_eq!(nice_ceiling(1_840_000.0), 2_000_000.0);
+        assert_eq!(nice_ceiling(2.2), 2.5);
+        assert_eq!(format_value(1_250_000.0, Some("USD")), "1.25M USD");
+        assert_eq!(format_value(85.0, Some("%")), "85%");
+        assert_eq!(format_value(12_000.0, None), "12k");
+    }
+
+    #[test]
+    fn dashes_cover_the_line() {
+        let segments = dashes((0, 0), (26, 0));
+        assert_eq!(segments, vec![((0, 0), (8, 0)), ((13, 0), (21, 0))]);
+        assert_eq!(dashes((0, 0), (0, 10)), vec![((0, 0), (0, 8))]);
+    }
+}
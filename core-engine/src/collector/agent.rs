@@ -0,0 +1,334 @@
+// Collector agent loop
+//
+// Every cycle pulls settings from the backend, takes a snapshot when one is
+// due and uploads the newest spooled snapshot. Each snapshot replaces the
+// project's inventory, so once the newest is accepted the older ones are
+// superseded and removed. Without a connection, settings come from the
+// cache and snapshots pile up in the spool until the backend is back.
+
+use chrono::Utc;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use super::config::{secret, AgentSettings, CollectorConfig};
+use super::discovery::{active_directory, ActiveDirectoryEnricher, DiscoverySource, NutanixSource, VCenterSource};
+use super::proto::InventoryChunk;
+use super::spool::Spool;
+use super::uplink::Uplink;
+use crate::project_crypto::ProjectKey;
+use crate::CoreEngineError;
+
+/// Time between cycles
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Wait before retrying a failed discovery, when shorter than the interval
+const DISCOVERY_RETRY: Duration = Duration::from_secs(15 * 60);
+
+/// What one cycle did
+#[derive(Debug, Clone, Default)]
+pub struct CycleReport {
+    pub online: bool,
+    /// VMs in the snapshot taken this cycle
+    pub discovered_vms: Option<usize>,
+    pub uploaded: Option<String>,
+    /// Older snapshots removed after a newer one was accepted
+    pub superseded: usize,
+    /// Snapshots removed because the spool was full, unreadable or rejected
+    pub dropped: usize,
+    pub pending: usize,
+}
+
+pub struct Agent {
+    config: CollectorConfig,
+    spool: Spool,
+    settings: AgentSettings,
+    uplink: Option<Uplink>,
+    next_discovery: Option<Instant>,
+}
+
+impl Agent {
+    pub fn new(config: CollectorConfig) -> Result<Self, CoreEngineError> {
+        let key = ProjectKey::from_base64(&secret(&config.spool.key_env)?)?;
+        let spool = Spool::open(&config.spool.dir, key, config.spool.max_snapshots)?;
+        let settings = spool.load_settings();
+        Ok(Self {
+            config,
+            spool,
+            settings,
+            uplink: None,
+            next_discovery: None,
+        })
+    }
+
+    pub fn settings(&self) -> &AgentSettings {
+        &self.settings
+    }
+
+    /// Run cycles until `shutdown` completes
+    pub async fn run(&mut self, shutdown: impl Future<Output = ()>) {
+        tokio::pin!(shutdown);
+        loop {
+            match self.cycle(false).await {
+                Ok(report) => tracing::info!(
+                    online = report.online,
+                    discovered_vms = ?report.discovered_vms,
+                    uploaded = ?report.uploaded,
+                    pending = report.pending,
+                    "Collector cycle finished"
+                ),
+                Err(e) => tracing::warn!("Collector cycle failed: {}", e),
+            }
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    }
+
+    /// Refresh settings, snapshot when due (always with `force_discovery`)
+    /// and upload what is spooled
+    pub async fn cycle(&mut self, force_discovery: bool) -> Result<CycleReport, CoreEngineError> {
+        let mut report = CycleReport::default();
+        self.refresh_settings().await;
+        report.online = self.uplink.is_some();
+
+        if !self.settings.paused {
+            let due = self.next_discovery.map_or(true, |next| Instant::now() >= next);
+            if due || force_discovery {
+                let interval = self.settings.snapshot_interval();
+                match self.discover().await {
+                    Ok(snapshot) => {
+                        self.next_discovery = Some(Instant::now() + interval);
+                        report.discovered_vms = Some(snapshot.vms.len());
+                        report.dropped += self.spool.push(&snapshot)?;
+                    }
+                    Err(e) => {
+                        self.next_discovery = Some(Instant::now() + interval.min(DISCOVERY_RETRY));
+                        return Err(e);
+                    }
+                }
+            }
+            if let Some(project_id) = self.settings.project_id.clone() {
+                self.upload_newest(&project_id, &mut report).await?;
+            }
+        }
+
+        report.pending = self.spool.pending()?.len();
+        Ok(report)
+    }
+
+    async fn refresh_settings(&mut self) {
+        if self.uplink.is_none() {
+            match Uplink::connect(&self.config.backend).await {
+                Ok(uplink) => self.uplink = Some(uplink),
+                Err(e) => {
+                    tracing::warn!("Backend unreachable, working offline: {}", e);
+                    return;
+                }
+            }
+        }
+        let Some(uplink) = self.uplink.as_mut() else {
+            return;
+        };
+        match uplink.fetch_settings().await {
+            Ok(settings) => {
+                if settings != self.settings {
+                    tracing::info!(?settings, "Collector settings changed");
+                    if let Err(e) = self.spool.save_settings(&settings) {
+                        tracing::warn!("{}", e);
+                    }
+                    self.settings = settings;
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Could not pull collector settings: {}", e);
+                self.uplink = None;
+            }
+        }
+    }
+
+    /// Run every enabled source into one snapshot. A failing inventory
+    /// source fails the snapshot, since uploading it would drop that
+    /// source's VMs from the project; Active Directory only adds details.
+    pub async fn discover(&self) -> Result<InventoryChunk, CoreEngineError> {
+        let settings = &self.settings;
+        let mut sources: Vec<Box<dyn DiscoverySource>> = Vec::new();
+        if let Some(vcenter) = self.config.vcenter.clone().filter(|_| settings.vcenter_enabled) {
+            sources.push(Box::new(VCenterSource::new(vcenter)));
+        }
+        if let Some(nutanix) = self.config.nutanix.clone().filter(|_| settings.nutanix_enabled) {
+            sources.push(Box::new(NutanixSource::new(nutanix)));
+        }
+        if sources.is_empty() {
+            return Err(CoreEngineError::config("No configured discovery source is enabled"));
+        }
+
+        let mut snapshot = InventoryChunk {
+            snapshot_id: new_snapshot_id(),
+            ..Default::default()
+        };
+        for source in &sources {
+            let started = Instant::now();
+            let before = snapshot.vms.len();
+            source.discover(&mut snapshot).await?;
+            tracing::info!(
+                source = source.name(),
+                vms = snapshot.vms.len() - before,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "Discovery finished"
+            );
+        }
+
+        if let Some(directory) = self.config.active_directory.clone().filter(|_| settings.active_directory_enabled) {
+            match ActiveDirectoryEnricher::new(directory).computers().await {
+                Ok(computers) => {
+                    let enriched = active_directory::enrich(&mut snapshot, &computers);
+                    tracing::info!(computers = computers.len(), enriched, "Active Directory lookup finished");
+                }
+                Err(e) => tracing::warn!("Active Directory lookup skipped: {}", e),
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Upload the newest readable snapshot; older ones are superseded once
+    /// it is accepted. Kept in the spool when the backend is unreachable.
+    async fn upload_newest(&mut self, project_id: &str, report: &mut CycleReport) -> Result<(), CoreEngineError> {
+        let Some(uplink) = self.uplink.as_mut() else {
+            return Ok(());
+        };
+        let mut pending = self.spool.pending()?;
+        while let Some(path) = pending.pop() {
+            let snapshot = match self.spool.load(&path) {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    tracing::warn!("Dropping unreadable snapshot: {}", e);
+                    self.spool.remove(&path)?;
+                    report.dropped += 1;
+                    continue;
+                }
+            };
+
+            match uplink.upload(project_id, snapshot).await {
+                Ok(summary) => {
+                    self.spool.remove(&path)?;
+                    for older in &pending {
+                        self.spool.remove(older)?;
+                    }
+                    report.superseded += pending.len();
+                    tracing::info!(
+                        snapshot = %summary.snapshot_id,
+                        vms = summary.vms_inserted,
+                        hosts = summary.hosts,
+                        "Snapshot uploaded"
+                    );
+                    report.uploaded = Some(summary.snapshot_id);
+                    return Ok(());
+                }
+                // The backend will never take this one; try the next older
+                Err(CoreEngineError::ValidationError(message)) => {
+                    tracing::warn!("Backend rejected snapshot {}: {}", path.display(), message);
+                    self.spool.remove(&path)?;
+                    report.dropped += 1;
+                }
+                Err(e) => {
+                    self.uplink = None;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Capture time first, so spool file names sort oldest first
+fn new_snapshot_id() -> String {
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%SZ"), &suffix[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::config::{ActiveDirectoryConfig, BackendConfig, SpoolConfig, VCenterConfig};
+
+    fn agent(dir: &std::path::Path) -> Agent {
+        let config = CollectorConfig {
+            backend: BackendConfig {
+                endpoint: "https://archer.invalid:50051".to_string(),
+                tls_domain: None,
+                ca_cert: dir.join("ca.pem"),
+                client_cert: dir.join("collector.pem"),
+                client_key: dir.join("collector.key"),
+                token_env: "ARCHER_TEST_COLLECTOR_TOKEN".to_string(),
+            },
+            spool: SpoolConfig {
+                dir: dir.to_path_buf(),
+                key_env: "UNUSED".to_string(),
+                max_snapshots: 3,
+            },
+            vcenter: Some(VCenterConfig {
+                url: "mock://vcenter".to_string(),
+                username: "archer".to_string(),
+                password_env: "UNUSED".to_string(),
+                insecure: false,
+            }),
+            nutanix: None,
+            active_directory: Some(ActiveDirectoryConfig {
+                url: "mock://dc01".to_string(),
+                bind_dn: "CN=archer".to_string(),
+                password_env: "UNUSED".to_string(),
+                base_dn: "DC=corp,DC=local".to_string(),
+                insecure: false,
+            }),
+        };
+        let spool = Spool::open(dir, ProjectKey::generate(), 3).unwrap();
+        Agent {
+            settings: spool.load_settings(),
+            config,
+            spool,
+            uplink: None,
+            next_discovery: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_offline_cycle_spools_enriched_snapshot() {
+        let dir = std::env::temp_dir().join(format!("archer-agent-{}", uuid::Uuid::new_v4()));
+        let mut agent = agent(&dir);
+
+        let report = agent.cycle(false).await.unwrap();
+        assert!(!report.online);
+        assert_eq!(report.discovered_vms, Some(3));
+        assert_eq!(report.pending, 1);
+
+        let spooled = agent.spool.load(&agent.spool.pending().unwrap()[0]).unwrap();
+        let app = spooled.vms.iter().find(|vm| vm.name == "APP01").unwrap();
+        assert_eq!(app.dns_name.as_deref(), Some("app01.corp.local"));
+
+        // Not due again until the interval has passed
+        let report = agent.cycle(false).await.unwrap();
+        assert_eq!(report.discovered_vms, None);
+        assert_eq!(report.pending, 1);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_disabled_sources_fail_discovery() {
+        let dir = std::env::temp_dir().join(format!("archer-agent-{}", uuid::Uuid::new_v4()));
+        let mut agent = agent(&dir);
+        agent.settings.vcenter_enabled = false;
+        assert!(agent.discover().await.is_err());
+
+        agent.settings.paused = true;
+        let report = agent.cycle(true).await.unwrap();
+        assert_eq!(report.discovered_vms, None);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_snapshot_ids_sort_by_capture_time() {
+        let id = new_snapshot_id();
+        assert_eq!(id.len(), "20261015T080000Z-".len() + 8);
+        assert!(!id.contains('.'));
+    }
+}
@@ -0,0 +1,230 @@
+// Collector agent configuration
+//
+// The local YAML file says where the backend is and how to reach the
+// discovery sources. Secrets are never in the file, only the names of the
+// environment variables holding them. What to discover and where to send it
+// comes from the backend as `AgentSettings`.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::proto;
+use crate::CoreEngineError;
+
+const DEFAULT_MAX_SPOOLED: usize = 20;
+const DEFAULT_SNAPSHOT_INTERVAL_MINUTES: u32 = 360;
+
+/// Contents of `collector.yaml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectorConfig {
+    pub backend: BackendConfig,
+    pub spool: SpoolConfig,
+    #[serde(default)]
+    pub vcenter: Option<VCenterConfig>,
+    #[serde(default)]
+    pub nutanix: Option<NutanixConfig>,
+    #[serde(default)]
+    pub active_directory: Option<ActiveDirectoryConfig>,
+}
+
+/// The backend's collector gRPC endpoint and the mutual-TLS material
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendConfig {
+    /// e.g. `https://archer.example.com:50051`
+    pub endpoint: String,
+    /// Name the server certificate is checked against, when it differs from
+    /// the endpoint host
+    #[serde(default)]
+    pub tls_domain: Option<String>,
+    pub ca_cert: PathBuf,
+    pub client_cert: PathBuf,
+    pub client_key: PathBuf,
+    /// Environment variable holding the collector token
+    pub token_env: String,
+}
+
+/// Where snapshots wait until the backend accepts them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpoolConfig {
+    pub dir: PathBuf,
+    /// Environment variable holding the base64 spool key
+    /// (`archer-collector keygen` prints one)
+    pub key_env: String,
+    /// Oldest snapshots are dropped beyond this many
+    #[serde(default = "default_max_spooled")]
+    pub max_snapshots: usize,
+}
+
+fn default_max_spooled() -> usize {
+    DEFAULT_MAX_SPOOLED
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VCenterConfig {
+    /// e.g. `https://vcenter.corp.local`; `mock://` serves sample data
+    pub url: String,
+    pub username: String,
+    pub password_env: String,
+    /// Accept self-signed vCenter certificates
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NutanixConfig {
+    /// Prism Central, e.g. `https://prism.corp.local:9440`; `mock://` serves
+    /// sample data
+    pub url: String,
+    pub username: String,
+    pub password_env: String,
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveDirectoryConfig {
+    /// e.g. `ldaps://dc01.corp.local`; `mock://` serves sample data
+    pub url: String,
+    pub bind_dn: String,
+    pub password_env: String,
+    /// Where computer objects are searched, e.g. `DC=corp,DC=local`
+    pub base_dn: String,
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+impl CollectorConfig {
+    pub fn load(path: &Path) -> Result<Self, CoreEngineError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| CoreEngineError::config(format!("Cannot read {}: {}", path.display(), e)))?;
+        let config: Self = serde_yaml::from_str(&content)
+            .map_err(|e| CoreEngineError::config(format!("Invalid collector config {}: {}", path.display(), e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), CoreEngineError> {
+        if !self.backend.endpoint.starts_with("https://") {
+            return Err(CoreEngineError::config("backend.endpoint must be an https:// URL"));
+        }
+        if self.vcenter.is_none() && self.nutanix.is_none() {
+            return Err(CoreEngineError::config("Configure at least one of vcenter and nutanix"));
+        }
+        if self.spool.max_snapshots == 0 {
+            return Err(CoreEngineError::config("spool.max_snapshots must be at least 1"));
+        }
+        Ok(())
+    }
+}
+
+/// Value of the environment variable a config field names
+pub fn secret(env: &str) -> Result<String, CoreEngineError> {
+    std::env::var(env)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| CoreEngineError::config(format!("Environment variable {} is not set", env)))
+}
+
+/// What the backend asks the agent to do. Cached in the spool directory so
+/// the agent keeps working while the backend is unreachable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentSettings {
+    /// Project snapshots are uploaded to; they stay spooled until one is set
+    #[serde(default)]
+    pub project_id: Option<String>,
+    pub snapshot_interval_minutes: u32,
+    pub vcenter_enabled: bool,
+    pub nutanix_enabled: bool,
+    pub active_directory_enabled: bool,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl Default for AgentSettings {
+    /// Used before the backend has answered once: every configured source,
+    /// every six hours
+    fn default() -> Self {
+        Self {
+            project_id: None,
+            snapshot_interval_minutes: DEFAULT_SNAPSHOT_INTERVAL_MINUTES,
+            vcenter_enabled: true,
+            nutanix_enabled: true,
+            active_directory_enabled: true,
+            paused: false,
+        }
+    }
+}
+
+impl AgentSettings {
+    pub fn snapshot_interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.snapshot_interval_minutes.max(1)) * 60)
+    }
+}
+
+impl From<proto::CollectorSettings> for AgentSettings {
+    fn from(settings: proto::CollectorSettings) -> Self {
+        Self {
+            project_id: Some(settings.project_id).filter(|id| !id.is_empty()),
+            snapshot_interval_minutes: settings.snapshot_interval_minutes,
+            vcenter_enabled: settings.vcenter_enabled,
+            nutanix_enabled: settings.nutanix_enabled,
+            active_directory_enabled: settings.active_directory_enabled,
+            paused: settings.paused,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+backend:
+  endpoint: https://archer.example.com:50051
+  ca_cert: /etc/archer/ca.pem
+  client_cert: /etc/archer/collector.pem
+  client_key: /etc/archer/collector.key
+  token_env: ARCHER_COLLECTOR_TOKEN
+spool:
+  dir: /var/lib/archer-collector
+  key_env: ARCHER_COLLECTOR_SPOOL_KEY
+vcenter:
+  url: https://vcenter.corp.local
+  username: archer@vsphere.local
+  password_env: VCENTER_PASSWORD
+"#;
+
+    #[test]
+    fn test_config_parses_with_defaults() {
+        let config: CollectorConfig = serde_yaml::from_str(CONFIG).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.spool.max_snapshots, DEFAULT_MAX_SPOOLED);
+        assert!(config.nutanix.is_none());
+        assert!(!config.vcenter.unwrap().insecure);
+    }
+
+    #[test]
+    fn test_config_needs_an_inventory_source_and_https() {
+        let mut config: CollectorConfig = serde_yaml::from_str(CONFIG).unwrap();
+        config.backend.endpoint = "http://archer.example.com:50051".to_string();
+        assert!(config.validate().is_err());
+
+        config.backend.endpoint = "https://archer.example.com:50051".to_string();
+        config.vcenter = None;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_from_backend_treat_empty_project_as_unset() {
+        let settings = AgentSettings::from(proto::CollectorSettings {
+            project_id: String::new(),
+            snapshot_interval_minutes: 60,
+            vcenter_enabled: true,
+            ..Default::default()
+        });
+        assert_eq!(settings.project_id, None);
+        assert!(!settings.nutanix_enabled);
+        assert_eq!(settings.snapshot_interval(), Duration::from_secs(3600));
+    }
+}
@@ -0,0 +1,175 @@
+// Active Directory lookups for guest details
+//
+// Enabled computer objects give the DNS name and operating system of VMs
+// whose guest tools report nothing, e.g. powered-off or tool-less VMs. VMs
+// are matched by name against the computer's CN or the first label of its
+// DNS host name. Values the hypervisor already reported are kept.
+
+use ldap3::adapters::{Adapter, EntriesOnly, PagedResults};
+use ldap3::{LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::collector::config::{secret, ActiveDirectoryConfig};
+use crate::collector::proto::InventoryChunk;
+use crate::CoreEngineError;
+
+/// AD answers at most 1000 entries per page by default
+const PAGE_SIZE: i32 = 500;
+/// Enabled computers; bit 2 of userAccountControl marks disabled accounts
+const COMPUTER_FILTER: &str = "(&(objectCategory=computer)(!(userAccountControl:1.2.840.113556.1.4.803:=2)))";
+
+/// A computer object from the directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DirectoryComputer {
+    pub name: String,
+    pub dns_host_name: Option<String>,
+    pub operating_system: Option<String>,
+    pub operating_system_version: Option<String>,
+}
+
+pub struct ActiveDirectoryEnricher {
+    config: ActiveDirectoryConfig,
+}
+
+impl ActiveDirectoryEnricher {
+    pub fn new(config: ActiveDirectoryConfig) -> Self {
+        Self { config }
+    }
+
+    /// Enabled computer objects under the configured base DN
+    pub async fn computers(&self) -> Result<Vec<DirectoryComputer>, CoreEngineError> {
+        if self.config.url.starts_with("mock://") {
+            return Ok(mock_computers());
+        }
+        let ldap_error = |e: ldap3::LdapError| CoreEngineError::NetworkError(format!("Active Directory: {}", e));
+
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(Duration::from_secs(30))
+            .set_no_tls_verify(self.config.insecure);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &self.config.url)
+            .await
+            .map_err(ldap_error)?;
+        ldap3::drive!(conn);
+        ldap.simple_bind(&self.config.bind_dn, &secret(&self.config.password_env)?)
+            .await
+            .and_then(|result| result.success())
+            .map_err(|e| CoreEngineError::authentication(format!("Active Directory rejected the bind: {}", e)))?;
+
+        let adapters: Vec<Box<dyn Adapter<_, _>>> =
+            vec![Box::new(EntriesOnly::new()), Box::new(PagedResults::new(PAGE_SIZE))];
+        let attrs = vec!["name", "dNSHostName", "operatingSystem", "operatingSystemVersion"];
+        let mut stream = ldap
+            .streaming_search_with(adapters, &self.config.base_dn, Scope::Subtree, COMPUTER_FILTER, attrs)
+            .await
+            .map_err(ldap_error)?;
+        let mut computers = Vec::new();
+        while let Some(entry) = stream.next().await.map_err(ldap_error)? {
+            computers.extend(computer(SearchEntry::construct(entry)));
+        }
+        stream.finish().await.success().map_err(ldap_error)?;
+        let _ = ldap.unbind().await;
+        Ok(computers)
+    }
+}
+
+fn computer(entry: SearchEntry) -> Option<DirectoryComputer> {
+    let first = |attr: &str| entry.attrs.get(attr).and_then(|values| values.first()).cloned();
+    Some(DirectoryComputer {
+        name: first("name")?,
+        dns_host_name: first("dNSHostName"),
+        operating_system: first("operatingSystem"),
+        operating_system_version: first("operatingSystemVersion"),
+    })
+}
+
+/// Fill DNS name and OS of matching VMs where they are missing; returns how
+/// many VMs gained a value
+pub fn enrich(inventory: &mut InventoryChunk, computers: &[DirectoryComputer]) -> usize {
+    let mut by_name: HashMap<String, &DirectoryComputer> = HashMap::new();
+    for computer in computers {
+        by_name.insert(computer.name.to_ascii_lowercase(), computer);
+        if let Some(label) = computer.dns_host_name.as_deref().and_then(|dns| dns.split('.').next()) {
+            by_name.entry(label.to_ascii_lowercase()).or_insert(computer);
+        }
+    }
+
+    let mut enriched = 0;
+    for vm in &mut inventory.vms {
+        let Some(computer) = by_name.get(&vm.name.to_ascii_lowercase()) else {
+            continue;
+        };
+        let before = (vm.dns_name.is_some(), vm.os.is_some(), vm.os_version.is_some());
+        if vm.dns_name.is_none() {
+            vm.dns_name = computer.dns_host_name.clone();
+        }
+        if vm.os.is_none() {
+            vm.os = computer.operating_system.clone();
+        }
+        if vm.os_version.is_none() {
+            vm.os_version = computer.operating_system_version.clone();
+        }
+        if before != (vm.dns_name.is_some(), vm.os.is_some(), vm.os_version.is_some()) {
+            enriched += 1;
+        }
+    }
+    enriched
+}
+
+fn mock_computers() -> Vec<DirectoryComputer> {
+    let computer = |name: &str, os: &str, version: &str| DirectoryComputer {
+        name: name.to_string(),
+        dns_host_name: Some(format!("{}.corp.local", name.to_ascii_lowercase())),
+        operating_system: Some(os.to_string()),
+        operating_system_version: Some(version.to_string()),
+    };
+    vec![
+        computer("APP01", "Windows Server 2019 Datacenter", "10.0 (17763)"),
+        computer("DB01", "Windows Server 2019 Datacenter", "10.0 (17763)"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::proto::Vm;
+
+    #[test]
+    fn test_enrich_fills_only_missing_fields() {
+        let mut inventory = InventoryChunk {
+            vms: vec![
+                Vm { name: "app01".to_string(), ..Default::default() },
+                Vm {
+                    name: "DB01".to_string(),
+                    os: Some("Microsoft Windows Server 2019 (64-bit)".to_string()),
+                    ..Default::default()
+                },
+                Vm { name: "web-01".to_string(), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(enrich(&mut inventory, &mock_computers()), 2);
+
+        let app = &inventory.vms[0];
+        assert_eq!(app.dns_name.as_deref(), Some("app01.corp.local"));
+        assert_eq!(app.os.as_deref(), Some("Windows Server 2019 Datacenter"));
+        assert_eq!(inventory.vms[1].os.as_deref(), Some("Microsoft Windows Server 2019 (64-bit)"));
+        assert_eq!(inventory.vms[1].os_version.as_deref(), Some("10.0 (17763)"));
+        assert!(inventory.vms[2].dns_name.is_none());
+    }
+
+    #[test]
+    fn test_enrich_matches_dns_label() {
+        let computers = vec![DirectoryComputer {
+            name: "SRV-LEGACY".to_string(),
+            dns_host_name: Some("web-01.corp.local".to_string()),
+            ..Default::default()
+        }];
+        let mut inventory = InventoryChunk {
+            vms: vec![Vm { name: "WEB-01".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        assert_eq!(enrich(&mut inventory, &computers), 1);
+        assert_eq!(inventory.vms[0].dns_name.as_deref(), Some("web-01.corp.local"));
+    }
+}
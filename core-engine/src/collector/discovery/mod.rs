@@ -0,0 +1,65 @@
+//! Discovery sources of the collector agent
+//!
+//! vCenter and Nutanix Prism Central add VMs, hosts, clusters and datastores
+//! to a snapshot. Active Directory only fills in guest details of VMs that
+//! are already there.
+
+pub mod active_directory;
+pub mod nutanix;
+pub mod vcenter;
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+use super::proto::InventoryChunk;
+use crate::CoreEngineError;
+
+pub use active_directory::{ActiveDirectoryEnricher, DirectoryComputer};
+pub use nutanix::NutanixSource;
+pub use vcenter::VCenterSource;
+
+/// Per-request timeout against vCenter and Prism Central
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A platform the agent reads inventory from
+#[async_trait]
+pub trait DiscoverySource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Append what the platform holds to `inventory`
+    async fn discover(&self, inventory: &mut InventoryChunk) -> Result<(), CoreEngineError>;
+}
+
+pub(crate) fn http_client(insecure: bool) -> Result<reqwest::Client, CoreEngineError> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .danger_accept_invalid_certs(insecure)
+        .build()
+        .map_err(|e| CoreEngineError::config(format!("Failed to build HTTP client: {}", e)))
+}
+
+/// RVTools spells power states `poweredOn`, `poweredOff` and `suspended`
+pub(crate) fn rvtools_powerstate(state: &str) -> Option<String> {
+    match state.to_ascii_uppercase().as_str() {
+        "POWERED_ON" | "ON" => Some("poweredOn".to_string()),
+        "POWERED_OFF" | "OFF" => Some("poweredOff".to_string()),
+        "SUSPENDED" | "PAUSED" => Some("suspended".to_string()),
+        _ => None,
+    }
+}
+
+pub(crate) fn network_error(platform: &str, e: reqwest::Error) -> CoreEngineError {
+    CoreEngineError::NetworkError(format!("{} request failed: {}", platform, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_powerstates_use_rvtools_spelling() {
+        assert_eq!(rvtools_powerstate("POWERED_ON").as_deref(), Some("poweredOn"));
+        assert_eq!(rvtools_powerstate("off").as_deref(), Some("poweredOff"));
+        assert_eq!(rvtools_powerstate("UNKNOWN"), None);
+    }
+}
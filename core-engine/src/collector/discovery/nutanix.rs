@@ -0,0 +1,216 @@
+// Nutanix discovery over the Prism Central v3 API
+//
+// Clusters, hosts and VMs come from the paged `<kind>/list` endpoints.
+// Prism Central lists itself as a cluster; that entry is skipped.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::{http_client, network_error, rvtools_powerstate, DiscoverySource};
+use crate::collector::config::{secret, NutanixConfig};
+use crate::collector::proto::{ClusterConfig, Host, InventoryChunk, Vm};
+use crate::units::Bytes;
+use crate::CoreEngineError;
+
+const PAGE_SIZE: u64 = 250;
+
+pub struct NutanixSource {
+    config: NutanixConfig,
+}
+
+impl NutanixSource {
+    pub fn new(config: NutanixConfig) -> Self {
+        Self { config }
+    }
+
+    /// Page through a v3 `<endpoint>/list` call
+    async fn list(&self, client: &reqwest::Client, endpoint: &str, kind: &str) -> Result<Vec<Value>, CoreEngineError> {
+        let url = format!("{}/api/nutanix/v3/{}/list", self.config.url.trim_end_matches('/'), endpoint);
+        let password = secret(&self.config.password_env)?;
+        let mut entities = Vec::new();
+        let mut offset = 0u64;
+        loop {
+            let response = client
+                .post(&url)
+                .basic_auth(&self.config.username, Some(&password))
+                .json(&json!({ "kind": kind, "length": PAGE_SIZE, "offset": offset }))
+                .send()
+                .await
+                .map_err(|e| network_error("Prism Central", e))?;
+            let status = response.status();
+            if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+                return Err(CoreEngineError::authentication(format!(
+                    "Prism Central rejected the credentials ({})",
+                    status
+                )));
+            }
+            if !status.is_success() {
+                return Err(CoreEngineError::NetworkError(format!("Prism Central {} returned {}", endpoint, status)));
+            }
+            let page: Value = response.json().await.map_err(|e| network_error("Prism Central", e))?;
+            let batch = page["entities"].as_array().cloned().unwrap_or_default();
+            let fetched = batch.len() as u64;
+            entities.extend(batch);
+
+            offset += fetched;
+            if fetched == 0 || offset >= page["metadata"]["total_matches"].as_u64().unwrap_or(0) {
+                return Ok(entities);
+            }
+        }
+    }
+
+    fn mock_entities() -> (Vec<Value>, Vec<Value>, Vec<Value>) {
+        let cluster = json!({ "metadata": { "uuid": "c-1" }, "status": { "name": "NTNX-Prod",
+            "resources": { "config": { "service_list": ["AOS"] } } } });
+        let host = json!({ "status": { "name": "ntnx-a", "cluster_reference": { "name": "NTNX-Prod" },
+            "resources": { "cpu_model": "Intel Xeon Gold 6248R", "num_cpu_sockets": 2, "num_cpu_cores": 48,
+                "memory_capacity_mib": 786432, "hypervisor": { "hypervisor_full_name": "AHV 20230302" } } } });
+        let vm = json!({ "spec": { "description": "ERP database" }, "status": { "name": "SQL-Prod-01",
+            "cluster_reference": { "name": "NTNX-Prod" },
+            "resources": { "power_state": "ON", "num_sockets": 4, "num_vcpus_per_socket": 2,
+                "memory_size_mib": 65536, "host_reference": { "name": "ntnx-a" },
+                "disk_list": [{ "device_properties": { "device_type": "DISK" }, "disk_size_bytes": 107374182400u64 }],
+                "nic_list": [{ "ip_endpoint_list": [{ "ip": "10.20.0.15" }] }],
+                "guest_tools": { "nutanix_guest_tools": { "guest_os_version": "Windows Server 2022" } } } } });
+        (vec![cluster], vec![host], vec![vm])
+    }
+}
+
+fn is_prism_central(cluster: &Value) -> bool {
+    cluster["status"]["resources"]["config"]["service_list"]
+        .as_array()
+        .is_some_and(|services| services.iter().any(|s| s == "PRISM_CENTRAL"))
+}
+
+fn reference_name(reference: &Value) -> Option<String> {
+    reference["name"].as_str().map(str::to_string)
+}
+
+fn map_host(entity: &Value) -> Option<Host> {
+    let status = &entity["status"];
+    let resources = &status["resources"];
+    Some(Host {
+        name: status["name"].as_str()?.to_string(),
+        cluster: reference_name(&status["cluster_reference"]),
+        cpu_model: resources["cpu_model"].as_str().map(str::to_string),
+        sockets: resources["num_cpu_sockets"].as_i64().unwrap_or(0) as i32,
+        cores: resources["num_cpu_cores"].as_i64().unwrap_or(0) as i32,
+        memory_mb: resources["memory_capacity_mib"].as_i64().unwrap_or(0),
+        esx_version: resources["hypervisor"]["hypervisor_full_name"].as_str().map(str::to_string),
+        ..Default::default()
+    })
+}
+
+fn map_vm(entity: &Value) -> Option<Vm> {
+    let status = &entity["status"];
+    let resources = &status["resources"];
+    let disks: Vec<&Value> = resources["disk_list"]
+        .as_array()
+        .map(|disks| {
+            disks
+                .iter()
+                .filter(|disk| disk["device_properties"]["device_type"].as_str().unwrap_or("DISK") == "DISK")
+                .collect()
+        })
+        .unwrap_or_default();
+    let nics = resources["nic_list"].as_array().cloned().unwrap_or_default();
+    let sockets = resources["num_sockets"].as_i64().unwrap_or(0);
+    let per_socket = resources["num_vcpus_per_socket"].as_i64().unwrap_or(1);
+
+    Some(Vm {
+        name: status["name"].as_str()?.to_string(),
+        powerstate: resources["power_state"].as_str().and_then(rvtools_powerstate),
+        cpus: (sockets * per_socket) as i32,
+        memory_mb: resources["memory_size_mib"].as_i64().unwrap_or(0) as i32,
+        provisioned_mb: Some(
+            Bytes::new(disks.iter().filter_map(|d| d["disk_size_bytes"].as_u64()).sum()).as_mib() as i32,
+        ),
+        primary_ip_address: nics
+            .iter()
+            .flat_map(|nic| nic["ip_endpoint_list"].as_array().cloned().unwrap_or_default())
+            .find_map(|ip| ip["ip"].as_str().map(str::to_string)),
+        cluster: reference_name(&status["cluster_reference"]),
+        host: reference_name(&resources["host_reference"]),
+        os: resources["guest_tools"]["nutanix_guest_tools"]["guest_os_version"].as_str().map(str::to_string),
+        num_disks: disks.len() as i32,
+        num_nics: nics.len() as i32,
+        annotation: entity["spec"]["description"].as_str().map(str::to_string),
+        ..Default::default()
+    })
+}
+
+/// Append clusters, hosts and VMs, counting hosts per cluster and VMs per host
+fn add_entities(inventory: &mut InventoryChunk, clusters: &[Value], hosts: &[Value], vms: &[Value]) {
+    let vms: Vec<Vm> = vms.iter().filter_map(map_vm).collect();
+    let hosts: Vec<Host> = hosts.iter().filter_map(map_host).collect();
+
+    let mut vms_per_host: HashMap<&str, i32> = HashMap::new();
+    for vm in &vms {
+        if let Some(host) = &vm.host {
+            *vms_per_host.entry(host.as_str()).or_default() += 1;
+        }
+    }
+    for cluster in clusters.iter().filter(|c| !is_prism_central(c)) {
+        let Some(name) = cluster["status"]["name"].as_str() else {
+            continue;
+        };
+        inventory.clusters.push(ClusterConfig {
+            name: name.to_string(),
+            num_hosts: hosts.iter().filter(|h| h.cluster.as_deref() == Some(name)).count() as i32,
+            ..Default::default()
+        });
+    }
+    for mut host in hosts.iter().cloned() {
+        host.vm_count = vms_per_host.get(host.name.as_str()).copied().unwrap_or(0);
+        inventory.hosts.push(host);
+    }
+    inventory.vms.extend(vms);
+}
+
+#[async_trait]
+impl DiscoverySource for NutanixSource {
+    fn name(&self) -> &'static str {
+        "nutanix"
+    }
+
+    async fn discover(&self, inventory: &mut InventoryChunk) -> Result<(), CoreEngineError> {
+        let (clusters, hosts, vms) = if self.config.url.starts_with("mock://") {
+            Self::mock_entities()
+        } else {
+            let client = http_client(self.config.insecure)?;
+            (
+                self.list(&client, "clusters", "cluster").await?,
+                self.list(&client, "hosts", "host").await?,
+                self.list(&client, "vms", "vm").await?,
+            )
+        };
+        add_entities(inventory, &clusters, &hosts, &vms);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entities_map_to_inventory_rows() {
+        let (mut clusters, hosts, vms) = NutanixSource::mock_entities();
+        clusters.push(json!({ "status": { "name": "prism-central",
+            "resources": { "config": { "service_list": ["PRISM_CENTRAL"] } } } }));
+        let mut inventory = InventoryChunk::default();
+        add_entities(&mut inventory, &clusters, &hosts, &vms);
+
+        assert_eq!(inventory.clusters.len(), 1);
+        assert_eq!(inventory.clusters[0].num_hosts, 1);
+        assert_eq!(inventory.hosts[0].vm_count, 1);
+        assert_eq!(inventory.hosts[0].cores, 48);
+
+        let vm = &inventory.vms[0];
+        assert_eq!(vm.cpus, 8);
+        assert_eq!(vm.provisioned_mb, Some(102400));
+        assert_eq!(vm.powerstate.as_deref(), Some("poweredOn"));
+        assert_eq!(vm.primary_ip_address.as_deref(), Some("10.20.0.15"));
+    }
+}
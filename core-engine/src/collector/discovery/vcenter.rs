@@ -0,0 +1,338 @@
+// vCenter discovery over the vSphere Automation REST API (vSphere 7.0 U2+)
+//
+// VMs are listed per host: list calls stop at 4000 results, and going
+// through hosts also tells which host and cluster each VM runs on. The API
+// does not expose host CPU and memory, so hosts carry names and placement
+// only.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use super::{http_client, network_error, rvtools_powerstate, DiscoverySource};
+use crate::collector::config::{secret, VCenterConfig};
+use crate::collector::proto::{ClusterConfig, Datastore, Host, InventoryChunk, Vm, VmTools};
+use crate::units::Bytes;
+use crate::CoreEngineError;
+
+/// VM detail requests in flight at once
+const VM_DETAIL_CONCURRENCY: usize = 8;
+const SESSION_HEADER: &str = "vmware-api-session-id";
+
+pub struct VCenterSource {
+    config: VCenterConfig,
+}
+
+/// An authenticated API session; cheap to clone into detail tasks
+#[derive(Clone)]
+struct Session {
+    client: reqwest::Client,
+    base: String,
+    token: String,
+}
+
+impl Session {
+    async fn get(&self, path: &str) -> Result<Value, CoreEngineError> {
+        let response = self
+            .client
+            .get(format!("{}/api/{}", self.base, path))
+            .header(SESSION_HEADER, &self.token)
+            .send()
+            .await
+            .map_err(|e| network_error("vCenter", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(CoreEngineError::NetworkError(format!("vCenter {} returned {}", path, status)));
+        }
+        response.json().await.map_err(|e| network_error("vCenter", e))
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<Value>, CoreEngineError> {
+        Ok(self.get(path).await?.as_array().cloned().unwrap_or_default())
+    }
+}
+
+/// One VM of a host list plus its detail calls
+struct VmRecord {
+    vm: Vm,
+    tools: Option<VmTools>,
+}
+
+impl VCenterSource {
+    pub fn new(config: VCenterConfig) -> Self {
+        Self { config }
+    }
+
+    async fn login(&self) -> Result<Session, CoreEngineError> {
+        let client = http_client(self.config.insecure)?;
+        let base = self.config.url.trim_end_matches('/').to_string();
+        let response = client
+            .post(format!("{}/api/session", base))
+            .basic_auth(&self.config.username, Some(secret(&self.config.password_env)?))
+            .send()
+            .await
+            .map_err(|e| network_error("vCenter", e))?;
+        if !response.status().is_success() {
+            return Err(CoreEngineError::authentication(format!(
+                "vCenter rejected the credentials ({})",
+                response.status()
+            )));
+        }
+        let token: String = response.json().await.map_err(|e| network_error("vCenter", e))?;
+        Ok(Session { client, base, token })
+    }
+
+    async fn logout(session: &Session) {
+        let _ = session
+            .client
+            .delete(format!("{}/api/session", session.base))
+            .header(SESSION_HEADER, &session.token)
+            .send()
+            .await;
+    }
+
+    async fn read(&self, session: &Session, inventory: &mut InventoryChunk) -> Result<(), CoreEngineError> {
+        let limit = Arc::new(Semaphore::new(VM_DETAIL_CONCURRENCY));
+        let mut details: JoinSet<Result<VmRecord, CoreEngineError>> = JoinSet::new();
+
+        for datacenter in session.list("vcenter/datacenter").await? {
+            let (Some(dc_id), Some(dc_name)) = (datacenter["datacenter"].as_str(), datacenter["name"].as_str()) else {
+                continue;
+            };
+
+            let mut clustered: Vec<(String, String)> = Vec::new();
+            for cluster in session.list(&format!("vcenter/cluster?datacenters={}", dc_id)).await? {
+                let (Some(id), Some(name)) = (cluster["cluster"].as_str(), cluster["name"].as_str()) else {
+                    continue;
+                };
+                clustered.push((id.to_string(), name.to_string()));
+                inventory.clusters.push(ClusterConfig {
+                    name: name.to_string(),
+                    datacenter: Some(dc_name.to_string()),
+                    ha_enabled: cluster["ha_enabled"].as_bool(),
+                    drs_enabled: cluster["drs_enabled"].as_bool(),
+                    ..Default::default()
+                });
+            }
+
+            // Hosts of each cluster, then the standalone ones
+            let mut hosts: Vec<(Value, Option<String>)> = Vec::new();
+            for (cluster_id, cluster_name) in &clustered {
+                for host in session.list(&format!("vcenter/host?clusters={}", cluster_id)).await? {
+                    hosts.push((host, Some(cluster_name.clone())));
+                }
+            }
+            for host in session.list(&format!("vcenter/host?datacenters={}", dc_id)).await? {
+                if !hosts.iter().any(|(known, _)| known["host"] == host["host"]) {
+                    hosts.push((host, None));
+                }
+            }
+
+            for (host, cluster) in hosts {
+                let (Some(host_id), Some(host_name)) = (host["host"].as_str(), host["name"].as_str()) else {
+                    continue;
+                };
+                if let Some(config) = inventory.clusters.iter_mut().find(|c| Some(&c.name) == cluster.as_ref()) {
+                    config.num_hosts += 1;
+                }
+
+                let vms = session.list(&format!("vcenter/vm?hosts={}", host_id)).await?;
+                inventory.hosts.push(Host {
+                    name: host_name.to_string(),
+                    datacenter: Some(dc_name.to_string()),
+                    cluster: cluster.clone(),
+                    vm_count: vms.len() as i32,
+                    ..Default::default()
+                });
+
+                for summary in vms {
+                    let Some(vm_id) = summary["vm"].as_str().map(str::to_string) else {
+                        continue;
+                    };
+                    let vm = Vm {
+                        name: summary["name"].as_str().unwrap_or(&vm_id).to_string(),
+                        powerstate: summary["power_state"].as_str().and_then(rvtools_powerstate),
+                        cpus: summary["cpu_count"].as_i64().unwrap_or(0) as i32,
+                        memory_mb: summary["memory_size_MiB"].as_i64().unwrap_or(0) as i32,
+                        cluster: cluster.clone(),
+                        host: Some(host_name.to_string()),
+                        datacenter: Some(dc_name.to_string()),
+                        ..Default::default()
+                    };
+                    let session = session.clone();
+                    let limit = Arc::clone(&limit);
+                    details.spawn(async move {
+                        let _permit = limit.acquire_owned().await.expect("semaphore is never closed");
+                        Self::vm_details(&session, &vm_id, vm).await
+                    });
+                }
+            }
+
+            for datastore in session.list(&format!("vcenter/datastore?datacenters={}", dc_id)).await? {
+                let capacity = Bytes::new(datastore["capacity"].as_u64().unwrap_or(0)).as_mib() as i64;
+                let free = Bytes::new(datastore["free_space"].as_u64().unwrap_or(0)).as_mib() as i64;
+                inventory.datastores.push(Datastore {
+                    name: datastore["name"].as_str().unwrap_or_default().to_string(),
+                    datastore_type: datastore["type"].as_str().map(str::to_string),
+                    capacity_mb: capacity,
+                    in_use_mb: capacity - free,
+                    free_mb: free,
+                    ..Default::default()
+                });
+            }
+        }
+
+        while let Some(joined) = details.join_next().await {
+            let record = joined.map_err(|e| CoreEngineError::NetworkError(format!("VM detail task failed: {}", e)))??;
+            inventory.tools.extend(record.tools);
+            inventory.vms.push(record.vm);
+        }
+        inventory.vms.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
+    /// Disks, NICs, guest identity and VMware Tools state. Guest identity
+    /// and tools fail for powered-off VMs; those fields stay empty.
+    async fn vm_details(session: &Session, vm_id: &str, mut vm: Vm) -> Result<VmRecord, CoreEngineError> {
+        let detail = session.get(&format!("vcenter/vm/{}", vm_id)).await?;
+        let disks: Vec<&Value> = detail["disks"].as_object().map(|d| d.values().collect()).unwrap_or_default();
+        vm.num_disks = disks.len() as i32;
+        vm.num_nics = detail["nics"].as_object().map_or(0, |n| n.len()) as i32;
+        vm.provisioned_mb = Some(Bytes::new(disks.iter().filter_map(|d| d["capacity"].as_u64()).sum()).as_mib() as i32);
+        vm.os = detail["guest_OS"].as_str().map(str::to_string);
+
+        if let Ok(identity) = session.get(&format!("vcenter/vm/{}/guest/identity", vm_id)).await {
+            if let Some(full_name) = identity["full_name"]["default_message"].as_str() {
+                vm.os = Some(full_name.to_string());
+            }
+            vm.dns_name = identity["host_name"].as_str().map(str::to_string);
+            vm.primary_ip_address = identity["ip_address"].as_str().map(str::to_string);
+        }
+
+        let tools = session
+            .get(&format!("vcenter/vm/{}/tools", vm_id))
+            .await
+            .ok()
+            .map(|tools| VmTools {
+                vm_name: vm.name.clone(),
+                status: tools_status(&tools),
+                version: tools["version"].as_str().map(str::to_string),
+                upgradeable: tools["version_status"]
+                    .as_str()
+                    .map(|s| s == "SUPPORTED_OLD" || s == "TOO_OLD_UNSUPPORTED"),
+                ..Default::default()
+            });
+        Ok(VmRecord { vm, tools })
+    }
+
+    fn mock_inventory(inventory: &mut InventoryChunk) {
+        let vm = |name: &str, host: &str, cpus: i32, memory_mb: i32, os: &str| Vm {
+            name: name.to_string(),
+            powerstate: Some("poweredOn".to_string()),
+            cpus,
+            memory_mb,
+            provisioned_mb: Some(memory_mb * 10),
+            cluster: Some("Cluster01".to_string()),
+            host: Some(host.to_string()),
+            datacenter: Some("DC1".to_string()),
+            os: Some(os.to_string()),
+            num_disks: 1,
+            num_nics: 1,
+            ..Default::default()
+        };
+        let host = |name: &str, vm_count: i32| Host {
+            name: name.to_string(),
+            datacenter: Some("DC1".to_string()),
+            cluster: Some("Cluster01".to_string()),
+            vm_count,
+            ..Default::default()
+        };
+        inventory.clusters.push(ClusterConfig {
+            name: "Cluster01".to_string(),
+            datacenter: Some("DC1".to_string()),
+            num_hosts: 2,
+            ha_enabled: Some(true),
+            drs_enabled: Some(true),
+            ..Default::default()
+        });
+        inventory.hosts.extend([host("esx-01.corp.local", 2), host("esx-02.corp.local", 1)]);
+        inventory.vms.extend([
+            vm("APP01", "esx-01.corp.local", 4, 8192, "Microsoft Windows Server 2019 (64-bit)"),
+            vm("DB01", "esx-01.corp.local", 8, 32768, "Microsoft Windows Server 2019 (64-bit)"),
+            vm("web-01", "esx-02.corp.local", 2, 4096, "Ubuntu Linux (64-bit)"),
+        ]);
+        inventory.datastores.push(Datastore {
+            name: "vsanDatastore".to_string(),
+            datastore_type: Some("vsan".to_string()),
+            capacity_mb: 8 * 1024 * 1024,
+            in_use_mb: 3 * 1024 * 1024,
+            free_mb: 5 * 1024 * 1024,
+            ..Default::default()
+        });
+    }
+}
+
+/// RVTools vTools status from the Automation API tools state
+fn tools_status(tools: &Value) -> Option<String> {
+    if tools["run_state"].as_str() == Some("NOT_RUNNING") && tools["version_status"].as_str() != Some("NOT_INSTALLED") {
+        return Some("toolsNotRunning".to_string());
+    }
+    let status = match tools["version_status"].as_str()? {
+        "CURRENT" => "toolsOk",
+        "NOT_INSTALLED" => "toolsNotInstalled",
+        "UNMANAGED" => "guestToolsUnmanaged",
+        _ => "toolsOld",
+    };
+    Some(status.to_string())
+}
+
+#[async_trait]
+impl DiscoverySource for VCenterSource {
+    fn name(&self) -> &'static str {
+        "vcenter"
+    }
+
+    async fn discover(&self, inventory: &mut InventoryChunk) -> Result<(), CoreEngineError> {
+        if self.config.url.starts_with("mock://") {
+            Self::mock_inventory(inventory);
+            return Ok(());
+        }
+        let session = self.login().await?;
+        let result = self.read(&session, inventory).await;
+        Self::logout(&session).await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tools_status_maps_to_rvtools_names() {
+        let status = |run: &str, version: &str| tools_status(&json!({ "run_state": run, "version_status": version }));
+        assert_eq!(status("RUNNING", "CURRENT").as_deref(), Some("toolsOk"));
+        assert_eq!(status("RUNNING", "SUPPORTED_OLD").as_deref(), Some("toolsOld"));
+        assert_eq!(status("NOT_RUNNING", "CURRENT").as_deref(), Some("toolsNotRunning"));
+        assert_eq!(status("NOT_RUNNING", "NOT_INSTALLED").as_deref(), Some("toolsNotInstalled"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_inventory_counts_hosts_and_vms() {
+        let source = VCenterSource::new(VCenterConfig {
+            url: "mock://vcenter".to_string(),
+            username: "archer".to_string(),
+            password_env: "UNUSED".to_string(),
+            insecure: false,
+        });
+        let mut inventory = InventoryChunk::default();
+        source.discover(&mut inventory).await.unwrap();
+
+        assert_eq!(inventory.vms.len(), 3);
+        assert_eq!(inventory.hosts.iter().map(|h| h.vm_count).sum::<i32>(), 3);
+        assert_eq!(inventory.clusters[0].num_hosts, 2);
+    }
+}
@@ -0,0 +1,29 @@
+//! Remote collector agent
+//!
+//! The `archer-collector` binary runs inside a customer network. It
+//! discovers VMs, hosts, clusters and datastores from vCenter and Nutanix
+//! Prism Central, fills in guest details from Active Directory, and ships
+//! each snapshot to the backend's `CollectorIngest` gRPC service over
+//! mutual TLS. Snapshots are gzipped and sealed with AES-256-GCM in a local
+//! spool first, so nothing is lost while the backend is unreachable. What to
+//! discover, how often and for which project is pulled from the backend.
+//!
+//! Only built with the `collector` feature.
+
+pub mod agent;
+pub mod config;
+pub mod discovery;
+pub mod spool;
+pub mod uplink;
+
+pub mod proto {
+    tonic::include_proto!("archer.collector.v1");
+}
+
+pub use agent::{Agent, CycleReport};
+pub use config::{AgentSettings, CollectorConfig};
+pub use spool::Spool;
+pub use uplink::Uplink;
+
+/// Reported to the backend when settings are pulled
+pub const AGENT_VERSION: &str = env!("CARGO_PKG_VERSION");
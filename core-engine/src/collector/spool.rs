@@ -0,0 +1,180 @@
+// Offline snapshot queue
+//
+// Each snapshot is one file: the protobuf encoding, gzipped, sealed with the
+// spool key in a project_crypto envelope. File names start with the capture
+// time, so listing the directory gives upload order.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use prost::Message;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::config::AgentSettings;
+use super::proto::InventoryChunk;
+use crate::project_crypto::{self, EncryptedEnvelope, ProjectKey};
+use crate::CoreEngineError;
+
+const SNAPSHOT_EXTENSION: &str = "snap";
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Encrypted, compressed snapshots waiting for upload
+pub struct Spool {
+    dir: PathBuf,
+    key: ProjectKey,
+    max_snapshots: usize,
+}
+
+impl Spool {
+    pub fn open(dir: &Path, key: ProjectKey, max_snapshots: usize) -> Result<Self, CoreEngineError> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| CoreEngineError::io(format!("Cannot create spool {}: {}", dir.display(), e)))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            key,
+            max_snapshots: max_snapshots.max(1),
+        })
+    }
+
+    /// Queue a snapshot, dropping the oldest ones beyond the limit. Returns
+    /// how many were dropped.
+    pub fn push(&self, snapshot: &InventoryChunk) -> Result<usize, CoreEngineError> {
+        if snapshot.snapshot_id.is_empty() || snapshot.snapshot_id.contains(|c: char| matches!(c, '/' | '\\' | '.')) {
+            return Err(CoreEngineError::validation("Snapshot id must be a plain file name"));
+        }
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&snapshot.encode_to_vec())
+            .and_then(|_| gzip.flush())
+            .map_err(|e| CoreEngineError::io(format!("Failed to compress snapshot: {}", e)))?;
+        let compressed = gzip
+            .finish()
+            .map_err(|e| CoreEngineError::io(format!("Failed to compress snapshot: {}", e)))?;
+        let envelope = project_crypto::encrypt(&self.key, &compressed)?;
+
+        // Written under a temporary name so a crash never leaves half a file
+        let path = self.dir.join(format!("{}.{}", snapshot.snapshot_id, SNAPSHOT_EXTENSION));
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, serde_json::to_vec(&envelope)?)
+            .and_then(|_| std::fs::rename(&partial, &path))
+            .map_err(|e| CoreEngineError::io(format!("Failed to spool snapshot: {}", e)))?;
+
+        let pending = self.pending()?;
+        let excess = pending.len().saturating_sub(self.max_snapshots);
+        for old in &pending[..excess] {
+            self.remove(old)?;
+        }
+        Ok(excess)
+    }
+
+    /// Spooled snapshots, oldest first
+    pub fn pending(&self) -> Result<Vec<PathBuf>, CoreEngineError> {
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|e| CoreEngineError::io(format!("Cannot read spool {}: {}", self.dir.display(), e)))?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == SNAPSHOT_EXTENSION))
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    pub fn load(&self, path: &Path) -> Result<InventoryChunk, CoreEngineError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| CoreEngineError::io(format!("Cannot read {}: {}", path.display(), e)))?;
+        let envelope = EncryptedEnvelope::parse(&content)
+            .ok_or_else(|| CoreEngineError::parsing(format!("{} is not a spooled snapshot", path.display())))?;
+        let compressed = project_crypto::decrypt(&self.key, &envelope)?;
+
+        let mut encoded = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut encoded)
+            .map_err(|e| CoreEngineError::parsing(format!("Corrupt snapshot {}: {}", path.display(), e)))?;
+        InventoryChunk::decode(encoded.as_slice())
+            .map_err(|e| CoreEngineError::parsing(format!("Corrupt snapshot {}: {}", path.display(), e)))
+    }
+
+    pub fn remove(&self, path: &Path) -> Result<(), CoreEngineError> {
+        std::fs::remove_file(path)
+            .map_err(|e| CoreEngineError::io(format!("Cannot remove {}: {}", path.display(), e)))
+    }
+
+    /// Settings the backend sent last; defaults before the first answer
+    pub fn load_settings(&self) -> AgentSettings {
+        std::fs::read(self.dir.join(SETTINGS_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_settings(&self, settings: &AgentSettings) -> Result<(), CoreEngineError> {
+        std::fs::write(self.dir.join(SETTINGS_FILE), serde_json::to_vec_pretty(settings)?)
+            .map_err(|e| CoreEngineError::io(format!("Cannot save collector settings: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::proto::Vm;
+
+    fn spool(max_snapshots: usize) -> (Spool, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("archer-spool-{}", uuid::Uuid::new_v4()));
+        (Spool::open(&dir, ProjectKey::generate(), max_snapshots).unwrap(), dir)
+    }
+
+    fn snapshot(id: &str, vms: usize) -> InventoryChunk {
+        InventoryChunk {
+            snapshot_id: id.to_string(),
+            vms: (0..vms)
+                .map(|i| Vm { name: format!("vm-{:04}", i), cpus: 2, memory_mb: 4096, ..Default::default() })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip_is_sealed_and_compressed() {
+        let (spool, dir) = spool(5);
+        spool.push(&snapshot("20261015T080000Z-a1", 500)).unwrap();
+
+        let pending = spool.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        let raw = std::fs::read_to_string(&pending[0]).unwrap();
+        assert!(!raw.contains("vm-0001"));
+        assert!(raw.len() < snapshot("x", 500).encoded_len());
+        assert_eq!(spool.load(&pending[0]).unwrap().vms.len(), 500);
+
+        let other = Spool::open(&dir, ProjectKey::generate(), 5).unwrap();
+        assert!(other.load(&pending[0]).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_oldest_snapshots_are_dropped_beyond_limit() {
+        let (spool, dir) = spool(2);
+        assert_eq!(spool.push(&snapshot("20261015T080000Z-a1", 1)).unwrap(), 0);
+        assert_eq!(spool.push(&snapshot("20261015T090000Z-b2", 1)).unwrap(), 0);
+        assert_eq!(spool.push(&snapshot("20261015T100000Z-c3", 1)).unwrap(), 1);
+
+        let names: Vec<String> = spool
+            .pending()
+            .unwrap()
+            .iter()
+            .map(|p| p.file_stem().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["20261015T090000Z-b2", "20261015T100000Z-c3"]);
+        assert!(spool.push(&snapshot("../escape", 1)).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_settings_default_until_saved() {
+        let (spool, dir) = spool(2);
+        assert_eq!(spool.load_settings(), AgentSettings::default());
+        let settings = AgentSettings { project_id: Some("p1".to_string()), ..Default::default() };
+        spool.save_settings(&settings).unwrap();
+        assert_eq!(spool.load_settings(), settings);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,193 @@
+// gRPC connection to the backend's CollectorIngest service
+//
+// Mutual TLS with the collector's client certificate, the collector token
+// on every call, gzip in both directions.
+
+use tonic::codec::CompressionEncoding;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
+use tonic::{Code, Request, Status};
+
+use super::config::{secret, AgentSettings, BackendConfig};
+use super::proto::collector_ingest_client::CollectorIngestClient;
+use super::proto::{InventoryChunk, InventorySummary, SettingsRequest};
+use super::AGENT_VERSION;
+use crate::CoreEngineError;
+
+/// Inventory rows per chunk; keeps messages well below the server's 16 MiB
+pub const CHUNK_ROWS: usize = 2000;
+
+/// Adds `authorization: Bearer <token>` to every call
+#[derive(Clone)]
+pub struct BearerToken(MetadataValue<Ascii>);
+
+impl Interceptor for BearerToken {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        request.metadata_mut().insert("authorization", self.0.clone());
+        Ok(request)
+    }
+}
+
+pub struct Uplink {
+    client: CollectorIngestClient<InterceptedService<Channel, BearerToken>>,
+}
+
+impl Uplink {
+    pub async fn connect(config: &BackendConfig) -> Result<Self, CoreEngineError> {
+        let read = |path: &std::path::Path| {
+            std::fs::read(path).map_err(|e| CoreEngineError::config(format!("Cannot read {}: {}", path.display(), e)))
+        };
+        let mut tls = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(read(&config.ca_cert)?))
+            .identity(Identity::from_pem(read(&config.client_cert)?, read(&config.client_key)?));
+        if let Some(domain) = &config.tls_domain {
+            tls = tls.domain_name(domain.clone());
+        }
+
+        let channel = Channel::from_shared(config.endpoint.clone())
+            .map_err(|e| CoreEngineError::config(format!("Invalid backend endpoint: {}", e)))?
+            .tls_config(tls)
+            .map_err(|e| CoreEngineError::config(format!("Invalid TLS material: {}", e)))?
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .connect()
+            .await
+            .map_err(|e| CoreEngineError::NetworkError(format!("Backend unreachable: {}", e)))?;
+
+        let token: MetadataValue<Ascii> = format!("Bearer {}", secret(&config.token_env)?)
+            .parse()
+            .map_err(|_| CoreEngineError::config("Collector token contains invalid characters"))?;
+        let client = CollectorIngestClient::with_interceptor(channel, BearerToken(token))
+            .send_compressed(CompressionEncoding::Gzip)
+            .accept_compressed(CompressionEncoding::Gzip);
+        Ok(Self { client })
+    }
+
+    pub async fn fetch_settings(&mut self) -> Result<AgentSettings, CoreEngineError> {
+        let request = SettingsRequest {
+            agent_version: AGENT_VERSION.to_string(),
+            hostname: std::env::var("HOSTNAME").unwrap_or_default(),
+        };
+        let settings = self.client.get_settings(request).await.map_err(status_error)?;
+        Ok(AgentSettings::from(settings.into_inner()))
+    }
+
+    /// Upload a spooled snapshot to a project
+    pub async fn upload(
+        &mut self,
+        project_id: &str,
+        snapshot: InventoryChunk,
+    ) -> Result<InventorySummary, CoreEngineError> {
+        let chunks = split_snapshot(snapshot, project_id, CHUNK_ROWS);
+        let summary = self
+            .client
+            .upload_inventory(tokio_stream::iter(chunks))
+            .await
+            .map_err(status_error)?;
+        Ok(summary.into_inner())
+    }
+}
+
+/// Validation errors mean the backend will never take the snapshot; the
+/// rest are worth retrying
+fn status_error(status: Status) -> CoreEngineError {
+    let message = status.message().to_string();
+    match status.code() {
+        Code::InvalidArgument => CoreEngineError::validation(message),
+        Code::Unauthenticated | Code::PermissionDenied => CoreEngineError::authentication(message),
+        _ => CoreEngineError::NetworkError(format!("{:?}: {}", status.code(), message)),
+    }
+}
+
+/// Cut a snapshot into stream chunks of at most `rows` rows each, every one
+/// naming the project and snapshot, the last one marked
+pub fn split_snapshot(snapshot: InventoryChunk, project_id: &str, rows: usize) -> Vec<InventoryChunk> {
+    let InventoryChunk {
+        snapshot_id,
+        vms,
+        hosts,
+        clusters,
+        datastores,
+        resource_pools,
+        snapshots,
+        tools,
+        ..
+    } = snapshot;
+    let template = InventoryChunk {
+        project_id: project_id.to_string(),
+        snapshot_id,
+        ..Default::default()
+    };
+    let mut chunker = Chunker { chunks: vec![template.clone()], template, rows: rows.max(1), used: 0 };
+
+    vms.into_iter().for_each(|row| chunker.slot().vms.push(row));
+    hosts.into_iter().for_each(|row| chunker.slot().hosts.push(row));
+    clusters.into_iter().for_each(|row| chunker.slot().clusters.push(row));
+    datastores.into_iter().for_each(|row| chunker.slot().datastores.push(row));
+    resource_pools.into_iter().for_each(|row| chunker.slot().resource_pools.push(row));
+    snapshots.into_iter().for_each(|row| chunker.slot().snapshots.push(row));
+    tools.into_iter().for_each(|row| chunker.slot().tools.push(row));
+
+    let mut chunks = chunker.chunks;
+    if let Some(last) = chunks.last_mut() {
+        last.last = true;
+    }
+    chunks
+}
+
+struct Chunker {
+    template: InventoryChunk,
+    chunks: Vec<InventoryChunk>,
+    rows: usize,
+    used: usize,
+}
+
+impl Chunker {
+    /// The chunk the next row goes into
+    fn slot(&mut self) -> &mut InventoryChunk {
+        if self.used == self.rows {
+            self.chunks.push(self.template.clone());
+            self.used = 0;
+        }
+        self.used += 1;
+        self.chunks.last_mut().expect("at least one chunk")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::proto::{Host, Vm};
+
+    #[test]
+    fn test_split_snapshot_fills_chunks_and_marks_last() {
+        let snapshot = InventoryChunk {
+            snapshot_id: "s1".to_string(),
+            vms: (0..5).map(|i| Vm { name: format!("vm-{}", i), ..Default::default() }).collect(),
+            hosts: vec![Host { name: "esx-01".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        let chunks = split_snapshot(snapshot, "p1", 2);
+
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.project_id == "p1" && c.snapshot_id == "s1"));
+        assert_eq!(chunks.iter().map(|c| c.vms.len()).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert_eq!(chunks[2].hosts.len(), 1);
+        assert_eq!(chunks.iter().filter(|c| c.last).count(), 1);
+        assert!(chunks[2].last);
+    }
+
+    #[test]
+    fn test_split_empty_snapshot_still_sends_last_chunk() {
+        let chunks = split_snapshot(InventoryChunk::default(), "p1", CHUNK_ROWS);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].last);
+    }
+
+    #[test]
+    fn test_only_invalid_arguments_are_final() {
+        assert!(matches!(status_error(Status::invalid_argument("too big")), CoreEngineError::ValidationError(_)));
+        assert!(matches!(status_error(Status::unavailable("down")), CoreEngineError::NetworkError(_)));
+    }
+}
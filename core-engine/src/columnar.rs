@@ -0,0 +1,418 @@
+//! Columnar view of a parsed environment for analysis, sizing and placement at scale
+//!
+//! `VsphereEnvironment` keeps every VM as its own struct with a dozen
+//! optional strings and nested disk and NIC vectors. That suits display and
+//! document generation, but at 100k+ VMs the hot loops in analysis, sizing
+//! and placement spend their time chasing pointers through fields they never
+//! read. [`ColumnarEnvironment`] keeps only the fields those loops use, one
+//! contiguous column per field. VM names share a single buffer, and
+//! low-cardinality strings (hosts, guest OS) are interned once. VMs are
+//! grouped by cluster, so each cluster is a contiguous row range.
+//!
+//! Build it once per loaded environment and keep it next to the original;
+//! both describe the same estate and give the same analysis and sizing results.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::ops::Range;
+
+use uuid::Uuid;
+
+use crate::models::*;
+
+/// Handle to a string in a [`StringPool`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StrId(NonZeroU32);
+
+/// Interned strings; each distinct value is stored once
+#[derive(Debug, Clone, Default)]
+pub struct StringPool {
+    values: Vec<Box<str>>,
+    index: HashMap<Box<str>, StrId>,
+}
+
+impl StringPool {
+    pub fn intern(&mut self, value: &str) -> StrId {
+        if let Some(id) = self.index.get(value) {
+            return *id;
+        }
+        self.values.push(value.into());
+        let id = StrId(NonZeroU32::new(self.values.len() as u32).expect("pool index starts at 1"));
+        self.index.insert(value.into(), id);
+        id
+    }
+
+    pub fn get(&self, id: StrId) -> &str {
+        &self.values[id.0.get() as usize - 1]
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        // Each value is held twice: once in `values`, once as an index key
+        let strings: usize = self.values.iter().map(|v| v.len() * 2).sum();
+        strings
+            + self.values.capacity() * std::mem::size_of::<Box<str>>()
+            + self.index.capacity() * (std::mem::size_of::<Box<str>>() + std::mem::size_of::<StrId>())
+    }
+}
+
+/// Bits of [`VmTable::flags`]
+pub mod vm_flags {
+    pub const TEMPLATE: u8 = 1 << 0;
+    pub const RDM: u8 = 1 << 1;
+    pub const FT: u8 = 1 << 2;
+    pub const ZOMBIE: u8 = 1 << 3;
+    pub const NEEDS_MANUAL_ATTENTION: u8 = 1 << 4;
+    pub const CRITICAL: u8 = 1 << 5;
+}
+
+/// One column per VM field used by analysis, sizing and placement
+#[derive(Debug, Clone, Default)]
+pub struct VmTable {
+    names: String,
+    /// End offset of each VM's name in `names`
+    name_ends: Vec<u32>,
+    pub host: Vec<StrId>,
+    pub guest_os: Vec<Option<StrId>>,
+    pub power_state: Vec<PowerState>,
+    pub vcpu: Vec<u32>,
+    pub memory_gb: Vec<u32>,
+    /// Sum of the VM's disks as consumed in the guest
+    pub consumed_storage_gb: Vec<f64>,
+    /// Sum of the VM's disks as provisioned
+    pub provisioned_storage_gb: Vec<f64>,
+    /// Disks provisioned thick (lazy zeroed)
+    pub thick_disks: Vec<u16>,
+    /// See [`vm_flags`]
+    pub flags: Vec<u8>,
+}
+
+impl VmTable {
+    pub fn len(&self) -> usize {
+        self.name_ends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name_ends.is_empty()
+    }
+
+    pub fn name(&self, row: usize) -> &str {
+        let start = if row == 0 { 0 } else { self.name_ends[row - 1] as usize };
+        &self.names[start..self.name_ends[row] as usize]
+    }
+
+    pub fn has_flag(&self, row: usize, flag: u8) -> bool {
+        self.flags[row] & flag != 0
+    }
+
+    /// Powered on and not a template: the VMs sizing has to place
+    pub fn is_active(&self, row: usize) -> bool {
+        self.power_state[row] == PowerState::PoweredOn && !self.has_flag(row, vm_flags::TEMPLATE)
+    }
+
+    fn push(&mut self, vm: &VirtualMachine, strings: &mut StringPool) {
+        self.names.push_str(&vm.name);
+        self.name_ends.push(self.names.len() as u32);
+        self.host.push(strings.intern(&vm.host_name));
+        self.guest_os.push(vm.guest_os.as_deref().map(|os| strings.intern(os)));
+        self.power_state.push(vm.power_state.clone());
+        self.vcpu.push(vm.num_vcpu);
+        self.memory_gb.push(vm.memory_gb);
+        self.consumed_storage_gb.push(vm.disks.iter().map(|d| d.consumed_in_guest_gb).sum());
+        self.provisioned_storage_gb.push(vm.disks.iter().map(|d| d.provisioned_gb).sum());
+        self.thick_disks.push(
+            vm.disks
+                .iter()
+                .filter(|d| d.provisioning_type == ProvisioningType::Thick)
+                .count()
+                .min(u16::MAX as usize) as u16,
+        );
+
+        let special = &vm.special_flags;
+        let mut flags = 0;
+        for (set, flag) in [
+            (vm.is_template, vm_flags::TEMPLATE),
+            (special.has_rdm, vm_flags::RDM),
+            (special.ft_enabled, vm_flags::FT),
+            (special.is_zombie, vm_flags::ZOMBIE),
+            (special.needs_manual_attention, vm_flags::NEEDS_MANUAL_ATTENTION),
+            (special.is_critical_workload, vm_flags::CRITICAL),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+        self.flags.push(flags);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.names.shrink_to_fit();
+        self.name_ends.shrink_to_fit();
+        self.host.shrink_to_fit();
+        self.guest_os.shrink_to_fit();
+        self.power_state.shrink_to_fit();
+        self.vcpu.shrink_to_fit();
+        self.memory_gb.shrink_to_fit();
+        self.consumed_storage_gb.shrink_to_fit();
+        self.provisioned_storage_gb.shrink_to_fit();
+        self.thick_disks.shrink_to_fit();
+        self.flags.shrink_to_fit();
+    }
+
+    fn heap_bytes(&self) -> usize {
+        fn column<T>(values: &Vec<T>) -> usize {
+            values.capacity() * std::mem::size_of::<T>()
+        }
+        self.names.capacity()
+            + column(&self.name_ends)
+            + column(&self.host)
+            + column(&self.guest_os)
+            + column(&self.power_state)
+            + column(&self.vcpu)
+            + column(&self.memory_gb)
+            + column(&self.consumed_storage_gb)
+            + column(&self.provisioned_storage_gb)
+            + column(&self.thick_disks)
+            + column(&self.flags)
+    }
+}
+
+/// A cluster and the rows of its VMs
+#[derive(Debug, Clone)]
+pub struct ColumnarCluster {
+    pub name: String,
+    pub host_count: usize,
+    pub metrics: ClusterMetrics,
+    pub rows: Range<usize>,
+}
+
+/// Columnar copy of a [`VsphereEnvironment`]
+#[derive(Debug, Clone)]
+pub struct ColumnarEnvironment {
+    pub id: Uuid,
+    pub name: String,
+    pub strings: StringPool,
+    pub vms: VmTable,
+    pub clusters: Vec<ColumnarCluster>,
+    /// Rows of VMs on hosts outside any cluster; they follow the clustered rows
+    pub standalone_rows: Range<usize>,
+    pub summary_metrics: EnvironmentSummary,
+}
+
+impl ColumnarEnvironment {
+    pub fn from_environment(environment: &VsphereEnvironment) -> Self {
+        let mut strings = StringPool::default();
+        let mut vms = VmTable::default();
+        let mut clusters = Vec::with_capacity(environment.clusters.len());
+
+        for cluster in &environment.clusters {
+            let start = vms.len();
+            for vm in &cluster.vms {
+                vms.push(vm, &mut strings);
+            }
+            clusters.push(ColumnarCluster {
+                name: cluster.name.clone(),
+                host_count: cluster.hosts.len(),
+                metrics: cluster.metrics.clone(),
+                rows: start..vms.len(),
+            });
+        }
+
+        let standalone_start = vms.len();
+        for vm in environment.standalone_hosts.iter().flat_map(|h| &h.vms) {
+            vms.push(vm, &mut strings);
+        }
+        let standalone_rows = standalone_start..vms.len();
+        vms.shrink_to_fit();
+
+        Self {
+            id: environment.id,
+            name: environment.name.clone(),
+            strings,
+            vms,
+            clusters,
+            standalone_rows,
+            summary_metrics: environment.summary_metrics.clone(),
+        }
+    }
+
+    /// Rows of every VM in a cluster, as sized by `sizing::calculate_sizing`
+    pub fn clustered_rows(&self) -> Range<usize> {
+        0..self.standalone_rows.start
+    }
+
+    /// Approximate heap memory held by this environment
+    pub fn heap_bytes(&self) -> usize {
+        let clusters: usize = self
+            .clusters
+            .iter()
+            .map(|c| c.name.capacity() + std::mem::size_of::<ColumnarCluster>())
+            .sum();
+        self.vms.heap_bytes() + self.strings.heap_bytes() + clusters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::AnalysisEngine;
+    use crate::sizing::SizingEngine;
+    use chrono::Utc;
+
+    fn vm(name: &str, cluster: &str, vcpu: u32, memory_gb: u32, power_state: PowerState, thick: bool) -> VirtualMachine {
+        VirtualMachine {
+            name: name.to_string(),
+            cluster_name: cluster.to_string(),
+            host_name: format!("{}-esx01", cluster),
+            power_state: power_state.clone(),
+            num_vcpu: vcpu,
+            memory_gb,
+            guest_os: Some("Microsoft Windows Server 2022 (64-bit)".to_string()),
+            vm_version: None,
+            tools_status: None,
+            tools_version: None,
+            is_template: false,
+            disks: vec![VirtualDisk {
+                vm_name: name.to_string(),
+                disk_label: "Hard disk 1".to_string(),
+                provisioned_gb: 100.0,
+                consumed_in_guest_gb: 40.0,
+                consumed_on_datastore_gb: 100.0,
+                is_rdm: false,
+                disk_mode: None,
+                provisioning_type: if thick { ProvisioningType::Thick } else { ProvisioningType::Thin },
+                datastore_name: None,
+            }],
+            nics: Vec::new(),
+            notes: None,
+            annotation: None,
+            folder: None,
+            resource_pool: None,
+            created_date: None,
+            last_powered_on: None,
+            special_flags: VmSpecialFlags {
+                is_zombie: power_state == PowerState::PoweredOff,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn cluster(name: &str, vms: Vec<VirtualMachine>) -> Cluster {
+        let total_vcpus = vms.iter().map(|v| v.num_vcpu).sum();
+        Cluster {
+            name: name.to_string(),
+            hosts: Vec::new(),
+            metrics: ClusterMetrics {
+                total_hosts: 2,
+                total_vms: vms.len(),
+                total_pcpu_cores: 64,
+                total_vcpus,
+                current_vcpu_pcpu_ratio: total_vcpus as f32 / 64.0,
+                total_memory_gb: 1024,
+                provisioned_memory_gb: vms.iter().map(|v| v.memory_gb as f64).sum(),
+                memory_overcommit_ratio: 0.5,
+                total_storage_gb: 100.0 * vms.len() as f64,
+                consumed_storage_gb: 40.0 * vms.len() as f64,
+            },
+            health_status: ClusterHealth {
+                zombie_vms: vms.iter().filter(|v| v.special_flags.is_zombie).map(|v| v.name.clone()).collect(),
+                outdated_tools: Vec::new(),
+                rdm_vms: Vec::new(),
+                ft_enabled_vms: Vec::new(),
+                warnings: Vec::new(),
+            },
+            vms,
+        }
+    }
+
+    fn environment() -> VsphereEnvironment {
+        let clusters = vec![
+            cluster(
+                "prod",
+                vec![
+                    vm("app01", "prod", 4, 16, PowerState::PoweredOn, false),
+                    vm("db01", "prod", 16, 128, PowerState::PoweredOn, true),
+                    vm("old01", "prod", 2, 4, PowerState::PoweredOff, true),
+                ],
+            ),
+            cluster("dev", vec![vm("dev01", "dev", 2, 8, PowerState::PoweredOn, false)]),
+        ];
+        VsphereEnvironment {
+            id: Uuid::new_v4(),
+            name: "test".to_string(),
+            parsed_at: Utc::now(),
+            total_vms: 4,
+            total_hosts: 0,
+            clusters,
+            standalone_hosts: Vec::new(),
+            summary_metrics: EnvironmentSummary {
+                total_vcpus: 22,
+                total_pcores: 128,
+                total_provisioned_memory_gb: 156.0,
+                total_consumed_memory_gb: 152.0,
+                total_provisioned_storage_gb: 400.0,
+                total_consumed_storage_gb: 160.0,
+                overall_vcpu_pcpu_ratio: 0.17,
+                health_issues: Vec::new(),
+            },
+            import_report: ImportReport::default(),
+        }
+    }
+
+    #[test]
+    fn test_columns_keep_cluster_order_and_intern_strings() {
+        let columnar = ColumnarEnvironment::from_environment(&environment());
+        assert_eq!(columnar.vms.len(), 4);
+        assert_eq!(columnar.clusters[0].rows, 0..3);
+        assert_eq!(columnar.clusters[1].rows, 3..4);
+        assert_eq!(columnar.vms.name(1), "db01");
+        assert_eq!(columnar.vms.name(3), "dev01");
+        assert_eq!(columnar.vms.thick_disks[1], 1);
+        assert!(columnar.vms.has_flag(2, vm_flags::ZOMBIE));
+        assert!(!columnar.vms.is_active(2));
+        // Two hosts and one guest OS
+        assert_eq!(columnar.strings.len(), 3);
+    }
+
+    #[test]
+    fn test_columnar_analysis_matches_struct_analysis() {
+        let environment = environment();
+        let expected = AnalysisEngine::analyze_environment(&environment).unwrap();
+        let actual = AnalysisEngine::analyze_columnar(&ColumnarEnvironment::from_environment(&environment)).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&actual.capacity_analysis).unwrap(),
+            serde_json::to_value(&expected.capacity_analysis).unwrap()
+        );
+        assert_eq!(actual.health_analysis.warning_issues, expected.health_analysis.warning_issues);
+        assert_eq!(
+            serde_json::to_value(&actual.optimization_recommendations).unwrap(),
+            serde_json::to_value(&expected.optimization_recommendations).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_columnar_sizing_matches_struct_sizing() {
+        let environment = environment();
+        let columnar = ColumnarEnvironment::from_environment(&environment);
+        let profile = crate::sizing::HardwareBasket::new().get_profiles()[0].clone();
+        let parameters = SizingParameters::default();
+
+        let all_vms: Vec<VirtualMachine> = environment.clusters.iter().flat_map(|c| c.vms.clone()).collect();
+        let expected = SizingEngine::calculate_sizing(&all_vms, &profile, &parameters).unwrap();
+        let actual =
+            SizingEngine::calculate_sizing_columnar(&columnar.vms, columnar.clustered_rows(), &profile, &parameters)
+                .unwrap();
+
+        assert_eq!(actual.required_hosts, expected.required_hosts);
+        assert_eq!(actual.vm_placement, expected.vm_placement);
+        assert_eq!(actual.warnings, expected.warnings);
+    }
+}
@@ -0,0 +1,199 @@
+//! Version negotiation between the desktop app and the backend
+//!
+//! Both sides share the models in this crate, so compatibility is tracked by
+//! one schema number rather than by app versions. Bump [`SCHEMA_VERSION`]
+//! whenever a model or API change would break the other side, and raise
+//! [`MIN_SUPPORTED_SCHEMA`] once the old shape is no longer accepted.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{CoreEngineError, Result};
+
+/// Schema of the shared models and API this build speaks
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Oldest peer schema this build still works with
+pub const MIN_SUPPORTED_SCHEMA: u32 = 1;
+
+/// API prefix both sides route through
+pub const API_VERSION: &str = "v1";
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What one side of the handshake runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    /// `desktop` or `backend`
+    pub component: String,
+    /// Release version of the app or backend, e.g. `1.4.0`
+    pub version: String,
+    pub schema_version: u32,
+    pub min_supported_schema: u32,
+    pub api_version: String,
+}
+
+impl VersionInfo {
+    /// This build's schema, reported with the caller's own release version
+    pub fn current(component: &str, version: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            version: version.to_string(),
+            schema_version: SCHEMA_VERSION,
+            min_supported_schema: MIN_SUPPORTED_SCHEMA,
+            api_version: API_VERSION.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatibilityStatus {
+    Compatible,
+    /// The desktop app must be updated
+    ClientOutdated,
+    /// The backend must be upgraded before this app can use it
+    ServerOutdated,
+}
+
+/// Outcome of comparing the desktop app with a backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    pub client: VersionInfo,
+    pub server: VersionInfo,
+    pub status: CompatibilityStatus,
+    /// Safe to show to end users; empty when both sides match exactly
+    pub warnings: Vec<String>,
+}
+
+impl CompatibilityReport {
+    pub fn is_compatible(&self) -> bool {
+        self.status == CompatibilityStatus::Compatible
+    }
+}
+
+/// Compare both sides. Each side states the oldest schema it accepts, so a
+/// newer side decides whether it can still serve an older one.
+pub fn negotiate(client: VersionInfo, server: VersionInfo) -> CompatibilityReport {
+    let mut warnings = Vec::new();
+
+    let status = if client.api_version != server.api_version || client.schema_version < server.min_supported_schema {
+        warnings.push(format!(
+            "This app (version {}) is too old for the server (version {}). Update the app to continue.",
+            client.version, server.version
+        ));
+        CompatibilityStatus::ClientOutdated
+    } else if server.schema_version < client.min_supported_schema {
+        warnings.push(format!(
+            "The server (version {}) is too old for this app (version {}). Ask an administrator to upgrade it.",
+            server.version, client.version
+        ));
+        CompatibilityStatus::ServerOutdated
+    } else {
+        if client.schema_version < server.schema_version {
+            warnings.push(format!(
+                "A newer app is available for this server (version {}). Some features may be missing until you update.",
+                server.version
+            ));
+        } else if client.schema_version > server.schema_version {
+            warnings.push(format!(
+                "The server (version {}) is older than this app. Features it does not support are hidden.",
+                server.version
+            ));
+        }
+        CompatibilityStatus::Compatible
+    };
+
+    CompatibilityReport {
+        client,
+        server,
+        status,
+        warnings,
+    }
+}
+
+/// Send `client` to a backend's handshake endpoint and return its verdict
+pub async fn handshake(base_url: &str, client: &VersionInfo) -> Result<CompatibilityReport> {
+    let url = format!("{}/api/{}/version/handshake", base_url.trim_end_matches('/'), API_VERSION);
+    let http = reqwest::Client::builder()
+        .timeout(HANDSHAKE_TIMEOUT)
+        .build()
+        .map_err(|e| CoreEngineError::NetworkError(e.to_string()))?;
+
+    let response = http
+        .post(&url)
+        .json(client)
+        .send()
+        .await
+        .map_err(|e| CoreEngineError::NetworkError(format!("Version handshake with {} failed: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(CoreEngineError::NetworkError(format!(
+            "Version handshake with {} returned {}",
+            url,
+            response.status()
+        )));
+    }
+
+    // The backend wraps responses in its standard envelope
+    #[derive(Deserialize)]
+    struct Envelope {
+        data: Option<CompatibilityReport>,
+    }
+    let envelope: Envelope = response
+        .json()
+        .await
+        .map_err(|e| CoreEngineError::NetworkError(format!("Unreadable handshake response: {}", e)))?;
+    envelope
+        .data
+        .ok_or_else(|| CoreEngineError::NetworkError("Handshake response had no data".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(component: &str, schema: u32, min_supported: u32) -> VersionInfo {
+        VersionInfo {
+            component: component.to_string(),
+            version: format!("{}.0.0", schema),
+            schema_version: schema,
+            min_supported_schema: min_supported,
+            api_version: API_VERSION.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matching_schemas_are_compatible_without_warnings() {
+        let report = negotiate(side("desktop", 3, 2), side("backend", 3, 2));
+        assert!(report.is_compatible());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_older_client_within_range_gets_a_warning() {
+        let report = negotiate(side("desktop", 2, 1), side("backend", 3, 2));
+        assert!(report.is_compatible());
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_client_below_server_minimum_is_outdated() {
+        let report = negotiate(side("desktop", 1, 1), side("backend", 3, 2));
+        assert_eq!(report.status, CompatibilityStatus::ClientOutdated);
+    }
+
+    #[test]
+    fn test_server_below_client_minimum_is_outdated() {
+        let report = negotiate(side("desktop", 4, 3), side("backend", 2, 1));
+        assert_eq!(report.status, CompatibilityStatus::ServerOutdated);
+    }
+
+    #[test]
+    fn test_different_api_versions_are_incompatible() {
+        let mut server = side("backend", 1, 1);
+        server.api_version = "v2".to_string();
+        let report = negotiate(side("desktop", 1, 1), server);
+        assert_eq!(report.status, CompatibilityStatus::ClientOutdated);
+    }
+}
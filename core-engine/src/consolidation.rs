@@ -0,0 +1,443 @@
+// Cluster consolidation optimizer
+//
+// Searches hardware model / node-count / cluster-count combinations for the
+// cheapest designs that can absorb a set of source clusters, rather than
+// returning a single deterministic sizing answer.
+
+use serde::{Deserialize, Serialize};
+
+use crate::vendor_data::ServerSpecifications;
+use crate::{CoreEngineError, Result};
+
+/// Aggregate resource demand of one source cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceClusterDemand {
+    pub name: String,
+    pub vm_count: u32,
+    pub vcpus: u32,
+    pub memory_gb: f64,
+    pub storage_gb: f64,
+}
+
+/// A fully specified node build that can be ordered in quantity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeProfile {
+    pub vendor: String,
+    pub model_id: String,
+    pub model_name: String,
+    pub cpu_model: String,
+    pub sockets: u32,
+    pub cores_per_node: u32,
+    pub memory_gb: u32,
+    /// Raw local capacity before resiliency overhead
+    pub raw_storage_gb: u64,
+    /// Per-node list price; `None` when the vendor data carries no pricing
+    pub unit_price: Option<f64>,
+    pub power_watts: u32,
+    pub rack_units: u32,
+}
+
+impl NodeProfile {
+    /// Expand a vendor model into candidate builds.
+    ///
+    /// Each supported CPU is combined with half and fully populated DIMM slots
+    /// for every memory option, and every supported drive filling all bays.
+    pub fn from_specifications(specs: &ServerSpecifications) -> Vec<NodeProfile> {
+        let model = &specs.model;
+        let sockets = model.cpu_sockets.max(1);
+        let memory = &specs.memory_configuration;
+        let dimm_counts: Vec<u32> = {
+            let mut counts = vec![memory.memory_slots / 2, memory.memory_slots];
+            counts.retain(|c| *c > 0);
+            counts.dedup();
+            counts
+        };
+
+        let controller_price = specs.storage_options.storage_controllers.first().and_then(|c| c.list_price);
+        let nic_price = specs.network_options.first().and_then(|n| n.list_price);
+        let psu_price = specs.power_cooling.power_supply_options.first().and_then(|p| p.list_price);
+        let rack_units = specs.dimensions.rack_units.unwrap_or(match model.form_factor {
+            crate::vendor_data::FormFactor::OneU => 1,
+            crate::vendor_data::FormFactor::FourU => 4,
+            _ => 2,
+        });
+
+        // A model without drive options still yields compute-only builds
+        let drives: Vec<(u64, Option<f64>)> = if specs.storage_options.supported_drives.is_empty() || model.drive_bays == 0 {
+            vec![(0, Some(0.0))]
+        } else {
+            specs.storage_options.supported_drives
+                .iter()
+                .map(|d| (d.capacity_gb * model.drive_bays as u64, d.list_price.map(|p| p * model.drive_bays as f64)))
+                .collect()
+        };
+
+        let mut profiles = Vec::new();
+        for cpu in &specs.supported_cpus {
+            for dimm in &memory.memory_options {
+                for &count in &dimm_counts {
+                    let memory_gb = (dimm.capacity_gb * count).min(memory.max_capacity_gb as u32);
+                    for &(raw_storage_gb, drive_price) in &drives {
+                        let unit_price = [
+                            cpu.list_price.map(|p| p * sockets as f64),
+                            dimm.list_price.map(|p| p * count as f64),
+                            drive_price,
+                            controller_price.or(Some(0.0)),
+                            nic_price.or(Some(0.0)),
+                            psu_price.map(|p| p * 2.0).or(Some(0.0)),
+                        ]
+                        .into_iter()
+                        .sum::<Option<f64>>();
+
+                        profiles.push(NodeProfile {
+                            vendor: model.vendor.clone(),
+                            model_id: model.model_id.clone(),
+                            model_name: model.model_name.clone(),
+                            cpu_model: cpu.model_name.clone(),
+                            sockets,
+                            cores_per_node: cpu.cores * sockets,
+                            memory_gb,
+                            raw_storage_gb,
+                            unit_price,
+                            power_watts: specs.power_cooling.typical_power_consumption_watts,
+                            rack_units,
+                        });
+                    }
+                }
+            }
+        }
+        profiles
+    }
+
+    pub fn label(&self) -> String {
+        format!(
+            "{} {} ({}x {}, {} GB RAM, {} GB raw)",
+            self.vendor, self.model_name, self.sockets, self.cpu_model, self.memory_gb, self.raw_storage_gb
+        )
+    }
+}
+
+/// Constraints every returned design must satisfy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsolidationConstraints {
+    pub vcpu_to_core_ratio: f64,
+    pub memory_overcommit_ratio: f64,
+    /// Capacity kept free after placement, per resource
+    pub cpu_headroom_percent: f64,
+    pub memory_headroom_percent: f64,
+    pub storage_headroom_percent: f64,
+    /// Usable fraction of raw storage after mirroring/parity (0.5 = two-way mirror)
+    pub storage_efficiency: f64,
+    /// Skip the storage check when the target uses external (SAN) storage
+    pub local_storage: bool,
+    /// Spare nodes added to every cluster for failover (1 = N+1)
+    pub ha_spare_nodes: u32,
+    pub min_nodes_per_cluster: u32,
+    pub max_nodes_per_cluster: u32,
+    /// Upper bound on target clusters; defaults to the number of source clusters
+    pub max_clusters: Option<u32>,
+    pub top_n: usize,
+}
+
+impl Default for ConsolidationConstraints {
+    fn default() -> Self {
+        Self {
+            vcpu_to_core_ratio: 4.0,
+            memory_overcommit_ratio: 1.0,
+            cpu_headroom_percent: 20.0,
+            memory_headroom_percent: 20.0,
+            storage_headroom_percent: 25.0,
+            storage_efficiency: 0.5,
+            local_storage: true,
+            ha_spare_nodes: 1,
+            min_nodes_per_cluster: 2,
+            max_nodes_per_cluster: 16,
+            max_clusters: None,
+            top_n: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitingResource {
+    Cpu,
+    Memory,
+    Storage,
+    /// Node count driven by the cluster minimum rather than demand
+    MinimumSize,
+}
+
+/// One candidate target design with its trade-off metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterDesign {
+    pub rank: usize,
+    pub profile: NodeProfile,
+    pub cluster_count: u32,
+    pub nodes_per_cluster: u32,
+    pub total_nodes: u32,
+    pub total_cost: Option<f64>,
+    pub cost_per_vm: Option<f64>,
+    /// Steady-state utilisation with all nodes running
+    pub cpu_utilization_percent: f64,
+    pub memory_utilization_percent: f64,
+    pub storage_utilization_percent: f64,
+    /// Utilisation of the surviving nodes after losing the HA spares
+    pub failover_cpu_utilization_percent: f64,
+    pub failover_memory_utilization_percent: f64,
+    pub limiting_resource: LimitingResource,
+    pub total_power_watts: u32,
+    pub total_rack_units: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidationResult {
+    pub total_vms: u32,
+    pub total_vcpus: u32,
+    pub total_memory_gb: f64,
+    pub total_storage_gb: f64,
+    pub profiles_evaluated: usize,
+    pub combinations_evaluated: usize,
+    pub designs: Vec<ClusterDesign>,
+}
+
+pub struct ConsolidationOptimizer;
+
+impl ConsolidationOptimizer {
+    /// Search all profile / cluster-count combinations and return the top-N by cost.
+    ///
+    /// Demand is spread evenly across target clusters; each cluster gets the
+    /// smallest node count meeting every headroom constraint plus its HA spares.
+    pub fn optimize(
+        sources: &[SourceClusterDemand],
+        profiles: &[NodeProfile],
+        constraints: &ConsolidationConstraints,
+    ) -> Result<ConsolidationResult> {
+        if sources.is_empty() {
+            return Err(CoreEngineError::validation("At least one source cluster is required"));
+        }
+        if profiles.is_empty() {
+            return Err(CoreEngineError::validation("At least one candidate hardware profile is required"));
+        }
+        if constraints.vcpu_to_core_ratio <= 0.0 || constraints.memory_overcommit_ratio <= 0.0 || constraints.storage_efficiency <= 0.0 {
+            return Err(CoreEngineError::validation("Ratios and storage efficiency must be positive"));
+        }
+
+        let total_vms: u32 = sources.iter().map(|s| s.vm_count).sum();
+        let total_vcpus: u32 = sources.iter().map(|s| s.vcpus).sum();
+        let total_memory_gb: f64 = sources.iter().map(|s| s.memory_gb).sum();
+        let total_storage_gb: f64 = sources.iter().map(|s| s.storage_gb).sum();
+
+        let max_clusters = constraints.max_clusters.unwrap_or(sources.len() as u32).max(1);
+        let mut combinations = 0;
+        let mut designs = Vec::new();
+
+        for profile in profiles {
+            for cluster_count in 1..=max_clusters {
+                combinations += 1;
+                if let Some(design) = Self::size_design(
+                    profile,
+                    cluster_count,
+                    total_vms,
+                    total_vcpus as f64,
+                    total_memory_gb,
+                    total_storage_gb,
+                    constraints,
+                ) {
+                    designs.push(design);
+                }
+            }
+        }
+
+        // Cheapest first; unpriced designs last. Ties go to fewer nodes, then better balance.
+        designs.sort_by(|a, b| {
+            let cost = |d: &ClusterDesign| d.total_cost.unwrap_or(f64::INFINITY);
+            cost(a)
+                .partial_cmp(&cost(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.total_nodes.cmp(&b.total_nodes))
+                .then_with(|| {
+                    Self::imbalance(a)
+                        .partial_cmp(&Self::imbalance(b))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        });
+        designs.truncate(constraints.top_n.max(1));
+        for (idx, design) in designs.iter_mut().enumerate() {
+            design.rank = idx + 1;
+        }
+
+        Ok(ConsolidationResult {
+            total_vms,
+            total_vcpus,
+            total_memory_gb,
+            total_storage_gb,
+            profiles_evaluated: profiles.len(),
+            combinations_evaluated: combinations,
+            designs,
+        })
+    }
+
+    fn size_design(
+        profile: &NodeProfile,
+        cluster_count: u32,
+        total_vms: u32,
+        vcpus: f64,
+        memory_gb: f64,
+        storage_gb: f64,
+        c: &ConsolidationConstraints,
+    ) -> Option<ClusterDesign> {
+        let clusters = cluster_count as f64;
+        let usable = |capacity: f64, headroom: f64| capacity * (1.0 - headroom / 100.0).max(0.0);
+
+        let node_vcpu = profile.cores_per_node as f64 * c.vcpu_to_core_ratio;
+        let node_memory = profile.memory_gb as f64 * c.memory_overcommit_ratio;
+        let node_storage = profile.raw_storage_gb as f64 * c.storage_efficiency;
+
+        let nodes_for = |demand: f64, per_node: f64| -> Option<u32> {
+            if demand <= 0.0 {
+                Some(0)
+            } else if per_node <= 0.0 {
+                None
+            } else {
+                Some((demand / clusters / per_node).ceil() as u32)
+            }
+        };
+
+        let cpu_nodes = nodes_for(vcpus, usable(node_vcpu, c.cpu_headroom_percent))?;
+        let memory_nodes = nodes_for(memory_gb, usable(node_memory, c.memory_headroom_percent))?;
+        let storage_nodes = if c.local_storage {
+            nodes_for(storage_gb, usable(node_storage, c.storage_headroom_percent))?
+        } else {
+            0
+        };
+
+        let demand_nodes = cpu_nodes.max(memory_nodes).max(storage_nodes);
+        let mut limiting = if demand_nodes == cpu_nodes && cpu_nodes > 0 {
+            LimitingResource::Cpu
+        } else if demand_nodes == memory_nodes && memory_nodes > 0 {
+            LimitingResource::Memory
+        } else {
+            LimitingResource::Storage
+        };
+
+        let mut nodes_per_cluster = demand_nodes + c.ha_spare_nodes;
+        if nodes_per_cluster < c.min_nodes_per_cluster {
+            nodes_per_cluster = c.min_nodes_per_cluster;
+            limiting = LimitingResource::MinimumSize;
+        }
+        if nodes_per_cluster > c.max_nodes_per_cluster {
+            return None;
+        }
+
+        let total_nodes = nodes_per_cluster * cluster_count;
+        let active_nodes = (nodes_per_cluster.saturating_sub(c.ha_spare_nodes).max(1) * cluster_count) as f64;
+        let percent = |demand: f64, capacity: f64| if capacity > 0.0 { demand / capacity * 100.0 } else { 0.0 };
+        let total_cost = profile.unit_price.map(|p| p * total_nodes as f64);
+
+        Some(ClusterDesign {
+            rank: 0,
+            profile: profile.clone(),
+            cluster_count,
+            nodes_per_cluster,
+            total_nodes,
+            total_cost,
+            cost_per_vm: total_cost.filter(|_| total_vms > 0).map(|cost| cost / total_vms as f64),
+            cpu_utilization_percent: percent(vcpus, node_vcpu * total_nodes as f64),
+            memory_utilization_percent: percent(memory_gb, node_memory * total_nodes as f64),
+            storage_utilization_percent: if c.local_storage {
+                percent(storage_gb, node_storage * total_nodes as f64)
+            } else {
+                0.0
+            },
+            failover_cpu_utilization_percent: percent(vcpus, node_vcpu * active_nodes),
+            failover_memory_utilization_percent: percent(memory_gb, node_memory * active_nodes),
+            limiting_resource: limiting,
+            total_power_watts: profile.power_watts * total_nodes,
+            total_rack_units: profile.rack_units * total_nodes,
+        })
+    }
+
+    /// Spread between CPU and memory utilisation; lower means less stranded capacity
+    fn imbalance(design: &ClusterDesign) -> f64 {
+        (design.cpu_utilization_percent - design.memory_utilization_percent).abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, cores: u32, memory_gb: u32, storage_gb: u64, price: Option<f64>) -> NodeProfile {
+        NodeProfile {
+            vendor: "Test".to_string(),
+            model_id: name.to_string(),
+            model_name: name.to_string(),
+            cpu_model: "CPU".to_string(),
+            sockets: 2,
+            cores_per_node: cores,
+            memory_gb,
+            raw_storage_gb: storage_gb,
+            unit_price: price,
+            power_watts: 500,
+            rack_units: 2,
+        }
+    }
+
+    fn sources() -> Vec<SourceClusterDemand> {
+        vec![
+            SourceClusterDemand { name: "a".to_string(), vm_count: 100, vcpus: 400, memory_gb: 1600.0, storage_gb: 20000.0 },
+            SourceClusterDemand { name: "b".to_string(), vm_count: 50, vcpus: 200, memory_gb: 800.0, storage_gb: 10000.0 },
+        ]
+    }
+
+    #[test]
+    fn test_designs_meet_headroom_and_sort_by_cost() {
+        let profiles = vec![
+            profile("small", 32, 512, 15360, Some(20000.0)),
+            profile("large", 64, 1024, 30720, Some(35000.0)),
+        ];
+        let constraints = ConsolidationConstraints::default();
+        let result = ConsolidationOptimizer::optimize(&sources(), &profiles, &constraints).unwrap();
+
+        assert_eq!(result.combinations_evaluated, 4);
+        assert!(!result.designs.is_empty());
+        for pair in result.designs.windows(2) {
+            assert!(pair[0].total_cost.unwrap() <= pair[1].total_cost.unwrap());
+        }
+        for design in &result.designs {
+            assert!(design.cpu_utilization_percent <= 100.0 - constraints.cpu_headroom_percent + 1e-9);
+            assert!(design.memory_utilization_percent <= 100.0 - constraints.memory_headroom_percent + 1e-9);
+            assert!(design.nodes_per_cluster <= constraints.max_nodes_per_cluster);
+        }
+        assert_eq!(result.designs[0].rank, 1);
+    }
+
+    #[test]
+    fn test_memory_bound_sizing_adds_ha_spare() {
+        // 2400 GB demand / (512 GB * 80%) = 5.86 -> 6 nodes + 1 spare
+        let profiles = vec![profile("mem", 128, 512, 0, Some(10000.0))];
+        let constraints = ConsolidationConstraints { local_storage: false, max_clusters: Some(1), ..Default::default() };
+        let result = ConsolidationOptimizer::optimize(&sources(), &profiles, &constraints).unwrap();
+
+        let design = &result.designs[0];
+        assert_eq!(design.nodes_per_cluster, 7);
+        assert_eq!(design.limiting_resource, LimitingResource::Memory);
+        assert_eq!(design.total_cost, Some(70000.0));
+    }
+
+    #[test]
+    fn test_oversized_clusters_are_rejected_and_unpriced_rank_last() {
+        let profiles = vec![
+            profile("tiny", 4, 64, 1000, Some(1000.0)),
+            profile("unpriced", 64, 1024, 30720, None),
+            profile("priced", 64, 1024, 30720, Some(40000.0)),
+        ];
+        let result = ConsolidationOptimizer::optimize(&sources(), &profiles, &ConsolidationConstraints::default()).unwrap();
+
+        assert!(result.designs.iter().all(|d| d.profile.model_id != "tiny"));
+        assert_eq!(result.designs.first().unwrap().profile.model_id, "priced");
+        assert!(result.designs.last().unwrap().total_cost.is_none());
+    }
+}
@@ -0,0 +1,282 @@
+//! Currency conversion for pricing, BOM and TCO aggregation
+//!
+//! Vendor quotes and price files arrive in whatever currency the vendor
+//! uses. Amounts keep their original currency and are only converted when
+//! they are aggregated into a display currency, using a rate table that is
+//! either maintained by hand or loaded from the ECB daily reference rates.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CoreEngineError;
+use crate::Result;
+
+/// ECB euro foreign exchange reference rates, published each working day
+pub const ECB_DAILY_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+static ECB_RATE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"currency=['"]([A-Z]{3})['"]\s+rate=['"]([0-9.]+)['"]"#).unwrap());
+static ECB_TIME: Lazy<Regex> = Lazy::new(|| Regex::new(r#"time=['"](\d{4}-\d{2}-\d{2})['"]"#).unwrap());
+
+/// Where a rate table came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateSource {
+    Manual,
+    Ecb,
+}
+
+/// Exchange rates quoted against a single base currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    pub base: String,
+    /// Units of each currency per one unit of `base`
+    pub rates: BTreeMap<String, f64>,
+    pub source: RateSource,
+    pub as_of: DateTime<Utc>,
+}
+
+impl ExchangeRates {
+    /// Empty table that can only convert the base currency to itself
+    pub fn new(base: &str, source: RateSource) -> Result<Self> {
+        let base = normalize_code(base)?;
+        let mut rates = BTreeMap::new();
+        rates.insert(base.clone(), 1.0);
+        Ok(Self { base, rates, source, as_of: Utc::now() })
+    }
+
+    /// Add or replace the rate for a currency
+    pub fn set_rate(&mut self, currency: &str, rate: f64) -> Result<()> {
+        let currency = normalize_code(currency)?;
+        if !(rate.is_finite() && rate > 0.0) {
+            return Err(CoreEngineError::validation(format!("Exchange rate for {} must be positive", currency)));
+        }
+        if currency == self.base && rate != 1.0 {
+            return Err(CoreEngineError::validation(format!("{} is the base currency; its rate is 1", currency)));
+        }
+        self.rates.insert(currency, rate);
+        Ok(())
+    }
+
+    /// Units of `to` per one unit of `from`, crossing through the base
+    pub fn rate(&self, from: &str, to: &str) -> Result<f64> {
+        let from = normalize_code(from)?;
+        let to = normalize_code(to)?;
+        if from == to {
+            return Ok(1.0);
+        }
+        let lookup = |code: &str| {
+            self.rates
+                .get(code)
+                .copied()
+                .ok_or_else(|| CoreEngineError::config(format!("No exchange rate for {} (base {})", code, self.base)))
+        };
+        Ok(lookup(&to)? / lookup(&from)?)
+    }
+
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Result<f64> {
+        Ok(amount * self.rate(from, to)?)
+    }
+
+    /// Convert an amount, keeping the original next to the result
+    pub fn convert_money(&self, money: &Money, to: &str) -> Result<ConvertedMoney> {
+        let rate = self.rate(&money.currency, to)?;
+        Ok(ConvertedMoney {
+            original: money.clone(),
+            converted: Money::new(round2(money.amount * rate), &normalize_code(to)?),
+            rate,
+        })
+    }
+
+    /// Price a bill of materials whose lines may be in different currencies
+    pub fn convert_bom(&self, lines: &[BomLine], to: &str) -> Result<ConvertedBom> {
+        let display_currency = normalize_code(to)?;
+        let mut subtotals: BTreeMap<String, f64> = BTreeMap::new();
+        let mut converted = Vec::with_capacity(lines.len());
+        for line in lines {
+            let unit = Money::new(line.unit_price.amount, &normalize_code(&line.unit_price.currency)?);
+            let extended = Money::new(round2(unit.amount * line.quantity as f64), &unit.currency);
+            *subtotals.entry(unit.currency.clone()).or_insert(0.0) += extended.amount;
+            converted.push(ConvertedBomLine {
+                part_number: line.part_number.clone(),
+                description: line.description.clone(),
+                quantity: line.quantity,
+                unit_price: self.convert_money(&unit, &display_currency)?,
+                extended_price: self.convert_money(&extended, &display_currency)?,
+            });
+        }
+
+        Ok(ConvertedBom {
+            total: round2(converted.iter().map(|l| l.extended_price.converted.amount).sum()),
+            mixed_currency: subtotals.len() > 1,
+            display_currency,
+            lines: converted,
+            subtotals_by_currency: subtotals.into_iter().map(|(c, a)| (c, round2(a))).collect(),
+            rate_source: self.source,
+            rates_as_of: self.as_of,
+        })
+    }
+
+    /// Parse the ECB daily reference rates XML (EUR base)
+    pub fn from_ecb_xml(xml: &str) -> Result<Self> {
+        let mut table = Self::new("EUR", RateSource::Ecb)?;
+        for capture in ECB_RATE.captures_iter(xml) {
+            let rate: f64 = capture[2]
+                .parse()
+                .map_err(|_| CoreEngineError::parsing(format!("Invalid ECB rate for {}", &capture[1])))?;
+            table.set_rate(&capture[1], rate)?;
+        }
+        if table.rates.len() == 1 {
+            return Err(CoreEngineError::parsing("ECB feed contained no exchange rates"));
+        }
+        if let Some(date) = ECB_TIME
+            .captures(xml)
+            .and_then(|c| NaiveDate::parse_from_str(&c[1], "%Y-%m-%d").ok())
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+        {
+            table.as_of = Utc.from_utc_datetime(&date);
+        }
+        Ok(table)
+    }
+
+    /// Download today's ECB reference rates
+    #[tracing::instrument(name = "currency.fetch_ecb", fields(otel.kind = "client", http.url = ECB_DAILY_URL))]
+    pub async fn fetch_ecb() -> Result<Self> {
+        let response = reqwest::get(ECB_DAILY_URL)
+            .await
+            .map_err(|e| CoreEngineError::NetworkError(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| CoreEngineError::NetworkError(e.to_string()))?;
+        let xml = response.text().await.map_err(|e| CoreEngineError::NetworkError(e.to_string()))?;
+        Self::from_ecb_xml(&xml)
+    }
+}
+
+/// An amount in a specific currency
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Money {
+    pub amount: f64,
+    /// ISO 4217 code, e.g. "EUR"
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(amount: f64, currency: &str) -> Self {
+        Self { amount, currency: currency.to_string() }
+    }
+}
+
+/// A converted amount with the value it was converted from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertedMoney {
+    pub original: Money,
+    pub converted: Money,
+    pub rate: f64,
+}
+
+/// Bill of materials line as priced by the vendor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BomLine {
+    pub part_number: String,
+    #[serde(default)]
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: Money,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertedBomLine {
+    pub part_number: String,
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: ConvertedMoney,
+    pub extended_price: ConvertedMoney,
+}
+
+/// Bill of materials totalled in a display currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertedBom {
+    pub display_currency: String,
+    pub lines: Vec<ConvertedBomLine>,
+    /// Line totals in the currencies they were quoted in
+    pub subtotals_by_currency: BTreeMap<String, f64>,
+    pub mixed_currency: bool,
+    pub total: f64,
+    pub rate_source: RateSource,
+    pub rates_as_of: DateTime<Utc>,
+}
+
+/// Upper-case and check an ISO 4217 style code
+pub fn normalize_code(code: &str) -> Result<String> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(CoreEngineError::validation(format!("'{}' is not a currency code", code)));
+    }
+    Ok(code)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eur_rates() -> ExchangeRates {
+        let mut rates = ExchangeRates::new("EUR", RateSource::Manual).unwrap();
+        rates.set_rate("USD", 1.25).unwrap();
+        rates.set_rate("GBP", 0.8).unwrap();
+        rates
+    }
+
+    #[test]
+    fn test_cross_rates_go_through_base() {
+        let rates = eur_rates();
+        assert_eq!(rates.convert(100.0, "EUR", "USD").unwrap(), 125.0);
+        assert_eq!(rates.convert(125.0, "usd", "EUR").unwrap(), 100.0);
+        assert!((rates.convert(80.0, "GBP", "USD").unwrap() - 125.0).abs() < 1e-9);
+        assert!(rates.convert(1.0, "JPY", "EUR").is_err());
+    }
+
+    #[test]
+    fn test_mixed_currency_bom_keeps_originals() {
+        let lines = vec![
+            BomLine {
+                part_number: "R760".to_string(),
+                description: String::new(),
+                quantity: 2,
+                unit_price: Money::new(10_000.0, "USD"),
+            },
+            BomLine {
+                part_number: "SR650".to_string(),
+                description: String::new(),
+                quantity: 1,
+                unit_price: Money::new(8_000.0, "GBP"),
+            },
+        ];
+        let bom = eur_rates().convert_bom(&lines, "eur").unwrap();
+        assert!(bom.mixed_currency);
+        assert_eq!(bom.subtotals_by_currency["USD"], 20_000.0);
+        assert_eq!(bom.lines[0].extended_price.original.currency, "USD");
+        assert_eq!(bom.lines[0].extended_price.converted.amount, 16_000.0);
+        assert_eq!(bom.total, 26_000.0);
+    }
+
+    #[test]
+    fn test_parse_ecb_feed() {
+        let xml = r#"<Cube><Cube time='2026-10-14'>
+            <Cube currency='USD' rate='1.0823'/>
+            <Cube currency='GBP' rate='0.85130'/>
+        </Cube></Cube>"#;
+        let rates = ExchangeRates::from_ecb_xml(xml).unwrap();
+        assert_eq!(rates.base, "EUR");
+        assert_eq!(rates.source, RateSource::Ecb);
+        assert_eq!(rates.rates["USD"], 1.0823);
+        assert_eq!(rates.as_of.date_naive().to_string(), "2026-10-14");
+    }
+}
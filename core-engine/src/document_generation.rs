@@ -1,13 +1,18 @@
+use crate::charts::{self, ChartSpec, ChartTheme};
 use crate::models::*;
 use crate::{Result, CoreEngineError};
 use docx_rs::*;
 use std::collections::HashMap;
 
+/// Chart width on the page: 6 inches, in EMUs
+const CHART_WIDTH_EMU: u32 = 5_486_400;
+
 /// Document generation engine for HLD and LLD documents
 pub struct DocumentGenerator;
 
 impl DocumentGenerator {
     /// Generate High-Level Design document
+    #[tracing::instrument(name = "document.generate_hld", skip_all)]
     pub fn generate_hld(
         environment: &VsphereEnvironment,
         sizing_result: &SizingResult,
@@ -53,6 +58,7 @@ impl DocumentGenerator {
     }
 
     /// Generate Low-Level Design document
+    #[tracing::instrument(name = "document.generate_lld", skip_all)]
     pub fn generate_lld(
         _environment: &VsphereEnvironment,
         _sizing_result: &SizingResult,
@@ -353,6 +359,8 @@ impl DocumentGenerator {
                 .add_run(Run::new().add_text(&compute_text))
         );
 
+        doc = Self::add_chart(doc, &charts::capacity_utilization(&sizing_result.utilization_metrics));
+
         // Add warnings if any
         if !sizing_result.warnings.is_empty() {
             doc = doc.add_paragraph(
@@ -509,19 +517,22 @@ impl DocumentGenerator {
         let tco_text = format!(
             "The TCO analysis compares the current VMware environment costs with the proposed Microsoft solution:\n\n\
             Current Annual Costs:\n\
-            • Hardware: ${:.0}\n\
-            • Software Licensing: ${:.0}\n\
-            • Datacenter: ${:.0}\n\
-            • Personnel: ${:.0}\n\
-            • Total Annual: ${:.0}\n\n\
+            • Hardware: {:.0} {cur}\n\
+            • Software Licensing: {:.0} {cur}\n\
+            • Datacenter: {:.0} {cur}\n\
+            • Personnel: {:.0} {cur}\n\
+            • Total Annual: {:.0} {cur}\n\n\
             Target Annual Costs:\n\
-            • Software Licensing: ${:.0}\n\
-            • Ongoing Operational: ${:.0}\n\
-            • Total Annual: ${:.0}\n\n\
+            • Software Licensing: {:.0} {cur}\n\
+            • Ongoing Operational: {:.0} {cur}\n\
+            • Total Annual: {:.0} {cur}\n\n\
+            One-time Costs:\n\
+            • Hardware Acquisition: {:.0} {cur}\n\
+            • Implementation Services: {:.0} {cur}\n\n\
             Financial Impact:\n\
-            • Annual Savings: ${:.0}\n\
+            • Annual Savings: {:.0} {cur}\n\
             • Payback Period: {:.1} months\n\
-            • 3-Year Savings: ${:.0}",
+            • 3-Year Savings: {:.0} {cur}",
             tco.current_environment_costs.hardware_annual,
             tco.current_environment_costs.software_licensing_annual,
             tco.current_environment_costs.datacenter_annual,
@@ -530,9 +541,12 @@ impl DocumentGenerator {
             tco.target_environment_costs.software_licensing_annual,
             tco.target_environment_costs.ongoing_operational_annual,
             tco.target_environment_costs.total_annual,
+            tco.target_environment_costs.hardware_acquisition,
+            tco.target_environment_costs.implementation_services,
             tco.savings_analysis.annual_savings,
             tco.payback_period_months,
-            tco.savings_analysis.three_year_savings
+            tco.savings_analysis.three_year_savings,
+            cur = tco.currency
         );
 
         doc = doc.add_paragraph(
@@ -540,9 +554,29 @@ impl DocumentGenerator {
                 .add_run(Run::new().add_text(&tco_text))
         );
 
+        doc = Self::add_chart(doc, &charts::tco_comparison(tco, 5));
+        doc = Self::add_chart(doc, &charts::current_cost_breakdown(tco));
+
         Ok(doc)
     }
 
+    /// Add a chart as a full-width picture. A chart that cannot be rendered
+    /// (e.g. no font for PNG text) is left out rather than failing the document.
+    fn add_chart(doc: Docx, spec: &ChartSpec) -> Docx {
+        let theme = ChartTheme::default();
+        match charts::render_png(spec, &theme) {
+            Ok(png) => {
+                let height = CHART_WIDTH_EMU * theme.height / theme.width;
+                let pic = Pic::new_with_dimensions(png, theme.width, theme.height).size(CHART_WIDTH_EMU, height);
+                doc.add_paragraph(Paragraph::new().add_run(Run::new().add_image(pic)))
+            }
+            Err(e) => {
+                tracing::warn!(chart = %spec.title, error = %e, "Skipping chart");
+                doc
+            }
+        }
+    }
+
     /// Add migration approach section
     fn add_hld_migration_approach(mut doc: Docx, manual_interventions: &[ManualInterventionItem]) -> Result<Docx> {
         doc = doc.add_paragraph(
@@ -1,45 +1,59 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::redaction::scrub;
+
+/// Display output goes through `redaction::scrub`, so errors can be logged or
+/// returned to clients without leaking credentials, addresses or hostnames.
+/// The variant payloads keep the original text.
 #[derive(Error, Debug)]
 pub enum CoreEngineError {
-    #[error("Failed to parse RVTools file: {0}")]
+    #[error("Failed to parse RVTools file: {}", scrub(.0))]
     ParsingError(String),
 
-    #[error("Data validation error: {0}")]
+    #[error("Data validation error: {}", scrub(.0))]
     ValidationError(String),
 
-    #[error("Calculation error: {0}")]
+    #[error("Not found: {}", scrub(.0))]
+    NotFound(String),
+
+    #[error("Calculation error: {}", scrub(.0))]
     CalculationError(String),
 
-    #[error("Document generation error: {0}")]
+    #[error("Document generation error: {}", scrub(.0))]
     DocumentError(String),
 
-    #[error("IO error: {0}")]
+    #[error("IO error: {}", scrub(.0))]
     Io(String),
 
-    #[error("Feature not implemented: {0}")]
+    #[error("Feature not implemented: {}", scrub(.0))]
     NotImplemented(String),
 
-    #[error("Serialization error: {0}")]
+    #[error("Serialization error: {}", scrub(&.0.to_string()))]
     SerializationError(#[from] serde_json::Error),
 
-    #[error("Excel parsing error: {0}")]
+    #[error("Excel parsing error: {}", scrub(&.0.to_string()))]
     ExcelError(#[from] calamine::Error),
 
-    #[error("Configuration error: {0}")]
+    #[error("Configuration error: {}", scrub(.0))]
     ConfigError(String),
 
-    #[error("Hardware basket error: {0}")]
+    #[error("Hardware basket error: {}", scrub(.0))]
     HardwareError(String),
 
-    #[error("Migration planning error: {0}")]
+    #[error("Migration planning error: {}", scrub(.0))]
     MigrationError(String),
 
-    #[error("Network error: {0}")]
+    #[error("Network error: {}", scrub(.0))]
     NetworkError(String),
 
-    #[error("Authentication error: {0}")]
+    #[error("Authentication error: {}", scrub(.0))]
     AuthenticationError(String),
+
+    #[error("Unsupported file format ({format}); parsers attempted: [{}]", .attempted.join(", "))]
+    UnsupportedFormat { format: String, attempted: Vec<String> },
 }
 
 impl CoreEngineError {
@@ -83,11 +97,266 @@ impl CoreEngineError {
         Self::AuthenticationError(msg.into())
     }
 
+    pub fn unsupported_format(format: impl Into<String>, attempted: Vec<String>) -> Self {
+        Self::UnsupportedFormat { format: format.into(), attempted }
+    }
+
     pub fn not_found(msg: impl Into<String>) -> Self {
-        Self::ValidationError(format!("Not found: {}", msg.into()))
+        Self::NotFound(msg.into())
     }
 
     pub fn serialization(msg: impl Into<String>) -> Self {
         Self::ParsingError(format!("Serialization error: {}", msg.into()))
     }
+
+    /// Stable code from the error registry
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::ParsingError(_) => codes::PARSE_FAILED,
+            Self::ValidationError(_) => codes::VALIDATION_ERROR,
+            Self::NotFound(_) => codes::NOT_FOUND,
+            Self::CalculationError(_) => codes::CALCULATION_FAILED,
+            Self::DocumentError(_) => codes::DOCUMENT_GENERATION_FAILED,
+            Self::Io(_) => codes::IO_ERROR,
+            Self::NotImplemented(_) => codes::NOT_IMPLEMENTED,
+            Self::SerializationError(_) => codes::SERIALIZATION_FAILED,
+            Self::ExcelError(_) => codes::WORKBOOK_UNREADABLE,
+            Self::ConfigError(_) => codes::CONFIGURATION_ERROR,
+            Self::HardwareError(_) => codes::HARDWARE_BASKET_ERROR,
+            Self::MigrationError(_) => codes::MIGRATION_PLANNING_ERROR,
+            Self::NetworkError(_) => codes::UPSTREAM_UNAVAILABLE,
+            Self::AuthenticationError(_) => codes::AUTHENTICATION_FAILED,
+            Self::UnsupportedFormat { .. } => codes::UNSUPPORTED_FORMAT,
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        self.error_code().category
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.error_code().retryable
+    }
+
+    /// Message for end users. Validation, lookup and format errors describe
+    /// the user's own input, so their text is shown; everything else gets the
+    /// registry message and the specifics go into the developer detail.
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::ValidationError(msg) | Self::NotFound(msg) => scrub(msg),
+            Self::UnsupportedFormat { .. } => self.to_string(),
+            _ => self.error_code().message.to_string(),
+        }
+    }
+
+    pub fn to_detail(&self) -> ErrorDetail {
+        ErrorDetail::new(self.error_code(), self.user_message()).with_detail(self.to_string())
+    }
+}
+
+// =============================================================================
+// ERROR TAXONOMY
+// =============================================================================
+
+/// Broad class of an error, for deciding how a client reacts to it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// The request or input is wrong; fix it and retry
+    Validation,
+    NotFound,
+    /// The request conflicts with current state
+    Conflict,
+    Authentication,
+    Authorization,
+    /// The file or feature is not supported
+    Unsupported,
+    /// A vendor API, feed or other remote service failed
+    External,
+    Configuration,
+    /// Parsing, sizing or document generation failed on otherwise valid input
+    Processing,
+    #[default]
+    Internal,
+}
+
+/// Entry in the error registry. Codes are stable and never reused; clients
+/// branch on `code`, never on message text.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct ErrorCode {
+    pub code: &'static str,
+    pub category: ErrorCategory,
+    pub http_status: u16,
+    /// Whether the same request may succeed if repeated later
+    pub retryable: bool,
+    /// Default message safe to show to end users
+    pub message: &'static str,
+}
+
+impl ErrorCode {
+    const fn new(
+        code: &'static str,
+        category: ErrorCategory,
+        http_status: u16,
+        retryable: bool,
+        message: &'static str,
+    ) -> Self {
+        Self { code, category, http_status, retryable, message }
+    }
+
+    /// Registry entry for a code string
+    pub fn lookup(code: &str) -> Option<ErrorCode> {
+        ERROR_REGISTRY.iter().find(|entry| entry.code == code).copied()
+    }
+}
+
+/// Registered error codes
+pub mod codes {
+    use super::{ErrorCategory as C, ErrorCode};
+
+    pub const BAD_REQUEST: ErrorCode = ErrorCode::new("BAD_REQUEST", C::Validation, 400, false, "The request could not be processed.");
+    pub const VALIDATION_ERROR: ErrorCode = ErrorCode::new("VALIDATION_ERROR", C::Validation, 400, false, "Some of the submitted values are invalid.");
+    pub const PAYLOAD_TOO_LARGE: ErrorCode = ErrorCode::new("PAYLOAD_TOO_LARGE", C::Validation, 413, false, "The upload is larger than allowed.");
+    pub const NOT_FOUND: ErrorCode = ErrorCode::new("NOT_FOUND", C::NotFound, 404, false, "The requested item does not exist.");
+    pub const CONFLICT: ErrorCode = ErrorCode::new("CONFLICT", C::Conflict, 409, false, "The item was changed or already exists.");
+    pub const PRECONDITION_FAILED: ErrorCode = ErrorCode::new("PRECONDITION_FAILED", C::Conflict, 412, false, "This action is not available in the current state.");
+    pub const UNAUTHORIZED: ErrorCode = ErrorCode::new("UNAUTHORIZED", C::Authentication, 401, false, "Please sign in again.");
+    pub const AUTHENTICATION_FAILED: ErrorCode = ErrorCode::new("AUTHENTICATION_FAILED", C::Authentication, 401, false, "Authentication failed.");
+    pub const FORBIDDEN: ErrorCode = ErrorCode::new("FORBIDDEN", C::Authorization, 403, false, "You do not have permission for this action.");
+    pub const UNSUPPORTED_FORMAT: ErrorCode = ErrorCode::new("UNSUPPORTED_FORMAT", C::Unsupported, 415, false, "This file format is not supported.");
+    pub const NOT_IMPLEMENTED: ErrorCode = ErrorCode::new("NOT_IMPLEMENTED", C::Unsupported, 501, false, "This feature is not available yet.");
+    pub const FEATURE_DISABLED: ErrorCode = ErrorCode::new("FEATURE_DISABLED", C::Unsupported, 403, false, "This feature is not enabled for your organization.");
+    pub const LICENSE_REQUIRED: ErrorCode = ErrorCode::new("LICENSE_REQUIRED", C::Configuration, 402, false, "A valid license is required to make changes.");
+    pub const MODULE_NOT_LICENSED: ErrorCode = ErrorCode::new("MODULE_NOT_LICENSED", C::Unsupported, 403, false, "This module is not included in your license.");
+    pub const SEAT_LIMIT_REACHED: ErrorCode = ErrorCode::new("SEAT_LIMIT_REACHED", C::Authorization, 403, false, "All licensed seats are in use.");
+    pub const PARSE_FAILED: ErrorCode = ErrorCode::new("PARSE_FAILED", C::Processing, 422, false, "The file could not be read.");
+    pub const WORKBOOK_UNREADABLE: ErrorCode = ErrorCode::new("WORKBOOK_UNREADABLE", C::Processing, 422, false, "The spreadsheet could not be opened.");
+    pub const HARDWARE_BASKET_ERROR: ErrorCode = ErrorCode::new("HARDWARE_BASKET_ERROR", C::Processing, 422, false, "The hardware basket could not be processed.");
+    pub const MIGRATION_PLANNING_ERROR: ErrorCode = ErrorCode::new("MIGRATION_PLANNING_ERROR", C::Processing, 422, false, "The migration plan could not be calculated.");
+    pub const CALCULATION_FAILED: ErrorCode = ErrorCode::new("CALCULATION_FAILED", C::Processing, 500, false, "The calculation could not be completed.");
+    pub const DOCUMENT_GENERATION_FAILED: ErrorCode = ErrorCode::new("DOCUMENT_GENERATION_FAILED", C::Processing, 500, false, "The document could not be generated.");
+    pub const UPSTREAM_UNAVAILABLE: ErrorCode = ErrorCode::new("UPSTREAM_UNAVAILABLE", C::External, 502, true, "A remote service is unavailable. Please try again later.");
+    pub const RATE_LIMITED: ErrorCode = ErrorCode::new("RATE_LIMITED", C::External, 429, true, "Too many requests. Please wait and try again.");
+    pub const CONFIGURATION_ERROR: ErrorCode = ErrorCode::new("CONFIGURATION_ERROR", C::Configuration, 500, false, "The application is not configured correctly.");
+    pub const NOT_CONFIGURED: ErrorCode = ErrorCode::new("NOT_CONFIGURED", C::Configuration, 503, false, "This feature has not been set up by an administrator.");
+    pub const IO_ERROR: ErrorCode = ErrorCode::new("IO_ERROR", C::Internal, 500, true, "A file could not be read or written.");
+    pub const SERIALIZATION_FAILED: ErrorCode = ErrorCode::new("SERIALIZATION_FAILED", C::Internal, 500, false, "The data could not be converted.");
+    pub const INTERNAL_ERROR: ErrorCode = ErrorCode::new("INTERNAL_ERROR", C::Internal, 500, false, "Something went wrong.");
+}
+
+/// Every registered code, served by the error registry endpoint
+pub const ERROR_REGISTRY: &[ErrorCode] = &[
+    codes::BAD_REQUEST,
+    codes::VALIDATION_ERROR,
+    codes::PAYLOAD_TOO_LARGE,
+    codes::NOT_FOUND,
+    codes::CONFLICT,
+    codes::PRECONDITION_FAILED,
+    codes::UNAUTHORIZED,
+    codes::AUTHENTICATION_FAILED,
+    codes::FORBIDDEN,
+    codes::UNSUPPORTED_FORMAT,
+    codes::NOT_IMPLEMENTED,
+    codes::FEATURE_DISABLED,
+    codes::LICENSE_REQUIRED,
+    codes::MODULE_NOT_LICENSED,
+    codes::SEAT_LIMIT_REACHED,
+    codes::PARSE_FAILED,
+    codes::WORKBOOK_UNREADABLE,
+    codes::HARDWARE_BASKET_ERROR,
+    codes::MIGRATION_PLANNING_ERROR,
+    codes::CALCULATION_FAILED,
+    codes::DOCUMENT_GENERATION_FAILED,
+    codes::UPSTREAM_UNAVAILABLE,
+    codes::RATE_LIMITED,
+    codes::CONFIGURATION_ERROR,
+    codes::NOT_CONFIGURED,
+    codes::IO_ERROR,
+    codes::SERIALIZATION_FAILED,
+    codes::INTERNAL_ERROR,
+];
+
+/// Serializable error as returned to API clients and Tauri commands
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorDetail {
+    pub code: String,
+    pub category: ErrorCategory,
+    pub retryable: bool,
+    /// Safe to show to end users
+    pub message: String,
+    /// Scrubbed technical detail for logs and support tickets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ErrorDetail {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code: code.code.to_string(),
+            category: code.category,
+            retryable: code.retryable,
+            message: message.into(),
+            detail: None,
+        }
+    }
+
+    /// Error with the registry's default message
+    pub fn from_code(code: ErrorCode) -> Self {
+        Self::new(code, code.message)
+    }
+
+    pub fn with_detail(mut self, detail: impl fmt::Display) -> Self {
+        self.detail = Some(scrub(&detail.to_string()));
+        self
+    }
+
+    /// Prefix the user-facing message with what was being attempted
+    pub fn context(mut self, context: &str) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+}
+
+impl fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ErrorDetail {}
+
+impl From<CoreEngineError> for ErrorDetail {
+    fn from(error: CoreEngineError) -> Self {
+        error.to_detail()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_registry_codes_are_unique_and_resolvable() {
+        let mut seen = HashSet::new();
+        for entry in ERROR_REGISTRY {
+            assert!(seen.insert(entry.code), "duplicate code {}", entry.code);
+            assert_eq!(ErrorCode::lookup(entry.code), Some(*entry));
+        }
+        assert_eq!(ErrorCode::lookup("NO_SUCH_CODE"), None);
+    }
+
+    #[test]
+    fn test_engine_error_detail_separates_user_message() {
+        let detail = CoreEngineError::NetworkError("vendor API at https://api:pw@10.1.1.1 timed out".to_string()).to_detail();
+        assert_eq!(detail.code, "UPSTREAM_UNAVAILABLE");
+        assert_eq!(detail.category, ErrorCategory::External);
+        assert!(detail.retryable);
+        assert_eq!(detail.message, codes::UPSTREAM_UNAVAILABLE.message);
+        assert!(!detail.detail.unwrap().contains("10.1.1.1"));
+
+        let detail = CoreEngineError::validation("Quantity must be positive").to_detail();
+        assert_eq!(detail.message, "Quantity must be positive");
+        assert_eq!(CoreEngineError::not_found("Model R750").category(), ErrorCategory::NotFound);
+    }
 }
@@ -424,6 +424,7 @@ mod tests {
 }
 
 /// Convenience function for generating forecasts
+#[tracing::instrument(name = "forecasting.generate", skip_all)]
 pub async fn generate_forecast(
     environment: &VsphereEnvironment,
     forecast_params: &ForecastParameters,
@@ -0,0 +1,336 @@
+//! Sanity checks for assembled server configurations
+//!
+//! Parsed configurations are checked against the chassis they are built on:
+//! DIMM population per socket, drives against drive bays and the power supply
+//! rating against an estimate of what the components draw. Problems are
+//! reported as structured warnings rather than errors so a basket still loads.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::UniversalServer;
+
+/// DIMMs per socket that keep every memory channel balanced
+const BALANCED_DIMMS_PER_SOCKET: &[u32] = &[1, 2, 4, 6, 8, 12, 16, 24];
+
+/// Fans, board, BMC and backplane
+const CHASSIS_BASE_WATTS: u32 = 150;
+/// CPU draw when neither TDP nor core count is known
+const CPU_DEFAULT_WATTS: u32 = 205;
+const CPU_BASE_WATTS: u32 = 50;
+const CPU_WATTS_PER_CORE: u32 = 5;
+const DDR4_DIMM_WATTS: u32 = 6;
+const DDR5_DIMM_WATTS: u32 = 10;
+const DRIVE_WATTS: u32 = 10;
+const NVME_DRIVE_WATTS: u32 = 20;
+const ADAPTER_WATTS: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssemblyRule {
+    CpuSockets,
+    DimmPopulation,
+    DriveBays,
+    PowerBudget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningSeverity {
+    Info,
+    Warning,
+    /// The configuration cannot be built as specified
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssemblyWarning {
+    pub rule: AssemblyRule,
+    pub severity: WarningSeverity,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+}
+
+impl AssemblyWarning {
+    fn new(rule: AssemblyRule, severity: WarningSeverity, message: impl Into<String>) -> Self {
+        Self { rule, severity, message: message.into(), expected: None, actual: None }
+    }
+
+    fn values(mut self, expected: impl ToString, actual: impl ToString) -> Self {
+        self.expected = Some(expected.to_string());
+        self.actual = Some(actual.to_string());
+        self
+    }
+}
+
+/// What the chassis can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChassisLimits {
+    pub cpu_sockets: u32,
+    pub memory_slots: u32,
+    pub drive_bays: u32,
+}
+
+/// Quantities in a configuration. Checks whose quantity is unknown are skipped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssemblyCounts {
+    pub cpus: u32,
+    /// Capacity of each DIMM in GB, 0 when not stated
+    pub dimm_capacities_gb: Option<Vec<u32>>,
+    pub drives: Option<u32>,
+    pub psu_watts: Vec<u32>,
+    pub power_draw_watts: u32,
+}
+
+impl AssemblyCounts {
+    pub fn from_server(server: &UniversalServer) -> Self {
+        let cpu_watts: u32 = server.cpus.iter().map(|cpu| {
+            cpu.vendor_specific_attributes
+                .get("tdp_watts")
+                .and_then(|tdp| tdp.trim_end_matches(['W', 'w']).trim().parse().ok())
+                .or_else(|| cpu.core_count.map(estimate_cpu_watts))
+                .unwrap_or(CPU_DEFAULT_WATTS)
+        }).sum();
+        let memory_watts: u32 = server.memory.iter().map(|dimm| {
+            match dimm.memory_type.as_deref() {
+                Some(t) if t.to_ascii_uppercase().contains("DDR5") => DDR5_DIMM_WATTS,
+                _ => DDR4_DIMM_WATTS,
+            }
+        }).sum();
+        let drive_watts: u32 = server.physical_disks.iter().map(|disk| {
+            match disk.interface_type.as_deref() {
+                Some(t) if t.eq_ignore_ascii_case("nvme") => NVME_DRIVE_WATTS,
+                _ => DRIVE_WATTS,
+            }
+        }).sum();
+        let adapters = (server.storage_controllers.len() + server.network_adapters.len()) as u32;
+
+        Self {
+            cpus: server.cpus.len() as u32,
+            dimm_capacities_gb: Some(server.memory.iter().map(|d| d.capacity_gb.unwrap_or(0)).collect()),
+            drives: Some(server.physical_disks.len() as u32),
+            psu_watts: server.power_supplies.iter().filter_map(|psu| psu.output_watts).collect(),
+            power_draw_watts: CHASSIS_BASE_WATTS + cpu_watts + memory_watts + drive_watts + adapters * ADAPTER_WATTS,
+        }
+    }
+}
+
+pub fn estimate_cpu_watts(cores: u32) -> u32 {
+    CPU_BASE_WATTS + cores * CPU_WATTS_PER_CORE
+}
+
+/// Draw of a chassis with every socket, memory slot and bay filled
+pub fn estimate_full_population_watts(limits: &ChassisLimits, cpu_cores: Option<u32>) -> u32 {
+    let cpu = cpu_cores.map(estimate_cpu_watts).unwrap_or(CPU_DEFAULT_WATTS);
+    CHASSIS_BASE_WATTS
+        + limits.cpu_sockets * cpu
+        + limits.memory_slots * DDR4_DIMM_WATTS
+        + limits.drive_bays * DRIVE_WATTS
+        + 2 * ADAPTER_WATTS
+}
+
+/// Run every check that the known quantities allow. `limits` is None when
+/// the chassis model is not in the platform rules; only the power budget
+/// can be checked then.
+pub fn validate_assembly(limits: Option<&ChassisLimits>, counts: &AssemblyCounts) -> Vec<AssemblyWarning> {
+    let mut warnings = Vec::new();
+    if let Some(limits) = limits {
+        check_sockets(limits, counts, &mut warnings);
+        check_dimms(limits, counts, &mut warnings);
+        check_drives(limits, counts, &mut warnings);
+    }
+    check_power(counts, &mut warnings);
+    warnings.sort_by(|a, b| b.severity.cmp(&a.severity));
+    warnings
+}
+
+fn check_sockets(limits: &ChassisLimits, counts: &AssemblyCounts, warnings: &mut Vec<AssemblyWarning>) {
+    if counts.cpus > limits.cpu_sockets {
+        warnings.push(
+            AssemblyWarning::new(
+                AssemblyRule::CpuSockets,
+                WarningSeverity::Error,
+                format!("{} CPUs configured for a {}-socket chassis", counts.cpus, limits.cpu_sockets),
+            )
+            .values(limits.cpu_sockets, counts.cpus),
+        );
+    }
+}
+
+fn check_dimms(limits: &ChassisLimits, counts: &AssemblyCounts, warnings: &mut Vec<AssemblyWarning>) {
+    let Some(dimms) = &counts.dimm_capacities_gb else {
+        return;
+    };
+    let total = dimms.len() as u32;
+    let rule = AssemblyRule::DimmPopulation;
+
+    if total > limits.memory_slots {
+        warnings.push(
+            AssemblyWarning::new(
+                rule,
+                WarningSeverity::Error,
+                format!("{} DIMMs do not fit in {} memory slots", total, limits.memory_slots),
+            )
+            .values(limits.memory_slots, total),
+        );
+    }
+    if counts.cpus == 0 {
+        return;
+    }
+    if total == 0 {
+        warnings.push(AssemblyWarning::new(rule, WarningSeverity::Error, "No memory configured"));
+        return;
+    }
+    if total % counts.cpus != 0 {
+        warnings.push(
+            AssemblyWarning::new(
+                rule,
+                WarningSeverity::Warning,
+                format!("{} DIMMs cannot be split evenly across {} CPUs", total, counts.cpus),
+            )
+            .values(format!("a multiple of {}", counts.cpus), total),
+        );
+    } else {
+        let per_socket = total / counts.cpus;
+        if !BALANCED_DIMMS_PER_SOCKET.contains(&per_socket) {
+            let balanced: Vec<String> = BALANCED_DIMMS_PER_SOCKET
+                .iter()
+                .filter(|n| **n * counts.cpus <= limits.memory_slots)
+                .map(|n| n.to_string())
+                .collect();
+            warnings.push(
+                AssemblyWarning::new(
+                    rule,
+                    WarningSeverity::Warning,
+                    format!("{} DIMMs per socket leaves memory channels unbalanced", per_socket),
+                )
+                .values(format!("{} per socket", balanced.join(", ")), per_socket),
+            );
+        }
+    }
+
+    let mut sizes: Vec<u32> = dimms.iter().copied().filter(|gb| *gb > 0).collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+    if sizes.len() > 1 {
+        let sizes: Vec<String> = sizes.iter().map(|gb| format!("{}GB", gb)).collect();
+        warnings.push(
+            AssemblyWarning::new(rule, WarningSeverity::Warning, "DIMMs of different capacities are mixed")
+                .values("one DIMM size", sizes.join(", ")),
+        );
+    }
+}
+
+fn check_drives(limits: &ChassisLimits, counts: &AssemblyCounts, warnings: &mut Vec<AssemblyWarning>) {
+    let Some(drives) = counts.drives else {
+        return;
+    };
+    if drives > limits.drive_bays {
+        warnings.push(
+            AssemblyWarning::new(
+                AssemblyRule::DriveBays,
+                WarningSeverity::Error,
+                format!("{} drives do not fit in {} drive bays", drives, limits.drive_bays),
+            )
+            .values(limits.drive_bays, drives),
+        );
+    }
+}
+
+fn check_power(counts: &AssemblyCounts, warnings: &mut Vec<AssemblyWarning>) {
+    let rule = AssemblyRule::PowerBudget;
+    let draw = counts.power_draw_watts;
+    if counts.psu_watts.is_empty() {
+        warnings.push(AssemblyWarning::new(
+            rule,
+            WarningSeverity::Info,
+            "Power supply rating unknown; power budget not checked",
+        ));
+        return;
+    }
+
+    let total: u32 = counts.psu_watts.iter().sum();
+    // With more than one supply the configuration should survive losing the largest
+    let redundant = if counts.psu_watts.len() > 1 {
+        total - counts.psu_watts.iter().max().copied().unwrap_or(0)
+    } else {
+        total
+    };
+
+    if draw > total {
+        warnings.push(
+            AssemblyWarning::new(
+                rule,
+                WarningSeverity::Error,
+                format!("Estimated draw of {}W exceeds the {}W the power supplies provide", draw, total),
+            )
+            .values(format!("<= {}W", total), format!("{}W", draw)),
+        );
+    } else if draw > redundant {
+        warnings.push(
+            AssemblyWarning::new(
+                rule,
+                WarningSeverity::Warning,
+                format!("Estimated draw of {}W is not covered if one power supply fails", draw),
+            )
+            .values(format!("<= {}W", redundant), format!("{}W", draw)),
+        );
+    }
+
+    if counts.psu_watts.iter().any(|w| *w != counts.psu_watts[0]) {
+        warnings.push(AssemblyWarning::new(rule, WarningSeverity::Warning, "Power supplies of different ratings are mixed"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const R750: ChassisLimits = ChassisLimits { cpu_sockets: 2, memory_slots: 32, drive_bays: 16 };
+
+    fn rules(warnings: &[AssemblyWarning]) -> Vec<(AssemblyRule, WarningSeverity)> {
+        warnings.iter().map(|w| (w.rule, w.severity)).collect()
+    }
+
+    #[test]
+    fn test_balanced_build_has_no_warnings() {
+        let counts = AssemblyCounts {
+            cpus: 2,
+            dimm_capacities_gb: Some(vec![64; 16]),
+            drives: Some(8),
+            psu_watts: vec![1400, 1400],
+            power_draw_watts: 900,
+        };
+        assert!(validate_assembly(Some(&R750), &counts).is_empty());
+    }
+
+    #[test]
+    fn test_population_and_power_rules() {
+        let counts = AssemblyCounts {
+            cpus: 2,
+            dimm_capacities_gb: Some(vec![32, 32, 32, 32, 32, 64, 64, 64, 64, 64]),
+            drives: Some(20),
+            psu_watts: vec![800, 800],
+            power_draw_watts: 1200,
+        };
+        let warnings = validate_assembly(Some(&R750), &counts);
+        let found = rules(&warnings);
+        assert!(found.contains(&(AssemblyRule::DriveBays, WarningSeverity::Error)));
+        assert!(found.contains(&(AssemblyRule::PowerBudget, WarningSeverity::Warning)));
+        // 5 DIMMs per socket is unbalanced, and capacities are mixed
+        assert_eq!(found.iter().filter(|(r, _)| *r == AssemblyRule::DimmPopulation).count(), 2);
+        assert_eq!(warnings[0].severity, WarningSeverity::Error);
+
+        let uneven = AssemblyCounts { dimm_capacities_gb: Some(vec![32; 3]), ..counts };
+        let warnings = validate_assembly(Some(&R750), &uneven);
+        assert!(warnings.iter().any(|w| w.message.contains("split evenly")));
+    }
+
+    #[test]
+    fn test_unknown_quantities_are_skipped() {
+        let counts = AssemblyCounts { cpus: 2, psu_watts: vec![], ..Default::default() };
+        assert_eq!(rules(&validate_assembly(Some(&R750), &counts)), vec![(AssemblyRule::PowerBudget, WarningSeverity::Info)]);
+    }
+}
@@ -0,0 +1,592 @@
+//! Hardware basket diffing between vendor price file revisions
+//!
+//! Vendors publish updated baskets every quarter. `diff_baskets` compares two
+//! parsed revisions of the same vendor's basket and reports new and removed
+//! models, per-part price changes and spec changes. `upgrade_bom` re-prices an
+//! existing bill of materials against the new revision and summarises the
+//! delta, flagging lines the new basket no longer offers.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::currency::{BomLine, Money};
+use crate::hardware_parser::basket_parser::{ParsedHardwareBasket, ParsedHardwareLot};
+
+/// Relative price moves below this are treated as rounding noise
+const PRICE_EPSILON: f64 = 0.005;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasketDiffReport {
+    pub vendor: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub added_models: Vec<ModelSummary>,
+    pub removed_models: Vec<ModelSummary>,
+    pub changed_models: Vec<ModelChange>,
+    pub added_parts: Vec<PartSummary>,
+    pub removed_parts: Vec<PartSummary>,
+    pub changed_parts: Vec<PartChange>,
+    pub summary: BasketDiffSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasketDiffSummary {
+    pub models_added: usize,
+    pub models_removed: usize,
+    pub models_changed: usize,
+    pub parts_added: usize,
+    pub parts_removed: usize,
+    pub parts_repriced: usize,
+    pub parts_spec_changed: usize,
+    /// Mean price move across repriced models and parts, in percent
+    pub average_price_change_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSummary {
+    /// Base part number, or the lot code when the vendor gives none
+    pub key: String,
+    pub lot_code: String,
+    pub description: String,
+    pub net_price_usd: Option<f64>,
+    pub net_price_eur: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelChange {
+    pub key: String,
+    pub lot_code: String,
+    pub description: String,
+    pub price_changes: Vec<PriceChange>,
+    pub spec_changes: Vec<SpecChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartSummary {
+    pub part_number: String,
+    pub description: String,
+    pub unit_price_usd: Option<f64>,
+    pub unit_price_eur: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartChange {
+    pub part_number: String,
+    pub description: String,
+    pub price_changes: Vec<PriceChange>,
+    pub spec_changes: Vec<SpecChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceChange {
+    pub currency: String,
+    pub old_price: f64,
+    pub new_price: f64,
+    pub delta: f64,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SpecChange {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// A part as offered in one basket revision, merged from components and options
+#[derive(Debug, Clone)]
+struct PartEntry {
+    part_number: String,
+    description: String,
+    unit_price_usd: Option<f64>,
+    unit_price_eur: Option<f64>,
+    specs: BTreeMap<String, String>,
+}
+
+impl PartEntry {
+    fn summary(&self) -> PartSummary {
+        PartSummary {
+            part_number: self.part_number.clone(),
+            description: self.description.clone(),
+            unit_price_usd: self.unit_price_usd,
+            unit_price_eur: self.unit_price_eur,
+        }
+    }
+
+    fn price(&self, currency: &str) -> Option<f64> {
+        match currency {
+            "USD" => self.unit_price_usd,
+            "EUR" => self.unit_price_eur,
+            _ => None,
+        }
+    }
+}
+
+/// Compare two revisions of a vendor basket
+pub fn diff_baskets(old: &ParsedHardwareBasket, new: &ParsedHardwareBasket) -> BasketDiffReport {
+    let old_models = index_models(old);
+    let new_models = index_models(new);
+    let old_parts = index_parts(old);
+    let new_parts = index_parts(new);
+
+    let mut percents = Vec::new();
+
+    let added_models: Vec<ModelSummary> = new_models
+        .iter()
+        .filter(|(key, _)| !old_models.contains_key(*key))
+        .map(|(key, lot)| model_summary(key, lot))
+        .collect();
+    let removed_models: Vec<ModelSummary> = old_models
+        .iter()
+        .filter(|(key, _)| !new_models.contains_key(*key))
+        .map(|(key, lot)| model_summary(key, lot))
+        .collect();
+    let mut changed_models = Vec::new();
+    for (key, old_lot) in &old_models {
+        let Some(new_lot) = new_models.get(key) else { continue };
+        let price_changes = price_changes(
+            (old_lot.net_price_usd, new_lot.net_price_usd),
+            (old_lot.net_price_eur, new_lot.net_price_eur),
+        );
+        let spec_changes = spec_changes(&lot_specs(old_lot), &lot_specs(new_lot));
+        if price_changes.is_empty() && spec_changes.is_empty() {
+            continue;
+        }
+        percents.extend(price_changes.first().map(|c| c.percent));
+        changed_models.push(ModelChange {
+            key: key.clone(),
+            lot_code: new_lot.lot_code.clone(),
+            description: new_lot.lot_description.clone(),
+            price_changes,
+            spec_changes,
+        });
+    }
+
+    let added_parts: Vec<PartSummary> = new_parts
+        .values()
+        .filter(|p| !old_parts.contains_key(&p.part_number))
+        .map(PartEntry::summary)
+        .collect();
+    let removed_parts: Vec<PartSummary> = old_parts
+        .values()
+        .filter(|p| !new_parts.contains_key(&p.part_number))
+        .map(PartEntry::summary)
+        .collect();
+    let mut changed_parts = Vec::new();
+    for (part_number, old_part) in &old_parts {
+        let Some(new_part) = new_parts.get(part_number) else { continue };
+        let price_changes = price_changes(
+            (old_part.unit_price_usd, new_part.unit_price_usd),
+            (old_part.unit_price_eur, new_part.unit_price_eur),
+        );
+        let spec_changes = spec_changes(&old_part.specs, &new_part.specs);
+        if price_changes.is_empty() && spec_changes.is_empty() {
+            continue;
+        }
+        percents.extend(price_changes.first().map(|c| c.percent));
+        changed_parts.push(PartChange {
+            part_number: part_number.clone(),
+            description: new_part.description.clone(),
+            price_changes,
+            spec_changes,
+        });
+    }
+
+    let summary = BasketDiffSummary {
+        models_added: added_models.len(),
+        models_removed: removed_models.len(),
+        models_changed: changed_models.len(),
+        parts_added: added_parts.len(),
+        parts_removed: removed_parts.len(),
+        parts_repriced: changed_parts.iter().filter(|p| !p.price_changes.is_empty()).count(),
+        parts_spec_changed: changed_parts.iter().filter(|p| !p.spec_changes.is_empty()).count(),
+        average_price_change_percent: (!percents.is_empty())
+            .then(|| round2(percents.iter().sum::<f64>() / percents.len() as f64)),
+    };
+
+    BasketDiffReport {
+        vendor: new.vendor.clone(),
+        old_version: old.vendor_config.file_version.clone(),
+        new_version: new.vendor_config.file_version.clone(),
+        added_models,
+        removed_models,
+        changed_models,
+        added_parts,
+        removed_parts,
+        changed_parts,
+        summary,
+    }
+}
+
+// =============================================================================
+// BOM UPGRADE
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BomLineStatus {
+    Unchanged,
+    Repriced,
+    /// The new basket no longer offers the part
+    Unavailable,
+    /// Offered, but not priced in the line's currency
+    Unpriced,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BomUpgradeLine {
+    pub part_number: String,
+    pub description: String,
+    pub quantity: u32,
+    pub old_unit_price: Money,
+    /// None when the line keeps its old price (unavailable or unpriced)
+    pub new_unit_price: Option<Money>,
+    pub status: BomLineStatus,
+    pub delta: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BomCurrencyTotal {
+    pub old_total: f64,
+    pub new_total: f64,
+    pub delta: f64,
+    pub percent: f64,
+}
+
+/// Existing BOM re-priced against a new basket revision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BomUpgrade {
+    pub basket_version: String,
+    pub lines: Vec<BomUpgradeLine>,
+    /// Old and new totals per line currency; unresolved lines keep their
+    /// old price in the new total
+    pub totals: BTreeMap<String, BomCurrencyTotal>,
+    pub repriced: usize,
+    /// Part numbers that need a replacement or manual price
+    pub unresolved: Vec<String>,
+}
+
+/// Re-price BOM lines (by part number) with the new basket's prices
+pub fn upgrade_bom(lines: &[BomLine], new: &ParsedHardwareBasket) -> BomUpgrade {
+    let parts = index_parts(new);
+    let models = index_models(new);
+
+    let mut upgraded = Vec::with_capacity(lines.len());
+    let mut totals: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+    for line in lines {
+        let key = normalize_key(&line.part_number);
+        let currency = line.unit_price.currency.trim().to_ascii_uppercase();
+        let offered = match (parts.get(&key), models.get(&key)) {
+            (Some(part), _) => Some(part.price(&currency)),
+            (None, Some(lot)) => Some(match currency.as_str() {
+                "USD" => lot.net_price_usd,
+                "EUR" => lot.net_price_eur,
+                _ => None,
+            }),
+            (None, None) => None,
+        };
+        let (status, new_price) = match offered {
+            None => (BomLineStatus::Unavailable, None),
+            Some(None) => (BomLineStatus::Unpriced, None),
+            Some(Some(price)) if relative_change(line.unit_price.amount, price).abs() < PRICE_EPSILON => {
+                (BomLineStatus::Unchanged, Some(price))
+            }
+            Some(Some(price)) => (BomLineStatus::Repriced, Some(price)),
+        };
+
+        let quantity = line.quantity as f64;
+        let old_extended = line.unit_price.amount * quantity;
+        let new_extended = new_price.unwrap_or(line.unit_price.amount) * quantity;
+        let total = totals.entry(currency.clone()).or_insert((0.0, 0.0));
+        total.0 += old_extended;
+        total.1 += new_extended;
+
+        upgraded.push(BomUpgradeLine {
+            part_number: line.part_number.clone(),
+            description: line.description.clone(),
+            quantity: line.quantity,
+            old_unit_price: line.unit_price.clone(),
+            new_unit_price: new_price.map(|p| Money::new(p, &currency)),
+            status,
+            delta: round2(new_extended - old_extended),
+        });
+    }
+
+    BomUpgrade {
+        basket_version: new.vendor_config.file_version.clone(),
+        repriced: upgraded.iter().filter(|l| l.status == BomLineStatus::Repriced).count(),
+        unresolved: upgraded
+            .iter()
+            .filter(|l| matches!(l.status, BomLineStatus::Unavailable | BomLineStatus::Unpriced))
+            .map(|l| l.part_number.clone())
+            .collect(),
+        totals: totals
+            .into_iter()
+            .map(|(currency, (old, new))| {
+                let total = BomCurrencyTotal {
+                    old_total: round2(old),
+                    new_total: round2(new),
+                    delta: round2(new - old),
+                    percent: round2(relative_change(old, new) * 100.0),
+                };
+                (currency, total)
+            })
+            .collect(),
+        lines: upgraded,
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+fn normalize_key(value: &str) -> String {
+    value.trim().to_ascii_uppercase()
+}
+
+fn model_key(lot: &ParsedHardwareLot) -> String {
+    normalize_key(lot.base_part_number.as_deref().filter(|p| !p.trim().is_empty()).unwrap_or(&lot.lot_code))
+}
+
+fn index_models(basket: &ParsedHardwareBasket) -> BTreeMap<String, &ParsedHardwareLot> {
+    let mut models = BTreeMap::new();
+    for lot in &basket.hardware_lots {
+        models.entry(model_key(lot)).or_insert(lot);
+    }
+    models
+}
+
+/// Parts by part number; the first occurrence wins when a part is listed
+/// under several lots
+fn index_parts(basket: &ParsedHardwareBasket) -> BTreeMap<String, PartEntry> {
+    let mut parts = BTreeMap::new();
+    for component in &basket.hardware_components {
+        let Some(part_number) = component.part_number.as_deref().filter(|p| !p.trim().is_empty()) else {
+            continue;
+        };
+        let mut specs = flatten_specs(&component.technical_specs);
+        if let Some(specification) = &component.specification {
+            specs.insert("specification".to_string(), specification.clone());
+        }
+        specs.insert("description".to_string(), component.description.clone());
+        parts.entry(normalize_key(part_number)).or_insert(PartEntry {
+            part_number: normalize_key(part_number),
+            description: component.description.clone(),
+            unit_price_usd: component.unit_price_usd,
+            unit_price_eur: component.unit_price_eur,
+            specs,
+        });
+    }
+    for option in &basket.hardware_options {
+        if option.part_number.trim().is_empty() {
+            continue;
+        }
+        let mut specs = flatten_specs(&option.specifications);
+        specs.insert("description".to_string(), option.description.clone());
+        parts.entry(normalize_key(&option.part_number)).or_insert(PartEntry {
+            part_number: normalize_key(&option.part_number),
+            description: option.description.clone(),
+            unit_price_usd: option.unit_price_usd,
+            unit_price_eur: option.unit_price_eur,
+            specs,
+        });
+    }
+    parts
+}
+
+fn lot_specs(lot: &ParsedHardwareLot) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("description".to_string(), lot.lot_description.clone()),
+        ("server_type".to_string(), lot.server_type.clone()),
+        ("form_factor".to_string(), lot.form_factor.clone()),
+    ])
+}
+
+/// Flatten a JSON spec object to dotted keys with display values
+fn flatten_specs(value: &serde_json::Value) -> BTreeMap<String, String> {
+    fn walk(prefix: &str, value: &serde_json::Value, out: &mut BTreeMap<String, String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    walk(&path, child, out);
+                }
+            }
+            serde_json::Value::Null => {}
+            serde_json::Value::String(s) => {
+                out.insert(prefix.to_string(), s.clone());
+            }
+            other => {
+                out.insert(prefix.to_string(), other.to_string());
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    walk("", value, &mut out);
+    out
+}
+
+fn spec_changes(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> Vec<SpecChange> {
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter(|field| old.get(*field).map(|v| v.trim()) != new.get(*field).map(|v| v.trim()))
+        .map(|field| SpecChange {
+            field: field.clone(),
+            old_value: old.get(field).cloned(),
+            new_value: new.get(field).cloned(),
+        })
+        .collect()
+}
+
+fn price_changes(usd: (Option<f64>, Option<f64>), eur: (Option<f64>, Option<f64>)) -> Vec<PriceChange> {
+    [("USD", usd), ("EUR", eur)]
+        .into_iter()
+        .filter_map(|(currency, prices)| match prices {
+            (Some(old), Some(new)) if relative_change(old, new).abs() >= PRICE_EPSILON => Some(PriceChange {
+                currency: currency.to_string(),
+                old_price: old,
+                new_price: new,
+                delta: round2(new - old),
+                percent: round2(relative_change(old, new) * 100.0),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn relative_change(old: f64, new: f64) -> f64 {
+    if old == 0.0 {
+        if new == 0.0 { 0.0 } else { 1.0 }
+    } else {
+        (new - old) / old
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware_parser::basket_parser::{ParsedHardwareComponent, ParsedVendorConfig};
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn lot(code: &str, base: &str, net_usd: f64, form_factor: &str) -> ParsedHardwareLot {
+        ParsedHardwareLot {
+            vendor: "Dell".to_string(),
+            lot_code: code.to_string(),
+            lot_description: format!("PowerEdge {}", base),
+            base_part_number: Some(base.to_string()),
+            server_type: "Rack Server".to_string(),
+            form_factor: form_factor.to_string(),
+            list_price_usd: None,
+            net_price_usd: Some(net_usd),
+            net_price_eur: None,
+            price_1yr_warranty_usd: None,
+            price_1yr_warranty_eur: None,
+            price_3yr_warranty_usd: None,
+            price_3yr_warranty_eur: None,
+            price_5yr_warranty_usd: None,
+            price_5yr_warranty_eur: None,
+            price_3yr_ps_usd: None,
+            price_5yr_ps_usd: None,
+            price_3yr_psp_usd: None,
+            price_5yr_psp_usd: None,
+            excel_source_file: String::new(),
+            excel_sheet_name: String::new(),
+            excel_row_number: 0,
+        }
+    }
+
+    fn part(part_number: &str, unit_usd: f64, speed: &str) -> ParsedHardwareComponent {
+        ParsedHardwareComponent {
+            vendor: "Dell".to_string(),
+            lot_code: "SMI1".to_string(),
+            part_number: Some(part_number.to_string()),
+            component_type: "RAM".to_string(),
+            component_category: "Hardware".to_string(),
+            description: "64GB RDIMM".to_string(),
+            specification: None,
+            quantity: 1,
+            unit_price_usd: Some(unit_usd),
+            unit_price_eur: None,
+            total_price_usd: None,
+            total_price_eur: None,
+            technical_specs: serde_json::json!({ "speed": speed }),
+            excel_source_file: String::new(),
+            excel_sheet_name: String::new(),
+            excel_row_number: 0,
+        }
+    }
+
+    fn basket(version: &str, lots: Vec<ParsedHardwareLot>, components: Vec<ParsedHardwareComponent>) -> ParsedHardwareBasket {
+        ParsedHardwareBasket {
+            vendor_config: ParsedVendorConfig {
+                vendor_name: "Dell".to_string(),
+                file_version: version.to_string(),
+                last_updated: None,
+                exchange_rates: HashMap::new(),
+                currency_valid_until: None,
+                contact_info: serde_json::Value::Null,
+            },
+            hardware_lots: lots,
+            hardware_components: components,
+            hardware_options: Vec::new(),
+            currency: "USD".to_string(),
+            vendor: "Dell".to_string(),
+            parsed_at: Utc::now(),
+            total_items_processed: 0,
+            processing_errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_models_prices_and_specs() {
+        let old = basket(
+            "Q2",
+            vec![lot("SMI1", "R660", 10_000.0, "1U"), lot("SMI2", "R650", 9_000.0, "1U")],
+            vec![part("370-AHCL", 500.0, "4800"), part("400-AXTV", 300.0, "n/a")],
+        );
+        let new = basket(
+            "Q3",
+            vec![lot("SMI1", "R660", 11_000.0, "1U"), lot("SMI3", "R760", 14_000.0, "2U")],
+            vec![part("370-AHCL", 450.0, "5600")],
+        );
+
+        let report = diff_baskets(&old, &new);
+        assert_eq!(report.added_models[0].key, "R760");
+        assert_eq!(report.removed_models[0].key, "R650");
+        assert_eq!(report.changed_models[0].price_changes[0].percent, 10.0);
+        assert_eq!(report.removed_parts[0].part_number, "400-AXTV");
+
+        let memory = &report.changed_parts[0];
+        assert_eq!(memory.price_changes[0].delta, -50.0);
+        assert_eq!(memory.spec_changes[0].field, "speed");
+        assert_eq!(report.summary.average_price_change_percent, Some(0.0));
+    }
+
+    #[test]
+    fn test_upgrade_bom_reprices_and_flags_unavailable() {
+        let new = basket("Q3", vec![lot("SMI1", "R660", 11_000.0, "1U")], vec![part("370-AHCL", 450.0, "5600")]);
+        let lines = vec![
+            BomLine { part_number: "r660".to_string(), description: String::new(), quantity: 2, unit_price: Money::new(10_000.0, "USD") },
+            BomLine { part_number: "370-AHCL".to_string(), description: String::new(), quantity: 4, unit_price: Money::new(450.0, "USD") },
+            BomLine { part_number: "400-AXTV".to_string(), description: String::new(), quantity: 1, unit_price: Money::new(300.0, "USD") },
+        ];
+
+        let upgrade = upgrade_bom(&lines, &new);
+        let statuses: Vec<BomLineStatus> = upgrade.lines.iter().map(|l| l.status).collect();
+        assert_eq!(
+            statuses,
+            vec![BomLineStatus::Repriced, BomLineStatus::Unchanged, BomLineStatus::Unavailable]
+        );
+        assert_eq!(upgrade.unresolved, vec!["400-AXTV"]);
+        assert_eq!(upgrade.totals["USD"].delta, 2_000.0);
+    }
+}
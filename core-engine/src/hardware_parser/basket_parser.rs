@@ -130,6 +130,7 @@ pub struct ParsedHardwareBasket {
 pub struct HardwareBasketParser;
 
 impl HardwareBasketParser {
+    #[tracing::instrument(name = "hardware_basket.parse", skip_all)]
     pub fn parse_file(&self, file_path: &str) -> Result<ParsedHardwareBasket> {
         let workbook: Xlsx<_> = open_workbook(file_path)
             .map_err(|e| CoreEngineError::parsing(format!("Failed to open Excel file: {}", e)))?;
@@ -1,10 +1,20 @@
 // Hardware Component Classification System
 // Based on HARDWARE_SCHEMA_DESIGN.md
 
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+use crate::error::CoreEngineError;
+use crate::hardware_parser::assembly_validation::AssemblyWarning;
+use crate::Result;
+use ComponentSubcategory as Sub;
+
+/// Classifications scoring below this go to the review queue
+pub const REVIEW_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PrimaryCategory {
     ServerChassis,
     Component,
@@ -21,7 +31,7 @@ pub enum ServerType {
     ModularServer,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ComponentCategory {
     Processing,
     Memory,
@@ -33,7 +43,7 @@ pub enum ComponentCategory {
     Management,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ComponentSubcategory {
     // Processing
     Cpu,
@@ -84,6 +94,26 @@ pub enum ComponentSubcategory {
     Monitoring,
 }
 
+impl ComponentSubcategory {
+    pub fn category(&self) -> ComponentCategory {
+        use ComponentSubcategory::*;
+        match self {
+            Cpu | Gpu | Coprocessor => ComponentCategory::Processing,
+            SystemMemory | Nvdimm | Hbm => ComponentCategory::Memory,
+            Hdd | Ssd | Nvme | RaidController | Hba | BootController | DriveBay | StorageEnclosure => {
+                ComponentCategory::Storage
+            }
+            Ethernet | FibreChannel | Infiniband | Wireless | NetworkSwitch | NetworkSecurity => {
+                ComponentCategory::Networking
+            }
+            PowerSupply | Ups | Pdu => ComponentCategory::Power,
+            Fan | HeatSink | LiquidCooling => ComponentCategory::Cooling,
+            ExpansionCard | RiserCard | Backplane => ComponentCategory::Expansion,
+            RemoteManagement | Kvm | Monitoring => ComponentCategory::Management,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentSpecifications {
     pub cores: Option<u32>,
@@ -127,6 +157,15 @@ pub struct ClassifiedComponent {
     pub specifications: ComponentSpecifications,
     pub compatibility: ComponentCompatibility,
     pub pricing: ComponentPricing,
+    /// Classifier confidence between 0 and 1
+    #[serde(default)]
+    pub confidence: f32,
+}
+
+impl ClassifiedComponent {
+    pub fn needs_review(&self) -> bool {
+        self.confidence < REVIEW_CONFIDENCE_THRESHOLD
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -142,6 +181,9 @@ pub struct ServerConfiguration {
     pub upgrade_options: Vec<ClassifiedComponent>,
     pub compatibility_matrix: CompatibilityMatrix,
     pub pricing: ComponentPricing,
+    /// Problems found when checking the configuration against its chassis
+    #[serde(default)]
+    pub assembly_warnings: Vec<AssemblyWarning>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,74 +206,453 @@ pub struct CompatibilityMatrix {
 }
 
 // Component Classification Rules
+//
+// Every description is scored against each category from weighted features:
+// description tokens, patterns over the whole description, vendor part-number
+// shapes and vendor-specific terms. Corrections users make are replayed on top
+// as an exact part-number override plus learned token weights.
+
+/// Score at which a category is considered fully supported by its features
+const CONFIDENT_SCORE: f32 = 4.0;
+/// Total weight one corrected description spreads over its tokens
+const LEARNED_DESCRIPTION_WEIGHT: f32 = 6.0;
+/// Vendor terms still count, at this fraction, when the vendor is unknown
+const UNKNOWN_VENDOR_FACTOR: f32 = 0.5;
+
+const ACCESSORY: Label = Label::Other(PrimaryCategory::Accessory);
+const SOFTWARE: Label = Label::Other(PrimaryCategory::Software);
+const SERVICE: Label = Label::Other(PrimaryCategory::Service);
+
+/// Single description tokens
+const TOKEN_FEATURES: &[(&str, Label, f32)] = &[
+    ("xeon", Label::Part(Sub::Cpu), 3.0),
+    ("epyc", Label::Part(Sub::Cpu), 3.0),
+    ("processor", Label::Part(Sub::Cpu), 3.0),
+    ("cpu", Label::Part(Sub::Cpu), 2.0),
+    ("cores", Label::Part(Sub::Cpu), 1.0),
+    ("turbo", Label::Part(Sub::Cpu), 1.0),
+    ("tdp", Label::Part(Sub::Cpu), 1.0),
+    ("gpu", Label::Part(Sub::Gpu), 3.0),
+    ("accelerator", Label::Part(Sub::Gpu), 2.0),
+    ("nvidia", Label::Part(Sub::Gpu), 2.0),
+    ("rdimm", Label::Part(Sub::SystemMemory), 3.0),
+    ("lrdimm", Label::Part(Sub::SystemMemory), 3.0),
+    ("dimm", Label::Part(Sub::SystemMemory), 2.5),
+    ("memory", Label::Part(Sub::SystemMemory), 2.0),
+    ("ddr4", Label::Part(Sub::SystemMemory), 2.0),
+    ("ddr5", Label::Part(Sub::SystemMemory), 2.0),
+    ("nvdimm", Label::Part(Sub::Nvdimm), 4.0),
+    ("hdd", Label::Part(Sub::Hdd), 3.0),
+    ("rpm", Label::Part(Sub::Hdd), 2.0),
+    ("ssd", Label::Part(Sub::Ssd), 3.0),
+    ("nvme", Label::Part(Sub::Ssd), 2.0),
+    ("flash", Label::Part(Sub::Ssd), 1.0),
+    ("u.2", Label::Part(Sub::Ssd), 1.5),
+    ("e3.s", Label::Part(Sub::Ssd), 1.5),
+    ("raid", Label::Part(Sub::RaidController), 3.0),
+    ("megaraid", Label::Part(Sub::RaidController), 3.0),
+    ("controller", Label::Part(Sub::RaidController), 1.5),
+    ("hba", Label::Part(Sub::Hba), 3.0),
+    ("boot", Label::Part(Sub::BootController), 2.0),
+    ("m.2", Label::Part(Sub::BootController), 1.0),
+    ("backplane", Label::Part(Sub::Backplane), 3.0),
+    ("ethernet", Label::Part(Sub::Ethernet), 3.0),
+    ("nic", Label::Part(Sub::Ethernet), 2.5),
+    ("ocp", Label::Part(Sub::Ethernet), 1.5),
+    ("sfp+", Label::Part(Sub::Ethernet), 1.5),
+    ("sfp28", Label::Part(Sub::Ethernet), 1.5),
+    ("qsfp28", Label::Part(Sub::Ethernet), 1.5),
+    ("fibre", Label::Part(Sub::FibreChannel), 3.0),
+    ("fc", Label::Part(Sub::FibreChannel), 2.5),
+    ("emulex", Label::Part(Sub::FibreChannel), 2.0),
+    ("qlogic", Label::Part(Sub::FibreChannel), 2.0),
+    ("infiniband", Label::Part(Sub::Infiniband), 3.0),
+    ("psu", Label::Part(Sub::PowerSupply), 3.0),
+    ("platinum", Label::Part(Sub::PowerSupply), 1.5),
+    ("titanium", Label::Part(Sub::PowerSupply), 1.5),
+    ("fan", Label::Part(Sub::Fan), 3.0),
+    ("fans", Label::Part(Sub::Fan), 3.0),
+    ("heatsink", Label::Part(Sub::HeatSink), 3.0),
+    ("riser", Label::Part(Sub::RiserCard), 3.0),
+    ("bmc", Label::Part(Sub::RemoteManagement), 2.0),
+    ("chassis", Label::Chassis, 2.0),
+    ("rails", ACCESSORY, 3.0),
+    ("rail", ACCESSORY, 3.0),
+    ("bezel", ACCESSORY, 3.0),
+    ("cable", ACCESSORY, 2.5),
+    ("cord", ACCESSORY, 2.5),
+    ("bracket", ACCESSORY, 2.0),
+    ("blank", ACCESSORY, 2.0),
+    ("blanks", ACCESSORY, 2.0),
+    ("license", SOFTWARE, 3.0),
+    ("licence", SOFTWARE, 3.0),
+    ("subscription", SOFTWARE, 2.5),
+    ("warranty", SERVICE, 3.0),
+    ("onsite", SERVICE, 2.0),
+    ("installation", SERVICE, 2.0),
+    ("deployment", SERVICE, 2.0),
+];
+
+/// Patterns over the whole lower-cased description
+const PATTERN_FEATURES: &[(&str, Label, f32)] = &[
+    (r"\d+c/\d+t", Label::Part(Sub::Cpu), 2.5),
+    (r"\d+(\.\d+)?\s?ghz", Label::Part(Sub::Cpu), 1.5),
+    (r"\d+\s?mt/s", Label::Part(Sub::SystemMemory), 1.5),
+    (r"\b\d+\s?gb\b", Label::Part(Sub::SystemMemory), 0.5),
+    (r"\b(7\.2|10|15)k\b", Label::Part(Sub::Hdd), 2.5),
+    (r"hard\s?(disk|drive)", Label::Part(Sub::Hdd), 2.5),
+    (r"(read|write)[ -]intensive|mixed[ -]use", Label::Part(Sub::Ssd), 2.5),
+    (r"\d+(\.\d+)?\s?tb\b", Label::Part(Sub::Ssd), 0.5),
+    (r"\d+(\.\d+)?\s?tb\b", Label::Part(Sub::Hdd), 0.5),
+    (r"host bus adapter", Label::Part(Sub::Hba), 3.0),
+    (r"\b\d+\s?gbe\b", Label::Part(Sub::Ethernet), 3.0),
+    (r"base-?t\b", Label::Part(Sub::Ethernet), 2.0),
+    (r"\b(dual|quad|[124])[ -]port\b", Label::Part(Sub::Ethernet), 1.0),
+    (r"\b(8|16|32|64)\s?gb?\s?fc\b|\bfc(16|32|64)\b", Label::Part(Sub::FibreChannel), 2.0),
+    (r"power\s?supply", Label::Part(Sub::PowerSupply), 3.0),
+    (r"\b\d{3,4}\s?w\b", Label::Part(Sub::PowerSupply), 1.5),
+    (r"heat\s?sink", Label::Part(Sub::HeatSink), 3.0),
+    (r"liquid|direct liquid", Label::Part(Sub::LiquidCooling), 3.0),
+    (r"cable management", ACCESSORY, 2.0),
+    (r"\b\d\s?(yr|year)s?\b", SERVICE, 2.0),
+];
+
+/// Vendor part-number shapes; option categories are encoded in the prefix
+const PART_NUMBER_FEATURES: &[(&str, &str, Label, f32)] = &[
+    ("dell", r"^338-", Label::Part(Sub::Cpu), 3.0),
+    ("dell", r"^370-", Label::Part(Sub::SystemMemory), 3.0),
+    ("dell", r"^400-", Label::Part(Sub::Hdd), 1.0),
+    ("dell", r"^400-", Label::Part(Sub::Ssd), 1.0),
+    ("dell", r"^345-", Label::Part(Sub::Ssd), 2.0),
+    ("dell", r"^405-", Label::Part(Sub::RaidController), 2.5),
+    ("dell", r"^540-", Label::Part(Sub::Ethernet), 2.0),
+    ("dell", r"^450-", Label::Part(Sub::PowerSupply), 3.0),
+    ("dell", r"^385-", Label::Part(Sub::RemoteManagement), 2.5),
+    ("dell", r"^770-", ACCESSORY, 2.5),
+    ("dell", r"^634-", SOFTWARE, 2.0),
+    ("dell", r"^(709|865|813|814)-", SERVICE, 2.5),
+    ("lenovo", r"^4XG7A", Label::Part(Sub::Cpu), 2.5),
+    ("lenovo", r"^4ZC7A", Label::Part(Sub::SystemMemory), 3.0),
+    ("lenovo", r"^4XB7A", Label::Part(Sub::Ssd), 1.0),
+    ("lenovo", r"^4XB7A", Label::Part(Sub::Hdd), 1.0),
+    ("lenovo", r"^4Y37A", Label::Part(Sub::RaidController), 2.5),
+    ("lenovo", r"^4XC7A", Label::Part(Sub::Ethernet), 2.0),
+    ("lenovo", r"^4P57A", Label::Part(Sub::PowerSupply), 3.0),
+];
+
+/// Vendor-specific product names
+const VENDOR_TERMS: &[(&str, &str, Label, f32)] = &[
+    ("dell", "perc", Label::Part(Sub::RaidController), 3.0),
+    ("dell", "boss", Label::Part(Sub::BootController), 3.0),
+    ("dell", "idrac", Label::Part(Sub::RemoteManagement), 3.0),
+    ("dell", "prosupport", SERVICE, 3.0),
+    ("dell", "readyrails", ACCESSORY, 3.0),
+    ("lenovo", "xclarity", Label::Part(Sub::RemoteManagement), 3.0),
+    ("lenovo", "xcc", Label::Part(Sub::RemoteManagement), 3.0),
+    ("lenovo", "truddr4", Label::Part(Sub::SystemMemory), 3.0),
+    ("lenovo", "truddr5", Label::Part(Sub::SystemMemory), 3.0),
+    ("lenovo", "premier support", SERVICE, 3.0),
+    ("hpe", "smart array", Label::Part(Sub::RaidController), 3.0),
+    ("hpe", "ilo", Label::Part(Sub::RemoteManagement), 3.0),
+    ("hpe", "smartmemory", Label::Part(Sub::SystemMemory), 3.0),
+    ("hpe", "flexible slot", Label::Part(Sub::PowerSupply), 2.5),
+    ("hpe", "care pack", SERVICE, 3.0),
+];
+
+/// Part-number shapes that identify the vendor when the description does not
+const VENDOR_PART_NUMBERS: &[(&str, &str)] = &[
+    ("dell", r"^\d{3}-[A-Z0-9]{4,5}$"),
+    ("lenovo", r"^\d[A-Z]{1,2}\d{2}[A-Z][A-Z0-9]{5}$"),
+    ("hpe", r"^(P\d{5}|\d{6})-[AB]\d{2}$"),
+];
+
+const STOP_WORDS: &[&str] = &["and", "for", "the", "with", "kit", "gb", "tb"];
+
+static PATTERNS: Lazy<Vec<(Regex, Label, f32)>> = Lazy::new(|| {
+    PATTERN_FEATURES
+        .iter()
+        .map(|(pattern, label, weight)| (Regex::new(pattern).unwrap(), *label, *weight))
+        .collect()
+});
+static PART_NUMBER_PATTERNS: Lazy<Vec<(&'static str, Regex, Label, f32)>> = Lazy::new(|| {
+    PART_NUMBER_FEATURES
+        .iter()
+        .map(|(vendor, pattern, label, weight)| (*vendor, Regex::new(pattern).unwrap(), *label, *weight))
+        .collect()
+});
+static VENDOR_PART_PATTERNS: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+    VENDOR_PART_NUMBERS
+        .iter()
+        .map(|(vendor, pattern)| (*vendor, Regex::new(pattern).unwrap()))
+        .collect()
+});
+
+/// What an item is scored as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Label {
+    Chassis,
+    Part(ComponentSubcategory),
+    /// Accessory, software or service line
+    Other(PrimaryCategory),
+}
+
+impl Label {
+    fn from_parts(primary: PrimaryCategory, subcategory: Option<ComponentSubcategory>) -> Option<Self> {
+        match (primary, subcategory) {
+            (PrimaryCategory::Component, Some(sub)) => Some(Label::Part(sub)),
+            (PrimaryCategory::Component, None) => None,
+            (PrimaryCategory::ServerChassis, _) => Some(Label::Chassis),
+            (other, _) => Some(Label::Other(other)),
+        }
+    }
+
+    fn parts(&self) -> (PrimaryCategory, Option<ComponentCategory>, Option<ComponentSubcategory>) {
+        match self {
+            Label::Chassis => (PrimaryCategory::ServerChassis, None, None),
+            Label::Part(sub) => (PrimaryCategory::Component, Some(sub.category()), Some(*sub)),
+            Label::Other(primary) => (*primary, None, None),
+        }
+    }
+}
+
+/// Result of scoring one item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Classification {
+    pub primary_category: PrimaryCategory,
+    pub component_category: Option<ComponentCategory>,
+    pub component_subcategory: Option<ComponentSubcategory>,
+    /// Between 0 and 1; below `REVIEW_CONFIDENCE_THRESHOLD` the item needs review
+    pub confidence: f32,
+    /// Features that scored for the chosen category, e.g. "token:xeon"
+    pub signals: Vec<String>,
+}
+
+impl Classification {
+    fn from_label(label: Label, confidence: f32, signals: Vec<String>) -> Self {
+        let (primary_category, component_category, component_subcategory) = label.parts();
+        Self { primary_category, component_category, component_subcategory, confidence, signals }
+    }
+
+    fn unclassified() -> Self {
+        Self::from_label(ACCESSORY, 0.0, Vec::new())
+    }
+
+    pub fn needs_review(&self) -> bool {
+        self.confidence < REVIEW_CONFIDENCE_THRESHOLD
+    }
+}
+
+/// A user's correction of a classification, replayed into the classifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationCorrection {
+    pub part_number: String,
+    pub description: String,
+    pub primary_category: PrimaryCategory,
+    #[serde(default)]
+    pub component_subcategory: Option<ComponentSubcategory>,
+}
+
+impl ClassificationCorrection {
+    pub fn validate(&self) -> Result<()> {
+        if self.part_number.trim().is_empty() && self.description.trim().is_empty() {
+            return Err(CoreEngineError::validation("A correction needs a part number or a description"));
+        }
+        if Label::from_parts(self.primary_category, self.component_subcategory).is_none() {
+            return Err(CoreEngineError::validation("Components must be corrected to a subcategory"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Scores(HashMap<Label, (f32, Vec<String>)>);
+
+impl Scores {
+    fn add(&mut self, label: Label, weight: f32, signal: String) {
+        let entry = self.0.entry(label).or_insert((0.0, Vec::new()));
+        entry.0 += weight;
+        entry.1.push(signal);
+    }
+
+    /// Highest scoring label with its confidence and signals
+    fn best(self) -> Option<(Label, f32, Vec<String>)> {
+        let mut ranked: Vec<(Label, (f32, Vec<String>))> = self.0.into_iter().collect();
+        ranked.sort_by(|a, b| b.1 .0.total_cmp(&a.1 .0).then_with(|| format!("{:?}", a.0).cmp(&format!("{:?}", b.0))));
+        let mut ranked = ranked.into_iter();
+        let (label, (best, signals)) = ranked.next()?;
+        let runner_up = ranked.next().map(|(_, (score, _))| score).unwrap_or(0.0);
+        Some((label, confidence(best, runner_up), signals))
+    }
+}
+
+/// How far the best score leads the runner-up, scaled down while the best
+/// score itself is weak
+fn confidence(best: f32, runner_up: f32) -> f32 {
+    if best <= 0.0 {
+        return 0.0;
+    }
+    let margin = best / (best + runner_up);
+    let strength = (best / CONFIDENT_SCORE).min(1.0);
+    (margin * strength * 100.0).round() / 100.0
+}
+
+fn tokenize(description: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = description
+        .to_lowercase()
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '+'))
+        .map(|t| t.trim_matches('.').to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
+
+/// Tokens worth learning from: words, not bare numbers or filler
+fn learnable_tokens(description: &str) -> Vec<String> {
+    tokenize(description)
+        .into_iter()
+        .filter(|t| t.len() >= 2 && t.chars().any(|c| c.is_ascii_alphabetic()) && !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+fn normalize_part_number(part_number: &str) -> String {
+    part_number.trim().to_ascii_uppercase()
+}
+
 pub struct ComponentClassifier {
-    cpu_patterns: Vec<&'static str>,
-    memory_patterns: Vec<&'static str>,
-    storage_patterns: Vec<&'static str>,
-    network_patterns: Vec<&'static str>,
-    power_patterns: Vec<&'static str>,
     server_patterns: HashMap<&'static str, Vec<&'static str>>,
+    /// Part numbers users have classified, upper-cased
+    learned_parts: HashMap<String, Label>,
+    /// Token weights learned from corrected descriptions
+    learned_tokens: HashMap<String, HashMap<Label, f32>>,
 }
 
 impl ComponentClassifier {
     pub fn new() -> Self {
         Self {
-            cpu_patterns: vec![
-                r"(?i)(intel|amd).*(xeon|epyc|core).*(processor|cpu)",
-                r"(?i)\d+c[/]\d+t",  // cores/threads pattern
-                r"(?i)\d+\.\d+ghz",  // frequency pattern
-            ],
-            memory_patterns: vec![
-                r"(?i)\d+gb.*rdimm",
-                r"(?i)\d+gb.*dimm",
-                r"(?i)ddr[4-5]",
-                r"(?i)memory",
-            ],
-            storage_patterns: vec![
-                r"(?i)\d+(\.\d+)?(tb|gb).*(ssd|hdd|hard.*drive)",
-                r"(?i)(sata|sas|nvme|pcie)",
-                r"(?i)2\.5.*in|3\.5.*in",  // drive form factors
-            ],
-            network_patterns: vec![
-                r"(?i)\d+gbe|ethernet",
-                r"(?i)fibre.*channel|fc\d+",
-                r"(?i)(dual|quad).*port",
-                r"(?i)(sfp|qsfp|base-t)",
-            ],
-            power_patterns: vec![
-                r"(?i)\d+w.*power.*supply",
-                r"(?i)hot.*plug.*power",
-                r"(?i)redundant.*power",
-            ],
             server_patterns: HashMap::from([
                 ("dell", vec!["poweredge r", "poweredge t", "poweredge m"]),
                 ("lenovo", vec!["thinksystem sr", "thinksystem st", "thinksystem sd"]),
                 ("hpe", vec!["proliant dl", "proliant ml", "proliant bl"]),
             ]),
+            learned_parts: HashMap::new(),
+            learned_tokens: HashMap::new(),
+        }
+    }
+
+    /// Classifier that has learned from a tenant's corrections
+    pub fn with_corrections(corrections: &[ClassificationCorrection]) -> Self {
+        let mut classifier = Self::new();
+        for correction in corrections {
+            classifier.learn(correction);
+        }
+        classifier
+    }
+
+    /// Remember a correction: the part number is classified as corrected from
+    /// now on and its description tokens lean towards the corrected category
+    pub fn learn(&mut self, correction: &ClassificationCorrection) {
+        let Some(label) = Label::from_parts(correction.primary_category, correction.component_subcategory) else {
+            return;
+        };
+        let part_number = normalize_part_number(&correction.part_number);
+        if !part_number.is_empty() {
+            self.learned_parts.insert(part_number, label);
+        }
+        let tokens = learnable_tokens(&correction.description);
+        if tokens.is_empty() {
+            return;
+        }
+        let weight = LEARNED_DESCRIPTION_WEIGHT / tokens.len() as f32;
+        for token in tokens {
+            *self.learned_tokens.entry(token).or_default().entry(label).or_insert(0.0) += weight;
+        }
+    }
+
+    /// Score an item against every category. `vendor` is the basket vendor
+    /// when known; otherwise it is inferred from the description and part number.
+    pub fn classify(&self, description: &str, part_number: &str, vendor: Option<&str>) -> Classification {
+        let part_number = normalize_part_number(part_number);
+        if let Some(label) = self.learned_parts.get(&part_number) {
+            return Classification::from_label(*label, 1.0, vec!["learned:part_number".to_string()]);
+        }
+
+        let description = description.to_lowercase();
+        let vendor = self.resolve_vendor(&description, &part_number, vendor);
+        let mut scores = Scores::default();
+
+        for patterns in self.server_patterns.values() {
+            for pattern in patterns {
+                if description.contains(pattern) {
+                    scores.add(Label::Chassis, CONFIDENT_SCORE, format!("server:{}", pattern.trim()));
+                }
+            }
+        }
+
+        for token in tokenize(&description) {
+            for (feature, label, weight) in TOKEN_FEATURES {
+                if token == *feature {
+                    scores.add(*label, *weight, format!("token:{}", token));
+                }
+            }
+            if let Some(learned) = self.learned_tokens.get(&token) {
+                for (label, weight) in learned {
+                    scores.add(*label, *weight, format!("learned:{}", token));
+                }
+            }
+        }
+
+        for (pattern, label, weight) in PATTERNS.iter() {
+            if let Some(found) = pattern.find(&description) {
+                scores.add(*label, *weight, format!("pattern:{}", found.as_str()));
+            }
+        }
+
+        if let Some(vendor) = vendor {
+            for (_, pattern, label, weight) in PART_NUMBER_PATTERNS.iter().filter(|(v, ..)| *v == vendor) {
+                if pattern.is_match(&part_number) {
+                    scores.add(*label, *weight, format!("part_number:{}", pattern.as_str().trim_start_matches('^')));
+                }
+            }
+        }
+
+        for (term_vendor, term, label, weight) in VENDOR_TERMS {
+            if !description.contains(term) {
+                continue;
+            }
+            match vendor {
+                Some(v) if v == *term_vendor => scores.add(*label, *weight, format!("{}:{}", term_vendor, term)),
+                None => scores.add(*label, weight * UNKNOWN_VENDOR_FACTOR, format!("{}:{}", term_vendor, term)),
+                Some(_) => {}
+            }
+        }
+
+        match scores.best() {
+            Some((label, confidence, signals)) => Classification::from_label(label, confidence, signals),
+            None => Classification::unclassified(),
         }
     }
 
     pub fn classify_component(&self, description: &str, part_number: &str) -> ClassifiedComponent {
+        let classification = self.classify(description, part_number, None);
         let description_lower = description.to_lowercase();
-        
-        // Determine primary category
-        let (primary_category, component_category, component_subcategory) = 
-            self.determine_component_type(&description_lower);
-        
+
         // Extract specifications
-        let specifications = self.extract_specifications(&description_lower, &component_subcategory);
-        
+        let specifications =
+            self.extract_specifications(&description_lower, &classification.component_subcategory);
+
         // Extract compatibility info
         let compatibility = self.extract_compatibility(&description_lower);
-        
+
         // Extract vendor info
         let vendor = self.extract_vendor(&description_lower);
-        
+
         ClassifiedComponent {
             id: format!("{}_{}", part_number, uuid::Uuid::new_v4().to_string()[..8].to_string()),
-            primary_category,
-            component_category,
-            component_subcategory,
+            primary_category: classification.primary_category,
+            component_category: classification.component_category,
+            component_subcategory: classification.component_subcategory,
             vendor,
             model: self.extract_model(description),
             display_name: description.to_string(),
@@ -244,61 +665,31 @@ impl ComponentClassifier {
                 currency: "USD".to_string(),
                 volume_discounts: None,
             },
+            confidence: classification.confidence,
         }
     }
 
-    fn determine_component_type(&self, description: &str) -> (PrimaryCategory, Option<ComponentCategory>, Option<ComponentSubcategory>) {
-        // Check for server chassis first
-        for (vendor, patterns) in &self.server_patterns {
-            for pattern in patterns {
-                if description.contains(pattern) {
-                    return (PrimaryCategory::ServerChassis, None, None);
-                }
-            }
-        }
-
-        // Check component types
-        if self.matches_patterns(description, &self.cpu_patterns) {
-            return (PrimaryCategory::Component, Some(ComponentCategory::Processing), Some(ComponentSubcategory::Cpu));
-        }
-        
-        if self.matches_patterns(description, &self.memory_patterns) {
-            return (PrimaryCategory::Component, Some(ComponentCategory::Memory), Some(ComponentSubcategory::SystemMemory));
-        }
-        
-        if self.matches_patterns(description, &self.storage_patterns) {
-            // Determine storage subcategory
-            if description.contains("raid") || description.contains("perc") || description.contains("controller") {
-                return (PrimaryCategory::Component, Some(ComponentCategory::Storage), Some(ComponentSubcategory::RaidController));
-            } else if description.contains("ssd") {
-                return (PrimaryCategory::Component, Some(ComponentCategory::Storage), Some(ComponentSubcategory::Ssd));
-            } else {
-                return (PrimaryCategory::Component, Some(ComponentCategory::Storage), Some(ComponentSubcategory::Hdd));
-            }
-        }
-        
-        if self.matches_patterns(description, &self.network_patterns) {
-            if description.contains("fibre") || description.contains("fc") {
-                return (PrimaryCategory::Component, Some(ComponentCategory::Networking), Some(ComponentSubcategory::FibreChannel));
+    /// Dell, Lenovo or HPE, from the hint, the description or the part number shape
+    fn resolve_vendor(&self, description: &str, part_number: &str, hint: Option<&str>) -> Option<&'static str> {
+        let from_text = |text: &str| -> Option<&'static str> {
+            if text.contains("dell") || text.contains("poweredge") {
+                Some("dell")
+            } else if text.contains("lenovo") || text.contains("thinksystem") {
+                Some("lenovo")
+            } else if text.contains("hpe") || text.contains("hewlett") || text.contains("proliant") {
+                Some("hpe")
             } else {
-                return (PrimaryCategory::Component, Some(ComponentCategory::Networking), Some(ComponentSubcategory::Ethernet));
+                None
             }
-        }
-        
-        if self.matches_patterns(description, &self.power_patterns) {
-            return (PrimaryCategory::Component, Some(ComponentCategory::Power), Some(ComponentSubcategory::PowerSupply));
-        }
-
-        // Default to accessory if no clear classification
-        (PrimaryCategory::Accessory, None, None)
-    }
-
-    fn matches_patterns(&self, text: &str, patterns: &[&str]) -> bool {
-        patterns.iter().any(|pattern| {
-            regex::Regex::new(pattern)
-                .map(|re| re.is_match(text))
-                .unwrap_or(false)
-        })
+        };
+        hint.and_then(|h| from_text(&h.to_lowercase()))
+            .or_else(|| from_text(description))
+            .or_else(|| {
+                VENDOR_PART_PATTERNS
+                    .iter()
+                    .find(|(_, pattern)| pattern.is_match(part_number))
+                    .map(|(vendor, _)| *vendor)
+            })
     }
 
     fn extract_specifications(&self, description: &str, subcategory: &Option<ComponentSubcategory>) -> ComponentSpecifications {
@@ -366,6 +757,12 @@ impl ComponentClassifier {
                     specs.port_count = Some(4);
                 }
             },
+            Some(ComponentSubcategory::PowerSupply) => {
+                // Rated output, e.g. "1400W"
+                if let Some(caps) = regex::Regex::new(r"(\d{3,4})\s?w\b").unwrap().captures(description) {
+                    specs.power_consumption = caps.get(1).and_then(|m| m.as_str().parse().ok());
+                }
+            },
             _ => {}
         }
 
@@ -417,3 +814,67 @@ impl ComponentClassifier {
             .join(" ")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scores_descriptions_the_keyword_rules_missed() {
+        let classifier = ComponentClassifier::new();
+
+        let cpu = classifier.classify("Intel Xeon Gold 6338 2.0GHz 32C/64T", "338-CBXK", None);
+        assert_eq!(cpu.component_subcategory, Some(ComponentSubcategory::Cpu));
+        assert!(!cpu.needs_review());
+
+        // "fc" inside another word used to make this a Fibre Channel card
+        let psu = classifier.classify("Single Hot-plug 1400W PSU (fcc)", "450-AKLF", None);
+        assert_eq!(psu.component_subcategory, Some(ComponentSubcategory::PowerSupply));
+
+        let raid = classifier.classify("PERC H755 Front Load", "405-AAZB", Some("Dell"));
+        assert_eq!(raid.component_subcategory, Some(ComponentSubcategory::RaidController));
+        assert!(raid.signals.iter().any(|s| s == "dell:perc"));
+
+        let rails = classifier.classify("ReadyRails Sliding Rails With Cable Management Arm", "770-BECC", None);
+        assert_eq!(rails.primary_category, PrimaryCategory::Accessory);
+        assert_eq!(rails.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_unknown_items_need_review() {
+        let classifier = ComponentClassifier::new();
+        let unknown = classifier.classify("Trusted Platform Module 2.0 V3", "461-AAIG", None);
+        assert!(unknown.needs_review());
+
+        let weak = classifier.classify("Front bezel blank", "", None);
+        assert_eq!(weak.primary_category, PrimaryCategory::Accessory);
+    }
+
+    #[test]
+    fn test_corrections_are_learned() {
+        let correction = ClassificationCorrection {
+            part_number: "461-aaig".to_string(),
+            description: "Trusted Platform Module 2.0 V3".to_string(),
+            primary_category: PrimaryCategory::Component,
+            component_subcategory: Some(ComponentSubcategory::Monitoring),
+        };
+        correction.validate().unwrap();
+        let classifier = ComponentClassifier::with_corrections(&[correction]);
+
+        let exact = classifier.classify("anything", "461-AAIG", None);
+        assert_eq!(exact.component_subcategory, Some(ComponentSubcategory::Monitoring));
+        assert_eq!(exact.confidence, 1.0);
+
+        let similar = classifier.classify("Trusted Platform Module 2.0 China", "461-AAIH", None);
+        assert_eq!(similar.component_subcategory, Some(ComponentSubcategory::Monitoring));
+        assert!(!similar.needs_review());
+
+        let invalid = ClassificationCorrection {
+            part_number: "X".to_string(),
+            description: String::new(),
+            primary_category: PrimaryCategory::Component,
+            component_subcategory: None,
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
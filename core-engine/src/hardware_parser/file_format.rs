@@ -0,0 +1,137 @@
+//! File format detection for uploaded hardware files
+//!
+//! Vendor exports come as XML configuration dumps, CSV or spreadsheets.
+//! The format is decided from the file signature first and the extension
+//! second, so binary workbooks are never read as UTF-8 text.
+
+use std::path::Path;
+
+use calamine::{open_workbook_auto, DataType, Reader};
+
+use crate::error::CoreEngineError;
+use crate::Result;
+
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+/// OLE2 compound document, used by legacy .xls workbooks
+const OLE2_SIGNATURE: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Xlsx,
+    Xlsb,
+    Xls,
+    Ods,
+    Xml,
+    Csv,
+    Text,
+    /// Zip archive that is not a known workbook type
+    Archive,
+    Binary,
+}
+
+impl FileFormat {
+    /// Decide the format from the leading bytes, using the extension to tell
+    /// zip-based workbook types apart
+    pub fn detect(file_path: &str, bytes: &[u8]) -> Self {
+        let extension = Path::new(file_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+
+        if bytes.starts_with(ZIP_SIGNATURE) {
+            return match extension.as_str() {
+                "xlsx" | "xlsm" => FileFormat::Xlsx,
+                "xlsb" => FileFormat::Xlsb,
+                "ods" => FileFormat::Ods,
+                _ if contains(bytes, b"xl/workbook") => FileFormat::Xlsx,
+                _ => FileFormat::Archive,
+            };
+        }
+        if bytes.starts_with(OLE2_SIGNATURE) {
+            return FileFormat::Xls;
+        }
+
+        let text = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+        if std::str::from_utf8(text).is_err() {
+            return FileFormat::Binary;
+        }
+        let trimmed = text.iter().position(|b| !b.is_ascii_whitespace()).map(|i| &text[i..]).unwrap_or(&[]);
+        if trimmed.starts_with(b"<") {
+            FileFormat::Xml
+        } else if extension == "csv" {
+            FileFormat::Csv
+        } else {
+            FileFormat::Text
+        }
+    }
+
+    pub fn is_spreadsheet(&self) -> bool {
+        matches!(self, FileFormat::Xlsx | FileFormat::Xlsb | FileFormat::Xls | FileFormat::Ods)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileFormat::Xlsx => "xlsx",
+            FileFormat::Xlsb => "xlsb",
+            FileFormat::Xls => "xls",
+            FileFormat::Ods => "ods",
+            FileFormat::Xml => "xml",
+            FileFormat::Csv => "csv",
+            FileFormat::Text => "text",
+            FileFormat::Archive => "zip archive",
+            FileFormat::Binary => "binary",
+        }
+    }
+}
+
+/// Text content of a file for vendor detection and text-based parsers.
+/// Workbooks are flattened to one tab-separated line per row, sheet by sheet.
+pub fn read_text(file_path: &str, format: FileFormat, bytes: &[u8]) -> Result<String> {
+    if format.is_spreadsheet() {
+        return spreadsheet_text(file_path);
+    }
+    let text = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+    Ok(String::from_utf8_lossy(text).into_owned())
+}
+
+fn spreadsheet_text(file_path: &str) -> Result<String> {
+    let mut workbook = open_workbook_auto(file_path)
+        .map_err(|e| CoreEngineError::parsing(format!("Failed to open workbook: {}", e)))?;
+    let mut text = String::new();
+    for (sheet, range) in workbook.worksheets() {
+        text.push_str(&format!("# {}\n", sheet));
+        for row in range.rows() {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| match cell {
+                    DataType::Empty => String::new(),
+                    other => other.to_string(),
+                })
+                .collect();
+            text.push_str(&cells.join("\t"));
+            text.push('\n');
+        }
+    }
+    Ok(text)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_wins_over_extension() {
+        assert_eq!(FileFormat::detect("basket.txt", b"PK\x03\x04....xl/workbook.xml"), FileFormat::Xlsx);
+        assert_eq!(FileFormat::detect("basket.xlsx", OLE2_SIGNATURE), FileFormat::Xls);
+        assert_eq!(FileFormat::detect("export.csv", b"\xEF\xBB\xBF  <SystemConfiguration Model="), FileFormat::Xml);
+        assert_eq!(FileFormat::detect("export.csv", b"part,qty\n"), FileFormat::Csv);
+        assert_eq!(FileFormat::detect("export.bin", &[0xFF, 0xFE, 0x00, 0x9F]), FileFormat::Binary);
+        assert_eq!(FileFormat::detect("bundle.zip", b"PK\x03\x04docs"), FileFormat::Archive);
+    }
+}
@@ -1,8 +1,11 @@
 pub mod adapters;
+pub mod assembly_validation;
+pub mod basket_diff;
 pub mod basket_parser;
 pub mod basket_parser_new;
 pub mod spec_parser;
 pub mod component_classifier;
+pub mod file_format;
 pub mod server_assembly;
 
 pub use basket_parser::{
@@ -13,6 +16,9 @@ pub use basket_parser::{
     ParsedHardwareOption,
     ParsedVendorConfig,
 };
+pub use assembly_validation::{AssemblyRule, AssemblyWarning, WarningSeverity};
+pub use basket_diff::{diff_baskets, upgrade_bom, BasketDiffReport, BomUpgrade};
+pub use file_format::FileFormat;
 
 use crate::models::UniversalServer;
 use crate::error::CoreEngineError;
@@ -31,50 +37,82 @@ pub trait HardwareParser {
 }
 
 // Enum to represent the supported vendors
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum Vendor {
     Dell,
     Lenovo,
     Hpe,
-    Unknown,
+}
+
+impl Vendor {
+    fn parser_name(&self) -> &'static str {
+        match self {
+            Vendor::Dell => "Dell SCP (XML)",
+            Vendor::Lenovo => "Lenovo DCSC (XML)",
+            Vendor::Hpe => "HPE iQuote",
+        }
+    }
+
+    // Content marker identifying the vendor's export
+    fn matches(&self, content: &str) -> bool {
+        match self {
+            // Dell SCP XML files have a very specific root element
+            Vendor::Dell => content.contains("<SystemConfiguration Model="),
+            // Lenovo DCSC XML seems to have this as a root element.
+            // This is an assumption based on the report and may need refinement.
+            Vendor::Lenovo => content.contains("<Configuration cf_ver="),
+            // iQuote exports are workbooks that name the tool in their header
+            Vendor::Hpe => content.contains("iQuote"),
+        }
+    }
+
+    // Parsers that can read a given file format
+    fn candidates(format: FileFormat) -> &'static [Vendor] {
+        match format {
+            FileFormat::Xml => &[Vendor::Dell, Vendor::Lenovo],
+            FileFormat::Xlsx | FileFormat::Xlsb | FileFormat::Xls | FileFormat::Ods => &[Vendor::Hpe],
+            FileFormat::Csv | FileFormat::Text => &[Vendor::Dell, Vendor::Lenovo, Vendor::Hpe],
+            FileFormat::Archive | FileFormat::Binary => &[],
+        }
+    }
 }
 
 // The main universal parser
 pub struct UniversalParser;
 
 impl UniversalParser {
-    // Detect the vendor based on file content
-    fn detect_vendor(content: &str) -> Vendor {
-        // Dell SCP XML files have a very specific root element
-        if content.contains("<SystemConfiguration Model=") {
-            Vendor::Dell
-        }
-        // Lenovo DCSC XML seems to have this as a root element.
-        // This is an assumption based on the report and may need refinement.
-        else if content.contains("<Configuration cf_ver=") {
-            Vendor::Lenovo
-        }
-        // A placeholder for HPE detection, which might be based on file extension (.xls, .xlsx)
-        // or content sniffing for specific headers.
-        else if content.contains("iQuote") { // A guess
-            Vendor::Hpe
-        } else {
-            Vendor::Unknown
-        }
+    // Detect the vendor among the parsers that understand the file format
+    fn detect_vendor(format: FileFormat, content: &str) -> Result<Vendor> {
+        let candidates = Vendor::candidates(format);
+        candidates
+            .iter()
+            .copied()
+            .find(|vendor| vendor.matches(content))
+            .ok_or_else(|| {
+                CoreEngineError::unsupported_format(
+                    format.label(),
+                    candidates.iter().map(|v| v.parser_name().to_string()).collect(),
+                )
+            })
     }
 
-    // Parse a file from a given path
+    // Parse a file from a given path, dispatching on its signature and
+    // extension before looking at the content
+    #[tracing::instrument(name = "hardware.parse", skip_all)]
     pub fn parse_file(&self, file_path: &str) -> Result<UniversalServer> {
-        let content = fs::read_to_string(file_path)
+        let bytes = fs::read(file_path)
             .map_err(|e| CoreEngineError::io(format!("Failed to read file: {}", e)))?;
 
-        let vendor = Self::detect_vendor(&content);
+        let format = FileFormat::detect(file_path, &bytes);
+        if Vendor::candidates(format).is_empty() {
+            return Err(CoreEngineError::unsupported_format(format.label(), Vec::new()));
+        }
+        let content = file_format::read_text(file_path, format, &bytes)?;
 
-        let parser: Box<dyn HardwareParser> = match vendor {
+        let parser: Box<dyn HardwareParser> = match Self::detect_vendor(format, &content)? {
             Vendor::Dell => Box::new(DellScpParser),
             Vendor::Lenovo => Box::new(LenovoDcscParser),
             Vendor::Hpe => Box::new(HpeIquoteParser),
-            Vendor::Unknown => return Err(CoreEngineError::parsing("Unknown or unsupported file format".to_string())),
         };
 
         parser.parse(&content)
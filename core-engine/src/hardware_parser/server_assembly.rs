@@ -1,7 +1,9 @@
 // Server Assembly Engine
 // Assembles classified components into server configurations
 
+use crate::hardware_parser::assembly_validation::{self, AssemblyCounts, AssemblyWarning, ChassisLimits};
 use crate::hardware_parser::component_classifier::*;
+use crate::models::UniversalServer;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -24,6 +26,16 @@ pub struct PlatformRules {
     pub supported_cpu_families: Vec<String>,
 }
 
+impl PlatformRules {
+    pub fn limits(&self) -> ChassisLimits {
+        ChassisLimits {
+            cpu_sockets: self.cpu_socket_count,
+            memory_slots: self.memory_slot_count,
+            drive_bays: self.drive_bay_count,
+        }
+    }
+}
+
 impl ServerAssemblyEngine {
     pub fn new() -> Self {
         let mut engine = Self {
@@ -111,6 +123,27 @@ impl ServerAssemblyEngine {
         });
     }
 
+    /// Platform rules for a model name such as "PowerEdge R750"
+    pub fn platform_rules_for(&self, model_name: &str) -> Option<&PlatformRules> {
+        let model = model_name.to_uppercase();
+        self.server_platform_rules
+            .iter()
+            .filter(|(platform, _)| model.contains(platform.as_str()))
+            .max_by_key(|(platform, _)| platform.len())
+            .map(|(_, rules)| rules)
+    }
+
+    /// Check a parsed server against its chassis. Without platform rules for
+    /// the model only the power budget is checked.
+    pub fn validate_server(&self, server: &UniversalServer) -> Vec<AssemblyWarning> {
+        let limits = server
+            .model_name
+            .as_deref()
+            .and_then(|model| self.platform_rules_for(model))
+            .map(PlatformRules::limits);
+        assembly_validation::validate_assembly(limits.as_ref(), &AssemblyCounts::from_server(server))
+    }
+
     pub fn process_hardware_basket(&self, raw_data: Vec<(String, String, String)>) -> ProcessingResult {
         // Phase 1: Classify all components
         println!("🔍 Phase 1: Component Classification");
@@ -264,9 +297,39 @@ impl ServerAssemblyEngine {
                 currency: "USD".to_string(),
                 volume_discounts: None,
             },
+            assembly_warnings: self.validate_base_configuration(rules, cpu, compatible_components),
         }
     }
 
+    /// Basket lines carry no quantities, so the base configuration is checked
+    /// as a fully populated chassis against its power supply option
+    fn validate_base_configuration(
+        &self,
+        rules: &PlatformRules,
+        cpu: Option<&ClassifiedComponent>,
+        compatible_components: &[&ClassifiedComponent],
+    ) -> Vec<AssemblyWarning> {
+        let limits = rules.limits();
+        let psu_watts = compatible_components
+            .iter()
+            .filter(|comp| matches!(comp.component_subcategory, Some(ComponentSubcategory::PowerSupply)))
+            .find_map(|comp| comp.specifications.power_consumption)
+            // Rack servers ship with a redundant pair of the priced supply
+            .map(|watts| vec![watts, watts])
+            .unwrap_or_default();
+        let counts = AssemblyCounts {
+            cpus: if cpu.is_some() { limits.cpu_sockets } else { 0 },
+            dimm_capacities_gb: None,
+            drives: None,
+            psu_watts,
+            power_draw_watts: assembly_validation::estimate_full_population_watts(
+                &limits,
+                cpu.and_then(|c| c.specifications.cores),
+            ),
+        };
+        assembly_validation::validate_assembly(Some(&limits), &counts)
+    }
+
     fn calculate_base_price(&self, cpu: Option<&ClassifiedComponent>, components: &[&ClassifiedComponent]) -> Option<f64> {
         let mut total_price = 0.0;
         let mut has_price = false;
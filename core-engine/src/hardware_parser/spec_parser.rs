@@ -1,6 +1,7 @@
 use crate::models::hardware_basket::{ProcessorSpec, MemorySpec, StorageSpec, NetworkSpec, NetworkPort, StorageSlot};
 use regex::{Regex, Captures};
 use once_cell::sync::Lazy;
+use crate::units::Hertz;
 
 // This module is responsible for parsing raw string descriptions of hardware
 // components into structured data models.
@@ -23,11 +24,6 @@ static PROCESSOR_CORES_ONLY_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)(\d+)\s*C\b").unwrap()
 });
 
-// Frequency regex to match "2.7GHz", "2.7 GHz", case-insensitive
-static PROCESSOR_FREQ_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)(\d+(?:\.\d+)?)\s*GHz").unwrap()
-});
-
 static MEMORY_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)(\d+)GB\s*(?:RDIMM|LRDIMM|DIMM),\s*(\d+)MT/s").unwrap()
 });
@@ -72,8 +68,8 @@ impl SpecParser {
                 core_count = caps_cores.get(1).and_then(|m| m.as_str().parse::<i32>().ok());
             }
 
-            if let Some(freq_caps) = PROCESSOR_FREQ_REGEX.captures(description).or_else(|| PROCESSOR_FREQ_REGEX.captures(&model)) {
-                frequency_ghz = freq_caps.get(1).and_then(|m| m.as_str().parse::<f32>().ok());
+            if let Some(frequency) = Hertz::find(description).or_else(|| Hertz::find(&model)) {
+                frequency_ghz = Some(frequency.as_ghz() as f32);
             }
 
             ProcessorSpec {
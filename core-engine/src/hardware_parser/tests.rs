@@ -62,3 +62,20 @@ fn test_hpe_parser_stub() {
     // Clean up the dummy file
     std::fs::remove_file(dummy_file_path).unwrap();
 }
+
+#[test]
+fn test_unsupported_format_lists_attempted_parsers() {
+    let dummy_file_path = "dummy_unknown.xml";
+    std::fs::write(dummy_file_path, "<Inventory vendor=\"Acme\"/>").unwrap();
+
+    let result = UniversalParser.parse_file(dummy_file_path);
+    std::fs::remove_file(dummy_file_path).unwrap();
+
+    match result {
+        Err(CoreEngineError::UnsupportedFormat { format, attempted }) => {
+            assert_eq!(format, "xml");
+            assert_eq!(attempted, vec!["Dell SCP (XML)", "Lenovo DCSC (XML)"]);
+        }
+        other => panic!("expected UnsupportedFormat, got {:?}", other.map(|s| s.vendor)),
+    }
+}
@@ -6,8 +6,12 @@
 pub mod models;
 pub mod parser;
 pub mod analysis;
+pub mod anonymization;
 pub mod forecasting;
 pub mod sizing;
+pub mod sizing_session;
+pub mod consolidation;
+pub mod currency;
 pub mod translation;
 pub mod document_generation;
 pub mod error;
@@ -16,6 +20,15 @@ pub mod vendor_client;
 pub mod vendor_data;
 pub mod network_visualizer;
 pub mod project_manager;
+pub mod project_crypto;
+pub mod redaction;
+pub mod rvtools_generator;
+pub mod units;
+pub mod charts;
+pub mod compat;
+pub mod columnar;
+#[cfg(feature = "collector")]
+pub mod collector;
 
 pub use error::CoreEngineError;
 pub type Result<T> = std::result::Result<T, CoreEngineError>;
@@ -18,6 +18,9 @@ pub struct VsphereEnvironment {
     pub total_vms: usize,
     pub total_hosts: usize,
     pub summary_metrics: EnvironmentSummary,
+    /// Rows skipped while parsing; empty for a clean import
+    #[serde(default)]
+    pub import_report: ImportReport,
 }
 
 impl VsphereEnvironment {
@@ -69,6 +72,59 @@ pub struct EnvironmentSummary {
     pub health_issues: Vec<HealthIssue>,
 }
 
+/// Outcome of an RVTools import. Malformed rows are skipped and recorded
+/// here instead of failing the whole workbook.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub rows_total: usize,
+    pub rows_imported: usize,
+    pub rows_failed: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    /// Count a parsed row, keeping its value or its error
+    pub fn record<T>(&mut self, row: std::result::Result<T, RowError>) -> Option<T> {
+        self.rows_total += 1;
+        match row {
+            Ok(value) => {
+                self.rows_imported += 1;
+                Some(value)
+            }
+            Err(error) => {
+                self.rows_failed += 1;
+                self.errors.push(error);
+                None
+            }
+        }
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+/// Why one row of an import was rejected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowError {
+    pub sheet: String,
+    /// 1-based, as shown in Excel; 0 when a whole sheet could not be read
+    pub row: usize,
+    pub column: Option<String>,
+    pub reason: String,
+}
+
+impl RowError {
+    pub fn new(sheet: &str, row: usize, column: Option<&str>, reason: impl Into<String>) -> Self {
+        Self {
+            sheet: sheet.to_string(),
+            row,
+            column: column.map(str::to_string),
+            reason: reason.into(),
+        }
+    }
+}
+
 /// Represents a vSphere cluster
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Cluster {
@@ -579,6 +635,48 @@ pub struct TcoAnalysis {
     pub target_environment_costs: TargetCosts,
     pub savings_analysis: SavingsAnalysis,
     pub payback_period_months: f32,
+    /// Currency every amount above is expressed in
+    #[serde(default = "default_tco_currency")]
+    pub currency: String,
+}
+
+fn default_tco_currency() -> String {
+    "USD".to_string()
+}
+
+impl TcoAnalysis {
+    /// The same analysis expressed in another currency; the payback period
+    /// and savings percentage do not depend on the currency
+    pub fn converted(&self, rates: &crate::currency::ExchangeRates, currency: &str) -> crate::Result<TcoAnalysis> {
+        let rate = rates.rate(&self.currency, currency)?;
+        let current = &self.current_environment_costs;
+        let target = &self.target_environment_costs;
+        let savings = &self.savings_analysis;
+        Ok(TcoAnalysis {
+            current_environment_costs: CurrentCosts {
+                hardware_annual: current.hardware_annual * rate,
+                software_licensing_annual: current.software_licensing_annual * rate,
+                datacenter_annual: current.datacenter_annual * rate,
+                personnel_annual: current.personnel_annual * rate,
+                total_annual: current.total_annual * rate,
+            },
+            target_environment_costs: TargetCosts {
+                hardware_acquisition: target.hardware_acquisition * rate,
+                software_licensing_annual: target.software_licensing_annual * rate,
+                implementation_services: target.implementation_services * rate,
+                ongoing_operational_annual: target.ongoing_operational_annual * rate,
+                total_annual: target.total_annual * rate,
+            },
+            savings_analysis: SavingsAnalysis {
+                annual_savings: savings.annual_savings * rate,
+                three_year_savings: savings.three_year_savings * rate,
+                five_year_savings: savings.five_year_savings * rate,
+                savings_percentage: savings.savings_percentage,
+            },
+            payback_period_months: self.payback_period_months,
+            currency: crate::currency::normalize_code(currency)?,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
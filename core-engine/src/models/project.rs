@@ -15,6 +15,9 @@ pub struct Project {
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
     pub users: Vec<String>, // Simple list of user names for now
+    /// Who may open and change the project; empty for projects that predate sharing
+    #[serde(default)]
+    pub members: Vec<ProjectMember>,
     pub timeline: Vec<TimelineItem>,
     pub artifacts: Vec<ProjectArtifact>,
     pub hardware_allocations: Vec<HardwareAllocation>,
@@ -92,6 +95,31 @@ pub struct Server {
     // Other relevant server specs...
 }
 
+/// Access level on a single project. Each role includes the ones below it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectRole {
+    /// Read the project, its placements and documents
+    Viewer,
+    /// Change the project's data and generate documents
+    Editor,
+    /// Share the project, change roles and delete it
+    Owner,
+}
+
+impl ProjectRole {
+    pub fn allows(self, required: ProjectRole) -> bool {
+        self >= required
+    }
+}
+
+/// A user's role on a project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectMember {
+    pub user: String,
+    pub role: ProjectRole,
+}
+
 /// Records the allocation of a specific server to a project for a period of time.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HardwareAllocation {
@@ -113,6 +141,7 @@ impl Project {
             start_date: now,
             end_date: now,
             users: vec![],
+            members: vec![],
             timeline: vec![],
             artifacts: vec![],
             hardware_allocations: vec![],
@@ -120,4 +149,67 @@ impl Project {
             updated_at: now,
         }
     }
+
+    /// Role of `user` on the project. Projects without members predate
+    /// sharing and stay open to everyone.
+    pub fn role_of(&self, user: &str) -> Option<ProjectRole> {
+        if self.members.is_empty() {
+            return Some(ProjectRole::Owner);
+        }
+        self.members.iter().filter(|m| m.user == user).map(|m| m.role).max()
+    }
+
+    /// Add `user` with `role`, or change the role they have
+    pub fn set_member(&mut self, user: &str, role: ProjectRole) {
+        match self.members.iter_mut().find(|m| m.user == user) {
+            Some(member) => member.role = role,
+            None => self.members.push(ProjectMember { user: user.to_string(), role }),
+        }
+    }
+
+    /// Remove `user`, unless they are the last owner
+    pub fn remove_member(&mut self, user: &str) -> bool {
+        let owners = self.members.iter().filter(|m| m.role == ProjectRole::Owner).count();
+        let is_owner = self.members.iter().any(|m| m.user == user && m.role == ProjectRole::Owner);
+        if is_owner && owners == 1 {
+            return false;
+        }
+        self.members.retain(|m| m.user != user);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_without_members_is_open() {
+        let project = Project::new("Legacy".to_string(), String::new());
+        assert_eq!(project.role_of("anyone"), Some(ProjectRole::Owner));
+    }
+
+    #[test]
+    fn test_role_of_members() {
+        let mut project = Project::new("Shared".to_string(), String::new());
+        project.set_member("alex", ProjectRole::Owner);
+        project.set_member("sam", ProjectRole::Viewer);
+        assert_eq!(project.role_of("sam"), Some(ProjectRole::Viewer));
+        assert_eq!(project.role_of("kim"), None);
+        assert!(ProjectRole::Owner.allows(ProjectRole::Editor));
+        assert!(!ProjectRole::Viewer.allows(ProjectRole::Editor));
+
+        project.set_member("sam", ProjectRole::Editor);
+        assert_eq!(project.role_of("sam"), Some(ProjectRole::Editor));
+    }
+
+    #[test]
+    fn test_last_owner_cannot_be_removed() {
+        let mut project = Project::new("Shared".to_string(), String::new());
+        project.set_member("alex", ProjectRole::Owner);
+        project.set_member("sam", ProjectRole::Editor);
+        assert!(!project.remove_member("alex"));
+        assert!(project.remove_member("sam"));
+        assert_eq!(project.members.len(), 1);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::models::*;
 use crate::error::CoreEngineError;
 use crate::Result;
+use crate::units::Bytes;
 use calamine::{Reader, Xlsx, open_workbook, Range, DataType};
 use std::collections::HashMap;
 use std::path::Path;
@@ -25,17 +26,20 @@ impl RvToolsParser {
         })
     }
 
-    /// Parse the entire RVTools file into a VsphereEnvironment
+    /// Parse the entire RVTools file into a VsphereEnvironment. Malformed
+    /// rows are skipped and listed in the environment's `import_report`.
+    #[tracing::instrument(name = "rvtools.parse", skip_all)]
     pub fn parse(&mut self) -> Result<VsphereEnvironment> {
         // First, build header maps for all required sheets
         self.build_header_maps()?;
 
         // Parse each required sheet
-        let vm_data = self.parse_vm_info()?;
-        let host_data = self.parse_host_info()?;
-        let disk_data = self.parse_disk_info()?;
-        let partition_data = self.parse_partition_info()?;
-        let network_data = self.parse_network_info()?;
+        let mut report = ImportReport::default();
+        let vm_data = self.parse_vm_info(&mut report)?;
+        let host_data = self.parse_host_info(&mut report)?;
+        let disk_data = self.parse_disk_info(&mut report)?;
+        let partition_data = self.parse_partition_info(&mut report)?;
+        let network_data = self.parse_network_info(&mut report)?;
 
         // Build the complete environment model
         let environment = self.build_environment_model(
@@ -44,6 +48,7 @@ impl RvToolsParser {
             disk_data,
             partition_data,
             network_data,
+            report,
         )?;
 
         Ok(environment)
@@ -92,7 +97,7 @@ impl RvToolsParser {
     }
 
     /// Parse vInfo sheet for VM data
-    fn parse_vm_info(&mut self) -> Result<Vec<RawVmData>> {
+    fn parse_vm_info(&mut self, report: &mut ImportReport) -> Result<Vec<RawVmData>> {
         let range = self.workbook.worksheet_range("vInfo")
             .ok_or_else(|| CoreEngineError::parsing("vInfo sheet not found".to_string()))?
             .map_err(|e| CoreEngineError::parsing(format!("Failed to read vInfo sheet: {}", e)))?;
@@ -103,20 +108,21 @@ impl RvToolsParser {
         let mut vms = Vec::new();
 
         // Skip header row
-        for row in range.rows().skip(1) {
+        for (index, row) in range.rows().enumerate().skip(1) {
             if row.is_empty() || self.is_row_empty(row) {
                 continue;
             }
 
-            let vm = self.parse_vm_row(row, header_map)?;
-            vms.push(vm);
+            if let Some(vm) = report.record(self.parse_vm_row(row, header_map, index + 1)) {
+                vms.push(vm);
+            }
         }
 
         Ok(vms)
     }
 
     /// Parse vHost sheet for host data
-    fn parse_host_info(&mut self) -> Result<Vec<Host>> {
+    fn parse_host_info(&mut self, report: &mut ImportReport) -> Result<Vec<Host>> {
         let range = self.workbook.worksheet_range("vHost")
             .ok_or_else(|| CoreEngineError::parsing("vHost sheet not found".to_string()))?
             .map_err(|e| CoreEngineError::parsing(format!("Failed to read vHost sheet: {}", e)))?;
@@ -126,20 +132,21 @@ impl RvToolsParser {
 
         let mut hosts = Vec::new();
 
-        for row in range.rows().skip(1) {
+        for (index, row) in range.rows().enumerate().skip(1) {
             if row.is_empty() || self.is_row_empty(row) {
                 continue;
             }
 
-            let host = self.parse_host_row(row, header_map)?;
-            hosts.push(host);
+            if let Some(host) = report.record(self.parse_host_row(row, header_map, index + 1)) {
+                hosts.push(host);
+            }
         }
 
         Ok(hosts)
     }
 
     /// Parse vDisk sheet for disk data
-    fn parse_disk_info(&mut self) -> Result<Vec<RawDiskData>> {
+    fn parse_disk_info(&mut self, report: &mut ImportReport) -> Result<Vec<RawDiskData>> {
         let range = self.workbook.worksheet_range("vDisk")
             .ok_or_else(|| CoreEngineError::parsing("vDisk sheet not found".to_string()))?
             .map_err(|e| CoreEngineError::parsing(format!("Failed to read vDisk sheet: {}", e)))?;
@@ -149,20 +156,21 @@ impl RvToolsParser {
 
         let mut disks = Vec::new();
 
-        for row in range.rows().skip(1) {
+        for (index, row) in range.rows().enumerate().skip(1) {
             if row.is_empty() || self.is_row_empty(row) {
                 continue;
             }
 
-            let disk = self.parse_disk_row(row, header_map)?;
-            disks.push(disk);
+            if let Some(disk) = report.record(self.parse_disk_row(row, header_map, index + 1)) {
+                disks.push(disk);
+            }
         }
 
         Ok(disks)
     }
 
     /// Parse vPartition sheet for partition data
-    fn parse_partition_info(&mut self) -> Result<Vec<RawPartitionData>> {
+    fn parse_partition_info(&mut self, report: &mut ImportReport) -> Result<Vec<RawPartitionData>> {
         let range = self.workbook.worksheet_range("vPartition")
             .ok_or_else(|| CoreEngineError::parsing("vPartition sheet not found".to_string()))?
             .map_err(|e| CoreEngineError::parsing(format!("Failed to read vPartition sheet: {}", e)))?;
@@ -172,31 +180,33 @@ impl RvToolsParser {
 
         let mut partitions = Vec::new();
 
-        for row in range.rows().skip(1) {
+        for (index, row) in range.rows().enumerate().skip(1) {
             if row.is_empty() || self.is_row_empty(row) {
                 continue;
             }
 
-            let partition = self.parse_partition_row(row, header_map)?;
-            partitions.push(partition);
+            if let Some(partition) = report.record(self.parse_partition_row(row, header_map, index + 1)) {
+                partitions.push(partition);
+            }
         }
 
         Ok(partitions)
     }
 
     /// Parse vNetwork sheet for network data
-    fn parse_network_info(&mut self) -> Result<Vec<RawNetworkData>> {
+    fn parse_network_info(&mut self, report: &mut ImportReport) -> Result<Vec<RawNetworkData>> {
         if let Some(Ok(range)) = self.workbook.worksheet_range("vNetwork") {
             if let Some(header_map) = self.header_maps.get("vNetwork") {
                 let mut networks = Vec::new();
 
-                for row in range.rows().skip(1) {
+                for (index, row) in range.rows().enumerate().skip(1) {
                     if row.is_empty() || self.is_row_empty(row) {
                         continue;
                     }
 
-                    let network = self.parse_network_row(row, header_map)?;
-                    networks.push(network);
+                    if let Some(network) = report.record(self.parse_network_row(row, header_map, index + 1)) {
+                        networks.push(network);
+                    }
                 }
 
                 return Ok(networks);
@@ -208,7 +218,12 @@ impl RvToolsParser {
     }
 
     /// Parse individual VM row
-    fn parse_vm_row(&self, row: &[DataType], header_map: &HashMap<String, usize>) -> Result<RawVmData> {
+    fn parse_vm_row(
+        &self,
+        row: &[DataType],
+        header_map: &HashMap<String, usize>,
+        row_number: usize,
+    ) -> std::result::Result<RawVmData, RowError> {
         let get_string = |col_name: &str| -> Option<String> {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
@@ -222,12 +237,6 @@ impl RvToolsParser {
                 .and_then(|cell| cell.get_float())
         };
 
-        let get_int = |col_name: &str| -> Option<i64> {
-            header_map.get(col_name)
-                .and_then(|&idx| row.get(idx))
-                .and_then(|cell| cell.get_int())
-        };
-
         let get_bool = |col_name: &str| -> bool {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
@@ -237,17 +246,21 @@ impl RvToolsParser {
         };
 
         let vm_name = get_string("VM")
-            .ok_or_else(|| CoreEngineError::parsing("Missing VM name"))?;
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| RowError::new("vInfo", row_number, Some("VM"), "Missing VM name"))?;
+        let number = |col_name: &str| numeric_cell(row, header_map, "vInfo", row_number, col_name);
+        let cpus = number("CPUs")?;
+        let memory = number("Memory")?;
 
         Ok(RawVmData {
             name: vm_name,
             cluster: get_string("Cluster"),
             host: get_string("Host"),
             powerstate: get_string("Powerstate"),
-            cpus: get_int("CPUs").unwrap_or(0) as u32,
-            memory: get_float("Memory").unwrap_or(0.0),
-            _provisioned_mb: get_float("Provisioned MB").unwrap_or(0.0),
-            _in_use_mb: get_float("In Use MB").unwrap_or(0.0),
+            cpus: cpus.unwrap_or(0.0) as u32,
+            memory: Bytes::from_mib(memory.unwrap_or(0.0)),
+            _provisioned: Bytes::from_mib(get_float("Provisioned MB").unwrap_or(0.0)),
+            _in_use: Bytes::from_mib(get_float("In Use MB").unwrap_or(0.0)),
             guest_os: get_string("OS"),
             vm_version: get_string("VM Version"),
             tools_status: get_string("Tools Status"),
@@ -261,7 +274,12 @@ impl RvToolsParser {
     }
 
     /// Parse individual host row
-    fn parse_host_row(&self, row: &[DataType], header_map: &HashMap<String, usize>) -> Result<Host> {
+    fn parse_host_row(
+        &self,
+        row: &[DataType],
+        header_map: &HashMap<String, usize>,
+        row_number: usize,
+    ) -> std::result::Result<Host, RowError> {
         let get_string = |col_name: &str| -> Option<String> {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
@@ -272,15 +290,21 @@ impl RvToolsParser {
         let get_int = |col_name: &str| -> Option<i64> {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
-                .and_then(|cell| cell.get_int())
+                // Excel stores every number as a float
+                .and_then(|cell| cell.get_int().or_else(|| cell.get_float().map(|f| f as i64)))
         };
 
         let host_name = get_string("Host")
-            .ok_or_else(|| CoreEngineError::parsing("Missing host name"))?;
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| RowError::new("vHost", row_number, Some("Host"), "Missing host name"))?;
+        for col_name in ["# CPU", "# Cores", "Memory"] {
+            numeric_cell(row, header_map, "vHost", row_number, col_name)?;
+        }
 
         let cpu_model = get_string("CPU Model").unwrap_or_default();
         let num_cores = get_int("# Cores").unwrap_or(0) as u32;
-        let memory_gb = get_int("Memory").map(|mb| (mb / 1024) as u32).unwrap_or(0);
+        // vSphere reports memory in MiB under an "MB" label
+        let memory_gb = get_int("Memory").map(|mib| Bytes::from_mib(mib as f64).gib_floor()).unwrap_or(0);
 
         Ok(Host {
             name: host_name,
@@ -306,7 +330,12 @@ impl RvToolsParser {
     }
 
     /// Parse individual disk row
-    fn parse_disk_row(&self, row: &[DataType], header_map: &HashMap<String, usize>) -> Result<RawDiskData> {
+    fn parse_disk_row(
+        &self,
+        row: &[DataType],
+        header_map: &HashMap<String, usize>,
+        row_number: usize,
+    ) -> std::result::Result<RawDiskData, RowError> {
         let get_string = |col_name: &str| -> Option<String> {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
@@ -314,12 +343,6 @@ impl RvToolsParser {
                 .map(|s| s.trim().to_string())
         };
 
-        let get_float = |col_name: &str| -> Option<f64> {
-            header_map.get(col_name)
-                .and_then(|&idx| row.get(idx))
-                .and_then(|cell| cell.get_float())
-        };
-
         let get_bool = |col_name: &str| -> bool {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
@@ -328,10 +351,15 @@ impl RvToolsParser {
                 .unwrap_or(false)
         };
 
+        let vm_name = get_string("VM")
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| RowError::new("vDisk", row_number, Some("VM"), "Missing VM name"))?;
+        let capacity = numeric_cell(row, header_map, "vDisk", row_number, "Capacity MB")?;
+
         Ok(RawDiskData {
-            vm_name: get_string("VM").unwrap_or_default(),
+            vm_name,
             disk: get_string("Disk").unwrap_or_default(),
-            capacity_mb: get_float("Capacity MB").unwrap_or(0.0),
+            capacity: Bytes::from_mib(capacity.unwrap_or(0.0)),
             _path: get_string("Path"),
             raw: get_bool("Raw"),
             thin: get_bool("Thin"),
@@ -340,7 +368,12 @@ impl RvToolsParser {
     }
 
     /// Parse individual partition row
-    fn parse_partition_row(&self, row: &[DataType], header_map: &HashMap<String, usize>) -> Result<RawPartitionData> {
+    fn parse_partition_row(
+        &self,
+        row: &[DataType],
+        header_map: &HashMap<String, usize>,
+        row_number: usize,
+    ) -> std::result::Result<RawPartitionData, RowError> {
         let get_string = |col_name: &str| -> Option<String> {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
@@ -354,17 +387,26 @@ impl RvToolsParser {
                 .and_then(|cell| cell.get_float())
         };
 
+        let vm_name = get_string("VM")
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| RowError::new("vPartition", row_number, Some("VM"), "Missing VM name"))?;
+
         Ok(RawPartitionData {
-            vm_name: get_string("VM").unwrap_or_default(),
+            vm_name,
             disk: get_string("Disk").unwrap_or_default(),
-            _capacity_mb: get_float("Capacity MB").unwrap_or(0.0),
-            consumed_mb: get_float("Consumed MB").unwrap_or(0.0),
-            _freespace_mb: get_float("Freespace MB").unwrap_or(0.0),
+            _capacity: Bytes::from_mib(get_float("Capacity MB").unwrap_or(0.0)),
+            consumed: Bytes::from_mib(get_float("Consumed MB").unwrap_or(0.0)),
+            _freespace: Bytes::from_mib(get_float("Freespace MB").unwrap_or(0.0)),
         })
     }
 
     /// Parse individual network row
-    fn parse_network_row(&self, row: &[DataType], header_map: &HashMap<String, usize>) -> Result<RawNetworkData> {
+    fn parse_network_row(
+        &self,
+        row: &[DataType],
+        header_map: &HashMap<String, usize>,
+        row_number: usize,
+    ) -> std::result::Result<RawNetworkData, RowError> {
         let get_string = |col_name: &str| -> Option<String> {
             header_map.get(col_name)
                 .and_then(|&idx| row.get(idx))
@@ -380,8 +422,12 @@ impl RvToolsParser {
                 .unwrap_or(false)
         };
 
+        let vm_name = get_string("VM")
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| RowError::new("vNetwork", row_number, Some("VM"), "Missing VM name"))?;
+
         Ok(RawNetworkData {
-            vm_name: get_string("VM").unwrap_or_default(),
+            vm_name,
             network_label: get_string("Network Label").unwrap_or_default(),
             connected: get_bool("Connected"),
             adapter_type: get_string("Adapter Type"),
@@ -408,6 +454,7 @@ impl RvToolsParser {
         disk_data: Vec<RawDiskData>,
         partition_data: Vec<RawPartitionData>,
         network_data: Vec<RawNetworkData>,
+        import_report: ImportReport,
     ) -> Result<VsphereEnvironment> {
         // Group hosts by cluster
         let mut cluster_map: HashMap<String, Vec<Host>> = HashMap::new();
@@ -453,11 +500,12 @@ impl RvToolsParser {
             id: Uuid::new_v4(),
             name: "RVTools Import".to_string(),
             parsed_at: Utc::now(),
-            total_vms: summary_metrics.total_vcpus as usize / 4, // Rough estimate
+            total_vms: clusters.iter().map(|c| c.vms.len()).sum(),
             total_hosts: clusters.iter().map(|c| c.hosts.len()).sum::<usize>() + standalone_hosts.len(),
             clusters,
             standalone_hosts,
             summary_metrics,
+            import_report,
         })
     }
 
@@ -499,13 +547,13 @@ impl RvToolsParser {
 
         // Process disks and merge with partition data
         for disk in disk_data {
-            let consumed_in_guest_gb = if let Some(partitions) = partition_map.get(&disk.vm_name) {
+            let consumed_in_guest: Bytes = if let Some(partitions) = partition_map.get(&disk.vm_name) {
                 partitions.iter()
                     .filter(|p| p.disk == disk.disk)
-                    .map(|p| p.consumed_mb / 1024.0)
+                    .map(|p| p.consumed)
                     .sum()
             } else {
-                disk.capacity_mb / 1024.0 // Fallback to capacity if no partition data
+                disk.capacity // Fallback to capacity if no partition data
             };
 
             let provisioning_type = if disk.thin {
@@ -517,9 +565,9 @@ impl RvToolsParser {
             let virtual_disk = VirtualDisk {
                 vm_name: disk.vm_name.clone(),
                 disk_label: disk.disk,
-                provisioned_gb: disk.capacity_mb / 1024.0,
-                consumed_in_guest_gb,
-                consumed_on_datastore_gb: disk.capacity_mb / 1024.0,
+                provisioned_gb: disk.capacity.as_gib(),
+                consumed_in_guest_gb: consumed_in_guest.as_gib(),
+                consumed_on_datastore_gb: disk.capacity.as_gib(),
                 is_rdm: disk.raw,
                 disk_mode: None,
                 provisioning_type,
@@ -577,7 +625,7 @@ impl RvToolsParser {
             host_name: raw_vm.host.unwrap_or_default(),
             power_state,
             num_vcpu: raw_vm.cpus,
-            memory_gb: (raw_vm.memory / 1024.0) as u32,
+            memory_gb: raw_vm.memory.gib_ceil(),
             guest_os: raw_vm.guest_os,
             vm_version: raw_vm.vm_version,
             tools_status: raw_vm.tools_status,
@@ -758,6 +806,33 @@ impl RvToolsParser {
     }
 }
 
+/// Read an optional numeric cell. A blank cell is `None`; anything that is
+/// not a number rejects the row.
+fn numeric_cell(
+    row: &[DataType],
+    header_map: &HashMap<String, usize>,
+    sheet: &str,
+    row_number: usize,
+    column: &str,
+) -> std::result::Result<Option<f64>, RowError> {
+    let cell = match header_map.get(column).and_then(|&idx| row.get(idx)) {
+        Some(cell) => cell,
+        None => return Ok(None),
+    };
+    let invalid = |found: &dyn std::fmt::Display| {
+        RowError::new(sheet, row_number, Some(column), format!("Expected a number, found \"{}\"", found))
+    };
+
+    match cell {
+        DataType::Empty => Ok(None),
+        DataType::Int(value) => Ok(Some(*value as f64)),
+        DataType::Float(value) => Ok(Some(*value)),
+        DataType::String(text) if text.trim().is_empty() => Ok(None),
+        DataType::String(text) => text.trim().replace(',', "").parse().map(Some).map_err(|_| invalid(&text.trim())),
+        other => Err(invalid(other)),
+    }
+}
+
 // Raw data structures for parsing
 #[derive(Debug)]
 struct RawVmData {
@@ -766,9 +841,9 @@ struct RawVmData {
     host: Option<String>,
     powerstate: Option<String>,
     cpus: u32,
-    memory: f64,
-    _provisioned_mb: f64,
-    _in_use_mb: f64,
+    memory: Bytes,
+    _provisioned: Bytes,
+    _in_use: Bytes,
     guest_os: Option<String>,
     vm_version: Option<String>,
     tools_status: Option<String>,
@@ -784,7 +859,7 @@ struct RawVmData {
 struct RawDiskData {
     vm_name: String,
     disk: String,
-    capacity_mb: f64,
+    capacity: Bytes,
     _path: Option<String>,
     raw: bool,
     thin: bool,
@@ -795,9 +870,9 @@ struct RawDiskData {
 struct RawPartitionData {
     vm_name: String,
     disk: String,
-    _capacity_mb: f64,
-    consumed_mb: f64,
-    _freespace_mb: f64,
+    _capacity: Bytes,
+    consumed: Bytes,
+    _freespace: Bytes,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,222 @@
+//! At-rest encryption for project and hardware pool files.
+//!
+//! Files are sealed with AES-256-GCM under a 32-byte project key. The key
+//! either comes from a passphrase (Argon2id with a per-installation salt) or
+//! is a random key the desktop app keeps in the OS keychain. Encrypted files
+//! are JSON envelopes so plaintext files from older versions can still be
+//! recognised and migrated.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::CoreEngineError;
+
+const ENVELOPE_FORMAT: &str = "archer-encrypted";
+const ENVELOPE_VERSION: u32 = 1;
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+/// Plaintext sealed into the metadata file to check a key before use
+const VERIFIER_PLAINTEXT: &[u8] = b"archer-project-key-check";
+
+/// Symmetric key protecting project files
+#[derive(Clone)]
+pub struct ProjectKey([u8; 32]);
+
+impl std::fmt::Debug for ProjectKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProjectKey(..)")
+    }
+}
+
+impl ProjectKey {
+    /// Random key, for storage in the OS keychain
+    pub fn generate() -> Self {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Self(key)
+    }
+
+    pub fn derive_from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self, CoreEngineError> {
+        if passphrase.is_empty() {
+            return Err(CoreEngineError::validation("Passphrase must not be empty"));
+        }
+        let mut key = [0u8; 32];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| CoreEngineError::config(format!("Failed to derive key from passphrase: {}", e)))?;
+        Ok(Self(key))
+    }
+
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(self.0)
+    }
+
+    pub fn from_base64(encoded: &str) -> Result<Self, CoreEngineError> {
+        let bytes = BASE64
+            .decode(encoded.trim())
+            .map_err(|e| CoreEngineError::config(format!("Invalid project key encoding: {}", e)))?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| CoreEngineError::config("Project key must be 32 bytes"))?;
+        Ok(Self(key))
+    }
+}
+
+/// Where the project key comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    Passphrase,
+    Keychain,
+}
+
+/// Sealed file contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedEnvelope {
+    pub format: String,
+    pub version: u32,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl EncryptedEnvelope {
+    /// Parse file contents as an envelope; `None` for plaintext JSON
+    pub fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str::<EncryptedEnvelope>(content)
+            .ok()
+            .filter(|e| e.format == ENVELOPE_FORMAT)
+    }
+}
+
+/// Stored alongside the project files to describe how they are encrypted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionMetadata {
+    pub key_source: KeySource,
+    /// Base64 Argon2 salt, for passphrase-derived keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    pub verifier: EncryptedEnvelope,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl EncryptionMetadata {
+    /// Metadata for a new passphrase-derived key, returning the derived key
+    pub fn for_passphrase(passphrase: &str) -> Result<(Self, ProjectKey), CoreEngineError> {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = ProjectKey::derive_from_passphrase(passphrase, &salt)?;
+        let metadata = Self {
+            key_source: KeySource::Passphrase,
+            salt: Some(BASE64.encode(salt)),
+            verifier: encrypt(&key, VERIFIER_PLAINTEXT)?,
+            created_at: chrono::Utc::now(),
+        };
+        Ok((metadata, key))
+    }
+
+    pub fn for_key(key: &ProjectKey, key_source: KeySource) -> Result<Self, CoreEngineError> {
+        Ok(Self {
+            key_source,
+            salt: None,
+            verifier: encrypt(key, VERIFIER_PLAINTEXT)?,
+            created_at: chrono::Utc::now(),
+        })
+    }
+
+    /// Re-derive the key from a passphrase and check it against the verifier
+    pub fn unlock_with_passphrase(&self, passphrase: &str) -> Result<ProjectKey, CoreEngineError> {
+        let salt = self
+            .salt
+            .as_deref()
+            .ok_or_else(|| CoreEngineError::config("Project files are not passphrase-protected"))?;
+        let salt = BASE64
+            .decode(salt)
+            .map_err(|e| CoreEngineError::config(format!("Invalid key salt: {}", e)))?;
+        let key = ProjectKey::derive_from_passphrase(passphrase, &salt)?;
+        self.verify(&key)?;
+        Ok(key)
+    }
+
+    pub fn verify(&self, key: &ProjectKey) -> Result<(), CoreEngineError> {
+        match decrypt(key, &self.verifier) {
+            Ok(plaintext) if plaintext == VERIFIER_PLAINTEXT => Ok(()),
+            _ => Err(CoreEngineError::authentication("Incorrect passphrase or project key")),
+        }
+    }
+}
+
+pub fn encrypt(key: &ProjectKey, plaintext: &[u8]) -> Result<EncryptedEnvelope, CoreEngineError> {
+    let cipher = Aes256Gcm::new_from_slice(&key.0)
+        .map_err(|e| CoreEngineError::config(format!("Invalid project key: {}", e)))?;
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| CoreEngineError::config("Failed to encrypt project data"))?;
+
+    Ok(EncryptedEnvelope {
+        format: ENVELOPE_FORMAT.to_string(),
+        version: ENVELOPE_VERSION,
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+pub fn decrypt(key: &ProjectKey, envelope: &EncryptedEnvelope) -> Result<Vec<u8>, CoreEngineError> {
+    if envelope.version != ENVELOPE_VERSION {
+        return Err(CoreEngineError::parsing(format!(
+            "Unsupported encrypted file version {}",
+            envelope.version
+        )));
+    }
+    let nonce = BASE64
+        .decode(&envelope.nonce)
+        .map_err(|e| CoreEngineError::parsing(format!("Invalid nonce encoding: {}", e)))?;
+    if nonce.len() != NONCE_LEN {
+        return Err(CoreEngineError::parsing("Invalid nonce length"));
+    }
+    let ciphertext = BASE64
+        .decode(&envelope.ciphertext)
+        .map_err(|e| CoreEngineError::parsing(format!("Invalid ciphertext encoding: {}", e)))?;
+
+    let cipher = Aes256Gcm::new_from_slice(&key.0)
+        .map_err(|e| CoreEngineError::config(format!("Invalid project key: {}", e)))?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| CoreEngineError::authentication("Failed to decrypt project data: wrong key or corrupted file"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_tamper_detection() {
+        let key = ProjectKey::generate();
+        let mut envelope = encrypt(&key, b"{\"name\":\"Customer A\"}").unwrap();
+        assert_eq!(decrypt(&key, &envelope).unwrap(), b"{\"name\":\"Customer A\"}");
+        assert!(decrypt(&ProjectKey::generate(), &envelope).is_err());
+
+        envelope.ciphertext = BASE64.encode(b"tampered");
+        assert!(decrypt(&key, &envelope).is_err());
+    }
+
+    #[test]
+    fn test_passphrase_metadata_verifies_key() {
+        let (metadata, key) = EncryptionMetadata::for_passphrase("correct horse").unwrap();
+        let unlocked = metadata.unlock_with_passphrase("correct horse").unwrap();
+        assert_eq!(unlocked.to_base64(), key.to_base64());
+        assert!(metadata.unlock_with_passphrase("wrong").is_err());
+    }
+
+    #[test]
+    fn test_envelope_detection() {
+        let envelope = encrypt(&ProjectKey::generate(), b"{}").unwrap();
+        let content = serde_json::to_string(&envelope).unwrap();
+        assert!(EncryptedEnvelope::parse(&content).is_some());
+        assert!(EncryptedEnvelope::parse("{\"id\":\"abc\",\"name\":\"plain\"}").is_none());
+    }
+}
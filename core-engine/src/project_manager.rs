@@ -1,4 +1,6 @@
 use crate::models::project::{Project, HardwarePool};
+use crate::project_crypto::{self, EncryptedEnvelope, EncryptionMetadata, KeySource, ProjectKey};
+use crate::vendor_data::VendorCredentials;
 use crate::CoreEngineError;
 use std::collections::HashMap;
 use std::fs;
@@ -7,6 +9,18 @@ use std::path::{Path, PathBuf};
 pub struct ProjectManager {
     projects_dir: PathBuf,
     hardware_pool_file: PathBuf,
+    encryption_file: PathBuf,
+    vendor_credentials_file: PathBuf,
+    /// Key for encrypted files; `None` while locked or when encryption is off
+    key: Option<ProjectKey>,
+}
+
+/// Encryption state reported to the UI
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub key_source: Option<KeySource>,
+    pub unlocked: bool,
 }
 
 impl ProjectManager {
@@ -26,16 +40,150 @@ impl ProjectManager {
         })?;
 
         let hardware_pool_file = config_dir.join("hardware_pool.json");
+        let encryption_file = config_dir.join("encryption.json");
+        let vendor_credentials_file = config_dir.join("vendor_credentials.json");
 
         Ok(Self {
             projects_dir,
             hardware_pool_file,
+            encryption_file,
+            vendor_credentials_file,
+            key: None,
         })
     }
 
-    /// Loads all projects from the projects directory.
-    pub fn load_projects(&self) -> Result<HashMap<String, Project>, CoreEngineError> {
-        let mut projects = HashMap::new();
+    // ========== ENCRYPTION ==========
+
+    /// Reads the encryption metadata, if project encryption has been enabled.
+    pub fn encryption_metadata(&self) -> Result<Option<EncryptionMetadata>, CoreEngineError> {
+        if !self.encryption_file.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&self.encryption_file)
+            .map_err(|e| CoreEngineError::io(format!("Failed to read encryption metadata: {}", e)))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| CoreEngineError::parsing(format!("Failed to parse encryption metadata: {}", e)))
+    }
+
+    pub fn encryption_status(&self) -> Result<EncryptionStatus, CoreEngineError> {
+        let metadata = self.encryption_metadata()?;
+        Ok(EncryptionStatus {
+            enabled: metadata.is_some(),
+            key_source: metadata.map(|m| m.key_source),
+            unlocked: self.key.is_some(),
+        })
+    }
+
+    /// Turns on encryption with a passphrase-derived key and encrypts existing files.
+    ///
+    /// Returns the number of plaintext files migrated.
+    pub fn enable_encryption_with_passphrase(&mut self, passphrase: &str) -> Result<usize, CoreEngineError> {
+        let (metadata, key) = EncryptionMetadata::for_passphrase(passphrase)?;
+        self.enable_encryption(metadata, key)
+    }
+
+    /// Turns on encryption with a caller-held key (e.g. one kept in the OS keychain).
+    pub fn enable_encryption_with_key(&mut self, key: ProjectKey, key_source: KeySource) -> Result<usize, CoreEngineError> {
+        let metadata = EncryptionMetadata::for_key(&key, key_source)?;
+        self.enable_encryption(metadata, key)
+    }
+
+    fn enable_encryption(&mut self, metadata: EncryptionMetadata, key: ProjectKey) -> Result<usize, CoreEngineError> {
+        if self.encryption_metadata()?.is_some() {
+            return Err(CoreEngineError::validation("Project encryption is already enabled"));
+        }
+        let content = serde_json::to_string_pretty(&metadata)
+            .map_err(|e| CoreEngineError::serialization(format!("Failed to serialize encryption metadata: {}", e)))?;
+        fs::write(&self.encryption_file, content)
+            .map_err(|e| CoreEngineError::io(format!("Failed to write encryption metadata: {}", e)))?;
+
+        self.key = Some(key);
+        self.migrate_plaintext_files()
+    }
+
+    /// Unlocks passphrase-protected project files for this session.
+    pub fn unlock_with_passphrase(&mut self, passphrase: &str) -> Result<(), CoreEngineError> {
+        let metadata = self
+            .encryption_metadata()?
+            .ok_or_else(|| CoreEngineError::validation("Project encryption is not enabled"))?;
+        self.key = Some(metadata.unlock_with_passphrase(passphrase)?);
+        Ok(())
+    }
+
+    /// Unlocks project files with a key retrieved from the OS keychain.
+    pub fn unlock_with_key(&mut self, key: ProjectKey) -> Result<(), CoreEngineError> {
+        let metadata = self
+            .encryption_metadata()?
+            .ok_or_else(|| CoreEngineError::validation("Project encryption is not enabled"))?;
+        metadata.verify(&key)?;
+        self.key = Some(key);
+        Ok(())
+    }
+
+    /// Re-writes any plaintext project or hardware pool files in encrypted form.
+    ///
+    /// Covers projects saved before encryption was enabled. Requires an unlocked key.
+    pub fn migrate_plaintext_files(&self) -> Result<usize, CoreEngineError> {
+        if self.key.is_none() {
+            return Err(CoreEngineError::authentication("Project files are locked"));
+        }
+
+        let mut migrated = 0;
+        let mut files = self.project_files()?;
+        if self.hardware_pool_file.exists() {
+            files.push(self.hardware_pool_file.clone());
+        }
+        for path in files {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| CoreEngineError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+            if EncryptedEnvelope::parse(&content).is_none() {
+                self.write_file(&path, &content)?;
+                migrated += 1;
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Reads a file, decrypting it when it is an encrypted envelope.
+    fn read_file(&self, path: &Path) -> Result<String, CoreEngineError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| CoreEngineError::io(format!("Failed to read file at {}: {}", path.display(), e)))?;
+
+        match EncryptedEnvelope::parse(&content) {
+            Some(envelope) => {
+                let key = self
+                    .key
+                    .as_ref()
+                    .ok_or_else(|| CoreEngineError::authentication("Project files are encrypted; unlock them first"))?;
+                let plaintext = project_crypto::decrypt(key, &envelope)?;
+                String::from_utf8(plaintext)
+                    .map_err(|e| CoreEngineError::parsing(format!("Decrypted {} is not valid UTF-8: {}", path.display(), e)))
+            }
+            None => Ok(content),
+        }
+    }
+
+    /// Writes a file, encrypting it when encryption is enabled.
+    fn write_file(&self, path: &Path, content: &str) -> Result<(), CoreEngineError> {
+        let output = match &self.key {
+            Some(key) => {
+                let envelope = project_crypto::encrypt(key, content.as_bytes())?;
+                serde_json::to_string_pretty(&envelope)
+                    .map_err(|e| CoreEngineError::serialization(format!("Failed to serialize encrypted file: {}", e)))?
+            }
+            None if self.encryption_file.exists() => {
+                // Never fall back to plaintext once encryption is on
+                return Err(CoreEngineError::authentication("Project files are encrypted; unlock them first"));
+            }
+            None => content.to_string(),
+        };
+
+        fs::write(path, output)
+            .map_err(|e| CoreEngineError::io(format!("Failed to write file {}: {}", path.display(), e)))
+    }
+
+    fn project_files(&self) -> Result<Vec<PathBuf>, CoreEngineError> {
         let entries = fs::read_dir(&self.projects_dir).map_err(|e| {
             CoreEngineError::io(format!(
                 "Failed to read projects directory at {}: {}",
@@ -44,19 +192,31 @@ impl ProjectManager {
             ))
         })?;
 
+        let mut files = Vec::new();
         for entry in entries {
             let entry = entry.map_err(|e| CoreEngineError::io(format!("Failed to read directory entry: {}", e)))?;
             let path = entry.path();
             if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
-                let file_content = fs::read_to_string(&path)
-                    .map_err(|e| CoreEngineError::io(format!("Failed to read project file at {}: {}", path.display(), e)))?;
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
 
-                let project: Project = serde_json::from_str(&file_content)
-                    .map_err(|e| CoreEngineError::parsing(format!("Failed to parse project file {}: {}", path.display(), e)))?;
+    // ========== PROJECTS ==========
 
-                if let Some(id) = &project.id {
-                    projects.insert(id.to_string(), project);
-                }
+    /// Loads all projects from the projects directory, decrypting as needed.
+    pub fn load_projects(&self) -> Result<HashMap<String, Project>, CoreEngineError> {
+        let mut projects = HashMap::new();
+
+        for path in self.project_files()? {
+            let file_content = self.read_file(&path)?;
+
+            let project: Project = serde_json::from_str(&file_content)
+                .map_err(|e| CoreEngineError::parsing(format!("Failed to parse project file {}: {}", path.display(), e)))?;
+
+            if let Some(id) = &project.id {
+                projects.insert(id.to_string(), project);
             }
         }
         Ok(projects)
@@ -73,8 +233,7 @@ impl ProjectManager {
         let file_content = serde_json::to_string_pretty(project)
             .map_err(|e| CoreEngineError::serialization(format!("Failed to serialize project {}: {}", id_str, e)))?;
 
-        fs::write(&project_file, file_content)
-            .map_err(|e| CoreEngineError::io(format!("Failed to write project file {}: {}", project_file.display(), e)))
+        self.write_file(&project_file, &file_content)
     }
 
     /// Deletes a project's file.
@@ -91,13 +250,7 @@ impl ProjectManager {
     /// Loads the hardware pool from its file.
     pub fn load_hardware_pool(&self) -> Result<HardwarePool, CoreEngineError> {
         if self.hardware_pool_file.exists() {
-            let file_content = fs::read_to_string(&self.hardware_pool_file).map_err(|e| {
-                CoreEngineError::io(format!(
-                    "Failed to read hardware pool file at {}: {}",
-                    self.hardware_pool_file.display(),
-                    e
-                ))
-            })?;
+            let file_content = self.read_file(&self.hardware_pool_file)?;
             serde_json::from_str(&file_content)
                 .map_err(|e| CoreEngineError::parsing(format!("Failed to parse hardware pool file: {}", e)))
         } else {
@@ -111,7 +264,89 @@ impl ProjectManager {
         let file_content = serde_json::to_string_pretty(hardware_pool)
             .map_err(|e| CoreEngineError::serialization(format!("Failed to serialize hardware pool: {}", e)))?;
 
-        fs::write(&self.hardware_pool_file, file_content)
-            .map_err(|e| CoreEngineError::io(format!("Failed to write hardware pool file {}: {}", self.hardware_pool_file.display(), e)))
+        self.write_file(&self.hardware_pool_file, &file_content)
+    }
+
+    // ========== VENDOR CREDENTIALS ==========
+
+    /// Loads stored vendor API credentials, keyed by vendor.
+    pub fn load_vendor_credentials(&self) -> Result<HashMap<String, VendorCredentials>, CoreEngineError> {
+        if !self.vendor_credentials_file.exists() {
+            return Ok(HashMap::new());
+        }
+        let file_content = self.read_file(&self.vendor_credentials_file)?;
+        serde_json::from_str(&file_content)
+            .map_err(|e| CoreEngineError::parsing(format!("Failed to parse vendor credentials: {}", e)))
+    }
+
+    /// Saves vendor API credentials.
+    ///
+    /// Unlike projects, credentials are never written in plaintext, so project
+    /// encryption must be enabled and unlocked first.
+    pub fn save_vendor_credentials(&self, credentials: &HashMap<String, VendorCredentials>) -> Result<(), CoreEngineError> {
+        if self.key.is_none() {
+            return Err(CoreEngineError::authentication(
+                "Vendor credentials are only stored encrypted; enable and unlock project encryption first",
+            ));
+        }
+        let file_content = serde_json::to_string_pretty(credentials)
+            .map_err(|e| CoreEngineError::serialization(format!("Failed to serialize vendor credentials: {}", e)))?;
+
+        self.write_file(&self.vendor_credentials_file, &file_content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plaintext_files_migrate_and_require_unlock() {
+        let dir = std::env::temp_dir().join(format!("archer-pm-{}", uuid::Uuid::new_v4()));
+        let mut manager = ProjectManager::new(&dir).unwrap();
+        manager.save_hardware_pool(&HardwarePool::default()).unwrap();
+
+        assert_eq!(manager.enable_encryption_with_passphrase("s3cret").unwrap(), 1);
+        let on_disk = fs::read_to_string(dir.join("hardware_pool.json")).unwrap();
+        assert!(EncryptedEnvelope::parse(&on_disk).is_some());
+
+        let mut reopened = ProjectManager::new(&dir).unwrap();
+        assert!(reopened.load_hardware_pool().is_err());
+        assert!(reopened.save_hardware_pool(&HardwarePool::default()).is_err());
+        assert!(reopened.unlock_with_passphrase("wrong").is_err());
+        reopened.unlock_with_passphrase("s3cret").unwrap();
+        assert!(reopened.load_hardware_pool().is_ok());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_vendor_credentials_are_never_plaintext() {
+        let dir = std::env::temp_dir().join(format!("archer-pm-{}", uuid::Uuid::new_v4()));
+        let mut manager = ProjectManager::new(&dir).unwrap();
+        let mut credentials = HashMap::new();
+        credentials.insert("Dell".to_string(), VendorCredentials {
+            vendor: "Dell".to_string(),
+            client_id: Some("archer".to_string()),
+            client_secret: Some("top-secret".to_string()),
+            ..VendorCredentials::default()
+        });
+
+        assert!(manager.save_vendor_credentials(&credentials).is_err());
+        assert!(manager.load_vendor_credentials().unwrap().is_empty());
+
+        manager.enable_encryption_with_passphrase("s3cret").unwrap();
+        manager.save_vendor_credentials(&credentials).unwrap();
+        let on_disk = fs::read_to_string(dir.join("vendor_credentials.json")).unwrap();
+        assert!(EncryptedEnvelope::parse(&on_disk).is_some());
+        assert!(!on_disk.contains("top-secret"));
+
+        let mut reopened = ProjectManager::new(&dir).unwrap();
+        assert!(reopened.load_vendor_credentials().is_err());
+        reopened.unlock_with_passphrase("s3cret").unwrap();
+        let loaded = reopened.load_vendor_credentials().unwrap();
+        assert_eq!(loaded["Dell"].client_secret.as_deref(), Some("top-secret"));
+
+        fs::remove_dir_all(&dir).ok();
     }
 }
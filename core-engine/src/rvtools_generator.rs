@@ -0,0 +1,486 @@
+//! Synthetic RVTools workbooks for performance tests and demos
+//!
+//! Builds an RVTools-like export of a made-up vSphere estate at any scale,
+//! so parser benchmarks, sizing tests and sales demos never need customer
+//! data. Generation is seeded: the same configuration and seed always give
+//! the same workbook. Distributions (VM shapes, power states, guest OS mix,
+//! disk fill, host models) follow what typical enterprise estates look like
+//! rather than being uniform.
+//!
+//! Only the sheets and columns read by `parser::RvToolsParser` are written:
+//! vInfo, vHost, vDisk, vPartition and vNetwork.
+
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_xlsxwriter::{Workbook, Worksheet, XlsxError};
+use serde::{Deserialize, Serialize};
+
+use crate::error::CoreEngineError;
+use crate::units::Bytes;
+use crate::Result;
+
+/// Scale and seed of a synthetic estate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    pub clusters: u32,
+    /// Hosts in total, spread as evenly as possible over the clusters
+    pub hosts: u32,
+    /// VMs in total, spread over the clusters in proportion to their hosts
+    pub vms: u32,
+    /// Prefix of every generated name, so fixtures are easy to recognise
+    pub name_prefix: String,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            clusters: 3,
+            hosts: 12,
+            vms: 400,
+            name_prefix: "syn".to_string(),
+        }
+    }
+}
+
+impl GeneratorConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.clusters == 0 {
+            return Err(CoreEngineError::validation("At least one cluster is required"));
+        }
+        if self.hosts < self.clusters {
+            return Err(CoreEngineError::validation(format!(
+                "{} hosts cannot populate {} clusters",
+                self.hosts, self.clusters
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntheticHost {
+    pub name: String,
+    pub cluster: String,
+    pub vendor: String,
+    pub model: String,
+    pub cpu_model: String,
+    pub sockets: u32,
+    pub cores: u32,
+    pub memory_mb: u64,
+    pub esx_version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntheticDisk {
+    pub label: String,
+    pub capacity_mb: u64,
+    pub consumed_mb: u64,
+    pub thin: bool,
+    pub raw: bool,
+    pub datastore: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntheticNic {
+    pub network: String,
+    pub adapter: String,
+    pub mac: String,
+    pub connected: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntheticVm {
+    pub name: String,
+    pub cluster: String,
+    pub host: String,
+    /// RVTools spelling: poweredOn, poweredOff, suspended
+    pub power_state: String,
+    pub template: bool,
+    pub cpus: u32,
+    pub memory_mb: u64,
+    pub guest_os: String,
+    pub vm_version: String,
+    pub tools_status: String,
+    pub folder: String,
+    pub annotation: String,
+    pub disks: Vec<SyntheticDisk>,
+    pub nics: Vec<SyntheticNic>,
+}
+
+impl SyntheticVm {
+    pub fn provisioned_mb(&self) -> u64 {
+        self.disks.iter().map(|d| d.capacity_mb).sum::<u64>() + self.memory_mb
+    }
+
+    pub fn in_use_mb(&self) -> u64 {
+        self.disks.iter().map(|d| if d.thin { d.consumed_mb } else { d.capacity_mb }).sum()
+    }
+}
+
+/// A generated estate, ready to be written as a workbook
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntheticEnvironment {
+    pub clusters: Vec<String>,
+    pub hosts: Vec<SyntheticHost>,
+    pub vms: Vec<SyntheticVm>,
+}
+
+/// (value, weight) tables; weights need not sum to 100
+const VCPU_SHAPES: &[(u32, u32)] = &[(1, 10), (2, 35), (4, 30), (8, 15), (12, 3), (16, 5), (24, 1), (32, 1)];
+const GB_PER_VCPU: &[(u64, u32)] = &[(2, 25), (4, 50), (8, 20), (16, 5)];
+const DISK_GB: &[(u64, u32)] = &[(40, 15), (60, 20), (100, 25), (200, 15), (500, 12), (1024, 8), (2048, 4), (4096, 1)];
+const DISK_COUNT: &[(usize, u32)] = &[(1, 40), (2, 35), (3, 15), (4, 10)];
+const POWER_STATES: &[(&str, u32)] = &[("poweredOn", 88), ("poweredOff", 10), ("suspended", 2)];
+const GUEST_OS: &[(&str, u32)] = &[
+    ("Microsoft Windows Server 2019 (64-bit)", 28),
+    ("Microsoft Windows Server 2022 (64-bit)", 18),
+    ("Microsoft Windows Server 2016 (64-bit)", 12),
+    ("Microsoft Windows Server 2012 R2 (64-bit)", 4),
+    ("Red Hat Enterprise Linux 8 (64-bit)", 14),
+    ("Red Hat Enterprise Linux 9 (64-bit)", 6),
+    ("Ubuntu Linux (64-bit)", 9),
+    ("SUSE Linux Enterprise 15 (64-bit)", 4),
+    ("CentOS 7 (64-bit)", 3),
+    ("Other Linux (64-bit)", 2),
+];
+const TOOLS_STATUS: &[(&str, u32)] = &[
+    ("toolsOk", 80),
+    ("toolsOld (out of date)", 14),
+    ("toolsNotRunning", 4),
+    ("toolsNotInstalled (not installed)", 2),
+];
+const VM_VERSIONS: &[(&str, u32)] = &[("vmx-19", 45), ("vmx-17", 25), ("vmx-15", 20), ("vmx-13", 10)];
+const ROLES: &[(&str, u32)] = &[
+    ("app", 30),
+    ("web", 20),
+    ("db", 10),
+    ("file", 8),
+    ("dc", 3),
+    ("sql", 6),
+    ("mgmt", 8),
+    ("batch", 10),
+    ("test", 5),
+];
+
+/// (vendor, model, cpu model, cores per socket, memory GB)
+const HOST_MODELS: &[(&str, &str, &str, u32, u64)] = &[
+    ("Dell Inc.", "PowerEdge R650", "Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz", 32, 1024),
+    ("Dell Inc.", "PowerEdge R750", "Intel(R) Xeon(R) Gold 6354 CPU @ 3.00GHz", 18, 768),
+    ("HPE", "ProLiant DL380 Gen10 Plus", "Intel(R) Xeon(R) Gold 6330 CPU @ 2.00GHz", 28, 1024),
+    ("Lenovo", "ThinkSystem SR650 V2", "Intel(R) Xeon(R) Gold 5318Y CPU @ 2.10GHz", 24, 768),
+    ("Cisco Systems Inc", "UCSC-C240-M6SX", "Intel(R) Xeon(R) Gold 6348 CPU @ 2.60GHz", 28, 1536),
+];
+const ESX_VERSIONS: &[(&str, u32)] = &[
+    ("VMware ESXi 7.0.3 build-21686933", 50),
+    ("VMware ESXi 8.0.2 build-22380479", 35),
+    ("VMware ESXi 6.7.0 build-17700523", 15),
+];
+
+fn pick<T: Copy>(rng: &mut StdRng, table: &[(T, u32)]) -> T {
+    let total: u32 = table.iter().map(|(_, w)| w).sum();
+    let mut roll = rng.gen_range(0..total);
+    for (value, weight) in table {
+        if roll < *weight {
+            return *value;
+        }
+        roll -= weight;
+    }
+    table[table.len() - 1].0
+}
+
+/// Build the estate described by `config`
+pub fn generate(config: &GeneratorConfig) -> Result<SyntheticEnvironment> {
+    config.validate()?;
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let prefix = &config.name_prefix;
+
+    let clusters: Vec<String> = (1..=config.clusters).map(|c| format!("{}-cl{:02}", prefix, c)).collect();
+
+    // Clusters are homogeneous, as they usually are in practice
+    let mut hosts = Vec::with_capacity(config.hosts as usize);
+    let mut hosts_per_cluster = Vec::with_capacity(clusters.len());
+    for (index, cluster) in clusters.iter().enumerate() {
+        let count = config.hosts / config.clusters + u32::from((index as u32) < config.hosts % config.clusters);
+        hosts_per_cluster.push(count);
+        let (vendor, model, cpu_model, cores_per_socket, memory_gb) =
+            HOST_MODELS[rng.gen_range(0..HOST_MODELS.len())];
+        let esx_version = pick(&mut rng, ESX_VERSIONS);
+        for h in 1..=count {
+            hosts.push(SyntheticHost {
+                name: format!("{}-esx{:02}.{}.lab.invalid", cluster, h, prefix),
+                cluster: cluster.clone(),
+                vendor: vendor.to_string(),
+                model: model.to_string(),
+                cpu_model: cpu_model.to_string(),
+                sockets: 2,
+                cores: cores_per_socket * 2,
+                memory_mb: Bytes::from_gib(memory_gb as f64).as_mib() as u64,
+                esx_version: esx_version.to_string(),
+            });
+        }
+    }
+
+    // VMs go to clusters in proportion to their hosts, round-robin over hosts
+    let mut vms = Vec::with_capacity(config.vms as usize);
+    let mut host_offset = 0usize;
+    let mut assigned = 0u32;
+    for (index, cluster) in clusters.iter().enumerate() {
+        let cluster_hosts = hosts_per_cluster[index] as usize;
+        let count = if index + 1 == clusters.len() {
+            config.vms - assigned
+        } else {
+            (config.vms as u64 * cluster_hosts as u64 / config.hosts as u64) as u32
+        };
+        assigned += count;
+
+        for n in 0..count {
+            let host = &hosts[host_offset + (n as usize % cluster_hosts)];
+            let vm_number = vms.len() + 1;
+            vms.push(generate_vm(&mut rng, prefix, vm_number, cluster, &host.name));
+        }
+        host_offset += cluster_hosts;
+    }
+
+    Ok(SyntheticEnvironment { clusters, hosts, vms })
+}
+
+fn generate_vm(rng: &mut StdRng, prefix: &str, number: usize, cluster: &str, host: &str) -> SyntheticVm {
+    let role = pick(rng, ROLES);
+    let template = rng.gen_bool(0.02);
+    let power_state = if template { "poweredOff" } else { pick(rng, POWER_STATES) };
+    let cpus = pick(rng, VCPU_SHAPES);
+    let memory_gb = (cpus as u64 * pick(rng, GB_PER_VCPU)).clamp(2, 512);
+    let memory_mb = Bytes::from_gib(memory_gb as f64).as_mib() as u64;
+    let datastore = format!("{}-ds{:02}", cluster, rng.gen_range(1..=4));
+
+    let disks = (1..=pick(rng, DISK_COUNT))
+        .map(|d| {
+            let capacity_mb = Bytes::from_gib(pick(rng, DISK_GB) as f64).as_mib() as u64;
+            let fill: f64 = rng.gen_range(0.15..0.9);
+            SyntheticDisk {
+                label: format!("Hard disk {}", d),
+                capacity_mb,
+                consumed_mb: (capacity_mb as f64 * fill) as u64,
+                thin: rng.gen_bool(0.7),
+                // Clustered databases and file servers are where RDMs linger
+                raw: d > 1 && matches!(role, "db" | "sql" | "file") && rng.gen_bool(0.1),
+                datastore: datastore.clone(),
+            }
+        })
+        .collect();
+
+    let nic_count = if rng.gen_bool(0.85) { 1 } else { 2 };
+    let nics = (0..nic_count)
+        .map(|_| SyntheticNic {
+            network: format!("vlan{}-{}", rng.gen_range(100..140), role),
+            adapter: if rng.gen_bool(0.92) { "Vmxnet3" } else { "E1000E" }.to_string(),
+            mac: format!(
+                "00:50:56:{:02x}:{:02x}:{:02x}",
+                rng.gen_range(0..0x40u8),
+                rng.gen::<u8>(),
+                rng.gen::<u8>()
+            ),
+            connected: power_state == "poweredOn",
+        })
+        .collect();
+
+    SyntheticVm {
+        name: format!("{}-{}{:05}", prefix, role, number),
+        cluster: cluster.to_string(),
+        host: host.to_string(),
+        power_state: power_state.to_string(),
+        template,
+        cpus,
+        memory_mb,
+        guest_os: pick(rng, GUEST_OS).to_string(),
+        vm_version: pick(rng, VM_VERSIONS).to_string(),
+        tools_status: pick(rng, TOOLS_STATUS).to_string(),
+        folder: format!("/{}/{}", cluster, role),
+        annotation: if matches!(role, "db" | "sql") && rng.gen_bool(0.3) {
+            "Critical: production database".to_string()
+        } else {
+            String::new()
+        },
+        disks,
+        nics,
+    }
+}
+
+impl SyntheticEnvironment {
+    /// Write the estate as an RVTools-style `.xlsx` workbook
+    pub fn write_xlsx(&self, path: &Path) -> Result<()> {
+        self.build_workbook()
+            .and_then(|mut workbook| workbook.save(path))
+            .map_err(|e| CoreEngineError::Io(format!("Failed to write synthetic RVTools workbook: {}", e)))
+    }
+
+    fn build_workbook(&self) -> std::result::Result<Workbook, XlsxError> {
+        let mut workbook = Workbook::new();
+
+        let sheet = workbook.add_worksheet().set_name("vInfo")?;
+        write_header(
+            sheet,
+            &[
+                "VM", "Powerstate", "Template", "CPUs", "Memory", "Provisioned MB", "In Use MB", "OS",
+                "VM Version", "Tools Status", "Tools Version", "Annotation", "Notes", "Folder", "Resource Pool",
+                "Cluster", "Host",
+            ],
+        )?;
+        for (i, vm) in self.vms.iter().enumerate() {
+            let row = i as u32 + 1;
+            sheet.write_string(row, 0, &vm.name)?;
+            sheet.write_string(row, 1, &vm.power_state)?;
+            sheet.write_string(row, 2, bool_text(vm.template))?;
+            sheet.write_number(row, 3, vm.cpus as f64)?;
+            sheet.write_number(row, 4, vm.memory_mb as f64)?;
+            sheet.write_number(row, 5, vm.provisioned_mb() as f64)?;
+            sheet.write_number(row, 6, vm.in_use_mb() as f64)?;
+            sheet.write_string(row, 7, &vm.guest_os)?;
+            sheet.write_string(row, 8, &vm.vm_version)?;
+            sheet.write_string(row, 9, &vm.tools_status)?;
+            sheet.write_string(row, 10, "12352")?;
+            sheet.write_string(row, 11, &vm.annotation)?;
+            sheet.write_string(row, 12, "")?;
+            sheet.write_string(row, 13, &vm.folder)?;
+            sheet.write_string(row, 14, &format!("/{}/Resources", vm.cluster))?;
+            sheet.write_string(row, 15, &vm.cluster)?;
+            sheet.write_string(row, 16, &vm.host)?;
+        }
+
+        let sheet = workbook.add_worksheet().set_name("vHost")?;
+        write_header(
+            sheet,
+            &[
+                "Host", "Cluster", "CPU Model", "# CPU", "# Cores", "Memory", "ESX Version", "Vendor", "Model",
+                "Connection State", "Power State", "# VMs",
+            ],
+        )?;
+        for (i, host) in self.hosts.iter().enumerate() {
+            let row = i as u32 + 1;
+            let vm_count = self.vms.iter().filter(|vm| vm.host == host.name).count();
+            sheet.write_string(row, 0, &host.name)?;
+            sheet.write_string(row, 1, &host.cluster)?;
+            sheet.write_string(row, 2, &host.cpu_model)?;
+            sheet.write_number(row, 3, host.sockets as f64)?;
+            sheet.write_number(row, 4, host.cores as f64)?;
+            sheet.write_number(row, 5, host.memory_mb as f64)?;
+            sheet.write_string(row, 6, &host.esx_version)?;
+            sheet.write_string(row, 7, &host.vendor)?;
+            sheet.write_string(row, 8, &host.model)?;
+            sheet.write_string(row, 9, "connected")?;
+            sheet.write_string(row, 10, "poweredOn")?;
+            sheet.write_number(row, 11, vm_count as f64)?;
+        }
+
+        let sheet = workbook.add_worksheet().set_name("vDisk")?;
+        write_header(sheet, &["VM", "Disk", "Capacity MB", "Path", "Raw", "Thin", "Datastore"])?;
+        let mut row = 1;
+        for vm in &self.vms {
+            for (d, disk) in vm.disks.iter().enumerate() {
+                let path = if d == 0 {
+                    format!("[{}] {}/{}.vmdk", disk.datastore, vm.name, vm.name)
+                } else {
+                    format!("[{}] {}/{}_{}.vmdk", disk.datastore, vm.name, vm.name, d)
+                };
+                sheet.write_string(row, 0, &vm.name)?;
+                sheet.write_string(row, 1, &disk.label)?;
+                sheet.write_number(row, 2, disk.capacity_mb as f64)?;
+                sheet.write_string(row, 3, &path)?;
+                sheet.write_string(row, 4, bool_text(disk.raw))?;
+                sheet.write_string(row, 5, bool_text(disk.thin))?;
+                sheet.write_string(row, 6, &disk.datastore)?;
+                row += 1;
+            }
+        }
+
+        let sheet = workbook.add_worksheet().set_name("vPartition")?;
+        write_header(sheet, &["VM", "Disk", "Capacity MB", "Consumed MB", "Freespace MB"])?;
+        let mut row = 1;
+        for vm in self.vms.iter().filter(|vm| vm.power_state == "poweredOn") {
+            // Guests do not report partitions for RDMs
+            for disk in vm.disks.iter().filter(|d| !d.raw) {
+                sheet.write_string(row, 0, &vm.name)?;
+                sheet.write_string(row, 1, &disk.label)?;
+                sheet.write_number(row, 2, disk.capacity_mb as f64)?;
+                sheet.write_number(row, 3, disk.consumed_mb as f64)?;
+                sheet.write_number(row, 4, (disk.capacity_mb - disk.consumed_mb) as f64)?;
+                row += 1;
+            }
+        }
+
+        let sheet = workbook.add_worksheet().set_name("vNetwork")?;
+        write_header(sheet, &["VM", "Network Label", "Adapter Type", "MAC Address", "Connected"])?;
+        let mut row = 1;
+        for vm in &self.vms {
+            for nic in &vm.nics {
+                sheet.write_string(row, 0, &vm.name)?;
+                sheet.write_string(row, 1, &nic.network)?;
+                sheet.write_string(row, 2, &nic.adapter)?;
+                sheet.write_string(row, 3, &nic.mac)?;
+                sheet.write_string(row, 4, bool_text(nic.connected))?;
+                row += 1;
+            }
+        }
+
+        Ok(workbook)
+    }
+}
+
+fn write_header(sheet: &mut Worksheet, columns: &[&str]) -> std::result::Result<(), XlsxError> {
+    for (col, name) in columns.iter().enumerate() {
+        sheet.write_string(0, col as u16, *name)?;
+    }
+    Ok(())
+}
+
+fn bool_text(value: bool) -> &'static str {
+    if value {
+        "True"
+    } else {
+        "False"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hosts_and_vms_are_spread_over_clusters() {
+        let config = GeneratorConfig {
+            clusters: 3,
+            hosts: 10,
+            vms: 101,
+            ..Default::default()
+        };
+        let env = generate(&config).unwrap();
+        assert_eq!(env.hosts.len(), 10);
+        assert_eq!(env.vms.len(), 101);
+
+        let per_cluster: Vec<usize> = env
+            .clusters
+            .iter()
+            .map(|c| env.hosts.iter().filter(|h| &h.cluster == c).count())
+            .collect();
+        assert_eq!(per_cluster, vec![4, 3, 3]);
+        // Every VM runs on a host of its own cluster
+        assert!(env
+            .vms
+            .iter()
+            .all(|vm| env.hosts.iter().any(|h| h.name == vm.host && h.cluster == vm.cluster)));
+    }
+
+    #[test]
+    fn rejects_more_clusters_than_hosts() {
+        let config = GeneratorConfig {
+            clusters: 4,
+            hosts: 3,
+            ..Default::default()
+        };
+        assert!(generate(&config).is_err());
+    }
+}
@@ -1,6 +1,9 @@
+use crate::columnar::VmTable;
 use crate::models::*;
+use crate::units::Bytes;
 use crate::Result;
 use std::collections::HashMap;
+use std::ops::Range;
 use crate::CoreEngineError;
 
 /// Multi-dimensional bin packing algorithm for VM placement
@@ -12,34 +15,73 @@ impl SizingEngine {
         vms: &[VirtualMachine],
         hardware_profile: &HardwareProfile,
         parameters: &SizingParameters,
+    ) -> Result<SizingResult> {
+        Self::calculate_sizing_iter(vms.iter(), hardware_profile, parameters)
+    }
+
+    fn calculate_sizing_iter<'a>(
+        vms: impl Iterator<Item = &'a VirtualMachine>,
+        hardware_profile: &HardwareProfile,
+        parameters: &SizingParameters,
     ) -> Result<SizingResult> {
         // Filter VMs based on power state and template status
-        let active_vms: Vec<_> = vms.iter()
+        let active_vms: Vec<_> = vms
             .filter(|vm| vm.power_state == PowerState::PoweredOn && !vm.is_template)
             .collect();
 
         // Apply growth projections
         let projected_vms = Self::apply_growth_projections(&active_vms, parameters);
 
+        Self::size_projected(&projected_vms, |row| active_vms[row].name.as_str(), hardware_profile, parameters)
+    }
+
+    /// Same sizing over rows of the columnar VM table, for very large estates
+    pub fn calculate_sizing_columnar(
+        vms: &VmTable,
+        rows: Range<usize>,
+        hardware_profile: &HardwareProfile,
+        parameters: &SizingParameters,
+    ) -> Result<SizingResult> {
+        let growth_multiplier = 1.0 + (parameters.growth_factor_percent / 100.0);
+        let projected_vms: Vec<ProjectedVm> = rows
+            .filter(|&row| vms.is_active(row))
+            .map(|row| ProjectedVm {
+                row,
+                vcpu: (vms.vcpu[row] as f32 * growth_multiplier).ceil() as u32,
+                memory: Bytes::from_gib(vms.memory_gb[row] as f64 * growth_multiplier as f64),
+                storage: Bytes::from_gib(vms.consumed_storage_gb[row] * growth_multiplier as f64),
+            })
+            .collect();
+
+        Self::size_projected(&projected_vms, |row| vms.name(row), hardware_profile, parameters)
+    }
+
+    /// Place projected VMs and size the result; `name_of` resolves a VM's row to its name
+    fn size_projected<'a>(
+        projected_vms: &[ProjectedVm],
+        name_of: impl Fn(usize) -> &'a str,
+        hardware_profile: &HardwareProfile,
+        parameters: &SizingParameters,
+    ) -> Result<SizingResult> {
         // Calculate usable host capacity considering reservations
         let usable_capacity = Self::calculate_usable_capacity(hardware_profile, parameters)?;
 
         // Run the bin packing algorithm
-        let placement_result = Self::run_bin_packing_algorithm(&projected_vms, &usable_capacity)?;
+        let placement_result = Self::run_bin_packing_algorithm(projected_vms, &usable_capacity, name_of)?;
 
         // Apply HA policy to determine final host count
         let final_host_count = Self::apply_ha_policy(placement_result.required_hosts, &parameters.ha_policy);
 
         // Calculate utilization metrics
         let utilization_metrics = Self::calculate_utilization_metrics(
-            &projected_vms,
+            projected_vms,
             final_host_count,
             &usable_capacity,
             &parameters.ha_policy,
         );
 
         // Generate warnings
-        let warnings = Self::generate_sizing_warnings(&projected_vms, &utilization_metrics, parameters);
+        let warnings = Self::generate_sizing_warnings(projected_vms, &utilization_metrics, parameters);
 
         Ok(SizingResult {
             hardware_profile: hardware_profile.clone(),
@@ -58,22 +100,20 @@ impl SizingEngine {
     ) -> Vec<ProjectedVm> {
         let growth_multiplier = 1.0 + (parameters.growth_factor_percent / 100.0);
         
-        vms.iter().map(|vm| {
+        vms.iter().enumerate().map(|(row, vm)| {
             ProjectedVm {
-                name: vm.name.clone(),
+                row,
                 vcpu: (vm.num_vcpu as f32 * growth_multiplier).ceil() as u32,
-                memory_gb: (vm.memory_gb as f32 * growth_multiplier).ceil() as u32,
-                storage_gb: vm.disks.iter()
-                    .map(|d| d.consumed_in_guest_gb * growth_multiplier as f64)
-                    .sum::<f64>(),
-                _special_flags: vm.special_flags.clone(),
-                _original_vm: *vm as *const VirtualMachine,
+                memory: Bytes::from_gib(vm.memory_gb as f64 * growth_multiplier as f64),
+                storage: vm.disks.iter()
+                    .map(|d| Bytes::from_gib(d.consumed_in_guest_gb * growth_multiplier as f64))
+                    .sum(),
             }
         }).collect()
     }
 
     /// Calculate usable host capacity considering reservations
-    fn calculate_usable_capacity(
+    pub(crate) fn calculate_usable_capacity(
         hardware_profile: &HardwareProfile,
         parameters: &SizingParameters,
     ) -> Result<UsableCapacity> {
@@ -81,85 +121,122 @@ impl SizingEngine {
         let usable_vcpu = (hardware_profile.total_cores as f32 * parameters.target_vcpu_pcpu_ratio).floor() as u32;
 
         // Calculate usable memory (reserve some for hypervisor)
-        let hypervisor_memory_reservation = 32; // GB reserved for hypervisor
-        let usable_memory = hardware_profile.max_memory_gb.saturating_sub(hypervisor_memory_reservation);
+        let hypervisor_memory_reservation = Bytes::from_gib(32.0);
+        let usable_memory = Bytes::from_gib(hardware_profile.max_memory_gb as f64)
+            .saturating_sub(hypervisor_memory_reservation);
 
         // Apply additional reservations
         let reserved_vcpu = (usable_vcpu as f32 * parameters.cpu_reservation_percent / 100.0).ceil() as u32;
-        let reserved_memory = (usable_memory as f32 * parameters.memory_reservation_percent / 100.0).ceil() as u32;
+        let reserved_memory = Bytes::from_gib(usable_memory.as_gib() * parameters.memory_reservation_percent as f64 / 100.0);
 
         Ok(UsableCapacity {
             vcpu: usable_vcpu.saturating_sub(reserved_vcpu),
-            memory_gb: usable_memory.saturating_sub(reserved_memory),
-            _storage_gb: 0.0, // Storage is typically shared, not per-host
+            memory: usable_memory.saturating_sub(reserved_memory),
+            _storage: Bytes::ZERO, // Storage is typically shared, not per-host
         })
     }
 
     /// Run the First-Fit Decreasing bin packing algorithm
-    fn run_bin_packing_algorithm(
+    fn run_bin_packing_algorithm<'a>(
         vms: &[ProjectedVm],
         usable_capacity: &UsableCapacity,
+        name_of: impl Fn(usize) -> &'a str,
     ) -> Result<PlacementResult> {
-        // Sort VMs by resource requirements (descending order)
         let mut sorted_vms = vms.to_vec();
-        sorted_vms.sort_by(|a, b| {
-            // Primary sort by vCPU, secondary by memory
-            let a_weight = a.vcpu * 1000 + a.memory_gb;
-            let b_weight = b.vcpu * 1000 + b.memory_gb;
-            b_weight.cmp(&a_weight)
-        });
+        Self::sort_for_packing(&mut sorted_vms);
+        let (hosts, host_of) = Self::pack_sorted(&sorted_vms, usable_capacity);
 
-        let mut hosts: Vec<HostBin> = Vec::new();
-        let mut vm_placement: HashMap<String, String> = HashMap::new();
+        Ok(PlacementResult {
+            required_hosts: hosts.len() as u32,
+            vm_placement: Self::placement_names(&sorted_vms, &host_of, name_of),
+            _host_details: hosts,
+        })
+    }
+
+    /// Sort VMs by resource requirements (descending order): primary by
+    /// vCPU, secondary by memory. Stable, so equal VMs keep their row order.
+    pub(crate) fn sort_for_packing(vms: &mut [ProjectedVm]) {
+        vms.sort_by(|a, b| (b.vcpu, b.memory).cmp(&(a.vcpu, a.memory)));
+    }
 
-        for vm in &sorted_vms {
-            let mut placed = false;
+    /// First-Fit Decreasing over VMs sorted by [`Self::sort_for_packing`];
+    /// returns the hosts and the host index of each VM
+    pub(crate) fn pack_sorted(
+        sorted_vms: &[ProjectedVm],
+        usable_capacity: &UsableCapacity,
+    ) -> (Vec<HostBin>, Vec<usize>) {
+        // Hosts with less room than the smallest VM can never take another
+        // one; skipping them keeps placement near-linear on large estates
+        let min_vcpu = sorted_vms.iter().map(|vm| vm.vcpu).min().unwrap_or(0);
+        let min_memory = sorted_vms.iter().map(|vm| vm.memory).min().unwrap_or(Bytes::ZERO);
+        let mut first_open = 0;
 
+        let mut hosts: Vec<HostBin> = Vec::new();
+        let mut host_of = Vec::with_capacity(sorted_vms.len());
+
+        for vm in sorted_vms {
             // Try to place VM on existing hosts
-            for (host_idx, host) in hosts.iter_mut().enumerate() {
-                if Self::can_fit_vm(vm, host, usable_capacity) {
-                    Self::place_vm_on_host(vm, host);
-                    vm_placement.insert(vm.name.clone(), format!("Host-{:02}", host_idx + 1));
-                    placed = true;
-                    break;
-                }
-            }
+            let existing = hosts
+                .iter()
+                .enumerate()
+                .skip(first_open)
+                .find(|(_, host)| Self::can_fit_vm(vm, host, usable_capacity))
+                .map(|(host_idx, _)| host_idx);
 
             // If VM doesn't fit on any existing host, create a new one
-            if !placed {
-                let mut new_host = HostBin::new(hosts.len() + 1, usable_capacity.clone());
-                Self::place_vm_on_host(vm, &mut new_host);
-                vm_placement.insert(vm.name.clone(), format!("Host-{:02}", new_host.id));
-                hosts.push(new_host);
+            let host_idx = existing.unwrap_or_else(|| {
+                hosts.push(HostBin::new(hosts.len() + 1, usable_capacity.clone()));
+                hosts.len() - 1
+            });
+            Self::place_vm_on_host(vm, &mut hosts[host_idx]);
+            host_of.push(host_idx);
+
+            while first_open < hosts.len() && Self::is_full(&hosts[first_open], usable_capacity, min_vcpu, min_memory) {
+                first_open += 1;
             }
         }
 
-        Ok(PlacementResult {
-            required_hosts: hosts.len() as u32,
-            vm_placement,
-            _host_details: hosts,
-        })
+        (hosts, host_of)
+    }
+
+    /// VM name to host name, from the host index of each sorted VM
+    pub(crate) fn placement_names<'a>(
+        sorted_vms: &[ProjectedVm],
+        host_of: &[usize],
+        name_of: impl Fn(usize) -> &'a str,
+    ) -> HashMap<String, String> {
+        sorted_vms
+            .iter()
+            .zip(host_of)
+            .map(|(vm, host_idx)| (name_of(vm.row).to_string(), format!("Host-{:02}", host_idx + 1)))
+            .collect()
     }
 
     /// Check if a VM can fit on a host
     fn can_fit_vm(vm: &ProjectedVm, host: &HostBin, capacity: &UsableCapacity) -> bool {
         let remaining_vcpu = capacity.vcpu.saturating_sub(host.allocated_vcpu);
-        let remaining_memory = capacity.memory_gb.saturating_sub(host.allocated_memory_gb);
+        let remaining_memory = capacity.memory.saturating_sub(host.allocated_memory);
 
-        vm.vcpu <= remaining_vcpu && vm.memory_gb <= remaining_memory
+        vm.vcpu <= remaining_vcpu && vm.memory <= remaining_memory
+    }
+
+    /// Whether a host has no room left for a VM of the given minimum size
+    fn is_full(host: &HostBin, capacity: &UsableCapacity, min_vcpu: u32, min_memory: Bytes) -> bool {
+        capacity.vcpu.saturating_sub(host.allocated_vcpu) < min_vcpu
+            || capacity.memory.saturating_sub(host.allocated_memory) < min_memory
     }
 
     /// Place a VM on a host
     fn place_vm_on_host(vm: &ProjectedVm, host: &mut HostBin) {
         host.allocated_vcpu += vm.vcpu;
-        host.allocated_memory_gb += vm.memory_gb;
-        host.allocated_storage_gb += vm.storage_gb;
+        host.allocated_memory += vm.memory;
+        host.allocated_storage += vm.storage;
         host.vm_count += 1;
-        host.assigned_vms.push(vm.name.clone());
+        host.assigned_vms.push(vm.row);
     }
 
     /// Apply HA policy to determine final host count
-    fn apply_ha_policy(calculated_hosts: u32, ha_policy: &HaPolicy) -> u32 {
+    pub(crate) fn apply_ha_policy(calculated_hosts: u32, ha_policy: &HaPolicy) -> u32 {
         match ha_policy {
             HaPolicy::None => calculated_hosts,
             HaPolicy::NPlusOne => calculated_hosts + 1,
@@ -168,18 +245,18 @@ impl SizingEngine {
     }
 
     /// Calculate utilization metrics for the sized solution
-    fn calculate_utilization_metrics(
+    pub(crate) fn calculate_utilization_metrics(
         vms: &[ProjectedVm],
         host_count: u32,
         usable_capacity: &UsableCapacity,
         ha_policy: &HaPolicy,
     ) -> UtilizationMetrics {
         let total_vm_vcpu: u32 = vms.iter().map(|vm| vm.vcpu).sum();
-        let total_vm_memory: u32 = vms.iter().map(|vm| vm.memory_gb).sum();
-        let _total_vm_storage: f64 = vms.iter().map(|vm| vm.storage_gb).sum();
+        let total_vm_memory: Bytes = vms.iter().map(|vm| vm.memory).sum();
+        let _total_vm_storage: Bytes = vms.iter().map(|vm| vm.storage).sum();
 
         let total_cluster_vcpu = usable_capacity.vcpu * host_count;
-        let total_cluster_memory = usable_capacity.memory_gb * host_count;
+        let total_cluster_memory = usable_capacity.memory.as_gib() * host_count as f64;
 
         let cpu_utilization = if total_cluster_vcpu > 0 {
             (total_vm_vcpu as f32 / total_cluster_vcpu as f32) * 100.0
@@ -187,8 +264,8 @@ impl SizingEngine {
             0.0
         };
 
-        let memory_utilization = if total_cluster_memory > 0 {
-            (total_vm_memory as f32 / total_cluster_memory as f32) * 100.0
+        let memory_utilization = if total_cluster_memory > 0.0 {
+            (total_vm_memory.as_gib() / total_cluster_memory) as f32 * 100.0
         } else {
             0.0
         };
@@ -220,7 +297,7 @@ impl SizingEngine {
     }
 
     /// Generate sizing warnings
-    fn generate_sizing_warnings(
+    pub(crate) fn generate_sizing_warnings(
         vms: &[ProjectedVm],
         utilization: &UtilizationMetrics,
         parameters: &SizingParameters,
@@ -243,7 +320,7 @@ impl SizingEngine {
 
         // Check for oversized VMs
         let oversized_vms: Vec<_> = vms.iter()
-            .filter(|vm| vm.vcpu > 16 || vm.memory_gb > 128)
+            .filter(|vm| vm.vcpu > 16 || vm.memory > Bytes::from_gib(128.0))
             .collect();
 
         if !oversized_vms.is_empty() {
@@ -324,31 +401,31 @@ impl SizingEngine {
 
 // Supporting structures for the sizing algorithm
 
+/// A VM's projected demand; `row` indexes the VMs being sized
 #[derive(Debug, Clone)]
-struct ProjectedVm {
-    name: String,
-    vcpu: u32,
-    memory_gb: u32,
-    storage_gb: f64,
-    _special_flags: VmSpecialFlags,
-    _original_vm: *const VirtualMachine,
+pub(crate) struct ProjectedVm {
+    pub(crate) row: usize,
+    pub(crate) vcpu: u32,
+    pub(crate) memory: Bytes,
+    pub(crate) storage: Bytes,
 }
 
 #[derive(Debug, Clone)]
-struct UsableCapacity {
-    vcpu: u32,
-    memory_gb: u32,
-    _storage_gb: f64,
+pub(crate) struct UsableCapacity {
+    pub(crate) vcpu: u32,
+    pub(crate) memory: Bytes,
+    _storage: Bytes,
 }
 
 #[derive(Debug, Clone)]
-struct HostBin {
+pub(crate) struct HostBin {
     id: usize,
     allocated_vcpu: u32,
-    allocated_memory_gb: u32,
-    allocated_storage_gb: f64,
+    allocated_memory: Bytes,
+    allocated_storage: Bytes,
     vm_count: u32,
-    assigned_vms: Vec<String>,
+    /// Rows of the VMs placed on this host
+    assigned_vms: Vec<usize>,
 }
 
 impl HostBin {
@@ -356,8 +433,8 @@ impl HostBin {
         Self {
             id,
             allocated_vcpu: 0,
-            allocated_memory_gb: 0,
-            allocated_storage_gb: 0.0,
+            allocated_memory: Bytes::ZERO,
+            allocated_storage: Bytes::ZERO,
             vm_count: 0,
             assigned_vms: Vec::new(),
         }
@@ -527,7 +604,8 @@ mod tests {
         let capacity = SizingEngine::calculate_usable_capacity(&hardware, &parameters).unwrap();
 
         assert_eq!(capacity.vcpu, 115); // 32 * 4.0 = 128, 128 * 0.1 = 12.8 -> 13, 128 - 13 = 115
-        assert!(capacity.memory_gb > 200); // 256 - 32 - 10% reservation
+        assert!(capacity.memory > Bytes::from_gib(200.0)); // 256 - 32 - 10% reservation
+        assert!((capacity.memory.as_gib() - 201.6).abs() < 1e-6); // reservation is no longer rounded to whole GB
     }
 
     #[test]
@@ -539,16 +617,13 @@ mod tests {
 }
 
 /// Convenience function for calculating sizing
+#[tracing::instrument(name = "sizing.calculate", skip_all, fields(clusters = environment.clusters.len()))]
 pub async fn calculate_sizing(
     environment: &VsphereEnvironment,
     hardware_profile: &HardwareProfile,
     parameters: &SizingParameters,
 ) -> Result<SizingResult> {
-    // Collect all VMs from clusters
-    let mut all_vms = Vec::new();
-    for cluster in &environment.clusters {
-        all_vms.extend_from_slice(&cluster.vms);
-    }
-    
-    SizingEngine::calculate_sizing(&all_vms, hardware_profile, parameters)
+    // Size the VMs of every cluster without copying them
+    let all_vms = environment.clusters.iter().flat_map(|cluster| &cluster.vms);
+    SizingEngine::calculate_sizing_iter(all_vms, hardware_profile, parameters)
 }
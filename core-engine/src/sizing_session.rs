@@ -0,0 +1,470 @@
+//! Interactive sizing over one loaded environment
+//!
+//! [`SizingEngine::calculate_sizing`] starts from the VM structs on every
+//! call, which is too slow to follow a slider on a large estate. A
+//! [`SizingSession`] loads the environment into columns once and keeps what
+//! a parameter change would otherwise rebuild: the active VMs' projected
+//! demand, sorted for packing, per growth factor, and the host packing per
+//! usable host capacity. Changing the HA policy or a ratio that does not
+//! move the usable capacity only re-derives the totals; a packing is only
+//! redone for a capacity the session has not packed yet.
+//!
+//! Results are the same as [`SizingEngine::calculate_sizing`] over the
+//! environment's clustered VMs with the same profile and parameters.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::columnar::ColumnarEnvironment;
+use crate::models::*;
+use crate::sizing::{ProjectedVm, SizingEngine};
+use crate::units::Bytes;
+use crate::{CoreEngineError, Result};
+
+/// Packings kept per session; the cache is emptied when it fills up
+const MAX_CACHED_PACKINGS: usize = 64;
+
+/// Parameters to change; `None` keeps the current value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SizingDelta {
+    pub target_vcpu_pcpu_ratio: Option<f32>,
+    pub target_memory_overcommit_ratio: Option<f32>,
+    pub ha_policy: Option<HaPolicy>,
+    pub growth_factor_percent: Option<f32>,
+    pub forecast_horizon_months: Option<u32>,
+    /// Headroom held back on each host's vCPUs
+    pub cpu_reservation_percent: Option<f32>,
+    /// Headroom held back on each host's memory
+    pub memory_reservation_percent: Option<f32>,
+    pub storage_overhead_percent: Option<f32>,
+}
+
+impl SizingDelta {
+    /// `parameters` with this delta applied
+    pub fn applied_to(&self, parameters: &SizingParameters) -> SizingParameters {
+        let mut updated = parameters.clone();
+        if let Some(ratio) = self.target_vcpu_pcpu_ratio {
+            updated.target_vcpu_pcpu_ratio = ratio;
+        }
+        if let Some(ratio) = self.target_memory_overcommit_ratio {
+            updated.target_memory_overcommit_ratio = ratio;
+        }
+        if let Some(policy) = &self.ha_policy {
+            updated.ha_policy = policy.clone();
+        }
+        if let Some(percent) = self.growth_factor_percent {
+            updated.growth_factor_percent = percent;
+        }
+        if let Some(months) = self.forecast_horizon_months {
+            updated.forecast_horizon_months = months;
+        }
+        if let Some(percent) = self.cpu_reservation_percent {
+            updated.cpu_reservation_percent = percent;
+        }
+        if let Some(percent) = self.memory_reservation_percent {
+            updated.memory_reservation_percent = percent;
+        }
+        if let Some(percent) = self.storage_overhead_percent {
+            updated.storage_overhead_percent = percent;
+        }
+        updated
+    }
+}
+
+/// One recalculation of a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSizing {
+    /// `vm_placement` is only filled when the placement was asked for
+    pub result: SizingResult,
+    /// False when a cached packing was reused
+    pub repacked: bool,
+    pub elapsed_ms: f64,
+}
+
+/// Active VMs projected with one growth factor, sorted for packing
+#[derive(Debug)]
+struct Projection {
+    growth_bits: u32,
+    sorted: Vec<ProjectedVm>,
+}
+
+/// Host packing of a projection into hosts of one usable capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PackingKey {
+    growth_bits: u32,
+    vcpu: u32,
+    memory: Bytes,
+}
+
+#[derive(Debug)]
+struct Packing {
+    required_hosts: u32,
+    /// Host index of each VM in the projection's sorted order
+    host_of: Vec<usize>,
+}
+
+/// What-if sizing of one environment against changing parameters
+#[derive(Debug)]
+pub struct SizingSession {
+    environment: ColumnarEnvironment,
+    rows: Range<usize>,
+    profile: HardwareProfile,
+    parameters: SizingParameters,
+    projection: Option<Projection>,
+    packings: HashMap<PackingKey, Packing>,
+}
+
+impl SizingSession {
+    pub fn new(
+        environment: &VsphereEnvironment,
+        profile: HardwareProfile,
+        parameters: SizingParameters,
+    ) -> Result<Self> {
+        validate_parameters(&parameters)?;
+        let environment = ColumnarEnvironment::from_environment(environment);
+        Ok(Self {
+            rows: environment.clustered_rows(),
+            environment,
+            profile,
+            parameters,
+            projection: None,
+            packings: HashMap::new(),
+        })
+    }
+
+    pub fn environment_id(&self) -> Uuid {
+        self.environment.id
+    }
+
+    pub fn profile(&self) -> &HardwareProfile {
+        &self.profile
+    }
+
+    pub fn parameters(&self) -> &SizingParameters {
+        &self.parameters
+    }
+
+    /// VMs the session sizes: powered on, not templates, in a cluster
+    pub fn active_vms(&self) -> usize {
+        self.rows.clone().filter(|&row| self.environment.vms.is_active(row)).count()
+    }
+
+    /// Switch the node model; packings for capacities already seen are kept
+    pub fn set_profile(&mut self, profile: HardwareProfile) {
+        self.profile = profile;
+    }
+
+    /// Change parameters; an invalid delta leaves the session unchanged
+    pub fn apply(&mut self, delta: &SizingDelta) -> Result<()> {
+        let parameters = delta.applied_to(&self.parameters);
+        validate_parameters(&parameters)?;
+        self.parameters = parameters;
+        Ok(())
+    }
+
+    /// Size the environment with the current profile and parameters
+    pub fn calculate(&mut self, include_placement: bool) -> Result<SessionSizing> {
+        let started = Instant::now();
+        let growth_bits = self.parameters.growth_factor_percent.to_bits();
+        if !matches!(&self.projection, Some(p) if p.growth_bits == growth_bits) {
+            self.projection = Some(self.project());
+        }
+        let sorted = &self.projection.as_ref().expect("projection was just built").sorted;
+
+        let capacity = SizingEngine::calculate_usable_capacity(&self.profile, &self.parameters)?;
+        let key = PackingKey {
+            growth_bits,
+            vcpu: capacity.vcpu,
+            memory: capacity.memory,
+        };
+        let repacked = !self.packings.contains_key(&key);
+        if repacked {
+            if self.packings.len() >= MAX_CACHED_PACKINGS {
+                self.packings.clear();
+            }
+            let (hosts, host_of) = SizingEngine::pack_sorted(sorted, &capacity);
+            self.packings.insert(
+                key,
+                Packing {
+                    required_hosts: hosts.len() as u32,
+                    host_of,
+                },
+            );
+        }
+        let packing = &self.packings[&key];
+
+        let ha_policy = &self.parameters.ha_policy;
+        let final_host_count = SizingEngine::apply_ha_policy(packing.required_hosts, ha_policy);
+        let utilization_metrics =
+            SizingEngine::calculate_utilization_metrics(sorted, final_host_count, &capacity, ha_policy);
+        let warnings = SizingEngine::generate_sizing_warnings(sorted, &utilization_metrics, &self.parameters);
+        let vm_placement = if include_placement {
+            let vms = &self.environment.vms;
+            SizingEngine::placement_names(sorted, &packing.host_of, |row| vms.name(row))
+        } else {
+            HashMap::new()
+        };
+
+        Ok(SessionSizing {
+            result: SizingResult {
+                hardware_profile: self.profile.clone(),
+                required_hosts: final_host_count,
+                total_cost: self.profile.estimated_cost.map(|cost| cost * final_host_count as f64),
+                utilization_metrics,
+                vm_placement,
+                warnings,
+            },
+            repacked,
+            elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+        })
+    }
+
+    /// Projected demand of the active VMs, the way `calculate_sizing_columnar`
+    /// projects it
+    fn project(&self) -> Projection {
+        let vms = &self.environment.vms;
+        let growth_multiplier = 1.0 + (self.parameters.growth_factor_percent / 100.0);
+        let mut sorted: Vec<ProjectedVm> = self
+            .rows
+            .clone()
+            .filter(|&row| vms.is_active(row))
+            .map(|row| ProjectedVm {
+                row,
+                vcpu: (vms.vcpu[row] as f32 * growth_multiplier).ceil() as u32,
+                memory: Bytes::from_gib(vms.memory_gb[row] as f64 * growth_multiplier as f64),
+                storage: Bytes::from_gib(vms.consumed_storage_gb[row] * growth_multiplier as f64),
+            })
+            .collect();
+        SizingEngine::sort_for_packing(&mut sorted);
+        Projection {
+            growth_bits: self.parameters.growth_factor_percent.to_bits(),
+            sorted,
+        }
+    }
+}
+
+/// Reject parameters that would size nothing or divide by zero
+fn validate_parameters(parameters: &SizingParameters) -> Result<()> {
+    let percent = |value: f32| (0.0..100.0).contains(&value);
+    if !(parameters.target_vcpu_pcpu_ratio > 0.0 && parameters.target_vcpu_pcpu_ratio.is_finite()) {
+        return Err(CoreEngineError::validation("target_vcpu_pcpu_ratio must be greater than 0"));
+    }
+    if !percent(parameters.cpu_reservation_percent) || !percent(parameters.memory_reservation_percent) {
+        return Err(CoreEngineError::validation("Reservations must be at least 0% and below 100%"));
+    }
+    if !(parameters.growth_factor_percent > -100.0 && parameters.growth_factor_percent.is_finite()) {
+        return Err(CoreEngineError::validation("growth_factor_percent must be above -100"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sizing::HardwareBasket;
+    use chrono::Utc;
+
+    fn vm(index: u32) -> VirtualMachine {
+        let name = format!("vm{:04}", index);
+        VirtualMachine {
+            name: name.clone(),
+            cluster_name: "prod".to_string(),
+            host_name: "esx01".to_string(),
+            power_state: if index % 10 == 9 { PowerState::PoweredOff } else { PowerState::PoweredOn },
+            num_vcpu: [1, 2, 4, 8, 16][index as usize % 5],
+            memory_gb: [4, 8, 16, 32, 64, 192][index as usize % 6],
+            guest_os: None,
+            vm_version: None,
+            tools_status: None,
+            tools_version: None,
+            is_template: false,
+            disks: vec![VirtualDisk {
+                vm_name: name,
+                disk_label: "Hard disk 1".to_string(),
+                provisioned_gb: 100.0,
+                consumed_in_guest_gb: 40.0 + index as f64 % 7.0,
+                consumed_on_datastore_gb: 100.0,
+                is_rdm: false,
+                disk_mode: None,
+                provisioning_type: ProvisioningType::Thin,
+                datastore_name: None,
+            }],
+            nics: Vec::new(),
+            notes: None,
+            annotation: None,
+            folder: None,
+            resource_pool: None,
+            created_date: None,
+            last_powered_on: None,
+            special_flags: VmSpecialFlags::default(),
+        }
+    }
+
+    fn environment(vm_count: u32) -> VsphereEnvironment {
+        let vms: Vec<VirtualMachine> = (0..vm_count).map(vm).collect();
+        VsphereEnvironment {
+            id: Uuid::new_v4(),
+            name: "test".to_string(),
+            parsed_at: Utc::now(),
+            total_vms: vms.len(),
+            total_hosts: 0,
+            clusters: vec![Cluster {
+                name: "prod".to_string(),
+                hosts: Vec::new(),
+                metrics: ClusterMetrics {
+                    total_hosts: 0,
+                    total_vms: vms.len(),
+                    total_pcpu_cores: 0,
+                    total_vcpus: 0,
+                    current_vcpu_pcpu_ratio: 0.0,
+                    total_memory_gb: 0,
+                    provisioned_memory_gb: 0.0,
+                    memory_overcommit_ratio: 0.0,
+                    total_storage_gb: 0.0,
+                    consumed_storage_gb: 0.0,
+                },
+                health_status: ClusterHealth {
+                    zombie_vms: Vec::new(),
+                    outdated_tools: Vec::new(),
+                    rdm_vms: Vec::new(),
+                    ft_enabled_vms: Vec::new(),
+                    warnings: Vec::new(),
+                },
+                vms,
+            }],
+            standalone_hosts: Vec::new(),
+            summary_metrics: EnvironmentSummary {
+                total_vcpus: 0,
+                total_pcores: 0,
+                total_provisioned_memory_gb: 0.0,
+                total_consumed_memory_gb: 0.0,
+                total_provisioned_storage_gb: 0.0,
+                total_consumed_storage_gb: 0.0,
+                overall_vcpu_pcpu_ratio: 0.0,
+                health_issues: Vec::new(),
+            },
+            import_report: ImportReport::default(),
+        }
+    }
+
+    fn full_sizing(
+        environment: &VsphereEnvironment,
+        profile: &HardwareProfile,
+        parameters: &SizingParameters,
+    ) -> SizingResult {
+        let all_vms: Vec<VirtualMachine> = environment.clusters.iter().flat_map(|c| c.vms.clone()).collect();
+        SizingEngine::calculate_sizing(&all_vms, profile, parameters).unwrap()
+    }
+
+    #[test]
+    fn test_session_matches_full_sizing_across_deltas() {
+        let environment = environment(500);
+        let profiles = HardwareBasket::new().get_profiles().to_vec();
+        let mut session = SizingSession::new(&environment, profiles[0].clone(), SizingParameters::default()).unwrap();
+        assert_eq!(session.active_vms(), 450);
+
+        let deltas = [
+            SizingDelta::default(),
+            SizingDelta {
+                target_vcpu_pcpu_ratio: Some(2.5),
+                ..Default::default()
+            },
+            SizingDelta {
+                memory_reservation_percent: Some(25.0),
+                ha_policy: Some(HaPolicy::NPlusTwo),
+                ..Default::default()
+            },
+            SizingDelta {
+                growth_factor_percent: Some(65.0),
+                ..Default::default()
+            },
+        ];
+        for (step, delta) in deltas.iter().enumerate() {
+            session.apply(delta).unwrap();
+            if step == 3 {
+                session.set_profile(profiles[1].clone());
+            }
+            let expected = full_sizing(&environment, session.profile(), session.parameters());
+            let actual = session.calculate(true).unwrap().result;
+
+            assert_eq!(actual.required_hosts, expected.required_hosts, "step {}", step);
+            assert_eq!(actual.vm_placement, expected.vm_placement, "step {}", step);
+            assert_eq!(actual.warnings, expected.warnings, "step {}", step);
+            assert_eq!(actual.total_cost, expected.total_cost, "step {}", step);
+            assert_eq!(
+                actual.utilization_metrics.cpu_utilization_percent,
+                expected.utilization_metrics.cpu_utilization_percent
+            );
+        }
+    }
+
+    #[test]
+    fn test_packing_is_reused_when_capacity_is_unchanged() {
+        let environment = environment(200);
+        let profile = HardwareBasket::new().get_profiles()[0].clone();
+        let mut session = SizingSession::new(&environment, profile, SizingParameters::default()).unwrap();
+
+        let first = session.calculate(false).unwrap();
+        assert!(first.repacked);
+        assert!(first.result.vm_placement.is_empty());
+
+        session
+            .apply(&SizingDelta {
+                ha_policy: Some(HaPolicy::None),
+                ..Default::default()
+            })
+            .unwrap();
+        let no_ha = session.calculate(false).unwrap();
+        assert!(!no_ha.repacked);
+        assert_eq!(no_ha.result.required_hosts + 1, first.result.required_hosts);
+
+        session
+            .apply(&SizingDelta {
+                target_vcpu_pcpu_ratio: Some(2.0),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(session.calculate(false).unwrap().repacked);
+
+        // Back to a capacity packed before
+        session
+            .apply(&SizingDelta {
+                target_vcpu_pcpu_ratio: Some(4.0),
+                ..Default::default()
+            })
+            .unwrap();
+        let back = session.calculate(false).unwrap();
+        assert!(!back.repacked);
+        assert_eq!(back.result.required_hosts, no_ha.result.required_hosts);
+    }
+
+    #[test]
+    fn test_invalid_delta_leaves_session_unchanged() {
+        let environment = environment(10);
+        let profile = HardwareBasket::new().get_profiles()[0].clone();
+        let mut session = SizingSession::new(&environment, profile, SizingParameters::default()).unwrap();
+
+        for delta in [
+            SizingDelta {
+                target_vcpu_pcpu_ratio: Some(0.0),
+                ..Default::default()
+            },
+            SizingDelta {
+                cpu_reservation_percent: Some(100.0),
+                growth_factor_percent: Some(10.0),
+                ..Default::default()
+            },
+            SizingDelta {
+                growth_factor_percent: Some(f32::NAN),
+                ..Default::default()
+            },
+        ] {
+            assert!(session.apply(&delta).is_err());
+        }
+        assert_eq!(session.parameters().growth_factor_percent, 20.0);
+    }
+}
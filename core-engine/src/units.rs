@@ -0,0 +1,290 @@
+//! Typed capacity and frequency quantities
+//!
+//! vSphere and RVTools label binary sizes "MB" (they are MiB), cluster
+//! definitions are kept in GB, drive capacities on vendor sheets are
+//! decimal and CPU speeds come as MHz or GHz. Converting with bare `/ 1024`
+//! wherever a value is used led to integer truncation and mixed bases in
+//! sizing. Values are converted into these types once, where they are read,
+//! and read back in the unit a calculation needs.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+const KIB: f64 = 1024.0;
+const MIB: f64 = KIB * 1024.0;
+const GIB: f64 = MIB * 1024.0;
+const TIB: f64 = GIB * 1024.0;
+
+/// How an unqualified "MB"/"GB"/"TB" label is read. Explicit "MiB"/"GiB"
+/// labels are always binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeConvention {
+    /// vSphere, RVTools and memory sizes: "GB" means GiB
+    Binary,
+    /// Drive and array capacities: "GB" means 10^9 bytes
+    Decimal,
+}
+
+/// A size in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bytes(u64);
+
+impl Bytes {
+    pub const ZERO: Bytes = Bytes(0);
+
+    pub const fn new(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    fn from_scaled(value: f64, scale: f64) -> Self {
+        if value.is_finite() && value > 0.0 {
+            Self((value * scale).round() as u64)
+        } else {
+            Self::ZERO
+        }
+    }
+
+    pub fn from_kib(kib: f64) -> Self {
+        Self::from_scaled(kib, KIB)
+    }
+
+    pub fn from_mib(mib: f64) -> Self {
+        Self::from_scaled(mib, MIB)
+    }
+
+    pub fn from_gib(gib: f64) -> Self {
+        Self::from_scaled(gib, GIB)
+    }
+
+    pub fn from_tib(tib: f64) -> Self {
+        Self::from_scaled(tib, TIB)
+    }
+
+    pub fn from_mb(mb: f64) -> Self {
+        Self::from_scaled(mb, 1e6)
+    }
+
+    pub fn from_gb(gb: f64) -> Self {
+        Self::from_scaled(gb, 1e9)
+    }
+
+    pub fn from_tb(tb: f64) -> Self {
+        Self::from_scaled(tb, 1e12)
+    }
+
+    pub const fn bytes(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_mib(self) -> f64 {
+        self.0 as f64 / MIB
+    }
+
+    pub fn as_gib(self) -> f64 {
+        self.0 as f64 / GIB
+    }
+
+    pub fn as_tib(self) -> f64 {
+        self.0 as f64 / TIB
+    }
+
+    pub fn as_gb(self) -> f64 {
+        self.0 as f64 / 1e9
+    }
+
+    pub fn as_tb(self) -> f64 {
+        self.0 as f64 / 1e12
+    }
+
+    /// Whole GiB rounded up; for demand, so a 1.5 GiB VM is not sized as 1 GiB
+    pub fn gib_ceil(self) -> u32 {
+        self.as_gib().ceil() as u32
+    }
+
+    /// Whole GiB rounded down; for capacity, so hosts are never overstated
+    pub fn gib_floor(self) -> u32 {
+        self.as_gib().floor() as u32
+    }
+
+    pub fn saturating_sub(self, other: Bytes) -> Bytes {
+        Bytes(self.0.saturating_sub(other.0))
+    }
+
+    /// Parse "512 GB", "1.5TiB", "2,048 MB" or "4096" (bytes). Unqualified
+    /// decimal-looking units are read according to `convention`.
+    pub fn parse(text: &str, convention: SizeConvention) -> Option<Bytes> {
+        static SIZE: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"(?i)^\s*([0-9][0-9,]*(?:\.[0-9]+)?)\s*([KMGT]i?B|B)?\s*$").unwrap());
+        let caps = SIZE.captures(text)?;
+        let value: f64 = caps[1].replace(',', "").parse().ok()?;
+        let unit = caps.get(2).map(|m| m.as_str().to_ascii_uppercase()).unwrap_or_else(|| "B".to_string());
+        let binary = unit.contains('I') || convention == SizeConvention::Binary;
+        let bytes = match (unit.chars().next()?, binary) {
+            ('B', _) => Bytes::from_scaled(value, 1.0),
+            ('K', true) => Bytes::from_kib(value),
+            ('M', true) => Bytes::from_mib(value),
+            ('G', true) => Bytes::from_gib(value),
+            ('T', true) => Bytes::from_tib(value),
+            ('K', false) => Bytes::from_scaled(value, 1e3),
+            ('M', false) => Bytes::from_mb(value),
+            ('G', false) => Bytes::from_gb(value),
+            ('T', false) => Bytes::from_tb(value),
+            _ => return None,
+        };
+        Some(bytes)
+    }
+}
+
+impl Add for Bytes {
+    type Output = Bytes;
+
+    fn add(self, other: Bytes) -> Bytes {
+        Bytes(self.0.saturating_add(other.0))
+    }
+}
+
+impl AddAssign for Bytes {
+    fn add_assign(&mut self, other: Bytes) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Bytes {
+    type Output = Bytes;
+
+    fn sub(self, other: Bytes) -> Bytes {
+        self.saturating_sub(other)
+    }
+}
+
+impl Sum for Bytes {
+    fn sum<I: Iterator<Item = Bytes>>(iter: I) -> Bytes {
+        iter.fold(Bytes::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Bytes> for Bytes {
+    fn sum<I: Iterator<Item = &'a Bytes>>(iter: I) -> Bytes {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.0 as f64;
+        if value >= TIB {
+            write!(f, "{:.2} TiB", value / TIB)
+        } else if value >= GIB {
+            write!(f, "{:.2} GiB", value / GIB)
+        } else if value >= MIB {
+            write!(f, "{:.0} MiB", value / MIB)
+        } else {
+            write!(f, "{} B", self.0)
+        }
+    }
+}
+
+/// A frequency in hertz
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Hertz(u64);
+
+impl Hertz {
+    pub const fn new(hz: u64) -> Self {
+        Self(hz)
+    }
+
+    pub fn from_mhz(mhz: f64) -> Self {
+        if mhz.is_finite() && mhz > 0.0 {
+            Self((mhz * 1e6).round() as u64)
+        } else {
+            Self(0)
+        }
+    }
+
+    pub fn from_ghz(ghz: f64) -> Self {
+        Self::from_mhz(ghz * 1000.0)
+    }
+
+    pub const fn hz(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_mhz(self) -> f64 {
+        self.0 as f64 / 1e6
+    }
+
+    pub fn as_ghz(self) -> f64 {
+        self.0 as f64 / 1e9
+    }
+
+    /// First frequency mentioned in `text`, e.g. "Xeon Gold 6338 2.0GHz" or
+    /// "2600 MHz"
+    pub fn find(text: &str) -> Option<Hertz> {
+        static FREQUENCY: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)(\d+(?:\.\d+)?)\s*([GM])Hz").unwrap());
+        let caps = FREQUENCY.captures(text)?;
+        let value: f64 = caps[1].parse().ok()?;
+        match caps[2].to_ascii_uppercase().as_str() {
+            "G" => Some(Hertz::from_ghz(value)),
+            _ => Some(Hertz::from_mhz(value)),
+        }
+    }
+}
+
+impl Add for Hertz {
+    type Output = Hertz;
+
+    fn add(self, other: Hertz) -> Hertz {
+        Hertz(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sum for Hertz {
+    fn sum<I: Iterator<Item = Hertz>>(iter: I) -> Hertz {
+        iter.fold(Hertz(0), Add::add)
+    }
+}
+
+impl fmt::Display for Hertz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} GHz", self.as_ghz())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rvtools_megabytes_are_mebibytes() {
+        let memory = Bytes::from_mib(1536.0);
+        assert_eq!(memory.as_gib(), 1.5);
+        assert_eq!(memory.gib_ceil(), 2);
+        assert_eq!(memory.gib_floor(), 1);
+        assert_eq!(Bytes::from_gib(1.0), Bytes::from_mib(1024.0));
+    }
+
+    #[test]
+    fn parse_respects_convention_and_explicit_binary_units() {
+        let binary = SizeConvention::Binary;
+        let decimal = SizeConvention::Decimal;
+        assert_eq!(Bytes::parse("2,048 MB", binary), Some(Bytes::from_gib(2.0)));
+        assert_eq!(Bytes::parse("1.92 TB", decimal), Some(Bytes::from_gb(1920.0)));
+        assert_eq!(Bytes::parse("1 TiB", decimal), Some(Bytes::from_gib(1024.0)));
+        assert_eq!(Bytes::parse("4096", binary), Some(Bytes::new(4096)));
+        assert_eq!(Bytes::parse("lots", binary), None);
+    }
+
+    #[test]
+    fn finds_frequencies_in_mhz_or_ghz() {
+        assert_eq!(Hertz::find("Intel Xeon Gold 6338 2.0GHz"), Some(Hertz::from_mhz(2000.0)));
+        assert_eq!(Hertz::find("DDR5 4800 MHz RDIMM").map(|hz| hz.as_ghz()), Some(4.8));
+        assert_eq!(Hertz::find("no speed here"), None);
+    }
+}
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::models::UniversalServer;
 use crate::error::CoreEngineError;
+use crate::hardware_parser::assembly_validation::AssemblyWarning;
+use crate::hardware_parser::server_assembly::ServerAssemblyEngine;
 
 /// Result type for configuration parsing operations
 pub type ParseResult<T> = Result<T, CoreEngineError>;
@@ -34,6 +36,9 @@ pub struct ParsedConfiguration {
     pub source_info: ConfigurationSource,
     /// Any warnings encountered during parsing
     pub warnings: Vec<String>,
+    /// DIMM population, drive bay and power budget findings for the server
+    #[serde(default)]
+    pub assembly_warnings: Vec<AssemblyWarning>,
     /// Pricing information if available
     pub pricing: Option<PricingInfo>,
 }
@@ -119,7 +124,9 @@ impl UniversalConfigurationParser {
         // Try each parser to see which can handle this file
         for parser in &self.parsers {
             if parser.can_parse(file_path).await? {
-                return parser.parse_configuration(file_path).await;
+                let mut parsed = parser.parse_configuration(file_path).await?;
+                parsed.assembly_warnings = ServerAssemblyEngine::new().validate_server(&parsed.server);
+                return Ok(parsed);
             }
         }
         
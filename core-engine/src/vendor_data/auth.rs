@@ -0,0 +1,580 @@
+// Authentication for vendor catalog APIs: static keys, OAuth2 client credentials and mTLS
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Client, Identity, Method, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::CoreEngineError;
+use crate::Result;
+use super::{AuthConfig, AuthType, VendorCredentials};
+
+/// Tokens are refreshed this long before the vendor says they expire
+const TOKEN_REFRESH_SKEW_SECONDS: i64 = 60;
+
+/// Lifetime assumed when a token response omits `expires_in`
+const DEFAULT_TOKEN_LIFETIME_SECONDS: i64 = 3600;
+
+/// Why a vendor could not be authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthFailureKind {
+    /// Required credential material is neither stored nor in the environment
+    MissingCredentials,
+    /// Client certificate or key could not be read or parsed
+    InvalidCertificate,
+    /// Token endpoint refused the client ID, secret or scopes
+    TokenRejected,
+    /// Token endpoint could not be reached (DNS, TLS handshake, timeout)
+    TokenEndpointUnreachable,
+    /// Token endpoint answered with something that is not a token
+    UnexpectedResponse,
+    /// Catalog API refused an authenticated request (401/403)
+    RequestRejected,
+}
+
+/// Most recent authentication failure for a vendor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthFailure {
+    pub kind: AuthFailureKind,
+    pub message: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Authentication diagnostics for one vendor, as reported in cache statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VendorAuthStatus {
+    pub vendor: String,
+    pub auth_type: AuthType,
+    /// Credentials have been supplied through the credential store
+    pub configured: bool,
+    /// Last authentication attempt succeeded and any token is still valid
+    pub authenticated: bool,
+    pub token_expires_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure: Option<AuthFailure>,
+    pub consecutive_failures: u32,
+}
+
+/// Cached OAuth2 access token
+#[derive(Debug, Clone)]
+struct AccessToken {
+    value: String,
+    expires_at: DateTime<Utc>,
+    refresh_token: Option<String>,
+}
+
+impl AccessToken {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - Duration::seconds(TOKEN_REFRESH_SKEW_SECONDS) > now
+    }
+}
+
+/// Token endpoint success body (RFC 6749 section 5.1)
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<i64>,
+    refresh_token: Option<String>,
+}
+
+/// Token endpoint error body (RFC 6749 section 5.2)
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// Mutable session state, held across token requests so concurrent callers
+/// share one refresh instead of each hitting the token endpoint
+struct AuthSession {
+    credentials: Option<VendorCredentials>,
+    client: Client,
+    token: Option<AccessToken>,
+}
+
+/// Authenticates requests to one vendor's catalog API
+pub struct VendorAuthenticator {
+    vendor: String,
+    config: AuthConfig,
+    timeout: StdDuration,
+    session: Mutex<AuthSession>,
+    status: std::sync::Mutex<VendorAuthStatus>,
+}
+
+impl std::fmt::Debug for VendorAuthenticator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VendorAuthenticator")
+            .field("vendor", &self.vendor)
+            .field("auth_type", &self.config.auth_type)
+            .finish()
+    }
+}
+
+impl VendorAuthenticator {
+    /// Create an unconfigured authenticator for a vendor
+    pub fn new(vendor: impl Into<String>, config: AuthConfig, timeout_seconds: u64) -> Self {
+        let vendor = vendor.into();
+        let timeout = StdDuration::from_secs(timeout_seconds);
+        let status = VendorAuthStatus {
+            vendor: vendor.clone(),
+            auth_type: config.auth_type.clone(),
+            configured: false,
+            authenticated: false,
+            token_expires_at: None,
+            last_success_at: None,
+            last_failure: None,
+            consecutive_failures: 0,
+        };
+
+        Self {
+            vendor,
+            config,
+            timeout,
+            session: Mutex::new(AuthSession {
+                credentials: None,
+                client: Client::builder().timeout(timeout).build().unwrap_or_default(),
+                token: None,
+            }),
+            status: std::sync::Mutex::new(status),
+        }
+    }
+
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    /// Current diagnostics for this vendor
+    pub fn status(&self) -> VendorAuthStatus {
+        let mut status = self.lock_status().clone();
+        if status.token_expires_at.is_some_and(|expires| expires <= Utc::now()) {
+            status.authenticated = false;
+        }
+        status
+    }
+
+    /// Install credentials and authenticate eagerly, so bad secrets or
+    /// certificates surface when they are saved rather than on first fetch
+    pub async fn configure(&self, credentials: VendorCredentials) -> Result<()> {
+        let mut session = self.session.lock().await;
+        session.token = None;
+        session.credentials = None;
+        self.lock_status().configured = true;
+
+        let client = self
+            .check_static_credentials(&credentials)
+            .and_then(|_| self.build_client(&credentials))
+            .map_err(|failure| self.record_failure(failure))?;
+        session.client = client;
+        session.credentials = Some(credentials);
+
+        if matches!(self.config.auth_type, AuthType::OAuth2 | AuthType::BearerToken) {
+            self.ensure_token(&mut session).await?;
+        } else {
+            self.record_success(None);
+        }
+        Ok(())
+    }
+
+    /// Build an authenticated request against the vendor's API
+    ///
+    /// Uses the mTLS-enabled client when a certificate is configured and
+    /// attaches a cached OAuth2 token, refreshing it shortly before expiry.
+    pub async fn request(&self, method: Method, url: &str) -> Result<RequestBuilder> {
+        let mut session = self.session.lock().await;
+        let Some(credentials) = session.credentials.clone() else {
+            return Err(self.record_failure(failure(
+                AuthFailureKind::MissingCredentials,
+                format!("No credentials configured for {}", self.vendor),
+            )));
+        };
+
+        let request = session.client.request(method, url);
+        match self.config.auth_type {
+            AuthType::OAuth2 | AuthType::BearerToken => {
+                let token = self.ensure_token(&mut session).await?;
+                Ok(request.bearer_auth(token))
+            }
+            AuthType::ApiKey => {
+                let api_key = resolve(credentials.api_key.as_ref(), self.config.api_key_env.as_ref())
+                    .ok_or_else(|| self.record_failure(missing(&self.vendor, "API key")))?;
+                Ok(request.header("X-API-Key", api_key))
+            }
+            AuthType::BasicAuth => {
+                let username = resolve(credentials.username.as_ref(), self.config.client_id_env.as_ref())
+                    .ok_or_else(|| self.record_failure(missing(&self.vendor, "username")))?;
+                let password = resolve(credentials.password.as_ref(), self.config.client_secret_env.as_ref());
+                Ok(request.basic_auth(username, password))
+            }
+            AuthType::ClientCertificate => Ok(request),
+        }
+    }
+
+    /// Record the outcome of a catalog call so rejected credentials show up
+    /// in diagnostics; a 401 also drops the cached token so the next call
+    /// fetches a new one
+    pub async fn observe_response(&self, status: StatusCode) {
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            if status == StatusCode::UNAUTHORIZED {
+                self.session.lock().await.token = None;
+            }
+            self.record_failure(failure(
+                AuthFailureKind::RequestRejected,
+                format!("{} API rejected the request with {}", self.vendor, status),
+            ));
+        } else if status.is_success() {
+            let expires_at = self.session.lock().await.token.as_ref().map(|t| t.expires_at);
+            self.record_success(expires_at);
+        }
+    }
+
+    /// Return a fresh token, reusing the cached one while it is valid and
+    /// preferring the refresh grant over new client credentials
+    async fn ensure_token(&self, session: &mut AuthSession) -> Result<String> {
+        let now = Utc::now();
+        if let Some(token) = session.token.as_ref().filter(|t| t.is_fresh(now)) {
+            return Ok(token.value.clone());
+        }
+
+        let credentials = session.credentials.clone().unwrap_or_else(|| empty_credentials(&self.vendor));
+        let fetched = match session.token.take().and_then(|t| t.refresh_token) {
+            Some(refresh_token) => match self.fetch_token(&session.client, &credentials, Some(&refresh_token)).await {
+                Ok(token) => Ok(token),
+                Err(_) => self.fetch_token(&session.client, &credentials, None).await,
+            },
+            None => self.fetch_token(&session.client, &credentials, None).await,
+        };
+
+        match fetched {
+            Ok(token) => {
+                let value = token.value.clone();
+                self.record_success(Some(token.expires_at));
+                session.token = Some(token);
+                Ok(value)
+            }
+            Err(failure) => Err(self.record_failure(failure)),
+        }
+    }
+
+    async fn fetch_token(
+        &self,
+        client: &Client,
+        credentials: &VendorCredentials,
+        refresh_token: Option<&str>,
+    ) -> std::result::Result<AccessToken, AuthFailure> {
+        let token_url = self
+            .config
+            .token_url
+            .clone()
+            .ok_or_else(|| missing(&self.vendor, "token URL"))?;
+        let client_id = resolve(credentials.client_id.as_ref(), self.config.client_id_env.as_ref())
+            .ok_or_else(|| missing(&self.vendor, "OAuth2 client ID"))?;
+        let client_secret = resolve(credentials.client_secret.as_ref(), self.config.client_secret_env.as_ref())
+            .ok_or_else(|| missing(&self.vendor, "OAuth2 client secret"))?;
+
+        let scope = self.config.scopes.as_ref().map(|s| s.join(" ")).unwrap_or_default();
+        let mut form = vec![("grant_type", "client_credentials"), ("scope", scope.as_str())];
+        if let Some(refresh_token) = refresh_token {
+            form = vec![("grant_type", "refresh_token"), ("refresh_token", refresh_token)];
+        }
+        form.retain(|(_, value)| !value.is_empty());
+
+        let response = client
+            .post(&token_url)
+            .basic_auth(&client_id, Some(&client_secret))
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| failure(
+                AuthFailureKind::TokenEndpointUnreachable,
+                format!("{} token endpoint {} unreachable: {}", self.vendor, token_url, e),
+            ))?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            let detail = serde_json::from_str::<TokenErrorResponse>(&body)
+                .map(|e| match e.error_description {
+                    Some(description) => format!("{} ({})", e.error, description),
+                    None => e.error,
+                })
+                .unwrap_or_else(|_| "no error detail".to_string());
+            let kind = if status.is_client_error() {
+                AuthFailureKind::TokenRejected
+            } else {
+                AuthFailureKind::UnexpectedResponse
+            };
+            return Err(failure(kind, format!("{} token endpoint returned {}: {}", self.vendor, status, detail)));
+        }
+
+        let token: TokenResponse = serde_json::from_str(&body).map_err(|e| failure(
+            AuthFailureKind::UnexpectedResponse,
+            format!("{} token endpoint returned an unreadable token: {}", self.vendor, e),
+        ))?;
+        let lifetime = token.expires_in.unwrap_or(DEFAULT_TOKEN_LIFETIME_SECONDS);
+
+        Ok(AccessToken {
+            value: token.access_token,
+            expires_at: Utc::now() + Duration::seconds(lifetime),
+            refresh_token: token.refresh_token,
+        })
+    }
+
+    /// HTTP client for this vendor, carrying the client certificate for mTLS
+    fn build_client(&self, credentials: &VendorCredentials) -> std::result::Result<Client, AuthFailure> {
+        let mut builder = Client::builder().timeout(self.timeout);
+
+        if matches!(self.config.auth_type, AuthType::ClientCertificate) {
+            let certificate_env = self.config.client_certificate_env.as_ref();
+            let certificate = pem(credentials.client_certificate_pem.as_ref(), certificate_env)
+                .map_err(|e| failure(AuthFailureKind::InvalidCertificate, format!("{}: {}", self.vendor, e)))?
+                .ok_or_else(|| missing(&self.vendor, "client certificate"))?;
+            let key = pem(credentials.client_key_pem.as_ref(), self.config.client_key_env.as_ref())
+                .map_err(|e| failure(AuthFailureKind::InvalidCertificate, format!("{}: {}", self.vendor, e)))?
+                .ok_or_else(|| missing(&self.vendor, "client private key"))?;
+
+            let identity = Identity::from_pem(format!("{}\n{}", certificate.trim(), key.trim()).as_bytes())
+                .map_err(|e| failure(
+                    AuthFailureKind::InvalidCertificate,
+                    format!("{} client certificate or key is not valid PEM: {}", self.vendor, e),
+                ))?;
+            builder = builder.identity(identity);
+        }
+
+        builder.build().map_err(|e| failure(
+            AuthFailureKind::InvalidCertificate,
+            format!("{} HTTP client could not be built: {}", self.vendor, e),
+        ))
+    }
+
+    fn check_static_credentials(&self, credentials: &VendorCredentials) -> std::result::Result<(), AuthFailure> {
+        match self.config.auth_type {
+            AuthType::ApiKey => resolve(credentials.api_key.as_ref(), self.config.api_key_env.as_ref())
+                .map(|_| ())
+                .ok_or_else(|| missing(&self.vendor, "API key")),
+            AuthType::BasicAuth => resolve(credentials.username.as_ref(), self.config.client_id_env.as_ref())
+                .map(|_| ())
+                .ok_or_else(|| missing(&self.vendor, "username")),
+            _ => Ok(()),
+        }
+    }
+
+    fn lock_status(&self) -> std::sync::MutexGuard<'_, VendorAuthStatus> {
+        self.status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record_success(&self, token_expires_at: Option<DateTime<Utc>>) {
+        let mut status = self.lock_status();
+        status.authenticated = true;
+        status.token_expires_at = token_expires_at;
+        status.last_success_at = Some(Utc::now());
+        status.consecutive_failures = 0;
+    }
+
+    fn record_failure(&self, failure: AuthFailure) -> CoreEngineError {
+        tracing::warn!(vendor = %self.vendor, kind = ?failure.kind, "vendor authentication failed");
+        let error = CoreEngineError::authentication(failure.message.clone());
+        let mut status = self.lock_status();
+        status.authenticated = false;
+        status.token_expires_at = None;
+        status.consecutive_failures += 1;
+        status.last_failure = Some(failure);
+        error
+    }
+}
+
+fn failure(kind: AuthFailureKind, message: String) -> AuthFailure {
+    AuthFailure { kind, message, occurred_at: Utc::now() }
+}
+
+fn missing(vendor: &str, what: &str) -> AuthFailure {
+    failure(
+        AuthFailureKind::MissingCredentials,
+        format!("No {} configured for {} in the credential store or environment", what, vendor),
+    )
+}
+
+fn empty_credentials(vendor: &str) -> VendorCredentials {
+    VendorCredentials {
+        vendor: vendor.to_string(),
+        ..VendorCredentials::default()
+    }
+}
+
+/// Stored value first, then the named environment variable
+fn resolve(stored: Option<&String>, env: Option<&String>) -> Option<String> {
+    stored
+        .filter(|value| !value.is_empty())
+        .cloned()
+        .or_else(|| env.and_then(|name| std::env::var(name).ok()).filter(|value| !value.is_empty()))
+}
+
+/// Stored PEM text first, then a PEM file named by the environment variable
+fn pem(stored: Option<&String>, path_env: Option<&String>) -> std::result::Result<Option<String>, String> {
+    if let Some(stored) = stored.filter(|value| !value.is_empty()) {
+        return Ok(Some(stored.clone()));
+    }
+    match path_env.and_then(|name| std::env::var(name).ok()).filter(|path| !path.is_empty()) {
+        Some(path) => std::fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|e| format!("failed to read PEM file {}: {}", path, e)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn oauth_config(token_url: &str) -> AuthConfig {
+        AuthConfig {
+            auth_type: AuthType::OAuth2,
+            client_id_env: None,
+            client_secret_env: None,
+            api_key_env: None,
+            token_url: Some(token_url.to_string()),
+            scopes: Some(vec!["catalog:read".to_string()]),
+            client_certificate_env: None,
+            client_key_env: None,
+        }
+    }
+
+    fn oauth_credentials() -> VendorCredentials {
+        VendorCredentials {
+            vendor: "Dell".to_string(),
+            client_id: Some("archer".to_string()),
+            client_secret: Some("secret".to_string()),
+            ..VendorCredentials::default()
+        }
+    }
+
+    /// Token endpoint answering every request with `body` and `status`
+    fn token_server(status: &'static str, body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                read_request(&mut stream);
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (url, hits)
+    }
+
+    /// Drain headers and body so closing the socket does not reset the connection
+    fn read_request(stream: &mut std::net::TcpStream) {
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        while let Ok(read) = stream.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let line = line.to_ascii_lowercase();
+                        line.strip_prefix("content-length:").map(|v| v.trim().to_string())
+                    })
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_oauth2_token_is_cached_until_near_expiry() {
+        let (url, hits) = token_server("200 OK", r#"{"access_token":"abc","token_type":"Bearer","expires_in":3600}"#);
+        let auth = VendorAuthenticator::new("Dell", oauth_config(&url), 5);
+
+        tokio_test::block_on(async {
+            auth.configure(oauth_credentials()).await.unwrap();
+            auth.request(Method::GET, "http://127.0.0.1:1/servers").await.unwrap();
+            auth.request(Method::GET, "http://127.0.0.1:1/servers").await.unwrap();
+            assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+            // A token inside the refresh window is replaced on next use
+            auth.session.lock().await.token.as_mut().unwrap().expires_at = Utc::now() + Duration::seconds(30);
+            auth.request(Method::GET, "http://127.0.0.1:1/servers").await.unwrap();
+            assert_eq!(hits.load(Ordering::SeqCst), 2);
+        });
+
+        let status = auth.status();
+        assert!(status.authenticated);
+        assert!(status.token_expires_at.unwrap() > Utc::now() + Duration::minutes(50));
+        assert_eq!(status.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_rejected_client_secret_is_reported() {
+        let (url, _) = token_server(
+            "401 Unauthorized",
+            r#"{"error":"invalid_client","error_description":"Client authentication failed"}"#,
+        );
+        let auth = VendorAuthenticator::new("Dell", oauth_config(&url), 5);
+
+        let result = tokio_test::block_on(auth.configure(oauth_credentials()));
+        assert!(result.is_err());
+
+        let status = auth.status();
+        assert!(status.configured);
+        assert!(!status.authenticated);
+        let failure = status.last_failure.unwrap();
+        assert_eq!(failure.kind, AuthFailureKind::TokenRejected);
+        assert!(failure.message.contains("invalid_client"));
+        assert!(failure.message.contains("Client authentication failed"));
+    }
+
+    #[test]
+    fn test_client_certificate_failures_are_diagnosed() {
+        let config = AuthConfig {
+            auth_type: AuthType::ClientCertificate,
+            ..oauth_config("http://127.0.0.1:1/token")
+        };
+        let auth = VendorAuthenticator::new("HPE", config, 5);
+
+        let no_key = VendorCredentials {
+            vendor: "HPE".to_string(),
+            client_certificate_pem: Some("-----BEGIN CERTIFICATE-----\n-----END CERTIFICATE-----".to_string()),
+            ..VendorCredentials::default()
+        };
+        assert!(tokio_test::block_on(auth.configure(no_key)).is_err());
+        assert_eq!(auth.status().last_failure.unwrap().kind, AuthFailureKind::MissingCredentials);
+
+        let garbage = VendorCredentials {
+            vendor: "HPE".to_string(),
+            client_certificate_pem: Some("not a certificate".to_string()),
+            client_key_pem: Some("not a key".to_string()),
+            ..VendorCredentials::default()
+        };
+        assert!(tokio_test::block_on(auth.configure(garbage)).is_err());
+        let status = auth.status();
+        assert_eq!(status.last_failure.unwrap().kind, AuthFailureKind::InvalidCertificate);
+        assert_eq!(status.consecutive_failures, 2);
+
+        // Nothing usable was installed, so requests fail rather than go out unauthenticated
+        let request = tokio_test::block_on(auth.request(Method::GET, "https://example.invalid/servers"));
+        assert!(request.is_err());
+    }
+}
@@ -391,7 +391,7 @@ impl VendorDataCache {
 }
 
 /// Cache statistics
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CacheStats {
     pub memory_entries: usize,
     pub disk_size_bytes: u64,
@@ -31,6 +31,12 @@ pub struct AuthConfig {
     pub api_key_env: Option<String>,
     pub token_url: Option<String>,
     pub scopes: Option<Vec<String>>,
+    /// Environment variable naming a PEM client certificate file (mTLS)
+    #[serde(default)]
+    pub client_certificate_env: Option<String>,
+    /// Environment variable naming the PEM private key for the client certificate
+    #[serde(default)]
+    pub client_key_env: Option<String>,
 }
 
 /// Authentication type
@@ -41,6 +47,8 @@ pub enum AuthType {
     OAuth2,
     BasicAuth,
     BearerToken,
+    /// Mutual TLS with a client certificate
+    ClientCertificate,
 }
 
 /// Rate limiting configuration
@@ -127,6 +135,8 @@ impl Default for VendorApiConfig {
                     "configurator:read".to_string(),
                     "pricing:read".to_string()
                 ]),
+                client_certificate_env: None,
+                client_key_env: None,
             },
             rate_limiting: RateLimitConfig {
                 requests_per_minute: 100,
@@ -159,6 +169,8 @@ impl Default for VendorApiConfig {
                 api_key_env: Some("HPE_API_KEY".to_string()),
                 token_url: None,
                 scopes: None,
+                client_certificate_env: None,
+                client_key_env: None,
             },
             rate_limiting: RateLimitConfig {
                 requests_per_minute: 60,
@@ -191,6 +203,8 @@ impl Default for VendorApiConfig {
                 api_key_env: None,
                 token_url: Some("https://auth.lenovo.com/token".to_string()),
                 scopes: Some(vec!["products:read".to_string()]),
+                client_certificate_env: None,
+                client_key_env: None,
             },
             rate_limiting: RateLimitConfig {
                 requests_per_minute: 80,
@@ -329,6 +343,14 @@ impl VendorApiConfig {
                             errors.push(format!("{}: Password environment variable not specified", vendor_name));
                         }
                     },
+                    AuthType::ClientCertificate => {
+                        // Certificates normally come from the credential store; a
+                        // half-configured environment fallback is still a mistake
+                        let auth = &config.authentication;
+                        if auth.client_certificate_env.is_some() != auth.client_key_env.is_some() {
+                            errors.push(format!("{}: Client certificate and key must be configured together", vendor_name));
+                        }
+                    },
                 }
                 
                 // Validate rate limiting
@@ -383,4 +405,25 @@ mod tests {
         assert_eq!(lenovo_config.api_version, "v1");
         assert!(matches!(lenovo_config.authentication.auth_type, AuthType::BearerToken));
     }
+
+    #[test]
+    fn test_client_certificate_config() {
+        let mut config = VendorApiConfig::default();
+        let hpe = config.vendors.get_mut("hpe").unwrap();
+        hpe.authentication.auth_type = AuthType::ClientCertificate;
+        assert!(config.validate().is_empty());
+
+        config.vendors.get_mut("hpe").unwrap().authentication.client_certificate_env = Some("HPE_CLIENT_CERT".to_string());
+        let errors = config.validate();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("certificate and key"));
+
+        // Configs saved before mTLS support still load
+        let auth: AuthConfig = serde_json::from_str(
+            r#"{"auth_type":"client_certificate","client_id_env":null,"client_secret_env":null,
+                "api_key_env":null,"token_url":null,"scopes":null}"#,
+        ).unwrap();
+        assert!(matches!(auth.auth_type, AuthType::ClientCertificate));
+        assert!(auth.client_certificate_env.is_none());
+    }
 }
\ No newline at end of file
@@ -125,6 +125,7 @@ impl DellCatalogClient {
                 power_supply_options: vec!["750W".to_string(), "1100W".to_string(), "1400W".to_string()],
                 launch_date: Some("2021-05-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://www.dell.com/en-us/work/shop/productdetailstxn/poweredge-r750".to_string()),
                 quickspecs_url: Some("https://i.dell.com/sites/csdocuments/CorpComm_Docs/en/poweredge-r750-spec-sheet.pdf".to_string()),
             },
@@ -141,6 +142,7 @@ impl DellCatalogClient {
                 power_supply_options: vec!["600W".to_string(), "800W".to_string(), "1100W".to_string()],
                 launch_date: Some("2021-05-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://www.dell.com/en-us/work/shop/productdetailstxn/poweredge-r650".to_string()),
                 quickspecs_url: Some("https://i.dell.com/sites/csdocuments/CorpComm_Docs/en/poweredge-r650-spec-sheet.pdf".to_string()),
             },
@@ -157,6 +159,7 @@ impl DellCatalogClient {
                 power_supply_options: vec!["495W".to_string(), "750W".to_string()],
                 launch_date: Some("2021-05-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://www.dell.com/en-us/work/shop/productdetailstxn/poweredge-t550".to_string()),
                 quickspecs_url: Some("https://i.dell.com/sites/csdocuments/CorpComm_Docs/en/poweredge-t550-spec-sheet.pdf".to_string()),
             },
@@ -173,6 +176,7 @@ impl DellCatalogClient {
                 power_supply_options: vec!["750W".to_string(), "1100W".to_string()],
                 launch_date: Some("2020-08-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://www.dell.com/en-us/work/shop/productdetailstxn/poweredge-r7525".to_string()),
                 quickspecs_url: Some("https://i.dell.com/sites/csdocuments/CorpComm_Docs/en/poweredge-r7525-spec-sheet.pdf".to_string()),
             },
@@ -205,6 +209,7 @@ impl DellCatalogClient {
             power_supply_options: vec!["750W".to_string(), "1100W".to_string(), "1400W".to_string()],
             launch_date: Some("2021-05-01T00:00:00Z".parse().unwrap()),
             end_of_sale: None,
+            end_of_life: None,
             product_brief_url: Some("https://www.dell.com/en-us/work/shop/productdetailstxn/poweredge-r750".to_string()),
             quickspecs_url: Some("https://i.dell.com/sites/csdocuments/CorpComm_Docs/en/poweredge-r750-spec-sheet.pdf".to_string()),
         };
@@ -541,6 +546,8 @@ impl VendorCatalogClient for DellCatalogClient {
                 pricing: None,
                 confidence_score: confidence,
                 reasoning: "Based on workload requirements and server capabilities".to_string(),
+                lifecycle: None,
+                objective_scores: None,
             });
         }
         
@@ -287,6 +287,7 @@ impl DellCatalogClient {
     }
     
     /// Execute authenticated API request with rate limiting
+    #[tracing::instrument(name = "vendor.dell.request", skip(self), fields(otel.kind = "client"))]
     async fn api_request<T: for<'de> Deserialize<'de>>(&self, endpoint: &str) -> Result<T> {
         // Rate limiting
         if let Some(limiter) = &self.rate_limiter {
@@ -373,6 +374,7 @@ impl DellCatalogClient {
                 .as_ref()
                 .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
                 .map(|d| d.with_timezone(&chrono::Utc)),
+            end_of_life: None,
             product_brief_url: Some(format!("https://dell.com/products/{}", config.id)),
             quickspecs_url: Some(format!("https://dell.com/specs/{}", config.id)),
         }
@@ -644,6 +646,8 @@ impl VendorCatalogClient for DellCatalogClient {
                     }),
                     confidence_score: 0.9,
                     reasoning: format!("Dell {} recommended based on your requirements", config.name),
+                    lifecycle: None,
+                    objective_scores: None,
                 }
             })
             .collect())
@@ -46,6 +46,7 @@ impl HPECatalogClient {
                 power_supply_options: vec!["800W".to_string(), "1600W".to_string()],
                 launch_date: Some("2019-04-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://www.hpe.com/us/en/product-catalog/servers/proliant-servers/pip.hpe-proliant-dl380-gen10-server.1010007891.html".to_string()),
                 quickspecs_url: Some("https://www.hpe.com/h20195/v2/getdocument.aspx?docname=a00008180enw".to_string()),
             },
@@ -62,6 +63,7 @@ impl HPECatalogClient {
                 power_supply_options: vec!["500W".to_string(), "800W".to_string()],
                 launch_date: Some("2019-04-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://www.hpe.com/us/en/product-catalog/servers/proliant-servers/pip.hpe-proliant-dl360-gen10-server.1010007889.html".to_string()),
                 quickspecs_url: Some("https://www.hpe.com/h20195/v2/getdocument.aspx?docname=a00008179enw".to_string()),
             },
@@ -78,6 +80,7 @@ impl HPECatalogClient {
                 power_supply_options: vec!["500W".to_string(), "800W".to_string()],
                 launch_date: Some("2019-04-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://www.hpe.com/us/en/product-catalog/servers/proliant-servers/pip.hpe-proliant-ml350-gen10-server.1010007893.html".to_string()),
                 quickspecs_url: Some("https://www.hpe.com/h20195/v2/getdocument.aspx?docname=a00008181enw".to_string()),
             },
@@ -46,6 +46,7 @@ impl LenovoCatalogClient {
                 power_supply_options: vec!["750W".to_string(), "1100W".to_string()],
                 launch_date: Some("2020-03-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://lenovopress.lenovo.com/lp0644.pdf".to_string()),
                 quickspecs_url: Some("https://lenovopress.lenovo.com/lp0644-lenovo-thinksystem-sr650-server".to_string()),
             },
@@ -62,6 +63,7 @@ impl LenovoCatalogClient {
                 power_supply_options: vec!["550W".to_string(), "750W".to_string()],
                 launch_date: Some("2020-03-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://lenovopress.lenovo.com/lp0643.pdf".to_string()),
                 quickspecs_url: Some("https://lenovopress.lenovo.com/lp0643-lenovo-thinksystem-sr630-server".to_string()),
             },
@@ -78,6 +80,7 @@ impl LenovoCatalogClient {
                 power_supply_options: vec!["550W".to_string(), "750W".to_string()],
                 launch_date: Some("2020-03-01T00:00:00Z".parse().unwrap()),
                 end_of_sale: None,
+                end_of_life: None,
                 product_brief_url: Some("https://lenovopress.lenovo.com/lp0646.pdf".to_string()),
                 quickspecs_url: Some("https://lenovopress.lenovo.com/lp0646-lenovo-thinksystem-st550-tower-server".to_string()),
             },
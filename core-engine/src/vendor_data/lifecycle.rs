@@ -0,0 +1,380 @@
+// Server model lifecycle (end-of-sale / end-of-life) assessment for recommendations
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{RecommendedConfiguration, ServerModel};
+
+/// Confidence taken off a model that leaves sale within the warning window
+const NEAR_END_OF_SALE_PENALTY: f32 = 0.15;
+/// Confidence taken off a model already past end-of-sale (when not excluded)
+const END_OF_SALE_PENALTY: f32 = 0.3;
+/// Confidence taken off a model superseded within its family
+const PREVIOUS_GENERATION_PENALTY: f32 = 0.1;
+/// Maximum confidence taken off when support ends before the planning horizon,
+/// scaled by how much of the horizon is uncovered
+const SHORT_RUNWAY_PENALTY: f32 = 0.2;
+
+/// How lifecycle dates affect recommendations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifecyclePolicy {
+    /// Months before end-of-sale at which a model counts as near end-of-sale
+    pub near_end_of_sale_months: u32,
+    /// Drop models already past end-of-sale instead of penalizing them
+    pub exclude_end_of_sale: bool,
+    /// Drop models near end-of-sale instead of penalizing them
+    pub exclude_near_end_of_sale: bool,
+    /// Models launched within this many months of their family's newest
+    /// launch count as current generation
+    pub generation_window_months: u32,
+    /// Support assumed after end-of-sale when no end-of-life date is published
+    pub assumed_support_after_sale_months: u32,
+    /// Time on sale assumed when a model has a launch date but no end-of-sale
+    pub assumed_sale_life_months: u32,
+}
+
+impl Default for LifecyclePolicy {
+    fn default() -> Self {
+        Self {
+            near_end_of_sale_months: 12,
+            exclude_end_of_sale: true,
+            exclude_near_end_of_sale: false,
+            generation_window_months: 24,
+            assumed_support_after_sale_months: 60,
+            assumed_sale_life_months: 48,
+        }
+    }
+}
+
+/// Where a model is in its vendor lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleStatus {
+    /// On sale, with no end-of-sale inside the warning window
+    Active,
+    /// End-of-sale falls within `near_end_of_sale_months`
+    NearEndOfSale,
+    /// No longer sold but still supported
+    EndOfSale,
+    /// No longer supported
+    EndOfLife,
+}
+
+/// Lifecycle annotation attached to a recommendation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleAssessment {
+    pub status: LifecycleStatus,
+    /// Launched within the generation window of the family's newest model
+    pub current_generation: bool,
+    /// Negative once the model is past end-of-sale
+    pub months_to_end_of_sale: Option<i32>,
+    pub support_ends: Option<DateTime<Utc>>,
+    /// `support_ends` is an estimate from end-of-sale or launch dates
+    pub support_end_projected: bool,
+    /// Months of vendor support left from the assessment date
+    pub support_runway_months: Option<i32>,
+    pub planning_horizon_months: Option<u32>,
+    /// Whether support lasts the planning horizon; unknown without dates or a horizon
+    pub covers_planning_horizon: Option<bool>,
+    /// Confidence removed from the recommendation for lifecycle reasons
+    pub confidence_penalty: f32,
+    pub notes: Vec<String>,
+}
+
+/// Whole months from `from` to `to`, negative when `to` is earlier
+fn months_between(from: DateTime<Utc>, to: DateTime<Utc>) -> i32 {
+    let mut months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    if to >= from && to.day() < from.day() {
+        months -= 1;
+    } else if to < from && to.day() > from.day() {
+        months += 1;
+    }
+    months
+}
+
+fn add_months(date: DateTime<Utc>, months: u32) -> DateTime<Utc> {
+    date.checked_add_months(Months::new(months)).unwrap_or(date)
+}
+
+fn family_key(model: &ServerModel) -> (String, String) {
+    (model.vendor.to_lowercase(), model.family.to_lowercase())
+}
+
+/// Newest launch date in each vendor family
+pub(crate) fn newest_launches<'a>(
+    models: impl IntoIterator<Item = &'a ServerModel>,
+) -> HashMap<(String, String), DateTime<Utc>> {
+    let mut newest: HashMap<(String, String), DateTime<Utc>> = HashMap::new();
+    for model in models {
+        if let Some(launch) = model.launch_date {
+            newest
+                .entry(family_key(model))
+                .and_modify(|date| *date = (*date).max(launch))
+                .or_insert(launch);
+        }
+    }
+    newest
+}
+
+/// Assess one model against the policy and planning horizon
+pub(crate) fn assess(
+    model: &ServerModel,
+    newest_in_family: Option<DateTime<Utc>>,
+    planning_horizon_months: Option<u32>,
+    policy: &LifecyclePolicy,
+    now: DateTime<Utc>,
+) -> LifecycleAssessment {
+    let mut notes = Vec::new();
+    let mut penalty = 0.0;
+
+    let (support_ends, support_end_projected) = match (model.end_of_life, model.end_of_sale, model.launch_date) {
+        (Some(end_of_life), _, _) => (Some(end_of_life), false),
+        (None, Some(end_of_sale), _) => {
+            (Some(add_months(end_of_sale, policy.assumed_support_after_sale_months)), true)
+        }
+        (None, None, Some(launch)) => {
+            let sale_months = policy.assumed_sale_life_months + policy.assumed_support_after_sale_months;
+            (Some(add_months(launch, sale_months)), true)
+        }
+        (None, None, None) => (None, false),
+    };
+
+    let months_to_end_of_sale = model.end_of_sale.map(|date| months_between(now, date));
+    let status = if support_ends.is_some_and(|date| date <= now && !support_end_projected) {
+        LifecycleStatus::EndOfLife
+    } else if model.end_of_sale.is_some_and(|date| date <= now) {
+        LifecycleStatus::EndOfSale
+    } else if months_to_end_of_sale.is_some_and(|months| months < policy.near_end_of_sale_months as i32) {
+        LifecycleStatus::NearEndOfSale
+    } else {
+        LifecycleStatus::Active
+    };
+
+    match status {
+        LifecycleStatus::EndOfLife => notes.push("Vendor support has ended".to_string()),
+        LifecycleStatus::EndOfSale => {
+            penalty += END_OF_SALE_PENALTY;
+            notes.push("No longer sold; replacements and expansion may be hard to source".to_string());
+        }
+        LifecycleStatus::NearEndOfSale => {
+            penalty += NEAR_END_OF_SALE_PENALTY;
+            notes.push(format!(
+                "End of sale in {} months",
+                months_to_end_of_sale.unwrap_or_default().max(0)
+            ));
+        }
+        LifecycleStatus::Active => {}
+    }
+
+    let current_generation = match (model.launch_date, newest_in_family) {
+        (Some(launch), Some(newest)) => months_between(launch, newest) <= policy.generation_window_months as i32,
+        _ => true,
+    };
+    if !current_generation {
+        penalty += PREVIOUS_GENERATION_PENALTY;
+        notes.push(format!("A newer {} generation is available", model.family));
+    }
+
+    let support_runway_months = support_ends.map(|date| months_between(now, date));
+    let covers_planning_horizon = match (support_runway_months, planning_horizon_months) {
+        (Some(runway), Some(horizon)) => Some(runway >= horizon as i32),
+        _ => None,
+    };
+    if let (Some(false), Some(runway), Some(horizon)) =
+        (covers_planning_horizon, support_runway_months, planning_horizon_months)
+    {
+        let shortfall = (horizon as i32 - runway.max(0)) as f32 / horizon.max(1) as f32;
+        penalty += SHORT_RUNWAY_PENALTY * shortfall.min(1.0);
+        let subject = if support_end_projected { "Projected support" } else { "Support" };
+        notes.push(format!(
+            "{} ends {} months into the {}-month planning horizon",
+            subject,
+            runway.max(0),
+            horizon
+        ));
+    }
+
+    LifecycleAssessment {
+        status,
+        current_generation,
+        months_to_end_of_sale,
+        support_ends,
+        support_end_projected,
+        support_runway_months,
+        planning_horizon_months,
+        covers_planning_horizon,
+        confidence_penalty: penalty,
+        notes,
+    }
+}
+
+/// Annotate recommendations with lifecycle status, drop the ones the policy
+/// excludes and lower confidence for the rest
+///
+/// `catalog` supplies the family peers used to tell current from previous
+/// generations; the recommended models themselves are always included.
+pub(crate) fn apply(
+    recommendations: Vec<RecommendedConfiguration>,
+    catalog: &[ServerModel],
+    planning_horizon_months: Option<u32>,
+    policy: &LifecyclePolicy,
+    now: DateTime<Utc>,
+) -> Vec<RecommendedConfiguration> {
+    let newest = newest_launches(catalog.iter().chain(recommendations.iter().map(|r| &r.model)));
+
+    recommendations
+        .into_iter()
+        .filter_map(|mut recommendation| {
+            let newest_in_family = newest.get(&family_key(&recommendation.model)).copied();
+            let assessment = assess(&recommendation.model, newest_in_family, planning_horizon_months, policy, now);
+
+            let excluded = match assessment.status {
+                LifecycleStatus::EndOfLife => true,
+                LifecycleStatus::EndOfSale => policy.exclude_end_of_sale,
+                LifecycleStatus::NearEndOfSale => policy.exclude_near_end_of_sale,
+                LifecycleStatus::Active => false,
+            };
+            if excluded {
+                return None;
+            }
+
+            let score = recommendation.confidence_score - assessment.confidence_penalty;
+            recommendation.confidence_score = score.max(0.0);
+            recommendation.lifecycle = Some(assessment);
+            Some(recommendation)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vendor_data::{ComponentBundle, FormFactor, PerformanceEstimate};
+
+    fn date(value: &str) -> DateTime<Utc> {
+        format!("{}T00:00:00Z", value).parse().unwrap()
+    }
+
+    fn model(id: &str, launch: &str, end_of_sale: Option<&str>) -> ServerModel {
+        ServerModel {
+            vendor: "Dell".to_string(),
+            model_id: id.to_string(),
+            model_name: id.to_string(),
+            family: "PowerEdge".to_string(),
+            form_factor: FormFactor::TwoU,
+            cpu_sockets: 2,
+            max_memory_gb: 4096,
+            drive_bays: 16,
+            pcie_slots: 8,
+            power_supply_options: vec![],
+            launch_date: Some(date(launch)),
+            end_of_sale: end_of_sale.map(date),
+            end_of_life: None,
+            product_brief_url: None,
+            quickspecs_url: None,
+        }
+    }
+
+    fn recommendation(model: ServerModel) -> RecommendedConfiguration {
+        RecommendedConfiguration {
+            configuration_id: model.model_id.clone(),
+            model,
+            recommended_components: ComponentBundle {
+                cpus: vec![],
+                memory: vec![],
+                storage_controllers: vec![],
+                drives: vec![],
+                network_adapters: vec![],
+                power_supplies: vec![],
+                additional_options: vec![],
+            },
+            performance_metrics: PerformanceEstimate {
+                cpu_performance_score: 80.0,
+                memory_bandwidth_gbps: 200.0,
+                storage_iops: 50000,
+                network_throughput_gbps: 25.0,
+                power_consumption_watts: 500,
+                thermal_output_btu: 1700,
+            },
+            pricing: None,
+            confidence_score: 0.8,
+            reasoning: String::new(),
+            lifecycle: None,
+            objective_scores: None,
+        }
+    }
+
+    #[test]
+    fn test_end_of_sale_models_are_excluded_and_near_eos_penalized() {
+        let now = date("2026-10-15");
+        let recommendations = vec![
+            recommendation(model("r760", "2023-08-01", None)),
+            recommendation(model("r750", "2021-05-01", Some("2027-03-01"))),
+            recommendation(model("r740", "2017-07-01", Some("2024-01-31"))),
+        ];
+
+        let result = apply(recommendations, &[], Some(36), &LifecyclePolicy::default(), now);
+        let ids: Vec<_> = result.iter().map(|r| r.model.model_id.as_str()).collect();
+        assert_eq!(ids, vec!["r760", "r750"]);
+
+        let current = result[0].lifecycle.as_ref().unwrap();
+        assert_eq!(current.status, LifecycleStatus::Active);
+        assert!(current.current_generation);
+        assert_eq!(result[0].confidence_score, 0.8);
+
+        let near = result[1].lifecycle.as_ref().unwrap();
+        assert_eq!(near.status, LifecycleStatus::NearEndOfSale);
+        assert_eq!(near.months_to_end_of_sale, Some(4));
+        assert!(!near.current_generation);
+        assert!(result[1].confidence_score < result[0].confidence_score - 0.2);
+
+        // Keeping end-of-sale models is a policy choice; they are then penalized
+        let policy = LifecyclePolicy { exclude_end_of_sale: false, ..LifecyclePolicy::default() };
+        let end_of_sale = recommendation(model("r740", "2017-07-01", Some("2024-01-31")));
+        let kept = apply(vec![end_of_sale], &[], None, &policy, now);
+        assert_eq!(kept[0].lifecycle.as_ref().unwrap().status, LifecycleStatus::EndOfSale);
+        assert!(kept[0].confidence_score < 0.6);
+    }
+
+    #[test]
+    fn test_support_runway_against_planning_horizon() {
+        let now = date("2026-10-15");
+        let policy = LifecyclePolicy::default();
+
+        // Published end of life inside the horizon
+        let mut published = model("r750", "2021-05-01", Some("2027-06-01"));
+        published.end_of_life = Some(date("2030-10-15"));
+        let assessment = assess(&published, None, Some(60), &policy, now);
+        assert!(!assessment.support_end_projected);
+        assert_eq!(assessment.support_runway_months, Some(48));
+        assert_eq!(assessment.covers_planning_horizon, Some(false));
+        assert!(assessment.notes.iter().any(|n| n.contains("48 months into the 60-month")));
+
+        // Projected from end-of-sale when no end of life is published
+        let projected = assess(&model("r750", "2021-05-01", Some("2027-06-01")), None, Some(36), &policy, now);
+        assert!(projected.support_end_projected);
+        assert_eq!(projected.support_ends, Some(date("2032-06-01")));
+        assert_eq!(projected.covers_planning_horizon, Some(true));
+
+        // Without a horizon the runway is reported but not judged
+        let no_horizon = assess(&model("r760", "2023-02-01", None), None, None, &policy, now);
+        assert!(no_horizon.support_runway_months.is_some());
+        assert_eq!(no_horizon.covers_planning_horizon, None);
+    }
+
+    #[test]
+    fn test_catalog_peers_decide_generation() {
+        let now = date("2026-10-15");
+        let catalog = vec![model("r770", "2025-03-01", None)];
+        let result = apply(
+            vec![recommendation(model("r750", "2021-05-01", None))],
+            &catalog,
+            None,
+            &LifecyclePolicy::default(),
+            now,
+        );
+        assert!(!result[0].lifecycle.as_ref().unwrap().current_generation);
+    }
+}
@@ -5,23 +5,30 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use async_trait::async_trait;
 
+use crate::currency::{BomLine, ConvertedMoney, ExchangeRates, Money};
 use crate::models::UniversalServer;
 use crate::error::CoreEngineError;
 use crate::Result;
 
 // Module declarations
+mod auth;
 mod cache;
 mod config;
 mod dell_catalog;
 mod hpe_catalog;
 mod lenovo_catalog;
+mod lifecycle;
+mod scoring;
 
 // Re-export main types
+pub use auth::{AuthFailure, AuthFailureKind, VendorAuthStatus, VendorAuthenticator};
 pub use cache::{VendorDataCache, CacheEntry, CacheStats};
 pub use config::{VendorApiConfig, VendorConfig, AuthConfig, AuthType, RateLimitConfig};
 pub use dell_catalog::DellCatalogClient;
 pub use hpe_catalog::HPECatalogClient;
 pub use lenovo_catalog::LenovoCatalogClient;
+pub use lifecycle::{LifecycleAssessment, LifecyclePolicy, LifecycleStatus};
+pub use scoring::{ConfigurationTradeoffs, ObjectiveScores, ObjectiveWeights};
 
 /// Universal trait for vendor hardware catalog APIs
 #[async_trait]
@@ -49,7 +56,7 @@ pub trait VendorCatalogClient: Send + Sync {
 }
 
 /// Vendor authentication credentials
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct VendorCredentials {
     pub vendor: String,
     pub api_key: Option<String>,
@@ -57,6 +64,37 @@ pub struct VendorCredentials {
     pub password: Option<String>,
     pub partner_id: Option<String>,
     pub region: Option<String>,
+    /// OAuth2 client-credentials grant
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// PEM client certificate and private key for mTLS
+    pub client_certificate_pem: Option<String>,
+    pub client_key_pem: Option<String>,
+}
+
+impl std::fmt::Debug for VendorCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted = |value: &Option<String>| value.as_ref().map(|_| "<redacted>");
+        f.debug_struct("VendorCredentials")
+            .field("vendor", &self.vendor)
+            .field("api_key", &redacted(&self.api_key))
+            .field("username", &self.username)
+            .field("password", &redacted(&self.password))
+            .field("partner_id", &self.partner_id)
+            .field("region", &self.region)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &redacted(&self.client_secret))
+            .field("client_certificate_pem", &redacted(&self.client_certificate_pem))
+            .field("client_key_pem", &redacted(&self.client_key_pem))
+            .finish()
+    }
+}
+
+/// Cache statistics plus per-vendor authentication diagnostics
+#[derive(Debug, Clone, Serialize)]
+pub struct VendorDataStatistics {
+    pub cache: CacheStats,
+    pub authentication: Vec<VendorAuthStatus>,
 }
 
 /// Server model information from vendor catalog
@@ -74,6 +112,9 @@ pub struct ServerModel {
     pub power_supply_options: Vec<String>,
     pub launch_date: Option<DateTime<Utc>>,
     pub end_of_sale: Option<DateTime<Utc>>,
+    /// End of vendor support; projected from end-of-sale when not published
+    #[serde(default)]
+    pub end_of_life: Option<DateTime<Utc>>,
     pub product_brief_url: Option<String>,
     pub quickspecs_url: Option<String>,
 }
@@ -333,6 +374,15 @@ pub struct SizingRequirements {
     pub power_limit_watts: Option<u32>,
     pub redundancy_required: bool,
     pub specific_features: Vec<String>,
+    /// Months the hardware must stay under vendor support, usually the
+    /// project's planning horizon
+    #[serde(default)]
+    pub planning_horizon_months: Option<u32>,
+    #[serde(default)]
+    pub lifecycle_policy: LifecyclePolicy,
+    /// Weights for capex, performance, power and density scoring; equal when unset
+    #[serde(default)]
+    pub objective_weights: Option<ObjectiveWeights>,
 }
 
 /// Workload types for sizing
@@ -359,6 +409,12 @@ pub struct RecommendedConfiguration {
     pub pricing: Option<PricingResponse>,
     pub confidence_score: f32,  // 0.0 to 1.0
     pub reasoning: String,
+    /// End-of-sale status and support runway, filled in by the data manager
+    #[serde(default)]
+    pub lifecycle: Option<LifecycleAssessment>,
+    /// Per-axis scores relative to the other candidates in the same search
+    #[serde(default)]
+    pub objective_scores: Option<ObjectiveScores>,
 }
 
 /// Bundle of components for a configuration
@@ -425,6 +481,27 @@ pub struct PricingResponse {
     pub quote_reference: Option<String>,
 }
 
+impl PricingResponse {
+    /// Quote lines as BOM lines in the quote currency, at partner price when quoted
+    pub fn bom_lines(&self) -> Vec<BomLine> {
+        self.component_pricing
+            .iter()
+            .map(|c| BomLine {
+                part_number: c.part_number.clone(),
+                description: String::new(),
+                quantity: c.quantity,
+                unit_price: Money::new(c.unit_partner_price.unwrap_or(c.unit_list_price), &self.currency),
+            })
+            .collect()
+    }
+
+    /// Quote total (partner price when quoted) converted to another currency
+    pub fn total_in(&self, rates: &ExchangeRates, currency: &str) -> Result<ConvertedMoney> {
+        let total = self.total_partner_price.unwrap_or(self.total_list_price);
+        rates.convert_money(&Money::new(total, &self.currency), currency)
+    }
+}
+
 /// Pricing for individual components
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentPricing {
@@ -440,6 +517,7 @@ pub struct ComponentPricing {
 #[derive(Clone)]
 pub struct VendorDataManager {
     clients: HashMap<String, Arc<dyn VendorCatalogClient + Send + Sync>>,
+    auth: HashMap<String, Arc<VendorAuthenticator>>,
     cache: cache::VendorDataCache,
 }
 
@@ -462,20 +540,83 @@ impl VendorDataManager {
         clients.insert("HPE".to_string(), Arc::new(hpe_catalog::HPECatalogClient::new()));
         clients.insert("Lenovo".to_string(), Arc::new(lenovo_catalog::LenovoCatalogClient::new()));
         
+        // One authenticator per client, driven by the vendor API configuration
+        let config = VendorApiConfig::default();
+        let auth = clients
+            .keys()
+            .filter_map(|vendor| {
+                let vendor_config = config.get_vendor_config(&vendor.to_lowercase())?;
+                let authenticator = VendorAuthenticator::new(
+                    vendor.clone(),
+                    vendor_config.authentication.clone(),
+                    config.global_settings.timeout_seconds,
+                );
+                Some((vendor.clone(), Arc::new(authenticator)))
+            })
+            .collect();
+        
         Self {
             clients,
+            auth,
             cache: cache::VendorDataCache::new(),
         }
     }
     
-    /// Configure credentials for a vendor
+    /// Authenticator for a vendor, matched case-insensitively
+    fn authenticator(&self, vendor: &str) -> Result<&Arc<VendorAuthenticator>> {
+        self.auth
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(vendor))
+            .map(|(_, auth)| auth)
+            .ok_or_else(|| CoreEngineError::config(format!("Unsupported vendor: {}", vendor)))
+    }
+    
+    /// Configure credentials for a vendor and authenticate with them
+    ///
+    /// OAuth2 vendors fetch a token and mTLS vendors load their client
+    /// certificate straight away; failures are kept for diagnostics.
+    #[tracing::instrument(name = "vendor_data.configure_vendor", skip(self, credentials))]
     pub async fn configure_vendor(&self, vendor: &str, credentials: VendorCredentials) -> Result<()> {
-        // For now, we'll skip the authentication since we need mutable access
-        // In production, this would handle credential storage differently
-        Ok(())
+        self.authenticator(vendor)?.configure(credentials).await
+    }
+    
+    /// Send an authenticated request to a vendor's API
+    ///
+    /// `build` adds query, body or extra headers. 401/403 responses are
+    /// recorded as authentication failures for that vendor.
+    pub async fn send_authenticated(
+        &self,
+        vendor: &str,
+        method: reqwest::Method,
+        url: &str,
+        build: impl FnOnce(reqwest::RequestBuilder) -> reqwest::RequestBuilder + Send,
+    ) -> Result<reqwest::Response> {
+        let auth = self.authenticator(vendor)?;
+        let response = build(auth.request(method, url).await?)
+            .send()
+            .await
+            .map_err(|e| CoreEngineError::NetworkError(format!("{} API request failed: {}", vendor, e)))?;
+        auth.observe_response(response.status()).await;
+        Ok(response)
+    }
+    
+    /// Authentication diagnostics for every vendor, sorted by vendor name
+    pub fn auth_statuses(&self) -> Vec<VendorAuthStatus> {
+        let mut statuses: Vec<_> = self.auth.values().map(|auth| auth.status()).collect();
+        statuses.sort_by(|a, b| a.vendor.cmp(&b.vendor));
+        statuses
+    }
+    
+    /// Cache statistics together with per-vendor authentication diagnostics
+    pub async fn statistics(&self) -> VendorDataStatistics {
+        VendorDataStatistics {
+            cache: self.cache.get_cache_stats().await,
+            authentication: self.auth_statuses(),
+        }
     }
     
     /// Get all server models from all vendors (with caching)
+    #[tracing::instrument(name = "vendor_data.get_all_server_models", skip_all)]
     pub async fn get_all_server_models(&self) -> Result<Vec<ServerModel>> {
         let mut all_models = Vec::new();
         
@@ -495,6 +636,7 @@ impl VendorDataManager {
     }
     
     /// Get server models from a specific vendor
+    #[tracing::instrument(name = "vendor_data.get_vendor_server_models", skip(self))]
     pub async fn get_vendor_server_models(&self, vendor: &str) -> Result<Vec<ServerModel>> {
         if let Some(client) = self.clients.get(vendor) {
             // Check cache first
@@ -512,6 +654,7 @@ impl VendorDataManager {
     }
     
     /// Get detailed specifications for a server model
+    #[tracing::instrument(name = "vendor_data.get_model_specifications", skip(self))]
     pub async fn get_model_specifications(&self, vendor: &str, model_id: &str) -> Result<ServerSpecifications> {
         if let Some(client) = self.clients.get(vendor) {
             // Check cache first
@@ -530,6 +673,7 @@ impl VendorDataManager {
     }
     
     /// Get compatibility matrix for a server model
+    #[tracing::instrument(name = "vendor_data.get_compatibility_matrix", skip(self))]
     pub async fn get_compatibility_matrix(&self, vendor: &str, model_id: &str) -> Result<CompatibilityMatrix> {
         if let Some(client) = self.clients.get(vendor) {
             // Check cache first
@@ -548,6 +692,11 @@ impl VendorDataManager {
     }
     
     /// Search for configurations based on requirements
+    ///
+    /// Recommendations are annotated with lifecycle status; models past
+    /// end-of-sale, near it or superseded are dropped or ranked lower
+    /// according to `requirements.lifecycle_policy`.
+    #[tracing::instrument(name = "vendor_data.search_configurations", skip_all)]
     pub async fn search_configurations(&self, requirements: &SizingRequirements) -> Result<Vec<RecommendedConfiguration>> {
         let mut all_recommendations = Vec::new();
         
@@ -561,12 +710,34 @@ impl VendorDataManager {
             }
         }
         
+        // Family peers from the full catalog tell current from previous generations
+        let catalog = self.get_all_server_models().await.unwrap_or_default();
+        let mut all_recommendations = lifecycle::apply(
+            all_recommendations,
+            &catalog,
+            requirements.planning_horizon_months,
+            &requirements.lifecycle_policy,
+            Utc::now(),
+        );
+        
+        // Per-axis scores let callers trade off beyond the single confidence number
+        let weights = requirements.objective_weights.clone().unwrap_or_default();
+        scoring::score(&mut all_recommendations, requirements, &weights)?;
+        
         // Sort by confidence score
         all_recommendations.sort_by(|a, b| b.confidence_score.partial_cmp(&a.confidence_score).unwrap_or(std::cmp::Ordering::Equal));
         
         Ok(all_recommendations)
     }
     
+    /// Search for configurations and rank them by weighted capex, performance,
+    /// power and density scores, returning the Pareto-front alternatives
+    #[tracing::instrument(name = "vendor_data.compare_configurations", skip_all)]
+    pub async fn compare_configurations(&self, requirements: &SizingRequirements) -> Result<ConfigurationTradeoffs> {
+        let recommendations = self.search_configurations(requirements).await?;
+        scoring::tradeoffs(recommendations, requirements)
+    }
+    
     /// Enrich a parsed configuration with vendor data
     pub async fn enrich_configuration(&self, server: &mut UniversalServer) -> Result<()> {
         // Try to find matching model in vendor catalogs
@@ -623,6 +794,7 @@ impl VendorDataManager {
     }
     
     /// Get pricing for a configuration (if available)
+    #[tracing::instrument(name = "vendor_data.get_configuration_pricing", skip(self, configuration))]
     pub async fn get_configuration_pricing(&self, vendor: &str, configuration: &ConfigurationRequest) -> Result<Option<PricingResponse>> {
         if let Some(client) = self.clients.get(vendor) {
             client.get_pricing(configuration).await
@@ -632,6 +804,7 @@ impl VendorDataManager {
     }
     
     /// Refresh all cached data from vendors
+    #[tracing::instrument(name = "vendor_data.refresh_vendor_data", skip_all)]
     pub async fn refresh_vendor_data(&self) -> Result<()> {
         for (vendor, client) in &self.clients {
             // Clear cache for this vendor
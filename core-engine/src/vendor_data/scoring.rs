@@ -0,0 +1,404 @@
+// Multi-objective scoring of recommended configurations (capex, performance, power, density)
+use serde::{Deserialize, Serialize};
+
+use crate::error::CoreEngineError;
+use crate::Result;
+use super::{FormFactor, RecommendedConfiguration, SizingRequirements};
+
+/// Relative importance of each objective; normalized to sum to 1 before use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectiveWeights {
+    /// Lower purchase price
+    pub capex: f32,
+    /// More CPU, memory, storage and network headroom
+    pub performance: f32,
+    /// Lower power draw and heat output
+    pub power: f32,
+    /// More performance per rack unit
+    pub density: f32,
+}
+
+impl Default for ObjectiveWeights {
+    fn default() -> Self {
+        Self {
+            capex: 0.25,
+            performance: 0.25,
+            power: 0.25,
+            density: 0.25,
+        }
+    }
+}
+
+impl ObjectiveWeights {
+    /// Weights scaled to sum to 1; rejects negative or all-zero weights
+    pub fn normalized(&self) -> Result<Self> {
+        let weights = [self.capex, self.performance, self.power, self.density];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(CoreEngineError::validation("Objective weights must be non-negative numbers"));
+        }
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return Err(CoreEngineError::validation("At least one objective weight must be positive"));
+        }
+        Ok(Self {
+            capex: self.capex / total,
+            performance: self.performance / total,
+            power: self.power / total,
+            density: self.density / total,
+        })
+    }
+}
+
+/// Per-axis scores for one configuration, each 0.0 (worst candidate) to
+/// 1.0 (best candidate) within the set being compared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectiveScores {
+    /// `None` when neither a quote nor component prices are available
+    pub capex: Option<f32>,
+    pub performance: f32,
+    pub power: f32,
+    pub density: f32,
+    /// Weighted sum over the known axes, with weights renormalized when capex is unknown
+    pub weighted: f32,
+    /// No other candidate is at least as good on every axis and better on one
+    pub pareto_optimal: bool,
+}
+
+/// Ranked alternatives and the Pareto front for a set of requirements
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigurationTradeoffs {
+    /// Weights actually applied, after normalization
+    pub weights: ObjectiveWeights,
+    /// Every candidate, best weighted score first
+    pub ranked: Vec<RecommendedConfiguration>,
+    /// Candidates no other candidate dominates, best weighted score first
+    pub pareto_front: Vec<RecommendedConfiguration>,
+}
+
+/// Raw objective values before normalization
+struct RawObjectives {
+    capex: Option<f64>,
+    performance: [f64; 5],
+    power: [f64; 2],
+    density: f64,
+    over_power_limit: bool,
+}
+
+/// Rack units a form factor occupies, as assumed in consolidation planning
+fn rack_units(form_factor: &FormFactor) -> f64 {
+    match form_factor {
+        FormFactor::OneU => 1.0,
+        FormFactor::FourU => 4.0,
+        _ => 2.0,
+    }
+}
+
+/// Quoted price when available, otherwise the sum of priced components
+fn capex(config: &RecommendedConfiguration) -> Option<f64> {
+    if let Some(pricing) = &config.pricing {
+        return Some(pricing.total_partner_price.unwrap_or(pricing.total_list_price));
+    }
+    let bundle = &config.recommended_components;
+    let components: Vec<_> = bundle
+        .cpus
+        .iter()
+        .chain(&bundle.memory)
+        .chain(&bundle.storage_controllers)
+        .chain(&bundle.drives)
+        .chain(&bundle.network_adapters)
+        .chain(&bundle.power_supplies)
+        .chain(&bundle.additional_options)
+        .collect();
+    if components.is_empty() || components.iter().any(|c| c.unit_price.is_none()) {
+        return None;
+    }
+    Some(components.iter().map(|c| c.unit_price.unwrap_or_default() * c.quantity as f64).sum())
+}
+
+fn raw_objectives(config: &RecommendedConfiguration, requirements: &SizingRequirements) -> RawObjectives {
+    let metrics = &config.performance_metrics;
+    let watts = metrics.power_consumption_watts as f64;
+    RawObjectives {
+        capex: capex(config),
+        performance: [
+            metrics.cpu_performance_score as f64,
+            metrics.memory_bandwidth_gbps as f64,
+            metrics.storage_iops as f64,
+            metrics.network_throughput_gbps as f64,
+            config.model.max_memory_gb as f64,
+        ],
+        power: [watts, metrics.thermal_output_btu as f64],
+        density: metrics.cpu_performance_score as f64 / rack_units(&config.model.form_factor),
+        over_power_limit: requirements
+            .power_limit_watts
+            .is_some_and(|limit| metrics.power_consumption_watts > limit),
+    }
+}
+
+/// Min-max scale `value` within `values`; 1.0 is best. A flat range scores 1.0.
+fn scale(value: f64, values: impl Iterator<Item = f64> + Clone, higher_is_better: bool) -> f32 {
+    let min = values.clone().fold(f64::INFINITY, f64::min);
+    let max = values.fold(f64::NEG_INFINITY, f64::max);
+    if max <= min {
+        return 1.0;
+    }
+    let position = ((value - min) / (max - min)) as f32;
+    if higher_is_better { position } else { 1.0 - position }
+}
+
+/// Mean of the min-max scaled sub-metrics of candidate `index`
+fn scaled_mean(columns: &[Vec<f64>], index: usize, higher_is_better: bool) -> f32 {
+    let total: f32 = columns
+        .iter()
+        .map(|column| scale(column[index], column.iter().copied(), higher_is_better))
+        .sum();
+    total / columns.len() as f32
+}
+
+/// True when `a` is at least as good as `b` on every axis and better on one;
+/// an unknown capex counts as the worst possible
+fn dominates(a: &ObjectiveScores, b: &ObjectiveScores) -> bool {
+    let a_axes = [a.capex.unwrap_or(0.0), a.performance, a.power, a.density];
+    let b_axes = [b.capex.unwrap_or(0.0), b.performance, b.power, b.density];
+    a_axes.iter().zip(&b_axes).all(|(x, y)| x >= y) && a_axes.iter().zip(&b_axes).any(|(x, y)| x > y)
+}
+
+/// Score every configuration on each objective relative to the others and
+/// mark the Pareto-optimal ones
+pub(crate) fn score(
+    recommendations: &mut [RecommendedConfiguration],
+    requirements: &SizingRequirements,
+    weights: &ObjectiveWeights,
+) -> Result<()> {
+    let weights = weights.normalized()?;
+    let raw: Vec<RawObjectives> = recommendations.iter().map(|r| raw_objectives(r, requirements)).collect();
+    let performance_columns: Vec<Vec<f64>> =
+        (0..5).map(|i| raw.iter().map(|r| r.performance[i]).collect()).collect();
+    let power_columns: Vec<Vec<f64>> = (0..2).map(|i| raw.iter().map(|r| r.power[i]).collect()).collect();
+
+    let mut scores: Vec<ObjectiveScores> = raw
+        .iter()
+        .enumerate()
+        .map(|(index, objectives)| {
+            let capex = objectives
+                .capex
+                .map(|cost| scale(cost, raw.iter().filter_map(|r| r.capex), false));
+            let performance = scaled_mean(&performance_columns, index, true);
+            let power = if objectives.over_power_limit {
+                0.0
+            } else {
+                scaled_mean(&power_columns, index, false)
+            };
+            let density = scale(objectives.density, raw.iter().map(|r| r.density), true);
+
+            let (mut weighted, mut weight_total) = (
+                weights.performance * performance + weights.power * power + weights.density * density,
+                weights.performance + weights.power + weights.density,
+            );
+            if let Some(capex) = capex {
+                weighted += weights.capex * capex;
+                weight_total += weights.capex;
+            }
+
+            ObjectiveScores {
+                capex,
+                performance,
+                power,
+                density,
+                weighted: if weight_total > 0.0 { weighted / weight_total } else { 0.0 },
+                pareto_optimal: false,
+            }
+        })
+        .collect();
+
+    let dominated: Vec<bool> = scores
+        .iter()
+        .map(|candidate| scores.iter().any(|other| dominates(other, candidate)))
+        .collect();
+    for (score, dominated) in scores.iter_mut().zip(dominated) {
+        score.pareto_optimal = !dominated;
+    }
+
+    for (recommendation, score) in recommendations.iter_mut().zip(scores) {
+        recommendation.objective_scores = Some(score);
+    }
+    Ok(())
+}
+
+/// Rank scored configurations by weighted score and split out the Pareto front
+pub(crate) fn tradeoffs(
+    mut recommendations: Vec<RecommendedConfiguration>,
+    requirements: &SizingRequirements,
+) -> Result<ConfigurationTradeoffs> {
+    let weights = requirements.objective_weights.clone().unwrap_or_default();
+    score(&mut recommendations, requirements, &weights)?;
+
+    let weighted = |r: &RecommendedConfiguration| r.objective_scores.as_ref().map_or(0.0, |s| s.weighted);
+    recommendations.sort_by(|a, b| {
+        weighted(b)
+            .partial_cmp(&weighted(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.confidence_score.partial_cmp(&a.confidence_score).unwrap_or(std::cmp::Ordering::Equal))
+    });
+
+    let pareto_front = recommendations
+        .iter()
+        .filter(|r| r.objective_scores.as_ref().is_some_and(|s| s.pareto_optimal))
+        .cloned()
+        .collect();
+
+    Ok(ConfigurationTradeoffs {
+        weights: weights.normalized()?,
+        ranked: recommendations,
+        pareto_front,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vendor_data::{
+        ComponentBundle, ComponentSelection, PerformanceEstimate, ServerModel, WorkloadType,
+    };
+
+    fn requirements(weights: Option<ObjectiveWeights>) -> SizingRequirements {
+        SizingRequirements {
+            workload_type: WorkloadType::Virtualization,
+            cpu_cores_minimum: None,
+            memory_gb_minimum: None,
+            storage_gb_minimum: None,
+            network_bandwidth_gbps: None,
+            form_factor_preference: None,
+            budget_maximum: None,
+            power_limit_watts: None,
+            redundancy_required: false,
+            specific_features: vec![],
+            planning_horizon_months: None,
+            lifecycle_policy: Default::default(),
+            objective_weights: weights,
+        }
+    }
+
+    fn candidate(
+        id: &str,
+        form_factor: FormFactor,
+        price: f64,
+        cpu_score: f32,
+        watts: u32,
+    ) -> RecommendedConfiguration {
+        RecommendedConfiguration {
+            configuration_id: id.to_string(),
+            model: ServerModel {
+                vendor: "Dell".to_string(),
+                model_id: id.to_string(),
+                model_name: id.to_string(),
+                family: "PowerEdge".to_string(),
+                form_factor,
+                cpu_sockets: 2,
+                max_memory_gb: 4096,
+                drive_bays: 10,
+                pcie_slots: 4,
+                power_supply_options: vec![],
+                launch_date: None,
+                end_of_sale: None,
+                end_of_life: None,
+                product_brief_url: None,
+                quickspecs_url: None,
+            },
+            recommended_components: ComponentBundle {
+                cpus: vec![ComponentSelection {
+                    part_number: "cpu".to_string(),
+                    description: "CPU".to_string(),
+                    quantity: 2,
+                    unit_price: Some(price / 2.0),
+                    justification: String::new(),
+                }],
+                memory: vec![],
+                storage_controllers: vec![],
+                drives: vec![],
+                network_adapters: vec![],
+                power_supplies: vec![],
+                additional_options: vec![],
+            },
+            performance_metrics: PerformanceEstimate {
+                cpu_performance_score: cpu_score,
+                memory_bandwidth_gbps: 200.0,
+                storage_iops: 50000,
+                network_throughput_gbps: 25.0,
+                power_consumption_watts: watts,
+                thermal_output_btu: watts * 3,
+            },
+            pricing: None,
+            confidence_score: 0.7,
+            reasoning: String::new(),
+            lifecycle: None,
+            objective_scores: None,
+        }
+    }
+
+    fn ids(configs: &[RecommendedConfiguration]) -> Vec<&str> {
+        configs.iter().map(|c| c.configuration_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_pareto_front_excludes_dominated_configurations() {
+        let candidates = vec![
+            // Cheap and frugal
+            candidate("budget", FormFactor::OneU, 8000.0, 60.0, 300),
+            // Fast but expensive and power hungry
+            candidate("fast", FormFactor::TwoU, 20000.0, 100.0, 700),
+            // Worse than budget on every axis
+            candidate("dominated", FormFactor::TwoU, 14000.0, 55.0, 450),
+        ];
+
+        let result = tradeoffs(candidates, &requirements(None)).unwrap();
+        let mut front = ids(&result.pareto_front);
+        front.sort();
+        assert_eq!(front, vec!["budget", "fast"]);
+
+        let dominated = result.ranked.iter().find(|c| c.configuration_id == "dominated").unwrap();
+        let scores = dominated.objective_scores.as_ref().unwrap();
+        assert!(!scores.pareto_optimal);
+        assert_eq!(scores.capex, Some(0.5));
+        assert_eq!(result.ranked.len(), 3);
+    }
+
+    #[test]
+    fn test_weights_change_the_ranking() {
+        let candidates = || vec![
+            candidate("budget", FormFactor::OneU, 8000.0, 60.0, 300),
+            candidate("fast", FormFactor::TwoU, 20000.0, 100.0, 700),
+        ];
+
+        let cost_first = ObjectiveWeights { capex: 1.0, performance: 0.0, power: 0.0, density: 0.0 };
+        let result = tradeoffs(candidates(), &requirements(Some(cost_first))).unwrap();
+        assert_eq!(ids(&result.ranked), vec!["budget", "fast"]);
+
+        let speed_first = ObjectiveWeights { capex: 0.0, performance: 2.0, power: 0.0, density: 0.0 };
+        let result = tradeoffs(candidates(), &requirements(Some(speed_first))).unwrap();
+        assert_eq!(ids(&result.ranked), vec!["fast", "budget"]);
+        assert!((result.weights.performance - 1.0).abs() < 1e-6);
+
+        let invalid = ObjectiveWeights { capex: 0.0, performance: 0.0, power: 0.0, density: 0.0 };
+        assert!(tradeoffs(candidates(), &requirements(Some(invalid))).is_err());
+    }
+
+    #[test]
+    fn test_power_limit_and_unknown_prices() {
+        let mut unpriced = candidate("unpriced", FormFactor::TwoU, 0.0, 80.0, 900);
+        unpriced.recommended_components.cpus[0].unit_price = None;
+        let candidates = vec![candidate("budget", FormFactor::OneU, 8000.0, 60.0, 300), unpriced];
+
+        let mut limited = requirements(None);
+        limited.power_limit_watts = Some(800);
+        let result = tradeoffs(candidates, &limited).unwrap();
+
+        let unpriced = result.ranked.iter().find(|c| c.configuration_id == "unpriced").unwrap();
+        let scores = unpriced.objective_scores.as_ref().unwrap();
+        assert_eq!(scores.capex, None);
+        assert_eq!(scores.power, 0.0);
+        // Still on the front: nothing beats its performance
+        assert!(scores.pareto_optimal);
+    }
+}
@@ -0,0 +1,151 @@
+use core_engine::models::{HardwareProfile, PowerState, SizingParameters};
+use core_engine::parser::RvToolsParser;
+use core_engine::rvtools_generator::{generate, GeneratorConfig, SyntheticEnvironment};
+use core_engine::sizing::SizingEngine;
+use std::path::PathBuf;
+
+fn workbook_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}-{}.xlsx", name, std::process::id()))
+}
+
+fn write_and_parse(environment: &SyntheticEnvironment, name: &str) -> core_engine::models::VsphereEnvironment {
+    let path = workbook_path(name);
+    environment.write_xlsx(&path).expect("workbook written");
+    let parsed = RvToolsParser::new(&path).and_then(|mut parser| parser.parse());
+    let _ = std::fs::remove_file(&path);
+    parsed.expect("synthetic workbook parses")
+}
+
+fn sizing_host() -> HardwareProfile {
+    HardwareProfile {
+        id: uuid::Uuid::nil(),
+        name: "2x32C / 1 TB".to_string(),
+        manufacturer: "Test".to_string(),
+        model: "Model".to_string(),
+        cpu_sockets: 2,
+        cores_per_socket: 32,
+        total_cores: 64,
+        max_memory_gb: 1024,
+        storage_slots: 24,
+        network_ports: 4,
+        is_hci_certified: true,
+        estimated_cost: Some(30000.0),
+        power_consumption_watts: Some(900),
+        rack_units: 2,
+        notes: None,
+    }
+}
+
+#[test]
+fn same_seed_gives_same_estate() {
+    let config = GeneratorConfig::default();
+    let first = generate(&config).unwrap();
+    assert_eq!(first, generate(&config).unwrap());
+
+    let other = generate(&GeneratorConfig { seed: 7, ..config }).unwrap();
+    assert_ne!(first, other);
+}
+
+#[test]
+fn parser_reads_back_the_generated_estate() {
+    let config = GeneratorConfig {
+        clusters: 4,
+        hosts: 18,
+        vms: 600,
+        ..Default::default()
+    };
+    let generated = generate(&config).unwrap();
+    let parsed = write_and_parse(&generated, "rvtools-generator-parse");
+
+    assert_eq!(parsed.clusters.len(), 4);
+    assert_eq!(parsed.total_hosts, 18);
+    assert_eq!(parsed.total_vms, 600);
+
+    let expected_vcpus: u32 = generated
+        .vms
+        .iter()
+        .filter(|vm| vm.power_state == "poweredOn")
+        .map(|vm| vm.cpus)
+        .sum();
+    assert_eq!(parsed.summary_metrics.total_vcpus, expected_vcpus);
+
+    let expected_cores: u32 = generated.hosts.iter().map(|h| h.cores).sum();
+    assert_eq!(parsed.summary_metrics.total_pcores, expected_cores);
+
+    for cluster in &parsed.clusters {
+        let expected_vms = generated.vms.iter().filter(|vm| vm.cluster == cluster.name).count();
+        assert_eq!(cluster.vms.len(), expected_vms, "VMs of {}", cluster.name);
+        assert!(cluster.hosts.iter().all(|h| h.total_memory_gb > 0 && h.num_cpu_sockets == 2));
+    }
+
+    let disks: usize = generated.vms.iter().map(|vm| vm.disks.len()).sum();
+    let parsed_disks: usize = parsed.clusters.iter().flat_map(|c| &c.vms).map(|vm| vm.disks.len()).sum();
+    assert_eq!(parsed_disks, disks);
+}
+
+#[test]
+fn sizing_places_every_running_vm() {
+    let generated = generate(&GeneratorConfig {
+        clusters: 2,
+        hosts: 8,
+        vms: 250,
+        seed: 1234,
+        ..Default::default()
+    })
+    .unwrap();
+    let parsed = write_and_parse(&generated, "rvtools-generator-sizing");
+    let vms: Vec<_> = parsed.clusters.into_iter().flat_map(|c| c.vms).collect();
+    let running = vms
+        .iter()
+        .filter(|vm| vm.power_state == PowerState::PoweredOn && !vm.is_template)
+        .count();
+
+    // Defaults include N+1
+    let result = SizingEngine::calculate_sizing(&vms, &sizing_host(), &SizingParameters::default()).unwrap();
+
+    assert_eq!(result.vm_placement.len(), running);
+    assert!(result.required_hosts >= 2);
+}
+
+/// `cargo test --release -- --ignored` for a parser timing run at estate scale
+#[test]
+#[ignore]
+fn parses_a_large_estate() {
+    let generated = generate(&GeneratorConfig {
+        clusters: 40,
+        hosts: 400,
+        vms: 10_000,
+        ..Default::default()
+    })
+    .unwrap();
+    let started = std::time::Instant::now();
+    let parsed = write_and_parse(&generated, "rvtools-generator-large");
+    println!("Wrote and parsed 10000 VMs in {:?}", started.elapsed());
+    assert_eq!(parsed.total_vms, 10_000);
+}
+
+#[test]
+fn malformed_rows_are_reported_not_fatal() {
+    let mut generated = generate(&GeneratorConfig {
+        clusters: 2,
+        hosts: 6,
+        vms: 40,
+        ..Default::default()
+    })
+    .unwrap();
+    // Row 5 in Excel; its vDisk, vPartition and vNetwork rows lose their VM too
+    generated.vms[3].name = String::new();
+
+    let parsed = write_and_parse(&generated, "rvtools-generator-malformed");
+    let report = &parsed.import_report;
+
+    assert_eq!(parsed.total_vms, 39);
+    assert!(report.has_warnings());
+    assert_eq!(report.rows_imported + report.rows_failed, report.rows_total);
+    assert_eq!(report.rows_failed, report.errors.len());
+    assert!(report
+        .errors
+        .iter()
+        .any(|e| e.sheet == "vInfo" && e.row == 5 && e.column.as_deref() == Some("VM")));
+    assert!(report.errors.iter().all(|e| e.reason == "Missing VM name"));
+}
@@ -67,6 +67,10 @@ const ApprovalInbox = lazyWithRetry(() => import('./views/ApprovalInbox'));
 const UserManagementView = lazyWithRetry(() => import('./views/UserManagementView'));
 const RoleManagementView = lazyWithRetry(() => import('./views/RoleManagementView'));
 const AuditLogView = lazyWithRetry(() => import('./views/AuditLogView'));
+const LicenseView = lazyWithRetry(() => import('./views/LicenseView'));
+const UsageTelemetryView = lazyWithRetry(() => import('./views/UsageTelemetryView'));
+const DataRetentionView = lazyWithRetry(() => import('./views/DataRetentionView'));
+const PersonalDataView = lazyWithRetry(() => import('./views/PersonalDataView'));
 
 // Inner App component that uses the theme context
 function AppContent() {
@@ -284,6 +288,10 @@ function AppContent() {
                     <Route path="admin/users" element={<UserManagementView />} />
                     <Route path="admin/roles" element={<RoleManagementView />} />
                     <Route path="admin/audit" element={<AuditLogView />} />
+                    <Route path="admin/license" element={<LicenseView />} />
+                    <Route path="admin/telemetry" element={<UsageTelemetryView />} />
+                    <Route path="admin/retention" element={<DataRetentionView />} />
+                    <Route path="admin/privacy" element={<PersonalDataView />} />
                     <Route path="capacity-visualizer" element={
                       <div data-testid="capacity-visualizer" style={{ height: '100%', width: '100%' }}>
                         <CapacityVisualizerView />
@@ -20,7 +20,7 @@ type SurrealThing =
   | { id?: string | { String?: string }; tb?: string }
   | { value?: string };
 
-type RvToolsStatusApi = 'uploaded' | 'processing' | 'processed' | 'failed';
+type RvToolsStatusApi = 'uploaded' | 'processing' | 'processed' | 'completed_with_warnings' | 'failed';
 
 type RvToolsProcessingErrorApi = {
   line_number: number;
@@ -28,6 +28,27 @@ type RvToolsProcessingErrorApi = {
   error: string;
 };
 
+type RvToolsRowErrorApi = {
+  sheet: string;
+  row: number;
+  column: string | null;
+  reason: string;
+};
+
+type RvToolsImportReportApi = {
+  rows_total: number;
+  rows_imported: number;
+  rows_failed: number;
+  errors: RvToolsRowErrorApi[];
+};
+
+type RvToolsImportReportResponseApi = {
+  upload_id: string;
+  upload_status: RvToolsStatusApi;
+  report: RvToolsImportReportApi;
+  processed_at: string | null;
+};
+
 type RvToolsProcessingSummaryApi = {
   total_cpu_cores: number;
   total_memory_gb: number;
@@ -40,6 +61,7 @@ type RvToolsUploadResponseApi = {
   servers_processed: number;
   servers_added_to_pool: number;
   processing_errors: RvToolsProcessingErrorApi[];
+  report: RvToolsImportReportApi;
   summary: RvToolsProcessingSummaryApi;
   upload_timestamp: string;
 };
@@ -76,6 +98,28 @@ export type RvToolsProcessingError = {
   error: string;
 };
 
+export type RvToolsRowError = {
+  sheet: string;
+  /** 1-based spreadsheet row; 0 when a whole sheet could not be read */
+  row: number;
+  column: string | null;
+  reason: string;
+};
+
+export type RvToolsImportReport = {
+  rowsTotal: number;
+  rowsImported: number;
+  rowsFailed: number;
+  errors: RvToolsRowError[];
+};
+
+export type RvToolsImportReportDetail = {
+  uploadId: string;
+  status: RvToolsStatusApi;
+  report: RvToolsImportReport;
+  processedAt: string | null;
+};
+
 export type RvToolsProcessingSummary = {
   totalCpuCores: number;
   totalMemoryGb: number;
@@ -88,6 +132,7 @@ export type RvToolsUploadSummary = {
   serversProcessed: number;
   serversAddedToPool: number;
   processingErrors: RvToolsProcessingError[];
+  report: RvToolsImportReport;
   summary: RvToolsProcessingSummary;
   uploadTimestamp: string;
 };
@@ -169,7 +214,7 @@ type HardwarePoolServerApi = {
 };
 
 type ServersListResponse = {
-  servers: HardwarePoolServerApi[];
+  items: HardwarePoolServerApi[];
   total: number;
 };
 
@@ -364,11 +409,19 @@ const toRvToolsProcessingError = (error: RvToolsProcessingErrorApi): RvToolsProc
   error: error.error,
 });
 
+const toRvToolsImportReport = (report: RvToolsImportReportApi): RvToolsImportReport => ({
+  rowsTotal: report.rows_total,
+  rowsImported: report.rows_imported,
+  rowsFailed: report.rows_failed,
+  errors: report.errors,
+});
+
 const normalizeRvToolsUploadResponse = (payload: RvToolsUploadResponseApi): RvToolsUploadSummary => ({
   uploadId: extractThingId(payload.upload_id),
   serversProcessed: payload.servers_processed,
   serversAddedToPool: payload.servers_added_to_pool,
   processingErrors: payload.processing_errors.map(toRvToolsProcessingError),
+  report: toRvToolsImportReport(payload.report),
   summary: toRvToolsProcessingSummary(payload.summary),
   uploadTimestamp: payload.upload_timestamp,
 });
@@ -418,8 +471,8 @@ type AssetStatus =
 
 export const BackendClient = {
   async listHardwarePoolServers(): Promise<HardwarePoolServerAggregate> {
-    const { servers, total } = await request<ServersListResponse>('/api/hardware-pool/servers');
-    return hardwareServerAggregate(servers, total);
+    const { items, total } = await request<ServersListResponse>('/api/hardware-pool/servers');
+    return hardwareServerAggregate(items, total);
   },
 
   async createHardwarePoolServer(payload: CreateHardwarePoolPayload): Promise<NormalizedHardwarePoolServer> {
@@ -498,6 +551,19 @@ export const BackendClient = {
     const { uploads } = await request<RvToolsUploadsListResponseApi>(`/api/rvtools/uploads${query}`);
     return uploads.map(toRvToolsUploadRecord);
   },
+
+  async getRvToolsImportReport(uploadId: string): Promise<RvToolsImportReportDetail> {
+    const response = await request<RvToolsImportReportResponseApi>(
+      `/api/rvtools/uploads/${encodeURIComponent(uploadId)}/report`,
+    );
+
+    return {
+      uploadId: response.upload_id,
+      status: response.upload_status,
+      report: toRvToolsImportReport(response.report),
+      processedAt: response.processed_at,
+    };
+  },
 };
 
 export type CreateHardwareAssetInput = {
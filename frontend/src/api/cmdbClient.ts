@@ -55,6 +55,18 @@ export type RelationshipDirection = 'INBOUND' | 'OUTBOUND' | 'BIDIRECTIONAL';
 
 export type DiscoverySource = 'MANUAL' | 'DISCOVERY_TOOL' | 'IMPORT' | 'API';
 
+export type DepreciationMethod = 'STRAIGHT_LINE' | 'DECLINING_BALANCE';
+
+export interface AssetFinancials {
+  purchase_cost: number;
+  purchase_date: string;
+  depreciation_method?: DepreciationMethod;
+  useful_life_months: number;
+  salvage_value?: number;
+  declining_factor?: number;
+  currency?: string;
+}
+
 export interface ConfigurationItem {
   id?: string;
   ci_id: string;
@@ -83,6 +95,7 @@ export interface ConfigurationItem {
   warranty_expiry?: string;
   end_of_life?: string;
   decommission_date?: string;
+  financials?: AssetFinancials;
   tags: string[];
   created_at: string;
   updated_at: string;
@@ -137,6 +150,7 @@ export interface CreateCIRequest {
   install_date?: string;
   warranty_expiry?: string;
   end_of_life?: string;
+  financials?: AssetFinancials;
 }
 
 export interface UpdateCIRequest {
@@ -160,6 +174,7 @@ export interface UpdateCIRequest {
   install_date?: string;
   warranty_expiry?: string;
   end_of_life?: string;
+  financials?: AssetFinancials;
   change_reason?: string;
 }
 
@@ -190,11 +205,37 @@ export interface CIRelationshipExpanded {
   related_ci: ConfigurationItem;
 }
 
+export type ContractType = 'SUPPORT' | 'MAINTENANCE' | 'WARRANTY' | 'LICENSE' | 'SUBSCRIPTION' | 'LEASE';
+export type ContractStatus = 'PENDING' | 'ACTIVE' | 'EXPIRING' | 'EXPIRED';
+
+export interface SupportContact {
+  phone?: string;
+  email?: string;
+  portal_url?: string;
+}
+
+/** Support entitlement from a contract covering the CI */
+export interface ContractCoverage {
+  contract_id?: string;
+  name: string;
+  vendor: string;
+  contract_number?: string;
+  contract_type: ContractType;
+  coverage_level?: string;
+  response_time_hours?: number;
+  support_contact: SupportContact;
+  start_date: string;
+  end_date: string;
+  status: ContractStatus;
+  days_remaining: number;
+}
+
 export interface CIDetailResponse {
   ci: ConfigurationItem;
   relationships: CIRelationshipExpanded[];
   history: CIHistory[];
   linked_tickets: string[];
+  contracts: ContractCoverage[];
 }
 
 export interface CreateRelationshipRequest {
@@ -387,6 +428,67 @@ export async function getCIHistory(ci_id: string): Promise<CIHistory[]> {
   return apiRequest<CIHistory[]>(`/cis/${ci_id}/history`);
 }
 
+// ============================================================================
+// FINANCIALS
+// ============================================================================
+
+export interface AssetBookValue {
+  id?: string;
+  ci_id: string;
+  name: string;
+  status: CIStatus;
+  location?: string;
+  currency: string;
+  purchase_cost: number;
+  purchase_date: string;
+  depreciation_method: DepreciationMethod;
+  salvage_value: number;
+  book_value: number;
+  accumulated_depreciation: number;
+  monthly_depreciation: number;
+  depreciation_end: string;
+  months_remaining: number;
+  fully_depreciated: boolean;
+}
+
+export interface DepreciationTotals {
+  asset_count: number;
+  purchase_cost: number;
+  book_value: number;
+  accumulated_depreciation: number;
+  monthly_depreciation: number;
+}
+
+export interface DepreciationReport {
+  as_of: string;
+  horizon_months: number;
+  assets: AssetBookValue[];
+  totals: Record<string, DepreciationTotals>;
+  refresh_overdue: string[];
+  refresh_forecast: {
+    month: string;
+    asset_count: number;
+    replacement_cost: Record<string, number>;
+    ci_ids: string[];
+  }[];
+}
+
+export async function getDepreciationReport(params?: {
+  as_of?: string;
+  horizon_months?: number;
+  location?: string;
+  environment?: string;
+}): Promise<DepreciationReport> {
+  const queryParams = new URLSearchParams();
+  if (params?.as_of) queryParams.append('as_of', params.as_of);
+  if (params?.horizon_months) queryParams.append('horizon_months', params.horizon_months.toString());
+  if (params?.location) queryParams.append('location', params.location);
+  if (params?.environment) queryParams.append('environment', params.environment);
+
+  const query = queryParams.toString();
+  return apiRequest<DepreciationReport>(query ? `/financials/depreciation?${query}` : '/financials/depreciation');
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
@@ -446,6 +548,7 @@ export default {
   deleteRelationship,
   analyzeImpact,
   getCIHistory,
+  getDepreciationReport,
   getCIStatusColor,
   getCICriticalityColor,
   getCIClassIcon,
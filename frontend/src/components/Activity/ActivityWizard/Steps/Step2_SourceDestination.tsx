@@ -526,9 +526,9 @@ const Step2_SourceDestination: React.FC = () => {
 
       try {
         // Fetch available hardware pool servers
-        const response = await apiGet<any[]>(`/hardware-pool/servers?status=available`);
+        const response = await apiGet<{ items: any[] }>(`/hardware-pool/servers?status=available`);
 
-        const availableServers = response || [];
+        const availableServers = response?.items || [];
         const availableCount = availableServers.length;
 
         // Show warning if low or no capacity (this is a warning, not a blocking error)
@@ -1,5 +1,6 @@
 import React, { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { errorMessage } from '../utils/errorTaxonomy';
 import { open } from '@tauri-apps/api/dialog';
 import LoadingSpinner from './LoadingSpinner';
 
@@ -155,7 +156,7 @@ export const HardwareFileUpload: React.FC = () => {
       
     } catch (err) {
       console.error('Error parsing hardware file:', err);
-      setError(err as string);
+      setError(errorMessage(err));
     } finally {
       setIsLoading(false);
     }
@@ -43,6 +43,14 @@ import {
   ShieldPersonFilled,
   HistoryRegular,
   HistoryFilled,
+  KeyRegular,
+  KeyFilled,
+  DataTrendingRegular,
+  DataTrendingFilled,
+  CalendarClockRegular,
+  CalendarClockFilled,
+  PersonLockRegular,
+  PersonLockFilled,
   ChartMultipleRegular,
   ChartMultipleFilled,
 } from '@fluentui/react-icons';
@@ -190,6 +198,10 @@ const NavigationSidebar: React.FC<NavigationSidebarProps> = ({
         { id: 'users', title: 'User Management', icon: <PeopleTeamRegular />, iconFilled: <PeopleTeamFilled />, path: '/app/admin/users' },
         { id: 'roles', title: 'Role Management', icon: <ShieldPersonRegular />, iconFilled: <ShieldPersonFilled />, path: '/app/admin/roles' },
         { id: 'audit', title: 'Audit Log', icon: <HistoryRegular />, iconFilled: <HistoryFilled />, path: '/app/admin/audit' },
+        { id: 'license', title: 'License', icon: <KeyRegular />, iconFilled: <KeyFilled />, path: '/app/admin/license' },
+        { id: 'telemetry', title: 'Usage Telemetry', icon: <DataTrendingRegular />, iconFilled: <DataTrendingFilled />, path: '/app/admin/telemetry' },
+        { id: 'retention', title: 'Data Retention', icon: <CalendarClockRegular />, iconFilled: <CalendarClockFilled />, path: '/app/admin/retention' },
+        { id: 'privacy', title: 'Personal Data', icon: <PersonLockRegular />, iconFilled: <PersonLockFilled />, path: '/app/admin/privacy' },
       ]
     }
   ];
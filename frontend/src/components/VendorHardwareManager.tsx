@@ -1,5 +1,6 @@
 import React, { useState, useCallback, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { errorMessage } from '../utils/errorTaxonomy';
 import { open } from '@tauri-apps/api/dialog';
 import LoadingSpinner from './LoadingSpinner';
 
@@ -225,7 +226,7 @@ const VendorHardwareManager: React.FC = () => {
       });
       setParsedServer(result);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
     } finally {
       setIsLoading(false);
     }
@@ -256,7 +257,7 @@ const VendorHardwareManager: React.FC = () => {
         await fetchVendorConfigurations();
       }
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       setIsConnected(false);
     } finally {
       setIsLoading(false);
@@ -272,7 +273,7 @@ const VendorHardwareManager: React.FC = () => {
       setAvailableConfigs(configs);
       await loadCacheStatus(); // Refresh cache status
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
     } finally {
       setIsLoading(false);
     }
@@ -290,7 +291,7 @@ const VendorHardwareManager: React.FC = () => {
       setParsedServer(server);
       await loadCacheStatus(); // Refresh cache status
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
     } finally {
       setIsLoading(false);
     }
@@ -312,7 +313,7 @@ const VendorHardwareManager: React.FC = () => {
         setError('Failed to connect to vendor API');
       }
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       setIsConnected(false);
     } finally {
       setIsLoading(false);
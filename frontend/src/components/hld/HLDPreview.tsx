@@ -7,7 +7,7 @@
 // Date: October 24, 2025
 // ============================================================================
 
-import React, { useState } from 'react';
+import React, { useEffect, useState } from 'react';
 import {
   PurpleGlassCard,
   PurpleGlassButton,
@@ -41,6 +41,21 @@ interface HLDProject {
   metadata?: Record<string, any>;
 }
 
+interface DryRunIssue {
+  severity: 'error' | 'warning';
+  variable?: string;
+  section_id?: string;
+  message: string;
+}
+
+interface HLDDryRunReport {
+  ready: boolean;
+  missing_count: number;
+  invalid_count: number;
+  empty_sections: number;
+  issues: DryRunIssue[];
+}
+
 interface HLDPreviewProps {
   projectId: string;
   variables: HLDVariable[];
@@ -57,6 +72,27 @@ export const HLDPreview: React.FC<HLDPreviewProps> = ({
   hldProject,
 }) => {
   const [isExporting, setIsExporting] = useState(false);
+  const [dryRun, setDryRun] = useState<HLDDryRunReport | null>(null);
+
+  // Ask the backend what an export would produce whenever variables change
+  useEffect(() => {
+    let cancelled = false;
+    fetch(`/api/v1/hld/projects/${projectId}/dry-run`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({}),
+    })
+      .then((response) => (response.ok ? response.json() : null))
+      .then((report: HLDDryRunReport | null) => {
+        if (!cancelled) setDryRun(report);
+      })
+      .catch(() => {
+        if (!cancelled) setDryRun(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, variables]);
 
   // Calculate statistics
   const totalVariables = variables.length;
@@ -70,6 +106,13 @@ export const HLDPreview: React.FC<HLDPreviewProps> = ({
 
   const percentComplete = totalVariables > 0 ? Math.round((filledVariables / totalVariables) * 100) : 0;
   const allRequiredFilled = requiredFilled === requiredVariables;
+  // The dry run knows about invalid values and empty sections too; fall back
+  // to the local count while it is unavailable
+  const canExport = dryRun ? dryRun.ready : allRequiredFilled;
+  const blockingIssues = dryRun?.issues.filter((issue) => issue.severity === 'error') ?? [];
+  // Includes VLAN/subnet clashes with other projects in the tenant
+  const networkWarnings =
+    dryRun?.issues.filter((issue) => issue.severity === 'warning' && issue.section_id === 'network_design') ?? [];
 
   // Get sections from section_order or use defaults
   const sections = hldProject?.section_order || [
@@ -99,8 +142,12 @@ export const HLDPreview: React.FC<HLDPreviewProps> = ({
 
   // Handle export to Word
   const handleExport = async () => {
-    if (!allRequiredFilled) {
-      alert(`Please fill in all required variables before exporting (${requiredFilled}/${requiredVariables} complete)`);
+    if (!canExport) {
+      alert(
+        blockingIssues.length > 0
+          ? `Please fix these before exporting:\n${blockingIssues.map((issue) => issue.message).join('\n')}`
+          : `Please fill in all required variables before exporting (${requiredFilled}/${requiredVariables} complete)`
+      );
       return;
     }
 
@@ -154,7 +201,7 @@ export const HLDPreview: React.FC<HLDPreviewProps> = ({
         <div style={{ display: 'flex', flexDirection: 'column', gap: '16px' }}>
           {/* Overall Progress */}
           <div style={{ display: 'flex', alignItems: 'center', gap: '12px' }}>
-            {allRequiredFilled ? (
+            {canExport ? (
               <DocumentCheckmarkRegular style={{ fontSize: 24, color: 'var(--colorPaletteGreenForeground1)' }} />
             ) : (
               <WarningRegular style={{ fontSize: 24, color: 'var(--colorPaletteYellowForeground1)' }} />
@@ -166,7 +213,7 @@ export const HLDPreview: React.FC<HLDPreviewProps> = ({
                 fontWeight: 600,
                 marginBottom: '4px',
               }}>
-                {allRequiredFilled ? 'Ready to Export' : 'Missing Required Fields'}
+                {canExport ? 'Ready to Export' : 'Missing Required Fields'}
               </div>
               <div style={{ 
                 fontFamily: 'Poppins, sans-serif', 
@@ -215,12 +262,44 @@ export const HLDPreview: React.FC<HLDPreviewProps> = ({
               icon={<ArrowDownloadRegular />}
               onClick={handleExport}
               loading={isExporting}
-              disabled={!allRequiredFilled}
+              disabled={!canExport}
               style={{ width: '100%' }}
             >
               {isExporting ? 'Exporting...' : 'Export to Word'}
             </PurpleGlassButton>
           </div>
+
+          {/* What blocks the export */}
+          {blockingIssues.length > 0 && (
+            <ul style={{
+              margin: 0,
+              paddingLeft: '20px',
+              fontFamily: 'Poppins, sans-serif',
+              fontSize: '13px',
+              color: 'var(--colorPaletteRedForeground1)',
+            }}>
+              {blockingIssues.slice(0, 5).map((issue) => (
+                <li key={`${issue.variable ?? issue.section_id ?? ''}-${issue.message}`}>{issue.message}</li>
+              ))}
+              {blockingIssues.length > 5 && <li>and {blockingIssues.length - 5} more</li>}
+            </ul>
+          )}
+
+          {/* Network warnings do not block the export */}
+          {networkWarnings.length > 0 && (
+            <ul style={{
+              margin: 0,
+              paddingLeft: '20px',
+              fontFamily: 'Poppins, sans-serif',
+              fontSize: '13px',
+              color: 'var(--colorPaletteYellowForeground1)',
+            }}>
+              {networkWarnings.slice(0, 5).map((issue) => (
+                <li key={issue.message}>{issue.message}</li>
+              ))}
+              {networkWarnings.length > 5 && <li>and {networkWarnings.length - 5} more</li>}
+            </ul>
+          )}
         </div>
       </PurpleGlassCard>
 
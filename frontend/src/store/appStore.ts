@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/tauri'
+import { errorMessage as toErrorMessage } from '../utils/errorTaxonomy'
 
 // Type definitions for the application state
 export interface VsphereEnvironment {
@@ -361,7 +362,7 @@ export const useAppStore = create<AppState>((set, get) => ({
   loadRVToolsFile: async (filePath: string) => {
     set({ isEnvironmentLoading: true, environmentError: null })
     try {
-      const result = await invoke<string>('process_rvtools_file', { filePath })
+      const result = await invoke<string>('process_rvtools_file', { projectId: get().selectedProject?.id, filePath })
       await get().refreshEnvironmentSummary()
       get().addNotification({
         id: Date.now().toString(),
@@ -371,7 +372,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       set({ environmentError: errorMessage })
       get().addNotification({
         id: Date.now().toString(),
@@ -387,7 +388,7 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   clearEnvironment: async () => {
     try {
-      await invoke('clear_environment')
+      await invoke('clear_environment', { projectId: get().selectedProject?.id })
       set({
         currentEnvironment: null,
         environmentSummary: null,
@@ -404,7 +405,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -417,7 +418,9 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   refreshEnvironmentSummary: async () => {
     try {
-      const summary = await invoke<EnvironmentSummary | null>('get_environment_summary')
+      const summary = await invoke<EnvironmentSummary | null>('get_environment_summary', {
+        projectId: get().selectedProject?.id,
+      })
       set({ environmentSummary: summary })
     } catch (error) {
       console.error('Failed to refresh environment summary:', error)
@@ -428,11 +431,11 @@ export const useAppStore = create<AppState>((set, get) => ({
   loadHardwareBasket: async () => {
     set({ isHardwareLoading: true, hardwareError: null })
     try {
-      const basketJson = await invoke<string>('get_hardware_basket')
+      const basketJson = await invoke<string>('get_hardware_basket', { projectId: get().selectedProject?.id })
       const basket = JSON.parse(basketJson)
       set({ hardwareBasket: basket.profiles || [] })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       set({ hardwareError: errorMessage })
     } finally {
       set({ isHardwareLoading: false })
@@ -441,7 +444,7 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   addHardwareProfile: async (profile: HardwareProfile) => {
     try {
-      await invoke('add_hardware_profile', { profile })
+      await invoke('add_hardware_profile', { projectId: get().selectedProject?.id, profile })
       await get().loadHardwareBasket()
       get().addNotification({
         id: Date.now().toString(),
@@ -451,7 +454,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -464,7 +467,7 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   removeHardwareProfile: async (profileId: string) => {
     try {
-      await invoke('remove_hardware_profile', { profileId })
+      await invoke('remove_hardware_profile', { projectId: get().selectedProject?.id, profileId })
       await get().loadHardwareBasket()
       get().addNotification({
         id: Date.now().toString(),
@@ -474,7 +477,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -487,7 +490,7 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   saveHardwareBasket: async (filePath: string) => {
     try {
-      await invoke('save_hardware_basket', { filePath })
+      await invoke('save_hardware_basket', { projectId: get().selectedProject?.id, filePath })
       get().addNotification({
         id: Date.now().toString(),
         type: 'success',
@@ -496,7 +499,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -509,7 +512,7 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   loadHardwareBasketFromFile: async (filePath: string) => {
     try {
-      await invoke('load_hardware_basket', { filePath })
+      await invoke('load_hardware_basket', { projectId: get().selectedProject?.id, filePath })
       await get().loadHardwareBasket()
       get().addNotification({
         id: Date.now().toString(),
@@ -519,7 +522,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -552,7 +555,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       set({ sizingError: errorMessage })
       get().addNotification({
         id: Date.now().toString(),
@@ -591,7 +594,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       set({ analysisError: errorMessage })
       get().addNotification({
         id: Date.now().toString(),
@@ -620,7 +623,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       set({ translationError: errorMessage })
       get().addNotification({
         id: Date.now().toString(),
@@ -653,7 +656,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       set({ tcoError: errorMessage })
       get().addNotification({
         id: Date.now().toString(),
@@ -675,7 +678,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       const settings = JSON.parse(settingsJson)
       set({ appSettings: settings })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       set({ settingsError: errorMessage })
     } finally {
       set({ isSettingsLoading: false })
@@ -694,7 +697,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -709,6 +712,7 @@ export const useAppStore = create<AppState>((set, get) => ({
   generateHLD: async (outputPath: string, sizingResult: SizingResult, translationResult: any) => {
     try {
       await invoke('generate_hld_document', {
+        projectId: get().selectedProject?.id,
         outputPath,
         sizingResult,
         translationResult,
@@ -721,7 +725,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -735,6 +739,7 @@ export const useAppStore = create<AppState>((set, get) => ({
   generateLLD: async (outputPath: string, sizingResult: SizingResult, translationResult: any) => {
     try {
       await invoke('generate_lld_document', {
+        projectId: get().selectedProject?.id,
         outputPath,
         sizingResult,
         translationResult,
@@ -747,7 +752,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       })
     } catch (error) {
-      const errorMessage = error as string
+      const errorMessage = toErrorMessage(error)
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -766,7 +771,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       const projects = JSON.parse(resultJson);
       set({ projects, isProjectsLoading: false });
     } catch (error) {
-      const errorMessage = error as string;
+      const errorMessage = toErrorMessage(error);
       set({ projectsError: errorMessage, isProjectsLoading: false });
       get().addNotification({
         id: Date.now().toString(),
@@ -785,7 +790,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       const project = JSON.parse(resultJson);
       set({ selectedProject: project, isProjectsLoading: false });
     } catch (error) {
-      const errorMessage = error as string;
+      const errorMessage = toErrorMessage(error);
       set({ projectsError: errorMessage, isProjectsLoading: false });
     }
   },
@@ -803,7 +808,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       });
     } catch (error) {
-      const errorMessage = error as string;
+      const errorMessage = toErrorMessage(error);
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -829,7 +834,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       });
     } catch (error) {
-      const errorMessage = error as string;
+      const errorMessage = toErrorMessage(error);
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -855,7 +860,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         timestamp: new Date(),
       });
     } catch (error) {
-      const errorMessage = error as string;
+      const errorMessage = toErrorMessage(error);
       get().addNotification({
         id: Date.now().toString(),
         type: 'error',
@@ -868,5 +873,12 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   setSelectedProject: (project: Project | null) => {
     set({ selectedProject: project });
+    if (project) {
+      // Environment and hardware commands work on open projects; opening
+      // one keeps any others open so switching back does not reload them
+      invoke('open_project', { id: project.id }).catch((error) =>
+        console.error('Failed to open project:', error)
+      );
+    }
   },
 }))
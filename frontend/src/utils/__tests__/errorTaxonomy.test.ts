@@ -0,0 +1,25 @@
+import { describe, it, expect } from 'vitest';
+import { errorMessage, isErrorDetail, isRetryable, type ErrorDetail } from '../errorTaxonomy';
+
+describe('errorTaxonomy', () => {
+  const upstream: ErrorDetail = {
+    code: 'UPSTREAM_UNAVAILABLE',
+    category: 'external',
+    retryable: true,
+    message: 'Failed to fetch server models: A remote service is unavailable. Please try again later.',
+    detail: 'Network error: connection refused',
+  };
+
+  it('reads the user-facing message from structured errors', () => {
+    expect(isErrorDetail(upstream)).toBe(true);
+    expect(errorMessage(upstream)).toBe(upstream.message);
+    expect(isRetryable(upstream)).toBe(true);
+  });
+
+  it('falls back for plain strings, Error objects and unknown values', () => {
+    expect(errorMessage('Project not found')).toBe('Project not found');
+    expect(errorMessage(new Error('boom'))).toBe('boom');
+    expect(errorMessage(undefined)).toBe('Something went wrong.');
+    expect(isRetryable('Project not found')).toBe(false);
+  });
+});
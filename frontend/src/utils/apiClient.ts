@@ -1,3 +1,5 @@
+import { isErrorDetail, type ErrorCategory } from './errorTaxonomy';
+
 // Default API base points to the local legacy backend
 // You can override with VITE_API_BASE_URL (e.g., http://localhost:3001)
 const API_BASE_URL = import.meta.env.VITE_API_BASE_URL || 'http://localhost:3001';
@@ -147,14 +149,65 @@ export interface CreateHardwareBasketRequest {
 export class ApiError extends Error {
   status: number;
   data?: any;
+  /** Stable code from the backend error registry, when the response carried one */
+  code?: string;
+  category?: ErrorCategory;
+  retryable: boolean;
+  /** Backend trace id (`x-trace-id`), to quote in support requests */
+  traceId?: string;
   constructor(status: number, message: string, data?: any) {
     super(message);
     this.name = 'ApiError';
     this.status = status;
     this.data = data;
+    const detail = data?.error;
+    if (isErrorDetail(detail)) {
+      this.code = detail.code;
+      this.category = detail.category;
+      this.retryable = detail.retryable === true;
+    } else {
+      this.retryable = false;
+    }
   }
 }
 
+/** Standard list envelope returned by paginated list endpoints */
+export interface Page<T> {
+  items: T[];
+  total: number;
+  limit: number;
+  offset: number;
+  /** Pass back as `cursor` for the next page; absent on the last page */
+  next_cursor?: string;
+}
+
+export interface PageParams {
+  limit?: number;
+  offset?: number;
+  cursor?: string;
+  sort_by?: string;
+  sort_order?: 'asc' | 'desc';
+}
+
+function appendPageParams(query: URLSearchParams, params?: PageParams): URLSearchParams {
+  if (params?.limit) query.append('limit', params.limit.toString());
+  if (params?.offset) query.append('offset', params.offset.toString());
+  if (params?.cursor) query.append('cursor', params.cursor);
+  if (params?.sort_by) query.append('sort_by', params.sort_by);
+  if (params?.sort_order) query.append('sort_order', params.sort_order);
+  return query;
+}
+
+/** Items of a page; mock responses are plain arrays */
+function pageItems<T>(response: Page<T> | T[]): T[] {
+  return Array.isArray(response) ? response : response?.items ?? [];
+}
+
+async function sha256Hex(bytes: ArrayBuffer): Promise<string> {
+  const digest = await crypto.subtle.digest('SHA-256', bytes);
+  return Array.from(new Uint8Array(digest), (b) => b.toString(16).padStart(2, '0')).join('');
+}
+
 export interface Ticket {
   id: string;
   title: string;
@@ -199,6 +252,568 @@ export interface CreateTicketRequest {
 // ===== Ticket Comments =====
 export type CommentType = 'NOTE' | 'WORKAROUND' | 'SOLUTION' | 'CUSTOMER_RESPONSE' | 'STATUS_UPDATE';
 
+export interface ChecklistItem {
+  title: string;
+  description?: string;
+}
+
+export interface TicketTemplate {
+  id: string;
+  name: string;
+  description?: string;
+  team_id?: string;
+  type: 'INCIDENT' | 'PROBLEM' | 'CHANGE' | 'SERVICE_REQUEST';
+  priority: 'P1' | 'P2' | 'P3' | 'P4';
+  impact?: string;
+  urgency?: string;
+  category?: string;
+  subcategory?: string;
+  assigned_group?: string;
+  title_prefix?: string;
+  description_template?: string;
+  tags: string[];
+  checklist: ChecklistItem[];
+  is_active: boolean;
+  created_by: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface TicketMacroActions {
+  status?: string;
+  priority?: string;
+  assignee?: string;
+  assigned_group?: string;
+  category?: string;
+  subcategory?: string;
+  add_tags?: string[];
+  remove_tags?: string[];
+  comment?: { content: string; is_internal: boolean };
+}
+
+export interface TicketMacro {
+  id: string;
+  name: string;
+  description?: string;
+  team_id?: string;
+  actions: TicketMacroActions;
+  is_active: boolean;
+  created_by: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface TicketTask {
+  id: string;
+  ticket_id: string;
+  title: string;
+  description?: string;
+  position: number;
+  completed: boolean;
+  completed_by?: string;
+  completed_at?: string;
+  template_id?: string;
+  created_at: string;
+}
+
+export type TicketWithTasks = Ticket & {
+  comments: TicketComment[];
+  tasks: TicketTask[];
+};
+
+export interface TicketSchedule {
+  id: string;
+  name: string;
+  description?: string;
+  template_id: string;
+  title: string;
+  cron: string;
+  ci_ids: string[];
+  ticket_per_ci: boolean;
+  assignment: { assignee?: string; rotation: string[]; assigned_group?: string };
+  calendar: { skip_weekends: boolean; holidays: string[]; policy: 'SKIP' | 'NEXT_BUSINESS_DAY' };
+  enabled: boolean;
+  next_run_at?: string;
+  next_occurrence_at?: string;
+  last_run_at?: string;
+  occurrence_count: number;
+  created_by: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface TicketScheduleRun {
+  id: string;
+  schedule_id: string;
+  occurrence_at: string;
+  status: 'CREATED' | 'SKIPPED' | 'FAILED';
+  ticket_ids: string[];
+  reason?: string;
+  created_at: string;
+}
+
+export interface Contract {
+  id: string;
+  name: string;
+  vendor: string;
+  contract_number?: string;
+  contract_type: 'SUPPORT' | 'MAINTENANCE' | 'WARRANTY' | 'LICENSE' | 'SUBSCRIPTION' | 'LEASE';
+  start_date: string;
+  end_date: string;
+  cost?: number;
+  currency?: string;
+  coverage_level?: string;
+  response_time_hours?: number;
+  support_contact: { phone?: string; email?: string; portal_url?: string };
+  covered_cis: string[];
+  notify: string[];
+  alert_days: number[];
+  auto_renew: boolean;
+  notes?: string;
+  status: 'PENDING' | 'ACTIVE' | 'EXPIRING' | 'EXPIRED';
+  days_remaining: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SparePart {
+  id: string;
+  part_number: string;
+  description: string;
+  category: 'drive' | 'memory' | 'power_supply' | 'transceiver' | 'fan' | 'other';
+  vendor?: string;
+  compatible_models: string[];
+  location: string;
+  datacenter?: string;
+  quantity: number;
+  min_quantity: number;
+  unit_cost?: number;
+  alert_emails: string[];
+  low_stock_alerted: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SparePartConsumption {
+  id: string;
+  part_id: string;
+  part_number: string;
+  ticket_id: string;
+  server_id?: string;
+  quantity: number;
+  remaining: number;
+  notes?: string;
+  consumed_by: string;
+  consumed_at: string;
+}
+
+export type PurchaseRequestStatus =
+  | 'DRAFT'
+  | 'PENDING_APPROVAL'
+  | 'APPROVED'
+  | 'REJECTED'
+  | 'ORDERED'
+  | 'PARTIALLY_RECEIVED'
+  | 'RECEIVED'
+  | 'CANCELLED';
+
+export type ReceiptTarget =
+  | { type: 'spare_part'; spare_part_id: string }
+  | { type: 'server'; model: string; asset_tag_prefix: string; location?: string; datacenter?: string };
+
+export interface PurchaseRequestLine {
+  line_no: number;
+  part_number: string;
+  description: string;
+  quantity: number;
+  unit_price: { amount: number; currency: string };
+  received_quantity: number;
+  status: 'PENDING' | 'ORDERED' | 'SHIPPED' | 'PARTIALLY_RECEIVED' | 'RECEIVED' | 'CANCELLED';
+  expected_delivery?: string;
+  receive_into?: ReceiptTarget;
+}
+
+export interface PurchaseRequest {
+  id: string;
+  title: string;
+  project_id?: string;
+  vendor: string;
+  lines: PurchaseRequestLine[];
+  totals_by_currency: Record<string, number>;
+  status: PurchaseRequestStatus;
+  workflow_instance_id?: string;
+  po_number?: string;
+  order_date?: string;
+  notes?: string;
+  requested_by: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export type IntegrationProviderType =
+  | 'NutanixPrism'
+  | 'CiscoCatalystCenter'
+  | 'F5BigIp'
+  | 'VeeamBackup'
+  | 'ActiveDirectory'
+  | 'CiscoACI'
+  | 'Splunk'
+  | 'GenericRest';
+
+export interface IntegrationSimulationSettings {
+  enabled: boolean;
+  providers: IntegrationProviderType[];
+  connection_ids: string[];
+  min_latency_ms: number;
+  max_latency_ms: number;
+  updated_at: string;
+  /** INTEGRATION_SIMULATION is set on the server; everything is simulated */
+  forced_by_env: boolean;
+}
+
+export interface ProjectDocument {
+  id: string;
+  project_id: string;
+  document_type: string;
+  document_name: string;
+  file_size: number;
+  content_type?: string;
+  storage_backend: 'disk' | 's3';
+  status: string;
+  created_at: string;
+}
+
+export interface DocumentDownloadUrl {
+  url: string;
+  expires_in_secs: number;
+}
+
+export interface DocumentStorageSettings {
+  backend: 'disk' | 's3';
+  backend_source: 'settings' | 'environment';
+  directory?: string;
+  bucket?: string;
+  endpoint?: string;
+  url_ttl_secs: number;
+}
+
+/** Layers later in the list win: system ← tenant ← project ← user */
+export type SettingScope = 'system' | 'tenant' | 'project' | 'user';
+export type SettingSource = 'default' | SettingScope;
+
+export type SettingValueType =
+  | { type: 'bool' }
+  | { type: 'number'; min: number; max: number }
+  | { type: 'string'; max_len: number }
+  | { type: 'choice'; options: string[] };
+
+export interface SettingDefinition {
+  key: string;
+  description: string;
+  value_type: SettingValueType;
+  default: unknown;
+  /** Most specific scope the key can be set at */
+  max_scope: SettingScope;
+}
+
+export interface ResolvedSetting {
+  key: string;
+  value: unknown;
+  source: SettingSource;
+  source_id?: string;
+  layers: { scope: SettingScope; scope_id?: string; value?: unknown }[];
+}
+
+export interface SettingOverride {
+  scope: SettingScope;
+  scope_id: string;
+  key: string;
+  value: unknown;
+  updated_by: string;
+  updated_at: string;
+}
+
+export interface SettingChange {
+  scope: SettingScope;
+  scope_id: string;
+  key: string;
+  old_value?: unknown;
+  new_value?: unknown;
+  changed_by: string;
+  changed_at: string;
+}
+
+export type FeatureFlag =
+  | 'ai_assistant'
+  | 'auto_placement_v2'
+  | 'graphql_api'
+  | 'guest_scan'
+  | 'hardware_pool'
+  | 'rvtools_import'
+  | 'capacity_analytics'
+  | 'timeline_gantt';
+
+export interface FeatureFlagState {
+  flag: FeatureFlag;
+  enabled: boolean;
+  source: SettingSource;
+  source_id?: string;
+}
+
+export interface VersionInfo {
+  component: 'desktop' | 'backend' | string;
+  version: string;
+  schema_version: number;
+  min_supported_schema: number;
+  api_version: string;
+}
+
+export interface CompatibilityReport {
+  client: VersionInfo;
+  server: VersionInfo;
+  status: 'compatible' | 'client_outdated' | 'server_outdated';
+  /** Safe to show to users */
+  warnings: string[];
+}
+
+export type VersionChangeKind = 'added' | 'removed' | 'modified' | 'moved';
+
+export interface GeneratedSectionSnapshot {
+  section_id: string;
+  display_name: string;
+  order_index: number;
+  variables: string[];
+  content_hash: string;
+}
+
+export interface GeneratedDocumentVersion {
+  id: string;
+  project_id: string;
+  document_name: string;
+  document_type: string;
+  file_size_bytes: number;
+  version: number;
+  previous_version?: string;
+  generation_parameters: Record<string, unknown>;
+  section_snapshot: GeneratedSectionSnapshot[];
+  variables_snapshot: Record<string, unknown>;
+  storage_backend: 'disk' | 's3';
+  generated_at: string;
+  generated_by: string;
+  /** Set once a review publishes this version */
+  locked: boolean;
+  approval_record?: DocumentApprovalRecord;
+  published_file_path?: string;
+  /** Redacted copies; only listed for the full audience */
+  variants?: DocumentVariant[];
+}
+
+export type DocumentAudience = 'full' | 'customer' | 'vendor';
+
+export interface DocumentVariant {
+  audience: DocumentAudience;
+  file_path: string;
+  file_size_bytes: number;
+  redacted_sections: string[];
+  redacted_variables: string[];
+}
+
+export type DocumentReviewStatus = 'IN_REVIEW' | 'CHANGES_REQUESTED' | 'APPROVED' | 'PUBLISHED' | 'CANCELLED';
+
+export interface ReviewerAssignment {
+  user_id: string;
+  decision: 'PENDING' | 'APPROVED' | 'CHANGES_REQUESTED' | 'NOT_REQUIRED';
+  decided_at?: string;
+  comments?: string;
+  delegated_to?: string;
+}
+
+export interface DocumentApprovalRecord {
+  document_name: string;
+  document_version: number;
+  reviewed_sha256: string;
+  approvals: ReviewerAssignment[];
+  approved_at: string;
+  workflow_instance_id?: string;
+  published_by: string;
+  published_at: string;
+}
+
+export interface DocumentReview {
+  id: string;
+  document_id: string;
+  project_id: string;
+  document_version: number;
+  status: DocumentReviewStatus;
+  reviewers: ReviewerAssignment[];
+  workflow_instance_id?: string;
+  requested_by: string;
+  message?: string;
+  approval_record?: DocumentApprovalRecord;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ReviewComment {
+  id: string;
+  review_id: string;
+  section_id: string;
+  quote?: string;
+  body: string;
+  author: string;
+  reply_to?: string;
+  resolved_by?: string;
+  resolved_at?: string;
+  created_at: string;
+}
+
+export interface DocumentVersionDiff {
+  from_version: number;
+  to_version: number;
+  sections: {
+    section_id: string;
+    display_name: string;
+    change: VersionChangeKind;
+    changed_variables: string[];
+  }[];
+  variables: ValueVersionChange[];
+  parameters: ValueVersionChange[];
+}
+
+export interface ValueVersionChange {
+  name: string;
+  change: VersionChangeKind;
+  old_value?: unknown;
+  new_value?: unknown;
+}
+
+export type HypervisorType = 'hyper-v' | 'vmware' | 'azure-local' | 'kvm';
+export type IacTarget = 'auto' | 'terraform' | 'bicep';
+
+export interface AutomationPlanVm {
+  key: string;
+  name: string;
+  cluster: string;
+  wave?: string;
+  cpus: number;
+  memory_mb: number;
+  storage_gb: number;
+  os?: string;
+  source_ip?: string;
+  network?: string;
+  vlan_id?: number;
+  new_ip?: string;
+  prefix_length?: number;
+  gateway?: string;
+  dns_servers: string[];
+}
+
+export interface AutomationPlan {
+  project_id: string;
+  project_name: string;
+  clusters: {
+    key: string;
+    name: string;
+    hypervisor: HypervisorType;
+    destination_cluster_id?: string;
+    total_cores: number;
+    memory_gb: number;
+    storage_tb: number;
+  }[];
+  networks: {
+    name: string;
+    vlan_id?: number;
+    subnet?: string;
+    gateway?: string;
+    dns_servers: string[];
+    source_name: string;
+    source_subnet?: string;
+  }[];
+  waves: { key: string; name: string; sequence: number; change_window?: string }[];
+  vms: AutomationPlanVm[];
+  warnings: string[];
+}
+
+export interface AutomationBundle {
+  name: string;
+  files: { path: string; content: string }[];
+  warnings: string[];
+}
+
+export type ProjectRole = 'owner' | 'editor' | 'viewer';
+
+export interface ProjectMembership {
+  id: string;
+  project_id: string;
+  principal_type: 'user' | 'team';
+  principal_id: string;
+  role: ProjectRole;
+  audience: DocumentAudience;
+  added_by: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export type Invitee =
+  | { type: 'user'; id: string }
+  | { type: 'team'; id: string }
+  | { type: 'email'; address: string };
+
+export interface ProjectInvitation {
+  id: string;
+  project_id: string;
+  invitee: Invitee;
+  role: ProjectRole;
+  audience: DocumentAudience;
+  status: 'PENDING' | 'ACCEPTED' | 'DECLINED' | 'REVOKED';
+  invited_by: string;
+  message?: string;
+  created_at: string;
+  expires_at: string;
+  responded_by?: string;
+  responded_at?: string;
+}
+
+export interface ProjectAccessSummary {
+  project_id: string;
+  role: ProjectRole | null;
+  /** Which HLD variant the user is served */
+  audience: DocumentAudience;
+  /** Nobody has shared the project yet, so every signed-in user has access */
+  open: boolean;
+}
+
+export type UploadTarget = 'rvtools' | 'hardware_basket';
+
+export interface UploadSession {
+  id: string;
+  target: UploadTarget;
+  file_name: string;
+  total_bytes: number;
+  received_bytes: number;
+  status: 'in_progress' | 'completed';
+  /** Largest chunk the server accepts in one request */
+  max_chunk_bytes: number;
+  expires_at: string;
+}
+
+export interface ResumableUploadOptions {
+  /** Form fields the target needs, e.g. project_id or vendor_id */
+  metadata?: Record<string, string>;
+  /** Resume this session instead of starting a new one */
+  sessionId?: string;
+  onProgress?: (receivedBytes: number, totalBytes: number) => void;
+  /** Retries per chunk before giving up; the session can still be resumed */
+  maxRetries?: number;
+}
+
+export interface WatchNotificationPreferences {
+  user_id: string;
+  immediate: boolean;
+  daily_digest: boolean;
+  last_digest_at?: string;
+}
+
 export interface TicketComment {
   id: string;
   ticket_id: string;
@@ -490,6 +1105,117 @@ export interface UpdateRoleRequest {
   permissions?: string[];
 }
 
+export type LicenseStatus = 'active' | 'grace' | 'expired' | 'invalid' | 'unlicensed' | 'unenforced';
+
+export interface LicenseEntitlement {
+  status: LicenseStatus;
+  license_id?: string;
+  customer?: string;
+  issued_at?: string;
+  expires_at?: string;
+  grace_ends_at?: string;
+  days_remaining?: number;
+  seats: {
+    licensed?: number;
+    used: number;
+    available?: number;
+    over_limit: boolean;
+  };
+  modules: { module: string; enabled: boolean }[];
+  message?: string;
+  checked_at: string;
+}
+
+export interface DailyUsage {
+  day: string;
+  feature: string;
+  count: number;
+  submitted_at?: string;
+}
+
+export interface TelemetryBatch {
+  installation_id: string;
+  app_version: string;
+  day: string;
+  counts: Record<string, number>;
+}
+
+export interface TelemetryOverview {
+  enabled: boolean;
+  hard_disabled: boolean;
+  endpoint?: string;
+  installation_id?: string;
+  last_submitted_at?: string;
+  days: DailyUsage[];
+  pending: TelemetryBatch[];
+}
+
+export type RetentionDataClass = 'rvtools_rows' | 'audit_logs' | 'generated_documents';
+
+export interface RetentionPolicy {
+  id: string;
+  tenant_id?: string;
+  data_class: RetentionDataClass;
+  retain_days: number;
+  enabled: boolean;
+  updated_by: string;
+  updated_at: string;
+}
+
+export interface PurgeItem {
+  data_class: RetentionDataClass;
+  tenant_id?: string;
+  table: string;
+  retain_days: number;
+  cutoff: string;
+  records: number;
+  files: number;
+}
+
+export interface PurgeReport {
+  dry_run: boolean;
+  items: PurgeItem[];
+  total_records: number;
+  started_at: string;
+  finished_at: string;
+}
+
+export type ErasureStatus = 'PENDING_APPROVAL' | 'APPROVED' | 'REJECTED' | 'COMPLETED' | 'CANCELLED';
+
+export interface ErasureSummary {
+  pseudonym: string;
+  comments: number;
+  ticket_history: number;
+  tickets_reassigned: number;
+  audit_entries: number;
+  kb_articles: number;
+  sessions_revoked: number;
+}
+
+export interface ErasureRequest {
+  id: string;
+  user_id: string;
+  username: string;
+  reason?: string;
+  status: ErasureStatus;
+  workflow_instance_id?: string;
+  requested_by: string;
+  executed_by?: string;
+  summary?: ErasureSummary;
+  created_at: string;
+  updated_at: string;
+  completed_at?: string;
+}
+
+export interface UserDataExport {
+  exported_at: string;
+  account: Record<string, unknown>;
+  tickets_authored: Record<string, unknown>[];
+  tickets_assigned: Record<string, unknown>[];
+  comments: Record<string, unknown>[];
+  audit_entries: Record<string, unknown>[];
+}
+
 export interface AuditLogEntry {
   id: string;
   event_type: string;
@@ -923,7 +1649,7 @@ export class ApiClient {
         try {
           data = await response.json();
         } catch (_) {}
-        const message = data?.message || data?.error || `${response.status} ${response.statusText}`;
+        const message = data?.error?.message || data?.message || (typeof data?.error === 'string' ? data.error : undefined) || `${response.status} ${response.statusText}`;
         
         // Handle 401 Unauthorized - token expired or invalid
         if (response.status === 401) {
@@ -931,7 +1657,9 @@ export class ApiClient {
           window.dispatchEvent(new CustomEvent('auth:unauthorized'));
         }
         
-        throw new ApiError(response.status, message, data);
+        const apiError = new ApiError(response.status, message, data);
+        apiError.traceId = response.headers.get('x-trace-id') ?? data?.trace_id ?? undefined;
+        throw apiError;
       }
 
       this.usingMockData = false;
@@ -1021,6 +1749,21 @@ export class ApiClient {
     return fetch(`${root}/health`).then(r => r.json());
   }
 
+  /** Backend release and schema version */
+  async getBackendVersion(): Promise<VersionInfo> {
+    const response = await this.request<any>('/api/v1/version');
+    return response.data || response;
+  }
+
+  /** Compare a client (e.g. the desktop app's get_version_info) with the backend */
+  async versionHandshake(client: VersionInfo): Promise<CompatibilityReport> {
+    const response = await this.request<any>('/api/v1/version/handshake', {
+      method: 'POST',
+      body: JSON.stringify(client),
+    });
+    return response.data || response;
+  }
+
   // Projects
   async getProjects(): Promise<Project[]> {
     // Prefer /api/projects for local backend
@@ -1315,8 +2058,13 @@ export class ApiClient {
   }
 
   // ===== Tickets (ITIL) =====
-  async getTickets(): Promise<Ticket[]> {
-    return this.request('/api/v1/tickets');
+  async getTickets(params?: PageParams): Promise<Ticket[]> {
+    return pageItems(await this.getTicketsPage(params));
+  }
+
+  async getTicketsPage(params?: PageParams): Promise<Page<Ticket>> {
+    const query = appendPageParams(new URLSearchParams(), params).toString();
+    return this.request(`/api/v1/tickets${query ? `?${query}` : ''}`);
   }
 
   async getTicket(id: string): Promise<Ticket> {
@@ -1363,30 +2111,761 @@ export class ApiClient {
     });
   }
 
-  // ===== Ticket Attachments =====
-  async getTicketAttachments(ticketId: string): Promise<{ data: TicketAttachment[]; count: number }> {
+  // ===== Ticket Watching =====
+  async watchTicket(ticketId: string): Promise<Ticket> {
     const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
-    return this.request(`/api/v1/tickets/${cleanId}/attachments`);
+    return this.request(`/api/v1/tickets/${cleanId}/watch`, { method: 'POST' });
   }
 
-  async uploadTicketAttachment(ticketId: string, file: File): Promise<TicketAttachment> {
+  async unwatchTicket(ticketId: string): Promise<Ticket> {
     const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
-    const formData = new FormData();
-    formData.append('file', file);
+    return this.request(`/api/v1/tickets/${cleanId}/watch`, { method: 'DELETE' });
+  }
 
-    const token = this.getAccessToken ? this.getAccessToken() : '';
-    const response = await fetch(`${this.baseUrl}/api/v1/tickets/${cleanId}/attachments`, {
-      method: 'POST',
-      headers: {
-        ...(token && { 'Authorization': `Bearer ${token}` }),
-      },
-      body: formData,
-    });
+  async getWatchedTickets(): Promise<{ data: Ticket[]; count: number }> {
+    return this.request('/api/v1/tickets/watching');
+  }
 
-    if (!response.ok) {
-      const error = await response.json().catch(() => ({ error: 'Upload failed' }));
-      throw new Error(error.error || 'Upload failed');
-    }
+  async getWatchPreferences(): Promise<WatchNotificationPreferences> {
+    return this.request('/api/v1/tickets/watching/preferences');
+  }
+
+  async updateWatchPreferences(
+    updates: Partial<Pick<WatchNotificationPreferences, 'immediate' | 'daily_digest'>>
+  ): Promise<WatchNotificationPreferences> {
+    return this.request('/api/v1/tickets/watching/preferences', {
+      method: 'PUT',
+      body: JSON.stringify(updates),
+    });
+  }
+
+  // ===== Ticket Templates & Macros =====
+  async getTicketTemplates(includeInactive = false): Promise<{ templates: TicketTemplate[]; total: number }> {
+    return this.request(`/api/v1/ticket-templates?include_inactive=${includeInactive}`);
+  }
+
+  async createTicketTemplate(template: Partial<TicketTemplate>): Promise<TicketTemplate> {
+    return this.request('/api/v1/ticket-templates', {
+      method: 'POST',
+      body: JSON.stringify(template),
+    });
+  }
+
+  async updateTicketTemplate(templateId: string, updates: Partial<TicketTemplate>): Promise<TicketTemplate> {
+    return this.request(`/api/v1/ticket-templates/${templateId}`, {
+      method: 'PUT',
+      body: JSON.stringify(updates),
+    });
+  }
+
+  async deleteTicketTemplate(templateId: string): Promise<void> {
+    return this.request(`/api/v1/ticket-templates/${templateId}`, { method: 'DELETE' });
+  }
+
+  async createTicketFromTemplate(
+    templateId: string,
+    ticket: { title: string; description?: string; priority?: string; assignee?: string; tags?: string[] }
+  ): Promise<TicketWithTasks> {
+    return this.request(`/api/v1/ticket-templates/${templateId}/tickets`, {
+      method: 'POST',
+      body: JSON.stringify(ticket),
+    });
+  }
+
+  async applyTicketTemplate(templateId: string, ticketId: string): Promise<TicketWithTasks> {
+    const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
+    return this.request(`/api/v1/ticket-templates/${templateId}/apply/${cleanId}`, { method: 'POST' });
+  }
+
+  async getTicketMacros(includeInactive = false): Promise<{ macros: TicketMacro[]; total: number }> {
+    return this.request(`/api/v1/ticket-macros?include_inactive=${includeInactive}`);
+  }
+
+  async createTicketMacro(ticketMacro: Partial<TicketMacro>): Promise<TicketMacro> {
+    return this.request('/api/v1/ticket-macros', {
+      method: 'POST',
+      body: JSON.stringify(ticketMacro),
+    });
+  }
+
+  async updateTicketMacro(macroId: string, updates: Partial<TicketMacro>): Promise<TicketMacro> {
+    return this.request(`/api/v1/ticket-macros/${macroId}`, {
+      method: 'PUT',
+      body: JSON.stringify(updates),
+    });
+  }
+
+  async deleteTicketMacro(macroId: string): Promise<void> {
+    return this.request(`/api/v1/ticket-macros/${macroId}`, { method: 'DELETE' });
+  }
+
+  async applyTicketMacro(macroId: string, ticketId: string): Promise<TicketWithTasks> {
+    const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
+    return this.request(`/api/v1/ticket-macros/${macroId}/apply/${cleanId}`, { method: 'POST' });
+  }
+
+  async getTicketTasks(ticketId: string): Promise<{ data: TicketTask[]; count: number }> {
+    const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
+    return this.request(`/api/v1/tickets/${cleanId}/tasks`);
+  }
+
+  async updateTicketTask(ticketId: string, taskId: string, completed: boolean): Promise<TicketTask> {
+    const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
+    return this.request(`/api/v1/tickets/${cleanId}/tasks/${taskId}`, {
+      method: 'PATCH',
+      body: JSON.stringify({ completed }),
+    });
+  }
+
+  // ===== Contracts =====
+  async getContracts(filters?: {
+    vendor?: string;
+    contract_type?: string;
+    ci_id?: string;
+    expiring_within_days?: number;
+  }): Promise<{ contracts: Contract[]; total: number }> {
+    const params = new URLSearchParams();
+    Object.entries(filters ?? {}).forEach(([key, value]) => {
+      if (value !== undefined) params.append(key, String(value));
+    });
+    const query = params.toString();
+    return this.request(query ? `/api/v1/contracts?${query}` : '/api/v1/contracts');
+  }
+
+  async createContract(contract: Partial<Contract>): Promise<Contract> {
+    return this.request('/api/v1/contracts', {
+      method: 'POST',
+      body: JSON.stringify(contract),
+    });
+  }
+
+  async updateContract(contractId: string, updates: Partial<Contract>): Promise<Contract> {
+    return this.request(`/api/v1/contracts/${contractId}`, {
+      method: 'PUT',
+      body: JSON.stringify(updates),
+    });
+  }
+
+  async deleteContract(contractId: string): Promise<void> {
+    return this.request(`/api/v1/contracts/${contractId}`, { method: 'DELETE' });
+  }
+
+  // ===== Spare Parts =====
+  async getSpareParts(filters?: {
+    category?: SparePart['category'];
+    location?: string;
+    model?: string;
+    low_stock?: boolean;
+  }): Promise<SparePart[]> {
+    const params = new URLSearchParams();
+    Object.entries(filters ?? {}).forEach(([key, value]) => {
+      if (value !== undefined) params.append(key, String(value));
+    });
+    const query = params.toString();
+    return this.request(query ? `/api/v1/hardware-pool/parts?${query}` : '/api/v1/hardware-pool/parts');
+  }
+
+  async createSparePart(part: Partial<SparePart>): Promise<SparePart> {
+    return this.request('/api/v1/hardware-pool/parts', {
+      method: 'POST',
+      body: JSON.stringify(part),
+    });
+  }
+
+  async updateSparePart(partId: string, updates: Partial<SparePart>): Promise<SparePart> {
+    return this.request(`/api/v1/hardware-pool/parts/${partId}`, {
+      method: 'PUT',
+      body: JSON.stringify(updates),
+    });
+  }
+
+  async deleteSparePart(partId: string): Promise<void> {
+    return this.request(`/api/v1/hardware-pool/parts/${partId}`, { method: 'DELETE' });
+  }
+
+  async consumeSparePart(
+    partId: string,
+    consumption: { ticket_id: string; quantity: number; server_id?: string; notes?: string }
+  ): Promise<{ part: SparePart; consumption: SparePartConsumption; low_stock_alert: boolean }> {
+    return this.request(`/api/v1/hardware-pool/parts/${partId}/consume`, {
+      method: 'POST',
+      body: JSON.stringify(consumption),
+    });
+  }
+
+  async restockSparePart(partId: string, quantity: number): Promise<SparePart> {
+    return this.request(`/api/v1/hardware-pool/parts/${partId}/restock`, {
+      method: 'POST',
+      body: JSON.stringify({ quantity }),
+    });
+  }
+
+  async getSparePartConsumption(filters: { part_id?: string; ticket_id?: string }): Promise<SparePartConsumption[]> {
+    const params = new URLSearchParams();
+    Object.entries(filters).forEach(([key, value]) => {
+      if (value !== undefined) params.append(key, value);
+    });
+    return this.request(`/api/v1/hardware-pool/parts/consumption?${params.toString()}`);
+  }
+
+  // ===== Procurement =====
+  async getPurchaseRequests(filters?: {
+    status?: PurchaseRequestStatus;
+    project_id?: string;
+    vendor?: string;
+  }): Promise<{ requests: PurchaseRequest[]; total: number }> {
+    const params = new URLSearchParams();
+    Object.entries(filters ?? {}).forEach(([key, value]) => {
+      if (value !== undefined) params.append(key, String(value));
+    });
+    const query = params.toString();
+    return this.request(query ? `/api/v1/procurement/requests?${query}` : '/api/v1/procurement/requests');
+  }
+
+  async getPurchaseRequest(requestId: string): Promise<PurchaseRequest> {
+    return this.request(`/api/v1/procurement/requests/${requestId}`);
+  }
+
+  async createPurchaseRequest(request: {
+    title: string;
+    vendor: string;
+    project_id?: string;
+    notes?: string;
+    lines: {
+      part_number: string;
+      description?: string;
+      quantity: number;
+      unit_price: { amount: number; currency: string };
+      receive_into?: ReceiptTarget;
+    }[];
+  }): Promise<PurchaseRequest> {
+    return this.request('/api/v1/procurement/requests', {
+      method: 'POST',
+      body: JSON.stringify(request),
+    });
+  }
+
+  async deletePurchaseRequest(requestId: string): Promise<void> {
+    return this.request(`/api/v1/procurement/requests/${requestId}`, { method: 'DELETE' });
+  }
+
+  async submitPurchaseRequest(requestId: string, workflowId?: string): Promise<PurchaseRequest> {
+    return this.request(`/api/v1/procurement/requests/${requestId}/submit`, {
+      method: 'POST',
+      body: JSON.stringify({ workflow_id: workflowId }),
+    });
+  }
+
+  async cancelPurchaseRequest(requestId: string): Promise<PurchaseRequest> {
+    return this.request(`/api/v1/procurement/requests/${requestId}/cancel`, { method: 'POST' });
+  }
+
+  async recordPurchaseOrder(
+    requestId: string,
+    order: { po_number: string; order_date?: string; expected_delivery?: string }
+  ): Promise<PurchaseRequest> {
+    return this.request(`/api/v1/procurement/requests/${requestId}/order`, {
+      method: 'POST',
+      body: JSON.stringify(order),
+    });
+  }
+
+  async updatePurchaseLine(
+    requestId: string,
+    lineNo: number,
+    update: { status?: 'SHIPPED' | 'CANCELLED'; expected_delivery?: string }
+  ): Promise<PurchaseRequest> {
+    return this.request(`/api/v1/procurement/requests/${requestId}/lines/${lineNo}`, {
+      method: 'PUT',
+      body: JSON.stringify(update),
+    });
+  }
+
+  async receivePurchaseLine(
+    requestId: string,
+    lineNo: number,
+    receipt: { quantity: number; serial_numbers?: string[] }
+  ): Promise<PurchaseRequest> {
+    return this.request(`/api/v1/procurement/requests/${requestId}/lines/${lineNo}/receive`, {
+      method: 'POST',
+      body: JSON.stringify(receipt),
+    });
+  }
+
+  // ===== Integration Simulation =====
+  async getIntegrationSimulation(): Promise<IntegrationSimulationSettings> {
+    return this.request('/api/v1/settings/integration-simulation');
+  }
+
+  async updateIntegrationSimulation(update: {
+    enabled?: boolean;
+    providers?: IntegrationProviderType[];
+    connection_ids?: string[];
+    min_latency_ms?: number;
+    max_latency_ms?: number;
+  }): Promise<IntegrationSimulationSettings> {
+    return this.request('/api/v1/settings/integration-simulation', {
+      method: 'PUT',
+      body: JSON.stringify(update),
+    });
+  }
+
+  // ===== Project Documents =====
+  async getProjectDocuments(projectId: string): Promise<ProjectDocument[]> {
+    return this.request(`/api/v1/documents/projects/${projectId}`);
+  }
+
+  async uploadProjectDocument(projectId: string, file: File, documentType?: string): Promise<ProjectDocument> {
+    const formData = new FormData();
+    if (documentType) {
+      formData.append('document_type', documentType);
+    }
+    formData.append('file', file);
+
+    const token = this.getAccessToken ? this.getAccessToken() : '';
+    const response = await fetch(`${this.baseUrl}/api/v1/documents/projects/${projectId}`, {
+      method: 'POST',
+      headers: {
+        ...(token && { 'Authorization': `Bearer ${token}` }),
+      },
+      body: formData,
+    });
+
+    if (!response.ok) {
+      const error = await response.json().catch(() => ({ error: 'Upload failed' }));
+      throw new ApiError(response.status, error.error?.message || error.error || 'Upload failed', error);
+    }
+
+    return response.json();
+  }
+
+  /** Time-limited link the browser can download the document from directly */
+  async getDocumentDownloadUrl(documentId: string): Promise<DocumentDownloadUrl> {
+    return this.request(`/api/v1/documents/${documentId}/url`);
+  }
+
+  async deleteProjectDocument(documentId: string): Promise<void> {
+    await this.request(`/api/v1/documents/${documentId}`, { method: 'DELETE' });
+  }
+
+  async getDocumentStorageSettings(): Promise<DocumentStorageSettings> {
+    return this.request('/api/v1/settings/document-storage');
+  }
+
+  /** Backend for new documents; existing ones stay put until migrated */
+  async updateDocumentStorageSettings(backend: 'disk' | 's3'): Promise<DocumentStorageSettings> {
+    return this.request('/api/v1/settings/document-storage', {
+      method: 'PUT',
+      body: JSON.stringify({ backend }),
+    });
+  }
+
+  // ===== Settings Hierarchy =====
+  async getSettingDefinitions(): Promise<SettingDefinition[]> {
+    return this.request('/api/v1/settings/keys');
+  }
+
+  /** Effective value of every key for the signed-in user */
+  async getEffectiveSettings(projectId?: string): Promise<ResolvedSetting[]> {
+    const query = projectId ? `?project_id=${encodeURIComponent(projectId)}` : '';
+    return this.request(`/api/v1/settings/effective${query}`);
+  }
+
+  async getEffectiveSetting(key: string, projectId?: string): Promise<ResolvedSetting> {
+    const query = projectId ? `?project_id=${encodeURIComponent(projectId)}` : '';
+    return this.request(`/api/v1/settings/effective/${key}${query}`);
+  }
+
+  /** Omit scopeId for your own tenant or user layer */
+  async getSettingOverrides(scope: SettingScope, scopeId?: string): Promise<SettingOverride[]> {
+    const params = new URLSearchParams({ scope });
+    if (scopeId) params.append('scope_id', scopeId);
+    return this.request(`/api/v1/settings/overrides?${params.toString()}`);
+  }
+
+  async setSettingOverride(key: string, scope: SettingScope, value: unknown, scopeId?: string): Promise<SettingOverride> {
+    return this.request(`/api/v1/settings/overrides/${key}`, {
+      method: 'PUT',
+      body: JSON.stringify({ scope, scope_id: scopeId, value }),
+    });
+  }
+
+  async clearSettingOverride(key: string, scope: SettingScope, scopeId?: string): Promise<void> {
+    const params = new URLSearchParams({ scope });
+    if (scopeId) params.append('scope_id', scopeId);
+    await this.request(`/api/v1/settings/overrides/${key}?${params.toString()}`, { method: 'DELETE' });
+  }
+
+  async getSettingChanges(filter: { key?: string; scope?: SettingScope; scopeId?: string; limit?: number } = {}): Promise<SettingChange[]> {
+    const params = new URLSearchParams();
+    if (filter.key) params.append('key', filter.key);
+    if (filter.scope) params.append('scope', filter.scope);
+    if (filter.scopeId) params.append('scope_id', filter.scopeId);
+    if (filter.limit) params.append('limit', String(filter.limit));
+    const query = params.toString();
+    return this.request(`/api/v1/settings/changes${query ? `?${query}` : ''}`);
+  }
+
+  // ===== Feature Flags =====
+  /** Every flag for the signed-in user; toggle one with setSettingOverride('features.<flag>', ...) */
+  async getFeatureFlags(projectId?: string): Promise<FeatureFlagState[]> {
+    const query = projectId ? `?project_id=${encodeURIComponent(projectId)}` : '';
+    return this.request(`/api/v1/features${query}`);
+  }
+
+  // ===== HLD Versions =====
+  /** Every HLD export, newest first */
+  async getHLDVersions(projectId: string): Promise<GeneratedDocumentVersion[]> {
+    return this.request(`/api/v1/hld/projects/${projectId}/versions`);
+  }
+
+  async getHLDVersion(projectId: string, version: number): Promise<GeneratedDocumentVersion> {
+    return this.request(`/api/v1/hld/projects/${projectId}/versions/${version}`);
+  }
+
+  getHLDVersionDownloadUrl(projectId: string, version: number): string {
+    return `${this.baseUrl}/api/v1/hld/projects/${projectId}/versions/${version}/content`;
+  }
+
+  /** Sections, variables and parameters that changed between two exports */
+  async diffHLDVersions(projectId: string, from: number, to: number): Promise<DocumentVersionDiff> {
+    return this.request(`/api/v1/hld/projects/${projectId}/diff?from=${from}&to=${to}`);
+  }
+
+  // ===== Document Reviews =====
+  async getDocumentReviews(filters?: {
+    project_id?: string;
+    status?: DocumentReviewStatus;
+  }): Promise<DocumentReview[]> {
+    const params = new URLSearchParams();
+    Object.entries(filters ?? {}).forEach(([key, value]) => {
+      if (value !== undefined) params.append(key, String(value));
+    });
+    const query = params.toString();
+    return this.request(query ? `/api/v1/document-reviews?${query}` : '/api/v1/document-reviews');
+  }
+
+  async getDocumentReview(
+    reviewId: string
+  ): Promise<DocumentReview & { comments: ReviewComment[]; open_comments: number }> {
+    return this.request(`/api/v1/document-reviews/${reviewId}`);
+  }
+
+  /** Send an HLD version out for review */
+  async startDocumentReview(request: {
+    project_id: string;
+    document_version: number;
+    reviewers: string[];
+    workflow_id?: string;
+    message?: string;
+  }): Promise<DocumentReview> {
+    return this.request('/api/v1/document-reviews', {
+      method: 'POST',
+      body: JSON.stringify(request),
+    });
+  }
+
+  async cancelDocumentReview(reviewId: string): Promise<DocumentReview> {
+    return this.request(`/api/v1/document-reviews/${reviewId}/cancel`, { method: 'POST' });
+  }
+
+  /** Lock an approved version and store it with its approval page */
+  async publishDocumentReview(reviewId: string): Promise<DocumentReview> {
+    return this.request(`/api/v1/document-reviews/${reviewId}/publish`, { method: 'POST' });
+  }
+
+  getPublishedDocumentDownloadUrl(reviewId: string): string {
+    return `${this.baseUrl}/api/v1/document-reviews/${reviewId}/published/content`;
+  }
+
+  async addReviewComment(
+    reviewId: string,
+    comment: { section_id: string; body: string; quote?: string; reply_to?: string }
+  ): Promise<ReviewComment> {
+    return this.request(`/api/v1/document-reviews/${reviewId}/comments`, {
+      method: 'POST',
+      body: JSON.stringify(comment),
+    });
+  }
+
+  async resolveReviewComment(reviewId: string, commentId: string): Promise<ReviewComment> {
+    return this.request(`/api/v1/document-reviews/${reviewId}/comments/${commentId}/resolve`, { method: 'POST' });
+  }
+
+  // ===== Project Access =====
+  /** The signed-in user's role on a project */
+  async getMyProjectAccess(projectId: string): Promise<ProjectAccessSummary> {
+    return this.request(`/api/v1/project-access/projects/${projectId}/me`);
+  }
+
+  async getProjectMembers(projectId: string): Promise<ProjectMembership[]> {
+    return this.request(`/api/v1/project-access/projects/${projectId}/members`);
+  }
+
+  async updateProjectMember(
+    projectId: string,
+    memberId: string,
+    role: ProjectRole,
+    audience?: DocumentAudience
+  ): Promise<ProjectMembership> {
+    return this.request(`/api/v1/project-access/projects/${projectId}/members/${memberId}`, {
+      method: 'PUT',
+      body: JSON.stringify({ role, audience }),
+    });
+  }
+
+  /** Remove a member, or leave the project when it is your own membership */
+  async removeProjectMember(projectId: string, memberId: string): Promise<void> {
+    return this.request(`/api/v1/project-access/projects/${projectId}/members/${memberId}`, { method: 'DELETE' });
+  }
+
+  async getProjectInvitations(projectId: string): Promise<ProjectInvitation[]> {
+    return this.request(`/api/v1/project-access/projects/${projectId}/invitations`);
+  }
+
+  async inviteToProject(
+    projectId: string,
+    invitation: { invitee: Invitee; role: ProjectRole; audience?: DocumentAudience; message?: string }
+  ): Promise<ProjectInvitation> {
+    return this.request(`/api/v1/project-access/projects/${projectId}/invitations`, {
+      method: 'POST',
+      body: JSON.stringify(invitation),
+    });
+  }
+
+  async revokeProjectInvitation(projectId: string, invitationId: string): Promise<ProjectInvitation> {
+    return this.request(`/api/v1/project-access/projects/${projectId}/invitations/${invitationId}`, {
+      method: 'DELETE',
+    });
+  }
+
+  /** Pending invitations the signed-in user can accept */
+  async getMyProjectInvitations(): Promise<ProjectInvitation[]> {
+    return this.request('/api/v1/project-access/invitations');
+  }
+
+  async acceptProjectInvitation(invitationId: string): Promise<ProjectMembership> {
+    return this.request(`/api/v1/project-access/invitations/${invitationId}/accept`, { method: 'POST' });
+  }
+
+  async declineProjectInvitation(invitationId: string): Promise<ProjectInvitation> {
+    return this.request(`/api/v1/project-access/invitations/${invitationId}/decline`, { method: 'POST' });
+  }
+
+  // ===== Automation Exports =====
+  /** Placement plan resolved against network mappings and waves */
+  async getAutomationPlan(projectId: string, hypervisor?: HypervisorType): Promise<AutomationPlan> {
+    const query = hypervisor ? `?hypervisor=${hypervisor}` : '';
+    return this.request(`/api/v1/automation-exports/projects/${projectId}/plan${query}`);
+  }
+
+  async getIacScaffold(
+    projectId: string,
+    options?: { target?: IacTarget; hypervisor?: HypervisorType }
+  ): Promise<AutomationBundle> {
+    const params = new URLSearchParams();
+    Object.entries(options ?? {}).forEach(([key, value]) => {
+      if (value !== undefined) params.append(key, String(value));
+    });
+    const query = params.toString();
+    return this.request(`/api/v1/automation-exports/projects/${projectId}/iac${query ? `?${query}` : ''}`);
+  }
+
+  getIacScaffoldDownloadUrl(projectId: string, target: IacTarget = 'auto'): string {
+    return `${this.baseUrl}/api/v1/automation-exports/projects/${projectId}/iac?format=zip&target=${target}`;
+  }
+
+  /** Ansible inventory grouped by wave and target cluster, with a playbook per wave */
+  async getAnsibleExport(projectId: string): Promise<AutomationBundle> {
+    return this.request(`/api/v1/automation-exports/projects/${projectId}/ansible`);
+  }
+
+  getAnsibleExportDownloadUrl(projectId: string): string {
+    return `${this.baseUrl}/api/v1/automation-exports/projects/${projectId}/ansible?format=zip`;
+  }
+
+  /** Hyper-V PowerShell scripts that create and validate the VM shells of each wave */
+  async getPowerShellExport(projectId: string): Promise<AutomationBundle> {
+    return this.request(`/api/v1/automation-exports/projects/${projectId}/powershell`);
+  }
+
+  getPowerShellExportDownloadUrl(projectId: string): string {
+    return `${this.baseUrl}/api/v1/automation-exports/projects/${projectId}/powershell?format=zip`;
+  }
+
+  // ===== Resumable Uploads =====
+  async createUploadSession(request: {
+    target: UploadTarget;
+    file_name: string;
+    total_bytes: number;
+    sha256?: string;
+    metadata?: Record<string, string>;
+  }): Promise<UploadSession> {
+    return this.request('/api/v1/uploads', {
+      method: 'POST',
+      body: JSON.stringify(request),
+    });
+  }
+
+  async getUploadSession(sessionId: string): Promise<UploadSession> {
+    return this.request(`/api/v1/uploads/${sessionId}`);
+  }
+
+  async abortUploadSession(sessionId: string): Promise<void> {
+    await this.request(`/api/v1/uploads/${sessionId}`, { method: 'DELETE' });
+  }
+
+  private async uploadFetch(path: string, init: RequestInit): Promise<Response> {
+    const token = this.getAccessToken ? this.getAccessToken() : '';
+    return fetch(`${this.baseUrl}/api/v1/uploads/${path}`, {
+      ...init,
+      headers: {
+        ...(token && { 'Authorization': `Bearer ${token}` }),
+        ...init.headers,
+      },
+    });
+  }
+
+  /** Send one chunk at `offset`; resolves to the server's new offset */
+  async uploadChunk(sessionId: string, offset: number, chunk: Blob): Promise<number> {
+    const bytes = await chunk.arrayBuffer();
+    const response = await this.uploadFetch(sessionId, {
+      method: 'PATCH',
+      headers: {
+        'Content-Type': 'application/offset+octet-stream',
+        'Upload-Offset': String(offset),
+        'Upload-Checksum': `sha256 ${await sha256Hex(bytes)}`,
+      },
+      body: bytes,
+    });
+    const serverOffset = Number(response.headers.get('Upload-Offset'));
+    // 409: the server is elsewhere, e.g. it stored a chunk whose response was lost
+    if (response.status === 409 && !Number.isNaN(serverOffset)) {
+      return serverOffset;
+    }
+    if (!response.ok) {
+      const error = await response.json().catch(() => ({ error: 'Chunk upload failed' }));
+      throw new ApiError(response.status, error.error?.message || error.error || 'Chunk upload failed', error);
+    }
+    return serverOffset;
+  }
+
+  /** Verify and import the assembled file; returns the target's upload response */
+  async completeUploadSession<T = unknown>(sessionId: string): Promise<T> {
+    const response = await this.uploadFetch(`${sessionId}/complete`, { method: 'POST' });
+    if (!response.ok) {
+      const error = await response.json().catch(() => ({ error: 'Upload failed' }));
+      throw new ApiError(response.status, error.error?.message || error.error || 'Upload failed', error);
+    }
+    return response.json();
+  }
+
+  /**
+   * Upload a large file in chunks, retrying failed chunks from the offset
+   * the server last acknowledged. Pass `sessionId` to resume an upload
+   * interrupted by a reload.
+   */
+  async uploadResumable<T = unknown>(
+    target: UploadTarget,
+    file: File,
+    options: ResumableUploadOptions = {}
+  ): Promise<T> {
+    const maxRetries = options.maxRetries ?? 5;
+    const session = options.sessionId
+      ? await this.getUploadSession(options.sessionId)
+      : await this.createUploadSession({
+          target,
+          file_name: file.name,
+          total_bytes: file.size,
+          metadata: options.metadata,
+        });
+
+    let offset = session.received_bytes;
+    let failures = 0;
+    while (offset < file.size) {
+      try {
+        offset = await this.uploadChunk(session.id, offset, file.slice(offset, offset + session.max_chunk_bytes));
+        failures = 0;
+        options.onProgress?.(offset, file.size);
+      } catch (error) {
+        failures += 1;
+        if (failures > maxRetries) {
+          throw error;
+        }
+        await new Promise((resolve) => setTimeout(resolve, 1000 * 2 ** (failures - 1)));
+        offset = (await this.getUploadSession(session.id)).received_bytes;
+      }
+    }
+
+    return this.completeUploadSession<T>(session.id);
+  }
+
+  // ===== Recurring Ticket Schedules =====
+  async getTicketSchedules(): Promise<{ schedules: TicketSchedule[]; total: number }> {
+    return this.request('/api/v1/tickets/schedules');
+  }
+
+  async createTicketSchedule(schedule: Partial<TicketSchedule>): Promise<TicketSchedule> {
+    return this.request('/api/v1/tickets/schedules', {
+      method: 'POST',
+      body: JSON.stringify(schedule),
+    });
+  }
+
+  async updateTicketSchedule(scheduleId: string, updates: Partial<TicketSchedule>): Promise<TicketSchedule> {
+    return this.request(`/api/v1/tickets/schedules/${scheduleId}`, {
+      method: 'PUT',
+      body: JSON.stringify(updates),
+    });
+  }
+
+  async deleteTicketSchedule(scheduleId: string): Promise<void> {
+    return this.request(`/api/v1/tickets/schedules/${scheduleId}`, { method: 'DELETE' });
+  }
+
+  async previewTicketSchedule(
+    scheduleId: string,
+    count = 5
+  ): Promise<{ occurrence_at: string; run_at?: string }[]> {
+    return this.request(`/api/v1/tickets/schedules/${scheduleId}/preview?count=${count}`);
+  }
+
+  async getTicketScheduleRuns(scheduleId: string): Promise<TicketScheduleRun[]> {
+    return this.request(`/api/v1/tickets/schedules/${scheduleId}/runs`);
+  }
+
+  async runTicketSchedule(scheduleId: string): Promise<TicketScheduleRun> {
+    return this.request(`/api/v1/tickets/schedules/${scheduleId}/run`, { method: 'POST' });
+  }
+
+  async skipTicketSchedule(scheduleId: string): Promise<TicketSchedule> {
+    return this.request(`/api/v1/tickets/schedules/${scheduleId}/skip`, { method: 'POST' });
+  }
+
+  // ===== Ticket Attachments =====
+  async getTicketAttachments(ticketId: string): Promise<{ data: TicketAttachment[]; count: number }> {
+    const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
+    return this.request(`/api/v1/tickets/${cleanId}/attachments`);
+  }
+
+  async uploadTicketAttachment(ticketId: string, file: File): Promise<TicketAttachment> {
+    const cleanId = ticketId.startsWith('ticket:') ? ticketId.split(':')[1] : ticketId;
+    const formData = new FormData();
+    formData.append('file', file);
+
+    const token = this.getAccessToken ? this.getAccessToken() : '';
+    const response = await fetch(`${this.baseUrl}/api/v1/tickets/${cleanId}/attachments`, {
+      method: 'POST',
+      headers: {
+        ...(token && { 'Authorization': `Bearer ${token}` }),
+      },
+      body: formData,
+    });
+
+    if (!response.ok) {
+      const error = await response.json().catch(() => ({ error: 'Upload failed' }));
+      throw new Error(error.error || 'Upload failed');
+    }
 
     return response.json();
   }
@@ -1451,12 +2930,12 @@ export class ApiClient {
   }
 
   // ===== Assets (CMDB) =====
-  async getAssets(filter?: { asset_type?: string; status?: string }): Promise<Asset[]> {
-    const queryParams = new URLSearchParams();
+  async getAssets(filter?: { asset_type?: string; status?: string } & PageParams): Promise<Asset[]> {
+    const queryParams = appendPageParams(new URLSearchParams(), filter);
     if (filter?.asset_type) queryParams.append('asset_type', filter.asset_type);
     if (filter?.status) queryParams.append('status', filter.status);
     
-    return this.request(`/api/v1/assets?${queryParams.toString()}`);
+    return pageItems(await this.request<Page<Asset>>(`/api/v1/assets?${queryParams.toString()}`));
   }
 
   async getAsset(id: string): Promise<Asset> {
@@ -1564,8 +3043,8 @@ export class ApiClient {
     status?: KBArticleStatus;
     page?: number;
     page_size?: number;
-  }): Promise<KBArticle[]> {
-    const queryParams = new URLSearchParams();
+  } & PageParams): Promise<KBArticle[]> {
+    const queryParams = appendPageParams(new URLSearchParams(), params);
     if (params?.query) queryParams.append('query', params.query);
     if (params?.category_id) queryParams.append('category_id', params.category_id);
     if (params?.tags) params.tags.forEach(tag => queryParams.append('tags', tag));
@@ -1573,7 +3052,7 @@ export class ApiClient {
     if (params?.page) queryParams.append('page', params.page.toString());
     if (params?.page_size) queryParams.append('page_size', params.page_size.toString());
     
-    return this.request(`/api/v1/knowledge-base/articles?${queryParams.toString()}`);
+    return pageItems(await this.request<Page<KBArticle>>(`/api/v1/knowledge-base/articles?${queryParams.toString()}`));
   }
 
   async getKBArticle(id: string): Promise<KBArticle> {
@@ -1667,14 +3146,14 @@ export class ApiClient {
     category_id?: string;
     tags?: string[];
   }): Promise<KBArticle[]> {
-    return this.request('/api/v1/knowledge-base/articles/search', {
+    return pageItems(await this.request<Page<KBArticle>>('/api/v1/knowledge-base/articles/search', {
       method: 'POST',
       body: JSON.stringify({
         query,
         category_id: params?.category_id,
         tags: params?.tags,
       }),
-    });
+    }));
   }
 
   async getKBCategories(): Promise<KBCategory[]> {
@@ -1927,6 +3406,91 @@ export class ApiClient {
     };
   }
 
+  // ===== License =====
+  async getLicenseEntitlement(): Promise<LicenseEntitlement> {
+    return this.request('/api/v1/license');
+  }
+
+  async installLicense(key: string): Promise<LicenseEntitlement> {
+    return this.request('/api/v1/license', {
+      method: 'PUT',
+      body: JSON.stringify({ key }),
+    });
+  }
+
+  // ===== Usage Telemetry =====
+  async getUsageTelemetry(): Promise<TelemetryOverview> {
+    return this.request('/api/v1/usage-telemetry');
+  }
+
+  async setUsageTelemetryEnabled(enabled: boolean): Promise<TelemetryOverview> {
+    return this.request('/api/v1/usage-telemetry', {
+      method: 'PUT',
+      body: JSON.stringify({ enabled }),
+    });
+  }
+
+  async submitUsageTelemetry(): Promise<{ batches_sent: number }> {
+    return this.request('/api/v1/usage-telemetry/submit', { method: 'POST' });
+  }
+
+  async purgeUsageTelemetry(): Promise<TelemetryOverview> {
+    return this.request('/api/v1/usage-telemetry/data', { method: 'DELETE' });
+  }
+
+  // ===== Personal Data =====
+  async exportUserData(userId: string): Promise<UserDataExport> {
+    return this.request(`/api/v1/privacy/users/${encodeURIComponent(userId)}/export`);
+  }
+
+  async getErasureRequests(): Promise<ErasureRequest[]> {
+    return this.request('/api/v1/privacy/erasure-requests');
+  }
+
+  async createErasureRequest(userId: string, reason?: string): Promise<ErasureRequest> {
+    return this.request('/api/v1/privacy/erasure-requests', {
+      method: 'POST',
+      body: JSON.stringify({ user_id: userId, reason }),
+    });
+  }
+
+  async executeErasureRequest(id: string): Promise<ErasureRequest> {
+    return this.request(`/api/v1/privacy/erasure-requests/${encodeURIComponent(id)}/execute`, { method: 'POST' });
+  }
+
+  async cancelErasureRequest(id: string): Promise<ErasureRequest> {
+    return this.request(`/api/v1/privacy/erasure-requests/${encodeURIComponent(id)}/cancel`, { method: 'POST' });
+  }
+
+  // ===== Data Retention =====
+  async getRetentionPolicies(): Promise<RetentionPolicy[]> {
+    return this.request('/api/v1/retention/policies');
+  }
+
+  async saveRetentionPolicy(policy: {
+    tenant_id?: string;
+    data_class: RetentionDataClass;
+    retain_days: number;
+    enabled: boolean;
+  }): Promise<RetentionPolicy> {
+    return this.request('/api/v1/retention/policies', {
+      method: 'PUT',
+      body: JSON.stringify(policy),
+    });
+  }
+
+  async deleteRetentionPolicy(id: string): Promise<{ deleted: string }> {
+    return this.request(`/api/v1/retention/policies/${encodeURIComponent(id)}`, { method: 'DELETE' });
+  }
+
+  async previewRetentionPurge(): Promise<PurgeReport> {
+    return this.request('/api/v1/retention/preview');
+  }
+
+  async runRetentionPurge(): Promise<PurgeReport> {
+    return this.request('/api/v1/retention/purge', { method: 'POST' });
+  }
+
   // ===== Workflows =====
   async getWorkflows(): Promise<{ workflows: WorkflowDefinition[]; total: number }> {
     return this.request('/api/v1/workflows');
@@ -0,0 +1,56 @@
+// Structured errors returned by the backend API (`error` in the response
+// envelope) and by Tauri commands. Branch on `code`, never on message text;
+// the full list is served by GET /api/v1/error-codes and the
+// `list_error_codes` command.
+
+export type ErrorCategory =
+  | 'validation'
+  | 'not_found'
+  | 'conflict'
+  | 'authentication'
+  | 'authorization'
+  | 'unsupported'
+  | 'external'
+  | 'configuration'
+  | 'processing'
+  | 'internal';
+
+export interface ErrorDetail {
+  code: string;
+  category: ErrorCategory;
+  retryable: boolean;
+  /** Safe to show to end users */
+  message: string;
+  /** Scrubbed technical detail for logs and support tickets */
+  detail?: string;
+}
+
+export interface ErrorCode {
+  code: string;
+  category: ErrorCategory;
+  http_status: number;
+  retryable: boolean;
+  message: string;
+}
+
+export function isErrorDetail(error: unknown): error is ErrorDetail {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as ErrorDetail).code === 'string' &&
+    typeof (error as ErrorDetail).message === 'string'
+  );
+}
+
+/** User-facing message for anything thrown by `invoke` or the API client */
+export function errorMessage(error: unknown): string {
+  if (isErrorDetail(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  if (typeof error === 'string') return error;
+  return 'Something went wrong.';
+}
+
+/** Whether repeating the same call later may succeed */
+export function isRetryable(error: unknown): boolean {
+  return isErrorDetail(error) && error.retryable === true;
+}
@@ -580,6 +580,9 @@ export function AuditLogView() {
     { value: 'PERMISSION_DENIED', label: 'Permission Denied' },
     { value: 'ROLE_ASSIGNED', label: 'Role Assigned' },
     { value: 'ROLE_REVOKED', label: 'Role Revoked' },
+    { value: 'DATA_PURGE', label: 'Data Purge' },
+    { value: 'DATA_EXPORT', label: 'Data Export' },
+    { value: 'DATA_ERASURE', label: 'Data Erasure' },
   ];
   
   const resourceTypeOptions = [
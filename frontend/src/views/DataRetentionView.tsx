@@ -0,0 +1,302 @@
+// Archer ITSM - Data Retention View
+// Admin interface for per-tenant retention policies, with a dry-run preview
+// of what the nightly purge deletes
+
+import React, { useState, useEffect, useCallback } from 'react';
+import {
+  Text,
+  Badge,
+  Spinner,
+  tokens,
+  makeStyles,
+} from '@fluentui/react-components';
+import {
+  CalendarClockRegular,
+  ArrowSyncRegular,
+  EyeRegular,
+  DeleteRegular,
+  SaveRegular,
+} from '@fluentui/react-icons';
+import {
+  PurpleGlassCard,
+  PurpleGlassButton,
+  PurpleGlassInput,
+  PurpleGlassDropdown,
+  PurpleGlassSwitch,
+  PurpleGlassBreadcrumb,
+  PageHeader,
+} from '../components/ui';
+import {
+  apiClient,
+  type RetentionDataClass,
+  type RetentionPolicy,
+  type PurgeReport,
+} from '../utils/apiClient';
+
+const useStyles = makeStyles({
+  container: {
+    display: 'flex',
+    flexDirection: 'column',
+    gap: tokens.spacingVerticalXL,
+  },
+  headerActions: {
+    display: 'flex',
+    gap: tokens.spacingHorizontalS,
+  },
+  form: {
+    display: 'flex',
+    alignItems: 'flex-end',
+    flexWrap: 'wrap',
+    gap: tokens.spacingHorizontalM,
+  },
+  table: {
+    width: '100%',
+    borderCollapse: 'collapse',
+    '& th, & td': {
+      textAlign: 'left',
+      padding: `${tokens.spacingVerticalS} ${tokens.spacingHorizontalM}`,
+      borderBottom: `1px solid ${tokens.colorNeutralStroke2}`,
+    },
+  },
+  error: {
+    color: tokens.colorPaletteRedForeground1,
+  },
+});
+
+const CLASS_LABELS: Record<RetentionDataClass, string> = {
+  rvtools_rows: 'Raw RVTools rows',
+  audit_logs: 'Audit log',
+  generated_documents: 'Generated documents',
+};
+
+const classOptions = Object.entries(CLASS_LABELS).map(([value, label]) => ({ value, label }));
+
+export function DataRetentionView() {
+  const styles = useStyles();
+
+  const [policies, setPolicies] = useState<RetentionPolicy[]>([]);
+  const [report, setReport] = useState<PurgeReport | null>(null);
+  const [isLoading, setIsLoading] = useState(true);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const [tenantId, setTenantId] = useState('');
+  const [dataClass, setDataClass] = useState<RetentionDataClass>('rvtools_rows');
+  const [retainDays, setRetainDays] = useState('180');
+  const [enabled, setEnabled] = useState(true);
+
+  const loadData = useCallback(async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      setPolicies(await apiClient.getRetentionPolicies());
+    } catch (err) {
+      console.error('Failed to load retention policies:', err);
+      setError('Failed to load retention policies. Please try again.');
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadData();
+  }, [loadData]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'The request failed.');
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleSave = () => run(async () => {
+    await apiClient.saveRetentionPolicy({
+      tenant_id: tenantId.trim() || undefined,
+      data_class: dataClass,
+      retain_days: Number(retainDays),
+      enabled,
+    });
+    setReport(null);
+    await loadData();
+  });
+
+  const handleDelete = (policy: RetentionPolicy) => run(async () => {
+    await apiClient.deleteRetentionPolicy(policy.id);
+    setReport(null);
+    await loadData();
+  });
+
+  const handlePreview = () => run(async () => setReport(await apiClient.previewRetentionPurge()));
+
+  const handlePurge = () => {
+    if (window.confirm('Permanently delete all data past its retention period?')) {
+      run(async () => setReport(await apiClient.runRetentionPurge()));
+    }
+  };
+
+  const breadcrumbItems = [
+    { label: 'Dashboard', href: '/app/dashboard' },
+    { label: 'Settings', href: '/app/settings' },
+    { label: 'Data Retention' },
+  ];
+
+  return (
+    <div className={styles.container}>
+      <PurpleGlassBreadcrumb items={breadcrumbItems} />
+
+      <PageHeader
+        icon={<CalendarClockRegular />}
+        title="Data Retention"
+        subtitle="How long customer data is kept before the nightly purge deletes it"
+        actions={
+          <div className={styles.headerActions}>
+            <PurpleGlassButton
+              variant="secondary"
+              icon={<EyeRegular />}
+              onClick={handlePreview}
+              disabled={!policies.length || isBusy}
+            >
+              Preview purge
+            </PurpleGlassButton>
+            <PurpleGlassButton
+              variant="danger"
+              icon={<DeleteRegular />}
+              onClick={handlePurge}
+              disabled={!policies.length || isBusy}
+            >
+              Purge now
+            </PurpleGlassButton>
+            <PurpleGlassButton
+              variant="secondary"
+              icon={<ArrowSyncRegular />}
+              onClick={loadData}
+              disabled={isLoading}
+            >
+              Refresh
+            </PurpleGlassButton>
+          </div>
+        }
+      />
+
+      {error && <Text className={styles.error}>{error}</Text>}
+
+      <PurpleGlassCard header="Set a policy">
+        <div className={styles.form}>
+          <PurpleGlassInput
+            label="Tenant"
+            value={tenantId}
+            onChange={(e) => setTenantId(e.target.value)}
+            placeholder="Empty for the default"
+            glass="light"
+          />
+          <PurpleGlassDropdown
+            label="Data"
+            value={dataClass}
+            onChange={(value) => setDataClass(value as RetentionDataClass)}
+            options={classOptions}
+            glass="light"
+          />
+          <PurpleGlassInput
+            label="Keep for (days)"
+            type="number"
+            value={retainDays}
+            onChange={(e) => setRetainDays(e.target.value)}
+            glass="light"
+          />
+          <PurpleGlassSwitch
+            label="Purge"
+            checked={enabled}
+            onChange={(e) => setEnabled(e.target.checked)}
+          />
+          <PurpleGlassButton
+            variant="primary"
+            icon={<SaveRegular />}
+            onClick={handleSave}
+            disabled={!Number(retainDays) || isBusy}
+          >
+            Save
+          </PurpleGlassButton>
+        </div>
+      </PurpleGlassCard>
+
+      <PurpleGlassCard header="Policies">
+        {isLoading ? (
+          <Spinner size="small" label="Loading policies..." />
+        ) : policies.length === 0 ? (
+          <Text size={200}>No policies; all data is kept.</Text>
+        ) : (
+          <table className={styles.table}>
+            <thead>
+              <tr>
+                <th>Tenant</th>
+                <th>Data</th>
+                <th>Keep for</th>
+                <th>Updated</th>
+                <th />
+              </tr>
+            </thead>
+            <tbody>
+              {policies.map((policy) => (
+                <tr key={policy.id}>
+                  <td>{policy.tenant_id ?? <Badge appearance="tint">Default</Badge>}</td>
+                  <td>{CLASS_LABELS[policy.data_class]}</td>
+                  <td>{policy.enabled ? `${policy.retain_days} days` : 'Forever'}</td>
+                  <td>{policy.updated_by} · {new Date(policy.updated_at).toLocaleString()}</td>
+                  <td>
+                    <PurpleGlassButton
+                      variant="ghost"
+                      icon={<DeleteRegular />}
+                      onClick={() => handleDelete(policy)}
+                      disabled={isBusy}
+                    />
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </PurpleGlassCard>
+
+      {report && (
+        <PurpleGlassCard header={report.dry_run ? 'Purge preview' : 'Purge result'}>
+          <Text size={200}>
+            {report.dry_run
+              ? `${report.total_records} record(s) would be deleted.`
+              : `${report.total_records} record(s) deleted.`}
+          </Text>
+          {report.items.length > 0 && (
+            <table className={styles.table}>
+              <thead>
+                <tr>
+                  <th>Tenant</th>
+                  <th>Table</th>
+                  <th>Older than</th>
+                  <th>Records</th>
+                  <th>Files</th>
+                </tr>
+              </thead>
+              <tbody>
+                {report.items.map((item) => (
+                  <tr key={`${item.tenant_id ?? ''}-${item.table}`}>
+                    <td>{item.tenant_id ?? 'Default'}</td>
+                    <td>{item.table}</td>
+                    <td>{new Date(item.cutoff).toLocaleDateString()}</td>
+                    <td>{item.records}</td>
+                    <td>{item.files}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
+        </PurpleGlassCard>
+      )}
+    </div>
+  );
+}
+
+export default DataRetentionView;
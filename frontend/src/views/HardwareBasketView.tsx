@@ -11,7 +11,8 @@ import {
   ArrowUploadRegular,
   DocumentRegular,
   CheckmarkCircleRegular,
-  DatabaseRegular
+  DatabaseRegular,
+  WarningRegular
 } from '@fluentui/react-icons';
 
 interface HardwareBasket {
@@ -52,6 +53,28 @@ interface CreateHardwareBasketRequest {
   year: number;
 }
 
+interface AssemblyWarning {
+  rule: 'cpu_sockets' | 'dimm_population' | 'drive_bays' | 'power_budget';
+  severity: 'info' | 'warning' | 'error';
+  message: string;
+  expected?: string;
+  actual?: string;
+}
+
+interface ConfigurationWarnings {
+  configuration: string;
+  warnings: AssemblyWarning[];
+}
+
+// Assembly findings worth showing after an upload; info-level notes are skipped
+const collectAssemblyWarnings = (response: any): ConfigurationWarnings[] =>
+  (response?.server_configurations ?? [])
+    .map((config: any) => ({
+      configuration: config.display_name ?? config.model_number ?? 'Configuration',
+      warnings: (config.assembly_warnings ?? []).filter((w: AssemblyWarning) => w.severity !== 'info'),
+    }))
+    .filter((entry: ConfigurationWarnings) => entry.warnings.length > 0);
+
 interface UploadProgress { 
   stage: string; 
   progress: number; 
@@ -73,6 +96,7 @@ const HardwareBasketView: React.FC = () => {
   const [success, setSuccess] = useState<string | null>(null);
   const [isUploading, setIsUploading] = useState(false);
   const [uploadProgress, setUploadProgress] = useState<UploadProgress | null>(null);
+  const [assemblyWarnings, setAssemblyWarnings] = useState<ConfigurationWarnings[]>([]);
   const [showCreateDialog, setShowCreateDialog] = useState(false);
   const [showUploadDialog, setShowUploadDialog] = useState(false);
   const [searchTerm, setSearchTerm] = useState('');
@@ -195,7 +219,8 @@ const HardwareBasketView: React.FC = () => {
       
       setUploadProgress({ stage: 'Processing...', progress: 50, message: 'Uploading and parsing file' });
       
-      await apiClient.uploadHardwareBasketFile(basketId, file);
+      const response = await apiClient.uploadHardwareBasketFile(basketId, file);
+      setAssemblyWarnings(collectAssemblyWarnings(response));
       
       setUploadProgress({ stage: 'Complete', progress: 100, message: 'File uploaded successfully' });
       
@@ -1020,6 +1045,40 @@ const HardwareBasketView: React.FC = () => {
         </div>
       )}
 
+      {/* Assembly Validation Warnings */}
+      {assemblyWarnings.length > 0 && (
+        <div style={{
+          marginTop: '24px',
+          padding: '16px 20px',
+          borderRadius: '8px',
+          border: '1px solid rgba(245, 158, 11, 0.4)',
+          background: 'rgba(245, 158, 11, 0.08)'
+        }}>
+          <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', marginBottom: '12px' }}>
+            <div style={{ display: 'flex', alignItems: 'center', gap: '8px', fontWeight: 600, color: '#b45309' }}>
+              <WarningRegular style={{ fontSize: '20px' }} />
+              Configuration checks found {assemblyWarnings.reduce((sum, entry) => sum + entry.warnings.length, 0)} issue(s)
+            </div>
+            <PurpleGlassButton variant="ghost" size="small" onClick={() => setAssemblyWarnings([])}>
+              Dismiss
+            </PurpleGlassButton>
+          </div>
+          {assemblyWarnings.map((entry) => (
+            <div key={entry.configuration} style={{ marginBottom: '8px' }}>
+              <div style={{ fontWeight: 500, fontSize: '14px' }}>{entry.configuration}</div>
+              <ul style={{ margin: '4px 0 0 20px', padding: 0, fontSize: '13px' }}>
+                {entry.warnings.map((warning, index) => (
+                  <li key={index} style={{ color: warning.severity === 'error' ? '#dc2626' : '#92400e' }}>
+                    {warning.message}
+                    {warning.expected && ` (expected ${warning.expected}, found ${warning.actual})`}
+                  </li>
+                ))}
+              </ul>
+            </div>
+          ))}
+        </div>
+      )}
+
       {/* Summary Stats */}
       <div style={{ 
         marginTop: '24px',
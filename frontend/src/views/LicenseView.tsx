@@ -0,0 +1,262 @@
+// Archer ITSM - License View
+// Admin interface for the installed license: status, seats and licensed modules
+
+import React, { useState, useEffect, useCallback } from 'react';
+import {
+  Text,
+  Badge,
+  Spinner,
+  tokens,
+  makeStyles,
+  shorthands,
+} from '@fluentui/react-components';
+import {
+  KeyRegular,
+  ArrowSyncRegular,
+  PeopleRegular,
+  CalendarRegular,
+  CheckmarkCircleRegular,
+  DismissCircleRegular,
+  WarningRegular,
+} from '@fluentui/react-icons';
+import {
+  PurpleGlassCard,
+  PurpleGlassButton,
+  PurpleGlassTextarea,
+  PurpleGlassBreadcrumb,
+  PageHeader,
+} from '../components/ui';
+import {
+  apiClient,
+  type LicenseEntitlement,
+  type LicenseStatus,
+} from '../utils/apiClient';
+
+const useStyles = makeStyles({
+  container: {
+    display: 'flex',
+    flexDirection: 'column',
+    gap: tokens.spacingVerticalXL,
+  },
+  stats: {
+    display: 'grid',
+    gridTemplateColumns: 'repeat(auto-fit, minmax(220px, 1fr))',
+    gap: tokens.spacingHorizontalL,
+  },
+  statCard: {
+    display: 'flex',
+    flexDirection: 'column',
+    gap: tokens.spacingVerticalS,
+  },
+  statValue: {
+    fontSize: tokens.fontSizeHero700,
+    fontWeight: tokens.fontWeightSemibold,
+  },
+  message: {
+    display: 'flex',
+    alignItems: 'center',
+    gap: tokens.spacingHorizontalS,
+    ...shorthands.padding(tokens.spacingVerticalM, tokens.spacingHorizontalL),
+    ...shorthands.borderRadius(tokens.borderRadiusMedium),
+    backgroundColor: 'rgba(245, 158, 11, 0.1)',
+    color: tokens.colorPaletteDarkOrangeForeground1,
+  },
+  modules: {
+    display: 'grid',
+    gridTemplateColumns: 'repeat(auto-fill, minmax(180px, 1fr))',
+    gap: tokens.spacingHorizontalM,
+    marginTop: tokens.spacingVerticalM,
+  },
+  module: {
+    display: 'flex',
+    alignItems: 'center',
+    gap: tokens.spacingHorizontalS,
+  },
+  install: {
+    display: 'flex',
+    flexDirection: 'column',
+    gap: tokens.spacingVerticalM,
+  },
+  error: {
+    color: tokens.colorPaletteRedForeground1,
+  },
+});
+
+const STATUS_BADGES: Record<LicenseStatus, { label: string; color: 'success' | 'warning' | 'danger' | 'informative' }> = {
+  active: { label: 'Active', color: 'success' },
+  grace: { label: 'Grace period', color: 'warning' },
+  expired: { label: 'Expired', color: 'danger' },
+  invalid: { label: 'Invalid', color: 'danger' },
+  unlicensed: { label: 'Unlicensed', color: 'danger' },
+  unenforced: { label: 'Not enforced', color: 'informative' },
+};
+
+const MODULE_LABELS: Record<string, string> = {
+  migration: 'Migration planning',
+  automation: 'Automation exports',
+  itsm: 'ITSM',
+  cmdb: 'CMDB',
+  integrations: 'Integrations',
+  ai: 'AI assistant',
+};
+
+const formatDate = (value?: string) => (value ? new Date(value).toLocaleDateString() : '—');
+
+export function LicenseView() {
+  const styles = useStyles();
+
+  const [entitlement, setEntitlement] = useState<LicenseEntitlement | null>(null);
+  const [isLoading, setIsLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const [licenseKey, setLicenseKey] = useState('');
+  const [isInstalling, setIsInstalling] = useState(false);
+  const [installError, setInstallError] = useState<string | null>(null);
+
+  const loadData = useCallback(async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      setEntitlement(await apiClient.getLicenseEntitlement());
+    } catch (err) {
+      console.error('Failed to load license:', err);
+      setError('Failed to load the license. Please try again.');
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadData();
+  }, [loadData]);
+
+  const handleInstall = async () => {
+    setIsInstalling(true);
+    setInstallError(null);
+    try {
+      setEntitlement(await apiClient.installLicense(licenseKey.trim()));
+      setLicenseKey('');
+    } catch (err) {
+      console.error('Failed to install license:', err);
+      setInstallError(err instanceof Error ? err.message : 'The license key could not be installed.');
+    } finally {
+      setIsInstalling(false);
+    }
+  };
+
+  const breadcrumbItems = [
+    { label: 'Dashboard', href: '/app/dashboard' },
+    { label: 'Settings', href: '/app/settings' },
+    { label: 'License' },
+  ];
+
+  const badge = entitlement ? STATUS_BADGES[entitlement.status] : null;
+  const seats = entitlement?.seats;
+
+  return (
+    <div className={styles.container}>
+      <PurpleGlassBreadcrumb items={breadcrumbItems} />
+
+      <PageHeader
+        icon={<KeyRegular />}
+        title="License"
+        subtitle="Seats, licensed modules and expiry of this installation"
+        actions={
+          <PurpleGlassButton
+            variant="secondary"
+            icon={<ArrowSyncRegular />}
+            onClick={loadData}
+            disabled={isLoading}
+          >
+            Refresh
+          </PurpleGlassButton>
+        }
+      />
+
+      {isLoading && !entitlement ? (
+        <div style={{ display: 'flex', justifyContent: 'center', padding: tokens.spacingVerticalXXL }}>
+          <Spinner size="large" label="Loading license..." />
+        </div>
+      ) : error ? (
+        <Text className={styles.error}>{error}</Text>
+      ) : entitlement && (
+        <>
+          {entitlement.message && (
+            <div className={styles.message}>
+              <WarningRegular />
+              <Text>{entitlement.message}</Text>
+            </div>
+          )}
+
+          <div className={styles.stats}>
+            <PurpleGlassCard className={styles.statCard}>
+              <Text size={200}>Status</Text>
+              {badge && <Badge appearance="filled" color={badge.color}>{badge.label}</Badge>}
+              <Text size={200}>{entitlement.customer ?? 'No license installed'}</Text>
+            </PurpleGlassCard>
+
+            <PurpleGlassCard className={styles.statCard}>
+              <Text size={200}><PeopleRegular /> Seats in use</Text>
+              <Text className={styles.statValue}>
+                {seats?.used ?? 0}{seats?.licensed != null ? ` / ${seats.licensed}` : ''}
+              </Text>
+              {seats?.over_limit && <Badge appearance="tint" color="danger">Over the limit</Badge>}
+            </PurpleGlassCard>
+
+            <PurpleGlassCard className={styles.statCard}>
+              <Text size={200}><CalendarRegular /> Expires</Text>
+              <Text className={styles.statValue}>{formatDate(entitlement.expires_at)}</Text>
+              {entitlement.days_remaining != null && (
+                <Text size={200}>
+                  {entitlement.days_remaining} day(s) {entitlement.status === 'grace' ? 'of grace left' : 'remaining'}
+                </Text>
+              )}
+            </PurpleGlassCard>
+          </div>
+
+          <PurpleGlassCard header="Modules">
+            <div className={styles.modules}>
+              {entitlement.modules.map(({ module, enabled }) => (
+                <div key={module} className={styles.module}>
+                  {enabled ? (
+                    <CheckmarkCircleRegular style={{ color: tokens.colorPaletteGreenForeground1 }} />
+                  ) : (
+                    <DismissCircleRegular style={{ color: tokens.colorNeutralForeground3 }} />
+                  )}
+                  <Text>{MODULE_LABELS[module] ?? module}</Text>
+                </div>
+              ))}
+            </div>
+          </PurpleGlassCard>
+
+          {entitlement.status !== 'unenforced' && (
+            <PurpleGlassCard header="Install license key">
+              <div className={styles.install}>
+                <PurpleGlassTextarea
+                  value={licenseKey}
+                  onChange={(e) => setLicenseKey(e.target.value)}
+                  placeholder="Paste the license key from your license certificate"
+                  rows={4}
+                  glass="light"
+                />
+                {installError && <Text className={styles.error}>{installError}</Text>}
+                <div>
+                  <PurpleGlassButton
+                    variant="primary"
+                    icon={<KeyRegular />}
+                    onClick={handleInstall}
+                    loading={isInstalling}
+                    disabled={!licenseKey.trim() || isInstalling}
+                  >
+                    Install
+                  </PurpleGlassButton>
+                </div>
+              </div>
+            </PurpleGlassCard>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
+
+export default LicenseView;
@@ -0,0 +1,204 @@
+// Archer ITSM - Personal Data View
+// Admin interface for erasure requests: follow their approval and erase
+// the user once approved
+
+import React, { useState, useEffect, useCallback } from 'react';
+import {
+  Text,
+  Badge,
+  Spinner,
+  tokens,
+  makeStyles,
+} from '@fluentui/react-components';
+import {
+  PersonLockRegular,
+  ArrowSyncRegular,
+  DismissRegular,
+  PersonDeleteRegular,
+} from '@fluentui/react-icons';
+import {
+  PurpleGlassCard,
+  PurpleGlassButton,
+  PurpleGlassBreadcrumb,
+  PageHeader,
+} from '../components/ui';
+import {
+  apiClient,
+  type ErasureRequest,
+  type ErasureStatus,
+} from '../utils/apiClient';
+
+const useStyles = makeStyles({
+  container: {
+    display: 'flex',
+    flexDirection: 'column',
+    gap: tokens.spacingVerticalXL,
+  },
+  table: {
+    width: '100%',
+    borderCollapse: 'collapse',
+    '& th, & td': {
+      textAlign: 'left',
+      padding: `${tokens.spacingVerticalS} ${tokens.spacingHorizontalM}`,
+      borderBottom: `1px solid ${tokens.colorNeutralStroke2}`,
+    },
+  },
+  actions: {
+    display: 'flex',
+    gap: tokens.spacingHorizontalS,
+    justifyContent: 'flex-end',
+  },
+  error: {
+    color: tokens.colorPaletteRedForeground1,
+  },
+});
+
+const STATUS_COLORS: Record<ErasureStatus, 'warning' | 'success' | 'danger' | 'informative' | 'subtle'> = {
+  PENDING_APPROVAL: 'warning',
+  APPROVED: 'success',
+  REJECTED: 'danger',
+  COMPLETED: 'informative',
+  CANCELLED: 'subtle',
+};
+
+export function PersonalDataView() {
+  const styles = useStyles();
+
+  const [requests, setRequests] = useState<ErasureRequest[]>([]);
+  const [isLoading, setIsLoading] = useState(true);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadData = useCallback(async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      setRequests(await apiClient.getErasureRequests());
+    } catch (err) {
+      console.error('Failed to load erasure requests:', err);
+      setError('Failed to load erasure requests. Please try again.');
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadData();
+  }, [loadData]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      await action();
+      await loadData();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'The request failed.');
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleExecute = (request: ErasureRequest) => {
+    if (window.confirm(`Erase ${request.username}? This cannot be undone.`)) {
+      run(() => apiClient.executeErasureRequest(request.id));
+    }
+  };
+
+  const breadcrumbItems = [
+    { label: 'Dashboard', href: '/app/dashboard' },
+    { label: 'Settings', href: '/app/settings' },
+    { label: 'Personal Data' },
+  ];
+
+  return (
+    <div className={styles.container}>
+      <PurpleGlassBreadcrumb items={breadcrumbItems} />
+
+      <PageHeader
+        icon={<PersonLockRegular />}
+        title="Personal Data"
+        subtitle="Erasure requests for departed users. Request one or export a user's data from User Management."
+        actions={
+          <PurpleGlassButton
+            variant="secondary"
+            icon={<ArrowSyncRegular />}
+            onClick={loadData}
+            disabled={isLoading}
+          >
+            Refresh
+          </PurpleGlassButton>
+        }
+      />
+
+      {error && <Text className={styles.error}>{error}</Text>}
+
+      <PurpleGlassCard header="Erasure requests">
+        {isLoading && requests.length === 0 ? (
+          <Spinner size="small" label="Loading erasure requests..." />
+        ) : requests.length === 0 ? (
+          <Text size={200}>No erasure requests.</Text>
+        ) : (
+          <table className={styles.table}>
+            <thead>
+              <tr>
+                <th>User</th>
+                <th>Reason</th>
+                <th>Status</th>
+                <th>Requested</th>
+                <th />
+              </tr>
+            </thead>
+            <tbody>
+              {requests.map((request) => (
+                <tr key={request.id}>
+                  <td>{request.username}</td>
+                  <td>{request.reason ?? '—'}</td>
+                  <td>
+                    <Badge appearance="tint" color={STATUS_COLORS[request.status]}>
+                      {request.status.replace('_', ' ')}
+                    </Badge>
+                    {request.summary && (
+                      <Text size={200} block>
+                        {request.summary.comments} comments, {request.summary.audit_entries} audit entries rewritten
+                      </Text>
+                    )}
+                  </td>
+                  <td>{new Date(request.created_at).toLocaleString()}</td>
+                  <td>
+                    <div className={styles.actions}>
+                      {request.status === 'APPROVED' && (
+                        <PurpleGlassButton
+                          variant="danger"
+                          size="small"
+                          icon={<PersonDeleteRegular />}
+                          onClick={() => handleExecute(request)}
+                          disabled={isBusy}
+                        >
+                          Erase
+                        </PurpleGlassButton>
+                      )}
+                      {(request.status === 'PENDING_APPROVAL' || request.status === 'APPROVED') && (
+                        <PurpleGlassButton
+                          variant="ghost"
+                          size="small"
+                          icon={<DismissRegular />}
+                          onClick={() => run(() => apiClient.cancelErasureRequest(request.id))}
+                          disabled={isBusy}
+                        >
+                          Cancel
+                        </PurpleGlassButton>
+                      )}
+                    </div>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </PurpleGlassCard>
+    </div>
+  );
+}
+
+export default PersonalDataView;
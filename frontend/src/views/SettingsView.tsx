@@ -178,7 +178,6 @@ const SettingsView: React.FC = () => {
     autoSaveEnabled: true,
     autoSaveInterval: '30',
     showWelcomeOnStartup: true,
-    enableTelemetry: false,
   });
 
   // Capacity thresholds state
@@ -312,13 +311,12 @@ const SettingsView: React.FC = () => {
           </div>
           <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between' }}>
             <div>
-              <p style={{ ...labelStyle, marginBottom: '4px' }}>Enable Telemetry</p>
+              <p style={{ ...labelStyle, marginBottom: '4px' }}>Usage Telemetry</p>
               <p style={{ fontSize: '12px', color: 'var(--text-muted)' }}>Help improve the application by sending anonymous usage data</p>
             </div>
-            <PurpleGlassSwitch
-              checked={generalSettings.enableTelemetry}
-              onChange={(e) => setGeneralSettings(prev => ({ ...prev, enableTelemetry: e.target.checked }))}
-            />
+            <PurpleGlassButton variant="secondary" size="small" onClick={() => navigate('/app/admin/telemetry')}>
+              Manage
+            </PurpleGlassButton>
           </div>
         </div>
       </div>
@@ -0,0 +1,258 @@
+// Archer ITSM - Usage Telemetry View
+// Admin interface for opting in to anonymized usage telemetry and reviewing
+// everything recorded locally before it is sent
+
+import React, { useState, useEffect, useCallback, useMemo } from 'react';
+import {
+  Text,
+  Badge,
+  Spinner,
+  tokens,
+  makeStyles,
+} from '@fluentui/react-components';
+import {
+  DataTrendingRegular,
+  ArrowSyncRegular,
+  SendRegular,
+  DeleteRegular,
+} from '@fluentui/react-icons';
+import {
+  PurpleGlassCard,
+  PurpleGlassButton,
+  PurpleGlassSwitch,
+  PurpleGlassBreadcrumb,
+  PageHeader,
+} from '../components/ui';
+import {
+  apiClient,
+  type TelemetryOverview,
+} from '../utils/apiClient';
+
+const useStyles = makeStyles({
+  container: {
+    display: 'flex',
+    flexDirection: 'column',
+    gap: tokens.spacingVerticalXL,
+  },
+  headerActions: {
+    display: 'flex',
+    gap: tokens.spacingHorizontalS,
+  },
+  optIn: {
+    display: 'flex',
+    alignItems: 'center',
+    justifyContent: 'space-between',
+    gap: tokens.spacingHorizontalL,
+  },
+  details: {
+    display: 'flex',
+    flexDirection: 'column',
+    gap: tokens.spacingVerticalXS,
+  },
+  table: {
+    width: '100%',
+    borderCollapse: 'collapse',
+    '& th, & td': {
+      textAlign: 'left',
+      padding: `${tokens.spacingVerticalS} ${tokens.spacingHorizontalM}`,
+      borderBottom: `1px solid ${tokens.colorNeutralStroke2}`,
+    },
+  },
+  payload: {
+    fontFamily: tokens.fontFamilyMonospace,
+    fontSize: tokens.fontSizeBase200,
+    whiteSpace: 'pre-wrap',
+    maxHeight: '320px',
+    overflowY: 'auto',
+  },
+  error: {
+    color: tokens.colorPaletteRedForeground1,
+  },
+});
+
+const FEATURE_LABELS: Record<string, string> = {
+  rvtools_parse: 'RVTools parsing',
+  hardware_basket_parse: 'Hardware basket parsing',
+  spreadsheet_import: 'Spreadsheet import',
+  capacity_sizing: 'Capacity sizing',
+  vm_placement: 'VM placement',
+  hld_generation: 'HLD generation',
+  document_generation: 'Document generation',
+};
+
+export function UsageTelemetryView() {
+  const styles = useStyles();
+
+  const [overview, setOverview] = useState<TelemetryOverview | null>(null);
+  const [isLoading, setIsLoading] = useState(true);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadData = useCallback(async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      setOverview(await apiClient.getUsageTelemetry());
+    } catch (err) {
+      console.error('Failed to load usage telemetry:', err);
+      setError('Failed to load usage telemetry. Please try again.');
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadData();
+  }, [loadData]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      await action();
+      await loadData();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'The request failed.');
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleToggle = (enabled: boolean) => run(() => apiClient.setUsageTelemetryEnabled(enabled));
+  const handleSubmit = () => run(() => apiClient.submitUsageTelemetry());
+  const handlePurge = () => {
+    if (window.confirm('Delete all locally recorded usage data?')) {
+      run(() => apiClient.purgeUsageTelemetry());
+    }
+  };
+
+  // One row per day, one column per feature
+  const { days, features } = useMemo(() => {
+    const byDay = new Map<string, Record<string, number>>();
+    const seen = new Set<string>();
+    for (const row of overview?.days ?? []) {
+      const counts = byDay.get(row.day) ?? {};
+      counts[row.feature] = (counts[row.feature] ?? 0) + row.count;
+      byDay.set(row.day, counts);
+      seen.add(row.feature);
+    }
+    return {
+      days: Array.from(byDay.entries()),
+      features: Object.keys(FEATURE_LABELS).filter((f) => seen.has(f)),
+    };
+  }, [overview]);
+
+  const breadcrumbItems = [
+    { label: 'Dashboard', href: '/app/dashboard' },
+    { label: 'Settings', href: '/app/settings' },
+    { label: 'Usage Telemetry' },
+  ];
+
+  return (
+    <div className={styles.container}>
+      <PurpleGlassBreadcrumb items={breadcrumbItems} />
+
+      <PageHeader
+        icon={<DataTrendingRegular />}
+        title="Usage Telemetry"
+        subtitle="Anonymized feature usage shared with the product team, only when you opt in"
+        actions={
+          <div className={styles.headerActions}>
+            <PurpleGlassButton
+              variant="secondary"
+              icon={<SendRegular />}
+              onClick={handleSubmit}
+              disabled={!overview?.enabled || !overview?.endpoint || isBusy}
+            >
+              Submit now
+            </PurpleGlassButton>
+            <PurpleGlassButton
+              variant="danger"
+              icon={<DeleteRegular />}
+              onClick={handlePurge}
+              disabled={!overview?.days.length || isBusy}
+            >
+              Delete local data
+            </PurpleGlassButton>
+            <PurpleGlassButton
+              variant="secondary"
+              icon={<ArrowSyncRegular />}
+              onClick={loadData}
+              disabled={isLoading}
+            >
+              Refresh
+            </PurpleGlassButton>
+          </div>
+        }
+      />
+
+      {error && <Text className={styles.error}>{error}</Text>}
+
+      {isLoading && !overview ? (
+        <div style={{ display: 'flex', justifyContent: 'center', padding: tokens.spacingVerticalXXL }}>
+          <Spinner size="large" label="Loading usage telemetry..." />
+        </div>
+      ) : overview && (
+        <>
+          <PurpleGlassCard>
+            <div className={styles.optIn}>
+              <div className={styles.details}>
+                <Text weight="semibold">
+                  Share usage telemetry{' '}
+                  {overview.hard_disabled && <Badge appearance="tint" color="danger">Switched off</Badge>}
+                </Text>
+                <Text size={200}>
+                  {overview.hard_disabled
+                    ? 'ARCHER_TELEMETRY_DISABLED or DO_NOT_TRACK is set on the server; nothing is recorded or sent.'
+                    : 'Counts how often parsing, sizing and document generation run. No names, files or user data are recorded.'}
+                </Text>
+                <Text size={200}>
+                  Endpoint: {overview.endpoint ?? 'not configured (kept local)'}
+                  {overview.last_submitted_at && ` · last sent ${new Date(overview.last_submitted_at).toLocaleString()}`}
+                </Text>
+              </div>
+              <PurpleGlassSwitch
+                checked={overview.enabled}
+                disabled={overview.hard_disabled || isBusy}
+                onChange={(e) => handleToggle(e.target.checked)}
+              />
+            </div>
+          </PurpleGlassCard>
+
+          <PurpleGlassCard header="Recorded usage (last 90 days)">
+            {days.length === 0 ? (
+              <Text size={200}>Nothing recorded.</Text>
+            ) : (
+              <table className={styles.table}>
+                <thead>
+                  <tr>
+                    <th>Day</th>
+                    {features.map((feature) => <th key={feature}>{FEATURE_LABELS[feature]}</th>)}
+                  </tr>
+                </thead>
+                <tbody>
+                  {days.map(([day, counts]) => (
+                    <tr key={day}>
+                      <td>{day}</td>
+                      {features.map((feature) => <td key={feature}>{counts[feature] ?? 0}</td>)}
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            )}
+          </PurpleGlassCard>
+
+          <PurpleGlassCard header="Next submission">
+            {overview.pending.length === 0 ? (
+              <Text size={200}>Nothing waiting to be sent.</Text>
+            ) : (
+              <div className={styles.payload}>{JSON.stringify(overview.pending, null, 2)}</div>
+            )}
+          </PurpleGlassCard>
+        </>
+      )}
+    </div>
+  );
+}
+
+export default UsageTelemetryView;
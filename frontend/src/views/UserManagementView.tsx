@@ -28,6 +28,8 @@ import {
   WarningRegular,
   KeyRegular,
   PeopleTeamRegular,
+  ArrowDownloadRegular,
+  PersonDeleteRegular,
 } from '@fluentui/react-icons';
 import {
   PurpleGlassCard,
@@ -40,6 +42,7 @@ import {
   PageHeader,
   type TableColumn,
 } from '../components/ui';
+import { useNavigate } from 'react-router-dom';
 import { useAuth } from '../contexts/AuthContext';
 import {
   apiClient,
@@ -491,6 +494,7 @@ const UserFormModal: React.FC<UserFormModalProps> = ({
 export function UserManagementView() {
   const styles = useStyles();
   const { user: currentUser, hasPermission } = useAuth();
+  const navigate = useNavigate();
   
   // State
   const [users, setUsers] = useState<AdminUser[]>([]);
@@ -669,6 +673,37 @@ export function UserManagementView() {
     }
   };
   
+  // Personal data
+  const handleExportUserData = async (user: AdminUser) => {
+    try {
+      const data = await apiClient.exportUserData(user.id);
+      const blob = new Blob([JSON.stringify(data, null, 2)], { type: 'application/json' });
+      const url = URL.createObjectURL(blob);
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `user-data-${user.username}.json`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (err: any) {
+      console.error('Failed to export user data:', err);
+      setError(err.message || 'Failed to export user data');
+    }
+  };
+
+  const handleRequestErasure = async (user: AdminUser) => {
+    const reason = window.prompt(
+      `Request erasure of ${user.display_name}? Their name and email are replaced with a pseudonym once the request is approved. Reason:`,
+    );
+    if (reason === null) return;
+    try {
+      await apiClient.createErasureRequest(user.id, reason || undefined);
+      navigate('/app/admin/privacy');
+    } catch (err: any) {
+      console.error('Failed to request erasure:', err);
+      setError(err.message || 'Failed to request erasure');
+    }
+  };
+
   // Table columns
   const columns: TableColumn<AdminUser>[] = [
     {
@@ -741,6 +776,21 @@ export function UserManagementView() {
             onClick={() => {/* TODO: Reset password modal */}}
             aria-label="Reset password"
           />
+          <PurpleGlassButton
+            variant="ghost"
+            size="small"
+            icon={<ArrowDownloadRegular />}
+            onClick={() => handleExportUserData(row)}
+            aria-label="Export personal data"
+          />
+          <PurpleGlassButton
+            variant="ghost"
+            size="small"
+            icon={<PersonDeleteRegular />}
+            onClick={() => handleRequestErasure(row)}
+            disabled={row.id === currentUser?.id}
+            aria-label="Request erasure"
+          />
           <PurpleGlassButton
             variant="ghost"
             size="small"
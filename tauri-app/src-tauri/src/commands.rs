@@ -1,72 +1,148 @@
+use crate::journal::ChangeScope;
 use crate::state::*;
+use crate::sync::{self, ConflictPolicy, ConflictResolution, PendingMutation, QueueMutationRequest, SyncStatus};
+use crate::updater::{self, UpdateSettings, UpdateStatus};
+use core_engine::compat::{CompatibilityReport, VersionInfo};
 use core_engine::{parser, analysis, sizing, forecasting, translation, document_generation, hardware_parser, vendor_data, network_visualizer};
 use core_engine::models::*;
-use core_engine::error::CoreEngineError;
+use core_engine::models::project::ProjectRole;
+use core_engine::sizing_session::{SessionSizing, SizingDelta, SizingSession};
+use core_engine::error::{codes, CoreEngineError, ErrorCode, ErrorDetail, ERROR_REGISTRY};
 use serde_json::Value as JsonValue;
 use std::path::Path;
 use uuid::Uuid;
 use chrono::Utc;
 use anyhow::{Result, Context};
 
+/// Result of a Tauri command. Errors reach the frontend as a serialized
+/// `ErrorDetail` with a stable code from the error registry.
+pub type CommandResult<T> = std::result::Result<T, ErrorDetail>;
+
+/// Error for a failure outside the core engine, e.g. serde or file I/O
+fn command_error(code: ErrorCode, message: &str, cause: impl std::fmt::Display) -> ErrorDetail {
+    ErrorDetail::new(code, message).with_detail(cause)
+}
+
 /// Process RVTools Excel file and load environment data
 #[tauri::command]
 pub async fn process_rvtools_file(
+    project_id: String,
     file_path: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Editor)?;
+
     // Parse the RVTools file
     let environment = match parser::RvToolsParser::new(&file_path).and_then(|mut p| p.parse()) {
         Ok(env) => env,
-        Err(e) => return Err(format!("Failed to parse RVTools file: {}", e)),
+        Err(e) => return Err(ErrorDetail::from(e).context("Failed to parse RVTools file")),
     };
 
-    // Store the environment in application state
-    state.set_current_environment(environment.clone());
+    // Store the environment in the project's workspace
+    state.set_environment(project_id, environment.clone());
 
     // Return environment summary
-    Ok(format!(
+    let mut message = format!(
         "Successfully loaded environment '{}' with {} clusters, {} VMs, {} hosts",
         environment.name,
         environment.clusters.len(),
         environment.get_total_vm_count(),
         environment.get_total_host_count()
-    ))
+    );
+    let report = &environment.import_report;
+    if report.has_warnings() {
+        message.push_str(&format!(
+            " (completed with warnings: {} of {} rows skipped, see the import report)",
+            report.rows_failed, report.rows_total
+        ));
+    }
+    Ok(message)
+}
+
+/// Row-level errors from the project's last RVTools import
+#[tauri::command]
+pub async fn get_import_report(
+    project_id: String,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<Option<ImportReport>> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    Ok(state.get_environment(&project_id).map(|environment| environment.import_report))
 }
 
-/// Get summary of currently loaded environment
+/// Get summary of the project's loaded environment
 #[tauri::command]
 pub async fn get_environment_summary(
+    project_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<Option<EnvironmentSummary>, String> {
-    if let Some(environment) = state.get_current_environment() {
-        let summary = EnvironmentSummary {
-            id: environment.id,
-            name: environment.name.clone(),
-            parsed_at: environment.parsed_at,
-            cluster_count: environment.clusters.len() as u32,
-            total_vms: environment.get_total_vm_count() as u32,
-            total_hosts: environment.get_total_host_count() as u32,
-            total_cpu_cores: environment.get_total_cpu_cores(),
-            total_memory_gb: environment.get_total_memory_gb(),
-            total_storage_gb: environment.get_total_storage_gb(),
-            power_on_vms: environment.get_powered_on_vm_count() as u32,
-            power_off_vms: environment.get_powered_off_vm_count() as u32,
-        };
-        Ok(Some(summary))
-    } else {
-        Ok(None)
-    }
+) -> CommandResult<Option<EnvironmentSummary>> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    Ok(state.get_environment(&project_id).map(|environment| EnvironmentSummary::of(&environment)))
 }
 
 // ========== PROJECT MANAGEMENT COMMANDS ==========
 
-/// List all available projects
+/// The project, if the current user's role on it allows `required`
+fn project_with_role(state: &AppState, project_id: &Uuid, required: ProjectRole) -> CommandResult<Project> {
+    let project = state
+        .projects
+        .read()
+        .get(project_id)
+        .cloned()
+        .ok_or_else(|| ErrorDetail::new(codes::NOT_FOUND, "Project not found"))?;
+    match project.role_of(&state.current_user()) {
+        Some(role) if role.allows(required) => Ok(project),
+        _ => Err(ErrorDetail::new(codes::FORBIDDEN, "Your role on this project does not allow this action")),
+    }
+}
+
+/// Parse a project ID and check that the project is open and the current
+/// user's role on it allows `required`
+fn open_project_context(state: &AppState, project_id: &str, required: ProjectRole) -> CommandResult<Uuid> {
+    let project_id = Uuid::parse_str(project_id)
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e))?;
+    project_with_role(state, &project_id, required)?;
+    if !state.is_project_open(&project_id) {
+        return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project is not open"));
+    }
+    Ok(project_id)
+}
+
+/// List the projects the current user can open
 #[tauri::command]
-pub async fn list_projects(state: tauri::State<'_, AppState>) -> Result<String, String> {
+pub async fn list_projects(state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let user = state.current_user();
     let projects_guard = state.projects.read();
-    let projects: Vec<&Project> = projects_guard.values().collect();
+    let projects: Vec<&Project> = projects_guard.values().filter(|p| p.role_of(&user).is_some()).collect();
     serde_json::to_string(&projects)
-        .map_err(|e| format!("Failed to serialize projects: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize projects", e))
+}
+
+/// Open a project alongside any already open, so its environment, hardware
+/// basket and analyses can be worked on
+#[tauri::command]
+pub async fn open_project(id: String, state: tauri::State<'_, AppState>) -> CommandResult<OpenProjectSummary> {
+    let project_id = Uuid::parse_str(&id).map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e))?;
+    project_with_role(&state, &project_id, ProjectRole::Viewer)?;
+    let open = state.open_project(project_id);
+    Ok(OpenProjectSummary::of(&state, &open))
+}
+
+/// Close a project, releasing its loaded environment and cached results
+#[tauri::command]
+pub async fn close_project(id: String, state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let project_id = Uuid::parse_str(&id).map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e))?;
+    if !state.close_project(&project_id) {
+        return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project is not open"));
+    }
+    Ok("Project closed".to_string())
+}
+
+/// Projects open in this session, oldest first
+#[tauri::command]
+pub async fn list_open_projects(state: tauri::State<'_, AppState>) -> CommandResult<Vec<OpenProjectSummary>> {
+    let mut open: Vec<OpenProject> = state.open_projects.read().values().cloned().collect();
+    open.sort_by_key(|p| p.opened_at);
+    Ok(open.iter().map(|p| OpenProjectSummary::of(&state, p)).collect())
 }
 
 /// Create a new project
@@ -75,37 +151,34 @@ pub async fn create_project(
     name: String,
     description: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let mut new_project = Project::new(name, description);
+    new_project.set_member(&state.current_user(), ProjectRole::Owner);
+    state.record_change(ChangeScope::Project(new_project.id), "Create project");
     let project_manager_guard = state.project_manager.read();
 
     if let Some(manager) = &*project_manager_guard {
         // Save the project to disk
-        manager.save_project(&new_project).map_err(|e| e.to_string())?;
+        manager.save_project(&new_project)?;
 
         // Add the project to the in-memory state
-        let mut projects_guard = state.projects.write();
-        projects_guard.insert(new_project.id, new_project.clone());
+        state.projects.write().insert(new_project.id, new_project.clone());
+        state.autosave("Create project");
 
         serde_json::to_string(&new_project)
-            .map_err(|e| format!("Failed to serialize new project: {}", e))
+            .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize new project", e))
     } else {
-        Err("Project manager not initialized".to_string())
+        Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"))
     }
 }
 
 /// Get a single project by its ID
 #[tauri::command]
-pub async fn get_project(id: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let project_id = Uuid::parse_str(&id).map_err(|e| format!("Invalid project ID: {}", e))?;
-    let projects_guard = state.projects.read();
-
-    if let Some(project) = projects_guard.get(&project_id) {
-        serde_json::to_string(project)
-            .map_err(|e| format!("Failed to serialize project: {}", e))
-    } else {
-        Err("Project not found".to_string())
-    }
+pub async fn get_project(id: String, state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let project_id = Uuid::parse_str(&id).map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e))?;
+    let project = project_with_role(&state, &project_id, ProjectRole::Viewer)?;
+    serde_json::to_string(&project)
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize project", e))
 }
 
 /// Update an existing project
@@ -113,60 +186,267 @@ pub async fn get_project(id: String, state: tauri::State<'_, AppState>) -> Resul
 pub async fn update_project(
     project_data: JsonValue,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let mut project: Project = serde_json::from_value(project_data)
-        .map_err(|e| format!("Invalid project data: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project data", e))?;
 
     project.updated_at = Utc::now();
 
+    let existing = project_with_role(&state, &project.id, ProjectRole::Editor)?;
+    // Sharing changes go through `set_project_member`
+    project.members = existing.members;
+    state.record_change(ChangeScope::Project(project.id), "Update project");
+
     let project_manager_guard = state.project_manager.read();
     if let Some(manager) = &*project_manager_guard {
         // Save the updated project to disk
-        manager.save_project(&project).map_err(|e| e.to_string())?;
+        manager.save_project(&project)?;
 
         // Update the in-memory state
-        let mut projects_guard = state.projects.write();
-        if projects_guard.contains_key(&project.id) {
-            projects_guard.insert(project.id, project);
-            Ok("Project updated successfully".to_string())
-        } else {
-            Err("Project not found in state".to_string())
-        }
+        state.projects.write().insert(project.id, project);
+        state.autosave("Update project");
+        Ok("Project updated successfully".to_string())
     } else {
-        Err("Project manager not initialized".to_string())
+        Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"))
     }
 }
 
 /// Delete a project by its ID
 #[tauri::command]
-pub async fn delete_project(id: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let project_id = Uuid::parse_str(&id).map_err(|e| format!("Invalid project ID: {}", e))?;
+pub async fn delete_project(id: String, state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let project_id = Uuid::parse_str(&id).map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e))?;
+
+    project_with_role(&state, &project_id, ProjectRole::Owner)?;
+    state.record_change(ChangeScope::Project(project_id), "Delete project");
 
     let project_manager_guard = state.project_manager.read();
     if let Some(manager) = &*project_manager_guard {
         // Delete the project file from disk
-        manager.delete_project(&project_id).map_err(|e| e.to_string())?;
+        manager.delete_project(&project_id)?;
+
+        // Remove the project and its workspace from in-memory state
+        state.close_project(&project_id);
+        state.hardware_baskets.write().remove(&project_id);
+        state.projects.write().remove(&project_id);
+        state.autosave("Delete project");
+        Ok("Project deleted successfully".to_string())
+    } else {
+        Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"))
+    }
+}
 
-        // Remove the project from in-memory state
-        let mut projects_guard = state.projects.write();
-        if projects_guard.remove(&project_id).is_some() {
-            Ok("Project deleted successfully".to_string())
-        } else {
-            Err("Project not found in state".to_string())
+/// Give a user a role on a project, or remove them with `role: null`.
+/// Only owners can share; the last owner cannot be removed.
+#[tauri::command]
+pub async fn set_project_member(
+    id: String,
+    user: String,
+    role: Option<ProjectRole>,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<String> {
+    let project_id = Uuid::parse_str(&id).map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e))?;
+    let mut project = project_with_role(&state, &project_id, ProjectRole::Owner)?;
+
+    if project.members.is_empty() {
+        // Sharing a project that predates sharing makes its current user the owner
+        project.set_member(&state.current_user(), ProjectRole::Owner);
+    }
+    match role {
+        Some(role) => {
+            let other_owners = project
+                .members
+                .iter()
+                .any(|m| m.role == ProjectRole::Owner && m.user != user);
+            if role != ProjectRole::Owner && !other_owners {
+                return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "A project needs at least one owner"));
+            }
+            project.set_member(&user, role);
+        }
+        None => {
+            if !project.remove_member(&user) {
+                return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "A project needs at least one owner"));
+            }
         }
+    }
+    project.updated_at = Utc::now();
+    state.record_change(ChangeScope::Project(project_id), "Share project");
+
+    let project_manager_guard = state.project_manager.read();
+    if let Some(manager) = &*project_manager_guard {
+        manager.save_project(&project)?;
+        let members = serde_json::to_string(&project.members)
+            .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize project members", e))?;
+        state.projects.write().insert(project_id, project);
+        state.autosave("Share project");
+        Ok(members)
     } else {
-        Err("Project manager not initialized".to_string())
+        Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"))
     }
 }
 
+// ========== HISTORY & RECOVERY COMMANDS ==========
+
+/// The undo history a command works on: a project's, or the shared one
+fn history_project(project_id: Option<&str>) -> CommandResult<Option<Uuid>> {
+    project_id
+        .map(|id| Uuid::parse_str(id).map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e)))
+        .transpose()
+}
+
+/// Undo the most recent change to a project and its hardware basket, or
+/// to shared settings when no project is given. Other projects are untouched.
+#[tauri::command]
+pub async fn undo_change(project_id: Option<String>, state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let project = history_project(project_id.as_deref())?;
+    let current = state.snapshot();
+    let step = state.journal.lock().undo(project, current.clone());
+    match step {
+        Some((scope, action, previous)) => {
+            state.apply_snapshot(current.restored(scope, previous))?;
+            state.autosave(&format!("Undo {}", action));
+            Ok(format!("Undid: {}", action))
+        }
+        None => Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Nothing to undo")),
+    }
+}
+
+/// Re-apply the most recently undone change to a project or shared settings
+#[tauri::command]
+pub async fn redo_change(project_id: Option<String>, state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let project = history_project(project_id.as_deref())?;
+    let current = state.snapshot();
+    let step = state.journal.lock().redo(project, current.clone());
+    match step {
+        Some((scope, action, next)) => {
+            state.apply_snapshot(current.restored(scope, next))?;
+            state.autosave(&format!("Redo {}", action));
+            Ok(format!("Redid: {}", action))
+        }
+        None => Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Nothing to redo")),
+    }
+}
+
+/// Get undo/redo availability of a project's history, or the shared one
+#[tauri::command]
+pub async fn get_history_status(
+    project_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<String> {
+    let project = history_project(project_id.as_deref())?;
+    let status = state.journal.lock().status(project);
+    serde_json::to_string(&status)
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize history status", e))
+}
+
+/// Get the unsaved session left behind by a crash, if any
+#[tauri::command]
+pub async fn get_recovery_info(state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let info = state.journal.lock().recovery_info();
+    serde_json::to_string(&info)
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize recovery info", e))
+}
+
+/// Restore the state autosaved by a crashed session
+#[tauri::command]
+pub async fn restore_from_journal(state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let entry = state.journal.lock().take_recovery();
+    match entry {
+        Some(entry) => {
+            state.record_change(ChangeScope::All, "Restore autosave");
+            state.apply_snapshot(entry.snapshot)?;
+            state.autosave("Restore autosave");
+            Ok(format!("Restored session autosaved at {}", entry.saved_at.to_rfc3339()))
+        }
+        None => Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "No autosaved session to restore")),
+    }
+}
+
+/// Discard the crashed session's autosave
+#[tauri::command]
+pub async fn discard_recovery(state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    state.journal.lock().take_recovery();
+    state.autosave("Discard autosave");
+    Ok("Autosaved session discarded".to_string())
+}
+
+// ========== PROJECT FILE ENCRYPTION COMMANDS ==========
+
+/// Report whether project files are encrypted and unlocked
+#[tauri::command]
+pub async fn get_project_encryption_status(state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let project_manager_guard = state.project_manager.read();
+    if let Some(manager) = &*project_manager_guard {
+        let status = manager.encryption_status()?;
+        serde_json::to_string(&status)
+            .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize encryption status", e))
+    } else {
+        Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"))
+    }
+}
+
+/// Enable project file encryption and encrypt existing plaintext files.
+///
+/// With a passphrase the key is derived from it; without one a random key
+/// is generated and kept in the OS keychain.
+#[tauri::command]
+pub async fn enable_project_encryption(
+    passphrase: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<String> {
+    let mut project_manager_guard = state.project_manager.write();
+    if let Some(manager) = &mut *project_manager_guard {
+        let migrated = match passphrase {
+            Some(passphrase) => manager.enable_encryption_with_passphrase(&passphrase),
+            None => {
+                let key = core_engine::project_crypto::ProjectKey::generate();
+                crate::keychain::store_key(&key)?;
+                manager.enable_encryption_with_key(key, core_engine::project_crypto::KeySource::Keychain)
+            }
+        }?;
+
+        Ok(format!("Project encryption enabled; {} existing files encrypted", migrated))
+    } else {
+        Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"))
+    }
+}
+
+/// Unlock passphrase-protected project files and load them into state
+#[tauri::command]
+pub async fn unlock_projects(passphrase: String, state: tauri::State<'_, AppState>) -> CommandResult<String> {
+    let count = {
+        let mut project_manager_guard = state.project_manager.write();
+        let Some(manager) = &mut *project_manager_guard else {
+            return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"));
+        };
+        manager.unlock_with_passphrase(&passphrase)?;
+
+        // Pick up any plaintext files written by older versions
+        manager.migrate_plaintext_files()?;
+
+        let projects = manager.load_projects()?;
+        let hardware_pool = manager.load_hardware_pool()?;
+        let count = projects.len();
+        *state.projects.write() = projects;
+        *state.hardware_pool.write() = hardware_pool;
+        count
+    };
+
+    // Vendor credentials share the project key, so they unlock here too
+    if let Err(e) = restore_vendor_credentials(&state).await {
+        eprintln!("Failed to restore vendor credentials: {}", e);
+    }
+
+    Ok(format!("Unlocked {} projects", count))
+}
+
 /// Parse a RVTools file and return the network topology
 #[tauri::command]
 pub async fn get_network_topology(
     file_path: String,
-) -> Result<network_visualizer::parser::NetworkTopology, String> {
+) -> CommandResult<network_visualizer::parser::NetworkTopology> {
     match network_visualizer::parser::parse_rvtools_report(&file_path) {
         Ok(topology) => Ok(topology),
-        Err(e) => Err(format!("Failed to parse network topology: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to parse network topology")),
     }
 }
 
@@ -174,37 +454,38 @@ pub async fn get_network_topology(
 #[tauri::command]
 pub async fn get_network_topology_from_hyperv(
     json_content: String,
-) -> Result<network_visualizer::parser::NetworkTopology, String> {
+) -> CommandResult<network_visualizer::parser::NetworkTopology> {
     match network_visualizer::hyperv_parser::parse_hyperv_report_json(&json_content) {
         Ok(topology) => Ok(topology),
-        Err(e) => Err(format!("Failed to parse Hyper-V network topology: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to parse Hyper-V network topology")),
     }
 }
 
-/// Get detailed analysis of the current environment
+/// Get detailed analysis of the project's environment
 #[tauri::command]
 pub async fn analyze_environment(
+    project_id: String,
     parameters: AnalysisParameters,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let environment = match state.get_current_environment() {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    let environment = match state.get_environment(&project_id) {
         Some(env) => env,
-        None => return Err("No environment loaded".to_string()),
+        None => return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "No environment loaded for this project")),
     };
 
     // Check if we have cached analysis results
-    let analysis_id = Uuid::new_v4();
-    if let Some(cached) = state.analysis_cache.read().get(&environment.id) {
-        if state._is_analysis_cache_valid(&environment.id) {
+    if let Some(cached) = state.analysis_cache.read().get(&project_id) {
+        if state._is_analysis_cache_valid(&project_id, &cached.environment_id) {
             return Ok(serde_json::to_string(&cached.analysis_report)
-                .map_err(|e| format!("Failed to serialize cached analysis: {}", e))?);
+                .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize cached analysis", e))?);
         }
     }
 
     // Perform new analysis
     let analysis_report = match analysis::AnalysisEngine::analyze_environment(&environment) {
         Ok(report) => report,
-        Err(e) => return Err(format!("Analysis failed: {}", e)),
+        Err(e) => return Err(ErrorDetail::from(e).context("Analysis failed")),
     };
 
     // Cache the results
@@ -214,137 +495,253 @@ pub async fn analyze_environment(
         generated_at: Utc::now(),
         parameters_used: parameters,
     };
-    state.analysis_cache.write().insert(environment.id, analysis_result);
+    state.analysis_cache.write().insert(project_id, analysis_result);
 
     // Return serialized analysis report
     serde_json::to_string(&analysis_report)
-        .map_err(|e| format!("Failed to serialize analysis report: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize analysis report", e))
 }
 
-/// Get hardware basket (available server profiles)
+/// Get the project's hardware basket (available server profiles)
 #[tauri::command]
 pub async fn get_hardware_basket(
+    project_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let basket = state._hardware_basket.read();
-    serde_json::to_string(&*basket)
-        .map_err(|e| format!("Failed to serialize hardware basket: {}", e))
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    let basket = state.hardware_basket(&project_id);
+    serde_json::to_string(&basket)
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize hardware basket", e))
 }
 
-/// Add hardware profile to basket
+/// Add hardware profile to the project's basket
 #[tauri::command]
 pub async fn add_hardware_profile(
+    project_id: String,
     profile: HardwareProfile,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let mut basket = state._hardware_basket.write();
-    basket.add_profile(profile.clone());
-    
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Editor)?;
+    state.record_change(ChangeScope::Project(project_id), "Add hardware profile");
+    state.update_hardware_basket(project_id, |basket| basket.add_profile(profile.clone()));
+    state.autosave("Add hardware profile");
+
     Ok(format!("Added hardware profile: {}", profile.name))
 }
 
-/// Remove hardware profile from basket
+/// Remove hardware profile from the project's basket
 #[tauri::command]
 pub async fn remove_hardware_profile(
+    project_id: String,
     profile_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Editor)?;
     let profile_uuid = Uuid::parse_str(&profile_id)
-        .map_err(|e| format!("Invalid profile ID: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid profile ID", e))?;
     
-    let mut basket = state._hardware_basket.write();
-    if basket.remove_profile(&profile_uuid) {
-        Ok("Hardware profile removed".to_string())
-    } else {
-        Err("Hardware profile not found".to_string())
+    if state.hardware_basket(&project_id).get_profile(&profile_uuid).is_none() {
+        return Err(ErrorDetail::new(codes::NOT_FOUND, "Hardware profile not found"));
     }
+
+    state.record_change(ChangeScope::Project(project_id), "Remove hardware profile");
+    state.update_hardware_basket(project_id, |basket| basket.remove_profile(&profile_uuid));
+    state.autosave("Remove hardware profile");
+
+    Ok("Hardware profile removed".to_string())
 }
 
 /// Perform sizing calculation
 #[tauri::command]
 pub async fn calculate_sizing(
+    project_id: String,
     hardware_profile_id: String,
     sizing_parameters: SizingParameters,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let environment = match state.get_current_environment() {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    let environment = match state.get_environment(&project_id) {
         Some(env) => env,
-        None => return Err("No environment loaded".to_string()),
+        None => return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "No environment loaded for this project")),
     };
 
-    let profile_uuid = Uuid::parse_str(&hardware_profile_id)
-        .map_err(|e| format!("Invalid hardware profile ID: {}", e))?;
-
     // Check cache
     let cache_key = format!("{}-{:?}", hardware_profile_id, sizing_parameters);
-    if let Some(env_hash) = state._get_environment_hash() {
-        if state._is_sizing_cache_valid(&cache_key, &env_hash) {
-            if let Some(cached) = state.sizing_cache.read().get(&cache_key) {
+    if let Some(env_hash) = state._get_environment_hash(&project_id) {
+        if state._is_sizing_cache_valid(&project_id, &cache_key, &env_hash) {
+            if let Some(cached) = state.sizing_cache.read().get(&project_id).and_then(|c| c.get(&cache_key)) {
                 return serde_json::to_string(&cached.sizing_result)
-                    .map_err(|e| format!("Failed to serialize cached sizing result: {}", e));
+                    .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize cached sizing result", e));
             }
         }
     }
 
     // Get hardware profile
-    let hardware_profile = {
-        let basket = state._hardware_basket.read();
-        basket.get_profile(&profile_uuid)
-            .ok_or_else(|| "Hardware profile not found".to_string())?
-            .clone()
-    };
+    let hardware_profile = basket_profile(&state, &project_id, &hardware_profile_id)?;
 
     // Perform sizing calculation
     let sizing_result = match sizing::calculate_sizing(&environment, &hardware_profile, &sizing_parameters).await {
         Ok(result) => result,
-        Err(e) => return Err(format!("Sizing calculation failed: {}", e)),
+        Err(e) => return Err(ErrorDetail::from(e).context("Sizing calculation failed")),
     };
 
     // Cache the result
-    if let Some(env_hash) = state._get_environment_hash() {
+    if let Some(env_hash) = state._get_environment_hash(&project_id) {
         let cache_entry = SizingResultCache {
-            hardware_profile_id: profile_uuid,
+            hardware_profile_id: hardware_profile.id,
             sizing_parameters: sizing_parameters.clone(),
             sizing_result: sizing_result.clone(),
             generated_at: Utc::now(),
             environment_hash: env_hash,
         };
-        state.sizing_cache.write().insert(cache_key, cache_entry);
+        state.sizing_cache.write().entry(project_id).or_default().insert(cache_key, cache_entry);
     }
 
     // Return serialized result
     serde_json::to_string(&sizing_result)
-        .map_err(|e| format!("Failed to serialize sizing result: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize sizing result", e))
+}
+
+/// A profile from the project's hardware basket
+fn basket_profile(state: &AppState, project_id: &Uuid, hardware_profile_id: &str) -> CommandResult<HardwareProfile> {
+    let profile_uuid = Uuid::parse_str(hardware_profile_id)
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid hardware profile ID", e))?;
+    state
+        .hardware_basket(project_id)
+        .get_profile(&profile_uuid)
+        .cloned()
+        .ok_or_else(|| ErrorDetail::new(codes::NOT_FOUND, "Hardware profile not found"))
+}
+
+/// State of a sizing session after a recalculation
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SizingSessionUpdate {
+    pub session_id: Uuid,
+    pub project_id: Uuid,
+    pub active_vms: usize,
+    pub sizing_parameters: SizingParameters,
+    #[serde(flatten)]
+    pub sizing: SessionSizing,
+}
+
+impl SizingSessionUpdate {
+    fn of(session_id: Uuid, open: &OpenSizingSession, sizing: SessionSizing) -> Self {
+        Self {
+            session_id,
+            project_id: open.project_id,
+            active_vms: open.session.active_vms(),
+            sizing_parameters: open.session.parameters().clone(),
+            sizing,
+        }
+    }
+}
+
+/// Load the project's environment into a sizing session for interactive
+/// what-if changes; returns the first result with the full VM placement
+#[tauri::command]
+pub async fn open_sizing_session(
+    project_id: String,
+    hardware_profile_id: String,
+    sizing_parameters: SizingParameters,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<SizingSessionUpdate> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    let hardware_profile = basket_profile(&state, &project_id, &hardware_profile_id)?;
+
+    let session = {
+        let environments = state.environments.read();
+        let environment = environments
+            .get(&project_id)
+            .ok_or_else(|| ErrorDetail::new(codes::PRECONDITION_FAILED, "No environment loaded for this project"))?;
+        SizingSession::new(environment, hardware_profile, sizing_parameters)
+            .map_err(|e| ErrorDetail::from(e).context("Invalid sizing parameters"))?
+    };
+    let mut open = OpenSizingSession { project_id, session };
+    let sizing = open
+        .session
+        .calculate(true)
+        .map_err(|e| ErrorDetail::from(e).context("Sizing calculation failed"))?;
+
+    let session_id = Uuid::new_v4();
+    let update = SizingSessionUpdate::of(session_id, &open, sizing);
+    state.sizing_sessions.lock().insert(session_id, open);
+    Ok(update)
+}
+
+/// Change a sizing session's parameters or node model and recalculate.
+/// The VM placement is left out unless `include_placement` is set.
+#[tauri::command]
+pub async fn update_sizing_session(
+    session_id: String,
+    delta: SizingDelta,
+    hardware_profile_id: Option<String>,
+    include_placement: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<SizingSessionUpdate> {
+    let session_id = Uuid::parse_str(&session_id)
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid sizing session ID", e))?;
+    let mut sessions = state.sizing_sessions.lock();
+    let open = sessions
+        .get_mut(&session_id)
+        .ok_or_else(|| ErrorDetail::new(codes::NOT_FOUND, "Sizing session not found"))?;
+    open_project_context(&state, &open.project_id.to_string(), ProjectRole::Viewer)?;
+
+    let hardware_profile = hardware_profile_id
+        .map(|id| basket_profile(&state, &open.project_id, &id))
+        .transpose()?;
+    open.session
+        .apply(&delta)
+        .map_err(|e| ErrorDetail::from(e).context("Invalid sizing parameters"))?;
+    if let Some(profile) = hardware_profile {
+        open.session.set_profile(profile);
+    }
+
+    let sizing = open
+        .session
+        .calculate(include_placement.unwrap_or(false))
+        .map_err(|e| ErrorDetail::from(e).context("Sizing calculation failed"))?;
+    Ok(SizingSessionUpdate::of(session_id, open, sizing))
+}
+
+/// Drop a sizing session; sessions also end when their project's
+/// environment is replaced or cleared
+#[tauri::command]
+pub async fn close_sizing_session(session_id: String, state: tauri::State<'_, AppState>) -> CommandResult<bool> {
+    let session_id = Uuid::parse_str(&session_id)
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid sizing session ID", e))?;
+    Ok(state.sizing_sessions.lock().remove(&session_id).is_some())
 }
 
 /// Get forecasting data
 #[tauri::command]
 pub async fn get_forecast(
+    project_id: String,
     forecast_parameters: core_engine::forecasting::ForecastParameters,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let environment = match state.get_current_environment() {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    let environment = match state.get_environment(&project_id) {
         Some(env) => env,
-        None => return Err("No environment loaded".to_string()),
+        None => return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "No environment loaded for this project")),
     };
 
     let forecast_result = match forecasting::generate_forecast(&environment, &forecast_parameters).await {
         Ok(result) => result,
-        Err(e) => return Err(format!("Forecasting failed: {}", e)),
+        Err(e) => return Err(ErrorDetail::from(e).context("Forecasting failed")),
     };
 
     serde_json::to_string(&forecast_result)
-        .map_err(|e| format!("Failed to serialize forecast result: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize forecast result", e))
 }
 
 /// Get translation rules
 #[tauri::command]
 pub async fn get_translation_rules(
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let rules = state._translation_rules.read();
     serde_json::to_string(&*rules)
-        .map_err(|e| format!("Failed to serialize translation rules: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize translation rules", e))
 }
 
 /// Update translation rules
@@ -352,14 +749,16 @@ pub async fn get_translation_rules(
 pub async fn update_translation_rules(
     rules: JsonValue,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let translation_rules: translation::TranslationRules = serde_json::from_value(rules)
-        .map_err(|e| format!("Invalid translation rules format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid translation rules format", e))?;
 
+    state.record_change(ChangeScope::Shared, "Update translation rules");
     {
         let mut rules = state._translation_rules.write();
         *rules = translation_rules;
     }
+    state.autosave("Update translation rules");
 
     Ok("Translation rules updated".to_string())
 }
@@ -367,71 +766,77 @@ pub async fn update_translation_rules(
 /// Perform VMware to Microsoft translation
 #[tauri::command]
 pub async fn translate_environment(
+    project_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let environment = match state.get_current_environment() {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    let environment = match state.get_environment(&project_id) {
         Some(env) => env,
-        None => return Err("No environment loaded".to_string()),
+        None => return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "No environment loaded for this project")),
     };
 
     let rules = state._translation_rules.read().clone();
     
     let translation_result = match translation::translate_environment(&environment, &rules).await {
         Ok(result) => result,
-        Err(e) => return Err(format!("Translation failed: {}", e)),
+        Err(e) => return Err(ErrorDetail::from(e).context("Translation failed")),
     };
 
     serde_json::to_string(&translation_result)
-        .map_err(|e| format!("Failed to serialize translation result: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize translation result", e))
 }
 
 /// Generate HLD document
 #[tauri::command]
 pub async fn generate_hld_document(
+    project_id: String,
     output_path: String,
     sizing_result: JsonValue,
     translation_result: JsonValue,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let environment = match state.get_current_environment() {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Editor)?;
+    let environment = match state.get_environment(&project_id) {
         Some(env) => env,
-        None => return Err("No environment loaded".to_string()),
+        None => return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "No environment loaded for this project")),
     };
 
     let sizing: SizingResult = serde_json::from_value(sizing_result)
-        .map_err(|e| format!("Invalid sizing result format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid sizing result format", e))?;
 
     let translation: TranslationResult = serde_json::from_value(translation_result)
-        .map_err(|e| format!("Invalid translation result format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid translation result format", e))?;
 
     match document_generation::generate_hld_document(&environment, &sizing, &translation, &output_path).await {
         Ok(_) => Ok(format!("HLD document generated: {}", output_path)),
-        Err(e) => Err(format!("Failed to generate HLD document: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to generate HLD document")),
     }
 }
 
 /// Generate LLD document
 #[tauri::command]
 pub async fn generate_lld_document(
+    project_id: String,
     output_path: String,
     sizing_result: JsonValue,
     translation_result: JsonValue,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let environment = match state.get_current_environment() {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Editor)?;
+    let environment = match state.get_environment(&project_id) {
         Some(env) => env,
-        None => return Err("No environment loaded".to_string()),
+        None => return Err(ErrorDetail::new(codes::PRECONDITION_FAILED, "No environment loaded for this project")),
     };
 
     let sizing: SizingResult = serde_json::from_value(sizing_result)
-        .map_err(|e| format!("Invalid sizing result format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid sizing result format", e))?;
 
     let translation: TranslationResult = serde_json::from_value(translation_result)
-        .map_err(|e| format!("Invalid translation result format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid translation result format", e))?;
 
     match document_generation::generate_lld_document(&environment, &sizing, &translation, &output_path).await {
         Ok(_) => Ok(format!("LLD document generated: {}", output_path)),
-        Err(e) => Err(format!("Failed to generate LLD document: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to generate LLD document")),
     }
 }
 
@@ -440,9 +845,9 @@ pub async fn generate_lld_document(
 pub async fn calculate_tco(
     sizing_result: JsonValue,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let sizing: SizingResult = serde_json::from_value(sizing_result)
-        .map_err(|e| format!("Invalid sizing result format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid sizing result format", e))?;
 
     let tco_params = state._tco_parameters.read().clone();
     
@@ -483,32 +888,38 @@ pub async fn calculate_tco(
     };
 
     serde_json::to_string(&tco_result)
-        .map_err(|e| format!("Failed to serialize TCO result: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize TCO result", e))
 }
 
 /// Get current application settings
 #[tauri::command]
 pub async fn get_app_settings(
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let settings = state._app_settings.read();
     serde_json::to_string(&*settings)
-        .map_err(|e| format!("Failed to serialize app settings: {}", e))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize app settings", e))
 }
 
 /// Update application settings
 #[tauri::command]
 pub async fn update_app_settings(
     settings: JsonValue,
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let app_settings: AppSettings = serde_json::from_value(settings)
-        .map_err(|e| format!("Invalid app settings format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid app settings format", e))?;
+    if app_settings.updates != state._app_settings.read().updates {
+        app_settings.updates.save(&config_dir(&app)?)?;
+    }
 
+    state.record_change(ChangeScope::Shared, "Update settings");
     {
         let mut settings = state._app_settings.write();
         *settings = app_settings;
     }
+    state.autosave("Update settings");
 
     Ok("Application settings updated".to_string())
 }
@@ -518,72 +929,74 @@ pub async fn update_app_settings(
 pub async fn update_tco_parameters(
     parameters: JsonValue,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let tco_parameters: TcoParameters = serde_json::from_value(parameters)
-        .map_err(|e| format!("Invalid TCO parameters format: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid TCO parameters format", e))?;
 
+    state.record_change(ChangeScope::Shared, "Update TCO parameters");
     {
         let mut params = state._tco_parameters.write();
         *params = tco_parameters;
     }
+    state.autosave("Update TCO parameters");
 
     Ok("TCO parameters updated".to_string())
 }
 
-/// Save hardware basket to file
+/// Save the project's hardware basket to file
 #[tauri::command]
 pub async fn save_hardware_basket(
+    project_id: String,
     file_path: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let json = {
-        let basket = state._hardware_basket.read();
-        serde_json::to_string_pretty(&*basket)
-            .map_err(|e| format!("Failed to serialize hardware basket: {}", e))?
-    };
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Viewer)?;
+    let json = serde_json::to_string_pretty(&state.hardware_basket(&project_id))
+        .map_err(|e| command_error(codes::SERIALIZATION_FAILED, "Failed to serialize hardware basket", e))?;
 
     tokio::fs::write(&file_path, json).await
-        .map_err(|e| format!("Failed to write hardware basket file: {}", e))?;
+        .map_err(|e| command_error(codes::IO_ERROR, "Failed to write hardware basket file", e))?;
 
     Ok(format!("Hardware basket saved to: {}", file_path))
 }
 
-/// Load hardware basket from file
+/// Load the project's hardware basket from file
 #[tauri::command]
 pub async fn load_hardware_basket(
+    project_id: String,
     file_path: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Editor)?;
     let json = tokio::fs::read_to_string(&file_path).await
-        .map_err(|e| format!("Failed to read hardware basket file: {}", e))?;
+        .map_err(|e| command_error(codes::IO_ERROR, "Failed to read hardware basket file", e))?;
 
     let basket: sizing::HardwareBasket = serde_json::from_str(&json)
-        .map_err(|e| format!("Failed to parse hardware basket file: {}", e))?;
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Failed to parse hardware basket file", e))?;
 
-    {
-        let mut current_basket = state._hardware_basket.write();
-        *current_basket = basket;
-    }
+    state.record_change(ChangeScope::Project(project_id), "Load hardware basket");
+    state.hardware_baskets.write().insert(project_id, basket);
+    state.autosave("Load hardware basket");
 
     Ok(format!("Hardware basket loaded from: {}", file_path))
 }
 
 /// Check if file exists
 #[tauri::command]
-pub async fn file_exists(file_path: String) -> Result<bool, String> {
+pub async fn file_exists(file_path: String) -> CommandResult<bool> {
     Ok(Path::new(&file_path).exists())
 }
 
 /// Get file info
 #[tauri::command]
-pub async fn get_file_info(file_path: String) -> Result<JsonValue, String> {
+pub async fn get_file_info(file_path: String) -> CommandResult<JsonValue> {
     let path = Path::new(&file_path);
     if !path.exists() {
-        return Err("File does not exist".to_string());
+        return Err(ErrorDetail::new(codes::NOT_FOUND, "File does not exist"));
     }
 
     let metadata = tokio::fs::metadata(&file_path).await
-        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+        .map_err(|e| command_error(codes::IO_ERROR, "Failed to get file metadata", e))?;
 
     let file_info = serde_json::json!({
         "exists": true,
@@ -599,12 +1012,14 @@ pub async fn get_file_info(file_path: String) -> Result<JsonValue, String> {
     Ok(file_info)
 }
 
-/// Clear current environment and caches
+/// Clear the project's environment and caches
 #[tauri::command]
 pub async fn clear_environment(
+    project_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    state.clear_current_environment();
+) -> CommandResult<String> {
+    let project_id = open_project_context(&state, &project_id, ProjectRole::Editor)?;
+    state.clear_environment(&project_id);
     Ok("Environment cleared".to_string())
 }
 
@@ -624,15 +1039,62 @@ pub struct EnvironmentSummary {
     pub power_off_vms: u32,
 }
 
+impl EnvironmentSummary {
+    pub fn of(environment: &VsphereEnvironment) -> Self {
+        Self {
+            id: environment.id,
+            name: environment.name.clone(),
+            parsed_at: environment.parsed_at,
+            cluster_count: environment.clusters.len() as u32,
+            total_vms: environment.get_total_vm_count() as u32,
+            total_hosts: environment.get_total_host_count() as u32,
+            total_cpu_cores: environment.get_total_cpu_cores(),
+            total_memory_gb: environment.get_total_memory_gb(),
+            total_storage_gb: environment.get_total_storage_gb(),
+            power_on_vms: environment.get_powered_on_vm_count() as u32,
+            power_off_vms: environment.get_powered_off_vm_count() as u32,
+        }
+    }
+}
+
+/// An open project and what is loaded for it
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OpenProjectSummary {
+    pub project_id: Uuid,
+    pub name: String,
+    pub opened_at: chrono::DateTime<chrono::Utc>,
+    pub environment: Option<EnvironmentSummary>,
+    pub hardware_profiles: usize,
+    pub has_analysis: bool,
+}
+
+impl OpenProjectSummary {
+    fn of(state: &AppState, open: &OpenProject) -> Self {
+        Self {
+            project_id: open.project_id,
+            name: state
+                .projects
+                .read()
+                .get(&open.project_id)
+                .map(|p| p.name.clone())
+                .unwrap_or_default(),
+            opened_at: open.opened_at,
+            environment: state.get_environment(&open.project_id).map(|env| EnvironmentSummary::of(&env)),
+            hardware_profiles: state.hardware_basket(&open.project_id).get_profiles().len(),
+            has_analysis: state.analysis_cache.read().contains_key(&open.project_id),
+        }
+    }
+}
+
 /// Parse a hardware configuration file (e.g., Dell SCP, Lenovo DCSC)
 #[tauri::command]
 pub async fn parse_hardware_file(
     file_path: String,
-) -> Result<UniversalServer, String> {
+) -> CommandResult<UniversalServer> {
     let parser = hardware_parser::UniversalParser;
     match parser.parse_file(&file_path) {
         Ok(server) => Ok(server),
-        Err(e) => Err(format!("Failed to parse hardware file: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to parse hardware file")),
     }
 }
 
@@ -650,8 +1112,33 @@ pub struct TcoResult {
 
 // ========== VENDOR DATA COLLECTION COMMANDS ==========
 
+/// Authenticate vendor catalog clients with credentials from the encrypted
+/// store. Authentication failures are kept as per-vendor diagnostics rather
+/// than returned; returns how many vendors had stored credentials.
+pub(crate) async fn restore_vendor_credentials(state: &AppState) -> std::result::Result<usize, CoreEngineError> {
+    let stored = match &*state.project_manager.read() {
+        Some(manager) => manager.load_vendor_credentials()?,
+        None => return Ok(0),
+    };
+    if stored.is_empty() {
+        return Ok(0);
+    }
+
+    let manager = state.get_vendor_data_manager().await?;
+    for (vendor, credentials) in &stored {
+        if let Err(e) = manager.configure_vendor(vendor, credentials.clone()).await {
+            eprintln!("Stored credentials for {} did not authenticate: {}", vendor, e);
+        }
+    }
+    Ok(stored.len())
+}
+
 /// Configure vendor credentials
+///
+/// Credentials are saved to the encrypted credential store (project
+/// encryption must be unlocked) and then used to authenticate straight away.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn configure_vendor_credentials(
     vendor: String,
     api_key: Option<String>,
@@ -659,8 +1146,12 @@ pub async fn configure_vendor_credentials(
     password: Option<String>,
     partner_id: Option<String>,
     region: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    client_certificate_pem: Option<String>,
+    client_key_pem: Option<String>,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let credentials = vendor_data::VendorCredentials {
         vendor: vendor.clone(),
         api_key,
@@ -668,16 +1159,32 @@ pub async fn configure_vendor_credentials(
         password,
         partner_id,
         region,
+        client_id,
+        client_secret,
+        client_certificate_pem,
+        client_key_pem,
     };
+
+    {
+        let project_manager_guard = state.project_manager.read();
+        let project_manager = project_manager_guard
+            .as_ref()
+            .ok_or_else(|| ErrorDetail::new(codes::PRECONDITION_FAILED, "Project manager not initialized"))?;
+        let mut stored = project_manager.load_vendor_credentials()?;
+        stored.insert(vendor.to_lowercase(), credentials.clone());
+        project_manager
+            .save_vendor_credentials(&stored)
+            .map_err(|e| ErrorDetail::from(e).context("Failed to store vendor credentials"))?;
+    }
     
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.configure_vendor(&vendor, credentials).await {
                 Ok(_) => Ok(format!("Successfully configured credentials for {}", vendor)),
-                Err(e) => Err(format!("Failed to configure vendor credentials: {}", e)),
+                Err(e) => Err(ErrorDetail::from(e).context("Failed to configure vendor credentials")),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
@@ -685,15 +1192,15 @@ pub async fn configure_vendor_credentials(
 #[tauri::command]
 pub async fn get_all_server_models(
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<vendor_data::ServerModel>, String> {
+) -> CommandResult<Vec<vendor_data::ServerModel>> {
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.get_all_server_models().await {
                 Ok(models) => Ok(models),
-                Err(e) => Err(format!("Failed to fetch server models: {}", e)),
+                Err(e) => Err(ErrorDetail::from(e).context("Failed to fetch server models")),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
@@ -702,15 +1209,15 @@ pub async fn get_all_server_models(
 pub async fn get_vendor_server_models(
     vendor: String,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<vendor_data::ServerModel>, String> {
+) -> CommandResult<Vec<vendor_data::ServerModel>> {
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.get_vendor_server_models(&vendor).await {
                 Ok(models) => Ok(models),
-                Err(e) => Err(format!("Failed to fetch {} server models: {}", vendor, e)),
+                Err(e) => Err(ErrorDetail::from(e).context(&format!("Failed to fetch {} server models", vendor))),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
@@ -720,15 +1227,15 @@ pub async fn get_model_specifications(
     vendor: String,
     model_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<vendor_data::ServerSpecifications, String> {
+) -> CommandResult<vendor_data::ServerSpecifications> {
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.get_model_specifications(&vendor, &model_id).await {
                 Ok(specs) => Ok(specs),
-                Err(e) => Err(format!("Failed to fetch specifications for {}: {}", model_id, e)),
+                Err(e) => Err(ErrorDetail::from(e).context(&format!("Failed to fetch specifications for {}", model_id))),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
@@ -738,32 +1245,74 @@ pub async fn get_compatibility_matrix(
     vendor: String,
     model_id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<vendor_data::CompatibilityMatrix, String> {
+) -> CommandResult<vendor_data::CompatibilityMatrix> {
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.get_compatibility_matrix(&vendor, &model_id).await {
                 Ok(matrix) => Ok(matrix),
-                Err(e) => Err(format!("Failed to fetch compatibility matrix for {}: {}", model_id, e)),
+                Err(e) => Err(ErrorDetail::from(e).context(&format!("Failed to fetch compatibility matrix for {}", model_id))),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
+/// Months from now the hardware must stay supported for a project: until
+/// the project ends, plus the sizing forecast horizon after it
+fn project_planning_horizon(state: &AppState, project_id: &str) -> CommandResult<u32> {
+    let project_id = Uuid::parse_str(project_id)
+        .map_err(|e| command_error(codes::VALIDATION_ERROR, "Invalid project ID", e))?;
+    let project = project_with_role(state, &project_id, ProjectRole::Viewer)?;
+    let forecast_months = state._app_settings.read().default_sizing_parameters.forecast_horizon_months;
+    let months_to_end = (project.end_date - Utc::now()).num_days().max(0) * 12 / 365;
+    Ok(months_to_end as u32 + forecast_months)
+}
+
 /// Search for server configurations based on requirements
+///
+/// With a `projectId` and no explicit `planning_horizon_months`, support
+/// runway is judged against that project's planning horizon.
 #[tauri::command]
 pub async fn search_server_configurations(
-    requirements: vendor_data::SizingRequirements,
+    mut requirements: vendor_data::SizingRequirements,
+    project_id: Option<String>,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<vendor_data::RecommendedConfiguration>, String> {
+) -> CommandResult<Vec<vendor_data::RecommendedConfiguration>> {
+    if let (None, Some(project_id)) = (requirements.planning_horizon_months, project_id.as_deref()) {
+        requirements.planning_horizon_months = Some(project_planning_horizon(&state, project_id)?);
+    }
+
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.search_configurations(&requirements).await {
                 Ok(configurations) => Ok(configurations),
-                Err(e) => Err(format!("Failed to search configurations: {}", e)),
+                Err(e) => Err(ErrorDetail::from(e).context("Failed to search configurations")),
+            }
+        },
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
+    }
+}
+
+/// Rank server configurations by weighted capex, performance, power and
+/// density scores and return the Pareto-front alternatives
+#[tauri::command]
+pub async fn compare_server_configurations(
+    mut requirements: vendor_data::SizingRequirements,
+    project_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<vendor_data::ConfigurationTradeoffs> {
+    if let (None, Some(project_id)) = (requirements.planning_horizon_months, project_id.as_deref()) {
+        requirements.planning_horizon_months = Some(project_planning_horizon(&state, project_id)?);
+    }
+
+    match state.get_vendor_data_manager().await {
+        Ok(manager) => {
+            match manager.compare_configurations(&requirements).await {
+                Ok(tradeoffs) => Ok(tradeoffs),
+                Err(e) => Err(ErrorDetail::from(e).context("Failed to compare configurations")),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
@@ -772,15 +1321,15 @@ pub async fn search_server_configurations(
 pub async fn enrich_server_configuration(
     mut server: UniversalServer,
     state: tauri::State<'_, AppState>,
-) -> Result<UniversalServer, String> {
+) -> CommandResult<UniversalServer> {
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.enrich_configuration(&mut server).await {
                 Ok(_) => Ok(server),
-                Err(e) => Err(format!("Failed to enrich server configuration: {}", e)),
+                Err(e) => Err(ErrorDetail::from(e).context("Failed to enrich server configuration")),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
@@ -790,15 +1339,15 @@ pub async fn get_configuration_pricing(
     vendor: String,
     configuration: vendor_data::ConfigurationRequest,
     state: tauri::State<'_, AppState>,
-) -> Result<Option<vendor_data::PricingResponse>, String> {
+) -> CommandResult<Option<vendor_data::PricingResponse>> {
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.get_configuration_pricing(&vendor, &configuration).await {
                 Ok(pricing) => Ok(pricing),
-                Err(e) => Err(format!("Failed to get pricing: {}", e)),
+                Err(e) => Err(ErrorDetail::from(e).context("Failed to get pricing")),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
@@ -806,28 +1355,192 @@ pub async fn get_configuration_pricing(
 #[tauri::command]
 pub async fn refresh_vendor_data(
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     match state.get_vendor_data_manager().await {
         Ok(manager) => {
             match manager.refresh_vendor_data().await {
                 Ok(_) => Ok("Successfully refreshed vendor data cache".to_string()),
-                Err(e) => Err(format!("Failed to refresh vendor data: {}", e)),
+                Err(e) => Err(ErrorDetail::from(e).context("Failed to refresh vendor data")),
             }
         },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
 
-/// Get vendor data cache statistics
+/// Get vendor data cache statistics and per-vendor authentication diagnostics
 #[tauri::command]
 pub async fn get_cache_statistics(
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
+) -> CommandResult<vendor_data::VendorDataStatistics> {
     match state.get_vendor_data_manager().await {
-        Ok(manager) => {
-            // Access the cache through the manager (would need to expose this method)
-            Ok("Cache statistics not available yet".to_string())
-        },
-        Err(e) => Err(format!("Failed to access vendor data manager: {}", e)),
+        Ok(manager) => Ok(manager.statistics().await),
+        Err(e) => Err(ErrorDetail::from(e).context("Failed to access vendor data manager")),
     }
 }
+
+/// Registry of error codes commands can return, for the frontend to map
+/// codes to handling without hard-coding the list
+#[tauri::command]
+pub async fn list_error_codes() -> CommandResult<Vec<ErrorCode>> {
+    Ok(ERROR_REGISTRY.to_vec())
+}
+
+fn config_dir(app: &tauri::AppHandle) -> CommandResult<std::path::PathBuf> {
+    app.path_resolver()
+        .app_config_dir()
+        .ok_or_else(|| ErrorDetail::new(codes::CONFIGURATION_ERROR, "App config directory is unavailable"))
+}
+
+/// Version and schema of this app, as sent in the backend handshake
+#[tauri::command]
+pub async fn get_version_info(app: tauri::AppHandle) -> CommandResult<VersionInfo> {
+    Ok(updater::app_version(&app))
+}
+
+/// Run the version handshake against a backend (the configured one when
+/// `backend_url` is omitted) and report whether this app can use it
+#[tauri::command]
+pub async fn check_backend_compatibility(
+    backend_url: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<CompatibilityReport> {
+    let backend_url = backend_url
+        .or_else(|| state._app_settings.read().updates.backend_url.clone())
+        .ok_or_else(|| ErrorDetail::new(codes::VALIDATION_ERROR, "No backend URL is configured"))?;
+    Ok(updater::check_backend(&app, &backend_url).await?)
+}
+
+/// Release channel and backend preferences
+#[tauri::command]
+pub async fn get_update_settings(state: tauri::State<'_, AppState>) -> CommandResult<UpdateSettings> {
+    Ok(state._app_settings.read().updates.clone())
+}
+
+/// Switch release channel or backend; takes effect on the next check
+#[tauri::command]
+pub async fn set_update_settings(
+    settings: UpdateSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<UpdateSettings> {
+    settings.save(&config_dir(&app)?)?;
+    state._app_settings.write().updates = settings.clone();
+    Ok(settings)
+}
+
+/// Check the selected release channel for a newer version
+#[tauri::command]
+pub async fn check_for_update(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<UpdateStatus> {
+    let channel = state._app_settings.read().updates.channel;
+    Ok(updater::check(&app, channel).await?)
+}
+
+/// Download and install the latest release on the selected channel; the app restarts
+#[tauri::command]
+pub async fn install_update(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<()> {
+    let channel = state._app_settings.read().updates.channel;
+    Ok(updater::install(&app, channel).await?)
+}
+
+/// Queue a change for the backend. It is sent right away when online and
+/// kept across restarts until the backend accepts it.
+#[tauri::command]
+pub async fn queue_backend_mutation(
+    request: QueueMutationRequest,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<PendingMutation> {
+    let entry = state
+        .sync
+        .lock()
+        .enqueue(request)
+        .map_err(|e| ErrorDetail::new(codes::VALIDATION_ERROR, &e))?;
+
+    let online = state.sync.lock().status().online;
+    if online {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = sync::sync_now(&app).await {
+                eprintln!("Sync failed: {}", e);
+            }
+        });
+    }
+    Ok(entry)
+}
+
+/// Connectivity and queue counts for the sync indicator
+#[tauri::command]
+pub async fn get_sync_status(state: tauri::State<'_, AppState>) -> CommandResult<SyncStatus> {
+    Ok(state.sync.lock().status())
+}
+
+/// Queued changes in replay order, including conflicts and failures
+#[tauri::command]
+pub async fn list_pending_mutations(state: tauri::State<'_, AppState>) -> CommandResult<Vec<PendingMutation>> {
+    Ok(state.sync.lock().entries().to_vec())
+}
+
+/// Replay queued changes now instead of waiting for the next connectivity check
+#[tauri::command]
+pub async fn sync_now(app: tauri::AppHandle) -> CommandResult<SyncStatus> {
+    Ok(sync::sync_now(&app).await?)
+}
+
+/// Settle a conflict by replaying the local change or keeping the server copy
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    id: Uuid,
+    resolution: ConflictResolution,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<SyncStatus> {
+    let mut queue = state.sync.lock();
+    queue
+        .resolve(id, resolution)
+        .map_err(|e| ErrorDetail::new(codes::VALIDATION_ERROR, &e))?;
+    Ok(queue.status())
+}
+
+/// Drop a queued change without sending it
+#[tauri::command]
+pub async fn discard_pending_mutation(id: Uuid, state: tauri::State<'_, AppState>) -> CommandResult<SyncStatus> {
+    let mut queue = state.sync.lock();
+    queue
+        .discard(id)
+        .map_err(|e| ErrorDetail::new(codes::NOT_FOUND, &e))?;
+    Ok(queue.status())
+}
+
+/// Bearer token used when replaying; held in memory only
+#[tauri::command]
+pub async fn set_sync_credentials(token: Option<String>, state: tauri::State<'_, AppState>) -> CommandResult<()> {
+    state.sync.lock().set_token(token);
+    Ok(())
+}
+
+/// Work offline on purpose; going back online replays the queue
+#[tauri::command]
+pub async fn set_offline_mode(
+    forced: bool,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> CommandResult<SyncStatus> {
+    state.sync.lock().set_forced_offline(forced);
+    if forced {
+        return Ok(state.sync.lock().status());
+    }
+    Ok(sync::sync_now(&app).await?)
+}
+
+/// How conflicts found during replay are handled
+#[tauri::command]
+pub async fn set_conflict_policy(policy: ConflictPolicy, state: tauri::State<'_, AppState>) -> CommandResult<SyncStatus> {
+    let mut queue = state.sync.lock();
+    queue.set_conflict_policy(policy);
+    Ok(queue.status())
+}
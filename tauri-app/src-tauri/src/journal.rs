@@ -0,0 +1,372 @@
+//! Autosave journal, undo/redo history and crash recovery for editable AppState.
+//!
+//! Every mutating command records a snapshot of the editable state before it
+//! changes anything (for undo) and writes the resulting state to an autosave
+//! journal (for recovery). Undo history is kept per project: a change to one
+//! project's record or hardware basket is undone without touching any other
+//! project, and shared settings have a history of their own. A session
+//! marker file is created on startup and
+//! removed on clean exit; if it is still present at the next launch, the
+//! previous session crashed and its journal can be restored.
+
+use core_engine::models::*;
+use core_engine::sizing::HardwareBasket;
+use core_engine::translation::TranslationRules;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::state::{AppSettings, TcoParameters};
+
+/// Maximum number of undo steps kept in memory
+pub const UNDO_LIMIT: usize = 50;
+
+const JOURNAL_FILE: &str = "autosave_journal.json";
+const SESSION_MARKER: &str = "session.lock";
+
+/// The user-editable part of AppState
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditableSnapshot {
+    /// Hardware basket per project
+    #[serde(default)]
+    pub hardware_baskets: HashMap<Uuid, HardwareBasket>,
+    pub translation_rules: TranslationRules,
+    pub tco_parameters: TcoParameters,
+    pub app_settings: AppSettings,
+    pub projects: HashMap<Uuid, Project>,
+    pub hardware_pool: HardwarePool,
+}
+
+impl EditableSnapshot {
+    /// `self` with the part covered by `scope` taken from `previous`
+    pub fn restored(mut self, scope: ChangeScope, mut previous: EditableSnapshot) -> EditableSnapshot {
+        match scope {
+            ChangeScope::All => previous,
+            ChangeScope::Shared => {
+                previous.projects = self.projects;
+                previous.hardware_baskets = self.hardware_baskets;
+                previous
+            }
+            ChangeScope::Project(id) => {
+                match previous.projects.remove(&id) {
+                    Some(project) => self.projects.insert(id, project),
+                    None => self.projects.remove(&id),
+                };
+                match previous.hardware_baskets.remove(&id) {
+                    Some(basket) => self.hardware_baskets.insert(id, basket),
+                    None => self.hardware_baskets.remove(&id),
+                };
+                self
+            }
+        }
+    }
+}
+
+/// What an undo step restores
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeScope {
+    /// One project's record and hardware basket
+    Project(Uuid),
+    /// Translation rules, TCO parameters, settings and the hardware pool
+    Shared,
+    /// The whole editable state, e.g. restoring an autosave
+    All,
+}
+
+impl ChangeScope {
+    /// The history a step is undone from: its project's, or the shared one
+    fn history(self) -> Option<Uuid> {
+        match self {
+            ChangeScope::Project(id) => Some(id),
+            ChangeScope::Shared | ChangeScope::All => None,
+        }
+    }
+}
+
+/// A snapshot taken before (undo) or after (redo) a change
+type Step = (ChangeScope, String, EditableSnapshot);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub sequence: u64,
+    pub action: String,
+    pub saved_at: chrono::DateTime<chrono::Utc>,
+    pub snapshot: EditableSnapshot,
+}
+
+/// Summary returned to the UI about the undo/redo stacks
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryStatus {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub undo_action: Option<String>,
+    pub redo_action: Option<String>,
+    pub undo_depth: usize,
+}
+
+/// Recovery offer shown on startup after a crash
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryInfo {
+    pub action: String,
+    pub saved_at: chrono::DateTime<chrono::Utc>,
+    pub projects: usize,
+    pub hardware_profiles: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionJournal {
+    dir: Option<PathBuf>,
+    /// Steps of every history, oldest first; bounded by UNDO_LIMIT overall
+    undo: VecDeque<Step>,
+    redo: Vec<Step>,
+    sequence: u64,
+    /// Journal left behind by a crashed session, awaiting restore/discard
+    pending_recovery: Option<JournalEntry>,
+}
+
+impl SessionJournal {
+    /// Start a session in `dir`, picking up any journal from a crashed session
+    pub fn open(&mut self, dir: PathBuf) -> Result<(), String> {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create journal directory: {}", e))?;
+
+        let marker = dir.join(SESSION_MARKER);
+        let journal = dir.join(JOURNAL_FILE);
+        if marker.exists() && journal.exists() {
+            match fs::read_to_string(&journal)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str::<JournalEntry>(&json).map_err(|e| e.to_string()))
+            {
+                Ok(entry) => self.pending_recovery = Some(entry),
+                Err(e) => eprintln!("Ignoring unreadable autosave journal: {}", e),
+            }
+        }
+
+        fs::write(&marker, chrono::Utc::now().to_rfc3339())
+            .map_err(|e| format!("Failed to write session marker: {}", e))?;
+        self.dir = Some(dir);
+        Ok(())
+    }
+
+    /// Mark a clean shutdown; the journal is no longer needed for recovery
+    pub fn close(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_file(dir.join(SESSION_MARKER));
+            let _ = fs::remove_file(dir.join(JOURNAL_FILE));
+        }
+    }
+
+    /// Push the pre-change state onto the undo stack. A new change discards
+    /// the redo branch of its own history only.
+    pub fn record(&mut self, scope: ChangeScope, action: &str, before: EditableSnapshot) {
+        self.undo.push_back((scope, action.to_string(), before));
+        while self.undo.len() > UNDO_LIMIT {
+            self.undo.pop_front();
+        }
+        self.redo.retain(|(s, _, _)| s.history() != scope.history());
+    }
+
+    /// Write the current state to the autosave journal
+    pub fn autosave(&mut self, action: &str, current: EditableSnapshot) {
+        let Some(dir) = &self.dir else { return };
+        self.sequence += 1;
+        let entry = JournalEntry {
+            sequence: self.sequence,
+            action: action.to_string(),
+            saved_at: chrono::Utc::now(),
+            snapshot: current,
+        };
+
+        // Write-then-rename so a crash mid-write never corrupts the last good journal
+        let tmp = dir.join(format!("{}.tmp", JOURNAL_FILE));
+        let result = serde_json::to_string(&entry)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(&tmp, json).map_err(|e| e.to_string()))
+            .and_then(|_| fs::rename(&tmp, dir.join(JOURNAL_FILE)).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Autosave failed: {}", e);
+        }
+    }
+
+    /// Swap `current` for the most recent undo snapshot in a project's
+    /// history (`None` for shared changes). Apply the result with
+    /// [`EditableSnapshot::restored`] so only the step's scope is reverted.
+    pub fn undo(&mut self, project: Option<Uuid>, current: EditableSnapshot) -> Option<Step> {
+        let index = self.undo.iter().rposition(|(s, _, _)| s.history() == project)?;
+        let (scope, action, previous) = self.undo.remove(index)?;
+        self.redo.push((scope, action.clone(), current));
+        Some((scope, action, previous))
+    }
+
+    /// Swap `current` for the most recent redo snapshot in a project's history
+    pub fn redo(&mut self, project: Option<Uuid>, current: EditableSnapshot) -> Option<Step> {
+        let index = self.redo.iter().rposition(|(s, _, _)| s.history() == project)?;
+        let (scope, action, next) = self.redo.remove(index);
+        self.undo.push_back((scope, action.clone(), current));
+        Some((scope, action, next))
+    }
+
+    /// Undo/redo availability in a project's history (`None` for shared changes)
+    pub fn status(&self, project: Option<Uuid>) -> HistoryStatus {
+        let undo: Vec<&Step> = self.undo.iter().filter(|(s, _, _)| s.history() == project).collect();
+        let redo = self.redo.iter().rfind(|(s, _, _)| s.history() == project);
+        HistoryStatus {
+            can_undo: !undo.is_empty(),
+            can_redo: redo.is_some(),
+            undo_action: undo.last().map(|(_, a, _)| a.clone()),
+            redo_action: redo.map(|(_, a, _)| a.clone()),
+            undo_depth: undo.len(),
+        }
+    }
+
+    pub fn recovery_info(&self) -> Option<RecoveryInfo> {
+        self.pending_recovery.as_ref().map(|entry| RecoveryInfo {
+            action: entry.action.clone(),
+            saved_at: entry.saved_at,
+            projects: entry.snapshot.projects.len(),
+            hardware_profiles: entry
+                .snapshot
+                .hardware_baskets
+                .values()
+                .map(|basket| basket.get_profiles().len())
+                .sum(),
+        })
+    }
+
+    pub fn take_recovery(&mut self) -> Option<JournalEntry> {
+        self.pending_recovery.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(rows_per_page: u32) -> EditableSnapshot {
+        let mut app_settings = AppSettings::default();
+        app_settings.ui_preferences.table_preferences.rows_per_page = rows_per_page;
+        EditableSnapshot {
+            hardware_baskets: HashMap::new(),
+            translation_rules: TranslationRules::default(),
+            tco_parameters: TcoParameters::default(),
+            app_settings,
+            projects: HashMap::new(),
+            hardware_pool: HardwarePool::default(),
+        }
+    }
+
+    fn profile(name: &str) -> HardwareProfile {
+        HardwareProfile {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            manufacturer: "Dell".to_string(),
+            model: "R760".to_string(),
+            cpu_sockets: 2,
+            cores_per_socket: 32,
+            total_cores: 64,
+            max_memory_gb: 1024,
+            storage_slots: 8,
+            network_ports: 4,
+            is_hci_certified: true,
+            estimated_cost: None,
+            power_consumption_watts: None,
+            rack_units: 2,
+            notes: None,
+        }
+    }
+
+    fn rows(s: &EditableSnapshot) -> u32 {
+        s.app_settings.ui_preferences.table_preferences.rows_per_page
+    }
+
+    #[test]
+    fn test_undo_redo_round_trip_and_bound() {
+        let mut journal = SessionJournal::default();
+        journal.record(ChangeScope::Shared, "first", snapshot(1));
+        journal.record(ChangeScope::Shared, "second", snapshot(2));
+
+        let (_, action, previous) = journal.undo(None, snapshot(3)).unwrap();
+        assert_eq!(action, "second");
+        assert_eq!(rows(&previous), 2);
+
+        let (_, _, next) = journal.redo(None, previous).unwrap();
+        assert_eq!(rows(&next), 3);
+
+        // A new change discards the redo branch
+        journal.undo(None, next);
+        journal.record(ChangeScope::Shared, "third", snapshot(4));
+        assert!(!journal.status(None).can_redo);
+
+        for i in 0..(UNDO_LIMIT as u32 + 10) {
+            journal.record(ChangeScope::Shared, "bulk", snapshot(i));
+        }
+        assert_eq!(journal.status(None).undo_depth, UNDO_LIMIT);
+    }
+
+    #[test]
+    fn test_undo_in_one_project_leaves_other_projects_alone() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let with_baskets = |ids: &[(Uuid, usize)]| {
+            let mut s = snapshot(25);
+            for (id, profiles) in ids {
+                let mut basket = HardwareBasket::new();
+                for i in 0..*profiles {
+                    basket.add_profile(profile(&format!("custom-{}", i)));
+                }
+                s.hardware_baskets.insert(*id, basket);
+            }
+            s
+        };
+        // Profiles added on top of the basket's defaults
+        let defaults = HardwareBasket::new().get_profiles().len();
+        let profiles = |s: &EditableSnapshot, id: Uuid| {
+            s.hardware_baskets.get(&id).map(|b| b.get_profiles().len() - defaults)
+        };
+
+        // Edit A, then B, then A again
+        let mut journal = SessionJournal::default();
+        journal.record(ChangeScope::Project(a), "Add to A", with_baskets(&[]));
+        journal.record(ChangeScope::Project(b), "Add to B", with_baskets(&[(a, 1)]));
+        journal.record(ChangeScope::Project(a), "Add to A", with_baskets(&[(a, 1), (b, 1)]));
+        let current = with_baskets(&[(a, 2), (b, 1)]);
+
+        let (scope, _, previous) = journal.undo(Some(a), current.clone()).unwrap();
+        let after = current.clone().restored(scope, previous);
+        assert_eq!(profiles(&after, a), Some(1));
+        assert_eq!(profiles(&after, b), Some(1));
+
+        // B's history is untouched: its only step is still next in line
+        assert_eq!(journal.status(Some(b)).undo_depth, 1);
+        let (scope, _, previous) = journal.undo(Some(b), after.clone()).unwrap();
+        let after = after.restored(scope, previous);
+        assert_eq!(profiles(&after, a), Some(1));
+        assert_eq!(profiles(&after, b), None);
+
+        // Undoing B kept A's redo step
+        assert!(journal.status(Some(a)).can_redo);
+        assert!(!journal.status(None).can_undo);
+    }
+
+    #[test]
+    fn test_crash_leaves_recoverable_journal() {
+        let dir = std::env::temp_dir().join(format!("archer-journal-{}", Uuid::new_v4()));
+
+        let mut crashed = SessionJournal::default();
+        crashed.open(dir.clone()).unwrap();
+        crashed.autosave("edit settings", snapshot(42));
+        // No close(): simulates a crash
+
+        let mut next = SessionJournal::default();
+        next.open(dir.clone()).unwrap();
+        assert_eq!(next.recovery_info().unwrap().action, "edit settings");
+        assert_eq!(rows(&next.take_recovery().unwrap().snapshot), 42);
+
+        next.close();
+        let mut clean = SessionJournal::default();
+        clean.open(dir.clone()).unwrap();
+        assert!(clean.recovery_info().is_none());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,28 @@
+//! OS keychain storage for the project encryption key
+//! (Windows Credential Manager, macOS Keychain, Secret Service on Linux).
+
+use core_engine::project_crypto::ProjectKey;
+use core_engine::CoreEngineError;
+
+const SERVICE: &str = "archer-infra-planner";
+const ACCOUNT: &str = "project-encryption-key";
+
+fn entry() -> Result<keyring::Entry, CoreEngineError> {
+    keyring::Entry::new(SERVICE, ACCOUNT)
+        .map_err(|e| CoreEngineError::config(format!("OS keychain unavailable: {}", e)))
+}
+
+/// Store a project key in the OS keychain
+pub fn store_key(key: &ProjectKey) -> Result<(), CoreEngineError> {
+    entry()?
+        .set_password(&key.to_base64())
+        .map_err(|e| CoreEngineError::config(format!("Failed to store key in OS keychain: {}", e)))
+}
+
+/// Load the project key from the OS keychain
+pub fn load_key() -> Result<ProjectKey, CoreEngineError> {
+    let encoded = entry()?
+        .get_password()
+        .map_err(|e| CoreEngineError::config(format!("Failed to read key from OS keychain: {}", e)))?;
+    ProjectKey::from_base64(&encoded)
+}
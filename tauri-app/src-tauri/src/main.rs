@@ -3,11 +3,16 @@
 
 mod state;
 mod commands;
+mod keychain;
+mod journal;
+mod updater;
+mod sync;
 
 use state::AppState;
 use commands::*;
 
 use tauri::Manager;
+use core_engine::project_crypto::KeySource;
 use core_engine::project_manager::ProjectManager;
 
 fn main() {
@@ -19,23 +24,76 @@ fn main() {
             let config_dir = app.path_resolver().app_config_dir().expect("failed to get app config dir");
 
             // Initialize the project manager
-            let project_manager = ProjectManager::new(&config_dir).expect("failed to create project manager");
+            let mut project_manager = ProjectManager::new(&config_dir).expect("failed to create project manager");
 
-            // Load projects and hardware pool
-            let projects = project_manager.load_projects().expect("failed to load projects");
-            let hardware_pool = project_manager.load_hardware_pool().expect("failed to load hardware pool");
+            // Keychain-protected projects unlock silently; passphrase-protected
+            // projects stay locked until the UI calls `unlock_projects`
+            let metadata = project_manager.encryption_metadata().expect("failed to read encryption metadata");
+            let unlocked = match metadata.map(|m| m.key_source) {
+                None => true,
+                Some(KeySource::Keychain) => match keychain::load_key().and_then(|key| project_manager.unlock_with_key(key)) {
+                    Ok(()) => {
+                        // Pick up any plaintext files written by older versions
+                        if let Err(e) = project_manager.migrate_plaintext_files() {
+                            eprintln!("Failed to encrypt plaintext project files: {}", e);
+                        }
+                        true
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to unlock projects from OS keychain: {}", e);
+                        false
+                    }
+                },
+                Some(KeySource::Passphrase) => false,
+            };
 
-            // Populate the state
-            *app_state.projects.write() = projects;
-            *app_state.hardware_pool.write() = hardware_pool;
+            if unlocked {
+                // Load projects and hardware pool
+                let projects = project_manager.load_projects().expect("failed to load projects");
+                let hardware_pool = project_manager.load_hardware_pool().expect("failed to load hardware pool");
+
+                // Populate the state
+                *app_state.projects.write() = projects;
+                *app_state.hardware_pool.write() = hardware_pool;
+            }
             *app_state.project_manager.write() = Some(project_manager);
 
+            // Stored vendor credentials are encrypted with the project key;
+            // passphrase-protected stores restore them in `unlock_projects`
+            if unlocked {
+                let handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = restore_vendor_credentials(&handle.state::<AppState>()).await {
+                        eprintln!("Failed to restore vendor credentials: {}", e);
+                    }
+                });
+            }
+
+            // Start the autosave journal; a journal left by a crashed session
+            // is offered to the UI through `get_recovery_info`
+            if let Err(e) = app_state.journal.lock().open(config_dir.join("journal")) {
+                eprintln!("Autosave disabled: {}", e);
+            }
+
+            // Release channel and backend handshake; results arrive as events
+            let update_settings = updater::UpdateSettings::load(&config_dir);
+            app_state._app_settings.write().updates = update_settings.clone();
+            updater::spawn_startup_checks(app_handle.clone(), update_settings);
+
+            // Backend changes left queued by the last session replay once
+            // the backend is reachable
+            if let Err(e) = app_state.sync.lock().open(config_dir.join("sync")) {
+                eprintln!("Offline sync disabled: {}", e);
+            }
+            sync::spawn_monitor(app_handle.clone());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             // Environment management
             process_rvtools_file,
             get_environment_summary,
+            get_import_report,
             clear_environment,
             
             // Analysis
@@ -58,6 +116,7 @@ fn main() {
             get_model_specifications,
             get_compatibility_matrix,
             search_server_configurations,
+            compare_server_configurations,
             enrich_server_configuration,
             get_configuration_pricing,
             refresh_vendor_data,
@@ -65,6 +124,9 @@ fn main() {
             
             // Planning
             calculate_sizing,
+            open_sizing_session,
+            update_sizing_session,
+            close_sizing_session,
             get_forecast,
             
             // Migration
@@ -83,11 +145,33 @@ fn main() {
             // Settings
             get_app_settings,
             update_app_settings,
+
+            // Versions & updates
+            get_version_info,
+            check_backend_compatibility,
+            get_update_settings,
+            set_update_settings,
+            check_for_update,
+            install_update,
+
+            // Offline sync
+            queue_backend_mutation,
+            get_sync_status,
+            list_pending_mutations,
+            sync_now,
+            resolve_sync_conflict,
+            discard_pending_mutation,
+            set_sync_credentials,
+            set_offline_mode,
+            set_conflict_policy,
             
             // File operations
             file_exists,
             get_file_info,
 
+            // Error registry
+            list_error_codes,
+
             // Network Visualizer
             get_network_topology,
 
@@ -97,7 +181,30 @@ fn main() {
             get_project,
             update_project,
             delete_project,
+            open_project,
+            close_project,
+            list_open_projects,
+            set_project_member,
+
+            // Project file encryption
+            get_project_encryption_status,
+            enable_project_encryption,
+            unlock_projects,
+
+            // History & recovery
+            undo_change,
+            redo_change,
+            get_history_status,
+            get_recovery_info,
+            restore_from_journal,
+            discard_recovery,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Clean shutdown: nothing to recover next time
+                app_handle.state::<AppState>().journal.lock().close();
+            }
+        });
 }
@@ -1,23 +1,31 @@
 use core_engine::models::*;
 use core_engine::sizing::HardwareBasket;
+use core_engine::sizing_session::SizingSession;
 use core_engine::forecasting::ForecastParameters;
 use core_engine::translation::TranslationRules;
 use core_engine::vendor_client::VendorCredentials;
 use core_engine::vendor_data::VendorDataManager;
 use core_engine::project_manager::ProjectManager;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::journal::{ChangeScope, EditableSnapshot, SessionJournal};
+use crate::sync::SyncQueue;
+use crate::updater::UpdateSettings;
+
 /// Application state shared across all Tauri commands
 #[derive(Debug)]
 pub struct AppState {
-    /// Currently loaded vSphere environment
-    pub current_environment: Arc<RwLock<Option<VsphereEnvironment>>>,
+    /// Projects open in this session; project-scoped commands require one
+    pub open_projects: Arc<RwLock<HashMap<Uuid, OpenProject>>>,
+
+    /// Loaded vSphere environment per project
+    pub environments: Arc<RwLock<HashMap<Uuid, VsphereEnvironment>>>,
     
-    /// Hardware basket with server profiles
-    pub _hardware_basket: Arc<RwLock<HardwareBasket>>,
+    /// Hardware basket with server profiles per project
+    pub hardware_baskets: Arc<RwLock<HashMap<Uuid, HardwareBasket>>>,
     
     /// Translation rules for VMware to Microsoft mapping
     pub _translation_rules: Arc<RwLock<TranslationRules>>,
@@ -37,11 +45,14 @@ pub struct AppState {
     /// Vendor data manager for server catalogs and sizing
     pub vendor_data_manager: Arc<RwLock<Option<VendorDataManager>>>,
     
-    /// Analysis results cache
+    /// Analysis results cache per project
     pub analysis_cache: Arc<RwLock<HashMap<Uuid, AnalysisResult>>>,
     
-    /// Sizing results cache
-    pub sizing_cache: Arc<RwLock<HashMap<String, SizingResultCache>>>,
+    /// Sizing results cache per project, keyed by profile and parameters
+    pub sizing_cache: Arc<RwLock<HashMap<Uuid, HashMap<String, SizingResultCache>>>>,
+
+    /// Interactive sizing sessions by session ID
+    pub sizing_sessions: Arc<Mutex<HashMap<Uuid, OpenSizingSession>>>,
 
     /// Loaded projects
     pub projects: Arc<RwLock<HashMap<Uuid, Project>>>,
@@ -51,6 +62,26 @@ pub struct AppState {
 
     /// Project manager for loading/saving projects
     pub project_manager: Arc<RwLock<Option<ProjectManager>>>,
+
+    /// Undo/redo history and autosave journal
+    pub journal: Arc<Mutex<SessionJournal>>,
+
+    /// Backend changes queued while offline
+    pub sync: Arc<Mutex<SyncQueue>>,
+}
+
+/// A project open in this session
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct OpenProject {
+    pub project_id: Uuid,
+    pub opened_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// An interactive sizing session over a project's loaded environment
+#[derive(Debug)]
+pub struct OpenSizingSession {
+    pub project_id: Uuid,
+    pub session: SizingSession,
 }
 
 /// TCO calculation parameters
@@ -95,6 +126,9 @@ pub struct AppSettings {
     pub ui_preferences: UiPreferences,
     pub file_locations: FileLocations,
     pub export_settings: ExportSettings,
+    /// Release channel and backend handshake; also saved to `updates.json`
+    #[serde(default)]
+    pub updates: UpdateSettings,
 }
 
 /// UI preferences
@@ -194,8 +228,9 @@ pub struct SizingResultCache {
 impl AppState {
     pub fn new() -> Self {
         Self {
-            current_environment: Arc::new(RwLock::new(None)),
-            _hardware_basket: Arc::new(RwLock::new(HardwareBasket::new())),
+            open_projects: Arc::new(RwLock::new(HashMap::new())),
+            environments: Arc::new(RwLock::new(HashMap::new())),
+            hardware_baskets: Arc::new(RwLock::new(HashMap::new())),
             _translation_rules: Arc::new(RwLock::new(TranslationRules::default())),
             _tco_parameters: Arc::new(RwLock::new(TcoParameters::default())),
             _app_settings: Arc::new(RwLock::new(AppSettings::default())),
@@ -204,60 +239,153 @@ impl AppState {
             vendor_data_manager: Arc::new(RwLock::new(None)),
             analysis_cache: Arc::new(RwLock::new(HashMap::new())),
             sizing_cache: Arc::new(RwLock::new(HashMap::new())),
+            sizing_sessions: Arc::new(Mutex::new(HashMap::new())),
             projects: Arc::new(RwLock::new(HashMap::new())),
             hardware_pool: Arc::new(RwLock::new(HardwarePool::default())),
             project_manager: Arc::new(RwLock::new(None)),
+            journal: Arc::new(Mutex::new(SessionJournal::default())),
+            sync: Arc::new(Mutex::new(SyncQueue::default())),
         }
     }
 
-    /// Get the current environment if loaded
-    pub fn get_current_environment(&self) -> Option<VsphereEnvironment> {
-        self.current_environment.read().clone()
+    /// The OS account running the app; project roles are granted to it
+    pub fn current_user(&self) -> String {
+        std::env::var("USERNAME")
+            .or_else(|_| std::env::var("USER"))
+            .unwrap_or_else(|_| "local".to_string())
     }
 
-    /// Set the current environment
-    pub fn set_current_environment(&self, environment: VsphereEnvironment) {
-        let mut env = self.current_environment.write();
-        *env = Some(environment);
-        
-        // Clear caches when environment changes
-        self.analysis_cache.write().clear();
-        self.sizing_cache.write().clear();
+    /// Capture the user-editable state
+    pub fn snapshot(&self) -> EditableSnapshot {
+        EditableSnapshot {
+            hardware_baskets: self.hardware_baskets.read().clone(),
+            translation_rules: self._translation_rules.read().clone(),
+            tco_parameters: self._tco_parameters.read().clone(),
+            app_settings: self._app_settings.read().clone(),
+            projects: self.projects.read().clone(),
+            hardware_pool: self.hardware_pool.read().clone(),
+        }
+    }
+
+    /// Replace the user-editable state, persisting projects and the hardware pool
+    pub fn apply_snapshot(&self, snapshot: EditableSnapshot) -> Result<(), core_engine::CoreEngineError> {
+        if let Some(manager) = &*self.project_manager.read() {
+            let current_ids: Vec<Uuid> = self.projects.read().keys().copied().collect();
+            for id in current_ids.iter().filter(|id| !snapshot.projects.contains_key(id)) {
+                manager.delete_project(&id.to_string())?;
+            }
+            for project in snapshot.projects.values() {
+                manager.save_project(project)?;
+            }
+            manager.save_hardware_pool(&snapshot.hardware_pool)?;
+        }
+
+        *self.hardware_baskets.write() = snapshot.hardware_baskets;
+        *self._translation_rules.write() = snapshot.translation_rules;
+        *self._tco_parameters.write() = snapshot.tco_parameters;
+        *self._app_settings.write() = snapshot.app_settings;
+        *self.projects.write() = snapshot.projects;
+        *self.hardware_pool.write() = snapshot.hardware_pool;
+        Ok(())
+    }
+
+    /// Record the state before a mutation so it can be undone within `scope`.
+    ///
+    /// Must be called before taking any write lock on the editable state.
+    pub fn record_change(&self, scope: ChangeScope, action: &str) {
+        let before = self.snapshot();
+        self.journal.lock().record(scope, action, before);
+    }
+
+    /// Write the state after a mutation to the autosave journal
+    pub fn autosave(&self, action: &str) {
+        let current = self.snapshot();
+        self.journal.lock().autosave(action, current);
+    }
+
+    /// Open a project for this session; opening an open project is a no-op
+    pub fn open_project(&self, project_id: Uuid) -> OpenProject {
+        self.open_projects
+            .write()
+            .entry(project_id)
+            .or_insert_with(|| OpenProject {
+                project_id,
+                opened_at: chrono::Utc::now(),
+            })
+            .clone()
+    }
+
+    /// Close a project, dropping its environment and caches. Its hardware
+    /// basket is kept so reopening the project picks it up again.
+    pub fn close_project(&self, project_id: &Uuid) -> bool {
+        self.clear_environment(project_id);
+        self.open_projects.write().remove(project_id).is_some()
+    }
+
+    pub fn is_project_open(&self, project_id: &Uuid) -> bool {
+        self.open_projects.read().contains_key(project_id)
+    }
+
+    /// Get a project's environment if loaded
+    pub fn get_environment(&self, project_id: &Uuid) -> Option<VsphereEnvironment> {
+        self.environments.read().get(project_id).cloned()
     }
 
-    /// Clear the current environment
-    pub fn clear_current_environment(&self) {
-        let mut env = self.current_environment.write();
-        *env = None;
+    /// Set a project's environment
+    pub fn set_environment(&self, project_id: Uuid, environment: VsphereEnvironment) {
+        self.environments.write().insert(project_id, environment);
         
-        // Clear caches
-        self.analysis_cache.write().clear();
-        self.sizing_cache.write().clear();
+        // Clear the project's caches when its environment changes
+        self.analysis_cache.write().remove(&project_id);
+        self.sizing_cache.write().remove(&project_id);
+        self.close_sizing_sessions(&project_id);
     }
 
-    /// Generate a hash for the current environment for cache validation
-    pub fn _get_environment_hash(&self) -> Option<String> {
-        if let Some(env) = self.get_current_environment() {
-            // Simple hash based on environment ID and parse timestamp
-            Some(format!("{}-{}", env.id, env.parsed_at.timestamp()))
-        } else {
-            None
-        }
+    /// Clear a project's environment and caches
+    pub fn clear_environment(&self, project_id: &Uuid) {
+        self.environments.write().remove(project_id);
+        self.analysis_cache.write().remove(project_id);
+        self.sizing_cache.write().remove(project_id);
+        self.close_sizing_sessions(project_id);
     }
 
-    /// Check if analysis cache is valid for current environment
-    pub fn _is_analysis_cache_valid(&self, environment_id: &Uuid) -> bool {
-        if let Some(env) = self.get_current_environment() {
-            env.id == *environment_id
-        } else {
-            false
-        }
+    /// Drop the sizing sessions over a project's environment
+    fn close_sizing_sessions(&self, project_id: &Uuid) {
+        self.sizing_sessions.lock().retain(|_, open| open.project_id != *project_id);
+    }
+
+    /// A project's hardware basket; empty until profiles are added or loaded
+    pub fn hardware_basket(&self, project_id: &Uuid) -> HardwareBasket {
+        self.hardware_baskets
+            .read()
+            .get(project_id)
+            .cloned()
+            .unwrap_or_else(HardwareBasket::new)
     }
 
-    /// Check if sizing cache is valid for current environment and parameters
-    pub fn _is_sizing_cache_valid(&self, cache_key: &str, environment_hash: &str) -> bool {
-        if let Some(current_hash) = self._get_environment_hash() {
-            if let Some(cached) = self.sizing_cache.read().get(cache_key) {
+    /// Change a project's hardware basket, creating it if needed
+    pub fn update_hardware_basket<R>(&self, project_id: Uuid, update: impl FnOnce(&mut HardwareBasket) -> R) -> R {
+        let mut baskets = self.hardware_baskets.write();
+        update(baskets.entry(project_id).or_insert_with(HardwareBasket::new))
+    }
+
+    /// Generate a hash for a project's environment for cache validation
+    pub fn _get_environment_hash(&self, project_id: &Uuid) -> Option<String> {
+        // Simple hash based on environment ID and parse timestamp
+        self.get_environment(project_id)
+            .map(|env| format!("{}-{}", env.id, env.parsed_at.timestamp()))
+    }
+
+    /// Check if a project's analysis cache is valid for its current environment
+    pub fn _is_analysis_cache_valid(&self, project_id: &Uuid, environment_id: &Uuid) -> bool {
+        self.get_environment(project_id)
+            .map_or(false, |env| env.id == *environment_id)
+    }
+
+    /// Check if a project's sizing cache is valid for its current environment and parameters
+    pub fn _is_sizing_cache_valid(&self, project_id: &Uuid, cache_key: &str, environment_hash: &str) -> bool {
+        if let Some(current_hash) = self._get_environment_hash(project_id) {
+            if let Some(cached) = self.sizing_cache.read().get(project_id).and_then(|c| c.get(cache_key)) {
                 return cached.environment_hash == current_hash && cached.environment_hash == environment_hash;
             }
         }
@@ -345,6 +473,7 @@ impl Default for AppSettings {
             ui_preferences: UiPreferences::default(),
             file_locations: FileLocations::default(),
             export_settings: ExportSettings::default(),
+            updates: UpdateSettings::default(),
         }
     }
 }
@@ -414,3 +543,105 @@ impl Default for AnalysisParameters {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_engine::analysis::AnalysisEngine;
+
+    fn environment(name: &str) -> VsphereEnvironment {
+        VsphereEnvironment {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            parsed_at: chrono::Utc::now(),
+            clusters: Vec::new(),
+            standalone_hosts: Vec::new(),
+            total_vms: 0,
+            total_hosts: 0,
+            summary_metrics: EnvironmentSummary {
+                total_vcpus: 0,
+                total_pcores: 0,
+                total_provisioned_memory_gb: 0.0,
+                total_consumed_memory_gb: 0.0,
+                total_provisioned_storage_gb: 0.0,
+                total_consumed_storage_gb: 0.0,
+                overall_vcpu_pcpu_ratio: 0.0,
+                health_issues: Vec::new(),
+            },
+            import_report: ImportReport::default(),
+        }
+    }
+
+    /// Open a project with a loaded environment, analysis, sizing cache and session
+    fn open_with_workspace(state: &AppState, name: &str) -> Uuid {
+        let project_id = Uuid::new_v4();
+        state.open_project(project_id);
+        let env = environment(name);
+        state.set_environment(project_id, env.clone());
+
+        state.analysis_cache.write().insert(
+            project_id,
+            AnalysisResult {
+                environment_id: env.id,
+                analysis_report: AnalysisEngine::analyze_environment(&env).unwrap(),
+                generated_at: chrono::Utc::now(),
+                parameters_used: AnalysisParameters::default(),
+            },
+        );
+        state.sizing_cache.write().insert(project_id, HashMap::new());
+        let profile = HardwareBasket::new().get_profiles()[0].clone();
+        state.sizing_sessions.lock().insert(
+            Uuid::new_v4(),
+            OpenSizingSession {
+                project_id,
+                session: SizingSession::new(&env, profile, SizingParameters::default()).unwrap(),
+            },
+        );
+        state.update_hardware_basket(project_id, |_| ());
+        project_id
+    }
+
+    fn has_workspace(state: &AppState, project_id: &Uuid) -> bool {
+        state.get_environment(project_id).is_some()
+            && state.analysis_cache.read().contains_key(project_id)
+            && state.sizing_cache.read().contains_key(project_id)
+            && state.sizing_sessions.lock().values().any(|open| open.project_id == *project_id)
+    }
+
+    #[test]
+    fn test_set_environment_only_invalidates_its_project() {
+        let state = AppState::new();
+        let a = open_with_workspace(&state, "site-a");
+        let b = open_with_workspace(&state, "site-b");
+
+        state.set_environment(a, environment("site-a-reimport"));
+
+        assert_eq!(state.get_environment(&a).unwrap().name, "site-a-reimport");
+        assert!(!state.analysis_cache.read().contains_key(&a));
+        assert!(!state.sizing_cache.read().contains_key(&a));
+        assert!(!state.sizing_sessions.lock().values().any(|open| open.project_id == a));
+        assert!(has_workspace(&state, &b));
+        assert_eq!(state.get_environment(&b).unwrap().name, "site-b");
+    }
+
+    #[test]
+    fn test_close_project_drops_only_that_project() {
+        let state = AppState::new();
+        let a = open_with_workspace(&state, "site-a");
+        let b = open_with_workspace(&state, "site-b");
+
+        assert!(state.close_project(&a));
+        assert!(!state.close_project(&a));
+
+        assert!(!state.is_project_open(&a));
+        assert!(state.get_environment(&a).is_none());
+        assert!(!state.analysis_cache.read().contains_key(&a));
+        assert!(!state.sizing_cache.read().contains_key(&a));
+        assert!(!state.sizing_sessions.lock().values().any(|open| open.project_id == a));
+        // The basket stays for when the project is reopened
+        assert!(state.hardware_baskets.read().contains_key(&a));
+
+        assert!(state.is_project_open(&b));
+        assert!(has_workspace(&state, &b));
+    }
+}
@@ -0,0 +1,685 @@
+//! Offline-first sync of backend-bound changes.
+//!
+//! Changes meant for the backend (ticket updates, project changes, ...) are
+//! never sent directly: the UI queues them with `queue_backend_mutation` and
+//! they are replayed in order whenever the backend is reachable. The queue is
+//! written to `sync_queue.json` after every change so nothing is lost when
+//! the app closes offline.
+//!
+//! A change queued with a `base_version` (the record's `updated_at` when the
+//! user started editing) is checked against the server copy before it is
+//! replayed. If someone else changed the record in the meantime, the
+//! [`ConflictPolicy`] decides: keep the change for the user to resolve, send
+//! it anyway, or drop it in favour of the server copy. Later changes to a
+//! record with an unresolved conflict wait until it is resolved.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use core_engine::CoreEngineError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+const QUEUE_FILE: &str = "sync_queue.json";
+
+/// How often the backend is probed to detect reconnection
+const PROBE_INTERVAL: Duration = Duration::from_secs(15);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Event emitted with a [`SyncStatus`] whenever connectivity or the queue changes
+pub const SYNC_STATUS_EVENT: &str = "sync-status";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum MutationMethod {
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl MutationMethod {
+    fn as_reqwest(&self) -> reqwest::Method {
+        match self {
+            MutationMethod::Post => reqwest::Method::POST,
+            MutationMethod::Put => reqwest::Method::PUT,
+            MutationMethod::Patch => reqwest::Method::PATCH,
+            MutationMethod::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+/// What to do when the server copy changed while the user was offline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Keep the change and let the user pick a side
+    #[default]
+    Manual,
+    /// Send the change anyway, overwriting the other edit
+    LocalWins,
+    /// Drop the change; the server copy stays
+    ServerWins,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationState {
+    Pending,
+    /// The server copy changed; waiting for the user
+    Conflict,
+    /// The backend rejected the change; waiting for the user to discard it
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Replay the local change over the server copy
+    KeepLocal,
+    /// Drop the local change
+    KeepServer,
+}
+
+/// A backend change waiting to be replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingMutation {
+    pub id: Uuid,
+    /// Record the change applies to, e.g. `ticket:42`; changes to one record replay in order
+    pub resource: String,
+    /// Shown in the sync panel, e.g. "Close ticket #42"
+    pub description: String,
+    pub method: MutationMethod,
+    /// Backend path, e.g. `/api/v1/tickets/42`
+    pub path: String,
+    pub body: Option<Value>,
+    /// `updated_at` of the record when the user started editing
+    pub base_version: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    pub attempts: u32,
+    pub state: MutationState,
+    pub last_error: Option<String>,
+    /// Server copy seen when a conflict was detected
+    pub server_copy: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueueMutationRequest {
+    pub resource: String,
+    pub description: String,
+    pub method: MutationMethod,
+    pub path: String,
+    #[serde(default)]
+    pub body: Option<Value>,
+    #[serde(default)]
+    pub base_version: Option<String>,
+}
+
+/// Connectivity and queue summary for the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatus {
+    pub online: bool,
+    /// The user chose to work offline; nothing is sent until they go back online
+    pub forced_offline: bool,
+    pub syncing: bool,
+    pub backend_configured: bool,
+    pub pending: usize,
+    pub conflicts: usize,
+    pub failed: usize,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub conflict_policy: ConflictPolicy,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedQueue {
+    entries: Vec<PendingMutation>,
+    #[serde(default)]
+    conflict_policy: ConflictPolicy,
+    #[serde(default)]
+    forced_offline: bool,
+    last_sync_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncQueue {
+    dir: Option<PathBuf>,
+    entries: Vec<PendingMutation>,
+    online: bool,
+    forced_offline: bool,
+    syncing: bool,
+    backend_configured: bool,
+    last_sync_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    conflict_policy: ConflictPolicy,
+    /// Bearer token for replayed requests; kept in memory only
+    token: Option<String>,
+}
+
+impl SyncQueue {
+    /// Load the queue left by the previous session from `dir`
+    pub fn open(&mut self, dir: PathBuf) -> Result<(), String> {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create sync directory: {}", e))?;
+        let file = dir.join(QUEUE_FILE);
+        if file.exists() {
+            match fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str::<PersistedQueue>(&json).map_err(|e| e.to_string()))
+            {
+                Ok(saved) => {
+                    self.entries = saved.entries;
+                    self.conflict_policy = saved.conflict_policy;
+                    self.forced_offline = saved.forced_offline;
+                    self.last_sync_at = saved.last_sync_at;
+                }
+                Err(e) => eprintln!("Ignoring unreadable sync queue: {}", e),
+            }
+        }
+        self.dir = Some(dir);
+        Ok(())
+    }
+
+    fn persist(&self) {
+        let Some(dir) = &self.dir else { return };
+        let saved = PersistedQueue {
+            entries: self.entries.clone(),
+            conflict_policy: self.conflict_policy,
+            forced_offline: self.forced_offline,
+            last_sync_at: self.last_sync_at,
+        };
+
+        // Write-then-rename, as for the autosave journal
+        let tmp = dir.join(format!("{}.tmp", QUEUE_FILE));
+        let result = serde_json::to_string(&saved)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(&tmp, json).map_err(|e| e.to_string()))
+            .and_then(|_| fs::rename(&tmp, dir.join(QUEUE_FILE)).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Failed to save sync queue: {}", e);
+        }
+    }
+
+    pub fn enqueue(&mut self, request: QueueMutationRequest) -> Result<PendingMutation, String> {
+        if !request.path.starts_with("/api/") {
+            return Err("Mutation path must start with /api/".to_string());
+        }
+        if request.resource.trim().is_empty() {
+            return Err("Mutation resource is required".to_string());
+        }
+        if request.method != MutationMethod::Delete && request.body.is_none() {
+            return Err("Mutation body is required".to_string());
+        }
+
+        let entry = PendingMutation {
+            id: Uuid::new_v4(),
+            resource: request.resource,
+            description: request.description,
+            method: request.method,
+            path: request.path,
+            body: request.body,
+            base_version: request.base_version,
+            queued_at: Utc::now(),
+            attempts: 0,
+            state: MutationState::Pending,
+            last_error: None,
+            server_copy: None,
+        };
+        self.entries.push(entry.clone());
+        self.persist();
+        Ok(entry)
+    }
+
+    pub fn entries(&self) -> &[PendingMutation] {
+        &self.entries
+    }
+
+    /// Oldest pending change whose record has no unresolved conflict or failure
+    pub fn next_pending(&self) -> Option<PendingMutation> {
+        let mut blocked = HashSet::new();
+        for entry in &self.entries {
+            match entry.state {
+                MutationState::Pending if !blocked.contains(&entry.resource) => return Some(entry.clone()),
+                MutationState::Pending => {}
+                MutationState::Conflict | MutationState::Failed => {
+                    blocked.insert(entry.resource.clone());
+                }
+            }
+        }
+        None
+    }
+
+    fn update(&mut self, id: Uuid, apply: impl FnOnce(&mut PendingMutation)) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) {
+            apply(entry);
+        }
+        self.persist();
+    }
+
+    fn remove(&mut self, id: Uuid) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        self.persist();
+        self.entries.len() != before
+    }
+
+    fn record_outcome(&mut self, id: Uuid, outcome: ReplayOutcome) -> bool {
+        match outcome {
+            ReplayOutcome::Applied | ReplayOutcome::Superseded => {
+                self.remove(id);
+            }
+            ReplayOutcome::Conflict(server_copy) => self.update(id, |e| {
+                e.attempts += 1;
+                e.state = MutationState::Conflict;
+                e.last_error = Some("The record was changed by someone else".to_string());
+                e.server_copy = server_copy;
+            }),
+            ReplayOutcome::Rejected(message) => self.update(id, |e| {
+                e.attempts += 1;
+                e.state = MutationState::Failed;
+                e.last_error = Some(message);
+            }),
+            ReplayOutcome::Retry(message) => {
+                self.update(id, |e| {
+                    e.attempts += 1;
+                    e.last_error = Some(message.clone());
+                });
+                self.last_error = Some(message);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Settle a conflict: replay the local change regardless, or drop it
+    pub fn resolve(&mut self, id: Uuid, resolution: ConflictResolution) -> Result<(), String> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| "Queued change not found".to_string())?;
+        if entry.state != MutationState::Conflict {
+            return Err("Only conflicting changes can be resolved".to_string());
+        }
+        match resolution {
+            ConflictResolution::KeepLocal => self.update(id, |e| {
+                e.state = MutationState::Pending;
+                e.base_version = None;
+                e.server_copy = None;
+                e.last_error = None;
+            }),
+            ConflictResolution::KeepServer => {
+                self.remove(id);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn discard(&mut self, id: Uuid) -> Result<(), String> {
+        if self.remove(id) {
+            Ok(())
+        } else {
+            Err("Queued change not found".to_string())
+        }
+    }
+
+    pub fn set_forced_offline(&mut self, forced: bool) {
+        self.forced_offline = forced;
+        self.persist();
+    }
+
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.conflict_policy = policy;
+        self.persist();
+    }
+
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.token = token.filter(|t| !t.is_empty());
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        let count = |state| self.entries.iter().filter(|e| e.state == state).count();
+        SyncStatus {
+            online: self.online && !self.forced_offline,
+            forced_offline: self.forced_offline,
+            syncing: self.syncing,
+            backend_configured: self.backend_configured,
+            pending: count(MutationState::Pending),
+            conflicts: count(MutationState::Conflict),
+            failed: count(MutationState::Failed),
+            last_sync_at: self.last_sync_at,
+            last_error: self.last_error.clone(),
+            conflict_policy: self.conflict_policy,
+        }
+    }
+}
+
+/// Result of replaying one change
+#[derive(Debug)]
+enum ReplayOutcome {
+    Applied,
+    /// Dropped in favour of the server copy
+    Superseded,
+    Conflict(Option<Value>),
+    /// The backend refused the change; retrying will not help
+    Rejected(String),
+    /// Offline, server error or expired sign-in; try again later
+    Retry(String),
+}
+
+/// Whether the server copy moved on from the version the user edited.
+/// Records without `updated_at` cannot be checked and never conflict.
+pub fn server_changed(base_version: &str, server_copy: &Value) -> bool {
+    let record = server_copy.get("data").filter(|d| d.is_object()).unwrap_or(server_copy);
+    let Some(current) = record.get("updated_at").and_then(Value::as_str) else {
+        return false;
+    };
+    match (DateTime::parse_from_rfc3339(base_version), DateTime::parse_from_rfc3339(current)) {
+        (Ok(base), Ok(current)) => current > base,
+        _ => current != base_version,
+    }
+}
+
+fn http_client() -> Result<reqwest::Client, CoreEngineError> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| CoreEngineError::NetworkError(e.to_string()))
+}
+
+async fn probe(client: &reqwest::Client, backend_url: &str) -> bool {
+    client
+        .get(format!("{}/health", backend_url))
+        .send()
+        .await
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+}
+
+async fn replay(
+    client: &reqwest::Client,
+    backend_url: &str,
+    token: Option<&str>,
+    policy: ConflictPolicy,
+    entry: &PendingMutation,
+) -> ReplayOutcome {
+    let url = format!("{}{}", backend_url, entry.path);
+    let with_auth = |request: reqwest::RequestBuilder| match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+
+    // Check the server copy first when the change was made against a known version
+    if let (Some(base), true) = (entry.base_version.as_deref(), policy != ConflictPolicy::LocalWins) {
+        if entry.method != MutationMethod::Post {
+            match with_auth(client.get(&url)).send().await {
+                Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => {
+                    if entry.method == MutationMethod::Delete {
+                        return ReplayOutcome::Applied;
+                    }
+                    return ReplayOutcome::Conflict(None);
+                }
+                Ok(response) if response.status().is_success() => {
+                    let server_copy = response.json::<Value>().await.ok();
+                    if server_copy.as_ref().is_some_and(|copy| server_changed(base, copy)) {
+                        return match policy {
+                            ConflictPolicy::ServerWins => ReplayOutcome::Superseded,
+                            _ => ReplayOutcome::Conflict(server_copy),
+                        };
+                    }
+                }
+                Ok(response) => return ReplayOutcome::Retry(format!("Version check returned {}", response.status())),
+                Err(e) => return ReplayOutcome::Retry(format!("Backend unreachable: {}", e)),
+            }
+        }
+    }
+
+    let mut request = with_auth(client.request(entry.method.as_reqwest(), &url));
+    if let Some(body) = &entry.body {
+        request = request.json(body);
+    }
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => return ReplayOutcome::Retry(format!("Backend unreachable: {}", e)),
+    };
+
+    let status = response.status();
+    if status.is_success() {
+        return ReplayOutcome::Applied;
+    }
+    let body = response.json::<Value>().await.ok();
+    let message = body
+        .as_ref()
+        .and_then(|b| b.pointer("/error/message").or_else(|| b.get("error")))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| status.to_string());
+    match status.as_u16() {
+        409 | 412 => match policy {
+            ConflictPolicy::ServerWins => ReplayOutcome::Superseded,
+            _ => ReplayOutcome::Conflict(body),
+        },
+        401 => ReplayOutcome::Retry("Sign in again to sync queued changes".to_string()),
+        408 | 429 => ReplayOutcome::Retry(message),
+        code if code >= 500 => ReplayOutcome::Retry(message),
+        _ => ReplayOutcome::Rejected(message),
+    }
+}
+
+fn backend_url(state: &AppState) -> Option<String> {
+    state
+        ._app_settings
+        .read()
+        .updates
+        .backend_url
+        .as_deref()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+}
+
+fn emit_status(app: &AppHandle, status: &SyncStatus) {
+    let _ = app.emit_all(SYNC_STATUS_EVENT, status);
+}
+
+/// Probe the backend and, if it is reachable, replay queued changes in order
+pub async fn sync_now(app: &AppHandle) -> Result<SyncStatus, CoreEngineError> {
+    let state = app.state::<AppState>();
+    let backend_url = backend_url(&state);
+    {
+        let mut queue = state.sync.lock();
+        queue.backend_configured = backend_url.is_some();
+        if queue.syncing || queue.forced_offline || backend_url.is_none() {
+            return Ok(queue.status());
+        }
+        queue.syncing = true;
+    }
+    let backend_url = backend_url.unwrap_or_default();
+
+    let client = match http_client() {
+        Ok(client) => client,
+        Err(e) => {
+            state.sync.lock().syncing = false;
+            return Err(e);
+        }
+    };
+
+    let online = probe(&client, &backend_url).await;
+    {
+        let mut queue = state.sync.lock();
+        queue.online = online;
+        if !online {
+            queue.syncing = false;
+            queue.last_error = Some("Backend unreachable".to_string());
+            let status = queue.status();
+            drop(queue);
+            emit_status(app, &status);
+            return Ok(status);
+        }
+    }
+
+    loop {
+        let (entry, token, policy) = {
+            let queue = state.sync.lock();
+            if queue.forced_offline {
+                break;
+            }
+            (queue.next_pending(), queue.token.clone(), queue.conflict_policy)
+        };
+        let Some(entry) = entry else {
+            let mut queue = state.sync.lock();
+            queue.last_sync_at = Some(Utc::now());
+            queue.last_error = None;
+            queue.persist();
+            break;
+        };
+
+        let outcome = replay(&client, &backend_url, token.as_deref(), policy, &entry).await;
+        let offline = matches!(&outcome, ReplayOutcome::Retry(message) if message.starts_with("Backend unreachable"));
+        let keep_going = state.sync.lock().record_outcome(entry.id, outcome);
+        if offline {
+            state.sync.lock().online = false;
+        }
+        if !keep_going {
+            break;
+        }
+        emit_status(app, &state.sync.lock().status());
+    }
+
+    let status = {
+        let mut queue = state.sync.lock();
+        queue.syncing = false;
+        queue.status()
+    };
+    emit_status(app, &status);
+    Ok(status)
+}
+
+/// Watch connectivity in the background and replay as soon as the backend is back
+pub fn spawn_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let client = match http_client() {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("Offline sync disabled: {}", e);
+                return;
+            }
+        };
+        loop {
+            let state = app.state::<AppState>();
+            let backend_url = backend_url(&state);
+            let (was_online, pending, forced_offline) = {
+                let mut queue = state.sync.lock();
+                queue.backend_configured = backend_url.is_some();
+                (queue.online, queue.status().pending, queue.forced_offline)
+            };
+
+            if let (Some(url), false) = (backend_url, forced_offline) {
+                let online = probe(&client, &url).await;
+                if online != was_online {
+                    let status = {
+                        let mut queue = state.sync.lock();
+                        queue.online = online;
+                        queue.status()
+                    };
+                    emit_status(&app, &status);
+                }
+                if online && pending > 0 {
+                    if let Err(e) = sync_now(&app).await {
+                        eprintln!("Sync failed: {}", e);
+                    }
+                }
+            }
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(resource: &str) -> QueueMutationRequest {
+        QueueMutationRequest {
+            resource: resource.to_string(),
+            description: format!("Update {}", resource),
+            method: MutationMethod::Patch,
+            path: format!("/api/v1/tickets/{}", resource),
+            body: Some(json!({ "status": "RESOLVED" })),
+            base_version: Some("2026-10-01T10:00:00Z".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_conflicts_block_later_changes_to_the_same_record_only() {
+        let mut queue = SyncQueue::default();
+        let first = queue.enqueue(request("ticket:1")).unwrap();
+        let second = queue.enqueue(request("ticket:1")).unwrap();
+        let other = queue.enqueue(request("ticket:2")).unwrap();
+
+        assert_eq!(queue.next_pending().unwrap().id, first.id);
+        queue.record_outcome(first.id, ReplayOutcome::Conflict(None));
+        assert_eq!(queue.next_pending().unwrap().id, other.id);
+
+        queue.resolve(first.id, ConflictResolution::KeepLocal).unwrap();
+        let replay = queue.next_pending().unwrap();
+        assert_eq!(replay.id, first.id);
+        assert!(replay.base_version.is_none());
+
+        queue.record_outcome(first.id, ReplayOutcome::Applied);
+        assert_eq!(queue.next_pending().unwrap().id, second.id);
+    }
+
+    #[test]
+    fn test_retry_keeps_the_change_and_stops_the_run() {
+        let mut queue = SyncQueue::default();
+        let entry = queue.enqueue(request("ticket:1")).unwrap();
+        assert!(!queue.record_outcome(entry.id, ReplayOutcome::Retry("Backend unreachable".into())));
+
+        let status = queue.status();
+        assert_eq!(status.pending, 1);
+        assert_eq!(queue.entries()[0].attempts, 1);
+        assert_eq!(status.last_error.as_deref(), Some("Backend unreachable"));
+    }
+
+    #[test]
+    fn test_enqueue_validates_requests() {
+        let mut queue = SyncQueue::default();
+        let mut bad_path = request("ticket:1");
+        bad_path.path = "https://elsewhere.example.com/x".to_string();
+        assert!(queue.enqueue(bad_path).is_err());
+
+        let mut no_body = request("ticket:1");
+        no_body.body = None;
+        assert!(queue.enqueue(no_body).is_err());
+    }
+
+    #[test]
+    fn test_server_changed_compares_updated_at() {
+        let base = "2026-10-01T10:00:00Z";
+        assert!(!server_changed(base, &json!({ "updated_at": "2026-10-01T10:00:00+00:00" })));
+        assert!(server_changed(base, &json!({ "data": { "updated_at": "2026-10-02T08:00:00Z" } })));
+        assert!(!server_changed(base, &json!({ "title": "no version" })));
+    }
+
+    #[test]
+    fn test_queue_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("archer-sync-{}", Uuid::new_v4()));
+        let mut queue = SyncQueue::default();
+        queue.open(dir.clone()).unwrap();
+        queue.enqueue(request("ticket:1")).unwrap();
+        queue.set_conflict_policy(ConflictPolicy::ServerWins);
+
+        let mut reopened = SyncQueue::default();
+        reopened.open(dir.clone()).unwrap();
+        assert_eq!(reopened.entries().len(), 1);
+        assert_eq!(reopened.status().conflict_policy, ConflictPolicy::ServerWins);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
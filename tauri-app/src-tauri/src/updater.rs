@@ -0,0 +1,193 @@
+//! Release channels, in-app updates and the backend version check.
+//!
+//! Update manifests are published per channel under `ARCHER_UPDATE_BASE_URL`
+//! (set when building a release) as `<base>/<channel>/latest.json`. Builds
+//! without it never check for updates. The chosen channel is stored in
+//! `updates.json` in the app config directory so it survives restarts.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use core_engine::compat::{self, CompatibilityReport, VersionInfo};
+use core_engine::CoreEngineError;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "updates.json";
+
+/// Event emitted when a startup check finds a newer release
+pub const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+
+/// Event emitted when the configured backend is not compatible with this app
+pub const BACKEND_INCOMPATIBLE_EVENT: &str = "backend-incompatible";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl ReleaseChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseChannel::Stable => "stable",
+            ReleaseChannel::Beta => "beta",
+        }
+    }
+}
+
+/// Update and backend connection preferences, part of the app settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateSettings {
+    pub channel: ReleaseChannel,
+    pub check_on_startup: bool,
+    /// Backend the version handshake runs against, e.g. `https://archer.example.com`
+    pub backend_url: Option<String>,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: ReleaseChannel::Stable,
+            check_on_startup: true,
+            backend_url: None,
+        }
+    }
+}
+
+impl UpdateSettings {
+    fn path(config_dir: &Path) -> PathBuf {
+        config_dir.join(SETTINGS_FILE)
+    }
+
+    /// Saved settings, or the defaults when none were saved or the file is unreadable
+    pub fn load(config_dir: &Path) -> Self {
+        fs::read_to_string(Self::path(config_dir))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, config_dir: &Path) -> Result<(), CoreEngineError> {
+        fs::create_dir_all(config_dir)
+            .map_err(|e| CoreEngineError::io(format!("Failed to create config directory: {}", e)))?;
+        fs::write(Self::path(config_dir), serde_json::to_string_pretty(self)?)
+            .map_err(|e| CoreEngineError::io(format!("Failed to save update settings: {}", e)))
+    }
+}
+
+/// Result of checking the release channel for a newer version
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateStatus {
+    pub channel: ReleaseChannel,
+    pub current_version: String,
+    pub available: bool,
+    pub latest_version: Option<String>,
+    pub notes: Option<String>,
+    pub published_at: Option<String>,
+}
+
+/// Manifest URL for a channel, if this build was given an update server
+pub fn channel_endpoint(channel: ReleaseChannel) -> Option<String> {
+    option_env!("ARCHER_UPDATE_BASE_URL")
+        .filter(|base| !base.is_empty())
+        .map(|base| format!("{}/{}/latest.json", base.trim_end_matches('/'), channel.as_str()))
+}
+
+/// This app's version and schema, as sent in the backend handshake
+pub fn app_version(app: &AppHandle) -> VersionInfo {
+    VersionInfo::current("desktop", &app.package_info().version.to_string())
+}
+
+fn updater_for(app: &AppHandle, channel: ReleaseChannel) -> Result<tauri::updater::UpdateBuilder<tauri::Wry>, CoreEngineError> {
+    let endpoint = channel_endpoint(channel)
+        .ok_or_else(|| CoreEngineError::config("This build has no update server configured"))?;
+    Ok(app.updater().endpoints(&[endpoint]))
+}
+
+/// Ask the channel's update server whether a newer release exists
+pub async fn check(app: &AppHandle, channel: ReleaseChannel) -> Result<UpdateStatus, CoreEngineError> {
+    let update = updater_for(app, channel)?
+        .check()
+        .await
+        .map_err(|e| CoreEngineError::NetworkError(format!("Update check failed: {}", e)))?;
+
+    let available = update.is_update_available();
+    Ok(UpdateStatus {
+        channel,
+        current_version: update.current_version().to_string(),
+        available,
+        latest_version: available.then(|| update.latest_version().to_string()),
+        notes: available.then(|| update.body().cloned()).flatten(),
+        published_at: available.then(|| update.date().map(|d| d.to_string())).flatten(),
+    })
+}
+
+/// Download and install the channel's latest release, then restart
+pub async fn install(app: &AppHandle, channel: ReleaseChannel) -> Result<(), CoreEngineError> {
+    let update = updater_for(app, channel)?
+        .check()
+        .await
+        .map_err(|e| CoreEngineError::NetworkError(format!("Update check failed: {}", e)))?;
+    if !update.is_update_available() {
+        return Err(CoreEngineError::validation("No newer release is available on this channel"));
+    }
+    update
+        .download_and_install()
+        .await
+        .map_err(|e| CoreEngineError::NetworkError(format!("Update install failed: {}", e)))?;
+    app.restart();
+    Ok(())
+}
+
+/// Run the backend handshake for the configured backend
+pub async fn check_backend(app: &AppHandle, backend_url: &str) -> Result<CompatibilityReport, CoreEngineError> {
+    compat::handshake(backend_url, &app_version(app)).await
+}
+
+/// Startup checks; problems are reported to the UI as events, never as errors
+pub fn spawn_startup_checks(app: AppHandle, settings: UpdateSettings) {
+    tauri::async_runtime::spawn(async move {
+        if let Some(backend_url) = settings.backend_url.as_deref() {
+            match check_backend(&app, backend_url).await {
+                Ok(report) if !report.is_compatible() => {
+                    let _ = app.emit_all(BACKEND_INCOMPATIBLE_EVENT, &report);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Backend version check failed: {}", e),
+            }
+        }
+
+        if settings.check_on_startup && channel_endpoint(settings.channel).is_some() {
+            match check(&app, settings.channel).await {
+                Ok(status) if status.available => {
+                    let _ = app.emit_all(UPDATE_AVAILABLE_EVENT, &status);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Update check failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip_and_default_when_missing() {
+        let dir = std::env::temp_dir().join(format!("archer-updates-{}", uuid::Uuid::new_v4()));
+        assert_eq!(UpdateSettings::load(&dir), UpdateSettings::default());
+
+        let settings = UpdateSettings {
+            channel: ReleaseChannel::Beta,
+            check_on_startup: false,
+            backend_url: Some("https://archer.example.com".to_string()),
+        };
+        settings.save(&dir).unwrap();
+        assert_eq!(UpdateSettings::load(&dir), settings);
+        let _ = fs::remove_dir_all(dir);
+    }
+}